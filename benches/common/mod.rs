@@ -0,0 +1,30 @@
+//! Benchmark data - banks of a given size, the same on every run
+//!
+//! Shared by the benchmarks with `mod common;`. For a bank to poke at by
+//! hand, `cargo run -- seed` writes one with believable activity instead.
+
+use rust_banking_system::models::CustomerId;
+use rust_banking_system::{AccountType, Bank, Money};
+
+/// A bank with `customers` customers, each with a checking account opened
+/// with 10,000, after `transfers_per_customer` rounds in which every
+/// customer sends 1 to another
+///
+/// Customer `n` is "Customer n" with the email `customer<n>@example.com`.
+pub fn generate(customers: usize, transfers_per_customer: usize) -> Bank {
+    let mut bank = Bank::new("Bench Bank".to_string());
+    let ids: Vec<CustomerId> = (0..customers)
+        .map(|n| {
+            let id = bank.register_customer(format!("Customer {}", n), format!("customer{}@example.com", n)).unwrap();
+            bank.create_account_for_customer(&id, Money::from_major(10_000), AccountType::Checking).unwrap();
+            id
+        })
+        .collect();
+    for round in 0..transfers_per_customer {
+        for (n, from) in ids.iter().enumerate() {
+            let to = &ids[(n + round + 1) % customers];
+            bank.transfer(from, to, Money::from_major(1)).unwrap();
+        }
+    }
+    bank
+}
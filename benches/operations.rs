@@ -0,0 +1,133 @@
+//! Benchmarks for the core operations at 10k and 100k customers
+//!
+//! Run with `cargo bench --bench operations`, or pick benchmarks by name,
+//! e.g. `cargo bench --bench operations -- deposit`. Each size is one
+//! bank from `common::generate` (every customer has made one transfer),
+//! built once and shared; benchmarks that change it work on a clone.
+//! Deposits, withdrawals and transfers go round the customers in turn, so
+//! no account's history grows much longer than the rest. `shared_deposit`
+//! goes through `SharedBank` from one and from four threads, which is
+//! where lock contention shows. Saving and loading take seconds at 100k,
+//! so they are sampled fewer times.
+
+mod common;
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{LazyLock, Mutex};
+
+use divan::Bencher;
+use rust_banking_system::models::CustomerId;
+use rust_banking_system::persistence::{self, Compression, DataFormat};
+use rust_banking_system::{Bank, Money, SharedBank};
+
+/// Bank sizes, in customers
+const SIZES: [usize; 2] = [10_000, 100_000];
+
+/// The generated banks by size, each built on first use
+static BANKS: LazyLock<Mutex<HashMap<usize, &'static Bank>>> = LazyLock::new(Default::default);
+
+fn bank(customers: usize) -> &'static Bank {
+    let mut banks = BANKS.lock().unwrap();
+    banks.entry(customers).or_insert_with(|| Box::leak(Box::new(common::generate(customers, 1))))
+}
+
+/// The bank's customer IDs in a fixed order
+fn customer_ids(bank: &Bank) -> Vec<CustomerId> {
+    let mut ids: Vec<CustomerId> = bank.list_customers().into_iter().map(|customer| customer.id.clone()).collect();
+    ids.sort();
+    ids
+}
+
+/// A path in the temp directory unique to this process
+fn temp_path(name: &str) -> String {
+    let path: PathBuf = std::env::temp_dir().join(format!("bench-{}-{}", std::process::id(), name));
+    path.to_string_lossy().into_owned()
+}
+
+fn main() {
+    divan::main();
+}
+
+/// Registering a new customer; includes the email uniqueness check
+#[divan::bench(args = SIZES)]
+fn register(bencher: Bencher, customers: usize) {
+    let mut bank = bank(customers).clone();
+    let mut n = 0;
+    bencher.bench_local(|| {
+        n += 1;
+        bank.register_customer(format!("New {}", n), format!("new{}@example.com", n)).unwrap()
+    });
+}
+
+/// Finding a customer by email
+#[divan::bench(args = SIZES)]
+fn find_by_email(bencher: Bencher, customers: usize) {
+    let bank = bank(customers);
+    let email = format!("customer{}@example.com", customers / 2);
+    bencher.bench(|| bank.find_customer_by_email(&email).unwrap().id.as_str().len());
+}
+
+#[divan::bench(args = SIZES)]
+fn deposit(bencher: Bencher, customers: usize) {
+    let mut bank = bank(customers).clone();
+    let ids = customer_ids(&bank);
+    let mut next = ids.iter().cycle();
+    bencher.bench_local(|| bank.deposit(next.next().unwrap(), Money::from_major(1)).unwrap());
+}
+
+#[divan::bench(args = SIZES)]
+fn withdraw(bencher: Bencher, customers: usize) {
+    let mut bank = bank(customers).clone();
+    let ids = customer_ids(&bank);
+    let mut next = ids.iter().cycle();
+    bencher.bench_local(|| bank.withdraw(next.next().unwrap(), Money::from_major(1)).unwrap());
+}
+
+/// Each customer sends 1 to the next one
+#[divan::bench(args = SIZES)]
+fn transfer(bencher: Bencher, customers: usize) {
+    let mut bank = bank(customers).clone();
+    let ids = customer_ids(&bank);
+    let mut n = 0;
+    bencher.bench_local(|| {
+        n = (n + 1) % ids.len();
+        bank.transfer(&ids[n], &ids[(n + 1) % ids.len()], Money::from_major(1)).unwrap()
+    });
+}
+
+/// Deposits through the shared, locked bank, contended by other threads
+#[divan::bench(threads = [1, 4])]
+fn shared_deposit(bencher: Bencher) {
+    let shared = SharedBank::new(bank(SIZES[0]).clone());
+    let ids = customer_ids(&shared.read());
+    let next = AtomicUsize::new(0);
+    bencher.bench(|| {
+        let n = next.fetch_add(1, Ordering::Relaxed) % ids.len();
+        shared.deposit(&ids[n], Money::from_major(1)).unwrap()
+    });
+}
+
+/// The statistics report (`stats`)
+#[divan::bench(args = SIZES, sample_count = 20)]
+fn statistics(bencher: Bencher, customers: usize) {
+    let bank = bank(customers);
+    bencher.bench(|| bank.report());
+}
+
+#[divan::bench(args = SIZES, sample_count = 10)]
+fn save(bencher: Bencher, customers: usize) {
+    let bank = bank(customers);
+    let path = temp_path(&format!("save-{}.json", customers));
+    bencher.bench(|| persistence::save_bank_as(bank, &path, DataFormat::Json, Compression::None, 0).unwrap());
+    let _ = std::fs::remove_file(&path);
+}
+
+#[divan::bench(args = SIZES, sample_count = 10)]
+fn load(bencher: Bencher, customers: usize) {
+    let path = temp_path(&format!("load-{}.json", customers));
+    persistence::save_bank_as(bank(customers), &path, DataFormat::Json, Compression::None, 0).unwrap();
+    bencher.bench(|| persistence::read_bank(&path).unwrap());
+    let _ = std::fs::remove_file(&path);
+}
@@ -0,0 +1,130 @@
+//! Benchmarks for saving and loading a large bank
+//!
+//! Run with `cargo bench --bench persistence`. Besides the timings, divan's
+//! allocation profiler reports the peak memory of each benchmark, which is
+//! where streaming pays off: building the whole file as one buffer holds
+//! an extra copy of it in memory. Streamed saving takes about as long as
+//! the buffered way with a fraction of its peak memory; streamed loading
+//! is slower than parsing one buffer but needs half the memory.
+//! The sizes of the saved file in each format and compression are
+//! printed before the timings. `load_lazy` is a lazy load followed by
+//! reading one account's history, the cost of opening a large bank to
+//! look up a single customer.
+//!
+//! `benches/operations.rs` times saving and loading at 10k and 100k
+//! customers; this one compares the ways of doing it on a smaller bank.
+
+mod common;
+
+use std::path::PathBuf;
+use std::sync::LazyLock;
+
+use divan::{AllocProfiler, Bencher};
+use rust_banking_system::persistence::{self, Compression, DataFormat};
+use rust_banking_system::Bank;
+
+#[global_allocator]
+static ALLOC: AllocProfiler = AllocProfiler::system();
+
+const CUSTOMERS: usize = 1_000;
+const TRANSFERS_PER_CUSTOMER: usize = 20;
+
+/// A bank with `CUSTOMERS` customers and a long transaction history
+static BANK: LazyLock<Bank> = LazyLock::new(|| common::generate(CUSTOMERS, TRANSFERS_PER_CUSTOMER));
+
+/// A path in the temp directory unique to this process
+fn temp_path(name: &str) -> String {
+    let path: PathBuf = std::env::temp_dir().join(format!("bench-{}-{}", std::process::id(), name));
+    path.to_string_lossy().into_owned()
+}
+
+fn main() {
+    for format in DataFormat::ALL {
+        let data = format.encode(&BANK).unwrap();
+        let sizes: Vec<String> = Compression::ALL
+            .iter()
+            .map(|compression| {
+                let size = compression.compress(&data).unwrap().len();
+                format!("{} {} KiB", compression, size / 1024)
+            })
+            .collect();
+        println!("{:<12} {}", format.to_string(), sizes.join(", "));
+    }
+    println!();
+
+    divan::main();
+}
+
+/// The way saving used to work: the whole file as one buffer, then written
+#[divan::bench]
+fn save_whole_buffer(bencher: Bencher) {
+    let path = temp_path("buffer.json");
+    bencher.bench(|| {
+        let data = serde_json::to_vec_pretty(&*BANK).unwrap();
+        persistence::write_atomic(&path, &data).unwrap();
+    });
+    let _ = std::fs::remove_file(&path);
+}
+
+/// Saving streamed into the file, in each compression
+#[divan::bench(args = Compression::ALL)]
+fn save_streamed(bencher: Bencher, compression: Compression) {
+    let path = temp_path("streamed.json");
+    bencher.bench(|| {
+        persistence::save_bank_as(&BANK, &path, DataFormat::Json, compression, 0).unwrap();
+    });
+    let _ = std::fs::remove_file(&path);
+}
+
+/// Saving streamed into the file, in each format
+#[divan::bench(args = DataFormat::ALL)]
+fn save_format(bencher: Bencher, format: DataFormat) {
+    let path = temp_path("format");
+    bencher.bench(|| {
+        persistence::save_bank_as(&BANK, &path, format, Compression::None, 0).unwrap();
+    });
+    let _ = std::fs::remove_file(&path);
+}
+
+/// The way loading used to work: the whole file read, then parsed
+#[divan::bench]
+fn load_whole_buffer(bencher: Bencher) {
+    let path = temp_path("load-buffer.json");
+    persistence::save_bank_with_backups(&BANK, &path, 0).unwrap();
+    bencher.bench(|| {
+        let data = std::fs::read(&path).unwrap();
+        serde_json::from_slice::<Bank>(&data).unwrap()
+    });
+    let _ = std::fs::remove_file(&path);
+}
+
+/// Loading streamed from the file, in each compression
+#[divan::bench(args = Compression::ALL)]
+fn load_streamed(bencher: Bencher, compression: Compression) {
+    let path = temp_path(&format!("load-streamed-{}", compression));
+    persistence::save_bank_as(&BANK, &path, DataFormat::Json, compression, 0).unwrap();
+    bencher.bench(|| persistence::read_bank(&path).unwrap());
+    let _ = std::fs::remove_file(&path);
+}
+
+/// Loading streamed from the file, in each format
+#[divan::bench(args = DataFormat::ALL)]
+fn load_format(bencher: Bencher, format: DataFormat) {
+    let path = temp_path(&format!("load-format-{}", format.extension()));
+    persistence::save_bank_as(&BANK, &path, format, Compression::None, 0).unwrap();
+    bencher.bench(|| persistence::read_bank(&path).unwrap());
+    let _ = std::fs::remove_file(&path);
+}
+
+/// Loading without parsing the histories, then using one of them
+#[divan::bench]
+fn load_lazy(bencher: Bencher) {
+    let path = temp_path("load-lazy.json");
+    persistence::save_bank_with_backups(&BANK, &path, 0).unwrap();
+    let customer = BANK.list_customers()[0].id.clone();
+    bencher.bench(|| {
+        let bank = persistence::load_bank_lazy(&path).unwrap();
+        bank.get_customer(&customer).unwrap().get_account().unwrap().transactions.len()
+    });
+    let _ = std::fs::remove_file(&path);
+}
@@ -0,0 +1,15 @@
+//! Build script - compiles the protobuf definitions for the `grpc` feature
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed=proto/bank.proto");
+
+    #[cfg(feature = "grpc")]
+    {
+        // Use the bundled protoc so no system installation is needed
+        let protoc = protoc_bin_vendored::protoc_bin_path().expect("bundled protoc is available");
+        std::env::set_var("PROTOC", protoc);
+
+        tonic_prost_build::compile_protos("proto/bank.proto").expect("proto/bank.proto compiles");
+    }
+}
@@ -0,0 +1,140 @@
+//! Balance alerts - checking rules as postings are made
+//!
+//! Demonstrates: Hooking a side effect into the single place state
+//! changes, so every operation (and nothing during replay) triggers it
+//!
+//! `emit` asks `alerts_for` which alerts an event will trigger before
+//! applying it, while the balances from before the posting are still
+//! there, and `dispatch_alerts` sends them once it has applied (see
+//! `outbox`). Deposits, withdrawals and transfers are the exception: the
+//! bank works out their alerts the same way, and the pipeline's `Notify`
+//! stage sends them.
+//! Replaying a journal goes through `apply_event` alone, so customers are
+//! not alerted twice for the same posting.
+
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+
+use crate::errors::BankResult;
+use crate::models::{AccountStatus, Alert, AlertKind, AlertRules, CustomerId, Transaction, TransactionId};
+use crate::notifications::Notifier;
+use super::core::Bank;
+use super::events::BankEvent;
+
+impl Bank {
+    /// Replaces the alert rules on a customer's account
+    ///
+    /// # Returns
+    /// * `Err(BankError::InvalidAmount)` - If a configured amount is not positive
+    pub fn set_alert_rules(&mut self, customer_id: &CustomerId, rules: AlertRules) -> BankResult<()> {
+        rules.validate()?;
+        self.get_customer(customer_id)?.get_account()?.ensure_open()?;
+
+        self.emit(BankEvent::AlertRulesChanged {
+            customer_id: customer_id.clone(),
+            rules,
+        })
+    }
+
+    /// Registers somewhere to send triggered alerts
+    ///
+    /// Notifiers are not saved with the bank; register them again after
+    /// loading it.
+    pub fn add_notifier(&mut self, notifier: Arc<dyn Notifier>) {
+        self.notifiers.push(notifier);
+    }
+
+    /// The alerts `event` triggers, checked against the current balances
+    pub(crate) fn alerts_for(&self, event: &BankEvent) -> Vec<Alert> {
+        match event {
+            BankEvent::Deposited { customer_id, transaction }
+            | BankEvent::Withdrawn { customer_id, transaction }
+            | BankEvent::Reversed { customer_id, transaction }
+            | BankEvent::ExternalTransferSent { customer_id, transaction }
+            | BankEvent::AuthorizationCaptured { customer_id, transaction }
+            | BankEvent::HoldCaptured { customer_id, transaction, .. }
+            | BankEvent::ChequeDeposited { customer_id, transaction, .. }
+            | BankEvent::BillPaid { customer_id, transaction }
+            | BankEvent::FeeCharged { customer_id, transaction }
+            | BankEvent::TermDepositOpened { customer_id, transaction, .. }
+            | BankEvent::TermDepositClosed { customer_id, transaction, .. }
+            | BankEvent::AutopayRan { customer_id, transaction: Some(transaction), .. }
+            | BankEvent::ExternalTransferRejected { customer_id, refund: transaction, .. } => {
+                self.check_posting(customer_id, transaction)
+            }
+            BankEvent::ChequeCleared { drawer_id: Some(drawer_id), debit: Some(debit), .. } => {
+                self.check_posting(drawer_id, debit)
+            }
+            BankEvent::ChequeBounced { customer_id, reversal, fee, .. } => {
+                let mut alerts = self.check_posting(customer_id, reversal);
+                if let Some(fee) = fee {
+                    alerts.extend(self.check_posting(customer_id, fee));
+                }
+                alerts
+            }
+            BankEvent::Transferred { from_customer_id, to_customer_id, debit, credit } => {
+                let mut alerts = self.check_posting(from_customer_id, debit);
+                alerts.extend(self.check_posting(to_customer_id, credit));
+                alerts
+            }
+            BankEvent::AccountDormant { customer_id, since, freeze } => {
+                self.dormancy_alert(customer_id, *since, *freeze).into_iter().collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    fn dormancy_alert(&self, customer_id: &CustomerId, since: DateTime<Utc>, freeze: bool) -> Option<Alert> {
+        let account = self.customers.get(customer_id)?.account.as_ref()?;
+        if account.dormant_since.is_some() {
+            return None;
+        }
+        let last = account.transactions.iter().max_by_key(|t| t.timestamp);
+        Some(Alert {
+            customer_id: customer_id.clone(),
+            account_id: account.id.clone(),
+            transaction_id: last.map_or_else(|| TransactionId::from(""), |t| t.id.clone()),
+            kind: AlertKind::Dormant {
+                inactive_days: (since - account.last_activity()).num_days(),
+                frozen: freeze && account.status == AccountStatus::Active,
+            },
+            balance: account.balance,
+            triggered_at: since,
+        })
+    }
+
+    fn check_posting(&self, customer_id: &CustomerId, transaction: &Transaction) -> Vec<Alert> {
+        let account = match self.customers.get(customer_id).and_then(|c| c.account.as_ref()) {
+            Some(account) => account,
+            None => return Vec::new(),
+        };
+        let mut kinds = account.alerts.check(transaction, account.balance);
+        kinds.extend(account.budget_alert(transaction));
+        kinds
+            .into_iter()
+            .map(|kind| Alert {
+                customer_id: customer_id.clone(),
+                account_id: account.id.clone(),
+                transaction_id: transaction.id.clone(),
+                kind,
+                balance: transaction.balance_after,
+                triggered_at: transaction.timestamp,
+            })
+            .collect()
+    }
+
+    /// Sends alerts to every notifier
+    ///
+    /// A notifier that fails is reported on stderr; the posting that
+    /// triggered the alert stands.
+    pub(crate) fn dispatch_alerts(&self, alerts: &[Alert]) {
+        for alert in alerts {
+            for notifier in &self.notifiers {
+                if let Err(e) = notifier.notify(alert) {
+                    eprintln!("⚠️  Could not send alert: {}", e);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,149 @@
+//! Analytics - top-N queries over accounts and transactions
+//!
+//! Demonstrates: Keeping the N largest items with a bounded min-heap,
+//! sort_by with tie-breakers, Duration arithmetic on timestamps
+//!
+//! These back the statistics screens: the biggest transactions, the
+//! busiest accounts over a recent period and accounts nobody has used in
+//! a while. Each result is a plain serializable struct.
+
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+
+use crate::models::{AccountNumber, AccountStatus, CustomerId, Transaction, TransactionType};
+use super::core::Bank;
+use super::ledger::LedgerEntry;
+use super::report::AccountActivity;
+
+/// A transaction with the customer whose account it is on
+#[derive(Debug, Clone, Serialize)]
+pub struct LargeTransaction {
+    pub customer_id: CustomerId,
+    pub name: String,
+    pub account_number: Option<AccountNumber>,
+    pub transaction: Transaction,
+}
+
+/// An open account with no activity for a while
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DormantAccount {
+    pub customer_id: CustomerId,
+    pub name: String,
+    pub account_number: Option<AccountNumber>,
+
+    /// Newest transaction, or the opening of the account if later
+    pub last_activity: DateTime<Utc>,
+
+    /// Whole days since `last_activity`
+    pub inactive_days: i64,
+}
+
+impl Bank {
+    /// The `n` largest transactions by amount, largest first
+    ///
+    /// Each transfer is counted once, by its outgoing side. Ties go to the
+    /// older transaction. Deleted customers' transactions are included.
+    pub fn largest_transactions(&self, n: usize) -> Vec<LargeTransaction> {
+        if n == 0 {
+            return Vec::new();
+        }
+
+        let entries: Vec<LedgerEntry> = self
+            .all_transactions()
+            .filter(|entry| !matches!(entry.transaction.transaction_type, TransactionType::TransferIn { .. }))
+            .collect();
+
+        // Min-heap of the n largest so far: the smallest is evicted first.
+        // Reverse(position) makes the older of two equal amounts rank higher.
+        let mut largest = BinaryHeap::with_capacity(n + 1);
+        for (position, entry) in entries.iter().enumerate() {
+            largest.push(Reverse((entry.transaction.amount, Reverse(position))));
+            if largest.len() > n {
+                largest.pop();
+            }
+        }
+
+        largest
+            .into_sorted_vec()
+            .into_iter()
+            .map(|Reverse((_, Reverse(position)))| {
+                let entry = &entries[position];
+                let customer = &self.customers[entry.customer_id];
+                LargeTransaction {
+                    customer_id: customer.id.clone(),
+                    name: customer.name.clone(),
+                    account_number: customer.account.as_ref().and_then(|a| a.number),
+                    transaction: entry.transaction.clone(),
+                }
+            })
+            .collect()
+    }
+
+    /// The `n` accounts with the most transactions during the last
+    /// `period`, busiest first
+    ///
+    /// Accounts without transactions in the period are left out; ties are
+    /// ordered by name.
+    pub fn most_active_accounts(&self, n: usize, period: Duration) -> Vec<AccountActivity> {
+        let since = self.now() - period;
+        let mut counts: HashMap<&CustomerId, usize> = HashMap::new();
+        for entry in self.all_transactions().between(Some(since), None) {
+            *counts.entry(entry.customer_id).or_default() += 1;
+        }
+
+        let mut busiest: Vec<AccountActivity> = counts
+            .into_iter()
+            .filter_map(|(customer_id, transactions)| {
+                let customer = self.customers.get(customer_id)?;
+                Some(AccountActivity {
+                    customer_id: customer.id.clone(),
+                    name: customer.name.clone(),
+                    account_number: customer.account.as_ref()?.number,
+                    transactions,
+                })
+            })
+            .collect();
+        busiest.sort_by(|a, b| {
+            b.transactions
+                .cmp(&a.transactions)
+                .then_with(|| a.name.cmp(&b.name))
+                .then_with(|| a.customer_id.cmp(&b.customer_id))
+        });
+        busiest.truncate(n);
+        busiest
+    }
+
+    /// Open accounts without any activity for at least `inactive_days`,
+    /// longest inactive first
+    ///
+    /// Frozen accounts count as open; closed accounts and deleted customers
+    /// are left out.
+    pub fn dormant_accounts(&self, inactive_days: u32) -> Vec<DormantAccount> {
+        self.dormant_as_of(self.now(), inactive_days)
+    }
+
+    /// `dormant_accounts` as of `now`
+    pub(super) fn dormant_as_of(&self, now: DateTime<Utc>, inactive_days: u32) -> Vec<DormantAccount> {
+        let mut dormant: Vec<DormantAccount> = self
+            .list_customers()
+            .into_iter()
+            .filter_map(|customer| {
+                let account = customer.account.as_ref().filter(|a| a.status != AccountStatus::Closed)?;
+                let last_activity = account.last_activity();
+                let inactive = (now - last_activity).num_days();
+                (inactive >= i64::from(inactive_days)).then(|| DormantAccount {
+                    customer_id: customer.id.clone(),
+                    name: customer.name.clone(),
+                    account_number: account.number,
+                    last_activity,
+                    inactive_days: inactive,
+                })
+            })
+            .collect();
+        dormant.sort_by(|a, b| a.last_activity.cmp(&b.last_activity).then_with(|| a.name.cmp(&b.name)));
+        dormant
+    }
+}
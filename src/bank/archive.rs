@@ -0,0 +1,334 @@
+//! Archive - moving old transactions out of the live data
+//!
+//! Demonstrates: A trait object as a pluggable store, draining the front
+//! of a Vec, folding many book entries into one
+//!
+//! An account's history only grows. `archive_transactions` moves the
+//! transactions older than a cutoff into a `HistoryArchive` (per-account
+//! files next to the data file, see `persistence::FileArchive`) and leaves
+//! an `ArchivedHistory` marker on the account with the balance the live
+//! history continues from. What was moved out stays in the books as one
+//! "brought forward" entry per account, so the trial balance still totals
+//! and reconciles, and the integrity audit starts each account from its
+//! marker. Statements and monthly summaries reaching back before the
+//! cutoff page the archived transactions back in from the archive.
+//!
+//! `set_history_cap` archives by count instead of by date: once an account
+//! holds more than the cap, its oldest transactions go to the archive as
+//! new ones post, which keeps the memory of a long-running server bounded.
+//! The cap never archives this month's withdrawals, bill payments or
+//! outgoing transfers, since the daily and monthly limits only count the
+//! live history; an account can go over the cap until the month turns.
+//! `get_transaction_history` walks the whole history, newest first,
+//! fetching archived transactions a page at a time as it reaches them.
+//!
+//! A transaction stays live, along with everything after it, while it is
+//! pending, waiting for fraud review, or reversed by a transaction that
+//! stays live, so nothing that can still change is archived.
+
+use std::collections::HashSet;
+use std::fmt;
+use std::sync::Arc;
+
+use chrono::{DateTime, Datelike, Utc};
+use serde::Serialize;
+
+use crate::errors::{BankError, BankResult};
+use crate::models::{
+    Account, AccountId, ArchivedHistory, BookEntry, CustomerId, MonthlySummary, ReviewStatus, Statement, Transaction,
+    TransactionId, TransactionStatus, TransactionType,
+};
+use crate::models::statement::month_period;
+use super::core::Bank;
+use super::events::BankEvent;
+
+/// Somewhere archived transactions are kept
+///
+/// Demonstrates: Trait as an extension point
+/// The bank only hands transactions over and asks for them back; where
+/// they live is up to the implementation.
+pub trait HistoryArchive: fmt::Debug + Send + Sync {
+    /// Adds transactions moved out of an account's live history, oldest
+    /// first, after those stored before
+    ///
+    /// Transactions already stored are skipped, so storing a batch again
+    /// after an interrupted archival is harmless.
+    fn store(&self, account_id: &AccountId, transactions: &[Transaction]) -> BankResult<()>;
+
+    /// Every archived transaction of the account, oldest first; none for
+    /// an account never archived
+    fn load(&self, account_id: &AccountId) -> BankResult<Vec<Transaction>>;
+
+    /// Up to `len` archived transactions of the account, starting with the
+    /// `start`th oldest
+    ///
+    /// The default loads everything and keeps the page; archives that can
+    /// read part of an account's history override it.
+    fn load_page(&self, account_id: &AccountId, start: usize, len: usize) -> BankResult<Vec<Transaction>> {
+        Ok(self.load(account_id)?.into_iter().skip(start).take(len).collect())
+    }
+}
+
+/// What one `archive_transactions` call moved out
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ArchiveRun {
+    pub before: DateTime<Utc>,
+    /// Accounts that had something to archive
+    pub accounts: usize,
+    pub transactions: usize,
+}
+
+impl Bank {
+    /// Attaches the archive that archived transactions go to and are paged
+    /// back in from
+    ///
+    /// Not bank data: whoever loads the bank attaches it, as
+    /// `JsonFileStorage` does with the archive next to its data file.
+    pub fn set_archive(&mut self, archive: Arc<dyn HistoryArchive>) {
+        self.archive = Some(archive);
+    }
+
+    /// Whether an archive is attached
+    pub fn has_archive(&self) -> bool {
+        self.archive.is_some()
+    }
+
+    /// Moves every account's transactions from before `before` into the
+    /// archive
+    ///
+    /// Each account's batch is stored before it leaves the live history,
+    /// so an interruption leaves transactions in both places rather than
+    /// in neither; archiving again finishes the job.
+    ///
+    /// # Returns
+    /// * `Ok(ArchiveRun)` - How much was moved out
+    /// * `Err(BankError::InvalidInput)` - If no archive is attached or the
+    ///   cutoff is in the future
+    pub fn archive_transactions(&mut self, before: DateTime<Utc>) -> BankResult<ArchiveRun> {
+        let archive = self
+            .archive
+            .clone()
+            .ok_or_else(|| BankError::InvalidInput("no archive is attached to the bank".to_string()))?;
+        if before > self.now() {
+            return Err(BankError::InvalidInput("the archive cutoff cannot be in the future".to_string()));
+        }
+
+        let mut customer_ids: Vec<CustomerId> = self.customers.keys().cloned().collect();
+        customer_ids.sort();
+        let mut run = ArchiveRun { before, accounts: 0, transactions: 0 };
+        for customer_id in customer_ids {
+            let Some(account) = self.customers[&customer_id].account.as_ref() else { continue };
+            let older = account.transactions.iter().take_while(|tx| tx.timestamp < before).count();
+            let count = self.archivable(account, older);
+            if count > 0 {
+                self.archive_oldest(archive.as_ref(), &customer_id, count, before)?;
+                run.accounts += 1;
+                run.transactions += count;
+            }
+        }
+        Ok(run)
+    }
+
+    /// Keeps at most `cap` transactions of each account in memory, moving
+    /// older ones to the archive as new ones post; `None` keeps them all
+    ///
+    /// Accounts already over the cap are trimmed straight away. An account
+    /// can stay over it while its oldest transactions cannot be archived
+    /// yet (see the module docs). The cap is not bank data: whoever loads
+    /// the bank sets it.
+    ///
+    /// # Returns
+    /// * `Err(BankError::InvalidInput)` - If the cap is zero or no archive
+    ///   is attached
+    pub fn set_history_cap(&mut self, cap: Option<usize>) -> BankResult<()> {
+        if cap == Some(0) {
+            return Err(BankError::InvalidInput("the history cap must be at least 1".to_string()));
+        }
+        if cap.is_some() && self.archive.is_none() {
+            return Err(BankError::InvalidInput("capping histories needs an archive".to_string()));
+        }
+        self.history_cap = cap;
+        let Some(cap) = cap else { return Ok(()) };
+        let mut over: Vec<CustomerId> = self
+            .customers
+            .values()
+            .filter(|customer| customer.account.as_ref().is_some_and(|account| account.transactions.len() > cap))
+            .map(|customer| customer.id.clone())
+            .collect();
+        over.sort();
+        self.over_cap = over;
+        self.enforce_history_cap()
+    }
+
+    /// The most transactions kept in memory per account, if capped
+    pub fn history_cap(&self) -> Option<usize> {
+        self.history_cap
+    }
+
+    /// Archives the oldest transactions of accounts that went over the
+    /// history cap, for `emit`
+    pub(crate) fn enforce_history_cap(&mut self) -> BankResult<()> {
+        let (Some(cap), Some(archive)) = (self.history_cap, self.archive.clone()) else { return Ok(()) };
+        for customer_id in std::mem::take(&mut self.over_cap) {
+            let account = self.get_customer(&customer_id)?.get_account()?;
+            let over = account.transactions.len().saturating_sub(cap);
+            let count = self.archivable(account, over.min(uncounted_by_limits(account, self.now())));
+            if count > 0 {
+                // The oldest transaction kept is where the archive ends
+                let before = account.transactions[count].timestamp;
+                self.archive_oldest(archive.as_ref(), &customer_id, count, before)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Stores the account's `count` oldest transactions in `archive`, then
+    /// drops them from the live history
+    fn archive_oldest(
+        &mut self,
+        archive: &dyn HistoryArchive,
+        customer_id: &CustomerId,
+        count: usize,
+        before: DateTime<Utc>,
+    ) -> BankResult<()> {
+        let account = self.get_customer(customer_id)?.get_account()?;
+        archive.store(&account.id, &account.transactions[..count])?;
+        let through = account.transactions[count - 1].id.clone();
+        self.emit(BankEvent::TransactionsArchived { customer_id: customer_id.clone(), before, through })
+    }
+
+    /// Every archived transaction of a customer's account, oldest first
+    ///
+    /// # Returns
+    /// * `Err(BankError::InvalidInput)` - If the account has archived
+    ///   history but no archive is attached
+    pub fn archived_transactions(&self, customer_id: &CustomerId) -> BankResult<Vec<Transaction>> {
+        let account = self.get_customer(customer_id)?.get_account()?;
+        match (&account.archived, &self.archive) {
+            (None, _) => Ok(Vec::new()),
+            (Some(_), Some(archive)) => archive.load(&account.id),
+            (Some(_), None) => Err(no_archive(&account.id)),
+        }
+    }
+
+    /// A customer's statement for the period `[from, to)`, paging archived
+    /// transactions back in if the period reaches back to the cutoff
+    pub fn statement(&self, customer_id: &CustomerId, from: DateTime<Utc>, to: DateTime<Utc>) -> BankResult<Statement> {
+        let account = self.get_customer(customer_id)?.get_account()?;
+        match &account.archived {
+            Some(archived) if from <= archived.before => {
+                let mut history = self.archived_transactions(customer_id)?;
+                let end = account.transactions.partition_point(|tx| tx.timestamp < to);
+                history.extend_from_slice(&account.transactions[..end]);
+                Ok(Statement::for_period(&account.id, &history, account.balance, from, to))
+            }
+            _ => Ok(account.statement(from, to)),
+        }
+    }
+
+    /// A customer's monthly summary, paging archived transactions back in
+    /// if the month starts before the cutoff
+    ///
+    /// # Returns
+    /// * `Err(BankError::InvalidInput)` - If the month is not 1 to 12 or the
+    ///   year is out of range
+    pub fn monthly_summary(&self, customer_id: &CustomerId, year: i32, month: u32) -> BankResult<MonthlySummary> {
+        let (from, to) = month_period(year, month)?;
+        Ok(MonthlySummary::from_statement(year, month, &self.statement(customer_id, from, to)?))
+    }
+
+    /// How many of the account's oldest transactions, up to `max`, can be
+    /// archived
+    fn archivable(&self, account: &Account, max: usize) -> usize {
+        let in_review: HashSet<&str> = self
+            .review_queue
+            .iter()
+            .filter(|flag| flag.status == ReviewStatus::Pending)
+            .map(|flag| flag.transaction_id.as_str())
+            .collect();
+        let history = &account.transactions;
+        let mut count = max.min(history.len());
+        if let Some(open) = history[..count]
+            .iter()
+            .position(|tx| tx.status == TransactionStatus::Pending || in_review.contains(tx.id.as_str()))
+        {
+            count = open;
+        }
+
+        // Stopping earlier can keep more reversals live, so repeat until
+        // no archived transaction is reversed by a live one
+        loop {
+            let live: HashSet<&TransactionId> = history[count..].iter().map(|tx| &tx.id).collect();
+            let reversed_live = history[..count]
+                .iter()
+                .position(|tx| tx.reversed_by.as_ref().is_some_and(|id| live.contains(id)));
+            match reversed_live {
+                Some(stop) => count = stop,
+                None => return count,
+            }
+        }
+    }
+
+    /// Moves the account's transactions up to and including `through` out
+    /// of its live history, for `apply_event`
+    ///
+    /// # Returns
+    /// * `Ok(false)` - If `through` is no longer live, i.e. the event was
+    ///   already applied
+    pub(crate) fn apply_archived(
+        &mut self,
+        customer_id: &CustomerId,
+        before: DateTime<Utc>,
+        through: &TransactionId,
+    ) -> BankResult<bool> {
+        let account = self.get_customer(customer_id)?.get_account()?;
+        let Some(last) = account.transactions.iter().position(|tx| tx.id == *through) else { return Ok(false) };
+        let count = last + 1;
+
+        // Their entries leave the books too, netted into one
+        let ids: HashSet<TransactionId> = account.transactions[..count].iter().map(|tx| tx.id.clone()).collect();
+        let at = account.transactions[count - 1].timestamp;
+        let books = self.books_mut();
+        let (entries, kept): (Vec<BookEntry>, Vec<BookEntry>) = std::mem::take(books)
+            .into_iter()
+            .partition(|entry| entry.transaction_id.as_ref().is_some_and(|id| ids.contains(id)));
+        *books = kept;
+        let entry = BookEntry::brought_forward(at, &entries);
+
+        let account = self.customers.get_mut(customer_id).expect("customer was just found").get_account_mut()?;
+        let archived: Vec<Transaction> = account.transactions.drain(..count).collect();
+        let opening_balance = archived[count - 1].balance_after;
+        let (earlier, before) = match &account.archived {
+            Some(earlier) => (earlier.transactions, before.max(earlier.before)),
+            None => (0, before),
+        };
+        account.archived = Some(ArchivedHistory { before, opening_balance, transactions: earlier + count });
+        for transaction in &archived {
+            self.transaction_index.remove(&transaction.id);
+            self.forget_undo(&transaction.id);
+        }
+        self.books_mut().push(entry);
+        Ok(true)
+    }
+}
+
+/// The error for reading archived history with no archive attached
+pub(crate) fn no_archive(account_id: &AccountId) -> BankError {
+    BankError::InvalidInput(format!("account {} has archived history but no archive is attached", account_id))
+}
+
+/// How many of the account's oldest transactions come before its first
+/// withdrawal, bill payment or outgoing transfer this month, which the
+/// daily withdrawal and transfer limits and the savings monthly
+/// withdrawal count still read from the live history
+fn uncounted_by_limits(account: &Account, now: DateTime<Utc>) -> usize {
+    account
+        .transactions
+        .iter()
+        .position(|tx| {
+            (tx.timestamp.year(), tx.timestamp.month()) == (now.year(), now.month())
+                && (matches!(tx.transaction_type, TransactionType::Withdrawal | TransactionType::BillPayment { .. })
+                    || tx.transaction_type.is_transfer_out())
+        })
+        .unwrap_or(account.transactions.len())
+}
@@ -0,0 +1,169 @@
+//! Async bank facade for tokio-based services (`async` feature)
+//!
+//! Demonstrates: tokio's async-aware RwLock, owned lock guards that can
+//! move into `spawn_blocking`, downgrading a write lock to a read lock
+//! https://docs.rs/tokio/latest/tokio/sync/struct.RwLock.html
+//!
+//! `SharedBank` uses a std lock, which parks the whole worker thread while
+//! it waits. `AsyncBank` waits on the lock by yielding to the runtime, and
+//! writes the journal on tokio's blocking thread pool, so a slow disk never
+//! stalls other requests.
+
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use tokio::sync::{OwnedRwLockReadGuard, RwLock, RwLockReadGuard};
+
+use crate::errors::{BankError, BankResult, Cause};
+use crate::models::{CustomerId, Money, Statement, TransactionDetails};
+use crate::persistence::Storage;
+use super::core::Bank;
+use super::report::BankReport;
+
+/// A cloneable handle for using a bank from async code
+///
+/// ```
+/// use rust_banking_system::{AccountType, AsyncBank, Bank, Money};
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let bank = AsyncBank::new(Bank::new("Demo Bank".to_string()));
+/// let id = bank
+///     .update(|bank| {
+///         let id = bank.register_customer("Alice".to_string(), "alice@example.com".to_string())?;
+///         bank.create_account_for_customer(&id, Money::ZERO, AccountType::Checking)?;
+///         Ok(id)
+///     })
+///     .await
+///     .unwrap();
+///
+/// let balance = bank.deposit(&id, Money::from_major(25)).await.unwrap();
+/// assert_eq!(balance, Money::from_major(25));
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct AsyncBank {
+    inner: Arc<RwLock<Bank>>,
+    storage: Option<Arc<dyn Storage>>,
+}
+
+impl AsyncBank {
+    /// Wraps a bank that is only kept in memory
+    pub fn new(bank: Bank) -> Self {
+        Self { inner: Arc::new(RwLock::new(bank)), storage: None }
+    }
+
+    /// Wraps a bank whose changes are journaled to `storage`
+    pub fn with_storage(mut bank: Bank, storage: Arc<dyn Storage>) -> Self {
+        bank.enable_journal();
+        Self { inner: Arc::new(RwLock::new(bank)), storage: Some(storage) }
+    }
+
+    /// Locks the bank for reading, waiting without blocking the runtime
+    pub async fn read(&self) -> RwLockReadGuard<'_, Bank> {
+        self.inner.read().await
+    }
+
+    /// Runs `operation` on the bank and journals the changes it made
+    ///
+    /// The journal is written on the blocking thread pool while a read
+    /// lock is held: readers carry on, and the next change waits so that
+    /// journal entries stay in order. A journal write failure is logged
+    /// but does not fail the operation, as in the HTTP server: the change
+    /// is already applied in memory and goes out with the next save.
+    pub async fn update<T>(&self, operation: impl FnOnce(&mut Bank) -> BankResult<T>) -> BankResult<T> {
+        let mut bank = self.inner.clone().write_owned().await;
+        let result = operation(&mut bank);
+
+        let entries = bank.take_journal();
+        if let (Some(storage), false) = (&self.storage, entries.is_empty()) {
+            let bank = bank.downgrade();
+            if let Err(e) = persist(storage.clone(), bank, move |storage, bank| {
+                storage.append_journal(bank, &entries)
+            })
+            .await
+            {
+                eprintln!("⚠️  Could not save changes: {}", e);
+            }
+        }
+        result
+    }
+
+    /// Deposits money into a customer's account
+    ///
+    /// # Returns
+    /// The new balance after the deposit
+    pub async fn deposit(&self, customer_id: &CustomerId, amount: Money) -> BankResult<Money> {
+        self.update(|bank| bank.deposit(customer_id, amount)).await
+    }
+
+    /// Withdraws money from a customer's account
+    ///
+    /// # Returns
+    /// The new balance after the withdrawal
+    pub async fn withdraw(&self, customer_id: &CustomerId, amount: Money) -> BankResult<Money> {
+        self.update(|bank| bank.withdraw(customer_id, amount)).await
+    }
+
+    /// Transfers money between two customers' accounts
+    pub async fn transfer(
+        &self,
+        from_customer_id: &CustomerId,
+        to_customer_id: &CustomerId,
+        amount: Money,
+    ) -> BankResult<()> {
+        self.update(|bank| bank.transfer(from_customer_id, to_customer_id, amount)).await
+    }
+
+    /// Transfers money with a description and tags
+    pub async fn transfer_with(
+        &self,
+        from_customer_id: &CustomerId,
+        to_customer_id: &CustomerId,
+        amount: Money,
+        details: TransactionDetails,
+    ) -> BankResult<()> {
+        self.update(|bank| bank.transfer_with(from_customer_id, to_customer_id, amount, details))
+            .await
+    }
+
+    /// Builds a customer's statement under the read lock
+    pub async fn statement(
+        &self,
+        customer_id: &CustomerId,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> BankResult<Statement> {
+        self.read().await.statement(customer_id, from, to)
+    }
+
+    /// Builds the statistics report under the read lock
+    pub async fn report(&self) -> BankReport {
+        self.read().await.report()
+    }
+
+    /// Writes a full snapshot to the storage backend
+    ///
+    /// # Returns
+    /// * `Err(BankError::IoError)` - If there is no storage backend or the
+    ///   write failed
+    pub async fn save(&self) -> BankResult<()> {
+        let storage = self
+            .storage
+            .clone()
+            .ok_or_else(|| BankError::IoError("this bank has no storage backend".into()))?;
+        let bank = self.inner.clone().read_owned().await;
+        persist(storage, bank, |storage, bank| storage.save_bank(bank)).await
+    }
+}
+
+/// Runs a storage call on the blocking thread pool
+async fn persist(
+    storage: Arc<dyn Storage>,
+    bank: OwnedRwLockReadGuard<Bank>,
+    write: impl FnOnce(&dyn Storage, &Bank) -> BankResult<()> + Send + 'static,
+) -> BankResult<()> {
+    tokio::task::spawn_blocking(move || write(storage.as_ref(), &bank))
+        .await
+        .map_err(|e| BankError::IoError(Cause::new(e)))?
+}
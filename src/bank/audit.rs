@@ -0,0 +1,258 @@
+//! Integrity checker - re-derives balances and cross-checks transfers
+//!
+//! Demonstrates: HashMap as a multiset for pairing records, internally
+//! tagged serde enums for machine-readable findings
+//!
+//! The checker only reads the bank. It trusts nothing that is stored as a
+//! running total: every balance is rebuilt from the transaction history,
+//! and every transfer must have its other half in the other account.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::models::{AccountId, CustomerId, Money, TransactionId, TransactionType};
+use super::core::Bank;
+
+/// One problem found by `Bank::verify_integrity`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "issue", rename_all = "snake_case")]
+pub enum IntegrityIssue {
+    /// The stored balance differs from the sum of the transactions
+    BalanceMismatch { customer_id: CustomerId, account_id: AccountId, recorded: Money, derived: Money },
+    /// A transaction's `balance_after` differs from the running balance
+    RunningBalanceMismatch {
+        customer_id: CustomerId,
+        transaction_id: TransactionId,
+        recorded: Money,
+        derived: Money,
+    },
+    /// The balance is below zero; no account type allows an overdraft
+    NegativeBalance { customer_id: CustomerId, account_id: AccountId, balance: Money },
+    /// A transfer names an account that does not exist
+    OrphanedTransfer { customer_id: CustomerId, transaction_id: TransactionId, counterparty: AccountId },
+    /// A transfer's other half is missing from the counterparty account
+    UnmatchedTransfer { customer_id: CustomerId, transaction_id: TransactionId, counterparty: AccountId },
+    /// A reversal names a transaction that is not in the same account
+    OrphanedReversal { customer_id: CustomerId, transaction_id: TransactionId, original_id: TransactionId },
+}
+
+impl fmt::Display for IntegrityIssue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            IntegrityIssue::BalanceMismatch { customer_id, recorded, derived, .. } => write!(
+                f,
+                "customer {}: balance is {} but the transactions add up to {}",
+                customer_id, recorded.display(), derived.display()
+            ),
+            IntegrityIssue::RunningBalanceMismatch { customer_id, transaction_id, recorded, derived } => write!(
+                f,
+                "customer {}: transaction {} records a balance of {}, expected {}",
+                customer_id, transaction_id, recorded.display(), derived.display()
+            ),
+            IntegrityIssue::NegativeBalance { customer_id, balance, .. } => {
+                write!(f, "customer {}: negative balance {}", customer_id, balance.display())
+            }
+            IntegrityIssue::OrphanedTransfer { customer_id, transaction_id, counterparty } => write!(
+                f,
+                "customer {}: transfer {} refers to unknown account {}",
+                customer_id, transaction_id, counterparty
+            ),
+            IntegrityIssue::UnmatchedTransfer { customer_id, transaction_id, counterparty } => write!(
+                f,
+                "customer {}: transfer {} has no matching entry in account {}",
+                customer_id, transaction_id, counterparty
+            ),
+            IntegrityIssue::OrphanedReversal { customer_id, transaction_id, original_id } => write!(
+                f,
+                "customer {}: reversal {} refers to unknown transaction {}",
+                customer_id, transaction_id, original_id
+            ),
+        }
+    }
+}
+
+/// The outcome of an integrity check
+#[derive(Debug, Clone, Serialize)]
+pub struct IntegrityReport {
+    pub checked_at: DateTime<Utc>,
+    pub accounts_checked: usize,
+    pub transactions_checked: usize,
+    /// Problems found, in the order they were found
+    pub issues: Vec<IntegrityIssue>,
+}
+
+impl IntegrityReport {
+    /// True if no problems were found
+    pub fn is_clean(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+impl fmt::Display for IntegrityReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "Checked {} account(s) and {} transaction(s)",
+            self.accounts_checked, self.transactions_checked
+        )?;
+        if self.is_clean() {
+            return write!(f, "✅ No problems found");
+        }
+        writeln!(f, "❌ {} problem(s) found:", self.issues.len())?;
+        for issue in &self.issues {
+            writeln!(f, "  - {}", issue)?;
+        }
+        Ok(())
+    }
+}
+
+/// The two halves of a transfer agree on these
+type TransferKey<'a> = (&'a str, &'a str, Money, DateTime<Utc>);
+
+impl Bank {
+    /// Checks every account, including closed ones and those of deleted
+    /// customers, for inconsistent records
+    ///
+    /// Each balance is re-derived from the account's transaction history
+    /// and compared with the stored balance and with every transaction's
+    /// `balance_after`, starting from the opening balance of any archived
+    /// history. Transfers must name an existing account that holds the
+    /// matching other half (same amount and time) unless it may be
+    /// archived, and reversals must name a transaction in the same account.
+    pub fn verify_integrity(&self) -> IntegrityReport {
+        let mut customers: Vec<_> = self.customers.values().filter(|c| c.account.is_some()).collect();
+        customers.sort_by(|a, b| a.id.cmp(&b.id));
+        let account_ids: HashSet<&str> = customers
+            .iter()
+            .filter_map(|c| c.account.as_ref())
+            .map(|a| a.id.as_str())
+            .collect();
+        // A transfer half no newer than the other account's archive cutoff
+        // may have its other half in the archive
+        let cutoffs: HashMap<&AccountId, DateTime<Utc>> = customers
+            .iter()
+            .filter_map(|c| c.account.as_ref())
+            .filter_map(|a| Some((&a.id, a.archived.as_ref()?.before)))
+            .collect();
+        let archived_half =
+            |account_id: &AccountId, at: DateTime<Utc>| cutoffs.get(account_id).is_some_and(|&cutoff| at <= cutoff);
+
+        // Incoming halves not yet claimed by an outgoing one
+        let mut incoming: HashMap<TransferKey, usize> = HashMap::new();
+        for account in customers.iter().filter_map(|c| c.account.as_ref()) {
+            for tx in &account.transactions {
+                if let TransactionType::TransferIn { from_account_id } = &tx.transaction_type {
+                    let key = (from_account_id.as_str(), account.id.as_str(), tx.amount, tx.timestamp);
+                    *incoming.entry(key).or_default() += 1;
+                }
+            }
+        }
+
+        let mut issues = Vec::new();
+        let mut transactions_checked = 0;
+        for customer in &customers {
+            let Some(account) = &customer.account else { continue };
+            let ids: HashSet<&str> = account.transactions.iter().map(|tx| tx.id.as_str()).collect();
+
+            // Archived history is summed up in the opening balance
+            let mut derived = account.archived.as_ref().map_or(Money::ZERO, |archived| archived.opening_balance);
+            for tx in &account.transactions {
+                transactions_checked += 1;
+                derived += tx.signed_amount();
+                if tx.balance_after != derived {
+                    issues.push(IntegrityIssue::RunningBalanceMismatch {
+                        customer_id: customer.id.clone(),
+                        transaction_id: tx.id.clone(),
+                        recorded: tx.balance_after,
+                        derived,
+                    });
+                }
+
+                match &tx.transaction_type {
+                    TransactionType::Transfer { to_account_id } if !account_ids.contains(to_account_id.as_str()) => {
+                        issues.push(IntegrityIssue::OrphanedTransfer {
+                            customer_id: customer.id.clone(),
+                            transaction_id: tx.id.clone(),
+                            counterparty: to_account_id.clone(),
+                        });
+                    }
+                    TransactionType::Transfer { to_account_id } => {
+                        let key = (account.id.as_str(), to_account_id.as_str(), tx.amount, tx.timestamp);
+                        match incoming.get_mut(&key) {
+                            Some(count) if *count > 0 => *count -= 1,
+                            _ if archived_half(to_account_id, tx.timestamp) => {}
+                            _ => issues.push(IntegrityIssue::UnmatchedTransfer {
+                                customer_id: customer.id.clone(),
+                                transaction_id: tx.id.clone(),
+                                counterparty: to_account_id.clone(),
+                            }),
+                        }
+                    }
+                    TransactionType::TransferIn { from_account_id }
+                        if !account_ids.contains(from_account_id.as_str()) =>
+                    {
+                        issues.push(IntegrityIssue::OrphanedTransfer {
+                            customer_id: customer.id.clone(),
+                            transaction_id: tx.id.clone(),
+                            counterparty: from_account_id.clone(),
+                        });
+                    }
+                    TransactionType::Reversal { original_id, .. } if !ids.contains(original_id.as_str()) => {
+                        issues.push(IntegrityIssue::OrphanedReversal {
+                            customer_id: customer.id.clone(),
+                            transaction_id: tx.id.clone(),
+                            original_id: original_id.clone(),
+                        });
+                    }
+                    _ => {}
+                }
+            }
+
+            if account.balance != derived {
+                issues.push(IntegrityIssue::BalanceMismatch {
+                    customer_id: customer.id.clone(),
+                    account_id: account.id.clone(),
+                    recorded: account.balance,
+                    derived,
+                });
+            }
+            if account.balance.is_negative() {
+                issues.push(IntegrityIssue::NegativeBalance {
+                    customer_id: customer.id.clone(),
+                    account_id: account.id.clone(),
+                    balance: account.balance,
+                });
+            }
+        }
+
+        // Incoming halves whose sender exists but never recorded the transfer
+        for customer in &customers {
+            let Some(account) = &customer.account else { continue };
+            for tx in &account.transactions {
+                let TransactionType::TransferIn { from_account_id } = &tx.transaction_type else { continue };
+                if !account_ids.contains(from_account_id.as_str()) || archived_half(from_account_id, tx.timestamp) {
+                    continue;
+                }
+                let key = (from_account_id.as_str(), account.id.as_str(), tx.amount, tx.timestamp);
+                if let Some(count) = incoming.get_mut(&key).filter(|count| **count > 0) {
+                    *count -= 1;
+                    issues.push(IntegrityIssue::UnmatchedTransfer {
+                        customer_id: customer.id.clone(),
+                        transaction_id: tx.id.clone(),
+                        counterparty: from_account_id.clone(),
+                    });
+                }
+            }
+        }
+
+        IntegrityReport {
+            checked_at: self.now(),
+            accounts_checked: customers.len(),
+            transactions_checked,
+            issues,
+        }
+    }
+}
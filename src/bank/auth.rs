@@ -0,0 +1,146 @@
+//! Authentication - customer PINs, staff logins and roles
+//!
+//! Demonstrates: Working on a copy and emitting it as an event, recording
+//! state changes that happen on the failure path
+
+use crate::errors::{BankError, BankResult};
+use crate::models::{Credentials, CustomerId, Role, StaffMember};
+use super::core::Bank;
+use super::events::BankEvent;
+
+impl Bank {
+    /// Sets the first PIN for a customer
+    ///
+    /// # Returns
+    /// * `Err(BankError::InvalidInput)` - If the PIN is malformed or one is
+    ///   already set (use `change_pin` instead)
+    pub fn set_pin(&mut self, customer_id: &CustomerId, pin: &str) -> BankResult<()> {
+        if self.get_customer(customer_id)?.credentials.is_some() {
+            return Err(BankError::InvalidInput("PIN is already set".to_string()));
+        }
+
+        self.emit(BankEvent::CredentialsUpdated {
+            customer_id: customer_id.clone(),
+            credentials: Credentials::new(pin)?,
+        })
+    }
+
+    /// Checks a customer's PIN
+    ///
+    /// Wrong PINs are counted even though the call fails, and after too
+    /// many the customer is locked out for a while.
+    ///
+    /// # Returns
+    /// * `Err(BankError::PinNotSet)` - If the customer has no PIN yet
+    /// * `Err(BankError::CustomerDeactivated)` - If the customer is deactivated
+    /// * `Err(BankError::InvalidPin)` / `Err(BankError::PinLocked)` - On failure
+    pub fn authenticate(&mut self, customer_id: &CustomerId, pin: &str) -> BankResult<()> {
+        let before = self
+            .active_customer(customer_id)?
+            .credentials
+            .clone()
+            .ok_or_else(|| BankError::PinNotSet(customer_id.clone()))?;
+
+        let mut credentials = before.clone();
+        let result = credentials.verify(pin, self.now());
+
+        // Failure counters must survive a restart, or lockout is pointless
+        if credentials != before {
+            self.emit(BankEvent::CredentialsUpdated {
+                customer_id: customer_id.clone(),
+                credentials,
+            })?;
+        }
+        result
+    }
+
+    /// Replaces a customer's PIN after checking the current one
+    pub fn change_pin(&mut self, customer_id: &CustomerId, current_pin: &str, new_pin: &str) -> BankResult<()> {
+        // Validate first so a malformed new PIN doesn't cost an attempt
+        Credentials::validate_pin(new_pin)?;
+        self.authenticate(customer_id, current_pin)?;
+
+        self.emit(BankEvent::CredentialsUpdated {
+            customer_id: customer_id.clone(),
+            credentials: Credentials::new(new_pin)?,
+        })
+    }
+
+    /// Adds an admin or teller login
+    ///
+    /// # Returns
+    /// * `Err(BankError::InvalidInput)` - If the username is blank or taken,
+    ///   the role is not a staff role, or the PIN is malformed
+    pub fn add_staff(&mut self, username: &str, role: Role, pin: &str) -> BankResult<()> {
+        let username = username.trim();
+        if username.is_empty() {
+            return Err(BankError::InvalidInput("username must not be empty".to_string()));
+        }
+        if !role.is_staff() {
+            return Err(BankError::InvalidInput("staff role must be admin or teller".to_string()));
+        }
+        if self.staff.contains_key(&username.to_lowercase()) {
+            return Err(BankError::InvalidInput(format!("user '{}' already exists", username)));
+        }
+
+        let member = StaffMember {
+            username: username.to_string(),
+            role,
+            credentials: Credentials::new(pin)?,
+        };
+        self.emit(BankEvent::StaffUpdated { member })
+    }
+
+    /// Whether any staff logins exist (a new bank has none until the
+    /// first admin is created)
+    pub fn has_staff(&self) -> bool {
+        !self.staff.is_empty()
+    }
+
+    /// Lists staff logins sorted by username
+    pub fn list_staff(&self) -> Vec<&StaffMember> {
+        let mut staff: Vec<_> = self.staff.values().collect();
+        staff.sort_by(|a, b| a.username.cmp(&b.username));
+        staff
+    }
+
+    /// Checks a staff member's PIN and returns their role
+    ///
+    /// Lockout works the same way as for customers.
+    ///
+    /// # Returns
+    /// * `Err(BankError::AuthenticationFailed)` - If no staff member has
+    ///   this username
+    pub fn authenticate_staff(&mut self, username: &str, pin: &str) -> BankResult<Role> {
+        let before = self
+            .staff_member(username)
+            .map_err(|_| BankError::AuthenticationFailed { user: username.trim().to_string() })?
+            .clone();
+
+        let mut member = before.clone();
+        let result = member.credentials.verify(pin, self.now());
+        let role = member.role;
+
+        if member != before {
+            self.emit(BankEvent::StaffUpdated { member })?;
+        }
+        result.map(|()| role)
+    }
+
+    /// Replaces a staff member's PIN after checking the current one
+    pub fn change_staff_pin(&mut self, username: &str, current_pin: &str, new_pin: &str) -> BankResult<()> {
+        Credentials::validate_pin(new_pin)?;
+        self.authenticate_staff(username, current_pin)?;
+
+        let mut member = self.staff_member(username)?.clone();
+        member.credentials = Credentials::new(new_pin)?;
+        self.emit(BankEvent::StaffUpdated { member })
+    }
+
+    /// Looks up a staff member by (case-insensitive) username
+    fn staff_member(&self, username: &str) -> BankResult<&StaffMember> {
+        self.staff
+            .get(&username.trim().to_lowercase())
+            .ok_or_else(|| BankError::InvalidInput(format!("unknown user '{}'", username.trim())))
+    }
+}
@@ -0,0 +1,107 @@
+//! Authorizations - holding money for a withdrawal before posting it
+//!
+//! Demonstrates: Keeping records that are not final yet beside the
+//! history rather than in it, and deriving a second balance from them
+//!
+//! A card payment is usually approved before the merchant asks for the
+//! money. `authorize_withdrawal` approves it and holds the amount: the
+//! available balance drops at once, the ledger balance only when the
+//! authorization is captured. Voiding it releases the hold. Authorizations
+//! wait on the account rather than in its history, so the history's
+//! running balances stay in order; a captured one is posted with the
+//! capture time.
+
+use crate::errors::{BankError, BankResult};
+use crate::models::{CustomerId, Money, Transaction, TransactionDetails, TransactionId, TransactionStatus};
+use super::core::Bank;
+use super::events::BankEvent;
+
+impl Bank {
+    /// Approves a withdrawal and holds the money until it is captured or
+    /// voided
+    ///
+    /// # Arguments
+    /// * `customer_id` - The customer whose money is held
+    /// * `amount` - Amount to hold, checked against the available balance
+    ///
+    /// # Returns
+    /// * `Ok(Transaction)` - The pending authorization; capture or void it
+    ///   by its ID
+    pub fn authorize_withdrawal(&mut self, customer_id: &CustomerId, amount: Money) -> BankResult<Transaction> {
+        self.authorize_withdrawal_with(customer_id, amount, TransactionDetails::new())
+    }
+
+    /// Approves a withdrawal with a description and tags, kept when it is
+    /// captured
+    ///
+    /// The same rules as for a withdrawal apply: account type, freezes and
+    /// withdrawal limits.
+    pub fn authorize_withdrawal_with(
+        &mut self,
+        customer_id: &CustomerId,
+        amount: Money,
+        details: TransactionDetails,
+    ) -> BankResult<Transaction> {
+        let mut authorization = self
+            .active_customer(customer_id)?
+            .get_account()?
+            .prepare_authorization(amount, self.now())?;
+        authorization.annotate(details);
+
+        self.emit(BankEvent::WithdrawalAuthorized {
+            customer_id: customer_id.clone(),
+            authorization: authorization.clone(),
+        })?;
+        Ok(authorization)
+    }
+
+    /// Posts an authorized withdrawal, taking the held money from the
+    /// ledger balance
+    ///
+    /// Works even if the account has been frozen since it was authorized.
+    ///
+    /// # Returns
+    /// * `Ok(Transaction)` - The completed withdrawal
+    /// * `Err(BankError::InvalidInput)` - If there is no pending
+    ///   authorization with this ID
+    pub fn capture(&mut self, authorization_id: &TransactionId) -> BankResult<Transaction> {
+        let (customer_id, authorization) = self.pending_authorization(authorization_id)?;
+        let transaction = self
+            .get_customer(&customer_id)?
+            .get_account()?
+            .prepare_capture(&authorization.id, self.now())?;
+
+        self.emit(BankEvent::AuthorizationCaptured { customer_id, transaction: transaction.clone() })?;
+        Ok(transaction)
+    }
+
+    /// Cancels an authorized withdrawal and releases the held money
+    ///
+    /// # Returns
+    /// * `Ok(Transaction)` - The authorization, now reversed
+    /// * `Err(BankError::InvalidInput)` - If there is no pending
+    ///   authorization with this ID
+    pub fn void(&mut self, authorization_id: &TransactionId) -> BankResult<Transaction> {
+        let (customer_id, mut authorization) = self.pending_authorization(authorization_id)?;
+
+        self.emit(BankEvent::AuthorizationVoided {
+            customer_id,
+            authorization_id: authorization.id.clone(),
+        })?;
+        authorization.status = TransactionStatus::Reversed;
+        Ok(authorization)
+    }
+
+    /// Finds a pending authorization by ID; returns its customer's ID and
+    /// a copy of it
+    fn pending_authorization(&self, authorization_id: &TransactionId) -> BankResult<(CustomerId, Transaction)> {
+        self.customers
+            .values()
+            .filter_map(|customer| Some((customer, customer.account.as_ref()?)))
+            .find_map(|(customer, account)| {
+                let authorization = account.authorizations.iter().find(|auth| auth.id == *authorization_id)?;
+                Some((customer.id.clone(), authorization.clone()))
+            })
+            .ok_or_else(|| BankError::InvalidInput(format!("authorization '{}' not found", authorization_id)))
+    }
+}
@@ -0,0 +1,160 @@
+//! Batch processing - many postings in one call
+//!
+//! Demonstrates: Internally tagged serde enums, validating against a
+//! scratch copy before committing, swapping state with a single assignment
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::errors::{BankError, BankResult};
+use crate::models::{Money, TransactionDetails};
+use super::core::Bank;
+use super::outbox::Outbox;
+
+/// One posting in a batch
+///
+/// Customers may be given by ID, unique ID prefix, email or account
+/// number, as in the CLI. In JSON the kind is the `type` field:
+///
+/// ```
+/// use rust_banking_system::bank::Operation;
+///
+/// let op: Operation = serde_json::from_str(
+///     r#"{"type": "transfer", "from": "alice@example.com", "to": "bob@example.com", "amount": "12.50"}"#,
+/// ).unwrap();
+/// assert!(matches!(op, Operation::Transfer { .. }));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum Operation {
+    Deposit {
+        customer: String,
+        amount: Money,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        description: Option<String>,
+    },
+    Withdraw {
+        customer: String,
+        amount: Money,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        description: Option<String>,
+    },
+    Transfer {
+        from: String,
+        to: String,
+        amount: Money,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        description: Option<String>,
+    },
+}
+
+impl fmt::Display for Operation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Operation::Deposit { customer, amount, .. } => write!(f, "deposit {} to {}", amount.display(), customer),
+            Operation::Withdraw { customer, amount, .. } => write!(f, "withdraw {} from {}", amount.display(), customer),
+            Operation::Transfer { from, to, amount, .. } => {
+                write!(f, "transfer {} from {} to {}", amount.display(), from, to)
+            }
+        }
+    }
+}
+
+/// What happens to the rest of a batch when an operation fails
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BatchMode {
+    /// Every valid operation is applied; failures are reported per operation
+    #[default]
+    BestEffort,
+    /// Either every operation is applied or none is
+    AllOrNothing,
+}
+
+impl Bank {
+    /// Applies a list of deposits, withdrawals and transfers in order
+    ///
+    /// Each operation sees the balances left by the ones before it. In
+    /// `AllOrNothing` mode the batch first runs against a copy of the bank;
+    /// only if every operation succeeds does the copy replace the bank, so
+    /// a failure leaves nothing applied. The operations that would have
+    /// succeeded then report `BankError::InvalidInput` naming the first
+    /// failure.
+    ///
+    /// # Returns
+    /// One result per operation, in order: the new balance of the account
+    /// the money left (or, for deposits, arrived in)
+    pub fn apply_batch(&mut self, operations: Vec<Operation>, mode: BatchMode) -> Vec<BankResult<Money>> {
+        if mode == BatchMode::BestEffort {
+            return operations.into_iter().map(|op| self.apply_operation(op)).collect();
+        }
+
+        // Journal the scratch copy on its own, so its entries can be added
+        // to ours if the batch commits; hold its outbox the same way, so
+        // nobody hears about postings that are rolled back
+        let journal = self.journal.take();
+        let held = self.held.take();
+        let mut scratch = self.clone();
+        scratch.journal = Some(Vec::new());
+        scratch.held = Some(Outbox::default());
+
+        let results: Vec<_> = operations.into_iter().map(|op| scratch.apply_operation(op)).collect();
+
+        match results.iter().position(Result::is_err) {
+            None => {
+                let entries = scratch.take_journal();
+                scratch.journal = journal.map(|mut journal| {
+                    journal.extend(entries);
+                    journal
+                });
+                let outbox = std::mem::replace(&mut scratch.held, held).unwrap_or_default();
+                *self = scratch;
+                self.send(outbox);
+                results
+            }
+            Some(failed) => {
+                self.journal = journal;
+                self.held = held;
+                results
+                    .into_iter()
+                    .map(|result| {
+                        result.and_then(|_| {
+                            Err(BankError::InvalidInput(format!(
+                                "not applied: operation {} failed",
+                                failed + 1
+                            )))
+                        })
+                    })
+                    .collect()
+            }
+        }
+    }
+
+    /// Applies a single batch operation
+    fn apply_operation(&mut self, operation: Operation) -> BankResult<Money> {
+        match operation {
+            Operation::Deposit { customer, amount, description } => {
+                let customer_id = self.resolve_customer(&customer)?;
+                self.deposit_with(&customer_id, amount, details(description)?)
+            }
+            Operation::Withdraw { customer, amount, description } => {
+                let customer_id = self.resolve_customer(&customer)?;
+                self.withdraw_with(&customer_id, amount, details(description)?)
+            }
+            Operation::Transfer { from, to, amount, description } => {
+                let from_id = self.resolve_customer(&from)?;
+                let to_id = self.resolve_customer(&to)?;
+                self.transfer_with(&from_id, &to_id, amount, details(description)?)?;
+                Ok(self.get_customer(&from_id)?.get_account()?.balance)
+            }
+        }
+    }
+}
+
+/// Turns an optional description into transaction details
+fn details(description: Option<String>) -> BankResult<TransactionDetails> {
+    match description.as_deref().map(str::trim) {
+        Some(description) if !description.is_empty() => TransactionDetails::new().description(description),
+        _ => Ok(TransactionDetails::new()),
+    }
+}
@@ -0,0 +1,131 @@
+//! Saved payees - transfers by nickname instead of by ID
+//!
+//! Demonstrates: Resolving a stored reference again at use time, since
+//! what it points to may have changed since it was saved
+//!
+//! A payee stores the account ID it was saved with. The account behind it
+//! is looked up again on every transfer, so a payee whose customer was
+//! deleted or whose account was closed is reported instead of paid.
+
+use crate::errors::{BankError, BankResult};
+use crate::models::{AccountStatus, Beneficiary, CustomerId, CustomerStatus, Money, TransactionDetails};
+use crate::validation;
+use super::core::Bank;
+use super::events::BankEvent;
+
+impl Bank {
+    /// Saves a payee under a nickname
+    ///
+    /// Nicknames are unique per customer, ignoring case.
+    ///
+    /// # Arguments
+    /// * `payee` - The payee's customer ID, account number, email or
+    ///   account ID (exact, not a prefix)
+    ///
+    /// # Returns
+    /// * `Ok(Beneficiary)` - The saved payee
+    /// * `Err(BankError::ValidationError)` - If the nickname is invalid or taken
+    /// * `Err(BankError::CustomerNotFound)` / `Err(BankError::AccountNotFound)` -
+    ///   If the payee has no account
+    /// * `Err(BankError::InvalidTransfer)` - If the payee is the customer
+    ///   themself
+    pub fn add_beneficiary(
+        &mut self,
+        customer_id: &CustomerId,
+        nickname: &str,
+        payee: &str,
+    ) -> BankResult<Beneficiary> {
+        let nickname = validation::validate_nickname(nickname)?;
+        let customer = self.active_customer(customer_id)?;
+        if customer.beneficiary(&nickname).is_some() {
+            return Err(BankError::ValidationError {
+                field: "nickname".to_string(),
+                reason: format!("'{}' is already used for another payee", nickname),
+            });
+        }
+
+        let payee = self
+            .find_customer(payee)
+            .ok_or_else(|| BankError::CustomerNotFound(payee.trim().into()))?;
+        if payee.id == *customer_id {
+            return Err(BankError::InvalidTransfer("Cannot save your own account as a payee".to_string()));
+        }
+        let account = payee.get_account()?;
+        account.ensure_open()?;
+
+        let beneficiary = Beneficiary { nickname, account_id: account.id.clone(), added_at: self.now() };
+        self.emit(BankEvent::BeneficiaryAdded {
+            customer_id: customer_id.clone(),
+            beneficiary: beneficiary.clone(),
+        })?;
+        Ok(beneficiary)
+    }
+
+    /// Deletes a saved payee
+    ///
+    /// # Returns
+    /// * `Ok(Beneficiary)` - The payee that was removed
+    /// * `Err(BankError::BeneficiaryNotFound)` - If there is no such nickname
+    pub fn remove_beneficiary(&mut self, customer_id: &CustomerId, nickname: &str) -> BankResult<Beneficiary> {
+        let beneficiary = self
+            .get_customer(customer_id)?
+            .beneficiary(nickname)
+            .cloned()
+            .ok_or_else(|| BankError::BeneficiaryNotFound(nickname.trim().to_string()))?;
+
+        self.emit(BankEvent::BeneficiaryRemoved {
+            customer_id: customer_id.clone(),
+            nickname: beneficiary.nickname.clone(),
+        })?;
+        Ok(beneficiary)
+    }
+
+    /// Lists a customer's saved payees, sorted by nickname
+    pub fn list_beneficiaries(&self, customer_id: &CustomerId) -> BankResult<Vec<&Beneficiary>> {
+        Ok(self.get_customer(customer_id)?.beneficiaries.values().collect())
+    }
+
+    /// Finds the customer a saved payee currently points to
+    ///
+    /// # Returns
+    /// * `Ok(String)` - The payee's customer ID
+    /// * `Err(BankError::BeneficiaryNotFound)` - If there is no such nickname
+    /// * `Err(BankError::InvalidTransfer)` - If the payee's account no
+    ///   longer exists or is closed
+    pub fn resolve_beneficiary(&self, customer_id: &CustomerId, nickname: &str) -> BankResult<CustomerId> {
+        let beneficiary = self
+            .get_customer(customer_id)?
+            .beneficiary(nickname)
+            .ok_or_else(|| BankError::BeneficiaryNotFound(nickname.trim().to_string()))?;
+
+        let gone = || {
+            BankError::InvalidTransfer(format!(
+                "payee '{}' no longer has an open account",
+                beneficiary.nickname
+            ))
+        };
+        let payee = self
+            .find_customer_by_account_id(&beneficiary.account_id)
+            .filter(|c| c.status != CustomerStatus::Deleted)
+            .ok_or_else(gone)?;
+        match &payee.account {
+            Some(account) if account.status != AccountStatus::Closed => Ok(payee.id.clone()),
+            _ => Err(gone()),
+        }
+    }
+
+    /// Transfers money to a saved payee
+    ///
+    /// Works like `transfer_with`, with the recipient looked up by nickname
+    /// in the sender's payees.
+    pub fn transfer_to_beneficiary(
+        &mut self,
+        from_customer_id: &CustomerId,
+        nickname: &str,
+        amount: Money,
+        details: TransactionDetails,
+    ) -> BankResult<()> {
+        let to_customer_id = self.resolve_beneficiary(from_customer_id, nickname)?;
+        self.transfer_with(from_customer_id, &to_customer_id, amount, details)
+    }
+}
@@ -0,0 +1,227 @@
+//! Bills - merchants registered with the bank, bill payments and autopay
+//!
+//! Demonstrates: Bank-wide reference data that customer operations point
+//! at by ID, and a recurring job recorded as events so it replays exactly
+//!
+//! A bill payment is a withdrawal with its own transaction type, so the
+//! account's status, balance and limits apply as usual. Autopay rules
+//! run like standing orders, but each run (paid or failed) is an event:
+//! replaying the journal gives the same payments and the same next dates.
+
+use chrono::{DateTime, Utc};
+
+use crate::errors::{BankError, BankResult};
+use crate::models::{Autopay, CustomerId, Frequency, Merchant, MerchantCategory, Money, Transaction, TransactionType};
+use crate::validation;
+use super::core::Bank;
+use super::events::BankEvent;
+
+/// Outcome of one autopay payment
+#[derive(Debug)]
+pub struct AutopayRun {
+    /// The rule that ran
+    pub autopay_id: String,
+
+    /// The payment date that was processed
+    pub run_at: DateTime<Utc>,
+
+    /// The bill payment, or why it could not be made
+    pub result: BankResult<Transaction>,
+}
+
+impl Bank {
+    /// Registers a company customers can pay bills to
+    ///
+    /// # Returns
+    /// * `Ok(Merchant)` - The new merchant
+    /// * `Err(BankError::ValidationError)` - If the name is empty
+    /// * `Err(BankError::InvalidInput)` - If the name is already taken
+    ///   (ignoring case)
+    pub fn add_merchant(&mut self, name: &str, category: MerchantCategory) -> BankResult<Merchant> {
+        let name = validation::validate_name(name)?;
+        if self.merchants.iter().any(|m| m.name.eq_ignore_ascii_case(&name)) {
+            return Err(BankError::InvalidInput(format!("merchant '{}' already exists", name)));
+        }
+
+        let merchant = Merchant::new_at(name, category, self.now());
+        self.emit(BankEvent::MerchantAdded { merchant: merchant.clone() })?;
+        Ok(merchant)
+    }
+
+    /// Removes a merchant; past payments to it keep their merchant ID
+    ///
+    /// # Returns
+    /// * `Err(BankError::MerchantNotFound)` - If there is no such merchant
+    /// * `Err(BankError::InvalidInput)` - If autopay rules still pay it
+    pub fn remove_merchant(&mut self, merchant: &str) -> BankResult<Merchant> {
+        let merchant = self.find_merchant(merchant)?.clone();
+        let rules = self.autopays.iter().filter(|a| a.merchant_id == merchant.id).count();
+        if rules > 0 {
+            return Err(BankError::InvalidInput(format!(
+                "{} autopay rule(s) still pay '{}'; cancel them first",
+                rules, merchant.name
+            )));
+        }
+
+        self.emit(BankEvent::MerchantRemoved { merchant_id: merchant.id.clone() })?;
+        Ok(merchant)
+    }
+
+    /// Every merchant, by name
+    pub fn list_merchants(&self) -> Vec<&Merchant> {
+        let mut merchants: Vec<_> = self.merchants.iter().collect();
+        merchants.sort_by_key(|m| m.name.to_lowercase());
+        merchants
+    }
+
+    /// Finds a merchant by ID or by name (ignoring case)
+    pub fn find_merchant(&self, merchant: &str) -> BankResult<&Merchant> {
+        let merchant = merchant.trim();
+        self.merchants
+            .iter()
+            .find(|m| m.id == merchant || m.name.eq_ignore_ascii_case(merchant))
+            .ok_or_else(|| BankError::MerchantNotFound(merchant.to_string()))
+    }
+
+    /// Pays a bill from a customer's account
+    ///
+    /// # Arguments
+    /// * `merchant` - The merchant's ID or name
+    /// * `reference` - The customer's reference at the merchant (see
+    ///   `validation::validate_bill_reference`)
+    ///
+    /// # Returns
+    /// * `Ok(Transaction)` - The bill payment, described with the merchant's
+    ///   name
+    /// * `Err(BankError::MerchantNotFound)` - If there is no such merchant
+    pub fn pay_bill(
+        &mut self,
+        customer_id: &CustomerId,
+        merchant: &str,
+        amount: Money,
+        reference: &str,
+    ) -> BankResult<Transaction> {
+        let merchant = self.find_merchant(merchant)?.clone();
+        let reference = validation::validate_bill_reference(reference)?;
+        let transaction = self.prepare_bill_payment(customer_id, &merchant, amount, reference)?;
+
+        self.emit(BankEvent::BillPaid { customer_id: customer_id.clone(), transaction: transaction.clone() })?;
+        Ok(transaction)
+    }
+
+    /// Sets up a recurring bill payment
+    ///
+    /// # Arguments
+    /// * `first_run` - When the first payment is due
+    ///
+    /// # Returns
+    /// * `Ok(Autopay)` - The new rule
+    /// * `Err(BankError::MerchantNotFound)` - If there is no such merchant
+    pub fn create_autopay(
+        &mut self,
+        customer_id: &CustomerId,
+        merchant: &str,
+        amount: Money,
+        reference: &str,
+        frequency: Frequency,
+        first_run: DateTime<Utc>,
+    ) -> BankResult<Autopay> {
+        if !amount.is_positive() {
+            return Err(BankError::InvalidAmount(amount));
+        }
+        let merchant_id = self.find_merchant(merchant)?.id.clone();
+        let reference = validation::validate_bill_reference(reference)?;
+        self.active_customer(customer_id)?.get_account()?.ensure_open()?;
+
+        let autopay = Autopay::new_at(customer_id, &merchant_id, reference, amount, frequency, first_run, self.now());
+        self.emit(BankEvent::AutopayCreated { autopay: autopay.clone() })?;
+        Ok(autopay)
+    }
+
+    /// Cancels an autopay rule so it never runs again
+    ///
+    /// # Returns
+    /// * `Err(BankError::ScheduleNotFound)` - If there is no such rule
+    pub fn cancel_autopay(&mut self, autopay_id: &str) -> BankResult<Autopay> {
+        let autopay_id = autopay_id.trim();
+        let autopay = self
+            .autopays
+            .iter()
+            .find(|a| a.id == autopay_id)
+            .cloned()
+            .ok_or_else(|| BankError::ScheduleNotFound(autopay_id.to_string()))?;
+
+        self.emit(BankEvent::AutopayCancelled { autopay_id: autopay.id.clone() })?;
+        Ok(autopay)
+    }
+
+    /// Every autopay rule, soonest first
+    pub fn list_autopays(&self) -> Vec<&Autopay> {
+        let mut autopays: Vec<_> = self.autopays.iter().collect();
+        autopays.sort_by_key(|a| a.next_run);
+        autopays
+    }
+
+    /// A customer's autopay rules, soonest first
+    pub fn autopays_for(&self, customer_id: &CustomerId) -> Vec<&Autopay> {
+        self.list_autopays().into_iter().filter(|a| a.customer_id == *customer_id).collect()
+    }
+
+    /// Makes every autopay payment due at or before `now`
+    ///
+    /// Missed payments are caught up one by one. A payment that fails
+    /// (e.g. insufficient funds) is recorded on the rule, which moves on to
+    /// its next date.
+    ///
+    /// # Returns
+    /// One `AutopayRun` per processed payment
+    pub fn run_due_autopays(&mut self, now: DateTime<Utc>) -> Vec<AutopayRun> {
+        let mut runs = Vec::new();
+
+        for idx in 0..self.autopays.len() {
+            while self.autopays.get(idx).is_some_and(|a| a.is_due(now)) {
+                let autopay = self.autopays[idx].clone();
+                let result = self.find_merchant(&autopay.merchant_id).cloned().and_then(|merchant| {
+                    self.prepare_bill_payment(&autopay.customer_id, &merchant, autopay.amount, autopay.reference.clone())
+                });
+
+                let (transaction, failure) = match &result {
+                    Ok(transaction) => (Some(transaction.clone()), None),
+                    Err(e) => (None, Some(e.to_string())),
+                };
+                let event = BankEvent::AutopayRan {
+                    autopay_id: autopay.id.clone(),
+                    customer_id: autopay.customer_id.clone(),
+                    run_at: autopay.next_run,
+                    transaction,
+                    failure,
+                };
+                // Without the event the rule would never advance, so stop
+                // rather than loop on it
+                if let Err(e) = self.emit(event) {
+                    runs.push(AutopayRun { autopay_id: autopay.id, run_at: autopay.next_run, result: Err(e) });
+                    break;
+                }
+
+                runs.push(AutopayRun { autopay_id: autopay.id, run_at: autopay.next_run, result });
+            }
+        }
+
+        runs
+    }
+
+    /// Builds the transaction a bill payment would post, without posting it
+    fn prepare_bill_payment(
+        &self,
+        customer_id: &CustomerId,
+        merchant: &Merchant,
+        amount: Money,
+        reference: String,
+    ) -> BankResult<Transaction> {
+        let account = self.active_customer(customer_id)?.get_account()?;
+        let mut transaction = account.prepare_withdrawal(amount, self.now())?;
+        transaction.transaction_type = TransactionType::BillPayment { merchant_id: merchant.id.clone(), reference };
+        transaction.description = Some(merchant.name.clone());
+        Ok(transaction)
+    }
+}
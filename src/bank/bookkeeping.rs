@@ -0,0 +1,373 @@
+//! Bookkeeping - the bank's books in double entry, and the trial balance
+//!
+//! Demonstrates: Keeping a second record of every change and checking the
+//! two against each other, a report that checks itself
+//!
+//! Every change that posts a customer transaction also posts its book
+//! entry, in `apply_event`, so replaying the journal rebuilds the books
+//! along with the accounts. `other_side` picks the internal account with
+//! the chart of accounts' mapping rules: clearing for cheques, fee income
+//! for fees, and so on; cash deposits and withdrawals always post against
+//! cash, and a reversal mirrors the entry of the transaction it undoes.
+//! A new rule applies to what is posted from then on; the books already
+//! posted stand. The bank's own operations, like topping up the vault,
+//! post entries with no customer side, and archiving a stretch of history
+//! nets its entries into one brought forward.
+//!
+//! `trial_balance` totals every account and checks that debits equal
+//! credits and that each customer's deposit in the books matches their
+//! account balance, so a balance or transaction changed without its entry
+//! shows up as unreconciled. `income_statement` totals the income and expense
+//! accounts over a period.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::errors::BankResult;
+use crate::format;
+use crate::models::{
+    Account, AccountClass, BookAccount, BookEntry, BookLine, ChartOfAccounts, CustomerId, Money, Transaction,
+    TransactionKind, TransactionType,
+};
+use super::core::Bank;
+use super::events::BankEvent;
+
+/// An account's net balance in the books; at most one side is non-zero
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TrialBalanceRow {
+    pub account: BookAccount,
+    pub code: String,
+    pub name: String,
+    pub class: AccountClass,
+    pub debit: Money,
+    pub credit: Money,
+}
+
+/// A customer whose deposit in the books differs from their balance
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Unreconciled {
+    pub customer_id: CustomerId,
+    pub books: Money,
+    pub balance: Money,
+}
+
+/// Every account's balance in the books, with the checks that prove them
+#[derive(Debug, Clone, Serialize)]
+pub struct TrialBalance {
+    pub bank: String,
+    pub generated_at: DateTime<Utc>,
+    pub entries: usize,
+
+    /// Accounts with postings, by code
+    pub rows: Vec<TrialBalanceRow>,
+    pub total_debits: Money,
+    pub total_credits: Money,
+
+    /// Entries whose own debits and credits differ, by memo
+    pub unbalanced_entries: Vec<String>,
+    pub unreconciled: Vec<Unreconciled>,
+}
+
+impl TrialBalance {
+    /// Whether the books balance and agree with every account
+    pub fn is_balanced(&self) -> bool {
+        self.total_debits == self.total_credits && self.unbalanced_entries.is_empty() && self.unreconciled.is_empty()
+    }
+}
+
+/// One income or expense account's total over the period
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct IncomeStatementLine {
+    pub account: BookAccount,
+    pub code: String,
+    pub name: String,
+    pub amount: Money,
+}
+
+/// Income less expenses over a period
+#[derive(Debug, Clone, Serialize)]
+pub struct IncomeStatement {
+    pub bank: String,
+    /// Start (inclusive) and end (exclusive); `None` leaves that end open
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+
+    /// Income and expense accounts, by code
+    pub income: Vec<IncomeStatementLine>,
+    pub expenses: Vec<IncomeStatementLine>,
+    pub total_income: Money,
+    pub total_expenses: Money,
+    pub net_income: Money,
+}
+
+impl Bank {
+    /// The chart of accounts and the rules that map transactions onto it
+    pub fn chart_of_accounts(&self) -> &ChartOfAccounts {
+        &self.chart
+    }
+
+    /// Replaces the chart of accounts for entries posted from now on
+    ///
+    /// # Returns
+    /// * `Err(BankError)` - If the chart fails `ChartOfAccounts::validate`
+    pub fn set_chart_of_accounts(&mut self, chart: ChartOfAccounts) -> BankResult<()> {
+        chart.validate()?;
+        self.emit(BankEvent::ChartOfAccountsChanged { chart })
+    }
+
+    /// Every entry in the books, oldest first
+    pub fn book_entries(&self) -> Vec<BookEntry> {
+        let mut entries = match &self.books {
+            Some(books) => books.clone(),
+            None => self.derived_books(),
+        };
+        entries.sort_by_key(|entry| entry.at);
+        entries
+    }
+
+    /// The books, to post to
+    ///
+    /// A bank loaded from a data file older than posted entries starts its
+    /// books from the account histories, the way those files were read.
+    pub(crate) fn books_mut(&mut self) -> &mut Vec<BookEntry> {
+        if self.books.is_none() {
+            let books = self.derived_books();
+            self.bank_entries.clear();
+            self.books = Some(books);
+        }
+        self.books.get_or_insert_with(Vec::new)
+    }
+
+    /// The entries behind every live transaction, and the bank's own
+    fn derived_books(&self) -> Vec<BookEntry> {
+        self.all_transactions()
+            .filter_map(|entry| {
+                let account = self.customers.get(entry.customer_id)?.account.as_ref()?;
+                Some(entry_for(&self.chart, account, entry.customer_id, entry.transaction))
+            })
+            .chain(self.bank_entries.iter().cloned())
+            .collect()
+    }
+
+    /// Totals the books and checks them against every account
+    pub fn trial_balance(&self) -> TrialBalance {
+        let entries = self.book_entries();
+        let mut totals: BTreeMap<BookAccount, Money> = BTreeMap::new();
+        let mut deposits: HashMap<&CustomerId, Money> = HashMap::new();
+        for line in entries.iter().flat_map(|entry| &entry.lines) {
+            *totals.entry(line.account).or_default() += line.debit - line.credit;
+            if let Some(customer_id) = &line.customer_id {
+                *deposits.entry(customer_id).or_default() += line.credit - line.debit;
+            }
+        }
+
+        let mut rows: Vec<TrialBalanceRow> = totals
+            .into_iter()
+            .map(|(account, net)| {
+                let (code, name, class) = self.chart_line(account);
+                let (debit, credit) = (net.max(Money::ZERO), (-net).max(Money::ZERO));
+                TrialBalanceRow { account, code, name, class, debit, credit }
+            })
+            .collect();
+        rows.sort_by(|a, b| a.code.cmp(&b.code));
+
+        let mut unreconciled: Vec<Unreconciled> = self
+            .customers
+            .values()
+            .filter_map(|customer| {
+                let balance = customer.account.as_ref()?.balance;
+                let books = deposits.get(&customer.id).copied().unwrap_or(Money::ZERO);
+                (books != balance).then(|| Unreconciled { customer_id: customer.id.clone(), books, balance })
+            })
+            .collect();
+        unreconciled.sort_by(|a, b| a.customer_id.cmp(&b.customer_id));
+
+        TrialBalance {
+            bank: self.name.clone(),
+            generated_at: self.now(),
+            entries: entries.len(),
+            total_debits: rows.iter().map(|row| row.debit).sum(),
+            total_credits: rows.iter().map(|row| row.credit).sum(),
+            rows,
+            unbalanced_entries: entries
+                .iter()
+                .filter(|entry| !entry.is_balanced())
+                .map(|entry| entry.to_string())
+                .collect(),
+            unreconciled,
+        }
+    }
+
+    /// Totals the income and expense accounts over entries from `from`
+    /// (inclusive) to `to` (exclusive); `None` leaves that end open
+    pub fn income_statement(&self, from: Option<DateTime<Utc>>, to: Option<DateTime<Utc>>) -> IncomeStatement {
+        let mut totals: BTreeMap<BookAccount, Money> = BTreeMap::new();
+        let in_period = |entry: &&BookEntry| {
+            from.is_none_or(|from| entry.at >= from) && to.is_none_or(|to| entry.at < to)
+        };
+        for line in self.book_entries().iter().filter(in_period).flat_map(|entry| &entry.lines) {
+            *totals.entry(line.account).or_default() += line.credit - line.debit;
+        }
+
+        let lines_of = |class: AccountClass| {
+            let mut lines: Vec<IncomeStatementLine> = BookAccount::ALL
+                .into_iter()
+                .filter(|account| self.chart_line(*account).2 == class)
+                .map(|account| {
+                    let (code, name, _) = self.chart_line(account);
+                    let credit = totals.get(&account).copied().unwrap_or(Money::ZERO);
+                    // Income is a credit balance, an expense a debit one
+                    let amount = if class == AccountClass::Income { credit } else { -credit };
+                    IncomeStatementLine { account, code, name, amount }
+                })
+                .collect();
+            lines.sort_by(|a, b| a.code.cmp(&b.code));
+            lines
+        };
+        let income = lines_of(AccountClass::Income);
+        let expenses = lines_of(AccountClass::Expense);
+        let total_income: Money = income.iter().map(|line| line.amount).sum();
+        let total_expenses: Money = expenses.iter().map(|line| line.amount).sum();
+
+        IncomeStatement {
+            bank: self.name.clone(),
+            from,
+            to,
+            income,
+            expenses,
+            total_income,
+            total_expenses,
+            net_income: total_income - total_expenses,
+        }
+    }
+
+    /// (code, name, class) of `account`, falling back to its built-in name
+    /// in a chart without a line for it
+    fn chart_line(&self, account: BookAccount) -> (String, String, AccountClass) {
+        match self.chart.line(account) {
+            Some(line) => (line.code.clone(), line.name.clone(), line.class),
+            None => (String::new(), account.to_string(), AccountClass::Liability),
+        }
+    }
+}
+
+/// The book entry for a transaction on `customer_id`'s account
+pub(crate) fn entry_for(
+    chart: &ChartOfAccounts,
+    account: &Account,
+    customer_id: &CustomerId,
+    transaction: &Transaction,
+) -> BookEntry {
+    let customer = match transaction.transaction_type.is_credit() {
+        true => BookLine::credit(BookAccount::CustomerDeposits, transaction.amount),
+        false => BookLine::debit(BookAccount::CustomerDeposits, transaction.amount),
+    };
+    let mut lines = vec![BookLine { customer_id: Some(customer_id.clone()), ..customer }];
+    lines.extend(other_side(chart, account, transaction));
+    BookEntry {
+        at: transaction.timestamp,
+        transaction_id: Some(transaction.id.clone()),
+        memo: transaction.transaction_type.to_string(),
+        lines,
+    }
+}
+
+/// The lines on the bank's side of a transaction's entry
+fn other_side(chart: &ChartOfAccounts, account: &Account, transaction: &Transaction) -> Vec<BookLine> {
+    let amount = transaction.amount;
+    let single = |book: BookAccount| match transaction.transaction_type.is_credit() {
+        true => vec![BookLine::debit(book, amount)],
+        false => vec![BookLine::credit(book, amount)],
+    };
+
+    match &transaction.transaction_type {
+        TransactionType::Deposit | TransactionType::Withdrawal if transaction.cash => single(BookAccount::Cash),
+        TransactionType::TermDeposit { deposit_id, credit: true } => {
+            // The payout is the principal plus interest, or less a penalty
+            let principal = account
+                .term_deposits
+                .iter()
+                .find(|deposit| &deposit.id == deposit_id)
+                .map_or(amount, |deposit| deposit.principal);
+            let mut lines = vec![BookLine::debit(chart.mapped(TransactionKind::TermDeposit), principal)];
+            if amount > principal {
+                lines.push(BookLine::debit(BookAccount::InterestExpense, amount - principal));
+            } else if amount < principal {
+                lines.push(BookLine::credit(BookAccount::FeeIncome, principal - amount));
+            }
+            lines
+        }
+        TransactionType::Reversal { original_id, .. } => {
+            match account.transactions.iter().find(|tx| &tx.id == original_id) {
+                Some(original) if original.amount == amount => {
+                    other_side(chart, account, original).iter().map(BookLine::mirrored).collect()
+                }
+                _ => single(BookAccount::Suspense),
+            }
+        }
+        other => single(chart.mapped(other.kind())),
+    }
+}
+
+impl fmt::Display for TrialBalance {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "════════════════════════════════════════════════════════════")?;
+        writeln!(f, "         TRIAL BALANCE: {}", self.bank)?;
+        writeln!(f, "════════════════════════════════════════════════════════════")?;
+        writeln!(f, "{:<6} {:<22} {:>15} {:>15}", "Code", "Account", "Debit", "Credit")?;
+        writeln!(f, "────────────────────────────────────────────────────────────")?;
+        let show = |amount: Money| if amount.is_zero() { String::new() } else { amount.display().to_string() };
+        for row in &self.rows {
+            writeln!(f, "{:<6} {:<22} {:>15} {:>15}", row.code, row.name, show(row.debit), show(row.credit))?;
+        }
+        writeln!(f, "────────────────────────────────────────────────────────────")?;
+        let (debits, credits) = (self.total_debits.display(), self.total_credits.display());
+        writeln!(f, "{:<29} {:>15} {:>15}", "Total", debits, credits)?;
+        writeln!(f)?;
+
+        if self.is_balanced() {
+            write!(f, "✅ The books balance over {} entries and agree with every account", self.entries)?;
+            return Ok(());
+        }
+        write!(f, "⚠️  The books do not balance")?;
+        for entry in &self.unbalanced_entries {
+            write!(f, "\n  Unbalanced entry: {}", entry.replace('\n', "\n  "))?;
+        }
+        for customer in &self.unreconciled {
+            write!(
+                f,
+                "\n  Customer {}: {} in the books, balance {}",
+                customer.customer_id,
+                customer.books.display(),
+                customer.balance.display()
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for IncomeStatement {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "════════════════════════════════════════════════════════════")?;
+        writeln!(f, "         INCOME STATEMENT: {}", self.bank)?;
+        let from = self.from.map_or_else(|| "the start".to_string(), |from| format::date(&from));
+        let to = self.to.map_or_else(|| "now".to_string(), |to| format::date(&to));
+        writeln!(f, "         From {} to {}", from, to)?;
+        writeln!(f, "════════════════════════════════════════════════════════════")?;
+        for (title, lines, total) in [
+            ("Income", &self.income, self.total_income),
+            ("Expenses", &self.expenses, self.total_expenses),
+        ] {
+            writeln!(f, "{}:", title)?;
+            for line in lines {
+                writeln!(f, "  {:<6} {:<34} {:>15}", line.code, line.name, line.amount.display())?;
+            }
+            writeln!(f, "  {:<41} {:>15}", format!("Total {}", title.to_lowercase()), total.display())?;
+        }
+        writeln!(f, "────────────────────────────────────────────────────────────")?;
+        write!(f, "{:<43} {:>15}", "Net income", self.net_income.display())
+    }
+}
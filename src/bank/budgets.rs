@@ -0,0 +1,83 @@
+//! Budgets - monthly spending limits per category
+//!
+//! Demonstrates: A check offered before an operation (`budget_warning`)
+//! and the same check raised as an alert after it
+//!
+//! Budgets never block spending. Callers that want to warn first ask
+//! `budget_warning`; any posting that takes a category past its budget
+//! also raises an `AlertKind::BudgetExceeded` alert (see `alerts`).
+
+use chrono::Datelike;
+
+use crate::errors::{BankError, BankResult};
+use crate::models::{Budget, BudgetStatus, CustomerId, Money};
+use crate::models::tags::normalize_label;
+use super::core::Bank;
+use super::events::BankEvent;
+
+impl Bank {
+    /// Sets the monthly budget for a category, replacing any earlier one
+    ///
+    /// # Arguments
+    /// * `category` - The spending category, as given to transactions
+    /// * `limit` - The most to spend in the category per calendar month
+    ///
+    /// # Returns
+    /// * `Ok(Budget)` - The budget as saved
+    /// * `Err(BankError::InvalidInput)` - If the category is blank or not a
+    ///   valid label
+    pub fn set_budget(&mut self, customer_id: &CustomerId, category: &str, limit: Money) -> BankResult<Budget> {
+        let category = normalize_label(category)?
+            .ok_or_else(|| BankError::InvalidInput("category must not be blank".to_string()))?;
+        if !limit.is_positive() {
+            return Err(BankError::InvalidAmount(limit));
+        }
+        self.active_customer(customer_id)?.get_account()?.ensure_open()?;
+
+        let budget = Budget::new_at(category, limit, self.now());
+        self.emit(BankEvent::BudgetSet { customer_id: customer_id.clone(), budget: budget.clone() })?;
+        Ok(budget)
+    }
+
+    /// Removes a category's budget
+    ///
+    /// # Returns
+    /// * `Err(BankError::BudgetNotFound)` - If the category has no budget
+    pub fn remove_budget(&mut self, customer_id: &CustomerId, category: &str) -> BankResult<Budget> {
+        let budget = self
+            .get_customer(customer_id)?
+            .get_account()?
+            .budget(category)
+            .cloned()
+            .ok_or_else(|| BankError::BudgetNotFound(category.trim().to_lowercase()))?;
+
+        self.emit(BankEvent::BudgetRemoved {
+            customer_id: customer_id.clone(),
+            category: budget.category.clone(),
+        })?;
+        Ok(budget)
+    }
+
+    /// A customer's budgets, in the order they were first set
+    pub fn list_budgets(&self, customer_id: &CustomerId) -> BankResult<&[Budget]> {
+        Ok(&self.get_customer(customer_id)?.get_account()?.budgets)
+    }
+
+    /// Spending against each of a customer's budgets in the current month
+    pub fn budget_report(&self, customer_id: &CustomerId) -> BankResult<Vec<BudgetStatus>> {
+        let now = self.now();
+        self.get_customer(customer_id)?.get_account()?.budget_report(now.year(), now.month())
+    }
+
+    /// The budget spending `amount` in `category` now would take past its
+    /// limit, if any (see `Account::budget_warning`)
+    pub fn budget_warning(
+        &self,
+        customer_id: &CustomerId,
+        category: Option<&str>,
+        amount: Money,
+    ) -> BankResult<Option<BudgetStatus>> {
+        let account = self.get_customer(customer_id)?.get_account()?;
+        Ok(account.budget_warning(category, amount, self.now()))
+    }
+}
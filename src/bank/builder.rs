@@ -0,0 +1,246 @@
+//! Bank builder - a bank and its settings in one expression
+//!
+//! Demonstrates: The builder pattern holding trait objects, borrowing a
+//! storage backend only for as long as the builder lives
+//!
+//! `Bank::new` only takes a name; everything else used to be set one call
+//! at a time afterwards, in an order that matters (an archive before the
+//! history cap, the journal before settings that should be saved with
+//! it). `BankBuilder` collects the options and applies them in that order
+//! in `build`. `register` and `open_account` do the same for customers and
+//! accounts, taking the builders from `models::builder`.
+//!
+//! There is no `currency` option: the bank keeps no currency of its own,
+//! and the symbol amounts are shown with is the process-wide
+//! `format::Format`, installed from the config. For the account options
+//! left out (overdraft limit, interest rate) see `models::builder`.
+
+use std::sync::Arc;
+
+use crate::clock::Clock;
+use crate::errors::{BankError, BankResult};
+use crate::models::{
+    AccountBuilder, AccountId, ChartOfAccounts, ChequePolicy, CustomerBuilder, CustomerId, DormancyPolicy, FraudRules,
+};
+use crate::notifications::Notifier;
+use crate::observer::BankObserver;
+use crate::persistence::Storage;
+use super::archive::HistoryArchive;
+use super::core::Bank;
+use super::events::BankEvent;
+
+/// A bank to be created or loaded (see `Bank::builder`)
+///
+/// ```
+/// use std::sync::Arc;
+/// use rust_banking_system::clock::MockClock;
+/// use rust_banking_system::models::FraudRules;
+/// use rust_banking_system::{Bank, Money};
+///
+/// let clock = Arc::new(MockClock::new(chrono::Utc::now()));
+/// let bank = Bank::builder("Campus Credit Union")
+///     .clock(clock)
+///     .fraud_rules(FraudRules { new_payee_transfer: Some(Money::from_major(1_000)), ..FraudRules::default() })
+///     .journal(true)
+///     .build()
+///     .unwrap();
+/// assert_eq!(bank.name, "Campus Credit Union");
+/// ```
+#[must_use = "a builder does nothing until `build` is called"]
+pub struct BankBuilder<'a> {
+    name: String,
+    storage: Option<&'a dyn Storage>,
+    clock: Option<Arc<dyn Clock>>,
+    archive: Option<Arc<dyn HistoryArchive>>,
+    history_cap: Option<usize>,
+    journal: bool,
+    fraud_rules: Option<FraudRules>,
+    cheque_policy: Option<ChequePolicy>,
+    dormancy_policy: Option<DormancyPolicy>,
+    chart: Option<ChartOfAccounts>,
+    notifiers: Vec<Arc<dyn Notifier>>,
+    observers: Vec<Arc<dyn BankObserver>>,
+}
+
+impl<'a> BankBuilder<'a> {
+    /// An empty bank called `name` with the default settings
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            storage: None,
+            clock: None,
+            archive: None,
+            history_cap: None,
+            journal: false,
+            fraud_rules: None,
+            cheque_policy: None,
+            dormancy_policy: None,
+            chart: None,
+            notifiers: Vec::new(),
+            observers: Vec::new(),
+        }
+    }
+
+    /// Loads the bank saved in `storage` instead of starting an empty one,
+    /// and attaches the storage's history archive
+    ///
+    /// The saved bank keeps its own name; the builder's settings are
+    /// applied on top of the saved ones.
+    pub fn storage(mut self, storage: &'a dyn Storage) -> Self {
+        self.storage = Some(storage);
+        self
+    }
+
+    /// Where the current time comes from, e.g. a `clock::MockClock`
+    pub fn clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = Some(clock);
+        self
+    }
+
+    /// Where archived transactions go (see `Bank::set_archive`); replaces
+    /// the storage's archive
+    pub fn archive(mut self, archive: Arc<dyn HistoryArchive>) -> Self {
+        self.archive = Some(archive);
+        self
+    }
+
+    /// The most transactions kept in memory per account (see
+    /// `Bank::set_history_cap`); needs an archive
+    pub fn history_cap(mut self, cap: usize) -> Self {
+        self.history_cap = Some(cap);
+        self
+    }
+
+    /// Records changes for `Storage::append_journal`, including the
+    /// settings below when they change a loaded bank
+    pub fn journal(mut self, enabled: bool) -> Self {
+        self.journal = enabled;
+        self
+    }
+
+    pub fn fraud_rules(mut self, rules: FraudRules) -> Self {
+        self.fraud_rules = Some(rules);
+        self
+    }
+
+    pub fn cheque_policy(mut self, policy: ChequePolicy) -> Self {
+        self.cheque_policy = Some(policy);
+        self
+    }
+
+    pub fn dormancy_policy(mut self, policy: DormancyPolicy) -> Self {
+        self.dormancy_policy = Some(policy);
+        self
+    }
+
+    pub fn chart_of_accounts(mut self, chart: ChartOfAccounts) -> Self {
+        self.chart = Some(chart);
+        self
+    }
+
+    /// Somewhere to send triggered alerts; call again to add several
+    pub fn notifier(mut self, notifier: Arc<dyn Notifier>) -> Self {
+        self.notifiers.push(notifier);
+        self
+    }
+
+    /// Somewhere to report every change; call again to add several
+    pub fn observer(mut self, observer: Arc<dyn BankObserver>) -> Self {
+        self.observers.push(observer);
+        self
+    }
+
+    /// Creates (or loads) the bank and applies the settings
+    ///
+    /// # Returns
+    /// * `Err(BankError)` - If the storage cannot be loaded, a setting is
+    ///   invalid, or a history cap is set without an archive
+    pub fn build(self) -> BankResult<Bank> {
+        let mut bank = match self.storage {
+            Some(storage) => {
+                let mut bank = storage.load_bank()?;
+                if let Some(archive) = storage.history_archive() {
+                    bank.set_archive(archive);
+                }
+                bank
+            }
+            None => Bank::new(self.name),
+        };
+        if let Some(clock) = self.clock {
+            bank.set_clock(clock);
+        }
+        if let Some(archive) = self.archive {
+            bank.set_archive(archive);
+        }
+        if self.journal {
+            bank.enable_journal();
+        }
+        if let Some(rules) = self.fraud_rules {
+            bank.set_fraud_rules(rules)?;
+        }
+        if let Some(policy) = self.cheque_policy {
+            bank.set_cheque_policy(policy)?;
+        }
+        if let Some(policy) = self.dormancy_policy {
+            bank.set_dormancy_policy(policy)?;
+        }
+        if let Some(chart) = self.chart {
+            bank.set_chart_of_accounts(chart)?;
+        }
+        if self.history_cap.is_some() {
+            bank.set_history_cap(self.history_cap)?;
+        }
+        for notifier in self.notifiers {
+            bank.add_notifier(notifier);
+        }
+        for observer in self.observers {
+            bank.add_observer(observer);
+        }
+        Ok(bank)
+    }
+}
+
+impl Bank {
+    /// Starts building a bank with optional settings
+    pub fn builder<'a>(name: impl Into<String>) -> BankBuilder<'a> {
+        BankBuilder::new(name)
+    }
+
+    /// Registers a customer built with `Customer::builder`
+    ///
+    /// Unlike `register_customer` this also validates the name, and sets
+    /// the contact details in the same event.
+    ///
+    /// # Returns
+    /// * `Ok(CustomerId)` - The customer ID
+    /// * `Err(BankError::ValidationError)` - Naming the first invalid field
+    /// * `Err(BankError::CustomerAlreadyExists)` - If the email is in use
+    pub fn register(&mut self, customer: CustomerBuilder) -> BankResult<CustomerId> {
+        let customer = customer.build_at(self.now())?;
+        if self.email_in_use(&customer.email, None) {
+            return Err(BankError::CustomerAlreadyExists(customer.email));
+        }
+        let customer_id = customer.id.clone();
+        self.emit(BankEvent::CustomerRegistered { customer })?;
+        Ok(customer_id)
+    }
+
+    /// Opens an account built with `Account::builder` for a customer
+    ///
+    /// # Returns
+    /// * `Ok(AccountId)` - The account ID
+    /// * `Err(BankError::CustomerAlreadyExists)` - If the customer already
+    ///   has an account
+    pub fn open_account(&mut self, customer_id: &CustomerId, account: AccountBuilder) -> BankResult<AccountId> {
+        if self.active_customer(customer_id)?.has_account() {
+            return Err(BankError::CustomerAlreadyExists("Customer already has an account".to_string()));
+        }
+
+        let mut account = account.build_at(self.now())?;
+        self.ensure_vault_takes(account.balance)?;
+        account.number = Some(self.next_account_number());
+        let account_id = account.id.clone();
+        self.emit(BankEvent::AccountOpened { customer_id: customer_id.clone(), account })?;
+        Ok(account_id)
+    }
+}
@@ -0,0 +1,140 @@
+//! Cards - debit cards and the purchases made with them
+//!
+//! Demonstrates: Layering a second set of checks (the card's) in front of
+//! an existing operation (a withdrawal) without changing it
+//!
+//! A card purchase is an ordinary withdrawal from the linked account, so
+//! the account's status, balance and limits all still apply; the card adds
+//! its own status, expiry and spending limits first, and its ID is kept on
+//! the transaction. Cards are stored with their customer.
+
+use crate::errors::{BankError, BankResult};
+use crate::models::{Card, CardLimits, CardStatus, Customer, CustomerId, Money, Transaction, TransactionDetails};
+use super::core::Bank;
+use super::events::BankEvent;
+
+impl Bank {
+    /// Issues a debit card for a customer's account
+    ///
+    /// # Arguments
+    /// * `limits` - Spending caps for the card; the default has none
+    ///
+    /// # Returns
+    /// * `Ok(Card)` - The new card, active until the end of its expiry month
+    /// * `Err(BankError::AccountClosed)` - If the account is closed
+    pub fn issue_card(&mut self, customer_id: &CustomerId, limits: CardLimits) -> BankResult<Card> {
+        limits.validate()?;
+        let account = self.active_customer(customer_id)?.get_account()?;
+        account.ensure_open()?;
+
+        let card = Card::new_at(&account.id, limits, self.now());
+        self.emit(BankEvent::CardIssued { customer_id: customer_id.clone(), card: card.clone() })?;
+        Ok(card)
+    }
+
+    /// Stops a card, e.g. because it was lost; replace it to get a new one
+    ///
+    /// # Returns
+    /// * `Ok(Card)` - The card, now blocked
+    /// * `Err(BankError::CardNotFound)` - If no customer holds the card
+    /// * `Err(BankError::CardDeclined)` - If the card is not active
+    pub fn block_card(&mut self, card_id: &str) -> BankResult<Card> {
+        let (customer_id, mut card) = self.owned_card(card_id)?;
+        if card.status != CardStatus::Active {
+            return Err(BankError::CardDeclined(format!("card {} is already {}", card.last_four(), card.status)));
+        }
+
+        self.emit(BankEvent::CardBlocked { customer_id, card_id: card.id.clone() })?;
+        card.status = CardStatus::Blocked;
+        Ok(card)
+    }
+
+    /// Retires a card and issues a new one with a new number and expiry
+    /// date and the same limits
+    ///
+    /// Both active and blocked cards can be replaced.
+    ///
+    /// # Returns
+    /// * `Ok(Card)` - The replacement card
+    /// * `Err(BankError::CardNotFound)` - If no customer holds the card
+    /// * `Err(BankError::CardDeclined)` - If it was replaced already
+    pub fn replace_card(&mut self, card_id: &str) -> BankResult<Card> {
+        let (customer_id, card) = self.owned_card(card_id)?;
+        if card.status == CardStatus::Replaced {
+            return Err(BankError::CardDeclined(format!("card {} was replaced already", card.last_four())));
+        }
+        let account = self.active_customer(&customer_id)?.get_account()?;
+        account.ensure_open()?;
+
+        let replacement = Card::new_at(&account.id, card.limits, self.now());
+        self.emit(BankEvent::CardReplaced {
+            customer_id,
+            card_id: card.id,
+            replacement: replacement.clone(),
+        })?;
+        Ok(replacement)
+    }
+
+    /// Changes a card's spending limits
+    ///
+    /// # Returns
+    /// * `Err(BankError::InvalidAmount)` - If a limit is not positive
+    /// * `Err(BankError::CardNotFound)` - If no customer holds the card
+    pub fn set_card_limits(&mut self, card_id: &str, limits: CardLimits) -> BankResult<()> {
+        limits.validate()?;
+        let (customer_id, card) = self.owned_card(card_id)?;
+        self.emit(BankEvent::CardLimitsChanged { customer_id, card_id: card.id, limits })
+    }
+
+    /// Pays for something with a card
+    ///
+    /// # Arguments
+    /// * `details` - Usually the merchant as the description, and a category
+    ///
+    /// # Returns
+    /// * `Ok(Transaction)` - The withdrawal, carrying the card's ID
+    /// * `Err(BankError::CardDeclined)` - If the card is blocked, replaced,
+    ///   expired or linked to an account the customer no longer has
+    /// * `Err(BankError::LimitExceeded)` - If a card or account limit would
+    ///   be exceeded
+    pub fn card_purchase(
+        &mut self,
+        card_id: &str,
+        amount: Money,
+        details: TransactionDetails,
+    ) -> BankResult<Transaction> {
+        let (customer_id, card) = self.owned_card(card_id)?;
+        let account = self.active_customer(&customer_id)?.get_account()?;
+        if card.account_id != account.id {
+            return Err(BankError::CardDeclined(format!(
+                "card {} is not linked to the current account",
+                card.last_four()
+            )));
+        }
+
+        let now = self.now();
+        card.check_purchase(amount, &account.transactions, now)?;
+        let mut transaction = account.prepare_withdrawal(amount, now)?;
+        transaction.annotate(details);
+        transaction.card_id = Some(card.id);
+
+        self.emit(BankEvent::Withdrawn { customer_id, transaction: transaction.clone() })?;
+        Ok(transaction)
+    }
+
+    /// Finds a card by ID, with the customer who holds it
+    pub fn find_card(&self, card_id: &str) -> BankResult<(&Customer, &Card)> {
+        let card_id = card_id.trim();
+        self.customers
+            .values()
+            .find_map(|customer| Some((customer, customer.card(card_id)?)))
+            .ok_or_else(|| BankError::CardNotFound(card_id.to_string()))
+    }
+
+    /// Like `find_card`, but returns the customer's ID and a copy of the
+    /// card, so the bank can be changed afterwards
+    fn owned_card(&self, card_id: &str) -> BankResult<(CustomerId, Card)> {
+        let (customer, card) = self.find_card(card_id)?;
+        Ok((customer.id.clone(), card.clone()))
+    }
+}
@@ -0,0 +1,169 @@
+//! Cash-flow report - money into and out of the bank per day or week
+//!
+//! Demonstrates: Grouping an iterator into contiguous periods with
+//! BTreeMap, scaling values for a text bar chart
+//!
+//! Inflow and outflow follow the bank statistics report: deposits bring
+//! money in; withdrawals, transfers to other banks, fees and bill payments
+//! take it out; transfers between customers stay inside the bank. Periods
+//! without activity between the first and last active one are listed with
+//! zeros, so a chart of the report shows gaps as gaps.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Utc};
+use serde::Serialize;
+
+use crate::models::Money;
+use super::core::Bank;
+use super::report::external_flow;
+
+/// Width in characters of the longest bar in the text chart
+pub const CHART_WIDTH: usize = 30;
+
+/// Length of the periods a cash-flow report is split into
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CashFlowPeriod {
+    Daily,
+    /// Weeks start on Monday
+    Weekly,
+}
+
+impl CashFlowPeriod {
+    /// First day of the period `day` falls in
+    fn start_of(self, day: NaiveDate) -> NaiveDate {
+        match self {
+            CashFlowPeriod::Daily => day,
+            CashFlowPeriod::Weekly => day - Duration::days(day.weekday().num_days_from_monday() as i64),
+        }
+    }
+
+    fn length(self) -> Duration {
+        match self {
+            CashFlowPeriod::Daily => Duration::days(1),
+            CashFlowPeriod::Weekly => Duration::weeks(1),
+        }
+    }
+}
+
+/// Money in and out of the bank during one period
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CashFlowBucket {
+    /// First day of the period
+    pub start: NaiveDate,
+    pub transactions: usize,
+    pub inflow: Money,
+    pub outflow: Money,
+    pub net: Money,
+}
+
+/// Inflow, outflow and net per period, oldest first
+#[derive(Debug, Clone, Serialize)]
+pub struct CashFlowReport {
+    pub period: CashFlowPeriod,
+    pub buckets: Vec<CashFlowBucket>,
+    pub inflow: Money,
+    pub outflow: Money,
+    pub net: Money,
+}
+
+impl Bank {
+    /// Splits the money flowing into and out of the bank into days or weeks
+    ///
+    /// # Arguments
+    /// * `from` - First moment to include; `None` for the first transaction
+    /// * `to` - Moment to stop at (exclusive); `None` for the last transaction
+    pub fn cash_flow(
+        &self,
+        period: CashFlowPeriod,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+    ) -> CashFlowReport {
+        let mut buckets: BTreeMap<NaiveDate, CashFlowBucket> = BTreeMap::new();
+        for entry in self.all_transactions().between(from, to) {
+            let Some(account) = self.customers.get(entry.customer_id).and_then(|c| c.account.as_ref()) else {
+                continue;
+            };
+            let start = period.start_of(entry.transaction.timestamp.date_naive());
+            let bucket = buckets.entry(start).or_insert_with(|| empty_bucket(start));
+            let (inflow, outflow) = external_flow(account, entry.transaction);
+            bucket.transactions += 1;
+            bucket.inflow += inflow;
+            bucket.outflow += outflow;
+            bucket.net = bucket.inflow - bucket.outflow;
+        }
+
+        // Fill the quiet periods between the first and last active one
+        if let (Some(&first), Some(&last)) = (buckets.keys().next(), buckets.keys().next_back()) {
+            let mut start = first;
+            while start < last {
+                buckets.entry(start).or_insert_with(|| empty_bucket(start));
+                start += period.length();
+            }
+        }
+
+        let buckets: Vec<CashFlowBucket> = buckets.into_values().collect();
+        let inflow: Money = buckets.iter().map(|b| b.inflow).sum();
+        let outflow: Money = buckets.iter().map(|b| b.outflow).sum();
+        CashFlowReport { period, buckets, inflow, outflow, net: inflow - outflow }
+    }
+}
+
+fn empty_bucket(start: NaiveDate) -> CashFlowBucket {
+    CashFlowBucket { start, transactions: 0, inflow: Money::ZERO, outflow: Money::ZERO, net: Money::ZERO }
+}
+
+/// A bar of `#` for `amount`, `largest` filling `CHART_WIDTH`; any amount
+/// above zero gets at least one `#`
+fn bar(amount: Money, largest: Money) -> String {
+    if !amount.is_positive() || !largest.is_positive() {
+        return String::new();
+    }
+    let width = (amount.cents() as i128 * CHART_WIDTH as i128 / largest.cents() as i128) as usize;
+    "#".repeat(width.max(1))
+}
+
+// Renders the report as a bar chart for the CLI: two bars per period
+impl fmt::Display for CashFlowReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let title = match self.period {
+            CashFlowPeriod::Daily => "DAILY CASH FLOW",
+            CashFlowPeriod::Weekly => "WEEKLY CASH FLOW",
+        };
+        writeln!(f, "═══════════════════════════════════════════")?;
+        writeln!(f, "{:^43}", title)?;
+        writeln!(f, "═══════════════════════════════════════════")?;
+        if self.buckets.is_empty() {
+            writeln!(f, "No transactions in this period.")?;
+        }
+
+        let largest = self.buckets.iter().map(|b| b.inflow.max(b.outflow)).max().unwrap_or(Money::ZERO);
+        for bucket in &self.buckets {
+            writeln!(
+                f,
+                "{}  in  {:<width$} {}",
+                bucket.start,
+                bar(bucket.inflow, largest),
+                bucket.inflow.display(),
+                width = CHART_WIDTH
+            )?;
+            writeln!(
+                f,
+                "{:10}  out {:<width$} {}  (net {})",
+                "",
+                bar(bucket.outflow, largest),
+                bucket.outflow.display(),
+                bucket.net.display(),
+                width = CHART_WIDTH
+            )?;
+        }
+
+        writeln!(f, "───────────────────────────────────────────")?;
+        writeln!(f, "Money In:  {}", self.inflow.display())?;
+        writeln!(f, "Money Out: {}", self.outflow.display())?;
+        writeln!(f, "Net Flow:  {}", self.net.display())?;
+        write!(f, "═══════════════════════════════════════════")
+    }
+}
@@ -0,0 +1,227 @@
+//! Cheques - deposits that wait to clear, then settle or bounce
+//!
+//! Demonstrates: Deciding each outcome before emitting it, so the event
+//! carries every transaction it posts and replay needs no decisions
+//!
+//! A deposited cheque credits the account straight away with a pending
+//! deposit, which does not count towards the available balance until the
+//! cheque clears. `clear_due_cheques` processes the cheques whose clearing
+//! period is over:
+//!
+//! - a cheque written by a customer of this bank is paid from the
+//!   writer's account, and bounces if they cannot pay
+//! - a cheque drawn on another bank clears, unless staff returned it
+//!   earlier with `return_cheque`
+//!
+//! A bounced cheque's deposit is marked failed and reversed, and the bank
+//! charges its bounce fee (capped at what the depositor has available).
+
+use chrono::{DateTime, Duration, Utc};
+
+use crate::errors::{BankError, BankResult};
+use crate::models::{
+    Cheque, ChequePolicy, ChequeStatus, Customer, CustomerId, Money, Transaction, TransactionStatus, TransactionType,
+};
+use crate::validation;
+use super::core::Bank;
+use super::events::BankEvent;
+
+impl Bank {
+    /// The clearing period and bounce fee for deposited cheques
+    pub fn cheque_policy(&self) -> ChequePolicy {
+        self.cheque_policy
+    }
+
+    /// Changes the clearing period and bounce fee for cheques deposited
+    /// from now on
+    ///
+    /// # Returns
+    /// * `Err(BankError)` - If the policy fails `ChequePolicy::validate`
+    pub fn set_cheque_policy(&mut self, policy: ChequePolicy) -> BankResult<()> {
+        policy.validate()?;
+        self.emit(BankEvent::ChequePolicyChanged { policy })
+    }
+
+    /// Pays a cheque into a customer's account
+    ///
+    /// # Arguments
+    /// * `customer_id` - The customer paying the cheque in
+    /// * `amount` - The cheque's amount
+    /// * `number` - Serial number printed on the cheque
+    /// * `drawn_on` - The customer who wrote it, if they bank here
+    ///
+    /// # Returns
+    /// * `Ok(Cheque)` - The cheque, pending until its clearing date
+    /// * `Err(BankError::InvalidTransfer)` - If the customer wrote it
+    /// * `Err(BankError::InvalidInput)` - If the same cheque was already
+    ///   paid in and has not bounced
+    pub fn deposit_cheque(
+        &mut self,
+        customer_id: &CustomerId,
+        amount: Money,
+        number: &str,
+        drawn_on: Option<&CustomerId>,
+    ) -> BankResult<Cheque> {
+        let number = validation::validate_cheque_number(number)?;
+        if let Some(drawer_id) = drawn_on {
+            if drawer_id == customer_id {
+                return Err(BankError::InvalidTransfer("Cannot deposit your own cheque".to_string()));
+            }
+            self.active_customer(drawer_id)?.get_account()?;
+        }
+
+        let account = self.active_customer(customer_id)?.get_account()?;
+        let paid_in = account.cheques.iter().any(|cheque| {
+            cheque.number == number && cheque.drawn_on.as_ref() == drawn_on && cheque.status != ChequeStatus::Bounced
+        });
+        if paid_in {
+            return Err(BankError::InvalidInput(format!("cheque #{} has already been deposited", number)));
+        }
+
+        let now = self.now();
+        let mut transaction = account.prepare_deposit(amount, now)?;
+        transaction.status = TransactionStatus::Pending;
+        transaction.description = Some(format!("Cheque #{}", number));
+
+        let cheque = Cheque {
+            id: uuid::Uuid::new_v4().to_string(),
+            number,
+            amount,
+            drawn_on: drawn_on.cloned(),
+            deposited_at: now,
+            clears_at: now + Duration::days(self.cheque_policy.clearing_days.into()),
+            status: ChequeStatus::Pending,
+            transaction_id: transaction.id.clone(),
+            bounce_reason: None,
+        };
+        self.emit(BankEvent::ChequeDeposited {
+            customer_id: customer_id.clone(),
+            cheque: cheque.clone(),
+            transaction,
+        })?;
+        Ok(cheque)
+    }
+
+    /// Every cheque still waiting to clear, soonest first
+    pub fn outstanding_cheques(&self) -> Vec<(&Customer, &Cheque)> {
+        let mut outstanding: Vec<_> = self
+            .customers
+            .values()
+            .filter_map(|customer| Some((customer, customer.account.as_ref()?)))
+            .flat_map(|(customer, account)| {
+                account
+                    .cheques
+                    .iter()
+                    .filter(|cheque| cheque.status == ChequeStatus::Pending)
+                    .map(move |cheque| (customer, cheque))
+            })
+            .collect();
+        outstanding.sort_by(|(_, a), (_, b)| a.clears_at.cmp(&b.clears_at).then_with(|| a.id.cmp(&b.id)));
+        outstanding
+    }
+
+    /// Clears or bounces every cheque due by `now`, soonest first
+    ///
+    /// # Returns
+    /// The processed cheques, each now cleared or bounced
+    pub fn clear_due_cheques(&mut self, now: DateTime<Utc>) -> BankResult<Vec<Cheque>> {
+        let due: Vec<(CustomerId, Cheque)> = self
+            .outstanding_cheques()
+            .into_iter()
+            .filter(|(_, cheque)| cheque.is_due(now))
+            .map(|(customer, cheque)| (customer.id.clone(), cheque.clone()))
+            .collect();
+
+        let mut processed = Vec::new();
+        for (customer_id, mut cheque) in due {
+            let debit = match &cheque.drawn_on {
+                Some(drawer_id) => match self.prepare_cheque_payment(drawer_id, &cheque) {
+                    Ok(debit) => Some(debit),
+                    Err(e) => {
+                        let reason = format!("Refer to drawer: {}", e);
+                        processed.push(self.bounce(&customer_id, cheque, reason)?);
+                        continue;
+                    }
+                },
+                None => None,
+            };
+
+            self.emit(BankEvent::ChequeCleared {
+                customer_id,
+                cheque_id: cheque.id.clone(),
+                drawer_id: cheque.drawn_on.clone(),
+                debit,
+            })?;
+            cheque.status = ChequeStatus::Cleared;
+            processed.push(cheque);
+        }
+        Ok(processed)
+    }
+
+    /// Bounces a pending cheque straight away, e.g. because the bank it is
+    /// drawn on returned it
+    ///
+    /// # Arguments
+    /// * `reason` - Why it bounced; "Returned unpaid" if not given
+    ///
+    /// # Returns
+    /// * `Ok(Cheque)` - The cheque, now bounced
+    /// * `Err(BankError::InvalidInput)` - If there is no such pending cheque
+    pub fn return_cheque(&mut self, cheque_id: &str, reason: Option<&str>) -> BankResult<Cheque> {
+        let reason = match reason.map(str::trim).filter(|r| !r.is_empty()) {
+            Some(reason) => validation::validate_description(reason)?,
+            None => "Returned unpaid".to_string(),
+        };
+        let cheque_id = cheque_id.trim();
+        let (customer_id, cheque) = self
+            .outstanding_cheques()
+            .into_iter()
+            .find(|(_, cheque)| cheque.id == cheque_id)
+            .map(|(customer, cheque)| (customer.id.clone(), cheque.clone()))
+            .ok_or_else(|| BankError::InvalidInput(format!("no pending cheque '{}'", cheque_id)))?;
+        self.bounce(&customer_id, cheque, reason)
+    }
+
+    /// Builds the withdrawal that pays `cheque` from its writer's account
+    fn prepare_cheque_payment(&self, drawer_id: &CustomerId, cheque: &Cheque) -> BankResult<Transaction> {
+        let mut debit = self
+            .active_customer(drawer_id)?
+            .get_account()?
+            .prepare_withdrawal(cheque.amount, self.now())?;
+        debit.description = Some(format!("Cheque #{}", cheque.number));
+        Ok(debit)
+    }
+
+    /// Reverses a cheque's deposit, charges the bounce fee and records why
+    fn bounce(&mut self, customer_id: &CustomerId, mut cheque: Cheque, reason: String) -> BankResult<Cheque> {
+        let now = self.now();
+        let account = self.get_customer(customer_id)?.get_account()?;
+
+        let mut reversal = Transaction::new_at(
+            TransactionType::Reversal { original_id: cheque.transaction_id.clone(), credit: false },
+            cheque.amount,
+            account.balance_after(cheque.amount, false)?,
+            now,
+        );
+        reversal.description = Some(reason.clone());
+
+        // Reversing an uncleared deposit leaves the available balance as it was
+        let fee_amount = self.cheque_policy.bounce_fee.min(account.available_balance_at(now).max(Money::ZERO));
+        let fee = fee_amount.is_positive().then(|| {
+            let mut fee = Transaction::new_at(TransactionType::Fee, fee_amount, reversal.balance_after - fee_amount, now);
+            fee.description = Some(format!("Returned cheque #{}", cheque.number));
+            fee
+        });
+
+        self.emit(BankEvent::ChequeBounced {
+            customer_id: customer_id.clone(),
+            cheque_id: cheque.id.clone(),
+            reason: reason.clone(),
+            reversal,
+            fee,
+        })?;
+        cheque.status = ChequeStatus::Bounced;
+        cheque.bounce_reason = Some(reason);
+        Ok(cheque)
+    }
+}
@@ -0,0 +1,165 @@
+//! Clearing - transfers to other banks, settled later
+//!
+//! Demonstrates: A two-step operation modelled as a status on the record
+//! it creates, with both outcomes recorded as events
+//!
+//! Money sent to another bank leaves the account at once, so it cannot be
+//! spent twice, but the debit stays `Pending` until the clearing house
+//! answers. Settling marks it `Completed`. Rejecting marks it `Failed` and
+//! posts a reversal that puts the money back; as with undo, nothing is
+//! removed from the history.
+
+use crate::errors::{BankError, BankResult};
+use crate::models::{
+    Customer, CustomerId, Money, Transaction, TransactionDetails, TransactionId, TransactionStatus, TransactionType,
+};
+use crate::validation;
+use super::core::Bank;
+use super::events::BankEvent;
+
+/// Description given to the refund of a rejected transfer when no reason
+/// is known
+const DEFAULT_REJECTION: &str = "Returned by the receiving bank";
+
+impl Bank {
+    /// Sends money to an account at another bank
+    ///
+    /// # Arguments
+    /// * `from_customer_id` - The sending customer
+    /// * `external_ref` - The receiving account, e.g. an IBAN (see
+    ///   `validation::validate_external_ref`)
+    /// * `amount` - Amount to send
+    ///
+    /// # Returns
+    /// * `Ok(Transaction)` - The pending debit; settle or reject it by its ID
+    pub fn external_transfer(
+        &mut self,
+        from_customer_id: &CustomerId,
+        external_ref: &str,
+        amount: Money,
+    ) -> BankResult<Transaction> {
+        self.external_transfer_with(from_customer_id, external_ref, amount, TransactionDetails::new())
+    }
+
+    /// Sends money to another bank with a description and tags
+    ///
+    /// The same rules as for a transfer apply: account type, freezes,
+    /// withdrawal limits and the daily transfer count.
+    pub fn external_transfer_with(
+        &mut self,
+        from_customer_id: &CustomerId,
+        external_ref: &str,
+        amount: Money,
+        details: TransactionDetails,
+    ) -> BankResult<Transaction> {
+        let external_ref = validation::validate_external_ref(external_ref)?;
+        let account = self.active_customer(from_customer_id)?.get_account()?;
+
+        let now = self.now();
+        account.limits.check_transfer(&account.transactions, now)?;
+        let mut transaction = account.prepare_withdrawal(amount, now)?;
+        transaction.transaction_type = TransactionType::ExternalTransfer { external_ref };
+        transaction.status = TransactionStatus::Pending;
+        transaction.annotate(details);
+
+        self.emit(BankEvent::ExternalTransferSent {
+            customer_id: from_customer_id.clone(),
+            transaction: transaction.clone(),
+        })?;
+        Ok(transaction)
+    }
+
+    /// Every external transfer waiting for the clearing house, oldest first
+    pub fn pending_external_transfers(&self) -> Vec<(&Customer, &Transaction)> {
+        let mut pending: Vec<_> = self
+            .customers
+            .values()
+            .filter_map(|customer| Some((customer, customer.account.as_ref()?)))
+            .flat_map(|(customer, account)| account.pending_transactions().map(move |tx| (customer, tx)))
+            .filter(|(_, tx)| matches!(tx.transaction_type, TransactionType::ExternalTransfer { .. }))
+            .collect();
+        pending.sort_by(|(_, a), (_, b)| a.timestamp.cmp(&b.timestamp).then_with(|| a.id.cmp(&b.id)));
+        pending
+    }
+
+    /// Records that the other bank accepted a pending external transfer
+    ///
+    /// # Returns
+    /// * `Ok(Transaction)` - The transfer, now completed
+    /// * `Err(BankError::InvalidInput)` - If there is no such transaction
+    /// * `Err(BankError::InvalidTransfer)` - If it is not a pending external
+    ///   transfer
+    pub fn settle_external_transfer(&mut self, transaction_id: &TransactionId) -> BankResult<Transaction> {
+        let (customer_id, mut transaction) = self.pending_external(transaction_id)?;
+
+        self.emit(BankEvent::ExternalTransferSettled {
+            customer_id,
+            transaction_id: transaction.id.clone(),
+        })?;
+        transaction.status = TransactionStatus::Completed;
+        Ok(transaction)
+    }
+
+    /// Records that the other bank refused a pending external transfer and
+    /// returns the money to the sender
+    ///
+    /// The refund is posted even if the account has been frozen since.
+    ///
+    /// # Arguments
+    /// * `reason` - Why it was refused, kept as the refund's description
+    ///
+    /// # Returns
+    /// * `Ok(Transaction)` - The refund
+    /// * `Err(BankError::InvalidInput)` - If there is no such transaction
+    /// * `Err(BankError::InvalidTransfer)` - If it is not a pending external
+    ///   transfer
+    pub fn reject_external_transfer(
+        &mut self,
+        transaction_id: &TransactionId,
+        reason: Option<&str>,
+    ) -> BankResult<Transaction> {
+        let description = match reason.map(str::trim).filter(|r| !r.is_empty()) {
+            Some(reason) => validation::validate_description(reason)?,
+            None => DEFAULT_REJECTION.to_string(),
+        };
+        let (customer_id, transaction) = self.pending_external(transaction_id)?;
+        let balance_after = self.get_customer(&customer_id)?.get_account()?.balance_after(transaction.amount, true)?;
+
+        let mut refund = Transaction::new_at(
+            TransactionType::Reversal { original_id: transaction.id.clone(), credit: true },
+            transaction.amount,
+            balance_after,
+            self.now(),
+        );
+        refund.description = Some(description);
+
+        self.emit(BankEvent::ExternalTransferRejected {
+            customer_id,
+            transaction_id: transaction.id,
+            refund: refund.clone(),
+        })?;
+        Ok(refund)
+    }
+
+    /// Finds a pending external transfer by ID; returns its customer's ID
+    /// and a copy of it
+    fn pending_external(&self, transaction_id: &TransactionId) -> BankResult<(CustomerId, Transaction)> {
+        let (customer, transaction) = self
+            .customers
+            .values()
+            .filter_map(|customer| Some((customer, customer.account.as_ref()?)))
+            .find_map(|(customer, account)| {
+                account.transactions.iter().find(|tx| tx.id == *transaction_id).map(|tx| (customer, tx))
+            })
+            .ok_or_else(|| BankError::InvalidInput(format!("transaction '{}' not found", transaction_id)))?;
+
+        let external = matches!(transaction.transaction_type, TransactionType::ExternalTransfer { .. });
+        if !external || transaction.status != TransactionStatus::Pending {
+            return Err(BankError::InvalidTransfer(format!(
+                "transaction '{}' is not a pending external transfer",
+                transaction_id
+            )));
+        }
+        Ok((customer.id.clone(), transaction.clone()))
+    }
+}
@@ -2,11 +2,32 @@
 //!
 //! Demonstrates: Business logic organization, HashMap operations
 
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+use crate::clock::{Clock, SystemClock};
 use crate::errors::{BankError, BankResult};
-use crate::models::Customer;
+use crate::events::{DeliveryQueue, Subscribers, Webhook};
+use crate::middleware::Pipeline;
+use crate::notifications::Notifier;
+use crate::observer::BankObserver;
+use crate::validation;
+use crate::models::{
+    Account, AccountId, AccountLimits, AccountNumber, AccountStatus, AccountType, Autopay, BookEntry, ChartOfAccounts,
+    ChequePolicy, Customer, CustomerId, CustomerStatus, DormancyPolicy, FlaggedTransaction, FraudRules, Merchant, Money,
+    Page, ScheduledTransaction, StaffMember,
+};
+use super::archive::HistoryArchive;
+use super::events::BankEvent;
+use super::sorting::{SortKey, SortOrder};
+use super::journal::JournalEntry;
+use super::outbox::Outbox;
+use super::transaction_index::TransactionIndex;
+use super::email_index::EmailIndex;
+use super::undo::Posting;
 
 /// The main bank system that manages all customers
 ///
@@ -14,7 +35,7 @@ use crate::models::Customer;
 /// - HashMap for O(1) customer lookups
 /// - Business logic encapsulation
 /// - Separation of concerns
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Bank {
     /// Bank name
     pub name: String,
@@ -22,10 +43,155 @@ pub struct Bank {
     /// All registered customers (customer_id -> Customer)
     /// Demonstrates: HashMap<K, V> for efficient key-value storage
     /// https://doc.rust-lang.org/std/collections/struct.HashMap.html
-    pub(crate) customers: HashMap<String, Customer>,
+    pub(crate) customers: HashMap<CustomerId, Customer>,
 
     /// Total number of transactions processed
     pub total_transactions: u64,
+
+    /// Standing orders (recurring transfers)
+    /// Older data files have no schedules, so default to empty
+    #[serde(default)]
+    pub(crate) schedules: Vec<ScheduledTransaction>,
+
+    /// Admin and teller logins (lowercased username -> StaffMember)
+    /// Older data files have no staff, so default to empty
+    #[serde(default)]
+    pub(crate) staff: HashMap<String, StaffMember>,
+
+    /// Account numbers handed out so far (number -> customer_id)
+    /// Kept alongside the accounts for O(1) lookups; older data files have
+    /// none until `assign_account_numbers` runs
+    #[serde(default)]
+    pub(crate) account_numbers: HashMap<AccountNumber, CustomerId>,
+
+    /// URLs sent domain events (see the `events` module)
+    /// Older data files have no webhooks, so default to empty
+    #[serde(default)]
+    pub(crate) webhooks: Vec<Webhook>,
+
+    /// Clearing period and bounce fee for deposited cheques
+    /// Older data files have none, so the defaults apply
+    #[serde(default)]
+    pub(crate) cheque_policy: ChequePolicy,
+
+    /// When accounts without activity are flagged dormant
+    /// Older data files have none, so the defaults apply
+    #[serde(default)]
+    pub(crate) dormancy_policy: DormancyPolicy,
+
+    /// Cash the bank holds; `None` in data files from before the vault was
+    /// tracked, until an admin first tops it up
+    #[serde(default)]
+    pub(crate) vault: Option<Money>,
+
+    /// Every entry posted to the books: one per customer transaction, the
+    /// bank's own operations, and what archived history nets to
+    /// Older data files have none until the next change posts to them
+    /// (see `books_mut`)
+    #[serde(default)]
+    pub(crate) books: Option<Vec<BookEntry>>,
+
+    /// The bank's own entries in data files from before every entry was
+    /// posted; `books_mut` moves them into `books`
+    #[serde(default, skip_serializing)]
+    pub(crate) bank_entries: Vec<BookEntry>,
+
+    /// Codes, names and classes of the accounts in the books, and which
+    /// account each kind of transaction posts against
+    #[serde(default)]
+    pub(crate) chart: ChartOfAccounts,
+
+    /// Rules that flag suspicious transactions, and every transaction
+    /// they flagged, reviewed or not, oldest first
+    #[serde(default)]
+    pub(crate) fraud_rules: FraudRules,
+    #[serde(default)]
+    pub(crate) review_queue: Vec<FlaggedTransaction>,
+
+    /// Companies customers can pay bills to
+    /// Older data files have none, so default to empty
+    #[serde(default)]
+    pub(crate) merchants: Vec<Merchant>,
+
+    /// Recurring bill payments, for all customers
+    #[serde(default)]
+    pub(crate) autopays: Vec<Autopay>,
+
+    /// Recent operations that `undo_last` can reverse, oldest first
+    /// Kept in memory only: undo is for the current session
+    #[serde(skip)]
+    pub(crate) undo_stack: VecDeque<Vec<Posting>>,
+
+    /// Buffered journal entries, `None` while journaling is disabled
+    /// Never serialized: the journal lives next to the snapshot, not in it
+    #[serde(skip)]
+    pub(crate) journal: Option<Vec<JournalEntry>>,
+
+    /// Changes made since the bank was last saved in full; a freshly
+    /// loaded bank has none
+    #[serde(skip)]
+    pub(crate) unsaved: usize,
+
+    /// Transaction ID -> customer ID, for `find_transaction`
+    /// Rebuilt from the accounts when first needed, so never serialized
+    #[serde(skip)]
+    pub(crate) transaction_index: TransactionIndex,
+
+    /// Normalized email -> customer ID, for registration and
+    /// `find_customer_by_email`; rebuilt like `transaction_index`
+    #[serde(skip)]
+    pub(crate) email_index: EmailIndex,
+
+    /// Where the current time comes from; the system clock unless a test
+    /// swaps it with `set_clock`
+    #[serde(skip, default = "system_clock")]
+    pub(crate) clock: Arc<dyn Clock>,
+
+    /// Where triggered balance alerts are sent
+    /// Not bank data: whoever loads the bank registers its own notifiers
+    #[serde(skip)]
+    pub(crate) notifiers: Vec<Arc<dyn Notifier>>,
+
+
+    /// Callbacks sent domain events; like notifiers, registered by
+    /// whoever loads the bank
+    #[serde(skip)]
+    pub(crate) subscribers: Subscribers,
+
+    /// Embedders' hooks; like notifiers, registered by whoever loads the bank
+    #[serde(skip)]
+    pub(crate) observers: Vec<Arc<dyn BankObserver>>,
+
+    /// Webhook deliveries on their way, sent by a background thread
+    #[serde(skip)]
+    pub(crate) deliveries: DeliveryQueue,
+
+    /// The stages deposits, withdrawals and transfers go through; set up
+    /// by whoever loads the bank, the standard one until then
+    #[serde(skip)]
+    pub(crate) pipeline: Pipeline,
+
+    /// Where archived transactions are kept, attached by whoever loads the
+    /// bank; see `archive`
+    #[serde(skip)]
+    pub(crate) archive: Option<Arc<dyn HistoryArchive>>,
+
+    /// Most transactions kept in memory per account, set by whoever loads
+    /// the bank, and the accounts that went over it since `emit` last
+    /// trimmed them
+    #[serde(skip)]
+    pub(crate) history_cap: Option<usize>,
+    #[serde(skip)]
+    pub(crate) over_cap: Vec<CustomerId>,
+
+    /// Alerts, domain events and observer calls held back while a batch
+    /// is pending, `None` when sending straight away; see `outbox`
+    #[serde(skip)]
+    pub(crate) held: Option<Outbox>,
+}
+
+fn system_clock() -> Arc<dyn Clock> {
+    Arc::new(SystemClock)
 }
 
 impl Bank {
@@ -38,35 +204,72 @@ impl Bank {
             name,
             customers: HashMap::new(),
             total_transactions: 0,
+            schedules: Vec::new(),
+            staff: HashMap::new(),
+            account_numbers: HashMap::new(),
+            webhooks: Vec::new(),
+            cheque_policy: ChequePolicy::default(),
+            dormancy_policy: DormancyPolicy::default(),
+            vault: Some(Money::ZERO),
+            books: Some(Vec::new()),
+            bank_entries: Vec::new(),
+            chart: ChartOfAccounts::default(),
+            fraud_rules: FraudRules::default(),
+            review_queue: Vec::new(),
+            merchants: Vec::new(),
+            autopays: Vec::new(),
+            undo_stack: VecDeque::new(),
+            journal: None,
+            unsaved: 0,
+            transaction_index: TransactionIndex::default(),
+            email_index: EmailIndex::default(),
+            clock: system_clock(),
+            notifiers: Vec::new(),
+            subscribers: Subscribers::default(),
+            observers: Vec::new(),
+            deliveries: DeliveryQueue::default(),
+            pipeline: Pipeline::standard(),
+            archive: None,
+            history_cap: None,
+            over_cap: Vec::new(),
+            held: None,
         }
     }
 
+    /// Replaces the clock the bank reads the current time from
+    pub fn set_clock(&mut self, clock: Arc<dyn Clock>) {
+        self.clock = clock;
+    }
+
+    /// The current time according to the bank's clock
+    pub fn now(&self) -> DateTime<Utc> {
+        self.clock.now()
+    }
+
     /// Registers a new customer
     ///
     /// Demonstrates: HashMap insertion, ownership transfer, iterator usage
     ///
     /// # Arguments
     /// * `name` - Customer's full name
-    /// * `email` - Customer's email (must be unique)
+    /// * `email` - Customer's email (must be unique); stored trimmed and
+    ///   lowercased
     ///
     /// # Returns
     /// * `Ok(String)` - The customer ID
-    /// * `Err(BankError)` - If email already exists
-    pub fn register_customer(&mut self, name: String, email: String) -> BankResult<String> {
-        // Check if customer already exists by email
-        // Demonstrates: Iterator methods (any) and closures
-        if self
-            .customers
-            .values()
-            .any(|c| c.email.to_lowercase() == email.to_lowercase())
-        {
+    /// * `Err(BankError::ValidationError)` - If the email is malformed
+    /// * `Err(BankError::CustomerAlreadyExists)` - If email already exists
+    pub fn register_customer(&mut self, name: String, email: String) -> BankResult<CustomerId> {
+        // Normalize first so "Bob@Example.com " and "bob@example.com" clash
+        let email = validation::validate_email(&email)?;
+        if self.email_in_use(&email, None) {
             return Err(BankError::CustomerAlreadyExists(email));
         }
 
-        let customer = Customer::new(name, email);
+        let mut customer = Customer::new(name, email);
+        customer.registered_at = self.now();
         let customer_id = customer.id.clone();
-
-        self.customers.insert(customer_id.clone(), customer);
+        self.emit(BankEvent::CustomerRegistered { customer })?;
 
         Ok(customer_id)
     }
@@ -76,56 +279,156 @@ impl Bank {
     /// # Arguments
     /// * `customer_id` - The customer's unique ID
     /// * `initial_deposit` - Initial deposit amount
+    /// * `account_type` - Checking, Savings, or Fixed Deposit
+    ///
+    /// `open_account` takes an `AccountBuilder` for limits and alerts.
     pub fn create_account_for_customer(
         &mut self,
-        customer_id: &str,
-        initial_deposit: f64,
-    ) -> BankResult<String> {
-        let customer = self
+        customer_id: &CustomerId,
+        initial_deposit: Money,
+        account_type: AccountType,
+    ) -> BankResult<AccountId> {
+        let account = Account::builder().account_type(account_type).initial_deposit(initial_deposit);
+        self.open_account(customer_id, account)
+    }
+
+    /// Gives every account that predates account numbers its number
+    ///
+    /// Accounts are numbered in the order they were opened. Call this after
+    /// loading an older data file; it does nothing once all accounts have
+    /// a number.
+    ///
+    /// # Returns
+    /// The number of accounts that got a number
+    pub fn assign_account_numbers(&mut self) -> BankResult<usize> {
+        let mut unnumbered: Vec<(CustomerId, Account)> = self
             .customers
-            .get_mut(customer_id)
-            .ok_or_else(|| BankError::CustomerNotFound(customer_id.to_string()))?;
+            .values()
+            .filter_map(|c| c.account.as_ref().map(|a| (c.id.clone(), a.clone())))
+            .filter(|(_, a)| a.number.is_none())
+            .collect();
+        unnumbered.sort_by(|(_, a), (_, b)| a.created_at.cmp(&b.created_at).then_with(|| a.id.cmp(&b.id)));
+
+        for (customer_id, _) in &unnumbered {
+            let number = self.next_account_number();
+            self.emit(BankEvent::AccountNumberAssigned { customer_id: customer_id.clone(), number })?;
+        }
+        Ok(unnumbered.len())
+    }
+
+    /// The number the next opened account gets
+    pub(crate) fn next_account_number(&self) -> AccountNumber {
+        AccountNumber::new(self.account_numbers.len() as u64 + 1)
+    }
 
-        customer.create_account(initial_deposit)?;
-        self.total_transactions += 1;
+    /// Replaces the withdrawal and transfer limits on a customer's account
+    ///
+    /// # Returns
+    /// * `Err(BankError::InvalidAmount)` - If a configured amount is not positive
+    pub fn set_limits(&mut self, customer_id: &CustomerId, limits: AccountLimits) -> BankResult<()> {
+        limits.validate()?;
+        self.get_customer(customer_id)?.get_account()?.ensure_open()?;
+
+        self.emit(BankEvent::LimitsChanged {
+            customer_id: customer_id.clone(),
+            limits,
+        })
+    }
 
-        let account_id = customer.get_account()?.id.clone();
-        Ok(account_id)
+    /// Whether another customer already uses this email
+    ///
+    /// Compares normalized addresses, since customers registered before
+    /// normalization may have mixed-case emails. Deleted customers free
+    /// up their address. A lookup in the email index, not a scan.
+    pub(crate) fn email_in_use(&self, email: &str, except_customer_id: Option<&CustomerId>) -> bool {
+        let email = validation::normalize_email(email);
+        self.email_index
+            .lookup(&self.customers, &email)
+            .is_some_and(|customer_id| Some(customer_id) != except_customer_id)
     }
 
     /// Gets a customer by ID
-    pub fn get_customer(&self, customer_id: &str) -> BankResult<&Customer> {
+    ///
+    /// Deleted customers are reported as not found.
+    pub fn get_customer(&self, customer_id: &CustomerId) -> BankResult<&Customer> {
         self.customers
             .get(customer_id)
-            .ok_or_else(|| BankError::CustomerNotFound(customer_id.to_string()))
+            .filter(|c| c.status != CustomerStatus::Deleted)
+            .ok_or_else(|| BankError::CustomerNotFound(customer_id.clone()))
     }
 
-    /// Lists all customers
+    /// Gets a customer who is allowed to log in and move money
+    ///
+    /// # Returns
+    /// * `Err(BankError::CustomerDeactivated)` - If the customer is deactivated
+    pub(crate) fn active_customer(&self, customer_id: &CustomerId) -> BankResult<&Customer> {
+        let customer = self.get_customer(customer_id)?;
+        if customer.status == CustomerStatus::Deactivated {
+            return Err(BankError::CustomerDeactivated(customer_id.clone()));
+        }
+        Ok(customer)
+    }
+
+    /// Lists all customers except deleted ones
     ///
     /// Demonstrates: Collecting iterator results into a Vec
     pub fn list_customers(&self) -> Vec<&Customer> {
-        self.customers.values().collect()
+        self.customers
+            .values()
+            .filter(|c| c.status != CustomerStatus::Deleted)
+            .collect()
     }
 
-    /// Finds customers by name (case-insensitive)
+    /// Gets up to `limit` customers starting at `offset`
+    ///
+    /// Customers are ordered by registration time (then ID), so pages stay
+    /// stable as long as nobody is deleted in between.
+    pub fn list_customers_page(&self, offset: usize, limit: usize) -> Page<&Customer> {
+        Page::from_items(self.list_customers_sorted(SortKey::Registered, SortOrder::Ascending), offset, limit)
+    }
+
+    /// Finds customers by name (case-insensitive), ordered by registration
+    /// time
     ///
     /// Demonstrates: Iterator filtering and collecting
     pub fn find_customers_by_name(&self, name_query: &str) -> Vec<&Customer> {
         let query_lower = name_query.to_lowercase();
-        self.customers
-            .values()
+        self.list_customers_sorted(SortKey::Registered, SortOrder::Ascending)
+            .into_iter()
             .filter(|c| c.name.to_lowercase().contains(&query_lower))
             .collect()
     }
 
-    /// Gets total balance across all accounts
+    /// Finds the customer who owns the given account
+    ///
+    /// Deleted customers are included so that account IDs in old
+    /// transactions still resolve to a name.
+    ///
+    /// Demonstrates: Iterator find with nested Option access
+    pub fn find_customer_by_account_id(&self, account_id: &AccountId) -> Option<&Customer> {
+        self.customers
+            .values()
+            .find(|c| c.account.as_ref().is_some_and(|account| account.id == *account_id))
+    }
+
+    /// Finds the customer who owns the account with this number
+    ///
+    /// Like `find_customer_by_account_id`, deleted customers are included.
+    pub fn find_customer_by_account_number(&self, number: AccountNumber) -> Option<&Customer> {
+        self.account_numbers
+            .get(&number)
+            .and_then(|customer_id| self.customers.get(customer_id))
+    }
+
+    /// Gets total balance across all open (not closed) accounts
     ///
     /// Demonstrates: Complex iterator chain with filter_map
     /// filter_map combines filter and map in one operation
-    pub fn total_bank_balance(&self) -> f64 {
+    pub fn total_bank_balance(&self) -> Money {
         self.customers
             .values()
             .filter_map(|c| c.account.as_ref())
+            .filter(|a| a.status != AccountStatus::Closed)
             .map(|a| a.balance)
             .sum()
     }
@@ -0,0 +1,98 @@
+//! Email index - finding a customer by email without a scan
+//!
+//! Demonstrates: A secondary index kept in step with the primary map,
+//! the same lazily built cache as the transaction index
+//!
+//! Registration has to check that an email is not taken, and command mode
+//! finds customers by email on every run; both used to compare against
+//! every customer. The index maps normalized emails to customer IDs. Like
+//! the transaction index it is not saved with the bank: the first lookup
+//! builds it from the customers, and `apply_event` keeps it up to date as
+//! customers register, change their email or are deleted. Deleted
+//! customers are left out, so their address is free again.
+
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
+use std::sync::OnceLock;
+
+use crate::models::{Customer, CustomerId, CustomerStatus};
+use crate::validation;
+
+/// The built index
+#[derive(Debug, Clone, Default)]
+struct Emails {
+    /// Normalized email -> customer ID
+    by_email: HashMap<String, CustomerId>,
+
+    /// Emails more than one customer had when the index was built
+    /// (customers registered before emails were normalized can share one);
+    /// the index holds the first registered
+    shared: HashSet<String>,
+}
+
+/// Which customer uses each email (normalized email -> customer ID)
+#[derive(Debug, Clone, Default)]
+pub(crate) struct EmailIndex(OnceLock<Emails>);
+
+impl EmailIndex {
+    /// The index, built from `customers` on first use
+    fn get(&self, customers: &HashMap<CustomerId, Customer>) -> &Emails {
+        self.0.get_or_init(|| {
+            let mut active: Vec<&Customer> =
+                customers.values().filter(|customer| customer.status != CustomerStatus::Deleted).collect();
+            active.sort_by(|a, b| a.registered_at.cmp(&b.registered_at).then_with(|| a.id.cmp(&b.id)));
+            let mut emails = Emails::default();
+            for customer in active {
+                match emails.by_email.entry(validation::normalize_email(&customer.email)) {
+                    Entry::Occupied(entry) => {
+                        emails.shared.insert(entry.key().clone());
+                    }
+                    Entry::Vacant(entry) => {
+                        entry.insert(customer.id.clone());
+                    }
+                }
+            }
+            emails
+        })
+    }
+
+    /// The ID of the customer using `email`, which must be normalized
+    pub(crate) fn lookup<'a>(
+        &'a self,
+        customers: &HashMap<CustomerId, Customer>,
+        email: &str,
+    ) -> Option<&'a CustomerId> {
+        self.get(customers).by_email.get(email)
+    }
+
+    /// Records a customer's email; nothing to do until the index is first
+    /// built, since building it picks the customer up
+    ///
+    /// An address another customer already uses (only possible for
+    /// customers from before normalization) stays with that customer.
+    pub(crate) fn insert(&mut self, email: &str, customer_id: &CustomerId) {
+        let Some(emails) = self.0.get_mut() else { return };
+        let email = validation::normalize_email(email);
+        match emails.by_email.get(&email) {
+            Some(holder) if holder != customer_id => {
+                emails.shared.insert(email);
+            }
+            _ => {
+                emails.by_email.insert(email, customer_id.clone());
+            }
+        }
+    }
+
+    /// Forgets a customer's email, after it changed or the customer was
+    /// deleted
+    pub(crate) fn remove(&mut self, email: &str, customer_id: &CustomerId) {
+        let email = validation::normalize_email(email);
+        let Some(emails) = self.0.get_mut() else { return };
+        if emails.shared.contains(&email) {
+            // Another customer may still use it; the next lookup rebuilds
+            self.0 = OnceLock::new();
+        } else if emails.by_email.get(&email) == Some(customer_id) {
+            emails.by_email.remove(&email);
+        }
+    }
+}
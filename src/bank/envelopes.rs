@@ -0,0 +1,174 @@
+//! Savings goals - parts of a balance set aside in named envelopes
+//!
+//! Demonstrates: Whole-record events (the envelope after the change)
+//! for a record edited in several small ways
+//!
+//! Allocating or releasing money only moves it between envelopes and the
+//! free balance, so no transaction is posted. A deposit into an envelope
+//! is an ordinary deposit followed by the allocation. Withdrawals are
+//! refused if they would need money from a locked envelope; the account
+//! trims unlocked envelopes itself when spending reaches them (see
+//! `Account::post`).
+
+use crate::errors::{BankError, BankResult};
+use crate::models::{CustomerId, Envelope, Money, Transaction, TransactionDetails};
+use crate::validation;
+use super::core::Bank;
+use super::events::BankEvent;
+
+impl Bank {
+    /// Starts a savings goal on a customer's account
+    ///
+    /// # Arguments
+    /// * `target` - The amount to save towards
+    /// * `locked` - Whether withdrawals must leave its money alone
+    ///
+    /// # Returns
+    /// * `Ok(Envelope)` - The new, empty goal
+    /// * `Err(BankError::ValidationError)` - If the name is invalid or taken
+    pub fn create_envelope(
+        &mut self,
+        customer_id: &CustomerId,
+        name: &str,
+        target: Money,
+        locked: bool,
+    ) -> BankResult<Envelope> {
+        let name = validation::validate_envelope_name(name)?;
+        if !target.is_positive() {
+            return Err(BankError::InvalidAmount(target));
+        }
+        let account = self.active_customer(customer_id)?.get_account()?;
+        account.ensure_open()?;
+        if account.envelope(&name).is_some() {
+            return Err(BankError::ValidationError {
+                field: "savings goal".to_string(),
+                reason: format!("'{}' already exists", name),
+            });
+        }
+
+        let envelope = Envelope::new_at(name, target, locked, self.now());
+        self.save_envelope(customer_id, envelope)
+    }
+
+    /// Deletes a savings goal; its money becomes free to spend
+    ///
+    /// # Returns
+    /// * `Err(BankError::EnvelopeLocked)` - If it is locked and not empty
+    pub fn remove_envelope(&mut self, customer_id: &CustomerId, name: &str) -> BankResult<Envelope> {
+        let envelope = self.owned_envelope(customer_id, name)?;
+        if envelope.locked && envelope.allocated.is_positive() {
+            return Err(BankError::EnvelopeLocked(envelope.name));
+        }
+
+        self.emit(BankEvent::EnvelopeRemoved {
+            customer_id: customer_id.clone(),
+            name: envelope.name.clone(),
+        })?;
+        Ok(envelope)
+    }
+
+    /// Sets free money aside in a savings goal
+    ///
+    /// # Returns
+    /// * `Ok(Envelope)` - The goal after the allocation
+    /// * `Err(BankError::InsufficientFunds)` - If less than `amount` is
+    ///   available outside the account's goals
+    pub fn allocate_to_envelope(
+        &mut self,
+        customer_id: &CustomerId,
+        name: &str,
+        amount: Money,
+    ) -> BankResult<Envelope> {
+        if !amount.is_positive() {
+            return Err(BankError::InvalidAmount(amount));
+        }
+        let mut envelope = self.owned_envelope(customer_id, name)?;
+        let account = self.get_customer(customer_id)?.get_account()?;
+        account.ensure_open()?;
+        let available = account.unallocated_balance_at(self.now());
+        if available < amount {
+            return Err(BankError::InsufficientFunds { available, requested: amount });
+        }
+
+        envelope.allocated += amount;
+        self.save_envelope(customer_id, envelope)
+    }
+
+    /// Moves money out of a savings goal back into the free balance
+    ///
+    /// # Returns
+    /// * `Ok(Envelope)` - The goal after the release
+    /// * `Err(BankError::EnvelopeLocked)` - If the goal is locked
+    /// * `Err(BankError::InsufficientFunds)` - If the goal holds less than
+    ///   `amount`
+    pub fn release_from_envelope(
+        &mut self,
+        customer_id: &CustomerId,
+        name: &str,
+        amount: Money,
+    ) -> BankResult<Envelope> {
+        if !amount.is_positive() {
+            return Err(BankError::InvalidAmount(amount));
+        }
+        let mut envelope = self.owned_envelope(customer_id, name)?;
+        if envelope.locked {
+            return Err(BankError::EnvelopeLocked(envelope.name));
+        }
+        if envelope.allocated < amount {
+            return Err(BankError::InsufficientFunds { available: envelope.allocated, requested: amount });
+        }
+
+        envelope.allocated -= amount;
+        self.save_envelope(customer_id, envelope)
+    }
+
+    /// Locks or unlocks a savings goal
+    pub fn set_envelope_locked(&mut self, customer_id: &CustomerId, name: &str, locked: bool) -> BankResult<Envelope> {
+        let mut envelope = self.owned_envelope(customer_id, name)?;
+        envelope.locked = locked;
+        self.save_envelope(customer_id, envelope)
+    }
+
+    /// Deposits money straight into a savings goal
+    ///
+    /// # Returns
+    /// * `Ok(Transaction)` - The deposit
+    /// * `Err(BankError::EnvelopeNotFound)` - If there is no such goal
+    pub fn deposit_to_envelope(
+        &mut self,
+        customer_id: &CustomerId,
+        name: &str,
+        amount: Money,
+        details: TransactionDetails,
+    ) -> BankResult<Transaction> {
+        let mut envelope = self.owned_envelope(customer_id, name)?;
+        let mut transaction = self.active_customer(customer_id)?.get_account()?.prepare_deposit(amount, self.now())?;
+        transaction.annotate(details);
+        transaction.cash = true;
+
+        self.emit(BankEvent::Deposited { customer_id: customer_id.clone(), transaction: transaction.clone() })?;
+        envelope.allocated += amount;
+        self.save_envelope(customer_id, envelope)?;
+        Ok(transaction)
+    }
+
+    /// A customer's savings goals, in the order they were created
+    pub fn list_envelopes(&self, customer_id: &CustomerId) -> BankResult<&[Envelope]> {
+        Ok(&self.get_customer(customer_id)?.get_account()?.envelopes)
+    }
+
+    /// Records `envelope` as the customer's goal of that name
+    fn save_envelope(&mut self, customer_id: &CustomerId, envelope: Envelope) -> BankResult<Envelope> {
+        self.emit(BankEvent::EnvelopeSaved { customer_id: customer_id.clone(), envelope: envelope.clone() })?;
+        Ok(envelope)
+    }
+
+    /// A copy of a customer's goal called `name` (ignoring case)
+    fn owned_envelope(&self, customer_id: &CustomerId, name: &str) -> BankResult<Envelope> {
+        self.get_customer(customer_id)?
+            .get_account()?
+            .envelope(name)
+            .cloned()
+            .ok_or_else(|| BankError::EnvelopeNotFound(name.trim().to_string()))
+    }
+}
@@ -0,0 +1,870 @@
+//! Events module - the bank's state as a fold over its change log
+//!
+//! Demonstrates: Event sourcing, enums as change records, idempotent replay
+//!
+//! Every change to a `Bank` is described by a `BankEvent`. Operations such
+//! as `deposit` or `transfer` first validate the request and build the
+//! event (with the exact records it creates, IDs and timestamps included),
+//! then hand it to `apply_event`, the single place where state changes.
+//! Because the same fold runs live and during replay:
+//!
+//! - the event log is a complete audit trail of what happened
+//! - `Bank::from_events` rebuilds the state from the log alone
+//! - a snapshot (the serialized `Bank`) plus the events recorded after it
+//!   gives the same state as the full log
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::errors::{BankError, BankResult};
+use crate::events::Webhook;
+use crate::models::{
+    Account, AccountLimits, AccountNumber, AccountStatus, AlertRules, Autopay, Beneficiary, BookAccount, BookEntry,
+    Budget, Card, CardLimits, CardStatus, ChartOfAccounts, Cheque, ChequePolicy, ChequeStatus, Credentials, Customer,
+    CustomerId, CustomerStatus, DormancyPolicy, Envelope, FlaggedTransaction, FraudRules, Hold, Merchant, Money,
+    ReviewStatus, StaffMember, TermDeposit, TermDepositStatus, Transaction, TransactionId, TransactionStatus,
+    TransactionTags,
+};
+use super::bookkeeping::entry_for;
+use super::core::Bank;
+use super::outbox::Outbox;
+
+/// A single change to the bank state
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum BankEvent {
+    CustomerRegistered { customer: Customer },
+    AccountOpened { customer_id: CustomerId, account: Account },
+    /// An account opened before account numbers existed got one
+    AccountNumberAssigned { customer_id: CustomerId, number: AccountNumber },
+    Deposited { customer_id: CustomerId, transaction: Transaction },
+    Withdrawn { customer_id: CustomerId, transaction: Transaction },
+    Transferred {
+        from_customer_id: CustomerId,
+        to_customer_id: CustomerId,
+        debit: Transaction,
+        credit: Transaction,
+    },
+    TransactionsImported { customer_id: CustomerId, transactions: Vec<Transaction> },
+    CredentialsUpdated { customer_id: CustomerId, credentials: Credentials },
+    StaffUpdated { member: StaffMember },
+    Reversed { customer_id: CustomerId, transaction: Transaction },
+    /// A posted transaction's category and tags were replaced
+    TransactionTagged { customer_id: CustomerId, transaction_id: TransactionId, tags: TransactionTags },
+    LimitsChanged { customer_id: CustomerId, limits: AccountLimits },
+    AlertRulesChanged { customer_id: CustomerId, rules: AlertRules },
+    WebhookAdded { webhook: Webhook },
+    WebhookRemoved { id: String },
+    AccountStatusChanged { customer_id: CustomerId, status: AccountStatus },
+    CustomerStatusChanged { customer_id: CustomerId, status: CustomerStatus },
+    /// The account was flagged dormant at `since`; `freeze` also freezes
+    /// it if it is active
+    AccountDormant { customer_id: CustomerId, since: DateTime<Utc>, freeze: bool },
+    DormancyPolicyChanged { policy: DormancyPolicy },
+    /// Cash was brought into the vault, or taken out of it, by an admin
+    VaultToppedUp { amount: Money, at: DateTime<Utc> },
+    VaultWithdrawn { amount: Money, at: DateTime<Utc> },
+    FraudRulesChanged { rules: FraudRules },
+    ChartOfAccountsChanged { chart: ChartOfAccounts },
+    /// A fraud rule put a transaction in the review queue
+    TransactionFlagged { flag: FlaggedTransaction },
+    /// Staff approved or reversed a flagged transaction; a reversal's
+    /// postings are `Reversed` events of their own
+    FlagReviewed { transaction_id: TransactionId, status: ReviewStatus, reviewer: String, at: DateTime<Utc> },
+    /// The customer's profile after an edit
+    CustomerUpdated {
+        customer_id: CustomerId,
+        name: String,
+        email: String,
+        phone: Option<String>,
+        address: Option<String>,
+        /// Older journals predate this field
+        #[serde(default)]
+        date_of_birth: Option<NaiveDate>,
+    },
+    /// A payee was saved, replacing any with the same nickname
+    BeneficiaryAdded { customer_id: CustomerId, beneficiary: Beneficiary },
+    BeneficiaryRemoved { customer_id: CustomerId, nickname: String },
+    /// Money left for another bank; `transaction` is posted as pending
+    ExternalTransferSent { customer_id: CustomerId, transaction: Transaction },
+    /// The other bank accepted a pending external transfer
+    ExternalTransferSettled { customer_id: CustomerId, transaction_id: TransactionId },
+    /// The other bank refused a pending external transfer; `refund` puts
+    /// the money back
+    ExternalTransferRejected { customer_id: CustomerId, transaction_id: TransactionId, refund: Transaction },
+    /// Money was held for a withdrawal that is not posted yet
+    WithdrawalAuthorized { customer_id: CustomerId, authorization: Transaction },
+    /// A held withdrawal was posted; `transaction` keeps the authorization's ID
+    AuthorizationCaptured { customer_id: CustomerId, transaction: Transaction },
+    /// A held withdrawal was cancelled and the money released
+    AuthorizationVoided { customer_id: CustomerId, authorization_id: TransactionId },
+    HoldPlaced { customer_id: CustomerId, hold: Hold },
+    /// A hold was released or had expired
+    HoldReleased { customer_id: CustomerId, hold_id: String },
+    /// A held amount was withdrawn; `transaction` is the withdrawal
+    HoldCaptured { customer_id: CustomerId, hold_id: String, transaction: Transaction },
+    /// A cheque was paid in; `transaction` is its pending deposit
+    ChequeDeposited { customer_id: CustomerId, cheque: Cheque, transaction: Transaction },
+    /// A cheque cleared; a cheque written by a customer here is paid by
+    /// `debit` from `drawer_id`'s account
+    ChequeCleared {
+        customer_id: CustomerId,
+        cheque_id: String,
+        drawer_id: Option<CustomerId>,
+        debit: Option<Transaction>,
+    },
+    /// A cheque bounced; `reversal` takes the deposit back and `fee`, if
+    /// any, charges for it
+    ChequeBounced {
+        customer_id: CustomerId,
+        cheque_id: String,
+        reason: String,
+        reversal: Transaction,
+        fee: Option<Transaction>,
+    },
+    ChequePolicyChanged { policy: ChequePolicy },
+    CardIssued { customer_id: CustomerId, card: Card },
+    CardBlocked { customer_id: CustomerId, card_id: String },
+    /// `card_id` was retired and `replacement` issued in its place
+    CardReplaced { customer_id: CustomerId, card_id: String, replacement: Card },
+    CardLimitsChanged { customer_id: CustomerId, card_id: String, limits: CardLimits },
+    /// A savings goal was created or changed; replaces any with the same name
+    EnvelopeSaved { customer_id: CustomerId, envelope: Envelope },
+    EnvelopeRemoved { customer_id: CustomerId, name: String },
+    /// A category's monthly budget was set, replacing any earlier one
+    BudgetSet { customer_id: CustomerId, budget: Budget },
+    BudgetRemoved { customer_id: CustomerId, category: String },
+    /// `transaction` moves the principal out of the account
+    TermDepositOpened { customer_id: CustomerId, deposit: TermDeposit, transaction: Transaction },
+    /// A term deposit was paid back, at maturity (`Matured`) or early
+    /// (`Withdrawn`); `transaction` is the payout
+    TermDepositClosed {
+        customer_id: CustomerId,
+        deposit_id: String,
+        status: TermDepositStatus,
+        transaction: Transaction,
+    },
+    MerchantAdded { merchant: Merchant },
+    MerchantRemoved { merchant_id: String },
+    /// `transaction` is the bill payment
+    BillPaid { customer_id: CustomerId, transaction: Transaction },
+    /// A fee the pipeline charged for a withdrawal or transfer, posted
+    /// right after it
+    FeeCharged { customer_id: CustomerId, transaction: Transaction },
+    AutopayCreated { autopay: Autopay },
+    AutopayCancelled { autopay_id: String },
+    /// The payment due at `run_at` was made (`transaction`) or not
+    /// (`failure`); either way the rule moves on to its next date
+    AutopayRan {
+        autopay_id: String,
+        customer_id: CustomerId,
+        run_at: DateTime<Utc>,
+        transaction: Option<Transaction>,
+        failure: Option<String>,
+    },
+    /// The account's oldest transactions, up to and including `through`,
+    /// were moved to the archive, which now reaches up to `before`
+    TransactionsArchived { customer_id: CustomerId, before: DateTime<Utc>, through: TransactionId },
+}
+
+impl Bank {
+    /// Rebuilds a bank by folding its events, oldest first
+    ///
+    /// # Arguments
+    /// * `name` - The bank's name (not part of the event log)
+    /// * `events` - The full event log
+    pub fn from_events(
+        name: String,
+        events: impl IntoIterator<Item = BankEvent>,
+    ) -> BankResult<Self> {
+        let mut bank = Bank::new(name);
+        for event in events {
+            bank.apply_event(event)?;
+        }
+        Ok(bank)
+    }
+
+    /// Applies one event to the state
+    ///
+    /// This is the only place where events change the bank. It is
+    /// idempotent: an event already reflected in the state (for example
+    /// because a snapshot was saved after it was recorded) is skipped.
+    ///
+    /// # Returns
+    /// * `Ok(true)` - The event changed the state
+    /// * `Ok(false)` - The event was already applied
+    pub fn apply_event(&mut self, event: BankEvent) -> BankResult<bool> {
+        match event {
+            BankEvent::CustomerRegistered { customer } => {
+                if self.customers.contains_key(&customer.id) {
+                    return Ok(false);
+                }
+                if customer.status != CustomerStatus::Deleted {
+                    self.email_index.insert(&customer.email, &customer.id);
+                }
+                self.customers.insert(customer.id.clone(), customer);
+            }
+            BankEvent::AccountOpened { customer_id, account } => {
+                if self.customer_mut(&customer_id)?.account.is_some() {
+                    return Ok(false);
+                }
+                let entries: Vec<BookEntry> =
+                    account.transactions.iter().map(|tx| entry_for(&self.chart, &account, &customer_id, tx)).collect();
+                self.books_mut().extend(entries);
+                let customer = self.customers.get_mut(&customer_id).expect("customer was just found");
+                for transaction in &account.transactions {
+                    self.transaction_index.insert(&transaction.id, &customer_id);
+                    // The opening deposit is cash; `customer` borrows from
+                    // `self`, so this cannot go through `move_vault_cash`
+                    if let (true, Some(vault)) = (transaction.cash, self.vault.as_mut()) {
+                        *vault += transaction.signed_amount();
+                    }
+                }
+                if let Some(number) = account.number {
+                    self.account_numbers.insert(number, customer_id);
+                }
+                customer.account = Some(account);
+                self.total_transactions += 1;
+            }
+            BankEvent::AccountNumberAssigned { customer_id, number } => {
+                let account = self
+                    .customers
+                    .get_mut(&customer_id)
+                    .ok_or_else(|| BankError::CustomerNotFound(customer_id.clone()))?
+                    .get_account_mut()?;
+                if account.number.is_some() {
+                    return Ok(false);
+                }
+                account.number = Some(number);
+                self.account_numbers.insert(number, customer_id);
+            }
+            BankEvent::Deposited { customer_id, transaction }
+            | BankEvent::Withdrawn { customer_id, transaction }
+            | BankEvent::ExternalTransferSent { customer_id, transaction }
+            | BankEvent::BillPaid { customer_id, transaction }
+            | BankEvent::FeeCharged { customer_id, transaction } => {
+                if !self.post_transaction(&customer_id, transaction)? {
+                    return Ok(false);
+                }
+                self.total_transactions += 1;
+            }
+            BankEvent::Reversed { customer_id, transaction } => {
+                let original_id = transaction.reverses().cloned();
+                let reversal_id = transaction.id.clone();
+                if !self.post_transaction(&customer_id, transaction)? {
+                    return Ok(false);
+                }
+                self.total_transactions += 1;
+                let account = self.account_mut(&customer_id)?;
+                if let Some(original) = account.transactions.iter_mut().find(|tx| Some(&tx.id) == original_id.as_ref()) {
+                    if original.status == TransactionStatus::Completed {
+                        original.status = TransactionStatus::Reversed;
+                        original.reversed_by = Some(reversal_id);
+                    }
+                }
+                if let Some(original_id) = original_id {
+                    self.forget_undo(&original_id);
+                }
+            }
+            BankEvent::WithdrawalAuthorized { customer_id, authorization } => {
+                let account = self.account_mut(&customer_id)?;
+                let known = |tx: &Transaction| tx.id == authorization.id;
+                if account.authorizations.iter().any(known) || account.transactions.iter().any(known) {
+                    return Ok(false);
+                }
+                account.authorizations.push(authorization);
+            }
+            BankEvent::AuthorizationCaptured { customer_id, transaction } => {
+                self.account_mut(&customer_id)?
+                    .authorizations
+                    .retain(|auth| auth.id != transaction.id);
+                if !self.post_transaction(&customer_id, transaction)? {
+                    return Ok(false);
+                }
+                self.total_transactions += 1;
+            }
+            BankEvent::HoldPlaced { customer_id, hold } => {
+                let account = self.account_mut(&customer_id)?;
+                if account.holds.iter().any(|h| h.id == hold.id) {
+                    return Ok(false);
+                }
+                account.holds.push(hold);
+            }
+            BankEvent::HoldReleased { customer_id, hold_id } => {
+                if self.account_mut(&customer_id)?.release_hold(&hold_id).is_err() {
+                    return Ok(false);
+                }
+            }
+            BankEvent::HoldCaptured { customer_id, hold_id, transaction } => {
+                self.account_mut(&customer_id)?.holds.retain(|h| h.id != hold_id);
+                if !self.post_transaction(&customer_id, transaction)? {
+                    return Ok(false);
+                }
+                self.total_transactions += 1;
+            }
+            BankEvent::ChequeDeposited { customer_id, cheque, transaction } => {
+                let account = self.account_mut(&customer_id)?;
+                if account.cheques.iter().any(|c| c.id == cheque.id) {
+                    return Ok(false);
+                }
+                account.cheques.push(cheque);
+                self.post_transaction(&customer_id, transaction)?;
+                self.total_transactions += 1;
+            }
+            BankEvent::ChequeCleared { customer_id, cheque_id, drawer_id, debit } => {
+                let transaction_id = match self.settle_cheque(&customer_id, &cheque_id, ChequeStatus::Cleared, None)? {
+                    Some(transaction_id) => transaction_id,
+                    None => return Ok(false),
+                };
+                self.set_transaction_status(&customer_id, &transaction_id, TransactionStatus::Completed)?;
+                if let (Some(drawer_id), Some(debit)) = (drawer_id, debit) {
+                    if self.post_transaction(&drawer_id, debit)? {
+                        self.total_transactions += 1;
+                    }
+                }
+            }
+            BankEvent::ChequeBounced { customer_id, cheque_id, reason, reversal, fee } => {
+                let transaction_id = match self.settle_cheque(&customer_id, &cheque_id, ChequeStatus::Bounced, Some(reason))? {
+                    Some(transaction_id) => transaction_id,
+                    None => return Ok(false),
+                };
+                self.set_transaction_status(&customer_id, &transaction_id, TransactionStatus::Failed)?;
+                for transaction in std::iter::once(reversal).chain(fee) {
+                    if self.post_transaction(&customer_id, transaction)? {
+                        self.total_transactions += 1;
+                    }
+                }
+            }
+            BankEvent::CardIssued { customer_id, card } => {
+                let customer = self.customer_mut(&customer_id)?;
+                if customer.card(&card.id).is_some() {
+                    return Ok(false);
+                }
+                customer.cards.push(card);
+            }
+            BankEvent::CardBlocked { customer_id, card_id } => {
+                let card = self.card_mut(&customer_id, &card_id)?;
+                if card.status != CardStatus::Active {
+                    return Ok(false);
+                }
+                card.status = CardStatus::Blocked;
+            }
+            BankEvent::CardReplaced { customer_id, card_id, replacement } => {
+                let card = self.card_mut(&customer_id, &card_id)?;
+                if card.status == CardStatus::Replaced {
+                    return Ok(false);
+                }
+                card.status = CardStatus::Replaced;
+                card.replaced_by = Some(replacement.id.clone());
+                self.customer_mut(&customer_id)?.cards.push(replacement);
+            }
+            BankEvent::CardLimitsChanged { customer_id, card_id, limits } => {
+                let card = self.card_mut(&customer_id, &card_id)?;
+                if card.limits == limits {
+                    return Ok(false);
+                }
+                card.limits = limits;
+            }
+            BankEvent::EnvelopeSaved { customer_id, envelope } => {
+                let account = self.account_mut(&customer_id)?;
+                let key = Envelope::key(&envelope.name);
+                match account.envelopes.iter_mut().find(|e| Envelope::key(&e.name) == key) {
+                    Some(existing) if *existing == envelope => return Ok(false),
+                    Some(existing) => *existing = envelope,
+                    None => account.envelopes.push(envelope),
+                }
+            }
+            BankEvent::EnvelopeRemoved { customer_id, name } => {
+                let account = self.account_mut(&customer_id)?;
+                let before = account.envelopes.len();
+                account.envelopes.retain(|e| Envelope::key(&e.name) != Envelope::key(&name));
+                if account.envelopes.len() == before {
+                    return Ok(false);
+                }
+            }
+            BankEvent::BudgetSet { customer_id, budget } => {
+                let account = self.account_mut(&customer_id)?;
+                match account.budgets.iter_mut().find(|b| b.category == budget.category) {
+                    Some(existing) if *existing == budget => return Ok(false),
+                    Some(existing) => *existing = budget,
+                    None => account.budgets.push(budget),
+                }
+            }
+            BankEvent::BudgetRemoved { customer_id, category } => {
+                let account = self.account_mut(&customer_id)?;
+                let before = account.budgets.len();
+                account.budgets.retain(|b| b.category != category);
+                if account.budgets.len() == before {
+                    return Ok(false);
+                }
+            }
+            BankEvent::TermDepositOpened { customer_id, deposit, transaction } => {
+                if !self.post_transaction(&customer_id, transaction)? {
+                    return Ok(false);
+                }
+                self.account_mut(&customer_id)?.term_deposits.push(deposit);
+                self.total_transactions += 1;
+            }
+            BankEvent::TermDepositClosed { customer_id, deposit_id, status, transaction } => {
+                let account = self.account_mut(&customer_id)?;
+                if account.transactions.iter().any(|tx| tx.id == transaction.id) {
+                    return Ok(false);
+                }
+                let deposit = account
+                    .term_deposits
+                    .iter_mut()
+                    .find(|d| d.id == deposit_id)
+                    .ok_or_else(|| BankError::TermDepositNotFound(deposit_id.clone()))?;
+                deposit.status = status;
+                deposit.payout = Some(transaction.amount);
+                deposit.closed_at = Some(transaction.timestamp);
+                let entry = entry_for(&self.chart, self.account(&customer_id)?, &customer_id, &transaction);
+                self.books_mut().push(entry);
+                let account = self.account_mut(&customer_id)?;
+                let transaction_id = transaction.id.clone();
+                account.post(transaction);
+                self.transaction_index.insert(&transaction_id, &customer_id);
+                self.total_transactions += 1;
+            }
+            BankEvent::MerchantAdded { merchant } => {
+                if self.merchants.iter().any(|m| m.id == merchant.id) {
+                    return Ok(false);
+                }
+                self.merchants.push(merchant);
+            }
+            BankEvent::MerchantRemoved { merchant_id } => {
+                let before = self.merchants.len();
+                self.merchants.retain(|m| m.id != merchant_id);
+                if self.merchants.len() == before {
+                    return Ok(false);
+                }
+            }
+            BankEvent::AutopayCreated { autopay } => {
+                if self.autopays.iter().any(|a| a.id == autopay.id) {
+                    return Ok(false);
+                }
+                self.autopays.push(autopay);
+            }
+            BankEvent::AutopayCancelled { autopay_id } => {
+                let before = self.autopays.len();
+                self.autopays.retain(|a| a.id != autopay_id);
+                if self.autopays.len() == before {
+                    return Ok(false);
+                }
+            }
+            BankEvent::AutopayRan { autopay_id, customer_id, run_at, transaction, failure } => {
+                let autopay = self
+                    .autopays
+                    .iter_mut()
+                    .find(|a| a.id == autopay_id)
+                    .ok_or_else(|| BankError::ScheduleNotFound(autopay_id.clone()))?;
+                // Each run moves `next_run` on, so a replayed run no longer matches
+                if autopay.next_run != run_at {
+                    return Ok(false);
+                }
+                autopay.next_run = autopay.frequency.advance(run_at);
+                autopay.last_error = failure;
+                if let Some(transaction) = transaction {
+                    autopay.run_count += 1;
+                    if self.post_transaction(&customer_id, transaction)? {
+                        self.total_transactions += 1;
+                    }
+                }
+            }
+            BankEvent::ChequePolicyChanged { policy } => {
+                if self.cheque_policy == policy {
+                    return Ok(false);
+                }
+                self.cheque_policy = policy;
+            }
+            BankEvent::AuthorizationVoided { customer_id, authorization_id } => {
+                let account = self.account_mut(&customer_id)?;
+                let before = account.authorizations.len();
+                account.authorizations.retain(|auth| auth.id != authorization_id);
+                if account.authorizations.len() == before {
+                    return Ok(false);
+                }
+            }
+            BankEvent::ExternalTransferSettled { customer_id, transaction_id } => {
+                if !self.set_transaction_status(&customer_id, &transaction_id, TransactionStatus::Completed)? {
+                    return Ok(false);
+                }
+            }
+            BankEvent::ExternalTransferRejected { customer_id, transaction_id, refund } => {
+                let failed = self.set_transaction_status(&customer_id, &transaction_id, TransactionStatus::Failed)?;
+                let refunded = self.post_transaction(&customer_id, refund)?;
+                if !failed && !refunded {
+                    return Ok(false);
+                }
+                if refunded {
+                    self.total_transactions += 1;
+                }
+            }
+            BankEvent::Transferred { from_customer_id, to_customer_id, debit, credit } => {
+                let debited = self.post_transaction(&from_customer_id, debit)?;
+                let credited = self.post_transaction(&to_customer_id, credit)?;
+                if !debited && !credited {
+                    return Ok(false);
+                }
+                self.total_transactions += 2;
+            }
+            BankEvent::TransactionsImported { customer_id, transactions } => {
+                let mut applied = 0;
+                for transaction in transactions {
+                    if self.post_transaction(&customer_id, transaction)? {
+                        applied += 1;
+                    }
+                }
+                if applied == 0 {
+                    return Ok(false);
+                }
+                self.total_transactions += applied;
+            }
+            BankEvent::TransactionTagged { customer_id, transaction_id, tags } => {
+                let account = self
+                    .customers
+                    .get_mut(&customer_id)
+                    .ok_or(BankError::CustomerNotFound(customer_id))?
+                    .get_account_mut()?;
+                let unchanged = account
+                    .transactions
+                    .iter()
+                    .any(|tx| tx.id == transaction_id && tx.category == tags.category && tx.tags == tags.tags);
+                if unchanged {
+                    return Ok(false);
+                }
+                account.tag_transaction(&transaction_id, tags)?;
+            }
+            BankEvent::CredentialsUpdated { customer_id, credentials } => {
+                let customer = self
+                    .customers
+                    .get_mut(&customer_id)
+                    .ok_or(BankError::CustomerNotFound(customer_id))?;
+                if customer.credentials.as_ref() == Some(&credentials) {
+                    return Ok(false);
+                }
+                customer.credentials = Some(credentials);
+            }
+            BankEvent::LimitsChanged { customer_id, limits } => {
+                let account = self
+                    .customers
+                    .get_mut(&customer_id)
+                    .ok_or(BankError::CustomerNotFound(customer_id))?
+                    .get_account_mut()?;
+                if account.limits == limits {
+                    return Ok(false);
+                }
+                account.limits = limits;
+            }
+            BankEvent::AlertRulesChanged { customer_id, rules } => {
+                let account = self
+                    .customers
+                    .get_mut(&customer_id)
+                    .ok_or(BankError::CustomerNotFound(customer_id))?
+                    .get_account_mut()?;
+                if account.alerts == rules {
+                    return Ok(false);
+                }
+                account.alerts = rules;
+            }
+            BankEvent::WebhookAdded { webhook } => {
+                if self.webhooks.iter().any(|w| w.id == webhook.id) {
+                    return Ok(false);
+                }
+                self.webhooks.push(webhook);
+            }
+            BankEvent::WebhookRemoved { id } => {
+                let before = self.webhooks.len();
+                self.webhooks.retain(|w| w.id != id);
+                if self.webhooks.len() == before {
+                    return Ok(false);
+                }
+            }
+            BankEvent::AccountStatusChanged { customer_id, status } => {
+                let account = self
+                    .customers
+                    .get_mut(&customer_id)
+                    .ok_or(BankError::CustomerNotFound(customer_id))?
+                    .get_account_mut()?;
+                if account.status == status {
+                    return Ok(false);
+                }
+                account.status = status;
+            }
+            BankEvent::AccountDormant { customer_id, since, freeze } => {
+                let account = self.account_mut(&customer_id)?;
+                if account.dormant_since.is_some() {
+                    return Ok(false);
+                }
+                account.dormant_since = Some(since);
+                if freeze && account.status == AccountStatus::Active {
+                    account.status = AccountStatus::Frozen;
+                }
+            }
+            BankEvent::DormancyPolicyChanged { policy } => {
+                if self.dormancy_policy == policy {
+                    return Ok(false);
+                }
+                self.dormancy_policy = policy;
+            }
+            BankEvent::VaultToppedUp { amount, at } => {
+                self.vault = Some(self.vault.unwrap_or(Money::ZERO) + amount);
+                let entry = BookEntry::bank(at, "VAULT TOP-UP", BookAccount::Cash, BookAccount::Capital, amount);
+                self.books_mut().push(entry);
+            }
+            BankEvent::VaultWithdrawn { amount, at } => {
+                self.vault = Some(self.vault.unwrap_or(Money::ZERO) - amount);
+                let entry = BookEntry::bank(at, "VAULT WITHDRAWAL", BookAccount::Capital, BookAccount::Cash, amount);
+                self.books_mut().push(entry);
+            }
+            BankEvent::ChartOfAccountsChanged { chart } => {
+                self.chart = chart;
+            }
+            BankEvent::TransactionsArchived { customer_id, before, through } => {
+                if !self.apply_archived(&customer_id, before, &through)? {
+                    return Ok(false);
+                }
+            }
+            BankEvent::FraudRulesChanged { rules } => {
+                if self.fraud_rules == rules {
+                    return Ok(false);
+                }
+                self.fraud_rules = rules;
+            }
+            BankEvent::TransactionFlagged { flag } => {
+                if self.review_queue.iter().any(|f| f.transaction_id == flag.transaction_id) {
+                    return Ok(false);
+                }
+                self.review_queue.push(flag);
+            }
+            BankEvent::FlagReviewed { transaction_id, status, reviewer, at } => {
+                let flag = self
+                    .review_queue
+                    .iter_mut()
+                    .find(|f| f.transaction_id == transaction_id)
+                    .ok_or(BankError::TransactionNotFound(transaction_id))?;
+                if flag.status != ReviewStatus::Pending {
+                    return Ok(false);
+                }
+                flag.status = status;
+                flag.reviewed_by = Some(reviewer);
+                flag.reviewed_at = Some(at);
+            }
+            BankEvent::CustomerStatusChanged { customer_id, status } => {
+                let customer = self
+                    .customers
+                    .get_mut(&customer_id)
+                    .ok_or_else(|| BankError::CustomerNotFound(customer_id.clone()))?;
+                if customer.status == status {
+                    return Ok(false);
+                }
+                if status == CustomerStatus::Deleted {
+                    self.email_index.remove(&customer.email, &customer_id);
+                } else if customer.status == CustomerStatus::Deleted {
+                    self.email_index.insert(&customer.email, &customer_id);
+                }
+                customer.status = status;
+                // A deleted customer's standing orders could never run again
+                if status == CustomerStatus::Deleted {
+                    self.schedules.retain(|s| {
+                        s.from_customer_id != customer_id && s.to_customer_id != customer_id
+                    });
+                    self.autopays.retain(|a| a.customer_id != customer_id);
+                }
+            }
+            BankEvent::CustomerUpdated { customer_id, name, email, phone, address, date_of_birth } => {
+                let customer = self
+                    .customers
+                    .get_mut(&customer_id)
+                    .ok_or_else(|| BankError::CustomerNotFound(customer_id.clone()))?;
+                if (&customer.name, &customer.email, &customer.phone, &customer.address, &customer.date_of_birth)
+                    == (&name, &email, &phone, &address, &date_of_birth)
+                {
+                    return Ok(false);
+                }
+                if customer.email != email && customer.status != CustomerStatus::Deleted {
+                    self.email_index.remove(&customer.email, &customer_id);
+                    self.email_index.insert(&email, &customer_id);
+                }
+                customer.name = name;
+                customer.email = email;
+                customer.phone = phone;
+                customer.address = address;
+                customer.date_of_birth = date_of_birth;
+            }
+            BankEvent::BeneficiaryAdded { customer_id, beneficiary } => {
+                let customer = self
+                    .customers
+                    .get_mut(&customer_id)
+                    .ok_or(BankError::CustomerNotFound(customer_id))?;
+                let key = Beneficiary::key(&beneficiary.nickname);
+                if customer.beneficiaries.get(&key) == Some(&beneficiary) {
+                    return Ok(false);
+                }
+                customer.beneficiaries.insert(key, beneficiary);
+            }
+            BankEvent::BeneficiaryRemoved { customer_id, nickname } => {
+                let customer = self
+                    .customers
+                    .get_mut(&customer_id)
+                    .ok_or(BankError::CustomerNotFound(customer_id))?;
+                if customer.beneficiaries.remove(&Beneficiary::key(&nickname)).is_none() {
+                    return Ok(false);
+                }
+            }
+            BankEvent::StaffUpdated { member } => {
+                let key = member.username.to_lowercase();
+                if self.staff.get(&key) == Some(&member) {
+                    return Ok(false);
+                }
+                self.staff.insert(key, member);
+            }
+        }
+        Ok(true)
+    }
+
+    /// Applies a new event, buffers it for the journal, sends the alerts,
+    /// domain events and observer calls it triggers and flags it if a
+    /// fraud rule says so
+    ///
+    /// Operations call this once they have validated a change; the
+    /// buffered copy is only made while journaling is enabled.
+    pub(crate) fn emit(&mut self, event: BankEvent) -> BankResult<()> {
+        let outbox = self.outbox_for(&event, true);
+        let flag = self.fraud_flag_for(&event);
+        self.commit(event, outbox, flag)
+    }
+
+    /// Records a change made by a deposit, withdrawal or transfer, whose
+    /// pipeline already checked it for fraud and sends its alerts
+    pub(crate) fn emit_screened(&mut self, event: BankEvent) -> BankResult<()> {
+        let outbox = self.outbox_for(&event, false);
+        self.commit(event, outbox, None)
+    }
+
+    fn commit(&mut self, event: BankEvent, outbox: Outbox, flag: Option<FlaggedTransaction>) -> BankResult<()> {
+        let journaled = self.journal.is_some().then(|| event.clone());
+        let changed = self.apply_event(event)?;
+        if let Some(event) = journaled {
+            self.record(event);
+        }
+        if changed {
+            self.unsaved += 1;
+        }
+        if changed && !outbox.is_empty() {
+            self.send(outbox);
+        }
+        // A flag is an event of its own, so replaying the journal restores
+        // it without checking the rules again
+        if let Some(flag) = flag.filter(|_| changed) {
+            self.emit(BankEvent::TransactionFlagged { flag })?;
+        }
+        if changed {
+            self.enforce_history_cap()?;
+        }
+        Ok(())
+    }
+
+    /// Moves a transaction out of `Pending`
+    ///
+    /// # Returns
+    /// * `Ok(false)` - If the transaction had already left `Pending`
+    fn set_transaction_status(
+        &mut self,
+        customer_id: &CustomerId,
+        transaction_id: &TransactionId,
+        status: TransactionStatus,
+    ) -> BankResult<bool> {
+        let transaction = self
+            .customers
+            .get_mut(customer_id)
+            .ok_or_else(|| BankError::CustomerNotFound(customer_id.clone()))?
+            .get_account_mut()?
+            .transactions
+            .iter_mut()
+            .find(|tx| tx.id == *transaction_id)
+            .ok_or_else(|| BankError::InvalidInput(format!("transaction '{}' not found", transaction_id)))?;
+
+        if transaction.status != TransactionStatus::Pending {
+            return Ok(false);
+        }
+        transaction.status = status;
+        Ok(true)
+    }
+
+    /// Moves a pending cheque to `status`, noting why if it bounced
+    ///
+    /// # Returns
+    /// * `Ok(Some(id))` - The ID of the cheque's deposit
+    /// * `Ok(None)` - If the cheque had already left `Pending`
+    fn settle_cheque(
+        &mut self,
+        customer_id: &CustomerId,
+        cheque_id: &str,
+        status: ChequeStatus,
+        reason: Option<String>,
+    ) -> BankResult<Option<TransactionId>> {
+        let cheque = self
+            .account_mut(customer_id)?
+            .cheques
+            .iter_mut()
+            .find(|c| c.id == cheque_id)
+            .ok_or_else(|| BankError::InvalidInput(format!("cheque '{}' not found", cheque_id)))?;
+        if cheque.status != ChequeStatus::Pending {
+            return Ok(None);
+        }
+        cheque.status = status;
+        cheque.bounce_reason = reason;
+        Ok(Some(cheque.transaction_id.clone()))
+    }
+
+    /// The customer `customer_id`, for changing them
+    fn customer_mut(&mut self, customer_id: &CustomerId) -> BankResult<&mut Customer> {
+        self.customers
+            .get_mut(customer_id)
+            .ok_or_else(|| BankError::CustomerNotFound(customer_id.clone()))
+    }
+
+    /// One of `customer_id`'s cards, for changing it
+    fn card_mut(&mut self, customer_id: &CustomerId, card_id: &str) -> BankResult<&mut Card> {
+        self.customer_mut(customer_id)?
+            .cards
+            .iter_mut()
+            .find(|card| card.id == card_id)
+            .ok_or_else(|| BankError::CardNotFound(card_id.to_string()))
+    }
+
+    /// The account of `customer_id`, deleted customers included
+    fn account(&self, customer_id: &CustomerId) -> BankResult<&Account> {
+        self.customers.get(customer_id).ok_or_else(|| BankError::CustomerNotFound(customer_id.clone()))?.get_account()
+    }
+
+    /// The account of `customer_id`, for changing it
+    fn account_mut(&mut self, customer_id: &CustomerId) -> BankResult<&mut Account> {
+        self.customers
+            .get_mut(customer_id)
+            .ok_or_else(|| BankError::CustomerNotFound(customer_id.clone()))?
+            .get_account_mut()
+    }
+
+    /// Posts a transaction to an account unless it is already there
+    fn post_transaction(&mut self, customer_id: &CustomerId, transaction: Transaction) -> BankResult<bool> {
+        let account = self.account(customer_id)?;
+
+        if account.transactions.iter().any(|tx| tx.id == transaction.id) {
+            return Ok(false);
+        }
+        let entry = entry_for(&self.chart, account, customer_id, &transaction);
+        self.books_mut().push(entry);
+        self.transaction_index.insert(&transaction.id, customer_id);
+        self.move_vault_cash(&transaction);
+        let cap = self.history_cap;
+        let account = self.account_mut(customer_id)?;
+        account.post(transaction);
+        // Trimmed by `emit` once the event is applied
+        let over_cap = cap.is_some_and(|cap| account.transactions.len() > cap);
+        if over_cap && !self.over_cap.contains(customer_id) {
+            self.over_cap.push(customer_id.clone());
+        }
+        Ok(true)
+    }
+}
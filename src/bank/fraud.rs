@@ -0,0 +1,224 @@
+//! Fraud rules - flagging suspicious transactions and reviewing them
+//!
+//! Demonstrates: Checking a new event against history before it is
+//! applied, a review queue kept as records with a status rather than
+//! removed when handled
+//!
+//! The pipeline's `FraudCheck` stage asks `fraud_reason_for` whether a
+//! withdrawal or transfer breaks one of the bank's `FraudRules`; for other
+//! changes that pay out, `emit` asks `fraud_flag_for`. Either way, once the
+//! transaction has posted the answer is recorded as a `TransactionFlagged`
+//! event. The rules look back into archived transactions when the live
+//! history is too short, so a history cap does not make a regular payee
+//! new again. Staff then approve a flagged transaction
+//! or reverse it; both sides of a transfer are reversed together.
+
+use std::borrow::Cow;
+
+use chrono::Duration;
+
+use crate::errors::{BankError, BankResult};
+use crate::middleware::{OperationKind, OperationRequest};
+use crate::models::{
+    Account, CustomerId, FlaggedTransaction, FraudReason, FraudRules, Money, ReviewStatus, Transaction, TransactionId,
+    TransactionType,
+};
+use super::core::Bank;
+use super::events::BankEvent;
+
+impl Bank {
+    /// The rules transactions are checked against
+    pub fn fraud_rules(&self) -> FraudRules {
+        self.fraud_rules
+    }
+
+    /// Changes the rules for transactions made from now on
+    ///
+    /// # Returns
+    /// * `Err(BankError)` - If the rules fail `FraudRules::validate`
+    pub fn set_fraud_rules(&mut self, rules: FraudRules) -> BankResult<()> {
+        rules.validate()?;
+        self.emit(BankEvent::FraudRulesChanged { rules })
+    }
+
+    /// Flagged transactions waiting for review, oldest first
+    pub fn review_queue(&self) -> Vec<&FlaggedTransaction> {
+        self.review_queue.iter().filter(|flag| flag.status == ReviewStatus::Pending).collect()
+    }
+
+    /// Every transaction ever flagged, reviewed or not, oldest first
+    pub fn flagged_transactions(&self) -> &[FlaggedTransaction] {
+        &self.review_queue
+    }
+
+    /// Lets a flagged transaction stand and takes it off the queue
+    ///
+    /// # Returns
+    /// * `Err(BankError::TransactionNotFound)` - If no such transaction
+    ///   was flagged
+    /// * `Err(BankError::InvalidInput)` - If it was already reviewed
+    pub fn approve_flagged(
+        &mut self,
+        transaction_id: &TransactionId,
+        reviewer: &str,
+    ) -> BankResult<FlaggedTransaction> {
+        let transaction_id = self.pending_flag(transaction_id)?.transaction_id.clone();
+        self.review(&transaction_id, ReviewStatus::Approved, reviewer)
+    }
+
+    /// Reverses a flagged transaction and takes it off the queue
+    ///
+    /// A transfer between customers is reversed on both sides.
+    ///
+    /// # Returns
+    /// * `Ok(Vec<Transaction>)` - The reversals that were posted
+    /// * `Err(BankError::TransactionNotFound)` - If no such transaction
+    ///   was flagged
+    /// * `Err(BankError::InvalidInput)` - If it was already reviewed, or
+    ///   the transaction can no longer be reversed
+    /// * `Err(BankError::InsufficientFunds)` - If the recipient has
+    ///   already spent the money; the flag stays pending
+    pub fn reverse_flagged(&mut self, transaction_id: &TransactionId, reviewer: &str) -> BankResult<Vec<Transaction>> {
+        let flag = self.pending_flag(transaction_id)?;
+        let transaction_id = flag.transaction_id.clone();
+        let reason = format!("Fraud review: {}", flag.reason);
+        let reversals = self.prepare_reversals_of(&flag.customer_id, &transaction_id)?;
+
+        let posted = self.post_reversals(reversals, &reason)?;
+        self.review(&transaction_id, ReviewStatus::Reversed, reviewer)?;
+        Ok(posted)
+    }
+
+    fn pending_flag(&self, transaction_id: &TransactionId) -> BankResult<&FlaggedTransaction> {
+        let flag = self
+            .review_queue
+            .iter()
+            .find(|flag| flag.transaction_id == *transaction_id)
+            .ok_or_else(|| BankError::TransactionNotFound(transaction_id.clone()))?;
+        if flag.status != ReviewStatus::Pending {
+            return Err(BankError::InvalidInput(format!("transaction was already reviewed: {}", flag.status)));
+        }
+        Ok(flag)
+    }
+
+    fn review(
+        &mut self,
+        transaction_id: &TransactionId,
+        status: ReviewStatus,
+        reviewer: &str,
+    ) -> BankResult<FlaggedTransaction> {
+        self.emit(BankEvent::FlagReviewed {
+            transaction_id: transaction_id.clone(),
+            status,
+            reviewer: reviewer.to_string(),
+            at: self.now(),
+        })?;
+        self.review_queue
+            .iter()
+            .find(|flag| flag.transaction_id == *transaction_id)
+            .cloned()
+            .ok_or_else(|| BankError::TransactionNotFound(transaction_id.clone()))
+    }
+
+    /// The flag `event` earns under the current rules, checked against
+    /// the history from before it is applied
+    pub(crate) fn fraud_flag_for(&self, event: &BankEvent) -> Option<FlaggedTransaction> {
+        let (customer_id, transaction) = match event {
+            BankEvent::Withdrawn { customer_id, transaction }
+            | BankEvent::ExternalTransferSent { customer_id, transaction } => (customer_id, transaction),
+            BankEvent::Transferred { from_customer_id, debit, .. } => (from_customer_id, debit),
+            _ => return None,
+        };
+        let reason = self.fraud_reason(customer_id, transaction)?;
+        Some(FlaggedTransaction::pending(customer_id.clone(), transaction, reason))
+    }
+
+    /// Why `request` should be reviewed under the current rules, checked
+    /// against the history from before it is posted
+    pub(crate) fn fraud_reason_for(&self, request: &OperationRequest) -> Option<FraudReason> {
+        let transaction_type = match request.kind {
+            OperationKind::Deposit => return None,
+            OperationKind::Withdrawal => TransactionType::Withdrawal,
+            OperationKind::Transfer => {
+                let recipient = self.customers.get(request.to_customer_id.as_ref()?)?;
+                TransactionType::Transfer { to_account_id: recipient.account.as_ref()?.id.clone() }
+            }
+        };
+        // Only the rules' inputs matter: the type, amount and time
+        let probe = Transaction::new_at(transaction_type, request.amount, Money::ZERO, request.at);
+        self.fraud_reason(&request.customer_id, &probe)
+    }
+
+    fn fraud_reason(&self, customer_id: &CustomerId, transaction: &Transaction) -> Option<FraudReason> {
+        let account = self.customers.get(customer_id)?.account.as_ref()?;
+        self.rapid_withdrawals(customer_id, account, transaction)
+            .or_else(|| self.new_payee(customer_id, account, transaction))
+    }
+
+    /// The account's archived transactions, newest first, loaded a page at
+    /// a time as the walk reaches them
+    ///
+    /// A page that cannot be loaded ends the walk, so the rules go by what
+    /// they have seen: a payee not found counts as new.
+    fn archived_history<'a>(
+        &'a self,
+        customer_id: &CustomerId,
+        account: &Account,
+    ) -> impl Iterator<Item = Cow<'a, Transaction>> + 'a {
+        let live = account.transactions.len();
+        self.get_transaction_history(customer_id).into_iter().flatten().skip(live).map_while(Result::ok)
+    }
+
+    fn rapid_withdrawals(
+        &self,
+        customer_id: &CustomerId,
+        account: &Account,
+        transaction: &Transaction,
+    ) -> Option<FraudReason> {
+        let rule = self.fraud_rules.rapid_withdrawals?;
+        if !matches!(transaction.transaction_type, TransactionType::Withdrawal) {
+            return None;
+        }
+
+        let since = transaction.timestamp - Duration::minutes(rule.minutes.into());
+        let counts =
+            |tx: &Transaction| matches!(tx.transaction_type, TransactionType::Withdrawal) && tx.timestamp > since;
+        let mut earlier = account.transactions.iter().filter(|tx| counts(tx)).count();
+        // Under a history cap the window can reach back into the archive
+        if account.archived.as_ref().is_some_and(|archived| archived.before > since) {
+            earlier += self
+                .archived_history(customer_id, account)
+                .take_while(|tx| tx.timestamp > since)
+                .filter(|tx| counts(tx))
+                .count();
+        }
+        let count = u32::try_from(earlier).unwrap_or(u32::MAX).saturating_add(1);
+        (count > rule.count).then_some(FraudReason::RapidWithdrawals { count, minutes: rule.minutes })
+    }
+
+    fn new_payee(&self, customer_id: &CustomerId, account: &Account, transaction: &Transaction) -> Option<FraudReason> {
+        let threshold = self.fraud_rules.new_payee_transfer?;
+        if transaction.amount <= threshold {
+            return None;
+        }
+        let paid_before = |payee: &str| {
+            let pays = |tx: &Transaction| match &tx.transaction_type {
+                TransactionType::Transfer { to_account_id } => to_account_id == payee,
+                TransactionType::ExternalTransfer { external_ref } => external_ref == payee,
+                _ => false,
+            };
+            // The archive is only read for payees the live history has not paid
+            account.transactions.iter().any(pays) || self.archived_history(customer_id, account).any(|tx| pays(&tx))
+        };
+
+        let payee = match &transaction.transaction_type {
+            TransactionType::Transfer { to_account_id } if !paid_before(to_account_id.as_str()) => self
+                .find_customer_by_account_id(to_account_id)
+                .and_then(|recipient| recipient.account.as_ref()?.number)
+                .map_or_else(|| to_account_id.to_string(), |number| number.to_string()),
+            TransactionType::ExternalTransfer { external_ref } if !paid_before(external_ref) => external_ref.clone(),
+            _ => return None,
+        };
+        Some(FraudReason::NewPayeeTransfer { payee, amount: transaction.amount, threshold })
+    }
+}
@@ -0,0 +1,109 @@
+//! History paging - an account's whole history, live and archived
+//!
+//! Demonstrates: A hand-written Iterator that fetches its next batch on
+//! demand, Cow for items that are either borrowed or owned
+//!
+//! An account with a history cap (or archived history) only holds its
+//! recent transactions in memory. `get_transaction_history` walks back
+//! from the newest: first the live transactions, borrowed from the
+//! account, then the archived ones, loaded from the archive a page at a
+//! time and only when the walk gets that far. Taking the last ten
+//! transactions never touches the archive.
+
+use std::borrow::Cow;
+use std::iter::Rev;
+use std::slice;
+use std::vec;
+
+use crate::errors::BankResult;
+use crate::models::{AccountId, CustomerId, Transaction};
+use super::archive::{no_archive, HistoryArchive};
+use super::core::Bank;
+
+/// Archived transactions fetched at a time
+pub const HISTORY_PAGE_SIZE: usize = 100;
+
+/// Every transaction of one account, newest first (see
+/// `Bank::get_transaction_history`)
+///
+/// Yields an error, and then nothing more, if a page cannot be loaded.
+pub struct TransactionHistory<'a> {
+    live: Rev<slice::Iter<'a, Transaction>>,
+    archive: Option<&'a dyn HistoryArchive>,
+    account_id: &'a AccountId,
+
+    /// Archived transactions not fetched yet; the next page ends here
+    unfetched: usize,
+
+    /// The page being walked, newest first
+    page: Rev<vec::IntoIter<Transaction>>,
+
+    /// Pages loaded so far
+    pages: usize,
+}
+
+impl TransactionHistory<'_> {
+    /// How many pages of archived transactions have been loaded so far
+    pub fn pages_fetched(&self) -> usize {
+        self.pages
+    }
+
+    /// Loads the page before the ones already walked
+    fn fetch(&mut self) -> BankResult<()> {
+        let archive = self.archive.ok_or_else(|| no_archive(self.account_id))?;
+        let start = self.unfetched.saturating_sub(HISTORY_PAGE_SIZE);
+        let page = archive.load_page(self.account_id, start, self.unfetched - start)?;
+        // An archive holding fewer than were counted ends the walk
+        self.unfetched = if page.is_empty() { 0 } else { start };
+        self.page = page.into_iter().rev();
+        self.pages += 1;
+        Ok(())
+    }
+}
+
+impl<'a> Iterator for TransactionHistory<'a> {
+    type Item = BankResult<Cow<'a, Transaction>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(transaction) = self.live.next() {
+            return Some(Ok(Cow::Borrowed(transaction)));
+        }
+        loop {
+            if let Some(transaction) = self.page.next() {
+                return Some(Ok(Cow::Owned(transaction)));
+            }
+            if self.unfetched == 0 {
+                return None;
+            }
+            if let Err(e) = self.fetch() {
+                self.unfetched = 0;
+                return Some(Err(e));
+            }
+        }
+    }
+}
+
+impl Bank {
+    /// Every transaction of a customer's account, newest first, archived
+    /// ones included
+    ///
+    /// Archived transactions are loaded a page at a time
+    /// (`HISTORY_PAGE_SIZE`) as the iterator reaches them, so stopping
+    /// early, e.g. with `take`, only loads what was needed.
+    /// `Account::get_transaction_history` gives the live part alone.
+    ///
+    /// # Returns
+    /// * `Err(BankError::CustomerNotFound)` - If there is no such customer
+    /// * `Err(BankError::AccountNotFound)` - If the customer has no account
+    pub fn get_transaction_history(&self, customer_id: &CustomerId) -> BankResult<TransactionHistory<'_>> {
+        let account = self.get_customer(customer_id)?.get_account()?;
+        Ok(TransactionHistory {
+            live: account.transactions.iter().rev(),
+            archive: self.archive.as_deref(),
+            account_id: &account.id,
+            unfetched: account.archived.as_ref().map_or(0, |archived| archived.transactions),
+            page: Vec::new().into_iter().rev(),
+            pages: 0,
+        })
+    }
+}
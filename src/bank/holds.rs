@@ -0,0 +1,108 @@
+//! Holds - setting money aside on an account without posting anything
+//!
+//! Demonstrates: Sweeping expired records with an event per removal, so
+//! replaying the journal removes the same ones
+
+use chrono::{DateTime, Utc};
+
+use crate::errors::{BankError, BankResult};
+use crate::models::{CustomerId, Hold, Money, Transaction};
+use super::core::Bank;
+use super::events::BankEvent;
+
+impl Bank {
+    /// Holds money on a customer's account
+    ///
+    /// The held amount is taken off the available balance, so it cannot be
+    /// withdrawn or transferred; the ledger balance does not change.
+    ///
+    /// # Arguments
+    /// * `reason` - Why the money is held; used as the description if the
+    ///   hold is captured
+    /// * `expires_at` - When the hold lapses by itself; `None` keeps it
+    ///   until it is released or captured
+    pub fn place_hold(
+        &mut self,
+        customer_id: &CustomerId,
+        amount: Money,
+        reason: &str,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> BankResult<Hold> {
+        let hold = self
+            .active_customer(customer_id)?
+            .get_account()?
+            .prepare_hold(amount, reason, expires_at, self.now())?;
+
+        self.emit(BankEvent::HoldPlaced { customer_id: customer_id.clone(), hold: hold.clone() })?;
+        Ok(hold)
+    }
+
+    /// Releases a hold, making its money available again
+    ///
+    /// # Returns
+    /// * `Err(BankError::InvalidInput)` - If there is no such hold
+    pub fn release_hold(&mut self, hold_id: &str) -> BankResult<Hold> {
+        let (customer_id, hold) = self.find_hold(hold_id)?;
+        self.emit(BankEvent::HoldReleased { customer_id, hold_id: hold.id.clone() })?;
+        Ok(hold)
+    }
+
+    /// Withdraws the held amount and removes the hold
+    ///
+    /// # Returns
+    /// * `Ok(Transaction)` - The withdrawal, described by the hold's reason
+    /// * `Err(BankError::InvalidInput)` - If there is no such hold or it
+    ///   has expired
+    pub fn capture_hold(&mut self, hold_id: &str) -> BankResult<Transaction> {
+        let (customer_id, hold) = self.find_hold(hold_id)?;
+        let transaction = self
+            .get_customer(&customer_id)?
+            .get_account()?
+            .prepare_hold_capture(&hold.id, self.now())?;
+
+        self.emit(BankEvent::HoldCaptured { customer_id, hold_id: hold.id, transaction: transaction.clone() })?;
+        Ok(transaction)
+    }
+
+    /// Removes every hold that has expired by `now`
+    ///
+    /// Expired holds already stop counting against the available balance;
+    /// this just clears them off the accounts.
+    ///
+    /// # Returns
+    /// The holds that were removed
+    pub fn release_expired_holds(&mut self, now: DateTime<Utc>) -> BankResult<Vec<Hold>> {
+        let expired: Vec<(CustomerId, Hold)> = self
+            .customers
+            .values()
+            .filter_map(|customer| Some((customer, customer.account.as_ref()?)))
+            .flat_map(|(customer, account)| {
+                account
+                    .holds
+                    .iter()
+                    .filter(|hold| hold.is_expired(now))
+                    .map(|hold| (customer.id.clone(), hold.clone()))
+            })
+            .collect();
+
+        let mut released = Vec::new();
+        for (customer_id, hold) in expired {
+            self.emit(BankEvent::HoldReleased { customer_id, hold_id: hold.id.clone() })?;
+            released.push(hold);
+        }
+        Ok(released)
+    }
+
+    /// Finds a hold by ID; returns its customer's ID and a copy of it
+    fn find_hold(&self, hold_id: &str) -> BankResult<(CustomerId, Hold)> {
+        let hold_id = hold_id.trim();
+        self.customers
+            .values()
+            .filter_map(|customer| Some((customer, customer.account.as_ref()?)))
+            .find_map(|(customer, account)| {
+                let hold = account.holds.iter().find(|hold| hold.id == hold_id)?;
+                Some((customer.id.clone(), hold.clone()))
+            })
+            .ok_or_else(|| BankError::InvalidInput(format!("hold '{}' not found", hold_id)))
+    }
+}
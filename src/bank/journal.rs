@@ -0,0 +1,77 @@
+//! Journal module - buffers events until they reach durable storage
+//!
+//! Demonstrates: Type aliases for backwards compatibility, std::mem::take
+//!
+//! When journaling is enabled, every event applied by an operation is
+//! also pushed into an in-memory buffer. The caller drains the buffer
+//! with `take_journal()` and appends it to durable storage, so a crash
+//! between full saves can be recovered by replaying the entries on top of
+//! the last snapshot.
+//!
+//! Separately, the bank counts the changes made since it was last saved
+//! in full, whether or not they were journaled, so a front end can show
+//! that there is something to save and skip saves that would rewrite the
+//! same data.
+
+use crate::errors::BankResult;
+use super::core::Bank;
+use super::events::BankEvent;
+
+/// A recorded change, as written to the journal file
+///
+/// Journal entries are bank events; the alias keeps the older name working.
+pub type JournalEntry = BankEvent;
+
+impl Bank {
+    /// Starts buffering journal entries for every mutation
+    pub fn enable_journal(&mut self) {
+        if self.journal.is_none() {
+            self.journal = Some(Vec::new());
+        }
+    }
+
+    /// Drains the buffered journal entries
+    ///
+    /// Demonstrates: std::mem::take to move data out of a &mut borrow
+    pub fn take_journal(&mut self) -> Vec<JournalEntry> {
+        self.journal.as_mut().map(std::mem::take).unwrap_or_default()
+    }
+
+    /// Number of changes made since the last full save (see `mark_saved`)
+    pub fn unsaved_changes(&self) -> usize {
+        self.unsaved
+    }
+
+    /// Whether anything changed since the last full save
+    pub fn has_unsaved_changes(&self) -> bool {
+        self.unsaved > 0
+    }
+
+    /// Records that the bank was just saved in full
+    ///
+    /// `Storage::save_bank` only borrows the bank, so whoever saves calls
+    /// this once the save succeeded. Appending to the journal does not
+    /// count as a full save.
+    pub fn mark_saved(&mut self) {
+        self.unsaved = 0;
+    }
+
+    /// Buffers an entry if journaling is enabled
+    pub(crate) fn record(&mut self, entry: JournalEntry) {
+        if let Some(journal) = self.journal.as_mut() {
+            journal.push(entry);
+        }
+    }
+
+    /// Applies a journal entry during replay
+    ///
+    /// Same as `apply_event`: entries already reflected in the state are
+    /// skipped.
+    ///
+    /// # Returns
+    /// * `Ok(true)` - The entry changed the state
+    /// * `Ok(false)` - The entry was already applied
+    pub fn apply_journal_entry(&mut self, entry: JournalEntry) -> BankResult<bool> {
+        self.apply_event(entry)
+    }
+}
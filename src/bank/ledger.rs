@@ -0,0 +1,133 @@
+//! Ledger - every account's transactions as one chronological stream
+//!
+//! Demonstrates: A k-way merge with BinaryHeap and Reverse (a min-heap),
+//! implementing Iterator for a struct that borrows the bank
+//! https://doc.rust-lang.org/std/collections/struct.BinaryHeap.html#min-heap
+//!
+//! Each account keeps its own history. `Bank::all_transactions` merges
+//! them by timestamp while it is iterated, holding only the next
+//! transaction of each account rather than copying every transaction into
+//! one list. Histories are in posting order, which is chronological except
+//! for imported transactions that keep their original dates; an account
+//! whose history is out of order is sorted (by reference) first.
+
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::slice;
+use std::vec;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::models::{AccountId, CustomerId, Transaction};
+use super::core::Bank;
+
+/// A transaction with the account it is on
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct LedgerEntry<'a> {
+    pub customer_id: &'a CustomerId,
+    pub account_id: &'a AccountId,
+    pub transaction: &'a Transaction,
+}
+
+/// One account's history, oldest first
+enum History<'a> {
+    /// Already in order: walked as it is
+    InOrder(slice::Iter<'a, Transaction>),
+    /// Out of order: sorted references
+    Sorted(vec::IntoIter<&'a Transaction>),
+}
+
+impl<'a> Iterator for History<'a> {
+    type Item = &'a Transaction;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            History::InOrder(transactions) => transactions.next(),
+            History::Sorted(transactions) => transactions.next(),
+        }
+    }
+}
+
+/// An account being merged, with its next transaction
+struct Source<'a> {
+    customer_id: &'a CustomerId,
+    account_id: &'a AccountId,
+    history: History<'a>,
+    next: Option<&'a Transaction>,
+}
+
+/// Iterator over every account's transactions, oldest first
+///
+/// Transactions with the same timestamp come out in posting order within
+/// an account and by account ID across accounts.
+pub struct Ledger<'a> {
+    sources: Vec<Source<'a>>,
+    /// (timestamp of the source's next transaction, source index)
+    heads: BinaryHeap<Reverse<(DateTime<Utc>, usize)>>,
+}
+
+impl<'a> Ledger<'a> {
+    /// Only the transactions from `from` (inclusive) to `to` (exclusive);
+    /// `None` leaves that end open
+    pub fn between(
+        self,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+    ) -> impl Iterator<Item = LedgerEntry<'a>> {
+        self.skip_while(move |entry| from.is_some_and(|from| entry.transaction.timestamp < from))
+            .take_while(move |entry| to.is_none_or(|to| entry.transaction.timestamp < to))
+    }
+}
+
+impl<'a> Iterator for Ledger<'a> {
+    type Item = LedgerEntry<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let Reverse((_, index)) = self.heads.pop()?;
+        let source = &mut self.sources[index];
+        let transaction = source.next.take()?;
+
+        source.next = source.history.next();
+        if let Some(next) = source.next {
+            self.heads.push(Reverse((next.timestamp, index)));
+        }
+        Some(LedgerEntry { customer_id: source.customer_id, account_id: source.account_id, transaction })
+    }
+}
+
+impl Bank {
+    /// Every account's transactions merged into one stream, oldest first
+    ///
+    /// Closed accounts and those of deleted customers are included: the
+    /// ledger is the bank's complete record. Nothing is copied up front;
+    /// use `Ledger::between` to limit it to a period.
+    pub fn all_transactions(&self) -> Ledger<'_> {
+        let mut accounts: Vec<_> = self
+            .customers
+            .values()
+            .filter_map(|customer| customer.account.as_ref().map(|account| (customer, account)))
+            .collect();
+        accounts.sort_by(|(_, a), (_, b)| a.id.cmp(&b.id));
+
+        let mut sources = Vec::with_capacity(accounts.len());
+        let mut heads = BinaryHeap::with_capacity(accounts.len());
+        for (customer, account) in accounts {
+            let transactions = &account.transactions;
+            let mut history = if transactions.is_sorted_by_key(|t| t.timestamp) {
+                History::InOrder(transactions.iter())
+            } else {
+                let mut sorted: Vec<&Transaction> = transactions.iter().collect();
+                sorted.sort_by_key(|t| t.timestamp);
+                History::Sorted(sorted.into_iter())
+            };
+            let next = history.next();
+            if let Some(next) = next {
+                heads.push(Reverse((next.timestamp, sources.len())));
+            }
+            sources.push(Source { customer_id: &customer.id, account_id: &account.id, history, next });
+        }
+
+        Ledger { sources, heads }
+    }
+}
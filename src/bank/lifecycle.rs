@@ -0,0 +1,157 @@
+//! Account and customer lifecycle - freezing and closing accounts,
+//! deactivating and deleting customers
+//!
+//! Demonstrates: Guarding state transitions with match on (from, to),
+//! soft deletes
+
+use crate::errors::{BankError, BankResult};
+use crate::models::{AccountStatus, CustomerId, CustomerStatus, Money, Transaction, TransactionType};
+use super::core::Bank;
+use super::events::BankEvent;
+
+impl Bank {
+    /// Freezes an active account so no money can leave it
+    pub fn freeze_account(&mut self, customer_id: &CustomerId) -> BankResult<()> {
+        self.change_status(customer_id, AccountStatus::Frozen)
+    }
+
+    /// Lifts a freeze
+    pub fn unfreeze_account(&mut self, customer_id: &CustomerId) -> BankResult<()> {
+        self.change_status(customer_id, AccountStatus::Active)
+    }
+
+    /// Closes an account for good
+    ///
+    /// A closed account must be empty. Any remaining balance is moved to
+    /// `transfer_to` first; this transfer ignores freezes, limits and
+    /// account-type rules, since it is the bank closing the account.
+    ///
+    /// # Returns
+    /// * `Ok(Money)` - The amount transferred out (zero if already empty)
+    /// * `Err(BankError::InvalidInput)` - If money is left and no
+    ///   `transfer_to` customer was given, or an external transfer is
+    ///   still pending
+    pub fn close_account(&mut self, customer_id: &CustomerId, transfer_to: Option<&CustomerId>) -> BankResult<Money> {
+        let account = self.get_customer(customer_id)?.get_account()?;
+        account.ensure_open()?;
+        account.ensure_nothing_pending()?;
+        let balance = account.balance;
+
+        if balance.is_positive() {
+            let to_customer_id = transfer_to.ok_or_else(|| {
+                BankError::InvalidInput(format!(
+                    "account still holds {}; transfer it out to close",
+                    balance.display()
+                ))
+            })?;
+            if to_customer_id == customer_id {
+                return Err(BankError::InvalidTransfer(
+                    "Cannot transfer to the same account".to_string(),
+                ));
+            }
+
+            let from_account_id = account.id.clone();
+            let to_account = self.active_customer(to_customer_id)?.get_account()?;
+
+            let now = self.now();
+            let debit = Transaction::new_at(
+                TransactionType::Transfer { to_account_id: to_account.id.clone() },
+                balance,
+                Money::ZERO,
+                now,
+            );
+            let mut credit = to_account.prepare_deposit(balance, now)?;
+            credit.transaction_type = TransactionType::TransferIn { from_account_id };
+
+            self.emit(BankEvent::Transferred {
+                from_customer_id: customer_id.clone(),
+                to_customer_id: to_customer_id.clone(),
+                debit,
+                credit,
+            })?;
+        }
+
+        self.emit(BankEvent::AccountStatusChanged {
+            customer_id: customer_id.clone(),
+            status: AccountStatus::Closed,
+        })?;
+        Ok(balance)
+    }
+
+    /// Moves an account between Active and Frozen
+    fn change_status(&mut self, customer_id: &CustomerId, status: AccountStatus) -> BankResult<()> {
+        let account = self.get_customer(customer_id)?.get_account()?;
+
+        match (account.status, status) {
+            (AccountStatus::Closed, _) => return Err(BankError::AccountClosed(account.id.clone())),
+            (current, wanted) if current == wanted => {
+                return Err(BankError::InvalidInput(format!("account is already {}", current)))
+            }
+            _ => {}
+        }
+
+        self.emit(BankEvent::AccountStatusChanged {
+            customer_id: customer_id.clone(),
+            status,
+        })
+    }
+
+    /// Deactivates a customer: they can no longer log in or move money
+    pub fn deactivate_customer(&mut self, customer_id: &CustomerId) -> BankResult<()> {
+        self.change_customer_status(customer_id, CustomerStatus::Deactivated)
+    }
+
+    /// Lets a deactivated customer use the bank again
+    pub fn reactivate_customer(&mut self, customer_id: &CustomerId) -> BankResult<()> {
+        self.change_customer_status(customer_id, CustomerStatus::Active)
+    }
+
+    /// Deletes a customer
+    ///
+    /// This is a soft delete: the customer is flagged rather than removed,
+    /// so account IDs in other customers' transactions still resolve. Their
+    /// account is closed and their standing orders are cancelled, and from
+    /// then on they are reported as not found.
+    ///
+    /// # Returns
+    /// * `Err(BankError::InvalidInput)` - If the account still holds money
+    ///   (transfer it out, or use `close_account`, first) or has a pending
+    ///   external transfer
+    pub fn delete_customer(&mut self, customer_id: &CustomerId) -> BankResult<()> {
+        let customer = self.get_customer(customer_id)?;
+
+        if let Some(account) = &customer.account {
+            account.ensure_nothing_pending()?;
+            if !account.balance.is_zero() {
+                return Err(BankError::InvalidInput(format!(
+                    "customer still holds {}; empty the account before deleting",
+                    account.balance.display()
+                )));
+            }
+            if account.status != AccountStatus::Closed {
+                self.emit(BankEvent::AccountStatusChanged {
+                    customer_id: customer_id.clone(),
+                    status: AccountStatus::Closed,
+                })?;
+            }
+        }
+
+        self.emit(BankEvent::CustomerStatusChanged {
+            customer_id: customer_id.clone(),
+            status: CustomerStatus::Deleted,
+        })
+    }
+
+    /// Moves a customer between Active and Deactivated
+    fn change_customer_status(&mut self, customer_id: &CustomerId, status: CustomerStatus) -> BankResult<()> {
+        let current = self.get_customer(customer_id)?.status;
+        if current == status {
+            return Err(BankError::InvalidInput(format!("customer is already {}", current)));
+        }
+
+        self.emit(BankEvent::CustomerStatusChanged {
+            customer_id: customer_id.clone(),
+            status,
+        })
+    }
+}
@@ -0,0 +1,104 @@
+//! Customer lookup - finding a customer by whatever the user remembers
+//!
+//! Demonstrates: Chaining fallible lookups with Option::or_else, enums
+//! carrying borrowed results
+
+use crate::errors::{BankError, BankResult};
+use crate::models::{AccountId, AccountNumber, Customer, CustomerId};
+use crate::validation;
+use super::core::Bank;
+
+/// Outcome of looking a customer up by the start of an ID
+#[derive(Debug, Clone)]
+pub enum PrefixMatch<'a> {
+    /// Exactly one customer matches
+    Unique(&'a Customer),
+    /// Several customers match, ordered by registration time
+    Ambiguous(Vec<&'a Customer>),
+    /// Nobody matches
+    NotFound,
+}
+
+impl Bank {
+    /// Finds the customer registered with this email
+    ///
+    /// The address is normalized first, so case and surrounding spaces do
+    /// not matter. Deleted customers are not found.
+    pub fn find_customer_by_email(&self, email: &str) -> Option<&Customer> {
+        let email = validation::normalize_email(email);
+        let customer_id = self.email_index.lookup(&self.customers, &email)?;
+        self.customers.get(customer_id)
+    }
+
+    /// Finds the customer an exact reference points to
+    ///
+    /// The reference may be, in order of precedence: a customer ID, an
+    /// account number (`ACC-000123-0`), an email or an account ID. Deleted
+    /// customers are not found.
+    pub fn find_customer(&self, reference: &str) -> Option<&Customer> {
+        let reference = reference.trim();
+        self.get_customer(&CustomerId::from(reference))
+            .ok()
+            .or_else(|| {
+                reference
+                    .parse::<AccountNumber>()
+                    .ok()
+                    .and_then(|number| self.find_customer_by_account_number(number))
+            })
+            .or_else(|| self.find_customer_by_email(reference))
+            .or_else(|| self.find_customer_by_account_id(&AccountId::from(reference)))
+            .filter(|c| self.get_customer(&c.id).is_ok())
+    }
+
+    /// Finds the customers whose customer ID or account ID starts with
+    /// `prefix` (e.g. the first 8 characters shown in listings)
+    ///
+    /// Deleted customers are left out; an empty prefix matches nobody.
+    pub fn resolve_customer_prefix(&self, prefix: &str) -> PrefixMatch<'_> {
+        let prefix = prefix.trim().to_lowercase();
+        if prefix.is_empty() {
+            return PrefixMatch::NotFound;
+        }
+
+        let mut matches: Vec<&Customer> = self
+            .list_customers()
+            .into_iter()
+            .filter(|c| {
+                c.id.as_str().starts_with(&prefix)
+                    || c.account.as_ref().is_some_and(|a| a.id.as_str().starts_with(&prefix))
+            })
+            .collect();
+        matches.sort_by(|a, b| a.registered_at.cmp(&b.registered_at).then_with(|| a.id.cmp(&b.id)));
+
+        match matches.len() {
+            0 => PrefixMatch::NotFound,
+            1 => PrefixMatch::Unique(matches[0]),
+            _ => PrefixMatch::Ambiguous(matches),
+        }
+    }
+
+    /// Turns a customer reference into a customer ID
+    ///
+    /// Tries `find_customer` first, then `resolve_customer_prefix`.
+    ///
+    /// # Returns
+    /// * `Ok(CustomerId)` - The full customer ID
+    /// * `Err(BankError::InvalidInput)` - If a prefix matches several customers
+    /// * `Err(BankError::CustomerNotFound)` - If nothing matches
+    pub fn resolve_customer(&self, reference: &str) -> BankResult<CustomerId> {
+        if let Some(customer) = self.find_customer(reference) {
+            return Ok(customer.id.clone());
+        }
+
+        match self.resolve_customer_prefix(reference) {
+            PrefixMatch::Unique(customer) => Ok(customer.id.clone()),
+            PrefixMatch::NotFound => Err(BankError::CustomerNotFound(reference.trim().into())),
+            PrefixMatch::Ambiguous(matches) => Err(BankError::InvalidInput(format!(
+                "'{}' matches {} customers ({}); type more of the ID",
+                reference.trim(),
+                matches.len(),
+                matches.iter().map(|c| c.id.short()).collect::<Vec<_>>().join(", ")
+            ))),
+        }
+    }
+}
@@ -5,6 +5,75 @@
 
 mod core;
 mod transactions;
+mod schedules;
+mod journal;
+mod events;
+mod auth;
+mod undo;
+mod lifecycle;
+mod profile;
+mod lookup;
+mod sorting;
+mod report;
+mod cash_flow;
+mod analytics;
+mod policies;
+mod fraud;
+mod batch;
+mod onboarding;
+mod seed;
+mod audit;
+mod beneficiaries;
+mod clearing;
+mod authorizations;
+mod holds;
+mod cheques;
+mod cards;
+mod bills;
+mod envelopes;
+mod vault;
+mod bookkeeping;
+mod budgets;
+mod term_deposits;
+mod receipts;
+mod transaction_index;
+mod email_index;
+mod ledger;
+mod alerts;
+mod webhooks;
+mod observers;
+mod pipeline;
+mod outbox;
+mod shared;
+mod archive;
+mod history;
+mod builder;
+#[cfg(feature = "async")]
+pub mod async_api;
 
 // Re-export the Bank struct
 pub use core::Bank;
+pub use schedules::ScheduleRun;
+pub use bills::AutopayRun;
+pub use journal::JournalEntry;
+pub use events::BankEvent;
+pub use undo::UNDO_LIMIT;
+pub use lookup::PrefixMatch;
+pub use sorting::{SortKey, SortOrder};
+pub use transaction_index::CustomerRef;
+pub use ledger::{Ledger, LedgerEntry};
+pub use batch::{BatchMode, Operation};
+pub use onboarding::{CustomerImport, ImportedRow};
+pub use seed::SeedOptions;
+pub use archive::{ArchiveRun, HistoryArchive};
+pub use builder::BankBuilder;
+pub use history::{TransactionHistory, HISTORY_PAGE_SIZE};
+pub use audit::{IntegrityIssue, IntegrityReport};
+pub use report::{AccountActivity, BalanceBucket, BankReport, PeriodVolume};
+pub use analytics::{DormantAccount, LargeTransaction};
+pub use policies::PolicyRun;
+pub use bookkeeping::{IncomeStatement, IncomeStatementLine, TrialBalance, TrialBalanceRow, Unreconciled};
+pub use cash_flow::{CashFlowBucket, CashFlowPeriod, CashFlowReport, CHART_WIDTH};
+pub use shared::{AccountsGuard, BankWriteGuard, SharedBank};
+#[cfg(feature = "async")]
+pub use async_api::AsyncBank;
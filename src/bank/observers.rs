@@ -0,0 +1,60 @@
+//! Observers - calling `BankObserver` hooks
+//!
+//! Demonstrates: Dispatching one enum onto several trait methods
+
+use std::sync::Arc;
+
+use crate::observer::BankObserver;
+use super::core::Bank;
+use super::events::BankEvent;
+
+impl Bank {
+    /// Registers an observer, called after each deposit, withdrawal,
+    /// transfer and registration from now on
+    ///
+    /// Observers are not saved with the bank; register them again after
+    /// loading it.
+    pub fn add_observer(&mut self, observer: Arc<dyn BankObserver>) {
+        self.observers.push(observer);
+    }
+
+    /// Whether observers have a hook for this kind of event
+    pub(crate) fn observable(event: &BankEvent) -> bool {
+        matches!(
+            event,
+            BankEvent::Deposited { .. }
+                | BankEvent::Withdrawn { .. }
+                | BankEvent::AuthorizationCaptured { .. }
+                | BankEvent::HoldCaptured { .. }
+                | BankEvent::BillPaid { .. }
+                | BankEvent::AutopayRan { transaction: Some(_), .. }
+                | BankEvent::Transferred { .. }
+                | BankEvent::CustomerRegistered { .. }
+        )
+    }
+
+    /// Calls the matching hook on every observer for each event
+    pub(crate) fn notify_observers(&self, observed: &[BankEvent]) {
+        for event in observed {
+            for observer in &self.observers {
+                match event {
+                    BankEvent::Deposited { customer_id, transaction } => {
+                        observer.on_deposit(customer_id, transaction)
+                    }
+                    BankEvent::Withdrawn { customer_id, transaction }
+                    | BankEvent::AuthorizationCaptured { customer_id, transaction }
+                    | BankEvent::HoldCaptured { customer_id, transaction, .. }
+                    | BankEvent::BillPaid { customer_id, transaction }
+                    | BankEvent::AutopayRan { customer_id, transaction: Some(transaction), .. } => {
+                        observer.on_withdraw(customer_id, transaction)
+                    }
+                    BankEvent::Transferred { from_customer_id, to_customer_id, debit, credit } => {
+                        observer.on_transfer(from_customer_id, to_customer_id, debit, credit)
+                    }
+                    BankEvent::CustomerRegistered { customer } => observer.on_customer_registered(customer),
+                    _ => {}
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,162 @@
+//! Bulk onboarding - customers and their accounts from a CSV file
+//!
+//! Demonstrates: Generic readers (impl Read), per-row results instead of
+//! failing the whole file, validating a row before changing anything
+//!
+//! The file has a header row with the columns `name, email,
+//! initial_deposit` (the deposit may be left blank for zero). Every valid
+//! row registers a customer and opens a checking account; an invalid row
+//! is reported with its line number and skipped, and never leaves a
+//! customer behind without their account.
+
+use std::fmt;
+use std::io::Read;
+
+use serde::Deserialize;
+
+use crate::errors::{BankError, BankResult, Cause};
+use crate::models::{AccountType, CustomerId, Money, ParseMoneyError};
+use crate::validation;
+use super::core::Bank;
+
+/// Columns every onboarding file must have
+const REQUIRED_COLUMNS: [&str; 2] = ["name", "email"];
+
+/// One CSV row, before validation
+#[derive(Debug, Deserialize)]
+struct OnboardingRow {
+    name: String,
+    email: String,
+    #[serde(default)]
+    initial_deposit: String,
+}
+
+/// What happened to one row of an onboarding file
+#[derive(Debug)]
+pub struct ImportedRow {
+    /// Line in the file (line 1 is the header)
+    pub line: usize,
+    pub name: String,
+    pub email: String,
+    pub initial_deposit: Option<Money>,
+    /// The new customer's ID, or why the row was skipped
+    pub result: BankResult<CustomerId>,
+}
+
+impl fmt::Display for ImportedRow {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.result {
+            Ok(_) => write!(f, "line {}: {} <{}> registered", self.line, self.name, self.email),
+            Err(e) => write!(f, "line {}: {}", self.line, e),
+        }
+    }
+}
+
+/// The per-row report of `Bank::import_customers`
+#[derive(Debug, Default)]
+pub struct CustomerImport {
+    /// One entry per data row, in file order
+    pub rows: Vec<ImportedRow>,
+}
+
+impl CustomerImport {
+    /// Rows that registered a customer
+    pub fn imported(&self) -> usize {
+        self.rows.iter().filter(|row| row.result.is_ok()).count()
+    }
+
+    /// Rows that were skipped
+    pub fn failed(&self) -> usize {
+        self.rows.len() - self.imported()
+    }
+
+    /// Total of the opening deposits of the imported rows
+    pub fn total_deposited(&self) -> Money {
+        self.rows
+            .iter()
+            .filter(|row| row.result.is_ok())
+            .filter_map(|row| row.initial_deposit)
+            .sum()
+    }
+}
+
+impl Bank {
+    /// Registers a customer with a checking account for every valid row
+    /// of a CSV file
+    ///
+    /// Rows are independent: a bad row is reported and the rest are still
+    /// imported. An email already in use (by an existing customer or an
+    /// earlier row) fails that row.
+    ///
+    /// # Returns
+    /// * `Ok(CustomerImport)` - One result per data row
+    /// * `Err(BankError::SerializationError)` - If the header row cannot be
+    ///   read or lacks the `name` or `email` column
+    pub fn import_customers<R: Read>(&mut self, reader: R) -> BankResult<CustomerImport> {
+        let mut reader = csv::ReaderBuilder::new().trim(csv::Trim::All).from_reader(reader);
+        let headers = reader.headers().map_err(|e| BankError::SerializationError(Cause::new(e)))?;
+        if let Some(missing) = REQUIRED_COLUMNS.iter().find(|column| !headers.iter().any(|h| h == **column)) {
+            return Err(BankError::SerializationError(format!("missing column '{}'", missing).into()));
+        }
+
+        let mut import = CustomerImport::default();
+        for (idx, row) in reader.deserialize::<OnboardingRow>().enumerate() {
+            // Line 1 is the header
+            let line = idx + 2;
+            let row = match row {
+                Ok(row) => row,
+                Err(e) => {
+                    let result = Err(BankError::SerializationError(Cause::new(e).context(format!("line {}", line))));
+                    import.rows.push(ImportedRow {
+                        line,
+                        name: String::new(),
+                        email: String::new(),
+                        initial_deposit: None,
+                        result,
+                    });
+                    continue;
+                }
+            };
+
+            let initial_deposit = parse_deposit(&row.initial_deposit);
+            let result = match &initial_deposit {
+                Ok(amount) => self.onboard(&row.name, &row.email, *amount),
+                Err(e) => Err(BankError::InvalidInput(format!("initial_deposit: {}", e))),
+            };
+            import.rows.push(ImportedRow {
+                line,
+                name: row.name,
+                email: row.email,
+                initial_deposit: initial_deposit.ok(),
+                result,
+            });
+        }
+        Ok(import)
+    }
+
+    /// Validates one row, then registers the customer and opens their
+    /// account; returns the customer ID
+    fn onboard(&mut self, name: &str, email: &str, initial_deposit: Money) -> BankResult<CustomerId> {
+        let name = validation::validate_name(name)?;
+        let email = validation::validate_email(email)?;
+        if self.email_in_use(&email, None) {
+            return Err(BankError::CustomerAlreadyExists(email));
+        }
+        if initial_deposit.is_negative() {
+            return Err(BankError::InvalidAmount(initial_deposit));
+        }
+
+        let customer_id = self.register_customer(name, email)?;
+        self.create_account_for_customer(&customer_id, initial_deposit, AccountType::Checking)?;
+        Ok(customer_id)
+    }
+}
+
+/// A blank deposit column means no opening deposit
+fn parse_deposit(amount: &str) -> Result<Money, ParseMoneyError> {
+    if amount.is_empty() {
+        Ok(Money::ZERO)
+    } else {
+        amount.parse()
+    }
+}
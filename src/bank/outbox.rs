@@ -0,0 +1,77 @@
+//! Outbox - what a change tells the outside world, sent once it sticks
+//!
+//! Demonstrates: Collecting side effects as data so they can be held back
+//! and sent later, or dropped
+//!
+//! `emit` fills an `Outbox` for each event: balance alerts and domain
+//! events are worked out before the event is applied (alerts need the old
+//! balance), and the event itself is kept for observers. A deposit,
+//! withdrawal or transfer adds its pipeline's `after` hooks, which send
+//! its alerts. While an all-or-nothing batch is pending, `held` collects
+//! the outboxes of its operations; they are sent if the batch commits and
+//! dropped otherwise.
+
+use crate::events::DomainEvent;
+use crate::middleware::OperationRequest;
+use crate::models::{Alert, Transaction};
+use super::core::Bank;
+use super::events::BankEvent;
+
+/// The alerts, domain events, observer calls and pipeline reports one or
+/// more changes trigger
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Outbox {
+    pub(crate) alerts: Vec<Alert>,
+    pub(crate) events: Vec<DomainEvent>,
+    pub(crate) observed: Vec<BankEvent>,
+
+    /// Operations whose `after` hooks have yet to run, with what they posted
+    pub(crate) reports: Vec<(OperationRequest, Vec<Transaction>)>,
+}
+
+impl Outbox {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.alerts.is_empty() && self.events.is_empty() && self.observed.is_empty() && self.reports.is_empty()
+    }
+
+    fn extend(&mut self, other: Outbox) {
+        self.alerts.extend(other.alerts);
+        self.events.extend(other.events);
+        self.observed.extend(other.observed);
+        self.reports.extend(other.reports);
+    }
+}
+
+impl Bank {
+    /// What `event` will trigger, checked against the state before it is
+    /// applied; `alerts` is false when the pipeline's `Notify` stage sends
+    /// them instead
+    pub(crate) fn outbox_for(&self, event: &BankEvent, alerts: bool) -> Outbox {
+        let holding = self.held.is_some();
+        let mut outbox = Outbox::default();
+        if alerts && (holding || !self.notifiers.is_empty()) {
+            outbox.alerts = self.alerts_for(event);
+        }
+        if holding || !self.subscribers.is_empty() || !self.webhooks.is_empty() {
+            outbox.events = self.domain_events_for(event);
+        }
+        if (holding || !self.observers.is_empty()) && Self::observable(event) {
+            outbox.observed.push(event.clone());
+        }
+        outbox
+    }
+
+    /// Sends everything in `outbox`, or holds it while a batch is pending
+    pub(crate) fn send(&mut self, outbox: Outbox) {
+        if let Some(held) = &mut self.held {
+            held.extend(outbox);
+            return;
+        }
+        self.dispatch_alerts(&outbox.alerts);
+        self.publish(outbox.events);
+        self.notify_observers(&outbox.observed);
+        for (request, posted) in &outbox.reports {
+            self.report_posted(request, posted);
+        }
+    }
+}
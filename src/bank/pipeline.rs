@@ -0,0 +1,100 @@
+//! Pipeline - the middleware deposits, withdrawals and transfers run through
+//!
+//! Demonstrates: Two shared borrows of the bank at once - the pipeline is
+//! a field of the bank its stages read
+//!
+//! See `crate::middleware` for the stages themselves. The bank keeps only
+//! the checks that protect its books; everything else is up to the
+//! pipeline. What the stages decide comes back on the request: the bank
+//! flags the operation if `FraudCheck` set `flag`, and fills in `alerts`
+//! for `Notify` to send.
+
+use crate::errors::{BankError, BankResult};
+use crate::middleware::{OperationRequest, Pipeline};
+use crate::models::{Alert, CustomerId, Money, Transaction, TransactionType};
+use super::core::Bank;
+use super::events::BankEvent;
+use super::outbox::Outbox;
+
+impl Bank {
+    /// The stages deposits, withdrawals and transfers go through
+    pub fn pipeline(&self) -> &Pipeline {
+        &self.pipeline
+    }
+
+    /// The pipeline, to add or remove stages
+    pub fn pipeline_mut(&mut self) -> &mut Pipeline {
+        &mut self.pipeline
+    }
+
+    /// Replaces the pipeline
+    ///
+    /// Pipelines are not saved with the bank; set it again after loading
+    /// the bank.
+    pub fn set_pipeline(&mut self, pipeline: Pipeline) {
+        self.pipeline = pipeline;
+    }
+
+    /// Runs the pipeline's `before` hooks on `request`
+    pub(crate) fn screen(&self, request: &mut OperationRequest) -> BankResult<()> {
+        self.pipeline.before(self, request)
+    }
+
+    /// Runs the pipeline's `after` hooks with what `request` posted
+    pub(crate) fn report_posted(&self, request: &OperationRequest, posted: &[Transaction]) {
+        self.pipeline.after(self, request, posted);
+    }
+
+    /// The transaction charging `fee` after `transaction`, if it is
+    /// positive
+    ///
+    /// # Returns
+    /// * `Err(BankError::InsufficientFunds)` - If the balance after
+    ///   `transaction` does not cover the fee
+    pub(crate) fn prepare_fee(fee: Money, transaction: &Transaction) -> BankResult<Option<Transaction>> {
+        if !fee.is_positive() {
+            return Ok(None);
+        }
+        let available = transaction.balance_after;
+        if available < fee {
+            return Err(BankError::InsufficientFunds { available, requested: fee });
+        }
+        Ok(Some(Transaction::new_at(TransactionType::Fee, fee, available - fee, transaction.timestamp)))
+    }
+
+    /// The alerts `event` triggers for the `Notify` stage to send, if
+    /// there is anyone to send them to
+    pub(crate) fn operation_alerts(&self, event: &BankEvent) -> Vec<Alert> {
+        if self.notifiers.is_empty() {
+            return Vec::new();
+        }
+        self.alerts_for(event)
+    }
+
+    /// Finishes an operation whose own transactions are already posted:
+    /// charges the fee, makes the whole thing undoable and runs the
+    /// `after` hooks (once the batch commits, inside an all-or-nothing
+    /// batch)
+    ///
+    /// # Returns
+    /// Everything the operation posted, the fee last
+    pub(crate) fn post_operation(
+        &mut self,
+        mut request: OperationRequest,
+        mut posted: Vec<(CustomerId, Transaction)>,
+        fee: Option<Transaction>,
+    ) -> BankResult<Vec<Transaction>> {
+        if let Some(fee) = fee {
+            let event = BankEvent::FeeCharged { customer_id: request.customer_id.clone(), transaction: fee.clone() };
+            request.alerts.extend(self.operation_alerts(&event));
+            self.emit_screened(event)?;
+            posted.push((request.customer_id.clone(), fee));
+        }
+        let undoable: Vec<_> = posted.iter().map(|(customer_id, tx)| (customer_id, tx)).collect();
+        self.push_undo(&undoable);
+
+        let posted: Vec<Transaction> = posted.into_iter().map(|(_, tx)| tx).collect();
+        self.send(Outbox { reports: vec![(request, posted.clone())], ..Outbox::default() });
+        Ok(posted)
+    }
+}
@@ -0,0 +1,84 @@
+//! Policies - rules the bank applies to every account from time to time
+//!
+//! Demonstrates: One entry point for periodic housekeeping, recording each
+//! decision as an event so replay never re-evaluates a policy
+//!
+//! `run_policies` is called like `run_due_schedules` and
+//! `process_maturities`: by the CLI before each menu, or by a job. The one
+//! policy so far is dormancy: open accounts without activity for the
+//! policy's number of days are flagged dormant, frozen if the policy says
+//! so, and reported to the notifiers as `AlertKind::Dormant` alerts. An
+//! account is flagged once; it can be flagged again only after a posting
+//! has cleared the flag and it has gone quiet again.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::errors::BankResult;
+use crate::models::{AccountStatus, CustomerId, DormancyPolicy};
+use super::analytics::DormantAccount;
+use super::core::Bank;
+use super::events::BankEvent;
+
+/// What one run of the policies changed
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PolicyRun {
+    /// Accounts flagged dormant by this run, longest inactive first
+    pub dormant: Vec<DormantAccount>,
+
+    /// Customer IDs of those whose accounts were frozen as well
+    pub frozen: Vec<CustomerId>,
+}
+
+impl Bank {
+    /// When accounts become dormant and whether they are frozen
+    pub fn dormancy_policy(&self) -> DormancyPolicy {
+        self.dormancy_policy
+    }
+
+    /// Changes the dormancy policy for runs from now on
+    ///
+    /// Accounts already flagged stay flagged.
+    ///
+    /// # Returns
+    /// * `Err(BankError)` - If the policy fails `DormancyPolicy::validate`
+    pub fn set_dormancy_policy(&mut self, policy: DormancyPolicy) -> BankResult<()> {
+        policy.validate()?;
+        self.emit(BankEvent::DormancyPolicyChanged { policy })
+    }
+
+    /// Applies the bank's policies to every account as of `now`
+    ///
+    /// # Returns
+    /// What changed; nothing when the dormancy policy is off
+    pub fn run_policies(&mut self, now: DateTime<Utc>) -> BankResult<PolicyRun> {
+        let policy = self.dormancy_policy;
+        let mut run = PolicyRun::default();
+        if !policy.is_enabled() {
+            return Ok(run);
+        }
+
+        let due: Vec<DormantAccount> = self
+            .dormant_as_of(now, policy.inactive_days)
+            .into_iter()
+            .filter(|dormant| {
+                let account = self.customers.get(&dormant.customer_id).and_then(|c| c.account.as_ref());
+                account.is_some_and(|account| account.dormant_since.is_none())
+            })
+            .collect();
+        for dormant in due {
+            let account = self.get_customer(&dormant.customer_id)?.get_account()?;
+            let freezes = policy.freeze && account.status == AccountStatus::Active;
+            self.emit(BankEvent::AccountDormant {
+                customer_id: dormant.customer_id.clone(),
+                since: now,
+                freeze: policy.freeze,
+            })?;
+            if freezes {
+                run.frozen.push(dormant.customer_id.clone());
+            }
+            run.dormant.push(dormant);
+        }
+        Ok(run)
+    }
+}
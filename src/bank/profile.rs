@@ -0,0 +1,81 @@
+//! Customer profile editing
+//!
+//! Demonstrates: Partial updates with Option fields, validating before
+//! building the event
+
+use crate::errors::{BankError, BankResult};
+use crate::models::{CustomerId, CustomerUpdate};
+use crate::validation;
+use super::core::Bank;
+use super::events::BankEvent;
+
+impl Bank {
+    /// Changes a customer's name, email, phone number, address or date of
+    /// birth
+    ///
+    /// Every new value goes through the `validation` module (phone numbers
+    /// are stored normalized). The edit is recorded as a `CustomerUpdated`
+    /// event holding the new profile, so the event log keeps every version.
+    ///
+    /// # Returns
+    /// * `Err(BankError::ValidationError)` - If a new value is not valid
+    /// * `Err(BankError::InvalidInput)` - If nothing would change
+    /// * `Err(BankError::CustomerAlreadyExists)` - If another customer
+    ///   already uses the new email
+    pub fn update_customer(&mut self, customer_id: &CustomerId, changes: CustomerUpdate) -> BankResult<()> {
+        let customer = self.get_customer(customer_id)?;
+
+        let name = match changes.name {
+            Some(name) => validation::validate_name(&name)?,
+            None => customer.name.clone(),
+        };
+        let email = match changes.email {
+            Some(email) => validation::validate_email(&email)?,
+            None => customer.email.clone(),
+        };
+        let phone = optional(changes.phone, &customer.phone, validation::normalize_phone)?;
+        let address = optional(changes.address, &customer.address, validation::validate_address)?;
+        let date_of_birth = match changes.date_of_birth {
+            Some(Some(date)) => {
+                validation::validate_date_of_birth(date, self.now().date_naive())?;
+                Some(date)
+            }
+            Some(None) => None,
+            None => customer.date_of_birth,
+        };
+
+        if (&name, &email, &phone, &address, &date_of_birth)
+            == (&customer.name, &customer.email, &customer.phone, &customer.address, &customer.date_of_birth)
+        {
+            return Err(BankError::InvalidInput("nothing to update".to_string()));
+        }
+        if self.email_in_use(&email, Some(customer_id)) {
+            return Err(BankError::CustomerAlreadyExists(email));
+        }
+
+        self.emit(BankEvent::CustomerUpdated {
+            customer_id: customer_id.clone(),
+            name,
+            email,
+            phone,
+            address,
+            date_of_birth,
+        })
+    }
+}
+
+/// Resolves the new value of an optional text field
+///
+/// A blank value removes the field like `Some(None)` does; anything else
+/// must pass `check`.
+fn optional(
+    change: Option<Option<String>>,
+    current: &Option<String>,
+    check: fn(&str) -> BankResult<String>,
+) -> BankResult<Option<String>> {
+    match change {
+        Some(Some(value)) if !value.trim().is_empty() => check(&value).map(Some),
+        Some(_) => Ok(None),
+        None => Ok(current.clone()),
+    }
+}
@@ -0,0 +1,79 @@
+//! Receipts - printable proof of a transaction, reprinted on demand
+//!
+//! Demonstrates: Resolving IDs to names only when a view is built
+//!
+//! Transactions only store the ID of the other account (or merchant);
+//! names and account numbers are looked up as the receipt is built, so a
+//! reprint shows the parties as they are now.
+
+use crate::errors::{BankError, BankResult};
+use crate::models::{Account, AccountId, Customer, Money, Party, Receipt, Transaction, TransactionId, TransactionType};
+use super::core::Bank;
+
+impl Bank {
+    /// Builds the receipt of any transaction, e.g. to print it again
+    ///
+    /// Transactions of deleted customers are found too.
+    ///
+    /// # Errors
+    /// `BankError::TransactionNotFound` if no account has a transaction
+    /// with this ID
+    pub fn get_receipt(&self, transaction_id: &TransactionId) -> BankResult<Receipt> {
+        let (customer, account, transaction) = self
+            .locate_transaction(transaction_id)
+            .ok_or_else(|| BankError::TransactionNotFound(transaction_id.clone()))?;
+        Ok(self.receipt_for(customer, account, transaction))
+    }
+
+    /// The receipt of `transaction`, which is on `customer`'s `account`
+    fn receipt_for(&self, customer: &Customer, account: &Account, transaction: &Transaction) -> Receipt {
+        let holder = account_party(customer, account);
+        let other = match &transaction.transaction_type {
+            TransactionType::Transfer { to_account_id } => Some(self.account_party(to_account_id)),
+            TransactionType::TransferIn { from_account_id } => Some(self.account_party(from_account_id)),
+            TransactionType::ExternalTransfer { external_ref } => {
+                Some(Party { name: None, account: external_ref.clone() })
+            }
+            TransactionType::BillPayment { merchant_id, reference } => Some(Party {
+                name: Some(self.find_merchant(merchant_id).map_or_else(|_| merchant_id.clone(), |m| m.name.clone())),
+                account: reference.clone(),
+            }),
+            _ => None,
+        };
+        let (from, to) = if transaction.transaction_type.is_credit() {
+            (other, Some(holder))
+        } else {
+            (Some(holder), other)
+        };
+
+        Receipt {
+            bank_name: self.name.clone(),
+            transaction_id: transaction.id.clone(),
+            timestamp: transaction.timestamp,
+            transaction_type: transaction.transaction_type.clone(),
+            from,
+            to,
+            amount: transaction.amount,
+            fees: Money::ZERO,
+            balance_after: transaction.balance_after,
+            description: transaction.description.clone(),
+            status: transaction.status,
+        }
+    }
+
+    /// The owner and number of the account with this ID, or just the ID
+    /// if nobody here owns it
+    fn account_party(&self, account_id: &AccountId) -> Party {
+        let owner = self.find_customer_by_account_id(account_id);
+        match owner.and_then(|customer| Some((customer, customer.account.as_ref()?))) {
+            Some((customer, account)) => account_party(customer, account),
+            None => Party { name: None, account: account_id.to_string() },
+        }
+    }
+}
+
+/// `customer` as the holder of `account`
+fn account_party(customer: &Customer, account: &Account) -> Party {
+    let number = account.number.map_or_else(|| account.id.to_string(), |number| number.to_string());
+    Party { name: Some(customer.name.clone()), account: number }
+}
@@ -0,0 +1,324 @@
+//! Bank statistics report - balances, activity and money flows
+//!
+//! Demonstrates: BTreeMap for ordered grouping, sorting and slicing for
+//! medians, Display for multi-line reports
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Utc};
+use serde::Serialize;
+
+use crate::models::{Account, AccountNumber, AccountStatus, CustomerId, Money, Transaction, TransactionType};
+use super::core::Bank;
+
+/// How many accounts `BankReport::busiest_accounts` lists
+pub const BUSIEST_ACCOUNTS: usize = 5;
+
+/// Upper bounds (exclusive, in whole currency units) of the balance buckets;
+/// the last bucket has no upper bound
+const BUCKET_BOUNDS: [i64; 4] = [100, 1_000, 10_000, 100_000];
+
+/// A snapshot of the bank's statistics
+#[derive(Debug, Clone, Serialize)]
+pub struct BankReport {
+    pub bank: String,
+    pub generated_at: DateTime<Utc>,
+
+    /// Customers that are not deleted
+    pub customers: usize,
+    pub customers_with_accounts: usize,
+
+    /// Balance figures over accounts that are not closed
+    pub open_accounts: usize,
+    /// Open accounts flagged dormant by the dormancy policy
+    pub dormant_accounts: usize,
+    pub total_balance: Money,
+    pub average_balance: Money,
+    pub median_balance: Money,
+    pub balance_distribution: Vec<BalanceBucket>,
+
+    /// Transactions processed since the bank was created
+    pub total_transactions: u64,
+    /// Number and amount of transactions per day / per week (weeks start
+    /// on Monday), oldest first; periods without activity are left out
+    pub daily_volume: Vec<PeriodVolume>,
+    pub weekly_volume: Vec<PeriodVolume>,
+
+    /// Accounts with the most transactions, busiest first
+    pub busiest_accounts: Vec<AccountActivity>,
+
+    /// Money that came into the bank (deposits) and left it (withdrawals);
+    /// transfers between customers stay inside the bank and are left out,
+    /// and undoing a deposit or withdrawal takes it back off its figure
+    pub inflow: Money,
+    pub outflow: Money,
+    pub net_flow: Money,
+
+    /// Cash in the bank's vault; `None` while it is not tracked
+    pub vault_cash: Option<Money>,
+}
+
+/// Number of accounts whose balance lies in `[min, max)`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BalanceBucket {
+    pub min: Money,
+    /// `None` for the open-ended top bucket
+    pub max: Option<Money>,
+    pub accounts: usize,
+}
+
+/// Transactions in one day or week
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PeriodVolume {
+    /// First day of the period
+    pub start: NaiveDate,
+    pub transactions: usize,
+    pub amount: Money,
+}
+
+/// How much one account was used
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AccountActivity {
+    pub customer_id: CustomerId,
+    pub name: String,
+    pub account_number: Option<AccountNumber>,
+    pub transactions: usize,
+}
+
+impl Bank {
+    /// Builds a statistics report over the whole bank
+    ///
+    /// Deleted customers are left out of the customer and balance figures,
+    /// but their past transactions still count towards volumes and flows.
+    pub fn report(&self) -> BankReport {
+        let customers = self.list_customers();
+        let customers_with_accounts = customers.iter().filter(|c| c.has_account()).count();
+
+        let open: Vec<&Account> = customers
+            .iter()
+            .filter_map(|c| c.account.as_ref())
+            .filter(|a| a.status != AccountStatus::Closed)
+            .collect();
+        let dormant_accounts = open.iter().filter(|a| a.dormant_since.is_some()).count();
+        let mut balances: Vec<Money> = open.iter().map(|a| a.balance).collect();
+        balances.sort();
+        let total_balance: Money = balances.iter().sum();
+
+        // Every account that ever existed, for activity and flows
+        let accounts: Vec<(&CustomerId, &str, &Account)> = self
+            .customers
+            .values()
+            .filter_map(|c| c.account.as_ref().map(|a| (&c.id, c.name.as_str(), a)))
+            .collect();
+
+        let mut daily: BTreeMap<NaiveDate, PeriodVolume> = BTreeMap::new();
+        let mut weekly: BTreeMap<NaiveDate, PeriodVolume> = BTreeMap::new();
+        let (mut inflow, mut outflow) = (Money::ZERO, Money::ZERO);
+
+        for (_, _, account) in &accounts {
+            for tx in &account.transactions {
+                let day = tx.timestamp.date_naive();
+                let week = day - Duration::days(day.weekday().num_days_from_monday() as i64);
+                for (map, start) in [(&mut daily, day), (&mut weekly, week)] {
+                    let volume = map.entry(start).or_insert(PeriodVolume {
+                        start,
+                        transactions: 0,
+                        amount: Money::ZERO,
+                    });
+                    volume.transactions += 1;
+                    volume.amount += tx.amount;
+                }
+
+                let (into_bank, out_of_bank) = external_flow(account, tx);
+                inflow += into_bank;
+                outflow += out_of_bank;
+            }
+        }
+
+        let mut busiest: Vec<AccountActivity> = accounts
+            .iter()
+            .filter(|(_, _, a)| !a.transactions.is_empty())
+            .map(|(customer_id, name, a)| AccountActivity {
+                customer_id: (*customer_id).clone(),
+                name: name.to_string(),
+                account_number: a.number,
+                transactions: a.transactions.len(),
+            })
+            .collect();
+        busiest.sort_by(|a, b| b.transactions.cmp(&a.transactions).then_with(|| a.name.cmp(&b.name)));
+        busiest.truncate(BUSIEST_ACCOUNTS);
+
+        BankReport {
+            bank: self.name.clone(),
+            generated_at: self.now(),
+            customers: customers.len(),
+            customers_with_accounts,
+            open_accounts: balances.len(),
+            dormant_accounts,
+            total_balance,
+            average_balance: average(total_balance, balances.len()),
+            median_balance: median(&balances),
+            balance_distribution: distribution(&balances),
+            total_transactions: self.total_transactions,
+            daily_volume: daily.into_values().collect(),
+            weekly_volume: weekly.into_values().collect(),
+            busiest_accounts: busiest,
+            inflow,
+            outflow,
+            net_flow: inflow - outflow,
+            vault_cash: self.vault,
+        }
+    }
+}
+
+/// How much a transaction adds to the bank's (inflow, outflow)
+///
+/// A reversal of a deposit or withdrawal takes its amount back off the
+/// same figure; transfers and their reversals stay inside the bank, except
+/// external transfers, which leave it. Fees and bill payments leave the
+/// customers' accounts, so they count as outflow. Money in a term deposit
+/// stays with the bank, so only a payout's difference from the principal
+/// counts: interest as inflow, a penalty larger than the interest as
+/// outflow.
+pub(super) fn external_flow(account: &Account, tx: &Transaction) -> (Money, Money) {
+    match &tx.transaction_type {
+        TransactionType::Deposit => (tx.amount, Money::ZERO),
+        TransactionType::Withdrawal
+        | TransactionType::ExternalTransfer { .. }
+        | TransactionType::Fee
+        | TransactionType::BillPayment { .. } => (Money::ZERO, tx.amount),
+        TransactionType::Reversal { original_id, .. } => {
+            let original = account.transactions.iter().find(|t| &t.id == original_id);
+            match original.map(|t| &t.transaction_type) {
+                Some(TransactionType::Deposit) => (-tx.amount, Money::ZERO),
+                Some(
+                    TransactionType::Withdrawal
+                    | TransactionType::ExternalTransfer { .. }
+                    | TransactionType::BillPayment { .. },
+                ) => (Money::ZERO, -tx.amount),
+                _ => (Money::ZERO, Money::ZERO),
+            }
+        }
+        TransactionType::TermDeposit { deposit_id, credit: true } => {
+            let principal = account.term_deposits.iter().find(|d| &d.id == deposit_id).map(|d| d.principal);
+            let gain = principal.map_or(Money::ZERO, |principal| tx.amount - principal);
+            (gain.max(Money::ZERO), (-gain).max(Money::ZERO))
+        }
+        TransactionType::Transfer { .. } | TransactionType::TransferIn { .. } | TransactionType::TermDeposit { .. } => {
+            (Money::ZERO, Money::ZERO)
+        }
+    }
+}
+
+/// Mean of `count` amounts adding up to `total` (zero for no amounts)
+fn average(total: Money, count: usize) -> Money {
+    match count {
+        0 => Money::ZERO,
+        n => Money::from_cents(total.cents() / n as i64),
+    }
+}
+
+/// Median of sorted amounts (zero for no amounts)
+fn median(sorted: &[Money]) -> Money {
+    match sorted.len() {
+        0 => Money::ZERO,
+        n if n % 2 == 1 => sorted[n / 2],
+        n => average(sorted[n / 2 - 1] + sorted[n / 2], 2),
+    }
+}
+
+/// Counts sorted balances per bucket of `BUCKET_BOUNDS`
+///
+/// Negative balances (which the bank never creates) fall in the first bucket.
+fn distribution(balances: &[Money]) -> Vec<BalanceBucket> {
+    let mut buckets = Vec::with_capacity(BUCKET_BOUNDS.len() + 1);
+    let mut min = Money::ZERO;
+    for bound in BUCKET_BOUNDS.iter().map(|b| Money::from_major(*b)).map(Some).chain([None]) {
+        let accounts = balances
+            .iter()
+            .filter(|b| (buckets.is_empty() || **b >= min) && bound.is_none_or(|max| **b < max))
+            .count();
+        buckets.push(BalanceBucket { min, max: bound, accounts });
+        min = bound.unwrap_or(min);
+    }
+    buckets
+}
+
+// Renders the report as plain text for the CLI
+impl fmt::Display for BankReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "═══════════════════════════════════════════")?;
+        writeln!(f, "         BANK REPORT: {}", self.bank)?;
+        writeln!(f, "═══════════════════════════════════════════")?;
+        writeln!(
+            f,
+            "Customers: {} ({} with an account, {} open)",
+            self.customers, self.customers_with_accounts, self.open_accounts
+        )?;
+        writeln!(f, "Dormant Accounts: {}", self.dormant_accounts)?;
+        writeln!(f, "Total Balance:   {}", self.total_balance.display())?;
+        writeln!(f, "Average Balance: {}", self.average_balance.display())?;
+        writeln!(f, "Median Balance:  {}", self.median_balance.display())?;
+        match self.vault_cash {
+            Some(cash) => writeln!(f, "Vault Cash:      {}", cash.display())?,
+            None => writeln!(f, "Vault Cash:      not tracked")?,
+        }
+
+        writeln!(f, "───────────────────────────────────────────")?;
+        writeln!(f, "Balance Distribution:")?;
+        for bucket in &self.balance_distribution {
+            let range = match bucket.max {
+                Some(max) => format!("{} - {}", bucket.min.display(), max.display()),
+                None => format!("{}+", bucket.min.display()),
+            };
+            writeln!(f, "  {:<24} {}", range, bucket.accounts)?;
+        }
+
+        writeln!(f, "───────────────────────────────────────────")?;
+        writeln!(f, "Transactions: {}", self.total_transactions)?;
+        writeln!(f, "Money In:  {}", self.inflow.display())?;
+        writeln!(f, "Money Out: {}", self.outflow.display())?;
+        writeln!(f, "Net Flow:  {}", self.net_flow.display())?;
+
+        // The text form only shows recent periods; the JSON has them all
+        writeln!(f, "───────────────────────────────────────────")?;
+        writeln!(f, "Daily Volume (last 7 active days):")?;
+        write_volumes(f, &self.daily_volume, 7)?;
+        writeln!(f, "Weekly Volume (last 4 active weeks):")?;
+        write_volumes(f, &self.weekly_volume, 4)?;
+
+        writeln!(f, "───────────────────────────────────────────")?;
+        writeln!(f, "Busiest Accounts:")?;
+        if self.busiest_accounts.is_empty() {
+            writeln!(f, "  No transactions yet.")?;
+        }
+        for (idx, activity) in self.busiest_accounts.iter().enumerate() {
+            let number = activity.account_number.map(|n| n.to_string()).unwrap_or_default();
+            writeln!(
+                f,
+                "  {}. {} {} - {} transaction(s)",
+                idx + 1,
+                activity.name,
+                number,
+                activity.transactions
+            )?;
+        }
+        write!(f, "═══════════════════════════════════════════")
+    }
+}
+
+/// Writes the last `count` periods of a volume listing
+fn write_volumes(f: &mut fmt::Formatter, volumes: &[PeriodVolume], count: usize) -> fmt::Result {
+    if volumes.is_empty() {
+        writeln!(f, "  No transactions yet.")?;
+    }
+    for volume in &volumes[volumes.len().saturating_sub(count)..] {
+        writeln!(
+            f,
+            "  {}  {:>4} transaction(s)  {}",
+            volume.start, volume.transactions, volume.amount.display()
+        )?;
+    }
+    Ok(())
+}
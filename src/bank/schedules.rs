@@ -0,0 +1,121 @@
+//! Scheduled transaction operations
+//!
+//! Demonstrates: Retain/filter on collections, looping until a condition holds
+
+use chrono::{DateTime, Utc};
+
+use crate::errors::{BankError, BankResult};
+use crate::models::{CustomerId, Frequency, Money, ScheduledTransaction};
+use super::core::Bank;
+
+/// Outcome of a single scheduled transfer execution
+#[derive(Debug)]
+pub struct ScheduleRun {
+    /// The schedule that ran
+    pub schedule_id: String,
+
+    /// The occurrence date that was processed
+    pub run_at: DateTime<Utc>,
+
+    /// Whether the underlying transfer succeeded
+    pub result: BankResult<()>,
+}
+
+impl Bank {
+    /// Creates a standing order between two customers
+    ///
+    /// # Arguments
+    /// * `from_customer_id` - Customer to debit
+    /// * `to_customer_id` - Customer to credit
+    /// * `amount` - Amount per run (must be positive)
+    /// * `frequency` - How often to repeat
+    /// * `first_run` - When the first transfer is due
+    ///
+    /// # Returns
+    /// * `Ok(String)` - The schedule ID
+    pub fn create_schedule(
+        &mut self,
+        from_customer_id: &CustomerId,
+        to_customer_id: &CustomerId,
+        amount: Money,
+        frequency: Frequency,
+        first_run: DateTime<Utc>,
+    ) -> BankResult<String> {
+        if !amount.is_positive() {
+            return Err(BankError::InvalidAmount(amount));
+        }
+        self.active_customer(from_customer_id)?;
+        self.active_customer(to_customer_id)?;
+
+        let schedule = ScheduledTransaction::new(
+            from_customer_id.clone(),
+            to_customer_id.clone(),
+            amount,
+            frequency,
+            first_run,
+        );
+        let schedule_id = schedule.id.clone();
+        self.schedules.push(schedule);
+        // Schedules are not journaled, but they still need saving
+        self.unsaved += 1;
+
+        Ok(schedule_id)
+    }
+
+    /// Lists all active schedules, soonest first
+    pub fn list_schedules(&self) -> Vec<&ScheduledTransaction> {
+        let mut schedules: Vec<_> = self.schedules.iter().collect();
+        schedules.sort_by_key(|s| s.next_run);
+        schedules
+    }
+
+    /// Cancels a schedule so it never runs again
+    pub fn cancel_schedule(&mut self, schedule_id: &str) -> BankResult<()> {
+        let before = self.schedules.len();
+        self.schedules.retain(|s| s.id != schedule_id);
+
+        if self.schedules.len() == before {
+            return Err(BankError::ScheduleNotFound(schedule_id.to_string()));
+        }
+        self.unsaved += 1;
+        Ok(())
+    }
+
+    /// Executes every schedule occurrence due at or before `now`
+    ///
+    /// Missed occurrences are caught up one by one. A failed transfer
+    /// (e.g. insufficient funds) is reported and skipped, and the schedule
+    /// moves on to its next date.
+    ///
+    /// # Returns
+    /// One `ScheduleRun` per processed occurrence
+    pub fn run_due_schedules(&mut self, now: DateTime<Utc>) -> Vec<ScheduleRun> {
+        let mut runs = Vec::new();
+
+        for idx in 0..self.schedules.len() {
+            while self.schedules[idx].is_due(now) {
+                let schedule = self.schedules[idx].clone();
+                let result = self.transfer(
+                    &schedule.from_customer_id,
+                    &schedule.to_customer_id,
+                    schedule.amount,
+                );
+
+                let entry = &mut self.schedules[idx];
+                if result.is_ok() {
+                    entry.run_count += 1;
+                }
+                entry.advance();
+                self.unsaved += 1;
+
+                runs.push(ScheduleRun {
+                    schedule_id: schedule.id,
+                    run_at: schedule.next_run,
+                    result,
+                });
+            }
+        }
+
+        runs
+    }
+}
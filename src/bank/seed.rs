@@ -0,0 +1,270 @@
+//! Demo data - fake customers with months of believable activity
+//!
+//! Demonstrates: Seedable random number generators for reproducible
+//! output, simulating day by day while tracking each customer's balance
+//! https://docs.rs/rand/0.8/rand/
+//!
+//! Histories are built in memory and posted with `import_transactions`,
+//! so every transaction keeps its simulated timestamp and the statistics
+//! screens show realistic daily and weekly volumes.
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Utc};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+
+use crate::errors::{BankError, BankResult};
+use crate::models::{Account, AccountId, AccountType, CustomerId, CustomerUpdate, Money, Transaction, TransactionType};
+use super::core::Bank;
+use super::events::BankEvent;
+
+const FIRST_NAMES: [&str; 20] = [
+    "Alice", "Bob", "Carmen", "Dev", "Elena", "Farid", "Grace", "Hiro", "Ines", "Jamal",
+    "Kofi", "Lena", "Mateo", "Nadia", "Omar", "Priya", "Quinn", "Rosa", "Sven", "Tara",
+];
+
+const LAST_NAMES: [&str; 16] = [
+    "Anderson", "Becker", "Chen", "Diaz", "Eriksen", "Fischer", "Gupta", "Hughes",
+    "Ivanova", "Jensen", "Kim", "Lopez", "Moreau", "Nakamura", "Okafor", "Patel",
+];
+
+/// Everyday spending: category, description and amount range in cents
+const PURCHASES: [(&str, &str, i64, i64); 6] = [
+    ("groceries", "Supermarket", 1_500, 12_000),
+    ("dining", "Restaurant", 1_200, 8_000),
+    ("transport", "Fuel", 2_000, 7_000),
+    ("entertainment", "Cinema", 1_000, 4_000),
+    ("shopping", "Online order", 1_500, 20_000),
+    ("utilities", "Phone bill", 3_000, 9_000),
+];
+
+/// Descriptions for transfers between customers
+const TRANSFER_MEMOS: [&str; 4] = ["Dinner split", "Rent share", "Gift", "Concert tickets"];
+
+/// What to generate with `Bank::seed`
+///
+/// ```
+/// use rust_banking_system::bank::SeedOptions;
+/// use rust_banking_system::Bank;
+///
+/// let mut bank = Bank::new("Demo Bank".to_string());
+/// let ids = bank.seed(SeedOptions::new(5).rng_seed(42)).unwrap();
+/// assert_eq!(ids.len(), 5);
+/// assert!(bank.report().total_transactions > 5);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SeedOptions {
+    customers: usize,
+    from: NaiveDate,
+    to: NaiveDate,
+    rng_seed: Option<u64>,
+}
+
+impl SeedOptions {
+    /// `customers` customers with activity over the last 90 days
+    pub fn new(customers: usize) -> Self {
+        let today = Utc::now().date_naive();
+        Self { customers, from: today - Duration::days(90), to: today, rng_seed: None }
+    }
+
+    /// First simulated day; accounts are opened that morning
+    pub fn from(mut self, from: NaiveDate) -> Self {
+        self.from = from;
+        self
+    }
+
+    /// Last simulated day (inclusive)
+    pub fn to(mut self, to: NaiveDate) -> Self {
+        self.to = to;
+        self
+    }
+
+    /// Makes the generated data the same on every run
+    pub fn rng_seed(mut self, seed: u64) -> Self {
+        self.rng_seed = Some(seed);
+        self
+    }
+}
+
+/// A seeded customer while their history is being simulated
+struct Member {
+    customer_id: CustomerId,
+    account_id: AccountId,
+    savings: bool,
+    balance: Money,
+    salary: Money,
+    payday: u32,
+    history: Vec<Transaction>,
+}
+
+impl Member {
+    fn post(&mut self, transaction_type: TransactionType, amount: Money, at: DateTime<Utc>) -> &mut Transaction {
+        match transaction_type {
+            TransactionType::Deposit | TransactionType::TransferIn { .. } => self.balance += amount,
+            _ => self.balance += -amount,
+        }
+        let mut transaction = Transaction::new(transaction_type, amount, self.balance);
+        transaction.timestamp = at;
+        // Salaries and purchases pass through the vault like cash
+        transaction.cash = matches!(transaction.transaction_type, TransactionType::Deposit | TransactionType::Withdrawal);
+        self.history.push(transaction);
+        self.history.last_mut().unwrap()
+    }
+}
+
+impl Bank {
+    /// Registers fake customers and fills their accounts with history
+    ///
+    /// Every customer gets an account opened on the first day with an
+    /// opening deposit, a monthly salary, everyday card spending filed
+    /// under categories, and the odd transfer to another seeded customer.
+    /// About a quarter of the accounts are savings accounts that only
+    /// receive money. No balance ever goes negative.
+    ///
+    /// # Returns
+    /// * `Ok(Vec<CustomerId>)` - The new customers' IDs
+    /// * `Err(BankError::InvalidInput)` - If the date range is empty
+    pub fn seed(&mut self, options: SeedOptions) -> BankResult<Vec<CustomerId>> {
+        if options.from > options.to {
+            return Err(BankError::InvalidInput("seed start date is after its end date".to_string()));
+        }
+        let mut rng = match options.rng_seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+        let opened = DayClock::new(options.from, 0).now;
+
+        let mut members = Vec::with_capacity(options.customers);
+        for _ in 0..options.customers {
+            members.push(self.seed_customer(&mut rng, opened)?);
+        }
+
+        let mut day = options.from;
+        while day <= options.to {
+            let mut clock = DayClock::new(day, members.len());
+            for i in 0..members.len() {
+                simulate_day(&mut rng, &mut clock, &mut members, i, day);
+            }
+            day += Duration::days(1);
+        }
+
+        let mut ids = Vec::with_capacity(members.len());
+        for member in members {
+            self.import_transactions(&member.customer_id, member.history)?;
+            ids.push(member.customer_id);
+        }
+        Ok(ids)
+    }
+
+    /// Registers one fake customer and opens their account at `opened`
+    fn seed_customer(&mut self, rng: &mut StdRng, opened: DateTime<Utc>) -> BankResult<Member> {
+        let first = FIRST_NAMES.choose(rng).unwrap();
+        let last = LAST_NAMES.choose(rng).unwrap();
+
+        let mut suffix = self.customers.len();
+        let email = loop {
+            let email = format!("{}.{}{}@example.com", first, last, suffix).to_lowercase();
+            if self.find_customer_by_email(&email).is_none() {
+                break email;
+            }
+            suffix += 1;
+        };
+        let customer_id = self.register_customer(format!("{} {}", first, last), email)?;
+        self.update_customer(
+            &customer_id,
+            CustomerUpdate {
+                phone: Some(Some(format!("555-{:04}", rng.gen_range(0..10_000)))),
+                ..CustomerUpdate::default()
+            },
+        )?;
+
+        let savings = rng.gen_bool(0.25);
+        let account_type = if savings { AccountType::Savings } else { AccountType::Checking };
+        let mut account = Account::new(Money::ZERO, account_type)?;
+        account.created_at = opened;
+        account.number = Some(self.next_account_number());
+        let account_id = account.id.clone();
+        self.emit(BankEvent::AccountOpened { customer_id: customer_id.clone(), account })?;
+
+        let mut member = Member {
+            customer_id,
+            account_id,
+            savings,
+            balance: Money::ZERO,
+            salary: Money::from_major(rng.gen_range(2_000..6_000)),
+            payday: *[1, 15, 28].choose(rng).unwrap(),
+            history: Vec::new(),
+        };
+        let opening = Money::from_major(rng.gen_range(100..5_000));
+        member.post(TransactionType::Deposit, opening, opened).description = Some("Opening deposit".to_string());
+        Ok(member)
+    }
+}
+
+/// Simulates one day of member `i`'s activity
+fn simulate_day(rng: &mut StdRng, clock: &mut DayClock, members: &mut [Member], i: usize, day: NaiveDate) {
+    if day.day() == members[i].payday {
+        let (salary, savings) = (members[i].salary, members[i].savings);
+        let (amount, memo) = if savings {
+            (Money::from_cents(salary.cents() / 10), "Monthly savings")
+        } else {
+            (salary, "Salary")
+        };
+        let at = clock.tick(rng);
+        members[i].post(TransactionType::Deposit, amount, at).description = Some(memo.to_string());
+    }
+
+    // Savings accounts only receive money
+    if members[i].savings {
+        return;
+    }
+
+    if rng.gen_bool(0.4) {
+        let (category, memo, min, max) = *PURCHASES.choose(rng).unwrap();
+        let amount = Money::from_cents(rng.gen_range(min..max));
+        if amount <= members[i].balance {
+            let at = clock.tick(rng);
+            let tx = members[i].post(TransactionType::Withdrawal, amount, at);
+            tx.category = Some(category.to_string());
+            tx.description = Some(memo.to_string());
+        }
+    }
+
+    if members.len() > 1 && rng.gen_bool(0.05) {
+        let mut j = rng.gen_range(0..members.len() - 1);
+        if j >= i {
+            j += 1;
+        }
+        let amount = Money::from_major(rng.gen_range(10..300));
+        if amount <= members[i].balance {
+            let at = clock.tick(rng);
+            let memo = TRANSFER_MEMOS.choose(rng).unwrap().to_string();
+            let (to_account_id, from_account_id) = (members[j].account_id.clone(), members[i].account_id.clone());
+
+            members[i].post(TransactionType::Transfer { to_account_id }, amount, at).description = Some(memo.clone());
+            members[j].post(TransactionType::TransferIn { from_account_id }, amount, at).description = Some(memo);
+        }
+    }
+}
+
+/// Hands out increasing times during one day's business hours, so every
+/// history is in chronological order
+struct DayClock {
+    now: DateTime<Utc>,
+    /// Longest gap between two events, in seconds
+    step: i64,
+}
+
+impl DayClock {
+    /// Starts at 08:00, leaving room for three events per member by 20:00
+    fn new(day: NaiveDate, members: usize) -> Self {
+        let now = day.and_hms_opt(8, 0, 0).unwrap().and_utc();
+        let step = (12 * 3600 / (3 * members as i64 + 1)).max(1);
+        Self { now, step }
+    }
+
+    fn tick(&mut self, rng: &mut StdRng) -> DateTime<Utc> {
+        self.now += Duration::seconds(rng.gen_range(1..=self.step));
+        self.now
+    }
+}
@@ -0,0 +1,303 @@
+//! Shared bank handle - one bank used from many threads
+//!
+//! Demonstrates: Arc<RwLock<T>> for many readers and one writer, a lock
+//! table (Mutex + Condvar) for per-account locks, and keeping critical
+//! sections short by copying data out before slow work
+
+use std::collections::HashSet;
+use std::ops::{Deref, DerefMut};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard, TryLockError};
+
+use chrono::{DateTime, Utc};
+
+use crate::errors::BankResult;
+use crate::models::{CustomerId, Money, Statement, Transaction, TransactionDetails};
+use super::core::Bank;
+use super::report::BankReport;
+use super::transactions::OperationPlan;
+
+/// A cheaply cloneable, `Send + Sync` handle to a bank
+///
+/// Reads (statements, reports, lookups) share the lock, so they run side
+/// by side. Deposits, withdrawals and transfers lock only the accounts
+/// they touch: they are checked under the read lock, next to readers and
+/// to operations on other accounts, and take the write lock just long
+/// enough to apply the result. Two of them on the same account wait for
+/// each other; `write` waits for all of them, since it may change any
+/// account. The methods that return data hand back owned copies, so slow
+/// work such as rendering or writing a file happens with the lock
+/// released.
+///
+/// ```
+/// use std::thread;
+/// use rust_banking_system::{AccountType, Bank, Money, SharedBank};
+///
+/// let bank = SharedBank::new(Bank::new("Demo Bank".to_string()));
+/// let id = {
+///     let mut bank = bank.write();
+///     let id = bank.register_customer("Alice".to_string(), "alice@example.com".to_string()).unwrap();
+///     bank.create_account_for_customer(&id, Money::ZERO, AccountType::Checking).unwrap();
+///     id
+/// };
+///
+/// let handles: Vec<_> = (0..4)
+///     .map(|_| {
+///         let (bank, id) = (bank.clone(), id.clone());
+///         thread::spawn(move || bank.deposit(&id, Money::from_major(10)).unwrap())
+///     })
+///     .collect();
+/// for handle in handles {
+///     handle.join().unwrap();
+/// }
+///
+/// let balance = bank.read().get_customer(&id).unwrap().get_account().unwrap().balance;
+/// assert_eq!(balance, Money::from_major(40));
+/// ```
+#[derive(Debug, Clone)]
+pub struct SharedBank {
+    inner: Arc<RwLock<Bank>>,
+    accounts: Arc<AccountLocks>,
+}
+
+impl SharedBank {
+    /// Wraps a bank for sharing
+    pub fn new(bank: Bank) -> Self {
+        Self { inner: Arc::new(RwLock::new(bank)), accounts: Arc::default() }
+    }
+
+    /// Locks the bank for reading; other readers are not blocked
+    ///
+    /// A thread that panicked while holding the write lock does not make
+    /// the bank unusable: the lock is taken anyway and the poisoning
+    /// cleared (see `write`).
+    pub fn read(&self) -> RwLockReadGuard<'_, Bank> {
+        self.inner.read().unwrap_or_else(|poisoned| {
+            self.inner.clear_poison();
+            poisoned.into_inner()
+        })
+    }
+
+    /// Locks the bank for reading if no writer holds it, without waiting
+    ///
+    /// # Returns
+    /// `None` if a change is in progress
+    pub fn try_read(&self) -> Option<RwLockReadGuard<'_, Bank>> {
+        match self.inner.try_read() {
+            Ok(bank) => Some(bank),
+            Err(TryLockError::Poisoned(poisoned)) => {
+                self.inner.clear_poison();
+                Some(poisoned.into_inner())
+            }
+            Err(TryLockError::WouldBlock) => None,
+        }
+    }
+
+    /// Locks the bank for changes; waits for every other reader and writer
+    /// and for the operations holding account locks
+    ///
+    /// If a thread panicked while holding the write lock, the bank is
+    /// handed out as that thread left it and the poisoning is cleared, so
+    /// one failed operation does not lock everyone out for good.
+    pub fn write(&self) -> BankWriteGuard<'_> {
+        let accounts = self.accounts.lock_all();
+        BankWriteGuard { bank: self.write_lock(), _accounts: accounts }
+    }
+
+    /// Locks the given customers' accounts until the guard is dropped
+    ///
+    /// Deposits, withdrawals and transfers on those accounts, and `write`,
+    /// wait meanwhile; everything else carries on. Use it to read an
+    /// account and act on what was read without it changing in between.
+    /// The thread holding the guard must not itself call those methods
+    /// for the same accounts, or `write`, until it drops the guard.
+    pub fn lock_accounts(&self, customer_ids: &[&CustomerId]) -> AccountsGuard<'_> {
+        self.accounts.lock(customer_ids)
+    }
+
+    /// Deposits money, locking only the customer's account
+    pub fn deposit(&self, customer_id: &CustomerId, amount: Money) -> BankResult<Money> {
+        let posted =
+            self.run(&[customer_id], |bank| bank.plan_deposit(customer_id, amount, TransactionDetails::new()))?;
+        Ok(posted.last().map(|tx| tx.balance_after).unwrap_or_default())
+    }
+
+    /// Withdraws money, locking only the customer's account
+    pub fn withdraw(&self, customer_id: &CustomerId, amount: Money) -> BankResult<Money> {
+        let posted =
+            self.run(&[customer_id], |bank| bank.plan_withdrawal(customer_id, amount, TransactionDetails::new()))?;
+        Ok(posted.last().map(|tx| tx.balance_after).unwrap_or_default())
+    }
+
+    /// Transfers money, locking only the two accounts
+    ///
+    /// Both sides change under the same write lock, so no reader ever sees
+    /// the money in neither account or in both.
+    pub fn transfer(
+        &self,
+        from_customer_id: &CustomerId,
+        to_customer_id: &CustomerId,
+        amount: Money,
+    ) -> BankResult<()> {
+        self.transfer_with(from_customer_id, to_customer_id, amount, TransactionDetails::new())
+    }
+
+    /// Transfers money with a description and tags
+    pub fn transfer_with(
+        &self,
+        from_customer_id: &CustomerId,
+        to_customer_id: &CustomerId,
+        amount: Money,
+        details: TransactionDetails,
+    ) -> BankResult<()> {
+        self.run(&[from_customer_id, to_customer_id], |bank| {
+            bank.plan_transfer(from_customer_id, to_customer_id, amount, details)
+        })?;
+        Ok(())
+    }
+
+    /// Builds a customer's statement under the read lock
+    ///
+    /// The statement owns its transactions, so it can be rendered after
+    /// the lock is released without holding up deposits.
+    pub fn statement(&self, customer_id: &CustomerId, from: DateTime<Utc>, to: DateTime<Utc>) -> BankResult<Statement> {
+        self.read().statement(customer_id, from, to)
+    }
+
+    /// Builds the statistics report under the read lock
+    pub fn report(&self) -> BankReport {
+        self.read().report()
+    }
+}
+
+impl SharedBank {
+    /// Plans an operation under the read lock and carries it out under
+    /// the write lock, holding the accounts it touches throughout
+    fn run(
+        &self,
+        customer_ids: &[&CustomerId],
+        plan: impl FnOnce(&Bank) -> BankResult<OperationPlan>,
+    ) -> BankResult<Vec<Transaction>> {
+        let _accounts = self.accounts.lock(customer_ids);
+        let plan = plan(&self.read())?;
+        self.write_lock().carry_out(plan)
+    }
+
+    /// The write lock alone, without waiting for the account locks
+    fn write_lock(&self) -> RwLockWriteGuard<'_, Bank> {
+        self.inner.write().unwrap_or_else(|poisoned| {
+            self.inner.clear_poison();
+            poisoned.into_inner()
+        })
+    }
+}
+
+impl From<Bank> for SharedBank {
+    fn from(bank: Bank) -> Self {
+        Self::new(bank)
+    }
+}
+
+/// The bank locked for changes, from `SharedBank::write`
+#[derive(Debug)]
+pub struct BankWriteGuard<'a> {
+    // Declared first so the bank is unlocked before the accounts are
+    bank: RwLockWriteGuard<'a, Bank>,
+    _accounts: AllAccountsGuard<'a>,
+}
+
+impl Deref for BankWriteGuard<'_> {
+    type Target = Bank;
+
+    fn deref(&self) -> &Bank {
+        &self.bank
+    }
+}
+
+impl DerefMut for BankWriteGuard<'_> {
+    fn deref_mut(&mut self) -> &mut Bank {
+        &mut self.bank
+    }
+}
+
+/// The accounts in use, and whether a writer has the whole bank
+///
+/// A lock table rather than one lock per account: customers come and go,
+/// and taking every account an operation needs at once, or waiting, means
+/// two transfers in opposite directions cannot deadlock.
+#[derive(Debug, Default)]
+struct AccountLocks {
+    state: Mutex<LockState>,
+    released: Condvar,
+}
+
+#[derive(Debug, Default)]
+struct LockState {
+    busy: HashSet<CustomerId>,
+    /// A writer holds, or is waiting for, every account
+    exclusive: bool,
+}
+
+impl AccountLocks {
+    fn state(&self) -> MutexGuard<'_, LockState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn wait<'a>(&self, state: MutexGuard<'a, LockState>) -> MutexGuard<'a, LockState> {
+        self.released.wait(state).unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Waits until none of `customer_ids` is in use and no writer is
+    /// waiting, then marks them in use
+    fn lock(&self, customer_ids: &[&CustomerId]) -> AccountsGuard<'_> {
+        let mut state = self.state();
+        while state.exclusive || customer_ids.iter().any(|id| state.busy.contains(*id)) {
+            state = self.wait(state);
+        }
+        let customer_ids: Vec<CustomerId> = customer_ids.iter().map(|id| (*id).clone()).collect();
+        state.busy.extend(customer_ids.iter().cloned());
+        AccountsGuard { locks: self, customer_ids }
+    }
+
+    /// Stops new account locks, then waits for the held ones to go
+    fn lock_all(&self) -> AllAccountsGuard<'_> {
+        let mut state = self.state();
+        while state.exclusive {
+            state = self.wait(state);
+        }
+        state.exclusive = true;
+        while !state.busy.is_empty() {
+            state = self.wait(state);
+        }
+        AllAccountsGuard { locks: self }
+    }
+}
+
+/// Accounts locked by `SharedBank::lock_accounts`; unlocked when dropped
+#[derive(Debug)]
+pub struct AccountsGuard<'a> {
+    locks: &'a AccountLocks,
+    customer_ids: Vec<CustomerId>,
+}
+
+impl Drop for AccountsGuard<'_> {
+    fn drop(&mut self) {
+        let mut state = self.locks.state();
+        for id in &self.customer_ids {
+            state.busy.remove(id);
+        }
+        self.locks.released.notify_all();
+    }
+}
+
+/// Every account, held by a writer
+#[derive(Debug)]
+struct AllAccountsGuard<'a> {
+    locks: &'a AccountLocks,
+}
+
+impl Drop for AllAccountsGuard<'_> {
+    fn drop(&mut self) {
+        self.locks.state().exclusive = false;
+        self.locks.released.notify_all();
+    }
+}
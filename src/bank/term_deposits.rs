@@ -0,0 +1,147 @@
+//! Term deposits - money locked away at a fixed rate, paid back at maturity
+//!
+//! Demonstrates: Computing a payout once, when it is made, and recording
+//! it in the event so replay never recalculates interest
+//!
+//! Opening a deposit posts a debit on the customer's account that passes
+//! the usual withdrawal checks. `process_maturities` pays back every
+//! deposit whose term is over; `withdraw_term_deposit` pays one back early,
+//! less the early-withdrawal penalty.
+
+use chrono::{DateTime, Utc};
+
+use crate::errors::{BankError, BankResult};
+use crate::models::{CustomerId, Money, TermDeposit, TermDepositStatus, Transaction, TransactionType};
+use crate::validation;
+use super::core::Bank;
+use super::events::BankEvent;
+
+impl Bank {
+    /// Moves money from a customer's account into a new term deposit
+    ///
+    /// # Arguments
+    /// * `rate_bps` - Yearly interest rate in basis points (450 = 4.50%)
+    /// * `term_months` - How long the money is locked away
+    ///
+    /// # Returns
+    /// * `Ok(TermDeposit)` - The new deposit
+    /// * `Err(BankError::ValidationError)` - If the rate or term is out of
+    ///   range
+    /// * `Err(BankError)` - If the account could not withdraw `amount`
+    pub fn open_term_deposit(
+        &mut self,
+        customer_id: &CustomerId,
+        amount: Money,
+        rate_bps: u32,
+        term_months: u32,
+    ) -> BankResult<TermDeposit> {
+        let rate_bps = validation::validate_rate_bps(rate_bps)?;
+        let term_months = validation::validate_term_months(term_months)?;
+        let now = self.now();
+        let account = self.active_customer(customer_id)?.get_account()?;
+        let mut transaction = account.prepare_withdrawal(amount, now)?;
+
+        let deposit = TermDeposit::new_at(amount, rate_bps, term_months, now)?;
+        transaction.transaction_type = TransactionType::TermDeposit { deposit_id: deposit.id.clone(), credit: false };
+
+        self.emit(BankEvent::TermDepositOpened {
+            customer_id: customer_id.clone(),
+            deposit: deposit.clone(),
+            transaction,
+        })?;
+        Ok(deposit)
+    }
+
+    /// Pays an active term deposit back to the account now
+    ///
+    /// Before maturity the payout is the principal plus the interest
+    /// earned so far, less the early-withdrawal penalty.
+    ///
+    /// # Returns
+    /// * `Ok(Transaction)` - The payout
+    /// * `Err(BankError::TermDepositNotFound)` - If the customer has no
+    ///   such deposit
+    /// * `Err(BankError::InvalidInput)` - If it was already paid back
+    pub fn withdraw_term_deposit(&mut self, customer_id: &CustomerId, deposit_id: &str) -> BankResult<Transaction> {
+        let deposit_id = deposit_id.trim();
+        let deposit = self
+            .active_customer(customer_id)?
+            .get_account()?
+            .term_deposits
+            .iter()
+            .find(|d| d.id == deposit_id)
+            .cloned()
+            .ok_or_else(|| BankError::TermDepositNotFound(deposit_id.to_string()))?;
+        if deposit.status != TermDepositStatus::Active {
+            return Err(BankError::InvalidInput(format!("term deposit is already {}", deposit.status)));
+        }
+
+        let status = match deposit.is_mature(self.now()) {
+            true => TermDepositStatus::Matured,
+            false => TermDepositStatus::Withdrawn,
+        };
+        self.close_term_deposit(customer_id, &deposit, status)
+    }
+
+    /// A customer's term deposits, oldest first
+    pub fn term_deposits_for(&self, customer_id: &CustomerId) -> BankResult<&[TermDeposit]> {
+        Ok(&self.get_customer(customer_id)?.get_account()?.term_deposits)
+    }
+
+    /// Pays back every active term deposit that has matured by `now`,
+    /// soonest first
+    ///
+    /// # Returns
+    /// The deposits paid back, each now `Matured` with its payout
+    pub fn process_maturities(&mut self, now: DateTime<Utc>) -> BankResult<Vec<TermDeposit>> {
+        let mut due: Vec<(CustomerId, TermDeposit)> = self
+            .customers
+            .values()
+            .filter_map(|customer| Some((customer, customer.account.as_ref()?)))
+            .flat_map(|(customer, account)| {
+                account
+                    .term_deposits
+                    .iter()
+                    .filter(|d| d.status == TermDepositStatus::Active && d.is_mature(now))
+                    .map(|d| (customer.id.clone(), d.clone()))
+            })
+            .collect();
+        due.sort_by(|(_, a), (_, b)| a.maturity_date.cmp(&b.maturity_date).then_with(|| a.id.cmp(&b.id)));
+
+        let mut matured = Vec::new();
+        for (customer_id, mut deposit) in due {
+            let payout = self.close_term_deposit(&customer_id, &deposit, TermDepositStatus::Matured)?;
+            deposit.status = TermDepositStatus::Matured;
+            deposit.payout = Some(payout.amount);
+            deposit.closed_at = Some(payout.timestamp);
+            matured.push(deposit);
+        }
+        Ok(matured)
+    }
+
+    /// Credits what `deposit` pays back and closes it as `status`: the
+    /// full amount once `Matured`, the early payout as of now otherwise
+    fn close_term_deposit(
+        &mut self,
+        customer_id: &CustomerId,
+        deposit: &TermDeposit,
+        status: TermDepositStatus,
+    ) -> BankResult<Transaction> {
+        let now = self.now();
+        let payout = match status {
+            TermDepositStatus::Matured => deposit.payout_at(deposit.maturity_date),
+            _ => deposit.payout_at(now),
+        };
+        let account = self.get_customer(customer_id)?.get_account()?;
+        let mut transaction = account.prepare_deposit(payout, now)?;
+        transaction.transaction_type = TransactionType::TermDeposit { deposit_id: deposit.id.clone(), credit: true };
+
+        self.emit(BankEvent::TermDepositClosed {
+            customer_id: customer_id.clone(),
+            deposit_id: deposit.id.clone(),
+            status,
+            transaction: transaction.clone(),
+        })?;
+        Ok(transaction)
+    }
+}
@@ -0,0 +1,98 @@
+//! Transaction index - finding any transaction by its ID
+//!
+//! Demonstrates: A lazily built cache (OnceLock) kept up to date through
+//! `&mut self`, borrowed views returned from lookups
+//!
+//! Transactions live inside their account, so finding one by ID alone
+//! would mean scanning every account. The index maps transaction IDs to
+//! customer IDs. It is not saved with the bank: the first lookup builds it
+//! from the accounts, and `apply_event` adds every transaction posted
+//! after that. Archived transactions are no longer found.
+
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use serde::Serialize;
+
+use crate::models::{Account, AccountId, AccountNumber, Customer, CustomerId, Transaction, TransactionId};
+use super::core::Bank;
+
+/// Which customer each transaction belongs to (transaction ID -> customer ID)
+#[derive(Debug, Clone, Default)]
+pub(crate) struct TransactionIndex(OnceLock<HashMap<TransactionId, CustomerId>>);
+
+impl TransactionIndex {
+    /// The index, built from `customers` on first use
+    fn get(&self, customers: &HashMap<CustomerId, Customer>) -> &HashMap<TransactionId, CustomerId> {
+        self.0.get_or_init(|| {
+            customers
+                .values()
+                .filter_map(|customer| customer.account.as_ref().map(|account| (customer, account)))
+                .flat_map(|(customer, account)| {
+                    account.transactions.iter().map(|t| (t.id.clone(), customer.id.clone()))
+                })
+                .collect()
+        })
+    }
+
+    /// Records a newly posted transaction; nothing to do until the index
+    /// is first built, since building it picks the transaction up
+    pub(crate) fn insert(&mut self, transaction_id: &TransactionId, customer_id: &CustomerId) {
+        if let Some(index) = self.0.get_mut() {
+            index.insert(transaction_id.clone(), customer_id.clone());
+        }
+    }
+
+    /// Forgets a transaction that left the live history (see `archive`)
+    pub(crate) fn remove(&mut self, transaction_id: &TransactionId) {
+        if let Some(index) = self.0.get_mut() {
+            index.remove(transaction_id);
+        }
+    }
+}
+
+/// The customer and account a transaction belongs to
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct CustomerRef<'a> {
+    pub customer_id: &'a CustomerId,
+    pub name: &'a str,
+    pub email: &'a str,
+    pub account_id: &'a AccountId,
+
+    /// `None` for accounts opened before account numbers existed
+    pub account_number: Option<AccountNumber>,
+}
+
+impl Bank {
+    /// Finds a transaction on any account by its ID
+    ///
+    /// Transactions of deleted customers are found too.
+    ///
+    /// # Returns
+    /// * `Some((owner, transaction))` - The transaction and whose account
+    ///   it is on
+    /// * `None` - If no account has a transaction with this ID
+    pub fn find_transaction(&self, transaction_id: &TransactionId) -> Option<(CustomerRef<'_>, &Transaction)> {
+        let (customer, account, transaction) = self.locate_transaction(transaction_id)?;
+        let owner = CustomerRef {
+            customer_id: &customer.id,
+            name: &customer.name,
+            email: &customer.email,
+            account_id: &account.id,
+            account_number: account.number,
+        };
+        Some((owner, transaction))
+    }
+
+    /// The customer, account and transaction with this transaction ID
+    pub(crate) fn locate_transaction(
+        &self,
+        transaction_id: &TransactionId,
+    ) -> Option<(&Customer, &Account, &Transaction)> {
+        let customer_id = self.transaction_index.get(&self.customers).get(transaction_id)?;
+        let customer = self.customers.get(customer_id)?;
+        let account = customer.account.as_ref()?;
+        let transaction = account.transactions.iter().find(|t| t.id == *transaction_id)?;
+        Some((customer, account, transaction))
+    }
+}
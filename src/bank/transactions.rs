@@ -1,50 +1,144 @@
 //! Transaction operations module
 //!
-//! Demonstrates: Complex borrowing patterns, scoped borrows
+//! Demonstrates: Validating against shared borrows, then changing state
+//! through a single event
+//!
+//! Deposits, withdrawals and transfers go through the bank's `Pipeline`
+//! first; see `crate::middleware`. Each is planned against a shared
+//! borrow and then carried out, so `SharedBank` can plan under its read
+//! lock and only take the write lock to apply the plan.
 
 use crate::errors::{BankError, BankResult};
+use crate::middleware::{OperationKind, OperationRequest};
+use crate::models::{
+    CustomerId, FlaggedTransaction, Money, Transaction, TransactionDetails, TransactionId, TransactionTags,
+    TransactionType,
+};
 use super::core::Bank;
+use super::events::BankEvent;
+
+/// A deposit, withdrawal or transfer that passed the pipeline and the
+/// bookkeeping checks, ready to be carried out
+///
+/// The plan holds as long as the accounts it touches do not change in
+/// between; `SharedBank` makes sure of that by holding their locks.
+#[derive(Debug)]
+pub(crate) struct OperationPlan {
+    request: OperationRequest,
+    event: BankEvent,
+    postings: Vec<(CustomerId, Transaction)>,
+    fee: Option<Transaction>,
+}
 
 impl Bank {
     /// Deposits money into a customer's account
     ///
     /// # Returns
     /// The new balance after deposit
-    pub fn deposit(&mut self, customer_id: &str, amount: f64) -> BankResult<f64> {
-        let customer = self
-            .customers
-            .get_mut(customer_id)
-            .ok_or_else(|| BankError::CustomerNotFound(customer_id.to_string()))?;
+    pub fn deposit(&mut self, customer_id: &CustomerId, amount: Money) -> BankResult<Money> {
+        self.deposit_with(customer_id, amount, TransactionDetails::new())
+    }
 
-        let account = customer.get_account_mut()?;
-        account.deposit(amount)?;
-        self.total_transactions += 1;
+    /// Deposits money with a description and tags
+    ///
+    /// # Returns
+    /// The new balance after deposit
+    pub fn deposit_with(
+        &mut self,
+        customer_id: &CustomerId,
+        amount: Money,
+        details: TransactionDetails,
+    ) -> BankResult<Money> {
+        let plan = self.plan_deposit(customer_id, amount, details)?;
+        let posted = self.carry_out(plan)?;
+        Ok(posted.last().map(|tx| tx.balance_after).unwrap_or_default())
+    }
+
+    /// Checks a deposit without making it
+    pub(crate) fn plan_deposit(
+        &self,
+        customer_id: &CustomerId,
+        amount: Money,
+        details: TransactionDetails,
+    ) -> BankResult<OperationPlan> {
+        let mut request = OperationRequest::new(OperationKind::Deposit, customer_id.clone(), None, amount, self.now());
+        self.screen(&mut request)?;
 
-        Ok(account.balance)
+        let mut transaction = self.get_customer(customer_id)?.get_account()?.prepare_deposit(amount, request.at)?;
+        self.ensure_vault_takes(amount)?;
+        transaction.annotate(details);
+        transaction.cash = true;
+        let fee = Self::prepare_fee(request.fee, &transaction)?;
+
+        Ok(OperationPlan {
+            event: BankEvent::Deposited {
+                customer_id: customer_id.clone(),
+                transaction: transaction.clone(),
+            },
+            postings: vec![(customer_id.clone(), transaction)],
+            fee,
+            request,
+        })
     }
 
     /// Withdraws money from a customer's account
     ///
     /// # Returns
     /// The new balance after withdrawal
-    pub fn withdraw(&mut self, customer_id: &str, amount: f64) -> BankResult<f64> {
-        let customer = self
-            .customers
-            .get_mut(customer_id)
-            .ok_or_else(|| BankError::CustomerNotFound(customer_id.to_string()))?;
+    pub fn withdraw(&mut self, customer_id: &CustomerId, amount: Money) -> BankResult<Money> {
+        self.withdraw_with(customer_id, amount, TransactionDetails::new())
+    }
+
+    /// Withdraws money with a description, filed under a category and tags
+    ///
+    /// # Returns
+    /// The new balance after withdrawal
+    pub fn withdraw_with(
+        &mut self,
+        customer_id: &CustomerId,
+        amount: Money,
+        details: TransactionDetails,
+    ) -> BankResult<Money> {
+        let plan = self.plan_withdrawal(customer_id, amount, details)?;
+        let posted = self.carry_out(plan)?;
+        Ok(posted.last().map(|tx| tx.balance_after).unwrap_or_default())
+    }
+
+    /// Checks a withdrawal without making it
+    pub(crate) fn plan_withdrawal(
+        &self,
+        customer_id: &CustomerId,
+        amount: Money,
+        details: TransactionDetails,
+    ) -> BankResult<OperationPlan> {
+        let mut request = OperationRequest::new(OperationKind::Withdrawal, customer_id.clone(), None, amount, self.now());
+        self.screen(&mut request)?;
 
-        let account = customer.get_account_mut()?;
-        account.withdraw(amount)?;
-        self.total_transactions += 1;
+        let account = self.get_customer(customer_id)?.get_account()?;
+        let total = amount.checked_add(request.fee).ok_or(BankError::InvalidAmount(amount))?;
+        account.ensure_available(total, request.at)?;
+        let mut transaction = account.prepare_debit(amount, request.at)?;
+        self.ensure_vault_covers(amount)?;
+        transaction.annotate(details);
+        transaction.cash = true;
+        let fee = Self::prepare_fee(request.fee, &transaction)?;
 
-        Ok(account.balance)
+        Ok(OperationPlan {
+            event: BankEvent::Withdrawn {
+                customer_id: customer_id.clone(),
+                transaction: transaction.clone(),
+            },
+            postings: vec![(customer_id.clone(), transaction)],
+            fee,
+            request,
+        })
     }
 
     /// Transfers money between two customers
     ///
-    /// Demonstrates: Complex borrowing scenarios with scoped borrows
-    /// We use scoped blocks to release borrows before acquiring new ones
-    /// This is a key pattern for working with the borrow checker
+    /// The transfer is all-or-nothing: both sides are validated and turned
+    /// into a single `Transferred` event before any money moves, so a
+    /// failure leaves both accounts untouched.
     ///
     /// # Arguments
     /// * `from_customer_id` - Source customer ID
@@ -52,42 +146,177 @@ impl Bank {
     /// * `amount` - Amount to transfer
     pub fn transfer(
         &mut self,
-        from_customer_id: &str,
-        to_customer_id: &str,
-        amount: f64,
+        from_customer_id: &CustomerId,
+        to_customer_id: &CustomerId,
+        amount: Money,
     ) -> BankResult<()> {
-        // Validate both customers exist
-        if !self.customers.contains_key(from_customer_id) {
-            return Err(BankError::CustomerNotFound(from_customer_id.to_string()));
+        self.transfer_with(from_customer_id, to_customer_id, amount, TransactionDetails::new())
+    }
+
+    /// Transfers money with a description and tags
+    ///
+    /// Both sides get the description; only the sender's side is filed
+    /// under the category and tags.
+    pub fn transfer_with(
+        &mut self,
+        from_customer_id: &CustomerId,
+        to_customer_id: &CustomerId,
+        amount: Money,
+        details: TransactionDetails,
+    ) -> BankResult<()> {
+        let plan = self.plan_transfer(from_customer_id, to_customer_id, amount, details)?;
+        self.carry_out(plan)?;
+        Ok(())
+    }
+
+    /// Checks a transfer without making it
+    pub(crate) fn plan_transfer(
+        &self,
+        from_customer_id: &CustomerId,
+        to_customer_id: &CustomerId,
+        amount: Money,
+        details: TransactionDetails,
+    ) -> BankResult<OperationPlan> {
+        if !amount.is_positive() {
+            return Err(BankError::InvalidAmount(amount));
         }
-        if !self.customers.contains_key(to_customer_id) {
-            return Err(BankError::CustomerNotFound(to_customer_id.to_string()));
+        if from_customer_id == to_customer_id {
+            return Err(BankError::InvalidTransfer(
+                "Cannot transfer to the same account".to_string(),
+            ));
         }
 
-        // Step 1: Withdraw from source (scoped to release borrow)
-        {
-            let from_customer = self.customers.get_mut(from_customer_id).unwrap();
-            let from_account = from_customer.get_account_mut()?;
-            from_account.withdraw(amount)?;
+        let mut request = OperationRequest::new(
+            OperationKind::Transfer,
+            from_customer_id.clone(),
+            Some(to_customer_id.clone()),
+            amount,
+            self.now(),
+        );
+        self.screen(&mut request)?;
+
+        // Demonstrates: Two shared borrows at once - nothing is mutated yet
+        let from_account = self.get_customer(from_customer_id)?.get_account()?;
+        let to_account = self.get_customer(to_customer_id)?.get_account()?;
+
+        let now = request.at;
+        let total = amount.checked_add(request.fee).ok_or(BankError::InvalidAmount(amount))?;
+        from_account.ensure_available(total, now)?;
+        let mut debit = from_account.prepare_debit(amount, now)?;
+        debit.transaction_type = TransactionType::Transfer { to_account_id: to_account.id.clone() };
+        let mut credit = to_account.prepare_deposit(amount, now)?;
+        credit.transaction_type = TransactionType::TransferIn { from_account_id: from_account.id.clone() };
+        credit.description = details.description.clone();
+        debit.annotate(details);
+        let fee = Self::prepare_fee(request.fee, &debit)?;
+
+        Ok(OperationPlan {
+            event: BankEvent::Transferred {
+                from_customer_id: from_customer_id.clone(),
+                to_customer_id: to_customer_id.clone(),
+                debit: debit.clone(),
+                credit: credit.clone(),
+            },
+            postings: vec![(from_customer_id.clone(), debit), (to_customer_id.clone(), credit)],
+            fee,
+            request,
+        })
+    }
+
+    /// Carries out a planned operation
+    ///
+    /// # Returns
+    /// Everything the operation posted, the fee last
+    pub(crate) fn carry_out(&mut self, plan: OperationPlan) -> BankResult<Vec<Transaction>> {
+        // The vault is shared by every account, so an operation on other
+        // accounts may have moved its cash since the plan was made
+        match &plan.event {
+            BankEvent::Deposited { transaction, .. } => self.ensure_vault_takes(transaction.amount)?,
+            BankEvent::Withdrawn { transaction, .. } => self.ensure_vault_covers(transaction.amount)?,
+            _ => {}
+        }
+
+        let mut request = plan.request;
+        request.alerts = self.operation_alerts(&plan.event);
+        self.emit_screened(plan.event)?;
+        if let (Some(reason), Some((customer_id, transaction))) = (&request.flag, plan.postings.first()) {
+            let flag = FlaggedTransaction::pending(customer_id.clone(), transaction, reason.clone());
+            self.emit(BankEvent::TransactionFlagged { flag })?;
+        }
+        self.post_operation(request, plan.postings, plan.fee)
+    }
+
+    /// Appends previously exported transactions to a customer's account
+    ///
+    /// Used to seed accounts from CSV files. Transactions whose ID already
+    /// exists in the account are skipped, and each imported transaction's
+    /// `balance_after` is recomputed from the account's running balance.
+    ///
+    /// # Returns
+    /// * `Ok(usize)` - The number of transactions imported
+    /// * `Err(BankError)` - If a debit would overdraw the account; nothing is
+    ///   imported in that case
+    pub fn import_transactions(
+        &mut self,
+        customer_id: &CustomerId,
+        transactions: Vec<Transaction>,
+    ) -> BankResult<usize> {
+        let account = self.get_customer(customer_id)?.get_account()?;
+        account.ensure_open()?;
+
+        // Validate the whole batch before touching the account
+        let mut balance = account.balance;
+        let mut accepted = Vec::new();
+        for mut tx in transactions {
+            if account.transactions.iter().any(|t| t.id == tx.id) {
+                continue;
+            }
+            if !tx.amount.is_positive() {
+                return Err(BankError::InvalidAmount(tx.amount));
+            }
+            let after = balance.checked_add(tx.signed_amount()).ok_or(BankError::InvalidAmount(tx.amount))?;
+            if after.is_negative() {
+                return Err(BankError::InsufficientFunds {
+                    available: balance,
+                    requested: tx.amount,
+                });
+            }
+            balance = after;
+            tx.balance_after = balance;
+            accepted.push(tx);
         }
 
-        // Step 2: Deposit to destination (scoped to release borrow)
-        let to_account_id = {
-            let to_customer = self.customers.get_mut(to_customer_id).unwrap();
-            let to_account = to_customer.get_account_mut()?;
-            to_account.deposit(amount)?;
-            to_account.id.clone()
-        };
-
-        // Step 3: Update transaction type to reflect transfer
-        {
-            let from_customer = self.customers.get_mut(from_customer_id).unwrap();
-            let from_account = from_customer.get_account_mut()?;
-            from_account.mark_last_as_transfer(to_account_id);
+        let count = accepted.len();
+        if count > 0 {
+            self.emit(BankEvent::TransactionsImported {
+                customer_id: customer_id.clone(),
+                transactions: accepted,
+            })?;
         }
 
-        self.total_transactions += 2; // Withdrawal + Deposit
+        Ok(count)
+    }
 
-        Ok(())
+    /// Sets the category and tags of a posted transaction, replacing
+    /// earlier ones
+    ///
+    /// # Returns
+    /// * `Err(BankError::InvalidInput)` - If the account has no such transaction
+    pub fn tag_transaction(
+        &mut self,
+        customer_id: &CustomerId,
+        transaction_id: &TransactionId,
+        tags: TransactionTags,
+    ) -> BankResult<()> {
+        let account = self.get_customer(customer_id)?.get_account()?;
+        if !account.transactions.iter().any(|tx| tx.id == *transaction_id) {
+            return Err(BankError::InvalidInput(format!("transaction '{}' not found", transaction_id)));
+        }
+
+        self.emit(BankEvent::TransactionTagged {
+            customer_id: customer_id.clone(),
+            transaction_id: transaction_id.clone(),
+            tags,
+        })
     }
 }
@@ -0,0 +1,211 @@
+//! Undo - reversing recent operations with compensating transactions
+//!
+//! Demonstrates: VecDeque as a bounded stack, peeking before popping so a
+//! failed undo leaves the stack untouched
+//!
+//! Nothing is ever deleted from an account's history. Undoing an operation
+//! posts a `Reversal` for each transaction it created, so balances, the
+//! history and `total_transactions` always agree. `reverse_transaction`
+//! reverses any single transaction by ID, with a reason kept on the
+//! reversal. An operation whose transaction is reversed some other way, or
+//! archived, leaves the undo stack, so undo moves on to the one before.
+
+use crate::errors::{BankError, BankResult};
+use crate::models::{CustomerId, Money, Transaction, TransactionId, TransactionStatus, TransactionType};
+use crate::validation;
+use super::core::Bank;
+use super::events::BankEvent;
+
+/// How many operations can be undone
+pub const UNDO_LIMIT: usize = 20;
+
+/// A transaction posted by an undoable operation
+#[derive(Debug, Clone)]
+pub(crate) struct Posting {
+    customer_id: CustomerId,
+    transaction_id: TransactionId,
+}
+
+impl Bank {
+    /// Remembers the transactions one operation posted, so it can be undone
+    pub(crate) fn push_undo(&mut self, posted: &[(&CustomerId, &Transaction)]) {
+        let postings = posted
+            .iter()
+            .map(|(customer_id, transaction)| Posting {
+                customer_id: (*customer_id).clone(),
+                transaction_id: transaction.id.clone(),
+            })
+            .collect();
+
+        self.undo_stack.push_back(postings);
+        if self.undo_stack.len() > UNDO_LIMIT {
+            self.undo_stack.pop_front();
+        }
+    }
+
+    /// Forgets the operation that posted `transaction_id`, once it has been
+    /// reversed or archived and so can no longer be undone
+    pub(crate) fn forget_undo(&mut self, transaction_id: &TransactionId) {
+        self.undo_stack
+            .retain(|postings| postings.iter().all(|posting| posting.transaction_id != *transaction_id));
+    }
+
+    /// Number of operations that can currently be undone
+    pub fn undo_depth(&self) -> usize {
+        self.undo_stack.len()
+    }
+
+    /// Reverses the most recent deposit, withdrawal or transfer
+    ///
+    /// The undo history is kept in memory only, so it starts empty each
+    /// time the bank is loaded. Operations reversed by `reverse_transaction`
+    /// or archived since are skipped.
+    ///
+    /// # Returns
+    /// * `Ok(Vec<Transaction>)` - The reversals that were posted
+    /// * `Err(BankError::InvalidInput)` - If there is nothing to undo
+    /// * `Err(BankError::InsufficientFunds)` - If a deposit being undone has
+    ///   already been spent; the operation stays undoable
+    pub fn undo_last(&mut self) -> BankResult<Vec<Transaction>> {
+        let postings = self
+            .undo_stack
+            .back()
+            .cloned()
+            .ok_or_else(|| BankError::InvalidInput("nothing to undo".to_string()))?;
+
+        // Prepare every reversal before posting any, so a failure changes nothing
+        let mut reversals: Vec<(CustomerId, Transaction)> = Vec::new();
+        for posting in &postings {
+            let mut reversal = self
+                .get_customer(&posting.customer_id)?
+                .get_account()?
+                .prepare_reversal(&posting.transaction_id, self.now())?;
+            // An operation and its fee are reversed on the same account, one after the other
+            let earlier: Money = reversals
+                .iter()
+                .filter(|(customer_id, _)| *customer_id == posting.customer_id)
+                .map(|(_, earlier)| earlier.signed_amount())
+                .sum();
+            reversal.balance_after =
+                reversal.balance_after.checked_add(earlier).ok_or(BankError::InvalidAmount(reversal.amount))?;
+            reversals.push((posting.customer_id.clone(), reversal));
+        }
+
+        self.undo_stack.pop_back();
+
+        let mut posted = Vec::new();
+        for (customer_id, transaction) in reversals {
+            posted.push(transaction.clone());
+            self.emit(BankEvent::Reversed { customer_id, transaction })?;
+        }
+        Ok(posted)
+    }
+
+    /// Reverses one transaction on any account by its ID, recording why
+    ///
+    /// Each reversal carries the reason and is linked both ways: its
+    /// `reverses()` is the original's ID and the original's `reversed_by`
+    /// is the reversal's. A transfer between customers is reversed on
+    /// both sides. Unlike `undo_last`, any completed transaction can be
+    /// reversed, however old.
+    ///
+    /// # Returns
+    /// * `Ok(Vec<Transaction>)` - The reversals that were posted
+    /// * `Err(BankError::TransactionNotFound)` - If no account has the
+    ///   transaction
+    /// * `Err(BankError::ValidationError)` - If the reason is empty or too
+    ///   long
+    /// * `Err(BankError::InvalidInput)` - If it was already reversed, is a
+    ///   reversal itself or is not completed
+    /// * `Err(BankError::InsufficientFunds)` - If money it brought in has
+    ///   since been spent
+    pub fn reverse_transaction(
+        &mut self,
+        transaction_id: &TransactionId,
+        reason: &str,
+    ) -> BankResult<Vec<Transaction>> {
+        let reason = validation::validate_reversal_reason(reason)?;
+        let customer_id = self
+            .locate_transaction(transaction_id)
+            .map(|(customer, _, _)| customer.id.clone())
+            .ok_or_else(|| BankError::TransactionNotFound(transaction_id.clone()))?;
+        let reversals = self.prepare_reversals_of(&customer_id, transaction_id)?;
+        self.post_reversals(reversals, &reason)
+    }
+
+    /// Posts prepared reversals, each recording `reason`
+    pub(crate) fn post_reversals(
+        &mut self,
+        reversals: Vec<(CustomerId, Transaction)>,
+        reason: &str,
+    ) -> BankResult<Vec<Transaction>> {
+        let mut posted = Vec::new();
+        for (customer_id, mut transaction) in reversals {
+            transaction.reversal_reason = Some(reason.to_string());
+            posted.push(transaction.clone());
+            self.emit(BankEvent::Reversed { customer_id, transaction })?;
+        }
+        Ok(posted)
+    }
+
+    /// Prepares the reversals that undo one completed transaction: both
+    /// legs for a transfer between customers, the transaction alone
+    /// otherwise
+    ///
+    /// Nothing is posted; the caller emits a `Reversed` event for each
+    /// (customer ID, reversal) pair.
+    ///
+    /// # Returns
+    /// * `Err(BankError::TransactionNotFound)` - If the customer has no
+    ///   such transaction
+    /// * `Err(BankError::InvalidInput)` - If it is not completed (e.g.
+    ///   already reversed or still pending)
+    /// * `Err(BankError::InsufficientFunds)` - If money it brought in has
+    ///   since been spent
+    pub(crate) fn prepare_reversals_of(
+        &self,
+        customer_id: &CustomerId,
+        transaction_id: &TransactionId,
+    ) -> BankResult<Vec<(CustomerId, Transaction)>> {
+        let account = self.get_customer(customer_id)?.get_account()?;
+        let original = account
+            .transactions
+            .iter()
+            .find(|tx| tx.id == *transaction_id)
+            .ok_or_else(|| BankError::TransactionNotFound(transaction_id.clone()))?;
+        let now = self.now();
+        let reversal = account.prepare_reversal(transaction_id, now)?;
+        if original.status != TransactionStatus::Completed {
+            return Err(BankError::InvalidInput(format!(
+                "only completed transactions can be reversed; this one is {}",
+                original.status
+            )));
+        }
+        let mut reversals = vec![(customer_id.clone(), reversal)];
+
+        // The other leg of a transfer: same accounts, amount and time
+        let counterparty = match &original.transaction_type {
+            TransactionType::Transfer { to_account_id } => Some((to_account_id, true)),
+            TransactionType::TransferIn { from_account_id } => Some((from_account_id, false)),
+            _ => None,
+        };
+        if let Some((other_account_id, outgoing)) = counterparty {
+            let other = self
+                .find_customer_by_account_id(other_account_id)
+                .ok_or_else(|| BankError::AccountNotFound(other_account_id.to_string()))?;
+            let other_account = other.get_account()?;
+            let leg = other_account.transactions.iter().find(|tx| {
+                let matches_type = match &tx.transaction_type {
+                    TransactionType::TransferIn { from_account_id } => outgoing && *from_account_id == account.id,
+                    TransactionType::Transfer { to_account_id } => !outgoing && *to_account_id == account.id,
+                    _ => false,
+                };
+                matches_type && tx.amount == original.amount && tx.timestamp == original.timestamp
+            });
+            if let Some(leg) = leg.filter(|leg| leg.status == TransactionStatus::Completed) {
+                reversals.push((other.id.clone(), other_account.prepare_reversal(&leg.id, now)?));
+            }
+        }
+        Ok(reversals)
+    }
+}
@@ -0,0 +1,83 @@
+//! Cash vault - the bank's own cash position
+//!
+//! Demonstrates: A running total kept up to date as transactions post,
+//! Option<T> for "not tracked yet" in data saved before the field existed
+//!
+//! Every transaction marked `cash` moves the vault by its signed amount as
+//! it posts, so cash deposits fill the vault, cash withdrawals empty it, and
+//! reversals put the cash back. A withdrawal the vault cannot cover is
+//! refused; reversals never are. Admins bring cash in or take it out with
+//! `vault_top_up` and `vault_withdraw`.
+
+use crate::errors::{BankError, BankResult};
+use crate::models::{Money, Transaction};
+use super::core::Bank;
+use super::events::BankEvent;
+
+impl Bank {
+    /// Cash in the vault, or `None` for a bank saved before the vault was
+    /// tracked and not topped up since
+    pub fn vault(&self) -> Option<Money> {
+        self.vault
+    }
+
+    /// Brings cash into the vault; an untracked vault starts counting from
+    /// this amount
+    ///
+    /// # Returns
+    /// * `Ok(Money)` - The vault balance afterwards
+    /// * `Err(BankError::InvalidAmount)` - If the amount is not positive,
+    ///   or the vault total would overflow
+    pub fn vault_top_up(&mut self, amount: Money) -> BankResult<Money> {
+        if !amount.is_positive() {
+            return Err(BankError::InvalidAmount(amount));
+        }
+        self.ensure_vault_takes(amount)?;
+        self.emit(BankEvent::VaultToppedUp { amount, at: self.now() })?;
+        Ok(self.vault.unwrap_or(Money::ZERO))
+    }
+
+    /// Takes cash out of the vault, e.g. to send it to a central bank
+    ///
+    /// # Returns
+    /// * `Ok(Money)` - The vault balance afterwards
+    /// * `Err(BankError::InvalidAmount)` - If the amount is not positive
+    /// * `Err(BankError::InsufficientVaultCash)` - If the vault holds less
+    pub fn vault_withdraw(&mut self, amount: Money) -> BankResult<Money> {
+        if !amount.is_positive() {
+            return Err(BankError::InvalidAmount(amount));
+        }
+        self.ensure_vault_covers(amount)?;
+        self.emit(BankEvent::VaultWithdrawn { amount, at: self.now() })?;
+        Ok(self.vault.unwrap_or(Money::ZERO))
+    }
+
+    /// Refuses to pay out `amount` in cash when the vault is tracked and
+    /// holds less
+    pub(crate) fn ensure_vault_covers(&self, amount: Money) -> BankResult<()> {
+        match self.vault {
+            Some(available) if available < amount => {
+                Err(BankError::InsufficientVaultCash { available, requested: amount })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Checks that a tracked vault can take `amount` more cash
+    ///
+    /// # Returns
+    /// * `Err(BankError::InvalidAmount)` - If the vault total would overflow
+    pub(crate) fn ensure_vault_takes(&self, amount: Money) -> BankResult<()> {
+        match self.vault {
+            Some(vault) if vault.checked_add(amount).is_none() => Err(BankError::InvalidAmount(amount)),
+            _ => Ok(()),
+        }
+    }
+
+    /// Moves a cash transaction's money into or out of a tracked vault
+    pub(crate) fn move_vault_cash(&mut self, transaction: &Transaction) {
+        if let (true, Some(vault)) = (transaction.cash, self.vault.as_mut()) {
+            *vault += transaction.signed_amount();
+        }
+    }
+}
@@ -0,0 +1,156 @@
+//! Subscriptions and webhooks - publishing domain events
+//!
+//! Demonstrates: Generic closure parameters boxed into trait objects
+//!
+//! Like balance alerts, domain events are worked out in `emit` before the
+//! change is applied and published once it has been. Webhooks are bank
+//! data, journaled like any other change; subscribed callbacks live only as
+//! long as the `Bank` value they were added to. Deliveries to webhooks are
+//! queued for a background thread (see `events::DeliveryQueue`).
+
+use std::sync::Arc;
+
+use crate::errors::{BankError, BankResult};
+use crate::events::{DomainEvent, EventKind, SubscriptionId, Webhook};
+use crate::models::{CustomerId, Transaction};
+use super::core::Bank;
+use super::events::BankEvent;
+
+impl Bank {
+    /// Calls `callback` with every domain event from now on
+    ///
+    /// The callback runs while the bank is being changed, so it must not
+    /// try to lock a `SharedBank` holding this bank.
+    pub fn subscribe<F>(&mut self, callback: F) -> SubscriptionId
+    where
+        F: Fn(&DomainEvent) + Send + Sync + 'static,
+    {
+        self.subscribers.add(Arc::new(callback))
+    }
+
+    /// Stops calling a subscribed callback; returns whether it was subscribed
+    pub fn unsubscribe(&mut self, id: SubscriptionId) -> bool {
+        self.subscribers.remove(id)
+    }
+
+    /// Registers a URL to POST domain events to
+    ///
+    /// # Arguments
+    /// * `events` - The kinds to send; empty sends every kind
+    ///
+    /// # Returns
+    /// * `Ok(Webhook)` - The registered webhook, with its ID
+    /// * `Err(BankError::InvalidInput)` - If the URL is not an `http://` URL
+    ///   or is already registered
+    pub fn add_webhook(&mut self, url: &str, events: Vec<EventKind>) -> BankResult<Webhook> {
+        let webhook = Webhook::new(url, events, self.now())?;
+        if self.webhooks.iter().any(|w| w.url == webhook.url) {
+            return Err(BankError::InvalidInput(format!("{} is already registered", webhook.url)));
+        }
+
+        self.emit(BankEvent::WebhookAdded { webhook: webhook.clone() })?;
+        Ok(webhook)
+    }
+
+    /// Unregisters a webhook
+    ///
+    /// # Returns
+    /// * `Ok(Webhook)` - The webhook that was removed
+    /// * `Err(BankError::WebhookNotFound)` - If no webhook has this ID
+    pub fn remove_webhook(&mut self, id: &str) -> BankResult<Webhook> {
+        let id = id.trim();
+        let webhook = self
+            .webhooks
+            .iter()
+            .find(|w| w.id == id)
+            .cloned()
+            .ok_or_else(|| BankError::WebhookNotFound(id.to_string()))?;
+
+        self.emit(BankEvent::WebhookRemoved { id: webhook.id.clone() })?;
+        Ok(webhook)
+    }
+
+    /// Lists registered webhooks, oldest first
+    pub fn list_webhooks(&self) -> &[Webhook] {
+        &self.webhooks
+    }
+
+    /// The domain events `event` amounts to
+    pub(crate) fn domain_events_for(&self, event: &BankEvent) -> Vec<DomainEvent> {
+        match event {
+            BankEvent::CustomerRegistered { customer } => vec![DomainEvent::CustomerRegistered {
+                customer_id: customer.id.clone(),
+                name: customer.name.clone(),
+                email: customer.email.clone(),
+                registered_at: customer.registered_at,
+            }],
+            BankEvent::Deposited { customer_id, transaction }
+            | BankEvent::Withdrawn { customer_id, transaction }
+            | BankEvent::Reversed { customer_id, transaction }
+            | BankEvent::ExternalTransferSent { customer_id, transaction }
+            | BankEvent::AuthorizationCaptured { customer_id, transaction }
+            | BankEvent::HoldCaptured { customer_id, transaction, .. }
+            | BankEvent::ChequeDeposited { customer_id, transaction, .. }
+            | BankEvent::BillPaid { customer_id, transaction }
+            | BankEvent::FeeCharged { customer_id, transaction }
+            | BankEvent::TermDepositOpened { customer_id, transaction, .. }
+            | BankEvent::TermDepositClosed { customer_id, transaction, .. }
+            | BankEvent::AutopayRan { customer_id, transaction: Some(transaction), .. }
+            | BankEvent::ExternalTransferRejected { customer_id, refund: transaction, .. } => {
+                self.posted(customer_id, transaction).into_iter().collect()
+            }
+            BankEvent::ChequeCleared { drawer_id: Some(drawer_id), debit: Some(debit), .. } => {
+                self.posted(drawer_id, debit).into_iter().collect()
+            }
+            BankEvent::ChequeBounced { customer_id, reversal, fee, .. } => std::iter::once(reversal)
+                .chain(fee)
+                .filter_map(|transaction| self.posted(customer_id, transaction))
+                .collect(),
+            BankEvent::TransactionsImported { customer_id, transactions } => transactions
+                .iter()
+                .filter_map(|transaction| self.posted(customer_id, transaction))
+                .collect(),
+            BankEvent::Transferred { from_customer_id, to_customer_id, debit, credit } => {
+                let mut published: Vec<_> = self
+                    .posted(from_customer_id, debit)
+                    .into_iter()
+                    .chain(self.posted(to_customer_id, credit))
+                    .collect();
+                published.push(DomainEvent::TransferCompleted {
+                    from_customer_id: from_customer_id.clone(),
+                    to_customer_id: to_customer_id.clone(),
+                    amount: debit.amount,
+                    debit_id: debit.id.clone(),
+                    credit_id: credit.id.clone(),
+                    completed_at: debit.timestamp,
+                });
+                published
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    fn posted(&self, customer_id: &CustomerId, transaction: &Transaction) -> Option<DomainEvent> {
+        let account = self.customers.get(customer_id)?.account.as_ref()?;
+        Some(DomainEvent::TransactionPosted {
+            customer_id: customer_id.clone(),
+            account_id: account.id.clone(),
+            transaction: transaction.clone(),
+        })
+    }
+
+    /// Hands events to every subscriber and queues them for every
+    /// interested webhook
+    ///
+    /// Webhooks are POSTed by a background thread, so this never waits on
+    /// one; a webhook that still fails after its retries is reported to
+    /// the observers with `on_webhook_failed`.
+    pub(crate) fn publish(&self, published: Vec<DomainEvent>) {
+        for event in &published {
+            self.subscribers.notify(event);
+            for webhook in self.webhooks.iter().filter(|w| w.wants(event.kind())) {
+                self.deliveries.push(&webhook.url, event, &self.observers);
+            }
+        }
+    }
+}
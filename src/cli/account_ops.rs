@@ -2,122 +2,691 @@
 //!
 //! Demonstrates: Banking operations through CLI, input validation
 
-use std::io;
-use std::sync::{Arc, Mutex};
+use std::fs;
+use std::io::{self, Write};
+use std::str::FromStr;
 
-use crate::bank::Bank;
-use super::utils::read_input;
+use chrono::Utc;
+use serde_json::json;
 
-/// Creates an account for a customer
-pub fn create_account(bank: &Arc<Mutex<Bank>>) -> io::Result<()> {
-    println!("\n--- Create Account ---");
+use crate::bank::{Bank, SharedBank};
+use crate::errors::BankError;
+use crate::models::{
+    AccountLimits, AccountStatus, AccountType, AlertRules, CustomerId, Money, TransactionDetails, TransactionId,
+    TransactionTags,
+};
+use crate::validation;
+use super::budget_ops::confirm_budget;
+use super::i18n::Msg;
+use super::render::Renderer;
+use super::session::{authorize, Session, ADMIN_ONLY, ANY_ROLE, STAFF};
+use super::utils::{
+    choose_customer, is_cancelled, parse_limit, read_amount, read_checked, read_customer, read_input, read_yes,
+};
 
-    let customer_id = read_input("Enter customer ID: ")?;
-    let initial_deposit = read_input("Enter initial deposit amount: ")?;
+/// Creates an account for a customer (staff only)
+pub fn create_account(
+    bank: &SharedBank,
+    session: Option<&Session>,
+    renderer: &dyn Renderer,
+) -> io::Result<()> {
+    if authorize(session, STAFF, renderer).is_none() {
+        return Ok(());
+    }
+
+    renderer.section(Msg::CreateAccount.text());
 
-    let amount: f64 = match initial_deposit.parse() {
-        Ok(amt) => amt,
-        Err(_) => {
-            println!("\n❌ Invalid amount\n");
+    let customer_id = match read_customer(bank, Msg::EnterCustomer.text(), renderer)? {
+        Some(id) => id,
+        None => return Ok(()),
+    };
+    let amount = read_amount(Msg::EnterInitialDeposit.text(), renderer)?;
+
+    let account_type = match read_account_type(renderer)? {
+        Some(account_type) => account_type,
+        None => {
+            renderer.failure(&Msg::InvalidAccountType);
             return Ok(());
         }
     };
 
-    let mut bank = bank.lock().unwrap();
+    let mut bank = bank.write();
 
-    match bank.create_account_for_customer(&customer_id, amount) {
+    match bank.create_account_for_customer(&customer_id, amount, account_type.clone()) {
         Ok(account_id) => {
-            println!("\n✅ Account created successfully!");
-            println!("💳 Account ID: {}", account_id);
-            println!("💰 Initial Balance: ${:.2}\n", amount);
+            let number = bank.get_customer(&customer_id).ok().and_then(|c| c.account.as_ref()?.number);
+            renderer.success(
+                &Msg::AccountCreated.fill(&[
+                    &number.map(|n| n.to_string()).unwrap_or_default(),
+                    &account_id,
+                    &account_type,
+                    &amount.display(),
+                ]),
+                json!({
+                    "account_id": account_id,
+                    "account_number": number,
+                    "account_type": account_type,
+                    "balance": amount,
+                }),
+            )
+        }
+        Err(e) => renderer.failure(&e),
+    }
+
+    Ok(())
+}
+
+/// Prompts for the account type (and term for fixed deposits)
+///
+/// # Returns
+/// * `Ok(None)` - If the input was not a valid choice
+fn read_account_type(renderer: &dyn Renderer) -> io::Result<Option<AccountType>> {
+    renderer.chrome(Msg::AccountTypes.text());
+    let choice = read_input(Msg::EnterAccountType.text())?;
+
+    let account_type = match choice.as_str() {
+        "" | "1" => Some(AccountType::Checking),
+        "2" => Some(AccountType::Savings),
+        "3" => {
+            let term = read_input(Msg::EnterTermMonths.text())?;
+            term.parse::<u32>()
+                .ok()
+                .filter(|months| *months > 0)
+                .and_then(AccountType::fixed_deposit)
+        }
+        _ => None,
+    };
+
+    Ok(account_type)
+}
+
+/// Deposits money into a customer's account
+///
+/// Customers can only deposit into their own account.
+pub fn deposit_money(
+    bank: &SharedBank,
+    session: Option<&Session>,
+    renderer: &dyn Renderer,
+) -> io::Result<()> {
+    let session = match authorize(session, ANY_ROLE, renderer) {
+        Some(session) => session,
+        None => return Ok(()),
+    };
+
+    renderer.section(Msg::DepositMoney.text());
+
+    let customer_id = match session.target_customer(bank, Msg::EnterCustomer.text(), renderer)? {
+        Some(id) => id,
+        None => return Ok(()),
+    };
+    let amount = read_amount(Msg::EnterDepositAmount.text(), renderer)?;
+
+    let details = match read_details(renderer, false)? {
+        Some(details) => details,
+        None => return Ok(()),
+    };
+
+    let result = {
+        let mut bank = bank.write();
+        bank.deposit_with(&customer_id, amount, details)
+            .map(|balance| (balance, last_transaction(&bank, &customer_id)))
+    };
+
+    match result {
+        Ok((new_balance, transaction_id)) => {
+            renderer.success(
+                &Msg::DepositDone.fill(&[&new_balance.display()]),
+                json!({ "balance": new_balance, "transaction_id": transaction_id }),
+            );
+            offer_receipt(bank, transaction_id, renderer)?;
+        }
+        Err(e) => renderer.failure(&e),
+    }
+
+    Ok(())
+}
+
+/// Withdraws money from a customer's account
+///
+/// Customers can only withdraw from their own account.
+pub fn withdraw_money(
+    bank: &SharedBank,
+    session: Option<&Session>,
+    renderer: &dyn Renderer,
+) -> io::Result<()> {
+    let session = match authorize(session, ANY_ROLE, renderer) {
+        Some(session) => session,
+        None => return Ok(()),
+    };
+
+    renderer.section(Msg::WithdrawMoney.text());
+
+    let customer_id = match session.target_customer(bank, Msg::EnterCustomer.text(), renderer)? {
+        Some(id) => id,
+        None => return Ok(()),
+    };
+    let amount = read_amount(Msg::EnterWithdrawalAmount.text(), renderer)?;
+
+    let details = match read_details(renderer, true)? {
+        Some(details) => details,
+        None => return Ok(()),
+    };
+    if !confirm_budget(bank, &customer_id, amount, &details, renderer)? {
+        return Ok(());
+    }
+
+    let result = {
+        let mut bank = bank.write();
+        bank.withdraw_with(&customer_id, amount, details)
+            .map(|balance| (balance, last_transaction(&bank, &customer_id)))
+    };
+
+    match result {
+        Ok((new_balance, transaction_id)) => {
+            renderer.success(
+                &Msg::WithdrawalDone.fill(&[&new_balance.display()]),
+                json!({ "balance": new_balance, "transaction_id": transaction_id }),
+            );
+            offer_receipt(bank, transaction_id, renderer)?;
         }
-        Err(e) => println!("\n❌ Error: {}\n", e),
+        Err(e) => renderer.failure(&e),
     }
 
     Ok(())
 }
 
-/// Deposits money into an account
-pub fn deposit_money(bank: &Arc<Mutex<Bank>>) -> io::Result<()> {
-    println!("\n--- Deposit Money ---");
+/// Transfers money between customers
+///
+/// Customers can only send money from their own account.
+pub fn transfer_money(
+    bank: &SharedBank,
+    session: Option<&Session>,
+    renderer: &dyn Renderer,
+) -> io::Result<()> {
+    let session = match authorize(session, ANY_ROLE, renderer) {
+        Some(session) => session,
+        None => return Ok(()),
+    };
+
+    renderer.section(Msg::TransferMoney.text());
 
-    let customer_id = read_input("Enter customer ID: ")?;
-    let amount_str = read_input("Enter amount to deposit: ")?;
+    let from_id = match session.target_customer(bank, Msg::EnterSender.text(), renderer)? {
+        Some(id) => id,
+        None => return Ok(()),
+    };
+    let to_id = match read_recipient(bank, &from_id, renderer)? {
+        Some(id) => id,
+        None => return Ok(()),
+    };
+    let amount = read_amount(Msg::EnterTransferAmount.text(), renderer)?;
+
+    let details = match read_details(renderer, true)? {
+        Some(details) => details,
+        None => return Ok(()),
+    };
+    if !confirm_budget(bank, &from_id, amount, &details, renderer)? {
+        return Ok(());
+    }
+
+    let result = {
+        let mut bank = bank.write();
+        bank.transfer_with(&from_id, &to_id, amount, details).map(|()| last_transaction(&bank, &from_id))
+    };
+
+    match result {
+        Ok(transaction_id) => {
+            renderer.success(
+                &Msg::TransferDone.fill(&[&amount.display()]),
+                json!({ "transferred": amount, "transaction_id": transaction_id }),
+            );
+            offer_receipt(bank, transaction_id, renderer)?;
+        }
+        Err(e) => renderer.failure(&e),
+    }
+
+    Ok(())
+}
 
-    let amount: f64 = match amount_str.parse() {
-        Ok(amt) => amt,
-        Err(_) => {
-            println!("\n❌ Invalid amount\n");
+/// ID of the newest transaction on a customer's account
+fn last_transaction(bank: &Bank, customer_id: &CustomerId) -> Option<TransactionId> {
+    let account = bank.get_customer(customer_id).ok()?.get_account().ok()?;
+    account.get_transaction_history().last().map(|t| t.id.clone())
+}
+
+/// Offers the receipt of the transaction just made, then asks for a file
+/// to save it to or shows it with a blank answer
+///
+/// The operation is already done, so cancelling either prompt only skips
+/// the receipt, and a file that already exists is never overwritten.
+fn offer_receipt(bank: &SharedBank, transaction_id: Option<TransactionId>, renderer: &dyn Renderer) -> io::Result<()> {
+    let Some(transaction_id) = transaction_id else {
+        return Ok(());
+    };
+    match read_yes(Msg::OfferReceipt.text()) {
+        Ok(true) => {}
+        Ok(false) => return Ok(()),
+        Err(e) if is_cancelled(&e) => return Ok(()),
+        Err(e) => return Err(e),
+    }
+    let receipt = match bank.read().get_receipt(&transaction_id) {
+        Ok(receipt) => receipt,
+        Err(e) => {
+            renderer.failure(&e);
             return Ok(());
         }
     };
+    let path = match read_input(Msg::ReceiptFile.text()) {
+        Ok(path) => path,
+        Err(e) if is_cancelled(&e) => return Ok(()),
+        Err(e) => return Err(e),
+    };
+
+    if path.is_empty() {
+        renderer.success(&format!("\n{}\n", receipt), json!(receipt));
+        return Ok(());
+    }
+    let saved = fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&path)
+        .and_then(|mut file| writeln!(file, "{}", receipt));
+    match saved {
+        Ok(()) => renderer.success(&Msg::ReceiptSaved.fill(&[&path]), json!({ "receipt": path })),
+        Err(e) if e.kind() == io::ErrorKind::AlreadyExists => renderer.failure(&Msg::ReceiptFileExists.fill(&[&path])),
+        Err(e) => renderer.failure(&BankError::from(e)),
+    }
+    Ok(())
+}
+
+/// Sends money to an account at another bank
+///
+/// The money leaves at once; the transfer stays pending until staff
+/// settle or reject it in the clearing queue.
+pub fn transfer_external(
+    bank: &SharedBank,
+    session: Option<&Session>,
+    renderer: &dyn Renderer,
+) -> io::Result<()> {
+    let session = match authorize(session, ANY_ROLE, renderer) {
+        Some(session) => session,
+        None => return Ok(()),
+    };
 
-    let mut bank = bank.lock().unwrap();
+    renderer.section(Msg::TransferExternal.text());
+
+    let from_id = match session.target_customer(bank, Msg::EnterSender.text(), renderer)? {
+        Some(id) => id,
+        None => return Ok(()),
+    };
+    let external_ref = read_checked(Msg::EnterExternalAccount.text(), renderer, validation::validate_external_ref)?;
+    let amount = read_amount(Msg::EnterAmountToSend.text(), renderer)?;
+    let details = match read_details(renderer, true)? {
+        Some(details) => details,
+        None => return Ok(()),
+    };
+    if !confirm_budget(bank, &from_id, amount, &details, renderer)? {
+        return Ok(());
+    }
+
+    let result = bank.write().external_transfer_with(&from_id, &external_ref, amount, details);
+    match result {
+        Ok(transaction) => renderer.success(
+            &Msg::ExternalTransferSent.fill(&[&amount.display(), &external_ref, &transaction.id]),
+            json!(transaction),
+        ),
+        Err(e) => renderer.failure(&e),
+    }
 
-    match bank.deposit(&customer_id, amount) {
-        Ok(new_balance) => {
-            println!("\n✅ Deposit successful!");
-            println!("💰 New Balance: ${:.2}\n", new_balance);
+    Ok(())
+}
+
+/// Prompts for a transfer recipient, trying the sender's payee nicknames
+/// before other customer references
+///
+/// # Returns
+/// * `Ok(None)` - If no recipient was chosen (reason already shown)
+fn read_recipient(
+    bank: &SharedBank,
+    from_id: &CustomerId,
+    renderer: &dyn Renderer,
+) -> io::Result<Option<CustomerId>> {
+    let reference = read_input(Msg::EnterRecipient.text())?;
+
+    let payee = {
+        let bank = bank.read();
+        let saved = bank
+            .get_customer(from_id)
+            .is_ok_and(|c| c.beneficiary(&reference).is_some());
+        saved.then(|| bank.resolve_beneficiary(from_id, &reference))
+    };
+    match payee {
+        Some(Ok(id)) => Ok(Some(id)),
+        Some(Err(e)) => {
+            renderer.failure(&e);
+            Ok(None)
         }
-        Err(e) => println!("\n❌ Error: {}\n", e),
+        None => choose_customer(bank, reference, renderer),
+    }
+}
+
+/// Prompts for an optional description and, for spending, an optional
+/// category and comma-separated tags
+///
+/// # Returns
+/// * `Ok(None)` - If an answer was invalid (error already shown)
+pub fn read_details(renderer: &dyn Renderer, spending: bool) -> io::Result<Option<TransactionDetails>> {
+    let description = read_input(Msg::DescriptionPrompt.text())?;
+    let (category, tags) = if spending {
+        (
+            read_input(Msg::CategoryPrompt.text())?,
+            read_input(Msg::TagsPrompt.text())?,
+        )
+    } else {
+        (String::new(), String::new())
+    };
+
+    let details = TransactionTags::parse(&category, &tags).and_then(|tags| {
+        let details = TransactionDetails::new().tags(tags);
+        match description.as_str() {
+            "" => Ok(details),
+            description => details.description(description),
+        }
+    });
+    match details {
+        Ok(details) => Ok(Some(details)),
+        Err(e) => {
+            renderer.failure(&e);
+            Ok(None)
+        }
+    }
+}
+
+/// Undoes the most recent deposit, withdrawal or transfer (staff only)
+///
+/// Posts reversing transactions rather than deleting anything.
+pub fn undo_last_operation(
+    bank: &SharedBank,
+    session: Option<&Session>,
+    renderer: &dyn Renderer,
+) -> io::Result<()> {
+    if authorize(session, STAFF, renderer).is_none() {
+        return Ok(());
+    }
+
+    renderer.section(Msg::UndoLastOperation.text());
+
+    let mut bank = bank.write();
+
+    match bank.undo_last() {
+        Ok(reversals) => {
+            let mut text = Msg::UndoDone.text().to_string();
+            for reversal in &reversals {
+                text.push_str(&format!("  • {}\n", reversal));
+            }
+            text.push_str(&Msg::UndoMore.fill(&[&bank.undo_depth()]));
+            renderer.success(&text, json!(reversals));
+        }
+        Err(e) => renderer.failure(&e),
     }
 
     Ok(())
 }
 
-/// Withdraws money from an account
-pub fn withdraw_money(bank: &Arc<Mutex<Bank>>) -> io::Result<()> {
-    println!("\n--- Withdraw Money ---");
+/// Reverses any transaction by its ID, recording a reason (admins only)
+///
+/// Shows the transaction and asks for confirmation first. A transfer
+/// between customers is reversed on both sides.
+pub fn reverse_transaction(
+    bank: &SharedBank,
+    session: Option<&Session>,
+    renderer: &dyn Renderer,
+) -> io::Result<()> {
+    if authorize(session, ADMIN_ONLY, renderer).is_none() {
+        return Ok(());
+    }
 
-    let customer_id = read_input("Enter customer ID: ")?;
-    let amount_str = read_input("Enter amount to withdraw: ")?;
+    renderer.section(Msg::ReverseTransaction.text());
 
-    let amount: f64 = match amount_str.parse() {
-        Ok(amt) => amt,
-        Err(_) => {
-            println!("\n❌ Invalid amount\n");
+    let transaction_id = TransactionId::from(read_input(Msg::EnterTransactionId.text())?);
+    let found = bank
+        .read()
+        .find_transaction(&transaction_id)
+        .map(|(owner, transaction)| Msg::TransactionOwner.fill(&[transaction, &owner.name, &owner.email]));
+    match found {
+        Some(text) => renderer.chrome(&text),
+        None => {
+            renderer.failure(&BankError::TransactionNotFound(transaction_id));
             return Ok(());
         }
+    }
+
+    let reason = read_checked(Msg::EnterReason.text(), renderer, validation::validate_reversal_reason)?;
+    if !read_yes(Msg::ConfirmReversal.text())? {
+        renderer.chrome(Msg::NothingReversed.text());
+        return Ok(());
+    }
+
+    let result = bank.write().reverse_transaction(&transaction_id, &reason);
+    match result {
+        Ok(reversals) => {
+            let mut text = Msg::ReversedDone.text().to_string();
+            for reversal in &reversals {
+                text.push_str(&format!("  • {}\n    ID: {}\n", reversal, reversal.id));
+            }
+            renderer.success(&text, json!(reversals));
+        }
+        Err(e) => renderer.failure(&e),
+    }
+
+    Ok(())
+}
+
+/// Shows a customer's account limits and lets staff change them
+///
+/// Customers can view their own limits but not change them.
+pub fn manage_limits(
+    bank: &SharedBank,
+    session: Option<&Session>,
+    renderer: &dyn Renderer,
+) -> io::Result<()> {
+    let session = match authorize(session, ANY_ROLE, renderer) {
+        Some(session) => session,
+        None => return Ok(()),
     };
 
-    let mut bank = bank.lock().unwrap();
+    renderer.section(Msg::AccountLimits.text());
 
-    match bank.withdraw(&customer_id, amount) {
-        Ok(new_balance) => {
-            println!("\n✅ Withdrawal successful!");
-            println!("💰 New Balance: ${:.2}\n", new_balance);
+    let customer_id = match session.target_customer(bank, Msg::EnterCustomer.text(), renderer)? {
+        Some(id) => id,
+        None => return Ok(()),
+    };
+    let current = match bank.read().get_customer(&customer_id).and_then(|c| c.get_account()) {
+        Ok(account) => account.limits.clone(),
+        Err(e) => {
+            renderer.failure(&e);
+            return Ok(());
         }
-        Err(e) => println!("\n❌ Error: {}\n", e),
+    };
+
+    if !session.allows(STAFF) {
+        renderer.success(&format!("\n{}\n", current), json!(current));
+        return Ok(());
+    }
+
+    renderer.chrome(&format!("\n{}\n", current));
+    renderer.chrome(Msg::LimitsHelp.text());
+
+    let max_withdrawal = match read_limit(Msg::EnterMaxWithdrawal.text(), current.max_withdrawal, renderer)? {
+        Some(value) => value,
+        None => return Ok(()),
+    };
+    let daily_withdrawal = match read_limit(Msg::EnterDailyWithdrawal.text(), current.daily_withdrawal, renderer)? {
+        Some(value) => value,
+        None => return Ok(()),
+    };
+    let daily_transfers = match read_limit(Msg::EnterDailyTransfers.text(), current.daily_transfers, renderer)? {
+        Some(value) => value,
+        None => return Ok(()),
+    };
+    let limits = AccountLimits { max_withdrawal, daily_withdrawal, daily_transfers };
+
+    match bank.write().set_limits(&customer_id, limits.clone()) {
+        Ok(()) => renderer.success(&Msg::LimitsUpdated.fill(&[&limits]), json!(limits)),
+        Err(e) => renderer.failure(&e),
     }
 
     Ok(())
 }
 
-/// Transfers money between accounts
-pub fn transfer_money(bank: &Arc<Mutex<Bank>>) -> io::Result<()> {
-    println!("\n--- Transfer Money ---");
+/// Prompts for one limit (see `parse_limit`)
+///
+/// # Returns
+/// * `Ok(None)` - If the input was not a valid value (error already shown)
+pub fn read_limit<T: FromStr>(
+    prompt: &str,
+    current: Option<T>,
+    renderer: &dyn Renderer,
+) -> io::Result<Option<Option<T>>> {
+    match parse_limit(&read_input(prompt)?, current) {
+        Ok(value) => Ok(Some(value)),
+        Err(e) => {
+            renderer.failure(&e);
+            Ok(None)
+        }
+    }
+}
+
+/// Shows and changes the balance alerts on a customer's account
+///
+/// Unlike limits, alerts are the customer's own to set.
+pub fn manage_alerts(
+    bank: &SharedBank,
+    session: Option<&Session>,
+    renderer: &dyn Renderer,
+) -> io::Result<()> {
+    let session = match authorize(session, ANY_ROLE, renderer) {
+        Some(session) => session,
+        None => return Ok(()),
+    };
 
-    let from_id = read_input("Enter sender customer ID: ")?;
-    let to_id = read_input("Enter recipient customer ID: ")?;
-    let amount_str = read_input("Enter amount to transfer: ")?;
+    renderer.section(Msg::BalanceAlerts.text());
 
-    let amount: f64 = match amount_str.parse() {
-        Ok(amt) => amt,
-        Err(_) => {
-            println!("\n❌ Invalid amount\n");
+    let customer_id = match session.target_customer(bank, Msg::EnterCustomer.text(), renderer)? {
+        Some(id) => id,
+        None => return Ok(()),
+    };
+    let current = match bank.read().get_customer(&customer_id).and_then(|c| c.get_account()) {
+        Ok(account) => account.alerts.clone(),
+        Err(e) => {
+            renderer.failure(&e);
             return Ok(());
         }
     };
 
-    let mut bank = bank.lock().unwrap();
+    renderer.chrome(&format!("\n{}\n", current));
+    renderer.chrome(Msg::AlertsHelp.text());
+
+    let low_balance = match read_limit(Msg::EnterLowBalanceAlert.text(), current.low_balance, renderer)? {
+        Some(value) => value,
+        None => return Ok(()),
+    };
+    let large_transaction = match read_limit(Msg::EnterLargeTransactionAlert.text(), current.large_transaction, renderer)? {
+        Some(value) => value,
+        None => return Ok(()),
+    };
+    let rules = AlertRules { low_balance, large_transaction };
+
+    match bank.write().set_alert_rules(&customer_id, rules.clone()) {
+        Ok(()) => renderer.success(&Msg::AlertsUpdated.fill(&[&rules]), json!(rules)),
+        Err(e) => renderer.failure(&e),
+    }
+
+    Ok(())
+}
+
+/// Freezes, unfreezes or closes a customer's account (admins only)
+pub fn manage_account_status(
+    bank: &SharedBank,
+    session: Option<&Session>,
+    renderer: &dyn Renderer,
+) -> io::Result<()> {
+    if authorize(session, ADMIN_ONLY, renderer).is_none() {
+        return Ok(());
+    }
+
+    renderer.section(Msg::FreezeCloseAccount.text());
+
+    let customer_id = match read_customer(bank, Msg::EnterCustomer.text(), renderer)? {
+        Some(id) => id,
+        None => return Ok(()),
+    };
+    renderer.chrome(Msg::AccountStatusActions.text());
+    let action = read_input(Msg::EnterAction.text())?;
+
+    // Ask where the money goes before closing a non-empty account
+    let transfer_to = if action == "3" {
+        let has_balance = bank
+            .read()
+            .get_customer(&customer_id)
+            .and_then(|c| c.get_account())
+            .map(|a| a.balance.is_positive())
+            .unwrap_or(false);
+        if has_balance {
+            match read_customer(bank, Msg::EnterBalanceDestination.text(), renderer)? {
+                Some(id) => Some(id),
+                None => return Ok(()),
+            }
+        } else {
+            None
+        }
+    } else {
+        None
+    };
+
+    let mut bank = bank.write();
+
+    let result = match action.as_str() {
+        "1" => bank.freeze_account(&customer_id).map(|()| (AccountStatus::Frozen, Money::ZERO)),
+        "2" => bank.unfreeze_account(&customer_id).map(|()| (AccountStatus::Active, Money::ZERO)),
+        "3" => bank
+            .close_account(&customer_id, transfer_to.as_ref())
+            .map(|moved| (AccountStatus::Closed, moved)),
+        _ => {
+            renderer.failure(&Msg::InvalidAction);
+            return Ok(());
+        }
+    };
 
-    match bank.transfer(&from_id, &to_id, amount) {
-        Ok(_) => {
-            println!("\n✅ Transfer successful!");
-            println!("💸 ${:.2} transferred\n", amount);
+    match result {
+        Ok((status, moved)) => {
+            let mut text = Msg::AccountNowStatus.fill(&[&status]);
+            if moved.is_positive() {
+                text.push_str(&Msg::TransferredOut.fill(&[&moved.display()]));
+            }
+            renderer.success(&text, json!({ "status": status, "transferred": moved }));
         }
-        Err(e) => println!("\n❌ Error: {}\n", e),
+        Err(e) => renderer.failure(&e),
     }
 
     Ok(())
 }
+
+/// Applies the bank's policies and reports the accounts flagged dormant
+///
+/// Prints nothing when no account became dormant.
+pub fn process_due_policies(bank: &SharedBank, renderer: &dyn Renderer) {
+    let run = bank.write().run_policies(Utc::now());
+    match run {
+        Ok(run) => {
+            for dormant in run.dormant {
+                let frozen = if run.frozen.contains(&dormant.customer_id) { Msg::AndFrozen.text() } else { "" };
+                let number = dormant.account_number.map(|n| n.to_string()).unwrap_or_default();
+                renderer.success(
+                    &Msg::FlaggedDormant.fill(&[&dormant.name, &number, &frozen, &dormant.inactive_days]),
+                    json!({ "dormant_account": dormant }),
+                );
+            }
+        }
+        Err(e) => renderer.failure(&Msg::PoliciesFailed.fill(&[&e])),
+    }
+}
@@ -0,0 +1,1152 @@
+//! Command-line arguments for non-interactive use
+//!
+//! Demonstrates: Declarative argument parsing with clap's derive API
+//! https://docs.rs/clap/latest/clap/_derive/index.html
+//!
+//! Running the binary without a subcommand starts the interactive menu;
+//! with a subcommand it performs that single operation and prints a JSON
+//! result, e.g. `banking-cli deposit --customer <id> --amount 50`.
+
+use clap::{Parser, Subcommand, ValueEnum};
+
+use crate::models::{CustomerId, TransactionId};
+use super::startup::StartupArgs;
+
+/// Top-level command-line arguments
+#[derive(Debug, Parser)]
+#[command(name = "banking-cli", version, about = "Rust Banking System")]
+pub struct Args {
+    /// Print results as JSON (menus and prompts go to stderr)
+    #[arg(long, global = true)]
+    pub json: bool,
+
+    /// Config file and per-run overrides of its settings
+    #[command(flatten)]
+    pub startup: StartupArgs,
+
+    /// Operation to perform; omit to start the interactive menu
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+/// Account types selectable from the command line
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum AccountKind {
+    Checking,
+    Savings,
+    Fixed,
+}
+
+/// Recurrence choices for scheduled transfers
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum FrequencyArg {
+    Daily,
+    Weekly,
+    Monthly,
+}
+
+/// Period lengths of the cash-flow report
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum CashFlowPeriodArg {
+    Daily,
+    Weekly,
+}
+
+/// Domain event kinds selectable from the command line
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum EventKindArg {
+    CustomerRegistered,
+    TransactionPosted,
+    TransferCompleted,
+}
+
+/// Export file formats
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum ExportFormat {
+    Csv,
+    Json,
+}
+
+/// Formats the whole bank can be written in
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum DataFormatArg {
+    Json,
+    Yaml,
+    Toml,
+    Msgpack,
+}
+
+/// Optional paging for listing commands
+///
+/// With neither option the whole listing is printed; with either, a page
+/// object (`items`, `offset`, `limit`, `total`) is printed instead.
+#[derive(Debug, Clone, Copy, clap::Args)]
+pub struct PageArgs {
+    /// Number of items to skip
+    #[arg(long)]
+    pub offset: Option<usize>,
+    /// Number of items to show
+    #[arg(long)]
+    pub limit: Option<usize>,
+}
+
+impl PageArgs {
+    /// The requested (offset, limit), or `None` for the whole listing
+    pub fn window(self) -> Option<(usize, usize)> {
+        match (self.offset, self.limit) {
+            (None, None) => None,
+            (offset, limit) => Some((offset.unwrap_or(0), limit.unwrap_or(usize::MAX))),
+        }
+    }
+}
+
+/// One subcommand per interactive menu operation
+///
+/// Amounts are decimal strings (e.g. `12.50`) and dates use `YYYY-MM-DD`.
+/// Customers can be given by ID, email, account ID or the start of their
+/// ID (see `Bank::resolve_customer`).
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Register a new customer
+    Register {
+        #[arg(long)]
+        name: String,
+        #[arg(long)]
+        email: String,
+        /// PIN for the new customer (can also be set later with set-pin)
+        #[arg(long)]
+        pin: Option<String>,
+    },
+    /// Create an account for a customer
+    CreateAccount {
+        #[arg(long)]
+        customer: CustomerId,
+        #[arg(long, default_value = "0")]
+        deposit: String,
+        #[arg(long = "type", value_enum, default_value = "checking")]
+        account_type: AccountKind,
+        /// Term for fixed deposits
+        #[arg(long, default_value_t = 12)]
+        term_months: u32,
+    },
+    /// Deposit money
+    Deposit {
+        #[arg(long)]
+        customer: CustomerId,
+        #[arg(long)]
+        amount: String,
+        /// The customer's PIN
+        #[arg(long)]
+        pin: String,
+        /// Free-text memo, e.g. salary
+        #[arg(long)]
+        description: Option<String>,
+    },
+    /// Withdraw money
+    Withdraw {
+        #[arg(long)]
+        customer: CustomerId,
+        #[arg(long)]
+        amount: String,
+        /// The customer's PIN
+        #[arg(long)]
+        pin: String,
+        /// Free-text memo
+        #[arg(long)]
+        description: Option<String>,
+        /// Spending category, e.g. groceries
+        #[arg(long)]
+        category: Option<String>,
+        /// Label for the withdrawal; repeat to add several
+        #[arg(long = "tag")]
+        tags: Vec<String>,
+    },
+    /// Hold money for a withdrawal without posting it yet
+    Authorize {
+        #[arg(long)]
+        customer: CustomerId,
+        #[arg(long)]
+        amount: String,
+        /// The customer's PIN
+        #[arg(long)]
+        pin: String,
+        /// Free-text memo, kept when the withdrawal is captured
+        #[arg(long)]
+        description: Option<String>,
+        /// Spending category, e.g. groceries
+        #[arg(long)]
+        category: Option<String>,
+        /// Label for the withdrawal; repeat to add several
+        #[arg(long = "tag")]
+        tags: Vec<String>,
+    },
+    /// Post an authorized withdrawal
+    Capture {
+        #[arg(long)]
+        authorization: TransactionId,
+    },
+    /// Cancel an authorized withdrawal and release the money
+    Void {
+        #[arg(long)]
+        authorization: TransactionId,
+    },
+    /// List the holds on a customer's account
+    Holds {
+        #[arg(long)]
+        customer: CustomerId,
+    },
+    /// Set money aside on an account without posting a transaction
+    PlaceHold {
+        #[arg(long)]
+        customer: CustomerId,
+        #[arg(long)]
+        amount: String,
+        /// Why the money is held
+        #[arg(long)]
+        reason: String,
+        /// Date (YYYY-MM-DD) the hold lapses at the start of; kept until released if omitted
+        #[arg(long)]
+        until: Option<String>,
+    },
+    /// Release a hold, making its money available again
+    ReleaseHold {
+        #[arg(long)]
+        hold: String,
+    },
+    /// Withdraw the held amount and remove the hold
+    CaptureHold {
+        #[arg(long)]
+        hold: String,
+    },
+    /// Transfer money between customers
+    Transfer {
+        #[arg(long)]
+        from: CustomerId,
+        #[arg(long, required_unless_present = "to_payee", conflicts_with = "to_payee")]
+        to: Option<CustomerId>,
+        /// Nickname of one of the sender's saved payees, instead of --to
+        #[arg(long)]
+        to_payee: Option<String>,
+        #[arg(long)]
+        amount: String,
+        /// The sender's PIN
+        #[arg(long)]
+        pin: String,
+        /// Free-text memo, shown to both sides
+        #[arg(long)]
+        description: Option<String>,
+        /// Spending category for the sender's side, e.g. rent
+        #[arg(long)]
+        category: Option<String>,
+        /// Label for the sender's side; repeat to add several
+        #[arg(long = "tag")]
+        tags: Vec<String>,
+    },
+    /// Send money to an account at another bank; it stays pending until settled
+    ExternalTransfer {
+        #[arg(long)]
+        from: CustomerId,
+        /// Receiving account, e.g. an IBAN
+        #[arg(long)]
+        to_external: String,
+        #[arg(long)]
+        amount: String,
+        /// The sender's PIN
+        #[arg(long)]
+        pin: String,
+        #[arg(long)]
+        description: Option<String>,
+        #[arg(long)]
+        category: Option<String>,
+        /// Label for the transfer; repeat to add several
+        #[arg(long = "tag")]
+        tags: Vec<String>,
+    },
+    /// List external transfers waiting for the other bank, oldest first
+    PendingTransfers,
+    /// Record that the other bank accepted a pending external transfer
+    Settle {
+        #[arg(long)]
+        transaction: TransactionId,
+    },
+    /// Record that the other bank refused a pending external transfer and refund it
+    Reject {
+        #[arg(long)]
+        transaction: TransactionId,
+        /// Kept as the refund's description
+        #[arg(long)]
+        reason: Option<String>,
+    },
+    /// Pay a cheque into an account; the money is available once it clears
+    DepositCheque {
+        #[arg(long)]
+        customer: CustomerId,
+        #[arg(long)]
+        amount: String,
+        /// Serial number printed on the cheque
+        #[arg(long)]
+        number: String,
+        /// The customer who wrote the cheque, if they bank here
+        #[arg(long)]
+        drawn_on: Option<CustomerId>,
+        /// The depositing customer's PIN
+        #[arg(long)]
+        pin: String,
+    },
+    /// List cheques waiting to clear, soonest first
+    Cheques,
+    /// Clear or bounce every cheque whose clearing period is over
+    ClearCheques,
+    /// Bounce a pending cheque now, reversing its deposit and charging the fee
+    ReturnCheque {
+        #[arg(long)]
+        cheque: String,
+        /// Why it was returned
+        #[arg(long)]
+        reason: Option<String>,
+    },
+    /// Change the cheque clearing period and bounce fee
+    SetChequePolicy {
+        /// Days from deposit until a cheque clears
+        #[arg(long)]
+        clearing_days: Option<u32>,
+        /// Charged when a cheque bounces; 0 for none
+        #[arg(long)]
+        bounce_fee: Option<String>,
+    },
+    /// Change when accounts without activity are flagged dormant
+    SetDormancyPolicy {
+        /// Days without activity; 0 turns the check off
+        #[arg(long)]
+        days: Option<u32>,
+        /// Freeze accounts as they are flagged
+        #[arg(long)]
+        freeze: Option<bool>,
+    },
+    /// Flag dormant accounts now, freezing them if the policy says so
+    RunPolicies,
+    /// Change the rules that flag suspicious transactions for review
+    SetFraudRules {
+        /// Flag a withdrawal that makes more than this many within the
+        /// window; 0 turns the rule off
+        #[arg(long)]
+        max_withdrawals: Option<u32>,
+        /// Minutes the withdrawal count looks back over (default 60)
+        #[arg(long)]
+        window_minutes: Option<u32>,
+        /// Flag transfers above this amount to a new payee; "none" turns
+        /// the rule off
+        #[arg(long)]
+        new_payee_amount: Option<String>,
+    },
+    /// List flagged transactions waiting for review, oldest first
+    ReviewQueue {
+        /// Include transactions already approved or reversed
+        #[arg(long)]
+        all: bool,
+    },
+    /// Let a flagged transaction stand
+    ApproveFlag {
+        #[arg(long)]
+        transaction: TransactionId,
+        /// The staff member reviewing it
+        #[arg(long)]
+        reviewer: String,
+    },
+    /// Reverse any transaction by ID, both sides for a transfer
+    ReverseTransaction {
+        #[arg(long)]
+        transaction: TransactionId,
+        /// Why it is being reversed; shown on statements
+        #[arg(long)]
+        reason: String,
+    },
+    /// Reverse a flagged transaction, both sides for a transfer
+    ReverseFlag {
+        #[arg(long)]
+        transaction: TransactionId,
+        /// The staff member reviewing it
+        #[arg(long)]
+        reviewer: String,
+    },
+    /// Show the cash in the bank's vault
+    Vault,
+    /// Bring cash into the bank's vault
+    VaultTopUp {
+        #[arg(long)]
+        amount: String,
+    },
+    /// Take cash out of the bank's vault
+    VaultWithdraw {
+        #[arg(long)]
+        amount: String,
+    },
+    /// List a customer's debit cards
+    Cards {
+        #[arg(long)]
+        customer: CustomerId,
+    },
+    /// Issue a debit card for a customer's account
+    IssueCard {
+        #[arg(long)]
+        customer: CustomerId,
+        /// Largest single purchase
+        #[arg(long)]
+        per_purchase: Option<String>,
+        /// Most spent with the card per day
+        #[arg(long)]
+        daily: Option<String>,
+    },
+    /// Stop a card, e.g. because it was lost
+    BlockCard {
+        #[arg(long)]
+        card: String,
+    },
+    /// Retire a card and issue a new one with the same limits
+    ReplaceCard {
+        #[arg(long)]
+        card: String,
+    },
+    /// Change a card's limits; omitted limits are kept, 'none' removes one
+    SetCardLimits {
+        #[arg(long)]
+        card: String,
+        #[arg(long)]
+        per_purchase: Option<String>,
+        #[arg(long)]
+        daily: Option<String>,
+    },
+    /// Pay with a debit card; posts a withdrawal that records the card
+    CardPurchase {
+        #[arg(long)]
+        card: String,
+        #[arg(long)]
+        amount: String,
+        /// The card holder's PIN
+        #[arg(long)]
+        pin: String,
+        /// Usually the merchant's name
+        #[arg(long)]
+        description: Option<String>,
+        #[arg(long)]
+        category: Option<String>,
+        /// Label for the purchase; repeat to add several
+        #[arg(long = "tag")]
+        tags: Vec<String>,
+    },
+    /// List a customer's savings goals
+    Envelopes {
+        #[arg(long)]
+        customer: CustomerId,
+    },
+    /// Start a savings goal
+    CreateEnvelope {
+        #[arg(long)]
+        customer: CustomerId,
+        #[arg(long)]
+        name: String,
+        /// Amount to save towards
+        #[arg(long)]
+        target: String,
+        /// Keep withdrawals away from the goal's money
+        #[arg(long)]
+        locked: bool,
+    },
+    /// Set free money aside in a savings goal
+    Allocate {
+        #[arg(long)]
+        customer: CustomerId,
+        /// Goal name
+        #[arg(long)]
+        envelope: String,
+        #[arg(long)]
+        amount: String,
+    },
+    /// Move money out of a savings goal
+    Release {
+        #[arg(long)]
+        customer: CustomerId,
+        #[arg(long)]
+        envelope: String,
+        #[arg(long)]
+        amount: String,
+    },
+    /// Deposit money straight into a savings goal
+    DepositToEnvelope {
+        #[arg(long)]
+        customer: CustomerId,
+        #[arg(long)]
+        envelope: String,
+        #[arg(long)]
+        amount: String,
+        /// The customer's PIN
+        #[arg(long)]
+        pin: String,
+        #[arg(long)]
+        description: Option<String>,
+    },
+    /// Stop withdrawals from using a savings goal's money
+    LockEnvelope {
+        #[arg(long)]
+        customer: CustomerId,
+        #[arg(long)]
+        envelope: String,
+    },
+    /// Let withdrawals use a savings goal's money again
+    UnlockEnvelope {
+        #[arg(long)]
+        customer: CustomerId,
+        #[arg(long)]
+        envelope: String,
+    },
+    /// Delete a savings goal; its money becomes free to spend
+    RemoveEnvelope {
+        #[arg(long)]
+        customer: CustomerId,
+        #[arg(long)]
+        envelope: String,
+    },
+    /// Show this month's spending against a customer's budgets
+    Budgets {
+        #[arg(long)]
+        customer: CustomerId,
+    },
+    /// Set a category's monthly budget, replacing any earlier one
+    SetBudget {
+        #[arg(long)]
+        customer: CustomerId,
+        /// Spending category, e.g. groceries
+        #[arg(long)]
+        category: String,
+        /// Most to spend in the category per month
+        #[arg(long)]
+        limit: String,
+    },
+    /// Remove a category's budget
+    RemoveBudget {
+        #[arg(long)]
+        customer: CustomerId,
+        #[arg(long)]
+        category: String,
+    },
+    /// List the merchants customers can pay bills to
+    Merchants,
+    /// Register a merchant
+    AddMerchant {
+        #[arg(long)]
+        name: String,
+        /// utility, telecom, insurance or other
+        #[arg(long, default_value = "other")]
+        category: String,
+    },
+    /// Remove a merchant that no autopay rule pays
+    RemoveMerchant {
+        /// Merchant ID or name
+        #[arg(long)]
+        merchant: String,
+    },
+    /// Pay a bill to a merchant
+    PayBill {
+        #[arg(long)]
+        customer: CustomerId,
+        /// Merchant ID or name
+        #[arg(long)]
+        merchant: String,
+        #[arg(long)]
+        amount: String,
+        /// The customer's reference at the merchant, e.g. their account number there
+        #[arg(long)]
+        reference: String,
+        /// The paying customer's PIN
+        #[arg(long)]
+        pin: String,
+    },
+    /// List autopay rules, soonest first
+    Autopays {
+        /// Only this customer's rules
+        #[arg(long)]
+        customer: Option<CustomerId>,
+    },
+    /// Pay a bill automatically on a schedule
+    AddAutopay {
+        #[arg(long)]
+        customer: CustomerId,
+        /// Merchant ID or name
+        #[arg(long)]
+        merchant: String,
+        #[arg(long)]
+        amount: String,
+        #[arg(long)]
+        reference: String,
+        #[arg(long, value_enum)]
+        frequency: FrequencyArg,
+        /// First payment date (defaults to now)
+        #[arg(long)]
+        start: Option<String>,
+    },
+    /// Cancel an autopay rule
+    CancelAutopay {
+        #[arg(long)]
+        id: String,
+    },
+    /// Make every autopay payment that is due
+    RunAutopays,
+    /// List a customer's term deposits
+    TermDeposits {
+        #[arg(long)]
+        customer: CustomerId,
+    },
+    /// Lock money away in a term deposit at a fixed rate
+    OpenTermDeposit {
+        #[arg(long)]
+        customer: CustomerId,
+        #[arg(long)]
+        amount: String,
+        /// Yearly interest rate in percent, e.g. 4.5
+        #[arg(long)]
+        rate: String,
+        /// Length of the term in months
+        #[arg(long)]
+        months: u32,
+        /// The customer's PIN
+        #[arg(long)]
+        pin: String,
+    },
+    /// Take a term deposit out before it matures, paying the penalty
+    WithdrawTermDeposit {
+        #[arg(long)]
+        customer: CustomerId,
+        /// Term deposit ID
+        #[arg(long)]
+        deposit: String,
+        /// The customer's PIN
+        #[arg(long)]
+        pin: String,
+    },
+    /// Pay back every term deposit that has matured
+    ProcessMaturities,
+    /// Set the first PIN for a customer
+    SetPin {
+        #[arg(long)]
+        customer: CustomerId,
+        #[arg(long)]
+        pin: String,
+    },
+    /// Change a customer's PIN
+    ChangePin {
+        #[arg(long)]
+        customer: CustomerId,
+        #[arg(long)]
+        current_pin: String,
+        #[arg(long)]
+        new_pin: String,
+    },
+    /// List a customer's saved payees
+    Payees {
+        #[arg(long)]
+        customer: CustomerId,
+    },
+    /// Save a payee under a nickname
+    AddPayee {
+        #[arg(long)]
+        customer: CustomerId,
+        #[arg(long)]
+        nickname: String,
+        /// The payee's customer ID, email, account number or account ID
+        #[arg(long)]
+        payee: String,
+    },
+    /// Delete a saved payee
+    RemovePayee {
+        #[arg(long)]
+        customer: CustomerId,
+        #[arg(long)]
+        nickname: String,
+    },
+    /// Show the withdrawal and transfer limits on a customer's account
+    Limits {
+        #[arg(long)]
+        customer: CustomerId,
+    },
+    /// Change account limits; omitted options keep their value, `none` removes a limit
+    SetLimits {
+        #[arg(long)]
+        customer: CustomerId,
+        /// Largest single withdrawal or transfer
+        #[arg(long)]
+        max_withdrawal: Option<String>,
+        /// Most money withdrawn or transferred out per day
+        #[arg(long)]
+        daily_withdrawal: Option<String>,
+        /// Most outgoing transfers per day
+        #[arg(long)]
+        daily_transfers: Option<String>,
+    },
+    /// Show the balance alerts set on a customer's account
+    Alerts {
+        #[arg(long)]
+        customer: CustomerId,
+    },
+    /// Change balance alerts; omitted options keep their value, `none` turns an alert off
+    SetAlerts {
+        #[arg(long)]
+        customer: CustomerId,
+        /// Alert when the balance drops below this amount
+        #[arg(long)]
+        low_balance: Option<String>,
+        /// Alert on any single transaction above this amount
+        #[arg(long)]
+        large_transaction: Option<String>,
+    },
+    /// List the webhooks domain events are sent to
+    Webhooks,
+    /// Register a URL to POST domain events to
+    AddWebhook {
+        /// An http:// URL
+        #[arg(long)]
+        url: String,
+        /// Only send this kind of event (repeatable); every kind if omitted
+        #[arg(long = "event", value_enum)]
+        events: Vec<EventKindArg>,
+    },
+    /// Unregister a webhook
+    RemoveWebhook {
+        #[arg(long)]
+        id: String,
+    },
+    /// Freeze an account so no money can leave it
+    Freeze {
+        #[arg(long)]
+        customer: CustomerId,
+    },
+    /// Lift a freeze
+    Unfreeze {
+        #[arg(long)]
+        customer: CustomerId,
+    },
+    /// Close an account; a remaining balance must be transferred out
+    CloseAccount {
+        #[arg(long)]
+        customer: CustomerId,
+        /// Customer who receives the remaining balance
+        #[arg(long)]
+        transfer_to: Option<CustomerId>,
+    },
+    /// Edit a customer's profile; omitted options keep their value, `none` removes an optional one
+    UpdateCustomer {
+        #[arg(long)]
+        customer: CustomerId,
+        #[arg(long)]
+        name: Option<String>,
+        #[arg(long)]
+        email: Option<String>,
+        #[arg(long)]
+        phone: Option<String>,
+        #[arg(long)]
+        address: Option<String>,
+        /// Date of birth (YYYY-MM-DD)
+        #[arg(long)]
+        date_of_birth: Option<String>,
+    },
+    /// Deactivate a customer so they cannot log in or move money
+    DeactivateCustomer {
+        #[arg(long)]
+        customer: CustomerId,
+    },
+    /// Let a deactivated customer use the bank again
+    ReactivateCustomer {
+        #[arg(long)]
+        customer: CustomerId,
+    },
+    /// Delete a customer; their account must be empty
+    DeleteCustomer {
+        #[arg(long)]
+        customer: CustomerId,
+    },
+    /// Show a customer and their account
+    Account {
+        #[arg(long)]
+        customer: CustomerId,
+    },
+    /// Show a customer's transaction history
+    History {
+        #[arg(long)]
+        customer: CustomerId,
+        #[command(flatten)]
+        page: PageArgs,
+        /// Show only this many of the most recent transactions
+        #[arg(long, conflicts_with_all = ["offset", "limit"])]
+        last: Option<usize>,
+    },
+    /// List every account's transactions, oldest first
+    Ledger {
+        /// First day to include (YYYY-MM-DD)
+        #[arg(long)]
+        from: Option<String>,
+        /// Last day to include (YYYY-MM-DD)
+        #[arg(long)]
+        to: Option<String>,
+        #[command(flatten)]
+        page: PageArgs,
+    },
+    /// Find any transaction by its ID, with the customer it belongs to
+    LookupTransaction {
+        /// Transaction ID
+        #[arg(long)]
+        id: TransactionId,
+    },
+    /// Show the receipt of any transaction, e.g. to print it again
+    Receipt {
+        /// Transaction ID
+        #[arg(long)]
+        id: TransactionId,
+    },
+    /// Search a customer's transactions; every filter is optional
+    SearchTransactions {
+        #[arg(long)]
+        customer: CustomerId,
+        /// First day to include (YYYY-MM-DD)
+        #[arg(long)]
+        from: Option<String>,
+        /// Last day to include (YYYY-MM-DD)
+        #[arg(long)]
+        to: Option<String>,
+        #[arg(long)]
+        min_amount: Option<String>,
+        #[arg(long)]
+        max_amount: Option<String>,
+        /// deposit, withdrawal, transfer-out, transfer-in, reversal, external-transfer, fee or bill-payment; repeat to allow several
+        #[arg(long = "type")]
+        kinds: Vec<String>,
+        /// Other account of a transfer (a prefix is enough)
+        #[arg(long)]
+        counterparty: Option<String>,
+        #[arg(long)]
+        tag: Option<String>,
+    },
+    /// Set the category and tags of a posted transaction, replacing earlier ones
+    TagTransaction {
+        #[arg(long)]
+        customer: CustomerId,
+        /// Transaction ID
+        #[arg(long)]
+        transaction: TransactionId,
+        #[arg(long)]
+        category: Option<String>,
+        /// Repeat to add several
+        #[arg(long = "tag")]
+        tags: Vec<String>,
+    },
+    /// Show a customer's spending per category
+    Categories {
+        #[arg(long)]
+        customer: CustomerId,
+    },
+    /// List all customers
+    Customers {
+        #[command(flatten)]
+        page: PageArgs,
+    },
+    /// Search customers by name
+    Search {
+        #[arg(long)]
+        query: String,
+    },
+    /// Show bank statistics
+    Stats,
+    /// Show every account in the bank's books and check that they balance
+    TrialBalance {
+        /// Write the trial balance to this CSV file instead of printing it
+        #[arg(long)]
+        output: Option<String>,
+    },
+    /// Show income, expenses and net income over a period
+    IncomeStatement {
+        /// First day, YYYY-MM-DD; from the start if not given
+        #[arg(long)]
+        from: Option<String>,
+        /// Last day (inclusive), YYYY-MM-DD; up to now if not given
+        #[arg(long)]
+        to: Option<String>,
+        /// Write the statement to this CSV file instead of printing it
+        #[arg(long)]
+        output: Option<String>,
+    },
+    /// Show the chart of accounts and the mapping rules
+    ChartOfAccounts,
+    /// Change an account's code, name or class in the chart of accounts
+    SetChartAccount {
+        /// The account's current code or name
+        #[arg(long)]
+        account: String,
+        #[arg(long)]
+        code: Option<String>,
+        #[arg(long)]
+        name: Option<String>,
+        /// asset, liability, equity, income or expense
+        #[arg(long)]
+        class: Option<String>,
+    },
+    /// Choose the account a type of transaction posts against
+    MapTransaction {
+        /// Transaction type, e.g. fee or bill-payment
+        #[arg(long)]
+        kind: String,
+        /// Code or name of the account
+        #[arg(long)]
+        account: String,
+    },
+    /// Show money into and out of the bank per day or week
+    CashFlow {
+        #[arg(long, value_enum, default_value = "daily")]
+        period: CashFlowPeriodArg,
+        /// First day to include (YYYY-MM-DD)
+        #[arg(long)]
+        from: Option<String>,
+        /// Last day to include (YYYY-MM-DD)
+        #[arg(long)]
+        to: Option<String>,
+    },
+    /// List the largest transactions, largest first
+    LargestTransactions {
+        #[arg(long, default_value_t = 10)]
+        count: usize,
+    },
+    /// List the accounts with the most transactions in recent days
+    MostActive {
+        #[arg(long, default_value_t = 10)]
+        count: usize,
+        /// How many days back to count
+        #[arg(long, default_value_t = 30)]
+        days: u32,
+    },
+    /// List open accounts without activity for some days
+    Dormant {
+        /// Days without activity
+        #[arg(long, default_value_t = 90)]
+        days: u32,
+    },
+    /// Check balances and transfer records for inconsistencies
+    Audit,
+    /// Create a scheduled transfer
+    ScheduleCreate {
+        #[arg(long)]
+        from: CustomerId,
+        #[arg(long)]
+        to: CustomerId,
+        #[arg(long)]
+        amount: String,
+        #[arg(long, value_enum)]
+        frequency: FrequencyArg,
+        /// First run date (defaults to now)
+        #[arg(long)]
+        start: Option<String>,
+    },
+    /// List scheduled transfers
+    Schedules,
+    /// Cancel a scheduled transfer
+    ScheduleCancel {
+        #[arg(long)]
+        id: String,
+    },
+    /// Restore a rotated backup of the data file
+    RestoreBackup {
+        #[arg(long)]
+        index: usize,
+    },
+    /// Export a customer's transactions
+    Export {
+        #[arg(long)]
+        customer: CustomerId,
+        #[arg(long, value_enum, default_value = "csv")]
+        format: ExportFormat,
+        /// Output file (defaults to transactions.<format>)
+        #[arg(long)]
+        output: Option<String>,
+    },
+    /// Import transactions from a CSV file
+    Import {
+        #[arg(long)]
+        customer: CustomerId,
+        #[arg(long)]
+        file: String,
+    },
+    /// Register customers with checking accounts from a CSV file of
+    /// name,email,initial_deposit rows
+    ImportCustomers {
+        #[arg(long)]
+        file: String,
+    },
+    /// Write a copy of the whole bank to a file in JSON, YAML, TOML or
+    /// MessagePack
+    ExportBank {
+        #[arg(long)]
+        output: String,
+        /// Format to write; by default it follows the output file's
+        /// extension (.yaml/.yml, .toml, .msgpack/.mpk, otherwise JSON)
+        #[arg(long, value_enum)]
+        format: Option<DataFormatArg>,
+    },
+    /// Compare two saved data files: customers added or removed, balance
+    /// changes and new transactions
+    Diff {
+        /// The earlier file, e.g. a backup taken before a batch run
+        #[arg(long)]
+        before: String,
+        /// The later file
+        #[arg(long)]
+        after: String,
+    },
+    /// Apply deposits, withdrawals and transfers from a CSV or JSON file
+    Batch {
+        #[arg(long)]
+        file: String,
+        /// Apply nothing unless every operation succeeds
+        #[arg(long)]
+        all_or_nothing: bool,
+    },
+    /// Add demo customers with randomized transaction histories
+    Seed {
+        /// Number of customers to generate
+        #[arg(long, default_value_t = 25)]
+        customers: usize,
+        /// First simulated day (YYYY-MM-DD); defaults to 90 days ago
+        #[arg(long)]
+        from: Option<String>,
+        /// Last simulated day (YYYY-MM-DD); defaults to today
+        #[arg(long)]
+        to: Option<String>,
+        /// Random seed, for the same data on every run
+        #[arg(long)]
+        rng_seed: Option<u64>,
+    },
+    /// Move transactions from before a date into the archive next to the
+    /// data file
+    Archive {
+        /// First day whose transactions stay live (YYYY-MM-DD)
+        #[arg(long)]
+        before: String,
+    },
+    /// Generate an account statement
+    Statement {
+        #[arg(long)]
+        customer: CustomerId,
+        #[arg(long)]
+        from: String,
+        /// Last day of the period (inclusive)
+        #[arg(long)]
+        to: String,
+    },
+    /// Summarize a customer's activity in one month
+    MonthlySummary {
+        #[arg(long)]
+        customer: CustomerId,
+        /// Month as YYYY-MM
+        #[arg(long)]
+        month: String,
+        /// Write the summary to this CSV file instead of printing it
+        #[arg(long)]
+        output: Option<String>,
+    },
+    /// Type commands at a prompt, with tab completion and history
+    Shell,
+    /// Run the shell commands in a file, one per line
+    Run {
+        /// Script to run; `#` starts a comment line
+        script: String,
+        /// Run the remaining lines after a command fails
+        #[arg(long)]
+        keep_going: bool,
+    },
+    /// Serve the bank over HTTP until stopped
+    #[cfg(feature = "server")]
+    Serve {
+        #[arg(long, default_value_t = 8080)]
+        port: u16,
+    },
+    /// Serve the bank over gRPC until stopped
+    #[cfg(feature = "grpc")]
+    Grpc {
+        #[arg(long, default_value_t = 50051)]
+        port: u16,
+    },
+    /// Browse customers and transactions in a full-screen terminal UI
+    #[cfg(feature = "tui")]
+    Tui,
+}
+
+impl Command {
+    /// The customer references this command carries, for resolving them
+    /// into customer IDs before it runs
+    pub fn customer_refs_mut(&mut self) -> Vec<&mut CustomerId> {
+        match self {
+            Command::CreateAccount { customer, .. }
+            | Command::Deposit { customer, .. }
+            | Command::Withdraw { customer, .. }
+            | Command::Authorize { customer, .. }
+            | Command::Holds { customer }
+            | Command::Cards { customer }
+            | Command::IssueCard { customer, .. }
+            | Command::PayBill { customer, .. }
+            | Command::Envelopes { customer }
+            | Command::CreateEnvelope { customer, .. }
+            | Command::Allocate { customer, .. }
+            | Command::Release { customer, .. }
+            | Command::DepositToEnvelope { customer, .. }
+            | Command::LockEnvelope { customer, .. }
+            | Command::UnlockEnvelope { customer, .. }
+            | Command::RemoveEnvelope { customer, .. }
+            | Command::Budgets { customer }
+            | Command::SetBudget { customer, .. }
+            | Command::RemoveBudget { customer, .. }
+            | Command::TermDeposits { customer }
+            | Command::OpenTermDeposit { customer, .. }
+            | Command::WithdrawTermDeposit { customer, .. }
+            | Command::AddAutopay { customer, .. }
+            | Command::PlaceHold { customer, .. }
+            | Command::ExternalTransfer { from: customer, .. }
+            | Command::SetPin { customer, .. }
+            | Command::ChangePin { customer, .. }
+            | Command::Payees { customer }
+            | Command::AddPayee { customer, .. }
+            | Command::RemovePayee { customer, .. }
+            | Command::Limits { customer }
+            | Command::SetLimits { customer, .. }
+            | Command::Alerts { customer }
+            | Command::SetAlerts { customer, .. }
+            | Command::Freeze { customer }
+            | Command::Unfreeze { customer }
+            | Command::UpdateCustomer { customer, .. }
+            | Command::DeactivateCustomer { customer }
+            | Command::ReactivateCustomer { customer }
+            | Command::DeleteCustomer { customer }
+            | Command::Account { customer }
+            | Command::History { customer, .. }
+            | Command::SearchTransactions { customer, .. }
+            | Command::TagTransaction { customer, .. }
+            | Command::Categories { customer }
+            | Command::Export { customer, .. }
+            | Command::Import { customer, .. }
+            | Command::Statement { customer, .. }
+            | Command::MonthlySummary { customer, .. } => vec![customer],
+            Command::CloseAccount { customer, transfer_to } => {
+                let mut refs = vec![customer];
+                refs.extend(transfer_to.as_mut());
+                refs
+            }
+            Command::Transfer { from, to, .. } => {
+                let mut refs = vec![from];
+                refs.extend(to.as_mut());
+                refs
+            }
+            Command::ScheduleCreate { from, to, .. } => vec![from, to],
+            Command::Autopays { customer } => customer.as_mut().into_iter().collect(),
+            Command::DepositCheque { customer, drawn_on, .. } => {
+                let mut refs = vec![customer];
+                refs.extend(drawn_on.as_mut());
+                refs
+            }
+            _ => Vec::new(),
+        }
+    }
+}
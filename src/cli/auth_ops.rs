@@ -0,0 +1,208 @@
+//! Login-related CLI operations
+//!
+//! Demonstrates: Session state kept in the CLI, hidden input for secrets
+
+use std::io;
+
+use serde_json::json;
+
+use crate::bank::SharedBank;
+use crate::models::{Credentials, CustomerId, Role};
+use super::i18n::Msg;
+use super::render::Renderer;
+use super::session::{authorize, Session, ADMIN_ONLY, ANY_ROLE};
+use super::utils::{read_customer, read_input, read_passphrase};
+
+/// Logs a customer or staff member in
+///
+/// # Returns
+/// * `Ok(Some(session))` - The user is now logged in
+/// * `Ok(None)` - Login failed (error already shown)
+pub fn login(bank: &SharedBank, renderer: &dyn Renderer) -> io::Result<Option<Session>> {
+    renderer.section(Msg::Login.text());
+
+    renderer.chrome(Msg::LogInAs.text());
+    match read_input(Msg::EnterChoiceDefaultOne.text())?.as_str() {
+        "" | "1" => customer_login(bank, renderer),
+        "2" => staff_login(bank, renderer),
+        _ => {
+            renderer.failure(&Msg::InvalidChoice);
+            Ok(None)
+        }
+    }
+}
+
+/// Logs a customer in with their PIN
+///
+/// Customers who have no PIN yet (e.g. registered before PINs existed)
+/// choose one here.
+fn customer_login(bank: &SharedBank, renderer: &dyn Renderer) -> io::Result<Option<Session>> {
+    let customer_id = match read_customer(bank, Msg::EnterCustomer.text(), renderer)? {
+        Some(id) => id,
+        None => return Ok(None),
+    };
+
+    let has_pin = match bank.read().get_customer(&customer_id) {
+        Ok(customer) => customer.has_pin(),
+        Err(e) => {
+            renderer.failure(&e);
+            return Ok(None);
+        }
+    };
+
+    let result = if has_pin {
+        let pin = read_passphrase(Msg::EnterPin.text())?;
+        bank.write().authenticate(&customer_id, &pin)
+    } else {
+        renderer.chrome(Msg::ChoosePinNow.text());
+        match read_new_pin(renderer)? {
+            Some(pin) => bank.write().set_pin(&customer_id, &pin),
+            None => return Ok(None),
+        }
+    };
+
+    match result {
+        Ok(()) => {
+            let name = bank.read().get_customer(&customer_id).map(|c| c.name.clone());
+            renderer.success(
+                &Msg::Welcome.fill(&[&name.unwrap_or_default()]),
+                json!({ "customer_id": customer_id, "role": Role::Customer }),
+            );
+            Ok(Some(Session::customer(customer_id)))
+        }
+        Err(e) => {
+            renderer.failure(&e);
+            Ok(None)
+        }
+    }
+}
+
+/// Logs a staff member in with their username and PIN
+///
+/// A new bank has no staff, so the first staff login creates the admin.
+fn staff_login(bank: &SharedBank, renderer: &dyn Renderer) -> io::Result<Option<Session>> {
+    let has_staff = bank.read().has_staff();
+    if !has_staff {
+        renderer.chrome(Msg::NoStaffYet.text());
+    }
+
+    let username = read_input(Msg::EnterUsername.text())?;
+
+    let result = if has_staff {
+        let pin = read_passphrase(Msg::EnterPin.text())?;
+        bank.write().authenticate_staff(&username, &pin)
+    } else {
+        match read_new_pin(renderer)? {
+            Some(pin) => bank
+                .write()
+                .add_staff(&username, Role::Admin, &pin)
+                .map(|()| Role::Admin),
+            None => return Ok(None),
+        }
+    };
+
+    match result {
+        Ok(role) => {
+            renderer.success(
+                &Msg::StaffWelcome.fill(&[&username, &role]),
+                json!({ "username": username, "role": role }),
+            );
+            Ok(Some(Session::staff(username, role)))
+        }
+        Err(e) => {
+            renderer.failure(&e);
+            Ok(None)
+        }
+    }
+}
+
+/// Changes the logged-in user's PIN
+pub fn change_pin(
+    bank: &SharedBank,
+    session: Option<&Session>,
+    renderer: &dyn Renderer,
+) -> io::Result<()> {
+    let session = match authorize(session, ANY_ROLE, renderer) {
+        Some(session) => session,
+        None => return Ok(()),
+    };
+
+    renderer.section(Msg::ChangePin.text());
+
+    let current = read_passphrase(Msg::EnterCurrentPin.text())?;
+    let new_pin = match read_new_pin(renderer)? {
+        Some(pin) => pin,
+        None => return Ok(()),
+    };
+
+    let mut bank = bank.write();
+    let result = if session.role.is_staff() {
+        bank.change_staff_pin(&session.user_id, &current, &new_pin)
+    } else {
+        bank.change_pin(&CustomerId::from(session.user_id.as_str()), &current, &new_pin)
+    };
+
+    match result {
+        Ok(()) => renderer.success(Msg::PinChanged.text(), json!({ "user": session.user_id })),
+        Err(e) => renderer.failure(&e),
+    }
+
+    Ok(())
+}
+
+/// Adds an admin or teller login (admins only)
+pub fn add_staff_user(
+    bank: &SharedBank,
+    session: Option<&Session>,
+    renderer: &dyn Renderer,
+) -> io::Result<()> {
+    if authorize(session, ADMIN_ONLY, renderer).is_none() {
+        return Ok(());
+    }
+
+    renderer.section(Msg::AddStaffUser.text());
+
+    let username = read_input(Msg::EnterUsername.text())?;
+    renderer.chrome(Msg::StaffRoles.text());
+    let role = match read_input(Msg::EnterRole.text())?.as_str() {
+        "" | "1" => Role::Teller,
+        "2" => Role::Admin,
+        _ => {
+            renderer.failure(&Msg::InvalidRole);
+            return Ok(());
+        }
+    };
+    let pin = match read_new_pin(renderer)? {
+        Some(pin) => pin,
+        None => return Ok(()),
+    };
+
+    match bank.write().add_staff(&username, role, &pin) {
+        Ok(()) => renderer.success(
+            &Msg::StaffAdded.fill(&[&role, &username.trim()]),
+            json!({ "username": username.trim(), "role": role }),
+        ),
+        Err(e) => renderer.failure(&e),
+    }
+
+    Ok(())
+}
+
+/// Prompts for a new PIN twice and checks its format
+///
+/// # Returns
+/// * `Ok(None)` - If the PIN is malformed or the entries differ (error shown)
+pub fn read_new_pin(renderer: &dyn Renderer) -> io::Result<Option<String>> {
+    let pin = read_passphrase(Msg::ChooseNewPin.text())?;
+    if let Err(e) = Credentials::validate_pin(&pin) {
+        renderer.failure(&e);
+        return Ok(None);
+    }
+
+    let confirm = read_passphrase(Msg::RepeatPin.text())?;
+    if pin != confirm {
+        renderer.failure(&Msg::PinsDiffer);
+        return Ok(None);
+    }
+    Ok(Some(pin))
+}
@@ -0,0 +1,270 @@
+//! Bill payment CLI operations
+//!
+//! Demonstrates: One screen per audience over the same data - admins
+//! keep the merchant list, customers pay from it
+
+use std::io;
+
+use chrono::{NaiveDate, Utc};
+use serde_json::json;
+
+use crate::bank::SharedBank;
+use crate::errors::BankError;
+use crate::format;
+use crate::models::{Autopay, Frequency, Merchant, MerchantCategory, Money};
+use crate::validation;
+use super::i18n::Msg;
+use super::render::Renderer;
+use super::session::{authorize, Session, ADMIN_ONLY, ANY_ROLE};
+use super::utils::{read_amount, read_checked, read_input};
+
+/// Lists the merchants and adds or removes them (admins only)
+pub fn manage_merchants(
+    bank: &SharedBank,
+    session: Option<&Session>,
+    renderer: &dyn Renderer,
+) -> io::Result<()> {
+    if authorize(session, ADMIN_ONLY, renderer).is_none() {
+        return Ok(());
+    }
+
+    renderer.section(Msg::Merchants.text());
+
+    loop {
+        let merchants = list_merchants(bank);
+        show_merchants(&merchants, renderer);
+
+        renderer.chrome(Msg::AddRemoveActions.text());
+        match read_input(Msg::EnterAction.text())?.as_str() {
+            "" => return Ok(()),
+            "1" => {
+                let name = read_checked(Msg::EnterMerchantName.text(), renderer, validation::validate_name)?;
+                let category = read_checked(Msg::EnterMerchantCategory.text(), renderer, |s| {
+                    s.parse::<MerchantCategory>().map_err(BankError::InvalidInput)
+                })?;
+                let result = bank.write().add_merchant(&name, category);
+                match result {
+                    Ok(merchant) => renderer.success(&Msg::Added.fill(&[&merchant]), json!(merchant)),
+                    Err(e) => renderer.failure(&e),
+                }
+            }
+            "2" => {
+                let merchant = match choose_merchant(&merchants, renderer)? {
+                    Some(merchant) => merchant,
+                    None => continue,
+                };
+                let result = bank.write().remove_merchant(&merchant.id);
+                match result {
+                    Ok(merchant) => renderer.success(&Msg::Removed.fill(&[&merchant]), json!(merchant)),
+                    Err(e) => renderer.failure(&e),
+                }
+            }
+            _ => renderer.failure(&Msg::InvalidChoice),
+        }
+    }
+}
+
+/// Pays a bill now, or sets up or cancels autopay; customers pay their
+/// own bills
+pub fn pay_bills(
+    bank: &SharedBank,
+    session: Option<&Session>,
+    renderer: &dyn Renderer,
+) -> io::Result<()> {
+    let session = match authorize(session, ANY_ROLE, renderer) {
+        Some(session) => session,
+        None => return Ok(()),
+    };
+
+    renderer.section(Msg::PayBills.text());
+
+    let customer_id = match session.target_customer(bank, Msg::EnterCustomer.text(), renderer)? {
+        Some(id) => id,
+        None => return Ok(()),
+    };
+
+    loop {
+        let autopays: Vec<Autopay> = bank.read().autopays_for(&customer_id).into_iter().cloned().collect();
+        show_autopays(bank, &autopays, renderer);
+
+        renderer.chrome(Msg::BillActions.text());
+        match read_input(Msg::EnterAction.text())?.as_str() {
+            "" => return Ok(()),
+            "1" => {
+                let (merchant, amount, reference) = match read_bill(bank, renderer)? {
+                    Some(bill) => bill,
+                    None => continue,
+                };
+                let result = bank.write().pay_bill(&customer_id, &merchant.id, amount, &reference);
+                match result {
+                    Ok(transaction) => renderer.success(
+                        &Msg::BillPaid.fill(&[
+                            &amount.display(),
+                            &merchant.name,
+                            &reference,
+                            &transaction.balance_after.display(),
+                        ]),
+                        json!(transaction),
+                    ),
+                    Err(e) => renderer.failure(&e),
+                }
+            }
+            "2" => {
+                let (merchant, amount, reference) = match read_bill(bank, renderer)? {
+                    Some(bill) => bill,
+                    None => continue,
+                };
+                renderer.chrome(Msg::Frequencies.text());
+                let frequency = match read_input(Msg::EnterFrequency.text())?.as_str() {
+                    "1" => Frequency::Daily,
+                    "2" => Frequency::Weekly,
+                    "3" => Frequency::Monthly,
+                    _ => {
+                        renderer.failure(&Msg::InvalidFrequency);
+                        continue;
+                    }
+                };
+                let date_str = read_input(Msg::EnterFirstPaymentDate.text())?;
+                let first_run = if date_str.is_empty() {
+                    Utc::now()
+                } else {
+                    match NaiveDate::parse_from_str(&date_str, "%Y-%m-%d") {
+                        Ok(date) => date.and_hms_opt(0, 0, 0).unwrap().and_utc(),
+                        Err(_) => {
+                            renderer.failure(&Msg::InvalidDate);
+                            continue;
+                        }
+                    }
+                };
+                let result = bank
+                    .write()
+                    .create_autopay(&customer_id, &merchant.id, amount, &reference, frequency, first_run);
+                match result {
+                    Ok(autopay) => renderer.success(
+                        &Msg::AutopaySetUp.fill(&[&merchant.name, &autopay]),
+                        json!(autopay),
+                    ),
+                    Err(e) => renderer.failure(&e),
+                }
+            }
+            "3" => {
+                if autopays.is_empty() {
+                    renderer.failure(&Msg::NoAutopayRules);
+                    continue;
+                }
+                let choice = read_input(Msg::EnterAutopay.text())?;
+                // Only this customer's rules can be picked, by number or ID
+                let autopay = match choice.parse::<usize>() {
+                    Ok(n) if (1..=autopays.len()).contains(&n) => Some(&autopays[n - 1]),
+                    _ => autopays.iter().find(|a| a.id == choice),
+                };
+                let id = match autopay {
+                    Some(autopay) => autopay.id.clone(),
+                    None => {
+                        renderer.failure(&Msg::InvalidChoice);
+                        continue;
+                    }
+                };
+                let result = bank.write().cancel_autopay(&id);
+                match result {
+                    Ok(autopay) => renderer.success(Msg::AutopayCancelled.text(), json!({ "cancelled": autopay.id })),
+                    Err(e) => renderer.failure(&e),
+                }
+            }
+            _ => renderer.failure(&Msg::InvalidChoice),
+        }
+    }
+}
+
+/// Makes the autopay payments that are due and reports each one
+///
+/// Prints nothing when no payment is due.
+pub fn process_due_autopays(bank: &SharedBank, renderer: &dyn Renderer) {
+    let runs = bank.write().run_due_autopays(Utc::now());
+
+    for run in runs {
+        match run.result {
+            Ok(transaction) => renderer.success(
+                &Msg::AutopayPaid.fill(&[
+                    &&run.autopay_id[..8],
+                    &format::date(&run.run_at),
+                    &transaction.amount.display(),
+                    &transaction.description.as_deref().unwrap_or(Msg::Merchant.text()),
+                ]),
+                json!({ "autopay_id": run.autopay_id, "run_at": run.run_at, "transaction": transaction }),
+            ),
+            Err(e) => renderer.failure(&Msg::AutopayFailed.fill(&[
+                &&run.autopay_id[..8],
+                &format::date(&run.run_at),
+                &e,
+            ])),
+        }
+    }
+}
+
+/// The merchants, by name
+fn list_merchants(bank: &SharedBank) -> Vec<Merchant> {
+    bank.read().list_merchants().into_iter().cloned().collect()
+}
+
+/// Prints a numbered list of `merchants`
+fn show_merchants(merchants: &[Merchant], renderer: &dyn Renderer) {
+    if merchants.is_empty() {
+        renderer.chrome(Msg::NoMerchants.text());
+        return;
+    }
+    renderer.chrome("");
+    for (i, merchant) in merchants.iter().enumerate() {
+        renderer.chrome(&format!("  {}. {}\n     ID: {}", i + 1, merchant, merchant.id));
+    }
+}
+
+/// Prints a numbered list of `autopays`, naming their merchants
+fn show_autopays(bank: &SharedBank, autopays: &[Autopay], renderer: &dyn Renderer) {
+    if autopays.is_empty() {
+        renderer.chrome(Msg::NoAutopays.text());
+        return;
+    }
+    let bank = bank.read();
+    renderer.chrome(Msg::AutopayHeading.text());
+    for (i, autopay) in autopays.iter().enumerate() {
+        let merchant = bank.find_merchant(&autopay.merchant_id).map_or(Msg::RemovedMerchant.to_string(), |m| m.name.clone());
+        renderer.chrome(&format!("  {}. {} - {}\n     ID: {}", i + 1, merchant, autopay, autopay.id));
+    }
+}
+
+/// Prompts for a merchant by its number in the list, its name or its ID
+///
+/// # Returns
+/// * `Ok(None)` - If there are no merchants or none matched (error shown)
+fn choose_merchant(merchants: &[Merchant], renderer: &dyn Renderer) -> io::Result<Option<Merchant>> {
+    if merchants.is_empty() {
+        renderer.failure(&Msg::NoMerchantsYet);
+        return Ok(None);
+    }
+    let choice = read_input(Msg::EnterMerchant.text())?;
+    let merchant = match choice.parse::<usize>() {
+        Ok(n) if (1..=merchants.len()).contains(&n) => Some(&merchants[n - 1]),
+        _ => merchants.iter().find(|m| m.id == choice || m.name.eq_ignore_ascii_case(&choice)),
+    };
+    if merchant.is_none() {
+        renderer.failure(&Msg::InvalidChoice);
+    }
+    Ok(merchant.cloned())
+}
+
+/// Prompts for the merchant, amount and reference of a bill
+///
+/// # Returns
+/// * `Ok(None)` - If an answer was invalid (error already shown)
+fn read_bill(bank: &SharedBank, renderer: &dyn Renderer) -> io::Result<Option<(Merchant, Money, String)>> {
+    let merchants = list_merchants(bank);
+    show_merchants(&merchants, renderer);
+    let merchant = match choose_merchant(&merchants, renderer)? {
+        Some(merchant) => merchant,
+        None => return Ok(None),
+    };
+    let reference = read_checked(Msg::EnterBillReference.text(), renderer, validation::validate_bill_reference)?;
+    let amount = read_amount(Msg::EnterAmount.text(), renderer)?;
+    Ok(Some((merchant, amount, reference)))
+}
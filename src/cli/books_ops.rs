@@ -0,0 +1,125 @@
+//! Bookkeeping CLI operations - trial balance, income statement and the
+//! chart of accounts
+//!
+//! Demonstrates: Editing a copy of a configuration and handing it back
+//! whole, so a half-made change is never seen
+
+use std::io;
+
+use chrono::{Datelike, Utc};
+use serde_json::json;
+
+use crate::bank::SharedBank;
+use crate::errors::{BankError, BankResult};
+use crate::models::{AccountClass, BookAccount, ChartOfAccounts, TransactionKind};
+use crate::persistence;
+use crate::validation;
+use super::i18n::Msg;
+use super::info_ops::{read_date, read_end_date};
+use super::render::Renderer;
+use super::utils::{read_checked, read_input, unless_blank};
+
+/// Shows the trial balance or writes it to a CSV file
+pub fn show_trial_balance(bank: &SharedBank, renderer: &dyn Renderer) -> io::Result<()> {
+    let output = read_input(Msg::EnterCsvOrShow.text())?;
+    let trial_balance = bank.read().trial_balance();
+    if output.is_empty() {
+        renderer.success(&format!("\n{}", trial_balance), json!(trial_balance));
+        return Ok(());
+    }
+    match persistence::export_trial_balance_csv(&trial_balance, &output) {
+        Ok(_) => renderer.success(&Msg::TrialBalanceWritten.fill(&[&output]), json!({ "file": output })),
+        Err(e) => renderer.failure(&e),
+    }
+    Ok(())
+}
+
+/// Shows the income statement for a period or writes it to a CSV file
+pub fn show_income_statement(bank: &SharedBank, renderer: &dyn Renderer) -> io::Result<()> {
+    // Default period: the current month so far
+    let today = Utc::now().date_naive();
+    let month_start = today.with_day(1).unwrap_or(today);
+    let from = match read_date(Msg::EnterStartDate.text(), month_start, renderer)? {
+        Some(date) => date,
+        None => return Ok(()),
+    };
+    let to = match read_end_date(Msg::EnterEndDate.text(), today, renderer)? {
+        Some(end) => end,
+        None => return Ok(()),
+    };
+    let output = read_input(Msg::EnterCsvOrShow.text())?;
+
+    let statement = bank.read().income_statement(Some(from.and_hms_opt(0, 0, 0).unwrap().and_utc()), Some(to));
+    if output.is_empty() {
+        renderer.success(&format!("\n{}", statement), json!(statement));
+        return Ok(());
+    }
+    match persistence::export_income_statement_csv(&statement, &output) {
+        Ok(_) => renderer.success(&Msg::IncomeStatementWritten.fill(&[&output]), json!({ "file": output })),
+        Err(e) => renderer.failure(&e),
+    }
+    Ok(())
+}
+
+/// Shows the chart of accounts and edits an account or a mapping rule
+pub fn manage_chart(bank: &SharedBank, renderer: &dyn Renderer) -> io::Result<()> {
+    loop {
+        let mut chart = bank.read().chart_of_accounts().clone();
+        renderer.chrome(&format!("\n📚 {}", chart));
+
+        renderer.chrome(Msg::ChartActions.text());
+        match read_input(Msg::EnterAction.text())?.as_str() {
+            "" => return Ok(()),
+            "1" => {
+                let account = read_checked(Msg::EnterLedgerAccount.text(), renderer, |input| find_account(&chart, input))?;
+                let Some(line) = chart.accounts.iter_mut().find(|line| line.account == account) else {
+                    continue;
+                };
+                let prompt = Msg::EnterLedgerCode.fill(&[&line.code]);
+                if let Some(code) = read_checked(&prompt, renderer, |input| {
+                    unless_blank(input, validation::validate_ledger_code)
+                })? {
+                    line.code = code;
+                }
+                let prompt = Msg::EnterLedgerName.fill(&[&line.name]);
+                if let Some(name) = read_checked(&prompt, renderer, |input| {
+                    unless_blank(input, validation::validate_ledger_name)
+                })? {
+                    line.name = name;
+                }
+                let prompt = Msg::EnterLedgerClass.fill(&[&line.class]);
+                if let Some(class) = read_checked(&prompt, renderer, |input| {
+                    unless_blank(input, |input| input.parse::<AccountClass>().map_err(BankError::InvalidInput))
+                })? {
+                    line.class = class;
+                }
+            }
+            "2" => {
+                let kind = read_checked(Msg::EnterTransactionKind.text(), renderer, |input| {
+                    input.parse::<TransactionKind>().map_err(BankError::InvalidInput)
+                })?;
+                let account = read_checked(Msg::EnterPostingAccount.text(), renderer, |input| {
+                    find_account(&chart, input)
+                })?;
+                chart.map(kind, account);
+            }
+            _ => {
+                renderer.failure(&Msg::InvalidChoice);
+                continue;
+            }
+        }
+
+        let result = bank.write().set_chart_of_accounts(chart.clone());
+        match result {
+            Ok(()) => renderer.success(Msg::ChartUpdated.text(), json!(chart)),
+            Err(e) => renderer.failure(&e),
+        }
+    }
+}
+
+/// The account in `chart` with this code or name
+pub(super) fn find_account(chart: &ChartOfAccounts, code_or_name: &str) -> BankResult<BookAccount> {
+    chart
+        .find(code_or_name)
+        .ok_or_else(|| BankError::InvalidInput(Msg::NoLedgerAccount.fill(&[&code_or_name.trim()])))
+}
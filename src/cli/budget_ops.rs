@@ -0,0 +1,118 @@
+//! Budget CLI operations
+//!
+//! Demonstrates: Asking for confirmation only when a check finds
+//! something worth confirming
+
+use std::io;
+
+use serde_json::json;
+
+use crate::bank::SharedBank;
+use crate::models::{BudgetStatus, CustomerId, Money, TransactionDetails};
+use super::i18n::Msg;
+use super::render::Renderer;
+use super::session::{authorize, Session, ANY_ROLE};
+use super::utils::{read_amount, read_input, read_yes};
+
+/// Width of a budget bar, in characters
+const BAR_WIDTH: usize = 20;
+
+/// Shows this month's spending against each budget and sets or removes
+/// budgets; customers manage their own
+pub fn manage_budgets(
+    bank: &SharedBank,
+    session: Option<&Session>,
+    renderer: &dyn Renderer,
+) -> io::Result<()> {
+    let session = match authorize(session, ANY_ROLE, renderer) {
+        Some(session) => session,
+        None => return Ok(()),
+    };
+
+    renderer.section(Msg::Budgets.text());
+
+    let customer_id = match session.target_customer(bank, Msg::EnterCustomer.text(), renderer)? {
+        Some(id) => id,
+        None => return Ok(()),
+    };
+
+    loop {
+        let report = match bank.read().budget_report(&customer_id) {
+            Ok(report) => report,
+            Err(e) => {
+                renderer.failure(&e);
+                return Ok(());
+            }
+        };
+        if report.is_empty() {
+            renderer.chrome(Msg::NoBudgets.text());
+        } else {
+            renderer.chrome(&Msg::BudgetsThisMonth.fill(&[&budget_lines(&report)]));
+        }
+
+        renderer.chrome(Msg::BudgetActions.text());
+        let result = match read_input(Msg::EnterAction.text())?.as_str() {
+            "" => return Ok(()),
+            "1" => {
+                let category = read_input(Msg::EnterBudgetCategory.text())?;
+                let limit = read_amount(Msg::EnterMonthlyLimit.text(), renderer)?;
+                bank.write().set_budget(&customer_id, &category, limit).map(|budget| {
+                    (Msg::BudgetSet.fill(&[&budget.category, &budget.limit.display()]), json!(budget))
+                })
+            }
+            "2" => {
+                let category = read_input(Msg::EnterCategory.text())?;
+                bank.write()
+                    .remove_budget(&customer_id, &category)
+                    .map(|budget| (Msg::BudgetRemoved.fill(&[&budget.category]), json!(budget)))
+            }
+            _ => {
+                renderer.failure(&Msg::InvalidChoice);
+                continue;
+            }
+        };
+        match result {
+            Ok((text, value)) => renderer.success(&text, value),
+            Err(e) => renderer.failure(&e),
+        }
+    }
+}
+
+/// One line per budget, with a bar of how much of it is spent
+pub fn budget_lines(report: &[BudgetStatus]) -> String {
+    report
+        .iter()
+        .map(|status| {
+            let filled = (status.percent_used() as usize).min(100) * BAR_WIDTH / 100;
+            let bar = format!("{}{}", "█".repeat(filled), "░".repeat(BAR_WIDTH - filled));
+            let icon = if status.is_exceeded() { "⚠️ " } else { "✅" };
+            format!("  {} [{}] {}\n", icon, bar, status)
+        })
+        .collect()
+}
+
+/// Warns if spending `amount` as described by `details` would take one
+/// of the customer's budgets past its limit, and asks whether to go on
+///
+/// # Returns
+/// * `Ok(true)` - If there is nothing to warn about or the user went on
+pub fn confirm_budget(
+    bank: &SharedBank,
+    customer_id: &CustomerId,
+    amount: Money,
+    details: &TransactionDetails,
+    renderer: &dyn Renderer,
+) -> io::Result<bool> {
+    let warning = bank.read().budget_warning(customer_id, details.tags.category.as_deref(), amount);
+    let status = match warning {
+        Ok(Some(status)) => status,
+        _ => return Ok(true),
+    };
+
+    renderer.chrome(&Msg::OverBudget.fill(&[&status]));
+    if read_yes(Msg::GoAheadAnyway.text())? {
+        return Ok(true);
+    }
+    renderer.success(Msg::CancelledDone.text(), json!({ "cancelled": true }));
+    Ok(false)
+}
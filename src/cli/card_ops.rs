@@ -0,0 +1,186 @@
+//! Debit card CLI operations
+//!
+//! Demonstrates: Reusing another screen's prompts for a new operation
+
+use std::io;
+
+use serde_json::json;
+
+use crate::bank::SharedBank;
+use crate::models::{Card, CardLimits, CardStatus};
+use super::account_ops::{read_details, read_limit};
+use super::budget_ops::confirm_budget;
+use super::i18n::Msg;
+use super::render::Renderer;
+use super::session::{authorize, Session, ANY_ROLE, STAFF};
+use super::utils::{read_amount, read_customer, read_input};
+
+/// Lists a customer's cards and issues, blocks or replaces them or
+/// changes their limits (staff only)
+pub fn manage_cards(
+    bank: &SharedBank,
+    session: Option<&Session>,
+    renderer: &dyn Renderer,
+) -> io::Result<()> {
+    if authorize(session, STAFF, renderer).is_none() {
+        return Ok(());
+    }
+
+    renderer.section(Msg::DebitCards.text());
+
+    let customer_id = match read_customer(bank, Msg::EnterCustomer.text(), renderer)? {
+        Some(id) => id,
+        None => return Ok(()),
+    };
+
+    loop {
+        let cards = match bank.read().get_customer(&customer_id) {
+            Ok(customer) => customer.cards.clone(),
+            Err(e) => {
+                renderer.failure(&e);
+                return Ok(());
+            }
+        };
+        show_cards(&cards, renderer);
+
+        renderer.chrome(Msg::CardActions.text());
+        let action = read_input(Msg::EnterAction.text())?;
+        let result = match action.as_str() {
+            "" => return Ok(()),
+            "1" => match read_card_limits(CardLimits::default(), renderer)? {
+                Some(limits) => bank.write().issue_card(&customer_id, limits),
+                None => continue,
+            },
+            "2" | "3" | "4" => {
+                let card = match choose_card(&cards, renderer)? {
+                    Some(card) => card,
+                    None => continue,
+                };
+                match action.as_str() {
+                    "2" => bank.write().block_card(&card.id),
+                    "3" => bank.write().replace_card(&card.id),
+                    _ => match read_card_limits(card.limits, renderer)? {
+                        Some(limits) => {
+                            let result = bank.write().set_card_limits(&card.id, limits);
+                            result.map(|()| Card { limits, ..card })
+                        }
+                        None => continue,
+                    },
+                }
+            }
+            _ => {
+                renderer.failure(&Msg::InvalidChoice);
+                continue;
+            }
+        };
+        match result {
+            Ok(card) => renderer.success(&Msg::CardDone.fill(&[&card, &card.limits]), json!(card)),
+            Err(e) => renderer.failure(&e),
+        }
+    }
+}
+
+/// Pays for something with one of a customer's active cards; customers
+/// use their own cards
+pub fn card_purchase(
+    bank: &SharedBank,
+    session: Option<&Session>,
+    renderer: &dyn Renderer,
+) -> io::Result<()> {
+    let session = match authorize(session, ANY_ROLE, renderer) {
+        Some(session) => session,
+        None => return Ok(()),
+    };
+
+    renderer.section(Msg::CardPurchase.text());
+
+    let customer_id = match session.target_customer(bank, Msg::EnterCustomer.text(), renderer)? {
+        Some(id) => id,
+        None => return Ok(()),
+    };
+    let cards: Vec<Card> = match bank.read().get_customer(&customer_id) {
+        Ok(customer) => customer.cards.iter().filter(|c| c.status == CardStatus::Active).cloned().collect(),
+        Err(e) => {
+            renderer.failure(&e);
+            return Ok(());
+        }
+    };
+    if cards.is_empty() {
+        renderer.failure(&Msg::NoActiveCards);
+        return Ok(());
+    }
+    show_cards(&cards, renderer);
+    let card = match choose_card(&cards, renderer)? {
+        Some(card) => card,
+        None => return Ok(()),
+    };
+
+    let amount = read_amount(Msg::EnterPurchaseAmount.text(), renderer)?;
+    renderer.chrome(Msg::DescribePurchase.text());
+    let details = match read_details(renderer, true)? {
+        Some(details) => details,
+        None => return Ok(()),
+    };
+    if !confirm_budget(bank, &customer_id, amount, &details, renderer)? {
+        return Ok(());
+    }
+
+    let result = bank.write().card_purchase(&card.id, amount, details);
+    match result {
+        Ok(transaction) => renderer.success(
+            &Msg::CardPaid.fill(&[&amount.display(), &card.last_four(), &transaction.balance_after.display()]),
+            json!(transaction),
+        ),
+        Err(e) => renderer.failure(&e),
+    }
+    Ok(())
+}
+
+/// Prints a numbered list of `cards`
+fn show_cards(cards: &[Card], renderer: &dyn Renderer) {
+    if cards.is_empty() {
+        renderer.chrome(Msg::NoCards.text());
+        return;
+    }
+    renderer.chrome("");
+    for (i, card) in cards.iter().enumerate() {
+        renderer.chrome(&Msg::CardLine.fill(&[&(i + 1), card, &card.limits, &card.id]));
+    }
+}
+
+/// Prompts for a card by its number in the list or its ID
+///
+/// # Returns
+/// * `Ok(None)` - If there are no cards or none matched (error shown)
+fn choose_card(cards: &[Card], renderer: &dyn Renderer) -> io::Result<Option<Card>> {
+    if cards.is_empty() {
+        renderer.failure(&Msg::NoCardsToChoose);
+        return Ok(None);
+    }
+    let choice = read_input(Msg::EnterCard.text())?;
+    let card = match choice.parse::<usize>() {
+        Ok(n) if (1..=cards.len()).contains(&n) => Some(&cards[n - 1]),
+        _ => cards.iter().find(|card| card.id == choice),
+    };
+    if card.is_none() {
+        renderer.failure(&Msg::InvalidChoice);
+    }
+    Ok(card.cloned())
+}
+
+/// Prompts for both card limits, starting from `current`
+///
+/// # Returns
+/// * `Ok(None)` - If an answer was invalid (error already shown)
+fn read_card_limits(current: CardLimits, renderer: &dyn Renderer) -> io::Result<Option<CardLimits>> {
+    renderer.chrome(Msg::CardLimitsHelp.text());
+    let per_purchase = match read_limit(Msg::EnterMaxPurchase.text(), current.per_purchase, renderer)? {
+        Some(value) => value,
+        None => return Ok(None),
+    };
+    let daily = match read_limit(Msg::EnterDailySpend.text(), current.daily, renderer)? {
+        Some(value) => value,
+        None => return Ok(None),
+    };
+    Ok(Some(CardLimits { per_purchase, daily }))
+}
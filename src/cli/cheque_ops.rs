@@ -0,0 +1,126 @@
+//! Cheque CLI operations
+//!
+//! Demonstrates: Background processing run between menu choices
+
+use std::io;
+
+use chrono::Utc;
+use serde_json::json;
+
+use crate::bank::SharedBank;
+use crate::format;
+use crate::models::ChequeStatus;
+use crate::validation;
+use super::i18n::Msg;
+use super::render::Renderer;
+use super::session::{authorize, Session, ANY_ROLE, STAFF};
+use super::utils::{choose_customer, read_amount, read_checked, read_input};
+
+/// Pays a cheque into an account; customers pay into their own
+pub fn deposit_cheque(
+    bank: &SharedBank,
+    session: Option<&Session>,
+    renderer: &dyn Renderer,
+) -> io::Result<()> {
+    let session = match authorize(session, ANY_ROLE, renderer) {
+        Some(session) => session,
+        None => return Ok(()),
+    };
+
+    renderer.section(Msg::DepositCheque.text());
+
+    let customer_id = match session.target_customer(bank, Msg::EnterCustomer.text(), renderer)? {
+        Some(id) => id,
+        None => return Ok(()),
+    };
+    let number = read_checked(Msg::EnterChequeNumber.text(), renderer, validation::validate_cheque_number)?;
+    let amount = read_amount(Msg::EnterAmount.text(), renderer)?;
+    let drawer = read_input(Msg::EnterDrawer.text())?;
+    let drawn_on = if drawer.is_empty() {
+        None
+    } else {
+        match choose_customer(bank, drawer, renderer)? {
+            Some(id) => Some(id),
+            None => return Ok(()),
+        }
+    };
+
+    let result = bank.write().deposit_cheque(&customer_id, amount, &number, drawn_on.as_ref());
+    match result {
+        Ok(cheque) => renderer.success(
+            &Msg::ChequeDeposited.fill(&[&cheque.number, &cheque.amount.display(), &format::date(&cheque.clears_at)]),
+            json!(cheque),
+        ),
+        Err(e) => renderer.failure(&e),
+    }
+    Ok(())
+}
+
+/// Lists the cheques waiting to clear, and clears the due ones or returns
+/// one unpaid (staff only)
+pub fn manage_cheques(
+    bank: &SharedBank,
+    session: Option<&Session>,
+    renderer: &dyn Renderer,
+) -> io::Result<()> {
+    if authorize(session, STAFF, renderer).is_none() {
+        return Ok(());
+    }
+
+    renderer.section(Msg::OutstandingCheques.text());
+
+    loop {
+        let outstanding: Vec<String> = {
+            let bank = bank.read();
+            renderer.chrome(&format!("\n📋 {}", bank.cheque_policy()));
+            let outstanding = bank.outstanding_cheques();
+            if outstanding.is_empty() {
+                renderer.chrome(Msg::NoChequesWaiting.text());
+            } else {
+                for (i, (customer, cheque)) in outstanding.iter().enumerate() {
+                    renderer.chrome(&format!("  {}. {} - {}\n     ID: {}", i + 1, customer.name, cheque, cheque.id));
+                }
+            }
+            outstanding.iter().map(|(_, cheque)| cheque.id.clone()).collect()
+        };
+        if outstanding.is_empty() {
+            return Ok(());
+        }
+
+        renderer.chrome(Msg::ChequeActions.text());
+        match read_input(Msg::EnterAction.text())?.as_str() {
+            "" => return Ok(()),
+            "1" => process_due_cheques(bank, renderer),
+            "2" => {
+                let choice = read_input(Msg::EnterCheque.text())?;
+                let id = match choice.parse::<usize>() {
+                    Ok(n) if (1..=outstanding.len()).contains(&n) => outstanding[n - 1].clone(),
+                    _ => choice,
+                };
+                let reason = read_input(Msg::EnterReturnReason.text())?;
+                let result = bank.write().return_cheque(&id, Some(&reason));
+                match result {
+                    Ok(cheque) => renderer.success(&format!("\n↩️  {}", cheque), json!(cheque)),
+                    Err(e) => renderer.failure(&e),
+                }
+            }
+            _ => renderer.failure(&Msg::InvalidChoice),
+        }
+    }
+}
+
+/// Clears or bounces the cheques that are due and reports each one
+///
+/// Prints nothing when no cheque is due.
+pub fn process_due_cheques(bank: &SharedBank, renderer: &dyn Renderer) {
+    let processed = bank.write().clear_due_cheques(Utc::now());
+    match processed {
+        Ok(cheques) => {
+            for cheque in cheques {
+                let icon = if cheque.status == ChequeStatus::Cleared { "✅" } else { "↩️ " };
+                renderer.success(&format!("{} {}", icon, cheque), json!({ "processed_cheque": cheque }));
+            }
+        }
+        Err(e) => renderer.failure(&Msg::ClearingChequesFailed.fill(&[&e])),
+    }
+}
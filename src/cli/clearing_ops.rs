@@ -0,0 +1,80 @@
+//! Clearing queue CLI operations
+//!
+//! Demonstrates: Picking an item from a numbered list or by ID
+
+use std::io;
+
+use serde_json::json;
+
+use crate::bank::SharedBank;
+use crate::models::TransactionId;
+use super::i18n::Msg;
+use super::render::Renderer;
+use super::session::{authorize, Session, STAFF};
+use super::utils::read_input;
+
+/// Shows the external transfers awaiting the other bank's answer and
+/// settles or rejects them (staff only)
+pub fn manage_clearing(
+    bank: &SharedBank,
+    session: Option<&Session>,
+    renderer: &dyn Renderer,
+) -> io::Result<()> {
+    if authorize(session, STAFF, renderer).is_none() {
+        return Ok(());
+    }
+
+    renderer.section(Msg::ClearingQueue.text());
+
+    loop {
+        let pending: Vec<(String, TransactionId)> = {
+            let bank = bank.read();
+            let pending = bank.pending_external_transfers();
+            if pending.is_empty() {
+                renderer.chrome(Msg::NoPendingExternal.text());
+            } else {
+                renderer.chrome(Msg::PendingExternalHeading.text());
+                for (i, (customer, tx)) in pending.iter().enumerate() {
+                    renderer.chrome(&format!("  {}. {} - {}\n     ID: {}", i + 1, customer.name, tx, tx.id));
+                }
+            }
+            pending.iter().map(|(customer, tx)| (customer.name.clone(), tx.id.clone())).collect()
+        };
+        if pending.is_empty() {
+            return Ok(());
+        }
+
+        renderer.chrome(Msg::ClearingActions.text());
+        let action = read_input(Msg::EnterAction.text())?;
+        if action.is_empty() {
+            return Ok(());
+        }
+        if action != "1" && action != "2" {
+            renderer.failure(&Msg::InvalidChoice);
+            continue;
+        }
+
+        let choice = read_input(Msg::EnterPendingTransfer.text())?;
+        let id = match choice.parse::<usize>() {
+            Ok(n) if (1..=pending.len()).contains(&n) => pending[n - 1].1.clone(),
+            _ => choice.into(),
+        };
+
+        if action == "1" {
+            match bank.write().settle_external_transfer(&id) {
+                Ok(tx) => renderer.success(&Msg::Settled.fill(&[&tx]), json!(tx)),
+                Err(e) => renderer.failure(&e),
+            }
+        } else {
+            let reason = read_input(Msg::EnterOptionalReason.text())?;
+            let result = bank.write().reject_external_transfer(&id, Some(&reason));
+            match result {
+                Ok(refund) => renderer.success(
+                    &Msg::RejectedRefunded.fill(&[&refund.amount.display()]),
+                    json!(refund),
+                ),
+                Err(e) => renderer.failure(&e),
+            }
+        }
+    }
+}
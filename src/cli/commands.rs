@@ -0,0 +1,883 @@
+//! Non-interactive command execution with JSON output
+//!
+//! Demonstrates: Mapping parsed arguments onto library calls, serde_json's
+//! json! macro, process exit codes
+//!
+//! Every command prints exactly one JSON object to stdout:
+//! `{"ok": true, "result": ...}` on success or
+//! `{"ok": false, "error": "...", "code": 2001, "kind": "insufficient_funds"}`
+//! on failure (with exit code 1); see `BankError::code` for the codes.
+
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use serde_json::{json, Value};
+
+use crate::bank::{Bank, BatchMode, CashFlowPeriod, SeedOptions};
+use crate::clock::end_of_day;
+use crate::config::Config;
+use crate::errors::{BankError, BankResult};
+use crate::events::{DomainEvent, EventKind};
+use crate::models::{
+    AccountClass, AccountLimits, AccountStatus, AccountType, AlertRules, CardLimits, Credentials, CustomerStatus,
+    CustomerUpdate, Frequency, MerchantCategory, Money, Page, TransactionDetails, TransactionFilter, TransactionKind,
+    TransactionTags, VelocityRule,
+};
+use crate::models::fraud::DEFAULT_WINDOW_MINUTES;
+use crate::notifications::{ConsoleNotifier, FileNotifier, WebhookNotifier};
+use crate::observer::BankObserver;
+use crate::persistence::{self, DataFormat, EncryptedFileStorage, JsonFileStorage, Storage};
+use crate::validation;
+use super::args::{
+    AccountKind, CashFlowPeriodArg, Command, DataFormatArg, EventKindArg, ExportFormat, FrequencyArg,
+};
+use super::books_ops::find_account;
+use super::data_ops::{batch_json, import_json};
+use super::render::{JsonRenderer, Renderer};
+use super::startup::guard_storage;
+use super::utils::{parse_limit, parse_month};
+
+/// Environment variable holding the passphrase for encrypted data files
+pub const PASSPHRASE_ENV: &str = "BANK_PASSPHRASE";
+
+/// Environment variable naming a file to append triggered alerts to
+pub const ALERT_FILE_ENV: &str = "BANK_ALERT_FILE";
+
+/// Environment variable holding an `http://` URL to POST triggered alerts to
+pub const ALERT_WEBHOOK_ENV: &str = "BANK_ALERT_WEBHOOK";
+
+/// Runs a single command against the data file and prints the JSON result
+///
+/// # Returns
+/// The process exit code (0 on success, 1 on error)
+pub fn run_command(command: Command, config: &Config) -> i32 {
+    let outcome = open_command_storage(config).and_then(|storage| {
+        let mut bank = load_or_create(storage.as_ref(), config)?;
+        // Numbering accounts of an older file is saved along with the
+        // command, but is not worth failing a read on a read-only file
+        let upgraded = !bank.take_journal().is_empty();
+        execute_and_save(command, &mut bank, storage.as_ref(), upgraded && !config.read_only)
+    });
+
+    let renderer = JsonRenderer::new();
+    match outcome {
+        Ok(value) => {
+            renderer.success("", value);
+            0
+        }
+        Err(e) => {
+            renderer.error(&e);
+            1
+        }
+    }
+}
+
+/// Runs a command against a loaded bank and saves it if anything changed
+///
+/// `unsaved` forces a save even when the command itself changed nothing,
+/// e.g. because loading upgraded the file. Read-only commands change
+/// nothing, so they skip the save.
+///
+/// # Returns
+/// The command's JSON result
+pub fn execute_and_save(command: Command, bank: &mut Bank, storage: &dyn Storage, unsaved: bool) -> BankResult<Value> {
+    let before = bank.unsaved_changes();
+    let result = execute(command, bank, storage);
+    bank.take_journal();
+
+    // A failed command can still have changed state (a wrong PIN counts
+    // towards the lockout), so this runs before the error is returned
+    let mutated = matches!(&result, Ok(r) if r.mutated);
+    let saved = if bank.unsaved_changes() > before || mutated || unsaved {
+        storage.save_bank(bank).map(|()| bank.mark_saved())
+    } else {
+        Ok(())
+    };
+    let value = result?.value;
+    saved.map(|_| value)
+}
+
+/// Result of one command: its JSON value and whether it changed state
+/// that is not covered by the journal (e.g. schedules)
+struct CommandResult {
+    value: Value,
+    mutated: bool,
+}
+
+impl CommandResult {
+    fn read(value: Value) -> Self {
+        Self { value, mutated: false }
+    }
+
+    fn write(value: Value) -> Self {
+        Self { value, mutated: true }
+    }
+}
+
+/// Picks the storage backend; encrypted files need `BANK_PASSPHRASE`
+pub fn open_command_storage(config: &Config) -> BankResult<Box<dyn Storage>> {
+    let data_file = config.data_file.as_str();
+    if persistence::is_encrypted_file(data_file) {
+        let passphrase = env::var(PASSPHRASE_ENV).map_err(|_| {
+            BankError::EncryptionError(format!(
+                "data file is encrypted; set {} to its passphrase",
+                PASSPHRASE_ENV
+            ))
+        })?;
+        let storage = EncryptedFileStorage::new(data_file, passphrase).with_backups(config.backup_count);
+        return Ok(guard_storage(Box::new(storage), config));
+    }
+    let storage = JsonFileStorage::new(data_file).with_backups(config.backup_count).with_lazy_load(config.lazy_load);
+    Ok(guard_storage(Box::new(storage), config))
+}
+
+/// Loads the bank, or starts a new one if the data file does not exist yet
+///
+/// Unlike the interactive mode, a file that exists but cannot be read is
+/// an error rather than silently replaced.
+pub fn load_or_create(storage: &dyn Storage, config: &Config) -> BankResult<Bank> {
+    let mut bank = if Path::new(&config.data_file).exists() {
+        storage.load_bank()?
+    } else {
+        Bank::new(config.bank_name.clone())
+    };
+    if let Some(archive) = storage.history_archive() {
+        bank.set_archive(archive);
+    }
+    bank.enable_journal();
+    bank.assign_account_numbers()?;
+    // Trimming archives, which a read-only storage refuses
+    if !config.read_only {
+        bank.set_history_cap(config.history_cap)?;
+    }
+    add_notifiers(&mut bank);
+    Ok(bank)
+}
+
+/// Registers the console notifier, plus a file and a webhook notifier when
+/// `BANK_ALERT_FILE` and `BANK_ALERT_WEBHOOK` are set, and an observer
+/// that warns about webhooks that could not be reached
+///
+/// An invalid webhook URL is reported and skipped rather than stopping the
+/// bank from loading.
+pub fn add_notifiers(bank: &mut Bank) {
+    bank.add_notifier(Arc::new(ConsoleNotifier));
+    bank.add_observer(Arc::new(WebhookWarnings));
+    if let Ok(path) = env::var(ALERT_FILE_ENV) {
+        bank.add_notifier(Arc::new(FileNotifier::new(path)));
+    }
+    if let Ok(url) = env::var(ALERT_WEBHOOK_ENV) {
+        match WebhookNotifier::new(&url) {
+            Ok(notifier) => bank.add_notifier(Arc::new(notifier)),
+            Err(e) => eprintln!("⚠️  Ignoring {}: {}", ALERT_WEBHOOK_ENV, e),
+        }
+    }
+}
+
+/// Prints a warning for each event a webhook never received
+#[derive(Debug)]
+struct WebhookWarnings;
+
+impl BankObserver for WebhookWarnings {
+    fn on_webhook_failed(&self, url: &str, event: &DomainEvent, error: &BankError) {
+        eprintln!("⚠️  Could not deliver {} to {}: {}", event.kind(), url, error);
+    }
+}
+
+/// Parses a decimal amount argument
+fn parse_amount(amount: &str) -> BankResult<Money> {
+    amount
+        .parse()
+        .map_err(|e: crate::models::ParseMoneyError| BankError::InvalidInput(e.to_string()))
+}
+
+/// Parses a `YYYY-MM-DD` date argument
+fn parse_date(date: &str) -> BankResult<NaiveDate> {
+    NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .map_err(|_| BankError::InvalidInput(format!("invalid date '{}'", date)))
+}
+
+/// Maps a frequency argument onto the model's `Frequency`
+fn frequency_arg(frequency: FrequencyArg) -> Frequency {
+    match frequency {
+        FrequencyArg::Daily => Frequency::Daily,
+        FrequencyArg::Weekly => Frequency::Weekly,
+        FrequencyArg::Monthly => Frequency::Monthly,
+    }
+}
+
+/// Maps a data format argument onto `DataFormat`
+fn data_format_arg(format: DataFormatArg) -> DataFormat {
+    match format {
+        DataFormatArg::Json => DataFormat::Json,
+        DataFormatArg::Yaml => DataFormat::Yaml,
+        DataFormatArg::Toml => DataFormat::Toml,
+        DataFormatArg::Msgpack => DataFormat::MessagePack,
+    }
+}
+
+/// Moments from (inclusive) and to (exclusive); `None` leaves that end open
+type Span = (Option<DateTime<Utc>>, Option<DateTime<Utc>>);
+
+/// Turns optional first and last days into the span they cover
+fn day_range(from: Option<String>, to: Option<String>) -> BankResult<Span> {
+    let from = match from {
+        Some(from) => Some(parse_date(&from)?.and_hms_opt(0, 0, 0).unwrap().and_utc()),
+        None => None,
+    };
+    let to = match to {
+        Some(to) => Some(end_of_day(parse_date(&to)?)?),
+        None => None,
+    };
+    Ok((from, to))
+}
+
+/// Parses an optional first-run date argument; absent means now
+fn start_arg(start: Option<String>) -> BankResult<DateTime<Utc>> {
+    match start {
+        Some(date) => Ok(parse_date(&date)?.and_hms_opt(0, 0, 0).unwrap().and_utc()),
+        None => Ok(Utc::now()),
+    }
+}
+
+/// Builds transaction details from the description, category and tag arguments
+fn details_arg(
+    description: Option<String>,
+    category: Option<String>,
+    tags: Vec<String>,
+) -> BankResult<TransactionDetails> {
+    let details = TransactionDetails::new().tags(TransactionTags::new(category.as_deref(), tags)?);
+    match description {
+        Some(description) => details.description(&description),
+        None => Ok(details),
+    }
+}
+
+/// Parses an optional limit argument; absent keeps `current`
+fn limit_arg<T: std::str::FromStr>(value: Option<String>, current: Option<T>) -> BankResult<Option<T>> {
+    parse_limit(value.as_deref().unwrap_or(""), current).map_err(BankError::InvalidInput)
+}
+
+/// Maps an optional field argument onto a value; `none` removes it
+fn optional_arg(value: String) -> Option<String> {
+    (!value.eq_ignore_ascii_case("none")).then_some(value)
+}
+
+/// Serializes any value into JSON
+fn to_json<T: serde::Serialize>(value: &T) -> BankResult<Value> {
+    serde_json::to_value(value).map_err(BankError::from)
+}
+
+/// Executes a command against the loaded bank
+fn execute(mut command: Command, bank: &mut Bank, storage: &dyn Storage) -> BankResult<CommandResult> {
+    for reference in command.customer_refs_mut() {
+        *reference = bank.resolve_customer(reference.as_str())?;
+    }
+
+    let result = match command {
+        Command::Register { name, email, pin } => {
+            // Check the PIN first so a bad one doesn't leave a half-registered customer
+            if let Some(pin) = &pin {
+                Credentials::validate_pin(pin)?;
+            }
+            let customer_id = bank.register_customer(name, email)?;
+            if let Some(pin) = pin {
+                bank.set_pin(&customer_id, &pin)?;
+            }
+            CommandResult::read(json!({ "customer_id": customer_id }))
+        }
+        Command::CreateAccount { customer, deposit, account_type, term_months } => {
+            let account_type = match account_type {
+                AccountKind::Checking => AccountType::Checking,
+                AccountKind::Savings => AccountType::Savings,
+                AccountKind::Fixed => AccountType::fixed_deposit(term_months)
+                    .ok_or_else(|| BankError::InvalidInput("invalid term".to_string()))?,
+            };
+            let account_id =
+                bank.create_account_for_customer(&customer, parse_amount(&deposit)?, account_type)?;
+            let number = bank.get_customer(&customer)?.get_account()?.number;
+            CommandResult::read(json!({ "account_id": account_id, "account_number": number }))
+        }
+        Command::Deposit { customer, amount, pin, description } => {
+            bank.authenticate(&customer, &pin)?;
+            let details = details_arg(description, None, Vec::new())?;
+            let balance = bank.deposit_with(&customer, parse_amount(&amount)?, details)?;
+            CommandResult::read(json!({ "balance": balance }))
+        }
+        Command::Withdraw { customer, amount, pin, description, category, tags } => {
+            bank.authenticate(&customer, &pin)?;
+            let details = details_arg(description, category, tags)?;
+            let balance = bank.withdraw_with(&customer, parse_amount(&amount)?, details)?;
+            CommandResult::read(json!({ "balance": balance }))
+        }
+        Command::Authorize { customer, amount, pin, description, category, tags } => {
+            bank.authenticate(&customer, &pin)?;
+            let details = details_arg(description, category, tags)?;
+            CommandResult::read(to_json(&bank.authorize_withdrawal_with(&customer, parse_amount(&amount)?, details)?)?)
+        }
+        Command::Capture { authorization } => CommandResult::read(to_json(&bank.capture(&authorization)?)?),
+        Command::Void { authorization } => CommandResult::read(to_json(&bank.void(&authorization)?)?),
+        Command::Holds { customer } => {
+            let account = bank.get_customer(&customer)?.get_account()?;
+            CommandResult::read(json!({
+                "ledger_balance": account.ledger_balance(),
+                "available_balance": account.available_balance_at(bank.now()),
+                "holds": account.holds,
+            }))
+        }
+        Command::PlaceHold { customer, amount, reason, until } => {
+            let expires_at = until
+                .map(|date| parse_date(&date).map(|date| date.and_hms_opt(0, 0, 0).unwrap().and_utc()))
+                .transpose()?;
+            CommandResult::read(to_json(&bank.place_hold(&customer, parse_amount(&amount)?, &reason, expires_at)?)?)
+        }
+        Command::ReleaseHold { hold } => CommandResult::read(to_json(&bank.release_hold(&hold)?)?),
+        Command::CaptureHold { hold } => CommandResult::read(to_json(&bank.capture_hold(&hold)?)?),
+        Command::Transfer { from, to, to_payee, amount, pin, description, category, tags } => {
+            bank.authenticate(&from, &pin)?;
+            let amount = parse_amount(&amount)?;
+            let details = details_arg(description, category, tags)?;
+            let to = match (to, to_payee) {
+                (Some(to), _) => to,
+                (None, Some(nickname)) => bank.resolve_beneficiary(&from, &nickname)?,
+                (None, None) => return Err(BankError::InvalidInput("--to or --to-payee is required".to_string())),
+            };
+            bank.transfer_with(&from, &to, amount, details)?;
+            CommandResult::read(json!({ "transferred": amount }))
+        }
+        Command::ExternalTransfer { from, to_external, amount, pin, description, category, tags } => {
+            bank.authenticate(&from, &pin)?;
+            let amount = parse_amount(&amount)?;
+            let details = details_arg(description, category, tags)?;
+            CommandResult::read(to_json(&bank.external_transfer_with(&from, &to_external, amount, details)?)?)
+        }
+        Command::PendingTransfers => {
+            let pending: Vec<Value> = bank
+                .pending_external_transfers()
+                .into_iter()
+                .map(|(customer, transaction)| {
+                    json!({ "customer_id": customer.id, "name": customer.name, "transaction": transaction })
+                })
+                .collect();
+            CommandResult::read(Value::Array(pending))
+        }
+        Command::Settle { transaction } => {
+            CommandResult::read(to_json(&bank.settle_external_transfer(&transaction)?)?)
+        }
+        Command::Reject { transaction, reason } => {
+            CommandResult::read(to_json(&bank.reject_external_transfer(&transaction, reason.as_deref())?)?)
+        }
+        Command::DepositCheque { customer, amount, number, drawn_on, pin } => {
+            bank.authenticate(&customer, &pin)?;
+            let cheque = bank.deposit_cheque(&customer, parse_amount(&amount)?, &number, drawn_on.as_ref())?;
+            CommandResult::read(to_json(&cheque)?)
+        }
+        Command::Cheques => {
+            let outstanding: Vec<Value> = bank
+                .outstanding_cheques()
+                .into_iter()
+                .map(|(customer, cheque)| json!({ "customer_id": customer.id, "name": customer.name, "cheque": cheque }))
+                .collect();
+            CommandResult::read(Value::Array(outstanding))
+        }
+        Command::ClearCheques => {
+            let now = bank.now();
+            CommandResult::read(to_json(&bank.clear_due_cheques(now)?)?)
+        }
+        Command::ReturnCheque { cheque, reason } => {
+            CommandResult::read(to_json(&bank.return_cheque(&cheque, reason.as_deref())?)?)
+        }
+        Command::SetChequePolicy { clearing_days, bounce_fee } => {
+            let mut policy = bank.cheque_policy();
+            if let Some(days) = clearing_days {
+                policy.clearing_days = days;
+            }
+            if let Some(fee) = bounce_fee {
+                policy.bounce_fee = parse_amount(&fee)?;
+            }
+            bank.set_cheque_policy(policy)?;
+            CommandResult::read(to_json(&policy)?)
+        }
+        Command::SetDormancyPolicy { days, freeze } => {
+            let mut policy = bank.dormancy_policy();
+            if let Some(days) = days {
+                policy.inactive_days = days;
+            }
+            if let Some(freeze) = freeze {
+                policy.freeze = freeze;
+            }
+            bank.set_dormancy_policy(policy)?;
+            CommandResult::read(to_json(&policy)?)
+        }
+        Command::RunPolicies => {
+            let now = bank.now();
+            CommandResult::read(to_json(&bank.run_policies(now)?)?)
+        }
+        Command::SetFraudRules { max_withdrawals, window_minutes, new_payee_amount } => {
+            let mut rules = bank.fraud_rules();
+            let current = rules.rapid_withdrawals;
+            let count = max_withdrawals.or(current.map(|rule| rule.count)).filter(|&count| count > 0);
+            if window_minutes.is_some() && count.is_none() {
+                return Err(BankError::InvalidInput("--window-minutes needs --max-withdrawals".to_string()));
+            }
+            let minutes = window_minutes.or(current.map(|rule| rule.minutes)).unwrap_or(DEFAULT_WINDOW_MINUTES);
+            rules.rapid_withdrawals = count.map(|count| VelocityRule { count, minutes });
+            rules.new_payee_transfer = limit_arg(new_payee_amount, rules.new_payee_transfer)?;
+            bank.set_fraud_rules(rules)?;
+            CommandResult::read(to_json(&rules)?)
+        }
+        Command::ReviewQueue { all } => {
+            let value = if all { to_json(&bank.flagged_transactions())? } else { to_json(&bank.review_queue())? };
+            CommandResult::read(value)
+        }
+        Command::ApproveFlag { transaction, reviewer } => {
+            CommandResult::read(to_json(&bank.approve_flagged(&transaction, &reviewer)?)?)
+        }
+        Command::ReverseTransaction { transaction, reason } => {
+            CommandResult::read(to_json(&bank.reverse_transaction(&transaction, &reason)?)?)
+        }
+        Command::ReverseFlag { transaction, reviewer } => {
+            CommandResult::read(to_json(&bank.reverse_flagged(&transaction, &reviewer)?)?)
+        }
+        Command::Vault => CommandResult::read(json!({ "vault": bank.vault() })),
+        Command::VaultTopUp { amount } => {
+            CommandResult::read(json!({ "vault": bank.vault_top_up(parse_amount(&amount)?)? }))
+        }
+        Command::VaultWithdraw { amount } => {
+            CommandResult::read(json!({ "vault": bank.vault_withdraw(parse_amount(&amount)?)? }))
+        }
+        Command::Cards { customer } => CommandResult::read(to_json(&bank.get_customer(&customer)?.cards)?),
+        Command::IssueCard { customer, per_purchase, daily } => {
+            let limits = CardLimits { per_purchase: limit_arg(per_purchase, None)?, daily: limit_arg(daily, None)? };
+            CommandResult::read(to_json(&bank.issue_card(&customer, limits)?)?)
+        }
+        Command::BlockCard { card } => CommandResult::read(to_json(&bank.block_card(&card)?)?),
+        Command::ReplaceCard { card } => CommandResult::read(to_json(&bank.replace_card(&card)?)?),
+        Command::SetCardLimits { card, per_purchase, daily } => {
+            let current = bank.find_card(&card)?.1.limits;
+            let limits = CardLimits {
+                per_purchase: limit_arg(per_purchase, current.per_purchase)?,
+                daily: limit_arg(daily, current.daily)?,
+            };
+            bank.set_card_limits(&card, limits)?;
+            CommandResult::read(to_json(&limits)?)
+        }
+        Command::CardPurchase { card, amount, pin, description, category, tags } => {
+            let holder = bank.find_card(&card)?.0.id.clone();
+            bank.authenticate(&holder, &pin)?;
+            let details = details_arg(description, category, tags)?;
+            CommandResult::read(to_json(&bank.card_purchase(&card, parse_amount(&amount)?, details)?)?)
+        }
+        Command::Envelopes { customer } => CommandResult::read(to_json(&bank.list_envelopes(&customer)?)?),
+        Command::CreateEnvelope { customer, name, target, locked } => {
+            CommandResult::read(to_json(&bank.create_envelope(&customer, &name, parse_amount(&target)?, locked)?)?)
+        }
+        Command::Allocate { customer, envelope, amount } => {
+            CommandResult::read(to_json(&bank.allocate_to_envelope(&customer, &envelope, parse_amount(&amount)?)?)?)
+        }
+        Command::Release { customer, envelope, amount } => {
+            CommandResult::read(to_json(&bank.release_from_envelope(&customer, &envelope, parse_amount(&amount)?)?)?)
+        }
+        Command::DepositToEnvelope { customer, envelope, amount, pin, description } => {
+            bank.authenticate(&customer, &pin)?;
+            let details = details_arg(description, None, Vec::new())?;
+            CommandResult::read(to_json(&bank.deposit_to_envelope(&customer, &envelope, parse_amount(&amount)?, details)?)?)
+        }
+        Command::LockEnvelope { customer, envelope } => {
+            CommandResult::read(to_json(&bank.set_envelope_locked(&customer, &envelope, true)?)?)
+        }
+        Command::UnlockEnvelope { customer, envelope } => {
+            CommandResult::read(to_json(&bank.set_envelope_locked(&customer, &envelope, false)?)?)
+        }
+        Command::RemoveEnvelope { customer, envelope } => {
+            CommandResult::read(to_json(&bank.remove_envelope(&customer, &envelope)?)?)
+        }
+        Command::Budgets { customer } => CommandResult::read(to_json(&bank.budget_report(&customer)?)?),
+        Command::SetBudget { customer, category, limit } => {
+            CommandResult::read(to_json(&bank.set_budget(&customer, &category, parse_amount(&limit)?)?)?)
+        }
+        Command::RemoveBudget { customer, category } => {
+            CommandResult::read(to_json(&bank.remove_budget(&customer, &category)?)?)
+        }
+        Command::Merchants => CommandResult::read(to_json(&bank.list_merchants())?),
+        Command::AddMerchant { name, category } => {
+            let category: MerchantCategory = category.parse().map_err(BankError::InvalidInput)?;
+            CommandResult::read(to_json(&bank.add_merchant(&name, category)?)?)
+        }
+        Command::RemoveMerchant { merchant } => CommandResult::read(to_json(&bank.remove_merchant(&merchant)?)?),
+        Command::PayBill { customer, merchant, amount, reference, pin } => {
+            bank.authenticate(&customer, &pin)?;
+            CommandResult::read(to_json(&bank.pay_bill(&customer, &merchant, parse_amount(&amount)?, &reference)?)?)
+        }
+        Command::Autopays { customer } => match customer {
+            Some(customer) => CommandResult::read(to_json(&bank.autopays_for(&customer))?),
+            None => CommandResult::read(to_json(&bank.list_autopays())?),
+        },
+        Command::AddAutopay { customer, merchant, amount, reference, frequency, start } => {
+            let autopay = bank.create_autopay(
+                &customer,
+                &merchant,
+                parse_amount(&amount)?,
+                &reference,
+                frequency_arg(frequency),
+                start_arg(start)?,
+            )?;
+            CommandResult::read(to_json(&autopay)?)
+        }
+        Command::CancelAutopay { id } => CommandResult::read(to_json(&bank.cancel_autopay(&id)?)?),
+        Command::RunAutopays => {
+            let now = bank.now();
+            let runs: Vec<Value> = bank
+                .run_due_autopays(now)
+                .into_iter()
+                .map(|run| {
+                    let outcome = match run.result {
+                        Ok(transaction) => json!({ "transaction": transaction }),
+                        Err(e) => json!({ "error": e.to_string() }),
+                    };
+                    json!({ "autopay_id": run.autopay_id, "run_at": run.run_at, "outcome": outcome })
+                })
+                .collect();
+            CommandResult::read(Value::Array(runs))
+        }
+        Command::TermDeposits { customer } => CommandResult::read(to_json(&bank.term_deposits_for(&customer)?)?),
+        Command::OpenTermDeposit { customer, amount, rate, months, pin } => {
+            bank.authenticate(&customer, &pin)?;
+            let rate = validation::parse_interest_rate(&rate)?;
+            CommandResult::read(to_json(&bank.open_term_deposit(&customer, parse_amount(&amount)?, rate, months)?)?)
+        }
+        Command::WithdrawTermDeposit { customer, deposit, pin } => {
+            bank.authenticate(&customer, &pin)?;
+            CommandResult::read(to_json(&bank.withdraw_term_deposit(&customer, &deposit)?)?)
+        }
+        Command::ProcessMaturities => {
+            let now = bank.now();
+            CommandResult::read(to_json(&bank.process_maturities(now)?)?)
+        }
+        Command::SetPin { customer, pin } => {
+            bank.set_pin(&customer, &pin)?;
+            CommandResult::read(json!({ "customer_id": customer }))
+        }
+        Command::ChangePin { customer, current_pin, new_pin } => {
+            bank.change_pin(&customer, &current_pin, &new_pin)?;
+            CommandResult::read(json!({ "customer_id": customer }))
+        }
+        Command::Payees { customer } => CommandResult::read(to_json(&bank.list_beneficiaries(&customer)?)?),
+        Command::AddPayee { customer, nickname, payee } => {
+            CommandResult::read(to_json(&bank.add_beneficiary(&customer, &nickname, &payee)?)?)
+        }
+        Command::RemovePayee { customer, nickname } => {
+            CommandResult::read(to_json(&bank.remove_beneficiary(&customer, &nickname)?)?)
+        }
+        Command::Limits { customer } => {
+            CommandResult::read(to_json(&bank.get_customer(&customer)?.get_account()?.limits)?)
+        }
+        Command::SetLimits { customer, max_withdrawal, daily_withdrawal, daily_transfers } => {
+            let current = bank.get_customer(&customer)?.get_account()?.limits.clone();
+            let limits = AccountLimits {
+                max_withdrawal: limit_arg(max_withdrawal, current.max_withdrawal)?,
+                daily_withdrawal: limit_arg(daily_withdrawal, current.daily_withdrawal)?,
+                daily_transfers: limit_arg(daily_transfers, current.daily_transfers)?,
+            };
+            bank.set_limits(&customer, limits.clone())?;
+            CommandResult::read(to_json(&limits)?)
+        }
+        Command::Alerts { customer } => {
+            CommandResult::read(to_json(&bank.get_customer(&customer)?.get_account()?.alerts)?)
+        }
+        Command::SetAlerts { customer, low_balance, large_transaction } => {
+            let current = bank.get_customer(&customer)?.get_account()?.alerts.clone();
+            let rules = AlertRules {
+                low_balance: limit_arg(low_balance, current.low_balance)?,
+                large_transaction: limit_arg(large_transaction, current.large_transaction)?,
+            };
+            bank.set_alert_rules(&customer, rules.clone())?;
+            CommandResult::read(to_json(&rules)?)
+        }
+        Command::Webhooks => CommandResult::read(to_json(&bank.list_webhooks())?),
+        Command::AddWebhook { url, events } => {
+            let events = events
+                .into_iter()
+                .map(|kind| match kind {
+                    EventKindArg::CustomerRegistered => EventKind::CustomerRegistered,
+                    EventKindArg::TransactionPosted => EventKind::TransactionPosted,
+                    EventKindArg::TransferCompleted => EventKind::TransferCompleted,
+                })
+                .collect();
+            CommandResult::read(to_json(&bank.add_webhook(&url, events)?)?)
+        }
+        Command::RemoveWebhook { id } => CommandResult::read(to_json(&bank.remove_webhook(&id)?)?),
+        Command::Freeze { customer } => {
+            bank.freeze_account(&customer)?;
+            CommandResult::read(json!({ "status": AccountStatus::Frozen }))
+        }
+        Command::Unfreeze { customer } => {
+            bank.unfreeze_account(&customer)?;
+            CommandResult::read(json!({ "status": AccountStatus::Active }))
+        }
+        Command::CloseAccount { customer, transfer_to } => {
+            let moved = bank.close_account(&customer, transfer_to.as_ref())?;
+            CommandResult::read(json!({ "status": AccountStatus::Closed, "transferred": moved }))
+        }
+        Command::UpdateCustomer { customer, name, email, phone, address, date_of_birth } => {
+            let date_of_birth = match date_of_birth.map(optional_arg) {
+                Some(Some(date)) => Some(Some(validation::parse_date_of_birth(&date)?)),
+                Some(None) => Some(None),
+                None => None,
+            };
+            let changes = CustomerUpdate {
+                name,
+                email,
+                phone: phone.map(optional_arg),
+                address: address.map(optional_arg),
+                date_of_birth,
+            };
+            bank.update_customer(&customer, changes)?;
+            CommandResult::read(to_json(bank.get_customer(&customer)?)?)
+        }
+        Command::DeactivateCustomer { customer } => {
+            bank.deactivate_customer(&customer)?;
+            CommandResult::read(json!({ "customer_id": customer, "status": CustomerStatus::Deactivated }))
+        }
+        Command::ReactivateCustomer { customer } => {
+            bank.reactivate_customer(&customer)?;
+            CommandResult::read(json!({ "customer_id": customer, "status": CustomerStatus::Active }))
+        }
+        Command::DeleteCustomer { customer } => {
+            bank.delete_customer(&customer)?;
+            CommandResult::read(json!({ "customer_id": customer, "status": CustomerStatus::Deleted }))
+        }
+        Command::Account { customer } => CommandResult::read(to_json(bank.get_customer(&customer)?)?),
+        Command::History { customer, page, last } => {
+            // Newest first, so `--last` loads no more archived history than it shows
+            let history = bank.get_transaction_history(&customer)?;
+            let mut shown = match last {
+                Some(last) => history.take(last).collect::<BankResult<Vec<_>>>()?,
+                None => history.collect::<BankResult<Vec<_>>>()?,
+            };
+            shown.reverse();
+            match page.window() {
+                Some((offset, limit)) => CommandResult::read(to_json(&Page::from_items(shown, offset, limit))?),
+                None => CommandResult::read(to_json(&shown)?),
+            }
+        }
+        Command::Ledger { from, to, page } => {
+            let (from, to) = day_range(from, to)?;
+            let entries = bank.all_transactions().between(from, to);
+            match page.window() {
+                Some((offset, limit)) => CommandResult::read(to_json(&Page::from_items(entries, offset, limit))?),
+                None => CommandResult::read(to_json(&entries.collect::<Vec<_>>())?),
+            }
+        }
+        Command::LookupTransaction { id } => {
+            let (owner, transaction) =
+                bank.find_transaction(&id).ok_or_else(|| BankError::TransactionNotFound(id.clone()))?;
+            CommandResult::read(json!({ "transaction": transaction, "owner": owner }))
+        }
+        Command::Receipt { id } => CommandResult::read(to_json(&bank.get_receipt(&id)?)?),
+        Command::SearchTransactions { customer, from, to, min_amount, max_amount, kinds, counterparty, tag } => {
+            let mut filter = TransactionFilter::new();
+            if let Some(from) = from {
+                filter = filter.from(parse_date(&from)?.and_hms_opt(0, 0, 0).unwrap().and_utc());
+            }
+            if let Some(to) = to {
+                filter = filter.to(end_of_day(parse_date(&to)?)?);
+            }
+            if let Some(min) = min_amount {
+                filter = filter.min_amount(parse_amount(&min)?);
+            }
+            if let Some(max) = max_amount {
+                filter = filter.max_amount(parse_amount(&max)?);
+            }
+            for kind in kinds {
+                filter = filter.kind(kind.parse::<TransactionKind>().map_err(BankError::InvalidInput)?);
+            }
+            if let Some(counterparty) = counterparty {
+                filter = filter.counterparty(counterparty);
+            }
+            if let Some(tag) = tag {
+                filter = filter.tag(&tag);
+            }
+            let account = bank.get_customer(&customer)?.get_account()?;
+            CommandResult::read(to_json(&account.filter_transactions(&filter))?)
+        }
+        Command::TagTransaction { customer, transaction, category, tags } => {
+            let tags = TransactionTags::new(category.as_deref(), tags)?;
+            bank.tag_transaction(&customer, &transaction, tags.clone())?;
+            CommandResult::read(json!({ "transaction_id": transaction, "category": tags.category, "tags": tags.tags }))
+        }
+        Command::Categories { customer } => {
+            CommandResult::read(to_json(&bank.get_customer(&customer)?.get_account()?.category_breakdown())?)
+        }
+        Command::Customers { page } => match page.window() {
+            Some((offset, limit)) => CommandResult::read(to_json(&bank.list_customers_page(offset, limit))?),
+            None => CommandResult::read(to_json(&bank.list_customers())?),
+        },
+        Command::Search { query } => CommandResult::read(to_json(&bank.find_customers_by_name(&query))?),
+        Command::Stats => CommandResult::read(json!(bank.report())),
+        Command::TrialBalance { output } => {
+            let trial_balance = bank.trial_balance();
+            match output {
+                Some(path) => {
+                    persistence::export_trial_balance_csv(&trial_balance, &path)?;
+                    CommandResult::read(json!({ "file": path }))
+                }
+                None => CommandResult::read(json!(trial_balance)),
+            }
+        }
+        Command::IncomeStatement { from, to, output } => {
+            let from = from.map(|from| parse_date(&from)).transpose()?;
+            // The last day is inclusive, so stop at the next midnight
+            let to = to.map(|to| end_of_day(parse_date(&to)?)).transpose()?;
+            let statement = bank.income_statement(from.map(|from| from.and_hms_opt(0, 0, 0).unwrap().and_utc()), to);
+            match output {
+                Some(path) => {
+                    persistence::export_income_statement_csv(&statement, &path)?;
+                    CommandResult::read(json!({ "file": path }))
+                }
+                None => CommandResult::read(json!(statement)),
+            }
+        }
+        Command::ChartOfAccounts => CommandResult::read(to_json(bank.chart_of_accounts())?),
+        Command::SetChartAccount { account, code, name, class } => {
+            let mut chart = bank.chart_of_accounts().clone();
+            let account = find_account(&chart, &account)?;
+            if let Some(line) = chart.accounts.iter_mut().find(|line| line.account == account) {
+                if let Some(code) = code {
+                    line.code = validation::validate_ledger_code(&code)?;
+                }
+                if let Some(name) = name {
+                    line.name = validation::validate_ledger_name(&name)?;
+                }
+                if let Some(class) = class {
+                    line.class = class.parse::<AccountClass>().map_err(BankError::InvalidInput)?;
+                }
+            }
+            bank.set_chart_of_accounts(chart)?;
+            CommandResult::read(to_json(bank.chart_of_accounts())?)
+        }
+        Command::MapTransaction { kind, account } => {
+            let mut chart = bank.chart_of_accounts().clone();
+            let kind = kind.parse::<TransactionKind>().map_err(BankError::InvalidInput)?;
+            chart.map(kind, find_account(&chart, &account)?);
+            bank.set_chart_of_accounts(chart)?;
+            CommandResult::read(to_json(bank.chart_of_accounts())?)
+        }
+        Command::CashFlow { period, from, to } => {
+            let period = match period {
+                CashFlowPeriodArg::Daily => CashFlowPeriod::Daily,
+                CashFlowPeriodArg::Weekly => CashFlowPeriod::Weekly,
+            };
+            let (from, to) = day_range(from, to)?;
+            CommandResult::read(json!(bank.cash_flow(period, from, to)))
+        }
+        Command::LargestTransactions { count } => CommandResult::read(json!(bank.largest_transactions(count))),
+        Command::MostActive { count, days } => {
+            CommandResult::read(json!(bank.most_active_accounts(count, Duration::days(days.into()))))
+        }
+        Command::Dormant { days } => CommandResult::read(json!(bank.dormant_accounts(days))),
+        Command::Audit => CommandResult::read(json!(bank.verify_integrity())),
+        Command::ScheduleCreate { from, to, amount, frequency, start } => {
+            let id = bank.create_schedule(&from, &to, parse_amount(&amount)?, frequency_arg(frequency), start_arg(start)?)?;
+            CommandResult::write(json!({ "schedule_id": id }))
+        }
+        Command::Schedules => CommandResult::read(to_json(&bank.list_schedules())?),
+        Command::ScheduleCancel { id } => {
+            bank.cancel_schedule(&id)?;
+            CommandResult::write(json!({ "cancelled": id }))
+        }
+        Command::RestoreBackup { index } => {
+            *bank = storage.restore_backup(index)?;
+            CommandResult::read(json!({ "restored": index }))
+        }
+        Command::Export { customer, format, output } => {
+            let account = bank.get_customer(&customer)?.get_account()?;
+            let (path, count) = match format {
+                ExportFormat::Csv => {
+                    let path = output.unwrap_or_else(|| "transactions.csv".to_string());
+                    let count = persistence::export_transactions_csv(account, &path)?;
+                    (path, count)
+                }
+                ExportFormat::Json => {
+                    let path = output.unwrap_or_else(|| "transactions.json".to_string());
+                    let json = serde_json::to_string_pretty(&account.transactions)?;
+                    fs::write(&path, json)?;
+                    (path, account.transactions.len())
+                }
+            };
+            CommandResult::read(json!({ "file": path, "transactions": count }))
+        }
+        Command::Import { customer, file } => {
+            let transactions = persistence::import_transactions_csv(&file)?;
+            let count = bank.import_transactions(&customer, transactions)?;
+            CommandResult::read(json!({ "imported": count }))
+        }
+        Command::ImportCustomers { file } => {
+            let import = bank.import_customers(fs::File::open(&file)?)?;
+            CommandResult::read(import_json(&import))
+        }
+        Command::ExportBank { output, format } => {
+            let format = persistence::export_bank(bank, &output, format.map(data_format_arg))?;
+            CommandResult::read(json!({ "file": output, "format": format }))
+        }
+        Command::Diff { before, after } => CommandResult::read(to_json(&persistence::diff(&before, &after)?)?),
+        Command::Batch { file, all_or_nothing } => {
+            let operations = persistence::load_batch(&file)?;
+            let mode = if all_or_nothing { BatchMode::AllOrNothing } else { BatchMode::BestEffort };
+            CommandResult::read(batch_json(&bank.apply_batch(operations, mode)))
+        }
+        Command::Seed { customers, from, to, rng_seed } => {
+            let mut options = SeedOptions::new(customers);
+            if let Some(from) = from {
+                options = options.from(parse_date(&from)?);
+            }
+            if let Some(to) = to {
+                options = options.to(parse_date(&to)?);
+            }
+            if let Some(seed) = rng_seed {
+                options = options.rng_seed(seed);
+            }
+            let ids = bank.seed(options)?;
+            CommandResult::read(json!({ "customers": ids.len(), "transactions": bank.total_transactions }))
+        }
+        Command::Archive { before } => {
+            let before = parse_date(&before)?.and_hms_opt(0, 0, 0).unwrap().and_utc();
+            CommandResult::read(to_json(&bank.archive_transactions(before)?)?)
+        }
+        Command::Statement { customer, from, to } => {
+            let from = parse_date(&from)?.and_hms_opt(0, 0, 0).unwrap().and_utc();
+            let to = end_of_day(parse_date(&to)?)?;
+            CommandResult::read(to_json(&bank.statement(&customer, from, to)?)?)
+        }
+        Command::MonthlySummary { customer, month, output } => {
+            let (year, month) = parse_month(&month)?;
+            let summary = bank.monthly_summary(&customer, year, month)?;
+            match output {
+                Some(path) => {
+                    persistence::export_monthly_summaries_csv(std::slice::from_ref(&summary), &path)?;
+                    CommandResult::read(json!({ "file": path }))
+                }
+                None => CommandResult::read(to_json(&summary)?),
+            }
+        }
+        // Long-running; dispatched by `main` before a command is executed
+        Command::Shell => return Err(BankError::InvalidInput("shell is not a single command".to_string())),
+        Command::Run { .. } => return Err(BankError::InvalidInput("run is not a single command".to_string())),
+        #[cfg(feature = "server")]
+        Command::Serve { .. } => {
+            return Err(BankError::InvalidInput("serve is not a single command".to_string()))
+        }
+        #[cfg(feature = "grpc")]
+        Command::Grpc { .. } => {
+            return Err(BankError::InvalidInput("grpc is not a single command".to_string()))
+        }
+        #[cfg(feature = "tui")]
+        Command::Tui => {
+            return Err(BankError::InvalidInput("tui is not a single command".to_string()))
+        }
+    };
+    Ok(result)
+}
@@ -3,99 +3,359 @@
 //! Demonstrates: Function organization, user interaction patterns
 
 use std::io;
-use std::sync::{Arc, Mutex};
 
-use crate::bank::Bank;
+use chrono::Utc;
+use serde_json::json;
+
+use crate::bank::{SharedBank, SortKey, SortOrder};
+use crate::errors::BankResult;
+use crate::models::{Customer, CustomerStatus, CustomerUpdate, Page};
 use crate::traits::Summarizable;
-use super::utils::read_input;
+use crate::validation;
+use super::auth_ops::read_new_pin;
+use super::envelope_ops::envelope_lines;
+use super::i18n::Msg;
+use super::render::Renderer;
+use super::session::{authorize, Session, ADMIN_ONLY, ANY_ROLE, STAFF};
+use super::table::customer_table;
+use super::utils::{
+    browse_pages, detailed_list, read_checked, read_customer, read_input, unless_blank, verbose, PAGE_SIZE,
+};
 
-/// Registers a new customer
+/// Registers a new customer (admins only)
 ///
-/// Demonstrates: Arc/Mutex usage, error handling in CLI context
-pub fn register_customer(bank: &Arc<Mutex<Bank>>) -> io::Result<()> {
-    println!("\n--- Register New Customer ---");
+/// Demonstrates: SharedBank usage, error handling in CLI context
+pub fn register_customer(
+    bank: &SharedBank,
+    session: Option<&Session>,
+    renderer: &dyn Renderer,
+) -> io::Result<()> {
+    if authorize(session, ADMIN_ONLY, renderer).is_none() {
+        return Ok(());
+    }
 
-    let name = read_input("Enter customer name: ")?;
-    let email = read_input("Enter customer email: ")?;
+    renderer.section(Msg::RegisterCustomer.text());
 
-    let mut bank = bank.lock().unwrap();
+    let name = read_input(Msg::EnterCustomerName.text())?;
+    let email = read_checked(Msg::EnterCustomerEmail.text(), renderer, validation::validate_email)?;
+    let pin = match read_new_pin(renderer)? {
+        Some(pin) => pin,
+        None => return Ok(()),
+    };
 
-    match bank.register_customer(name, email) {
-        Ok(customer_id) => {
-            println!("\n✅ Customer registered successfully!");
-            println!("📋 Customer ID: {}\n", customer_id);
-        }
-        Err(e) => println!("\n❌ Error: {}\n", e),
+    let mut bank = bank.write();
+
+    let result = bank
+        .register_customer(name, email)
+        .and_then(|customer_id| bank.set_pin(&customer_id, &pin).map(|()| customer_id));
+
+    match result {
+        Ok(customer_id) => renderer.success(
+            &Msg::CustomerRegistered.fill(&[&customer_id]),
+            json!({ "customer_id": customer_id }),
+        ),
+        Err(e) => renderer.failure(&e),
     }
 
     Ok(())
 }
 
-/// Lists all customers
-pub fn list_all_customers(bank: &Arc<Mutex<Bank>>) -> io::Result<()> {
-    println!("\n--- All Customers ---");
+/// Lists all customers, a page at a time (staff only)
+pub fn list_all_customers(
+    bank: &SharedBank,
+    session: Option<&Session>,
+    renderer: &dyn Renderer,
+) -> io::Result<()> {
+    if authorize(session, STAFF, renderer).is_none() {
+        return Ok(());
+    }
 
-    let bank = bank.lock().unwrap();
-    let customers = bank.list_customers();
+    renderer.section(Msg::AllCustomers.text());
 
-    if customers.is_empty() {
-        println!("\n📭 No customers registered yet.\n");
-    } else {
-        println!("\n👥 Total Customers: {}", customers.len());
-        println!("─────────────────────────────────────────\n");
+    let Some((key, order)) = read_customer_sort(renderer)? else {
+        return Ok(());
+    };
 
-        for customer in customers {
-            println!("  • {}", customer.summary());
-        }
-        println!();
-    }
+    browse_pages(renderer, |offset| {
+        let bank = bank.read();
+        let page = Page::from_items(bank.list_customers_sorted(key, order), offset, PAGE_SIZE);
 
-    Ok(())
+        let text = if page.total == 0 {
+            Msg::NoCustomersYet.text().to_string()
+        } else {
+            Msg::CustomersPage.fill(&[&page.total, &page.number(), &page.count(), &customer_listing(&page.items)])
+        };
+        renderer.success(&text, json!(page));
+        (page.has_prev(), page.has_next())
+    })
 }
 
-/// Searches for customers by name
-pub fn search_customers(bank: &Arc<Mutex<Bank>>) -> io::Result<()> {
-    println!("\n--- Search Customers ---");
-
-    let query = read_input("Enter search query (name): ")?;
+/// Asks what to sort a customer listing by, and which way
+///
+/// # Returns
+/// * `Ok(None)` - If an answer was not one of the choices (already reported)
+fn read_customer_sort(renderer: &dyn Renderer) -> io::Result<Option<(SortKey, SortOrder)>> {
+    renderer.chrome(Msg::CustomerSortKeys.text());
+    let key = match read_input(Msg::EnterChoiceDefaultOne.text())?.as_str() {
+        "" | "1" => SortKey::Registered,
+        "2" => SortKey::Name,
+        "3" => SortKey::Balance,
+        _ => {
+            renderer.failure(&Msg::InvalidChoice);
+            return Ok(None);
+        }
+    };
 
-    let bank = bank.lock().unwrap();
-    let results = bank.find_customers_by_name(&query);
+    renderer.chrome(Msg::SortDirections.text());
+    let order = match read_input(Msg::EnterChoiceDefaultOne.text())?.as_str() {
+        "" | "1" => SortOrder::Ascending,
+        "2" => SortOrder::Descending,
+        _ => {
+            renderer.failure(&Msg::InvalidChoice);
+            return Ok(None);
+        }
+    };
+    Ok(Some((key, order)))
+}
 
-    if results.is_empty() {
-        println!("\n🔍 No customers found matching '{}'\n", query);
+/// `customers` as a table, or as detailed summaries in verbose mode
+fn customer_listing(customers: &[&Customer]) -> String {
+    if verbose() {
+        detailed_list(customers)
     } else {
-        println!("\n🔍 Found {} customer(s):", results.len());
-        println!("─────────────────────────────────────────\n");
+        customer_table(customers).render()
+    }
+}
 
-        for customer in results {
-            println!("  • {}", customer.summary());
-        }
-        println!();
+/// Searches for customers by name (staff only)
+pub fn search_customers(
+    bank: &SharedBank,
+    session: Option<&Session>,
+    renderer: &dyn Renderer,
+) -> io::Result<()> {
+    if authorize(session, STAFF, renderer).is_none() {
+        return Ok(());
     }
 
+    renderer.section(Msg::SearchCustomers.text());
+
+    let query = read_input(Msg::EnterSearchQuery.text())?;
+    let Some((key, order)) = read_customer_sort(renderer)? else {
+        return Ok(());
+    };
+
+    let bank = bank.read();
+    let mut results = bank.find_customers_by_name(&query);
+    key.sort(&mut results, order);
+
+    let text = if results.is_empty() {
+        Msg::NoCustomersMatching.fill(&[&query])
+    } else {
+        Msg::FoundCustomers.fill(&[&results.len(), &customer_listing(&results)])
+    };
+    renderer.success(&text, json!(results));
+
     Ok(())
 }
 
 /// Views account details for a customer
-pub fn view_account_details(bank: &Arc<Mutex<Bank>>) -> io::Result<()> {
-    println!("\n--- Account Details ---");
+///
+/// Customers only see their own details.
+pub fn view_account_details(
+    bank: &SharedBank,
+    session: Option<&Session>,
+    renderer: &dyn Renderer,
+) -> io::Result<()> {
+    let session = match authorize(session, ANY_ROLE, renderer) {
+        Some(session) => session,
+        None => return Ok(()),
+    };
 
-    let customer_id = read_input("Enter customer ID: ")?;
+    renderer.section(Msg::AccountDetails.text());
 
-    let bank = bank.lock().unwrap();
+    let customer_id = match session.target_customer(bank, Msg::EnterCustomer.text(), renderer)? {
+        Some(id) => id,
+        None => return Ok(()),
+    };
+
+    let bank = bank.read();
 
     match bank.get_customer(&customer_id) {
         Ok(customer) => {
-            println!("\n{}", customer.summary());
+            let mut text = Msg::DetailsEmail.fill(&[&customer.summary(), &customer.email]);
+            if let Some(phone) = &customer.phone {
+                text.push_str(&Msg::DetailsPhone.fill(&[phone]));
+            }
+            if let Some(address) = &customer.address {
+                text.push_str(&Msg::DetailsAddress.fill(&[address]));
+            }
+            if let Some(date_of_birth) = customer.date_of_birth {
+                text.push_str(&Msg::DetailsBirthDate.fill(&[&date_of_birth]));
+            }
             if let Some(account) = &customer.account {
-                println!("\n📊 Account Statistics:");
-                println!("  Total Deposits: ${:.2}", account.total_deposits());
-                println!("  Total Withdrawals: ${:.2}", account.total_withdrawals());
-                println!("  Transaction Count: {}\n", account.transactions.len());
+                text.push_str(&Msg::AccountStatistics.fill(&[
+                    &account.account_type,
+                    &account.status,
+                    &account.available_balance().display(),
+                    &account.held_amount().display(),
+                    &account.total_deposits().display(),
+                    &account.total_withdrawals().display(),
+                    &account.transactions.len(),
+                ]));
+                if !account.envelopes.is_empty() {
+                    text.push_str(&Msg::SavingsGoalsHeading.fill(&[
+                        &account.allocated_amount().display(),
+                        &envelope_lines(&account.envelopes),
+                    ]));
+                }
             }
+            renderer.success(&text, json!(customer));
         }
-        Err(e) => println!("\n❌ Error: {}\n", e),
+        Err(e) => renderer.failure(&e),
+    }
+
+    Ok(())
+}
+
+/// Edits a customer's name, email, phone number or address
+///
+/// Customers can only edit their own profile.
+pub fn edit_customer(
+    bank: &SharedBank,
+    session: Option<&Session>,
+    renderer: &dyn Renderer,
+) -> io::Result<()> {
+    let session = match authorize(session, ANY_ROLE, renderer) {
+        Some(session) => session,
+        None => return Ok(()),
+    };
+
+    renderer.section(Msg::EditCustomer.text());
+
+    let customer_id = match session.target_customer(bank, Msg::EnterCustomer.text(), renderer)? {
+        Some(id) => id,
+        None => return Ok(()),
+    };
+    let current = match bank.read().get_customer(&customer_id) {
+        Ok(customer) => customer.clone(),
+        Err(e) => {
+            renderer.failure(&e);
+            return Ok(());
+        }
+    };
+
+    renderer.chrome(Msg::EditHelp.text());
+    let changes = match read_customer_update(&current, renderer)? {
+        Some(changes) if changes.is_empty() => {
+            renderer.failure(&Msg::NothingChanged);
+            return Ok(());
+        }
+        Some(changes) => changes,
+        None => return Ok(()),
+    };
+
+    let mut bank = bank.write();
+
+    match bank.update_customer(&customer_id, changes) {
+        Ok(()) => {
+            let customer = bank.get_customer(&customer_id).ok();
+            renderer.success(Msg::CustomerUpdated.text(), json!(customer));
+        }
+        Err(e) => renderer.failure(&e),
+    }
+
+    Ok(())
+}
+
+/// Prompts for each profile field, validating as it goes
+///
+/// # Returns
+/// * `Ok(None)` - If a field was rejected too often (errors already shown)
+fn read_customer_update(current: &Customer, renderer: &dyn Renderer) -> io::Result<Option<CustomerUpdate>> {
+    let name = read_checked(&Msg::EnterNewName.fill(&[&current.name]), renderer, |input| {
+        unless_blank(input, validation::validate_name)
+    })?;
+    let email = read_checked(&Msg::EnterNewEmail.fill(&[&current.email]), renderer, |input| {
+        unless_blank(input, validation::validate_email)
+    })?;
+    let phone = read_checked(&optional_prompt(Msg::PhoneLabel.text(), &current.phone), renderer, |input| {
+        keep_remove_or(input, validation::normalize_phone)
+    })?;
+    let address = read_checked(&optional_prompt(Msg::AddressLabel.text(), &current.address), renderer, |input| {
+        keep_remove_or(input, validation::validate_address)
+    })?;
+
+    let today = Utc::now().date_naive();
+    let born = current.date_of_birth.map(|d| d.to_string());
+    let date_of_birth = read_checked(&optional_prompt(Msg::BirthDateLabel.text(), &born), renderer, |input| {
+        keep_remove_or(input, |input| {
+            let date = validation::parse_date_of_birth(input)?;
+            validation::validate_date_of_birth(date, today).map(|()| date)
+        })
+    })?;
+
+    Ok(Some(CustomerUpdate { name, email, phone, address, date_of_birth }))
+}
+
+/// Prompt for an optional field, showing its current value
+fn optional_prompt(label: &str, current: &Option<String>) -> String {
+    format!("{} [{}]: ", label, current.as_deref().unwrap_or("none"))
+}
+
+/// Blank input keeps the field, `none` removes it, anything else must
+/// pass `check`
+fn keep_remove_or<T>(input: &str, check: impl Fn(&str) -> BankResult<T>) -> BankResult<Option<Option<T>>> {
+    if input.eq_ignore_ascii_case("none") {
+        return Ok(Some(None));
+    }
+    unless_blank(input, check).map(|value| value.map(Some))
+}
+
+/// Deactivates, reactivates or deletes a customer (admins only)
+pub fn manage_customer_status(
+    bank: &SharedBank,
+    session: Option<&Session>,
+    renderer: &dyn Renderer,
+) -> io::Result<()> {
+    if authorize(session, ADMIN_ONLY, renderer).is_none() {
+        return Ok(());
+    }
+
+    renderer.section(Msg::DeactivateDeleteCustomer.text());
+
+    let customer_id = match read_customer(bank, Msg::EnterCustomer.text(), renderer)? {
+        Some(id) => id,
+        None => return Ok(()),
+    };
+    renderer.chrome(Msg::CustomerStatusActions.text());
+    let action = read_input(Msg::EnterAction.text())?;
+
+    if action == "3" {
+        let confirm = read_input(&Msg::ConfirmDeletion.fill(&[&Msg::ConfirmWord]))?;
+        if confirm.to_lowercase() != Msg::ConfirmWord.text() {
+            renderer.failure(&Msg::DeletionCancelled);
+            return Ok(());
+        }
+    }
+
+    let mut bank = bank.write();
+
+    let result = match action.as_str() {
+        "1" => bank.deactivate_customer(&customer_id).map(|()| CustomerStatus::Deactivated),
+        "2" => bank.reactivate_customer(&customer_id).map(|()| CustomerStatus::Active),
+        "3" => bank.delete_customer(&customer_id).map(|()| CustomerStatus::Deleted),
+        _ => {
+            renderer.failure(&Msg::InvalidAction);
+            return Ok(());
+        }
+    };
+
+    match result {
+        Ok(status) => renderer.success(
+            &Msg::CustomerNowStatus.fill(&[&status]),
+            json!({ "customer_id": customer_id, "status": status }),
+        ),
+        Err(e) => renderer.failure(&e),
     }
 
     Ok(())
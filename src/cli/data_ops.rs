@@ -0,0 +1,333 @@
+//! Data file CLI operations - unlocking storage, backups, CSV export/import,
+//! batch files, bulk onboarding
+//!
+//! Demonstrates: Trait objects (&dyn Storage), confirmation prompts
+
+use std::fs::File;
+use std::io;
+
+use serde_json::{json, Value};
+
+use crate::bank::{BatchMode, CustomerImport, SharedBank};
+use crate::config::Config;
+use crate::errors::{BankError, BankResult};
+use crate::format;
+use crate::models::Money;
+use crate::persistence::{self, EncryptedFileStorage, JsonFileStorage, Storage};
+use super::i18n::Msg;
+use super::render::Renderer;
+use super::session::{authorize, Session, ADMIN_ONLY, STAFF};
+use super::startup::guard_storage;
+use super::table::import_table;
+use super::utils::{read_customer, read_input, read_passphrase, read_yes};
+
+/// Maximum passphrase attempts before giving up on an encrypted file
+const MAX_PASSPHRASE_ATTEMPTS: u32 = 3;
+
+/// Chooses the storage backend for the configured data file, prompting
+/// for a passphrase
+///
+/// - Encrypted files must be unlocked with the right passphrase
+/// - Plaintext or missing files may optionally be encrypted from now on
+/// - With `read_only` set, the storage is wrapped so it never writes
+/// - With `lazy_load` set, a plaintext file's histories are parsed on use
+///
+/// # Returns
+/// * `Err(io::Error)` - If an encrypted file could not be unlocked
+pub fn open_storage(config: &Config) -> io::Result<Box<dyn Storage>> {
+    let data_file = config.data_file.as_str();
+    if persistence::is_encrypted_file(data_file) {
+        for _ in 0..MAX_PASSPHRASE_ATTEMPTS {
+            let passphrase = read_passphrase(Msg::EnterPassphrase.text())?;
+            let storage = EncryptedFileStorage::new(data_file, passphrase).with_backups(config.backup_count);
+
+            match storage.read_bank() {
+                Ok(_) => return Ok(guard_storage(Box::new(storage), config)),
+                Err(e) => eprintln!("❌ {}", e),
+            }
+        }
+        return Err(io::Error::other(Msg::CouldNotUnlock.text()));
+    }
+
+    // Nothing will be written, so there is nothing to encrypt
+    if config.read_only {
+        let storage = JsonFileStorage::new(data_file).with_lazy_load(config.lazy_load);
+        return Ok(guard_storage(Box::new(storage), config));
+    }
+
+    let passphrase = read_passphrase(Msg::SetPassphrase.text())?;
+
+    if passphrase.is_empty() {
+        let storage = JsonFileStorage::new(data_file)
+            .with_backups(config.backup_count)
+            .with_lazy_load(config.lazy_load);
+        Ok(Box::new(storage))
+    } else {
+        Ok(Box::new(EncryptedFileStorage::new(data_file, passphrase).with_backups(config.backup_count)))
+    }
+}
+
+/// Restores the bank from one of the rotated backups
+///
+/// Replaces the in-memory bank with the restored data. Admins only.
+pub fn restore_backup(
+    bank: &SharedBank,
+    storage: &dyn Storage,
+    session: Option<&Session>,
+    renderer: &dyn Renderer,
+) -> io::Result<()> {
+    if authorize(session, ADMIN_ONLY, renderer).is_none() {
+        return Ok(());
+    }
+
+    renderer.section(Msg::RestoreBackup.text());
+
+    let backups = storage.list_backups();
+    if backups.is_empty() {
+        renderer.success(
+            &Msg::NoBackups.fill(&[&storage.location()]),
+            json!({ "backups": [] }),
+        );
+        return Ok(());
+    }
+
+    renderer.chrome("");
+    for backup in &backups {
+        let when = backup
+            .modified
+            .map(|m| format::datetime(&m))
+            .unwrap_or_else(|| Msg::UnknownTime.text().to_string());
+        renderer.chrome(&Msg::BackupLine.fill(&[&backup.index, &backup.path, &when]));
+    }
+
+    let choice = read_input(Msg::EnterBackupNumber.text())?;
+    let index = match choice.parse::<usize>() {
+        Ok(index) if backups.iter().any(|b| b.index == index) => index,
+        _ => {
+            renderer.failure(&Msg::InvalidBackupNumber);
+            return Ok(());
+        }
+    };
+
+    if !read_yes(Msg::ConfirmRestore.text())? {
+        renderer.success(Msg::RestoreCancelled.text(), json!({ "restored": null }));
+        return Ok(());
+    }
+
+    match storage.restore_backup(index) {
+        Ok(mut restored) => {
+            restored.enable_journal();
+            // The cap is a setting, not bank data, so it carries over
+            let cap = bank.read().history_cap();
+            if let Err(e) = restored.set_history_cap(cap) {
+                renderer.failure(&e);
+            }
+            *bank.write() = restored;
+            renderer.success(
+                &Msg::BackupRestored.fill(&[&index]),
+                json!({ "restored": index }),
+            );
+        }
+        Err(e) => renderer.failure(&e),
+    }
+
+    Ok(())
+}
+
+/// Exports a customer's transaction history to a CSV file (staff only)
+pub fn export_transactions(
+    bank: &SharedBank,
+    session: Option<&Session>,
+    renderer: &dyn Renderer,
+) -> io::Result<()> {
+    if authorize(session, STAFF, renderer).is_none() {
+        return Ok(());
+    }
+
+    renderer.section(Msg::ExportTransactions.text());
+
+    let customer_id = match read_customer(bank, Msg::EnterCustomer.text(), renderer)? {
+        Some(id) => id,
+        None => return Ok(()),
+    };
+    let path = read_input(Msg::EnterExportFile.text())?;
+    let path = if path.is_empty() { "transactions.csv".to_string() } else { path };
+
+    let bank = bank.read();
+
+    let result = bank
+        .get_customer(&customer_id)
+        .and_then(|customer| customer.get_account())
+        .and_then(|account| persistence::export_transactions_csv(account, &path));
+
+    match result {
+        Ok(count) => renderer.success(
+            &Msg::TransactionsExported.fill(&[&count, &path]),
+            json!({ "file": path, "transactions": count }),
+        ),
+        Err(e) => renderer.failure(&e),
+    }
+
+    Ok(())
+}
+
+/// Imports transactions from a CSV file into a customer's account (admins only)
+pub fn import_transactions(
+    bank: &SharedBank,
+    session: Option<&Session>,
+    renderer: &dyn Renderer,
+) -> io::Result<()> {
+    if authorize(session, ADMIN_ONLY, renderer).is_none() {
+        return Ok(());
+    }
+
+    renderer.section(Msg::ImportTransactions.text());
+
+    let customer_id = match read_customer(bank, Msg::EnterCustomer.text(), renderer)? {
+        Some(id) => id,
+        None => return Ok(()),
+    };
+    let path = read_input(Msg::EnterImportFile.text())?;
+
+    let transactions = match persistence::import_transactions_csv(&path) {
+        Ok(transactions) => transactions,
+        Err(e) => {
+            renderer.failure(&e);
+            return Ok(());
+        }
+    };
+
+    let mut bank = bank.write();
+
+    match bank.import_transactions(&customer_id, transactions) {
+        Ok(count) => renderer.success(
+            &Msg::TransactionsImported.fill(&[&count]),
+            json!({ "imported": count }),
+        ),
+        Err(e) => renderer.failure(&e),
+    }
+
+    Ok(())
+}
+
+/// Applies deposits, withdrawals and transfers from a CSV or JSON batch
+/// file, e.g. a payroll run (admins only)
+pub fn process_batch(
+    bank: &SharedBank,
+    session: Option<&Session>,
+    renderer: &dyn Renderer,
+) -> io::Result<()> {
+    if authorize(session, ADMIN_ONLY, renderer).is_none() {
+        return Ok(());
+    }
+
+    renderer.section(Msg::ProcessBatchFile.text());
+
+    let path = read_input(Msg::EnterBatchFile.text())?;
+    let operations = match persistence::load_batch(&path) {
+        Ok(operations) => operations,
+        Err(e) => {
+            renderer.failure(&e);
+            return Ok(());
+        }
+    };
+
+    let mode = if read_yes(Msg::AllOrNothing.text())? {
+        BatchMode::AllOrNothing
+    } else {
+        BatchMode::BestEffort
+    };
+
+    let labels: Vec<String> = operations.iter().map(ToString::to_string).collect();
+    let results = bank.write().apply_batch(operations, mode);
+
+    let mut text = String::from("\n");
+    for (i, (label, result)) in labels.iter().zip(&results).enumerate() {
+        match result {
+            Ok(balance) => text.push_str(&Msg::BatchApplied.fill(&[&(i + 1), label, &balance.display()])),
+            Err(e) => text.push_str(&format!("  {}. ❌ {}: {}\n", i + 1, label, e)),
+        }
+    }
+    let applied = results.iter().filter(|r| r.is_ok()).count();
+    text.push_str(&Msg::BatchSummary.fill(&[&applied, &results.len()]));
+
+    renderer.success(&text, batch_json(&results));
+    Ok(())
+}
+
+/// JSON view of batch results, shared with the command mode
+pub(crate) fn batch_json(results: &[BankResult<Money>]) -> Value {
+    let results: Vec<Value> = results
+        .iter()
+        .map(|result| match result {
+            Ok(balance) => json!({ "ok": true, "balance": balance }),
+            Err(e) => json!({ "ok": false, "error": e.to_string() }),
+        })
+        .collect();
+    json!({
+        "applied": results.iter().filter(|r| r["ok"] == true).count(),
+        "operations": results.len(),
+        "results": results,
+    })
+}
+
+/// Registers customers with checking accounts from a CSV file of
+/// `name,email,initial_deposit` rows and prints a table of what happened
+/// to each row (admins only)
+pub fn import_customers(
+    bank: &SharedBank,
+    session: Option<&Session>,
+    renderer: &dyn Renderer,
+) -> io::Result<()> {
+    if authorize(session, ADMIN_ONLY, renderer).is_none() {
+        return Ok(());
+    }
+
+    renderer.section(Msg::ImportCustomers.text());
+
+    let path = read_input(Msg::EnterCustomerCsv.text())?;
+    let result = File::open(&path)
+        .map_err(BankError::from)
+        .and_then(|file| bank.write().import_customers(file));
+
+    match result {
+        Ok(import) if import.rows.is_empty() => {
+            renderer.success(Msg::NoCustomersInFile.text(), import_json(&import));
+        }
+        Ok(import) => {
+            let text = Msg::CustomersImported.fill(&[
+                &import_table(&import).render(),
+                &import.imported(),
+                &import.rows.len(),
+                &import.failed(),
+            ]);
+            renderer.success(&text, import_json(&import));
+        }
+        Err(e) => renderer.failure(&e),
+    }
+    Ok(())
+}
+
+/// JSON view of a customer import, shared with the command mode
+pub(crate) fn import_json(import: &CustomerImport) -> Value {
+    let rows: Vec<Value> = import
+        .rows
+        .iter()
+        .map(|row| match &row.result {
+            Ok(customer_id) => json!({
+                "line": row.line,
+                "ok": true,
+                "customer_id": customer_id,
+                "email": row.email,
+                "initial_deposit": row.initial_deposit,
+            }),
+            Err(e) => json!({ "line": row.line, "ok": false, "email": row.email, "error": e.to_string() }),
+        })
+        .collect();
+    json!({
+        "imported": import.imported(),
+        "failed": import.failed(),
+        "total_deposited": import.total_deposited(),
+        "rows": rows,
+    })
+}
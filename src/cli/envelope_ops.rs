@@ -0,0 +1,146 @@
+//! Savings goal CLI operations
+//!
+//! Demonstrates: Drawing progress bars with repeated characters
+
+use std::io;
+
+use serde_json::json;
+
+use crate::bank::SharedBank;
+use crate::models::Envelope;
+use crate::validation;
+use super::account_ops::read_details;
+use super::i18n::Msg;
+use super::render::Renderer;
+use super::session::{authorize, Session, ANY_ROLE};
+use super::utils::{read_amount, read_checked, read_input, read_yes};
+
+/// Width of a progress bar, in characters
+const BAR_WIDTH: usize = 20;
+
+/// Lists a customer's savings goals and creates, funds, empties, locks
+/// or removes them; customers manage their own
+pub fn manage_envelopes(
+    bank: &SharedBank,
+    session: Option<&Session>,
+    renderer: &dyn Renderer,
+) -> io::Result<()> {
+    let session = match authorize(session, ANY_ROLE, renderer) {
+        Some(session) => session,
+        None => return Ok(()),
+    };
+
+    renderer.section(Msg::SavingsGoals.text());
+
+    let customer_id = match session.target_customer(bank, Msg::EnterCustomer.text(), renderer)? {
+        Some(id) => id,
+        None => return Ok(()),
+    };
+
+    loop {
+        let (envelopes, free) = {
+            let bank = bank.read();
+            match bank.get_customer(&customer_id).and_then(|c| c.get_account()) {
+                Ok(account) => (account.envelopes.clone(), account.unallocated_balance_at(bank.now())),
+                Err(e) => {
+                    renderer.failure(&e);
+                    return Ok(());
+                }
+            }
+        };
+        if envelopes.is_empty() {
+            renderer.chrome(Msg::NoSavingsGoals.text());
+        } else {
+            renderer.chrome(&format!("\n{}", envelope_lines(&envelopes)));
+        }
+        renderer.chrome(&Msg::FreeToSpend.fill(&[&free.display()]));
+
+        renderer.chrome(Msg::SavingsGoalActions.text());
+        let action = read_input(Msg::EnterAction.text())?;
+        let result = match action.as_str() {
+            "" => return Ok(()),
+            "1" => {
+                let name = read_checked(Msg::GoalName.text(), renderer, validation::validate_envelope_name)?;
+                let target = read_amount(Msg::TargetAmount.text(), renderer)?;
+                let locked = read_yes(Msg::LockGoal.text())?;
+                bank.write().create_envelope(&customer_id, &name, target, locked)
+            }
+            "2" | "3" | "4" | "5" | "6" => {
+                let envelope = match choose_envelope(&envelopes, renderer)? {
+                    Some(envelope) => envelope,
+                    None => continue,
+                };
+                match action.as_str() {
+                    "2" => {
+                        let amount = read_amount(Msg::AmountToDeposit.text(), renderer)?;
+                        let details = match read_details(renderer, false)? {
+                            Some(details) => details,
+                            None => continue,
+                        };
+                        let result = bank.write().deposit_to_envelope(&customer_id, &envelope.name, amount, details);
+                        result.map(|_| Envelope { allocated: envelope.allocated + amount, ..envelope })
+                    }
+                    "3" => {
+                        let amount = read_amount(Msg::AmountToSetAside.text(), renderer)?;
+                        bank.write().allocate_to_envelope(&customer_id, &envelope.name, amount)
+                    }
+                    "4" => {
+                        let amount = read_amount(Msg::AmountToTakeOut.text(), renderer)?;
+                        bank.write().release_from_envelope(&customer_id, &envelope.name, amount)
+                    }
+                    "5" => bank.write().set_envelope_locked(&customer_id, &envelope.name, !envelope.locked),
+                    _ => match bank.write().remove_envelope(&customer_id, &envelope.name) {
+                        Ok(removed) => {
+                            renderer.success(&Msg::GoalRemoved.fill(&[&removed.name]), json!(removed));
+                            continue;
+                        }
+                        Err(e) => Err(e),
+                    },
+                }
+            }
+            _ => {
+                renderer.failure(&Msg::InvalidChoice);
+                continue;
+            }
+        };
+        match result {
+            Ok(envelope) => renderer.success(&format!("\n✅ {}", envelope), json!(envelope)),
+            Err(e) => renderer.failure(&e),
+        }
+    }
+}
+
+/// One numbered line per savings goal, with a progress bar towards its
+/// target
+pub fn envelope_lines(envelopes: &[Envelope]) -> String {
+    envelopes
+        .iter()
+        .enumerate()
+        .map(|(i, envelope)| {
+            let filled = envelope.progress_percent() as usize * BAR_WIDTH / 100;
+            let bar = format!("{}{}", "█".repeat(filled), "░".repeat(BAR_WIDTH - filled));
+            let icon = if envelope.is_reached() { "🏁" } else { "🎯" };
+            format!("  {}. {} [{}] {}\n", i + 1, icon, bar, envelope)
+        })
+        .collect()
+}
+
+/// Prompts for a goal by its number in the list or its name
+///
+/// # Returns
+/// * `Ok(None)` - If there are no goals or none matched (error shown)
+fn choose_envelope(envelopes: &[Envelope], renderer: &dyn Renderer) -> io::Result<Option<Envelope>> {
+    if envelopes.is_empty() {
+        renderer.failure(&Msg::NoGoalsToChoose);
+        return Ok(None);
+    }
+    let choice = read_input(Msg::ChooseGoal.text())?;
+    let envelope = match choice.parse::<usize>() {
+        Ok(n) if (1..=envelopes.len()).contains(&n) => Some(&envelopes[n - 1]),
+        _ => envelopes.iter().find(|e| Envelope::key(&e.name) == Envelope::key(&choice)),
+    };
+    if envelope.is_none() {
+        renderer.failure(&Msg::InvalidChoice);
+    }
+    Ok(envelope.cloned())
+}
@@ -0,0 +1,80 @@
+//! Fraud review CLI operations
+//!
+//! Demonstrates: Working through a queue that shrinks as items are handled
+
+use std::io;
+
+use serde_json::json;
+
+use crate::bank::SharedBank;
+use crate::models::TransactionId;
+use super::i18n::Msg;
+use super::render::Renderer;
+use super::session::{authorize, Session, STAFF};
+use super::utils::read_input;
+
+/// Lists the transactions the fraud rules flagged, and approves or
+/// reverses them one at a time (staff only)
+pub fn review_flagged(bank: &SharedBank, session: Option<&Session>, renderer: &dyn Renderer) -> io::Result<()> {
+    let session = match authorize(session, STAFF, renderer) {
+        Some(session) => session,
+        None => return Ok(()),
+    };
+
+    renderer.section(Msg::FraudReview.text());
+
+    loop {
+        let pending: Vec<TransactionId> = {
+            let bank = bank.read();
+            renderer.chrome(&format!("\n📋 {}", bank.fraud_rules()));
+            let queue = bank.review_queue();
+            if queue.is_empty() {
+                renderer.chrome(Msg::NothingToReview.text());
+            } else {
+                for (i, flag) in queue.iter().enumerate() {
+                    let name = bank.get_customer(&flag.customer_id).map(|c| c.name.clone()).unwrap_or_default();
+                    renderer.chrome(&format!("  {}. {} - {}\n     ID: {}", i + 1, name, flag, flag.transaction_id));
+                }
+            }
+            queue.iter().map(|flag| flag.transaction_id.clone()).collect()
+        };
+        if pending.is_empty() {
+            return Ok(());
+        }
+
+        renderer.chrome(Msg::ReviewActions.text());
+        let action = read_input(Msg::EnterAction.text())?;
+        if action.is_empty() {
+            return Ok(());
+        }
+        if action != "1" && action != "2" {
+            renderer.failure(&Msg::InvalidChoice);
+            continue;
+        }
+
+        let choice = read_input(Msg::ChooseTransaction.text())?;
+        let id = match choice.parse::<usize>() {
+            Ok(n) if (1..=pending.len()).contains(&n) => pending[n - 1].clone(),
+            _ => choice.into(),
+        };
+        if action == "1" {
+            let result = bank.write().approve_flagged(&id, &session.user_id);
+            match result {
+                Ok(flag) => renderer.success(&format!("\n✅ {}", flag), json!(flag)),
+                Err(e) => renderer.failure(&e),
+            }
+        } else {
+            let result = bank.write().reverse_flagged(&id, &session.user_id);
+            match result {
+                Ok(reversals) => {
+                    let lines: Vec<String> = reversals.iter().map(|tx| format!("   {}", tx)).collect();
+                    renderer.success(
+                        &Msg::TransactionReversed.fill(&[&id, &lines.join("\n")]),
+                        json!({ "transaction_id": id, "reversals": reversals }),
+                    );
+                }
+                Err(e) => renderer.failure(&e),
+            }
+        }
+    }
+}
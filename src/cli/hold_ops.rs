@@ -0,0 +1,126 @@
+//! Account hold CLI operations
+//!
+//! Demonstrates: A sub-menu loop over one customer's records
+
+use std::io;
+
+use chrono::{Duration, Utc};
+use serde_json::json;
+
+use crate::bank::SharedBank;
+use crate::models::CustomerId;
+use super::i18n::Msg;
+use super::render::Renderer;
+use super::session::{authorize, Session, STAFF};
+use super::utils::{read_amount, read_customer, read_input};
+
+/// Lists a customer's holds and places, releases or captures them
+/// (staff only)
+pub fn manage_holds(
+    bank: &SharedBank,
+    session: Option<&Session>,
+    renderer: &dyn Renderer,
+) -> io::Result<()> {
+    if authorize(session, STAFF, renderer).is_none() {
+        return Ok(());
+    }
+
+    renderer.section(Msg::AccountHolds.text());
+
+    let customer_id = match read_customer(bank, Msg::EnterCustomer.text(), renderer)? {
+        Some(id) => id,
+        None => return Ok(()),
+    };
+
+    loop {
+        let holds: Vec<String> = {
+            let bank = bank.read();
+            let account = match bank.get_customer(&customer_id).and_then(|c| c.get_account()) {
+                Ok(account) => account,
+                Err(e) => {
+                    renderer.failure(&e);
+                    return Ok(());
+                }
+            };
+            renderer.chrome(&Msg::LedgerAndAvailable.fill(&[
+                &account.ledger_balance().display(),
+                &account.available_balance().display(),
+            ]));
+            if account.holds.is_empty() {
+                renderer.chrome(Msg::NoHolds.text());
+            } else {
+                for (i, hold) in account.holds.iter().enumerate() {
+                    renderer.chrome(&format!("  {}. {}\n     ID: {}", i + 1, hold, hold.id));
+                }
+            }
+            account.holds.iter().map(|hold| hold.id.clone()).collect()
+        };
+
+        renderer.chrome(Msg::HoldActions.text());
+        match read_input(Msg::EnterAction.text())?.as_str() {
+            "" => return Ok(()),
+            "1" => place_hold(bank, &customer_id, renderer)?,
+            action @ ("2" | "3") => {
+                if holds.is_empty() {
+                    renderer.failure(&Msg::NoHoldsToChoose);
+                    continue;
+                }
+                let choice = read_input(Msg::ChooseHold.text())?;
+                let id = match choice.parse::<usize>() {
+                    Ok(n) if (1..=holds.len()).contains(&n) => holds[n - 1].clone(),
+                    _ => choice,
+                };
+                if action == "2" {
+                    match bank.write().release_hold(&id) {
+                        Ok(hold) => renderer.success(&Msg::HoldReleased.fill(&[&hold]), json!(hold)),
+                        Err(e) => renderer.failure(&e),
+                    }
+                } else {
+                    match bank.write().capture_hold(&id) {
+                        Ok(tx) => renderer.success(&Msg::HoldCaptured.fill(&[&tx]), json!(tx)),
+                        Err(e) => renderer.failure(&e),
+                    }
+                }
+            }
+            _ => renderer.failure(&Msg::InvalidChoice),
+        }
+    }
+}
+
+/// Asks for the amount, reason and lifetime of a new hold and places it
+fn place_hold(bank: &SharedBank, customer_id: &CustomerId, renderer: &dyn Renderer) -> io::Result<()> {
+    let amount = read_amount(Msg::AmountToHold.text(), renderer)?;
+    let reason = read_input(Msg::EnterReason.text())?;
+    let expires_at = match read_input(Msg::HoldExpiresAfter.text())?.as_str() {
+        "" => None,
+        days => match days.parse::<u32>() {
+            Ok(days) if days > 0 => Some(Utc::now() + Duration::days(days.into())),
+            _ => {
+                renderer.failure(&Msg::WholeDays);
+                return Ok(());
+            }
+        },
+    };
+
+    let result = bank.write().place_hold(customer_id, amount, &reason, expires_at);
+    match result {
+        Ok(hold) => renderer.success(&Msg::HoldPlaced.fill(&[&hold, &hold.id]), json!(hold)),
+        Err(e) => renderer.failure(&e),
+    }
+    Ok(())
+}
+
+/// Removes holds that have expired and reports them
+///
+/// Prints nothing when no hold has expired.
+pub fn process_expired_holds(bank: &SharedBank, renderer: &dyn Renderer) {
+    let released = bank.write().release_expired_holds(Utc::now());
+    match released {
+        Ok(holds) => {
+            for hold in holds {
+                renderer.success(&Msg::HoldExpired.fill(&[&hold]), json!({ "expired_hold": hold }));
+            }
+        }
+        Err(e) => renderer.failure(&Msg::CouldNotReleaseHolds.fill(&[&e])),
+    }
+}
@@ -0,0 +1,1607 @@
+//! Message catalog for the interactive menu
+//!
+//! Demonstrates: Exhaustive `match` as a compile-time check - a message
+//! added to `Msg` without a translation in every language does not build
+//!
+//! Each user-facing string is a `Msg` variant. `Msg::text` looks it up in
+//! the language chosen at startup (from the config's `locale` or the
+//! `--locale` flag), and `Msg::fill` replaces its `{}` placeholders in
+//! order. Every prompt and message the menu's screens write is in the
+//! catalog. Table headers and the library's own `Display` text (statuses,
+//! transaction types, errors) stay as the library writes them, and so do
+//! the words the parsers expect, such as `none`.
+
+use std::fmt;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// The language the menu is shown in
+static LANGUAGE: AtomicU8 = AtomicU8::new(Language::English as u8);
+
+/// A language the catalog is translated into
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[repr(u8)]
+pub enum Language {
+    #[default]
+    English,
+    Spanish,
+}
+
+impl Language {
+    /// Every supported language
+    pub const ALL: [Language; 2] = [Language::English, Language::Spanish];
+
+    /// The ISO 639-1 code, e.g. `en`
+    pub fn code(self) -> &'static str {
+        match self {
+            Language::English => "en",
+            Language::Spanish => "es",
+        }
+    }
+
+    /// The language of a locale such as `es-MX`, or English when it has no
+    /// translation
+    pub fn from_locale(locale: &str) -> Self {
+        let language = locale.split(['-', '_']).next().unwrap_or("");
+        language.parse().unwrap_or_default()
+    }
+}
+
+impl FromStr for Language {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Language::ALL
+            .into_iter()
+            .find(|language| language.code().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| format!("no translation for language '{}'", s))
+    }
+}
+
+impl fmt::Display for Language {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Language::English => write!(f, "English"),
+            Language::Spanish => write!(f, "Español"),
+        }
+    }
+}
+
+/// Shows the menu in `language` from now on
+pub fn set_language(language: Language) {
+    LANGUAGE.store(language as u8, Ordering::Relaxed);
+}
+
+/// The language the menu is shown in
+pub fn language() -> Language {
+    match LANGUAGE.load(Ordering::Relaxed) {
+        1 => Language::Spanish,
+        _ => Language::English,
+    }
+}
+
+/// A translatable message
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Msg {
+    // Main menu
+    MainMenu,
+    LoggedInAs,
+    UnsavedChanges,
+    RegisterCustomer,
+    CreateAccountForCustomer,
+    DepositMoney,
+    WithdrawMoney,
+    TransferMoney,
+    ViewAccountDetails,
+    ViewTransactionHistory,
+    ListAllCustomers,
+    SearchCustomers,
+    ViewBankStatistics,
+    SaveData,
+    CreateScheduledTransfer,
+    ListScheduledTransfers,
+    CancelScheduledTransfer,
+    ToggleAutosave,
+    RestoreBackup,
+    ExportTransactions,
+    ImportTransactions,
+    GenerateStatement,
+    Login,
+    Logout,
+    ChangePin,
+    AddStaffUser,
+    UndoLastOperation,
+    AccountLimits,
+    FreezeCloseAccount,
+    DeactivateDeleteCustomer,
+    EditCustomer,
+    SearchTransactions,
+    MonthlySummary,
+    SpendingByCategory,
+    ProcessBatchFile,
+    RunAudit,
+    SavedPayees,
+    BalanceAlerts,
+    Webhooks,
+    TransferExternal,
+    ClearingQueue,
+    AccountHolds,
+    DepositCheque,
+    OutstandingCheques,
+    DebitCards,
+    CardPurchase,
+    Merchants,
+    PayBills,
+    SavingsGoals,
+    Budgets,
+    TermDeposits,
+    LookupTransaction,
+    Ledger,
+    CashFlow,
+    FraudReview,
+    ReverseTransaction,
+    CashVault,
+    ImportCustomers,
+    AuditRecord,
+    Exit,
+    On,
+    Off,
+
+    // Section titles that differ from the menu entry
+    CreateAccount,
+    AllCustomers,
+    AccountDetails,
+    TransactionHistory,
+    ScheduledTransfers,
+    BankStatistics,
+    IntegrityAudit,
+    AccountStatement,
+
+    // Menu loop
+    EnterYourChoice,
+    InvalidChoiceTryAgain,
+    ReadOnlyBanner,
+    ReadOnlyCannotSave,
+    DataSaved,
+    NothingToSave,
+    SaveBeforeExit,
+    ExitWithoutSaving,
+    UnsavedChangesLost,
+    AutosaveNow,
+    LoggedOut,
+    NobodyLoggedIn,
+    ThankYou,
+    ReadOnlyGoodbye,
+    SavedGoodbye,
+    CouldNotSave,
+    EmergencySaved,
+    Interrupted,
+    BankBusy,
+
+    // Shared prompts and errors
+    Error,
+    LogInFirst,
+    RoleNotAllowed,
+    EnterAction,
+    EnterCustomer,
+    InvalidChoice,
+    InvalidAmount,
+    InvalidDate,
+    InvalidFrequency,
+    InvalidAction,
+    Cancelled,
+    OperationCrashed,
+    TooManyAttempts,
+    AmountFormat,
+    AmountNegative,
+    InvalidId,
+    ChoiceOutOfRange,
+    MatchesCustomers,
+    ChooseOneOf,
+    PageNext,
+    PagePrev,
+    PageQuit,
+
+    // Login
+    LogInAs,
+    EnterChoiceDefaultOne,
+    EnterPin,
+    ChoosePinNow,
+    Welcome,
+    NoStaffYet,
+    EnterUsername,
+
+    // Everyday account operations
+    EnterCustomerName,
+    EnterCustomerEmail,
+    CustomerRegistered,
+    EnterInitialDeposit,
+    AccountTypes,
+    EnterAccountType,
+    EnterTermMonths,
+    InvalidAccountType,
+    AccountCreated,
+    EnterDepositAmount,
+    DepositDone,
+    EnterWithdrawalAmount,
+    WithdrawalDone,
+    EnterSender,
+    EnterRecipient,
+    EnterTransferAmount,
+    TransferDone,
+    OfferReceipt,
+    ReceiptFile,
+    ReceiptSaved,
+    ReceiptFileExists,
+    DescriptionPrompt,
+    CategoryPrompt,
+    TagsPrompt,
+
+    // Transfers abroad, undo, limits, alerts and account status
+    EnterExternalAccount,
+    EnterAmountToSend,
+    ExternalTransferSent,
+    UndoDone,
+    UndoMore,
+    EnterTransactionId,
+    TransactionOwner,
+    EnterReason,
+    ReversedDone,
+    LimitsHelp,
+    EnterMaxWithdrawal,
+    EnterDailyWithdrawal,
+    EnterDailyTransfers,
+    LimitsUpdated,
+    AlertsHelp,
+    EnterLowBalanceAlert,
+    EnterLargeTransactionAlert,
+    AlertsUpdated,
+    AccountStatusActions,
+    EnterBalanceDestination,
+    AccountNowStatus,
+    TransferredOut,
+    FlaggedDormant,
+    AndFrozen,
+    PoliciesFailed,
+
+    // PINs and staff users
+    StaffWelcome,
+    EnterCurrentPin,
+    PinChanged,
+    StaffRoles,
+    EnterRole,
+    InvalidRole,
+    StaffAdded,
+    ChooseNewPin,
+    RepeatPin,
+    PinsDiffer,
+
+    // Merchants and bills
+    AddRemoveActions,
+    EnterMerchantName,
+    EnterMerchantCategory,
+    Added,
+    Removed,
+    BillActions,
+    BillPaid,
+    Frequencies,
+    EnterFrequency,
+    EnterFirstPaymentDate,
+    AutopaySetUp,
+    NoAutopayRules,
+    EnterAutopay,
+    AutopayCancelled,
+    AutopayPaid,
+    Merchant,
+    AutopayFailed,
+    NoMerchants,
+    NoAutopays,
+    AutopayHeading,
+    RemovedMerchant,
+    NoMerchantsYet,
+    EnterMerchant,
+    EnterBillReference,
+    EnterAmount,
+
+    // Books
+    EnterCsvOrShow,
+    TrialBalanceWritten,
+    EnterStartDate,
+    EnterEndDate,
+    IncomeStatementWritten,
+    ChartActions,
+    EnterLedgerAccount,
+    EnterLedgerCode,
+    EnterLedgerName,
+    EnterLedgerClass,
+    EnterTransactionKind,
+    EnterPostingAccount,
+    ChartUpdated,
+    NoLedgerAccount,
+
+    // Budgets
+    NoBudgets,
+    BudgetsThisMonth,
+    BudgetActions,
+    EnterBudgetCategory,
+    EnterMonthlyLimit,
+    BudgetSet,
+    EnterCategory,
+    BudgetRemoved,
+    OverBudget,
+    GoAheadAnyway,
+    CancelledDone,
+
+    // Debit cards
+    CardActions,
+    CardDone,
+    NoActiveCards,
+    EnterPurchaseAmount,
+    DescribePurchase,
+    CardPaid,
+    NoCards,
+    CardLine,
+    NoCardsToChoose,
+    EnterCard,
+    CardLimitsHelp,
+    EnterMaxPurchase,
+    EnterDailySpend,
+
+    // Cheques
+    EnterChequeNumber,
+    EnterDrawer,
+    ChequeDeposited,
+    NoChequesWaiting,
+    ChequeActions,
+    EnterCheque,
+    EnterReturnReason,
+    ClearingChequesFailed,
+
+    // Clearing queue
+    NoPendingExternal,
+    PendingExternalHeading,
+    ClearingActions,
+    EnterPendingTransfer,
+    Settled,
+    EnterOptionalReason,
+    RejectedRefunded,
+
+    // Customers
+    NoCustomersYet,
+    CustomersPage,
+    EnterSearchQuery,
+    NoCustomersMatching,
+    FoundCustomers,
+    DetailsEmail,
+    DetailsPhone,
+    DetailsAddress,
+    DetailsBirthDate,
+    AccountStatistics,
+    SavingsGoalsHeading,
+    EditHelp,
+    NothingChanged,
+    CustomerUpdated,
+    EnterNewName,
+    EnterNewEmail,
+    PhoneLabel,
+    AddressLabel,
+    BirthDateLabel,
+    CustomerStatusActions,
+    ConfirmWord,
+    ConfirmDeletion,
+    DeletionCancelled,
+    CustomerNowStatus,
+
+    // Data files
+    EnterPassphrase,
+    CouldNotUnlock,
+    SetPassphrase,
+    NoBackups,
+    UnknownTime,
+    BackupLine,
+    EnterBackupNumber,
+    InvalidBackupNumber,
+    ConfirmRestore,
+    RestoreCancelled,
+    BackupRestored,
+    EnterExportFile,
+    TransactionsExported,
+    EnterImportFile,
+    TransactionsImported,
+    EnterBatchFile,
+    AllOrNothing,
+    BatchApplied,
+    BatchSummary,
+    EnterCustomerCsv,
+    NoCustomersInFile,
+    CustomersImported,
+
+    // Savings goals
+    NoSavingsGoals,
+    FreeToSpend,
+    SavingsGoalActions,
+    GoalName,
+    TargetAmount,
+    LockGoal,
+    AmountToDeposit,
+    AmountToSetAside,
+    AmountToTakeOut,
+    GoalRemoved,
+    NoGoalsToChoose,
+    ChooseGoal,
+
+    // Fraud review
+    NothingToReview,
+    ReviewActions,
+    ChooseTransaction,
+    TransactionReversed,
+
+    // Holds
+    LedgerAndAvailable,
+    NoHolds,
+    HoldActions,
+    NoHoldsToChoose,
+    ChooseHold,
+    HoldReleased,
+    HoldCaptured,
+    AmountToHold,
+    HoldExpiresAfter,
+    WholeDays,
+    HoldPlaced,
+    HoldExpired,
+    CouldNotReleaseHolds,
+
+    // History, reports and statements
+    FilterByTag,
+    NoTransactionsTagged,
+    NoTransactionsYet,
+    HistoryPage,
+    LookupDetails,
+    LookupReverses,
+    LookupReversedBy,
+    LookupReason,
+    AuditRecords,
+    LeaveDateBlank,
+    FromDate,
+    ToDate,
+    NoTransactionsInPeriod,
+    LedgerPage,
+    LeaveCriterionBlank,
+    MinimumAmount,
+    MaximumAmount,
+    TransactionTypeFilter,
+    CounterpartyFilter,
+    TagFilter,
+    NoMatchingTransactions,
+    FoundTransactions,
+    InvalidDateValue,
+    InvalidAmountValue,
+    ReportActions,
+    HowManyTen,
+    LargestTransactions,
+    NoTransactionsLine,
+    OverHowManyDays,
+    MostActiveAccounts,
+    NoTransactionsInPeriodLine,
+    ActiveAccountLine,
+    InactiveForDays,
+    AccountsInactiveFor,
+    NoneLine,
+    DormantAccountLine,
+    NotWholeNumber,
+    PerDayOrWeek,
+    NeitherDayNorWeek,
+    EnterOutputFile,
+    StatementWritten,
+    NoSpendingYet,
+    SpendingByCategoryHeading,
+    TransactionCount,
+    Uncategorized,
+    TotalLabel,
+    EnterMonth,
+    EnterCsvExport,
+    SummaryWritten,
+
+    // Payees and scheduled transfers
+    NoPayees,
+    SavedPayeesHeading,
+    NicknameToRemove,
+    PayeeRemoved,
+    EnterNickname,
+    EnterPayee,
+    PayeeSaved,
+    EnterRecipientId,
+    AmountPerTransfer,
+    FirstRunDate,
+    ScheduleCreated,
+    NoSchedules,
+    TotalSchedules,
+    EnterScheduleId,
+    ScheduleCancelled,
+    ScheduleRan,
+    ScheduleFailed,
+
+    // Term deposits, vault and webhooks
+    TermActionsStaff,
+    TermActions,
+    TermMatured,
+    CouldNotPayMaturities,
+    AmountToLock,
+    YearlyRate,
+    TermMonths,
+    InvalidMonths,
+    TermOpened,
+    NoActiveTerms,
+    ChooseTerm,
+    TermNoLongerActive,
+    EarlyPayout,
+    ConfirmEarlyWithdrawal,
+    TermPaidBack,
+    NoTermDeposits,
+    VaultCash,
+    VaultUntracked,
+    VaultActions,
+    AmountToBringIn,
+    VaultNowHolds,
+    NoWebhooks,
+    RegisteredWebhooks,
+    ChooseWebhook,
+    WebhookRemoved,
+    WebhookUrl,
+    WebhookEvents,
+    EventsToSend,
+    WebhookRegistered,
+
+    // Shared helpers
+    InvalidLimit,
+    InvalidMonth,
+    InterruptNotSaved,
+    AuditRecordHeading,
+    NoMatch,
+
+    // Customer sorting
+    CustomerSortKeys,
+    SortDirections,
+
+    // History sorting
+    HistorySortKeys,
+
+    // Reversals
+    ConfirmReversal,
+    NothingReversed,
+}
+
+impl Msg {
+    /// The message in the current language
+    pub fn text(self) -> &'static str {
+        match language() {
+            Language::English => self.english(),
+            Language::Spanish => self.spanish(),
+        }
+    }
+
+    /// The message in the current language with each `{}` replaced by the
+    /// next of `args`
+    pub fn fill(self, args: &[&dyn fmt::Display]) -> String {
+        let mut args = args.iter();
+        let mut parts = self.text().split("{}");
+        let mut text = parts.next().unwrap_or_default().to_string();
+        for part in parts {
+            if let Some(arg) = args.next() {
+                text.push_str(&arg.to_string());
+            }
+            text.push_str(part);
+        }
+        text
+    }
+
+    fn english(self) -> &'static str {
+        match self {
+            Msg::MainMenu => "MAIN MENU",
+            Msg::LoggedInAs => "🔐 Logged in as {} ({})",
+            Msg::UnsavedChanges => "* {} unsaved change(s)",
+            Msg::RegisterCustomer => "Register New Customer",
+            Msg::CreateAccountForCustomer => "Create Account for Customer",
+            Msg::DepositMoney => "Deposit Money",
+            Msg::WithdrawMoney => "Withdraw Money",
+            Msg::TransferMoney => "Transfer Money",
+            Msg::ViewAccountDetails => "View Account Details",
+            Msg::ViewTransactionHistory => "View Transaction History",
+            Msg::ListAllCustomers => "List All Customers",
+            Msg::SearchCustomers => "Search Customers",
+            Msg::ViewBankStatistics => "View Bank Statistics",
+            Msg::SaveData => "Save Data",
+            Msg::CreateScheduledTransfer => "Create Scheduled Transfer",
+            Msg::ListScheduledTransfers => "List Scheduled Transfers",
+            Msg::CancelScheduledTransfer => "Cancel Scheduled Transfer",
+            Msg::ToggleAutosave => "Toggle Autosave (currently {})",
+            Msg::RestoreBackup => "Restore Backup",
+            Msg::ExportTransactions => "Export Transactions (CSV)",
+            Msg::ImportTransactions => "Import Transactions (CSV)",
+            Msg::GenerateStatement => "Generate Statement",
+            Msg::Login => "Login",
+            Msg::Logout => "Logout",
+            Msg::ChangePin => "Change PIN",
+            Msg::AddStaffUser => "Add Staff User",
+            Msg::UndoLastOperation => "Undo Last Operation",
+            Msg::AccountLimits => "Account Limits",
+            Msg::FreezeCloseAccount => "Freeze / Close Account",
+            Msg::DeactivateDeleteCustomer => "Deactivate / Delete Customer",
+            Msg::EditCustomer => "Edit Customer",
+            Msg::SearchTransactions => "Search Transactions",
+            Msg::MonthlySummary => "Monthly Summary",
+            Msg::SpendingByCategory => "Spending by Category",
+            Msg::ProcessBatchFile => "Process Batch File",
+            Msg::RunAudit => "Run Audit",
+            Msg::SavedPayees => "Saved Payees",
+            Msg::BalanceAlerts => "Balance Alerts",
+            Msg::Webhooks => "Webhooks",
+            Msg::TransferExternal => "Transfer to Another Bank",
+            Msg::ClearingQueue => "Clearing Queue",
+            Msg::AccountHolds => "Account Holds",
+            Msg::DepositCheque => "Deposit Cheque",
+            Msg::OutstandingCheques => "Outstanding Cheques",
+            Msg::DebitCards => "Debit Cards",
+            Msg::CardPurchase => "Card Purchase",
+            Msg::Merchants => "Merchants",
+            Msg::PayBills => "Pay Bills",
+            Msg::SavingsGoals => "Savings Goals",
+            Msg::Budgets => "Budgets",
+            Msg::TermDeposits => "Term Deposits",
+            Msg::LookupTransaction => "Lookup Transaction",
+            Msg::Ledger => "Bank Ledger",
+            Msg::CashFlow => "Cash Flow Report",
+            Msg::FraudReview => "Fraud Review",
+            Msg::ReverseTransaction => "Reverse Transaction",
+            Msg::CashVault => "Cash Vault",
+            Msg::ImportCustomers => "Import Customers (CSV)",
+            Msg::AuditRecord => "Audit Record",
+            Msg::Exit => "Exit",
+            Msg::On => "ON",
+            Msg::Off => "OFF",
+
+            Msg::CreateAccount => "Create Account",
+            Msg::AllCustomers => "All Customers",
+            Msg::AccountDetails => "Account Details",
+            Msg::TransactionHistory => "Transaction History",
+            Msg::ScheduledTransfers => "Scheduled Transfers",
+            Msg::BankStatistics => "Bank Statistics",
+            Msg::IntegrityAudit => "Integrity Audit",
+            Msg::AccountStatement => "Account Statement",
+
+            Msg::EnterYourChoice => "Enter your choice: ",
+            Msg::InvalidChoiceTryAgain => "Invalid choice. Please try again.",
+            Msg::ReadOnlyBanner => "🔒 Read-only: changes last until you exit and are never saved\n",
+            Msg::ReadOnlyCannotSave => "Read-only mode: nothing can be saved",
+            Msg::DataSaved => "\n✅ Data saved successfully!",
+            Msg::NothingToSave => "\n✅ Nothing to save: no changes since the last save",
+            Msg::SaveBeforeExit => "⚠️  {} unsaved change(s). Save before exiting? (Y/n): ",
+            Msg::ExitWithoutSaving => "Not saved in full; the journal keeps the changes until the next save\n",
+            Msg::UnsavedChangesLost => "⚠️  {} change(s) made in this session are lost",
+            Msg::AutosaveNow => "\n⚙️  Autosave is now {}\n",
+            Msg::LoggedOut => "\n👋 Logged out.\n",
+            Msg::NobodyLoggedIn => "Nobody is logged in",
+            Msg::ThankYou => "\n👋 Thank you for using Rust Banking System!",
+            Msg::ReadOnlyGoodbye => "🔒 Read-only: nothing was saved. Goodbye!\n",
+            Msg::SavedGoodbye => "💾 Data saved. Goodbye!\n",
+            Msg::CouldNotSave => "⚠️  Could not save changes: {}\n",
+            Msg::EmergencySaved => "💾 Saved the bank to {} before stopping\n",
+            Msg::Interrupted => "\n\n⚠️  Interrupted; saving before exiting...",
+            Msg::BankBusy => "an operation is still using the bank",
+
+            Msg::Error => "Error",
+            Msg::LogInFirst => "Please log in first (menu option 20)",
+            Msg::RoleNotAllowed => "{}s are not allowed to do that",
+            Msg::EnterAction => "Enter action: ",
+            Msg::EnterCustomer => "Enter customer ID or email: ",
+            Msg::InvalidChoice => "Invalid choice",
+            Msg::InvalidAmount => "Invalid amount",
+            Msg::InvalidDate => "Invalid date",
+            Msg::InvalidFrequency => "Invalid frequency",
+            Msg::InvalidAction => "Invalid action",
+            Msg::Cancelled => "Cancelled",
+            Msg::OperationCrashed => "That operation stopped unexpectedly ({}); the session goes on",
+            Msg::TooManyAttempts => "No valid answer after {} tries",
+            Msg::AmountFormat => "Invalid amount '{}': use digits with at most two decimals, e.g. 12.50",
+            Msg::AmountNegative => "The amount cannot be negative",
+            Msg::InvalidId => "'{}' is not a full ID (e.g. 67e55044-10b1-426f-9247-bb680e5fe0c8)",
+            Msg::ChoiceOutOfRange => "Enter a number from 1 to {}",
+            Msg::MatchesCustomers => "\n'{}' matches {} customers:",
+            Msg::ChooseOneOf => "Choose 1-{} (blank to cancel): ",
+            Msg::PageNext => "[n]ext",
+            Msg::PagePrev => "[p]rev",
+            Msg::PageQuit => "[q]uit",
+
+            Msg::LogInAs => "Log in as: 1. Customer  2. Staff",
+            Msg::EnterChoiceDefaultOne => "Enter choice [1]: ",
+            Msg::EnterPin => "Enter PIN: ",
+            Msg::ChoosePinNow => "No PIN set yet - please choose one.",
+            Msg::Welcome => "\n✅ Welcome, {}!\n",
+            Msg::NoStaffYet => "No staff users yet - creating the first admin.",
+            Msg::EnterUsername => "Enter username: ",
+
+            Msg::EnterCustomerName => "Enter customer name: ",
+            Msg::EnterCustomerEmail => "Enter customer email: ",
+            Msg::CustomerRegistered => "\n✅ Customer registered successfully!\n📋 Customer ID: {}\n",
+            Msg::EnterInitialDeposit => "Enter initial deposit amount: ",
+            Msg::AccountTypes => "Account types: 1. Checking  2. Savings  3. Fixed Deposit",
+            Msg::EnterAccountType => "Enter account type [1]: ",
+            Msg::EnterTermMonths => "Enter term in months: ",
+            Msg::InvalidAccountType => "Invalid account type",
+            Msg::AccountCreated => {
+                "\n✅ Account created successfully!\n🔢 Account Number: {}\n💳 Account ID: {}\n\
+                 🏷️  Account Type: {}\n💰 Initial Balance: {}\n"
+            }
+            Msg::EnterDepositAmount => "Enter amount to deposit: ",
+            Msg::DepositDone => "\n✅ Deposit successful!\n💰 New Balance: {}\n",
+            Msg::EnterWithdrawalAmount => "Enter amount to withdraw: ",
+            Msg::WithdrawalDone => "\n✅ Withdrawal successful!\n💰 New Balance: {}\n",
+            Msg::EnterSender => "Enter sender ID or email: ",
+            Msg::EnterRecipient => "Enter recipient ID, email or payee nickname: ",
+            Msg::EnterTransferAmount => "Enter amount to transfer: ",
+            Msg::TransferDone => "\n✅ Transfer successful!\n💸 {} transferred\n",
+            Msg::OfferReceipt => "🧾 Receipt? (y/N): ",
+            Msg::ReceiptFile => "🧾 File to save it to (blank to show it here): ",
+            Msg::ReceiptSaved => "🧾 Receipt saved to {}",
+            Msg::ReceiptFileExists => "{} already exists; the receipt was not saved",
+            Msg::DescriptionPrompt => "Description (blank for none): ",
+            Msg::CategoryPrompt => "Category (blank for none): ",
+            Msg::TagsPrompt => "Tags, comma-separated (blank for none): ",
+
+            Msg::EnterExternalAccount => "Receiving account (IBAN or routing-account): ",
+            Msg::EnterAmountToSend => "Enter amount to send: ",
+            Msg::ExternalTransferSent => {
+                "\n✅ {} sent to {}.\n⏳ Pending until the receiving bank settles it (ref {}).\n"
+            }
+            Msg::UndoDone => "\n↩️  Undone! Reversals posted:\n",
+            Msg::UndoMore => "\n{} more operation(s) can be undone\n",
+            Msg::EnterTransactionId => "Transaction ID: ",
+            Msg::TransactionOwner => "\n{}\n   Customer: {} <{}>\n",
+            Msg::EnterReason => "Reason: ",
+            Msg::ReversedDone => "\n↩️  Reversed! Compensating entries posted:\n",
+            Msg::LimitsHelp => "Enter a new value, 'none' for unlimited, or leave blank to keep it.",
+            Msg::EnterMaxWithdrawal => "Max single withdrawal: ",
+            Msg::EnterDailyWithdrawal => "Max withdrawn per day: ",
+            Msg::EnterDailyTransfers => "Max transfers per day: ",
+            Msg::LimitsUpdated => "\n✅ Limits updated.\n{}\n",
+            Msg::AlertsHelp => "Enter a new amount, 'none' to turn the alert off, or leave blank to keep it.",
+            Msg::EnterLowBalanceAlert => "Alert when balance drops below: ",
+            Msg::EnterLargeTransactionAlert => "Alert on transactions above: ",
+            Msg::AlertsUpdated => "\n✅ Alerts updated.\n{}\n",
+            Msg::AccountStatusActions => "Actions: 1. Freeze  2. Unfreeze  3. Close",
+            Msg::EnterBalanceDestination => "Transfer the remaining balance to (ID or email): ",
+            Msg::AccountNowStatus => "\n✅ Account is now {}.\n",
+            Msg::TransferredOut => "💸 {} transferred out\n",
+            Msg::FlaggedDormant => "💤 Account of {} {} flagged dormant{} after {} days without activity",
+            Msg::AndFrozen => " and frozen",
+            Msg::PoliciesFailed => "could not apply account policies: {}",
+
+            Msg::StaffWelcome => "\n✅ Welcome, {} ({})!\n",
+            Msg::EnterCurrentPin => "Enter current PIN: ",
+            Msg::PinChanged => "\n✅ PIN changed.\n",
+            Msg::StaffRoles => "Roles: 1. Teller  2. Admin",
+            Msg::EnterRole => "Enter role [1]: ",
+            Msg::InvalidRole => "Invalid role",
+            Msg::StaffAdded => "\n✅ {} '{}' added.\n",
+            Msg::ChooseNewPin => "Choose a PIN (4-12 digits): ",
+            Msg::RepeatPin => "Repeat the PIN: ",
+            Msg::PinsDiffer => "PINs do not match",
+
+            Msg::AddRemoveActions => "\nActions: 1. Add  2. Remove  (blank to finish)",
+            Msg::EnterMerchantName => "Merchant name: ",
+            Msg::EnterMerchantCategory => "Category (utility, telecom, insurance, other): ",
+            Msg::Added => "\n✅ Added {}",
+            Msg::Removed => "\n🗑️  Removed {}",
+            Msg::BillActions => "\nActions: 1. Pay a bill  2. Set up autopay  3. Cancel autopay  (blank to finish)",
+            Msg::BillPaid => "\n✅ {} paid to {} (ref {}).\n💰 New balance: {}\n",
+            Msg::Frequencies => "Frequencies: 1. Daily  2. Weekly  3. Monthly",
+            Msg::EnterFrequency => "Enter frequency: ",
+            Msg::EnterFirstPaymentDate => "First payment date (YYYY-MM-DD, blank for today): ",
+            Msg::AutopaySetUp => "\n✅ Autopay to {} set up: {}",
+            Msg::NoAutopayRules => "There are no autopay rules",
+            Msg::EnterAutopay => "Autopay number in the list or ID: ",
+            Msg::AutopayCancelled => "\n✅ Autopay cancelled.",
+            Msg::AutopayPaid => "🧾 Autopay {} for {}: {} paid to {}",
+            Msg::Merchant => "merchant",
+            Msg::AutopayFailed => "autopay {} for {} failed: {}",
+            Msg::NoMerchants => "\n📭 No merchants registered.",
+            Msg::NoAutopays => "\n📭 No autopay rules.",
+            Msg::AutopayHeading => "\n🔁 Autopay:",
+            Msg::RemovedMerchant => "(removed)",
+            Msg::NoMerchantsYet => "There are no merchants; an admin can add them with the Merchants option",
+            Msg::EnterMerchant => "Merchant number in the list, name or ID: ",
+            Msg::EnterBillReference => "Your reference with the merchant: ",
+            Msg::EnterAmount => "Amount: ",
+
+            Msg::EnterCsvOrShow => "CSV file to write (blank to show here): ",
+            Msg::TrialBalanceWritten => "\n✅ Trial balance written to {}",
+            Msg::EnterStartDate => "Enter start date (YYYY-MM-DD)",
+            Msg::EnterEndDate => "Enter end date (YYYY-MM-DD)",
+            Msg::IncomeStatementWritten => "\n✅ Income statement written to {}",
+            Msg::ChartActions => "\nActions: 1. Edit an account  2. Change a mapping rule  (blank to finish)",
+            Msg::EnterLedgerAccount => "Account code or name: ",
+            Msg::EnterLedgerCode => "New code [{}]: ",
+            Msg::EnterLedgerName => "New name [{}]: ",
+            Msg::EnterLedgerClass => "Class (asset, liability, equity, income, expense) [{}]: ",
+            Msg::EnterTransactionKind => "Transaction type (e.g. fee, bill-payment): ",
+            Msg::EnterPostingAccount => "Post against account (code or name): ",
+            Msg::ChartUpdated => "\n✅ Chart of accounts updated",
+            Msg::NoLedgerAccount => "no account with code or name '{}'",
+
+            Msg::NoBudgets => "\n📭 No budgets yet.",
+            Msg::BudgetsThisMonth => "\n📊 This month:\n{}",
+            Msg::BudgetActions => "\nActions: 1. Set a budget  2. Remove a budget  (blank to finish)",
+            Msg::EnterBudgetCategory => "Category, e.g. groceries: ",
+            Msg::EnterMonthlyLimit => "Monthly limit: ",
+            Msg::BudgetSet => "\n✅ Budget for {} set to {} a month",
+            Msg::EnterCategory => "Category: ",
+            Msg::BudgetRemoved => "\n🗑️  Budget for {} removed",
+            Msg::OverBudget => "\n⚠️  This would put you over budget: {}",
+            Msg::GoAheadAnyway => "Go ahead anyway? (y/N): ",
+            Msg::CancelledDone => "\n↩️  Cancelled.\n",
+
+            Msg::CardActions => "\nActions: 1. Issue  2. Block  3. Replace  4. Set limits  (blank to finish)",
+            Msg::CardDone => "\n✅ {}\n   Limits: {}",
+            Msg::NoActiveCards => "No active cards; staff can issue one with the Debit Cards option",
+            Msg::EnterPurchaseAmount => "Enter purchase amount: ",
+            Msg::DescribePurchase => "Describe the purchase, e.g. with the merchant's name.",
+            Msg::CardPaid => "\n✅ {} paid with card {}.\n💰 New balance: {}\n",
+            Msg::NoCards => "\n📭 No cards.",
+            Msg::CardLine => "  {}. {}\n     Limits: {}\n     ID: {}",
+            Msg::NoCardsToChoose => "There are no cards",
+            Msg::EnterCard => "Card number in the list or ID: ",
+            Msg::CardLimitsHelp => "Enter a limit, 'none' for unlimited, or leave blank to keep it.",
+            Msg::EnterMaxPurchase => "Max single purchase: ",
+            Msg::EnterDailySpend => "Max spent per day: ",
+
+            Msg::EnterChequeNumber => "Cheque number: ",
+            Msg::EnterDrawer => "Written by (customer ID or email, blank for another bank): ",
+            Msg::ChequeDeposited => {
+                "\n✅ Cheque #{} for {} deposited.\n⏳ The funds are available once it clears on {}.\n"
+            }
+            Msg::NoChequesWaiting => "📭 No cheques are waiting to clear.",
+            Msg::ChequeActions => "\nActions: 1. Clear due cheques  2. Return a cheque  (blank to finish)",
+            Msg::EnterCheque => "Cheque number in the list or ID: ",
+            Msg::EnterReturnReason => "Reason (blank for \"Returned unpaid\"): ",
+            Msg::ClearingChequesFailed => "could not clear due cheques: {}",
+
+            Msg::NoPendingExternal => "\n📭 No external transfers are pending.",
+            Msg::PendingExternalHeading => "\n⏳ Pending external transfers:",
+            Msg::ClearingActions => "\nActions: 1. Settle  2. Reject  (blank to finish)",
+            Msg::EnterPendingTransfer => "Transfer number or ID: ",
+            Msg::Settled => "\n✅ Settled: {}",
+            Msg::EnterOptionalReason => "Reason (blank for none): ",
+            Msg::RejectedRefunded => "\n↩️  Rejected; {} returned to the sender.",
+
+            Msg::NoCustomersYet => "\n📭 No customers registered yet.\n",
+            Msg::CustomersPage => "\n👥 Total Customers: {} (page {} of {})\n\n{}",
+            Msg::EnterSearchQuery => "Enter search query (name): ",
+            Msg::NoCustomersMatching => "\n🔍 No customers found matching '{}'\n",
+            Msg::FoundCustomers => "\n🔍 Found {} customer(s):\n\n{}",
+            Msg::DetailsEmail => "\n{}\n📧 Email: {}",
+            Msg::DetailsPhone => "\n📞 Phone: {}",
+            Msg::DetailsAddress => "\n🏠 Address: {}",
+            Msg::DetailsBirthDate => "\n🎂 Date of Birth: {}",
+            Msg::AccountStatistics => {
+                "\n\n📊 Account Statistics:\n  Account Type: {}\n  Status: {}\n  Available Balance: {} ({} held)\n  \
+                 Total Deposits: {}\n  Total Withdrawals: {}\n  Transaction Count: {}\n"
+            }
+            Msg::SavingsGoalsHeading => "\n🎯 Savings Goals ({} set aside):\n{}",
+            Msg::EditHelp => "Leave a field blank to keep it; enter 'none' to remove an optional one.",
+            Msg::NothingChanged => "Nothing changed",
+            Msg::CustomerUpdated => "\n✅ Customer updated.\n",
+            Msg::EnterNewName => "Name [{}]: ",
+            Msg::EnterNewEmail => "Email [{}]: ",
+            Msg::PhoneLabel => "Phone",
+            Msg::AddressLabel => "Address",
+            Msg::BirthDateLabel => "Date of birth (YYYY-MM-DD)",
+            Msg::CustomerStatusActions => "Actions: 1. Deactivate  2. Reactivate  3. Delete",
+            Msg::ConfirmWord => "yes",
+            Msg::ConfirmDeletion => "Deleting cannot be undone. Type '{}' to confirm: ",
+            Msg::DeletionCancelled => "Deletion cancelled",
+            Msg::CustomerNowStatus => "\n✅ Customer is now {}.\n",
+
+            Msg::EnterPassphrase => "🔒 Enter data file passphrase: ",
+            Msg::CouldNotUnlock => "could not unlock the data file",
+            Msg::SetPassphrase => "🔑 Set a passphrase to encrypt the data file (leave blank for none): ",
+            Msg::NoBackups => "\n📭 No backups available for {}.\n",
+            Msg::UnknownTime => "unknown time",
+            Msg::BackupLine => "  {}. {} (saved {})",
+            Msg::EnterBackupNumber => "\nEnter backup number to restore: ",
+            Msg::InvalidBackupNumber => "Invalid backup number",
+            Msg::ConfirmRestore => "⚠️  Current data will be replaced. Continue? (y/N): ",
+            Msg::RestoreCancelled => "\n↩️  Restore cancelled.\n",
+            Msg::BackupRestored => "\n✅ Backup {} restored!\n",
+            Msg::EnterExportFile => "Enter output file [transactions.csv]: ",
+            Msg::TransactionsExported => "\n✅ Exported {} transaction(s) to {}\n",
+            Msg::EnterImportFile => "Enter CSV file to import: ",
+            Msg::TransactionsImported => "\n✅ Imported {} transaction(s)\n",
+            Msg::EnterBatchFile => "Enter batch file (.csv or .json): ",
+            Msg::AllOrNothing => "Apply all or nothing? (y/N): ",
+            Msg::BatchApplied => "  {}. ✅ {} (balance {})\n",
+            Msg::BatchSummary => "\n📦 {} of {} operation(s) applied\n",
+            Msg::EnterCustomerCsv => "Enter CSV file (name,email,initial_deposit): ",
+            Msg::NoCustomersInFile => "\n📭 The file has no customers.\n",
+            Msg::CustomersImported => "\n{}\n\n👥 {} of {} customer(s) imported, {} skipped\n",
+
+            Msg::NoSavingsGoals => "\n📭 No savings goals yet.",
+            Msg::FreeToSpend => "💵 Free to spend or set aside: {}",
+            Msg::SavingsGoalActions => {
+                "\nActions: 1. New goal  2. Deposit into goal  3. Set money aside  4. Take money out  \
+                 5. Lock / unlock  6. Remove  (blank to finish)"
+            }
+            Msg::GoalName => "Goal name: ",
+            Msg::TargetAmount => "Target amount: ",
+            Msg::LockGoal => "Lock it so withdrawals cannot use its money? (y/N): ",
+            Msg::AmountToDeposit => "Amount to deposit: ",
+            Msg::AmountToSetAside => "Amount to set aside: ",
+            Msg::AmountToTakeOut => "Amount to take out: ",
+            Msg::GoalRemoved => "\n🗑️  Removed {}",
+            Msg::NoGoalsToChoose => "There are no savings goals",
+            Msg::ChooseGoal => "Goal number in the list or name: ",
+
+            Msg::NothingToReview => "📭 No transactions are waiting for review.",
+            Msg::ReviewActions => "\nActions: 1. Approve  2. Reverse  (blank to finish)",
+            Msg::ChooseTransaction => "Transaction number in the list or ID: ",
+            Msg::TransactionReversed => "\n↩️  Transaction {} reversed:\n{}",
+
+            Msg::LedgerAndAvailable => "\n💳 Ledger balance: {}  💵 Available: {}",
+            Msg::NoHolds => "📭 No holds on this account.",
+            Msg::HoldActions => "\nActions: 1. Place  2. Release  3. Capture  (blank to finish)",
+            Msg::NoHoldsToChoose => "There are no holds",
+            Msg::ChooseHold => "Hold number or ID: ",
+            Msg::HoldReleased => "\n✅ Released: {}",
+            Msg::HoldCaptured => "\n✅ Captured: {}",
+            Msg::AmountToHold => "Amount to hold: ",
+            Msg::HoldExpiresAfter => "Expires after how many days (blank for never): ",
+            Msg::WholeDays => "Enter a whole number of days",
+            Msg::HoldPlaced => "\n✅ Placed: {}\n   ID: {}",
+            Msg::HoldExpired => "⏰ Hold expired: {}",
+            Msg::CouldNotReleaseHolds => "could not release expired holds: {}",
+
+            Msg::FilterByTag => "Filter by tag (blank for all): ",
+            Msg::NoTransactionsTagged => "\n📭 No transactions tagged #{}.\n",
+            Msg::NoTransactionsYet => "\n📭 No transactions yet.\n",
+            Msg::HistoryPage => "\n📜 Transaction History for {} (page {} of {}):\n\n{}",
+            Msg::LookupDetails => "\n{}\n   ID: {}\n   Customer: {} <{}>\n   Account: {}\n",
+            Msg::LookupReverses => "   Reverses: {}\n",
+            Msg::LookupReversedBy => "   Reversed by: {}\n",
+            Msg::LookupReason => "   Reason: {}\n",
+            Msg::AuditRecords => "\nRecords: 1. Bank  2. Customer  3. Account  4. Transaction",
+            Msg::LeaveDateBlank => "Leave a date blank to leave that end open.",
+            Msg::FromDate => "From date (YYYY-MM-DD): ",
+            Msg::ToDate => "To date (YYYY-MM-DD): ",
+            Msg::NoTransactionsInPeriod => "\n📭 No transactions in this period.\n",
+            Msg::LedgerPage => "\n📒 Ledger (page {} of {}):\n\n{}",
+            Msg::LeaveCriterionBlank => "Leave a criterion blank to skip it.",
+            Msg::MinimumAmount => "Minimum amount: ",
+            Msg::MaximumAmount => "Maximum amount: ",
+            Msg::TransactionTypeFilter => {
+                "Type (deposit, withdrawal, transfer-out, transfer-in, reversal, external-transfer, fee, \
+                 bill-payment): "
+            }
+            Msg::CounterpartyFilter => "Counterparty account ID (or its first characters): ",
+            Msg::TagFilter => "Tag: ",
+            Msg::NoMatchingTransactions => "\n🔍 No matching transactions.\n",
+            Msg::FoundTransactions => "\n🔍 Found {} transaction(s):\n─────────────────────────────────────────\n",
+            Msg::InvalidDateValue => "invalid date '{}'",
+            Msg::InvalidAmountValue => "invalid amount '{}'",
+            Msg::ReportActions => {
+                "\nReports: 1. Bank report  2. Largest transactions  3. Most active accounts  4. Dormant accounts  \
+                 5. Trial balance  6. Income statement  7. Chart of accounts  (blank to finish)"
+            }
+            Msg::HowManyTen => "How many? [10]: ",
+            Msg::LargestTransactions => "\n💰 Largest transactions:",
+            Msg::NoTransactionsLine => "\n  No transactions yet.",
+            Msg::OverHowManyDays => "Over the last how many days? [30]: ",
+            Msg::MostActiveAccounts => "\n🔥 Most active accounts (last {} days):",
+            Msg::NoTransactionsInPeriodLine => "\n  No transactions in this period.",
+            Msg::ActiveAccountLine => "\n  {}. {} {} - {} transaction(s)",
+            Msg::InactiveForDays => "Inactive for at least how many days? [{}]: ",
+            Msg::AccountsInactiveFor => "\n💤 Accounts inactive for {}+ days:",
+            Msg::NoneLine => "\n  None.",
+            Msg::DormantAccountLine => "\n  {} {} - last activity {} ({} days ago)",
+            Msg::NotWholeNumber => "'{}' is not a whole number",
+            Msg::PerDayOrWeek => "Per day or week? (d/w) [d]: ",
+            Msg::NeitherDayNorWeek => "'{}' is neither d nor w",
+            Msg::EnterOutputFile => "Enter output file (blank to print): ",
+            Msg::StatementWritten => "\n✅ Statement written to {}\n",
+            Msg::NoSpendingYet => "\n📭 No spending yet.\n",
+            Msg::SpendingByCategoryHeading => {
+                "\n🏷️  Spending by Category:\n─────────────────────────────────────────\n"
+            }
+            Msg::TransactionCount => "({} transaction(s))",
+            Msg::Uncategorized => "uncategorized",
+            Msg::TotalLabel => "total",
+            Msg::EnterMonth => "Enter month (YYYY-MM) [{}]: ",
+            Msg::EnterCsvExport => "Enter CSV file to export to (blank to print): ",
+            Msg::SummaryWritten => "\n✅ Summary for {} written to {}\n",
+
+            Msg::NoPayees => "\n📭 No saved payees yet.",
+            Msg::SavedPayeesHeading => "\n📇 Saved payees:",
+            Msg::NicknameToRemove => "Nickname to remove: ",
+            Msg::PayeeRemoved => "\n✅ Removed payee '{}'.",
+            Msg::EnterNickname => "Nickname (e.g. landlord): ",
+            Msg::EnterPayee => "Payee ID, email or account number: ",
+            Msg::PayeeSaved => "\n✅ Saved payee {}.",
+            Msg::EnterRecipientId => "Enter recipient ID or email: ",
+            Msg::AmountPerTransfer => "Enter amount per transfer: ",
+            Msg::FirstRunDate => "Enter first run date (YYYY-MM-DD, blank for today): ",
+            Msg::ScheduleCreated => "\n✅ Scheduled transfer created!\n📅 Schedule ID: {}\n",
+            Msg::NoSchedules => "\n📭 No scheduled transfers.\n",
+            Msg::TotalSchedules => "\n🗓️  Total Schedules: {}\n─────────────────────────────────────────\n\n",
+            Msg::EnterScheduleId => "Enter schedule ID: ",
+            Msg::ScheduleCancelled => "\n✅ Scheduled transfer cancelled.\n",
+            Msg::ScheduleRan => "📅 Scheduled transfer {} for {} completed",
+            Msg::ScheduleFailed => "scheduled transfer {} for {} failed: {}",
+
+            Msg::TermActionsStaff => "\nActions: 1. Open a term deposit  2. Withdraw early  (blank to finish)",
+            Msg::TermActions => "\nActions: 2. Withdraw early  (blank to finish)",
+            Msg::TermMatured => "🏦 Term deposit {} matured: {}",
+            Msg::CouldNotPayMaturities => "could not pay back matured term deposits: {}",
+            Msg::AmountToLock => "Amount to lock away: ",
+            Msg::YearlyRate => "Yearly interest rate in %, e.g. 4.5: ",
+            Msg::TermMonths => "Term in months: ",
+            Msg::InvalidMonths => "Invalid number of months",
+            Msg::TermOpened => "\n✅ Opened: {}\n💰 Pays {} at maturity (ID {})\n",
+            Msg::NoActiveTerms => "There are no active term deposits",
+            Msg::ChooseTerm => "Term deposit number in the list: ",
+            Msg::TermNoLongerActive => "That term deposit is no longer active",
+            Msg::EarlyPayout => "\nWithdrawing now pays {}: interest so far {}, penalty {}.",
+            Msg::ConfirmEarlyWithdrawal => "Withdraw it? (y/N): ",
+            Msg::TermPaidBack => "\n✅ {} paid back to the account.\n💰 New balance: {}\n",
+            Msg::NoTermDeposits => "\n📭 No term deposits.",
+            Msg::VaultCash => "\n🏦 Cash in the vault: {}",
+            Msg::VaultUntracked => "\n🏦 The vault is not tracked yet; a top-up starts tracking it.",
+            Msg::VaultActions => "\nActions: 1. Top up  2. Withdraw  (blank to finish)",
+            Msg::AmountToBringIn => "Amount to bring in: ",
+            Msg::VaultNowHolds => "\n✅ Vault now holds {}",
+            Msg::NoWebhooks => "\n📭 No webhooks registered.",
+            Msg::RegisteredWebhooks => "\n🪝 Registered webhooks:",
+            Msg::ChooseWebhook => "Webhook number or ID to remove: ",
+            Msg::WebhookRemoved => "\n✅ Removed webhook {}.",
+            Msg::WebhookUrl => "URL (http://host:port/path): ",
+            Msg::WebhookEvents => "Events: {}",
+            Msg::EventsToSend => "Events to send (comma separated, blank for all): ",
+            Msg::WebhookRegistered => "\n✅ Registered webhook {}.",
+
+            Msg::InvalidLimit => "invalid limit '{}'",
+            Msg::InvalidMonth => "invalid month '{}' (expected YYYY-MM)",
+            Msg::InterruptNotSaved => "⚠️  Ctrl+C will not save changes: {}",
+            Msg::AuditRecordHeading => "\n🧾 Audit record: {}\n\n{}",
+            Msg::NoMatch => "   (no match)",
+
+            Msg::CustomerSortKeys => "Sort by: 1. Registration date  2. Name  3. Balance",
+            Msg::SortDirections => "Order: 1. Ascending  2. Descending",
+
+            Msg::HistorySortKeys => {
+                "Sort by: 1. Oldest first  2. Newest first  3. Largest amount first  4. Smallest amount first"
+            }
+
+            Msg::ConfirmReversal => "Reverse it? (y/N): ",
+            Msg::NothingReversed => "Nothing was reversed.",
+        }
+    }
+
+    fn spanish(self) -> &'static str {
+        match self {
+            Msg::MainMenu => "MENÚ PRINCIPAL",
+            Msg::LoggedInAs => "🔐 Sesión iniciada como {} ({})",
+            Msg::UnsavedChanges => "* {} cambio(s) sin guardar",
+            Msg::RegisterCustomer => "Registrar cliente nuevo",
+            Msg::CreateAccountForCustomer => "Abrir cuenta a un cliente",
+            Msg::DepositMoney => "Depositar dinero",
+            Msg::WithdrawMoney => "Retirar dinero",
+            Msg::TransferMoney => "Transferir dinero",
+            Msg::ViewAccountDetails => "Ver detalles de la cuenta",
+            Msg::ViewTransactionHistory => "Ver historial de movimientos",
+            Msg::ListAllCustomers => "Listar todos los clientes",
+            Msg::SearchCustomers => "Buscar clientes",
+            Msg::ViewBankStatistics => "Ver estadísticas del banco",
+            Msg::SaveData => "Guardar datos",
+            Msg::CreateScheduledTransfer => "Programar transferencia",
+            Msg::ListScheduledTransfers => "Listar transferencias programadas",
+            Msg::CancelScheduledTransfer => "Cancelar transferencia programada",
+            Msg::ToggleAutosave => "Autoguardado (ahora {})",
+            Msg::RestoreBackup => "Restaurar copia de seguridad",
+            Msg::ExportTransactions => "Exportar movimientos (CSV)",
+            Msg::ImportTransactions => "Importar movimientos (CSV)",
+            Msg::GenerateStatement => "Generar extracto",
+            Msg::Login => "Iniciar sesión",
+            Msg::Logout => "Cerrar sesión",
+            Msg::ChangePin => "Cambiar PIN",
+            Msg::AddStaffUser => "Añadir empleado",
+            Msg::UndoLastOperation => "Deshacer última operación",
+            Msg::AccountLimits => "Límites de la cuenta",
+            Msg::FreezeCloseAccount => "Congelar / cerrar cuenta",
+            Msg::DeactivateDeleteCustomer => "Desactivar / eliminar cliente",
+            Msg::EditCustomer => "Editar cliente",
+            Msg::SearchTransactions => "Buscar movimientos",
+            Msg::MonthlySummary => "Resumen mensual",
+            Msg::SpendingByCategory => "Gastos por categoría",
+            Msg::ProcessBatchFile => "Procesar archivo por lotes",
+            Msg::RunAudit => "Ejecutar auditoría",
+            Msg::SavedPayees => "Beneficiarios guardados",
+            Msg::BalanceAlerts => "Alertas de saldo",
+            Msg::Webhooks => "Webhooks",
+            Msg::TransferExternal => "Transferir a otro banco",
+            Msg::ClearingQueue => "Cola de compensación",
+            Msg::AccountHolds => "Retenciones de la cuenta",
+            Msg::DepositCheque => "Depositar cheque",
+            Msg::OutstandingCheques => "Cheques pendientes",
+            Msg::DebitCards => "Tarjetas de débito",
+            Msg::CardPurchase => "Compra con tarjeta",
+            Msg::Merchants => "Comercios",
+            Msg::PayBills => "Pagar facturas",
+            Msg::SavingsGoals => "Metas de ahorro",
+            Msg::Budgets => "Presupuestos",
+            Msg::TermDeposits => "Depósitos a plazo",
+            Msg::LookupTransaction => "Buscar movimiento por ID",
+            Msg::Ledger => "Libro mayor del banco",
+            Msg::CashFlow => "Flujo de caja",
+            Msg::FraudReview => "Revisión de fraude",
+            Msg::ReverseTransaction => "Anular movimiento",
+            Msg::CashVault => "Bóveda de efectivo",
+            Msg::ImportCustomers => "Importar clientes (CSV)",
+            Msg::AuditRecord => "Registro de auditoría",
+            Msg::Exit => "Salir",
+            Msg::On => "ACTIVADO",
+            Msg::Off => "DESACTIVADO",
+
+            Msg::CreateAccount => "Abrir cuenta",
+            Msg::AllCustomers => "Todos los clientes",
+            Msg::AccountDetails => "Detalles de la cuenta",
+            Msg::TransactionHistory => "Historial de movimientos",
+            Msg::ScheduledTransfers => "Transferencias programadas",
+            Msg::BankStatistics => "Estadísticas del banco",
+            Msg::IntegrityAudit => "Auditoría de integridad",
+            Msg::AccountStatement => "Extracto de la cuenta",
+
+            Msg::EnterYourChoice => "Elija una opción: ",
+            Msg::InvalidChoiceTryAgain => "Opción no válida. Inténtelo de nuevo.",
+            Msg::ReadOnlyBanner => "🔒 Solo lectura: los cambios duran hasta que salga y nunca se guardan\n",
+            Msg::ReadOnlyCannotSave => "Modo de solo lectura: no se puede guardar nada",
+            Msg::DataSaved => "\n✅ ¡Datos guardados!",
+            Msg::NothingToSave => "\n✅ Nada que guardar: no hay cambios desde el último guardado",
+            Msg::SaveBeforeExit => "⚠️  {} cambio(s) sin guardar. ¿Guardar antes de salir? (S/n): ",
+            Msg::ExitWithoutSaving => "No se guardó completo; el diario conserva los cambios hasta el próximo guardado\n",
+            Msg::UnsavedChangesLost => "⚠️  Se pierden {} cambio(s) hechos en esta sesión",
+            Msg::AutosaveNow => "\n⚙️  Autoguardado {}\n",
+            Msg::LoggedOut => "\n👋 Sesión cerrada.\n",
+            Msg::NobodyLoggedIn => "No hay ninguna sesión iniciada",
+            Msg::ThankYou => "\n👋 ¡Gracias por usar Rust Banking System!",
+            Msg::ReadOnlyGoodbye => "🔒 Solo lectura: no se guardó nada. ¡Adiós!\n",
+            Msg::SavedGoodbye => "💾 Datos guardados. ¡Adiós!\n",
+            Msg::CouldNotSave => "⚠️  No se pudieron guardar los cambios: {}\n",
+            Msg::EmergencySaved => "💾 Se guardó el banco en {} antes de salir\n",
+            Msg::Interrupted => "\n\n⚠️  Interrumpido; guardando antes de salir...",
+            Msg::BankBusy => "una operación sigue usando el banco",
+
+            Msg::Error => "Error",
+            Msg::LogInFirst => "Inicie sesión primero (opción 20 del menú)",
+            Msg::RoleNotAllowed => "El rol {} no tiene permiso para hacer eso",
+            Msg::EnterAction => "Elija una acción: ",
+            Msg::EnterCustomer => "ID o correo del cliente: ",
+            Msg::InvalidChoice => "Opción no válida",
+            Msg::InvalidAmount => "Importe no válido",
+            Msg::InvalidDate => "Fecha no válida",
+            Msg::InvalidFrequency => "Frecuencia no válida",
+            Msg::InvalidAction => "Acción no válida",
+            Msg::Cancelled => "Cancelado",
+            Msg::OperationCrashed => "La operación se detuvo de forma inesperada ({}); la sesión continúa",
+            Msg::TooManyAttempts => "Ninguna respuesta válida tras {} intentos",
+            Msg::AmountFormat => "Importe no válido '{}': use cifras con dos decimales como máximo, p. ej. 12.50",
+            Msg::AmountNegative => "El importe no puede ser negativo",
+            Msg::InvalidId => "'{}' no es un ID completo (p. ej. 67e55044-10b1-426f-9247-bb680e5fe0c8)",
+            Msg::ChoiceOutOfRange => "Introduzca un número del 1 al {}",
+            Msg::MatchesCustomers => "\n'{}' coincide con {} clientes:",
+            Msg::ChooseOneOf => "Elija 1-{} (en blanco para cancelar): ",
+            Msg::PageNext => "[n] siguiente",
+            Msg::PagePrev => "[p] anterior",
+            Msg::PageQuit => "[q] salir",
+
+            Msg::LogInAs => "Entrar como: 1. Cliente  2. Empleado",
+            Msg::EnterChoiceDefaultOne => "Elija una opción [1]: ",
+            Msg::EnterPin => "PIN: ",
+            Msg::ChoosePinNow => "Todavía no tiene PIN; elija uno.",
+            Msg::Welcome => "\n✅ ¡Bienvenido/a, {}!\n",
+            Msg::NoStaffYet => "Todavía no hay empleados; se creará el primer administrador.",
+            Msg::EnterUsername => "Nombre de usuario: ",
+
+            Msg::EnterCustomerName => "Nombre del cliente: ",
+            Msg::EnterCustomerEmail => "Correo del cliente: ",
+            Msg::CustomerRegistered => "\n✅ ¡Cliente registrado!\n📋 ID de cliente: {}\n",
+            Msg::EnterInitialDeposit => "Depósito inicial: ",
+            Msg::AccountTypes => "Tipos de cuenta: 1. Corriente  2. Ahorro  3. Plazo fijo",
+            Msg::EnterAccountType => "Tipo de cuenta [1]: ",
+            Msg::EnterTermMonths => "Plazo en meses: ",
+            Msg::InvalidAccountType => "Tipo de cuenta no válido",
+            Msg::AccountCreated => {
+                "\n✅ ¡Cuenta abierta!\n🔢 Número de cuenta: {}\n💳 ID de cuenta: {}\n\
+                 🏷️  Tipo de cuenta: {}\n💰 Saldo inicial: {}\n"
+            }
+            Msg::EnterDepositAmount => "Importe a depositar: ",
+            Msg::DepositDone => "\n✅ ¡Depósito realizado!\n💰 Nuevo saldo: {}\n",
+            Msg::EnterWithdrawalAmount => "Importe a retirar: ",
+            Msg::WithdrawalDone => "\n✅ ¡Retiro realizado!\n💰 Nuevo saldo: {}\n",
+            Msg::EnterSender => "ID o correo del ordenante: ",
+            Msg::EnterRecipient => "ID, correo o alias del beneficiario: ",
+            Msg::EnterTransferAmount => "Importe a transferir: ",
+            Msg::TransferDone => "\n✅ ¡Transferencia realizada!\n💸 {} transferidos\n",
+            Msg::OfferReceipt => "🧾 ¿Recibo? (s/N): ",
+            Msg::ReceiptFile => "🧾 Archivo donde guardarlo (vacío para mostrarlo aquí): ",
+            Msg::ReceiptSaved => "🧾 Recibo guardado en {}",
+            Msg::ReceiptFileExists => "{} ya existe; el recibo no se guardó",
+            Msg::DescriptionPrompt => "Concepto (en blanco para ninguno): ",
+            Msg::CategoryPrompt => "Categoría (en blanco para ninguna): ",
+            Msg::TagsPrompt => "Etiquetas separadas por comas (en blanco para ninguna): ",
+
+            Msg::EnterExternalAccount => "Cuenta de destino (IBAN o entidad-cuenta): ",
+            Msg::EnterAmountToSend => "Importe a enviar: ",
+            Msg::ExternalTransferSent => {
+                "\n✅ {} enviado a {}.\n⏳ Pendiente hasta que el banco de destino lo liquide (ref. {}).\n"
+            }
+            Msg::UndoDone => "\n↩️  ¡Deshecho! Anulaciones registradas:\n",
+            Msg::UndoMore => "\nSe pueden deshacer {} operación(es) más\n",
+            Msg::EnterTransactionId => "ID del movimiento: ",
+            Msg::TransactionOwner => "\n{}\n   Cliente: {} <{}>\n",
+            Msg::EnterReason => "Motivo: ",
+            Msg::ReversedDone => "\n↩️  ¡Anulado! Asientos de compensación registrados:\n",
+            Msg::LimitsHelp => {
+                "Escriba un valor nuevo, 'none' para no tener límite, o déjelo en blanco para conservarlo."
+            }
+            Msg::EnterMaxWithdrawal => "Retiro máximo por operación: ",
+            Msg::EnterDailyWithdrawal => "Retiro máximo por día: ",
+            Msg::EnterDailyTransfers => "Transferencias máximas por día: ",
+            Msg::LimitsUpdated => "\n✅ Límites actualizados.\n{}\n",
+            Msg::AlertsHelp => {
+                "Escriba un importe nuevo, 'none' para desactivar el aviso, o déjelo en blanco para conservarlo."
+            }
+            Msg::EnterLowBalanceAlert => "Avisar cuando el saldo baje de: ",
+            Msg::EnterLargeTransactionAlert => "Avisar de movimientos superiores a: ",
+            Msg::AlertsUpdated => "\n✅ Avisos actualizados.\n{}\n",
+            Msg::AccountStatusActions => "Acciones: 1. Congelar  2. Descongelar  3. Cerrar",
+            Msg::EnterBalanceDestination => "Transferir el saldo restante a (ID o correo): ",
+            Msg::AccountNowStatus => "\n✅ La cuenta ahora está {}.\n",
+            Msg::TransferredOut => "💸 {} transferido fuera\n",
+            Msg::FlaggedDormant => "💤 Cuenta de {} {} marcada como inactiva{} tras {} días sin movimientos",
+            Msg::AndFrozen => " y congelada",
+            Msg::PoliciesFailed => "no se pudieron aplicar las políticas de cuentas: {}",
+
+            Msg::StaffWelcome => "\n✅ ¡Bienvenido/a, {} ({})!\n",
+            Msg::EnterCurrentPin => "PIN actual: ",
+            Msg::PinChanged => "\n✅ PIN cambiado.\n",
+            Msg::StaffRoles => "Roles: 1. Cajero  2. Administrador",
+            Msg::EnterRole => "Rol [1]: ",
+            Msg::InvalidRole => "Rol no válido",
+            Msg::StaffAdded => "\n✅ {} '{}' añadido.\n",
+            Msg::ChooseNewPin => "Elija un PIN (de 4 a 12 dígitos): ",
+            Msg::RepeatPin => "Repita el PIN: ",
+            Msg::PinsDiffer => "Los PIN no coinciden",
+
+            Msg::AddRemoveActions => "\nAcciones: 1. Añadir  2. Quitar  (en blanco para terminar)",
+            Msg::EnterMerchantName => "Nombre del comercio: ",
+            Msg::EnterMerchantCategory => "Categoría (utility, telecom, insurance, other): ",
+            Msg::Added => "\n✅ Añadido: {}",
+            Msg::Removed => "\n🗑️  Eliminado: {}",
+            Msg::BillActions => {
+                "\nAcciones: 1. Pagar una factura  2. Domiciliar un pago  3. Cancelar una domiciliación  \
+                 (en blanco para terminar)"
+            }
+            Msg::BillPaid => "\n✅ {} pagado a {} (ref. {}).\n💰 Saldo nuevo: {}\n",
+            Msg::Frequencies => "Frecuencias: 1. Diaria  2. Semanal  3. Mensual",
+            Msg::EnterFrequency => "Frecuencia: ",
+            Msg::EnterFirstPaymentDate => "Fecha del primer pago (AAAA-MM-DD, en blanco para hoy): ",
+            Msg::AutopaySetUp => "\n✅ Domiciliación a {} creada: {}",
+            Msg::NoAutopayRules => "No hay domiciliaciones",
+            Msg::EnterAutopay => "Número de la domiciliación en la lista o ID: ",
+            Msg::AutopayCancelled => "\n✅ Domiciliación cancelada.",
+            Msg::AutopayPaid => "🧾 Domiciliación {} del {}: {} pagado a {}",
+            Msg::Merchant => "comercio",
+            Msg::AutopayFailed => "la domiciliación {} del {} falló: {}",
+            Msg::NoMerchants => "\n📭 No hay comercios registrados.",
+            Msg::NoAutopays => "\n📭 No hay domiciliaciones.",
+            Msg::AutopayHeading => "\n🔁 Domiciliaciones:",
+            Msg::RemovedMerchant => "(eliminado)",
+            Msg::NoMerchantsYet => "No hay comercios; un administrador puede añadirlos con la opción Comercios",
+            Msg::EnterMerchant => "Número del comercio en la lista, nombre o ID: ",
+            Msg::EnterBillReference => "Su referencia con el comercio: ",
+            Msg::EnterAmount => "Importe: ",
+
+            Msg::EnterCsvOrShow => "Archivo CSV a escribir (en blanco para mostrarlo aquí): ",
+            Msg::TrialBalanceWritten => "\n✅ Balance de comprobación escrito en {}",
+            Msg::EnterStartDate => "Fecha de inicio (AAAA-MM-DD)",
+            Msg::EnterEndDate => "Fecha de fin (AAAA-MM-DD)",
+            Msg::IncomeStatementWritten => "\n✅ Cuenta de resultados escrita en {}",
+            Msg::ChartActions => {
+                "\nAcciones: 1. Editar una cuenta  2. Cambiar una regla de asignación  (en blanco para terminar)"
+            }
+            Msg::EnterLedgerAccount => "Código o nombre de la cuenta: ",
+            Msg::EnterLedgerCode => "Código nuevo [{}]: ",
+            Msg::EnterLedgerName => "Nombre nuevo [{}]: ",
+            Msg::EnterLedgerClass => "Clase (asset, liability, equity, income, expense) [{}]: ",
+            Msg::EnterTransactionKind => "Tipo de movimiento (p. ej. fee, bill-payment): ",
+            Msg::EnterPostingAccount => "Contabilizar en la cuenta (código o nombre): ",
+            Msg::ChartUpdated => "\n✅ Plan de cuentas actualizado",
+            Msg::NoLedgerAccount => "no hay ninguna cuenta con código o nombre '{}'",
+
+            Msg::NoBudgets => "\n📭 Todavía no hay presupuestos.",
+            Msg::BudgetsThisMonth => "\n📊 Este mes:\n{}",
+            Msg::BudgetActions => {
+                "\nAcciones: 1. Fijar un presupuesto  2. Quitar un presupuesto  (en blanco para terminar)"
+            }
+            Msg::EnterBudgetCategory => "Categoría, p. ej. groceries: ",
+            Msg::EnterMonthlyLimit => "Límite mensual: ",
+            Msg::BudgetSet => "\n✅ Presupuesto de {} fijado en {} al mes",
+            Msg::EnterCategory => "Categoría: ",
+            Msg::BudgetRemoved => "\n🗑️  Presupuesto de {} eliminado",
+            Msg::OverBudget => "\n⚠️  Esto superaría su presupuesto: {}",
+            Msg::GoAheadAnyway => "¿Continuar de todos modos? (s/N): ",
+            Msg::CancelledDone => "\n↩️  Cancelado.\n",
+
+            Msg::CardActions => {
+                "\nAcciones: 1. Emitir  2. Bloquear  3. Reemplazar  4. Fijar límites  (en blanco para terminar)"
+            }
+            Msg::CardDone => "\n✅ {}\n   Límites: {}",
+            Msg::NoActiveCards => {
+                "No hay tarjetas activas; el personal puede emitir una con la opción Tarjetas de débito"
+            }
+            Msg::EnterPurchaseAmount => "Importe de la compra: ",
+            Msg::DescribePurchase => "Describa la compra, p. ej. con el nombre del comercio.",
+            Msg::CardPaid => "\n✅ {} pagado con la tarjeta {}.\n💰 Saldo nuevo: {}\n",
+            Msg::NoCards => "\n📭 No hay tarjetas.",
+            Msg::CardLine => "  {}. {}\n     Límites: {}\n     ID: {}",
+            Msg::NoCardsToChoose => "No hay tarjetas",
+            Msg::EnterCard => "Número de la tarjeta en la lista o ID: ",
+            Msg::CardLimitsHelp => {
+                "Escriba un límite, 'none' para no tener límite, o déjelo en blanco para conservarlo."
+            }
+            Msg::EnterMaxPurchase => "Compra máxima por operación: ",
+            Msg::EnterDailySpend => "Gasto máximo por día: ",
+
+            Msg::EnterChequeNumber => "Número de cheque: ",
+            Msg::EnterDrawer => "Librado por (ID o correo del cliente, en blanco si es de otro banco): ",
+            Msg::ChequeDeposited => {
+                "\n✅ Cheque n.º {} por {} depositado.\n⏳ Los fondos estarán disponibles cuando se compense el {}.\n"
+            }
+            Msg::NoChequesWaiting => "📭 No hay cheques pendientes de compensación.",
+            Msg::ChequeActions => {
+                "\nAcciones: 1. Compensar los cheques vencidos  2. Devolver un cheque  (en blanco para terminar)"
+            }
+            Msg::EnterCheque => "Número del cheque en la lista o ID: ",
+            Msg::EnterReturnReason => "Motivo (en blanco para \"Returned unpaid\"): ",
+            Msg::ClearingChequesFailed => "no se pudieron compensar los cheques vencidos: {}",
+
+            Msg::NoPendingExternal => "\n📭 No hay transferencias externas pendientes.",
+            Msg::PendingExternalHeading => "\n⏳ Transferencias externas pendientes:",
+            Msg::ClearingActions => "\nAcciones: 1. Liquidar  2. Rechazar  (en blanco para terminar)",
+            Msg::EnterPendingTransfer => "Número de la transferencia o ID: ",
+            Msg::Settled => "\n✅ Liquidada: {}",
+            Msg::EnterOptionalReason => "Motivo (en blanco para ninguno): ",
+            Msg::RejectedRefunded => "\n↩️  Rechazada; {} devuelto al remitente.",
+
+            Msg::NoCustomersYet => "\n📭 Todavía no hay clientes registrados.\n",
+            Msg::CustomersPage => "\n👥 Total de clientes: {} (página {} de {})\n\n{}",
+            Msg::EnterSearchQuery => "Texto a buscar (nombre): ",
+            Msg::NoCustomersMatching => "\n🔍 Ningún cliente coincide con '{}'\n",
+            Msg::FoundCustomers => "\n🔍 {} cliente(s) encontrado(s):\n\n{}",
+            Msg::DetailsEmail => "\n{}\n📧 Correo: {}",
+            Msg::DetailsPhone => "\n📞 Teléfono: {}",
+            Msg::DetailsAddress => "\n🏠 Dirección: {}",
+            Msg::DetailsBirthDate => "\n🎂 Fecha de nacimiento: {}",
+            Msg::AccountStatistics => {
+                "\n\n📊 Estadísticas de la cuenta:\n  Tipo de cuenta: {}\n  Estado: {}\n  \
+                 Saldo disponible: {} ({} retenido)\n  Total depositado: {}\n  Total retirado: {}\n  \
+                 Número de movimientos: {}\n"
+            }
+            Msg::SavingsGoalsHeading => "\n🎯 Metas de ahorro ({} apartado):\n{}",
+            Msg::EditHelp => "Deje un campo en blanco para conservarlo; escriba 'none' para quitar uno opcional.",
+            Msg::NothingChanged => "No ha cambiado nada",
+            Msg::CustomerUpdated => "\n✅ Cliente actualizado.\n",
+            Msg::EnterNewName => "Nombre [{}]: ",
+            Msg::EnterNewEmail => "Correo [{}]: ",
+            Msg::PhoneLabel => "Teléfono",
+            Msg::AddressLabel => "Dirección",
+            Msg::BirthDateLabel => "Fecha de nacimiento (AAAA-MM-DD)",
+            Msg::CustomerStatusActions => "Acciones: 1. Desactivar  2. Reactivar  3. Eliminar",
+            Msg::ConfirmWord => "sí",
+            Msg::ConfirmDeletion => "La eliminación no se puede deshacer. Escriba '{}' para confirmar: ",
+            Msg::DeletionCancelled => "Eliminación cancelada",
+            Msg::CustomerNowStatus => "\n✅ El cliente ahora está {}.\n",
+
+            Msg::EnterPassphrase => "🔒 Contraseña del archivo de datos: ",
+            Msg::CouldNotUnlock => "no se pudo desbloquear el archivo de datos",
+            Msg::SetPassphrase => "🔑 Elija una contraseña para cifrar el archivo de datos (en blanco para ninguna): ",
+            Msg::NoBackups => "\n📭 No hay copias de seguridad de {}.\n",
+            Msg::UnknownTime => "hora desconocida",
+            Msg::BackupLine => "  {}. {} (guardada {})",
+            Msg::EnterBackupNumber => "\nNúmero de la copia a restaurar: ",
+            Msg::InvalidBackupNumber => "Número de copia no válido",
+            Msg::ConfirmRestore => "⚠️  Se reemplazarán los datos actuales. ¿Continuar? (s/N): ",
+            Msg::RestoreCancelled => "\n↩️  Restauración cancelada.\n",
+            Msg::BackupRestored => "\n✅ ¡Copia {} restaurada!\n",
+            Msg::EnterExportFile => "Archivo de salida [transactions.csv]: ",
+            Msg::TransactionsExported => "\n✅ {} movimiento(s) exportado(s) a {}\n",
+            Msg::EnterImportFile => "Archivo CSV a importar: ",
+            Msg::TransactionsImported => "\n✅ {} movimiento(s) importado(s)\n",
+            Msg::EnterBatchFile => "Archivo de lote (.csv o .json): ",
+            Msg::AllOrNothing => "¿Aplicar todo o nada? (s/N): ",
+            Msg::BatchApplied => "  {}. ✅ {} (saldo {})\n",
+            Msg::BatchSummary => "\n📦 {} de {} operación(es) aplicada(s)\n",
+            Msg::EnterCustomerCsv => "Archivo CSV (name,email,initial_deposit): ",
+            Msg::NoCustomersInFile => "\n📭 El archivo no contiene clientes.\n",
+            Msg::CustomersImported => "\n{}\n\n👥 {} de {} cliente(s) importado(s), {} omitido(s)\n",
+
+            Msg::NoSavingsGoals => "\n📭 Todavía no hay metas de ahorro.",
+            Msg::FreeToSpend => "💵 Libre para gastar o apartar: {}",
+            Msg::SavingsGoalActions => {
+                "\nAcciones: 1. Nueva meta  2. Depositar en la meta  3. Apartar dinero  4. Sacar dinero  \
+                 5. Bloquear / desbloquear  6. Quitar  (en blanco para terminar)"
+            }
+            Msg::GoalName => "Nombre de la meta: ",
+            Msg::TargetAmount => "Importe objetivo: ",
+            Msg::LockGoal => "¿Bloquearla para que los retiros no usen su dinero? (s/N): ",
+            Msg::AmountToDeposit => "Importe a depositar: ",
+            Msg::AmountToSetAside => "Importe a apartar: ",
+            Msg::AmountToTakeOut => "Importe a sacar: ",
+            Msg::GoalRemoved => "\n🗑️  {} quitada",
+            Msg::NoGoalsToChoose => "No hay metas de ahorro",
+            Msg::ChooseGoal => "Número de la meta en la lista o nombre: ",
+
+            Msg::NothingToReview => "📭 No hay movimientos pendientes de revisión.",
+            Msg::ReviewActions => "\nAcciones: 1. Aprobar  2. Anular  (en blanco para terminar)",
+            Msg::ChooseTransaction => "Número del movimiento en la lista o ID: ",
+            Msg::TransactionReversed => "\n↩️  Movimiento {} anulado:\n{}",
+
+            Msg::LedgerAndAvailable => "\n💳 Saldo contable: {}  💵 Disponible: {}",
+            Msg::NoHolds => "📭 Esta cuenta no tiene retenciones.",
+            Msg::HoldActions => "\nAcciones: 1. Retener  2. Liberar  3. Cobrar  (en blanco para terminar)",
+            Msg::NoHoldsToChoose => "No hay retenciones",
+            Msg::ChooseHold => "Número o ID de la retención: ",
+            Msg::HoldReleased => "\n✅ Liberada: {}",
+            Msg::HoldCaptured => "\n✅ Cobrada: {}",
+            Msg::AmountToHold => "Importe a retener: ",
+            Msg::HoldExpiresAfter => "¿Vence en cuántos días? (en blanco para nunca): ",
+            Msg::WholeDays => "Introduzca un número entero de días",
+            Msg::HoldPlaced => "\n✅ Retenido: {}\n   ID: {}",
+            Msg::HoldExpired => "⏰ Retención vencida: {}",
+            Msg::CouldNotReleaseHolds => "no se pudieron liberar las retenciones vencidas: {}",
+
+            Msg::FilterByTag => "Filtrar por etiqueta (en blanco para todas): ",
+            Msg::NoTransactionsTagged => "\n📭 No hay movimientos con la etiqueta #{}.\n",
+            Msg::NoTransactionsYet => "\n📭 Todavía no hay movimientos.\n",
+            Msg::HistoryPage => "\n📜 Historial de movimientos de {} (página {} de {}):\n\n{}",
+            Msg::LookupDetails => "\n{}\n   ID: {}\n   Cliente: {} <{}>\n   Cuenta: {}\n",
+            Msg::LookupReverses => "   Anula: {}\n",
+            Msg::LookupReversedBy => "   Anulado por: {}\n",
+            Msg::LookupReason => "   Motivo: {}\n",
+            Msg::AuditRecords => "\nRegistros: 1. Banco  2. Cliente  3. Cuenta  4. Movimiento",
+            Msg::LeaveDateBlank => "Deje una fecha en blanco para dejar ese extremo abierto.",
+            Msg::FromDate => "Desde (AAAA-MM-DD): ",
+            Msg::ToDate => "Hasta (AAAA-MM-DD): ",
+            Msg::NoTransactionsInPeriod => "\n📭 No hay movimientos en este periodo.\n",
+            Msg::LedgerPage => "\n📒 Libro mayor (página {} de {}):\n\n{}",
+            Msg::LeaveCriterionBlank => "Deje un criterio en blanco para omitirlo.",
+            Msg::MinimumAmount => "Importe mínimo: ",
+            Msg::MaximumAmount => "Importe máximo: ",
+            Msg::TransactionTypeFilter => {
+                "Tipo (deposit, withdrawal, transfer-out, transfer-in, reversal, external-transfer, fee, \
+                 bill-payment): "
+            }
+            Msg::CounterpartyFilter => "ID de la cuenta de la contraparte (o sus primeros caracteres): ",
+            Msg::TagFilter => "Etiqueta: ",
+            Msg::NoMatchingTransactions => "\n🔍 Ningún movimiento coincide.\n",
+            Msg::FoundTransactions => {
+                "\n🔍 {} movimiento(s) encontrado(s):\n─────────────────────────────────────────\n"
+            }
+            Msg::InvalidDateValue => "fecha no válida '{}'",
+            Msg::InvalidAmountValue => "importe no válido '{}'",
+            Msg::ReportActions => {
+                "\nInformes: 1. Informe del banco  2. Mayores movimientos  3. Cuentas más activas  \
+                 4. Cuentas inactivas  5. Balance de comprobación  6. Cuenta de resultados  7. Plan de cuentas  \
+                 (en blanco para terminar)"
+            }
+            Msg::HowManyTen => "¿Cuántos? [10]: ",
+            Msg::LargestTransactions => "\n💰 Mayores movimientos:",
+            Msg::NoTransactionsLine => "\n  Todavía no hay movimientos.",
+            Msg::OverHowManyDays => "¿En cuántos de los últimos días? [30]: ",
+            Msg::MostActiveAccounts => "\n🔥 Cuentas más activas (últimos {} días):",
+            Msg::NoTransactionsInPeriodLine => "\n  No hay movimientos en este periodo.",
+            Msg::ActiveAccountLine => "\n  {}. {} {} - {} movimiento(s)",
+            Msg::InactiveForDays => "¿Inactivas durante al menos cuántos días? [{}]: ",
+            Msg::AccountsInactiveFor => "\n💤 Cuentas inactivas durante {}+ días:",
+            Msg::NoneLine => "\n  Ninguna.",
+            Msg::DormantAccountLine => "\n  {} {} - última actividad {} (hace {} días)",
+            Msg::NotWholeNumber => "'{}' no es un número entero",
+            Msg::PerDayOrWeek => "¿Por día o por semana? (d/w) [d]: ",
+            Msg::NeitherDayNorWeek => "'{}' no es ni d ni w",
+            Msg::EnterOutputFile => "Archivo de salida (en blanco para mostrarlo): ",
+            Msg::StatementWritten => "\n✅ Extracto escrito en {}\n",
+            Msg::NoSpendingYet => "\n📭 Todavía no hay gastos.\n",
+            Msg::SpendingByCategoryHeading => {
+                "\n🏷️  Gastos por categoría:\n─────────────────────────────────────────\n"
+            }
+            Msg::TransactionCount => "({} movimiento(s))",
+            Msg::Uncategorized => "sin categoría",
+            Msg::TotalLabel => "total",
+            Msg::EnterMonth => "Mes (AAAA-MM) [{}]: ",
+            Msg::EnterCsvExport => "Archivo CSV de exportación (en blanco para mostrarlo): ",
+            Msg::SummaryWritten => "\n✅ Resumen de {} escrito en {}\n",
+
+            Msg::NoPayees => "\n📭 Todavía no hay beneficiarios guardados.",
+            Msg::SavedPayeesHeading => "\n📇 Beneficiarios guardados:",
+            Msg::NicknameToRemove => "Alias a quitar: ",
+            Msg::PayeeRemoved => "\n✅ Beneficiario '{}' quitado.",
+            Msg::EnterNickname => "Alias (p. ej. casero): ",
+            Msg::EnterPayee => "ID, correo o número de cuenta del beneficiario: ",
+            Msg::PayeeSaved => "\n✅ Beneficiario {} guardado.",
+            Msg::EnterRecipientId => "ID o correo del beneficiario: ",
+            Msg::AmountPerTransfer => "Importe de cada transferencia: ",
+            Msg::FirstRunDate => "Fecha de la primera ejecución (AAAA-MM-DD, en blanco para hoy): ",
+            Msg::ScheduleCreated => "\n✅ ¡Transferencia programada creada!\n📅 ID de la programación: {}\n",
+            Msg::NoSchedules => "\n📭 No hay transferencias programadas.\n",
+            Msg::TotalSchedules => "\n🗓️  Total de programaciones: {}\n─────────────────────────────────────────\n\n",
+            Msg::EnterScheduleId => "ID de la programación: ",
+            Msg::ScheduleCancelled => "\n✅ Transferencia programada cancelada.\n",
+            Msg::ScheduleRan => "📅 Transferencia programada {} del {} completada",
+            Msg::ScheduleFailed => "la transferencia programada {} del {} falló: {}",
+
+            Msg::TermActionsStaff => {
+                "\nAcciones: 1. Abrir un depósito a plazo  2. Retirar anticipadamente  (en blanco para terminar)"
+            }
+            Msg::TermActions => "\nAcciones: 2. Retirar anticipadamente  (en blanco para terminar)",
+            Msg::TermMatured => "🏦 Depósito a plazo {} vencido: {}",
+            Msg::CouldNotPayMaturities => "no se pudieron devolver los depósitos a plazo vencidos: {}",
+            Msg::AmountToLock => "Importe a inmovilizar: ",
+            Msg::YearlyRate => "Interés anual en %, p. ej. 4.5: ",
+            Msg::TermMonths => "Plazo en meses: ",
+            Msg::InvalidMonths => "Número de meses no válido",
+            Msg::TermOpened => "\n✅ Abierto: {}\n💰 Paga {} al vencimiento (ID {})\n",
+            Msg::NoActiveTerms => "No hay depósitos a plazo activos",
+            Msg::ChooseTerm => "Número del depósito a plazo en la lista: ",
+            Msg::TermNoLongerActive => "Ese depósito a plazo ya no está activo",
+            Msg::EarlyPayout => "\nRetirarlo ahora paga {}: intereses hasta hoy {}, penalización {}.",
+            Msg::ConfirmEarlyWithdrawal => "¿Retirarlo? (s/N): ",
+            Msg::TermPaidBack => "\n✅ {} devuelto a la cuenta.\n💰 Nuevo saldo: {}\n",
+            Msg::NoTermDeposits => "\n📭 No hay depósitos a plazo.",
+            Msg::VaultCash => "\n🏦 Efectivo en la cámara: {}",
+            Msg::VaultUntracked => "\n🏦 La cámara todavía no se controla; una reposición empieza a controlarla.",
+            Msg::VaultActions => "\nAcciones: 1. Reponer  2. Retirar  (en blanco para terminar)",
+            Msg::AmountToBringIn => "Importe a ingresar: ",
+            Msg::VaultNowHolds => "\n✅ La cámara tiene ahora {}",
+            Msg::NoWebhooks => "\n📭 No hay webhooks registrados.",
+            Msg::RegisteredWebhooks => "\n🪝 Webhooks registrados:",
+            Msg::ChooseWebhook => "Número o ID del webhook a quitar: ",
+            Msg::WebhookRemoved => "\n✅ Webhook {} quitado.",
+            Msg::WebhookUrl => "URL (http://host:puerto/ruta): ",
+            Msg::WebhookEvents => "Eventos: {}",
+            Msg::EventsToSend => "Eventos a enviar (separados por comas, en blanco para todos): ",
+            Msg::WebhookRegistered => "\n✅ Webhook {} registrado.",
+
+            Msg::InvalidLimit => "límite no válido '{}'",
+            Msg::InvalidMonth => "mes no válido '{}' (se esperaba AAAA-MM)",
+            Msg::InterruptNotSaved => "⚠️  Ctrl+C no guardará los cambios: {}",
+            Msg::AuditRecordHeading => "\n🧾 Registro de auditoría: {}\n\n{}",
+            Msg::NoMatch => "   (sin coincidencias)",
+
+            Msg::CustomerSortKeys => "Ordenar por: 1. Fecha de alta  2. Nombre  3. Saldo",
+            Msg::SortDirections => "Orden: 1. Ascendente  2. Descendente",
+
+            Msg::HistorySortKeys => {
+                "Ordenar por: 1. Más antiguos primero  2. Más recientes primero  3. Mayor importe primero  \
+                 4. Menor importe primero"
+            }
+
+            Msg::ConfirmReversal => "¿Anularlo? (s/N): ",
+            Msg::NothingReversed => "No se ha anulado nada.",
+        }
+    }
+}
+
+/// Writes the message in the current language, so a `Msg` can be passed
+/// straight to `Renderer::failure`
+impl fmt::Display for Msg {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.text())
+    }
+}
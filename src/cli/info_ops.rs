@@ -2,83 +2,683 @@
 //!
 //! Demonstrates: Data display, iterator usage for analytics
 
+use std::fs;
 use std::io;
-use std::sync::{Arc, Mutex};
 
-use crate::bank::Bank;
-use crate::traits::Summarizable;
-use super::utils::read_input;
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Utc};
+use serde_json::json;
+
+use crate::bank::{CashFlowPeriod, SharedBank};
+use crate::clock::end_of_day;
+use crate::errors::{BankError, BankResult};
+use crate::models::{AccountId, Money, Page, TransactionFilter, TransactionId, TransactionKind, TransactionOrder};
+use crate::persistence;
+use super::books_ops::{manage_chart, show_income_statement, show_trial_balance};
+use super::i18n::Msg;
+use super::render::Renderer;
+use super::session::{authorize, Session, ADMIN_ONLY, ANY_ROLE, STAFF};
+use super::table::{ledger_table, transaction_table};
+use super::utils::{
+    browse_pages, detailed_list, parse_month, read_checked, read_customer, read_input, unless_blank, verbose, PAGE_SIZE,
+};
 
 /// Views transaction history for a customer
-pub fn view_transaction_history(bank: &Arc<Mutex<Bank>>) -> io::Result<()> {
-    println!("\n--- Transaction History ---");
+///
+/// Customers only see their own history.
+pub fn view_transaction_history(
+    bank: &SharedBank,
+    session: Option<&Session>,
+    renderer: &dyn Renderer,
+) -> io::Result<()> {
+    let session = match authorize(session, ANY_ROLE, renderer) {
+        Some(session) => session,
+        None => return Ok(()),
+    };
+
+    renderer.section(Msg::TransactionHistory.text());
+
+    let customer_id = match session.target_customer(bank, Msg::EnterCustomer.text(), renderer)? {
+        Some(id) => id,
+        None => return Ok(()),
+    };
+
+    // Check the customer up front so a bad ID is reported once, not per page
+    let found = bank.read().get_customer(&customer_id).and_then(|c| c.get_account().map(|_| ()));
+    if let Err(e) = found {
+        renderer.failure(&e);
+        return Ok(());
+    }
+
+    let tag = read_input(Msg::FilterByTag.text())?.to_lowercase();
+    let filter = match tag.as_str() {
+        "" => TransactionFilter::new(),
+        tag => TransactionFilter::new().tag(tag),
+    };
+
+    renderer.chrome(Msg::HistorySortKeys.text());
+    let order = match read_input(Msg::EnterChoiceDefaultOne.text())?.as_str() {
+        "" | "1" => TransactionOrder::OldestFirst,
+        "2" => TransactionOrder::NewestFirst,
+        "3" => TransactionOrder::LargestFirst,
+        "4" => TransactionOrder::SmallestFirst,
+        _ => {
+            renderer.failure(&Msg::InvalidChoice);
+            return Ok(());
+        }
+    };
+
+    browse_pages(renderer, |offset| {
+        let bank = bank.read();
+        let Ok(customer) = bank.get_customer(&customer_id) else {
+            return (false, false);
+        };
+        let Ok(account) = customer.get_account() else {
+            return (false, false);
+        };
+        let page = if tag.is_empty() && order == TransactionOrder::OldestFirst {
+            account.transactions_page(offset, PAGE_SIZE)
+        } else {
+            let mut matching = account.filter_transactions(&filter);
+            order.sort(&mut matching);
+            Page::from_items(matching, offset, PAGE_SIZE)
+        };
+
+        let text = if page.total == 0 && !tag.is_empty() {
+            Msg::NoTransactionsTagged.fill(&[&tag])
+        } else if page.total == 0 {
+            Msg::NoTransactionsYet.text().to_string()
+        } else if verbose() {
+            Msg::HistoryPage.fill(&[&customer.name, &page.number(), &page.count(), &detailed_list(&page.items)])
+        } else {
+            // Show who was on the other side of a transfer
+            let table = transaction_table(&page.items, |transaction| {
+                let counterparty = transaction.transaction_type.counterparty()?;
+                let owner = bank.find_customer_by_account_id(&AccountId::from(counterparty))?;
+                let number = owner.account.as_ref().and_then(|a| a.number);
+                Some(format!(
+                    "{} ({})",
+                    owner.name,
+                    number.map(|n| n.to_string()).unwrap_or_else(|| counterparty.get(..8).unwrap_or(counterparty).to_string())
+                ))
+            });
+            Msg::HistoryPage.fill(&[&customer.name, &page.number(), &page.count(), &table.render()])
+        };
+        renderer.success(&text, json!(page));
+        (page.has_prev(), page.has_next())
+    })
+}
+
+/// Finds any transaction by its ID and shows whose account it is on
+///
+/// Customers only find their own transactions; anyone else's are reported
+/// as not found.
+pub fn lookup_transaction(
+    bank: &SharedBank,
+    session: Option<&Session>,
+    renderer: &dyn Renderer,
+) -> io::Result<()> {
+    let session = match authorize(session, ANY_ROLE, renderer) {
+        Some(session) => session,
+        None => return Ok(()),
+    };
+
+    renderer.section(Msg::LookupTransaction.text());
+
+    let transaction_id = TransactionId::from(read_input(Msg::EnterTransactionId.text())?);
+    let bank = bank.read();
+    let found = bank
+        .find_transaction(&transaction_id)
+        .filter(|(owner, _)| session.customer_id().is_none_or(|id| id == *owner.customer_id));
+
+    match found {
+        Some((owner, transaction)) => {
+            let account = owner.account_number.map_or_else(|| owner.account_id.to_string(), |n| n.to_string());
+            let mut text =
+                Msg::LookupDetails.fill(&[transaction, &transaction.id, &owner.name, &owner.email, &account]);
+            if let Some(original_id) = transaction.reverses() {
+                text.push_str(&Msg::LookupReverses.fill(&[&original_id]));
+            }
+            if let Some(reversal_id) = &transaction.reversed_by {
+                text.push_str(&Msg::LookupReversedBy.fill(&[reversal_id]));
+            }
+            if let Some(reason) = &transaction.reversal_reason {
+                text.push_str(&Msg::LookupReason.fill(&[reason]));
+            }
+            renderer.success(&text, json!({ "transaction": transaction, "owner": owner }));
+        }
+        None => renderer.failure(&BankError::TransactionNotFound(transaction_id)),
+    }
+
+    Ok(())
+}
+
+/// Shows the audit facts of the bank, a customer, a customer's account or
+/// a transaction (staff only)
+pub fn view_audit_record(bank: &SharedBank, session: Option<&Session>, renderer: &dyn Renderer) -> io::Result<()> {
+    if authorize(session, STAFF, renderer).is_none() {
+        return Ok(());
+    }
+
+    renderer.section(Msg::AuditRecord.text());
+    renderer.chrome(Msg::AuditRecords.text());
+
+    match read_input(Msg::EnterAction.text())?.as_str() {
+        "1" => renderer.report(&*bank.read()),
+        choice @ ("2" | "3") => {
+            let Some(customer_id) = read_customer(bank, Msg::EnterCustomer.text(), renderer)? else {
+                return Ok(());
+            };
+            let bank = bank.read();
+            let customer = match bank.get_customer(&customer_id) {
+                Ok(customer) => customer,
+                Err(e) => {
+                    renderer.failure(&e);
+                    return Ok(());
+                }
+            };
+            match (choice, customer.get_account()) {
+                ("2", _) => renderer.report(customer),
+                (_, Ok(account)) => renderer.report(account),
+                (_, Err(e)) => renderer.failure(&e),
+            }
+        }
+        "4" => {
+            let transaction_id = TransactionId::from(read_input(Msg::EnterTransactionId.text())?);
+            match bank.read().find_transaction(&transaction_id) {
+                Some((_, transaction)) => renderer.report(transaction),
+                None => renderer.failure(&BankError::TransactionNotFound(transaction_id)),
+            }
+        }
+        _ => renderer.failure(&Msg::InvalidChoice),
+    }
+
+    Ok(())
+}
+
+/// Pages through every account's transactions, oldest first, optionally
+/// limited to a period (staff only)
+pub fn view_ledger(bank: &SharedBank, session: Option<&Session>, renderer: &dyn Renderer) -> io::Result<()> {
+    if authorize(session, STAFF, renderer).is_none() {
+        return Ok(());
+    }
+
+    renderer.section(Msg::Ledger.text());
+    renderer.chrome(Msg::LeaveDateBlank.text());
+
+    let from = read_checked(Msg::FromDate.text(), renderer, |input| unless_blank(input, parse_day))?;
+    let to = read_checked(Msg::ToDate.text(), renderer, |input| unless_blank(input, parse_last_day))?;
+    let from = from.map(|day| day.and_hms_opt(0, 0, 0).unwrap().and_utc());
+
+    browse_pages(renderer, |offset| {
+        let bank = bank.read();
+        let page = Page::from_items(bank.all_transactions().between(from, to), offset, PAGE_SIZE);
+        let text = if page.total == 0 {
+            Msg::NoTransactionsInPeriod.text().to_string()
+        } else {
+            // Deleted customers are named too: the ledger keeps their history
+            let table = ledger_table(&page.items, |entry| match bank.find_customer_by_account_id(entry.account_id) {
+                Some(customer) => match customer.account.as_ref().and_then(|account| account.number) {
+                    Some(number) => format!("{} ({})", customer.name, number),
+                    None => customer.name.clone(),
+                },
+                None => entry.account_id.short().to_string(),
+            });
+            Msg::LedgerPage.fill(&[&page.number(), &page.count(), &table.render()])
+        };
+        renderer.success(&text, json!(page));
+        (page.has_prev(), page.has_next())
+    })
+}
+
+/// Searches a customer's transactions by date, amount, type, counterparty
+/// and tag
+///
+/// Every criterion is optional; a blank answer leaves it out. Customers
+/// only search their own account.
+pub fn search_transactions(
+    bank: &SharedBank,
+    session: Option<&Session>,
+    renderer: &dyn Renderer,
+) -> io::Result<()> {
+    let session = match authorize(session, ANY_ROLE, renderer) {
+        Some(session) => session,
+        None => return Ok(()),
+    };
+
+    renderer.section(Msg::SearchTransactions.text());
+
+    let customer_id = match session.target_customer(bank, Msg::EnterCustomer.text(), renderer)? {
+        Some(id) => id,
+        None => return Ok(()),
+    };
+    renderer.chrome(Msg::LeaveCriterionBlank.text());
+
+    let from = read_checked(Msg::FromDate.text(), renderer, |input| unless_blank(input, parse_day))?;
+    let to = read_checked(Msg::ToDate.text(), renderer, |input| unless_blank(input, parse_last_day))?;
+    let min = read_checked(Msg::MinimumAmount.text(), renderer, |input| unless_blank(input, parse_amount))?;
+    let max = read_checked(Msg::MaximumAmount.text(), renderer, |input| unless_blank(input, parse_amount))?;
+    let kind = read_checked(Msg::TransactionTypeFilter.text(), renderer, |input| unless_blank(input, parse_kind))?;
+    let counterparty = read_input(Msg::CounterpartyFilter.text())?;
+    let tag = read_input(Msg::TagFilter.text())?;
+
+    let mut filter = TransactionFilter::new();
+    if let Some(from) = from {
+        filter = filter.from(from.and_hms_opt(0, 0, 0).unwrap().and_utc());
+    }
+    if let Some(to) = to {
+        filter = filter.to(to);
+    }
+    if let Some(min) = min {
+        filter = filter.min_amount(min);
+    }
+    if let Some(max) = max {
+        filter = filter.max_amount(max);
+    }
+    if let Some(kind) = kind {
+        filter = filter.kind(kind);
+    }
+    if !counterparty.is_empty() {
+        filter = filter.counterparty(counterparty);
+    }
+    if !tag.is_empty() {
+        filter = filter.tag(&tag);
+    }
+
+    let bank = bank.read();
+    let account = match bank.get_customer(&customer_id).and_then(|c| c.get_account()) {
+        Ok(account) => account,
+        Err(e) => {
+            renderer.failure(&e);
+            return Ok(());
+        }
+    };
+
+    let matches = account.filter_transactions(&filter);
+    let text = if matches.is_empty() {
+        Msg::NoMatchingTransactions.text().to_string()
+    } else {
+        let mut text = Msg::FoundTransactions.fill(&[&matches.len()]);
+        for transaction in &matches {
+            text.push_str(&format!("  • {}\n", transaction));
+        }
+        text
+    };
+    renderer.success(&text, json!(matches));
+
+    Ok(())
+}
+
+/// Parses a `YYYY-MM-DD` search date
+fn parse_day(input: &str) -> BankResult<NaiveDate> {
+    NaiveDate::parse_from_str(input, "%Y-%m-%d")
+        .map_err(|_| BankError::InvalidInput(Msg::InvalidDateValue.fill(&[&input])))
+}
+
+/// Parses the last day of a search, which is inclusive for the user, into
+/// the midnight after it
+fn parse_last_day(input: &str) -> BankResult<DateTime<Utc>> {
+    end_of_day(parse_day(input)?)
+}
 
-    let customer_id = read_input("Enter customer ID: ")?;
+/// Parses a search amount
+fn parse_amount(input: &str) -> BankResult<Money> {
+    input.parse().map_err(|_| BankError::InvalidInput(Msg::InvalidAmountValue.fill(&[&input])))
+}
 
-    let bank = bank.lock().unwrap();
+/// Parses a transaction type name
+fn parse_kind(input: &str) -> BankResult<TransactionKind> {
+    input.parse().map_err(BankError::InvalidInput)
+}
 
-    match bank.get_customer(&customer_id) {
-        Ok(customer) => {
-            if let Some(account) = &customer.account {
-                let history = account.get_transaction_history();
+/// Views the bank statistics report (admins only)
+///
+/// Demonstrates: Rendering one serializable struct as text and as JSON
+pub fn view_bank_statistics(
+    bank: &SharedBank,
+    session: Option<&Session>,
+    renderer: &dyn Renderer,
+) -> io::Result<()> {
+    if authorize(session, ADMIN_ONLY, renderer).is_none() {
+        return Ok(());
+    }
 
-                if history.is_empty() {
-                    println!("\n📭 No transactions yet.\n");
-                } else {
-                    println!("\n📜 Transaction History for {}:", customer.name);
-                    println!("─────────────────────────────────────────");
+    renderer.section(Msg::BankStatistics.text());
 
-                    // Demonstrates: Iterator with enumerate
-                    for (idx, transaction) in history.iter().enumerate() {
-                        println!("{}. {}", idx + 1, transaction);
-                    }
-                    println!();
+    loop {
+        renderer.chrome(Msg::ReportActions.text());
+        match read_input(Msg::EnterAction.text())?.as_str() {
+            "" => return Ok(()),
+            "1" => {
+                let report = bank.read().report();
+                renderer.success(&format!("\n{}", report), json!(report));
+            }
+            "2" => {
+                let count = read_number(Msg::HowManyTen.text(), 10, renderer)?;
+                let largest = bank.read().largest_transactions(count as usize);
+                let mut text = Msg::LargestTransactions.text().to_string();
+                if largest.is_empty() {
+                    text.push_str(Msg::NoTransactionsLine.text());
+                }
+                for (idx, ranked) in largest.iter().enumerate() {
+                    let number = ranked.account_number.map(|n| n.to_string()).unwrap_or_default();
+                    text.push_str(&format!("\n  {}. {} {} - {}", idx + 1, ranked.name, number, ranked.transaction));
                 }
-            } else {
-                println!("\n❌ Customer has no account\n");
+                renderer.success(&text, json!(largest));
             }
+            "3" => {
+                let count = read_number(Msg::HowManyTen.text(), 10, renderer)?;
+                let days = read_number(Msg::OverHowManyDays.text(), 30, renderer)?;
+                let busiest = bank.read().most_active_accounts(count as usize, Duration::days(days.into()));
+                let mut text = Msg::MostActiveAccounts.fill(&[&days]);
+                if busiest.is_empty() {
+                    text.push_str(Msg::NoTransactionsInPeriodLine.text());
+                }
+                for (idx, activity) in busiest.iter().enumerate() {
+                    let number = activity.account_number.map(|n| n.to_string()).unwrap_or_default();
+                    text.push_str(&Msg::ActiveAccountLine.fill(&[
+                        &(idx + 1),
+                        &activity.name,
+                        &number,
+                        &activity.transactions,
+                    ]));
+                }
+                renderer.success(&text, json!(busiest));
+            }
+            "4" => {
+                let policy = bank.read().dormancy_policy();
+                renderer.chrome(&format!("📋 {}", policy));
+                let default = if policy.is_enabled() { policy.inactive_days } else { 90 };
+                let prompt = Msg::InactiveForDays.fill(&[&default]);
+                let days = read_number(&prompt, default, renderer)?;
+                let dormant = bank.read().dormant_accounts(days);
+                let mut text = Msg::AccountsInactiveFor.fill(&[&days]);
+                if dormant.is_empty() {
+                    text.push_str(Msg::NoneLine.text());
+                }
+                for account in &dormant {
+                    let number = account.account_number.map(|n| n.to_string()).unwrap_or_default();
+                    text.push_str(&Msg::DormantAccountLine.fill(&[
+                        &account.name,
+                        &number,
+                        &account.last_activity.format("%Y-%m-%d"),
+                        &account.inactive_days,
+                    ]));
+                }
+                renderer.success(&text, json!(dormant));
+            }
+            "5" => show_trial_balance(bank, renderer)?,
+            "6" => show_income_statement(bank, renderer)?,
+            "7" => manage_chart(bank, renderer)?,
+            _ => renderer.failure(&Msg::InvalidChoice),
         }
-        Err(e) => println!("\n❌ Error: {}\n", e),
     }
+}
+
+/// Reads a whole number, `default` when left blank
+fn read_number(prompt: &str, default: u32, renderer: &dyn Renderer) -> io::Result<u32> {
+    let number = read_checked(prompt, renderer, |input| {
+        unless_blank(input, |input| {
+            input.parse::<u32>().map_err(|_| BankError::InvalidInput(Msg::NotWholeNumber.fill(&[&input])))
+        })
+    })?;
+    Ok(number.unwrap_or(default))
+}
+
+/// Charts the money flowing into and out of the bank per day or week
+/// (admins only)
+pub fn view_cash_flow(bank: &SharedBank, session: Option<&Session>, renderer: &dyn Renderer) -> io::Result<()> {
+    if authorize(session, ADMIN_ONLY, renderer).is_none() {
+        return Ok(());
+    }
+
+    renderer.section(Msg::CashFlow.text());
+
+    let period = read_checked(Msg::PerDayOrWeek.text(), renderer, |input| match input.to_lowercase().as_str() {
+        "" | "d" | "day" => Ok(CashFlowPeriod::Daily),
+        "w" | "week" => Ok(CashFlowPeriod::Weekly),
+        other => Err(BankError::InvalidInput(Msg::NeitherDayNorWeek.fill(&[&other]))),
+    })?;
+    renderer.chrome(Msg::LeaveDateBlank.text());
+    let from = read_checked(Msg::FromDate.text(), renderer, |input| unless_blank(input, parse_day))?;
+    let to = read_checked(Msg::ToDate.text(), renderer, |input| unless_blank(input, parse_last_day))?;
+    let from = from.map(|day| day.and_hms_opt(0, 0, 0).unwrap().and_utc());
+
+    let report = bank.read().cash_flow(period, from, to);
+    renderer.success(&format!("\n{}", report), json!(report));
 
     Ok(())
 }
 
-/// Views bank statistics
+/// Re-derives every balance and checks transfer records
+pub fn run_audit(
+    bank: &SharedBank,
+    session: Option<&Session>,
+    renderer: &dyn Renderer,
+) -> io::Result<()> {
+    if authorize(session, ADMIN_ONLY, renderer).is_none() {
+        return Ok(());
+    }
+
+    renderer.section(Msg::IntegrityAudit.text());
+
+    let report = bank.read().verify_integrity();
+    renderer.success(&format!("\n{}\n", report), json!(report));
+
+    Ok(())
+}
+
+/// Generates an account statement for a date range
 ///
-/// Demonstrates: Complex iterator operations for data analysis
-pub fn view_bank_statistics(bank: &Arc<Mutex<Bank>>) -> io::Result<()> {
-    println!("\n--- Bank Statistics ---");
+/// The statement is printed, or written to a text file if one is given.
+/// Customers only get statements for their own account.
+pub fn generate_statement(
+    bank: &SharedBank,
+    session: Option<&Session>,
+    renderer: &dyn Renderer,
+) -> io::Result<()> {
+    let session = match authorize(session, ANY_ROLE, renderer) {
+        Some(session) => session,
+        None => return Ok(()),
+    };
 
-    let bank = bank.lock().unwrap();
+    renderer.section(Msg::AccountStatement.text());
 
-    println!("\n{}", bank.summary());
+    let customer_id = match session.target_customer(bank, Msg::EnterCustomer.text(), renderer)? {
+        Some(id) => id,
+        None => return Ok(()),
+    };
 
-    // Calculate customers with accounts
-    let customers_with_accounts = bank
-        .list_customers()
-        .iter()
-        .filter(|c| c.account.is_some())
-        .count();
+    // Default period: the current month so far
+    let today = Utc::now().date_naive();
+    let month_start = today.with_day(1).unwrap_or(today);
 
-    println!("Customers with Accounts: {}", customers_with_accounts);
-    println!(
-        "Customers without Accounts: {}",
-        bank.list_customers().len() - customers_with_accounts
-    );
+    let from = match read_date(Msg::EnterStartDate.text(), month_start, renderer)? {
+        Some(date) => date,
+        None => return Ok(()),
+    };
+    let to = match read_end_date(Msg::EnterEndDate.text(), today, renderer)? {
+        Some(end) => end,
+        None => return Ok(()),
+    };
+    let output = read_input(Msg::EnterOutputFile.text())?;
+
+    // The read lock is released before the statement is rendered or written
+    let statement = match bank.statement(&customer_id, from.and_hms_opt(0, 0, 0).unwrap().and_utc(), to) {
+        Ok(statement) => statement,
+        Err(e) => {
+            renderer.failure(&e);
+            return Ok(());
+        }
+    };
 
-    // Find richest customer using iterator operations
-    // Demonstrates: filter_map, max_by with partial_cmp
-    if let Some(richest) = bank
-        .list_customers()
-        .iter()
-        .filter_map(|c| c.account.as_ref().map(|a| (c, a)))
-        .max_by(|(_, a1), (_, a2)| a1.balance.partial_cmp(&a2.balance).unwrap())
-    {
-        println!("💎 Richest Customer: {} (${:.2})", richest.0.name, richest.1.balance);
+    if output.is_empty() {
+        renderer.success(&format!("\n{}\n", statement), json!(statement));
+    } else {
+        match fs::write(&output, format!("{}\n", statement)) {
+            Ok(()) => renderer.success(
+                &Msg::StatementWritten.fill(&[&output]),
+                json!({ "file": output }),
+            ),
+            Err(e) => renderer.failure(&e),
+        }
     }
 
-    println!();
+    Ok(())
+}
+
+/// Shows how a customer's spending splits across categories
+///
+/// Customers only see their own breakdown.
+pub fn view_category_breakdown(
+    bank: &SharedBank,
+    session: Option<&Session>,
+    renderer: &dyn Renderer,
+) -> io::Result<()> {
+    let session = match authorize(session, ANY_ROLE, renderer) {
+        Some(session) => session,
+        None => return Ok(()),
+    };
+
+    renderer.section(Msg::SpendingByCategory.text());
+
+    let customer_id = match session.target_customer(bank, Msg::EnterCustomer.text(), renderer)? {
+        Some(id) => id,
+        None => return Ok(()),
+    };
+
+    let bank = bank.read();
+    let breakdown = match bank.get_customer(&customer_id).and_then(|c| c.get_account()) {
+        Ok(account) => account.category_breakdown(),
+        Err(e) => {
+            renderer.failure(&e);
+            return Ok(());
+        }
+    };
+
+    let text = if breakdown.is_empty() {
+        Msg::NoSpendingYet.text().to_string()
+    } else {
+        let total: Money = breakdown.iter().map(|c| c.amount).sum();
+        let mut text = Msg::SpendingByCategoryHeading.text().to_string();
+        for category in &breakdown {
+            // Share of the total, in whole percent
+            let share = category.amount.cents() * 100 / total.cents().max(1);
+            text.push_str(&format!(
+                "  {:<20} {:>13}  {:>3}%  {}\n",
+                category.category.as_deref().unwrap_or(Msg::Uncategorized.text()),
+                category.amount.display(),
+                share,
+                Msg::TransactionCount.fill(&[&category.transactions])
+            ));
+        }
+        text.push_str(&format!(
+            "─────────────────────────────────────────\n  {:<20} {:>13}\n",
+            Msg::TotalLabel.text(),
+            total.display()
+        ));
+        text
+    };
+    renderer.success(&text, json!(breakdown));
 
     Ok(())
 }
+
+/// Shows a customer's monthly summary, optionally exporting it as CSV
+///
+/// Customers only get summaries for their own account.
+pub fn view_monthly_summary(
+    bank: &SharedBank,
+    session: Option<&Session>,
+    renderer: &dyn Renderer,
+) -> io::Result<()> {
+    let session = match authorize(session, ANY_ROLE, renderer) {
+        Some(session) => session,
+        None => return Ok(()),
+    };
+
+    renderer.section(Msg::MonthlySummary.text());
+
+    let customer_id = match session.target_customer(bank, Msg::EnterCustomer.text(), renderer)? {
+        Some(id) => id,
+        None => return Ok(()),
+    };
+
+    // Default: the current month so far
+    let today = Utc::now().date_naive();
+    let prompt = Msg::EnterMonth.fill(&[&today.format("%Y-%m")]);
+    let (year, month) = read_checked(&prompt, renderer, |input| match input {
+        "" => Ok((today.year(), today.month())),
+        input => parse_month(input),
+    })?;
+    let output = read_input(Msg::EnterCsvExport.text())?;
+
+    let bank = bank.read();
+
+    let summary = match bank.monthly_summary(&customer_id, year, month) {
+        Ok(summary) => summary,
+        Err(e) => {
+            renderer.failure(&e);
+            return Ok(());
+        }
+    };
+
+    if output.is_empty() {
+        renderer.success(&format!("\n{}\n", summary), json!(summary));
+    } else {
+        match persistence::export_monthly_summaries_csv(std::slice::from_ref(&summary), &output) {
+            Ok(_) => renderer.success(
+                &Msg::SummaryWritten.fill(&[&format!("{}-{:02}", year, month), &output]),
+                json!({ "file": output }),
+            ),
+            Err(e) => renderer.failure(&e),
+        }
+    }
+
+    Ok(())
+}
+
+/// Prompts for the last day of a period, using `default` when the input is
+/// blank. The day is inclusive for the user, so this is the midnight after it.
+///
+/// # Returns
+/// * `Ok(None)` - If the input was not a valid date (error already shown)
+pub(super) fn read_end_date(
+    prompt: &str,
+    default: NaiveDate,
+    renderer: &dyn Renderer,
+) -> io::Result<Option<DateTime<Utc>>> {
+    let day = match read_date(prompt, default, renderer)? {
+        Some(day) => day,
+        None => return Ok(None),
+    };
+    match end_of_day(day) {
+        Ok(end) => Ok(Some(end)),
+        Err(e) => {
+            renderer.failure(&e);
+            Ok(None)
+        }
+    }
+}
+
+/// Prompts for a date, using `default` when the input is blank
+///
+/// # Returns
+/// * `Ok(None)` - If the input was not a valid date (error already shown)
+pub(super) fn read_date(
+    prompt: &str,
+    default: NaiveDate,
+    renderer: &dyn Renderer,
+) -> io::Result<Option<NaiveDate>> {
+    let input = read_input(&format!("{} [{}]: ", prompt, default))?;
+    if input.is_empty() {
+        return Ok(Some(default));
+    }
+
+    match NaiveDate::parse_from_str(&input, "%Y-%m-%d") {
+        Ok(date) => Ok(Some(date)),
+        Err(_) => {
+            renderer.failure(&Msg::InvalidDate);
+            Ok(None)
+        }
+    }
+}
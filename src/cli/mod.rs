@@ -1,40 +1,180 @@
 //! CLI module - user interface and interaction logic
 //!
-//! Demonstrates: Module organization, Arc/Mutex for thread-safe shared state
+//! Demonstrates: Module organization, Arc/RwLock for thread-safe shared state,
+//! catching panics so one failed operation does not end the session
 
 use std::io;
-use std::sync::{Arc, Mutex};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Arc;
 
-use crate::bank::Bank;
-use crate::persistence;
-use crate::traits::Summarizable;
+use serde_json::json;
+
+use crate::bank::{Bank, SharedBank};
+use crate::errors::BankResult;
+use crate::persistence::Storage;
 
 // Submodules
+pub mod args;
+pub mod i18n;
+#[cfg(feature = "arrow-menu")]
+pub mod picker;
+pub mod script;
+pub mod shell;
+pub mod shutdown;
+pub mod startup;
+pub mod table;
+pub mod theme;
+mod commands;
+mod render;
 mod utils;
 mod customer_ops;
 mod account_ops;
 mod info_ops;
+mod schedule_ops;
+mod data_ops;
+mod auth_ops;
+mod payee_ops;
+mod webhook_ops;
+mod clearing_ops;
+mod hold_ops;
+mod cheque_ops;
+mod card_ops;
+mod bill_ops;
+mod envelope_ops;
+mod budget_ops;
+mod term_ops;
+mod fraud_ops;
+mod vault_ops;
+mod books_ops;
+mod session;
 
 // Import all operations
 use customer_ops::*;
 use account_ops::*;
 use info_ops::*;
+use schedule_ops::*;
+use data_ops::{export_transactions, import_customers, import_transactions, process_batch, restore_backup};
+use auth_ops::{add_staff_user, change_pin, login};
+use payee_ops::manage_payees;
+use webhook_ops::manage_webhooks;
+use clearing_ops::manage_clearing;
+use hold_ops::{manage_holds, process_expired_holds};
+use cheque_ops::{deposit_cheque, manage_cheques, process_due_cheques};
+use card_ops::{card_purchase, manage_cards};
+use bill_ops::{manage_merchants, pay_bills, process_due_autopays};
+use envelope_ops::manage_envelopes;
+use budget_ops::manage_budgets;
+use term_ops::{manage_term_deposits, process_due_maturities};
+use fraud_ops::review_flagged;
+use vault_ops::manage_vault;
+
+pub use commands::{
+    add_notifiers, load_or_create, open_command_storage, run_command, ALERT_FILE_ENV, ALERT_WEBHOOK_ENV,
+    PASSPHRASE_ENV,
+};
+pub use data_ops::open_storage;
+pub use render::{JsonRenderer, Renderer, TextRenderer};
+pub use session::Session;
+pub use startup::{StartupArgs, DATA_FILE_ENV};
+pub use utils::{is_cancelled, isolate, parse_amount, set_verbose, Cancelled, CANCEL_INPUT, MAX_ATTEMPTS};
+use i18n::Msg;
+use session::{authorize, ADMIN_ONLY};
 use utils::read_input;
 
+/// Main menu entries after the header: number, icon and label
+///
+/// Exit (0) is listed last. Icons that render narrow carry an extra space.
+const MENU: [(u8, &str, Msg); 56] = [
+    (1, "📝", Msg::RegisterCustomer),
+    (2, "💳", Msg::CreateAccountForCustomer),
+    (3, "💰", Msg::DepositMoney),
+    (4, "💸", Msg::WithdrawMoney),
+    (5, "🔄", Msg::TransferMoney),
+    (6, "📊", Msg::ViewAccountDetails),
+    (7, "📜", Msg::ViewTransactionHistory),
+    (8, "👥", Msg::ListAllCustomers),
+    (9, "🔍", Msg::SearchCustomers),
+    (10, "📈", Msg::ViewBankStatistics),
+    (11, "💾", Msg::SaveData),
+    (12, "📅", Msg::CreateScheduledTransfer),
+    (13, "🗓️ ", Msg::ListScheduledTransfers),
+    (14, "🚫", Msg::CancelScheduledTransfer),
+    (15, "⚙️ ", Msg::ToggleAutosave),
+    (16, "♻️ ", Msg::RestoreBackup),
+    (17, "📤", Msg::ExportTransactions),
+    (18, "📥", Msg::ImportTransactions),
+    (19, "🧾", Msg::GenerateStatement),
+    (20, "🔐", Msg::Login),
+    (21, "🔓", Msg::Logout),
+    (22, "🔑", Msg::ChangePin),
+    (23, "👔", Msg::AddStaffUser),
+    (24, "↩️ ", Msg::UndoLastOperation),
+    (25, "🚦", Msg::AccountLimits),
+    (26, "🧊", Msg::FreezeCloseAccount),
+    (27, "🗑️ ", Msg::DeactivateDeleteCustomer),
+    (28, "✏️ ", Msg::EditCustomer),
+    (29, "🔎", Msg::SearchTransactions),
+    (30, "📆", Msg::MonthlySummary),
+    (31, "🏷️ ", Msg::SpendingByCategory),
+    (32, "📦", Msg::ProcessBatchFile),
+    (33, "🩺", Msg::RunAudit),
+    (34, "📇", Msg::SavedPayees),
+    (35, "🔔", Msg::BalanceAlerts),
+    (36, "🪝", Msg::Webhooks),
+    (37, "🌍", Msg::TransferExternal),
+    (38, "🏛️ ", Msg::ClearingQueue),
+    (39, "⏸️ ", Msg::AccountHolds),
+    (40, "🧾", Msg::DepositCheque),
+    (41, "📬", Msg::OutstandingCheques),
+    (42, "💳", Msg::DebitCards),
+    (43, "🛒", Msg::CardPurchase),
+    (44, "🏢", Msg::Merchants),
+    (45, "🧾", Msg::PayBills),
+    (46, "🎯", Msg::SavingsGoals),
+    (47, "📊", Msg::Budgets),
+    (48, "🏦", Msg::TermDeposits),
+    (49, "🆔", Msg::LookupTransaction),
+    (50, "📒", Msg::Ledger),
+    (51, "📉", Msg::CashFlow),
+    (52, "🚨", Msg::FraudReview),
+    (53, "⏪", Msg::ReverseTransaction),
+    (54, "🏦", Msg::CashVault),
+    (55, "🆕", Msg::ImportCustomers),
+    (56, "🧾", Msg::AuditRecord),
+];
+
 /// The main CLI application
 ///
 /// Demonstrates:
-/// - Arc<Mutex<T>> for thread-safe shared state
+/// - Arc<RwLock<T>> (via SharedBank) for thread-safe shared state
 /// - Interior mutability pattern
 /// - https://doc.rust-lang.org/book/ch16-03-shared-state.html
 pub struct BankCLI {
     /// Thread-safe reference to the bank
     /// Arc: Atomic Reference Counted pointer for shared ownership
-    /// Mutex: Mutual exclusion for thread-safe interior mutability
-    bank: Arc<Mutex<Bank>>,
+    /// RwLock: many readers or one writer at a time
+    bank: SharedBank,
+
+    /// Backend used to load and save the bank
+    /// Demonstrates: Arc<dyn Trait> for runtime-selected behavior, shared
+    /// with the Ctrl+C handler
+    storage: Arc<dyn Storage>,
+
+    /// Save the whole bank after every successful change
+    /// When off, changes are journaled and saved on demand or at exit
+    autosave: bool,
 
-    /// Filename for data persistence
-    data_file: String,
+    /// Never save; changes last only until the program exits
+    read_only: bool,
+
+    /// How results are presented (decorated text or JSON)
+    renderer: Box<dyn Renderer>,
+
+    /// The logged-in user; every menu option except login and exit needs one
+    session: Option<Session>,
+
+    /// Choose operations with the arrow keys instead of typing numbers
+    arrow_menu: bool,
 }
 
 impl BankCLI {
@@ -42,98 +182,394 @@ impl BankCLI {
     ///
     /// # Arguments
     /// * `bank_name` - Name for the bank (used if creating new)
-    /// * `data_file` - Path to the persistence file
-    pub fn new(bank_name: String, data_file: String) -> Self {
+    /// * `storage` - Backend used to load and save bank data
+    pub fn new(bank_name: String, storage: Box<dyn Storage>) -> Self {
         // Try to load existing data, or create a new bank
-        let bank = persistence::load_bank(&data_file)
+        let mut bank = storage
+            .load_bank()
             .unwrap_or_else(|_| Bank::new(bank_name));
+        if let Some(archive) = storage.history_archive() {
+            bank.set_archive(archive);
+        }
+        bank.enable_journal();
+        // Number accounts from older data files; the events are journaled
+        // with the first change. Only accounts that exist get numbered, so
+        // this cannot fail.
+        let _ = bank.assign_account_numbers();
+        add_notifiers(&mut bank);
 
         Self {
-            bank: Arc::new(Mutex::new(bank)),
-            data_file,
+            bank: SharedBank::new(bank),
+            storage: Arc::from(storage),
+            autosave: false,
+            read_only: false,
+            renderer: Box::new(TextRenderer::default()),
+            session: None,
+            arrow_menu: false,
         }
     }
 
+    /// Replaces the output renderer, e.g. with `JsonRenderer` for `--json`
+    pub fn set_renderer(&mut self, renderer: Box<dyn Renderer>) {
+        self.renderer = renderer;
+    }
+
+    /// Turns autosave on or off
+    pub fn set_autosave(&mut self, enabled: bool) {
+        self.autosave = enabled;
+    }
+
+    /// Turns read-only mode on or off; pair it with a `ReadOnlyStorage`
+    /// so that backup restores are refused too
+    pub fn set_read_only(&mut self, enabled: bool) {
+        self.read_only = enabled;
+    }
+
+    /// Caps the transactions kept in memory per account (see
+    /// `Bank::set_history_cap`); leave it unset in read-only mode, where
+    /// nothing can be archived
+    pub fn set_history_cap(&mut self, cap: Option<usize>) -> BankResult<()> {
+        self.bank.write().set_history_cap(cap)
+    }
+
+    /// Turns the arrow-key menu on or off
+    ///
+    /// With it on, operations are picked from a list with the arrow keys
+    /// and typeahead, and `Esc` at any prompt cancels the operation. It
+    /// needs the `arrow-menu` feature and a terminal; without the feature
+    /// the numbered menu is kept.
+    pub fn set_arrow_menu(&mut self, enabled: bool) {
+        self.arrow_menu = enabled && cfg!(feature = "arrow-menu");
+        utils::set_raw_prompts(self.arrow_menu);
+    }
+
     /// Runs the main CLI loop
     ///
     /// Demonstrates: Loop control, pattern matching, error handling
+    ///
+    /// A panic inside one operation is reported and the menu shown again.
+    /// If the loop itself fails or panics, the bank is saved in full before
+    /// the error or panic is passed on, so no change is lost. Ctrl+C or
+    /// SIGTERM saves the bank and exits with status 130 (see `shutdown`).
     pub fn run(&mut self) -> io::Result<()> {
+        let _interrupt = shutdown::save_on_interrupt(&self.bank, &self.storage, self.read_only);
+        match panic::catch_unwind(AssertUnwindSafe(|| self.run_menu())) {
+            Ok(Ok(())) => Ok(()),
+            Ok(Err(e)) => {
+                self.emergency_save();
+                Err(e)
+            }
+            Err(payload) => {
+                self.emergency_save();
+                panic::resume_unwind(payload)
+            }
+        }
+    }
+
+    /// The menu loop behind `run`
+    fn run_menu(&mut self) -> io::Result<()> {
         self.print_header();
 
         // Display bank summary
         {
-            let bank = self.bank.lock().unwrap();
-            println!("{}\n", bank.summary());
+            let bank = self.bank.read();
+            self.renderer.meta(&format!("{}\n", utils::summarize(&*bank)));
+        }
+        if self.read_only {
+            self.renderer.chrome(Msg::ReadOnlyBanner.text());
         }
 
         loop {
-            self.display_menu();
-
-            let choice = read_input("Enter your choice: ")?;
-
-            // Demonstrates: Pattern matching with match expression
-            // https://doc.rust-lang.org/book/ch06-02-match.html
-            match choice.trim() {
-                "1" => register_customer(&self.bank)?,
-                "2" => create_account(&self.bank)?,
-                "3" => deposit_money(&self.bank)?,
-                "4" => withdraw_money(&self.bank)?,
-                "5" => transfer_money(&self.bank)?,
-                "6" => view_account_details(&self.bank)?,
-                "7" => view_transaction_history(&self.bank)?,
-                "8" => list_all_customers(&self.bank)?,
-                "9" => search_customers(&self.bank)?,
-                "10" => view_bank_statistics(&self.bank)?,
-                "11" => {
-                    self.save_data()?;
-                    println!("\n✅ Data saved successfully!");
-                }
-                "0" => {
-                    self.save_data()?;
-                    println!("\n👋 Thank you for using Rust Banking System!");
-                    println!("💾 Data saved. Goodbye!\n");
+            // Execute any standing orders that fell due while idle
+            process_due_schedules(&self.bank, self.renderer.as_ref());
+            process_expired_holds(&self.bank, self.renderer.as_ref());
+            process_due_cheques(&self.bank, self.renderer.as_ref());
+            process_due_autopays(&self.bank, self.renderer.as_ref());
+            process_due_maturities(&self.bank, self.renderer.as_ref());
+            process_due_policies(&self.bank, self.renderer.as_ref());
+
+            match self.read_menu_choice().and_then(|choice| self.handle_isolated(&choice)) {
+                Ok(true) => {}
+                Ok(false) => break,
+                // `:q` or Esc at a prompt: drop the operation and show the menu again
+                Err(e) if is_cancelled(&e) => self.renderer.failure(&Msg::Cancelled),
+                // Input ran out (piped input ended): record what was done and stop
+                Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => {
+                    self.persist_changes();
                     break;
                 }
-                _ => println!("\n❌ Invalid choice. Please try again.\n"),
+                Err(e) => return Err(e),
+            }
+
+            self.persist_changes();
+        }
+
+        Ok(())
+    }
+
+    /// Shows the menu and reads the chosen operation's number
+    fn read_menu_choice(&self) -> io::Result<String> {
+        #[cfg(feature = "arrow-menu")]
+        if self.arrow_menu {
+            self.display_menu_title();
+            let entries = MENU
+                .iter()
+                .map(|(number, icon, item)| (*number, format!("{} {}", icon, self.menu_label(*item))))
+                .chain([(0, format!("🚪 {}", Msg::Exit.text()))])
+                .collect();
+            return picker::pick(entries).map(|number| number.to_string());
+        }
+
+        self.display_menu();
+        read_input(Msg::EnterYourChoice.text())
+    }
+
+    /// Runs the operation numbered `choice`, reporting a panic inside it
+    /// as a failure instead of ending the session
+    fn handle_isolated(&mut self, choice: &str) -> io::Result<bool> {
+        utils::isolate(|| self.handle_choice(choice)).unwrap_or_else(|message| {
+            self.renderer.failure(&Msg::OperationCrashed.fill(&[&message]));
+            Ok(true)
+        })
+    }
+
+    /// Runs the operation numbered `choice`
+    ///
+    /// # Returns
+    /// * `Ok(false)` - If the user chose to exit
+    fn handle_choice(&mut self, choice: &str) -> io::Result<bool> {
+        // Demonstrates: Pattern matching with match expression
+        // https://doc.rust-lang.org/book/ch06-02-match.html
+        match choice.trim() {
+            "1" => register_customer(&self.bank, self.session.as_ref(), self.renderer.as_ref())?,
+            "2" => create_account(&self.bank, self.session.as_ref(), self.renderer.as_ref())?,
+            "3" => deposit_money(&self.bank, self.session.as_ref(), self.renderer.as_ref())?,
+            "4" => withdraw_money(&self.bank, self.session.as_ref(), self.renderer.as_ref())?,
+            "5" => transfer_money(&self.bank, self.session.as_ref(), self.renderer.as_ref())?,
+            "6" => view_account_details(&self.bank, self.session.as_ref(), self.renderer.as_ref())?,
+            "7" => view_transaction_history(&self.bank, self.session.as_ref(), self.renderer.as_ref())?,
+            "8" => list_all_customers(&self.bank, self.session.as_ref(), self.renderer.as_ref())?,
+            "9" => search_customers(&self.bank, self.session.as_ref(), self.renderer.as_ref())?,
+            "10" => view_bank_statistics(&self.bank, self.session.as_ref(), self.renderer.as_ref())?,
+            "11" if self.read_only => self.renderer.failure(&Msg::ReadOnlyCannotSave),
+            "11" if !self.bank.read().has_unsaved_changes() => {
+                self.renderer.success(Msg::NothingToSave.text(), json!({ "saved": null }));
+            }
+            "11" => {
+                self.save_data()?;
+                self.renderer.success(
+                    Msg::DataSaved.text(),
+                    json!({ "saved": self.storage.location() }),
+                );
+            }
+            "12" => create_schedule(&self.bank, self.session.as_ref(), self.renderer.as_ref())?,
+            "13" => list_schedules(&self.bank, self.session.as_ref(), self.renderer.as_ref())?,
+            "14" => cancel_schedule(&self.bank, self.session.as_ref(), self.renderer.as_ref())?,
+            "15" => {
+                if authorize(self.session.as_ref(), ADMIN_ONLY, self.renderer.as_ref()).is_some() {
+                    self.autosave = !self.autosave;
+                    let state = if self.autosave { Msg::On } else { Msg::Off };
+                    self.renderer.success(
+                        &Msg::AutosaveNow.fill(&[&state]),
+                        json!({ "autosave": self.autosave }),
+                    );
+                }
             }
+            "16" => restore_backup(
+                &self.bank,
+                self.storage.as_ref(),
+                self.session.as_ref(),
+                self.renderer.as_ref(),
+            )?,
+            "17" => export_transactions(&self.bank, self.session.as_ref(), self.renderer.as_ref())?,
+            "18" => import_transactions(&self.bank, self.session.as_ref(), self.renderer.as_ref())?,
+            "19" => generate_statement(&self.bank, self.session.as_ref(), self.renderer.as_ref())?,
+            "20" => {
+                if let Some(session) = login(&self.bank, self.renderer.as_ref())? {
+                    self.session = Some(session);
+                }
+            }
+            "21" => match self.session.take() {
+                Some(session) => self
+                    .renderer
+                    .success(Msg::LoggedOut.text(), json!({ "user": session.user_id })),
+                None => self.renderer.failure(&Msg::NobodyLoggedIn),
+            },
+            "22" => change_pin(&self.bank, self.session.as_ref(), self.renderer.as_ref())?,
+            "23" => add_staff_user(&self.bank, self.session.as_ref(), self.renderer.as_ref())?,
+            "24" => undo_last_operation(&self.bank, self.session.as_ref(), self.renderer.as_ref())?,
+            "25" => manage_limits(&self.bank, self.session.as_ref(), self.renderer.as_ref())?,
+            "26" => manage_account_status(&self.bank, self.session.as_ref(), self.renderer.as_ref())?,
+            "27" => manage_customer_status(&self.bank, self.session.as_ref(), self.renderer.as_ref())?,
+            "28" => edit_customer(&self.bank, self.session.as_ref(), self.renderer.as_ref())?,
+            "29" => search_transactions(&self.bank, self.session.as_ref(), self.renderer.as_ref())?,
+            "30" => view_monthly_summary(&self.bank, self.session.as_ref(), self.renderer.as_ref())?,
+            "31" => view_category_breakdown(&self.bank, self.session.as_ref(), self.renderer.as_ref())?,
+            "32" => process_batch(&self.bank, self.session.as_ref(), self.renderer.as_ref())?,
+            "33" => run_audit(&self.bank, self.session.as_ref(), self.renderer.as_ref())?,
+            "34" => manage_payees(&self.bank, self.session.as_ref(), self.renderer.as_ref())?,
+            "35" => manage_alerts(&self.bank, self.session.as_ref(), self.renderer.as_ref())?,
+            "36" => manage_webhooks(&self.bank, self.session.as_ref(), self.renderer.as_ref())?,
+            "37" => transfer_external(&self.bank, self.session.as_ref(), self.renderer.as_ref())?,
+            "38" => manage_clearing(&self.bank, self.session.as_ref(), self.renderer.as_ref())?,
+            "39" => manage_holds(&self.bank, self.session.as_ref(), self.renderer.as_ref())?,
+            "40" => deposit_cheque(&self.bank, self.session.as_ref(), self.renderer.as_ref())?,
+            "41" => manage_cheques(&self.bank, self.session.as_ref(), self.renderer.as_ref())?,
+            "42" => manage_cards(&self.bank, self.session.as_ref(), self.renderer.as_ref())?,
+            "43" => card_purchase(&self.bank, self.session.as_ref(), self.renderer.as_ref())?,
+            "44" => manage_merchants(&self.bank, self.session.as_ref(), self.renderer.as_ref())?,
+            "45" => pay_bills(&self.bank, self.session.as_ref(), self.renderer.as_ref())?,
+            "46" => manage_envelopes(&self.bank, self.session.as_ref(), self.renderer.as_ref())?,
+            "47" => manage_budgets(&self.bank, self.session.as_ref(), self.renderer.as_ref())?,
+            "48" => manage_term_deposits(&self.bank, self.session.as_ref(), self.renderer.as_ref())?,
+            "49" => lookup_transaction(&self.bank, self.session.as_ref(), self.renderer.as_ref())?,
+            "50" => view_ledger(&self.bank, self.session.as_ref(), self.renderer.as_ref())?,
+            "51" => view_cash_flow(&self.bank, self.session.as_ref(), self.renderer.as_ref())?,
+            "52" => review_flagged(&self.bank, self.session.as_ref(), self.renderer.as_ref())?,
+            "53" => reverse_transaction(&self.bank, self.session.as_ref(), self.renderer.as_ref())?,
+            "54" => manage_vault(&self.bank, self.session.as_ref(), self.renderer.as_ref())?,
+            "55" => import_customers(&self.bank, self.session.as_ref(), self.renderer.as_ref())?,
+            "56" => view_audit_record(&self.bank, self.session.as_ref(), self.renderer.as_ref())?,
+            "0" => {
+                self.exit()?;
+                return Ok(false);
+            }
+            _ => self.renderer.failure(&Msg::InvalidChoiceTryAgain),
         }
 
+        Ok(true)
+    }
+
+    /// Says goodbye, first offering to save any unsaved changes
+    ///
+    /// Declining leaves the snapshot as it was; changes already appended
+    /// to the journal are replayed when the bank is next loaded. `:q` at
+    /// the question goes back to the menu.
+    fn exit(&self) -> io::Result<()> {
+        let unsaved = self.bank.read().unsaved_changes();
+        if self.read_only {
+            if unsaved > 0 {
+                self.renderer.chrome(&Msg::UnsavedChangesLost.fill(&[&unsaved]));
+            }
+            self.renderer.chrome(Msg::ThankYou.text());
+            self.renderer.chrome(Msg::ReadOnlyGoodbye.text());
+            return Ok(());
+        }
+
+        if unsaved > 0 {
+            let answer = read_input(&Msg::SaveBeforeExit.fill(&[&unsaved]))?;
+            if matches!(answer.to_lowercase().as_str(), "n" | "no") {
+                self.renderer.chrome(Msg::ThankYou.text());
+                self.renderer.chrome(Msg::ExitWithoutSaving.text());
+                return Ok(());
+            }
+            self.save_data()?;
+        }
+        self.renderer.chrome(Msg::ThankYou.text());
+        self.renderer.chrome(Msg::SavedGoodbye.text());
         Ok(())
     }
 
     /// Prints the application header
     fn print_header(&self) {
-        println!("\n╔═══════════════════════════════════════════╗");
-        println!("║   🏦  RUST BANKING SYSTEM CLI v1.0  🏦   ║");
-        println!("╚═══════════════════════════════════════════╝\n");
+        self.renderer.chrome("\n╔═══════════════════════════════════════════╗");
+        self.renderer.chrome("║   🏦  RUST BANKING SYSTEM CLI v1.0  🏦   ║");
+        self.renderer.chrome("╚═══════════════════════════════════════════╝\n");
     }
 
     /// Displays the main menu
     fn display_menu(&self) {
-        println!("═══════════════════════════════════════════");
-        println!("                MAIN MENU");
-        println!("═══════════════════════════════════════════");
-        println!("  1. 📝 Register New Customer");
-        println!("  2. 💳 Create Account for Customer");
-        println!("  3. 💰 Deposit Money");
-        println!("  4. 💸 Withdraw Money");
-        println!("  5. 🔄 Transfer Money");
-        println!("  6. 📊 View Account Details");
-        println!("  7. 📜 View Transaction History");
-        println!("  8. 👥 List All Customers");
-        println!("  9. 🔍 Search Customers");
-        println!(" 10. 📈 View Bank Statistics");
-        println!(" 11. 💾 Save Data");
-        println!("  0. 🚪 Exit");
-        println!("═══════════════════════════════════════════\n");
+        self.display_menu_title();
+        for (number, icon, item) in MENU {
+            self.renderer.chrome(&format!("{:>3}. {} {}", number, icon, self.menu_label(item)));
+        }
+        self.renderer.chrome(&format!("{:>3}. 🚪 {}", 0, Msg::Exit.text()));
+        self.renderer.chrome("═══════════════════════════════════════════\n");
+    }
+
+    /// Displays the menu's title and who is logged in
+    fn display_menu_title(&self) {
+        self.renderer.chrome("═══════════════════════════════════════════");
+        self.renderer.chrome(format!("{:^43}", Msg::MainMenu.text()).trim_end());
+        if let (Some(session), Some(name)) = (&self.session, self.session_name()) {
+            self.renderer.meta(&format!("  {}", Msg::LoggedInAs.fill(&[&name, &session.role])));
+        }
+        let unsaved = self.bank.read().unsaved_changes();
+        if unsaved > 0 {
+            self.renderer.meta(&format!("  {}", Msg::UnsavedChanges.fill(&[&unsaved])));
+        }
+        self.renderer.chrome("═══════════════════════════════════════════");
+    }
+
+    /// The text of a menu entry
+    fn menu_label(&self, item: Msg) -> String {
+        match item {
+            Msg::ToggleAutosave => item.fill(&[if self.autosave { &Msg::On } else { &Msg::Off }]),
+            _ => item.text().to_string(),
+        }
+    }
+
+    /// Display name of the logged-in user, if any
+    fn session_name(&self) -> Option<String> {
+        let session = self.session.as_ref()?;
+        match session.customer_id() {
+            Some(id) => {
+                let bank = self.bank.read();
+                bank.get_customer(&id).ok().map(|c| c.name.clone())
+            }
+            None => Some(session.user_id.clone()),
+        }
+    }
+
+    /// Persists changes made by the last operation
+    ///
+    /// With autosave on, the whole bank is saved (atomically); otherwise the
+    /// changes are appended to the journal. In read-only mode they are
+    /// dropped. A failure is reported but not
+    /// fatal: the changes are still in memory and will be written by the
+    /// next full save.
+    fn persist_changes(&self) {
+        let mut bank = self.bank.write();
+        let entries = bank.take_journal();
+        if entries.is_empty() || self.read_only {
+            return;
+        }
+
+        let result = if self.autosave {
+            self.storage.save_bank(&bank).map(|()| bank.mark_saved())
+        } else {
+            self.storage.append_journal(&bank, &entries)
+        };
+
+        if let Err(e) = result {
+            self.renderer.chrome(&Msg::CouldNotSave.fill(&[&e]));
+        }
+    }
+
+    /// Saves the whole bank on the way out of a failed session
+    ///
+    /// Only done if something changed since the last save. Messages go to
+    /// stderr, as stdout may be what failed.
+    fn emergency_save(&self) {
+        let mut bank = self.bank.write();
+        bank.take_journal();
+        if !bank.has_unsaved_changes() || self.read_only {
+            return;
+        }
+        match self.storage.save_bank(&bank) {
+            Ok(()) => eprint!("{}", Msg::EmergencySaved.fill(&[&self.storage.location()])),
+            Err(e) => eprint!("{}", Msg::CouldNotSave.fill(&[&e])),
+        }
     }
 
     /// Saves bank data to file
     fn save_data(&self) -> io::Result<()> {
-        let bank = self.bank.lock().unwrap();
+        let mut bank = self.bank.write();
 
-        persistence::save_bank(&bank, &self.data_file)
-            .map_err(|e| io::Error::new(io::ErrorKind::Other, e.to_string()))?;
+        self.storage
+            .save_bank(&bank)
+            .map_err(|e| io::Error::other(e.to_string()))?;
+        bank.mark_saved();
 
         Ok(())
     }
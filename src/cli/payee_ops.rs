@@ -0,0 +1,83 @@
+//! Saved payee CLI operations
+//!
+//! Demonstrates: Looping sub-menus, validating input as it is typed
+
+use std::io;
+
+use serde_json::json;
+
+use crate::bank::SharedBank;
+use crate::models::CustomerId;
+use crate::validation;
+use super::i18n::Msg;
+use super::render::Renderer;
+use super::session::{authorize, Session, ANY_ROLE};
+use super::utils::{read_checked, read_customer, read_input};
+
+/// Lists, adds and removes a customer's saved payees
+///
+/// Customers manage their own payees; staff pick the customer.
+pub fn manage_payees(
+    bank: &SharedBank,
+    session: Option<&Session>,
+    renderer: &dyn Renderer,
+) -> io::Result<()> {
+    let session = match authorize(session, ANY_ROLE, renderer) {
+        Some(session) => session,
+        None => return Ok(()),
+    };
+
+    renderer.section(Msg::SavedPayees.text());
+
+    let customer_id = match session.target_customer(bank, Msg::EnterCustomer.text(), renderer)? {
+        Some(id) => id,
+        None => return Ok(()),
+    };
+
+    loop {
+        let payees = match bank.read().list_beneficiaries(&customer_id) {
+            Ok(payees) => payees.into_iter().cloned().collect::<Vec<_>>(),
+            Err(e) => {
+                renderer.failure(&e);
+                return Ok(());
+            }
+        };
+        if payees.is_empty() {
+            renderer.chrome(Msg::NoPayees.text());
+        } else {
+            renderer.chrome(Msg::SavedPayeesHeading.text());
+            for payee in &payees {
+                renderer.chrome(&format!("  • {}", payee));
+            }
+        }
+
+        renderer.chrome(Msg::AddRemoveActions.text());
+        match read_input(Msg::EnterAction.text())?.as_str() {
+            "" => return Ok(()),
+            "1" => add_payee(bank, &customer_id, renderer)?,
+            "2" => {
+                let nickname = read_input(Msg::NicknameToRemove.text())?;
+                match bank.write().remove_beneficiary(&customer_id, &nickname) {
+                    Ok(removed) => renderer.success(&Msg::PayeeRemoved.fill(&[&removed.nickname]), json!(removed)),
+                    Err(e) => renderer.failure(&e),
+                }
+            }
+            _ => renderer.failure(&Msg::InvalidChoice),
+        }
+    }
+}
+
+/// Prompts for a nickname and a payee, then saves them
+fn add_payee(bank: &SharedBank, customer_id: &CustomerId, renderer: &dyn Renderer) -> io::Result<()> {
+    let nickname = read_checked(Msg::EnterNickname.text(), renderer, validation::validate_nickname)?;
+    let payee_id = match read_customer(bank, Msg::EnterPayee.text(), renderer)? {
+        Some(id) => id,
+        None => return Ok(()),
+    };
+
+    match bank.write().add_beneficiary(customer_id, &nickname, payee_id.as_str()) {
+        Ok(payee) => renderer.success(&Msg::PayeeSaved.fill(&[&payee]), json!(payee)),
+        Err(e) => renderer.failure(&e),
+    }
+    Ok(())
+}
@@ -0,0 +1,283 @@
+//! Arrow-key menu - choosing operations without typing numbers
+//! (`arrow-menu` feature)
+//!
+//! Demonstrates: Terminal raw mode with crossterm, redrawing a region in
+//! place, keeping key handling separate from drawing so it can be tested
+//! https://docs.rs/crossterm/latest/crossterm/terminal/index.html#raw-mode
+//!
+//! `Picker` holds the menu state: the typeahead filter and the selected
+//! entry. `pick` draws it below the cursor and feeds it key presses until
+//! an entry is chosen. While the arrow menu is on, prompts are read with
+//! `read_line`, where `Esc` cancels the whole operation (see
+//! `utils::Cancelled`) instead of walking through every remaining prompt.
+
+use std::io::{self, Write};
+
+use crossterm::cursor::{MoveToColumn, MoveToPreviousLine};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::style::{Attribute, Print, SetAttribute};
+use crossterm::terminal::{self, Clear, ClearType};
+use crossterm::{execute, queue};
+
+use super::i18n::Msg;
+use super::utils::{self, Cancelled};
+
+/// Lines the picker keeps for itself besides the entries: the filter line
+/// and room for the prompt under it
+const RESERVED_LINES: usize = 3;
+
+/// What a key press led to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Step {
+    /// Keep reading keys
+    Continue,
+    /// The entry with this number was chosen
+    Chosen(u8),
+}
+
+/// The state of the arrow-key menu: entries, typeahead filter, selection
+#[derive(Debug, Clone)]
+pub struct Picker {
+    entries: Vec<(u8, String)>,
+    filter: String,
+    selected: usize,
+}
+
+impl Picker {
+    /// Creates a picker over numbered entries, with the first selected
+    pub fn new(entries: Vec<(u8, String)>) -> Self {
+        Self { entries, filter: String::new(), selected: 0 }
+    }
+
+    /// The typeahead filter typed so far
+    pub fn filter(&self) -> &str {
+        &self.filter
+    }
+
+    /// The entries matching the filter: those whose label contains it
+    /// (ignoring case) or whose number starts with it
+    pub fn visible(&self) -> Vec<&(u8, String)> {
+        let filter = self.filter.to_lowercase();
+        self.entries
+            .iter()
+            .filter(|(number, label)| {
+                filter.is_empty() || label.to_lowercase().contains(&filter) || number.to_string().starts_with(&filter)
+            })
+            .collect()
+    }
+
+    /// Position of the selected entry within `visible()`
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    /// Applies one key press
+    ///
+    /// - `↑`/`↓` move the selection, wrapping around; `Home`/`End` jump
+    /// - typing filters the entries, `Backspace` takes a character back
+    /// - `Esc` (or `Ctrl+C`) clears the filter
+    /// - `Enter` chooses the selected entry
+    pub fn handle_key(&mut self, key: KeyEvent) -> Step {
+        let count = self.visible().len();
+        match key.code {
+            KeyCode::Up if count > 0 => self.selected = (self.selected + count - 1) % count,
+            KeyCode::Down if count > 0 => self.selected = (self.selected + 1) % count,
+            KeyCode::Home => self.selected = 0,
+            KeyCode::End => self.selected = count.saturating_sub(1),
+            KeyCode::Enter => {
+                if let Some((number, _)) = self.visible().get(self.selected) {
+                    return Step::Chosen(*number);
+                }
+            }
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => self.set_filter(String::new()),
+            KeyCode::Esc => self.set_filter(String::new()),
+            KeyCode::Backspace => {
+                let mut filter = self.filter.clone();
+                filter.pop();
+                self.set_filter(filter);
+            }
+            KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                let filter = format!("{}{}", self.filter, c);
+                self.set_filter(filter);
+            }
+            _ => {}
+        }
+        Step::Continue
+    }
+
+    fn set_filter(&mut self, filter: String) {
+        if filter != self.filter {
+            self.filter = filter;
+            self.selected = 0;
+        }
+    }
+
+    /// The lines to draw: the filter, then up to `height` entries around
+    /// the selection, the selected one marked with `▶` and `selected_line`
+    /// set to its index
+    fn lines(&self, height: usize) -> (Vec<String>, Option<usize>) {
+        let visible = self.visible();
+        let height = height.max(1);
+        let first = self.selected.saturating_sub(height - 1).min(visible.len().saturating_sub(height));
+
+        let mut lines = vec![format!("🔎 {}", self.filter)];
+        if visible.is_empty() {
+            lines.push(Msg::NoMatch.text().to_string());
+        }
+        for (i, (number, label)) in visible.iter().enumerate().skip(first).take(height) {
+            let marker = if i == self.selected { "▶" } else { " " };
+            lines.push(format!("{} {:>3}. {}", marker, number, label));
+        }
+        let selected_line = (!visible.is_empty()).then(|| self.selected - first + 1);
+        (lines, selected_line)
+    }
+}
+
+/// Restores the terminal's normal mode when dropped, even on an error
+struct RawMode;
+
+impl RawMode {
+    fn enable() -> io::Result<Self> {
+        terminal::enable_raw_mode()?;
+        Ok(RawMode)
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+/// Lets the user choose one of `entries` with the arrow keys
+///
+/// The menu is drawn below the cursor and redrawn in place on each key;
+/// once an entry is chosen the menu is replaced by a single line naming it.
+pub fn pick(entries: Vec<(u8, String)>) -> io::Result<u8> {
+    let mut picker = Picker::new(entries);
+    let mut stdout = io::stdout();
+    // Some terminals (and pseudo-terminals) report no size at all
+    let rows = terminal::size().ok().map_or(0, |(_, rows)| rows);
+    let rows = if rows == 0 { 24 } else { usize::from(rows) };
+    let height = rows.saturating_sub(RESERVED_LINES);
+
+    let _raw = RawMode::enable()?;
+    let mut drawn = 0;
+    loop {
+        let (lines, selected_line) = picker.lines(height);
+        if drawn > 0 {
+            queue!(stdout, MoveToPreviousLine(drawn as u16))?;
+        }
+        queue!(stdout, MoveToColumn(0), Clear(ClearType::FromCursorDown))?;
+        for (i, line) in lines.iter().enumerate() {
+            let line = utils::prompt_text(line);
+            if Some(i) == selected_line {
+                queue!(stdout, SetAttribute(Attribute::Reverse), Print(line), SetAttribute(Attribute::Reset))?;
+            } else {
+                queue!(stdout, Print(line))?;
+            }
+            queue!(stdout, Print("\r\n"))?;
+        }
+        stdout.flush()?;
+        drawn = lines.len();
+
+        if let Event::Key(key) = event::read()? {
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            if let Step::Chosen(number) = picker.handle_key(key) {
+                let label = picker.visible()[picker.selected()].1.clone();
+                execute!(
+                    stdout,
+                    MoveToPreviousLine(drawn as u16),
+                    Clear(ClearType::FromCursorDown),
+                    Print(utils::prompt_text(&format!("▶ {}. {}\r\n", number, label)))
+                )?;
+                return Ok(number);
+            }
+        }
+    }
+}
+
+/// What a key press did to a line being typed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LineStep {
+    /// Keep reading keys
+    Continue,
+    /// `Enter` finished the line
+    Done(String),
+    /// `Esc` or `Ctrl+C` gave up on the operation
+    Cancel,
+}
+
+/// A line being typed at a prompt
+#[derive(Debug, Clone, Default)]
+pub struct LineEditor {
+    text: String,
+}
+
+impl LineEditor {
+    /// Creates an empty line
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// What has been typed so far
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Applies one key press; characters are added at the end and
+    /// `Backspace` removes the last one
+    pub fn handle_key(&mut self, key: KeyEvent) -> LineStep {
+        match key.code {
+            KeyCode::Esc => LineStep::Cancel,
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => LineStep::Cancel,
+            KeyCode::Enter => LineStep::Done(self.text.trim().to_string()),
+            KeyCode::Backspace => {
+                self.text.pop();
+                LineStep::Continue
+            }
+            KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.text.push(c);
+                LineStep::Continue
+            }
+            _ => LineStep::Continue,
+        }
+    }
+}
+
+/// Reads a line after `prompt` in raw mode, so `Esc` can be noticed
+///
+/// # Returns
+/// * `Err(io::Error)` wrapping `Cancelled` - If `Esc` was pressed
+pub fn read_line(prompt: &str) -> io::Result<String> {
+    let mut stdout = io::stdout();
+    let mut editor = LineEditor::new();
+    // Raw mode does not return the carriage on a newline, and only the
+    // prompt's last line is redrawn while typing
+    let last_line = prompt.rsplit('\n').next().unwrap_or(prompt);
+    let _raw = RawMode::enable()?;
+    execute!(stdout, Print(prompt.replace('\n', "\r\n")))?;
+    loop {
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        match editor.handle_key(key) {
+            LineStep::Continue => {
+                execute!(stdout, MoveToColumn(0), Clear(ClearType::CurrentLine), Print(last_line), Print(editor.text()))?;
+            }
+            LineStep::Done(line) => {
+                execute!(stdout, Print("\r\n"))?;
+                return Ok(line);
+            }
+            LineStep::Cancel => {
+                execute!(stdout, Print("\r\n"))?;
+                return Err(io::Error::other(Cancelled));
+            }
+        }
+    }
+}
@@ -0,0 +1,155 @@
+//! Output rendering - human-readable text or machine-readable JSON
+//!
+//! Demonstrates: Trait objects for interchangeable output strategies
+//!
+//! CLI operations never print results directly. They hand a text rendering
+//! and a structured JSON value to a `Renderer`, which decides what to show:
+//! - `TextRenderer` prints the emoji-decorated text (default), colored
+//!   and simplified as its `TextStyle` says (see `theme`)
+//! - `JsonRenderer` prints one JSON object per result on stdout and moves
+//!   menus and prompts to stderr, so stdout can be parsed by other tools;
+//!   bank errors carry their `code` and `kind` for those tools to branch on
+
+use std::fmt;
+
+use serde_json::{json, Value};
+
+use crate::errors::BankError;
+use crate::traits::Auditable;
+
+use super::i18n::Msg;
+use super::table::audit_table;
+use super::theme::{TextStyle, Tone};
+use super::utils;
+
+/// Strategy for presenting the results of CLI operations
+pub trait Renderer: Send + Sync {
+    /// Decorative output such as headers, menus, and section titles
+    fn chrome(&self, text: &str);
+
+    /// A successful result with its text form and structured data
+    fn success(&self, text: &str, data: Value);
+
+    /// A failed operation
+    fn failure(&self, error: &dyn fmt::Display);
+
+    /// An operation the bank refused, shown like any failure unless the
+    /// renderer has a use for the error's code
+    fn error(&self, error: &BankError) {
+        self.failure(error);
+    }
+
+    /// Prints a section title like `--- Deposit Money ---`
+    fn section(&self, title: &str) {
+        self.chrome(&format!("\n--- {} ---", title));
+    }
+
+    /// Background information such as the bank summary or who is logged in
+    fn meta(&self, text: &str) {
+        self.chrome(text);
+    }
+
+    /// Any audited record, laid out the same way whatever its kind: a
+    /// table of its facts, or `{ "record": kind, "facts": [...] }`
+    fn report(&self, item: &dyn Auditable) {
+        let text = Msg::AuditRecordHeading.fill(&[&item.audit_kind(), &audit_table(item).render()]);
+        self.success(&text, json!({ "record": item.audit_kind(), "facts": item.audit_facts() }));
+    }
+}
+
+/// Renders results as decorated text for humans
+#[derive(Debug, Default)]
+pub struct TextRenderer {
+    style: TextStyle,
+}
+
+impl TextRenderer {
+    /// Creates a text renderer writing in `style`
+    pub fn new(style: TextStyle) -> Self {
+        utils::set_ascii_prompts(!style.unicode);
+        Self { style }
+    }
+
+    /// The style this renderer writes in
+    pub fn style(&self) -> TextStyle {
+        self.style
+    }
+}
+
+/// Whether `line` is only a border, like the `═══` rules around the menu
+fn is_border(line: &str) -> bool {
+    let line = line.trim();
+    !line.is_empty() && line.chars().all(|c| matches!(c, '═' | '─' | '-' | '='))
+}
+
+impl Renderer for TextRenderer {
+    fn chrome(&self, text: &str) {
+        let lines: Vec<String> = text
+            .split('\n')
+            .map(|line| if is_border(line) { self.style.paint(Tone::Dim, line) } else { self.style.symbols(line) })
+            .collect();
+        println!("{}", lines.join("\n"));
+    }
+
+    fn success(&self, text: &str, _data: Value) {
+        // Only the headline is colored; details after it stay readable
+        let body = text.trim_start_matches('\n');
+        let leading = &text[..text.len() - body.len()];
+        let (headline, details) = body.split_once('\n').map_or((body, None), |(h, d)| (h, Some(d)));
+        let mut out = format!("{}{}", leading, self.style.paint(Tone::Success, headline));
+        if let Some(details) = details {
+            out.push('\n');
+            out.push_str(&self.style.symbols(details));
+        }
+        println!("{}", out);
+    }
+
+    fn failure(&self, error: &dyn fmt::Display) {
+        let line = format!("❌ {}: {}", Msg::Error, error);
+        println!("\n{}\n", self.style.paint(Tone::Error, &line));
+    }
+
+    fn section(&self, title: &str) {
+        println!("\n{}", self.style.paint(Tone::Heading, &format!("--- {} ---", title)));
+    }
+
+    fn meta(&self, text: &str) {
+        println!("{}", self.style.paint(Tone::Dim, text));
+    }
+}
+
+/// Renders results as JSON lines for other programs
+#[derive(Debug)]
+pub struct JsonRenderer;
+
+impl JsonRenderer {
+    /// Creates a JSON renderer and routes input prompts to stderr
+    pub fn new() -> Self {
+        utils::set_prompts_to_stderr(true);
+        Self
+    }
+}
+
+impl Default for JsonRenderer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Renderer for JsonRenderer {
+    fn chrome(&self, text: &str) {
+        eprintln!("{}", text);
+    }
+
+    fn success(&self, _text: &str, data: Value) {
+        println!("{}", json!({ "ok": true, "result": data }));
+    }
+
+    fn failure(&self, error: &dyn fmt::Display) {
+        println!("{}", json!({ "ok": false, "error": error.to_string() }));
+    }
+
+    fn error(&self, error: &BankError) {
+        println!("{}", json!({ "ok": false, "error": error.to_string(), "code": error.code(), "kind": error.kind() }));
+    }
+}
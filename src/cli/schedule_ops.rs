@@ -0,0 +1,153 @@
+//! Scheduled transfer CLI operations
+//!
+//! Demonstrates: Parsing user input into enums and dates
+
+use std::io;
+
+use chrono::{NaiveDate, Utc};
+use serde_json::json;
+
+use crate::bank::SharedBank;
+use crate::format;
+use crate::models::Frequency;
+use super::i18n::Msg;
+use super::render::Renderer;
+use super::session::{authorize, Session, STAFF};
+use super::utils::{read_amount, read_customer, read_input, read_uuid};
+
+/// Creates a recurring transfer between two customers (staff only)
+pub fn create_schedule(
+    bank: &SharedBank,
+    session: Option<&Session>,
+    renderer: &dyn Renderer,
+) -> io::Result<()> {
+    if authorize(session, STAFF, renderer).is_none() {
+        return Ok(());
+    }
+
+    renderer.section(Msg::CreateScheduledTransfer.text());
+
+    let from_id = match read_customer(bank, Msg::EnterSender.text(), renderer)? {
+        Some(id) => id,
+        None => return Ok(()),
+    };
+    let to_id = match read_customer(bank, Msg::EnterRecipientId.text(), renderer)? {
+        Some(id) => id,
+        None => return Ok(()),
+    };
+    let amount = read_amount(Msg::AmountPerTransfer.text(), renderer)?;
+
+    renderer.chrome(Msg::Frequencies.text());
+    let frequency = match read_input(Msg::EnterFrequency.text())?.as_str() {
+        "1" => Frequency::Daily,
+        "2" => Frequency::Weekly,
+        "3" => Frequency::Monthly,
+        _ => {
+            renderer.failure(&Msg::InvalidFrequency);
+            return Ok(());
+        }
+    };
+
+    // An empty date means the first transfer runs right away
+    let date_str = read_input(Msg::FirstRunDate.text())?;
+    let first_run = if date_str.is_empty() {
+        Utc::now()
+    } else {
+        match NaiveDate::parse_from_str(&date_str, "%Y-%m-%d") {
+            Ok(date) => date.and_hms_opt(0, 0, 0).unwrap().and_utc(),
+            Err(_) => {
+                renderer.failure(&Msg::InvalidDate);
+                return Ok(());
+            }
+        }
+    };
+
+    let mut bank = bank.write();
+
+    match bank.create_schedule(&from_id, &to_id, amount, frequency, first_run) {
+        Ok(schedule_id) => renderer.success(
+            &Msg::ScheduleCreated.fill(&[&schedule_id]),
+            json!({ "schedule_id": schedule_id }),
+        ),
+        Err(e) => renderer.failure(&e),
+    }
+
+    Ok(())
+}
+
+/// Lists all scheduled transfers (staff only)
+pub fn list_schedules(
+    bank: &SharedBank,
+    session: Option<&Session>,
+    renderer: &dyn Renderer,
+) -> io::Result<()> {
+    if authorize(session, STAFF, renderer).is_none() {
+        return Ok(());
+    }
+
+    renderer.section(Msg::ScheduledTransfers.text());
+
+    let bank = bank.read();
+    let schedules = bank.list_schedules();
+
+    let text = if schedules.is_empty() {
+        Msg::NoSchedules.text().to_string()
+    } else {
+        let mut text = Msg::TotalSchedules.fill(&[&schedules.len()]);
+        for schedule in &schedules {
+            text.push_str(&format!("  • {} (ID: {})\n", schedule, schedule.id));
+        }
+        text
+    };
+    renderer.success(&text, json!(schedules));
+
+    Ok(())
+}
+
+/// Cancels a scheduled transfer (staff only)
+pub fn cancel_schedule(
+    bank: &SharedBank,
+    session: Option<&Session>,
+    renderer: &dyn Renderer,
+) -> io::Result<()> {
+    if authorize(session, STAFF, renderer).is_none() {
+        return Ok(());
+    }
+
+    renderer.section(Msg::CancelScheduledTransfer.text());
+
+    let schedule_id = read_uuid(Msg::EnterScheduleId.text(), renderer)?;
+
+    let mut bank = bank.write();
+
+    match bank.cancel_schedule(&schedule_id) {
+        Ok(()) => renderer.success(Msg::ScheduleCancelled.text(), json!({ "cancelled": schedule_id })),
+        Err(e) => renderer.failure(&e),
+    }
+
+    Ok(())
+}
+
+/// Runs any schedules that are due and reports what happened
+///
+/// Prints nothing when no schedules were due.
+pub fn process_due_schedules(bank: &SharedBank, renderer: &dyn Renderer) {
+    let mut bank = bank.write();
+    let runs = bank.run_due_schedules(Utc::now());
+
+    for run in runs {
+        let data = json!({
+            "schedule_id": run.schedule_id,
+            "run_at": run.run_at,
+        });
+        match run.result {
+            Ok(()) => renderer.success(
+                &Msg::ScheduleRan.fill(&[&&run.schedule_id[..8], &format::date(&run.run_at)]),
+                data,
+            ),
+            Err(e) => {
+                renderer.failure(&Msg::ScheduleFailed.fill(&[&&run.schedule_id[..8], &format::date(&run.run_at), &e]))
+            }
+        }
+    }
+}
@@ -0,0 +1,168 @@
+//! Command scripts - running a file of shell commands without a prompt
+//!
+//! Demonstrates: Reusing one parser for interactive and batch input,
+//! early exit versus carrying on, Serialize for a report
+//!
+//! `banking-cli run script.txt` reads the file one line at a time, with
+//! the same grammar as the command shell: blank lines and `#` comments
+//! are skipped, the common commands take their first values bare and
+//! `exit` ends the script early.
+//!
+//! ```text
+//! # seed.txt
+//! register Ann ann@example.com --pin 1234
+//! create-account ann@example.com --deposit 100
+//! deposit ann@example.com 25 --pin 1234
+//! ```
+//!
+//! The first failing line stops the script unless `--keep-going` is
+//! given; either way a summary of what ran is printed last.
+
+use std::fmt;
+use std::fs;
+use std::mem;
+
+use serde::Serialize;
+use serde_json::json;
+
+use crate::bank::Bank;
+use crate::config::Config;
+use crate::errors::BankError;
+use crate::persistence::Storage;
+use super::commands::{execute_and_save, load_or_create, open_command_storage};
+use super::render::{JsonRenderer, Renderer, TextRenderer};
+use super::shell::{history_entry, parse_line, problem};
+use super::theme::TextStyle;
+
+/// What happened to the commands of a script
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ScriptSummary {
+    /// Commands that ran and succeeded
+    pub succeeded: usize,
+    /// Line numbers (from 1) of the commands that failed
+    pub failed: Vec<usize>,
+    /// Commands never run because an earlier one failed
+    pub skipped: usize,
+}
+
+impl ScriptSummary {
+    /// Whether every command that was run succeeded
+    pub fn is_success(&self) -> bool {
+        self.failed.is_empty()
+    }
+}
+
+impl fmt::Display for ScriptSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} succeeded, {} failed", self.succeeded, self.failed.len())?;
+        if !self.failed.is_empty() {
+            let lines: Vec<String> = self.failed.iter().map(usize::to_string).collect();
+            write!(f, " (line {})", lines.join(", "))?;
+        }
+        if self.skipped > 0 {
+            write!(f, ", {} skipped", self.skipped)?;
+        }
+        Ok(())
+    }
+}
+
+/// Runs every command in `script` against `bank`, saving after each one
+///
+/// Each line is echoed with its number (PINs masked) before its result
+/// is shown.
+///
+/// # Arguments
+/// * `keep_going` - Run the remaining lines after a failure instead of
+///   skipping them
+pub fn run_lines(
+    script: &str,
+    bank: &mut Bank,
+    storage: &dyn Storage,
+    keep_going: bool,
+    renderer: &dyn Renderer,
+) -> ScriptSummary {
+    // Numbering the accounts of an older file is saved with the first command
+    let mut unsaved = !bank.take_journal().is_empty();
+    let mut summary = ScriptSummary::default();
+    let mut stopped = false;
+
+    for (i, line) in script.lines().enumerate() {
+        let number = i + 1;
+        let command = line.trim();
+        if command.is_empty() || command.starts_with('#') {
+            continue;
+        }
+        if stopped {
+            summary.skipped += 1;
+            continue;
+        }
+        if matches!(command, "exit" | "quit") {
+            break;
+        }
+
+        renderer.chrome(&format!("▶ {}: {}", number, history_entry(command)));
+        let result = match parse_line(command) {
+            Ok(Some(command)) => execute_and_save(command, bank, storage, mem::take(&mut unsaved)),
+            Ok(None) => continue,
+            Err(e) => Err(BankError::InvalidInput(problem(&e))),
+        };
+        match result {
+            Ok(value) => {
+                summary.succeeded += 1;
+                let text = serde_json::to_string_pretty(&value).unwrap_or_default();
+                renderer.success(&text, value);
+            }
+            Err(e) => {
+                summary.failed.push(number);
+                renderer.error(&e);
+                stopped = !keep_going;
+            }
+        }
+    }
+    summary
+}
+
+/// Runs the script at `path` and prints a summary
+///
+/// # Returns
+/// The process exit code: 0 if every command succeeded, 1 if one failed
+/// or the script or bank could not be loaded
+pub fn run_script(config: &Config, path: &str, keep_going: bool, json: bool) -> i32 {
+    let renderer: Box<dyn Renderer> = if json {
+        Box::new(JsonRenderer::new())
+    } else {
+        Box::new(TextRenderer::new(TextStyle::for_terminal(config)))
+    };
+
+    let script = match fs::read_to_string(path) {
+        Ok(script) => script,
+        Err(e) => {
+            renderer.failure(&format!("Could not read {}: {}", path, e));
+            return 1;
+        }
+    };
+    let loaded = open_command_storage(config).and_then(|storage| {
+        let bank = load_or_create(storage.as_ref(), config)?;
+        Ok((storage, bank))
+    });
+    let (storage, mut bank) = match loaded {
+        Ok(loaded) => loaded,
+        Err(e) => {
+            renderer.failure(&e);
+            return 1;
+        }
+    };
+    if config.read_only {
+        // Nothing may be written, not even the upgrade of an older file
+        bank.take_journal();
+    }
+
+    let summary = run_lines(&script, &mut bank, storage.as_ref(), keep_going, renderer.as_ref());
+    let icon = if summary.is_success() { "✅" } else { "⚠️ " };
+    renderer.success(&format!("\n{} {}: {}", icon, path, summary), json!({ "summary": summary }));
+    if summary.is_success() {
+        0
+    } else {
+        1
+    }
+}
@@ -0,0 +1,93 @@
+//! Login sessions and role checks
+//!
+//! Demonstrates: Encoding permissions as data (slices of allowed roles),
+//! Option combinators for access control
+
+use std::io;
+
+use crate::bank::SharedBank;
+use crate::models::{CustomerId, Role};
+use super::i18n::Msg;
+use super::render::Renderer;
+use super::utils::read_customer;
+
+/// Roles allowed to manage the bank itself
+pub const ADMIN_ONLY: &[Role] = &[Role::Admin];
+
+/// Roles allowed to work on any customer's behalf
+pub const STAFF: &[Role] = &[Role::Admin, Role::Teller];
+
+/// Every logged-in user; customers are limited to their own account
+pub const ANY_ROLE: &[Role] = &[Role::Admin, Role::Teller, Role::Customer];
+
+/// The logged-in user
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Session {
+    /// Customer ID for customers, username for staff
+    pub user_id: String,
+    pub role: Role,
+}
+
+impl Session {
+    /// Starts a session for a customer
+    pub fn customer(customer_id: CustomerId) -> Self {
+        Self { user_id: customer_id.into(), role: Role::Customer }
+    }
+
+    /// Starts a session for an admin or teller
+    pub fn staff(username: String, role: Role) -> Self {
+        Self { user_id: username, role }
+    }
+
+    /// Whether this session's role is one of `roles`
+    pub fn allows(&self, roles: &[Role]) -> bool {
+        roles.contains(&self.role)
+    }
+
+    /// The customer ID for customer sessions, `None` for staff
+    pub fn customer_id(&self) -> Option<CustomerId> {
+        (self.role == Role::Customer).then(|| self.user_id.as_str().into())
+    }
+
+    /// Chooses the customer an operation acts on
+    ///
+    /// Customers always act on their own account; staff are asked (see
+    /// `read_customer` for what they can type).
+    ///
+    /// # Returns
+    /// * `Ok(None)` - If no single customer matched (error already shown)
+    pub fn target_customer(
+        &self,
+        bank: &SharedBank,
+        prompt: &str,
+        renderer: &dyn Renderer,
+    ) -> io::Result<Option<CustomerId>> {
+        match self.customer_id() {
+            Some(id) => Ok(Some(id)),
+            None => read_customer(bank, prompt, renderer),
+        }
+    }
+}
+
+/// Checks that someone is logged in with one of the `allowed` roles
+///
+/// # Returns
+/// * `Some(session)` - The operation may go ahead
+/// * `None` - Access denied (reason already shown)
+pub fn authorize<'a>(
+    session: Option<&'a Session>,
+    allowed: &[Role],
+    renderer: &dyn Renderer,
+) -> Option<&'a Session> {
+    match session {
+        None => {
+            renderer.failure(&Msg::LogInFirst);
+            None
+        }
+        Some(session) if !session.allows(allowed) => {
+            renderer.failure(&Msg::RoleNotAllowed.fill(&[&session.role]));
+            None
+        }
+        Some(session) => Some(session),
+    }
+}
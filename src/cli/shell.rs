@@ -0,0 +1,347 @@
+//! Command shell - typing commands instead of walking through menus
+//!
+//! Demonstrates: Reusing a clap parser for lines typed at a prompt, line
+//! editing with rustyline, implementing a library's helper traits
+//! https://docs.rs/rustyline/latest/rustyline/
+//!
+//! `banking-cli shell` loads the bank once and reads one command per
+//! line. The commands are the ones the binary takes as subcommands, with
+//! the same options:
+//!
+//! ```text
+//! bank> deposit --customer alice@example.com --amount 100 --pin 1234
+//! bank> history alice@example.com --last 10
+//! ```
+//!
+//! The first few values of the common commands may be given without
+//! their option names (see `SHORTHANDS`). `Tab` completes command names,
+//! option names and customer names or emails; what was typed is kept in
+//! the config's `history_file` for the next session, with PINs masked.
+//! `help` lists the commands, `exit` (or `Ctrl+D`) leaves.
+
+use std::borrow::Cow;
+use std::mem;
+
+use clap::error::ErrorKind;
+use clap::{CommandFactory, Parser};
+use rustyline::completion::Completer;
+use rustyline::error::ReadlineError;
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::history::DefaultHistory;
+use rustyline::validate::Validator;
+use rustyline::{Context, Editor, Helper};
+
+use crate::bank::Bank;
+use crate::config::Config;
+use super::args::Command;
+use super::commands::{execute_and_save, load_or_create, open_command_storage};
+use super::render::{JsonRenderer, Renderer, TextRenderer};
+use super::theme::TextStyle;
+
+/// Shown before every line
+pub const PROMPT: &str = "bank> ";
+
+/// Most lines kept in the history file
+pub const HISTORY_SIZE: usize = 1000;
+
+/// Commands whose first values may be given without their option names,
+/// e.g. `deposit alice 100` for `deposit --customer alice --amount 100`
+pub const SHORTHANDS: &[(&str, &[&str])] = &[
+    ("register", &["name", "email"]),
+    ("create-account", &["customer"]),
+    ("deposit", &["customer", "amount"]),
+    ("withdraw", &["customer", "amount"]),
+    ("transfer", &["from", "to", "amount"]),
+    ("account", &["customer"]),
+    ("history", &["customer"]),
+    ("lookup-transaction", &["id"]),
+    ("receipt", &["id"]),
+    ("categories", &["customer"]),
+    ("holds", &["customer"]),
+    ("limits", &["customer"]),
+    ("search", &["query"]),
+];
+
+/// Options whose values are never written to the history file
+pub const SECRET_OPTIONS: [&str; 3] = ["--pin", "--current-pin", "--new-pin"];
+
+/// Words the shell handles itself
+const BUILTINS: [&str; 3] = ["exit", "quit", "help"];
+
+/// Parses a typed line as a subcommand
+#[derive(Debug, Parser)]
+#[command(
+    name = "bank>",
+    about = "Commands of the banking shell; `exit` leaves",
+    no_binary_name = true,
+    disable_version_flag = true
+)]
+struct ShellLine {
+    #[command(subcommand)]
+    command: Command,
+}
+
+/// Turns a typed line into a command
+///
+/// # Returns
+/// * `Ok(None)` - If the line is blank or a `#` comment
+/// * `Err(clap::Error)` - If the line is not a valid command; for `help`
+///   the error's kind is `DisplayHelp` and its text is the help
+pub fn parse_line(line: &str) -> Result<Option<Command>, clap::Error> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return Ok(None);
+    }
+    let words = shlex::split(line)
+        .ok_or_else(|| ShellLine::command().error(ErrorKind::InvalidValue, "unclosed quote"))?;
+    ShellLine::try_parse_from(expand_shorthand(words)).map(|parsed| Some(parsed.command))
+}
+
+/// Gives the leading bare values of a command in `SHORTHANDS` their
+/// option names; anything from the first option on is left alone
+pub fn expand_shorthand(words: Vec<String>) -> Vec<String> {
+    let Some((name, rest)) = words.split_first() else {
+        return words;
+    };
+    let Some((_, options)) = SHORTHANDS.iter().find(|(command, _)| command == name) else {
+        return words;
+    };
+
+    let mut expanded = vec![name.clone()];
+    let mut options = options.iter();
+    let mut bare = true;
+    for word in rest {
+        bare &= !word.starts_with('-');
+        match options.next().filter(|_| bare) {
+            Some(option) => expanded.extend([format!("--{}", option), word.clone()]),
+            None => expanded.push(word.clone()),
+        }
+    }
+    expanded
+}
+
+/// `line` as it is kept in the history, with PINs masked
+pub fn history_entry(line: &str) -> String {
+    let mut masked = Vec::new();
+    let mut secret_next = false;
+    for word in line.split_whitespace() {
+        let option = word.split('=').next().unwrap_or(word);
+        if mem::take(&mut secret_next) {
+            masked.push("****".to_string());
+        } else if SECRET_OPTIONS.contains(&option) && word.contains('=') {
+            masked.push(format!("{}=****", option));
+        } else {
+            secret_next = SECRET_OPTIONS.contains(&word);
+            masked.push(word.to_string());
+        }
+    }
+    masked.join(" ")
+}
+
+/// Tab completion for the shell: command names first, then option names
+/// or customer names and emails
+#[derive(Debug, Clone, Default)]
+pub struct ShellHelper {
+    customers: Vec<String>,
+}
+
+impl ShellHelper {
+    /// Completes with the names and emails of `bank`'s customers
+    pub fn new(bank: &Bank) -> Self {
+        let mut helper = Self::default();
+        helper.refresh(bank);
+        helper
+    }
+
+    /// Picks up customers added or changed since the last refresh
+    pub fn refresh(&mut self, bank: &Bank) {
+        self.customers = bank
+            .find_customers_by_name("")
+            .into_iter()
+            .flat_map(|customer| [customer.name.clone(), customer.email.clone()])
+            .collect();
+        self.customers.sort();
+        self.customers.dedup();
+    }
+
+    /// Where the word under the cursor starts and what it could become
+    pub fn candidates(&self, line: &str, pos: usize) -> (usize, Vec<String>) {
+        let before = &line[..pos];
+        let start = word_start(before);
+        let word = &before[start..];
+
+        let candidates: Vec<String> = if before[..start].trim().is_empty() {
+            let names = ShellLine::command().get_subcommands().map(|c| c.get_name().to_string()).collect::<Vec<_>>();
+            names.into_iter().chain(BUILTINS.map(String::from)).filter(|name| name.starts_with(word)).collect()
+        } else if word.starts_with('-') {
+            let name = before.split_whitespace().next().unwrap_or_default();
+            match ShellLine::command().find_subcommand(name) {
+                Some(command) => command
+                    .get_arguments()
+                    .filter_map(|arg| arg.get_long())
+                    .map(|long| format!("--{}", long))
+                    .filter(|option| option.starts_with(word))
+                    .collect(),
+                None => Vec::new(),
+            }
+        } else {
+            let typed = word.trim_start_matches(['"', '\'']).to_lowercase();
+            self.customers
+                .iter()
+                .filter(|customer| customer.to_lowercase().starts_with(&typed))
+                .map(|customer| shlex::try_quote(customer).map_or_else(|_| customer.clone(), Cow::into_owned))
+                .collect()
+        };
+        (start, candidates)
+    }
+}
+
+/// Byte position where the last word of `before` starts, treating a
+/// quoted stretch as part of one word
+fn word_start(before: &str) -> usize {
+    let mut start = 0;
+    let mut quote = None;
+    for (i, c) in before.char_indices() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None if c == '"' || c == '\'' => quote = Some(c),
+            None if c.is_whitespace() => start = i + c.len_utf8(),
+            None => {}
+        }
+    }
+    start
+}
+
+impl Completer for ShellHelper {
+    type Candidate = String;
+
+    fn complete(&self, line: &str, pos: usize, _ctx: &Context<'_>) -> rustyline::Result<(usize, Vec<String>)> {
+        Ok(self.candidates(line, pos))
+    }
+}
+
+impl Hinter for ShellHelper {
+    type Hint = String;
+}
+
+impl Highlighter for ShellHelper {}
+
+impl Validator for ShellHelper {}
+
+impl Helper for ShellHelper {}
+
+/// Runs the shell until `exit` or the end of input
+///
+/// Results are printed as they would be in command mode: indented JSON,
+/// or one compact JSON object per line with `--json`.
+///
+/// # Returns
+/// The process exit code: 0, or 1 if the bank could not be loaded or the
+/// terminal failed
+pub fn run_shell(config: &Config, json: bool) -> i32 {
+    let renderer: Box<dyn Renderer> = if json {
+        Box::new(JsonRenderer::new())
+    } else {
+        Box::new(TextRenderer::new(TextStyle::for_terminal(config)))
+    };
+
+    let loaded = open_command_storage(config).and_then(|storage| {
+        let bank = load_or_create(storage.as_ref(), config)?;
+        Ok((storage, bank))
+    });
+    let (storage, mut bank) = match loaded {
+        Ok(loaded) => loaded,
+        Err(e) => {
+            renderer.failure(&e);
+            return 1;
+        }
+    };
+    // Numbering the accounts of an older file is saved with the first command
+    let mut upgraded = !bank.take_journal().is_empty() && !config.read_only;
+
+    let editor_config = rustyline::Config::builder().max_history_size(HISTORY_SIZE);
+    let editor_config = match editor_config.and_then(|builder| builder.history_ignore_dups(true)) {
+        Ok(builder) => builder.build(),
+        Err(e) => {
+            renderer.failure(&e);
+            return 1;
+        }
+    };
+    let mut editor = match Editor::<ShellHelper, DefaultHistory>::with_config(editor_config) {
+        Ok(editor) => editor,
+        Err(e) => {
+            renderer.failure(&e);
+            return 1;
+        }
+    };
+    editor.set_helper(Some(ShellHelper::new(&bank)));
+    // A missing history file just means this is the first session
+    if !config.history_file.is_empty() {
+        let _ = editor.load_history(&config.history_file);
+    }
+
+    let mut status = 0;
+    loop {
+        let line = match editor.readline(PROMPT) {
+            Ok(line) => line,
+            // Ctrl+C drops the line being typed
+            Err(ReadlineError::Interrupted) => continue,
+            Err(ReadlineError::Eof) => break,
+            Err(e) => {
+                renderer.failure(&e);
+                status = 1;
+                break;
+            }
+        };
+        if matches!(line.trim(), "exit" | "quit") {
+            break;
+        }
+        if !line.trim().is_empty() {
+            let _ = editor.add_history_entry(history_entry(&line));
+        }
+
+        match parse_line(&line) {
+            Ok(None) => {}
+            Ok(Some(command)) => {
+                match execute_and_save(command, &mut bank, storage.as_ref(), mem::take(&mut upgraded)) {
+                    Ok(value) => {
+                        let text = serde_json::to_string_pretty(&value).unwrap_or_default();
+                        renderer.success(&text, value);
+                    }
+                    Err(e) => renderer.error(&e),
+                }
+                if let Some(helper) = editor.helper_mut() {
+                    helper.refresh(&bank);
+                }
+            }
+            Err(e) if is_help(&e) => renderer.chrome(e.to_string().trim_end()),
+            Err(e) => renderer.failure(&describe(&e)),
+        }
+    }
+
+    if !config.history_file.is_empty() {
+        if let Err(e) = editor.save_history(&config.history_file) {
+            eprintln!("⚠️  Could not save the shell history: {}", e);
+        }
+    }
+    status
+}
+
+/// Whether `error` is clap showing help rather than a mistake
+fn is_help(error: &clap::Error) -> bool {
+    matches!(error.kind(), ErrorKind::DisplayHelp | ErrorKind::DisplayHelpOnMissingArgumentOrSubcommand)
+}
+
+/// A clap error without its `error:` prefix and usage lines
+pub(super) fn problem(error: &clap::Error) -> String {
+    let text = error.to_string();
+    let problem = text.split("\n\n").next().unwrap_or_default();
+    problem.trim_start_matches("error: ").trim_end().to_string()
+}
+
+/// The problem with a typed line and where to look for the right way
+fn describe(error: &clap::Error) -> String {
+    format!("{}\n   Type `help` for the commands", problem(error))
+}
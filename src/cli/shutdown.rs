@@ -0,0 +1,109 @@
+//! Graceful shutdown - saving the bank when the session is interrupted
+//!
+//! Demonstrates: Signal handling on a separate thread, a global slot
+//! shared with that thread, RAII guards, process exit codes
+//!
+//! Ctrl+C (SIGINT) or SIGTERM while the menu is running would otherwise
+//! end the process on the spot. While `BankCLI::run` is running, its bank
+//! and storage are registered here; the handler waits briefly for the
+//! operation in progress to let go of the bank, saves it in full with
+//! `Storage::save_bank` (for the file backends, the same atomic write as
+//! menu option 11), says goodbye and exits with status 130.
+
+use std::process;
+use std::sync::{Arc, Mutex, Once, PoisonError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::bank::SharedBank;
+use crate::persistence::Storage;
+use super::i18n::Msg;
+use super::utils::prompt_text;
+
+/// Exit status after an interrupt (128 + SIGINT, as shells report it)
+pub const INTERRUPTED_EXIT_CODE: i32 = 130;
+
+/// How long the handler waits for a running operation to release the bank
+const SAVE_WAIT: Duration = Duration::from_secs(2);
+
+/// The session the handler saves, if one is running
+static SESSION: Mutex<Option<Interruptible>> = Mutex::new(None);
+
+/// The handler is process-wide and can only be installed once
+static INSTALL: Once = Once::new();
+
+/// What an interrupt saves
+struct Interruptible {
+    bank: SharedBank,
+    storage: Arc<dyn Storage>,
+    read_only: bool,
+}
+
+/// Keeps the session registered for saving on an interrupt; dropping it
+/// unregisters the session
+pub(super) struct InterruptGuard(());
+
+impl Drop for InterruptGuard {
+    fn drop(&mut self) {
+        *SESSION.lock().unwrap_or_else(PoisonError::into_inner) = None;
+    }
+}
+
+/// Saves `bank` to `storage` and exits if the process is interrupted
+/// before the returned guard is dropped
+///
+/// The signal handler is installed on first use. If the program embedding
+/// the CLI has installed its own, that one is kept and a warning printed.
+pub(super) fn save_on_interrupt(bank: &SharedBank, storage: &Arc<dyn Storage>, read_only: bool) -> InterruptGuard {
+    INSTALL.call_once(|| {
+        if let Err(e) = ctrlc::set_handler(on_interrupt) {
+            eprintln!("{}", Msg::InterruptNotSaved.fill(&[&e]));
+        }
+    });
+    let session = Interruptible { bank: bank.clone(), storage: Arc::clone(storage), read_only };
+    *SESSION.lock().unwrap_or_else(PoisonError::into_inner) = Some(session);
+    InterruptGuard(())
+}
+
+/// Runs on the handler thread when SIGINT or SIGTERM arrives
+///
+/// Messages go to stderr; stdout may be carrying JSON results.
+fn on_interrupt() {
+    let session = SESSION.lock().unwrap_or_else(PoisonError::into_inner).take();
+    let Some(session) = session else {
+        // Not in the menu loop: nothing to save
+        process::exit(INTERRUPTED_EXIT_CODE);
+    };
+
+    eprintln!("{}", prompt_text(Msg::Interrupted.text()));
+    let farewell = session.save();
+    eprintln!("{}", prompt_text(Msg::ThankYou.text()));
+    eprint!("{}", prompt_text(&farewell));
+    process::exit(INTERRUPTED_EXIT_CODE);
+}
+
+impl Interruptible {
+    /// Saves the bank unless read-only or already saved; returns the goodbye
+    /// to print
+    fn save(&self) -> String {
+        if self.read_only {
+            return Msg::ReadOnlyGoodbye.text().to_string();
+        }
+        let deadline = Instant::now() + SAVE_WAIT;
+        let bank = loop {
+            match self.bank.try_read() {
+                Some(bank) => break bank,
+                None if Instant::now() < deadline => thread::sleep(Duration::from_millis(20)),
+                None => return Msg::CouldNotSave.fill(&[&Msg::BankBusy]),
+            }
+        };
+        if !bank.has_unsaved_changes() {
+            // The data file already holds everything
+            return Msg::SavedGoodbye.text().to_string();
+        }
+        match self.storage.save_bank(&bank) {
+            Ok(()) => Msg::SavedGoodbye.text().to_string(),
+            Err(e) => Msg::CouldNotSave.fill(&[&e]),
+        }
+    }
+}
@@ -0,0 +1,133 @@
+//! Startup settings - the config file plus per-run overrides
+//!
+//! Demonstrates: Flattening a group of clap arguments into the top-level
+//! parser, layering settings by precedence
+//! https://docs.rs/clap/latest/clap/_derive/index.html#flattening
+//!
+//! Each setting is taken from the first of these that gives it:
+//!
+//! 1. A flag (`--data-file`, `--bank-name`, `--locale`, `--theme`,
+//!    `--no-color`, `--ascii`, `--arrow-menu`, `--read-only`)
+//! 2. The `BANK_DATA_FILE` environment variable (data file only)
+//! 3. The config file (see the `config` module)
+//! 4. The built-in default
+//!
+//! so one binary can work with several banks, e.g.
+//! `banking-cli --data-file campus.json --bank-name "Campus Credit Union"`.
+
+use std::env;
+use std::path::PathBuf;
+
+use crate::config::Config;
+use crate::errors::BankResult;
+use crate::persistence::{ReadOnlyStorage, Storage};
+
+/// Environment variable naming the data file to use
+pub const DATA_FILE_ENV: &str = "BANK_DATA_FILE";
+
+/// Flags that choose the config file and override its settings
+#[derive(Debug, Clone, Default, clap::Args)]
+pub struct StartupArgs {
+    /// Read settings from this file instead of the default locations
+    #[arg(long, global = true, value_name = "FILE")]
+    pub config: Option<PathBuf>,
+
+    /// Data file to use (overrides BANK_DATA_FILE and the config file)
+    #[arg(long, global = true, value_name = "FILE")]
+    pub data_file: Option<String>,
+
+    /// Name given to the bank if the data file does not exist yet
+    #[arg(long, global = true, value_name = "NAME")]
+    pub bank_name: Option<String>,
+
+    /// Language and region for the menu, amounts and dates, e.g. `es-ES`
+    #[arg(long, global = true, value_name = "LOCALE")]
+    pub locale: Option<String>,
+
+    /// Colors for the text output: `default` or `high-contrast`
+    #[arg(long, global = true, value_name = "THEME")]
+    pub theme: Option<String>,
+
+    /// Print text without colors, e.g. when piping it to another program
+    #[arg(long, global = true)]
+    pub no_color: bool,
+
+    /// Print plain ASCII instead of emoji and box-drawing characters
+    #[arg(long, global = true)]
+    pub ascii: bool,
+
+    /// Pick menu entries with the arrow keys; Esc cancels an operation
+    #[arg(long, global = true)]
+    pub arrow_menu: bool,
+
+    /// Show detailed summaries in the menu's listings instead of tables
+    #[arg(long, global = true)]
+    pub verbose: bool,
+
+    /// Open the data file without ever writing to it
+    #[arg(long, global = true)]
+    pub read_only: bool,
+
+    /// Parse transaction histories only when they are used, for large files
+    #[arg(long, global = true)]
+    pub lazy_load: bool,
+
+    /// Keep at most this many transactions per account in memory, moving
+    /// older ones to the archive
+    #[arg(long, global = true, value_name = "N")]
+    pub history_cap: Option<usize>,
+}
+
+impl StartupArgs {
+    /// Loads the config file and applies the environment and the flags
+    ///
+    /// # Returns
+    /// * `Err(BankError)` - If the config file cannot be loaded or a
+    ///   resulting setting is invalid
+    pub fn load(&self) -> BankResult<Config> {
+        let config = Config::discover(self.config.as_deref())?;
+        self.apply(config, env::var(DATA_FILE_ENV).ok())
+    }
+
+    /// Applies `data_file_env` (the value of `BANK_DATA_FILE`, if set) and
+    /// then the flags on top of `config`, and validates the result
+    ///
+    /// An empty `BANK_DATA_FILE` counts as unset.
+    pub fn apply(&self, mut config: Config, data_file_env: Option<String>) -> BankResult<Config> {
+        if let Some(path) = data_file_env.filter(|path| !path.trim().is_empty()) {
+            config.data_file = path;
+        }
+        if let Some(path) = &self.data_file {
+            config.data_file = path.clone();
+        }
+        if let Some(name) = &self.bank_name {
+            config.bank_name = name.trim().to_string();
+        }
+        if let Some(locale) = &self.locale {
+            config.locale = locale.trim().to_string();
+        }
+        if let Some(theme) = &self.theme {
+            config.theme = theme.trim().to_string();
+        }
+        config.color &= !self.no_color;
+        config.unicode &= !self.ascii;
+        config.arrow_menu |= self.arrow_menu;
+        config.verbose |= self.verbose;
+        config.read_only |= self.read_only;
+        config.lazy_load |= self.lazy_load;
+        if let Some(cap) = self.history_cap {
+            config.history_cap = Some(cap);
+        }
+        config.validate()?;
+        Ok(config)
+    }
+}
+
+/// Wraps `storage` in a `ReadOnlyStorage` when the config asks for it
+pub(crate) fn guard_storage(storage: Box<dyn Storage>, config: &Config) -> Box<dyn Storage> {
+    if config.read_only {
+        Box::new(ReadOnlyStorage::new(storage))
+    } else {
+        storage
+    }
+}
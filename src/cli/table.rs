@@ -0,0 +1,585 @@
+//! Tables - listings laid out in aligned columns
+//!
+//! Demonstrates: A small builder, measuring text in characters rather
+//! than bytes, a trait with an associated constant shared by two enums
+//! https://doc.rust-lang.org/reference/items/associated-items.html#associated-constants
+//!
+//! The customer list, customer search and transaction history print a
+//! `Table`: a header, one aligned row per item, and a totals row. Cells
+//! longer than their column's limit are cut short with `…`. Which columns
+//! are shown comes from the config (`customer_columns`,
+//! `transaction_columns`), installed with `set_columns` at startup.
+//!
+//! ```
+//! use rust_banking_system::cli::table::{Align, Table};
+//!
+//! let mut table = Table::new().column("Name", Align::Left, Some(8)).column("Balance", Align::Right, None);
+//! table.row(vec!["Alice".to_string(), "$1,250.00".to_string()]);
+//! table.row(vec!["Bartholomew".to_string(), "$3.10".to_string()]);
+//! table.totals(vec!["Total".to_string(), "$1,253.10".to_string()]);
+//! assert_eq!(table.render().lines().nth(2).unwrap(), "Alice     $1,250.00");
+//! assert_eq!(table.render().lines().nth(3).unwrap(), "Barthol…      $3.10");
+//! ```
+
+use std::fmt;
+use std::str::FromStr;
+use std::sync::{LazyLock, PoisonError, RwLock};
+
+use crate::bank::{CustomerImport, LedgerEntry};
+use crate::format;
+use crate::models::{Customer, Money, Transaction};
+use crate::traits::Auditable;
+
+/// Space between two columns
+const GAP: &str = "  ";
+
+/// Columns shown until `set_columns` is called
+static COLUMNS: LazyLock<RwLock<(Vec<CustomerColumn>, Vec<TransactionColumn>)>> = LazyLock::new(|| {
+    RwLock::new((CustomerColumn::DEFAULT.to_vec(), TransactionColumn::DEFAULT.to_vec()))
+});
+
+/// Which side of its column a value sits on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Align {
+    Left,
+    /// For amounts and counts, so the digits line up
+    Right,
+}
+
+#[derive(Debug, Clone)]
+struct Column {
+    header: String,
+    align: Align,
+    max_width: Option<usize>,
+}
+
+/// Rows of text laid out in aligned columns
+#[derive(Debug, Clone, Default)]
+pub struct Table {
+    columns: Vec<Column>,
+    rows: Vec<Vec<String>>,
+    totals: Option<Vec<String>>,
+}
+
+impl Table {
+    /// Creates a table without columns
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a column; longer cells are cut to `max_width` characters
+    pub fn column(mut self, header: &str, align: Align, max_width: Option<usize>) -> Self {
+        self.columns.push(Column { header: header.to_string(), align, max_width });
+        self
+    }
+
+    /// Adds a row; missing cells are left blank and extra ones ignored
+    pub fn row(&mut self, cells: Vec<String>) {
+        self.rows.push(cells);
+    }
+
+    /// Sets the totals row printed under the other rows
+    pub fn totals(&mut self, cells: Vec<String>) {
+        self.totals = Some(cells);
+    }
+
+    /// Whether there are no rows
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    /// The table as text: the header, a rule, the rows and, if set, a
+    /// rule and the totals row, each line ending in a newline
+    pub fn render(&self) -> String {
+        let fitted = |cells: &[String]| -> Vec<String> {
+            self.columns
+                .iter()
+                .enumerate()
+                .map(|(i, column)| truncate(cells.get(i).map_or("", String::as_str), column.max_width))
+                .collect()
+        };
+        let headers: Vec<String> = self.columns.iter().map(|column| column.header.clone()).collect();
+        let rows: Vec<Vec<String>> = self.rows.iter().map(|cells| fitted(cells)).collect();
+        let totals = self.totals.as_deref().map(fitted);
+
+        let widths: Vec<usize> = (0..self.columns.len())
+            .map(|i| {
+                std::iter::once(&headers)
+                    .chain(&rows)
+                    .chain(&totals)
+                    .map(|cells| cells[i].chars().count())
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect();
+        let rule = "─".repeat(widths.iter().sum::<usize>() + GAP.len() * widths.len().saturating_sub(1));
+
+        let mut out = self.line(&headers, &widths);
+        out.push_str(&rule);
+        out.push('\n');
+        for cells in &rows {
+            out.push_str(&self.line(cells, &widths));
+        }
+        if let Some(totals) = &totals {
+            out.push_str(&rule);
+            out.push('\n');
+            out.push_str(&self.line(totals, &widths));
+        }
+        out
+    }
+
+    fn line(&self, cells: &[String], widths: &[usize]) -> String {
+        let padded: Vec<String> = self
+            .columns
+            .iter()
+            .zip(cells.iter().zip(widths))
+            .map(|(column, (cell, &width))| match column.align {
+                Align::Left => format!("{:<width$}", cell, width = width),
+                Align::Right => format!("{:>width$}", cell, width = width),
+            })
+            .collect();
+        format!("{}\n", padded.join(GAP).trim_end())
+    }
+}
+
+/// `text` cut to `max_width` characters, ending in `…` if anything was cut
+fn truncate(text: &str, max_width: Option<usize>) -> String {
+    match max_width {
+        Some(max) if max > 0 && text.chars().count() > max => {
+            let mut cut: String = text.chars().take(max - 1).collect();
+            cut.push('…');
+            cut
+        }
+        _ => text.to_string(),
+    }
+}
+
+/// A set of columns a listing can show
+pub trait ColumnSet: Copy + Sized + 'static {
+    /// Every column, in the order they are listed to users
+    const ALL: &'static [Self];
+
+    /// The name used in config files
+    fn name(self) -> &'static str;
+}
+
+/// Parses a list of column names, rejecting unknown names, repeats and an
+/// empty list
+pub fn parse_columns<C: ColumnSet + PartialEq>(names: &[String]) -> Result<Vec<C>, String> {
+    if names.is_empty() {
+        return Err("must name at least one column".to_string());
+    }
+    let mut columns = Vec::with_capacity(names.len());
+    for name in names {
+        let column = C::ALL
+            .iter()
+            .copied()
+            .find(|column| column.name().eq_ignore_ascii_case(name.trim()))
+            .ok_or_else(|| {
+                let known: Vec<&str> = C::ALL.iter().map(|column| column.name()).collect();
+                format!("unknown column '{}' (expected any of: {})", name, known.join(", "))
+            })?;
+        if columns.contains(&column) {
+            return Err(format!("column '{}' is listed twice", column.name()));
+        }
+        columns.push(column);
+    }
+    Ok(columns)
+}
+
+/// A column of the customer list and customer search
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CustomerColumn {
+    Id,
+    Name,
+    Email,
+    Status,
+    /// The account number, or the start of the account ID for older accounts
+    Account,
+    Type,
+    Balance,
+    Transactions,
+    Registered,
+}
+
+impl CustomerColumn {
+    /// Columns shown unless the config picks others
+    pub const DEFAULT: [CustomerColumn; 6] = [
+        CustomerColumn::Id,
+        CustomerColumn::Name,
+        CustomerColumn::Status,
+        CustomerColumn::Account,
+        CustomerColumn::Type,
+        CustomerColumn::Balance,
+    ];
+
+    fn header(self) -> &'static str {
+        match self {
+            CustomerColumn::Id => "ID",
+            CustomerColumn::Name => "Name",
+            CustomerColumn::Email => "Email",
+            CustomerColumn::Status => "Status",
+            CustomerColumn::Account => "Account",
+            CustomerColumn::Type => "Type",
+            CustomerColumn::Balance => "Balance",
+            CustomerColumn::Transactions => "Txns",
+            CustomerColumn::Registered => "Registered",
+        }
+    }
+
+    fn align(self) -> Align {
+        match self {
+            CustomerColumn::Balance | CustomerColumn::Transactions => Align::Right,
+            _ => Align::Left,
+        }
+    }
+
+    fn max_width(self) -> Option<usize> {
+        match self {
+            CustomerColumn::Name => Some(24),
+            CustomerColumn::Email => Some(28),
+            _ => None,
+        }
+    }
+
+    /// What this column shows for `customer`; blank where there is no account
+    pub fn cell(self, customer: &Customer) -> String {
+        let account = customer.account.as_ref();
+        match self {
+            CustomerColumn::Id => customer.id.short().to_string(),
+            CustomerColumn::Name => customer.name.clone(),
+            CustomerColumn::Email => customer.email.clone(),
+            CustomerColumn::Status => customer.status.to_string(),
+            CustomerColumn::Account => account
+                .map(|account| account.number.map_or_else(|| account.id.short().to_string(), |n| n.to_string()))
+                .unwrap_or_default(),
+            CustomerColumn::Type => account.map(|account| account.account_type.to_string()).unwrap_or_default(),
+            CustomerColumn::Balance => account.map(|account| account.balance.display().to_string()).unwrap_or_default(),
+            CustomerColumn::Transactions => {
+                account.map(|account| account.transactions.len().to_string()).unwrap_or_default()
+            }
+            CustomerColumn::Registered => format::date(&customer.registered_at),
+        }
+    }
+}
+
+impl ColumnSet for CustomerColumn {
+    const ALL: &'static [Self] = &[
+        CustomerColumn::Id,
+        CustomerColumn::Name,
+        CustomerColumn::Email,
+        CustomerColumn::Status,
+        CustomerColumn::Account,
+        CustomerColumn::Type,
+        CustomerColumn::Balance,
+        CustomerColumn::Transactions,
+        CustomerColumn::Registered,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            CustomerColumn::Id => "id",
+            CustomerColumn::Name => "name",
+            CustomerColumn::Email => "email",
+            CustomerColumn::Status => "status",
+            CustomerColumn::Account => "account",
+            CustomerColumn::Type => "type",
+            CustomerColumn::Balance => "balance",
+            CustomerColumn::Transactions => "transactions",
+            CustomerColumn::Registered => "registered",
+        }
+    }
+}
+
+/// A column of the transaction history
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransactionColumn {
+    Date,
+    Type,
+    /// Negative for money leaving the account
+    Amount,
+    Balance,
+    Description,
+    Category,
+    Tags,
+    Status,
+    /// Who was on the other side of a transfer
+    Counterparty,
+    Id,
+}
+
+impl TransactionColumn {
+    /// Columns shown unless the config picks others
+    pub const DEFAULT: [TransactionColumn; 6] = [
+        TransactionColumn::Date,
+        TransactionColumn::Type,
+        TransactionColumn::Amount,
+        TransactionColumn::Balance,
+        TransactionColumn::Description,
+        TransactionColumn::Counterparty,
+    ];
+
+    fn header(self) -> &'static str {
+        match self {
+            TransactionColumn::Date => "Date",
+            TransactionColumn::Type => "Type",
+            TransactionColumn::Amount => "Amount",
+            TransactionColumn::Balance => "Balance",
+            TransactionColumn::Description => "Description",
+            TransactionColumn::Category => "Category",
+            TransactionColumn::Tags => "Tags",
+            TransactionColumn::Status => "Status",
+            TransactionColumn::Counterparty => "Counterparty",
+            TransactionColumn::Id => "ID",
+        }
+    }
+
+    fn align(self) -> Align {
+        match self {
+            TransactionColumn::Amount | TransactionColumn::Balance => Align::Right,
+            _ => Align::Left,
+        }
+    }
+
+    fn max_width(self) -> Option<usize> {
+        match self {
+            TransactionColumn::Type | TransactionColumn::Description | TransactionColumn::Counterparty => Some(24),
+            TransactionColumn::Tags => Some(20),
+            _ => None,
+        }
+    }
+
+    /// What this column shows for `transaction`
+    ///
+    /// The counterparty is the start of the other account's ID; callers
+    /// that can look up its owner pass a nicer name to `transaction_table`.
+    pub fn cell(self, transaction: &Transaction) -> String {
+        match self {
+            TransactionColumn::Date => format::datetime(&transaction.timestamp),
+            TransactionColumn::Type => transaction.transaction_type.to_string(),
+            TransactionColumn::Amount => transaction.signed_amount().display().to_string(),
+            TransactionColumn::Balance => transaction.balance_after.display().to_string(),
+            TransactionColumn::Description => transaction.description.clone().unwrap_or_default(),
+            TransactionColumn::Category => transaction.category.clone().unwrap_or_default(),
+            TransactionColumn::Tags => {
+                transaction.tags.iter().map(|tag| format!("#{}", tag)).collect::<Vec<_>>().join(" ")
+            }
+            TransactionColumn::Status => transaction.status.to_string(),
+            TransactionColumn::Counterparty => transaction
+                .transaction_type
+                .counterparty()
+                .map(|id| id.get(..8).unwrap_or(id).to_string())
+                .unwrap_or_default(),
+            TransactionColumn::Id => transaction.id.short().to_string(),
+        }
+    }
+}
+
+impl ColumnSet for TransactionColumn {
+    const ALL: &'static [Self] = &[
+        TransactionColumn::Date,
+        TransactionColumn::Type,
+        TransactionColumn::Amount,
+        TransactionColumn::Balance,
+        TransactionColumn::Description,
+        TransactionColumn::Category,
+        TransactionColumn::Tags,
+        TransactionColumn::Status,
+        TransactionColumn::Counterparty,
+        TransactionColumn::Id,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            TransactionColumn::Date => "date",
+            TransactionColumn::Type => "type",
+            TransactionColumn::Amount => "amount",
+            TransactionColumn::Balance => "balance",
+            TransactionColumn::Description => "description",
+            TransactionColumn::Category => "category",
+            TransactionColumn::Tags => "tags",
+            TransactionColumn::Status => "status",
+            TransactionColumn::Counterparty => "counterparty",
+            TransactionColumn::Id => "id",
+        }
+    }
+}
+
+macro_rules! column_traits {
+    ($($column:ty),*) => {$(
+        impl FromStr for $column {
+            type Err = String;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                parse_columns(&[s.to_string()]).map(|columns| columns[0])
+            }
+        }
+
+        impl fmt::Display for $column {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str(self.name())
+            }
+        }
+    )*};
+}
+
+column_traits!(CustomerColumn, TransactionColumn);
+
+/// A table of `customers` with the installed customer columns and a row
+/// totalling their balances
+pub fn customer_table(customers: &[&Customer]) -> Table {
+    let columns = customer_columns();
+    let mut table = Table::new();
+    for column in &columns {
+        table = table.column(column.header(), column.align(), column.max_width());
+    }
+    for customer in customers {
+        table.row(columns.iter().map(|column| column.cell(customer)).collect());
+    }
+
+    let total: Money = customers
+        .iter()
+        .filter_map(|customer| customer.account.as_ref())
+        .map(|account| account.balance)
+        .sum();
+    table.totals(totals_row(&columns, customers.len(), |column| match column {
+        CustomerColumn::Balance => Some(total.display().to_string()),
+        _ => None,
+    }));
+    table
+}
+
+/// A table of `transactions` with the installed transaction columns and a
+/// row with their net amount
+///
+/// `counterparty` may name the other side of a transfer, e.g. as
+/// `Bob (1000002)`; when it returns `None` the start of the account ID is
+/// shown.
+pub fn transaction_table(
+    transactions: &[&Transaction],
+    counterparty: impl Fn(&Transaction) -> Option<String>,
+) -> Table {
+    let columns = transaction_columns();
+    let mut table = Table::new();
+    for column in &columns {
+        table = table.column(column.header(), column.align(), column.max_width());
+    }
+    for transaction in transactions {
+        table.row(
+            columns
+                .iter()
+                .map(|column| match column {
+                    TransactionColumn::Counterparty => {
+                        counterparty(transaction).unwrap_or_else(|| column.cell(transaction))
+                    }
+                    _ => column.cell(transaction),
+                })
+                .collect(),
+        );
+    }
+
+    let net: Money = transactions.iter().map(|transaction| transaction.signed_amount()).sum();
+    table.totals(totals_row(&columns, transactions.len(), |column| match column {
+        TransactionColumn::Amount => Some(net.display().to_string()),
+        _ => None,
+    }));
+    table
+}
+
+/// A table of ledger entries, naming each entry's account with `account`
+/// (e.g. `Alice (1000001)`), with a row of their net amount
+pub fn ledger_table(entries: &[LedgerEntry], account: impl Fn(&LedgerEntry) -> String) -> Table {
+    let mut table = Table::new()
+        .column("Date", Align::Left, None)
+        .column("Account", Align::Left, Some(24))
+        .column("Type", Align::Left, Some(24))
+        .column("Amount", Align::Right, None)
+        .column("ID", Align::Left, None);
+    for entry in entries {
+        let transaction = entry.transaction;
+        table.row(vec![
+            TransactionColumn::Date.cell(transaction),
+            account(entry),
+            TransactionColumn::Type.cell(transaction),
+            TransactionColumn::Amount.cell(transaction),
+            TransactionColumn::Id.cell(transaction),
+        ]);
+    }
+
+    let net: Money = entries.iter().map(|entry| entry.transaction.signed_amount()).sum();
+    table.totals(vec![
+        String::new(),
+        format!("Total ({})", entries.len()),
+        String::new(),
+        net.display().to_string(),
+        String::new(),
+    ]);
+    table
+}
+
+/// A table of the rows of a customer import, one per line of the file,
+/// with a row of the imported count and their opening deposits
+pub fn import_table(import: &CustomerImport) -> Table {
+    let mut table = Table::new()
+        .column("Line", Align::Right, None)
+        .column("Name", Align::Left, Some(24))
+        .column("Email", Align::Left, Some(32))
+        .column("Deposit", Align::Right, None)
+        .column("Result", Align::Left, Some(48));
+    for row in &import.rows {
+        table.row(vec![
+            row.line.to_string(),
+            row.name.clone(),
+            row.email.clone(),
+            row.initial_deposit.map(|amount| amount.display().to_string()).unwrap_or_default(),
+            match &row.result {
+                Ok(_) => "✅ Imported".to_string(),
+                Err(e) => format!("❌ {}", e),
+            },
+        ]);
+    }
+
+    table.totals(vec![
+        String::new(),
+        format!("Imported ({} of {})", import.imported(), import.rows.len()),
+        String::new(),
+        import.total_deposited().display().to_string(),
+        String::new(),
+    ]);
+    table
+}
+
+/// A table of an audited record's facts, one per row
+pub fn audit_table(item: &dyn Auditable) -> Table {
+    let mut table = Table::new().column("Fact", Align::Left, None).column("Value", Align::Left, Some(64));
+    for fact in item.audit_facts() {
+        table.row(vec![fact.key.to_string(), fact.value]);
+    }
+    table
+}
+
+/// A totals row: `Total (n)` in the first free column and `value` for
+/// the columns it covers
+fn totals_row<C: ColumnSet>(columns: &[C], count: usize, value: impl Fn(C) -> Option<String>) -> Vec<String> {
+    let mut cells: Vec<String> = columns.iter().map(|&column| value(column).unwrap_or_default()).collect();
+    if let Some(label) = cells.iter_mut().find(|cell| cell.is_empty()) {
+        *label = format!("Total ({})", count);
+    }
+    cells
+}
+
+/// Makes these the columns shown from now on by `customer_table` and
+/// `transaction_table`
+pub fn set_columns(customers: Vec<CustomerColumn>, transactions: Vec<TransactionColumn>) {
+    *COLUMNS.write().unwrap_or_else(PoisonError::into_inner) = (customers, transactions);
+}
+
+/// The customer columns currently installed
+pub fn customer_columns() -> Vec<CustomerColumn> {
+    COLUMNS.read().unwrap_or_else(PoisonError::into_inner).0.clone()
+}
+
+/// The transaction columns currently installed
+pub fn transaction_columns() -> Vec<TransactionColumn> {
+    COLUMNS.read().unwrap_or_else(PoisonError::into_inner).1.clone()
+}
@@ -0,0 +1,154 @@
+//! Term deposit CLI operations
+//!
+//! Demonstrates: One screen whose actions depend on the user's role
+
+use std::io;
+
+use chrono::Utc;
+use serde_json::json;
+
+use crate::bank::SharedBank;
+use crate::models::{CustomerId, TermDeposit, TermDepositStatus};
+use crate::validation;
+use super::i18n::Msg;
+use super::render::Renderer;
+use super::session::{authorize, Session, ANY_ROLE, STAFF};
+use super::utils::{read_amount, read_checked, read_choice, read_input, read_parsed, read_yes};
+
+/// Lists a customer's term deposits and opens (staff only) or withdraws
+/// them early; customers see and withdraw their own
+pub fn manage_term_deposits(
+    bank: &SharedBank,
+    session: Option<&Session>,
+    renderer: &dyn Renderer,
+) -> io::Result<()> {
+    let session = match authorize(session, ANY_ROLE, renderer) {
+        Some(session) => session,
+        None => return Ok(()),
+    };
+
+    renderer.section(Msg::TermDeposits.text());
+
+    let customer_id = match session.target_customer(bank, Msg::EnterCustomer.text(), renderer)? {
+        Some(id) => id,
+        None => return Ok(()),
+    };
+
+    loop {
+        let deposits = match bank.read().term_deposits_for(&customer_id) {
+            Ok(deposits) => deposits.to_vec(),
+            Err(e) => {
+                renderer.failure(&e);
+                return Ok(());
+            }
+        };
+        show_deposits(&deposits, renderer);
+
+        let staff = session.allows(STAFF);
+        if staff {
+            renderer.chrome(Msg::TermActionsStaff.text());
+        } else {
+            renderer.chrome(Msg::TermActions.text());
+        }
+        match read_input(Msg::EnterAction.text())?.as_str() {
+            "" => return Ok(()),
+            "1" if staff => open_deposit(bank, &customer_id, renderer)?,
+            "2" => withdraw_early(bank, &customer_id, &deposits, renderer)?,
+            _ => renderer.failure(&Msg::InvalidChoice),
+        }
+    }
+}
+
+/// Pays back the term deposits that have matured, reporting each
+pub fn process_due_maturities(bank: &SharedBank, renderer: &dyn Renderer) {
+    let matured = bank.write().process_maturities(Utc::now());
+    match matured {
+        Ok(deposits) => {
+            for deposit in deposits {
+                renderer.success(
+                    &Msg::TermMatured.fill(&[&&deposit.id[..8], &deposit]),
+                    json!({ "matured_term_deposit": deposit }),
+                );
+            }
+        }
+        Err(e) => renderer.failure(&Msg::CouldNotPayMaturities.fill(&[&e])),
+    }
+}
+
+/// Prompts for the amount, rate and term and opens the deposit
+fn open_deposit(bank: &SharedBank, customer_id: &CustomerId, renderer: &dyn Renderer) -> io::Result<()> {
+    let amount = read_amount(Msg::AmountToLock.text(), renderer)?;
+    let rate = read_checked(Msg::YearlyRate.text(), renderer, validation::parse_interest_rate)?;
+    let months: u32 = read_parsed(Msg::TermMonths.text(), renderer, &Msg::InvalidMonths)?;
+
+    let result = bank.write().open_term_deposit(customer_id, amount, rate, months);
+    match result {
+        Ok(deposit) => renderer.success(
+            &Msg::TermOpened.fill(&[
+                &deposit,
+                &(deposit.principal + deposit.interest_at_maturity()).display(),
+                &deposit.id,
+            ]),
+            json!(deposit),
+        ),
+        Err(e) => renderer.failure(&e),
+    }
+    Ok(())
+}
+
+/// Shows what withdrawing a chosen deposit now pays and, once confirmed,
+/// withdraws it
+fn withdraw_early(
+    bank: &SharedBank,
+    customer_id: &CustomerId,
+    deposits: &[TermDeposit],
+    renderer: &dyn Renderer,
+) -> io::Result<()> {
+    let active: Vec<&TermDeposit> = deposits.iter().filter(|d| d.status == TermDepositStatus::Active).collect();
+    if active.is_empty() {
+        renderer.failure(&Msg::NoActiveTerms);
+        return Ok(());
+    }
+    let deposit = &deposits[read_choice(Msg::ChooseTerm.text(), renderer, deposits.len())?];
+    if deposit.status != TermDepositStatus::Active {
+        renderer.failure(&Msg::TermNoLongerActive);
+        return Ok(());
+    }
+
+    let now = bank.read().now();
+    renderer.chrome(&Msg::EarlyPayout.fill(&[
+        &deposit.payout_at(now).display(),
+        &deposit.accrued_interest(now).display(),
+        &deposit.early_withdrawal_penalty().display(),
+    ]));
+    if !read_yes(Msg::ConfirmEarlyWithdrawal.text())? {
+        return Ok(());
+    }
+
+    let result = bank.write().withdraw_term_deposit(customer_id, &deposit.id);
+    match result {
+        Ok(transaction) => renderer.success(
+            &Msg::TermPaidBack.fill(&[&transaction.amount.display(), &transaction.balance_after.display()]),
+            json!(transaction),
+        ),
+        Err(e) => renderer.failure(&e),
+    }
+    Ok(())
+}
+
+/// Prints a numbered list of `deposits`
+fn show_deposits(deposits: &[TermDeposit], renderer: &dyn Renderer) {
+    if deposits.is_empty() {
+        renderer.chrome(Msg::NoTermDeposits.text());
+        return;
+    }
+    renderer.chrome("");
+    for (i, deposit) in deposits.iter().enumerate() {
+        let icon = match deposit.status {
+            TermDepositStatus::Active => "🔒",
+            TermDepositStatus::Matured => "✅",
+            TermDepositStatus::Withdrawn => "↩️ ",
+        };
+        renderer.chrome(&format!("  {}. {} {}", i + 1, icon, deposit));
+    }
+}
@@ -0,0 +1,230 @@
+//! Output themes - colors and symbols for the text renderer
+//!
+//! Demonstrates: ANSI escape codes, reading the environment to pick
+//! sensible defaults (`NO_COLOR`, whether stdout is a terminal, the
+//! locale's character set)
+//! https://no-color.org/
+//!
+//! `TextRenderer` passes everything it prints through a `TextStyle`:
+//! successes are green, errors red, section titles highlighted and
+//! decoration and metadata dim, in the colors of the chosen `Theme`.
+//! Without color (`--no-color`, `NO_COLOR`, or output piped to a file)
+//! the text is printed as is. Without Unicode (`--ascii`, or a locale that
+//! is not UTF-8) emoji and box-drawing characters are swapped for plain
+//! ASCII (see `to_ascii`).
+
+use std::env;
+use std::fmt;
+use std::io::{self, IsTerminal};
+use std::str::FromStr;
+
+use crate::config::Config;
+
+/// A set of colors for the text renderer
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Theme {
+    /// Green, red and cyan on the terminal's own background
+    #[default]
+    Default,
+    /// Bold, bright colors that stay readable on dim or washed-out screens
+    HighContrast,
+}
+
+impl Theme {
+    /// Every theme, in the order they are listed to users
+    pub const ALL: [Theme; 2] = [Theme::Default, Theme::HighContrast];
+
+    /// The name used in config files and `--theme`
+    pub fn name(self) -> &'static str {
+        match self {
+            Theme::Default => "default",
+            Theme::HighContrast => "high-contrast",
+        }
+    }
+
+    /// The ANSI SGR parameters for `tone`, e.g. `32` for green
+    pub fn sgr(self, tone: Tone) -> &'static str {
+        match (self, tone) {
+            (Theme::Default, Tone::Success) => "32",
+            (Theme::Default, Tone::Error) => "31",
+            (Theme::Default, Tone::Heading) => "1;36",
+            (Theme::Default, Tone::Dim) => "2",
+            (Theme::HighContrast, Tone::Success) => "1;92",
+            (Theme::HighContrast, Tone::Error) => "1;91",
+            (Theme::HighContrast, Tone::Heading) => "1;93",
+            // Faint text is hard to read on some screens; use light gray
+            (Theme::HighContrast, Tone::Dim) => "37",
+        }
+    }
+}
+
+impl FromStr for Theme {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Theme::ALL
+            .into_iter()
+            .find(|theme| theme.name().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| {
+                let names: Vec<&str> = Theme::ALL.iter().map(|theme| theme.name()).collect();
+                format!("unknown theme '{}' (expected one of: {})", s, names.join(", "))
+            })
+    }
+}
+
+impl fmt::Display for Theme {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// What a piece of output is, which decides its color
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tone {
+    Success,
+    Error,
+    /// Section titles
+    Heading,
+    /// Decoration and metadata
+    Dim,
+}
+
+/// How the text renderer writes its output
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TextStyle {
+    pub theme: Theme,
+
+    /// Wrap output in ANSI color codes
+    pub color: bool,
+
+    /// Keep emoji and box-drawing characters; `false` swaps them for ASCII
+    pub unicode: bool,
+}
+
+impl Default for TextStyle {
+    /// No color and full Unicode: the output exactly as written
+    fn default() -> Self {
+        Self { theme: Theme::Default, color: false, unicode: true }
+    }
+}
+
+impl TextStyle {
+    /// The style `config` asks for, toned down for the terminal: no color
+    /// when `NO_COLOR` is set or stdout is not a terminal, and ASCII when
+    /// the locale's character set is not UTF-8
+    pub fn for_terminal(config: &Config) -> Self {
+        let no_color_env = env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+        Self {
+            theme: config.theme.parse().unwrap_or_default(),
+            color: config.color && !no_color_env && io::stdout().is_terminal(),
+            unicode: config.unicode && locale_is_utf8(),
+        }
+    }
+
+    /// `text` with its symbols swapped for ASCII if this style has no
+    /// Unicode, otherwise unchanged
+    pub fn symbols(&self, text: &str) -> String {
+        if self.unicode {
+            text.to_string()
+        } else {
+            to_ascii(text)
+        }
+    }
+
+    /// `text` as it should be printed for `tone`
+    ///
+    /// Each line is colored on its own, so paging tools and a line cut off
+    /// by the terminal don't leave the color switched on.
+    pub fn paint(&self, tone: Tone, text: &str) -> String {
+        let text = self.symbols(text);
+        if !self.color {
+            return text;
+        }
+        let sgr = self.theme.sgr(tone);
+        text.split('\n')
+            .map(|line| {
+                if line.trim().is_empty() {
+                    line.to_string()
+                } else {
+                    format!("\x1b[{}m{}\x1b[0m", sgr, line)
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// Whether the environment's character set is UTF-8, judged by the first
+/// of `LC_ALL`, `LC_CTYPE` and `LANG` that is set; with none set (e.g. on
+/// Windows) it is assumed to be
+fn locale_is_utf8() -> bool {
+    ["LC_ALL", "LC_CTYPE", "LANG"]
+        .iter()
+        .filter_map(|name| env::var(name).ok())
+        .find(|value| !value.is_empty())
+        .is_none_or(|value| {
+            let value = value.to_ascii_lowercase();
+            value.contains("utf-8") || value.contains("utf8")
+        })
+}
+
+/// Replaces emoji and box-drawing characters with plain ASCII
+///
+/// Status emoji become tags (`✅` is `[ok]`, `❌` is `[x]`, `⚠️` is `[!]`),
+/// lines and arrows become their ASCII look-alikes, and other emoji are
+/// dropped with the space after them, or turned into spaces where they are
+/// padded for alignment (as in the banner). Letters such as `é` are kept.
+pub fn to_ascii(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        let replacement = match c {
+            '✅' => "[ok]",
+            '❌' => "[x]",
+            '⚠' => "[!]",
+            '─' | '━' => "-",
+            '═' => "=",
+            '│' | '║' => "|",
+            '╔' | '╗' | '╚' | '╝' => "+",
+            '•' => "*",
+            '…' => "...",
+            '→' | '↳' => "->",
+            '↑' => "^",
+            '↓' => "v",
+            '▶' => ">",
+            '█' => "#",
+            '░' => ".",
+            c if is_emoji(c) => {
+                // Skip any selector or joiner; then, if the emoji was padded
+                // for alignment, keep its two columns as spaces, otherwise
+                // drop it together with the space after it
+                while chars.next_if(|&next| is_emoji(next)).is_some() {}
+                let mut spaces = 0;
+                while chars.next_if_eq(&' ').is_some() {
+                    spaces += 1;
+                }
+                if spaces > 1 {
+                    out.push_str(&" ".repeat(spaces + 2));
+                }
+                continue;
+            }
+            c => {
+                out.push(c);
+                continue;
+            }
+        };
+        out.push_str(replacement);
+        // A variation selector after a replaced symbol has nothing to select
+        chars.next_if_eq(&'\u{fe0f}');
+    }
+    out
+}
+
+/// Emoji, pictographs and the invisible characters that modify them
+fn is_emoji(c: char) -> bool {
+    matches!(
+        c as u32,
+        0x1F000..=0x1FAFF | 0x2190..=0x21FF | 0x2300..=0x23FF | 0x2600..=0x27BF | 0x2B00..=0x2BFF
+            | 0xFE0F | 0x200D | 0x20E3
+    )
+}
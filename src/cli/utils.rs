@@ -1,8 +1,150 @@
 //! CLI utility functions
 //!
-//! Demonstrates: Helper functions, I/O operations
+//! Demonstrates: Helper functions, I/O operations, carrying a custom error
+//! inside `io::Error` so `?` can unwind a whole operation
+//!
+//! Every prompt goes through `read_input`. Typing `:q` there (or pressing
+//! `Esc` with the arrow-key menu) returns an `io::Error` holding
+//! `Cancelled`; operations pass it up with `?` and the menu loop reports
+//! it and shows the menu again. Prompts that need a valid value
+//! (`read_checked`, `read_amount`, `read_uuid`, `read_choice`, ...) say
+//! what was wrong and ask again, so a typo no longer throws away
+//! everything typed before it; after `MAX_ATTEMPTS` invalid answers they
+//! give up on the operation as if it had been cancelled.
+//!
+//! A bug that panics in the middle of an operation is caught by
+//! `isolate` so the menu comes back instead of the program ending.
+
+use std::any::Any;
+use std::error::Error;
+use std::fmt;
+use std::io::{self, IsTerminal, Write};
+use std::panic::{self, AssertUnwindSafe};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use uuid::Uuid;
+
+use crate::bank::{PrefixMatch, SharedBank};
+use crate::errors::{BankError, BankResult};
+use crate::models::{CustomerId, Money};
+use crate::traits::Summarizable;
+use super::i18n::Msg;
+use super::render::Renderer;
+use super::theme;
+
+/// Typed at any prompt to abandon the current operation
+pub const CANCEL_INPUT: &str = ":q";
+
+/// How many invalid answers a prompt takes before giving up on the
+/// operation
+pub const MAX_ATTEMPTS: usize = 3;
+
+/// Rows per page in paginated listings
+pub const PAGE_SIZE: usize = 10;
+
+/// When set, prompts go to stderr so stdout only carries results
+static PROMPTS_TO_STDERR: AtomicBool = AtomicBool::new(false);
+
+/// When set, emoji in prompts are swapped for ASCII (see `theme::to_ascii`)
+static ASCII_PROMPTS: AtomicBool = AtomicBool::new(false);
+
+/// When set, views show detailed summaries instead of compact ones and
+/// tables (see `Summarizable::detailed_summary`)
+static VERBOSE: AtomicBool = AtomicBool::new(false);
+
+/// When set, prompts are read in raw mode so `Esc` can cancel (used by the
+/// arrow-key menu)
+static RAW_PROMPTS: AtomicBool = AtomicBool::new(false);
+
+/// The error inside the `io::Error` returned by a prompt the user backed
+/// out of; the menu loop reports it and goes back to the menu
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cancelled;
+
+impl fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("cancelled")
+    }
+}
+
+impl Error for Cancelled {}
+
+/// Whether `error` means the user cancelled the operation
+pub fn is_cancelled(error: &io::Error) -> bool {
+    error.get_ref().is_some_and(|inner| inner.is::<Cancelled>())
+}
+
+/// Runs `operation`, turning a panic inside it into an error
+///
+/// The panic is still reported by the panic hook (on stderr); the caller
+/// gets its message and carries on. Locks held by the operation recover
+/// from the poisoning (see `SharedBank::write`), so the bank stays usable.
+///
+/// # Errors
+/// The panic's message, if `operation` panicked
+pub fn isolate<T>(operation: impl FnOnce() -> T) -> Result<T, String> {
+    panic::catch_unwind(AssertUnwindSafe(operation)).map_err(|payload| panic_message(payload.as_ref()))
+}
+
+/// The text passed to `panic!`, if it was a string
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    match (payload.downcast_ref::<&str>(), payload.downcast_ref::<String>()) {
+        (Some(message), _) => message.to_string(),
+        (_, Some(message)) => message.clone(),
+        _ => "unknown panic".to_string(),
+    }
+}
+
+/// Routes input prompts to stderr (used by the JSON output mode)
+pub fn set_prompts_to_stderr(enabled: bool) {
+    PROMPTS_TO_STDERR.store(enabled, Ordering::Relaxed);
+}
+
+/// Writes prompts in plain ASCII (used by the text renderer's `--ascii` mode)
+pub fn set_ascii_prompts(enabled: bool) {
+    ASCII_PROMPTS.store(enabled, Ordering::Relaxed);
+}
+
+/// Reads prompts in raw mode, where `Esc` cancels the operation; needs the
+/// `arrow-menu` feature and is ignored without it
+pub fn set_raw_prompts(enabled: bool) {
+    RAW_PROMPTS.store(enabled && cfg!(feature = "arrow-menu"), Ordering::Relaxed);
+}
+
+/// Shows detailed summaries in the menu's views (used by `--verbose`)
+pub fn set_verbose(enabled: bool) {
+    VERBOSE.store(enabled, Ordering::Relaxed);
+}
 
-use std::io::{self, Write};
+/// Whether views show detailed summaries
+pub(super) fn verbose() -> bool {
+    VERBOSE.load(Ordering::Relaxed)
+}
+
+/// `item`'s detailed summary in verbose mode, its compact one otherwise
+pub(super) fn summarize(item: &impl Summarizable) -> String {
+    if verbose() {
+        item.detailed_summary()
+    } else {
+        item.summary()
+    }
+}
+
+/// Each item's detailed summary, separated by blank lines
+pub(super) fn detailed_list<T: Summarizable>(items: &[&T]) -> String {
+    let summaries: Vec<String> = items.iter().map(|item| item.detailed_summary()).collect();
+    format!("{}\n", summaries.join("\n\n"))
+}
+
+/// `prompt` as it should be shown
+pub(super) fn prompt_text(prompt: &str) -> String {
+    if ASCII_PROMPTS.load(Ordering::Relaxed) {
+        theme::to_ascii(prompt)
+    } else {
+        prompt.to_string()
+    }
+}
 
 /// Helper function to read input from stdin
 ///
@@ -13,12 +155,271 @@ use std::io::{self, Write};
 ///
 /// # Returns
 /// The trimmed user input as a String
+///
+/// # Errors
+/// * `Cancelled` (inside the `io::Error`) - If the user typed `:q` or
+///   pressed `Esc`
+/// * `io::ErrorKind::UnexpectedEof` - If stdin has ended, so callers that
+///   ask again never spin on an empty input
 pub fn read_input(prompt: &str) -> io::Result<String> {
-    print!("{}", prompt);
-    io::stdout().flush()?;
+    let input = read_line(&prompt_text(prompt))?;
+    if input == CANCEL_INPUT {
+        return Err(io::Error::other(Cancelled));
+    }
+    Ok(input)
+}
+
+/// Whether an answer to a yes/no prompt means yes, in any language the
+/// menu is shown in; anything else, blank included, means no
+pub fn is_yes(answer: &str) -> bool {
+    matches!(answer.to_lowercase().as_str(), "y" | "yes" | "s" | "si" | "sí")
+}
+
+/// Asks a yes/no question that defaults to no
+///
+/// # Errors
+/// * `Cancelled` (inside the `io::Error`) - If the user typed `:q` or
+///   pressed `Esc`
+pub fn read_yes(prompt: &str) -> io::Result<bool> {
+    read_input(prompt).map(|answer| is_yes(&answer))
+}
+
+fn read_line(prompt: &str) -> io::Result<String> {
+    #[cfg(feature = "arrow-menu")]
+    if RAW_PROMPTS.load(Ordering::Relaxed) {
+        return super::picker::read_line(prompt);
+    }
+    if PROMPTS_TO_STDERR.load(Ordering::Relaxed) {
+        eprint!("{}", prompt);
+        io::stderr().flush()?;
+    } else {
+        print!("{}", prompt);
+        io::stdout().flush()?;
+    }
 
     let mut input = String::new();
-    io::stdin().read_line(&mut input)?;
+    if io::stdin().read_line(&mut input)? == 0 {
+        return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "no more input"));
+    }
 
     Ok(input.trim().to_string())
 }
+
+/// Prompts until `parse` accepts the input, showing why each answer was
+/// rejected
+///
+/// # Errors
+/// * `Cancelled` (inside the `io::Error`) - If the user cancelled, or gave
+///   `MAX_ATTEMPTS` invalid answers in a row
+fn read_valid<T, E: fmt::Display>(
+    prompt: &str,
+    renderer: &dyn Renderer,
+    parse: impl Fn(&str) -> Result<T, E>,
+) -> io::Result<T> {
+    for _ in 0..MAX_ATTEMPTS {
+        match parse(&read_input(prompt)?) {
+            Ok(value) => return Ok(value),
+            Err(e) => renderer.failure(&e),
+        }
+    }
+    renderer.failure(&Msg::TooManyAttempts.fill(&[&MAX_ATTEMPTS]));
+    Err(io::Error::other(Cancelled))
+}
+
+/// Prompts until `check` accepts the input, showing why it was rejected
+/// (see `read_valid` for giving up)
+pub fn read_checked<T>(
+    prompt: &str,
+    renderer: &dyn Renderer,
+    check: impl Fn(&str) -> BankResult<T>,
+) -> io::Result<T> {
+    read_valid(prompt, renderer, check)
+}
+
+/// Prompts until the input parses as a `T`, showing `invalid` otherwise
+pub fn read_parsed<T: FromStr>(prompt: &str, renderer: &dyn Renderer, invalid: &dyn fmt::Display) -> io::Result<T> {
+    read_valid(prompt, renderer, |input| input.parse().map_err(|_| invalid))
+}
+
+/// Prompts until the input is an amount such as `12.50`; negative
+/// amounts are refused
+pub fn read_amount(prompt: &str, renderer: &dyn Renderer) -> io::Result<Money> {
+    read_valid(prompt, renderer, parse_amount)
+}
+
+/// Parses an amount typed at a prompt, explaining what is wrong with it
+pub fn parse_amount(input: &str) -> Result<Money, String> {
+    let amount: Money = input.parse().map_err(|_| Msg::AmountFormat.fill(&[&input]))?;
+    if amount.is_negative() {
+        return Err(Msg::AmountNegative.text().to_string());
+    }
+    Ok(amount)
+}
+
+/// Prompts until the input is a full ID such as
+/// `67e55044-10b1-426f-9247-bb680e5fe0c8`, returned in that form
+pub fn read_uuid(prompt: &str, renderer: &dyn Renderer) -> io::Result<String> {
+    read_valid(prompt, renderer, |input| {
+        Uuid::parse_str(input).map(|id| id.to_string()).map_err(|_| Msg::InvalidId.fill(&[&input]))
+    })
+}
+
+/// Prompts until the input is a number from 1 to `count`
+///
+/// # Returns
+/// The chosen position, counting from 0
+pub fn read_choice(prompt: &str, renderer: &dyn Renderer, count: usize) -> io::Result<usize> {
+    read_valid(prompt, renderer, |input| match input.parse::<usize>() {
+        Ok(n) if (1..=count).contains(&n) => Ok(n - 1),
+        _ => Err(Msg::ChoiceOutOfRange.fill(&[&count])),
+    })
+}
+
+/// Prompts for a customer and resolves it
+///
+/// Accepts anything `Bank::find_customer` understands, or the start of a
+/// customer or account ID. When a prefix fits several customers they are
+/// listed and the user picks one.
+///
+/// # Returns
+/// * `Ok(None)` - If no customer was chosen (reason already shown)
+pub fn read_customer(
+    bank: &SharedBank,
+    prompt: &str,
+    renderer: &dyn Renderer,
+) -> io::Result<Option<CustomerId>> {
+    let reference = read_input(prompt)?;
+    choose_customer(bank, reference, renderer)
+}
+
+/// Resolves a customer reference that was already typed, asking which
+/// customer was meant if it is an ambiguous prefix (see `read_customer`)
+pub fn choose_customer(
+    bank: &SharedBank,
+    reference: String,
+    renderer: &dyn Renderer,
+) -> io::Result<Option<CustomerId>> {
+    // Collect the candidates, then let go of the lock before prompting again
+    let candidates: Vec<(CustomerId, String)> = {
+        let bank = bank.read();
+        if let Some(customer) = bank.find_customer(&reference) {
+            return Ok(Some(customer.id.clone()));
+        }
+        match bank.resolve_customer_prefix(&reference) {
+            PrefixMatch::Unique(customer) => return Ok(Some(customer.id.clone())),
+            PrefixMatch::NotFound => {
+                renderer.failure(&BankError::CustomerNotFound(reference.as_str().into()));
+                return Ok(None);
+            }
+            PrefixMatch::Ambiguous(matches) => {
+                matches.iter().map(|c| (c.id.clone(), c.summary())).collect()
+            }
+        }
+    };
+
+    renderer.chrome(&Msg::MatchesCustomers.fill(&[&reference, &candidates.len()]));
+    for (idx, (_, summary)) in candidates.iter().enumerate() {
+        renderer.chrome(&format!("  {}. {}", idx + 1, summary));
+    }
+
+    let choice = read_input(&Msg::ChooseOneOf.fill(&[&candidates.len()]))?;
+    if choice.is_empty() {
+        renderer.failure(&Msg::Cancelled);
+        return Ok(None);
+    }
+    match choice.parse::<usize>().ok().and_then(|n| candidates.get(n.wrapping_sub(1))) {
+        Some((customer_id, _)) => Ok(Some(customer_id.clone())),
+        None => {
+            renderer.failure(&Msg::InvalidChoice);
+            Ok(None)
+        }
+    }
+}
+
+/// Blank input means "no value", anything else must pass `check`
+///
+/// Meant as (part of) a `read_checked` check for optional fields.
+pub fn unless_blank<T>(input: &str, check: impl Fn(&str) -> BankResult<T>) -> BankResult<Option<T>> {
+    if input.is_empty() {
+        return Ok(None);
+    }
+    check(input).map(Some)
+}
+
+/// Shows a listing one page at a time with next/previous navigation
+///
+/// `show_page` renders the page starting at the given offset and returns
+/// whether there are pages (before, after) it. Re-rendering each page lets
+/// callers take the bank lock only while drawing, not while waiting for
+/// input.
+pub fn browse_pages(
+    renderer: &dyn Renderer,
+    mut show_page: impl FnMut(usize) -> (bool, bool),
+) -> io::Result<()> {
+    let mut offset = 0;
+    loop {
+        let (has_prev, has_next) = show_page(offset);
+        if !has_prev && !has_next {
+            return Ok(());
+        }
+
+        let mut options = Vec::new();
+        if has_next {
+            options.push(Msg::PageNext.text());
+        }
+        if has_prev {
+            options.push(Msg::PagePrev.text());
+        }
+        options.push(Msg::PageQuit.text());
+
+        match read_input(&format!("{}: ", options.join(", ")))?.to_lowercase().as_str() {
+            "n" if has_next => offset += PAGE_SIZE,
+            "p" if has_prev => offset = offset.saturating_sub(PAGE_SIZE),
+            "" | "q" => return Ok(()),
+            _ => renderer.failure(&Msg::InvalidChoice),
+        }
+    }
+}
+
+/// Reads a passphrase without echoing it to the terminal
+///
+/// Falls back to a normal (echoed) read when input is piped in or no
+/// terminal is available.
+pub fn read_passphrase(prompt: &str) -> io::Result<String> {
+    if !io::stdin().is_terminal() {
+        return read_input(prompt);
+    }
+    match rpassword::prompt_password(prompt_text(prompt)) {
+        Ok(passphrase) => Ok(passphrase),
+        Err(_) => read_input(prompt),
+    }
+}
+
+/// Parses a new value for an optional limit
+///
+/// Blank input keeps `current`, `none` removes the limit.
+///
+/// # Returns
+/// * `Err(String)` - If the input is not a valid value
+pub fn parse_limit<T: FromStr>(input: &str, current: Option<T>) -> Result<Option<T>, String> {
+    match input.trim() {
+        "" => Ok(current),
+        value if value.eq_ignore_ascii_case("none") => Ok(None),
+        value => value
+            .parse()
+            .map(Some)
+            .map_err(|_| Msg::InvalidLimit.fill(&[&value])),
+    }
+}
+
+/// Parses a `YYYY-MM` month into (year, month)
+pub fn parse_month(input: &str) -> BankResult<(i32, u32)> {
+    let invalid = || BankError::InvalidInput(Msg::InvalidMonth.fill(&[&input]));
+    let (year, month) = input.trim().split_once('-').ok_or_else(invalid)?;
+    let year = year.parse().map_err(|_| invalid())?;
+    let month = month.parse().map_err(|_| invalid())?;
+    if !(1..=12).contains(&month) {
+        return Err(invalid());
+    }
+    Ok((year, month))
+}
@@ -0,0 +1,52 @@
+//! Cash vault CLI operations
+//!
+//! Demonstrates: Showing an optional figure with a fallback text
+
+use std::io;
+
+use serde_json::json;
+
+use crate::bank::SharedBank;
+use super::i18n::Msg;
+use super::render::Renderer;
+use super::session::{authorize, Session, ADMIN_ONLY};
+use super::utils::{read_amount, read_input};
+
+/// Shows the cash in the bank's vault and tops it up or takes cash out
+/// (admin only)
+pub fn manage_vault(bank: &SharedBank, session: Option<&Session>, renderer: &dyn Renderer) -> io::Result<()> {
+    if authorize(session, ADMIN_ONLY, renderer).is_none() {
+        return Ok(());
+    }
+
+    renderer.section(Msg::CashVault.text());
+
+    loop {
+        match bank.read().vault() {
+            Some(cash) => renderer.chrome(&Msg::VaultCash.fill(&[&cash.display()])),
+            None => renderer.chrome(Msg::VaultUntracked.text()),
+        }
+
+        renderer.chrome(Msg::VaultActions.text());
+        let action = read_input(Msg::EnterAction.text())?;
+        let result = match action.as_str() {
+            "" => return Ok(()),
+            "1" => {
+                let amount = read_amount(Msg::AmountToBringIn.text(), renderer)?;
+                bank.write().vault_top_up(amount)
+            }
+            "2" => {
+                let amount = read_amount(Msg::AmountToTakeOut.text(), renderer)?;
+                bank.write().vault_withdraw(amount)
+            }
+            _ => {
+                renderer.failure(&Msg::InvalidChoice);
+                continue;
+            }
+        };
+        match result {
+            Ok(cash) => renderer.success(&Msg::VaultNowHolds.fill(&[&cash.display()]), json!({ "vault": cash })),
+            Err(e) => renderer.failure(&e),
+        }
+    }
+}
@@ -0,0 +1,86 @@
+//! Webhook CLI operations
+//!
+//! Demonstrates: Parsing a comma-separated list into enum values
+
+use std::io;
+
+use serde_json::json;
+
+use crate::bank::SharedBank;
+use crate::errors::{BankError, BankResult};
+use crate::events::EventKind;
+use crate::notifications;
+use super::i18n::Msg;
+use super::render::Renderer;
+use super::session::{authorize, Session, ADMIN_ONLY};
+use super::utils::{read_checked, read_input};
+
+/// Lists, registers and removes webhooks (admins only)
+pub fn manage_webhooks(
+    bank: &SharedBank,
+    session: Option<&Session>,
+    renderer: &dyn Renderer,
+) -> io::Result<()> {
+    if authorize(session, ADMIN_ONLY, renderer).is_none() {
+        return Ok(());
+    }
+
+    renderer.section(Msg::Webhooks.text());
+
+    loop {
+        let webhooks = bank.read().list_webhooks().to_vec();
+        if webhooks.is_empty() {
+            renderer.chrome(Msg::NoWebhooks.text());
+        } else {
+            renderer.chrome(Msg::RegisteredWebhooks.text());
+            for (i, webhook) in webhooks.iter().enumerate() {
+                renderer.chrome(&format!("  {}. {}", i + 1, webhook));
+            }
+        }
+
+        renderer.chrome(Msg::AddRemoveActions.text());
+        match read_input(Msg::EnterAction.text())?.as_str() {
+            "" => return Ok(()),
+            "1" => add_webhook(bank, renderer)?,
+            "2" => {
+                let choice = read_input(Msg::ChooseWebhook.text())?;
+                let id = match choice.parse::<usize>() {
+                    Ok(n) if (1..=webhooks.len()).contains(&n) => webhooks[n - 1].id.clone(),
+                    _ => choice,
+                };
+                match bank.write().remove_webhook(&id) {
+                    Ok(removed) => renderer.success(&Msg::WebhookRemoved.fill(&[&removed.url]), json!(removed)),
+                    Err(e) => renderer.failure(&e),
+                }
+            }
+            _ => renderer.failure(&Msg::InvalidChoice),
+        }
+    }
+}
+
+/// Prompts for a URL and the events to send to it, then registers it
+fn add_webhook(bank: &SharedBank, renderer: &dyn Renderer) -> io::Result<()> {
+    let url = read_checked(Msg::WebhookUrl.text(), renderer, |url| {
+        notifications::validate_url(url.trim()).map(|_| url.trim().to_string())
+    })?;
+
+    let names = EventKind::ALL.map(|kind| kind.as_str()).join(", ");
+    renderer.chrome(&Msg::WebhookEvents.fill(&[&names]));
+    let events = read_checked(Msg::EventsToSend.text(), renderer, parse_events)?;
+
+    match bank.write().add_webhook(&url, events) {
+        Ok(webhook) => renderer.success(&Msg::WebhookRegistered.fill(&[&webhook]), json!(webhook)),
+        Err(e) => renderer.failure(&e),
+    }
+    Ok(())
+}
+
+/// Parses "transaction_posted, transfer_completed" into event kinds
+fn parse_events(input: &str) -> BankResult<Vec<EventKind>> {
+    input
+        .split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(|name| name.parse().map_err(BankError::InvalidInput))
+        .collect()
+}
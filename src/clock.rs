@@ -0,0 +1,91 @@
+//! Clock module - where the bank gets the current time from
+//!
+//! Demonstrates: Trait objects for dependency injection, interior
+//! mutability with Mutex so a shared test clock can be moved forward
+//!
+//! The bank asks its clock instead of calling `Utc::now()` directly, so
+//! tests can freeze time and step it past daily limits, PIN lockouts and
+//! month boundaries without sleeping.
+
+use std::fmt;
+use std::sync::{Mutex, MutexGuard};
+
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+
+use crate::errors::{BankError, BankResult};
+
+/// A source of the current time
+pub trait Clock: fmt::Debug + Send + Sync {
+    /// The current time
+    fn now(&self) -> DateTime<Utc>;
+}
+
+/// The real wall clock; what a bank uses unless told otherwise
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// A clock that only moves when told to, for tests
+///
+/// Share it with the bank through an `Arc` and keep a handle to move it:
+///
+/// ```
+/// use std::sync::Arc;
+/// use chrono::{Duration, TimeZone, Utc};
+/// use rust_banking_system::clock::{Clock, MockClock};
+/// use rust_banking_system::Bank;
+///
+/// let clock = Arc::new(MockClock::new(Utc.with_ymd_and_hms(2024, 1, 1, 9, 0, 0).unwrap()));
+/// let mut bank = Bank::new("Test Bank".to_string());
+/// bank.set_clock(clock.clone());
+///
+/// clock.advance(Duration::days(1));
+/// assert_eq!(bank.now(), Utc.with_ymd_and_hms(2024, 1, 2, 9, 0, 0).unwrap());
+/// ```
+#[derive(Debug)]
+pub struct MockClock {
+    now: Mutex<DateTime<Utc>>,
+}
+
+impl MockClock {
+    /// A clock stopped at `now`
+    pub fn new(now: DateTime<Utc>) -> Self {
+        Self { now: Mutex::new(now) }
+    }
+
+    /// Moves the clock to `now`
+    pub fn set(&self, now: DateTime<Utc>) {
+        *self.lock() = now;
+    }
+
+    /// Moves the clock forward by `by`
+    pub fn advance(&self, by: Duration) {
+        *self.lock() += by;
+    }
+
+    fn lock(&self) -> MutexGuard<'_, DateTime<Utc>> {
+        // A test that panicked with the clock locked leaves a valid time
+        self.now.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Clock for MockClock {
+    fn now(&self) -> DateTime<Utc> {
+        *self.lock()
+    }
+}
+
+/// The midnight after `day`, where a span that includes `day` ends
+///
+/// # Returns
+/// * `Err(BankError::InvalidInput)` - If `day` is the last date chrono can hold
+pub fn end_of_day(day: NaiveDate) -> BankResult<DateTime<Utc>> {
+    day.succ_opt()
+        .map(|next| next.and_hms_opt(0, 0, 0).unwrap().and_utc())
+        .ok_or_else(|| BankError::InvalidInput(format!("no day follows {}", day)))
+}
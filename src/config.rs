@@ -0,0 +1,358 @@
+//! Config module - settings read from a file instead of hard-coded
+//!
+//! Demonstrates: `#[serde(default)]` for partial files, choosing a parser
+//! by file extension, validating after deserializing
+//! https://serde.rs/container-attrs.html#default
+//!
+//! Settings come from the file given with `--config`, or else from the
+//! first of these that exists:
+//!
+//! 1. `bank.toml` in the current directory
+//! 2. `$XDG_CONFIG_HOME/rust-banking/config.toml` (or `~/.config/...`)
+//! 3. `%APPDATA%\rust-banking\config.toml` on Windows
+//!
+//! Flags and environment variables can override some settings for one
+//! run (see `cli::startup`). With no file at all the defaults apply. Any setting may be left out of
+//! the file; unknown settings are rejected so typos don't go unnoticed.
+//!
+//! ```toml
+//! bank_name = "Campus Credit Union"
+//! data_file = "campus.json"
+//! autosave = true
+//! backup_count = 5
+//! locale = "de-DE"
+//! currency_symbol = "€"
+//! ```
+//!
+//! `locale` picks the menu language (see `cli::i18n`) and how amounts and
+//! dates are written (see `format`); `thousands_separator`,
+//! `decimal_places` and `date_format` override single parts of the latter.
+//! `theme`, `color` and `unicode` style the text output (see `cli::theme`),
+//! and `customer_columns` and `transaction_columns` pick what the listings
+//! show (see `cli::table`). `history_file` is where the command shell
+//! remembers what was typed (see `cli::shell`). `history_cap` bounds the
+//! transactions kept in memory per account (see `Bank::set_history_cap`).
+//! Without the `cli` feature the display settings are read but not
+//! checked, and the column lists default to empty.
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::format::{Item, StrftimeItems};
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "cli")]
+use crate::cli::table::{self, ColumnSet, CustomerColumn, TransactionColumn};
+#[cfg(feature = "cli")]
+use crate::cli::theme::Theme;
+use crate::errors::{BankError, BankResult, Cause};
+use crate::format::Format;
+use crate::persistence::DEFAULT_BACKUP_COUNT;
+
+/// Name of the config file looked for in the current directory
+pub const CONFIG_FILE_NAME: &str = "bank.toml";
+
+/// Directory under the user's config directory holding `config.toml`
+pub const CONFIG_DIR_NAME: &str = "rust-banking";
+
+/// Most rotated backups a config may ask for
+pub const MAX_BACKUP_COUNT: usize = 100;
+
+/// Longest accepted bank name
+pub const MAX_BANK_NAME_LEN: usize = 64;
+
+/// Application settings
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Name given to a new bank when the data file does not exist yet
+    pub bank_name: String,
+
+    /// Where the bank is saved
+    pub data_file: String,
+
+    /// Start the interactive menu with autosave on
+    pub autosave: bool,
+
+    /// Symbol shown with amounts, e.g. `$` or `€`
+    pub currency_symbol: String,
+
+    /// Language and region for display, e.g. `en-US`
+    pub locale: String,
+
+    /// Put between groups of three digits, `""` for none; the locale's
+    /// when left out
+    pub thousands_separator: Option<String>,
+
+    /// Digits shown after the decimal separator (0 to 2)
+    pub decimal_places: Option<u8>,
+
+    /// `chrono` format string for dates, e.g. `%d/%m/%Y`; the locale's
+    /// when left out
+    pub date_format: Option<String>,
+
+    /// Colors for the text output: `default` or `high-contrast`
+    pub theme: String,
+
+    /// Color the text output when it goes to a terminal
+    pub color: bool,
+
+    /// Use emoji and box-drawing characters; `false` writes plain ASCII
+    pub unicode: bool,
+
+    /// Pick menu entries with the arrow keys (needs the `arrow-menu` feature)
+    pub arrow_menu: bool,
+
+    /// Show detailed summaries in the menu's listings instead of tables
+    pub verbose: bool,
+
+    /// Where the command shell keeps its history ("" keeps none)
+    pub history_file: String,
+
+    /// Columns of the customer list and search, e.g. `["name", "balance"]`
+    pub customer_columns: Vec<String>,
+
+    /// Columns of the transaction history
+    pub transaction_columns: Vec<String>,
+
+    /// Rotated backups kept of the data file (0 disables them)
+    pub backup_count: usize,
+
+    /// Open the data file without ever writing to it
+    pub read_only: bool,
+
+    /// Parse each account's transaction history only when it is first
+    /// used, so a large JSON data file opens quickly
+    pub lazy_load: bool,
+
+    /// Transactions kept in memory per account; older ones are moved to
+    /// the archive next to the data file (unset keeps them all)
+    pub history_cap: Option<usize>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            bank_name: "Rust National Bank".to_string(),
+            data_file: "bank_data.json".to_string(),
+            autosave: false,
+            currency_symbol: "$".to_string(),
+            locale: "en-US".to_string(),
+            thousands_separator: None,
+            decimal_places: None,
+            date_format: None,
+            theme: "default".to_string(),
+            color: true,
+            unicode: true,
+            arrow_menu: false,
+            verbose: false,
+            history_file: ".bank_history".to_string(),
+            customer_columns: default_columns().0,
+            transaction_columns: default_columns().1,
+            backup_count: DEFAULT_BACKUP_COUNT,
+            read_only: false,
+            lazy_load: false,
+            history_cap: None,
+        }
+    }
+}
+
+impl Config {
+    /// Loads the config from `explicit`, or else from the first default
+    /// location that exists, or else uses the defaults
+    ///
+    /// # Returns
+    /// * `Err(BankError::IoError)` - If `explicit` cannot be read
+    /// * `Err(BankError::InvalidInput)` / `Err(BankError::ValidationError)` -
+    ///   If the file that was found is malformed or has an invalid setting
+    pub fn discover(explicit: Option<&Path>) -> BankResult<Self> {
+        if let Some(path) = explicit {
+            return Self::load(path);
+        }
+        match Self::default_locations().into_iter().find(|path| path.is_file()) {
+            Some(path) => Self::load(&path),
+            None => Ok(Self::default()),
+        }
+    }
+
+    /// Where `discover` looks when no file is given, in order
+    pub fn default_locations() -> Vec<PathBuf> {
+        let mut locations = vec![PathBuf::from(CONFIG_FILE_NAME)];
+        let config_home = env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")));
+        if let Some(dir) = config_home {
+            locations.push(dir.join(CONFIG_DIR_NAME).join("config.toml"));
+        }
+        if let Some(dir) = env::var_os("APPDATA") {
+            locations.push(PathBuf::from(dir).join(CONFIG_DIR_NAME).join("config.toml"));
+        }
+        locations
+    }
+
+    /// Reads and validates a config file; `.json` files are parsed as
+    /// JSON, anything else as TOML
+    pub fn load(path: &Path) -> BankResult<Self> {
+        let text = fs::read_to_string(path)
+            .map_err(|e| BankError::IoError(Cause::new(e).context(path.display().to_string())))?;
+        let is_json = path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+        let parsed = if is_json { Self::from_json(&text) } else { Self::from_toml(&text) };
+        parsed.map_err(|e| match e {
+            BankError::InvalidInput(reason) => BankError::InvalidInput(format!("{}: {}", path.display(), reason)),
+            other => other,
+        })
+    }
+
+    /// Parses and validates a TOML config
+    pub fn from_toml(text: &str) -> BankResult<Self> {
+        let config: Self = toml::from_str(text).map_err(|e| BankError::InvalidInput(e.message().to_string()))?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Parses and validates a JSON config
+    pub fn from_json(text: &str) -> BankResult<Self> {
+        let config: Self = serde_json::from_str(text).map_err(|e| BankError::InvalidInput(e.to_string()))?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Checks every setting
+    ///
+    /// # Returns
+    /// * `Err(BankError::ValidationError)` - Naming the first invalid setting
+    pub fn validate(&self) -> BankResult<()> {
+        let invalid = |field: &str, reason: String| {
+            Err(BankError::ValidationError { field: field.to_string(), reason })
+        };
+
+        let name = self.bank_name.trim();
+        if name.is_empty() || name.chars().count() > MAX_BANK_NAME_LEN {
+            return invalid("bank_name", format!("must be 1 to {} characters", MAX_BANK_NAME_LEN));
+        }
+        if self.data_file.trim().is_empty() {
+            return invalid("data_file", "must not be empty".to_string());
+        }
+        let symbol_len = self.currency_symbol.chars().count();
+        if !(1..=3).contains(&symbol_len)
+            || self.currency_symbol.chars().any(|c| c.is_whitespace() || c.is_ascii_digit())
+        {
+            return invalid("currency_symbol", "must be 1 to 3 characters, without digits or spaces".to_string());
+        }
+        if !is_locale(&self.locale) {
+            return invalid("locale", format!("'{}' is not a locale like 'en' or 'en-US'", self.locale));
+        }
+        if let Some(separator) = &self.thousands_separator {
+            let mut chars = separator.chars();
+            let valid = match (chars.next(), chars.next()) {
+                (None, _) => true,
+                (Some(c), None) => !c.is_ascii_digit() && c != self.format().decimal_separator,
+                _ => false,
+            };
+            if !valid {
+                return invalid(
+                    "thousands_separator",
+                    "must be one character other than a digit or the decimal separator".to_string(),
+                );
+            }
+        }
+        if self.decimal_places.is_some_and(|places| places > 2) {
+            return invalid("decimal_places", "must be 0, 1 or 2".to_string());
+        }
+        if let Some(date_format) = &self.date_format {
+            if !date_format.contains('%') || StrftimeItems::new(date_format).any(|item| item == Item::Error) {
+                return invalid("date_format", format!("'{}' is not a date format like '%d/%m/%Y'", date_format));
+            }
+        }
+        #[cfg(feature = "cli")]
+        self.validate_display()?;
+        if self.backup_count > MAX_BACKUP_COUNT {
+            return invalid("backup_count", format!("must be at most {}", MAX_BACKUP_COUNT));
+        }
+        if self.history_cap == Some(0) {
+            return invalid("history_cap", "must be at least 1".to_string());
+        }
+        Ok(())
+    }
+
+    /// How amounts and dates are written: the `locale`'s usual format with
+    /// the configured symbol and overrides
+    pub fn format(&self) -> Format {
+        let mut format = Format::for_locale(&self.locale, &self.currency_symbol);
+        if let Some(separator) = &self.thousands_separator {
+            format.thousands_separator = separator.chars().next();
+        }
+        if let Some(places) = self.decimal_places {
+            format.decimal_places = places;
+        }
+        if let Some(date_format) = &self.date_format {
+            // Keep the locale's time of day after the new date
+            let time = format.datetime_format.strip_prefix(format.date_format.as_str()).unwrap_or(" %H:%M");
+            format.datetime_format = format!("{}{}", date_format, time);
+            format.date_format = date_format.clone();
+        }
+        format
+    }
+
+    /// Checks the settings only the CLI's text output uses
+    #[cfg(feature = "cli")]
+    fn validate_display(&self) -> BankResult<()> {
+        let invalid = |field: &str, reason: String| {
+            Err(BankError::ValidationError { field: field.to_string(), reason })
+        };
+        if let Err(reason) = self.theme.parse::<Theme>() {
+            return invalid("theme", reason);
+        }
+        if let Err(reason) = table::parse_columns::<CustomerColumn>(&self.customer_columns) {
+            return invalid("customer_columns", reason);
+        }
+        if let Err(reason) = table::parse_columns::<TransactionColumn>(&self.transaction_columns) {
+            return invalid("transaction_columns", reason);
+        }
+        Ok(())
+    }
+
+    /// The columns the listings show; the defaults for a list that does
+    /// not pass `validate`
+    #[cfg(feature = "cli")]
+    pub fn table_columns(&self) -> (Vec<CustomerColumn>, Vec<TransactionColumn>) {
+        (
+            table::parse_columns(&self.customer_columns).unwrap_or_else(|_| CustomerColumn::DEFAULT.to_vec()),
+            table::parse_columns(&self.transaction_columns).unwrap_or_else(|_| TransactionColumn::DEFAULT.to_vec()),
+        )
+    }
+}
+
+/// The default columns of the customer list and the transaction history;
+/// none without the `cli` feature, which is all that shows them
+#[cfg(feature = "cli")]
+fn default_columns() -> (Vec<String>, Vec<String>) {
+    (column_names(&CustomerColumn::DEFAULT), column_names(&TransactionColumn::DEFAULT))
+}
+
+#[cfg(not(feature = "cli"))]
+fn default_columns() -> (Vec<String>, Vec<String>) {
+    (Vec::new(), Vec::new())
+}
+
+/// The config file names of `columns`
+#[cfg(feature = "cli")]
+fn column_names<C: ColumnSet>(columns: &[C]) -> Vec<String> {
+    columns.iter().map(|column| column.name().to_string()).collect()
+}
+
+/// Whether `tag` looks like `ll`, `lll`, `ll-CC` or `ll_CC`
+fn is_locale(tag: &str) -> bool {
+    let mut parts = tag.splitn(2, ['-', '_']);
+    let language = parts.next().unwrap_or("");
+    let language_ok = (2..=3).contains(&language.len()) && language.chars().all(|c| c.is_ascii_lowercase());
+    let region_ok = match parts.next() {
+        None => true,
+        Some(region) => {
+            (region.len() == 2 && region.chars().all(|c| c.is_ascii_uppercase()))
+                || (region.len() == 3 && region.chars().all(|c| c.is_ascii_digit()))
+        }
+    };
+    language_ok && region_ok
+}
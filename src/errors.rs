@@ -1,81 +1,353 @@
 //! Error handling module for the banking system
 //!
-//! Demonstrates: Custom error types, enum-based error handling, trait implementations
+//! Demonstrates: Custom error types, enum-based error handling, deriving
+//! Display and Error with thiserror, error source chaining
 //! https://doc.rust-lang.org/book/ch09-02-recoverable-errors-with-result.html
+//!
+//! Every error has a stable numeric `code()` and a snake_case `kind()`
+//! for programs to branch on; the message may be reworded at any time.
+//! Storage errors keep the `io::Error` or `serde_json::Error` behind
+//! them in a `Cause`, which is next in the `Error::source()` chain.
+//! Both convert into `BankError`, so `?` works on them directly.
 
+use chrono::{DateTime, Utc};
+use std::error::Error;
 use std::fmt;
+use std::io;
+use std::sync::Arc;
+
+use crate::format;
+use crate::models::{AccountId, CustomerId, Limit, Money, TransactionId};
 
 /// Custom error types for banking operations
 ///
 /// Demonstrates: Enums with associated data, derive macros
-/// This enum represents all possible errors that can occur in the banking system
-#[derive(Debug, Clone)]
+/// This enum represents all possible errors that can occur in the banking system.
+/// `thiserror` writes the Display and Error impls from the `#[error]`
+/// attributes; `.field` in an attribute refers to the variant's field.
+/// https://docs.rs/thiserror
+#[derive(Debug, Clone, thiserror::Error)]
 pub enum BankError {
     /// Customer not found in the system
-    CustomerNotFound(String),
+    #[error("Customer '{0}' not found")]
+    CustomerNotFound(CustomerId),
 
     /// Account not found for the customer
+    #[error("Account '{0}' not found")]
     AccountNotFound(String),
 
     /// Insufficient funds for the requested operation
     /// Uses struct-like variant to hold both available and requested amounts
+    #[error("Insufficient funds: available {}, requested {}", .available.display(), .requested.display())]
     InsufficientFunds {
-        available: f64,
-        requested: f64
+        available: Money,
+        requested: Money
+    },
+
+    /// The bank's vault holds less cash than a withdrawal needs
+    #[error("Not enough cash in the vault: available {}, requested {}", .available.display(), .requested.display())]
+    InsufficientVaultCash {
+        available: Money,
+        requested: Money,
     },
 
+    /// Savings account has used up its withdrawals for this month
+    #[error("Monthly withdrawal limit of {limit} reached")]
+    WithdrawalLimitReached { limit: u32 },
+
+    /// Account is frozen; money cannot leave it
+    #[error("Account '{0}' is frozen")]
+    AccountFrozen(AccountId),
+
+    /// Account is closed; no operations are allowed
+    #[error("Account '{0}' is closed")]
+    AccountClosed(AccountId),
+
+    /// A configured account limit would be exceeded
+    #[error("Limit exceeded: {0}")]
+    LimitExceeded(Limit),
+
+    /// Fixed deposit cannot be withdrawn from before maturity
+    #[error("Account is locked until {}", format::date(.until))]
+    AccountLocked { until: DateTime<Utc> },
+
+    /// User-supplied input could not be parsed (e.g. a malformed date)
+    #[error("Invalid input: {0}")]
+    InvalidInput(String),
+
+    /// A customer detail failed validation (see the `validation` module)
+    #[error("Invalid {field}: {reason}")]
+    ValidationError { field: String, reason: String },
+
     /// Invalid amount (negative or zero)
-    InvalidAmount(f64),
+    #[error("Invalid amount: {}", .0.display())]
+    InvalidAmount(Money),
+
+    /// Transfer request is not valid (e.g. sender and recipient are the same)
+    #[error("Invalid transfer: {0}")]
+    InvalidTransfer(String),
+
+    /// Scheduled transaction not found
+    #[error("Scheduled transaction '{0}' not found")]
+    ScheduleNotFound(String),
+
+    /// The customer has no saved payee with this nickname
+    #[error("No saved payee called '{0}'")]
+    BeneficiaryNotFound(String),
+
+    /// No webhook is registered with this ID
+    #[error("Webhook '{0}' not found")]
+    WebhookNotFound(String),
+
+    /// No debit card has this ID
+    #[error("Card '{0}' not found")]
+    CardNotFound(String),
+
+    /// The card cannot be used, e.g. because it is blocked or expired
+    #[error("Card declined: {0}")]
+    CardDeclined(String),
+
+    /// No merchant has this ID or name
+    #[error("Merchant '{0}' not found")]
+    MerchantNotFound(String),
+
+    /// The account has no savings goal with this name
+    #[error("Savings goal '{0}' not found")]
+    EnvelopeNotFound(String),
+
+    /// The money is in this locked savings goal
+    #[error("Savings goal '{0}' is locked; unlock it to use its money")]
+    EnvelopeLocked(String),
+
+    /// The account has no budget for this category
+    #[error("No budget for category '{0}'")]
+    BudgetNotFound(String),
+
+    /// No term deposit has this ID
+    #[error("Term deposit '{0}' not found")]
+    TermDepositNotFound(String),
+
+    /// No account has a transaction with this ID
+    #[error("Transaction '{0}' not found")]
+    TransactionNotFound(TransactionId),
 
     /// Customer already exists
+    #[error("Customer '{0}' already exists")]
     CustomerAlreadyExists(String),
 
+    /// Customer is deactivated; they cannot log in or move money
+    #[error("Customer '{0}' is deactivated")]
+    CustomerDeactivated(CustomerId),
+
+    /// Wrong PIN; the credentials lock once no attempts are left
+    #[error("Incorrect PIN ({attempts_left} attempt(s) left)")]
+    InvalidPin { attempts_left: u32 },
+
+    /// Too many wrong PINs; login is blocked until the given time
+    #[error("Too many incorrect PINs; locked until {}", .until.format("%H:%M UTC"))]
+    PinLocked { until: DateTime<Utc> },
+
+    /// Customer has not set a PIN yet
+    #[error("Customer '{0}' has not set a PIN")]
+    PinNotSet(CustomerId),
+
+    /// Nobody logs in under this name
+    #[error("Login failed for '{user}'")]
+    AuthenticationFailed { user: String },
+
     /// General IO error
-    IoError(String),
+    #[error("IO Error: {0}")]
+    IoError(#[source] Cause),
 
     /// Serialization/Deserialization error
-    SerializationError(String),
+    #[error("Serialization Error: {0}")]
+    SerializationError(#[source] Cause),
+
+    /// A stored file exists but its contents cannot be read back
+    #[error("{location} is corrupted: {cause}")]
+    StorageCorrupted {
+        location: String,
+        #[source]
+        cause: Cause,
+    },
+
+    /// Encryption/decryption error (e.g. wrong passphrase)
+    #[error("Encryption Error: {0}")]
+    EncryptionError(String),
+
+    /// The data file was opened with `--read-only`; nothing may be written
+    #[error("{0} is open read-only")]
+    ReadOnly(String),
 }
 
-// Implementing Display trait for user-friendly error messages
-// https://doc.rust-lang.org/std/fmt/trait.Display.html
-impl fmt::Display for BankError {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        // Demonstrates: Pattern matching with destructuring
+impl BankError {
+    /// Stable numeric code for this kind of error
+    ///
+    /// The thousands group the errors: 1xxx something was not found,
+    /// 2xxx the account or card refused the operation, 3xxx the request
+    /// was invalid, 4xxx authentication or permission, 5xxx storage.
+    /// Codes are never reused or renumbered.
+    pub fn code(&self) -> u16 {
+        match self {
+            BankError::CustomerNotFound(_) => 1001,
+            BankError::AccountNotFound(_) => 1002,
+            BankError::ScheduleNotFound(_) => 1003,
+            BankError::BeneficiaryNotFound(_) => 1004,
+            BankError::WebhookNotFound(_) => 1005,
+            BankError::CardNotFound(_) => 1006,
+            BankError::MerchantNotFound(_) => 1007,
+            BankError::EnvelopeNotFound(_) => 1008,
+            BankError::BudgetNotFound(_) => 1009,
+            BankError::TermDepositNotFound(_) => 1010,
+            BankError::TransactionNotFound(_) => 1011,
+            BankError::InsufficientFunds{ .. } => 2001,
+            BankError::WithdrawalLimitReached{ .. } => 2002,
+            BankError::LimitExceeded(_) => 2003,
+            BankError::AccountFrozen(_) => 2004,
+            BankError::AccountClosed(_) => 2005,
+            BankError::AccountLocked{ .. } => 2006,
+            BankError::CardDeclined(_) => 2007,
+            BankError::EnvelopeLocked(_) => 2008,
+            BankError::CustomerDeactivated(_) => 2009,
+            BankError::InsufficientVaultCash{ .. } => 2010,
+            BankError::InvalidInput(_) => 3001,
+            BankError::ValidationError{ .. } => 3002,
+            BankError::InvalidAmount(_) => 3003,
+            BankError::InvalidTransfer(_) => 3004,
+            BankError::CustomerAlreadyExists(_) => 3005,
+            BankError::InvalidPin{ .. } => 4001,
+            BankError::PinLocked{ .. } => 4002,
+            BankError::PinNotSet(_) => 4003,
+            BankError::ReadOnly(_) => 4004,
+            BankError::AuthenticationFailed{ .. } => 4005,
+            BankError::IoError(_) => 5001,
+            BankError::SerializationError(_) => 5002,
+            BankError::EncryptionError(_) => 5003,
+            BankError::StorageCorrupted{ .. } => 5004,
+        }
+    }
+
+    /// The variant's name in snake_case, e.g. `insufficient_funds`
+    ///
+    /// The string form of `code()`: stable across message wording
+    /// changes, so it suits metric labels, log filters and JSON output.
+    pub fn kind(&self) -> &'static str {
         match self {
-            BankError::CustomerNotFound(id) => {
-                write!(f, "Customer '{}' not found", id)
-            }
-            BankError::AccountNotFound(id) => {
-                write!(f, "Account '{}' not found", id)
-            }
-            BankError::InsufficientFunds { available, requested } => {
-                write!(
-                    f,
-                    "Insufficient funds: available ${:.2}, requested ${:.2}",
-                    available, requested
-                )
-            }
-            BankError::InvalidAmount(amt) => {
-                write!(f, "Invalid amount: ${:.2}", amt)
-            }
-            BankError::CustomerAlreadyExists(id) => {
-                write!(f, "Customer '{}' already exists", id)
-            }
-            BankError::IoError(msg) => {
-                write!(f, "IO Error: {}", msg)
-            }
-            BankError::SerializationError(msg) => {
-                write!(f, "Serialization Error: {}", msg)
-            }
+            BankError::CustomerNotFound(_) => "customer_not_found",
+            BankError::AccountNotFound(_) => "account_not_found",
+            BankError::InsufficientFunds{ .. } => "insufficient_funds",
+            BankError::InsufficientVaultCash{ .. } => "insufficient_vault_cash",
+            BankError::WithdrawalLimitReached{ .. } => "withdrawal_limit_reached",
+            BankError::AccountFrozen(_) => "account_frozen",
+            BankError::AccountClosed(_) => "account_closed",
+            BankError::LimitExceeded(_) => "limit_exceeded",
+            BankError::AccountLocked{ .. } => "account_locked",
+            BankError::InvalidInput(_) => "invalid_input",
+            BankError::ValidationError{ .. } => "validation_error",
+            BankError::InvalidAmount(_) => "invalid_amount",
+            BankError::InvalidTransfer(_) => "invalid_transfer",
+            BankError::ScheduleNotFound(_) => "schedule_not_found",
+            BankError::BeneficiaryNotFound(_) => "beneficiary_not_found",
+            BankError::WebhookNotFound(_) => "webhook_not_found",
+            BankError::CardNotFound(_) => "card_not_found",
+            BankError::CardDeclined(_) => "card_declined",
+            BankError::MerchantNotFound(_) => "merchant_not_found",
+            BankError::EnvelopeNotFound(_) => "envelope_not_found",
+            BankError::EnvelopeLocked(_) => "envelope_locked",
+            BankError::BudgetNotFound(_) => "budget_not_found",
+            BankError::TermDepositNotFound(_) => "term_deposit_not_found",
+            BankError::TransactionNotFound(_) => "transaction_not_found",
+            BankError::CustomerAlreadyExists(_) => "customer_already_exists",
+            BankError::CustomerDeactivated(_) => "customer_deactivated",
+            BankError::InvalidPin{ .. } => "invalid_pin",
+            BankError::PinLocked{ .. } => "pin_locked",
+            BankError::PinNotSet(_) => "pin_not_set",
+            BankError::AuthenticationFailed{ .. } => "authentication_failed",
+            BankError::IoError(_) => "io_error",
+            BankError::SerializationError(_) => "serialization_error",
+            BankError::StorageCorrupted{ .. } => "storage_corrupted",
+            BankError::EncryptionError(_) => "encryption_error",
+            BankError::ReadOnly(_) => "read_only",
+        }
+    }
+}
+
+// `#[from]` would need the bare error as the field, so these go through `Cause`
+impl From<io::Error> for BankError {
+    fn from(error: io::Error) -> Self {
+        BankError::IoError(error.into())
+    }
+}
+
+impl From<serde_json::Error> for BankError {
+    fn from(error: serde_json::Error) -> Self {
+        BankError::SerializationError(error.into())
+    }
+}
+
+/// What went wrong below the bank: a message, the underlying error, or
+/// the error with a note on what was being done (e.g. `line 3`)
+///
+/// Demonstrates: Arc<dyn Error> to keep a non-Clone error in a Clone type
+#[derive(Debug, Clone)]
+pub struct Cause {
+    context: String,
+    source: Option<Arc<dyn Error + Send + Sync>>,
+}
+
+impl Cause {
+    /// Wraps an underlying error
+    pub fn new(error: impl Error + Send + Sync + 'static) -> Self {
+        Cause { context: String::new(), source: Some(Arc::new(error)) }
+    }
+
+    /// Adds what was being done when the error happened
+    pub fn context(mut self, context: impl Into<String>) -> Self {
+        self.context = context.into();
+        self
+    }
+}
+
+// The underlying error continues the `source()` chain past the cause
+impl Error for Cause {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.source.as_deref().map(|e| e as &(dyn Error + 'static))
+    }
+}
+
+impl fmt::Display for Cause {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match (&self.source, self.context.is_empty()) {
+            (Some(source), true) => write!(f, "{}", source),
+            (Some(source), false) => write!(f, "{}: {}", self.context, source),
+            (None, _) => write!(f, "{}", self.context),
         }
     }
 }
 
-// Implementing std::error::Error trait makes this a proper error type
-// This allows BankError to be used with the ? operator and error handling infrastructure
-// https://doc.rust-lang.org/std/error/trait.Error.html
-impl std::error::Error for BankError {}
+impl From<String> for Cause {
+    fn from(message: String) -> Self {
+        Cause { context: message, source: None }
+    }
+}
+
+impl From<&str> for Cause {
+    fn from(message: &str) -> Self {
+        message.to_string().into()
+    }
+}
+
+impl From<io::Error> for Cause {
+    fn from(error: io::Error) -> Self {
+        Cause::new(error)
+    }
+}
+
+impl From<serde_json::Error> for Cause {
+    fn from(error: serde_json::Error) -> Self {
+        Cause::new(error)
+    }
+}
 
 /// Type alias for Results in banking operations
 ///
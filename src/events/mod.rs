@@ -0,0 +1,164 @@
+//! Events module - domain events for external integrations
+//!
+//! Demonstrates: Boxed closures as callbacks, an internally tagged enum as
+//! a wire format, translating internal change records into a public API
+//!
+//! `BankEvent` records every change to the bank state and is free to
+//! change shape with it. `DomainEvent` is the smaller, stable set of things
+//! outside systems care about. Each one is handed to the callbacks added
+//! with `Bank::subscribe` and POSTed to the webhooks registered with
+//! `Bank::add_webhook`:
+//!
+//! - `CustomerRegistered` - a new customer signed up
+//! - `TransactionPosted` - money was credited to or debited from an account
+//!   (a transfer posts one on each side)
+//! - `TransferCompleted` - both sides of a transfer were posted
+//!
+//! Events are published once the change is applied, never while a journal
+//! is replayed, and not at all for an all-or-nothing batch that is rolled
+//! back.
+
+mod webhooks;
+
+pub use webhooks::{deliver, Webhook, DELIVERY_ATTEMPTS, RETRY_DELAY};
+pub(crate) use webhooks::DeliveryQueue;
+
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::models::{AccountId, CustomerId, Money, Transaction, TransactionId};
+
+/// Something that happened, as told to subscribers and webhooks
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum DomainEvent {
+    CustomerRegistered {
+        customer_id: CustomerId,
+        name: String,
+        email: String,
+        registered_at: DateTime<Utc>,
+    },
+    TransactionPosted {
+        customer_id: CustomerId,
+        account_id: AccountId,
+        transaction: Transaction,
+    },
+    TransferCompleted {
+        from_customer_id: CustomerId,
+        to_customer_id: CustomerId,
+        amount: Money,
+        /// The debit posted to the sender
+        debit_id: TransactionId,
+        /// The credit posted to the recipient
+        credit_id: TransactionId,
+        completed_at: DateTime<Utc>,
+    },
+}
+
+impl DomainEvent {
+    /// Which kind of event this is, for filtering
+    pub fn kind(&self) -> EventKind {
+        match self {
+            DomainEvent::CustomerRegistered { .. } => EventKind::CustomerRegistered,
+            DomainEvent::TransactionPosted { .. } => EventKind::TransactionPosted,
+            DomainEvent::TransferCompleted { .. } => EventKind::TransferCompleted,
+        }
+    }
+}
+
+/// The kinds of `DomainEvent`, as named on the wire
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventKind {
+    CustomerRegistered,
+    TransactionPosted,
+    TransferCompleted,
+}
+
+impl EventKind {
+    /// Every kind, in declaration order
+    pub const ALL: [EventKind; 3] =
+        [EventKind::CustomerRegistered, EventKind::TransactionPosted, EventKind::TransferCompleted];
+
+    /// The name used in JSON, e.g. `transaction_posted`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EventKind::CustomerRegistered => "customer_registered",
+            EventKind::TransactionPosted => "transaction_posted",
+            EventKind::TransferCompleted => "transfer_completed",
+        }
+    }
+}
+
+impl fmt::Display for EventKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for EventKind {
+    type Err = String;
+
+    /// Parses a kind name; dashes and underscores are interchangeable
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s.trim().to_lowercase().replace('-', "_");
+        EventKind::ALL
+            .into_iter()
+            .find(|kind| kind.as_str() == name)
+            .ok_or_else(|| format!("unknown event '{}'", s.trim()))
+    }
+}
+
+/// Identifies a callback added with `Bank::subscribe`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SubscriptionId(u64);
+
+/// A function called with every published event
+pub type Callback = Arc<dyn Fn(&DomainEvent) + Send + Sync>;
+
+/// The callbacks subscribed to a bank
+///
+/// Closures cannot be printed, so this implements `Debug` by hand.
+#[derive(Clone, Default)]
+pub struct Subscribers {
+    next_id: u64,
+    callbacks: Vec<(SubscriptionId, Callback)>,
+}
+
+impl Subscribers {
+    /// Adds a callback
+    pub fn add(&mut self, callback: Callback) -> SubscriptionId {
+        self.next_id += 1;
+        let id = SubscriptionId(self.next_id);
+        self.callbacks.push((id, callback));
+        id
+    }
+
+    /// Removes a callback; returns whether it was subscribed
+    pub fn remove(&mut self, id: SubscriptionId) -> bool {
+        let before = self.callbacks.len();
+        self.callbacks.retain(|(subscribed, _)| *subscribed != id);
+        self.callbacks.len() != before
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.callbacks.is_empty()
+    }
+
+    /// Calls every callback with `event`
+    pub fn notify(&self, event: &DomainEvent) {
+        for (_, callback) in &self.callbacks {
+            callback(event);
+        }
+    }
+}
+
+impl fmt::Debug for Subscribers {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Subscribers").field("count", &self.callbacks.len()).finish()
+    }
+}
@@ -0,0 +1,171 @@
+//! Webhooks - domain events POSTed to registered URLs
+//!
+//! Demonstrates: Retrying with exponential backoff, filtering with an
+//! "empty means everything" list, handing work to a background thread
+//! over a channel
+//!
+//! Publishing an event only queues its deliveries; a `DeliveryQueue`
+//! thread POSTs them in order, so a slow or dead endpoint never holds up
+//! the change, or a `SharedBank` lock held around it. After
+//! `DELIVERY_ATTEMPTS` tries the event is dropped and every observer
+//! registered when it was published hears of it through
+//! `BankObserver::on_webhook_failed`.
+
+use std::fmt;
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::errors::BankResult;
+use crate::notifications;
+use crate::observer::BankObserver;
+use super::{DomainEvent, EventKind};
+
+/// How many times an event is sent before giving up
+pub const DELIVERY_ATTEMPTS: u32 = 3;
+
+/// Wait before the first retry; doubled before each one after that
+pub const RETRY_DELAY: Duration = Duration::from_millis(200);
+
+/// A URL that is sent domain events
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Webhook {
+    /// Unique identifier (UUID v4)
+    pub id: String,
+
+    /// An `http://` URL
+    pub url: String,
+
+    /// The kinds sent to this URL; empty means every kind
+    #[serde(default)]
+    pub events: Vec<EventKind>,
+
+    pub created_at: DateTime<Utc>,
+}
+
+impl Webhook {
+    /// Creates a webhook, sorting and de-duplicating `events`
+    ///
+    /// # Returns
+    /// * `Err(BankError::InvalidInput)` - If the URL is not an `http://` URL
+    pub fn new(url: &str, mut events: Vec<EventKind>, created_at: DateTime<Utc>) -> BankResult<Self> {
+        let url = url.trim();
+        notifications::validate_url(url)?;
+        events.sort();
+        events.dedup();
+        Ok(Self { id: Uuid::new_v4().to_string(), url: url.to_string(), events, created_at })
+    }
+
+    /// Whether events of this kind are sent here
+    pub fn wants(&self, kind: EventKind) -> bool {
+        self.events.is_empty() || self.events.contains(&kind)
+    }
+}
+
+impl fmt::Display for Webhook {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let events = if self.events.is_empty() {
+            "all events".to_string()
+        } else {
+            self.events.iter().map(EventKind::as_str).collect::<Vec<_>>().join(", ")
+        };
+        write!(f, "{} → {} ({})", self.id, self.url, events)
+    }
+}
+
+/// POSTs an event to `url` as JSON, retrying failed attempts
+///
+/// # Returns
+/// * `Ok(u32)` - How many attempts it took
+/// * `Err(BankError::IoError)` - The last failure, once every attempt failed
+pub fn deliver(url: &str, event: &DomainEvent) -> BankResult<u32> {
+    let body = serde_json::to_string(event)?;
+
+    let mut delay = RETRY_DELAY;
+    let mut attempt = 1;
+    loop {
+        match notifications::post_json(url, &body) {
+            Ok(()) => return Ok(attempt),
+            Err(e) if attempt == DELIVERY_ATTEMPTS => return Err(e),
+            Err(_) => {
+                thread::sleep(delay);
+                delay *= 2;
+                attempt += 1;
+            }
+        }
+    }
+}
+
+/// One event on its way to one webhook
+struct Delivery {
+    url: String,
+    event: DomainEvent,
+    observers: Vec<Arc<dyn BankObserver>>,
+}
+
+/// Webhook deliveries waiting for the background thread, shared by clones
+/// of a bank
+///
+/// The thread starts with the first delivery. Dropping the last clone
+/// waits for what is already queued, so a command that posts an event
+/// still delivers it before the program exits.
+#[derive(Clone, Default)]
+pub(crate) struct DeliveryQueue {
+    worker: Arc<Mutex<Option<Worker>>>,
+}
+
+struct Worker {
+    sender: Option<Sender<Delivery>>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl DeliveryQueue {
+    /// Queues `event` for `url`; `observers` hear if it cannot be delivered
+    pub(crate) fn push(&self, url: &str, event: &DomainEvent, observers: &[Arc<dyn BankObserver>]) {
+        let delivery = Delivery { url: url.to_string(), event: event.clone(), observers: observers.to_vec() };
+        // A poisoned lock only means another thread panicked mid-push
+        let mut worker = self.worker.lock().unwrap_or_else(|e| e.into_inner());
+        let worker = worker.get_or_insert_with(Worker::start);
+        if let Some(sender) = &worker.sender {
+            // The thread only stops once the sender is dropped
+            let _ = sender.send(delivery);
+        }
+    }
+}
+
+impl fmt::Debug for DeliveryQueue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("DeliveryQueue").finish_non_exhaustive()
+    }
+}
+
+impl Worker {
+    fn start() -> Self {
+        let (sender, receiver) = mpsc::channel::<Delivery>();
+        let handle = thread::spawn(move || {
+            for delivery in receiver {
+                if let Err(e) = deliver(&delivery.url, &delivery.event) {
+                    for observer in &delivery.observers {
+                        observer.on_webhook_failed(&delivery.url, &delivery.event, &e);
+                    }
+                }
+            }
+        });
+        Self { sender: Some(sender), handle: Some(handle) }
+    }
+}
+
+impl Drop for Worker {
+    fn drop(&mut self) {
+        // Closing the channel ends the thread once the queue is empty
+        self.sender.take();
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
@@ -0,0 +1,319 @@
+//! Facade module - everything the CLI can do, as plain library calls
+//!
+//! Demonstrates: The facade pattern over a shared handle and a storage
+//! backend, closures taking `&Bank` / `&mut Bank` for the long tail
+//!
+//! `BankFacade` is what a program embedding the bank uses instead of the
+//! CLI (which needs the `cli` feature). It takes customers the way the
+//! CLI does (customer ID or a unique prefix of it, account number, email
+//! or account ID), resolves them with `Bank::resolve_customer`, and
+//! persists every change: journaled changes are appended to the storage's
+//! journal, and changes outside the journal (schedules) save the whole
+//! bank, exactly like command mode.
+//!
+//! The everyday operations have their own methods. The rest of the CLI's
+//! commands are one `Bank` method each, run through `update` (changes)
+//! or `query` (reads) so they get the same locking and saving:
+//!
+//! | CLI area                  | `Bank` methods                                                                  |
+//! |---------------------------|---------------------------------------------------------------------------------|
+//! | PINs and staff            | `set_pin`, `change_pin`, `add_staff`, `authenticate`                            |
+//! | Saved payees              | `add_beneficiary`, `remove_beneficiary`, `transfer_to_beneficiary`              |
+//! | External transfers        | `external_transfer`, `settle_external_transfer`, `reject_external_transfer`     |
+//! | Holds and authorizations  | `place_hold`, `capture_hold`, `authorize_withdrawal`, `capture`, `void`         |
+//! | Cheques                   | `deposit_cheque`, `clear_due_cheques`, `return_cheque`                          |
+//! | Cards                     | `issue_card`, `block_card`, `replace_card`, `card_purchase`                     |
+//! | Bills and autopay         | `add_merchant`, `pay_bill`, `create_autopay`, `run_due_autopays`                |
+//! | Savings goals and budgets | `create_envelope`, `allocate_to_envelope`, `set_budget`                         |
+//! | Term deposits             | `open_term_deposit`, `withdraw_term_deposit`, `process_maturities`              |
+//! | Alerts and webhooks       | `set_alert_rules`, `add_webhook`, `remove_webhook`                              |
+//! | Fraud review              | `set_fraud_rules`, `review_queue`, `approve_flagged`, `reverse_flagged`         |
+//! | Cash vault                | `vault`, `vault_top_up`, `vault_withdraw`                                       |
+//! | Bookkeeping               | `trial_balance`, `income_statement`, `set_chart_of_accounts`                    |
+//! | Analytics                 | `cash_flow`, `largest_transactions`, `most_active_accounts`, `dormant_accounts` |
+//! | Tags and summaries        | `tag_transaction`, `monthly_summary`                                            |
+//! | Batches and imports       | `apply_batch`, `import_customers`, `import_transactions`                        |
+//!
+//! ```
+//! use std::sync::Arc;
+//! use rust_banking_system::{AccountType, Bank, BankFacade, MemoryStorage, Money};
+//!
+//! let bank = BankFacade::new(Bank::new("Demo Bank".to_string()), Arc::new(MemoryStorage::new()));
+//! bank.register("Alice", "alice@example.com").unwrap();
+//! bank.open_account("alice@example.com", Money::from_major(50), AccountType::Checking).unwrap();
+//! bank.deposit("alice@example.com", Money::from_major(25)).unwrap();
+//!
+//! // Anything else the CLI can do, e.g. setting a PIN
+//! let alice = bank.resolve("alice@example.com").unwrap();
+//! bank.update(|bank| bank.set_pin(&alice, "4821")).unwrap();
+//! assert_eq!(bank.customer(alice.as_str()).unwrap().get_account().unwrap().balance, Money::from_major(75));
+//! ```
+
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+
+use crate::bank::{Bank, BankReport, IntegrityReport, SharedBank};
+use crate::errors::BankResult;
+use crate::models::{
+    AccountId, AccountLimits, AccountType, Customer, CustomerId, CustomerUpdate, Frequency, Money, Receipt,
+    ScheduledTransaction, Statement, Transaction, TransactionId,
+};
+use crate::persistence::Storage;
+
+/// A bank and the storage its changes go to, behind one API
+///
+/// Cloning is cheap and shares the bank, so one facade can serve many
+/// threads. Methods that return data hand back owned copies, taken under
+/// the read lock.
+#[derive(Clone)]
+pub struct BankFacade {
+    bank: SharedBank,
+    storage: Arc<dyn Storage>,
+}
+
+impl BankFacade {
+    /// Wraps a bank whose changes are saved to `storage`
+    pub fn new(bank: Bank, storage: Arc<dyn Storage>) -> Self {
+        Self::with_shared(SharedBank::new(bank), storage)
+    }
+
+    /// Wraps a bank that is already shared, e.g. with a server
+    pub fn with_shared(bank: SharedBank, storage: Arc<dyn Storage>) -> Self {
+        bank.write().enable_journal();
+        Self { bank, storage }
+    }
+
+    /// Loads the bank saved in `storage`
+    ///
+    /// Accounts from before account numbers get theirs, as when the CLI
+    /// opens an older file.
+    ///
+    /// # Returns
+    /// * `Err(BankError)` - If the storage cannot be read
+    pub fn open(storage: Arc<dyn Storage>) -> BankResult<Self> {
+        let bank = Bank::builder("").storage(storage.as_ref()).journal(true).build()?;
+        let facade = Self::with_shared(SharedBank::new(bank), storage);
+        facade.update(|bank| bank.assign_account_numbers())?;
+        Ok(facade)
+    }
+
+    /// The shared bank, for locking it directly
+    pub fn shared(&self) -> &SharedBank {
+        &self.bank
+    }
+
+    /// Runs a read-only operation under the read lock
+    pub fn query<T>(&self, operation: impl FnOnce(&Bank) -> T) -> T {
+        operation(&self.bank.read())
+    }
+
+    /// Runs an operation that changes the bank and saves what it changed
+    ///
+    /// The operation's own error comes first; a change that was made but
+    /// could not be saved is reported as the storage's error.
+    pub fn update<T>(&self, operation: impl FnOnce(&mut Bank) -> BankResult<T>) -> BankResult<T> {
+        let mut bank = self.bank.write();
+        let before = bank.unsaved_changes();
+        let result = operation(&mut bank);
+
+        let entries = bank.take_journal();
+        let saved = if !entries.is_empty() {
+            self.storage.append_journal(&bank, &entries)
+        } else if bank.unsaved_changes() > before {
+            // Changes the journal does not cover, such as schedules
+            self.storage.save_bank(&bank).map(|()| bank.mark_saved())
+        } else {
+            Ok(())
+        };
+        let value = result?;
+        saved.map(|()| value)
+    }
+
+    /// Saves the whole bank, folding the journal into the snapshot
+    pub fn save(&self) -> BankResult<()> {
+        let mut bank = self.bank.write();
+        self.storage.save_bank(&bank)?;
+        bank.mark_saved();
+        Ok(())
+    }
+
+    /// The ID of the customer `reference` names
+    ///
+    /// # Returns
+    /// * `Err(BankError::CustomerNotFound)` - If nothing matches
+    /// * `Err(BankError::InvalidInput)` - If an ID prefix matches several
+    ///   customers
+    pub fn resolve(&self, reference: &str) -> BankResult<CustomerId> {
+        self.bank.read().resolve_customer(reference)
+    }
+
+    /// Registers a customer
+    ///
+    /// # Returns
+    /// * `Ok(CustomerId)` - The new customer's ID
+    /// * `Err(BankError::CustomerAlreadyExists)` - If the email is in use
+    pub fn register(&self, name: &str, email: &str) -> BankResult<CustomerId> {
+        self.update(|bank| bank.register_customer(name.to_string(), email.to_string()))
+    }
+
+    /// Opens `customer`'s account with an opening deposit
+    pub fn open_account(&self, customer: &str, deposit: Money, account_type: AccountType) -> BankResult<AccountId> {
+        self.update(|bank| {
+            let id = bank.resolve_customer(customer)?;
+            bank.create_account_for_customer(&id, deposit, account_type)
+        })
+    }
+
+    /// A customer with their account
+    pub fn customer(&self, reference: &str) -> BankResult<Customer> {
+        let bank = self.bank.read();
+        let id = bank.resolve_customer(reference)?;
+        bank.get_customer(&id).cloned()
+    }
+
+    /// Every customer not deleted, by name
+    pub fn customers(&self) -> Vec<Customer> {
+        let mut customers: Vec<Customer> = self.bank.read().list_customers().into_iter().cloned().collect();
+        customers.sort_by(|a, b| a.name.cmp(&b.name).then_with(|| a.id.cmp(&b.id)));
+        customers
+    }
+
+    /// Customers whose name contains `query`, ignoring case, by name
+    pub fn search(&self, query: &str) -> Vec<Customer> {
+        let mut customers: Vec<Customer> =
+            self.bank.read().find_customers_by_name(query).into_iter().cloned().collect();
+        customers.sort_by(|a, b| a.name.cmp(&b.name).then_with(|| a.id.cmp(&b.id)));
+        customers
+    }
+
+    /// Changes a customer's name or contact details
+    pub fn update_customer(&self, customer: &str, changes: CustomerUpdate) -> BankResult<()> {
+        self.update(|bank| bank.update_customer(&bank.resolve_customer(customer)?, changes))
+    }
+
+    pub fn deactivate_customer(&self, customer: &str) -> BankResult<()> {
+        self.update(|bank| bank.deactivate_customer(&bank.resolve_customer(customer)?))
+    }
+
+    pub fn reactivate_customer(&self, customer: &str) -> BankResult<()> {
+        self.update(|bank| bank.reactivate_customer(&bank.resolve_customer(customer)?))
+    }
+
+    /// Deletes a customer whose account is closed or who never had one
+    pub fn delete_customer(&self, customer: &str) -> BankResult<()> {
+        self.update(|bank| bank.delete_customer(&bank.resolve_customer(customer)?))
+    }
+
+    /// # Returns
+    /// * `Ok(Money)` - The new balance
+    pub fn deposit(&self, customer: &str, amount: Money) -> BankResult<Money> {
+        self.update(|bank| bank.deposit(&bank.resolve_customer(customer)?, amount))
+    }
+
+    /// # Returns
+    /// * `Ok(Money)` - The new balance
+    pub fn withdraw(&self, customer: &str, amount: Money) -> BankResult<Money> {
+        self.update(|bank| bank.withdraw(&bank.resolve_customer(customer)?, amount))
+    }
+
+    /// Moves money between two customers of this bank
+    pub fn transfer(&self, from: &str, to: &str, amount: Money) -> BankResult<()> {
+        self.update(|bank| bank.transfer(&bank.resolve_customer(from)?, &bank.resolve_customer(to)?, amount))
+    }
+
+    /// Replaces the withdrawal and transfer caps of `customer`'s account
+    pub fn set_limits(&self, customer: &str, limits: AccountLimits) -> BankResult<()> {
+        self.update(|bank| bank.set_limits(&bank.resolve_customer(customer)?, limits))
+    }
+
+    pub fn freeze_account(&self, customer: &str) -> BankResult<()> {
+        self.update(|bank| bank.freeze_account(&bank.resolve_customer(customer)?))
+    }
+
+    pub fn unfreeze_account(&self, customer: &str) -> BankResult<()> {
+        self.update(|bank| bank.unfreeze_account(&bank.resolve_customer(customer)?))
+    }
+
+    /// Closes `customer`'s account, paying the balance out in cash or to
+    /// `transfer_to`
+    ///
+    /// # Returns
+    /// * `Ok(Money)` - The balance paid out
+    pub fn close_account(&self, customer: &str, transfer_to: Option<&str>) -> BankResult<Money> {
+        self.update(|bank| {
+            let id = bank.resolve_customer(customer)?;
+            let transfer_to = transfer_to.map(|to| bank.resolve_customer(to)).transpose()?;
+            bank.close_account(&id, transfer_to.as_ref())
+        })
+    }
+
+    /// The `limit` newest transactions, newest first, paging archived
+    /// ones back in if needed
+    pub fn history(&self, customer: &str, limit: usize) -> BankResult<Vec<Transaction>> {
+        let bank = self.bank.read();
+        let history = bank.get_transaction_history(&bank.resolve_customer(customer)?)?;
+        history.take(limit).map(|tx| tx.map(|tx| tx.into_owned())).collect()
+    }
+
+    /// `customer`'s statement for the period `[from, to)`
+    pub fn statement(&self, customer: &str, from: DateTime<Utc>, to: DateTime<Utc>) -> BankResult<Statement> {
+        let bank = self.bank.read();
+        bank.statement(&bank.resolve_customer(customer)?, from, to)
+    }
+
+    /// The receipt of one transaction
+    pub fn receipt(&self, transaction_id: &TransactionId) -> BankResult<Receipt> {
+        self.bank.read().get_receipt(transaction_id)
+    }
+
+    /// Reverses a transaction, recording `reason`
+    ///
+    /// # Returns
+    /// * `Ok(Vec<Transaction>)` - The reversing transactions
+    pub fn reverse(&self, transaction_id: &TransactionId, reason: &str) -> BankResult<Vec<Transaction>> {
+        self.update(|bank| bank.reverse_transaction(transaction_id, reason))
+    }
+
+    /// Reverses the last deposit, withdrawal or transfer
+    pub fn undo(&self) -> BankResult<Vec<Transaction>> {
+        self.update(|bank| bank.undo_last())
+    }
+
+    /// Creates a standing order from `from` to `to`
+    ///
+    /// # Returns
+    /// * `Ok(String)` - The schedule ID
+    pub fn schedule_transfer(
+        &self,
+        from: &str,
+        to: &str,
+        amount: Money,
+        frequency: Frequency,
+        first_run: DateTime<Utc>,
+    ) -> BankResult<String> {
+        self.update(|bank| {
+            let (from, to) = (bank.resolve_customer(from)?, bank.resolve_customer(to)?);
+            bank.create_schedule(&from, &to, amount, frequency, first_run)
+        })
+    }
+
+    /// Active schedules, soonest first
+    pub fn schedules(&self) -> Vec<ScheduledTransaction> {
+        self.bank.read().list_schedules().into_iter().cloned().collect()
+    }
+
+    pub fn cancel_schedule(&self, schedule_id: &str) -> BankResult<()> {
+        self.update(|bank| bank.cancel_schedule(schedule_id))
+    }
+
+    /// The statistics report (`stats`)
+    pub fn report(&self) -> BankReport {
+        self.bank.report()
+    }
+
+    /// Checks that balances, histories and the books agree (`audit`)
+    pub fn audit(&self) -> IntegrityReport {
+        self.bank.read().verify_integrity()
+    }
+}
@@ -0,0 +1,196 @@
+//! Format module - how amounts and dates are shown to people
+//!
+//! Demonstrates: A process-wide setting kept in a `LazyLock<RwLock<_>>`,
+//! and a small wrapper type whose `Display` reads that setting
+//! https://doc.rust-lang.org/std/sync/struct.LazyLock.html
+//!
+//! `Money`'s own `Display` stays plain (`-1234.50`) because data files,
+//! CSV exports and JSON depend on it. Text meant for a person goes through
+//! `Money::display()`, [`date`] and [`datetime`] instead, which follow the
+//! installed [`Format`]. The program installs one built from the config
+//! (`Config::format`) at startup; until then the neutral defaults apply:
+//! `$1,234.50` and ISO dates.
+//!
+//! ```
+//! use rust_banking_system::format::Format;
+//! use rust_banking_system::Money;
+//!
+//! let german = Format::for_locale("de-DE", "€");
+//! assert_eq!(german.money(Money::from_cents(123_450)), "1.234,50 €");
+//! ```
+
+use std::fmt;
+use std::sync::{LazyLock, PoisonError, RwLock};
+
+use chrono::{DateTime, Utc};
+
+use crate::models::Money;
+
+/// The format used until another one is installed
+static CURRENT: LazyLock<RwLock<Format>> = LazyLock::new(|| RwLock::new(Format::default()));
+
+/// How amounts and dates are written
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Format {
+    /// Shown with every amount, e.g. `$` or `€`
+    pub currency_symbol: String,
+
+    /// Write the symbol after the number (`12,50 €`) instead of before it
+    pub symbol_after: bool,
+
+    /// Put between groups of three digits, if anything
+    pub thousands_separator: Option<char>,
+
+    pub decimal_separator: char,
+
+    /// Digits after the decimal separator, 0 to 2; amounts are rounded
+    /// half away from zero when fewer than 2
+    pub decimal_places: u8,
+
+    /// `chrono` format string for dates
+    pub date_format: String,
+
+    /// `chrono` format string for a date with a time of day
+    pub datetime_format: String,
+}
+
+impl Default for Format {
+    fn default() -> Self {
+        Self {
+            currency_symbol: "$".to_string(),
+            symbol_after: false,
+            thousands_separator: Some(','),
+            decimal_separator: '.',
+            decimal_places: 2,
+            date_format: "%Y-%m-%d".to_string(),
+            datetime_format: "%Y-%m-%d %H:%M".to_string(),
+        }
+    }
+}
+
+impl Format {
+    /// The usual way of writing numbers and dates for a locale such as
+    /// `en-US`, `en-GB` or `de-DE`, shown with `currency_symbol`
+    ///
+    /// Locales without a preset of their own use the defaults (ISO dates).
+    pub fn for_locale(locale: &str, currency_symbol: &str) -> Self {
+        let mut parts = locale.splitn(2, ['-', '_']);
+        let language = parts.next().unwrap_or("");
+        let region = parts.next().unwrap_or("");
+        let dates = |date: &str, time: &str| (date.to_string(), format!("{} {}", date, time));
+
+        let mut format = Format { currency_symbol: currency_symbol.to_string(), ..Format::default() };
+        let (date_format, datetime_format) = match (language, region) {
+            ("en", "" | "US") => dates("%m/%d/%Y", "%I:%M %p"),
+            ("en", "CA") => dates("%Y-%m-%d", "%H:%M"),
+            ("en", _) => dates("%d/%m/%Y", "%H:%M"),
+            ("de", _) => {
+                format.thousands_separator = Some('.');
+                format.decimal_separator = ',';
+                format.symbol_after = true;
+                dates("%d.%m.%Y", "%H:%M")
+            }
+            ("fr", _) => {
+                format.thousands_separator = Some('\u{a0}');
+                format.decimal_separator = ',';
+                format.symbol_after = true;
+                dates("%d/%m/%Y", "%H:%M")
+            }
+            ("es" | "it" | "pt" | "nl", _) => {
+                format.thousands_separator = Some('.');
+                format.decimal_separator = ',';
+                format.symbol_after = language != "nl";
+                dates("%d/%m/%Y", "%H:%M")
+            }
+            ("ja" | "zh", _) => dates("%Y/%m/%d", "%H:%M"),
+            _ => return format,
+        };
+        format.date_format = date_format;
+        format.datetime_format = datetime_format;
+        format
+    }
+
+    /// Writes `amount` with the symbol, separators and decimal places of
+    /// this format, e.g. `-$1,234.50`
+    pub fn money(&self, amount: Money) -> String {
+        let places = u32::from(self.decimal_places.min(2));
+        let step = 10u64.pow(2 - places);
+        let units = (amount.cents().unsigned_abs() + step / 2) / step;
+        let scale = 10u64.pow(places);
+
+        let mut number = self.group(units / scale);
+        if places > 0 {
+            number.push(self.decimal_separator);
+            number.push_str(&format!("{:0width$}", units % scale, width = places as usize));
+        }
+        let sign = if amount.is_negative() && units > 0 { "-" } else { "" };
+        if self.symbol_after {
+            format!("{}{} {}", sign, number, self.currency_symbol)
+        } else {
+            format!("{}{}{}", sign, self.currency_symbol, number)
+        }
+    }
+
+    /// Writes the calendar date of `at`
+    pub fn date(&self, at: &DateTime<Utc>) -> String {
+        at.format(&self.date_format).to_string()
+    }
+
+    /// Writes `at` with its time of day
+    pub fn datetime(&self, at: &DateTime<Utc>) -> String {
+        at.format(&self.datetime_format).to_string()
+    }
+
+    /// `whole` with the thousands separator between groups of three digits
+    fn group(&self, whole: u64) -> String {
+        let digits = whole.to_string();
+        let separator = match self.thousands_separator {
+            Some(separator) => separator,
+            None => return digits,
+        };
+        let mut grouped = String::with_capacity(digits.len() + digits.len() / 3);
+        for (i, digit) in digits.chars().enumerate() {
+            if i > 0 && (digits.len() - i).is_multiple_of(3) {
+                grouped.push(separator);
+            }
+            grouped.push(digit);
+        }
+        grouped
+    }
+}
+
+/// Makes `format` the one used from now on by `Money::display`, [`date`]
+/// and [`datetime`]
+pub fn install(format: Format) {
+    *CURRENT.write().unwrap_or_else(PoisonError::into_inner) = format;
+}
+
+/// A copy of the format currently installed
+pub fn current() -> Format {
+    with_current(Format::clone)
+}
+
+/// Writes the calendar date of `at` in the installed format
+pub fn date(at: &DateTime<Utc>) -> String {
+    with_current(|format| format.date(at))
+}
+
+/// Writes `at` with its time of day in the installed format
+pub fn datetime(at: &DateTime<Utc>) -> String {
+    with_current(|format| format.datetime(at))
+}
+
+fn with_current<T>(f: impl FnOnce(&Format) -> T) -> T {
+    f(&CURRENT.read().unwrap_or_else(PoisonError::into_inner))
+}
+
+/// An amount written in the installed format; see `Money::display`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MoneyDisplay(pub(crate) Money);
+
+impl fmt::Display for MoneyDisplay {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // pad keeps width/alignment specifiers like {:>12} working
+        f.pad(&with_current(|format| format.money(self.0)))
+    }
+}
@@ -0,0 +1,229 @@
+//! gRPC module - tonic service for the bank (`grpc` feature)
+//!
+//! Demonstrates: Code generation from protobuf (see `build.rs`), implementing
+//! an async trait generated by tonic, From conversions between domain and
+//! wire types
+//! https://docs.rs/tonic/latest/tonic/
+//!
+//! The service definition lives in `proto/bank.proto`. Like the HTTP server,
+//! the service wraps the same `SharedBank` and `Storage` backend as
+//! the interactive CLI, so every change is journaled to the data file.
+
+use std::io;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use tonic::metadata::MetadataValue;
+use tonic::transport::Server;
+use tonic::{Code, Request, Response, Status};
+
+use crate::bank::{Bank, SharedBank};
+use crate::errors::{BankError, BankResult};
+use crate::models::{AccountType, CustomerId, Money, Transaction};
+use crate::persistence::Storage;
+
+/// Types generated from `proto/bank.proto`
+pub mod proto {
+    tonic::include_proto!("banking");
+}
+
+use proto::bank_service_server::{BankService, BankServiceServer};
+
+/// Maps a domain error onto the closest gRPC status code, with its
+/// `code()` and `kind()` in the `error-code` and `error-kind` metadata
+impl From<BankError> for Status {
+    fn from(error: BankError) -> Self {
+        let code = match &error {
+            BankError::CustomerNotFound(_)
+            | BankError::AccountNotFound(_)
+            | BankError::ScheduleNotFound(_)
+            | BankError::BeneficiaryNotFound(_)
+            | BankError::WebhookNotFound(_)
+            | BankError::CardNotFound(_)
+            | BankError::MerchantNotFound(_)
+            | BankError::EnvelopeNotFound(_)
+            | BankError::BudgetNotFound(_)
+            | BankError::TermDepositNotFound(_)
+            | BankError::TransactionNotFound(_) => Code::NotFound,
+            BankError::CustomerAlreadyExists(_) => Code::AlreadyExists,
+            BankError::InvalidPin { .. }
+            | BankError::PinNotSet(_)
+            | BankError::AuthenticationFailed { .. } => Code::Unauthenticated,
+            BankError::PinLocked { .. } | BankError::ReadOnly(_) => Code::PermissionDenied,
+            BankError::InsufficientFunds { .. }
+            | BankError::InsufficientVaultCash { .. }
+            | BankError::WithdrawalLimitReached { .. }
+            | BankError::LimitExceeded(_)
+            | BankError::AccountFrozen(_)
+            | BankError::AccountClosed(_)
+            | BankError::CustomerDeactivated(_)
+            | BankError::CardDeclined(_)
+            | BankError::EnvelopeLocked(_)
+            | BankError::AccountLocked { .. } => Code::FailedPrecondition,
+            BankError::InvalidInput(_)
+            | BankError::ValidationError { .. }
+            | BankError::InvalidAmount(_)
+            | BankError::InvalidTransfer(_) => Code::InvalidArgument,
+            BankError::StorageCorrupted { .. } => Code::DataLoss,
+            BankError::IoError(_)
+            | BankError::SerializationError(_)
+            | BankError::EncryptionError(_) => Code::Internal,
+        };
+        let mut status = Status::new(code, error.to_string());
+        // Clients branch on these rather than on the message
+        status.metadata_mut().insert("error-code", error.code().into());
+        status.metadata_mut().insert("error-kind", MetadataValue::from_static(error.kind()));
+        status
+    }
+}
+
+impl From<&Transaction> for proto::Transaction {
+    fn from(tx: &Transaction) -> Self {
+        proto::Transaction {
+            id: tx.id.to_string(),
+            transaction_type: tx.transaction_type.code().to_string(),
+            amount: tx.amount.to_string(),
+            balance_after: tx.balance_after.to_string(),
+            timestamp: tx.timestamp.to_rfc3339(),
+            counterparty_account_id: tx
+                .transaction_type
+                .counterparty()
+                .unwrap_or_default()
+                .to_string(),
+        }
+    }
+}
+
+/// The gRPC service backed by a shared bank
+#[derive(Clone)]
+pub struct GrpcBank {
+    bank: SharedBank,
+    storage: Arc<dyn Storage>,
+}
+
+impl GrpcBank {
+    /// Creates a service over the shared bank and its storage backend
+    pub fn new(bank: SharedBank, storage: Arc<dyn Storage>) -> Self {
+        Self { bank, storage }
+    }
+
+    /// Runs `operation` on the locked bank and journals any changes it made
+    ///
+    /// A journal write failure is logged but does not fail the call: the
+    /// change is already applied in memory and goes out with the next save.
+    fn with_bank<T>(&self, operation: impl FnOnce(&mut Bank) -> BankResult<T>) -> BankResult<T> {
+        let mut bank = self.bank.write();
+        let result = operation(&mut bank);
+
+        let entries = bank.take_journal();
+        if !entries.is_empty() {
+            if let Err(e) = self.storage.append_journal(&bank, &entries) {
+                eprintln!("⚠️  Could not save changes: {}", e);
+            }
+        }
+        result
+    }
+}
+
+/// Parses a decimal amount field
+fn parse_amount(amount: &str) -> BankResult<Money> {
+    amount
+        .parse()
+        .map_err(|e: crate::models::ParseMoneyError| BankError::InvalidInput(e.to_string()))
+}
+
+#[tonic::async_trait]
+impl BankService for GrpcBank {
+    async fn register_customer(
+        &self,
+        request: Request<proto::RegisterCustomerRequest>,
+    ) -> Result<Response<proto::RegisterCustomerResponse>, Status> {
+        let request = request.into_inner();
+        let customer_id = self.with_bank(|bank| bank.register_customer(request.name, request.email))?;
+        Ok(Response::new(proto::RegisterCustomerResponse { customer_id: customer_id.into() }))
+    }
+
+    async fn create_account(
+        &self,
+        request: Request<proto::CreateAccountRequest>,
+    ) -> Result<Response<proto::CreateAccountResponse>, Status> {
+        let request = request.into_inner();
+        let initial_deposit = parse_amount(&request.initial_deposit)?;
+
+        let account_type = match proto::AccountKind::try_from(request.account_type) {
+            Ok(proto::AccountKind::Checking) => AccountType::Checking,
+            Ok(proto::AccountKind::Savings) => AccountType::Savings,
+            Ok(proto::AccountKind::FixedDeposit) => {
+                let term = if request.term_months == 0 { 12 } else { request.term_months };
+                AccountType::fixed_deposit(term)
+                    .ok_or_else(|| BankError::InvalidInput("invalid term".to_string()))?
+            }
+            Err(_) => return Err(BankError::InvalidInput("unknown account type".to_string()).into()),
+        };
+
+        let customer_id = CustomerId::from(request.customer_id);
+        let account_id = self.with_bank(|bank| {
+            bank.create_account_for_customer(&customer_id, initial_deposit, account_type)
+        })?;
+        Ok(Response::new(proto::CreateAccountResponse { account_id: account_id.into() }))
+    }
+
+    async fn deposit(
+        &self,
+        request: Request<proto::AmountRequest>,
+    ) -> Result<Response<proto::BalanceResponse>, Status> {
+        let request = request.into_inner();
+        let amount = parse_amount(&request.amount)?;
+        let customer_id = CustomerId::from(request.customer_id);
+        let balance = self.with_bank(|bank| bank.deposit(&customer_id, amount))?;
+        Ok(Response::new(proto::BalanceResponse { balance: balance.to_string() }))
+    }
+
+    async fn withdraw(
+        &self,
+        request: Request<proto::AmountRequest>,
+    ) -> Result<Response<proto::BalanceResponse>, Status> {
+        let request = request.into_inner();
+        let amount = parse_amount(&request.amount)?;
+        let customer_id = CustomerId::from(request.customer_id);
+        let balance = self.with_bank(|bank| bank.withdraw(&customer_id, amount))?;
+        Ok(Response::new(proto::BalanceResponse { balance: balance.to_string() }))
+    }
+
+    async fn transfer(
+        &self,
+        request: Request<proto::TransferRequest>,
+    ) -> Result<Response<proto::TransferResponse>, Status> {
+        let request = request.into_inner();
+        let amount = parse_amount(&request.amount)?;
+        let (from, to) = (CustomerId::from(request.from_customer_id), CustomerId::from(request.to_customer_id));
+        self.with_bank(|bank| bank.transfer(&from, &to, amount))?;
+        Ok(Response::new(proto::TransferResponse { transferred: amount.to_string() }))
+    }
+
+    async fn get_history(
+        &self,
+        request: Request<proto::GetHistoryRequest>,
+    ) -> Result<Response<proto::GetHistoryResponse>, Status> {
+        let request = request.into_inner();
+        let bank = self.bank.read();
+        let customer_id = CustomerId::from(request.customer_id);
+        let mut history = bank.get_transaction_history(&customer_id)?.collect::<BankResult<Vec<_>>>()?;
+        history.reverse();
+
+        let transactions = history.iter().map(|tx| tx.as_ref().into()).collect();
+        Ok(Response::new(proto::GetHistoryResponse { transactions }))
+    }
+}
+
+/// Serves the gRPC API on `0.0.0.0:<port>` until the process is stopped
+pub async fn serve(bank: SharedBank, storage: Arc<dyn Storage>, port: u16) -> io::Result<()> {
+    let addr = SocketAddr::from(([0, 0, 0, 0], port));
+    eprintln!("📡 Serving the bank gRPC API on {}", addr);
+
+    Server::builder()
+        .add_service(BankServiceServer::new(GrpcBank::new(bank, storage)))
+        .serve(addr)
+        .await
+        .map_err(io::Error::other)
+}
@@ -5,39 +5,97 @@
 //! ## Module Organization
 //!
 //! - `errors` - Custom error types and error handling
+//! - `clock` - The bank's time source, replaceable in tests
+//! - `config` - Settings read from a config file
+//! - `format` - How amounts and dates are shown (currency, separators, locale)
 //! - `models` - Data structures (Transaction, Account, Customer)
-//! - `bank` - Core banking logic
+//! - `bank` - Core banking logic (`bank::async_api` requires the `async` feature)
 //! - `traits` - Custom trait definitions
+//! - `validation` - Checks for customer-supplied details
 //! - `persistence` - Data saving/loading
-//! - `cli` - Command-line interface
+//! - `notifications` - Where triggered balance alerts are sent
+//! - `events` - Domain events for callbacks and webhooks
+//! - `observer` - `BankObserver` hooks for embedders
+//! - `middleware` - The pipeline of checks around deposits, withdrawals and transfers
+//! - `metrics` - Operational metrics and exporters
+//! - `facade` - `BankFacade`, every CLI operation as a library call
+//! - `cli` - Command-line interface (requires the `cli` feature, on by default)
+//! - `server` - HTTP REST API (requires the `server` feature)
+//! - `grpc` - gRPC service (requires the `grpc` feature)
+//! - `tui` - Full-screen terminal browser (requires the `tui` feature)
 //!
 //! ## Usage
 //!
 //! ```no_run
+//! # #[cfg(feature = "cli")] {
 //! use rust_banking_system::cli::BankCLI;
+//! use rust_banking_system::persistence::JsonFileStorage;
 //!
 //! let mut cli = BankCLI::new(
 //!     "My Bank".to_string(),
-//!     "bank_data.json".to_string()
+//!     Box::new(JsonFileStorage::new("bank_data.json"))
 //! );
 //! cli.run().unwrap();
+//! # }
 //! ```
+//!
+//! ## As a library
+//!
+//! Servers and other programs that only need the core can turn the CLI
+//! off, which also drops its terminal dependencies (clap, rustyline and
+//! friends):
+//!
+//! ```toml
+//! rust-banking-system = { version = "0.1", default-features = false }
+//! ```
+//!
+//! `BankFacade` then offers everything the CLI can do, with customers
+//! referred to the same way (ID, account number or email).
 
 // Module declarations
 // Demonstrates: Module system and visibility control
 // https://doc.rust-lang.org/book/ch07-00-managing-growing-projects-with-packages-crates-and-modules.html
 
 pub mod errors;
+pub mod clock;
+pub mod config;
+pub mod format;
 pub mod models;
 pub mod bank;
 pub mod traits;
+pub mod validation;
 pub mod persistence;
+pub mod notifications;
+pub mod events;
+pub mod observer;
+pub mod middleware;
+pub mod metrics;
+pub mod facade;
+#[cfg(feature = "cli")]
 pub mod cli;
+#[cfg(feature = "server")]
+pub mod server;
+#[cfg(feature = "grpc")]
+pub mod grpc;
+#[cfg(feature = "tui")]
+pub mod tui;
 
 // Re-export commonly used types for convenience
 // This allows users to write `use rust_banking_system::Bank` instead of
 // `use rust_banking_system::bank::Bank`
-pub use bank::Bank;
-pub use errors::{BankError, BankResult};
-pub use models::{Transaction, TransactionType, Account, Customer};
-pub use traits::Summarizable;
+pub use bank::{AutopayRun, Bank, BankEvent, ScheduleRun, SharedBank};
+#[cfg(feature = "async")]
+pub use bank::AsyncBank;
+pub use config::Config;
+pub use errors::{BankError, BankResult, Cause};
+pub use models::{
+    Transaction, TransactionType, Account, AccountType, Customer, Money,
+    Frequency, ScheduledTransaction, Statement, MonthlySummary,
+};
+pub use events::{DomainEvent, EventKind};
+pub use facade::BankFacade;
+pub use middleware::{OperationMiddleware, Pipeline};
+pub use notifications::Notifier;
+pub use observer::{BankObserver, LoggingObserver};
+pub use persistence::{EncryptedFileStorage, JsonFileStorage, MemoryStorage, Storage};
+pub use traits::{Auditable, AuditFact, Summarizable};
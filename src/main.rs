@@ -11,6 +11,8 @@
 //!
 //! ## Module Structure
 //! - `errors` - Custom error handling
+//! - `config` - Settings from `bank.toml` or `--config`
+//! - `format` - Currency and date formatting for the locale
 //! - `models` - Data structures (Transaction, Account, Customer)
 //! - `bank` - Core banking logic
 //! - `traits` - Custom trait definitions
@@ -21,7 +23,7 @@
 //! - Ownership and Borrowing
 //! - Traits and Trait Objects
 //! - Error Handling with Result and Option
-//! - Smart Pointers (Arc, Mutex)
+//! - Smart Pointers (Arc, RwLock)
 //! - Iterators and Closures
 //! - Pattern Matching
 //! - Generics and Type Aliases
@@ -33,8 +35,30 @@
 //! - [Rust By Example](https://doc.rust-lang.org/rust-by-example/)
 //! - [Serde Documentation](https://serde.rs/)
 
-use std::io;
-use rust_banking_system::cli::BankCLI;
+use std::io::{self, IsTerminal};
+use std::process;
+#[cfg(any(feature = "server", feature = "grpc"))]
+use std::sync::Arc;
+
+use clap::Parser;
+use rust_banking_system::cli::i18n::{self, Language};
+use rust_banking_system::cli::table;
+use rust_banking_system::cli::theme::TextStyle;
+use rust_banking_system::cli::args::{Args, Command};
+use rust_banking_system::cli::{self, script, shell, BankCLI, JsonRenderer, Renderer, TextRenderer};
+use rust_banking_system::format;
+#[cfg(any(feature = "server", feature = "grpc", feature = "tui"))]
+use rust_banking_system::Config;
+#[cfg(any(feature = "server", feature = "grpc", feature = "tui"))]
+use rust_banking_system::SharedBank;
+#[cfg(feature = "tui")]
+use rust_banking_system::Bank;
+#[cfg(any(feature = "server", feature = "grpc"))]
+use rust_banking_system::{BankError, Storage};
+
+/// The bank and storage backend shared by a server's request handlers
+#[cfg(any(feature = "server", feature = "grpc"))]
+type ServerState = (SharedBank, Arc<dyn Storage>);
 
 /// Main entry point for the banking application
 ///
@@ -43,17 +67,119 @@ use rust_banking_system::cli::BankCLI;
 /// - Module imports and usage
 /// - Clean separation of concerns
 ///
+/// With a subcommand (e.g. `deposit --customer <id> --amount 50`) a single
+/// operation runs and prints JSON; without one the interactive menu starts.
+/// `--json` makes the interactive menu print its results as JSON too.
+/// Feature-gated subcommands start the long-running modes: `serve --port`
+/// (HTTP API, `server`), `grpc --port` (gRPC, `grpc`) and `tui` (`tui`);
+/// `shell` reads one command per line until `exit`, and `run <script>`
+/// reads them from a file.
+/// The bank name, data file and other settings come from the config file
+/// (see the `config` module), overridden by `--data-file`, `--bank-name`,
+/// `--read-only` and `BANK_DATA_FILE`; invalid settings stop the program.
+/// The config's locale (or `--locale`) and currency settings decide the
+/// menu's language and how amounts and dates are shown from then on.
+/// `--theme`, `--no-color` and `--ascii` style the menu's text output, and
+/// the config's column lists shape its tables. `--arrow-menu` picks menu
+/// entries with the arrow keys when the `arrow-menu` feature is built in.
+///
 /// # Returns
 /// * `Ok(())` - Application exited normally
 /// * `Err(io::Error)` - If an I/O error occurred
 fn main() -> io::Result<()> {
-    // Create and run the CLI
-    let mut cli = BankCLI::new(
-        "Rust National Bank".to_string(),
-        "bank_data.json".to_string(),
-    );
+    let args = Args::parse();
+    let config = match args.startup.load() {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("❌ Invalid config: {}", e);
+            process::exit(2);
+        }
+    };
+    format::install(config.format());
+    i18n::set_language(Language::from_locale(&config.locale));
+    let (customer_columns, transaction_columns) = config.table_columns();
+    table::set_columns(customer_columns, transaction_columns);
+    cli::set_verbose(config.verbose);
+
+    match args.command {
+        #[cfg(feature = "server")]
+        Some(Command::Serve { port }) => return serve(&config, port),
+        #[cfg(feature = "grpc")]
+        Some(Command::Grpc { port }) => return serve_grpc(&config, port),
+        #[cfg(feature = "tui")]
+        Some(Command::Tui) => return run_tui(&config),
+        Some(Command::Shell) => process::exit(shell::run_shell(&config, args.json)),
+        Some(Command::Run { script, keep_going }) => {
+            process::exit(script::run_script(&config, &script, keep_going, args.json))
+        }
+        Some(command) => process::exit(cli::run_command(command, &config)),
+        None => {}
+    }
 
-    
+    // Pick the renderer first so that JSON mode also moves the passphrase
+    // prompts off stdout
+    let renderer: Box<dyn Renderer> = if args.json {
+        Box::new(JsonRenderer::new())
+    } else {
+        Box::new(TextRenderer::new(TextStyle::for_terminal(&config)))
+    };
+
+    // Unlock (or optionally encrypt) the data file, then run the CLI
+    let storage = cli::open_storage(&config)?;
+    let mut cli = BankCLI::new(config.bank_name.clone(), storage);
+    cli.set_renderer(renderer);
+    cli.set_autosave(config.autosave && !config.read_only);
+    cli.set_read_only(config.read_only);
+    if !config.read_only {
+        if let Err(e) = cli.set_history_cap(config.history_cap) {
+            eprintln!("⚠️  Keeping every transaction in memory: {}", e);
+        }
+    }
+    // The arrow menu draws on the terminal, so it is no use for JSON output
+    // or input piped in from a file
+    if config.arrow_menu && !cfg!(feature = "arrow-menu") {
+        eprintln!("⚠️  The arrow-key menu needs the arrow-menu feature; using the numbered menu");
+    }
+    cli.set_arrow_menu(config.arrow_menu && !args.json && io::stdin().is_terminal() && io::stdout().is_terminal());
 
     cli.run()
 }
+
+/// Loads the bank for a long-running server mode
+///
+/// Encrypted data files are unlocked with `BANK_PASSPHRASE`, as in the
+/// command mode.
+#[cfg(any(feature = "server", feature = "grpc"))]
+fn load_shared_bank(config: &Config) -> io::Result<ServerState> {
+    let to_io = |e: BankError| io::Error::other(e.to_string());
+    let storage = cli::open_command_storage(config).map_err(to_io)?;
+    let bank = cli::load_or_create(storage.as_ref(), config).map_err(to_io)?;
+    Ok((SharedBank::new(bank), Arc::from(storage)))
+}
+
+/// Serves the bank over HTTP until the process is stopped
+#[cfg(feature = "server")]
+fn serve(config: &Config, port: u16) -> io::Result<()> {
+    let (bank, storage) = load_shared_bank(config)?;
+    let runtime = tokio::runtime::Runtime::new()?;
+    runtime.block_on(rust_banking_system::server::serve(bank, storage, port))
+}
+
+/// Serves the bank over gRPC until the process is stopped
+#[cfg(feature = "grpc")]
+fn serve_grpc(config: &Config, port: u16) -> io::Result<()> {
+    let (bank, storage) = load_shared_bank(config)?;
+    let runtime = tokio::runtime::Runtime::new()?;
+    runtime.block_on(rust_banking_system::grpc::serve(bank, storage, port))
+}
+
+/// Unlocks the data file and browses it in the full-screen TUI
+#[cfg(feature = "tui")]
+fn run_tui(config: &Config) -> io::Result<()> {
+    let storage = cli::open_storage(config)?;
+    let bank = storage
+        .load_bank()
+        .unwrap_or_else(|_| Bank::new(config.bank_name.clone()));
+
+    rust_banking_system::tui::run(SharedBank::new(bank))
+}
@@ -0,0 +1,92 @@
+//! Metrics exporters - turning a snapshot into text
+//!
+//! Demonstrates: A small trait with interchangeable implementations,
+//! writing into a `String` with `fmt::Write`
+
+use std::fmt::Write;
+
+use super::MetricsSnapshot;
+
+/// Renders metrics for a monitoring system
+pub trait MetricsExporter: Send + Sync {
+    /// The HTTP `Content-Type` of the rendered text
+    fn content_type(&self) -> &'static str;
+
+    /// Renders a snapshot
+    fn export(&self, snapshot: &MetricsSnapshot) -> String;
+}
+
+/// The Prometheus text exposition format
+/// https://prometheus.io/docs/instrumenting/exposition_formats/
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PrometheusExporter;
+
+impl PrometheusExporter {
+    fn header(out: &mut String, name: &str, kind: &str, help: &str) {
+        // Writing to a String cannot fail
+        let _ = writeln!(out, "# HELP {} {}", name, help);
+        let _ = writeln!(out, "# TYPE {} {}", name, kind);
+    }
+}
+
+impl MetricsExporter for PrometheusExporter {
+    fn content_type(&self) -> &'static str {
+        "text/plain; version=0.0.4"
+    }
+
+    fn export(&self, snapshot: &MetricsSnapshot) -> String {
+        let mut out = String::new();
+
+        Self::header(&mut out, "bank_transactions_total", "counter", "Transactions posted, by type");
+        for (kind, count) in &snapshot.transactions {
+            let _ = writeln!(out, "bank_transactions_total{{type=\"{}\"}} {}", kind, count);
+        }
+
+        Self::header(&mut out, "bank_errors_total", "counter", "Failed operations, by error");
+        for (kind, count) in &snapshot.errors {
+            let _ = writeln!(out, "bank_errors_total{{error=\"{}\"}} {}", kind, count);
+        }
+
+        Self::header(&mut out, "bank_total_balance", "gauge", "Sum of all open account balances");
+        let _ = writeln!(out, "bank_total_balance {}", snapshot.total_balance);
+
+        Self::header(&mut out, "bank_customers", "gauge", "Registered customers");
+        let _ = writeln!(out, "bank_customers {}", snapshot.customers);
+
+        Self::header(&mut out, "bank_operation_duration_seconds", "histogram", "Time taken by operations");
+        for (operation, histogram) in &snapshot.latencies {
+            let operation = operation.replace('\\', "\\\\").replace('"', "\\\"");
+            for (bound, count) in &histogram.buckets {
+                let _ = writeln!(
+                    out,
+                    "bank_operation_duration_seconds_bucket{{operation=\"{}\",le=\"{}\"}} {}",
+                    operation, bound, count
+                );
+            }
+            let _ = writeln!(
+                out,
+                "bank_operation_duration_seconds_bucket{{operation=\"{}\",le=\"+Inf\"}} {}",
+                operation, histogram.count
+            );
+            let _ = writeln!(out, "bank_operation_duration_seconds_sum{{operation=\"{}\"}} {}", operation, histogram.sum);
+            let _ = writeln!(out, "bank_operation_duration_seconds_count{{operation=\"{}\"}} {}", operation, histogram.count);
+        }
+
+        out
+    }
+}
+
+/// The snapshot as a JSON object, for tools that don't speak Prometheus
+#[derive(Debug, Clone, Copy, Default)]
+pub struct JsonExporter;
+
+impl MetricsExporter for JsonExporter {
+    fn content_type(&self) -> &'static str {
+        "application/json"
+    }
+
+    fn export(&self, snapshot: &MetricsSnapshot) -> String {
+        // Every field serializes to plain JSON, so this cannot fail
+        serde_json::to_string_pretty(snapshot).unwrap_or_default()
+    }
+}
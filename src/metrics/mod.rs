@@ -0,0 +1,168 @@
+//! Metrics module - operational counters, gauges and histograms
+//!
+//! Demonstrates: Interior mutability behind a shared `Arc`, implementing
+//! someone else's trait (`BankObserver`) to collect data without touching
+//! the code that produces it
+//!
+//! A `Metrics` registry collects:
+//!
+//! - transactions posted, by type (registered as a `BankObserver`)
+//! - failed operations, by `BankError` variant (`record_error`)
+//! - how long operations take, as histograms (`observe` / `time`)
+//!
+//! Gauges such as the total balance are read from the bank when a
+//! snapshot is taken rather than tracked. A `MetricsExporter` turns the
+//! snapshot into text; the HTTP server serves it at `/metrics`.
+
+mod exporter;
+
+pub use exporter::{JsonExporter, MetricsExporter, PrometheusExporter};
+
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+use crate::bank::Bank;
+use crate::errors::{BankError, BankResult};
+use crate::models::{CustomerId, Money, Transaction};
+use crate::observer::BankObserver;
+
+/// Upper bounds of the latency buckets, in seconds
+pub const LATENCY_BUCKETS: [f64; 8] = [0.001, 0.005, 0.01, 0.05, 0.1, 0.5, 1.0, 5.0];
+
+/// A thread-safe metrics registry
+///
+/// Share it with `Arc` and register it on the bank with `add_observer` to
+/// count transactions.
+#[derive(Debug, Default)]
+pub struct Metrics {
+    inner: Mutex<Counters>,
+}
+
+#[derive(Debug, Default)]
+struct Counters {
+    transactions: BTreeMap<&'static str, u64>,
+    errors: BTreeMap<&'static str, u64>,
+    latencies: BTreeMap<String, Histogram>,
+}
+
+/// Observations sorted into `LATENCY_BUCKETS`
+#[derive(Debug, Clone, Default)]
+struct Histogram {
+    /// Observations per bucket (not cumulative); the last slot is +Inf
+    counts: [u64; LATENCY_BUCKETS.len() + 1],
+    sum: f64,
+}
+
+impl Histogram {
+    fn observe(&mut self, seconds: f64) {
+        let slot = LATENCY_BUCKETS.iter().position(|&bound| seconds <= bound).unwrap_or(LATENCY_BUCKETS.len());
+        self.counts[slot] += 1;
+        self.sum += seconds;
+    }
+
+    fn snapshot(&self) -> HistogramSnapshot {
+        let mut total = 0;
+        let mut buckets = Vec::with_capacity(LATENCY_BUCKETS.len());
+        for (bound, count) in LATENCY_BUCKETS.iter().zip(self.counts) {
+            total += count;
+            buckets.push((*bound, total));
+        }
+        HistogramSnapshot { buckets, sum: self.sum, count: total + self.counts[LATENCY_BUCKETS.len()] }
+    }
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Counts a posted transaction of the given type
+    pub fn record_transaction(&self, kind: &'static str) {
+        *self.lock().transactions.entry(kind).or_default() += 1;
+    }
+
+    /// Counts a failed operation under its error variant
+    pub fn record_error(&self, error: &BankError) {
+        *self.lock().errors.entry(error.kind()).or_default() += 1;
+    }
+
+    /// Records how long an operation took
+    pub fn observe(&self, operation: &str, elapsed: Duration) {
+        self.lock()
+            .latencies
+            .entry(operation.to_string())
+            .or_default()
+            .observe(elapsed.as_secs_f64());
+    }
+
+    /// Runs `f`, recording its duration and, if it fails, its error
+    pub fn time<T>(&self, operation: &str, f: impl FnOnce() -> BankResult<T>) -> BankResult<T> {
+        let started = Instant::now();
+        let result = f();
+        self.observe(operation, started.elapsed());
+        if let Err(e) = &result {
+            self.record_error(e);
+        }
+        result
+    }
+
+    /// The current values, with gauges read from `bank`
+    pub fn snapshot(&self, bank: &Bank) -> MetricsSnapshot {
+        let counters = self.lock();
+        MetricsSnapshot {
+            transactions: counters.transactions.iter().map(|(k, v)| (k.to_string(), *v)).collect(),
+            errors: counters.errors.iter().map(|(k, v)| (k.to_string(), *v)).collect(),
+            total_balance: bank.total_bank_balance(),
+            customers: bank.list_customers().len(),
+            latencies: counters.latencies.iter().map(|(k, h)| (k.clone(), h.snapshot())).collect(),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Counters> {
+        // Counters stay consistent even if a thread panicked mid-update
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl BankObserver for Metrics {
+    fn on_deposit(&self, _customer_id: &CustomerId, _transaction: &Transaction) {
+        self.record_transaction("deposit");
+    }
+
+    fn on_withdraw(&self, _customer_id: &CustomerId, _transaction: &Transaction) {
+        self.record_transaction("withdrawal");
+    }
+
+    fn on_transfer(&self, _from: &CustomerId, _to: &CustomerId, _debit: &Transaction, _credit: &Transaction) {
+        self.record_transaction("transfer");
+    }
+}
+
+/// Metric values at one point in time, as handed to exporters
+#[derive(Debug, Clone, Serialize)]
+pub struct MetricsSnapshot {
+    /// Transactions posted, by type
+    pub transactions: BTreeMap<String, u64>,
+    /// Failed operations, by `BankError::kind`
+    pub errors: BTreeMap<String, u64>,
+    /// Sum of all open account balances
+    pub total_balance: Money,
+    /// Registered customers, deleted ones excluded
+    pub customers: usize,
+    /// Operation durations, by operation name
+    pub latencies: BTreeMap<String, HistogramSnapshot>,
+}
+
+/// A latency histogram with cumulative buckets, as Prometheus expects
+#[derive(Debug, Clone, Serialize)]
+pub struct HistogramSnapshot {
+    /// (upper bound in seconds, observations at or below it)
+    pub buckets: Vec<(f64, u64)>,
+    /// Total seconds observed
+    pub sum: f64,
+    /// Number of observations, including those above the last bound
+    pub count: u64,
+}
@@ -0,0 +1,350 @@
+//! Middleware module - the checks around deposits, withdrawals and transfers
+//!
+//! Demonstrates: The chain-of-responsibility pattern with trait objects,
+//! default method implementations so a stage only writes the hooks it needs
+//! https://doc.rust-lang.org/book/ch17-02-trait-objects.html
+//!
+//! `Bank::deposit`, `withdraw` and `transfer` (and everything built on
+//! them: batches, standing orders, saved payees) describe the operation as
+//! an `OperationRequest` and hand it to the bank's `Pipeline`. Each stage's
+//! `before` runs in order and may refuse the operation, add a fee or mark
+//! it for fraud review; then the bank posts the money, checking only what
+//! keeps the books right (a positive amount, open accounts, no transfer to
+//! oneself, enough money for the amount and the fee, cash in the vault);
+//! then each stage's `after` runs in order with what was posted.
+//!
+//! The standard pipeline is validation → limits → fees → fraud check →
+//! (the operation) → notification: `Validation`, `Limits`, `Fees` (which
+//! charges nothing until it is replaced with one that does), `FraudCheck`
+//! and `Notify`. Take `FraudCheck` out and these operations are no longer
+//! flagged for review; take `Notify` out and they send no balance alerts.
+//! Changes made other ways (card payments, external transfers, closing an
+//! account) still meet the fraud rules and alerts through the bank's
+//! events, and webhooks and observers hear about every change.
+//!
+//! ```
+//! use std::sync::Arc;
+//! use rust_banking_system::middleware::{Fees, OperationMiddleware, OperationRequest};
+//! use rust_banking_system::{AccountType, Bank, BankError, BankResult, Money};
+//!
+//! /// Refuses anything over $5,000
+//! #[derive(Debug)]
+//! struct Ceiling;
+//!
+//! impl OperationMiddleware for Ceiling {
+//!     fn name(&self) -> &str {
+//!         "ceiling"
+//!     }
+//!
+//!     fn before(&self, _bank: &Bank, request: &mut OperationRequest) -> BankResult<()> {
+//!         if request.amount > Money::from_major(5_000) {
+//!             return Err(BankError::InvalidInput("over the ceiling".to_string()));
+//!         }
+//!         Ok(())
+//!     }
+//! }
+//!
+//! let mut bank = Bank::new("Demo Bank".to_string());
+//! bank.pipeline_mut().insert_before("limits", Arc::new(Ceiling));
+//! bank.pipeline_mut().replace("fees", Arc::new(Fees { withdrawal: Money::from_major(1), ..Fees::default() }));
+//!
+//! let alice = bank.register_customer("Alice".to_string(), "alice@example.com".to_string()).unwrap();
+//! bank.create_account_for_customer(&alice, Money::from_major(100), AccountType::Checking).unwrap();
+//! assert_eq!(bank.withdraw(&alice, Money::from_major(10)).unwrap(), Money::from_major(89));
+//! assert!(bank.deposit(&alice, Money::from_major(6_000)).is_err());
+//! ```
+
+use std::fmt;
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+
+use crate::bank::Bank;
+use crate::errors::{BankError, BankResult};
+use crate::models::{Alert, CustomerId, FraudReason, Money, Transaction};
+
+/// Which operation a request is for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OperationKind {
+    Deposit,
+    Withdrawal,
+    Transfer,
+}
+
+/// A deposit, withdrawal or transfer on its way through the pipeline
+///
+/// Stages may add to `fee` and set `flag`; the other fields describe the
+/// operation, and changing them does not change what is posted.
+#[derive(Debug, Clone)]
+pub struct OperationRequest {
+    pub kind: OperationKind,
+
+    /// The customer paying in, paying out or sending
+    pub customer_id: CustomerId,
+
+    /// The customer receiving a transfer
+    pub to_customer_id: Option<CustomerId>,
+
+    pub amount: Money,
+
+    /// Charged to `customer_id` on top of `amount`; stages add to it
+    pub fee: Money,
+
+    /// When the operation happens, by the bank's clock
+    pub at: DateTime<Utc>,
+
+    /// Why the operation should be reviewed for fraud; the bank flags its
+    /// first transaction (for a transfer, the debit)
+    pub flag: Option<FraudReason>,
+
+    /// The balance alerts the posting triggered, filled in by the bank
+    /// before the `after` hooks run
+    pub alerts: Vec<Alert>,
+}
+
+impl OperationRequest {
+    pub fn new(
+        kind: OperationKind,
+        customer_id: CustomerId,
+        to_customer_id: Option<CustomerId>,
+        amount: Money,
+        at: DateTime<Utc>,
+    ) -> Self {
+        Self { kind, customer_id, to_customer_id, amount, fee: Money::ZERO, at, flag: None, alerts: Vec::new() }
+    }
+}
+
+/// One stage of the pipeline around deposits, withdrawals and transfers
+pub trait OperationMiddleware: fmt::Debug + Send + Sync {
+    /// What the stage is called in `Pipeline::names`, `insert_before` and
+    /// `remove`
+    fn name(&self) -> &str;
+
+    /// Runs before any money moves; an error refuses the operation and
+    /// the stages after this one do not run
+    fn before(&self, _bank: &Bank, _request: &mut OperationRequest) -> BankResult<()> {
+        Ok(())
+    }
+
+    /// Runs once the operation is posted, with its transactions (a
+    /// transfer's debit and credit, then the fee if one was charged)
+    ///
+    /// Inside an all-or-nothing batch this waits for the batch to commit,
+    /// and does not run at all if it is rolled back.
+    fn after(&self, _bank: &Bank, _request: &OperationRequest, _posted: &[Transaction]) {}
+}
+
+/// The stages every deposit, withdrawal and transfer goes through, in order
+///
+/// Cloning is cheap and shares the stages. Pipelines are not saved with
+/// the bank; set them up again after loading it.
+#[derive(Clone)]
+pub struct Pipeline {
+    stages: Vec<Arc<dyn OperationMiddleware>>,
+}
+
+impl Pipeline {
+    /// A pipeline with no stages: only the bank's own bookkeeping checks
+    pub fn empty() -> Self {
+        Self { stages: Vec::new() }
+    }
+
+    /// `Validation`, `Limits`, `Fees` (charging nothing), `FraudCheck`
+    /// and `Notify`: what the bank has always done around these operations
+    pub fn standard() -> Self {
+        Self {
+            stages: vec![
+                Arc::new(Validation),
+                Arc::new(Limits),
+                Arc::new(Fees::default()),
+                Arc::new(FraudCheck),
+                Arc::new(Notify),
+            ],
+        }
+    }
+
+    /// Adds a stage at the end
+    pub fn push(&mut self, stage: Arc<dyn OperationMiddleware>) {
+        self.stages.push(stage);
+    }
+
+    /// Adds a stage just before the one called `name`, or at the end if
+    /// there is none
+    pub fn insert_before(&mut self, name: &str, stage: Arc<dyn OperationMiddleware>) {
+        let index = self.stages.iter().position(|s| s.name() == name).unwrap_or(self.stages.len());
+        self.stages.insert(index, stage);
+    }
+
+    /// Puts `stage` in place of the one called `name`, or at the end if
+    /// there is none
+    ///
+    /// # Returns
+    /// * `Some(stage)` - The stage replaced
+    /// * `None` - If the pipeline had no such stage
+    pub fn replace(&mut self, name: &str, stage: Arc<dyn OperationMiddleware>) -> Option<Arc<dyn OperationMiddleware>> {
+        match self.stages.iter().position(|s| s.name() == name) {
+            Some(index) => Some(std::mem::replace(&mut self.stages[index], stage)),
+            None => {
+                self.stages.push(stage);
+                None
+            }
+        }
+    }
+
+    /// Takes out the stage called `name`
+    ///
+    /// # Returns
+    /// * `Some(stage)` - The stage removed
+    /// * `None` - If the pipeline has no such stage
+    pub fn remove(&mut self, name: &str) -> Option<Arc<dyn OperationMiddleware>> {
+        let index = self.stages.iter().position(|s| s.name() == name)?;
+        Some(self.stages.remove(index))
+    }
+
+    /// The stages' names, in order
+    pub fn names(&self) -> Vec<&str> {
+        self.stages.iter().map(|stage| stage.name()).collect()
+    }
+
+    /// Runs every stage's `before`, stopping at the first refusal
+    pub(crate) fn before(&self, bank: &Bank, request: &mut OperationRequest) -> BankResult<()> {
+        self.stages.iter().try_for_each(|stage| stage.before(bank, request))
+    }
+
+    /// Runs every stage's `after`
+    pub(crate) fn after(&self, bank: &Bank, request: &OperationRequest, posted: &[Transaction]) {
+        for stage in &self.stages {
+            stage.after(bank, request, posted);
+        }
+    }
+}
+
+impl Default for Pipeline {
+    fn default() -> Self {
+        Self::standard()
+    }
+}
+
+impl fmt::Debug for Pipeline {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("Pipeline").field(&self.names()).finish()
+    }
+}
+
+/// Refuses operations that make no sense: amounts that are not positive or
+/// beyond `Money::MAX`, inactive customers, closed accounts and frozen
+/// accounts paying out
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Validation;
+
+impl OperationMiddleware for Validation {
+    fn name(&self) -> &str {
+        "validation"
+    }
+
+    fn before(&self, bank: &Bank, request: &mut OperationRequest) -> BankResult<()> {
+        // Amounts sent as JSON skip the `Money::MAX` check of typed ones
+        if !request.amount.is_positive() || request.amount > Money::MAX {
+            return Err(BankError::InvalidAmount(request.amount));
+        }
+        let account = bank.active_customer(&request.customer_id)?.get_account()?;
+        match request.kind {
+            OperationKind::Deposit => account.ensure_open(),
+            OperationKind::Withdrawal => account.ensure_can_debit(request.amount),
+            OperationKind::Transfer => {
+                let to_customer_id = request
+                    .to_customer_id
+                    .as_ref()
+                    .ok_or_else(|| BankError::InvalidTransfer("a transfer needs a recipient".to_string()))?;
+                let to_account = bank.active_customer(to_customer_id)?.get_account()?;
+                account.ensure_can_debit(request.amount)?;
+                to_account.ensure_open()
+            }
+        }
+    }
+}
+
+/// Applies the account type's rules (lock-in periods, monthly withdrawal
+/// counts) and the account's withdrawal and transfer limits
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Limits;
+
+impl OperationMiddleware for Limits {
+    fn name(&self) -> &str {
+        "limits"
+    }
+
+    fn before(&self, bank: &Bank, request: &mut OperationRequest) -> BankResult<()> {
+        let account = bank.get_customer(&request.customer_id)?.get_account()?;
+        match request.kind {
+            OperationKind::Deposit => Ok(()),
+            OperationKind::Withdrawal => account.check_debit_rules(request.amount, request.at),
+            OperationKind::Transfer => {
+                account.limits.check_transfer(&account.transactions, request.at)?;
+                account.check_debit_rules(request.amount, request.at)
+            }
+        }
+    }
+}
+
+/// Charges a flat fee for each withdrawal and each transfer (paid by the
+/// sender); zero charges nothing
+///
+/// The fee is posted as a transaction of its own right after the
+/// operation, and undoing the operation refunds it.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Fees {
+    pub withdrawal: Money,
+    pub transfer: Money,
+}
+
+impl OperationMiddleware for Fees {
+    fn name(&self) -> &str {
+        "fees"
+    }
+
+    fn before(&self, _bank: &Bank, request: &mut OperationRequest) -> BankResult<()> {
+        request.fee += match request.kind {
+            OperationKind::Deposit => Money::ZERO,
+            OperationKind::Withdrawal => self.withdrawal,
+            OperationKind::Transfer => self.transfer,
+        };
+        Ok(())
+    }
+}
+
+/// Checks the operation against the bank's `FraudRules`, with the history
+/// from before it is posted, and marks it for review if it breaks one
+///
+/// A flagged operation still goes ahead; staff approve or reverse it from
+/// `Bank::review_queue`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FraudCheck;
+
+impl OperationMiddleware for FraudCheck {
+    fn name(&self) -> &str {
+        "fraud-check"
+    }
+
+    fn before(&self, bank: &Bank, request: &mut OperationRequest) -> BankResult<()> {
+        if request.flag.is_none() {
+            request.flag = bank.fraud_reason_for(request);
+        }
+        Ok(())
+    }
+}
+
+/// Sends the balance alerts the operation triggered to the bank's
+/// notifiers
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Notify;
+
+impl OperationMiddleware for Notify {
+    fn name(&self) -> &str {
+        "notify"
+    }
+
+    fn after(&self, bank: &Bank, request: &OperationRequest, _posted: &[Transaction]) {
+        bank.dispatch_alerts(&request.alerts);
+    }
+}
@@ -2,12 +2,28 @@
 //!
 //! Demonstrates: Struct methods, mutable borrowing, error handling
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Datelike, Utc};
 use serde::{Deserialize, Serialize};
-use uuid::Uuid;
 
 use crate::errors::{BankError, BankResult};
-use super::transaction::{Transaction, TransactionType};
+use crate::validation;
+pub use super::account_type::AccountType;
+use super::account_number::AccountNumber;
+use super::account_status::AccountStatus;
+use super::ids::{AccountId, TransactionId};
+use super::alerts::AlertRules;
+use super::limits::AccountLimits;
+use super::money::Money;
+use super::filter::TransactionFilter;
+use super::history::History;
+use super::archive::ArchivedHistory;
+use super::cheque::Cheque;
+use super::budget::Budget;
+use super::envelope::Envelope;
+use super::hold::Hold;
+use super::page::Page;
+use super::term_deposit::{TermDeposit, TermDepositStatus};
+use super::transaction::{Transaction, TransactionDetails, TransactionStatus, TransactionType};
 
 /// Represents a bank account
 ///
@@ -17,17 +33,75 @@ use super::transaction::{Transaction, TransactionType};
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Account {
     /// Unique account identifier (UUID v4)
-    pub id: String,
+    pub id: AccountId,
+
+    /// Short number for people to type, assigned by the bank
+    /// Older data files have none until the bank assigns them
+    #[serde(default)]
+    pub number: Option<AccountNumber>,
 
     /// Current account balance
-    pub balance: f64,
+    pub balance: Money,
+
+    /// Account type and its withdrawal rules
+    /// Older data files have no type, so they default to Checking
+    #[serde(default)]
+    pub account_type: AccountType,
 
-    /// Transaction history - demonstrates Vec<T> ownership
+    /// Transaction history - a Vec<T> that may be parsed on first use
+    /// (see `History`)
     /// https://doc.rust-lang.org/std/vec/struct.Vec.html
-    pub transactions: Vec<Transaction>,
+    pub transactions: History,
 
     /// Account creation timestamp
     pub created_at: DateTime<Utc>,
+
+    /// Withdrawal and transfer caps; older data files have none
+    #[serde(default)]
+    pub limits: AccountLimits,
+
+    /// Balance alert thresholds; older data files have none
+    #[serde(default)]
+    pub alerts: AlertRules,
+
+    /// Lifecycle state; older data files only have active accounts
+    #[serde(default)]
+    pub status: AccountStatus,
+
+    /// Authorized withdrawals waiting to be captured or voided; their
+    /// amounts are held out of the available balance
+    #[serde(default)]
+    pub authorizations: Vec<Transaction>,
+
+    /// Money set aside without a transaction (see `Hold`)
+    #[serde(default)]
+    pub holds: Vec<Hold>,
+
+    /// Cheques paid in, whether pending, cleared or bounced
+    #[serde(default)]
+    pub cheques: Vec<Cheque>,
+
+    /// Savings goals the balance is partly set aside for
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub envelopes: Vec<Envelope>,
+
+    /// Monthly spending budgets, one per category
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub budgets: Vec<Budget>,
+
+    /// Term deposits opened from this account, active and closed
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub term_deposits: Vec<TermDeposit>,
+
+    /// When the account was flagged dormant (see `DormancyPolicy`); the
+    /// next posting clears it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dormant_since: Option<DateTime<Utc>>,
+
+    /// Older transactions moved out of `transactions` into the archive,
+    /// `None` until the account is first archived
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub archived: Option<ArchivedHistory>,
 }
 
 impl Account {
@@ -37,29 +111,50 @@ impl Account {
     ///
     /// # Arguments
     /// * `initial_deposit` - Initial amount to deposit (must be non-negative)
+    /// * `account_type` - The kind of account to open
     ///
     /// # Returns
     /// * `Ok(Account)` - Successfully created account
     /// * `Err(BankError)` - If initial_deposit is negative
-    pub fn new(initial_deposit: f64) -> BankResult<Self> {
-        if initial_deposit < 0.0 {
+    pub fn new(initial_deposit: Money, account_type: AccountType) -> BankResult<Self> {
+        Self::new_at(initial_deposit, account_type, Utc::now())
+    }
+
+    /// Creates a new account opened at `now`
+    pub(crate) fn new_at(initial_deposit: Money, account_type: AccountType, now: DateTime<Utc>) -> BankResult<Self> {
+        if initial_deposit.is_negative() {
             return Err(BankError::InvalidAmount(initial_deposit));
         }
 
         let mut account = Self {
-            id: Uuid::new_v4().to_string(),
+            id: AccountId::generate(),
+            number: None,
             balance: initial_deposit,
-            transactions: Vec::new(),
-            created_at: Utc::now(),
+            account_type,
+            transactions: History::new(),
+            created_at: now,
+            limits: AccountLimits::default(),
+            alerts: AlertRules::default(),
+            status: AccountStatus::Active,
+            authorizations: Vec::new(),
+            holds: Vec::new(),
+            cheques: Vec::new(),
+            envelopes: Vec::new(),
+            budgets: Vec::new(),
+            term_deposits: Vec::new(),
+            dormant_since: None,
+            archived: None,
         };
 
-        // Record the initial deposit if non-zero
-        if initial_deposit > 0.0 {
-            let transaction = Transaction::new(
+        // Record the initial deposit if non-zero; it is paid in cash
+        if initial_deposit.is_positive() {
+            let mut transaction = Transaction::new_at(
                 TransactionType::Deposit,
                 initial_deposit,
                 initial_deposit,
+                now,
             );
+            transaction.cash = true;
             account.transactions.push(transaction);
         }
 
@@ -73,20 +168,43 @@ impl Account {
     ///
     /// # Arguments
     /// * `amount` - Amount to deposit (must be positive)
-    pub fn deposit(&mut self, amount: f64) -> BankResult<()> {
-        if amount <= 0.0 {
+    pub fn deposit(&mut self, amount: Money) -> BankResult<()> {
+        self.deposit_with(amount, TransactionDetails::new())
+    }
+
+    /// Deposits money with a description and tags
+    pub fn deposit_with(&mut self, amount: Money, details: TransactionDetails) -> BankResult<()> {
+        let mut transaction = self.prepare_deposit(amount, Utc::now())?;
+        transaction.annotate(details);
+        self.post(transaction);
+        Ok(())
+    }
+
+    /// Builds the transaction a deposit would post, without posting it
+    ///
+    /// Lets the bank validate an operation and turn it into an event
+    /// before any state changes. The transaction is stamped with `now`.
+    pub(crate) fn prepare_deposit(&self, amount: Money, now: DateTime<Utc>) -> BankResult<Transaction> {
+        self.ensure_open()?;
+        if !amount.is_positive() {
             return Err(BankError::InvalidAmount(amount));
         }
 
-        self.balance += amount;
-        let transaction = Transaction::new(
+        Ok(Transaction::new_at(
             TransactionType::Deposit,
             amount,
-            self.balance
-        );
-        self.transactions.push(transaction);
+            self.balance_after(amount, true)?,
+            now,
+        ))
+    }
 
-        Ok(())
+    /// The balance once `amount` is paid in (`credit`) or out
+    ///
+    /// # Returns
+    /// * `Err(BankError::InvalidAmount)` - If the balance would overflow
+    pub(crate) fn balance_after(&self, amount: Money, credit: bool) -> BankResult<Money> {
+        let balance = if credit { self.balance.checked_add(amount) } else { self.balance.checked_sub(amount) };
+        balance.ok_or(BankError::InvalidAmount(amount))
     }
 
     /// Withdraws money from the account
@@ -95,25 +213,357 @@ impl Account {
     ///
     /// # Arguments
     /// * `amount` - Amount to withdraw (must be positive and <= balance)
-    pub fn withdraw(&mut self, amount: f64) -> BankResult<()> {
-        if amount <= 0.0 {
+    pub fn withdraw(&mut self, amount: Money) -> BankResult<()> {
+        self.withdraw_with(amount, TransactionDetails::new())
+    }
+
+    /// Withdraws money with a description and tags
+    pub fn withdraw_with(&mut self, amount: Money, details: TransactionDetails) -> BankResult<()> {
+        let mut transaction = self.prepare_withdrawal(amount, Utc::now())?;
+        transaction.annotate(details);
+        self.post(transaction);
+        Ok(())
+    }
+
+    /// Builds the transaction a withdrawal would post, without posting it
+    ///
+    /// Type rules and limits are checked as of `now`.
+    pub(crate) fn prepare_withdrawal(&self, amount: Money, now: DateTime<Utc>) -> BankResult<Transaction> {
+        self.ensure_can_debit(amount)?;
+        self.check_debit_rules(amount, now)?;
+        self.prepare_debit(amount, now)
+    }
+
+    /// Checks that the account can pay `amount` out at all: it is open,
+    /// not frozen, and the amount is positive
+    pub(crate) fn ensure_can_debit(&self, amount: Money) -> BankResult<()> {
+        self.ensure_open()?;
+        if self.status == AccountStatus::Frozen {
+            return Err(BankError::AccountFrozen(self.id.clone()));
+        }
+        if !amount.is_positive() {
             return Err(BankError::InvalidAmount(amount));
         }
+        Ok(())
+    }
 
-        if self.balance < amount {
+    /// Checks the account type's rules and the withdrawal limits for
+    /// paying `amount` out at `now`
+    pub(crate) fn check_debit_rules(&self, amount: Money, now: DateTime<Utc>) -> BankResult<()> {
+        self.check_withdrawal_rules(now)?;
+        self.limits.check_withdrawal(amount, &self.transactions, now)
+    }
+
+    /// Checks that `amount` is available and not set aside in a locked
+    /// savings goal
+    pub(crate) fn ensure_available(&self, amount: Money, now: DateTime<Utc>) -> BankResult<()> {
+        let available = self.available_balance_at(now);
+        if available < amount {
+            return Err(BankError::InsufficientFunds { available, requested: amount });
+        }
+        if available - self.locked_amount() < amount {
+            let name = self.envelopes.iter().find(|e| e.locked && e.allocated.is_positive()).map(|e| e.name.clone());
+            return Err(BankError::EnvelopeLocked(name.unwrap_or_default()));
+        }
+        Ok(())
+    }
+
+    /// Builds a withdrawal checking only what keeps the balance right (an
+    /// open account, a positive amount, the money being there), not
+    /// freezes, type rules or limits
+    pub(crate) fn prepare_debit(&self, amount: Money, now: DateTime<Utc>) -> BankResult<Transaction> {
+        self.ensure_open()?;
+        if !amount.is_positive() {
+            return Err(BankError::InvalidAmount(amount));
+        }
+        self.ensure_available(amount, now)?;
+
+        Ok(Transaction::new_at(
+            TransactionType::Withdrawal,
+            amount,
+            self.balance_after(amount, false)?,
+            now,
+        ))
+    }
+
+    /// Builds a pending withdrawal that holds `amount` until it is
+    /// captured or voided
+    ///
+    /// The same checks as for a withdrawal apply. The ledger balance does
+    /// not change, so `balance_after` is the balance when it was authorized.
+    pub(crate) fn prepare_authorization(&self, amount: Money, now: DateTime<Utc>) -> BankResult<Transaction> {
+        let mut authorization = self.prepare_withdrawal(amount, now)?;
+        authorization.balance_after = self.balance;
+        authorization.status = TransactionStatus::Pending;
+        Ok(authorization)
+    }
+
+    /// Builds the completed withdrawal that captures a pending
+    /// authorization, stamped with `now`
+    ///
+    /// The money was held when it was authorized, so only the ledger
+    /// balance is checked; freezes and limits do not apply.
+    pub(crate) fn prepare_capture(
+        &self,
+        authorization_id: &TransactionId,
+        now: DateTime<Utc>,
+    ) -> BankResult<Transaction> {
+        self.ensure_open()?;
+        let mut transaction = self
+            .authorizations
+            .iter()
+            .find(|auth| auth.id == *authorization_id)
+            .cloned()
+            .ok_or_else(|| BankError::InvalidInput(format!("authorization '{}' not found", authorization_id)))?;
+
+        if self.balance < transaction.amount {
             return Err(BankError::InsufficientFunds {
                 available: self.balance,
-                requested: amount,
+                requested: transaction.amount,
             });
         }
+        transaction.balance_after = self.balance_after(transaction.amount, false)?;
+        transaction.timestamp = now;
+        transaction.status = TransactionStatus::Completed;
+        Ok(transaction)
+    }
 
-        self.balance -= amount;
-        let transaction = Transaction::new(
-            TransactionType::Withdrawal,
-            amount,
-            self.balance
-        );
+    /// Sets money aside until the hold is released or captured
+    ///
+    /// # Arguments
+    /// * `amount` - Amount to hold, at most the available balance
+    /// * `reason` - Why it is held, e.g. "hotel deposit"
+    pub fn place_hold(&mut self, amount: Money, reason: &str) -> BankResult<Hold> {
+        let hold = self.prepare_hold(amount, reason, None, Utc::now())?;
+        self.holds.push(hold.clone());
+        Ok(hold)
+    }
+
+    /// Builds a hold placed at `now`, without placing it
+    ///
+    /// # Returns
+    /// * `Err(BankError::InvalidInput)` - If `expires_at` is not after `now`
+    /// * `Err(BankError::InsufficientFunds)` - If less than `amount` is available
+    pub(crate) fn prepare_hold(
+        &self,
+        amount: Money,
+        reason: &str,
+        expires_at: Option<DateTime<Utc>>,
+        now: DateTime<Utc>,
+    ) -> BankResult<Hold> {
+        self.ensure_open()?;
+        if !amount.is_positive() {
+            return Err(BankError::InvalidAmount(amount));
+        }
+        let reason = validation::validate_description(reason)?;
+        if expires_at.is_some_and(|expires_at| expires_at <= now) {
+            return Err(BankError::InvalidInput("a hold must expire in the future".to_string()));
+        }
+
+        let available = self.available_balance_at(now);
+        if available < amount {
+            return Err(BankError::InsufficientFunds { available, requested: amount });
+        }
+        Ok(Hold::new_at(amount, reason, expires_at, now))
+    }
+
+    /// Removes a hold, making its money available again
+    pub fn release_hold(&mut self, hold_id: &str) -> BankResult<Hold> {
+        let index = self
+            .holds
+            .iter()
+            .position(|hold| hold.id == hold_id)
+            .ok_or_else(|| BankError::InvalidInput(format!("hold '{}' not found", hold_id)))?;
+        Ok(self.holds.remove(index))
+    }
+
+    /// Builds the withdrawal that takes a held amount, stamped with `now`
+    ///
+    /// The reason becomes its description. Like a capture, freezes and
+    /// limits do not apply.
+    ///
+    /// # Returns
+    /// * `Err(BankError::InvalidInput)` - If there is no such hold or it
+    ///   has expired
+    pub(crate) fn prepare_hold_capture(&self, hold_id: &str, now: DateTime<Utc>) -> BankResult<Transaction> {
+        self.ensure_open()?;
+        let hold = self
+            .holds
+            .iter()
+            .find(|hold| hold.id == hold_id)
+            .ok_or_else(|| BankError::InvalidInput(format!("hold '{}' not found", hold_id)))?;
+        if hold.is_expired(now) {
+            return Err(BankError::InvalidInput(format!("hold '{}' has expired", hold_id)));
+        }
+
+        // The hold itself is part of what is unavailable
+        let available = self.available_balance_at(now) + hold.amount;
+        if available < hold.amount {
+            return Err(BankError::InsufficientFunds { available, requested: hold.amount });
+        }
+        let balance_after = self.balance_after(hold.amount, false)?;
+        let mut transaction = Transaction::new_at(TransactionType::Withdrawal, hold.amount, balance_after, now);
+        transaction.description = Some(hold.reason.clone());
+        Ok(transaction)
+    }
+
+    /// The balance including every posted transaction
+    ///
+    /// Same as `balance`; named to contrast with `available_balance`.
+    pub fn ledger_balance(&self) -> Money {
+        self.balance
+    }
+
+    /// Total held right now by authorizations and unexpired holds
+    pub fn held_amount(&self) -> Money {
+        self.held_amount_at(Utc::now())
+    }
+
+    /// Total held at `now` by authorizations and unexpired holds
+    pub fn held_amount_at(&self, now: DateTime<Utc>) -> Money {
+        let authorized: Money = self.authorizations.iter().map(|auth| auth.amount).sum();
+        let held: Money = self.holds.iter().filter(|hold| !hold.is_expired(now)).map(|hold| hold.amount).sum();
+        authorized + held
+    }
+
+    /// Credits posted but still pending, such as cheques that have not
+    /// cleared
+    pub fn uncleared_amount(&self) -> Money {
+        self.transactions
+            .iter()
+            .filter(|tx| tx.status == TransactionStatus::Pending && tx.transaction_type.is_credit())
+            .map(|tx| tx.amount)
+            .sum()
+    }
+
+    /// What can be spent right now: the ledger balance less the held and
+    /// uncleared amounts
+    pub fn available_balance(&self) -> Money {
+        self.available_balance_at(Utc::now())
+    }
+
+    /// What can be spent at `now`
+    pub fn available_balance_at(&self, now: DateTime<Utc>) -> Money {
+        self.balance - self.held_amount_at(now) - self.uncleared_amount()
+    }
+
+    /// Money set aside in savings goals
+    pub fn allocated_amount(&self) -> Money {
+        self.envelopes.iter().map(|e| e.allocated).sum()
+    }
+
+    /// Money in locked savings goals, which withdrawals cannot touch
+    pub fn locked_amount(&self) -> Money {
+        self.envelopes.iter().filter(|e| e.locked).map(|e| e.allocated).sum()
+    }
+
+    /// The available balance not set aside in any savings goal, as of `now`
+    pub fn unallocated_balance_at(&self, now: DateTime<Utc>) -> Money {
+        self.available_balance_at(now) - self.allocated_amount()
+    }
+
+    /// The savings goal called `name` (ignoring case)
+    pub fn envelope(&self, name: &str) -> Option<&Envelope> {
+        let key = Envelope::key(name);
+        self.envelopes.iter().find(|e| Envelope::key(&e.name) == key)
+    }
+
+    /// When the account was last used: its newest posting, or its opening
+    /// if that is later (imported postings keep their original dates)
+    pub fn last_activity(&self) -> DateTime<Utc> {
+        let newest = self.transactions.iter().map(|t| t.timestamp).max();
+        newest.map_or(self.created_at, |t| t.max(self.created_at))
+    }
+
+    /// Fails if the account is closed
+    pub fn ensure_open(&self) -> BankResult<()> {
+        if self.status == AccountStatus::Closed {
+            return Err(BankError::AccountClosed(self.id.clone()));
+        }
+        Ok(())
+    }
+
+    /// Transactions still waiting to be settled, oldest first, followed
+    /// by authorizations waiting to be captured
+    pub fn pending_transactions(&self) -> impl Iterator<Item = &Transaction> {
+        self.transactions
+            .iter()
+            .filter(|tx| tx.status == TransactionStatus::Pending)
+            .chain(&self.authorizations)
+    }
+
+    /// Fails if any transaction or authorization is still pending, since
+    /// resolving it later may need the account, if money is held, or if a
+    /// term deposit has yet to pay back into it
+    pub fn ensure_nothing_pending(&self) -> BankResult<()> {
+        let term_deposits = self.term_deposits.iter().filter(|d| d.status == TermDepositStatus::Active).count();
+        if term_deposits > 0 {
+            return Err(BankError::InvalidInput(format!(
+                "account has {} active term deposit(s); withdraw them first",
+                term_deposits
+            )));
+        }
+        match (self.pending_transactions().count(), self.holds.len()) {
+            (0, 0) => Ok(()),
+            (0, n) => Err(BankError::InvalidInput(format!("account has {} hold(s); release them first", n))),
+            (n, _) => Err(BankError::InvalidInput(format!(
+                "account has {} pending transaction(s); settle, capture or void them first",
+                n
+            ))),
+        }
+    }
+
+    /// Appends a prepared transaction and takes over its resulting
+    /// balance; the account is no longer dormant
+    pub(crate) fn post(&mut self, transaction: Transaction) {
+        self.dormant_since = None;
+        self.balance = transaction.balance_after;
         self.transactions.push(transaction);
+        self.shrink_envelopes();
+    }
+
+    /// Takes money out of savings goals when the balance no longer covers
+    /// them: newest unlocked goals first, then locked ones, which only a
+    /// debit that skips the withdrawal checks (e.g. a fee) can reach
+    fn shrink_envelopes(&mut self) {
+        let mut shortfall = self.allocated_amount() - self.balance.max(Money::ZERO);
+        for locked in [false, true] {
+            for envelope in self.envelopes.iter_mut().rev().filter(|e| e.locked == locked) {
+                if !shortfall.is_positive() {
+                    return;
+                }
+                let taken = envelope.allocated.min(shortfall);
+                envelope.allocated -= taken;
+                shortfall -= taken;
+            }
+        }
+    }
+
+    /// Enforces the account type's withdrawal rules
+    ///
+    /// Demonstrates: Combining Option<T> with early returns
+    fn check_withdrawal_rules(&self, now: DateTime<Utc>) -> BankResult<()> {
+        if let Some(until) = self.account_type.locked_until() {
+            if now < until {
+                return Err(BankError::AccountLocked { until });
+            }
+        }
+
+        if let Some(limit) = self.account_type.monthly_withdrawal_limit() {
+            // Outgoing transfers count as withdrawals too
+            let this_month = self
+                .transactions
+                .iter()
+                .filter(|tx| {
+                    matches!(tx.transaction_type, TransactionType::Withdrawal) || tx.transaction_type.is_transfer_out()
+                })
+                .filter(|tx| tx.timestamp.year() == now.year() && tx.timestamp.month() == now.month())
+                .count();
+
+            if this_month >= limit as usize {
+                return Err(BankError::WithdrawalLimitReached { limit });
+            }
+        }
 
         Ok(())
     }
@@ -121,11 +571,23 @@ impl Account {
     /// Gets the transaction history
     ///
     /// Demonstrates: Borrowing with immutable reference, slice type
-    /// Returns a slice of transactions instead of cloning the Vec
+    /// Returns a slice of transactions instead of cloning the Vec. Only
+    /// the live history is included; `Bank::get_transaction_history` also
+    /// walks archived transactions.
     pub fn get_transaction_history(&self) -> &[Transaction] {
         &self.transactions
     }
 
+    /// Gets the transactions matching `filter`, oldest first
+    pub fn filter_transactions(&self, filter: &TransactionFilter) -> Vec<&Transaction> {
+        self.transactions.iter().filter(|tx| filter.matches(tx)).collect()
+    }
+
+    /// Gets up to `limit` transactions starting at `offset`, oldest first
+    pub fn transactions_page(&self, offset: usize, limit: usize) -> Page<&Transaction> {
+        Page::from_items(&self.transactions, offset, limit)
+    }
+
     /// Calculates total deposits
     ///
     /// Demonstrates: Iterators, closures, and functional programming
@@ -136,7 +598,7 @@ impl Account {
     /// 2. filter() - keeps only deposits using closure and pattern matching
     /// 3. map() - extracts amount from each transaction
     /// 4. sum() - aggregates all amounts
-    pub fn total_deposits(&self) -> f64 {
+    pub fn total_deposits(&self) -> Money {
         self.transactions
             .iter()
             .filter(|tx| matches!(tx.transaction_type, TransactionType::Deposit))
@@ -147,7 +609,7 @@ impl Account {
     /// Calculates total withdrawals
     ///
     /// Demonstrates: Iterator chains with filter, map, and sum
-    pub fn total_withdrawals(&self) -> f64 {
+    pub fn total_withdrawals(&self) -> Money {
         self.transactions
             .iter()
             .filter(|tx| matches!(tx.transaction_type, TransactionType::Withdrawal))
@@ -155,15 +617,94 @@ impl Account {
             .sum()
     }
 
+    /// Builds a compensating transaction that cancels out `original_id`
+    ///
+    /// The original stays in the history; the reversal moves the same
+    /// amount the other way. Withdrawal rules (monthly limits, fixed-term
+    /// locks, freezes) do not apply, only the balance check.
+    ///
+    /// # Returns
+    /// * `Ok(Transaction)` - The reversal to post
+    /// * `Err(BankError::InvalidInput)` - If the transaction was already
+    ///   reversed or is a reversal itself
+    /// * `Err(BankError::InsufficientFunds)` - If the money has since been spent
+    pub(crate) fn prepare_reversal(&self, original_id: &TransactionId, now: DateTime<Utc>) -> BankResult<Transaction> {
+        self.ensure_open()?;
+        let original = self
+            .transactions
+            .iter()
+            .find(|tx| tx.id == *original_id)
+            .ok_or_else(|| BankError::InvalidInput(format!("transaction '{}' not found", original_id)))?;
+        if original.status == TransactionStatus::Reversed {
+            return Err(BankError::InvalidInput(format!("transaction '{}' was already reversed", original_id)));
+        }
+        if original.reverses().is_some() {
+            return Err(BankError::InvalidInput("a reversal cannot itself be reversed".to_string()));
+        }
+
+        let credit = !original.transaction_type.is_credit();
+        let amount = original.amount;
+        let available = self.available_balance_at(now);
+        if !credit && available < amount {
+            return Err(BankError::InsufficientFunds { available, requested: amount });
+        }
+
+        let mut reversal = Transaction::new_at(
+            TransactionType::Reversal { original_id: original_id.clone(), credit },
+            amount,
+            self.balance_after(amount, credit)?,
+            now,
+        );
+        reversal.cash = original.cash;
+        Ok(reversal)
+    }
+
     /// Updates the last transaction to mark it as a transfer
     ///
     /// Demonstrates: Mutable iteration and Option handling
     ///
     /// # Arguments
     /// * `to_account_id` - The destination account ID for the transfer
-    pub fn mark_last_as_transfer(&mut self, to_account_id: String) {
+    pub fn mark_last_as_transfer(&mut self, to_account_id: AccountId) {
         if let Some(last_tx) = self.transactions.last_mut() {
             last_tx.transaction_type = TransactionType::Transfer { to_account_id };
         }
     }
+
+    /// Updates the last transaction to mark it as an incoming transfer
+    ///
+    /// # Arguments
+    /// * `from_account_id` - The source account ID for the transfer
+    pub fn mark_last_as_transfer_in(&mut self, from_account_id: AccountId) {
+        if let Some(last_tx) = self.transactions.last_mut() {
+            last_tx.transaction_type = TransactionType::TransferIn { from_account_id };
+        }
+    }
+}
+
+/// The account number (or the start of the ID for accounts without one),
+/// type and balance, e.g. `ACC-000123-0 (Checking) $90.00`
+impl std::fmt::Display for Account {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self.number {
+            Some(number) => write!(f, "{}", number)?,
+            None => write!(f, "{}", self.id.short())?,
+        }
+        write!(f, " ({}) {}", self.account_type, self.balance.display())
+    }
+}
+
+/// Two accounts are equal when they have the same ID
+impl PartialEq for Account {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl Eq for Account {}
+
+impl std::hash::Hash for Account {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
 }
@@ -0,0 +1,108 @@
+//! Account number module - short, typeable account references
+//!
+//! Demonstrates: Newtypes, check digits, serializing through a string form
+//! with `#[serde(try_from, into)]`
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// A sequential account number such as `ACC-000123-0`
+///
+/// The bank hands these out in order (1, 2, 3, ...) alongside the account
+/// UUID. The last digit is a Luhn check digit, so a single mistyped digit
+/// and most swaps of neighbouring digits are caught when parsing.
+///
+/// ```
+/// use rust_banking_system::models::AccountNumber;
+///
+/// let number = AccountNumber::new(123);
+/// assert_eq!(number.to_string(), "ACC-000123-0");
+/// assert_eq!("acc-000123-0".parse(), Ok(number));
+/// assert!("ACC-000123-4".parse::<AccountNumber>().is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct AccountNumber(u64);
+
+impl AccountNumber {
+    /// Wraps a sequence number
+    pub fn new(sequence: u64) -> Self {
+        Self(sequence)
+    }
+
+    /// The sequence number without prefix or check digit
+    pub fn sequence(self) -> u64 {
+        self.0
+    }
+
+    /// The Luhn check digit for this sequence number
+    ///
+    /// Demonstrates: Iterating over digits from the right with rev()
+    pub fn check_digit(self) -> u32 {
+        let digits = self.0.to_string();
+        let sum: u32 = digits
+            .chars()
+            .rev()
+            .filter_map(|c| c.to_digit(10))
+            .enumerate()
+            .map(|(i, d)| {
+                // The check digit will be appended on the right, so the
+                // rightmost digit here is the first one that gets doubled
+                if i % 2 == 0 {
+                    let doubled = d * 2;
+                    if doubled > 9 { doubled - 9 } else { doubled }
+                } else {
+                    d
+                }
+            })
+            .sum();
+        (10 - sum % 10) % 10
+    }
+}
+
+impl fmt::Display for AccountNumber {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "ACC-{:06}-{}", self.0, self.check_digit())
+    }
+}
+
+impl FromStr for AccountNumber {
+    type Err = String;
+
+    /// Parses `ACC-000123-0` (case-insensitive, leading zeros optional)
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("'{}' is not an account number", s.trim());
+        let upper = s.trim().to_uppercase();
+        let rest = upper.strip_prefix("ACC-").ok_or_else(invalid)?;
+        let (sequence, check) = rest.split_once('-').ok_or_else(invalid)?;
+
+        if sequence.is_empty() || !sequence.chars().all(|c| c.is_ascii_digit()) {
+            return Err(invalid());
+        }
+        let number = AccountNumber(sequence.parse().map_err(|_| invalid())?);
+        let check: u32 = match check {
+            digit if digit.len() == 1 => digit.parse().map_err(|_| invalid())?,
+            _ => return Err(invalid()),
+        };
+
+        if check != number.check_digit() {
+            return Err(format!("'{}' has a wrong check digit", s.trim()));
+        }
+        Ok(number)
+    }
+}
+
+impl TryFrom<String> for AccountNumber {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<AccountNumber> for String {
+    fn from(number: AccountNumber) -> Self {
+        number.to_string()
+    }
+}
@@ -0,0 +1,30 @@
+//! Account status module - the account lifecycle
+//!
+//! Demonstrates: Enums as state machines, Default derive on enums
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Where an account is in its lifecycle
+///
+/// - Active: everything allowed
+/// - Frozen: money can come in but not go out (reversible)
+/// - Closed: nothing allowed; the balance is zero and the account no
+///   longer counts towards the bank's total (final)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AccountStatus {
+    #[default]
+    Active,
+    Frozen,
+    Closed,
+}
+
+impl fmt::Display for AccountStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AccountStatus::Active => write!(f, "Active"),
+            AccountStatus::Frozen => write!(f, "Frozen"),
+            AccountStatus::Closed => write!(f, "Closed"),
+        }
+    }
+}
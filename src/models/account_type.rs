@@ -0,0 +1,68 @@
+//! Account type module - product types and their rules
+//!
+//! Demonstrates: Enums carrying data, associated constants, Option<T> returns
+
+use chrono::{DateTime, Months, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::format;
+
+/// The kind of account a customer holds
+///
+/// Demonstrates: Mixing unit variants and struct-like variants
+/// Each type carries its own withdrawal rules:
+/// - Checking: no restrictions
+/// - Savings: limited number of withdrawals per calendar month
+/// - FixedDeposit: no withdrawals before the maturity date
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub enum AccountType {
+    #[default]
+    Checking,
+    Savings,
+    /// Fixed deposit holds the date the funds are unlocked
+    FixedDeposit { maturity_date: DateTime<Utc> },
+}
+
+impl AccountType {
+    /// Maximum number of withdrawals allowed per month for savings accounts
+    pub const SAVINGS_MONTHLY_WITHDRAWAL_LIMIT: u32 = 6;
+
+    /// Creates a fixed deposit type maturing `term_months` from now
+    ///
+    /// # Returns
+    /// * `None` - If the maturity date would overflow the calendar
+    pub fn fixed_deposit(term_months: u32) -> Option<Self> {
+        Utc::now()
+            .checked_add_months(Months::new(term_months))
+            .map(|maturity_date| AccountType::FixedDeposit { maturity_date })
+    }
+
+    /// Returns the monthly withdrawal limit, if this type has one
+    pub fn monthly_withdrawal_limit(&self) -> Option<u32> {
+        match self {
+            AccountType::Savings => Some(Self::SAVINGS_MONTHLY_WITHDRAWAL_LIMIT),
+            _ => None,
+        }
+    }
+
+    /// Returns the date until which withdrawals are locked, if any
+    pub fn locked_until(&self) -> Option<DateTime<Utc>> {
+        match self {
+            AccountType::FixedDeposit { maturity_date } => Some(*maturity_date),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for AccountType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AccountType::Checking => write!(f, "Checking"),
+            AccountType::Savings => write!(f, "Savings"),
+            AccountType::FixedDeposit { maturity_date } => {
+                write!(f, "Fixed Deposit (matures {})", format::date(maturity_date))
+            }
+        }
+    }
+}
@@ -0,0 +1,125 @@
+//! Alerts module - standing balance alerts on an account
+//!
+//! Demonstrates: Option<T> fields as "off", flattening an internally
+//! tagged enum into its parent when serializing
+//!
+//! Rules are checked against each posting as it is made. A low-balance
+//! alert fires when a posting takes the balance from at or above the
+//! threshold to below it, so a customer is told once per dip rather than
+//! on every purchase while the balance stays low.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::errors::{BankError, BankResult};
+use super::ids::{AccountId, CustomerId, TransactionId};
+use super::money::Money;
+use super::transaction::Transaction;
+
+/// Per-account alert thresholds; `None` means the alert is off
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AlertRules {
+    /// Alert when the balance drops below this amount
+    #[serde(default)]
+    pub low_balance: Option<Money>,
+
+    /// Alert on any single transaction above this amount
+    #[serde(default)]
+    pub large_transaction: Option<Money>,
+}
+
+impl AlertRules {
+    /// Checks that the configured thresholds are positive
+    pub fn validate(&self) -> BankResult<()> {
+        for amount in [self.low_balance, self.large_transaction].into_iter().flatten() {
+            if !amount.is_positive() {
+                return Err(BankError::InvalidAmount(amount));
+            }
+        }
+        Ok(())
+    }
+
+    /// The alerts `transaction` triggers on an account whose balance was
+    /// `balance_before`
+    pub fn check(&self, transaction: &Transaction, balance_before: Money) -> Vec<AlertKind> {
+        let mut alerts = Vec::new();
+        if let Some(threshold) = self.large_transaction {
+            if transaction.amount > threshold {
+                alerts.push(AlertKind::LargeTransaction { amount: transaction.amount, threshold });
+            }
+        }
+        if let Some(threshold) = self.low_balance {
+            if balance_before >= threshold && transaction.balance_after < threshold {
+                alerts.push(AlertKind::LowBalance { threshold });
+            }
+        }
+        alerts
+    }
+}
+
+impl fmt::Display for AlertRules {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fn show(value: Option<Money>) -> String {
+            value.map_or("off".to_string(), |v| v.display().to_string())
+        }
+
+        writeln!(f, "Alert when balance drops below: {}", show(self.low_balance))?;
+        write!(f, "Alert on transactions above:    {}", show(self.large_transaction))
+    }
+}
+
+/// Which rule an alert was raised by
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "alert", rename_all = "snake_case")]
+pub enum AlertKind {
+    LowBalance { threshold: Money },
+    LargeTransaction { amount: Money, threshold: Money },
+    /// Spending in `category` this month went past its budget
+    BudgetExceeded { category: String, spent: Money, limit: Money },
+    /// The account had no activity for `inactive_days` and was flagged
+    /// dormant; `frozen` when the bank froze it too
+    Dormant { inactive_days: i64, frozen: bool },
+}
+
+/// A triggered alert, as handed to each `Notifier`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Alert {
+    pub customer_id: CustomerId,
+    pub account_id: AccountId,
+    /// The posting that triggered the alert; for a dormancy alert the
+    /// account's last posting, empty if it has none
+    pub transaction_id: TransactionId,
+    #[serde(flatten)]
+    pub kind: AlertKind,
+    /// Balance after the posting (the current balance for a dormancy alert)
+    pub balance: Money,
+    pub triggered_at: DateTime<Utc>,
+}
+
+impl fmt::Display for Alert {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.kind {
+            AlertKind::LowBalance { threshold } => write!(
+                f,
+                "Balance of account {} fell below {} (now {})",
+                self.account_id, threshold.display(), self.balance.display()
+            ),
+            AlertKind::LargeTransaction { amount, threshold } => write!(
+                f,
+                "Transaction of {} on account {} is above {}",
+                amount.display(), self.account_id, threshold.display()
+            ),
+            AlertKind::BudgetExceeded { category, spent, limit } => write!(
+                f,
+                "Spending on {} from account {} is {} this month, over its {} budget",
+                category, self.account_id, spent.display(), limit.display()
+            ),
+            AlertKind::Dormant { inactive_days, frozen } => write!(
+                f,
+                "Account {} has had no activity for {} days and is now dormant{}",
+                self.account_id, inactive_days, if *frozen { " and frozen" } else { "" }
+            ),
+        }
+    }
+}
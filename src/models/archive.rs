@@ -0,0 +1,30 @@
+//! Archive module - what is left of an account's archived history
+//!
+//! Demonstrates: A small summary record standing in for data moved elsewhere
+//!
+//! `Bank::archive_transactions` moves old transactions out of an account's
+//! live history. The account keeps an `ArchivedHistory` marker so that the
+//! live history still adds up: the balance it continues from, and how far
+//! back the archive reaches.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::money::Money;
+
+/// The part of an account's history that was moved to the archive
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArchivedHistory {
+    /// The latest cutoff the account was archived up to; transactions
+    /// before it are archived, except any kept live with a later one
+    /// (see `Bank::archive_transactions`). Under a history cap it is the
+    /// time of the oldest transaction kept.
+    pub before: DateTime<Utc>,
+
+    /// Balance after the last archived transaction, which the first live
+    /// transaction starts from
+    pub opening_balance: Money,
+
+    /// Number of transactions archived so far
+    pub transactions: usize,
+}
@@ -0,0 +1,34 @@
+//! Beneficiary module - a customer's saved payees
+//!
+//! Demonstrates: Case-insensitive keys by normalizing before lookup,
+//! BTreeMap for a listing that is always in order
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use super::ids::AccountId;
+
+/// A payee saved under a nickname, for transfers without typing an ID
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Beneficiary {
+    /// The nickname as it was typed, e.g. "Landlord"
+    pub nickname: String,
+
+    /// The payee's account ID
+    pub account_id: AccountId,
+
+    pub added_at: DateTime<Utc>,
+}
+
+impl Beneficiary {
+    /// The map key for a nickname; lookups ignore case and surrounding spaces
+    pub fn key(nickname: &str) -> String {
+        nickname.trim().to_lowercase()
+    }
+}
+
+impl fmt::Display for Beneficiary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} → account {}", self.nickname, self.account_id)
+    }
+}
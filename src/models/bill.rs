@@ -0,0 +1,171 @@
+//! Bill module - merchants customers pay bills to, and autopay rules
+//!
+//! Demonstrates: Reusing an existing enum (`Frequency`) for a second kind
+//! of recurring record
+//!
+//! Merchants belong to the bank rather than to a customer: once an admin
+//! registers "City Power", every customer can pay it. A customer tells
+//! the merchant apart from other payers by their bill reference (e.g. an
+//! electricity account number), which travels with each payment.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+use uuid::Uuid;
+
+use crate::format;
+use super::ids::CustomerId;
+use super::money::Money;
+use super::scheduled::Frequency;
+
+/// What a merchant bills for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MerchantCategory {
+    Utility,
+    Telecom,
+    Insurance,
+    Other,
+}
+
+impl FromStr for MerchantCategory {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "utility" => Ok(MerchantCategory::Utility),
+            "telecom" => Ok(MerchantCategory::Telecom),
+            "insurance" => Ok(MerchantCategory::Insurance),
+            "other" => Ok(MerchantCategory::Other),
+            other => Err(format!(
+                "unknown merchant category '{}' (use utility, telecom, insurance or other)",
+                other
+            )),
+        }
+    }
+}
+
+impl fmt::Display for MerchantCategory {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MerchantCategory::Utility => write!(f, "Utility"),
+            MerchantCategory::Telecom => write!(f, "Telecom"),
+            MerchantCategory::Insurance => write!(f, "Insurance"),
+            MerchantCategory::Other => write!(f, "Other"),
+        }
+    }
+}
+
+/// A company customers can pay bills to
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Merchant {
+    /// Unique identifier (UUID v4)
+    pub id: String,
+
+    /// Unique, ignoring case
+    pub name: String,
+
+    pub category: MerchantCategory,
+
+    pub added_at: DateTime<Utc>,
+}
+
+impl Merchant {
+    /// Creates a merchant added at `now`
+    pub(crate) fn new_at(name: String, category: MerchantCategory, now: DateTime<Utc>) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            name,
+            category,
+            added_at: now,
+        }
+    }
+}
+
+impl fmt::Display for Merchant {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} ({})", self.name, self.category)
+    }
+}
+
+/// A bill a customer pays automatically, e.g. a phone contract every month
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Autopay {
+    /// Unique identifier (UUID v4)
+    pub id: String,
+
+    /// The paying customer
+    pub customer_id: CustomerId,
+
+    pub merchant_id: String,
+
+    /// The customer's reference at the merchant
+    pub reference: String,
+
+    /// Amount paid on each run
+    pub amount: Money,
+
+    pub frequency: Frequency,
+
+    /// When the next payment is due
+    pub next_run: DateTime<Utc>,
+
+    /// Number of successful payments so far
+    #[serde(default)]
+    pub run_count: u32,
+
+    /// Why the latest run failed; cleared by the next successful one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+
+    pub created_at: DateTime<Utc>,
+}
+
+impl Autopay {
+    /// Creates a rule whose first payment is due at `first_run`
+    pub(crate) fn new_at(
+        customer_id: &CustomerId,
+        merchant_id: &str,
+        reference: String,
+        amount: Money,
+        frequency: Frequency,
+        first_run: DateTime<Utc>,
+        now: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            customer_id: customer_id.clone(),
+            merchant_id: merchant_id.to_string(),
+            reference,
+            amount,
+            frequency,
+            next_run: first_run,
+            run_count: 0,
+            last_error: None,
+            created_at: now,
+        }
+    }
+
+    /// Checks whether a payment is due at `now`
+    pub fn is_due(&self, now: DateTime<Utc>) -> bool {
+        self.next_run <= now
+    }
+}
+
+impl fmt::Display for Autopay {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} {} (ref {}) - next payment {}",
+            self.frequency,
+            self.amount.display(),
+            self.reference,
+            format::date(&self.next_run)
+        )?;
+        if let Some(error) = &self.last_error {
+            write!(f, " - last run failed: {}", error)?;
+        }
+        Ok(())
+    }
+}
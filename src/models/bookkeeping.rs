@@ -0,0 +1,394 @@
+//! Bookkeeping module - double-entry entries underneath the transactions
+//!
+//! Demonstrates: A fieldless enum as a fixed list of accounts, an
+//! invariant (debits equal credits) checked by a method rather than
+//! trusted, configuration validated as a whole before it replaces the old
+//!
+//! Every transaction on a customer's account is one side of a book entry:
+//! the customer's deposit (a liability of the bank) goes up or down, and
+//! an internal account such as cash, clearing or fee income takes the
+//! other side. The bank's own operations, like bringing cash into the
+//! vault, are book entries with no customer side.
+//!
+//! The chart of accounts gives each account a code, a name and a class,
+//! and says which account takes the other side of each kind of
+//! transaction. Cash deposits and withdrawals always post against cash,
+//! reversals mirror what they undo, and a term deposit's interest or
+//! penalty goes to interest expense or fee income.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::errors::{BankError, BankResult};
+use crate::format;
+use crate::validation;
+use super::ids::{CustomerId, TransactionId};
+use super::money::Money;
+use super::transaction::TransactionKind;
+
+/// An account in the bank's books
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BookAccount {
+    /// Notes and coins in the vault
+    Cash,
+    /// Money on its way to or from other banks, cheques and card networks
+    Clearing,
+    /// What the bank owes its customers; one sub-account per customer
+    CustomerDeposits,
+    /// Principal locked away in term deposits
+    TermDeposits,
+    /// Bill payments owed to merchants
+    MerchantPayables,
+    /// Transfers between customers; both legs together leave it at zero
+    InternalTransfers,
+    /// Cash the bank itself brought in or took out
+    Capital,
+    FeeIncome,
+    /// Interest paid on term deposits
+    InterestExpense,
+    /// The other side of a reversal whose original cannot be found
+    Suspense,
+}
+
+impl BookAccount {
+    /// Every account, in the order reports list them
+    pub const ALL: [BookAccount; 10] = [
+        BookAccount::Cash,
+        BookAccount::Clearing,
+        BookAccount::CustomerDeposits,
+        BookAccount::TermDeposits,
+        BookAccount::MerchantPayables,
+        BookAccount::InternalTransfers,
+        BookAccount::Capital,
+        BookAccount::FeeIncome,
+        BookAccount::InterestExpense,
+        BookAccount::Suspense,
+    ];
+}
+
+impl fmt::Display for BookAccount {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            BookAccount::Cash => "Cash",
+            BookAccount::Clearing => "Clearing",
+            BookAccount::CustomerDeposits => "Customer deposits",
+            BookAccount::TermDeposits => "Term deposits",
+            BookAccount::MerchantPayables => "Merchant payables",
+            BookAccount::InternalTransfers => "Internal transfers",
+            BookAccount::Capital => "Capital",
+            BookAccount::FeeIncome => "Fee income",
+            BookAccount::InterestExpense => "Interest expense",
+            BookAccount::Suspense => "Suspense",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Where an account goes in the reports
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AccountClass {
+    Asset,
+    Liability,
+    Equity,
+    Income,
+    Expense,
+}
+
+impl fmt::Display for AccountClass {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            AccountClass::Asset => "Asset",
+            AccountClass::Liability => "Liability",
+            AccountClass::Equity => "Equity",
+            AccountClass::Income => "Income",
+            AccountClass::Expense => "Expense",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for AccountClass {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "asset" => Ok(AccountClass::Asset),
+            "liability" => Ok(AccountClass::Liability),
+            "equity" => Ok(AccountClass::Equity),
+            "income" => Ok(AccountClass::Income),
+            "expense" => Ok(AccountClass::Expense),
+            other => Err(format!("unknown account class '{}'", other)),
+        }
+    }
+}
+
+/// An account's line in the chart of accounts
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChartAccount {
+    pub account: BookAccount,
+    /// Digits, e.g. `4000`; reports list accounts by code
+    pub code: String,
+    pub name: String,
+    pub class: AccountClass,
+}
+
+/// "Transactions of `kind` post against `account`"
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MappingRule {
+    pub kind: TransactionKind,
+    pub account: BookAccount,
+}
+
+/// Transaction kinds with a mapping rule; reversals mirror the
+/// transaction they undo instead
+pub const MAPPED_KINDS: [TransactionKind; 8] = [
+    TransactionKind::Deposit,
+    TransactionKind::Withdrawal,
+    TransactionKind::TransferOut,
+    TransactionKind::TransferIn,
+    TransactionKind::ExternalTransfer,
+    TransactionKind::Fee,
+    TransactionKind::BillPayment,
+    TransactionKind::TermDeposit,
+];
+
+/// The bank's chart of accounts and mapping rules
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChartOfAccounts {
+    /// One line per `BookAccount`
+    pub accounts: Vec<ChartAccount>,
+    /// One rule per kind in `MAPPED_KINDS`
+    pub rules: Vec<MappingRule>,
+}
+
+impl Default for ChartOfAccounts {
+    fn default() -> Self {
+        let line = |account, code: &str, class| ChartAccount {
+            account,
+            code: code.to_string(),
+            name: account.to_string(),
+            class,
+        };
+        let rule = |kind, account| MappingRule { kind, account };
+        ChartOfAccounts {
+            accounts: vec![
+                line(BookAccount::Cash, "1000", AccountClass::Asset),
+                line(BookAccount::Clearing, "1100", AccountClass::Asset),
+                line(BookAccount::CustomerDeposits, "2000", AccountClass::Liability),
+                line(BookAccount::TermDeposits, "2100", AccountClass::Liability),
+                line(BookAccount::MerchantPayables, "2200", AccountClass::Liability),
+                line(BookAccount::InternalTransfers, "2300", AccountClass::Liability),
+                line(BookAccount::Suspense, "2900", AccountClass::Liability),
+                line(BookAccount::Capital, "3000", AccountClass::Equity),
+                line(BookAccount::FeeIncome, "4000", AccountClass::Income),
+                line(BookAccount::InterestExpense, "5000", AccountClass::Expense),
+            ],
+            rules: vec![
+                // Non-cash deposits and withdrawals: cheques, card
+                // purchases, captured holds and imports
+                rule(TransactionKind::Deposit, BookAccount::Clearing),
+                rule(TransactionKind::Withdrawal, BookAccount::Clearing),
+                rule(TransactionKind::TransferOut, BookAccount::InternalTransfers),
+                rule(TransactionKind::TransferIn, BookAccount::InternalTransfers),
+                rule(TransactionKind::ExternalTransfer, BookAccount::Clearing),
+                rule(TransactionKind::Fee, BookAccount::FeeIncome),
+                rule(TransactionKind::BillPayment, BookAccount::MerchantPayables),
+                rule(TransactionKind::TermDeposit, BookAccount::TermDeposits),
+            ],
+        }
+    }
+}
+
+impl ChartOfAccounts {
+    /// Checks that every account appears once with a valid, unique code
+    /// and a name, and that every kind in `MAPPED_KINDS` has one rule
+    pub fn validate(&self) -> BankResult<()> {
+        for account in BookAccount::ALL {
+            let lines = self.accounts.iter().filter(|line| line.account == account).count();
+            if lines != 1 {
+                return Err(BankError::InvalidInput(format!(
+                    "the chart needs exactly one line for {}, not {}",
+                    account, lines
+                )));
+            }
+        }
+        for (i, line) in self.accounts.iter().enumerate() {
+            validation::validate_ledger_code(&line.code)?;
+            validation::validate_ledger_name(&line.name)?;
+            if self.accounts[..i].iter().any(|other| other.code == line.code) {
+                return Err(BankError::InvalidInput(format!("account code {} is used twice", line.code)));
+            }
+        }
+        for kind in MAPPED_KINDS {
+            let rules = self.rules.iter().filter(|rule| rule.kind == kind).count();
+            if rules != 1 {
+                return Err(BankError::InvalidInput(format!("{} needs exactly one mapping rule, not {}", kind, rules)));
+            }
+        }
+        if let Some(rule) = self.rules.iter().find(|rule| !MAPPED_KINDS.contains(&rule.kind)) {
+            return Err(BankError::InvalidInput(format!("{} transactions cannot be mapped", rule.kind)));
+        }
+        Ok(())
+    }
+
+    /// The chart's line for `account`; `None` only in a chart that fails
+    /// `validate`
+    pub fn line(&self, account: BookAccount) -> Option<&ChartAccount> {
+        self.accounts.iter().find(|line| line.account == account)
+    }
+
+    /// The account with this code, or this name ignoring case
+    pub fn find(&self, code_or_name: &str) -> Option<BookAccount> {
+        let wanted = code_or_name.trim();
+        self.accounts
+            .iter()
+            .find(|line| line.code == wanted || line.name.eq_ignore_ascii_case(wanted))
+            .map(|line| line.account)
+    }
+
+    /// The account across from the customer's deposit for `kind`
+    pub fn mapped(&self, kind: TransactionKind) -> BookAccount {
+        self.rules.iter().find(|rule| rule.kind == kind).map_or(BookAccount::Suspense, |rule| rule.account)
+    }
+
+    /// Points `kind` at `account`, replacing its rule
+    pub fn map(&mut self, kind: TransactionKind, account: BookAccount) {
+        self.rules.retain(|rule| rule.kind != kind);
+        self.rules.push(MappingRule { kind, account });
+    }
+}
+
+impl fmt::Display for ChartOfAccounts {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut lines: Vec<&ChartAccount> = self.accounts.iter().collect();
+        lines.sort_by(|a, b| a.code.cmp(&b.code));
+        write!(f, "Chart of accounts:")?;
+        for line in lines {
+            write!(f, "\n  {:<10} {:<24} {}", line.code, line.name, line.class)?;
+        }
+        write!(f, "\nMapping rules:")?;
+        for kind in MAPPED_KINDS {
+            let account = self.mapped(kind);
+            match self.line(account) {
+                Some(line) => write!(f, "\n  {:<18} -> {} {}", kind.to_string(), line.code, line.name)?,
+                None => write!(f, "\n  {:<18} -> {}", kind.to_string(), account)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// One side of a book entry; exactly one of `debit` and `credit` is
+/// non-zero
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BookLine {
+    pub account: BookAccount,
+
+    /// The customer whose deposit this line moves, for `CustomerDeposits`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub customer_id: Option<CustomerId>,
+
+    pub debit: Money,
+    pub credit: Money,
+}
+
+impl BookLine {
+    pub fn debit(account: BookAccount, amount: Money) -> Self {
+        BookLine { account, customer_id: None, debit: amount, credit: Money::ZERO }
+    }
+
+    pub fn credit(account: BookAccount, amount: Money) -> Self {
+        BookLine { account, customer_id: None, debit: Money::ZERO, credit: amount }
+    }
+
+    /// The same line on the other side, as a reversal posts it
+    pub fn mirrored(&self) -> Self {
+        BookLine { debit: self.credit, credit: self.debit, ..self.clone() }
+    }
+}
+
+/// Lines that move money between accounts in the books
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BookEntry {
+    pub at: DateTime<Utc>,
+
+    /// The customer transaction behind the entry, if there is one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transaction_id: Option<TransactionId>,
+
+    pub memo: String,
+    pub lines: Vec<BookLine>,
+}
+
+impl BookEntry {
+    /// An entry for the bank's own operations: `amount` from `credit` to
+    /// `debit`, with no customer transaction behind it
+    pub fn bank(at: DateTime<Utc>, memo: &str, debit: BookAccount, credit: BookAccount, amount: Money) -> Self {
+        BookEntry {
+            at,
+            transaction_id: None,
+            memo: memo.to_string(),
+            lines: vec![BookLine::debit(debit, amount), BookLine::credit(credit, amount)],
+        }
+    }
+
+    /// One entry at `at` with the net effect of `entries`, the way the
+    /// books keep history that was archived
+    ///
+    /// Lines on the same account (and customer) are netted; accounts that
+    /// net to zero are left out.
+    pub fn brought_forward(at: DateTime<Utc>, entries: &[BookEntry]) -> Self {
+        let mut net: BTreeMap<(BookAccount, Option<&CustomerId>), Money> = BTreeMap::new();
+        for line in entries.iter().flat_map(|entry| &entry.lines) {
+            *net.entry((line.account, line.customer_id.as_ref())).or_default() += line.debit - line.credit;
+        }
+        let lines = net
+            .into_iter()
+            .filter(|(_, amount)| !amount.is_zero())
+            .map(|((account, customer_id), amount)| {
+                let line = match amount.is_positive() {
+                    true => BookLine::debit(account, amount),
+                    false => BookLine::credit(account, -amount),
+                };
+                BookLine { customer_id: customer_id.cloned(), ..line }
+            })
+            .collect();
+        BookEntry { at, transaction_id: None, memo: "BROUGHT FORWARD".to_string(), lines }
+    }
+
+    pub fn total_debits(&self) -> Money {
+        self.lines.iter().map(|line| line.debit).sum()
+    }
+
+    pub fn total_credits(&self) -> Money {
+        self.lines.iter().map(|line| line.credit).sum()
+    }
+
+    /// Whether the debits equal the credits, as every entry's must
+    pub fn is_balanced(&self) -> bool {
+        self.total_debits() == self.total_credits()
+    }
+}
+
+impl fmt::Display for BookEntry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} - {}", format::datetime(&self.at), self.memo)?;
+        for line in &self.lines {
+            let account = match &line.customer_id {
+                Some(customer_id) => format!("{} ({})", line.account, customer_id.short()),
+                None => line.account.to_string(),
+            };
+            if line.debit.is_positive() {
+                write!(f, "\n    Dr {:<32} {:>14}", account, line.debit.display())?;
+            } else {
+                write!(f, "\n        Cr {:<28} {:>14}", account, line.credit.display())?;
+            }
+        }
+        Ok(())
+    }
+}
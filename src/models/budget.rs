@@ -0,0 +1,148 @@
+//! Budget module - monthly spending limits per category
+//!
+//! Demonstrates: Deriving a report from history on demand instead of
+//! keeping running totals that would need updating on every posting
+//!
+//! A budget caps what a customer means to spend in one category (as set
+//! by `TransactionTags`) each calendar month (UTC). Nothing is stored
+//! besides the limit: spending is added up from the account's
+//! transactions, counted the same way as `Account::category_breakdown`.
+//! A budget is a warning, not a limit; spending past it still goes
+//! through.
+
+use chrono::{DateTime, Datelike, Months, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::errors::{BankError, BankResult};
+use super::account::Account;
+use super::alerts::AlertKind;
+use super::money::Money;
+use super::transaction::{Transaction, TransactionType};
+
+/// A monthly spending limit for one category
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Budget {
+    /// The category, normalized like a transaction's (trimmed, lowercase)
+    pub category: String,
+
+    /// The most the customer means to spend in the category per month
+    pub limit: Money,
+
+    pub created_at: DateTime<Utc>,
+}
+
+impl Budget {
+    /// Creates a budget set at `now`
+    pub(crate) fn new_at(category: String, limit: Money, now: DateTime<Utc>) -> Self {
+        Self { category, limit, created_at: now }
+    }
+}
+
+/// Spending against one budget in one month
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BudgetStatus {
+    pub category: String,
+    pub limit: Money,
+    pub spent: Money,
+    /// `limit - spent`; negative once the budget is exceeded
+    pub remaining: Money,
+}
+
+impl BudgetStatus {
+    fn new(budget: &Budget, spent: Money) -> Self {
+        Self {
+            category: budget.category.clone(),
+            limit: budget.limit,
+            spent,
+            remaining: budget.limit - spent,
+        }
+    }
+
+    /// How much of the budget is spent, in whole percent (may pass 100)
+    pub fn percent_used(&self) -> u32 {
+        if !self.limit.is_positive() {
+            return 100;
+        }
+        (self.spent.cents() * 100 / self.limit.cents()) as u32
+    }
+
+    /// Returns true once more than the limit has been spent
+    pub fn is_exceeded(&self) -> bool {
+        self.spent > self.limit
+    }
+}
+
+impl fmt::Display for BudgetStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {} of {} ({}%)", self.category, self.spent.display(), self.limit.display(), self.percent_used())?;
+        if self.is_exceeded() {
+            write!(f, ", {} over", (-self.remaining).display())?;
+        }
+        Ok(())
+    }
+}
+
+/// The first instant of the month `at` falls in, and of the month after
+fn month_bounds(at: DateTime<Utc>) -> (DateTime<Utc>, DateTime<Utc>) {
+    let first = NaiveDate::from_ymd_opt(at.year(), at.month(), 1).unwrap();
+    let next = first.checked_add_months(Months::new(1)).unwrap();
+    (first.and_hms_opt(0, 0, 0).unwrap().and_utc(), next.and_hms_opt(0, 0, 0).unwrap().and_utc())
+}
+
+impl Account {
+    /// The budget for `category`, if there is one
+    pub fn budget(&self, category: &str) -> Option<&Budget> {
+        let category = category.trim().to_lowercase();
+        self.budgets.iter().find(|b| b.category == category)
+    }
+
+    /// Spending against every budget in one calendar month, in the order
+    /// the budgets were set
+    ///
+    /// # Returns
+    /// * `Err(BankError::InvalidInput)` - If the month is not 1 to 12 or the
+    ///   year is out of range
+    pub fn budget_report(&self, year: i32, month: u32) -> BankResult<Vec<BudgetStatus>> {
+        let first = NaiveDate::from_ymd_opt(year, month, 1)
+            .ok_or_else(|| BankError::InvalidInput(format!("{}-{:02} is not a valid month", year, month)))?;
+        let (from, to) = month_bounds(first.and_hms_opt(0, 0, 0).unwrap().and_utc());
+
+        Ok(self
+            .budgets
+            .iter()
+            .map(|budget| BudgetStatus::new(budget, self.category_spending(&budget.category, from, to)))
+            .collect())
+    }
+
+    /// The budget spending `amount` in `category` at `at` would exceed
+    ///
+    /// # Returns
+    /// * `Some(BudgetStatus)` - The budget as it would stand afterwards, if
+    ///   the spending takes it past its limit
+    /// * `None` - If there is no budget for the category, or it would still
+    ///   be within its limit
+    pub fn budget_warning(&self, category: Option<&str>, amount: Money, at: DateTime<Utc>) -> Option<BudgetStatus> {
+        let budget = self.budget(category?)?;
+        let (from, to) = month_bounds(at);
+        let status = BudgetStatus::new(budget, self.category_spending(&budget.category, from, to) + amount);
+        status.is_exceeded().then_some(status)
+    }
+
+    /// The alert for a budget that `transaction` is about to take past
+    /// its limit; later spending in the same month, already over, raises
+    /// none
+    pub(crate) fn budget_alert(&self, transaction: &Transaction) -> Option<AlertKind> {
+        let spending = matches!(transaction.transaction_type, TransactionType::Withdrawal)
+            || transaction.transaction_type.is_transfer_out();
+        if !spending {
+            return None;
+        }
+        let status = self.budget_warning(transaction.category.as_deref(), transaction.amount, transaction.timestamp)?;
+        (status.spent - transaction.amount <= status.limit).then_some(AlertKind::BudgetExceeded {
+            category: status.category,
+            spent: status.spent,
+            limit: status.limit,
+        })
+    }
+}
@@ -0,0 +1,184 @@
+//! Builder module - customers and accounts with only the options you need
+//!
+//! Demonstrates: The builder pattern with by-value `self` methods,
+//! validating everything once in `build`
+//!
+//! `Customer::new` and `Account::new` take what every customer and
+//! account has. The builders add the optional parts (contact details,
+//! account type, limits, alert rules) without the constructors growing a
+//! parameter for each. `Bank::register` and `Bank::open_account` take a
+//! builder and stamp the result with the bank's clock.
+//!
+//! `AccountBuilder` has no currency, overdraft limit or interest rate,
+//! because `Account` has none yet. An account holds plain `Money` in the
+//! one currency the whole bank uses; a balance below zero is an error
+//! everywhere from `Account::withdraw` to `Bank::verify_integrity`; and
+//! interest is only paid on term deposits. Each of these needs its rules
+//! in the bank before it can be a builder option.
+
+use chrono::{DateTime, NaiveDate, Utc};
+
+use crate::errors::BankResult;
+use crate::validation;
+use super::account::{Account, AccountType};
+use super::alerts::AlertRules;
+use super::customer::Customer;
+use super::limits::AccountLimits;
+use super::money::Money;
+
+/// A customer to be created (see `Customer::builder`)
+///
+/// ```
+/// use chrono::NaiveDate;
+/// use rust_banking_system::Customer;
+///
+/// let customer = Customer::builder("Ada Lovelace", "Ada@Example.com")
+///     .phone("+44 20 7946 0000")
+///     .date_of_birth(NaiveDate::from_ymd_opt(1990, 12, 10).unwrap())
+///     .build()
+///     .unwrap();
+/// assert_eq!(customer.email, "ada@example.com");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CustomerBuilder {
+    name: String,
+    email: String,
+    phone: Option<String>,
+    address: Option<String>,
+    date_of_birth: Option<NaiveDate>,
+}
+
+impl CustomerBuilder {
+    /// A customer with just a name and an email
+    pub fn new(name: impl Into<String>, email: impl Into<String>) -> Self {
+        Self { name: name.into(), email: email.into(), phone: None, address: None, date_of_birth: None }
+    }
+
+    /// Their phone number, stored in a normalized form
+    pub fn phone(mut self, phone: impl Into<String>) -> Self {
+        self.phone = Some(phone.into());
+        self
+    }
+
+    /// Their postal address
+    pub fn address(mut self, address: impl Into<String>) -> Self {
+        self.address = Some(address.into());
+        self
+    }
+
+    pub fn date_of_birth(mut self, date_of_birth: NaiveDate) -> Self {
+        self.date_of_birth = Some(date_of_birth);
+        self
+    }
+
+    /// Creates the customer, registered now
+    ///
+    /// # Returns
+    /// * `Err(BankError::ValidationError)` - Naming the first invalid field
+    pub fn build(self) -> BankResult<Customer> {
+        self.build_at(Utc::now())
+    }
+
+    /// Creates the customer, registered at `now`
+    pub(crate) fn build_at(self, now: DateTime<Utc>) -> BankResult<Customer> {
+        let name = validation::validate_name(&self.name)?;
+        let mut customer = Customer::new(name, validation::validate_email(&self.email)?);
+        customer.phone = self.phone.as_deref().map(validation::normalize_phone).transpose()?;
+        customer.address = self.address.as_deref().map(validation::validate_address).transpose()?;
+        if let Some(date_of_birth) = self.date_of_birth {
+            validation::validate_date_of_birth(date_of_birth, now.date_naive())?;
+        }
+        customer.date_of_birth = self.date_of_birth;
+        customer.registered_at = now;
+        Ok(customer)
+    }
+}
+
+/// An account to be opened (see `Account::builder`)
+///
+/// Without any options it is an empty checking account with no limits or
+/// alerts.
+///
+/// ```
+/// use rust_banking_system::models::{AccountLimits, AccountType};
+/// use rust_banking_system::{Account, Money};
+///
+/// let account = Account::builder()
+///     .account_type(AccountType::Savings)
+///     .initial_deposit(Money::from_major(250))
+///     .limits(AccountLimits { daily_withdrawal: Some(Money::from_major(100)), ..AccountLimits::default() })
+///     .build()
+///     .unwrap();
+/// assert_eq!(account.balance, Money::from_major(250));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AccountBuilder {
+    account_type: AccountType,
+    initial_deposit: Money,
+    limits: AccountLimits,
+    alerts: AlertRules,
+}
+
+impl AccountBuilder {
+    /// An empty checking account
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn account_type(mut self, account_type: AccountType) -> Self {
+        self.account_type = account_type;
+        self
+    }
+
+    /// Paid in as cash when the account opens
+    pub fn initial_deposit(mut self, amount: Money) -> Self {
+        self.initial_deposit = amount;
+        self
+    }
+
+    /// Withdrawal and transfer caps from the start
+    pub fn limits(mut self, limits: AccountLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Balance alert thresholds from the start
+    pub fn alerts(mut self, rules: AlertRules) -> Self {
+        self.alerts = rules;
+        self
+    }
+
+    /// Creates the account, opened now
+    ///
+    /// # Returns
+    /// * `Err(BankError::InvalidAmount)` - If the deposit is negative or a
+    ///   limit or alert amount is not positive
+    pub fn build(self) -> BankResult<Account> {
+        self.build_at(Utc::now())
+    }
+
+    /// Creates the account, opened at `now`
+    pub(crate) fn build_at(self, now: DateTime<Utc>) -> BankResult<Account> {
+        self.limits.validate()?;
+        self.alerts.validate()?;
+        let mut account = Account::new_at(self.initial_deposit, self.account_type, now)?;
+        account.limits = self.limits;
+        account.alerts = self.alerts;
+        Ok(account)
+    }
+}
+
+impl Customer {
+    /// Starts building a customer with optional contact details
+    pub fn builder(name: impl Into<String>, email: impl Into<String>) -> CustomerBuilder {
+        CustomerBuilder::new(name, email)
+    }
+}
+
+impl Account {
+    /// Starts building an account with an optional type, opening deposit,
+    /// limits and alerts
+    pub fn builder() -> AccountBuilder {
+        AccountBuilder::new()
+    }
+}
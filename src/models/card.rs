@@ -0,0 +1,201 @@
+//! Card module - debit cards linked to an account
+//!
+//! Demonstrates: A record that keeps only a masked form of its secret,
+//! and a lifecycle where replacing retires a record instead of editing it
+//!
+//! The full card number is generated when the card is issued and then
+//! thrown away; only the masked form (`4000 **** **** 1234`) is stored.
+//! A blocked card can be replaced: the old card is marked `Replaced` and a
+//! new one with a new number, the same limits and a fresh expiry date is
+//! issued in its place.
+
+use chrono::{DateTime, Datelike, Months, NaiveDate, Utc};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use uuid::Uuid;
+
+use crate::errors::{BankError, BankResult};
+use super::account_number::AccountNumber;
+use super::ids::AccountId;
+use super::limits::Limit;
+use super::money::Money;
+use super::transaction::{Transaction, TransactionStatus};
+
+/// Years a new card stays valid
+pub const CARD_VALIDITY_YEARS: u32 = 3;
+
+/// Leading digits of every card number the bank issues
+const CARD_PREFIX: u64 = 4000;
+
+/// Whether a card can be used
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CardStatus {
+    #[default]
+    Active,
+    /// Stopped, e.g. because it was lost; replace it to get a working card
+    Blocked,
+    /// Superseded by a replacement card
+    Replaced,
+}
+
+impl fmt::Display for CardStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CardStatus::Active => write!(f, "Active"),
+            CardStatus::Blocked => write!(f, "Blocked"),
+            CardStatus::Replaced => write!(f, "Replaced"),
+        }
+    }
+}
+
+/// Spending caps for one card, on top of the account's own limits;
+/// `None` means unlimited
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CardLimits {
+    /// Largest single purchase
+    #[serde(default)]
+    pub per_purchase: Option<Money>,
+
+    /// Most spent with the card per day
+    #[serde(default)]
+    pub daily: Option<Money>,
+}
+
+impl CardLimits {
+    /// Checks that the configured amounts are positive
+    pub fn validate(&self) -> BankResult<()> {
+        for amount in [self.per_purchase, self.daily].into_iter().flatten() {
+            if !amount.is_positive() {
+                return Err(BankError::InvalidAmount(amount));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for CardLimits {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let show = |value: Option<Money>| value.map_or("unlimited".to_string(), |v| v.display().to_string());
+        write!(f, "per purchase {}, per day {}", show(self.per_purchase), show(self.daily))
+    }
+}
+
+/// A debit card
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Card {
+    /// Unique identifier (UUID v4)
+    pub id: String,
+
+    /// The card number with all but the first and last four digits hidden
+    pub masked_number: String,
+
+    /// The account purchases are taken from
+    pub account_id: AccountId,
+
+    /// Last day the card can be used
+    pub expires_on: NaiveDate,
+
+    #[serde(default)]
+    pub status: CardStatus,
+
+    #[serde(default)]
+    pub limits: CardLimits,
+
+    pub issued_at: DateTime<Utc>,
+
+    /// The card that replaced this one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub replaced_by: Option<String>,
+}
+
+impl Card {
+    /// Creates an active card for `account_id`, valid until the end of the
+    /// month `CARD_VALIDITY_YEARS` from `now`
+    pub(crate) fn new_at(account_id: &AccountId, limits: CardLimits, now: DateTime<Utc>) -> Self {
+        // Fifteen digits, then a Luhn check digit like account numbers have
+        let body = CARD_PREFIX * 100_000_000_000 + rand::thread_rng().gen_range(0..100_000_000_000);
+        let number = format!("{}{}", body, AccountNumber::new(body).check_digit());
+
+        Self {
+            id: Uuid::new_v4().to_string(),
+            masked_number: format!("{} **** **** {}", &number[..4], &number[12..]),
+            account_id: account_id.clone(),
+            expires_on: expiry_date(now),
+            status: CardStatus::Active,
+            limits,
+            issued_at: now,
+            replaced_by: None,
+        }
+    }
+
+    /// The last four digits of the card number
+    pub fn last_four(&self) -> &str {
+        &self.masked_number[self.masked_number.len() - 4..]
+    }
+
+    /// Returns true once the expiry date has passed
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        now.date_naive() > self.expires_on
+    }
+
+    /// Checks that the card can pay `amount` at `now`
+    ///
+    /// # Arguments
+    /// * `history` - The linked account's transactions, for the daily limit
+    ///
+    /// # Returns
+    /// * `Err(BankError::CardDeclined)` - If the card is blocked, replaced
+    ///   or expired
+    /// * `Err(BankError::LimitExceeded)` - If a card limit would be exceeded
+    pub fn check_purchase(&self, amount: Money, history: &[Transaction], now: DateTime<Utc>) -> BankResult<()> {
+        if self.status != CardStatus::Active {
+            return Err(BankError::CardDeclined(format!("card {} is {}", self.last_four(), self.status)));
+        }
+        if self.is_expired(now) {
+            return Err(BankError::CardDeclined(format!("card {} has expired", self.last_four())));
+        }
+
+        if let Some(max) = self.limits.per_purchase {
+            if amount > max {
+                return Err(BankError::LimitExceeded(Limit::CardPurchase(max)));
+            }
+        }
+        if let Some(max) = self.limits.daily {
+            let day = now.date_naive();
+            let today: Money = history
+                .iter()
+                .filter(|tx| tx.card_id.as_deref() == Some(self.id.as_str()))
+                .filter(|tx| tx.timestamp.date_naive() == day && tx.status != TransactionStatus::Reversed)
+                .map(|tx| tx.amount)
+                .sum();
+            if today + amount > max {
+                return Err(BankError::LimitExceeded(Limit::CardDaily(max)));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Card {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} (expires {}) - {}",
+            self.masked_number,
+            self.expires_on.format("%m/%y"),
+            self.status
+        )
+    }
+}
+
+/// The last day of the month `CARD_VALIDITY_YEARS` after `now`
+fn expiry_date(now: DateTime<Utc>) -> NaiveDate {
+    let today = now.date_naive();
+    let first_of_month = today.with_day(1).unwrap_or(today);
+    first_of_month
+        .checked_add_months(Months::new(12 * CARD_VALIDITY_YEARS + 1))
+        .and_then(|next_month| next_month.pred_opt())
+        .unwrap_or(NaiveDate::MAX)
+}
@@ -0,0 +1,134 @@
+//! Cheque module - paper cheques paid in and waiting to clear
+//!
+//! Demonstrates: A record with a small state machine (pending, then
+//! cleared or bounced), a policy struct with defaults
+//!
+//! A cheque is credited to the account as soon as it is deposited, but
+//! the deposit stays pending, and out of the available balance, until the
+//! cheque clears. A cheque that bounces is reversed and may cost a fee.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::errors::{BankError, BankResult};
+use crate::format;
+use super::ids::{CustomerId, TransactionId};
+use super::money::Money;
+
+/// Days a cheque takes to clear unless the bank sets otherwise
+pub const DEFAULT_CLEARING_DAYS: u32 = 3;
+
+/// Longest clearing period the bank may set
+pub const MAX_CLEARING_DAYS: u32 = 30;
+
+/// Where a deposited cheque stands
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChequeStatus {
+    Pending,
+    Cleared,
+    Bounced,
+}
+
+impl fmt::Display for ChequeStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ChequeStatus::Pending => write!(f, "Pending"),
+            ChequeStatus::Cleared => write!(f, "Cleared"),
+            ChequeStatus::Bounced => write!(f, "Bounced"),
+        }
+    }
+}
+
+/// A cheque paid into an account
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Cheque {
+    /// Unique identifier (UUID v4)
+    pub id: String,
+
+    /// Serial number printed on the cheque
+    pub number: String,
+
+    pub amount: Money,
+
+    /// The customer who wrote the cheque, if they bank here; such a cheque
+    /// is paid from their account when it clears. `None` for a cheque
+    /// drawn on another bank.
+    #[serde(default)]
+    pub drawn_on: Option<CustomerId>,
+
+    pub deposited_at: DateTime<Utc>,
+
+    /// When the cheque is due to clear
+    pub clears_at: DateTime<Utc>,
+
+    pub status: ChequeStatus,
+
+    /// The pending deposit that credited the cheque
+    pub transaction_id: TransactionId,
+
+    /// Why the cheque bounced
+    #[serde(default)]
+    pub bounce_reason: Option<String>,
+}
+
+impl Cheque {
+    /// Returns true if the cheque is pending and due to clear by `now`
+    pub fn is_due(&self, now: DateTime<Utc>) -> bool {
+        self.status == ChequeStatus::Pending && self.clears_at <= now
+    }
+}
+
+impl fmt::Display for Cheque {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Cheque #{} for {} - {}", self.number, self.amount.display(), self.status)?;
+        match (self.status, &self.bounce_reason) {
+            (ChequeStatus::Pending, _) => write!(f, " (clears {})", format::datetime(&self.clears_at)),
+            (ChequeStatus::Bounced, Some(reason)) => write!(f, " ({})", reason),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// How the bank handles deposited cheques
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChequePolicy {
+    /// Days from deposit until a cheque clears
+    pub clearing_days: u32,
+
+    /// Charged to the depositor when a cheque bounces; zero for none
+    pub bounce_fee: Money,
+}
+
+impl Default for ChequePolicy {
+    fn default() -> Self {
+        Self {
+            clearing_days: DEFAULT_CLEARING_DAYS,
+            bounce_fee: Money::from_major(25),
+        }
+    }
+}
+
+impl ChequePolicy {
+    /// Checks the clearing period (0 to `MAX_CLEARING_DAYS`) and that the
+    /// fee is not negative
+    pub fn validate(&self) -> BankResult<()> {
+        if self.clearing_days > MAX_CLEARING_DAYS {
+            return Err(BankError::InvalidInput(format!(
+                "cheques must clear within {} days",
+                MAX_CLEARING_DAYS
+            )));
+        }
+        if self.bounce_fee.is_negative() {
+            return Err(BankError::InvalidAmount(self.bounce_fee));
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for ChequePolicy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Cheques clear after {} day(s); bounce fee {}", self.clearing_days, self.bounce_fee.display())
+    }
+}
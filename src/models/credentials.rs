@@ -0,0 +1,104 @@
+//! Credentials module - salted PIN hashes and lockout
+//!
+//! Demonstrates: Password hashing with Argon2 (PHC strings), state machines
+//! driven by time
+//! https://docs.rs/argon2/
+//!
+//! Only a salted Argon2 hash of the PIN is stored. After
+//! `MAX_PIN_ATTEMPTS` wrong PINs in a row the credentials are locked for
+//! `PIN_LOCKOUT_MINUTES`.
+
+use aes_gcm::aead::OsRng;
+use argon2::password_hash::SaltString;
+use argon2::{Argon2, PasswordHash, PasswordHasher, PasswordVerifier};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::errors::{BankError, BankResult};
+
+/// Wrong PINs allowed in a row before the credentials are locked
+pub const MAX_PIN_ATTEMPTS: u32 = 3;
+
+/// How long credentials stay locked after too many wrong PINs
+pub const PIN_LOCKOUT_MINUTES: i64 = 15;
+
+/// A customer's login credentials
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Credentials {
+    /// Argon2 hash of the PIN in PHC string format (includes the salt)
+    pin_hash: String,
+
+    /// Wrong PINs entered since the last successful login
+    #[serde(default)]
+    failed_attempts: u32,
+
+    /// Set while the credentials are locked out
+    #[serde(default)]
+    locked_until: Option<DateTime<Utc>>,
+}
+
+impl Credentials {
+    /// Checks that a PIN is 4 to 12 digits
+    pub fn validate_pin(pin: &str) -> BankResult<()> {
+        if (4..=12).contains(&pin.len()) && pin.chars().all(|c| c.is_ascii_digit()) {
+            Ok(())
+        } else {
+            Err(BankError::InvalidInput("PIN must be 4 to 12 digits".to_string()))
+        }
+    }
+
+    /// Hashes a new PIN with a fresh random salt
+    pub fn new(pin: &str) -> BankResult<Self> {
+        Self::validate_pin(pin)?;
+
+        let salt = SaltString::generate(&mut OsRng);
+        let pin_hash = Argon2::default()
+            .hash_password(pin.as_bytes(), &salt)
+            .map_err(|e| BankError::EncryptionError(e.to_string()))?
+            .to_string();
+
+        Ok(Self {
+            pin_hash,
+            failed_attempts: 0,
+            locked_until: None,
+        })
+    }
+
+    /// Returns the end of the lockout, if the credentials are locked at `now`
+    pub fn locked_until(&self, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        self.locked_until.filter(|until| *until > now)
+    }
+
+    /// Checks a PIN, counting failures and locking after too many
+    ///
+    /// # Returns
+    /// * `Ok(())` - The PIN is correct; the failure count is reset
+    /// * `Err(BankError::InvalidPin)` - Wrong PIN, with attempts left
+    /// * `Err(BankError::PinLocked)` - Locked now or by this attempt
+    pub fn verify(&mut self, pin: &str, now: DateTime<Utc>) -> BankResult<()> {
+        if let Some(until) = self.locked_until(now) {
+            return Err(BankError::PinLocked { until });
+        }
+
+        let hash = PasswordHash::new(&self.pin_hash)
+            .map_err(|e| BankError::EncryptionError(e.to_string()))?;
+
+        if Argon2::default().verify_password(pin.as_bytes(), &hash).is_ok() {
+            self.failed_attempts = 0;
+            self.locked_until = None;
+            return Ok(());
+        }
+
+        self.failed_attempts += 1;
+        if self.failed_attempts >= MAX_PIN_ATTEMPTS {
+            let until = now + Duration::minutes(PIN_LOCKOUT_MINUTES);
+            self.failed_attempts = 0;
+            self.locked_until = Some(until);
+            return Err(BankError::PinLocked { until });
+        }
+
+        Err(BankError::InvalidPin {
+            attempts_left: MAX_PIN_ATTEMPTS - self.failed_attempts,
+        })
+    }
+}
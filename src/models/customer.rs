@@ -2,12 +2,62 @@
 //!
 //! Demonstrates: Struct composition, Option<T> handling
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
-use uuid::Uuid;
+use std::collections::BTreeMap;
+use std::fmt;
 
 use crate::errors::{BankError, BankResult};
-use super::account::Account;
+use super::account::{Account, AccountType};
+use super::beneficiary::Beneficiary;
+use super::card::Card;
+use super::credentials::Credentials;
+use super::ids::{AccountId, CustomerId};
+use super::money::Money;
+
+/// Whether a customer can still use the bank
+///
+/// - Active: everything allowed
+/// - Deactivated: cannot log in or move money until reactivated
+/// - Deleted: soft-deleted; hidden from lookups and listings but kept so
+///   account IDs in other customers' transactions still resolve (final)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CustomerStatus {
+    #[default]
+    Active,
+    Deactivated,
+    Deleted,
+}
+
+impl fmt::Display for CustomerStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CustomerStatus::Active => write!(f, "Active"),
+            CustomerStatus::Deactivated => write!(f, "Deactivated"),
+            CustomerStatus::Deleted => write!(f, "Deleted"),
+        }
+    }
+}
+
+/// Changes to a customer's profile for `Bank::update_customer`
+///
+/// `None` leaves a field as it is. For the optional fields, `Some(None)`
+/// removes the value.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CustomerUpdate {
+    pub name: Option<String>,
+    pub email: Option<String>,
+    pub phone: Option<Option<String>>,
+    pub address: Option<Option<String>>,
+    pub date_of_birth: Option<Option<NaiveDate>>,
+}
+
+impl CustomerUpdate {
+    /// Whether the update changes nothing
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
 
 /// Represents a bank customer
 ///
@@ -18,7 +68,7 @@ use super::account::Account;
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Customer {
     /// Unique customer identifier (UUID v4)
-    pub id: String,
+    pub id: CustomerId,
 
     /// Customer's full name
     pub name: String,
@@ -26,6 +76,18 @@ pub struct Customer {
     /// Customer's email address (used for uniqueness check)
     pub email: String,
 
+    /// Contact phone number
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub phone: Option<String>,
+
+    /// Postal address
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub address: Option<String>,
+
+    /// Date of birth, for know-your-customer checks
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub date_of_birth: Option<NaiveDate>,
+
     /// Customer's account (optional)
     /// Demonstrates: Option<T> for optional values
     /// https://doc.rust-lang.org/std/option/enum.Option.html
@@ -33,6 +95,24 @@ pub struct Customer {
 
     /// Customer registration timestamp
     pub registered_at: DateTime<Utc>,
+
+    /// Login credentials; `None` until the customer sets a PIN
+    /// Older data files have no credentials, so default to `None`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) credentials: Option<Credentials>,
+
+    /// Active, deactivated or (soft-)deleted
+    /// Older data files have no status, so default to Active
+    #[serde(default)]
+    pub status: CustomerStatus,
+
+    /// Saved payees, keyed by `Beneficiary::key` of their nickname
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub beneficiaries: BTreeMap<String, Beneficiary>,
+
+    /// Debit cards, including blocked and replaced ones
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub cards: Vec<Card>,
 }
 
 impl Customer {
@@ -46,11 +126,18 @@ impl Customer {
     /// * `email` - Customer's email address
     pub fn new(name: String, email: String) -> Self {
         Self {
-            id: Uuid::new_v4().to_string(),
+            id: CustomerId::generate(),
             name,
             email,
+            phone: None,
+            address: None,
+            date_of_birth: None,
             account: None, // No account initially
             registered_at: Utc::now(),
+            credentials: None,
+            status: CustomerStatus::Active,
+            beneficiaries: BTreeMap::new(),
+            cards: Vec::new(),
         }
     }
 
@@ -60,11 +147,16 @@ impl Customer {
     ///
     /// # Arguments
     /// * `initial_deposit` - Initial deposit amount
+    /// * `account_type` - The kind of account to open
     ///
     /// # Returns
     /// * `Ok(())` - Account created successfully
     /// * `Err(BankError)` - If customer already has an account or invalid amount
-    pub fn create_account(&mut self, initial_deposit: f64) -> BankResult<()> {
+    pub fn create_account(
+        &mut self,
+        initial_deposit: Money,
+        account_type: AccountType,
+    ) -> BankResult<()> {
         // Check if account already exists
         if self.account.is_some() {
             return Err(BankError::CustomerAlreadyExists(
@@ -72,7 +164,7 @@ impl Customer {
             ));
         }
 
-        let account = Account::new(initial_deposit)?;
+        let account = Account::new(initial_deposit, account_type)?;
         self.account = Some(account);
         Ok(())
     }
@@ -88,7 +180,7 @@ impl Customer {
     pub fn get_account(&self) -> BankResult<&Account> {
         self.account
             .as_ref()
-            .ok_or_else(|| BankError::AccountNotFound(self.id.clone()))
+            .ok_or_else(|| BankError::AccountNotFound(self.id.to_string()))
     }
 
     /// Gets a mutable reference to the customer's account
@@ -102,7 +194,7 @@ impl Customer {
     pub fn get_account_mut(&mut self) -> BankResult<&mut Account> {
         self.account
             .as_mut()
-            .ok_or_else(|| BankError::AccountNotFound(self.id.clone()))
+            .ok_or_else(|| BankError::AccountNotFound(self.id.to_string()))
     }
 
     /// Checks if the customer has an account
@@ -112,11 +204,48 @@ impl Customer {
         self.account.is_some()
     }
 
+    /// Checks if the customer has set a PIN
+    pub fn has_pin(&self) -> bool {
+        self.credentials.is_some()
+    }
+
+    /// Finds a saved payee by nickname, ignoring case
+    pub fn beneficiary(&self, nickname: &str) -> Option<&Beneficiary> {
+        self.beneficiaries.get(&Beneficiary::key(nickname))
+    }
+
+    /// Finds one of the customer's cards by ID
+    pub fn card(&self, card_id: &str) -> Option<&Card> {
+        self.cards.iter().find(|card| card.id == card_id)
+    }
+
     /// Gets the account ID if it exists
     ///
     /// Demonstrates: Option<T> mapping
     /// https://doc.rust-lang.org/std/option/enum.Option.html#method.map
-    pub fn get_account_id(&self) -> Option<String> {
+    pub fn get_account_id(&self) -> Option<AccountId> {
         self.account.as_ref().map(|acc| acc.id.clone())
     }
 }
+
+/// The customer's name and email, e.g. `Alice <alice@example.com>`
+impl fmt::Display for Customer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} <{}>", self.name, self.email)
+    }
+}
+
+/// Two customers are equal when they have the same ID
+impl PartialEq for Customer {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl Eq for Customer {}
+
+impl std::hash::Hash for Customer {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
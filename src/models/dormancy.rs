@@ -0,0 +1,77 @@
+//! Dormancy module - how the bank treats accounts nobody uses
+//!
+//! Demonstrates: A policy struct with defaults, zero as "off"
+//!
+//! An open account without any posting for `inactive_days` is flagged
+//! dormant when the bank runs its policies, and optionally frozen. The
+//! next posting on the account clears the flag; a freeze stays until
+//! staff lift it.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::errors::{BankError, BankResult};
+
+/// Days without activity before an account is dormant unless the bank
+/// sets otherwise
+pub const DEFAULT_DORMANT_DAYS: u32 = 365;
+
+/// Longest inactivity period the bank may set
+pub const MAX_DORMANT_DAYS: u32 = 3650;
+
+/// When accounts become dormant and what happens to them
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DormancyPolicy {
+    /// Days without activity before an account is flagged; 0 turns the
+    /// check off
+    pub inactive_days: u32,
+
+    /// Freeze accounts as they are flagged
+    #[serde(default)]
+    pub freeze: bool,
+}
+
+impl Default for DormancyPolicy {
+    fn default() -> Self {
+        Self {
+            inactive_days: DEFAULT_DORMANT_DAYS,
+            freeze: false,
+        }
+    }
+}
+
+impl DormancyPolicy {
+    /// Checks the inactivity period (0 to `MAX_DORMANT_DAYS`)
+    pub fn validate(&self) -> BankResult<()> {
+        if self.inactive_days > MAX_DORMANT_DAYS {
+            return Err(BankError::InvalidInput(format!(
+                "accounts must be flagged dormant within {} days",
+                MAX_DORMANT_DAYS
+            )));
+        }
+        Ok(())
+    }
+
+    /// Whether accounts are checked at all
+    pub fn is_enabled(&self) -> bool {
+        self.inactive_days > 0
+    }
+}
+
+impl fmt::Display for DormancyPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match (self.is_enabled(), self.freeze) {
+            (false, _) => write!(f, "Dormant accounts are not flagged"),
+            (true, false) => write!(
+                f,
+                "Accounts are flagged dormant after {} day(s) without activity",
+                self.inactive_days
+            ),
+            (true, true) => write!(
+                f,
+                "Accounts are flagged dormant and frozen after {} day(s) without activity",
+                self.inactive_days
+            ),
+        }
+    }
+}
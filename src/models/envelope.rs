@@ -0,0 +1,85 @@
+//! Envelope module - savings goals that set part of a balance aside
+//!
+//! Demonstrates: Labelling part of a total without moving it, and keeping
+//! the labels consistent whenever the total changes
+//!
+//! An envelope ("Vacation", "Emergency fund") has a target and the amount
+//! allocated to it so far. Allocated money stays in the account balance;
+//! it is only earmarked. Spending comes out of the unallocated money
+//! first and then out of unlocked envelopes. A locked envelope cannot be
+//! spent from or emptied until it is unlocked.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use super::money::Money;
+
+/// A named savings goal within an account
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Envelope {
+    /// The name as it was typed; unique within the account, ignoring case
+    pub name: String,
+
+    /// The amount the customer is saving towards
+    pub target: Money,
+
+    /// Money set aside so far; may go past the target
+    #[serde(default)]
+    pub allocated: Money,
+
+    /// Whether withdrawals are kept away from this envelope
+    #[serde(default)]
+    pub locked: bool,
+
+    pub created_at: DateTime<Utc>,
+}
+
+impl Envelope {
+    /// Creates an empty envelope
+    pub(crate) fn new_at(name: String, target: Money, locked: bool, now: DateTime<Utc>) -> Self {
+        Self {
+            name,
+            target,
+            allocated: Money::ZERO,
+            locked,
+            created_at: now,
+        }
+    }
+
+    /// The lookup key for a name; lookups ignore case and surrounding spaces
+    pub fn key(name: &str) -> String {
+        name.trim().to_lowercase()
+    }
+
+    /// How far towards the target the envelope is, in whole percent,
+    /// at most 100
+    pub fn progress_percent(&self) -> u32 {
+        if !self.target.is_positive() || self.allocated >= self.target {
+            return 100;
+        }
+        (self.allocated.cents() * 100 / self.target.cents()) as u32
+    }
+
+    /// Returns true once the target is reached
+    pub fn is_reached(&self) -> bool {
+        self.allocated >= self.target
+    }
+}
+
+impl fmt::Display for Envelope {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}: {} of {} ({}%)",
+            self.name,
+            self.allocated.display(),
+            self.target.display(),
+            self.progress_percent()
+        )?;
+        if self.locked {
+            write!(f, " 🔒")?;
+        }
+        Ok(())
+    }
+}
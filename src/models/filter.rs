@@ -0,0 +1,128 @@
+//! Filter module - composable criteria for searching transactions
+//!
+//! Demonstrates: The builder pattern with by-value `self` methods,
+//! Option fields as "no constraint"
+
+use chrono::{DateTime, Utc};
+
+use super::money::Money;
+use super::transaction::{Transaction, TransactionKind};
+
+/// Criteria for `Account::filter_transactions`
+///
+/// Start from `TransactionFilter::new()` (which matches everything) and
+/// chain the constraints you need; a transaction must meet all of them.
+///
+/// ```
+/// use rust_banking_system::models::{TransactionFilter, TransactionKind};
+/// use rust_banking_system::Money;
+///
+/// let large_withdrawals = TransactionFilter::new()
+///     .kind(TransactionKind::Withdrawal)
+///     .min_amount(Money::from_major(500));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TransactionFilter {
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+    min_amount: Option<Money>,
+    max_amount: Option<Money>,
+    kinds: Vec<TransactionKind>,
+    counterparty: Option<String>,
+    tag: Option<String>,
+}
+
+impl TransactionFilter {
+    /// A filter that matches every transaction
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only transactions at or after `from`
+    pub fn from(mut self, from: DateTime<Utc>) -> Self {
+        self.from = Some(from);
+        self
+    }
+
+    /// Only transactions before `to` (exclusive)
+    pub fn to(mut self, to: DateTime<Utc>) -> Self {
+        self.to = Some(to);
+        self
+    }
+
+    /// Only transactions of at least `amount`
+    pub fn min_amount(mut self, amount: Money) -> Self {
+        self.min_amount = Some(amount);
+        self
+    }
+
+    /// Only transactions of at most `amount`
+    pub fn max_amount(mut self, amount: Money) -> Self {
+        self.max_amount = Some(amount);
+        self
+    }
+
+    /// Only transactions of this kind; call again to allow several kinds
+    pub fn kind(mut self, kind: TransactionKind) -> Self {
+        if !self.kinds.contains(&kind) {
+            self.kinds.push(kind);
+        }
+        self
+    }
+
+    /// Only transfers to or from this account
+    ///
+    /// A prefix is enough, so the 8-character IDs shown by the CLI work.
+    pub fn counterparty(mut self, account_id: impl Into<String>) -> Self {
+        self.counterparty = Some(account_id.into());
+        self
+    }
+
+    /// Only transactions carrying this tag (case-insensitive)
+    pub fn tag(mut self, tag: &str) -> Self {
+        self.tag = Some(tag.trim().to_lowercase());
+        self
+    }
+
+    /// Whether a transaction meets every constraint
+    pub fn matches(&self, tx: &Transaction) -> bool {
+        self.from.is_none_or(|from| tx.timestamp >= from)
+            && self.to.is_none_or(|to| tx.timestamp < to)
+            && self.min_amount.is_none_or(|min| tx.amount >= min)
+            && self.max_amount.is_none_or(|max| tx.amount <= max)
+            && (self.kinds.is_empty() || self.kinds.contains(&tx.transaction_type.kind()))
+            && self.counterparty.as_deref().is_none_or(|wanted| {
+                tx.transaction_type
+                    .counterparty()
+                    .is_some_and(|account_id| account_id.starts_with(wanted))
+            })
+            && self.tag.as_ref().is_none_or(|wanted| tx.tags.contains(wanted))
+    }
+}
+
+/// The orders a list of transactions can be shown in
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TransactionOrder {
+    /// Oldest first, the order they were posted in
+    #[default]
+    OldestFirst,
+    NewestFirst,
+    LargestFirst,
+    SmallestFirst,
+}
+
+impl TransactionOrder {
+    /// Sorts `transactions` in this order; equal amounts stay oldest first
+    pub fn sort(self, transactions: &mut [&Transaction]) {
+        match self {
+            TransactionOrder::OldestFirst => transactions.sort(),
+            TransactionOrder::NewestFirst => transactions.sort_by(|a, b| b.cmp(a)),
+            TransactionOrder::LargestFirst => {
+                transactions.sort_by(|a, b| b.amount.cmp(&a.amount).then_with(|| a.cmp(b)))
+            }
+            TransactionOrder::SmallestFirst => {
+                transactions.sort_by(|a, b| a.amount.cmp(&b.amount).then_with(|| a.cmp(b)))
+            }
+        }
+    }
+}
@@ -0,0 +1,167 @@
+//! Fraud module - rules that flag suspicious transactions for review
+//!
+//! Demonstrates: Option<T> fields as "off", an internally tagged enum for
+//! reasons, a record with a small state machine (pending, then approved
+//! or reversed)
+//!
+//! Flagging never blocks a transaction: the money moves, and the flag
+//! waits in the bank's review queue until staff approve the transaction
+//! or reverse it.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::errors::{BankError, BankResult};
+use crate::format;
+use super::ids::{CustomerId, TransactionId};
+use super::money::Money;
+use super::transaction::Transaction;
+
+/// Window the rapid-withdrawals rule looks back over unless set otherwise
+pub const DEFAULT_WINDOW_MINUTES: u32 = 60;
+
+/// Longest window the rapid-withdrawals rule may look back over (a day)
+pub const MAX_WINDOW_MINUTES: u32 = 24 * 60;
+
+/// "More than `count` withdrawals within `minutes`"
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VelocityRule {
+    pub count: u32,
+    pub minutes: u32,
+}
+
+/// Bank-wide fraud rules; `None` means the rule is off
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FraudRules {
+    /// Flag a withdrawal that makes more than `count` from one account
+    /// within `minutes`
+    #[serde(default)]
+    pub rapid_withdrawals: Option<VelocityRule>,
+
+    /// Flag a transfer above this amount to an account the sender has
+    /// never sent money to before
+    #[serde(default)]
+    pub new_payee_transfer: Option<Money>,
+}
+
+impl FraudRules {
+    /// Checks that counts, windows and amounts are positive and windows
+    /// at most `MAX_WINDOW_MINUTES`
+    pub fn validate(&self) -> BankResult<()> {
+        if let Some(rule) = self.rapid_withdrawals {
+            if rule.count == 0 || rule.minutes == 0 || rule.minutes > MAX_WINDOW_MINUTES {
+                return Err(BankError::InvalidInput(format!(
+                    "rapid withdrawals need a count above 0 and a window of 1 to {} minutes",
+                    MAX_WINDOW_MINUTES
+                )));
+            }
+        }
+        if let Some(amount) = self.new_payee_transfer {
+            if !amount.is_positive() {
+                return Err(BankError::InvalidAmount(amount));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for FraudRules {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.rapid_withdrawals {
+            Some(rule) => writeln!(f, "Flag more than {} withdrawal(s) within {} minutes", rule.count, rule.minutes)?,
+            None => writeln!(f, "Rapid withdrawals: off")?,
+        }
+        match self.new_payee_transfer {
+            Some(amount) => write!(f, "Flag transfers above {} to a new payee", amount.display()),
+            None => write!(f, "Transfers to new payees: off"),
+        }
+    }
+}
+
+/// Which rule flagged a transaction
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "rule", rename_all = "snake_case")]
+pub enum FraudReason {
+    /// `count` withdrawals, this one included, within `minutes`
+    RapidWithdrawals { count: u32, minutes: u32 },
+    /// A transfer of `amount` to `payee`, who was never paid before
+    NewPayeeTransfer { payee: String, amount: Money, threshold: Money },
+}
+
+impl fmt::Display for FraudReason {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FraudReason::RapidWithdrawals { count, minutes } => {
+                write!(f, "{} withdrawals within {} minutes", count, minutes)
+            }
+            FraudReason::NewPayeeTransfer { payee, amount, threshold } => write!(
+                f,
+                "{} to new payee {}, above {}",
+                amount.display(), payee, threshold.display()
+            ),
+        }
+    }
+}
+
+/// Where a flagged transaction stands
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReviewStatus {
+    Pending,
+    /// Looked at and let stand
+    Approved,
+    /// Undone with compensating transactions
+    Reversed,
+}
+
+impl fmt::Display for ReviewStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ReviewStatus::Pending => write!(f, "Pending review"),
+            ReviewStatus::Approved => write!(f, "Approved"),
+            ReviewStatus::Reversed => write!(f, "Reversed"),
+        }
+    }
+}
+
+/// A transaction in the review queue
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FlaggedTransaction {
+    pub customer_id: CustomerId,
+    pub transaction_id: TransactionId,
+    pub reason: FraudReason,
+    pub flagged_at: DateTime<Utc>,
+    pub status: ReviewStatus,
+
+    /// The staff member who approved or reversed it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reviewed_by: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reviewed_at: Option<DateTime<Utc>>,
+}
+
+impl FlaggedTransaction {
+    /// `transaction`, waiting for review for `reason`
+    pub(crate) fn pending(customer_id: CustomerId, transaction: &Transaction, reason: FraudReason) -> Self {
+        Self {
+            customer_id,
+            transaction_id: transaction.id.clone(),
+            reason,
+            flagged_at: transaction.timestamp,
+            status: ReviewStatus::Pending,
+            reviewed_by: None,
+            reviewed_at: None,
+        }
+    }
+}
+
+impl fmt::Display for FlaggedTransaction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} - {} ({})", format::datetime(&self.flagged_at), self.reason, self.status)?;
+        if let Some(reviewer) = &self.reviewed_by {
+            write!(f, " by {}", reviewer)?;
+        }
+        Ok(())
+    }
+}
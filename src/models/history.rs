@@ -0,0 +1,180 @@
+//! History module - an account's transactions, parsed on first use
+//!
+//! Demonstrates: Deref/DerefMut to a wrapped Vec, OnceLock for lazy
+//! initialization, serde_json's RawValue, hand-written Serialize and
+//! Deserialize
+//!
+//! The transactions are most of a large bank. A bank loaded lazily (see
+//! `persistence::load_bank_lazy`) keeps each account's history as the JSON
+//! text it was saved as, and only keeps it parsed from the first time the
+//! history is used. Each history is still checked as the file is read, so
+//! a damaged one fails the load rather than its first use. Everything else
+//! sees a `Vec<Transaction>` through `Deref`, so code reading or changing
+//! `account.transactions` works the same either way.
+
+use std::cell::Cell;
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+use std::sync::OnceLock;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::value::RawValue;
+
+use crate::errors::{BankError, BankResult, Cause};
+use super::transaction::Transaction;
+
+thread_local! {
+    /// Whether histories being (de)serialized on this thread stay as raw
+    /// JSON; only ever set around a serde_json call (see `with_raw_history`)
+    static RAW_HISTORY: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Runs `f` with histories kept as raw JSON text: deserializing leaves
+/// them unparsed, and serializing writes unparsed ones back verbatim
+///
+/// Only serde_json understands raw values, so `f` must not use another
+/// format.
+pub(crate) fn with_raw_history<T>(f: impl FnOnce() -> T) -> T {
+    /// Restores the previous setting even if `f` panics
+    struct Reset(bool);
+    impl Drop for Reset {
+        fn drop(&mut self) {
+            RAW_HISTORY.with(|raw| raw.set(self.0));
+        }
+    }
+
+    let _reset = Reset(RAW_HISTORY.with(|raw| raw.replace(true)));
+    f()
+}
+
+/// The transactions of one account, oldest first
+///
+/// Behaves like the `Vec<Transaction>` it dereferences to. A history
+/// loaded lazily is parsed on first access.
+#[derive(Default)]
+pub struct History {
+    /// The parsed transactions, once they are needed
+    loaded: OnceLock<Vec<Transaction>>,
+
+    /// The saved JSON, until it is parsed
+    raw: Option<Box<RawValue>>,
+}
+
+impl History {
+    /// An empty history
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether the transactions have been parsed yet
+    pub fn is_loaded(&self) -> bool {
+        self.raw.is_none() || self.loaded.get().is_some()
+    }
+
+    /// The transactions, parsing them first if they were loaded lazily
+    ///
+    /// # Returns
+    /// * `Err(BankError::SerializationError)` - If the saved history is
+    ///   not a list of transactions, which deserializing already rules out
+    pub fn load(&self) -> BankResult<&Vec<Transaction>> {
+        if let Some(transactions) = self.loaded.get() {
+            return Ok(transactions);
+        }
+        let parsed = match &self.raw {
+            Some(raw) => serde_json::from_str(raw.get())
+                .map_err(|e| BankError::SerializationError(Cause::new(e).context("reading a transaction history")))?,
+            None => Vec::new(),
+        };
+        Ok(self.loaded.get_or_init(|| parsed))
+    }
+
+    /// The transactions for `Deref`, which cannot return an error
+    ///
+    /// A raw history was checked to be a list of transactions when it was
+    /// deserialized, so parsing it again cannot fail.
+    fn loaded(&self) -> &Vec<Transaction> {
+        match self.load() {
+            Ok(transactions) => transactions,
+            Err(_) => self.loaded.get_or_init(Vec::new),
+        }
+    }
+}
+
+impl Deref for History {
+    type Target = Vec<Transaction>;
+
+    fn deref(&self) -> &Vec<Transaction> {
+        self.loaded()
+    }
+}
+
+impl DerefMut for History {
+    fn deref_mut(&mut self) -> &mut Vec<Transaction> {
+        self.loaded();
+        // Once parsed, the text is no longer needed
+        self.raw = None;
+        self.loaded.get_mut().expect("history was just loaded")
+    }
+}
+
+impl From<Vec<Transaction>> for History {
+    fn from(transactions: Vec<Transaction>) -> Self {
+        Self { loaded: OnceLock::from(transactions), raw: None }
+    }
+}
+
+impl<'a> IntoIterator for &'a History {
+    type Item = &'a Transaction;
+    type IntoIter = std::slice::Iter<'a, Transaction>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a mut History {
+    type Item = &'a mut Transaction;
+    type IntoIter = std::slice::IterMut<'a, Transaction>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl Clone for History {
+    fn clone(&self) -> Self {
+        Self { loaded: self.loaded.clone(), raw: self.raw.clone() }
+    }
+}
+
+impl fmt::Debug for History {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match (self.loaded.get(), &self.raw) {
+            (Some(transactions), _) => transactions.fmt(f),
+            (None, Some(raw)) => write!(f, "History(<{} bytes not loaded>)", raw.get().len()),
+            (None, None) => f.write_str("[]"),
+        }
+    }
+}
+
+impl Serialize for History {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match (self.loaded.get(), &self.raw) {
+            (None, Some(raw)) if RAW_HISTORY.with(Cell::get) => raw.serialize(serializer),
+            _ => self.load().map_err(serde::ser::Error::custom)?.serialize(serializer),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for History {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if RAW_HISTORY.with(Cell::get) {
+            let raw = Box::<RawValue>::deserialize(deserializer)?;
+            // Parsed once to check it, so `Deref` never meets a bad history
+            serde_json::from_str::<Vec<Transaction>>(raw.get()).map_err(serde::de::Error::custom)?;
+            Ok(Self { loaded: OnceLock::new(), raw: Some(raw) })
+        } else {
+            Vec::deserialize(deserializer).map(History::from)
+        }
+    }
+}
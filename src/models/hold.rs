@@ -0,0 +1,63 @@
+//! Hold module - money set aside on an account without posting anything
+//!
+//! Demonstrates: An optional deadline as Option<DateTime>, checked against
+//! a time passed in rather than read from the system clock
+//!
+//! A hold lowers the available balance until it is released, captured
+//! (turned into a withdrawal) or reaches its expiry time. Expired holds
+//! stop counting straight away; `Bank::release_expired_holds` later
+//! removes them from the account.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use uuid::Uuid;
+
+use crate::format;
+use super::money::Money;
+
+/// Money held on an account, e.g. for a hotel deposit or a legal order
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Hold {
+    /// Unique identifier (UUID v4)
+    pub id: String,
+
+    pub amount: Money,
+
+    /// Why the money is held; becomes the description if it is captured
+    pub reason: String,
+
+    pub placed_at: DateTime<Utc>,
+
+    /// When the hold lapses by itself; `None` holds until released
+    #[serde(default)]
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl Hold {
+    /// Creates a hold placed at `now`
+    pub(crate) fn new_at(amount: Money, reason: String, expires_at: Option<DateTime<Utc>>, now: DateTime<Utc>) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            amount,
+            reason,
+            placed_at: now,
+            expires_at,
+        }
+    }
+
+    /// Returns true once the expiry time has been reached
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at <= now)
+    }
+}
+
+impl fmt::Display for Hold {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} held for {}", self.amount.display(), self.reason)?;
+        match self.expires_at {
+            Some(expires_at) => write!(f, " (until {})", format::datetime(&expires_at)),
+            None => write!(f, " (until released)"),
+        }
+    }
+}
@@ -0,0 +1,130 @@
+//! ID module - customer, account and transaction IDs that cannot be mixed up
+//!
+//! Demonstrates: Newtypes generated by a declarative macro,
+//! `#[serde(transparent)]`, `Borrow<str>` for map lookups by `&str`
+//!
+//! All three IDs are UUIDs, and they used to be plain `String`s, so a
+//! customer ID could be passed where an account ID was wanted and the
+//! compiler would not notice. Each is now its own type. They serialize as
+//! the bare string, so data files and journals are unchanged, and
+//! `Borrow<str>` lets a map keyed by IDs be searched with a `&str`.
+//! Input typed at the CLI becomes an ID with `parse()` or `From<String>`.
+
+use serde::{Deserialize, Serialize};
+use std::borrow::Borrow;
+use std::fmt;
+use std::str::FromStr;
+use uuid::Uuid;
+
+macro_rules! id_type {
+    ($(#[$meta:meta])* $name:ident, $what:literal) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+        #[serde(transparent)]
+        pub struct $name(String);
+
+        impl $name {
+            /// A new random ID (UUID v4)
+            pub fn generate() -> Self {
+                Self(Uuid::new_v4().to_string())
+            }
+
+            pub fn as_str(&self) -> &str {
+                &self.0
+            }
+
+            /// The first 8 characters, enough to tell IDs apart on screen
+            pub fn short(&self) -> &str {
+                self.0.get(..8).unwrap_or(&self.0)
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str(&self.0)
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = String;
+
+            /// Takes the ID as typed, without surrounding whitespace
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                match s.trim() {
+                    "" => Err(format!("{} cannot be empty", $what)),
+                    id => Ok(Self(id.to_string())),
+                }
+            }
+        }
+
+        impl From<String> for $name {
+            fn from(id: String) -> Self {
+                Self(id)
+            }
+        }
+
+        impl From<&str> for $name {
+            fn from(id: &str) -> Self {
+                Self(id.to_string())
+            }
+        }
+
+        impl From<$name> for String {
+            fn from(id: $name) -> Self {
+                id.0
+            }
+        }
+
+        impl AsRef<str> for $name {
+            fn as_ref(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl Borrow<str> for $name {
+            fn borrow(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl PartialEq<str> for $name {
+            fn eq(&self, other: &str) -> bool {
+                self.0 == other
+            }
+        }
+
+        impl PartialEq<&str> for $name {
+            fn eq(&self, other: &&str) -> bool {
+                self.0 == *other
+            }
+        }
+    };
+}
+
+id_type!(
+    /// Identifies a customer
+    ///
+    /// ```
+    /// use rust_banking_system::models::CustomerId;
+    ///
+    /// let id: CustomerId = " 3f2b9c1e ".parse().unwrap();
+    /// assert_eq!(id, "3f2b9c1e");
+    /// assert_eq!(id.to_string(), "3f2b9c1e");
+    /// assert!("  ".parse::<CustomerId>().is_err());
+    /// ```
+    CustomerId,
+    "a customer ID"
+);
+
+id_type!(
+    /// Identifies an account; see `AccountNumber` for the short form
+    /// people type
+    AccountId,
+    "an account ID"
+);
+
+id_type!(
+    /// Identifies a transaction
+    TransactionId,
+    "a transaction ID"
+);
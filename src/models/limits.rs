@@ -0,0 +1,123 @@
+//! Limits module - configurable caps on money leaving an account
+//!
+//! Demonstrates: Option<T> fields as "unlimited", filtering by calendar day
+//!
+//! Outgoing transfers and bill payments count as withdrawals for the
+//! amount limits, and each limit is checked against the current UTC day.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::errors::{BankError, BankResult};
+use super::money::Money;
+use super::transaction::{Transaction, TransactionType};
+
+/// Per-account limits; `None` means unlimited
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccountLimits {
+    /// Largest amount a single withdrawal or transfer may take
+    #[serde(default)]
+    pub max_withdrawal: Option<Money>,
+
+    /// Most money that may be withdrawn or transferred out per day
+    #[serde(default)]
+    pub daily_withdrawal: Option<Money>,
+
+    /// Most outgoing transfers per day
+    #[serde(default)]
+    pub daily_transfers: Option<u32>,
+}
+
+/// The limit an operation ran into, with its configured value
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Limit {
+    SingleWithdrawal(Money),
+    DailyWithdrawal(Money),
+    DailyTransfers(u32),
+    CardPurchase(Money),
+    CardDaily(Money),
+}
+
+impl fmt::Display for Limit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Limit::SingleWithdrawal(max) => write!(f, "single withdrawal limit of {}", max.display()),
+            Limit::DailyWithdrawal(max) => write!(f, "daily withdrawal limit of {}", max.display()),
+            Limit::DailyTransfers(max) => write!(f, "daily limit of {} transfer(s)", max),
+            Limit::CardPurchase(max) => write!(f, "card purchase limit of {}", max.display()),
+            Limit::CardDaily(max) => write!(f, "card daily spending limit of {}", max.display()),
+        }
+    }
+}
+
+impl AccountLimits {
+    /// Checks that the configured amounts are positive
+    pub fn validate(&self) -> BankResult<()> {
+        for amount in [self.max_withdrawal, self.daily_withdrawal].into_iter().flatten() {
+            if !amount.is_positive() {
+                return Err(BankError::InvalidAmount(amount));
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks a withdrawal of `amount` against the account's history
+    pub fn check_withdrawal(
+        &self,
+        amount: Money,
+        history: &[Transaction],
+        now: DateTime<Utc>,
+    ) -> BankResult<()> {
+        if let Some(max) = self.max_withdrawal {
+            if amount > max {
+                return Err(BankError::LimitExceeded(Limit::SingleWithdrawal(max)));
+            }
+        }
+
+        if let Some(max) = self.daily_withdrawal {
+            let today: Money = outgoing_today(history, now).map(|tx| tx.amount).sum();
+            if today + amount > max {
+                return Err(BankError::LimitExceeded(Limit::DailyWithdrawal(max)));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Checks that one more outgoing transfer is allowed today
+    pub fn check_transfer(&self, history: &[Transaction], now: DateTime<Utc>) -> BankResult<()> {
+        if let Some(max) = self.daily_transfers {
+            let today = outgoing_today(history, now)
+                .filter(|tx| tx.transaction_type.is_transfer_out())
+                .count();
+            if today >= max as usize {
+                return Err(BankError::LimitExceeded(Limit::DailyTransfers(max)));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for AccountLimits {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fn show<T: fmt::Display>(value: Option<T>, prefix: &str) -> String {
+            value.map_or("unlimited".to_string(), |v| format!("{}{}", prefix, v))
+        }
+
+        writeln!(f, "Max single withdrawal: {}", show(self.max_withdrawal, "$"))?;
+        writeln!(f, "Max withdrawn per day: {}", show(self.daily_withdrawal, "$"))?;
+        write!(f, "Max transfers per day: {}", show(self.daily_transfers, ""))
+    }
+}
+
+/// Withdrawals, outgoing transfers and bill payments made on the same UTC
+/// day as `now`
+fn outgoing_today(history: &[Transaction], now: DateTime<Utc>) -> impl Iterator<Item = &Transaction> {
+    let day = now.date_naive();
+    history.iter().filter(move |tx| {
+        tx.timestamp.date_naive() == day
+            && (matches!(tx.transaction_type, TransactionType::Withdrawal | TransactionType::BillPayment { .. })
+                || tx.transaction_type.is_transfer_out())
+    })
+}
@@ -4,12 +4,69 @@
 //! https://doc.rust-lang.org/book/ch07-00-managing-growing-projects-with-packages-crates-and-modules.html
 
 // Declare submodules
+pub mod money;
+pub mod ids;
 pub mod transaction;
+pub mod history;
+pub mod archive;
+pub mod account_type;
+pub mod account_status;
+pub mod account_number;
 pub mod account;
+pub mod credentials;
+pub mod limits;
+pub mod alerts;
+pub mod beneficiary;
+pub mod hold;
+pub mod cheque;
+pub mod dormancy;
+pub mod fraud;
+pub mod card;
+pub mod bill;
+pub mod envelope;
+pub mod budget;
+pub mod term_deposit;
 pub mod customer;
+pub mod scheduled;
+pub mod statement;
+pub mod user;
+pub mod page;
+pub mod filter;
+pub mod tags;
+pub mod receipt;
+pub mod bookkeeping;
+pub mod builder;
 
 // Re-export commonly used types for convenience
 // This allows users to write `use models::Transaction` instead of `use models::transaction::Transaction`
-pub use transaction::{Transaction, TransactionType};
-pub use account::Account;
-pub use customer::Customer;
+pub use money::{Money, ParseMoneyError};
+pub use ids::{AccountId, CustomerId, TransactionId};
+pub use transaction::{Transaction, TransactionDetails, TransactionKind, TransactionStatus, TransactionType};
+pub use history::History;
+pub use archive::ArchivedHistory;
+pub use account::{Account, AccountType};
+pub use account_number::AccountNumber;
+pub use account_status::AccountStatus;
+pub use credentials::Credentials;
+pub use limits::{AccountLimits, Limit};
+pub use alerts::{Alert, AlertKind, AlertRules};
+pub use beneficiary::Beneficiary;
+pub use hold::Hold;
+pub use cheque::{Cheque, ChequePolicy, ChequeStatus};
+pub use dormancy::DormancyPolicy;
+pub use fraud::{FlaggedTransaction, FraudReason, FraudRules, ReviewStatus, VelocityRule};
+pub use card::{Card, CardLimits, CardStatus};
+pub use bill::{Autopay, Merchant, MerchantCategory};
+pub use envelope::Envelope;
+pub use budget::{Budget, BudgetStatus};
+pub use term_deposit::{TermDeposit, TermDepositStatus};
+pub use customer::{Customer, CustomerStatus, CustomerUpdate};
+pub use scheduled::{Frequency, ScheduledTransaction};
+pub use statement::{MonthlySummary, Statement};
+pub use user::{Role, StaffMember};
+pub use page::Page;
+pub use filter::{TransactionFilter, TransactionOrder};
+pub use tags::{CategoryTotal, TransactionTags};
+pub use receipt::{Party, Receipt};
+pub use builder::{AccountBuilder, CustomerBuilder};
+pub use bookkeeping::{AccountClass, BookAccount, BookEntry, BookLine, ChartAccount, ChartOfAccounts, MappingRule};
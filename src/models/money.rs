@@ -0,0 +1,268 @@
+//! Money module - fixed-point monetary amounts
+//!
+//! Demonstrates: Newtype pattern, operator overloading, custom Serde impls
+//! https://doc.rust-lang.org/book/ch19-03-advanced-traits.html
+
+use serde::de::{self, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Neg, Sub, SubAssign};
+use std::str::FromStr;
+
+use crate::format::MoneyDisplay;
+
+/// Number of minor units (cents) in one major unit (dollar)
+const MINOR_PER_MAJOR: i64 = 100;
+
+/// A monetary amount stored as an integer number of minor units (cents)
+///
+/// Demonstrates:
+/// - Newtype pattern wrapping a primitive
+/// - Exact arithmetic: 0.10 + 0.10 + 0.10 is exactly 0.30
+///
+/// Serialized as a decimal string (e.g. `"12.34"`). Plain JSON numbers
+/// written by older versions (which stored `f64` dollars) are still accepted.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Money(i64);
+
+impl Money {
+    /// Zero amount
+    pub const ZERO: Money = Money(0);
+
+    /// Largest amount accepted from text, just under a trillion
+    ///
+    /// Far below the limit of the `i64` underneath, so balances built
+    /// from such amounts stay clear of overflow; arithmetic on balances
+    /// still goes through `checked_add` and `checked_sub`. Only amounts
+    /// coming in are held to it: a saved balance past it still loads.
+    pub const MAX: Money = Money(99_999_999_999_999);
+
+    /// Creates an amount from minor units (cents)
+    pub const fn from_cents(cents: i64) -> Self {
+        Money(cents)
+    }
+
+    /// Creates an amount from whole major units (dollars)
+    pub const fn from_major(major: i64) -> Self {
+        Money(major * MINOR_PER_MAJOR)
+    }
+
+    /// Converts a floating point dollar amount, rounding to the nearest cent
+    ///
+    /// Only used for reading legacy data; new code should never create
+    /// money from floats.
+    pub fn from_f64(value: f64) -> Self {
+        Money((value * MINOR_PER_MAJOR as f64).round() as i64)
+    }
+
+    /// Returns the amount in minor units (cents)
+    pub const fn cents(self) -> i64 {
+        self.0
+    }
+
+    /// Returns true if the amount is greater than zero
+    pub const fn is_positive(self) -> bool {
+        self.0 > 0
+    }
+
+    /// Returns true if the amount is less than zero
+    pub const fn is_negative(self) -> bool {
+        self.0 < 0
+    }
+
+    /// Returns true if the amount is exactly zero
+    pub const fn is_zero(self) -> bool {
+        self.0 == 0
+    }
+
+    /// `self + rhs`, or `None` if the result would overflow
+    pub const fn checked_add(self, rhs: Money) -> Option<Money> {
+        match self.0.checked_add(rhs.0) {
+            Some(cents) => Some(Money(cents)),
+            None => None,
+        }
+    }
+
+    /// `self - rhs`, or `None` if the result would overflow
+    pub const fn checked_sub(self, rhs: Money) -> Option<Money> {
+        match self.0.checked_sub(rhs.0) {
+            Some(cents) => Some(Money(cents)),
+            None => None,
+        }
+    }
+
+    /// The amount as people should see it, with the currency symbol and
+    /// separators of the installed `format::Format`, e.g. `$1,234.50`
+    pub const fn display(self) -> MoneyDisplay {
+        MoneyDisplay(self)
+    }
+}
+
+// Display prints the amount with exactly two decimal places, e.g. "-12.05",
+// for files and exports; `display()` is the formatted version for people
+impl fmt::Display for Money {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let sign = if self.0 < 0 { "-" } else { "" };
+        let abs = self.0.unsigned_abs();
+        let text = format!(
+            "{}{}.{:02}",
+            sign,
+            abs / MINOR_PER_MAJOR as u64,
+            abs % MINOR_PER_MAJOR as u64
+        );
+        // Use pad so width/alignment specifiers like {:>10} still work
+        f.pad(&text)
+    }
+}
+
+/// Error returned when parsing a string into `Money` fails
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseMoneyError(String);
+
+impl fmt::Display for ParseMoneyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid money amount '{}'", self.0)
+    }
+}
+
+impl std::error::Error for ParseMoneyError {}
+
+/// Parses decimal strings such as "12", "12.5", "-0.05" without going
+/// through floating point; amounts beyond `Money::MAX` either way are
+/// refused, since this is how typed and imported amounts are read
+impl FromStr for Money {
+    type Err = ParseMoneyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_decimal(s)
+            .filter(|money| money.0.abs() <= Money::MAX.0)
+            .ok_or_else(|| ParseMoneyError(s.to_string()))
+    }
+}
+
+/// A decimal string as an amount, anywhere in the range of the `i64`
+/// underneath
+///
+/// Saved data goes through this rather than `FromStr`: balances, the vault
+/// and book totals may add up past `Money::MAX`, and a file holding them
+/// must still load.
+fn parse_decimal(s: &str) -> Option<Money> {
+    let trimmed = s.trim();
+    let (negative, digits) = match trimmed.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, trimmed.strip_prefix('+').unwrap_or(trimmed)),
+    };
+
+    let (whole, frac) = match digits.split_once('.') {
+        Some((whole, frac)) => (whole, frac),
+        None => (digits, ""),
+    };
+
+    let all_digits = |part: &str| part.chars().all(|c| c.is_ascii_digit());
+    if (whole.is_empty() && frac.is_empty()) || frac.len() > 2 || !all_digits(whole) || !all_digits(frac) {
+        return None;
+    }
+
+    let whole: i64 = if whole.is_empty() { 0 } else { whole.parse().ok()? };
+    let frac: i64 = format!("{:0<2}", frac).parse().ok()?;
+    let cents = whole.checked_mul(MINOR_PER_MAJOR)?.checked_add(frac)?;
+    Some(Money(if negative { -cents } else { cents }))
+}
+
+// Operator overloading for natural arithmetic on amounts; like the
+// integer operators they panic on overflow in debug builds, so balances
+// use `checked_add` and `checked_sub` instead
+// https://doc.rust-lang.org/std/ops/index.html
+impl Add for Money {
+    type Output = Money;
+
+    fn add(self, rhs: Money) -> Money {
+        Money(self.0 + rhs.0)
+    }
+}
+
+impl Sub for Money {
+    type Output = Money;
+
+    fn sub(self, rhs: Money) -> Money {
+        Money(self.0 - rhs.0)
+    }
+}
+
+impl AddAssign for Money {
+    fn add_assign(&mut self, rhs: Money) {
+        self.0 += rhs.0;
+    }
+}
+
+impl SubAssign for Money {
+    fn sub_assign(&mut self, rhs: Money) {
+        self.0 -= rhs.0;
+    }
+}
+
+impl Neg for Money {
+    type Output = Money;
+
+    fn neg(self) -> Money {
+        Money(-self.0)
+    }
+}
+
+/// Allows `.sum()` over iterators of Money
+impl Sum for Money {
+    fn sum<I: Iterator<Item = Money>>(iter: I) -> Money {
+        iter.fold(Money::ZERO, |acc, m| acc + m)
+    }
+}
+
+impl<'a> Sum<&'a Money> for Money {
+    fn sum<I: Iterator<Item = &'a Money>>(iter: I) -> Money {
+        iter.copied().sum()
+    }
+}
+
+impl Serialize for Money {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+/// Visitor accepting both the current string format and legacy numbers
+struct MoneyVisitor;
+
+impl<'de> Visitor<'de> for MoneyVisitor {
+    type Value = Money;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a decimal string like \"12.34\" or a number")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Money, E> {
+        parse_decimal(v).ok_or_else(|| E::custom(ParseMoneyError(v.to_string())))
+    }
+
+    // Legacy files stored balances as f64 dollars
+    fn visit_f64<E: de::Error>(self, v: f64) -> Result<Money, E> {
+        Ok(Money::from_f64(v))
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<Money, E> {
+        v.checked_mul(MINOR_PER_MAJOR)
+            .map(Money)
+            .ok_or_else(|| E::custom("amount out of range"))
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<Money, E> {
+        i64::try_from(v)
+            .map_err(|_| E::custom("amount out of range"))
+            .and_then(|v| self.visit_i64(v))
+    }
+}
+
+impl<'de> Deserialize<'de> for Money {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Money, D::Error> {
+        deserializer.deserialize_any(MoneyVisitor)
+    }
+}
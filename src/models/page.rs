@@ -0,0 +1,59 @@
+//! Page module - one slice of a longer listing
+//!
+//! Demonstrates: Generic structs, iterator adapters (skip/take)
+
+use serde::Serialize;
+
+/// Up to `limit` items starting at `offset`, out of `total`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Page<T> {
+    /// The items on this page
+    pub items: Vec<T>,
+
+    /// Position of the first item within the whole listing
+    pub offset: usize,
+
+    /// Page size that was asked for
+    pub limit: usize,
+
+    /// Number of items in the whole listing
+    pub total: usize,
+}
+
+impl<T> Page<T> {
+    /// Cuts a page out of a listing
+    ///
+    /// An offset past the end gives an empty page (with the right total).
+    pub fn from_items(items: impl IntoIterator<Item = T>, offset: usize, limit: usize) -> Self {
+        let all: Vec<T> = items.into_iter().collect();
+        let total = all.len();
+        let items = all.into_iter().skip(offset).take(limit).collect();
+        Self { items, offset, limit, total }
+    }
+
+    /// Whether items exist before this page
+    pub fn has_prev(&self) -> bool {
+        self.offset > 0
+    }
+
+    /// Whether items exist after this page
+    pub fn has_next(&self) -> bool {
+        self.offset + self.items.len() < self.total
+    }
+
+    /// 1-based number of this page
+    pub fn number(&self) -> usize {
+        if self.limit == 0 {
+            return 1;
+        }
+        self.offset / self.limit + 1
+    }
+
+    /// Number of pages in the whole listing (at least 1)
+    pub fn count(&self) -> usize {
+        if self.limit == 0 {
+            return 1;
+        }
+        self.total.div_ceil(self.limit).max(1)
+    }
+}
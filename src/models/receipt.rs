@@ -0,0 +1,97 @@
+//! Receipt module - proof of a single transaction
+//!
+//! Demonstrates: A read-only view assembled from several models, Display
+//! for a printable block
+//!
+//! A receipt names both sides of a transaction by customer (or merchant)
+//! name and account number, so it still makes sense away from the bank.
+//! `Bank::get_receipt` builds one for any transaction, for reprints.
+
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::format;
+use super::ids::TransactionId;
+use super::money::Money;
+use super::transaction::{TransactionStatus, TransactionType};
+
+/// One side of a transaction
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Party {
+    /// Customer or merchant name; `None` for accounts outside the bank
+    pub name: Option<String>,
+
+    /// Account number (the account ID for accounts without one), external
+    /// reference, or the customer's reference at a merchant
+    pub account: String,
+}
+
+impl fmt::Display for Party {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.name {
+            Some(name) if self.account.is_empty() => write!(f, "{}", name),
+            Some(name) => write!(f, "{} ({})", name, self.account),
+            None => write!(f, "{}", self.account),
+        }
+    }
+}
+
+/// A printable record of one transaction
+#[derive(Debug, Clone, Serialize)]
+pub struct Receipt {
+    /// Name of the bank issuing the receipt
+    pub bank_name: String,
+
+    pub transaction_id: TransactionId,
+
+    pub timestamp: DateTime<Utc>,
+
+    pub transaction_type: TransactionType,
+
+    /// Where the money came from; `None` for deposits
+    pub from: Option<Party>,
+
+    /// Where the money went; `None` for withdrawals
+    pub to: Option<Party>,
+
+    pub amount: Money,
+
+    /// Fees charged with the transaction; no transaction type carries one
+    /// yet, so this is zero
+    pub fees: Money,
+
+    /// Balance of the account the transaction is on, right after it
+    pub balance_after: Money,
+
+    pub description: Option<String>,
+
+    pub status: TransactionStatus,
+}
+
+impl fmt::Display for Receipt {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "═══════════════════════════════════════════")?;
+        writeln!(f, "{:^43}", format!("RECEIPT - {}", self.bank_name).trim_end())?;
+        writeln!(f, "═══════════════════════════════════════════")?;
+        writeln!(f, "Transaction: {}", self.transaction_id)?;
+        writeln!(f, "Date:        {}", format::datetime(&self.timestamp))?;
+        writeln!(f, "Type:        {}", self.transaction_type.code().replace('_', " "))?;
+        if let Some(from) = &self.from {
+            writeln!(f, "From:        {}", from)?;
+        }
+        if let Some(to) = &self.to {
+            writeln!(f, "To:          {}", to)?;
+        }
+        if let Some(description) = &self.description {
+            writeln!(f, "Description: {}", description)?;
+        }
+        writeln!(f, "Status:      {}", self.status)?;
+        writeln!(f, "───────────────────────────────────────────")?;
+        writeln!(f, "Amount:      {}", self.amount.display())?;
+        writeln!(f, "Fees:        {}", self.fees.display())?;
+        writeln!(f, "Balance:     {}", self.balance_after.display())?;
+        write!(f, "═══════════════════════════════════════════")
+    }
+}
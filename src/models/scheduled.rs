@@ -0,0 +1,126 @@
+//! Scheduled transaction module - standing orders that repeat over time
+//!
+//! Demonstrates: Enums driving behavior, date arithmetic with chrono
+
+use chrono::{DateTime, Duration, Months, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use uuid::Uuid;
+
+use crate::format;
+use super::ids::CustomerId;
+use super::money::Money;
+
+/// How often a scheduled transaction repeats
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+}
+
+impl Frequency {
+    /// Returns the date of the run following `from`
+    ///
+    /// Demonstrates: Pattern matching returning computed values
+    pub fn advance(&self, from: DateTime<Utc>) -> DateTime<Utc> {
+        match self {
+            Frequency::Daily => from + Duration::days(1),
+            Frequency::Weekly => from + Duration::weeks(1),
+            // Month arithmetic clamps to the last day (Jan 31 -> Feb 28)
+            Frequency::Monthly => from
+                .checked_add_months(Months::new(1))
+                .unwrap_or(from + Duration::days(30)),
+        }
+    }
+}
+
+impl fmt::Display for Frequency {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Frequency::Daily => write!(f, "Daily"),
+            Frequency::Weekly => write!(f, "Weekly"),
+            Frequency::Monthly => write!(f, "Monthly"),
+        }
+    }
+}
+
+/// A standing order that transfers money between two customers on a schedule
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduledTransaction {
+    /// Unique schedule identifier (UUID v4)
+    pub id: String,
+
+    /// Customer whose account is debited
+    pub from_customer_id: CustomerId,
+
+    /// Customer whose account is credited
+    pub to_customer_id: CustomerId,
+
+    /// Amount transferred on each run
+    pub amount: Money,
+
+    /// How often the transfer repeats
+    pub frequency: Frequency,
+
+    /// When the transfer is next due
+    pub next_run: DateTime<Utc>,
+
+    /// When the transfer last ran (successfully or not)
+    pub last_run: Option<DateTime<Utc>>,
+
+    /// Number of successful runs so far
+    pub run_count: u32,
+
+    /// Schedule creation timestamp
+    pub created_at: DateTime<Utc>,
+}
+
+impl ScheduledTransaction {
+    /// Creates a new schedule whose first run is at `first_run`
+    pub fn new(
+        from_customer_id: CustomerId,
+        to_customer_id: CustomerId,
+        amount: Money,
+        frequency: Frequency,
+        first_run: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            from_customer_id,
+            to_customer_id,
+            amount,
+            frequency,
+            next_run: first_run,
+            last_run: None,
+            run_count: 0,
+            created_at: Utc::now(),
+        }
+    }
+
+    /// Checks whether the schedule should run at `now`
+    pub fn is_due(&self, now: DateTime<Utc>) -> bool {
+        self.next_run <= now
+    }
+
+    /// Moves the schedule to its next occurrence
+    pub fn advance(&mut self) {
+        self.last_run = Some(self.next_run);
+        self.next_run = self.frequency.advance(self.next_run);
+    }
+}
+
+impl fmt::Display for ScheduledTransaction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "[{}] {} {} from {} to {} - next run: {}",
+            &self.id[..8],
+            self.frequency,
+            self.amount.display(),
+            self.from_customer_id.short(),
+            self.to_customer_id.short(),
+            format::datetime(&self.next_run)
+        )
+    }
+}
@@ -0,0 +1,274 @@
+//! Statement module - account activity over a period
+//!
+//! Demonstrates: Slices and binary search over sorted data, Display for
+//! multi-line reports
+
+use chrono::{DateTime, Duration, Months, NaiveDate, Utc};
+use serde::Serialize;
+use std::fmt;
+
+use crate::errors::{BankError, BankResult};
+use crate::format;
+use super::account::Account;
+use super::ids::AccountId;
+use super::money::Money;
+use super::transaction::{Transaction, TransactionType};
+
+/// An account statement for the period `[from, to)`
+#[derive(Debug, Clone, Serialize)]
+pub struct Statement {
+    /// Account the statement belongs to
+    pub account_id: AccountId,
+
+    /// Start of the period (inclusive)
+    pub from: DateTime<Utc>,
+
+    /// End of the period (exclusive)
+    pub to: DateTime<Utc>,
+
+    /// Balance before the first transaction of the period
+    pub opening_balance: Money,
+
+    /// Transactions within the period, oldest first
+    pub transactions: Vec<Transaction>,
+
+    /// Balance after the last transaction of the period
+    pub closing_balance: Money,
+}
+
+impl Statement {
+    /// Sum of all credits (deposits, incoming transfers) in the period
+    pub fn total_credits(&self) -> Money {
+        self.transactions
+            .iter()
+            .filter(|tx| tx.transaction_type.is_credit())
+            .map(|tx| tx.amount)
+            .sum()
+    }
+
+    /// Sum of all debits (withdrawals, outgoing transfers) in the period
+    pub fn total_debits(&self) -> Money {
+        self.transactions
+            .iter()
+            .filter(|tx| !tx.transaction_type.is_credit())
+            .map(|tx| tx.amount)
+            .sum()
+    }
+}
+
+/// Totals of one calendar month of account activity (UTC)
+///
+/// The figures reconcile: `opening_balance + deposits + transfers_in +
+/// reversals + term_deposits - withdrawals - transfers_out - fees -
+/// bill_payments = ending_balance`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MonthlySummary {
+    pub account_id: AccountId,
+    pub year: i32,
+    /// 1 (January) to 12 (December)
+    pub month: u32,
+    pub opening_balance: Money,
+    pub deposits: Money,
+    pub withdrawals: Money,
+    pub transfers_in: Money,
+    pub transfers_out: Money,
+    /// Net effect of undone operations; positive when money came back
+    pub reversals: Money,
+    /// Fees charged in the month, e.g. for bounced cheques
+    pub fees: Money,
+    /// Bills paid to merchants in the month
+    pub bill_payments: Money,
+    /// Net money moved out to and back from term deposits; positive when
+    /// more came back
+    pub term_deposits: Money,
+    pub net_change: Money,
+    pub ending_balance: Money,
+    /// Number of transactions in the month
+    pub transactions: usize,
+}
+
+impl Account {
+    /// Summarizes the account's activity in one calendar month
+    ///
+    /// # Returns
+    /// * `Ok(MonthlySummary)` - The month's totals (all zero if the account
+    ///   was quiet, with opening and ending balance still filled in)
+    /// * `Err(BankError::InvalidInput)` - If the month is not 1 to 12 or the
+    ///   year is out of range
+    pub fn monthly_summary(&self, year: i32, month: u32) -> BankResult<MonthlySummary> {
+        let (from, to) = month_period(year, month)?;
+        Ok(MonthlySummary::from_statement(year, month, &self.statement(from, to)))
+    }
+
+    /// Builds a statement for the period `[from, to)`
+    ///
+    /// Only the live history is used: for an account with archived
+    /// history, `Bank::statement` pages it back in when the period needs it.
+    ///
+    /// # Arguments
+    /// * `from` - Start of the period (inclusive)
+    /// * `to` - End of the period (exclusive)
+    pub fn statement(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> Statement {
+        Statement::for_period(&self.id, &self.transactions, self.balance, from, to)
+    }
+}
+
+/// The first instant of a calendar month (UTC) and of the month after it
+///
+/// # Returns
+/// * `Err(BankError::InvalidInput)` - If the month is not 1 to 12 or the
+///   year is out of range
+pub(crate) fn month_period(year: i32, month: u32) -> BankResult<(DateTime<Utc>, DateTime<Utc>)> {
+    let invalid = || BankError::InvalidInput(format!("{}-{:02} is not a valid month", year, month));
+    let first = NaiveDate::from_ymd_opt(year, month, 1).ok_or_else(invalid)?;
+    let next = first.checked_add_months(Months::new(1)).ok_or_else(invalid)?;
+    Ok((first.and_hms_opt(0, 0, 0).unwrap().and_utc(), next.and_hms_opt(0, 0, 0).unwrap().and_utc()))
+}
+
+impl MonthlySummary {
+    /// Totals a statement covering the month
+    pub(crate) fn from_statement(year: i32, month: u32, statement: &Statement) -> Self {
+        let total = |wanted: fn(&TransactionType) -> bool| -> Money {
+            statement
+                .transactions
+                .iter()
+                .filter(|tx| wanted(&tx.transaction_type))
+                .map(|tx| tx.signed_amount())
+                .sum()
+        };
+        let reversals = total(|t| matches!(t, TransactionType::Reversal { .. }));
+
+        MonthlySummary {
+            account_id: statement.account_id.clone(),
+            year,
+            month,
+            opening_balance: statement.opening_balance,
+            deposits: total(|t| matches!(t, TransactionType::Deposit)),
+            withdrawals: -total(|t| matches!(t, TransactionType::Withdrawal)),
+            transfers_in: total(|t| matches!(t, TransactionType::TransferIn { .. })),
+            transfers_out: -total(TransactionType::is_transfer_out),
+            reversals,
+            fees: -total(|t| matches!(t, TransactionType::Fee)),
+            bill_payments: -total(|t| matches!(t, TransactionType::BillPayment { .. })),
+            term_deposits: total(|t| matches!(t, TransactionType::TermDeposit { .. })),
+            net_change: statement.closing_balance - statement.opening_balance,
+            ending_balance: statement.closing_balance,
+            transactions: statement.transactions.len(),
+        }
+    }
+}
+
+impl Statement {
+    /// Builds the statement of `account_id` for the period `[from, to)`
+    /// from its history, oldest first
+    ///
+    /// Demonstrates: partition_point (binary search) on chronological data
+    ///
+    /// `balance` is the account's balance, the opening balance of a
+    /// history with no transactions at all.
+    pub(crate) fn for_period(
+        account_id: &AccountId,
+        transactions: &[Transaction],
+        balance: Money,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Self {
+        let start = transactions.partition_point(|tx| tx.timestamp < from);
+        let end = transactions.partition_point(|tx| tx.timestamp < to).max(start);
+        let in_period = &transactions[start..end];
+
+        // The balance just before `from` is the balance after the previous
+        // transaction; before any transaction at all it is the balance the
+        // first transaction started from.
+        let opening_balance = match start.checked_sub(1) {
+            Some(prev) => transactions[prev].balance_after,
+            None => transactions
+                .first()
+                .map(|tx| tx.balance_after - tx.signed_amount())
+                .unwrap_or(balance),
+        };
+
+        let closing_balance = in_period
+            .last()
+            .map(|tx| tx.balance_after)
+            .unwrap_or(opening_balance);
+
+        Statement {
+            account_id: account_id.clone(),
+            from,
+            to,
+            opening_balance,
+            transactions: in_period.to_vec(),
+            closing_balance,
+        }
+    }
+}
+
+// Renders the statement as a plain-text report
+impl fmt::Display for Statement {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "═══════════════════════════════════════════")?;
+        writeln!(f, "              ACCOUNT STATEMENT")?;
+        writeln!(f, "═══════════════════════════════════════════")?;
+        writeln!(f, "Account: {}", self.account_id)?;
+        // `to` is exclusive, so show the last instant that is included
+        writeln!(
+            f,
+            "Period:  {} to {}",
+            format::date(&self.from),
+            format::date(&(self.to - Duration::nanoseconds(1)))
+        )?;
+        writeln!(f, "───────────────────────────────────────────")?;
+        writeln!(f, "Opening Balance: {}", self.opening_balance.display())?;
+        writeln!(f, "───────────────────────────────────────────")?;
+
+        if self.transactions.is_empty() {
+            writeln!(f, "No transactions in this period.")?;
+        }
+        for tx in &self.transactions {
+            writeln!(f, "{}", tx)?;
+            // The audit trail of reversals: which entry undid which, and why
+            if let Some(reversal_id) = &tx.reversed_by {
+                writeln!(f, "    Reversed by {}", reversal_id)?;
+            }
+            if let Some(reason) = &tx.reversal_reason {
+                writeln!(f, "    Reason: {}", reason)?;
+            }
+        }
+
+        writeln!(f, "───────────────────────────────────────────")?;
+        writeln!(f, "Total Credits:   {}", self.total_credits().display())?;
+        writeln!(f, "Total Debits:    {}", self.total_debits().display())?;
+        writeln!(f, "Closing Balance: {}", self.closing_balance.display())?;
+        write!(f, "═══════════════════════════════════════════")
+    }
+}
+
+// Renders the summary as a plain-text mini statement
+impl fmt::Display for MonthlySummary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "═══════════════════════════════════════════")?;
+        writeln!(f, "          MONTHLY SUMMARY {}-{:02}", self.year, self.month)?;
+        writeln!(f, "═══════════════════════════════════════════")?;
+        writeln!(f, "Account: {}", self.account_id)?;
+        writeln!(f, "Transactions: {}", self.transactions)?;
+        writeln!(f, "───────────────────────────────────────────")?;
+        writeln!(f, "Opening Balance: {}", self.opening_balance.display())?;
+        writeln!(f, "Deposits:        {}", self.deposits.display())?;
+        writeln!(f, "Withdrawals:     {}", self.withdrawals.display())?;
+        writeln!(f, "Transfers In:    {}", self.transfers_in.display())?;
+        writeln!(f, "Transfers Out:   {}", self.transfers_out.display())?;
+        if self.reversals != Money::ZERO {
+            writeln!(f, "Reversals:       {}", self.reversals.display())?;
+        }
+        writeln!(f, "Fees:            {}", self.fees.display())?;
+        writeln!(f, "Bill Payments:   {}", self.bill_payments.display())?;
+        if self.term_deposits != Money::ZERO {
+            writeln!(f, "Term Deposits:   {}", self.term_deposits.display())?;
+        }
+        writeln!(f, "───────────────────────────────────────────")?;
+        writeln!(f, "Net Change:      {}", self.net_change.display())?;
+        writeln!(f, "Ending Balance:  {}", self.ending_balance.display())?;
+        write!(f, "═══════════════════════════════════════════")
+    }
+}
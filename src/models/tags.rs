@@ -0,0 +1,168 @@
+//! Tags module - spending categories and free-form transaction labels
+//!
+//! Demonstrates: Normalizing user input once at the boundary, grouping
+//! with a HashMap and sorting the result
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+use crate::errors::{BankError, BankResult};
+use super::account::Account;
+use super::ids::TransactionId;
+use super::money::Money;
+use super::transaction::{Transaction, TransactionType};
+
+/// Longest category or tag accepted, in characters
+pub const MAX_LABEL_LENGTH: usize = 32;
+
+/// A category and tags to attach to a transaction
+///
+/// Labels are stored trimmed and lowercase, and tags without duplicates,
+/// so `Groceries` and ` groceries ` are the same category.
+///
+/// ```
+/// use rust_banking_system::models::TransactionTags;
+///
+/// let tags = TransactionTags::parse("Groceries", "weekly, Food, weekly").unwrap();
+/// assert_eq!(tags.category.as_deref(), Some("groceries"));
+/// assert_eq!(tags.tags, ["weekly", "food"]);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransactionTags {
+    pub category: Option<String>,
+    pub tags: Vec<String>,
+}
+
+impl TransactionTags {
+    /// Builds normalized tags; blank labels are dropped
+    ///
+    /// # Returns
+    /// * `Err(BankError::InvalidInput)` - If a label is longer than
+    ///   `MAX_LABEL_LENGTH` or contains a comma
+    pub fn new(
+        category: Option<&str>,
+        tags: impl IntoIterator<Item = impl AsRef<str>>,
+    ) -> BankResult<Self> {
+        let category = match category {
+            Some(category) => normalize_label(category)?,
+            None => None,
+        };
+
+        let mut seen = HashSet::new();
+        let mut normalized = Vec::new();
+        for tag in tags {
+            if let Some(tag) = normalize_label(tag.as_ref())? {
+                if seen.insert(tag.clone()) {
+                    normalized.push(tag);
+                }
+            }
+        }
+
+        Ok(Self { category, tags: normalized })
+    }
+
+    /// Builds tags from CLI input: a category and comma-separated tags,
+    /// either of which may be blank
+    pub fn parse(category: &str, tags: &str) -> BankResult<Self> {
+        Self::new(Some(category), tags.split(','))
+    }
+
+    /// Whether there is neither a category nor a tag
+    pub fn is_empty(&self) -> bool {
+        self.category.is_none() && self.tags.is_empty()
+    }
+}
+
+/// Trims and lowercases a label; blank labels become `None`
+pub fn normalize_label(label: &str) -> BankResult<Option<String>> {
+    let label = label.trim().to_lowercase();
+    if label.is_empty() {
+        return Ok(None);
+    }
+    if label.contains(',') {
+        return Err(BankError::InvalidInput(format!("'{}' must not contain a comma", label)));
+    }
+    if label.chars().count() > MAX_LABEL_LENGTH {
+        return Err(BankError::InvalidInput(format!(
+            "'{}' is longer than {} characters",
+            label, MAX_LABEL_LENGTH
+        )));
+    }
+    Ok(Some(label))
+}
+
+/// Money spent in one category
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CategoryTotal {
+    /// `None` for spending without a category
+    pub category: Option<String>,
+    pub transactions: usize,
+    pub amount: Money,
+}
+
+impl Account {
+    /// Sets the category and tags of a transaction, replacing earlier ones
+    ///
+    /// # Returns
+    /// * `Err(BankError::InvalidInput)` - If the account has no such transaction
+    pub fn tag_transaction(&mut self, transaction_id: &TransactionId, tags: TransactionTags) -> BankResult<()> {
+        let transaction = self
+            .transactions
+            .iter_mut()
+            .find(|tx| tx.id == *transaction_id)
+            .ok_or_else(|| BankError::InvalidInput(format!("transaction '{}' not found", transaction_id)))?;
+
+        transaction.category = tags.category;
+        transaction.tags = tags.tags;
+        Ok(())
+    }
+
+    /// Totals the account's spending (withdrawals and outgoing transfers)
+    /// per category, largest first
+    ///
+    /// Spending that was undone by a reversal is left out.
+    pub fn category_breakdown(&self) -> Vec<CategoryTotal> {
+        let mut totals: HashMap<Option<&str>, CategoryTotal> = HashMap::new();
+        for tx in self.spending() {
+            let total = totals.entry(tx.category.as_deref()).or_insert_with(|| CategoryTotal {
+                category: tx.category.clone(),
+                transactions: 0,
+                amount: Money::ZERO,
+            });
+            total.transactions += 1;
+            total.amount += tx.amount;
+        }
+
+        let mut totals: Vec<CategoryTotal> = totals.into_values().collect();
+        totals.sort_by(|a, b| b.amount.cmp(&a.amount).then_with(|| a.category.cmp(&b.category)));
+        totals
+    }
+
+    /// Spending in `category` during `[from, to)`, counted as in
+    /// `category_breakdown`
+    pub fn category_spending(&self, category: &str, from: DateTime<Utc>, to: DateTime<Utc>) -> Money {
+        self.spending()
+            .filter(|tx| tx.category.as_deref() == Some(category) && tx.timestamp >= from && tx.timestamp < to)
+            .map(|tx| tx.amount)
+            .sum()
+    }
+
+    /// Withdrawals and outgoing transfers that were not reversed
+    fn spending(&self) -> impl Iterator<Item = &Transaction> {
+        let reversed: HashSet<&str> = self
+            .transactions
+            .iter()
+            .filter_map(|tx| match &tx.transaction_type {
+                TransactionType::Reversal { original_id, .. } => Some(original_id.as_str()),
+                _ => None,
+            })
+            .collect();
+
+        self.transactions.iter().filter(move |tx| {
+            let spending =
+                matches!(tx.transaction_type, TransactionType::Withdrawal) || tx.transaction_type.is_transfer_out();
+            spending && !reversed.contains(tx.id.as_str())
+        })
+    }
+}
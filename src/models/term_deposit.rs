@@ -0,0 +1,156 @@
+//! Term deposit module - money locked away for a fixed term at a fixed rate
+//!
+//! Demonstrates: Integer arithmetic in a wider type (i128) so interest
+//! never overflows or goes through floating point
+//!
+//! Opening a term deposit takes the principal out of the customer's
+//! account. At maturity the principal and the interest for the full term
+//! go back to it. Taking the money out early pays the interest earned so
+//! far, less a penalty of `EARLY_WITHDRAWAL_PENALTY_BPS` of the principal.
+//! Interest is simple (not compounded) and rounded down to the cent.
+
+use chrono::{DateTime, Months, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use uuid::Uuid;
+
+use crate::errors::{BankError, BankResult};
+use crate::format;
+use super::money::Money;
+
+/// Penalty for taking a term deposit out before it matures, in basis
+/// points (hundredths of a percent) of the principal
+pub const EARLY_WITHDRAWAL_PENALTY_BPS: u32 = 100;
+
+/// Where a term deposit stands
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TermDepositStatus {
+    #[default]
+    Active,
+    /// Paid out in full at the end of its term
+    Matured,
+    /// Taken out early, with the penalty
+    Withdrawn,
+}
+
+impl fmt::Display for TermDepositStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TermDepositStatus::Active => write!(f, "Active"),
+            TermDepositStatus::Matured => write!(f, "Matured"),
+            TermDepositStatus::Withdrawn => write!(f, "Withdrawn early"),
+        }
+    }
+}
+
+/// An amount locked away from an account until its maturity date
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TermDeposit {
+    /// Unique identifier (UUID v4)
+    pub id: String,
+
+    /// The amount taken from the account
+    pub principal: Money,
+
+    /// Yearly interest rate in basis points, e.g. 450 for 4.50%
+    pub rate_bps: u32,
+
+    pub term_months: u32,
+
+    pub opened_at: DateTime<Utc>,
+
+    /// When the principal and interest are paid back
+    pub maturity_date: DateTime<Utc>,
+
+    #[serde(default)]
+    pub status: TermDepositStatus,
+
+    /// What was paid back to the account, once it is closed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub payout: Option<Money>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub closed_at: Option<DateTime<Utc>>,
+}
+
+impl TermDeposit {
+    /// Creates an active deposit opened at `now`
+    ///
+    /// # Returns
+    /// * `Err(BankError::InvalidInput)` - If the maturity date would
+    ///   overflow the calendar
+    pub(crate) fn new_at(principal: Money, rate_bps: u32, term_months: u32, now: DateTime<Utc>) -> BankResult<Self> {
+        let maturity_date = now
+            .checked_add_months(Months::new(term_months))
+            .ok_or_else(|| BankError::InvalidInput(format!("a {}-month term is too long", term_months)))?;
+
+        Ok(Self {
+            id: Uuid::new_v4().to_string(),
+            principal,
+            rate_bps,
+            term_months,
+            opened_at: now,
+            maturity_date,
+            status: TermDepositStatus::Active,
+            payout: None,
+            closed_at: None,
+        })
+    }
+
+    /// Interest for the full term
+    pub fn interest_at_maturity(&self) -> Money {
+        let cents = self.principal.cents() as i128 * self.rate_bps as i128 * self.term_months as i128 / (12 * 10_000);
+        Money::from_cents(cents as i64)
+    }
+
+    /// Interest earned by `now`, in proportion to the time since opening
+    pub fn accrued_interest(&self, now: DateTime<Utc>) -> Money {
+        if now >= self.maturity_date {
+            return self.interest_at_maturity();
+        }
+        let elapsed = (now - self.opened_at).num_seconds().max(0) as i128;
+        let term = (self.maturity_date - self.opened_at).num_seconds().max(1) as i128;
+        Money::from_cents((self.interest_at_maturity().cents() as i128 * elapsed / term) as i64)
+    }
+
+    /// What taking the deposit out early costs
+    pub fn early_withdrawal_penalty(&self) -> Money {
+        let cents = self.principal.cents() as i128 * EARLY_WITHDRAWAL_PENALTY_BPS as i128 / 10_000;
+        Money::from_cents(cents as i64)
+    }
+
+    /// What the account gets back if the deposit is closed at `now`:
+    /// principal and full interest once mature, otherwise the interest so
+    /// far less the penalty (never less than nothing)
+    pub fn payout_at(&self, now: DateTime<Utc>) -> Money {
+        if self.is_mature(now) {
+            return self.principal + self.interest_at_maturity();
+        }
+        (self.principal + self.accrued_interest(now) - self.early_withdrawal_penalty()).max(Money::ZERO)
+    }
+
+    /// Returns true once the maturity date has been reached
+    pub fn is_mature(&self, now: DateTime<Utc>) -> bool {
+        now >= self.maturity_date
+    }
+}
+
+impl fmt::Display for TermDeposit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} at {}.{:02}% for {} months, matures {} - {}",
+            self.principal.display(),
+            self.rate_bps / 100,
+            self.rate_bps % 100,
+            self.term_months,
+            format::date(&self.maturity_date),
+            self.status
+        )?;
+        if let Some(payout) = self.payout {
+            write!(f, " (paid {})", payout.display())?;
+        }
+        Ok(())
+    }
+}
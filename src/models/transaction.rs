@@ -4,20 +4,238 @@
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use uuid::Uuid;
 use std::fmt;
+use std::str::FromStr;
+
+use crate::errors::BankResult;
+use crate::format;
+use crate::validation;
+use super::ids::{AccountId, TransactionId};
+use super::money::Money;
+use super::tags::TransactionTags;
 
 /// Represents different types of transactions
 ///
 /// Demonstrates: Enum variants with different data types
 /// - Simple variants (Deposit, Withdrawal)
-/// - Struct-like variant with named fields (Transfer)
+/// - Struct-like variants with named fields (Transfer, TransferIn)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum TransactionType {
     Deposit,
     Withdrawal,
-    /// Transfer variant holds the destination account ID
-    Transfer { to_account_id: String },
+    /// Outgoing transfer, holds the destination account ID
+    Transfer { to_account_id: AccountId },
+    /// Incoming transfer, holds the source account ID
+    TransferIn { from_account_id: AccountId },
+    /// Compensating entry posted by an undo, holds the reversed transaction
+    /// ID; `credit` is true when it puts money back into the account
+    Reversal { original_id: TransactionId, credit: bool },
+    /// Outgoing transfer to an account at another bank, holds that
+    /// account's reference (e.g. an IBAN)
+    ExternalTransfer { external_ref: String },
+    /// Charge taken by the bank, e.g. for a bounced cheque
+    Fee,
+    /// Payment to a merchant registered with the bank; `reference` is the
+    /// customer's reference at the merchant
+    BillPayment { merchant_id: String, reference: String },
+    /// Money moved into a term deposit (`credit` false) or paid back from
+    /// one (`credit` true), holds the deposit's ID
+    TermDeposit { deposit_id: String, credit: bool },
+}
+
+/// The kind of a transaction, without its associated data
+///
+/// Used to filter by type; parses from `deposit`, `withdrawal`,
+/// `transfer-out`, `transfer-in`, `reversal`, `external-transfer`, `fee`,
+/// `bill-payment` and `term-deposit`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TransactionKind {
+    Deposit,
+    Withdrawal,
+    TransferOut,
+    TransferIn,
+    Reversal,
+    ExternalTransfer,
+    Fee,
+    BillPayment,
+    TermDeposit,
+}
+
+/// Shown the way it parses, e.g. `transfer-out`
+impl fmt::Display for TransactionKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            TransactionKind::Deposit => "deposit",
+            TransactionKind::Withdrawal => "withdrawal",
+            TransactionKind::TransferOut => "transfer-out",
+            TransactionKind::TransferIn => "transfer-in",
+            TransactionKind::Reversal => "reversal",
+            TransactionKind::ExternalTransfer => "external-transfer",
+            TransactionKind::Fee => "fee",
+            TransactionKind::BillPayment => "bill-payment",
+            TransactionKind::TermDeposit => "term-deposit",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for TransactionKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().replace('_', "-").as_str() {
+            "deposit" => Ok(TransactionKind::Deposit),
+            "withdrawal" => Ok(TransactionKind::Withdrawal),
+            "transfer-out" => Ok(TransactionKind::TransferOut),
+            "transfer-in" => Ok(TransactionKind::TransferIn),
+            "reversal" => Ok(TransactionKind::Reversal),
+            "external-transfer" => Ok(TransactionKind::ExternalTransfer),
+            "fee" => Ok(TransactionKind::Fee),
+            "bill-payment" => Ok(TransactionKind::BillPayment),
+            "term-deposit" => Ok(TransactionKind::TermDeposit),
+            other => Err(format!("unknown transaction type '{}'", other)),
+        }
+    }
+}
+
+impl TransactionType {
+    /// Returns the kind of this transaction
+    pub fn kind(&self) -> TransactionKind {
+        match self {
+            TransactionType::Deposit => TransactionKind::Deposit,
+            TransactionType::Withdrawal => TransactionKind::Withdrawal,
+            TransactionType::Transfer { .. } => TransactionKind::TransferOut,
+            TransactionType::TransferIn { .. } => TransactionKind::TransferIn,
+            TransactionType::Reversal { .. } => TransactionKind::Reversal,
+            TransactionType::ExternalTransfer { .. } => TransactionKind::ExternalTransfer,
+            TransactionType::Fee => TransactionKind::Fee,
+            TransactionType::BillPayment { .. } => TransactionKind::BillPayment,
+            TransactionType::TermDeposit { .. } => TransactionKind::TermDeposit,
+        }
+    }
+
+    /// Returns true if this transaction adds money to the account
+    pub fn is_credit(&self) -> bool {
+        matches!(
+            self,
+            TransactionType::Deposit
+                | TransactionType::TransferIn { .. }
+                | TransactionType::Reversal { credit: true, .. }
+                | TransactionType::TermDeposit { credit: true, .. }
+        )
+    }
+
+    /// Short, stable name used in exports (e.g. CSV files)
+    pub fn code(&self) -> &'static str {
+        match self {
+            TransactionType::Deposit => "DEPOSIT",
+            TransactionType::Withdrawal => "WITHDRAWAL",
+            TransactionType::Transfer { .. } => "TRANSFER_OUT",
+            TransactionType::TransferIn { .. } => "TRANSFER_IN",
+            TransactionType::Reversal { credit: true, .. } => "REVERSAL_CREDIT",
+            TransactionType::Reversal { credit: false, .. } => "REVERSAL_DEBIT",
+            TransactionType::ExternalTransfer { .. } => "EXTERNAL_TRANSFER",
+            TransactionType::Fee => "FEE",
+            TransactionType::BillPayment { .. } => "BILL_PAYMENT",
+            TransactionType::TermDeposit { credit: true, .. } => "TERM_DEPOSIT_PAYOUT",
+            TransactionType::TermDeposit { credit: false, .. } => "TERM_DEPOSIT",
+        }
+    }
+
+    /// Rebuilds a type from its `code()` and optional `reference()`
+    ///
+    /// A bill payment's reference is the merchant; the customer's own
+    /// reference at the merchant is not part of the code and comes back
+    /// empty.
+    ///
+    /// # Returns
+    /// * `None` - If the code is unknown or a transfer/reversal lacks a reference
+    pub fn from_code(code: &str, reference: Option<String>) -> Option<Self> {
+        match (code, reference) {
+            ("DEPOSIT", _) => Some(TransactionType::Deposit),
+            ("WITHDRAWAL", _) => Some(TransactionType::Withdrawal),
+            ("FEE", _) => Some(TransactionType::Fee),
+            ("TRANSFER_OUT", Some(id)) => Some(TransactionType::Transfer { to_account_id: id.into() }),
+            ("TRANSFER_IN", Some(id)) => Some(TransactionType::TransferIn { from_account_id: id.into() }),
+            ("REVERSAL_CREDIT", Some(id)) => {
+                Some(TransactionType::Reversal { original_id: id.into(), credit: true })
+            }
+            ("REVERSAL_DEBIT", Some(id)) => {
+                Some(TransactionType::Reversal { original_id: id.into(), credit: false })
+            }
+            ("EXTERNAL_TRANSFER", Some(external_ref)) => Some(TransactionType::ExternalTransfer { external_ref }),
+            ("BILL_PAYMENT", Some(merchant_id)) => {
+                Some(TransactionType::BillPayment { merchant_id, reference: String::new() })
+            }
+            ("TERM_DEPOSIT", Some(deposit_id)) => Some(TransactionType::TermDeposit { deposit_id, credit: false }),
+            ("TERM_DEPOSIT_PAYOUT", Some(deposit_id)) => {
+                Some(TransactionType::TermDeposit { deposit_id, credit: true })
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the ID the type refers to: the other account for transfers,
+    /// the reversed transaction for reversals, the merchant for bill
+    /// payments, the term deposit for money moved into or out of one
+    pub fn reference(&self) -> Option<&str> {
+        match self {
+            TransactionType::Reversal { original_id, .. } => Some(original_id.as_str()),
+            TransactionType::BillPayment { merchant_id, .. } => Some(merchant_id),
+            TransactionType::TermDeposit { deposit_id, .. } => Some(deposit_id),
+            _ => self.counterparty(),
+        }
+    }
+
+    /// Returns the other account involved, if this is a transfer
+    ///
+    /// For an external transfer this is the reference of the account at
+    /// the other bank, which no customer here owns.
+    ///
+    /// Demonstrates: Returning borrowed data from an enum variant
+    pub fn counterparty(&self) -> Option<&str> {
+        match self {
+            TransactionType::Transfer { to_account_id } => Some(to_account_id.as_str()),
+            TransactionType::TransferIn { from_account_id } => Some(from_account_id.as_str()),
+            TransactionType::ExternalTransfer { external_ref } => Some(external_ref),
+            _ => None,
+        }
+    }
+
+    /// Returns true for money sent to another account, here or elsewhere
+    pub fn is_transfer_out(&self) -> bool {
+        matches!(self, TransactionType::Transfer { .. } | TransactionType::ExternalTransfer { .. })
+    }
+}
+
+/// Where a transaction stands
+///
+/// Most transactions complete as they are posted. An external transfer
+/// leaves the account straight away but stays `Pending` until the other
+/// bank settles it (`Completed`) or rejects it (`Failed`, with the money
+/// returned by a reversal). An authorized withdrawal is `Pending` until it
+/// is captured (`Completed`) or voided (`Reversed`), and a transaction
+/// that has been undone is `Reversed` too.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TransactionStatus {
+    Pending,
+    #[default]
+    Completed,
+    Failed,
+    Reversed,
+}
+
+impl fmt::Display for TransactionStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            TransactionStatus::Pending => "PENDING",
+            TransactionStatus::Completed => "COMPLETED",
+            TransactionStatus::Failed => "FAILED",
+            TransactionStatus::Reversed => "REVERSED",
+        };
+        write!(f, "{}", name)
+    }
 }
 
 /// A record of a single transaction
@@ -29,19 +247,55 @@ pub enum TransactionType {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Transaction {
     /// Unique identifier for the transaction (UUID v4)
-    pub id: String,
+    pub id: TransactionId,
 
     /// Type of transaction (deposit, withdrawal, transfer)
     pub transaction_type: TransactionType,
 
     /// Amount involved in the transaction
-    pub amount: f64,
+    pub amount: Money,
 
     /// Timestamp when the transaction occurred (UTC)
     pub timestamp: DateTime<Utc>,
 
     /// Balance after the transaction was completed
-    pub balance_after: f64,
+    pub balance_after: Money,
+
+    /// Free-text memo given when the transaction was made, e.g. "rent"
+    #[serde(default)]
+    pub description: Option<String>,
+
+    /// Spending category, e.g. `groceries` (see `TransactionTags`)
+    #[serde(default)]
+    pub category: Option<String>,
+
+    /// Free-form labels, lowercase and without duplicates
+    #[serde(default)]
+    pub tags: Vec<String>,
+
+    /// Whether the transaction has gone through; older data files only
+    /// have completed ones
+    #[serde(default)]
+    pub status: TransactionStatus,
+
+    /// The debit card that paid, for card purchases
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub card_id: Option<String>,
+
+    /// ID of the reversal that undid this transaction; older data files
+    /// only have the `Reversed` status
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reversed_by: Option<TransactionId>,
+
+    /// Why the transaction this reversal undoes was reversed, when a
+    /// reason was given
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reversal_reason: Option<String>,
+
+    /// Paid in or out in cash, so it moved money in or out of the bank's
+    /// vault; reversals copy it from the transaction they undo
+    #[serde(default)]
+    pub cash: bool,
 }
 
 impl Transaction {
@@ -59,40 +313,198 @@ impl Transaction {
     /// A new `Transaction` instance with a generated UUID and current timestamp
     pub fn new(
         transaction_type: TransactionType,
-        amount: f64,
-        balance_after: f64
+        amount: Money,
+        balance_after: Money
+    ) -> Self {
+        Self::new_at(transaction_type, amount, balance_after, Utc::now())
+    }
+
+    /// Creates a new transaction stamped with `timestamp`
+    ///
+    /// The bank passes the time from its `Clock`, so tests control it.
+    pub fn new_at(
+        transaction_type: TransactionType,
+        amount: Money,
+        balance_after: Money,
+        timestamp: DateTime<Utc>,
     ) -> Self {
         Self {
-            id: Uuid::new_v4().to_string(),
+            id: TransactionId::generate(),
             transaction_type,
             amount,
-            timestamp: Utc::now(),
+            timestamp,
             balance_after,
+            description: None,
+            category: None,
+            tags: Vec::new(),
+            status: TransactionStatus::Completed,
+            card_id: None,
+            reversed_by: None,
+            reversal_reason: None,
+            cash: false,
+        }
+    }
+
+    /// Attaches a description and tags to a transaction being prepared
+    pub(crate) fn annotate(&mut self, details: TransactionDetails) {
+        self.description = details.description;
+        self.category = details.tags.category;
+        self.tags = details.tags.tags;
+    }
+}
+
+/// Optional extras for a deposit, withdrawal or transfer
+///
+/// Demonstrates: A builder whose validating step returns a Result, so it
+/// chains with `?`
+///
+/// ```
+/// use rust_banking_system::models::{TransactionDetails, TransactionTags};
+///
+/// let details = TransactionDetails::new()
+///     .description("March rent")?
+///     .tags(TransactionTags::parse("rent", "home")?);
+/// assert_eq!(details.description.as_deref(), Some("March rent"));
+/// # Ok::<(), rust_banking_system::BankError>(())
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TransactionDetails {
+    pub description: Option<String>,
+    pub tags: TransactionTags,
+}
+
+impl TransactionDetails {
+    /// No description and no tags
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the description, checked with `validation::validate_description`
+    pub fn description(mut self, description: &str) -> BankResult<Self> {
+        self.description = Some(validation::validate_description(description)?);
+        Ok(self)
+    }
+
+    /// Sets the category and tags
+    pub fn tags(mut self, tags: TransactionTags) -> Self {
+        self.tags = tags;
+        self
+    }
+}
+
+impl Transaction {
+    /// ID of the transaction this one reverses, for reversals
+    pub fn reverses(&self) -> Option<&TransactionId> {
+        match &self.transaction_type {
+            TransactionType::Reversal { original_id, .. } => Some(original_id),
+            _ => None,
+        }
+    }
+
+    /// Returns the amount as a signed change to the balance
+    ///
+    /// Credits are positive, debits negative.
+    pub fn signed_amount(&self) -> Money {
+        if self.transaction_type.is_credit() {
+            self.amount
+        } else {
+            -self.amount
         }
     }
 }
 
 // Implementing Display trait for pretty printing
 // https://doc.rust-lang.org/std/fmt/trait.Display.html
-impl fmt::Display for Transaction {
+/// How the type is shown to people, e.g. `TRANSFER to 1a2b3c4d`
+impl fmt::Display for TransactionType {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         // Demonstrates: Pattern matching and string formatting
-        let tx_type = match &self.transaction_type {
+        let tx_type = match self {
             TransactionType::Deposit => "DEPOSIT".to_string(),
             TransactionType::Withdrawal => "WITHDRAWAL".to_string(),
+            TransactionType::Fee => "FEE".to_string(),
+            TransactionType::BillPayment { reference, .. } if reference.is_empty() => "BILL PAYMENT".to_string(),
+            TransactionType::BillPayment { reference, .. } => format!("BILL PAYMENT ref {}", reference),
             TransactionType::Transfer { to_account_id } => {
                 // Show first 8 characters of the destination account ID
-                format!("TRANSFER to {}", &to_account_id[..8])
+                format!("TRANSFER to {}", to_account_id.short())
+            }
+            TransactionType::TransferIn { from_account_id } => {
+                format!("TRANSFER from {}", from_account_id.short())
+            }
+            TransactionType::Reversal { original_id, .. } => {
+                format!("REVERSAL of {}", original_id.short())
+            }
+            TransactionType::ExternalTransfer { external_ref } => {
+                format!("EXTERNAL TRANSFER to {}", external_ref)
+            }
+            TransactionType::TermDeposit { deposit_id, credit: false } => {
+                format!("TERM DEPOSIT {}", deposit_id.get(..8).unwrap_or(deposit_id))
+            }
+            TransactionType::TermDeposit { deposit_id, credit: true } => {
+                format!("TERM DEPOSIT PAYOUT {}", deposit_id.get(..8).unwrap_or(deposit_id))
             }
         };
+        f.write_str(&tx_type)
+    }
+}
 
+impl fmt::Display for Transaction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(
             f,
-            "[{}] {} ${:.2} - Balance: ${:.2}",
-            self.timestamp.format("%Y-%m-%d %H:%M:%S"),
-            tx_type,
-            self.amount,
-            self.balance_after
-        )
+            "[{}] {} {} - Balance: {}",
+            format::datetime(&self.timestamp),
+            self.transaction_type,
+            self.amount.display(),
+            self.balance_after.display()
+        )?;
+        if let Some(description) = &self.description {
+            write!(f, " \"{}\"", description)?;
+        }
+        if let Some(category) = &self.category {
+            write!(f, " ({})", category)?;
+        }
+        for tag in &self.tags {
+            write!(f, " #{}", tag)?;
+        }
+        if self.status != TransactionStatus::Completed {
+            write!(f, " [{}]", self.status)?;
+        }
+        Ok(())
+    }
+}
+
+// Demonstrates: Implementing comparison traits by hand, so they look only
+// at the fields that identify a transaction
+// https://doc.rust-lang.org/std/cmp/trait.Ord.html
+
+/// Two transactions are equal when they have the same ID, whatever else
+/// differs (e.g. one copy was tagged since)
+impl PartialEq for Transaction {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl Eq for Transaction {}
+
+impl std::hash::Hash for Transaction {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
+
+/// Oldest first; transactions posted at the same moment are ordered by ID
+/// so sorting is deterministic
+impl Ord for Transaction {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.timestamp.cmp(&other.timestamp).then_with(|| self.id.cmp(&other.id))
+    }
+}
+
+impl PartialOrd for Transaction {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
     }
 }
@@ -0,0 +1,67 @@
+//! User module - roles and bank staff accounts
+//!
+//! Demonstrates: Fieldless enums with Display/FromStr, keeping secrets
+//! out of the public API with pub(crate)
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use super::credentials::Credentials;
+
+/// What a logged-in user is allowed to do
+///
+/// - `Admin` - registers customers, manages staff and the bank's data
+/// - `Teller` - opens accounts and processes transactions for any customer
+/// - `Customer` - views and operates their own account only
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    Admin,
+    Teller,
+    Customer,
+}
+
+impl Role {
+    /// Whether the role belongs to bank staff rather than a customer
+    pub fn is_staff(self) -> bool {
+        matches!(self, Role::Admin | Role::Teller)
+    }
+}
+
+impl fmt::Display for Role {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Role::Admin => write!(f, "Admin"),
+            Role::Teller => write!(f, "Teller"),
+            Role::Customer => write!(f, "Customer"),
+        }
+    }
+}
+
+impl FromStr for Role {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "admin" => Ok(Role::Admin),
+            "teller" => Ok(Role::Teller),
+            "customer" => Ok(Role::Customer),
+            other => Err(format!("unknown role '{}'", other)),
+        }
+    }
+}
+
+/// A bank employee who logs in with a username and PIN
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StaffMember {
+    /// Login name (unique, case-insensitive)
+    pub username: String,
+
+    /// `Admin` or `Teller`
+    pub role: Role,
+
+    /// Salted PIN hash and lockout state
+    pub(crate) credentials: Credentials,
+}
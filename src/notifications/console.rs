@@ -0,0 +1,16 @@
+//! Console notifier - alerts printed to the terminal
+
+use crate::errors::BankResult;
+use crate::models::Alert;
+use super::Notifier;
+
+/// Prints alerts to stderr, so they never mix with JSON results on stdout
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ConsoleNotifier;
+
+impl Notifier for ConsoleNotifier {
+    fn notify(&self, alert: &Alert) -> BankResult<()> {
+        eprintln!("🔔 {}", alert);
+        Ok(())
+    }
+}
@@ -0,0 +1,37 @@
+//! File notifier - alerts appended to a JSON Lines file
+
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::PathBuf;
+
+use crate::errors::{BankError, BankResult};
+use crate::models::Alert;
+use super::Notifier;
+
+/// Appends each alert as one line of JSON, for other programs to tail
+#[derive(Debug, Clone)]
+pub struct FileNotifier {
+    path: PathBuf,
+}
+
+impl FileNotifier {
+    /// Writes to `path`, creating it on the first alert
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+impl Notifier for FileNotifier {
+    fn notify(&self, alert: &Alert) -> BankResult<()> {
+        let mut line = serde_json::to_string(alert)?;
+        line.push('\n');
+
+        // One write per line, so concurrent writers don't interleave lines
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .and_then(|mut file| file.write_all(line.as_bytes()))
+            .map_err(BankError::from)
+    }
+}
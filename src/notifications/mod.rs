@@ -0,0 +1,30 @@
+//! Notifications module - delivering triggered alerts
+//!
+//! Demonstrates: Trait objects as plug-in points, one trait with several
+//! small implementations in their own files
+//!
+//! The bank checks each account's `AlertRules` as postings are made and
+//! hands every triggered `Alert` to the notifiers registered with
+//! `Bank::add_notifier`; running the bank's policies sends dormancy
+//! alerts the same way. A notifier that fails is reported on stderr but
+//! never fails the operation that triggered it: the money has moved either
+//! way.
+
+mod console;
+mod file;
+mod webhook;
+
+pub use console::ConsoleNotifier;
+pub use file::FileNotifier;
+pub use webhook::{post_json, validate_url, WebhookNotifier, WEBHOOK_TIMEOUT};
+
+use std::fmt;
+
+use crate::errors::BankResult;
+use crate::models::Alert;
+
+/// Somewhere triggered alerts are sent
+pub trait Notifier: fmt::Debug + Send + Sync {
+    /// Delivers one alert
+    fn notify(&self, alert: &Alert) -> BankResult<()>;
+}
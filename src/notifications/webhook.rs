@@ -0,0 +1,115 @@
+//! Webhook notifier - alerts POSTed to an HTTP endpoint
+//!
+//! Demonstrates: Speaking just enough HTTP/1.1 over a `TcpStream` to send
+//! a request and read the status line
+//! https://doc.rust-lang.org/std/net/struct.TcpStream.html
+//!
+//! Only plain `http://` URLs are supported, which suits webhooks on a
+//! local network or behind a TLS-terminating proxy.
+
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::Duration;
+
+use crate::errors::{BankError, BankResult, Cause};
+use crate::models::Alert;
+use super::Notifier;
+
+/// How long a webhook may take to connect, accept and answer
+pub const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// POSTs each alert as JSON; any 2xx answer counts as delivered
+#[derive(Debug, Clone)]
+pub struct WebhookNotifier {
+    url: String,
+}
+
+impl WebhookNotifier {
+    /// Sends alerts to `url`
+    ///
+    /// # Returns
+    /// * `Err(BankError::InvalidInput)` - If the URL is not `http://host[:port][/path]`
+    pub fn new(url: &str) -> BankResult<Self> {
+        validate_url(url)?;
+        Ok(Self { url: url.to_string() })
+    }
+}
+
+impl Notifier for WebhookNotifier {
+    fn notify(&self, alert: &Alert) -> BankResult<()> {
+        let body = serde_json::to_string(alert)?;
+        post_json(&self.url, &body)
+    }
+}
+
+/// Checks that `url` is an `http://host[:port][/path]` URL `post_json` can send to
+pub fn validate_url(url: &str) -> BankResult<()> {
+    Target::parse(url).map(|_| ())
+}
+
+/// Where a request goes: `host[:port]` and the path
+struct Target<'a> {
+    authority: &'a str,
+    path: &'a str,
+}
+
+impl<'a> Target<'a> {
+    fn parse(url: &'a str) -> BankResult<Self> {
+        let invalid = || BankError::InvalidInput(format!("'{}' is not an http:// URL", url));
+        let rest = url.strip_prefix("http://").ok_or_else(invalid)?;
+        let (authority, path) = match rest.find('/') {
+            Some(i) => (&rest[..i], &rest[i..]),
+            None => (rest, "/"),
+        };
+        if authority.is_empty() {
+            return Err(invalid());
+        }
+        Ok(Self { authority, path })
+    }
+}
+
+/// POSTs a JSON body to an `http://` URL and checks for a 2xx status
+pub fn post_json(url: &str, body: &str) -> BankResult<()> {
+    let target = Target::parse(url)?;
+    let io_error = |e: std::io::Error| BankError::IoError(Cause::new(e).context(url));
+
+    let host = if target.authority.contains(':') {
+        target.authority.to_string()
+    } else {
+        format!("{}:80", target.authority)
+    };
+    let address = host
+        .to_socket_addrs()
+        .map_err(io_error)?
+        .next()
+        .ok_or_else(|| BankError::IoError(format!("{}: host not found", url).into()))?;
+
+    let mut stream = TcpStream::connect_timeout(&address, WEBHOOK_TIMEOUT).map_err(io_error)?;
+    stream.set_read_timeout(Some(WEBHOOK_TIMEOUT)).map_err(io_error)?;
+    stream.set_write_timeout(Some(WEBHOOK_TIMEOUT)).map_err(io_error)?;
+
+    write!(
+        stream,
+        "POST {} HTTP/1.1\r\nHost: {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        target.path,
+        target.authority,
+        body.len(),
+        body
+    )
+    .map_err(io_error)?;
+
+    // "HTTP/1.1 204 No Content"
+    let mut status_line = String::new();
+    BufReader::new(stream).read_line(&mut status_line).map_err(io_error)?;
+    let status: u16 = status_line
+        .split_whitespace()
+        .nth(1)
+        .and_then(|code| code.parse().ok())
+        .ok_or_else(|| BankError::IoError(format!("{}: malformed HTTP response", url).into()))?;
+
+    if (200..300).contains(&status) {
+        Ok(())
+    } else {
+        Err(BankError::IoError(format!("{} answered with status {}", url, status).into()))
+    }
+}
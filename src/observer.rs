@@ -0,0 +1,148 @@
+//! Observer module - hooks for code embedding the bank
+//!
+//! Demonstrates: The observer pattern with trait objects, default method
+//! implementations so observers only write the hooks they need
+//! https://doc.rust-lang.org/book/ch10-02-traits.html#default-implementations
+//!
+//! Observers registered with `Bank::add_observer` are called after each
+//! change sticks: not for failed operations, not for an all-or-nothing
+//! batch that is rolled back and not while a journal is replayed. They run
+//! while the bank is being changed, so they must not lock a `SharedBank`
+//! holding it. The exception is `on_webhook_failed`, which is called from
+//! the thread delivering webhooks, after the change has returned.
+
+use std::fmt;
+use std::io::{self, Write};
+use std::sync::Mutex;
+
+use chrono::Utc;
+
+use crate::errors::BankError;
+use crate::events::DomainEvent;
+use crate::models::{Customer, CustomerId, Transaction};
+
+/// Something told about changes to a bank
+pub trait BankObserver: fmt::Debug + Send + Sync {
+    /// Money was deposited
+    fn on_deposit(&self, _customer_id: &CustomerId, _transaction: &Transaction) {}
+
+    /// Money was withdrawn, including when an authorization or a hold is captured
+    fn on_withdraw(&self, _customer_id: &CustomerId, _transaction: &Transaction) {}
+
+    /// Money moved between two customers; `debit` and `credit` are the
+    /// two postings
+    fn on_transfer(
+        &self,
+        _from_customer_id: &CustomerId,
+        _to_customer_id: &CustomerId,
+        _debit: &Transaction,
+        _credit: &Transaction,
+    ) {
+    }
+
+    /// A new customer registered
+    fn on_customer_registered(&self, _customer: &Customer) {}
+
+    /// Every attempt to POST `event` to the webhook at `url` failed;
+    /// `error` is the last failure, and the event is dropped
+    fn on_webhook_failed(&self, _url: &str, _event: &DomainEvent, _error: &BankError) {}
+}
+
+/// Writes one line per change, e.g. for an audit log
+///
+/// ```text
+/// 2024-03-01T09:30:00Z deposit customer=9f1c… amount=50.00 balance=150.00 tx=4be2…
+/// ```
+pub struct LoggingObserver {
+    out: Mutex<Box<dyn Write + Send>>,
+}
+
+impl LoggingObserver {
+    /// Logs to stderr
+    pub fn new() -> Self {
+        Self::with_writer(io::stderr())
+    }
+
+    /// Logs to any writer, such as a file
+    pub fn with_writer(out: impl Write + Send + 'static) -> Self {
+        Self { out: Mutex::new(Box::new(out)) }
+    }
+
+    fn log(&self, line: fmt::Arguments) {
+        // A poisoned lock only means another thread panicked mid-line
+        let mut out = self.out.lock().unwrap_or_else(|e| e.into_inner());
+        // Logging must never fail the change it describes
+        let _ = writeln!(out, "{}", line);
+    }
+
+    fn log_posting(&self, action: &str, customer_id: &CustomerId, transaction: &Transaction) {
+        self.log(format_args!(
+            "{} {} customer={} amount={} balance={} tx={}",
+            transaction.timestamp.format("%Y-%m-%dT%H:%M:%SZ"),
+            action,
+            customer_id,
+            transaction.amount,
+            transaction.balance_after,
+            transaction.id
+        ));
+    }
+}
+
+impl Default for LoggingObserver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for LoggingObserver {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("LoggingObserver").finish_non_exhaustive()
+    }
+}
+
+impl BankObserver for LoggingObserver {
+    fn on_deposit(&self, customer_id: &CustomerId, transaction: &Transaction) {
+        self.log_posting("deposit", customer_id, transaction);
+    }
+
+    fn on_withdraw(&self, customer_id: &CustomerId, transaction: &Transaction) {
+        self.log_posting("withdraw", customer_id, transaction);
+    }
+
+    fn on_transfer(
+        &self,
+        from_customer_id: &CustomerId,
+        to_customer_id: &CustomerId,
+        debit: &Transaction,
+        credit: &Transaction,
+    ) {
+        self.log(format_args!(
+            "{} transfer from={} to={} amount={} debit={} credit={}",
+            debit.timestamp.format("%Y-%m-%dT%H:%M:%SZ"),
+            from_customer_id,
+            to_customer_id,
+            debit.amount,
+            debit.id,
+            credit.id
+        ));
+    }
+
+    fn on_customer_registered(&self, customer: &Customer) {
+        self.log(format_args!(
+            "{} register customer={} email={}",
+            customer.registered_at.format("%Y-%m-%dT%H:%M:%SZ"),
+            customer.id,
+            customer.email
+        ));
+    }
+
+    fn on_webhook_failed(&self, url: &str, event: &DomainEvent, error: &BankError) {
+        self.log(format_args!(
+            "{} webhook-failed url={} event={} error={}",
+            Utc::now().format("%Y-%m-%dT%H:%M:%SZ"),
+            url,
+            event.kind(),
+            error
+        ));
+    }
+}
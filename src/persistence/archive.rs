@@ -0,0 +1,160 @@
+//! Archive files - transactions moved out of the live data
+//!
+//! Demonstrates: Implementing a trait from another module, one append-only
+//! JSON Lines file per key
+//!
+//! `FileArchive` keeps each account's archived transactions in its own
+//! file, `<account id>.jsonl`, in a directory next to the data file
+//! (`bank.json.archive/` for `bank.json`). Archiving appends to the file;
+//! it is only read again when a statement reaches back before the cutoff,
+//! or a walk through the history gets that far, and then only as far as
+//! the page it needs.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, ErrorKind, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+use crate::bank::HistoryArchive;
+use crate::errors::{BankError, BankResult};
+use crate::models::{AccountId, Transaction};
+
+/// Bytes read at a time when looking for the last line of a file
+const TAIL_CHUNK: u64 = 4096;
+
+/// Returns the archive directory belonging to a data file
+pub fn archive_dir(filename: &str) -> String {
+    format!("{}.archive", filename)
+}
+
+/// Archived transactions as JSON Lines files, one per account
+#[derive(Debug, Clone)]
+pub struct FileArchive {
+    dir: PathBuf,
+}
+
+impl FileArchive {
+    /// An archive kept in `dir`, which is created when first stored to
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// The archive next to a data file (see `archive_dir`)
+    pub fn for_data_file(filename: &str) -> Self {
+        Self::new(archive_dir(filename))
+    }
+
+    /// Returns the directory the archive files are in
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// The file holding an account's archived transactions
+    fn path(&self, account_id: &AccountId) -> BankResult<PathBuf> {
+        // Account IDs are UUIDs; anything else could point outside the directory
+        let account_id = account_id.as_str();
+        if account_id.is_empty() || !account_id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+            return Err(BankError::InvalidInput(format!("'{}' is not an account ID", account_id)));
+        }
+        Ok(self.dir.join(format!("{}.jsonl", account_id)))
+    }
+
+    /// The account's file opened for reading, `None` if nothing was
+    /// archived for it yet
+    fn open(&self, account_id: &AccountId) -> BankResult<Option<BufReader<File>>> {
+        match File::open(self.path(account_id)?) {
+            Ok(file) => Ok(Some(BufReader::new(file))),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// The transactions in a file's lines
+    ///
+    /// A partially written last line (from a crash mid-append) is skipped:
+    /// its transaction was still live, and archiving again stores it anew.
+    fn parse(lines: impl Iterator<Item = std::io::Result<String>>) -> BankResult<Vec<Transaction>> {
+        let mut transactions = Vec::new();
+        for line in lines {
+            if let Ok(transaction) = serde_json::from_str(&line?) {
+                transactions.push(transaction);
+            }
+        }
+        Ok(transactions)
+    }
+
+    /// Cuts off a partially written last line and returns the last
+    /// complete one, reading the file backwards from its end
+    fn repair_tail(file: &mut File) -> BankResult<Option<String>> {
+        let len = file.metadata()?.len();
+        let mut tail: Vec<u8> = Vec::new();
+        let mut start = len;
+        let (last, complete) = loop {
+            let step = TAIL_CHUNK.min(start);
+            start -= step;
+            let mut chunk = vec![0; step as usize];
+            file.seek(SeekFrom::Start(start))?;
+            file.read_exact(&mut chunk)?;
+            chunk.extend_from_slice(&tail);
+            tail = chunk;
+
+            // `end` is where the complete lines end
+            match tail.iter().rposition(|&b| b == b'\n') {
+                Some(end) => match tail[..end].iter().rposition(|&b| b == b'\n') {
+                    Some(before) => break (Some(&tail[before + 1..end]), start + end as u64 + 1),
+                    None if start == 0 => break (Some(&tail[..end]), end as u64 + 1),
+                    None => {}
+                },
+                None if start == 0 => break (None, 0),
+                None => {}
+            }
+        };
+        if complete < len {
+            file.set_len(complete)?;
+        }
+        Ok(last.map(|line| String::from_utf8_lossy(line).into_owned()))
+    }
+}
+
+impl HistoryArchive for FileArchive {
+    /// Appends to the account's file
+    ///
+    /// Batches are stored oldest first, so one repeated after an
+    /// interruption overlaps the end of the file: whatever the batch holds
+    /// up to the last transaction already stored is skipped.
+    fn store(&self, account_id: &AccountId, transactions: &[Transaction]) -> BankResult<()> {
+        let path = self.path(account_id)?;
+        fs::create_dir_all(&self.dir)?;
+        let mut file = OpenOptions::new().read(true).append(true).create(true).open(&path)?;
+        let last_stored = Self::repair_tail(&mut file)?
+            .and_then(|line| serde_json::from_str::<Transaction>(&line).ok())
+            .and_then(|last| transactions.iter().position(|tx| tx.id == last.id));
+        let new = &transactions[last_stored.map_or(0, |stored| stored + 1)..];
+        if new.is_empty() {
+            return Ok(());
+        }
+
+        let mut lines = String::new();
+        for transaction in new {
+            lines.push_str(&serde_json::to_string(transaction)?);
+            lines.push('\n');
+        }
+        file.write_all(lines.as_bytes())
+            .and_then(|_| file.sync_data())
+            .map_err(BankError::from)
+    }
+
+    fn load(&self, account_id: &AccountId) -> BankResult<Vec<Transaction>> {
+        match self.open(account_id)? {
+            Some(reader) => Self::parse(reader.lines()),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Reads lines up to the end of the page, parsing only the page's own
+    fn load_page(&self, account_id: &AccountId, start: usize, len: usize) -> BankResult<Vec<Transaction>> {
+        match self.open(account_id)? {
+            Some(reader) => Self::parse(reader.lines().skip(start).take(len)),
+            None => Ok(Vec::new()),
+        }
+    }
+}
@@ -0,0 +1,90 @@
+//! Backup rotation - keeps the last N versions of the data file
+//!
+//! Demonstrates: File system operations, reverse ranges, Option chaining
+//!
+//! Before each save the current data file is copied to `<file>.1`, the
+//! previous `<file>.1` moves to `<file>.2`, and so on up to the configured
+//! count. The oldest backup falls off the end.
+
+use std::fs;
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+
+use crate::errors::{BankError, BankResult};
+
+/// Number of backups kept when none is configured
+pub const DEFAULT_BACKUP_COUNT: usize = 3;
+
+/// Information about one backup file
+#[derive(Debug, Clone)]
+pub struct BackupInfo {
+    /// Backup number (1 = most recent)
+    pub index: usize,
+
+    /// Path of the backup file
+    pub path: String,
+
+    /// When the backup was written, if the file system reports it
+    pub modified: Option<DateTime<Utc>>,
+}
+
+/// Returns the path of backup number `index`
+pub fn backup_path(filename: &str, index: usize) -> String {
+    format!("{}.{}", filename, index)
+}
+
+/// Shifts existing backups up by one and copies the current file to `.1`
+///
+/// Does nothing if `count` is zero or the data file does not exist yet.
+pub fn rotate_backups(filename: &str, count: usize) -> BankResult<()> {
+    if count == 0 || !Path::new(filename).exists() {
+        return Ok(());
+    }
+
+    // Drop the oldest, then move each backup one slot up: .2 -> .3, .1 -> .2
+    let oldest = backup_path(filename, count);
+    if Path::new(&oldest).exists() {
+        fs::remove_file(&oldest)?;
+    }
+    for index in (1..count).rev() {
+        let from = backup_path(filename, index);
+        if Path::new(&from).exists() {
+            fs::rename(&from, backup_path(filename, index + 1))?;
+        }
+    }
+
+    // Copy rather than rename so the data file is never missing
+    fs::copy(filename, backup_path(filename, 1))?;
+    Ok(())
+}
+
+/// Lists existing backups, most recent first
+pub fn list_backups(filename: &str, count: usize) -> Vec<BackupInfo> {
+    (1..=count)
+        .map(|index| (index, backup_path(filename, index)))
+        .filter(|(_, path)| Path::new(path).exists())
+        .map(|(index, path)| {
+            let modified = fs::metadata(&path)
+                .and_then(|m| m.modified())
+                .ok()
+                .map(DateTime::<Utc>::from);
+            BackupInfo { index, path, modified }
+        })
+        .collect()
+}
+
+/// Replaces the data file with backup number `index`
+///
+/// The current data file is rotated into the backups first, so a restore
+/// can itself be undone. Any journal is discarded because it describes
+/// changes on top of the replaced file.
+pub fn restore_backup(filename: &str, index: usize, count: usize) -> BankResult<()> {
+    let path = backup_path(filename, index);
+    let contents = fs::read(&path)
+        .map_err(|_| BankError::IoError(format!("backup '{}' not found", path).into()))?;
+
+    rotate_backups(filename, count)?;
+    super::write_atomic(filename, &contents)?;
+    super::journal::clear_journal(filename)
+}
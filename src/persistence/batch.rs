@@ -0,0 +1,77 @@
+//! Batch files - bulk postings read from CSV or JSON
+//!
+//! Demonstrates: Choosing a parser by file extension, converting flat CSV
+//! rows into an enum
+//!
+//! CSV columns: `type, customer, to, amount, description`, where `type` is
+//! `deposit`, `withdraw` or `transfer`, `customer` is the sender of a
+//! transfer and `to` is only used by transfers. JSON files hold an array
+//! of `Operation`s.
+
+use std::fs;
+use std::path::Path;
+
+use serde::Deserialize;
+
+use crate::bank::Operation;
+use crate::errors::{BankError, BankResult, Cause};
+use crate::models::Money;
+
+/// One CSV row of a batch file
+#[derive(Debug, Deserialize)]
+struct BatchRow {
+    #[serde(rename = "type")]
+    kind: String,
+    customer: String,
+    #[serde(default)]
+    to: Option<String>,
+    amount: Money,
+    #[serde(default)]
+    description: Option<String>,
+}
+
+impl BatchRow {
+    fn into_operation(self) -> Result<Operation, String> {
+        let description = self.description.filter(|d| !d.trim().is_empty());
+        match self.kind.trim().to_lowercase().as_str() {
+            "deposit" => Ok(Operation::Deposit { customer: self.customer, amount: self.amount, description }),
+            "withdraw" => Ok(Operation::Withdraw { customer: self.customer, amount: self.amount, description }),
+            "transfer" => match self.to.filter(|to| !to.trim().is_empty()) {
+                Some(to) => Ok(Operation::Transfer { from: self.customer, to, amount: self.amount, description }),
+                None => Err("transfer needs a 'to' customer".to_string()),
+            },
+            other => Err(format!("unknown operation type '{}'", other)),
+        }
+    }
+}
+
+/// Reads a batch from a `.json` file, or from CSV for any other extension
+///
+/// The whole file is rejected if any row is invalid; CSV errors carry the
+/// offending line number.
+pub fn load_batch(path: &str) -> BankResult<Vec<Operation>> {
+    let is_json = Path::new(path)
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+
+    if is_json {
+        let json = fs::read_to_string(path)?;
+        return serde_json::from_str(&json).map_err(BankError::from);
+    }
+
+    let mut reader = csv::Reader::from_path(path)
+        .map_err(|e| BankError::IoError(Cause::new(e)))?;
+
+    let mut operations = Vec::new();
+    for (idx, row) in reader.deserialize::<BatchRow>().enumerate() {
+        // Line 1 is the header
+        let line = idx + 2;
+        let operation = row
+            .map_err(|e| e.to_string())
+            .and_then(BatchRow::into_operation)
+            .map_err(|e| BankError::SerializationError(format!("line {}: {}", line, e).into()))?;
+        operations.push(operation);
+    }
+
+    Ok(operations)
+}
@@ -0,0 +1,189 @@
+//! Compression - gzip or zstd around any data format
+//!
+//! Demonstrates: Stacking `Read`/`Write` adapters, detecting a stream by
+//! its magic number
+//!
+//! A data file whose name ends in `.gz` is gzip-compressed and one ending
+//! in `.zst` is zstd-compressed, around whichever `DataFormat` the rest of
+//! the name picks (`bank.json.gz`, `bank.msgpack.zst`). Loading does not
+//! trust the name: compression is detected from the first bytes, like the
+//! format. Data is compressed and decompressed as it streams to and from
+//! the file, so no uncompressed copy of a large bank is held in memory.
+
+use std::fmt;
+use std::io::{self, BufRead, Read, Write};
+use std::path::Path;
+use std::str::FromStr;
+
+use flate2::bufread::GzDecoder;
+use flate2::write::GzEncoder;
+use serde::{Deserialize, Serialize};
+
+use crate::errors::{BankError, BankResult};
+
+/// First bytes of every gzip stream
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// First bytes of every zstd frame
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+
+/// zstd's own default level: fast, and already smaller than gzip
+const ZSTD_LEVEL: i32 = 3;
+
+/// How a data file is compressed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Compression {
+    /// Stored as is, the default
+    #[default]
+    None,
+    /// gzip, readable with standard tools (`zcat`)
+    Gzip,
+    /// zstd: smaller than gzip and much faster
+    Zstd,
+}
+
+impl Compression {
+    /// Every compression, `None` first
+    pub const ALL: [Compression; 3] = [Compression::None, Compression::Gzip, Compression::Zstd];
+
+    /// The compression a file with this name is saved with, chosen by its
+    /// last extension
+    pub fn from_path(path: &str) -> Self {
+        let extension = Path::new(path).extension().and_then(|ext| ext.to_str()).unwrap_or_default();
+        match extension.to_ascii_lowercase().as_str() {
+            "gz" | "gzip" => Compression::Gzip,
+            "zst" | "zstd" => Compression::Zstd,
+            _ => Compression::None,
+        }
+    }
+
+    /// The file name without its compression extension, e.g. `bank.json`
+    /// for `bank.json.gz`
+    pub fn strip_extension(path: &str) -> &str {
+        match Compression::from_path(path) {
+            Compression::None => path,
+            _ => path.rsplit_once('.').map_or(path, |(stem, _)| stem),
+        }
+    }
+
+    /// The compression of saved data, judged from its magic number
+    pub fn detect(data: &[u8]) -> Self {
+        if data.starts_with(&GZIP_MAGIC) {
+            Compression::Gzip
+        } else if data.starts_with(&ZSTD_MAGIC) {
+            Compression::Zstd
+        } else {
+            Compression::None
+        }
+    }
+
+    /// The usual file extension, without the dot; `None` adds none
+    pub fn extension(self) -> Option<&'static str> {
+        match self {
+            Compression::None => None,
+            Compression::Gzip => Some("gz"),
+            Compression::Zstd => Some("zst"),
+        }
+    }
+
+    /// Wraps `writer` so that everything written to it is compressed
+    ///
+    /// The stream is only complete once `Encoder::finish` has been called.
+    pub fn writer<W: Write>(self, writer: W) -> BankResult<Encoder<W>> {
+        let encoder = match self {
+            Compression::None => Encoder::Plain(writer),
+            Compression::Gzip => Encoder::Gzip(GzEncoder::new(writer, flate2::Compression::default())),
+            Compression::Zstd => Encoder::Zstd(zstd::Encoder::new(writer, ZSTD_LEVEL)?),
+        };
+        Ok(encoder)
+    }
+
+    /// Wraps `reader` so that reading from it yields the decompressed data
+    pub fn reader<'a, R: BufRead + 'a>(self, reader: R) -> BankResult<Box<dyn Read + 'a>> {
+        let reader: Box<dyn Read + 'a> = match self {
+            Compression::None => Box::new(reader),
+            Compression::Gzip => Box::new(GzDecoder::new(reader)),
+            Compression::Zstd => Box::new(zstd::Decoder::with_buffer(reader)?),
+        };
+        Ok(reader)
+    }
+
+    /// Compresses a whole buffer
+    pub fn compress(self, data: &[u8]) -> BankResult<Vec<u8>> {
+        let mut encoder = self.writer(Vec::new())?;
+        encoder.write_all(data)?;
+        Ok(encoder.finish()?)
+    }
+
+    /// Decompresses a whole buffer
+    pub fn decompress(self, data: &[u8]) -> BankResult<Vec<u8>> {
+        let mut decompressed = Vec::new();
+        self.reader(data)?.read_to_end(&mut decompressed)?;
+        Ok(decompressed)
+    }
+}
+
+/// A writer that compresses what is written to it (see
+/// `Compression::writer`)
+pub enum Encoder<W: Write> {
+    Plain(W),
+    Gzip(GzEncoder<W>),
+    Zstd(zstd::Encoder<'static, W>),
+}
+
+impl<W: Write> Encoder<W> {
+    /// Writes the end of the compressed stream and returns the inner writer
+    pub fn finish(self) -> io::Result<W> {
+        match self {
+            Encoder::Plain(writer) => Ok(writer),
+            Encoder::Gzip(encoder) => encoder.finish(),
+            Encoder::Zstd(encoder) => encoder.finish(),
+        }
+    }
+}
+
+impl<W: Write> Write for Encoder<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Encoder::Plain(writer) => writer.write(buf),
+            Encoder::Gzip(encoder) => encoder.write(buf),
+            Encoder::Zstd(encoder) => encoder.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Encoder::Plain(writer) => writer.flush(),
+            Encoder::Gzip(encoder) => encoder.flush(),
+            Encoder::Zstd(encoder) => encoder.flush(),
+        }
+    }
+}
+
+impl fmt::Display for Compression {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Compression::None => write!(f, "none"),
+            Compression::Gzip => write!(f, "gzip"),
+            Compression::Zstd => write!(f, "zstd"),
+        }
+    }
+}
+
+impl FromStr for Compression {
+    type Err = BankError;
+
+    /// Parses a compression name or extension, e.g. `gzip`, `gz` or `zst`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "none" => Ok(Compression::None),
+            "gzip" | "gz" => Ok(Compression::Gzip),
+            "zstd" | "zst" => Ok(Compression::Zstd),
+            other => Err(BankError::InvalidInput(format!(
+                "unknown compression '{}' (expected none, gzip or zstd)",
+                other
+            ))),
+        }
+    }
+}
@@ -0,0 +1,226 @@
+//! CSV export/import of transaction histories and monthly summaries
+//!
+//! Demonstrates: The csv crate, Serde-friendly row structs, per-row errors
+//! https://docs.rs/csv/
+//!
+//! Transaction columns: `id, type, counterparty, amount, timestamp, balance_after,
+//! category, tags, description, status` (tags separated by `;`; files
+//! without the last four columns still import, as completed transactions)
+//!
+//! Monthly summaries are written with the fields of `MonthlySummary` as
+//! columns, one month per row.
+//!
+//! Trial balances are written as `code, account, class, debit, credit`,
+//! income statements as `section, code, account, amount`; both end with a
+//! totals row whose code is empty.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::bank::{IncomeStatement, TrialBalance};
+use crate::errors::{BankError, BankResult, Cause};
+use crate::models::{Account, Money, MonthlySummary, Transaction, TransactionStatus, TransactionTags, TransactionType};
+use crate::validation;
+
+/// One CSV row, mirroring a `Transaction`
+#[derive(Debug, Serialize, Deserialize)]
+struct TransactionRow {
+    id: String,
+    #[serde(rename = "type")]
+    kind: String,
+    /// Other account for transfers, reversed transaction for reversals
+    counterparty: Option<String>,
+    amount: Money,
+    timestamp: DateTime<Utc>,
+    balance_after: Money,
+    #[serde(default)]
+    category: Option<String>,
+    /// Tags joined with `;`
+    #[serde(default)]
+    tags: String,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    status: TransactionStatus,
+}
+
+impl From<&Transaction> for TransactionRow {
+    fn from(tx: &Transaction) -> Self {
+        Self {
+            id: tx.id.to_string(),
+            kind: tx.transaction_type.code().to_string(),
+            counterparty: tx.transaction_type.reference().map(str::to_string),
+            amount: tx.amount,
+            timestamp: tx.timestamp,
+            balance_after: tx.balance_after,
+            category: tx.category.clone(),
+            tags: tx.tags.join(";"),
+            description: tx.description.clone(),
+            status: tx.status,
+        }
+    }
+}
+
+/// Writes an account's transaction history to a CSV file
+///
+/// # Returns
+/// The number of transactions written
+pub fn export_transactions_csv(account: &Account, path: &str) -> BankResult<usize> {
+    let mut writer = csv::Writer::from_path(path)
+        .map_err(|e| BankError::IoError(Cause::new(e)))?;
+
+    for tx in &account.transactions {
+        writer
+            .serialize(TransactionRow::from(tx))
+            .map_err(|e| BankError::SerializationError(Cause::new(e)))?;
+    }
+    writer.flush().map_err(|e| BankError::IoError(Cause::new(e)))?;
+
+    Ok(account.transactions.len())
+}
+
+/// Writes monthly summaries to a CSV file, one row per month
+///
+/// # Returns
+/// The number of months written
+pub fn export_monthly_summaries_csv(summaries: &[MonthlySummary], path: &str) -> BankResult<usize> {
+    write_rows(summaries, path)?;
+    Ok(summaries.len())
+}
+
+/// One row of an exported trial balance
+#[derive(Debug, Serialize)]
+struct TrialBalanceCsvRow<'a> {
+    code: &'a str,
+    account: &'a str,
+    class: String,
+    debit: Money,
+    credit: Money,
+}
+
+/// Writes a trial balance to a CSV file, one row per account and a total
+///
+/// # Returns
+/// The number of accounts written
+pub fn export_trial_balance_csv(trial_balance: &TrialBalance, path: &str) -> BankResult<usize> {
+    let mut rows: Vec<TrialBalanceCsvRow> = trial_balance
+        .rows
+        .iter()
+        .map(|row| TrialBalanceCsvRow {
+            code: &row.code,
+            account: &row.name,
+            class: row.class.to_string(),
+            debit: row.debit,
+            credit: row.credit,
+        })
+        .collect();
+    rows.push(TrialBalanceCsvRow {
+        code: "",
+        account: "Total",
+        class: String::new(),
+        debit: trial_balance.total_debits,
+        credit: trial_balance.total_credits,
+    });
+    write_rows(&rows, path)?;
+    Ok(trial_balance.rows.len())
+}
+
+/// One row of an exported income statement
+#[derive(Debug, Serialize)]
+struct IncomeStatementCsvRow<'a> {
+    section: &'a str,
+    code: &'a str,
+    account: &'a str,
+    amount: Money,
+}
+
+/// Writes an income statement to a CSV file: the income accounts, the
+/// expense accounts and the net income
+///
+/// # Returns
+/// The number of accounts written
+pub fn export_income_statement_csv(statement: &IncomeStatement, path: &str) -> BankResult<usize> {
+    let sections = [("Income", &statement.income), ("Expenses", &statement.expenses)];
+    let mut rows: Vec<IncomeStatementCsvRow> = sections
+        .iter()
+        .flat_map(|(section, lines)| {
+            lines.iter().map(|line| IncomeStatementCsvRow {
+                section,
+                code: &line.code,
+                account: &line.name,
+                amount: line.amount,
+            })
+        })
+        .collect();
+    let written = rows.len();
+    rows.push(IncomeStatementCsvRow { section: "Net income", code: "", account: "", amount: statement.net_income });
+    write_rows(&rows, path)?;
+    Ok(written)
+}
+
+/// Serializes `rows` to a new CSV file at `path`
+fn write_rows<T: Serialize>(rows: &[T], path: &str) -> BankResult<()> {
+    let mut writer = csv::Writer::from_path(path)
+        .map_err(|e| BankError::IoError(Cause::new(e)))?;
+
+    for row in rows {
+        writer
+            .serialize(row)
+            .map_err(|e| BankError::SerializationError(Cause::new(e)))?;
+    }
+    writer.flush().map_err(|e| BankError::IoError(Cause::new(e)))
+}
+
+/// Reads transactions from a CSV file written by `export_transactions_csv`
+///
+/// The whole file is rejected if any row is invalid, with the offending
+/// line number in the error.
+pub fn import_transactions_csv(path: &str) -> BankResult<Vec<Transaction>> {
+    let mut reader = csv::Reader::from_path(path)
+        .map_err(|e| BankError::IoError(Cause::new(e)))?;
+
+    let mut transactions = Vec::new();
+    for (idx, row) in reader.deserialize::<TransactionRow>().enumerate() {
+        // Line 1 is the header
+        let line = idx + 2;
+        let row = row.map_err(|e| {
+            BankError::SerializationError(Cause::new(e).context(format!("line {}", line)))
+        })?;
+
+        let counterparty = row.counterparty.filter(|c| !c.is_empty());
+        let transaction_type = TransactionType::from_code(&row.kind, counterparty)
+            .ok_or_else(|| {
+                BankError::SerializationError(format!(
+                    "line {}: unknown transaction type '{}'",
+                    line, row.kind
+                ).into())
+            })?;
+
+        let tags = TransactionTags::new(row.category.as_deref(), row.tags.split(';'))
+            .map_err(|e| BankError::SerializationError(Cause::new(e).context(format!("line {}", line))))?;
+        let description = row
+            .description
+            .filter(|d| !d.trim().is_empty())
+            .map(|d| validation::validate_description(&d))
+            .transpose()
+            .map_err(|e| BankError::SerializationError(Cause::new(e).context(format!("line {}", line))))?;
+
+        transactions.push(Transaction {
+            id: row.id.into(),
+            transaction_type,
+            amount: row.amount,
+            timestamp: row.timestamp,
+            balance_after: row.balance_after,
+            description,
+            category: tags.category,
+            tags: tags.tags,
+            status: row.status,
+            card_id: None,
+            reversed_by: None,
+            reversal_reason: None,
+            cash: false,
+        });
+    }
+
+    Ok(transactions)
+}
@@ -0,0 +1,197 @@
+//! Snapshot diff - what changed between two saved bank states
+//!
+//! Demonstrates: HashMap/HashSet lookups to compare two collections by
+//! key, Display for a multi-section report
+//!
+//! Both files are read like `read_bank` reads them (journal replayed, never
+//! written), so a data file can be compared with one of its own backups
+//! while it is in use. Customers are matched by ID and transactions by
+//! transaction ID; a customer that was deleted counts as removed.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use serde::Serialize;
+
+use crate::bank::Bank;
+use crate::errors::BankResult;
+use crate::models::{Customer, CustomerId, Money, Transaction};
+
+/// A customer that is in one state and not the other
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CustomerChange {
+    pub customer_id: CustomerId,
+    pub name: String,
+    pub email: String,
+    /// Balance of the customer's account, if they have one
+    pub balance: Option<Money>,
+}
+
+impl CustomerChange {
+    fn from_customer(customer: &Customer) -> Self {
+        Self {
+            customer_id: customer.id.clone(),
+            name: customer.name.clone(),
+            email: customer.email.clone(),
+            balance: customer.account.as_ref().map(|account| account.balance),
+        }
+    }
+}
+
+impl fmt::Display for CustomerChange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} <{}>", self.name, self.email)?;
+        match self.balance {
+            Some(balance) => write!(f, " - Balance: {}", balance.display()),
+            None => write!(f, " - no account"),
+        }
+    }
+}
+
+/// A customer in both states whose balance differs; a customer without
+/// an account counts as zero
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BalanceChange {
+    pub customer_id: CustomerId,
+    pub name: String,
+    pub before: Money,
+    pub after: Money,
+}
+
+impl BalanceChange {
+    /// `after - before`; negative when the balance went down
+    pub fn change(&self) -> Money {
+        self.after - self.before
+    }
+}
+
+impl fmt::Display for BalanceChange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let change = self.change();
+        let sign = if change.is_negative() { "" } else { "+" };
+        write!(
+            f,
+            "{}: {} -> {} ({}{})",
+            self.name,
+            self.before.display(),
+            self.after.display(),
+            sign,
+            change.display()
+        )
+    }
+}
+
+/// A transaction in the second state that the first does not have
+#[derive(Debug, Clone, Serialize)]
+pub struct NewTransaction {
+    pub customer_id: CustomerId,
+    pub transaction: Transaction,
+}
+
+/// Everything that changed from one saved state to another
+#[derive(Debug, Clone, Serialize)]
+pub struct BankDiff {
+    /// Customers in the second state only, by name
+    pub added_customers: Vec<CustomerChange>,
+    /// Customers in the first state only, or deleted since, by name
+    pub removed_customers: Vec<CustomerChange>,
+    /// Customers in both whose balance changed, by name
+    pub balance_changes: Vec<BalanceChange>,
+    /// Transactions in the second state only, oldest first
+    pub new_transactions: Vec<NewTransaction>,
+}
+
+impl BankDiff {
+    /// True when the two states hold the same customers, balances and
+    /// transactions
+    pub fn is_empty(&self) -> bool {
+        self.added_customers.is_empty()
+            && self.removed_customers.is_empty()
+            && self.balance_changes.is_empty()
+            && self.new_transactions.is_empty()
+    }
+}
+
+impl fmt::Display for BankDiff {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_empty() {
+            return write!(f, "No differences");
+        }
+        writeln!(f, "Added customers ({}):", self.added_customers.len())?;
+        for customer in &self.added_customers {
+            writeln!(f, "  + {}", customer)?;
+        }
+        writeln!(f, "Removed customers ({}):", self.removed_customers.len())?;
+        for customer in &self.removed_customers {
+            writeln!(f, "  - {}", customer)?;
+        }
+        writeln!(f, "Balance changes ({}):", self.balance_changes.len())?;
+        for change in &self.balance_changes {
+            writeln!(f, "  {}", change)?;
+        }
+        write!(f, "New transactions ({}):", self.new_transactions.len())?;
+        for new in &self.new_transactions {
+            write!(f, "\n  {}", new.transaction)?;
+        }
+        Ok(())
+    }
+}
+
+/// Compares two saved data files
+///
+/// # Arguments
+/// * `file_a` - The earlier state, e.g. a backup taken before a batch run
+/// * `file_b` - The later state
+///
+/// # Returns
+/// * `Err(BankError)` - If either file cannot be read, as with `read_bank`
+pub fn diff(file_a: &str, file_b: &str) -> BankResult<BankDiff> {
+    let before = super::read_bank(file_a)?;
+    let after = super::read_bank(file_b)?;
+    Ok(diff_banks(&before, &after))
+}
+
+/// Compares two banks already in memory
+pub fn diff_banks(before: &Bank, after: &Bank) -> BankDiff {
+    let old: HashMap<&str, &Customer> = before.list_customers().into_iter().map(|c| (c.id.as_str(), c)).collect();
+    let new: HashMap<&str, &Customer> = after.list_customers().into_iter().map(|c| (c.id.as_str(), c)).collect();
+    let balance = |customer: &Customer| customer.account.as_ref().map_or(Money::ZERO, |account| account.balance);
+
+    let mut added_customers: Vec<CustomerChange> = new
+        .values()
+        .filter(|customer| !old.contains_key(customer.id.as_str()))
+        .map(|customer| CustomerChange::from_customer(customer))
+        .collect();
+    let mut removed_customers: Vec<CustomerChange> = old
+        .values()
+        .filter(|customer| !new.contains_key(customer.id.as_str()))
+        .map(|customer| CustomerChange::from_customer(customer))
+        .collect();
+    let mut balance_changes: Vec<BalanceChange> = new
+        .values()
+        .filter_map(|customer| {
+            let previous = old.get(customer.id.as_str())?;
+            (balance(previous) != balance(customer)).then(|| BalanceChange {
+                customer_id: customer.id.clone(),
+                name: customer.name.clone(),
+                before: balance(previous),
+                after: balance(customer),
+            })
+        })
+        .collect();
+    added_customers.sort_by(|a, b| a.name.cmp(&b.name).then_with(|| a.customer_id.cmp(&b.customer_id)));
+    removed_customers.sort_by(|a, b| a.name.cmp(&b.name).then_with(|| a.customer_id.cmp(&b.customer_id)));
+    balance_changes.sort_by(|a, b| a.name.cmp(&b.name).then_with(|| a.customer_id.cmp(&b.customer_id)));
+
+    let known: HashSet<&str> = before.all_transactions().map(|entry| entry.transaction.id.as_str()).collect();
+    let new_transactions = after
+        .all_transactions()
+        .filter(|entry| !known.contains(entry.transaction.id.as_str()))
+        .map(|entry| NewTransaction {
+            customer_id: entry.customer_id.clone(),
+            transaction: entry.transaction.clone(),
+        })
+        .collect();
+
+    BankDiff { added_customers, removed_customers, balance_changes, new_transactions }
+}
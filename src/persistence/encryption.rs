@@ -0,0 +1,178 @@
+//! Encryption at rest - passphrase-protected data files
+//!
+//! Demonstrates: Authenticated encryption (AES-256-GCM), key derivation
+//! with Argon2, binary file formats
+//! https://docs.rs/aes-gcm/
+//!
+//! Encrypted file layout:
+//! `MAGIC (8 bytes) | salt (16 bytes) | nonce (12 bytes) | ciphertext`
+
+use std::fs;
+use std::path::Path;
+
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use argon2::Argon2;
+
+use crate::bank::{Bank, JournalEntry};
+use crate::errors::{BankError, BankResult};
+use super::backup::{self, BackupInfo, DEFAULT_BACKUP_COUNT};
+use super::storage::Storage;
+
+/// Marks a file as encrypted by this module (format version 1)
+const MAGIC: &[u8; 8] = b"RBSENC01";
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+
+/// Checks whether raw file contents are in the encrypted format
+pub fn is_encrypted(data: &[u8]) -> bool {
+    data.starts_with(MAGIC)
+}
+
+/// Checks whether the file at `path` exists and is encrypted
+pub fn is_encrypted_file(path: &str) -> bool {
+    fs::read(path).map(|data| is_encrypted(&data)).unwrap_or(false)
+}
+
+/// Derives a 256-bit key from a passphrase and salt
+fn derive_key(passphrase: &str, salt: &[u8]) -> BankResult<Key<Aes256Gcm>> {
+    let mut key = [0u8; 32];
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| BankError::EncryptionError(e.to_string()))?;
+    Ok(key.into())
+}
+
+/// Encrypts `plaintext` with a key derived from `passphrase`
+///
+/// A fresh random salt and nonce are generated for every call.
+pub fn encrypt(plaintext: &[u8], passphrase: &str) -> BankResult<Vec<u8>> {
+    let mut salt = [0u8; SALT_LEN];
+    OsRng.fill_bytes(&mut salt);
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+
+    let cipher = Aes256Gcm::new(&derive_key(passphrase, &salt)?);
+    let ciphertext = cipher
+        .encrypt(&nonce, plaintext)
+        .map_err(|_| BankError::EncryptionError("encryption failed".to_string()))?;
+
+    let mut out = Vec::with_capacity(MAGIC.len() + SALT_LEN + NONCE_LEN + ciphertext.len());
+    out.extend_from_slice(MAGIC);
+    out.extend_from_slice(&salt);
+    out.extend_from_slice(&nonce);
+    out.extend_from_slice(&ciphertext);
+    Ok(out)
+}
+
+/// Decrypts data produced by `encrypt`
+///
+/// # Returns
+/// * `Err(BankError::EncryptionError)` - Wrong passphrase, tampered or
+///   truncated data, or data that is not encrypted at all
+pub fn decrypt(data: &[u8], passphrase: &str) -> BankResult<Vec<u8>> {
+    if !is_encrypted(data) {
+        return Err(BankError::EncryptionError("data is not encrypted".to_string()));
+    }
+
+    let header = MAGIC.len() + SALT_LEN + NONCE_LEN;
+    if data.len() < header {
+        return Err(BankError::EncryptionError("encrypted data is truncated".to_string()));
+    }
+
+    let salt = &data[MAGIC.len()..MAGIC.len() + SALT_LEN];
+    let nonce = Nonce::from_slice(&data[MAGIC.len() + SALT_LEN..header]);
+
+    let cipher = Aes256Gcm::new(&derive_key(passphrase, salt)?);
+    cipher.decrypt(nonce, &data[header..]).map_err(|_| {
+        BankError::EncryptionError("wrong passphrase or corrupted data file".to_string())
+    })
+}
+
+/// Stores the bank as passphrase-encrypted JSON
+///
+/// Legacy plaintext files are read transparently and encrypted on the next
+/// save. Because a plaintext journal would leak what the encryption
+/// protects, this backend writes a full encrypted snapshot instead of
+/// appending journal entries.
+pub struct EncryptedFileStorage {
+    /// Path to the encrypted data file
+    path: String,
+
+    /// Passphrase the file key is derived from
+    passphrase: String,
+
+    /// Number of rotated (encrypted) backups to keep
+    backups: usize,
+}
+
+impl EncryptedFileStorage {
+    /// Creates an encrypted storage for the file at `path`
+    pub fn new(path: impl Into<String>, passphrase: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            passphrase: passphrase.into(),
+            backups: DEFAULT_BACKUP_COUNT,
+        }
+    }
+
+    /// Sets how many rotated backups to keep (0 disables backups)
+    pub fn with_backups(mut self, backups: usize) -> Self {
+        self.backups = backups;
+        self
+    }
+}
+
+impl Storage for EncryptedFileStorage {
+    fn save_bank(&self, bank: &Bank) -> BankResult<()> {
+        let json = serde_json::to_vec(bank)?;
+        let data = encrypt(&json, &self.passphrase)?;
+
+        backup::rotate_backups(&self.path, self.backups)?;
+        super::write_atomic(&self.path, &data)?;
+
+        // A journal left over from plaintext mode is now part of the snapshot
+        super::journal::clear_journal(&self.path)
+    }
+
+    fn load_bank(&self) -> BankResult<Bank> {
+        let data = fs::read(&self.path)?;
+
+        if !is_encrypted(&data) {
+            // Legacy plaintext file: load it (and any journal) the normal way
+            return super::load_bank(&self.path);
+        }
+
+        let json = decrypt(&data, &self.passphrase)?;
+        super::parse_snapshot(json.as_slice(), &self.path, false)
+    }
+
+    fn read_bank(&self) -> BankResult<Bank> {
+        let data = fs::read(&self.path)?;
+
+        if !is_encrypted(&data) {
+            return super::read_bank(&self.path);
+        }
+        self.load_bank()
+    }
+
+    fn append_journal(&self, bank: &Bank, entries: &[JournalEntry]) -> BankResult<()> {
+        if entries.is_empty() && Path::new(&self.path).exists() {
+            return Ok(());
+        }
+        self.save_bank(bank)
+    }
+
+    fn list_backups(&self) -> Vec<BackupInfo> {
+        backup::list_backups(&self.path, self.backups)
+    }
+
+    fn restore_backup(&self, index: usize) -> BankResult<Bank> {
+        backup::restore_backup(&self.path, index, self.backups)?;
+        self.load_bank()
+    }
+
+    fn location(&self) -> String {
+        format!("{} (encrypted)", self.path)
+    }
+}
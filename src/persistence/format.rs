@@ -0,0 +1,207 @@
+//! Data formats - the bank as JSON, YAML, TOML or MessagePack
+//!
+//! Demonstrates: One serde model written through several serializers,
+//! sniffing a format from the first bytes of a file
+//!
+//! A data file is saved in the format its extension names (`.yaml`/`.yml`,
+//! `.toml`, `.msgpack`/`.mpk`) and in pretty-printed JSON otherwise.
+//! Loading does not trust the extension: the format is detected from the
+//! contents, so a file renamed after it was written still loads. Journals
+//! and encrypted files stay JSON inside whatever the snapshot format.
+//!
+//! Banks are written to and read from a stream, so saving never builds the
+//! whole file as one string first (TOML, which has no streaming
+//! serializer, is the exception).
+
+use std::fmt;
+use std::io::{Read, Write};
+use std::path::Path;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::bank::Bank;
+use crate::errors::{BankError, BankResult, Cause};
+use crate::models::history::with_raw_history;
+use super::compression::Compression;
+
+/// A serialization format for a whole bank
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DataFormat {
+    /// Pretty-printed JSON, the default
+    #[default]
+    Json,
+    /// YAML, for reading and editing by hand
+    Yaml,
+    /// TOML
+    Toml,
+    /// MessagePack: compact binary, the fastest to save and load
+    #[serde(rename = "msgpack")]
+    MessagePack,
+}
+
+impl DataFormat {
+    /// Every format, JSON first
+    pub const ALL: [DataFormat; 4] = [DataFormat::Json, DataFormat::Yaml, DataFormat::Toml, DataFormat::MessagePack];
+
+    /// The format a file with this name is saved in: chosen by extension
+    /// (ignoring a compression extension such as `.gz`), JSON for any
+    /// other or none
+    pub fn from_path(path: &str) -> Self {
+        let path = Compression::strip_extension(path);
+        let extension = Path::new(path).extension().and_then(|ext| ext.to_str()).unwrap_or_default();
+        match extension.to_ascii_lowercase().as_str() {
+            "yaml" | "yml" => DataFormat::Yaml,
+            "toml" => DataFormat::Toml,
+            "msgpack" | "mpk" => DataFormat::MessagePack,
+            _ => DataFormat::Json,
+        }
+    }
+
+    /// The format of saved data, judged from its first bytes
+    ///
+    /// JSON starts with `{` and a MessagePack bank with a map marker. Text
+    /// whose first line (past blank lines and comments) is a `[table]` or
+    /// a `key = value` is TOML; any other text is YAML.
+    pub fn detect(data: &[u8]) -> Self {
+        let start = data.iter().position(|b| !b.is_ascii_whitespace()).map_or(&[][..], |i| &data[i..]);
+        match start.first() {
+            Some(b'{') => return DataFormat::Json,
+            Some(0x80..=0x8f | 0xde | 0xdf) => return DataFormat::MessagePack,
+            _ => {}
+        }
+
+        let text = String::from_utf8_lossy(start);
+        let first_line = text
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty() && !line.starts_with('#') && *line != "---")
+            .unwrap_or_default();
+        let is_toml = first_line.starts_with('[')
+            || first_line.find('=').is_some_and(|eq| first_line.find(':').is_none_or(|colon| eq < colon));
+        if is_toml {
+            DataFormat::Toml
+        } else {
+            DataFormat::Yaml
+        }
+    }
+
+    /// The usual file extension, without the dot
+    pub fn extension(self) -> &'static str {
+        match self {
+            DataFormat::Json => "json",
+            DataFormat::Yaml => "yaml",
+            DataFormat::Toml => "toml",
+            DataFormat::MessagePack => "msgpack",
+        }
+    }
+
+    /// Serializes the bank
+    ///
+    /// # Returns
+    /// * `Err(BankError::SerializationError)` - If the format cannot hold
+    ///   some value of the bank
+    pub fn encode(self, bank: &Bank) -> BankResult<Vec<u8>> {
+        let mut data = Vec::new();
+        self.write_to(bank, &mut data)?;
+        Ok(data)
+    }
+
+    /// Serializes the bank into `writer` as it goes
+    ///
+    /// # Returns
+    /// * `Err(BankError::SerializationError)` - If the format cannot hold
+    ///   some value of the bank or the writer fails
+    pub fn write_to<W: Write>(self, bank: &Bank, mut writer: W) -> BankResult<()> {
+        match self {
+            // Histories a lazy load never parsed are written back as is
+            DataFormat::Json => with_raw_history(|| serde_json::to_writer_pretty(writer, bank))?,
+            DataFormat::Yaml => serde_yaml::to_writer(writer, bank).map_err(serialization_error)?,
+            DataFormat::Toml => {
+                let text = toml::to_string(bank).map_err(serialization_error)?;
+                writer.write_all(text.as_bytes()).map_err(serialization_error)?;
+            }
+            // Fields by name rather than position, so that skipped
+            // optional fields and older files still line up
+            DataFormat::MessagePack => rmp_serde::encode::write_named(&mut writer, bank).map_err(serialization_error)?,
+        }
+        Ok(())
+    }
+
+    /// Parses a bank saved in this format at `location`
+    ///
+    /// # Returns
+    /// * `Err(BankError::StorageCorrupted)` - If the data is not a valid
+    ///   bank, with the parse error as its cause
+    pub fn decode(self, data: &[u8], location: &str) -> BankResult<Bank> {
+        match self {
+            // Much faster than going through a reader, above all for the
+            // raw histories of a lazy load
+            DataFormat::Json => serde_json::from_slice(data).map_err(|e| self.corrupted(location, Cause::new(e))),
+            _ => self.read_from(data, location),
+        }
+    }
+
+    /// Parses a bank in this format from `reader` as it goes
+    ///
+    /// # Returns
+    /// * `Err(BankError::StorageCorrupted)` - If the data is not a valid
+    ///   bank or cannot be read, with the error as its cause
+    pub fn read_from<R: Read>(self, mut reader: R, location: &str) -> BankResult<Bank> {
+        let parsed = match self {
+            DataFormat::Json => serde_json::from_reader(reader).map_err(Cause::new),
+            DataFormat::Yaml => serde_yaml::from_reader(reader).map_err(Cause::new),
+            DataFormat::Toml => {
+                let mut text = String::new();
+                reader
+                    .read_to_string(&mut text)
+                    .map_err(Cause::new)
+                    .and_then(|_| toml::from_str(&text).map_err(Cause::new))
+            }
+            DataFormat::MessagePack => rmp_serde::from_read(reader).map_err(Cause::new),
+        };
+        parsed.map_err(|cause| self.corrupted(location, cause))
+    }
+
+    /// The error for data at `location` that is not a valid bank
+    fn corrupted(self, location: &str, cause: Cause) -> BankError {
+        BankError::StorageCorrupted {
+            location: location.to_string(),
+            cause: cause.context(format!("reading {}", self)),
+        }
+    }
+}
+
+fn serialization_error(error: impl std::error::Error + Send + Sync + 'static) -> BankError {
+    BankError::SerializationError(Cause::new(error))
+}
+
+impl fmt::Display for DataFormat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DataFormat::Json => write!(f, "JSON"),
+            DataFormat::Yaml => write!(f, "YAML"),
+            DataFormat::Toml => write!(f, "TOML"),
+            DataFormat::MessagePack => write!(f, "MessagePack"),
+        }
+    }
+}
+
+impl FromStr for DataFormat {
+    type Err = BankError;
+
+    /// Parses a format name or extension, e.g. `yaml`, `yml` or `msgpack`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "json" => Ok(DataFormat::Json),
+            "yaml" | "yml" => Ok(DataFormat::Yaml),
+            "toml" => Ok(DataFormat::Toml),
+            "msgpack" | "mpk" | "messagepack" => Ok(DataFormat::MessagePack),
+            other => Err(BankError::InvalidInput(format!(
+                "unknown data format '{}' (expected json, yaml, toml or msgpack)",
+                other
+            ))),
+        }
+    }
+}
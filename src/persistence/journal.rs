@@ -0,0 +1,82 @@
+//! Journal file handling - append-only log next to the snapshot
+//!
+//! Demonstrates: Appending to files, line-oriented JSON (JSON Lines)
+//! https://jsonlines.org/
+//!
+//! Every mutation is appended as one JSON line to `<data file>.journal`.
+//! Loading replays the journal on top of the snapshot; saving a new
+//! snapshot makes the journal redundant, so it is removed.
+
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Write};
+
+use crate::bank::{Bank, JournalEntry};
+use crate::errors::{BankError, BankResult};
+
+/// Returns the journal path belonging to a data file
+pub fn journal_path(filename: &str) -> String {
+    format!("{}.journal", filename)
+}
+
+/// Appends entries to the journal and flushes them to disk
+///
+/// # Arguments
+/// * `filename` - Path of the data file (not the journal itself)
+/// * `entries` - Entries to append, in order
+pub fn append_journal(filename: &str, entries: &[JournalEntry]) -> BankResult<()> {
+    if entries.is_empty() {
+        return Ok(());
+    }
+
+    let mut lines = String::new();
+    for entry in entries {
+        let line = serde_json::to_string(entry)?;
+        lines.push_str(&line);
+        lines.push('\n');
+    }
+
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(journal_path(filename))?;
+
+    file.write_all(lines.as_bytes())
+        .and_then(|_| file.sync_data())
+        .map_err(BankError::from)
+}
+
+/// Replays the journal on top of a loaded snapshot
+///
+/// A partially written last line (from a crash mid-append) is ignored.
+///
+/// # Returns
+/// The number of entries that changed the bank state
+pub fn replay_journal(bank: &mut Bank, filename: &str) -> BankResult<usize> {
+    let contents = match fs::read_to_string(journal_path(filename)) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(0),
+        Err(e) => return Err(e.into()),
+    };
+
+    let mut applied = 0;
+    for line in contents.lines().filter(|l| !l.trim().is_empty()) {
+        let entry: JournalEntry = match serde_json::from_str(line) {
+            Ok(entry) => entry,
+            Err(_) => break, // Torn write: nothing after it can be trusted
+        };
+        if bank.apply_journal_entry(entry)? {
+            applied += 1;
+        }
+    }
+
+    Ok(applied)
+}
+
+/// Removes the journal once its entries are part of a snapshot
+pub fn clear_journal(filename: &str) -> BankResult<()> {
+    match fs::remove_file(journal_path(filename)) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(e.into()),
+    }
+}
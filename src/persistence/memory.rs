@@ -0,0 +1,74 @@
+//! In-memory storage - a `Storage` backend that never touches the disk
+//!
+//! Demonstrates: Implementing a trait for a test double, Mutex for
+//! interior mutability behind `&self` trait methods
+//!
+//! The snapshot is kept as serialized JSON and the journal as a list of
+//! entries, so a save/load round trip goes through the same serde code
+//! and journal replay as the file backends.
+
+use std::sync::{Mutex, MutexGuard};
+
+use crate::bank::{Bank, JournalEntry};
+use crate::errors::{BankError, BankResult};
+use super::storage::Storage;
+
+/// Keeps the bank in memory, for tests and embedding
+#[derive(Debug, Default)]
+pub struct MemoryStorage {
+    snapshot: Mutex<Option<String>>,
+    journal: Mutex<Vec<JournalEntry>>,
+}
+
+impl MemoryStorage {
+    /// An empty storage; `load_bank` fails until something is saved
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of journal entries recorded since the last full save
+    pub fn journal_len(&self) -> usize {
+        lock(&self.journal).len()
+    }
+}
+
+impl Storage for MemoryStorage {
+    fn save_bank(&self, bank: &Bank) -> BankResult<()> {
+        let json = serde_json::to_string(bank)?;
+        *lock(&self.snapshot) = Some(json);
+        lock(&self.journal).clear();
+        Ok(())
+    }
+
+    fn load_bank(&self) -> BankResult<Bank> {
+        let snapshot = lock(&self.snapshot);
+        let json = snapshot
+            .as_deref()
+            .ok_or_else(|| BankError::IoError("nothing saved yet".into()))?;
+        let mut bank: Bank = serde_json::from_str(json)?;
+
+        for entry in lock(&self.journal).iter() {
+            bank.apply_journal_entry(entry.clone())?;
+        }
+        Ok(bank)
+    }
+
+    fn append_journal(&self, bank: &Bank, entries: &[JournalEntry]) -> BankResult<()> {
+        // Without a snapshot there is nothing to replay on top of
+        if lock(&self.snapshot).is_none() {
+            return self.save_bank(bank);
+        }
+        lock(&self.journal).extend_from_slice(entries);
+        Ok(())
+    }
+
+    fn location(&self) -> String {
+        "memory".to_string()
+    }
+}
+
+/// Locks `mutex` even if a thread panicked while holding it; the data is
+/// only replaced or appended to whole, so it is never left half-written
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
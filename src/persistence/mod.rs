@@ -0,0 +1,279 @@
+//! Persistence module - handles data saving and loading
+//!
+//! Demonstrates: File I/O, serialization/deserialization with Serde
+//! https://serde.rs/
+//!
+//! The `Storage` trait abstracts over where data lives; the free functions
+//! below implement the data files used by `JsonFileStorage`: JSON by
+//! default, or another `DataFormat` chosen by the file's extension,
+//! optionally gzip- or zstd-compressed (see `Compression`). Snapshots are
+//! streamed to and from the file rather than built in memory first.
+//!
+//! `load_bank_lazy` loads a JSON data file without parsing the transaction
+//! histories: each account's history is parsed the first time it is used
+//! (see `models::History`), so a bank with millions of transactions starts
+//! quickly.
+
+mod archive;
+mod backup;
+mod batch;
+mod compression;
+mod csv;
+mod diff;
+mod encryption;
+mod format;
+mod journal;
+mod memory;
+mod storage;
+
+pub use archive::{archive_dir, FileArchive};
+pub use backup::{backup_path, list_backups, restore_backup, BackupInfo, DEFAULT_BACKUP_COUNT};
+pub use batch::load_batch;
+pub use compression::{Compression, Encoder};
+pub use self::csv::{
+    export_income_statement_csv, export_monthly_summaries_csv, export_transactions_csv, export_trial_balance_csv,
+    import_transactions_csv,
+};
+pub use diff::{diff, diff_banks, BalanceChange, BankDiff, CustomerChange, NewTransaction};
+pub use encryption::{decrypt, encrypt, is_encrypted, is_encrypted_file, EncryptedFileStorage};
+pub use format::DataFormat;
+pub use journal::{append_journal, journal_path};
+pub use memory::MemoryStorage;
+pub use storage::{JsonFileStorage, ReadOnlyStorage, Storage};
+
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+use crate::bank::Bank;
+use crate::errors::{BankError, BankResult, Cause};
+use crate::models::history::with_raw_history;
+
+/// Saves bank data to a JSON file
+///
+/// Demonstrates:
+/// - File I/O operations
+/// - Error conversion with map_err
+/// - Serialization with serde_json
+///
+/// # Arguments
+/// * `bank` - Reference to the bank to save
+/// * `filename` - Path to the file
+///
+/// The previous file is kept as a rotated backup (see `backup`), and the
+/// snapshot contains every journaled change, so the journal is removed
+/// afterwards.
+pub fn save_bank(bank: &Bank, filename: &str) -> BankResult<()> {
+    save_bank_with_backups(bank, filename, DEFAULT_BACKUP_COUNT)
+}
+
+/// Saves bank data keeping `backups` rotated copies of the previous file
+pub fn save_bank_with_backups(bank: &Bank, filename: &str, backups: usize) -> BankResult<()> {
+    save_bank_as(bank, filename, DataFormat::from_path(filename), Compression::from_path(filename), backups)
+}
+
+/// Saves bank data in `format` with `compression`, whatever the file's
+/// extension, keeping `backups` rotated copies of the previous file
+pub fn save_bank_as(
+    bank: &Bank,
+    filename: &str,
+    format: DataFormat,
+    compression: Compression,
+    backups: usize,
+) -> BankResult<()> {
+    backup::rotate_backups(filename, backups)?;
+    write_atomic_with(filename, |writer| write_snapshot(bank, writer, format, compression))?;
+
+    journal::clear_journal(filename)
+}
+
+/// Size of the buffer between the serializer and the compressor
+const SNAPSHOT_BUFFER: usize = 64 * 1024;
+
+/// Streams the bank through the compressor into `writer`
+///
+/// Serializers write a few bytes at a time; the buffer hands them to the
+/// compressor in large blocks, which it handles much faster.
+fn write_snapshot<W: Write>(bank: &Bank, writer: W, format: DataFormat, compression: Compression) -> BankResult<()> {
+    let mut encoder = compression.writer(writer)?;
+    let mut buffered = BufWriter::with_capacity(SNAPSHOT_BUFFER, &mut encoder);
+    format.write_to(bank, &mut buffered)?;
+    buffered.flush()?;
+    drop(buffered);
+    encoder.finish()?;
+    Ok(())
+}
+
+/// Writes a copy of the whole bank to `path`, e.g. to read it as YAML
+///
+/// Unlike a save this keeps no backups and leaves any journal alone.
+///
+/// # Arguments
+/// * `format` - The format to write; `None` picks it from the extension
+///
+/// The file is compressed if its name ends in `.gz` or `.zst`.
+///
+/// # Returns
+/// The format the file was written in
+pub fn export_bank(bank: &Bank, path: &str, format: Option<DataFormat>) -> BankResult<DataFormat> {
+    let format = format.unwrap_or_else(|| DataFormat::from_path(path));
+    write_atomic_with(path, |writer| write_snapshot(bank, writer, format, Compression::from_path(path)))?;
+    Ok(format)
+}
+
+/// Writes a file atomically: write to a temporary file, then rename
+///
+/// Demonstrates: Crash-safe file replacement
+/// A rename within one directory either fully happens or not at all, so
+/// readers see the old contents or the new ones, never a half-written file.
+/// The data is fsynced before the rename, and the directory after it, so
+/// the new contents survive a power loss.
+///
+/// # Arguments
+/// * `filename` - Final path of the file
+/// * `contents` - Complete new contents
+pub fn write_atomic(filename: &str, contents: &[u8]) -> BankResult<()> {
+    write_atomic_with(filename, |writer| Ok(writer.write_all(contents)?))
+}
+
+/// Writes a file atomically like `write_atomic`, with `write` streaming
+/// the contents into a buffered writer
+///
+/// If `write` fails the temporary file is removed and the file is left
+/// as it was.
+pub fn write_atomic_with<F>(filename: &str, write: F) -> BankResult<()>
+where
+    F: FnOnce(&mut BufWriter<File>) -> BankResult<()>,
+{
+    let tmp_path = format!("{}.tmp", filename);
+
+    let mut writer = BufWriter::new(File::create(&tmp_path)?);
+    let written = write(&mut writer).and_then(|_| {
+        let file = writer.into_inner().map_err(|e| e.into_error())?;
+        Ok(file.sync_all()?)
+    });
+    if let Err(e) = written {
+        let _ = fs::remove_file(&tmp_path);
+        return Err(e);
+    }
+
+    fs::rename(&tmp_path, filename)?;
+
+    sync_parent_dir(filename);
+    Ok(())
+}
+
+/// Flushes the directory entry of a renamed file (best effort)
+///
+/// Opening a directory for syncing is only supported on Unix-like systems,
+/// so failures are ignored.
+fn sync_parent_dir(filename: &str) {
+    let dir = match Path::new(filename).parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    if let Ok(dir) = File::open(dir) {
+        let _ = dir.sync_all();
+    }
+}
+
+/// Loads bank data from a JSON file
+///
+/// Demonstrates:
+/// - File reading
+/// - Deserialization
+/// - Error handling and conversion
+///
+/// Any journal left behind by a crash is replayed on top of the snapshot,
+/// then compacted into a fresh snapshot.
+///
+/// # Arguments
+/// * `filename` - Path to the file
+///
+/// # Returns
+/// * `Ok(Bank)` - The loaded bank
+/// * `Err(BankError)` - If file doesn't exist or is invalid
+pub fn load_bank(filename: &str) -> BankResult<Bank> {
+    load_bank_as(filename, DataFormat::from_path(filename), Compression::from_path(filename), false)
+}
+
+/// Loads bank data like `load_bank`, leaving each account's transaction
+/// history unparsed until it is first used
+///
+/// Only JSON data files (compressed or not) can be loaded lazily; other
+/// formats are loaded in full. Saving the bank as JSON again writes the
+/// histories that were never used back as they were, without parsing
+/// them.
+pub fn load_bank_lazy(filename: &str) -> BankResult<Bank> {
+    load_bank_as(filename, DataFormat::from_path(filename), Compression::from_path(filename), true)
+}
+
+/// Loads bank data like `load_bank` (lazily if `lazy`), compacting a
+/// replayed journal into a snapshot saved in `format` with `compression`
+fn load_bank_as(filename: &str, format: DataFormat, compression: Compression, lazy: bool) -> BankResult<Bank> {
+    let (bank, replayed) = read_with_journal(filename, lazy)?;
+
+    if replayed > 0 {
+        save_bank_as(&bank, filename, format, compression, DEFAULT_BACKUP_COUNT)?;
+    } else {
+        journal::clear_journal(filename)?;
+    }
+
+    Ok(bank)
+}
+
+/// Loads bank data like `load_bank`, but never writes
+///
+/// A leftover journal is replayed in memory and left in place.
+pub fn read_bank(filename: &str) -> BankResult<Bank> {
+    read_with_journal(filename, false).map(|(bank, _)| bank)
+}
+
+/// Reads the snapshot (lazily if `lazy`) and replays its journal; returns
+/// the bank and the number of journal entries replayed
+fn read_with_journal(filename: &str, lazy: bool) -> BankResult<(Bank, usize)> {
+    let mut reader = BufReader::new(File::open(filename)?);
+
+    if is_encrypted(reader.fill_buf()?) {
+        return Err(BankError::EncryptionError(
+            "data file is encrypted; a passphrase is required".to_string(),
+        ));
+    }
+
+    let mut bank = parse_snapshot(reader, filename, lazy)?;
+    let replayed = journal::replay_journal(&mut bank, filename)?;
+    Ok((bank, replayed))
+}
+
+/// Parses a snapshot read from `location`, in whichever `DataFormat` and
+/// `Compression` it was saved with
+///
+/// Both are detected from the first bytes, which a `BufRead` can look at
+/// without consuming them; the rest is decompressed and parsed as it is
+/// read. With `lazy`, a JSON snapshot keeps its transaction histories as
+/// unparsed text.
+///
+/// # Returns
+/// * `Err(BankError::StorageCorrupted)` - If the data is not a valid bank,
+///   with the parse error as its cause
+fn parse_snapshot<R: BufRead>(mut reader: R, location: &str, lazy: bool) -> BankResult<Bank> {
+    let compression = Compression::detect(reader.fill_buf()?);
+    let mut reader = BufReader::new(compression.reader(reader)?);
+
+    let format = match reader.fill_buf() {
+        Ok(start) => DataFormat::detect(start),
+        Err(e) => {
+            return Err(BankError::StorageCorrupted {
+                location: location.to_string(),
+                cause: Cause::new(e).context(format!("reading {}", compression)),
+            })
+        }
+    };
+    if lazy && format == DataFormat::Json {
+        // The histories are kept as text anyway, so read it all at once
+        let mut data = Vec::new();
+        reader.read_to_end(&mut data)?;
+        with_raw_history(|| format.decode(&data, location))
+    } else {
+        format.read_from(reader, location)
+    }
+}
@@ -0,0 +1,271 @@
+//! Storage backends - pluggable persistence
+//!
+//! Demonstrates: Trait objects, dynamic dispatch, default implementations
+//! https://doc.rust-lang.org/book/ch17-02-trait-objects.html
+
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::bank::{Bank, HistoryArchive, JournalEntry};
+use crate::errors::{BankError, BankResult};
+use crate::models::{AccountId, Transaction};
+use super::archive::FileArchive;
+use super::backup::{self, BackupInfo, DEFAULT_BACKUP_COUNT};
+use super::compression::Compression;
+use super::format::DataFormat;
+
+/// A backend capable of saving and loading the whole bank
+///
+/// Demonstrates: Trait as an extension point
+/// Implement this trait to store bank data somewhere other than a local
+/// JSON file (a database, a remote service, memory for tests, ...).
+/// `Send + Sync` lets a boxed storage be shared with other threads.
+pub trait Storage: Send + Sync {
+    /// Persists the complete bank state
+    fn save_bank(&self, bank: &Bank) -> BankResult<()>;
+
+    /// Loads the previously saved bank state
+    ///
+    /// # Returns
+    /// * `Err(BankError)` - If nothing was saved yet or the data is invalid
+    fn load_bank(&self) -> BankResult<Bank>;
+
+    /// Loads the saved bank without writing anything
+    ///
+    /// Backends whose `load_bank` tidies up on the way (e.g. by compacting
+    /// a journal) override this; the default just calls `load_bank`.
+    fn read_bank(&self) -> BankResult<Bank> {
+        self.load_bank()
+    }
+
+    /// Durably records mutations made since the last call
+    ///
+    /// `bank` is the current state, which a backend may snapshot instead
+    /// of appending. The default does nothing, so backends without a
+    /// journal only persist on `save_bank`.
+    fn append_journal(&self, bank: &Bank, entries: &[JournalEntry]) -> BankResult<()> {
+        let _ = (bank, entries);
+        Ok(())
+    }
+
+    /// Lists the available backups, most recent first
+    ///
+    /// The default reports none.
+    fn list_backups(&self) -> Vec<BackupInfo> {
+        Vec::new()
+    }
+
+    /// Restores backup number `index` and returns the restored bank
+    ///
+    /// The default reports that backups are not supported.
+    fn restore_backup(&self, index: usize) -> BankResult<Bank> {
+        let _ = index;
+        Err(BankError::IoError(format!("{} does not keep backups", self.location()).into()))
+    }
+
+    /// Where transactions archived from this storage's bank are kept
+    ///
+    /// The default has none, so its banks cannot be archived.
+    fn history_archive(&self) -> Option<Arc<dyn HistoryArchive>> {
+        None
+    }
+
+    /// Human-readable description of where data is stored
+    fn location(&self) -> String {
+        "custom storage".to_string()
+    }
+}
+
+/// Stores the bank in a local file, as pretty-printed JSON unless the
+/// file's extension names another `DataFormat`, compressed if it ends in
+/// `.gz` or `.zst`
+///
+/// This is the default backend used by the CLI. Each save keeps rotated
+/// backups of the previous file (`<path>.1`, `<path>.2`, ...). Loading
+/// detects the format and compression from the contents, and attaches the
+/// `FileArchive` next to the file to the bank it loads.
+#[derive(Debug, Clone)]
+pub struct JsonFileStorage {
+    /// Path to the data file
+    path: String,
+
+    /// Number of rotated backups to keep
+    backups: usize,
+
+    /// Format the file is saved in
+    format: DataFormat,
+
+    /// Compression the file is saved with
+    compression: Compression,
+
+    /// Leave transaction histories unparsed until they are used
+    lazy: bool,
+}
+
+impl JsonFileStorage {
+    /// Creates a storage backed by the file at `path`
+    pub fn new(path: impl Into<String>) -> Self {
+        let path = path.into();
+        Self {
+            format: DataFormat::from_path(&path),
+            compression: Compression::from_path(&path),
+            lazy: false,
+            path,
+            backups: DEFAULT_BACKUP_COUNT,
+        }
+    }
+
+    /// Sets how many rotated backups to keep (0 disables backups)
+    pub fn with_backups(mut self, backups: usize) -> Self {
+        self.backups = backups;
+        self
+    }
+
+    /// Saves in `format` instead of the one the extension names
+    pub fn with_format(mut self, format: DataFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Returns the format the file is saved in
+    pub fn format(&self) -> DataFormat {
+        self.format
+    }
+
+    /// Compresses the file with `compression` instead of the one the
+    /// extension names
+    pub fn with_compression(mut self, compression: Compression) -> Self {
+        self.compression = compression;
+        self
+    }
+
+    /// Returns the compression the file is saved with
+    pub fn compression(&self) -> Compression {
+        self.compression
+    }
+
+    /// Loads transaction histories on demand, one account at a time (see
+    /// `load_bank_lazy`)
+    pub fn with_lazy_load(mut self, lazy: bool) -> Self {
+        self.lazy = lazy;
+        self
+    }
+
+    /// Returns the path of the data file
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+}
+
+impl Storage for JsonFileStorage {
+    fn save_bank(&self, bank: &Bank) -> BankResult<()> {
+        super::save_bank_as(bank, &self.path, self.format, self.compression, self.backups)
+    }
+
+    fn load_bank(&self) -> BankResult<Bank> {
+        let mut bank = super::load_bank_as(&self.path, self.format, self.compression, self.lazy)?;
+        bank.set_archive(Arc::new(FileArchive::for_data_file(&self.path)));
+        Ok(bank)
+    }
+
+    fn read_bank(&self) -> BankResult<Bank> {
+        let (mut bank, _) = super::read_with_journal(&self.path, self.lazy)?;
+        bank.set_archive(Arc::new(FileArchive::for_data_file(&self.path)));
+        Ok(bank)
+    }
+
+    fn append_journal(&self, bank: &Bank, entries: &[JournalEntry]) -> BankResult<()> {
+        // Without a snapshot there is nothing to replay on top of
+        if !Path::new(&self.path).exists() {
+            return self.save_bank(bank);
+        }
+        super::append_journal(&self.path, entries)
+    }
+
+    fn list_backups(&self) -> Vec<BackupInfo> {
+        backup::list_backups(&self.path, self.backups)
+    }
+
+    fn restore_backup(&self, index: usize) -> BankResult<Bank> {
+        backup::restore_backup(&self.path, index, self.backups)?;
+        self.load_bank()
+    }
+
+    /// The `FileArchive` next to the data file
+    fn history_archive(&self) -> Option<Arc<dyn HistoryArchive>> {
+        Some(Arc::new(FileArchive::for_data_file(&self.path)))
+    }
+
+    fn location(&self) -> String {
+        self.path.clone()
+    }
+}
+
+/// Wraps another backend so that nothing is ever written
+///
+/// Loading goes through `Storage::read_bank`, so even a leftover journal
+/// stays as it is; every save, journal append, backup restore and
+/// archival fails with `BankError::ReadOnly`.
+pub struct ReadOnlyStorage {
+    inner: Box<dyn Storage>,
+}
+
+impl ReadOnlyStorage {
+    /// Wraps `inner`
+    pub fn new(inner: Box<dyn Storage>) -> Self {
+        Self { inner }
+    }
+}
+
+impl Storage for ReadOnlyStorage {
+    fn save_bank(&self, _bank: &Bank) -> BankResult<()> {
+        Err(BankError::ReadOnly(self.inner.location()))
+    }
+
+    fn load_bank(&self) -> BankResult<Bank> {
+        self.inner.read_bank()
+    }
+
+    fn append_journal(&self, _bank: &Bank, _entries: &[JournalEntry]) -> BankResult<()> {
+        Err(BankError::ReadOnly(self.inner.location()))
+    }
+
+    fn list_backups(&self) -> Vec<BackupInfo> {
+        self.inner.list_backups()
+    }
+
+    fn restore_backup(&self, _index: usize) -> BankResult<Bank> {
+        Err(BankError::ReadOnly(self.inner.location()))
+    }
+
+    fn history_archive(&self) -> Option<Arc<dyn HistoryArchive>> {
+        let inner = self.inner.history_archive()?;
+        Some(Arc::new(ReadOnlyArchive { inner, location: self.inner.location() }))
+    }
+
+    fn location(&self) -> String {
+        format!("{} (read-only)", self.inner.location())
+    }
+}
+
+/// The archive of a `ReadOnlyStorage`: archived history can be read, but
+/// nothing more is archived
+#[derive(Debug)]
+struct ReadOnlyArchive {
+    inner: Arc<dyn HistoryArchive>,
+    location: String,
+}
+
+impl HistoryArchive for ReadOnlyArchive {
+    fn store(&self, _account_id: &AccountId, _transactions: &[Transaction]) -> BankResult<()> {
+        Err(BankError::ReadOnly(self.location.clone()))
+    }
+
+    fn load(&self, account_id: &AccountId) -> BankResult<Vec<Transaction>> {
+        self.inner.load(account_id)
+    }
+
+    fn load_page(&self, account_id: &AccountId, start: usize, len: usize) -> BankResult<Vec<Transaction>> {
+        self.inner.load_page(account_id, start, len)
+    }
+}
@@ -0,0 +1,256 @@
+//! Request handlers for the REST API
+//!
+//! Demonstrates: axum extractors (State, Path, Query, Json), IntoResponse
+//! for mapping domain errors onto HTTP status codes
+
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use chrono::NaiveDate;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::bank::Bank;
+use crate::clock::end_of_day;
+use crate::errors::{BankError, BankResult};
+use crate::models::{AccountType, CustomerId, Money};
+use super::AppState;
+
+/// A `BankError` turned into an HTTP error response
+#[derive(Debug)]
+pub struct ApiError(pub BankError);
+
+impl From<BankError> for ApiError {
+    fn from(error: BankError) -> Self {
+        ApiError(error)
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let status = match &self.0 {
+            BankError::CustomerNotFound(_)
+            | BankError::AccountNotFound(_)
+            | BankError::ScheduleNotFound(_)
+            | BankError::BeneficiaryNotFound(_)
+            | BankError::WebhookNotFound(_)
+            | BankError::CardNotFound(_)
+            | BankError::MerchantNotFound(_)
+            | BankError::EnvelopeNotFound(_)
+            | BankError::BudgetNotFound(_)
+            | BankError::TermDepositNotFound(_)
+            | BankError::TransactionNotFound(_) => StatusCode::NOT_FOUND,
+            BankError::CustomerAlreadyExists(_) => StatusCode::CONFLICT,
+            BankError::InvalidPin { .. }
+            | BankError::PinNotSet(_)
+            | BankError::AuthenticationFailed { .. } => StatusCode::UNAUTHORIZED,
+            BankError::PinLocked { .. } | BankError::ReadOnly(_) => StatusCode::FORBIDDEN,
+            BankError::InsufficientFunds { .. }
+            | BankError::InsufficientVaultCash { .. }
+            | BankError::WithdrawalLimitReached { .. }
+            | BankError::LimitExceeded(_)
+            | BankError::AccountFrozen(_)
+            | BankError::AccountClosed(_)
+            | BankError::CustomerDeactivated(_)
+            | BankError::CardDeclined(_)
+            | BankError::EnvelopeLocked(_)
+            | BankError::AccountLocked { .. } => StatusCode::UNPROCESSABLE_ENTITY,
+            BankError::InvalidInput(_)
+            | BankError::ValidationError { .. }
+            | BankError::InvalidAmount(_)
+            | BankError::InvalidTransfer(_) => StatusCode::BAD_REQUEST,
+            BankError::IoError(_)
+            | BankError::SerializationError(_)
+            | BankError::StorageCorrupted { .. }
+            | BankError::EncryptionError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+
+        let body = json!({ "ok": false, "error": self.0.to_string(), "code": self.0.code(), "kind": self.0.kind() });
+        let mut response = (status, Json(body)).into_response();
+        // For the metrics middleware to count
+        response.extensions_mut().insert(self.0);
+        response
+    }
+}
+
+/// Result type returned by every handler
+type ApiResult = Result<Json<Value>, ApiError>;
+
+/// Wraps a successful result in the response envelope
+fn ok(result: Value) -> ApiResult {
+    Ok(Json(json!({ "ok": true, "result": result })))
+}
+
+/// Runs `operation` on the locked bank and journals any changes it made
+///
+/// A journal write failure is logged but does not fail the request: the
+/// change is already applied in memory and goes out with the next save.
+fn with_bank<T>(
+    state: &AppState,
+    operation: impl FnOnce(&mut Bank) -> BankResult<T>,
+) -> BankResult<T> {
+    let mut bank = state.bank.write();
+    let result = operation(&mut bank);
+
+    let entries = bank.take_journal();
+    if !entries.is_empty() {
+        if let Err(e) = state.storage.append_journal(&bank, &entries) {
+            eprintln!("⚠️  Could not save changes: {}", e);
+        }
+    }
+    result
+}
+
+/// Body of `POST /customers`
+#[derive(Debug, Deserialize)]
+pub struct RegisterRequest {
+    pub name: String,
+    pub email: String,
+}
+
+/// Account types accepted by `POST /customers/{id}/account`
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AccountKind {
+    #[default]
+    Checking,
+    Savings,
+    Fixed,
+}
+
+/// Body of `POST /customers/{id}/account`
+#[derive(Debug, Deserialize)]
+pub struct OpenAccountRequest {
+    #[serde(default)]
+    pub deposit: Money,
+    #[serde(default)]
+    pub account_type: AccountKind,
+    /// Term for fixed deposits
+    #[serde(default = "default_term_months")]
+    pub term_months: u32,
+}
+
+fn default_term_months() -> u32 {
+    12
+}
+
+/// Body of the deposit and withdraw endpoints
+#[derive(Debug, Deserialize)]
+pub struct AmountRequest {
+    pub amount: Money,
+}
+
+/// Body of `POST /transfers`
+#[derive(Debug, Deserialize)]
+pub struct TransferRequest {
+    pub from: CustomerId,
+    pub to: CustomerId,
+    pub amount: Money,
+}
+
+/// Query of `GET /customers/{id}/statement`; dates are `YYYY-MM-DD`
+#[derive(Debug, Deserialize)]
+pub struct StatementQuery {
+    pub from: String,
+    /// Last day of the period (inclusive)
+    pub to: String,
+}
+
+/// `GET /customers`
+pub async fn list_customers(State(state): State<AppState>) -> ApiResult {
+    let bank = state.bank.read();
+    ok(json!(bank.list_customers()))
+}
+
+/// `POST /customers`
+pub async fn register_customer(
+    State(state): State<AppState>,
+    Json(request): Json<RegisterRequest>,
+) -> ApiResult {
+    let customer_id = with_bank(&state, |bank| {
+        bank.register_customer(request.name, request.email)
+    })?;
+    ok(json!({ "customer_id": customer_id }))
+}
+
+/// `GET /customers/{id}`
+pub async fn get_customer(State(state): State<AppState>, Path(id): Path<CustomerId>) -> ApiResult {
+    let bank = state.bank.read();
+    ok(json!(bank.get_customer(&id)?))
+}
+
+/// `POST /customers/{id}/account`
+pub async fn open_account(
+    State(state): State<AppState>,
+    Path(id): Path<CustomerId>,
+    Json(request): Json<OpenAccountRequest>,
+) -> ApiResult {
+    let account_type = match request.account_type {
+        AccountKind::Checking => AccountType::Checking,
+        AccountKind::Savings => AccountType::Savings,
+        AccountKind::Fixed => AccountType::fixed_deposit(request.term_months)
+            .ok_or_else(|| BankError::InvalidInput("invalid term".to_string()))?,
+    };
+
+    let account_id = with_bank(&state, |bank| {
+        bank.create_account_for_customer(&id, request.deposit, account_type)
+    })?;
+    ok(json!({ "account_id": account_id }))
+}
+
+/// `POST /customers/{id}/deposit`
+pub async fn deposit(
+    State(state): State<AppState>,
+    Path(id): Path<CustomerId>,
+    Json(request): Json<AmountRequest>,
+) -> ApiResult {
+    let balance = with_bank(&state, |bank| bank.deposit(&id, request.amount))?;
+    ok(json!({ "balance": balance }))
+}
+
+/// `POST /customers/{id}/withdraw`
+pub async fn withdraw(
+    State(state): State<AppState>,
+    Path(id): Path<CustomerId>,
+    Json(request): Json<AmountRequest>,
+) -> ApiResult {
+    let balance = with_bank(&state, |bank| bank.withdraw(&id, request.amount))?;
+    ok(json!({ "balance": balance }))
+}
+
+/// `GET /customers/{id}/transactions`
+pub async fn transactions(State(state): State<AppState>, Path(id): Path<CustomerId>) -> ApiResult {
+    let bank = state.bank.read();
+    let mut history = bank.get_transaction_history(&id)?.collect::<BankResult<Vec<_>>>()?;
+    history.reverse();
+    ok(json!(history))
+}
+
+/// `GET /customers/{id}/statement?from=YYYY-MM-DD&to=YYYY-MM-DD`
+pub async fn statement(
+    State(state): State<AppState>,
+    Path(id): Path<CustomerId>,
+    Query(query): Query<StatementQuery>,
+) -> ApiResult {
+    let from = parse_date(&query.from)?;
+    let to = end_of_day(parse_date(&query.to)?)?;
+
+    let statement = state.bank.statement(&id, from.and_hms_opt(0, 0, 0).unwrap().and_utc(), to)?;
+    ok(json!(statement))
+}
+
+/// `POST /transfers`
+pub async fn transfer(
+    State(state): State<AppState>,
+    Json(request): Json<TransferRequest>,
+) -> ApiResult {
+    with_bank(&state, |bank| bank.transfer(&request.from, &request.to, request.amount))?;
+    ok(json!({ "transferred": request.amount }))
+}
+
+/// Parses a `YYYY-MM-DD` query parameter
+fn parse_date(date: &str) -> BankResult<NaiveDate> {
+    NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .map_err(|_| BankError::InvalidInput(format!("invalid date '{}'", date)))
+}
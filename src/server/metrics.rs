@@ -0,0 +1,44 @@
+//! Request metrics and the `/metrics` endpoint
+//!
+//! Demonstrates: axum middleware with `from_fn_with_state`, passing data
+//! from a response to middleware through response extensions
+
+use std::time::Instant;
+
+use axum::extract::{MatchedPath, Request, State};
+use axum::http::header;
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+
+use crate::errors::BankError;
+use super::AppState;
+
+/// Times each API request and counts the `BankError` it failed with
+///
+/// Requests are labelled by route, e.g. `POST /customers/{id}/deposit`,
+/// so IDs don't create a new series per customer.
+pub async fn track(State(state): State<AppState>, request: Request, next: Next) -> Response {
+    let operation = match request.extensions().get::<MatchedPath>() {
+        Some(path) => format!("{} {}", request.method(), path.as_str()),
+        None => request.method().to_string(),
+    };
+
+    let started = Instant::now();
+    let response = next.run(request).await;
+    state.metrics.observe(&operation, started.elapsed());
+
+    // Set by `ApiError::into_response`
+    if let Some(error) = response.extensions().get::<BankError>() {
+        state.metrics.record_error(error);
+    }
+    response
+}
+
+/// `GET /metrics`
+pub async fn export(State(state): State<AppState>) -> impl IntoResponse {
+    let snapshot = state.metrics.snapshot(&state.bank.read());
+    (
+        [(header::CONTENT_TYPE, state.exporter.content_type())],
+        state.exporter.export(&snapshot),
+    )
+}
@@ -0,0 +1,104 @@
+//! Server module - HTTP REST API for the bank (`server` feature)
+//!
+//! Demonstrates: async Rust with tokio, axum routing and extractors,
+//! sharing a cloneable `SharedBank` handle between request handlers
+//! https://docs.rs/axum/latest/axum/
+//!
+//! The server uses the same `SharedBank` and `Storage` backend as the
+//! interactive CLI. Every response uses the command-mode envelope:
+//! `{"ok": true, "result": ...}` or `{"ok": false, "error": "..."}`.
+//!
+//! | Method | Path                               | Action                   |
+//! |--------|------------------------------------|--------------------------|
+//! | GET    | `/customers`                       | List customers           |
+//! | POST   | `/customers`                       | Register a customer      |
+//! | GET    | `/customers/{id}`                  | Customer and account     |
+//! | POST   | `/customers/{id}/account`          | Open an account          |
+//! | POST   | `/customers/{id}/deposit`          | Deposit money            |
+//! | POST   | `/customers/{id}/withdraw`         | Withdraw money           |
+//! | GET    | `/customers/{id}/transactions`     | Transaction history      |
+//! | GET    | `/customers/{id}/statement`        | Statement (`?from=&to=`) |
+//! | POST   | `/transfers`                       | Transfer money           |
+//! | GET    | `/metrics`                         | Metrics (Prometheus)     |
+//!
+//! `/metrics` answers in the exporter's format rather than the envelope.
+
+use std::io;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use axum::middleware;
+use axum::routing::{get, post};
+use axum::Router;
+
+use crate::bank::SharedBank;
+use crate::metrics::{Metrics, MetricsExporter, PrometheusExporter};
+use crate::persistence::Storage;
+
+mod handlers;
+mod metrics;
+
+pub use handlers::ApiError;
+
+/// State shared by all request handlers
+#[derive(Clone)]
+pub struct AppState {
+    /// The bank, shared exactly like in the interactive CLI
+    pub bank: SharedBank,
+
+    /// Backend that receives the journal of every change
+    pub storage: Arc<dyn Storage>,
+
+    /// Request, error and transaction counts served at `/metrics`
+    pub metrics: Arc<Metrics>,
+
+    /// Format `/metrics` is served in
+    pub exporter: Arc<dyn MetricsExporter>,
+}
+
+/// Builds the router with all API endpoints
+///
+/// Exposed separately from `serve` so the API can be exercised in tests
+/// without binding a socket. Metrics are served in the Prometheus format.
+pub fn router(bank: SharedBank, storage: Arc<dyn Storage>) -> Router {
+    router_with_metrics(bank, storage, Arc::new(Metrics::new()), Arc::new(PrometheusExporter))
+}
+
+/// Builds the router, recording into `metrics` and serving them with `exporter`
+///
+/// `metrics` is registered as an observer on the bank to count transactions.
+pub fn router_with_metrics(
+    bank: SharedBank,
+    storage: Arc<dyn Storage>,
+    metrics: Arc<Metrics>,
+    exporter: Arc<dyn MetricsExporter>,
+) -> Router {
+    bank.write().add_observer(metrics.clone());
+    let state = AppState { bank, storage, metrics, exporter };
+
+    Router::new()
+        .route(
+            "/customers",
+            get(handlers::list_customers).post(handlers::register_customer),
+        )
+        .route("/customers/{id}", get(handlers::get_customer))
+        .route("/customers/{id}/account", post(handlers::open_account))
+        .route("/customers/{id}/deposit", post(handlers::deposit))
+        .route("/customers/{id}/withdraw", post(handlers::withdraw))
+        .route("/customers/{id}/transactions", get(handlers::transactions))
+        .route("/customers/{id}/statement", get(handlers::statement))
+        .route("/transfers", post(handlers::transfer))
+        // Only the routes above are timed, not scrapes of /metrics
+        .route_layer(middleware::from_fn_with_state(state.clone(), metrics::track))
+        .route("/metrics", get(metrics::export))
+        .with_state(state)
+}
+
+/// Serves the API on `0.0.0.0:<port>` until the process is stopped
+pub async fn serve(bank: SharedBank, storage: Arc<dyn Storage>, port: u16) -> io::Result<()> {
+    let addr = SocketAddr::from(([0, 0, 0, 0], port));
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    eprintln!("🌐 Serving the bank API on http://{}", listener.local_addr()?);
+
+    axum::serve(listener, router(bank, storage)).await
+}
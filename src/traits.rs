@@ -2,17 +2,37 @@
 //!
 //! Demonstrates: Trait definition and implementation
 //! https://doc.rust-lang.org/book/ch10-02-traits.html
+//!
+//! `Summarizable` describes a value in words for people; `Auditable`
+//! lists its facts as key/value pairs, so one renderer can show any record
+//! the same way (see `cli::Renderer::report`).
+
+use serde::Serialize;
 
-use crate::models::Customer;
+use crate::format;
+use crate::models::{Account, AccountStatus, Customer, CustomerStatus, Transaction};
 use crate::bank::Bank;
 
 /// Trait for objects that can provide a summary
 ///
-/// Demonstrates: Custom trait definition with a single method
+/// Demonstrates: Custom trait definition with a required method and a
+/// default method built on it
 /// Traits define shared behavior in an abstract way
 pub trait Summarizable {
-    /// Returns a human-readable summary of the object
+    /// Returns a one-line, human-readable summary of the object
     fn summary(&self) -> String;
+
+    /// Returns the summary followed by one indented line per detail
+    ///
+    /// Types with nothing more to say keep the default, the summary alone.
+    fn detailed_summary(&self) -> String {
+        self.summary()
+    }
+}
+
+/// Appends `  label: value` on a line of its own
+fn detail(text: &mut String, label: &str, value: impl std::fmt::Display) {
+    text.push_str(&format!("\n  {}: {}", label, value));
 }
 
 /// Implementation of Summarizable for Customer
@@ -21,18 +41,112 @@ pub trait Summarizable {
 impl Summarizable for Customer {
     fn summary(&self) -> String {
         let account_info = match &self.account {
-            Some(acc) => {
-                format!("Account: {}, Balance: ${:.2}", &acc.id[..8], acc.balance)
-            }
+            Some(acc) => acc.summary(),
             None => "No account".to_string(),
         };
+        let status = match self.status {
+            CustomerStatus::Active => String::new(),
+            status => format!(" [{}]", status),
+        };
         format!(
-            "Customer: {} ({}), {}",
+            "Customer: {} ({}){}, {}",
             self.name,
-            &self.id[..8],
+            self.id.short(),
+            status,
             account_info
         )
     }
+
+    fn detailed_summary(&self) -> String {
+        let mut text = format!("Customer: {} ({})", self.name, self.id);
+        detail(&mut text, "Email", &self.email);
+        if let Some(phone) = &self.phone {
+            detail(&mut text, "Phone", phone);
+        }
+        detail(&mut text, "Status", self.status);
+        detail(&mut text, "Registered", format::date(&self.registered_at));
+        match &self.account {
+            Some(account) => {
+                for line in account.detailed_summary().lines() {
+                    text.push_str(&format!("\n  {}", line));
+                }
+            }
+            None => detail(&mut text, "Account", "none"),
+        }
+        text
+    }
+}
+
+/// Implementation of Summarizable for Account
+impl Summarizable for Account {
+    fn summary(&self) -> String {
+        // Prefer the short account number; older accounts may lack one
+        let account_ref = match self.number {
+            Some(number) => number.to_string(),
+            None => self.id.short().to_string(),
+        };
+        let status = match self.status {
+            AccountStatus::Active => String::new(),
+            status => format!(" [{}]", status),
+        };
+        format!(
+            "Account: {} ({}){}, Balance: {}",
+            account_ref,
+            self.account_type,
+            status,
+            self.balance.display()
+        )
+    }
+
+    fn detailed_summary(&self) -> String {
+        let mut text = match self.number {
+            Some(number) => format!("Account: {} ({})", number, self.id),
+            None => format!("Account: {}", self.id),
+        };
+        detail(&mut text, "Type", &self.account_type);
+        detail(&mut text, "Status", self.status);
+        detail(&mut text, "Balance", self.balance.display());
+        detail(&mut text, "Available", self.available_balance().display());
+        detail(&mut text, "Opened", format::date(&self.created_at));
+        detail(&mut text, "Transactions", self.transactions.len());
+        detail(&mut text, "Last Activity", format::datetime(&self.last_activity()));
+        text
+    }
+}
+
+/// Implementation of Summarizable for Transaction
+///
+/// The summary is the transaction's `Display` form, as shown in histories.
+impl Summarizable for Transaction {
+    fn summary(&self) -> String {
+        self.to_string()
+    }
+
+    fn detailed_summary(&self) -> String {
+        let mut text = format!("Transaction: {}", self.id);
+        detail(&mut text, "Type", &self.transaction_type);
+        detail(&mut text, "Amount", self.amount.display());
+        detail(&mut text, "Balance After", self.balance_after.display());
+        detail(&mut text, "Date", format::datetime(&self.timestamp));
+        detail(&mut text, "Status", self.status);
+        if let Some(description) = &self.description {
+            detail(&mut text, "Description", description);
+        }
+        if let Some(category) = &self.category {
+            detail(&mut text, "Category", category);
+        }
+        if !self.tags.is_empty() {
+            let tags: Vec<String> = self.tags.iter().map(|tag| format!("#{}", tag)).collect();
+            detail(&mut text, "Tags", tags.join(" "));
+        }
+        if let Some(reversal) = &self.reversed_by {
+            detail(&mut text, "Reversed By", reversal);
+        }
+        if let Some(reason) = &self.reversal_reason {
+            detail(&mut text, "Reversal Reason", reason);
+        }
+        text
+    }
 }
 
 /// Implementation of Summarizable for Bank
@@ -41,11 +155,163 @@ impl Summarizable for Customer {
 impl Summarizable for Bank {
     fn summary(&self) -> String {
         format!(
-            "Bank: {}, Customers: {}, Total Balance: ${:.2}, Transactions: {}",
+            "Bank: {}, Customers: {}, Total Balance: {}, Transactions: {}",
             self.name,
             self.list_customers().len(),
-            self.total_bank_balance(),
+            self.total_bank_balance().display(),
             self.total_transactions
         )
     }
+
+    fn detailed_summary(&self) -> String {
+        let customers = self.list_customers();
+        let accounts = customers.iter().filter(|c| c.account.is_some()).count();
+        let mut text = format!("Bank: {}", self.name);
+        detail(&mut text, "Customers", customers.len());
+        detail(&mut text, "Accounts", accounts);
+        detail(&mut text, "Total Balance", self.total_bank_balance().display());
+        detail(&mut text, "Transactions", self.total_transactions);
+        if let Some(vault) = self.vault() {
+            detail(&mut text, "Vault Cash", vault.display());
+        }
+        text
+    }
+}
+
+/// One fact about an audited record, e.g. `balance` = `90.00`
+///
+/// Keys are snake_case; values are written so they sort and parse well:
+/// amounts without a currency symbol, times in RFC 3339.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AuditFact {
+    pub key: &'static str,
+    pub value: String,
+}
+
+impl AuditFact {
+    pub fn new(key: &'static str, value: impl ToString) -> Self {
+        Self { key, value: value.to_string() }
+    }
+}
+
+/// Trait for records that can list their facts for an audit
+///
+/// Demonstrates: A trait used through trait objects (`&dyn Auditable`),
+/// so a single function renders every kind of record
+pub trait Auditable {
+    /// What kind of record this is, e.g. `customer`
+    fn audit_kind(&self) -> &'static str;
+
+    /// The record's facts in the order they are shown, identifying ones first
+    ///
+    /// Facts that do not apply (a phone number nobody gave) are left out.
+    fn audit_facts(&self) -> Vec<AuditFact>;
+}
+
+impl Auditable for Bank {
+    fn audit_kind(&self) -> &'static str {
+        "bank"
+    }
+
+    fn audit_facts(&self) -> Vec<AuditFact> {
+        let customers = self.list_customers();
+        let accounts = customers.iter().filter(|c| c.account.is_some()).count();
+        let mut facts = vec![
+            AuditFact::new("name", &self.name),
+            AuditFact::new("customers", customers.len()),
+            AuditFact::new("accounts", accounts),
+            AuditFact::new("total_balance", self.total_bank_balance()),
+            AuditFact::new("total_transactions", self.total_transactions),
+        ];
+        if let Some(vault) = self.vault() {
+            facts.push(AuditFact::new("vault_cash", vault));
+        }
+        facts
+    }
+}
+
+impl Auditable for Customer {
+    fn audit_kind(&self) -> &'static str {
+        "customer"
+    }
+
+    fn audit_facts(&self) -> Vec<AuditFact> {
+        let mut facts = vec![
+            AuditFact::new("id", &self.id),
+            AuditFact::new("name", &self.name),
+            AuditFact::new("email", &self.email),
+        ];
+        if let Some(phone) = &self.phone {
+            facts.push(AuditFact::new("phone", phone));
+        }
+        facts.push(AuditFact::new("status", self.status));
+        facts.push(AuditFact::new("registered_at", self.registered_at.to_rfc3339()));
+        if let Some(account) = &self.account {
+            facts.push(AuditFact::new("account_id", &account.id));
+        }
+        facts
+    }
+}
+
+impl Auditable for Account {
+    fn audit_kind(&self) -> &'static str {
+        "account"
+    }
+
+    fn audit_facts(&self) -> Vec<AuditFact> {
+        let mut facts = vec![AuditFact::new("id", &self.id)];
+        if let Some(number) = self.number {
+            facts.push(AuditFact::new("number", number));
+        }
+        facts.extend([
+            AuditFact::new("type", &self.account_type),
+            AuditFact::new("status", self.status),
+            AuditFact::new("balance", self.balance),
+            AuditFact::new("available_balance", self.available_balance()),
+            AuditFact::new("held", self.held_amount()),
+            AuditFact::new("transactions", self.transactions.len()),
+            AuditFact::new("opened_at", self.created_at.to_rfc3339()),
+        ]);
+        if let Some(since) = self.dormant_since {
+            facts.push(AuditFact::new("dormant_since", since.to_rfc3339()));
+        }
+        facts
+    }
+}
+
+impl Auditable for Transaction {
+    fn audit_kind(&self) -> &'static str {
+        "transaction"
+    }
+
+    fn audit_facts(&self) -> Vec<AuditFact> {
+        let mut facts = vec![
+            AuditFact::new("id", &self.id),
+            AuditFact::new("type", &self.transaction_type),
+            AuditFact::new("amount", self.amount),
+            AuditFact::new("balance_after", self.balance_after),
+            AuditFact::new("timestamp", self.timestamp.to_rfc3339()),
+            AuditFact::new("status", self.status),
+            AuditFact::new("cash", self.cash),
+        ];
+        if let Some(description) = &self.description {
+            facts.push(AuditFact::new("description", description));
+        }
+        if let Some(category) = &self.category {
+            facts.push(AuditFact::new("category", category));
+        }
+        if !self.tags.is_empty() {
+            facts.push(AuditFact::new("tags", self.tags.join(",")));
+        }
+        if let Some(original_id) = self.reverses() {
+            facts.push(AuditFact::new("reverses", original_id));
+        }
+        if let Some(reversal_id) = &self.reversed_by {
+            facts.push(AuditFact::new("reversed_by", reversal_id));
+        }
+        if let Some(reason) = &self.reversal_reason {
+            facts.push(AuditFact::new("reversal_reason", reason));
+        }
+        facts
+    }
 }
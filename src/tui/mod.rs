@@ -0,0 +1,133 @@
+//! TUI module - full-screen terminal browser for the bank (`tui` feature)
+//!
+//! Demonstrates: Immediate-mode rendering with ratatui, an event loop over
+//! crossterm key events, keeping UI state separate from the data it shows
+//! https://ratatui.rs/
+//!
+//! The TUI shares the bank through the same `SharedBank` as the
+//! interactive CLI. It is a browser: it shows the customer list, the
+//! selected customer's account, and a scrolling transaction history.
+
+use std::io;
+
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::widgets::ListState;
+
+use crate::bank::SharedBank;
+
+mod ui;
+
+/// Which pane receives the arrow keys
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Focus {
+    Customers,
+    History,
+}
+
+/// State of the TUI between frames
+pub struct TuiApp {
+    /// The bank being browsed
+    bank: SharedBank,
+
+    /// Selected row in the customer list
+    customers: ListState,
+
+    /// First visible line of the transaction history
+    history_scroll: u16,
+
+    /// Pane that receives the arrow keys
+    focus: Focus,
+
+    /// Set once the user asked to leave
+    quit: bool,
+}
+
+impl TuiApp {
+    /// Creates the TUI state with the first customer selected
+    pub fn new(bank: SharedBank) -> Self {
+        Self {
+            bank,
+            customers: ListState::default().with_selected(Some(0)),
+            history_scroll: 0,
+            focus: Focus::Customers,
+            quit: false,
+        }
+    }
+
+    /// Whether the user asked to leave
+    pub fn should_quit(&self) -> bool {
+        self.quit
+    }
+
+    /// Pane that currently receives the arrow keys
+    pub fn focus(&self) -> Focus {
+        self.focus
+    }
+
+    /// Index of the selected customer in the (name-sorted) list
+    pub fn selected(&self) -> Option<usize> {
+        self.customers.selected()
+    }
+
+    /// Applies one key press
+    ///
+    /// - `↑`/`↓` (or `k`/`j`) move the selection or scroll the history
+    /// - `Tab` switches between the customer list and the history
+    /// - `PgUp`/`PgDn` scroll the history by a page
+    /// - `q` or `Esc` quits
+    pub fn handle_key(&mut self, code: KeyCode) {
+        let customer_count = self.bank.read().list_customers().len();
+
+        match (code, self.focus) {
+            (KeyCode::Char('q') | KeyCode::Esc, _) => self.quit = true,
+            (KeyCode::Tab | KeyCode::BackTab, Focus::Customers) => self.focus = Focus::History,
+            (KeyCode::Tab | KeyCode::BackTab, Focus::History) => self.focus = Focus::Customers,
+            (KeyCode::Down | KeyCode::Char('j'), Focus::Customers) => {
+                self.select(self.selected().map_or(0, |i| i + 1), customer_count)
+            }
+            (KeyCode::Up | KeyCode::Char('k'), Focus::Customers) => {
+                self.select(self.selected().map_or(0, |i| i.saturating_sub(1)), customer_count)
+            }
+            (KeyCode::Down | KeyCode::Char('j'), Focus::History) => {
+                self.history_scroll = self.history_scroll.saturating_add(1)
+            }
+            (KeyCode::Up | KeyCode::Char('k'), Focus::History) => {
+                self.history_scroll = self.history_scroll.saturating_sub(1)
+            }
+            (KeyCode::PageDown, _) => self.history_scroll = self.history_scroll.saturating_add(10),
+            (KeyCode::PageUp, _) => self.history_scroll = self.history_scroll.saturating_sub(10),
+            _ => {}
+        }
+    }
+
+    /// Selects customer `index`, clamped to the list, and resets the history
+    fn select(&mut self, index: usize, customer_count: usize) {
+        let index = index.min(customer_count.saturating_sub(1));
+        if self.selected() != Some(index) {
+            self.history_scroll = 0;
+        }
+        self.customers.select(Some(index));
+    }
+}
+
+/// Runs the TUI until the user quits
+///
+/// Takes over the terminal (alternate screen, raw mode) and restores it
+/// on exit, even when drawing fails.
+pub fn run(bank: SharedBank) -> io::Result<()> {
+    let mut app = TuiApp::new(bank);
+
+    ratatui::run(|terminal| {
+        while !app.should_quit() {
+            terminal.draw(|frame| app.draw(frame))?;
+
+            if let Event::Key(key) = event::read()? {
+                // Ignore key releases reported by some terminals
+                if key.kind == KeyEventKind::Press {
+                    app.handle_key(key.code);
+                }
+            }
+        }
+        Ok(())
+    })
+}
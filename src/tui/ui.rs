@@ -0,0 +1,161 @@
+//! Drawing the TUI panes
+//!
+//! Demonstrates: Layout constraints, stateful widgets (List), styled text
+
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, List, ListItem, Paragraph};
+use ratatui::Frame;
+
+use crate::bank::Bank;
+use crate::models::{AccountId, Customer};
+use super::{Focus, TuiApp};
+
+impl TuiApp {
+    /// Draws the whole screen for the current state
+    pub fn draw(&mut self, frame: &mut Frame) {
+        let bank = self.bank.read();
+
+        // Sort by name so the list order is stable between frames
+        let mut customers = bank.list_customers();
+        customers.sort_by(|a, b| a.name.cmp(&b.name).then_with(|| a.id.cmp(&b.id)));
+
+        // Keep the selection valid if customers were added or removed
+        if let Some(selected) = self.customers.selected() {
+            if selected >= customers.len() {
+                self.customers.select(customers.len().checked_sub(1));
+            }
+        }
+        let selected = self.customers.selected().and_then(|i| customers.get(i).copied());
+
+        let [header, body, footer] = Layout::vertical([
+            Constraint::Length(3),
+            Constraint::Min(0),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+        let [list_area, right] =
+            Layout::horizontal([Constraint::Percentage(30), Constraint::Percentage(70)]).areas(body);
+        let [details_area, history_area] =
+            Layout::vertical([Constraint::Length(9), Constraint::Min(0)]).areas(right);
+
+        let title = format!(
+            "🏦 {}  |  {} customers  |  Total balance: {}",
+            bank.name,
+            customers.len(),
+            bank.total_bank_balance().display()
+        );
+        frame.render_widget(
+            Paragraph::new(title).block(Block::default().borders(Borders::ALL)),
+            header,
+        );
+
+        let items: Vec<ListItem> = customers.iter().map(|c| ListItem::new(c.name.clone())).collect();
+        let list = List::new(items)
+            .block(pane("Customers", self.focus == Focus::Customers))
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+            .highlight_symbol("▶ ");
+        frame.render_stateful_widget(list, list_area, &mut self.customers);
+
+        frame.render_widget(
+            Paragraph::new(details_lines(selected)).block(pane("Account", false)),
+            details_area,
+        );
+
+        // Don't scroll past the last transaction
+        let history = history_lines(&bank, selected);
+        let max_scroll = history.len().saturating_sub(1).try_into().unwrap_or(u16::MAX);
+        self.history_scroll = self.history_scroll.min(max_scroll);
+        frame.render_widget(
+            Paragraph::new(history)
+                .block(pane("Transaction History", self.focus == Focus::History))
+                .scroll((self.history_scroll, 0)),
+            history_area,
+        );
+
+        draw_footer(frame, footer);
+    }
+}
+
+/// A bordered pane, highlighted when it has the focus
+fn pane(title: &str, focused: bool) -> Block<'_> {
+    let style = if focused {
+        Style::default().fg(Color::Cyan)
+    } else {
+        Style::default()
+    };
+    Block::default().borders(Borders::ALL).title(title).border_style(style)
+}
+
+/// Lines of the account details pane
+fn details_lines(customer: Option<&Customer>) -> Vec<Line<'static>> {
+    let Some(customer) = customer else {
+        return vec![Line::from("No customers registered yet.")];
+    };
+
+    let mut lines = vec![
+        Line::from(format!("Name:    {}", customer.name)),
+        Line::from(format!("Email:   {}", customer.email)),
+        Line::from(format!("ID:      {}", customer.id)),
+    ];
+    match &customer.account {
+        Some(account) => lines.extend([
+            Line::from(format!("Account: {} ({})", account.id, account.account_type)),
+            Line::from(format!(
+                "Number:  {}",
+                account.number.map(|n| n.to_string()).unwrap_or_else(|| "-".to_string())
+            )),
+            Line::from(Span::styled(
+                format!("Balance: {}", account.balance.display()),
+                Style::default().add_modifier(Modifier::BOLD),
+            )),
+            Line::from(format!(
+                "In: {}  Out: {}  Transactions: {}",
+                account.total_deposits().display(),
+                account.total_withdrawals().display(),
+                account.transactions.len()
+            )),
+        ]),
+        None => lines.push(Line::from("No account yet.")),
+    }
+    lines
+}
+
+/// Lines of the transaction history pane, oldest first
+fn history_lines(bank: &Bank, customer: Option<&Customer>) -> Vec<Line<'static>> {
+    let Some(account) = customer.and_then(|c| c.account.as_ref()) else {
+        return Vec::new();
+    };
+    if account.transactions.is_empty() {
+        return vec![Line::from("No transactions yet.")];
+    }
+
+    account
+        .get_transaction_history()
+        .iter()
+        .map(|tx| {
+            let color = if tx.transaction_type.is_credit() { Color::Green } else { Color::Red };
+            let mut spans = vec![Span::styled(tx.to_string(), Style::default().fg(color))];
+
+            // Name the other side of a transfer when it is a known customer
+            if let Some(owner) = tx
+                .transaction_type
+                .counterparty()
+                .and_then(|account_id| bank.find_customer_by_account_id(&AccountId::from(account_id)))
+            {
+                spans.push(Span::raw(format!("  ({})", owner.name)));
+            }
+            Line::from(spans)
+        })
+        .collect()
+}
+
+/// Key help along the bottom edge
+fn draw_footer(frame: &mut Frame, area: Rect) {
+    let help = "↑/↓ select or scroll  Tab switch pane  PgUp/PgDn page history  q quit";
+    frame.render_widget(
+        Paragraph::new(help).style(Style::default().fg(Color::DarkGray)),
+        area,
+    );
+}
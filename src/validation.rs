@@ -0,0 +1,379 @@
+//! Validation module - checks and normalizes customer-supplied details
+//!
+//! Demonstrates: Small pure functions returning Result, leaning on a crate
+//! for a problem that is harder than it looks (email syntax)
+//!
+//! Each function returns the cleaned-up value on success, or a
+//! `BankError::ValidationError` naming the field and what is wrong with
+//! it. The bank calls these before changing state, and the CLI calls them
+//! as each field is typed so mistakes are reported straight away.
+
+use chrono::NaiveDate;
+use email_address::{EmailAddress, Options};
+
+use crate::errors::{BankError, BankResult};
+
+/// Youngest age at which a customer may give their date of birth
+pub const MIN_CUSTOMER_AGE: u32 = 18;
+
+/// Longest accepted postal address, in characters
+pub const MAX_ADDRESS_LEN: usize = 200;
+
+/// Longest accepted transaction description, in characters
+pub const MAX_DESCRIPTION_LEN: usize = 140;
+
+/// Longest accepted payee nickname or savings goal name, in characters
+pub const MAX_NICKNAME_LEN: usize = 32;
+
+/// Longest accepted reference of an account at another bank; 34 is the
+/// longest IBAN
+pub const MAX_EXTERNAL_REF_LEN: usize = 34;
+
+/// Longest accepted cheque serial number, in digits
+pub const MAX_CHEQUE_NUMBER_LEN: usize = 10;
+
+/// Longest accepted bill reference (the customer's number at a merchant)
+pub const MAX_BILL_REFERENCE_LEN: usize = 30;
+
+/// Longest accepted code in the chart of accounts, in digits
+pub const MAX_LEDGER_CODE_LEN: usize = 10;
+
+/// Longest term deposit, in months
+pub const MAX_TERM_MONTHS: u32 = 120;
+
+/// Highest yearly interest rate accepted, in basis points (25.00%)
+pub const MAX_RATE_BPS: u32 = 2_500;
+
+/// Shortest and longest phone numbers, counted in digits
+const PHONE_DIGITS: std::ops::RangeInclusive<usize> = 7..=15;
+
+/// Builds a `ValidationError` for `field`
+fn invalid(field: &str, reason: impl Into<String>) -> BankError {
+    BankError::ValidationError {
+        field: field.to_string(),
+        reason: reason.into(),
+    }
+}
+
+/// Checks that a name is not blank
+///
+/// # Returns
+/// The trimmed name
+pub fn validate_name(name: &str) -> BankResult<String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(invalid("name", "must not be empty"));
+    }
+    Ok(name.to_string())
+}
+
+/// Puts an email address into its stored form: trimmed and lowercased
+///
+/// Mail providers treat addresses case-insensitively in practice, so the
+/// bank does too; this is what makes `Bob@Example.com` and
+/// `bob@example.com` the same customer.
+pub fn normalize_email(email: &str) -> String {
+    email.trim().to_lowercase()
+}
+
+/// Checks an email address against the RFC 5322 address syntax
+///
+/// Display names (`Bob <bob@example.com>`) and IP-address domains are not
+/// accepted, and the domain needs at least two parts.
+///
+/// # Returns
+/// The normalized address (see `normalize_email`)
+pub fn validate_email(email: &str) -> BankResult<String> {
+    let email = normalize_email(email);
+    if email.is_empty() {
+        return Err(invalid("email", "must not be empty"));
+    }
+
+    let options = Options::default()
+        .with_required_tld()
+        .without_domain_literal()
+        .without_display_text();
+    EmailAddress::parse_with_options(&email, options)
+        .map_err(|e| invalid("email", e.to_string().trim_end_matches('.')))?;
+
+    Ok(email)
+}
+
+/// Normalizes a phone number to its digits, keeping a leading `+`
+///
+/// Spaces, dashes, dots and parentheses are dropped, so
+/// `+1 (555) 010-0100` becomes `+15550100100`.
+pub fn normalize_phone(phone: &str) -> BankResult<String> {
+    let phone = phone.trim();
+    let (plus, rest) = match phone.strip_prefix('+') {
+        Some(rest) => ("+", rest),
+        None => ("", phone),
+    };
+
+    let mut digits = String::new();
+    for c in rest.chars() {
+        match c {
+            '0'..='9' => digits.push(c),
+            ' ' | '-' | '.' | '(' | ')' => {}
+            _ => return Err(invalid("phone", format!("unexpected character '{}'", c))),
+        }
+    }
+    if !PHONE_DIGITS.contains(&digits.len()) {
+        return Err(invalid(
+            "phone",
+            format!(
+                "must have {} to {} digits",
+                PHONE_DIGITS.start(),
+                PHONE_DIGITS.end()
+            ),
+        ));
+    }
+
+    Ok(format!("{}{}", plus, digits))
+}
+
+/// Checks that an address is not blank or overly long
+///
+/// # Returns
+/// The trimmed address
+pub fn validate_address(address: &str) -> BankResult<String> {
+    let address = address.trim();
+    if address.is_empty() {
+        return Err(invalid("address", "must not be empty"));
+    }
+    if address.chars().count() > MAX_ADDRESS_LEN {
+        return Err(invalid(
+            "address",
+            format!("must be at most {} characters", MAX_ADDRESS_LEN),
+        ));
+    }
+    Ok(address.to_string())
+}
+
+/// Checks a transaction description (memo) such as "rent"
+///
+/// # Returns
+/// The trimmed description
+pub fn validate_description(description: &str) -> BankResult<String> {
+    validate_text("description", description)
+}
+
+/// Checks the reason given for reversing a transaction; the same rules
+/// as for descriptions apply
+///
+/// # Returns
+/// The trimmed reason
+pub fn validate_reversal_reason(reason: &str) -> BankResult<String> {
+    validate_text("reason", reason)
+}
+
+/// Checks the name of an account in the chart of accounts; the same rules
+/// as for descriptions apply
+///
+/// # Returns
+/// The trimmed name
+pub fn validate_ledger_name(name: &str) -> BankResult<String> {
+    validate_text("account name", name)
+}
+
+/// Checks a single line of free text up to `MAX_DESCRIPTION_LEN` characters
+fn validate_text(field: &str, text: &str) -> BankResult<String> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Err(invalid(field, "must not be empty"));
+    }
+    if text.chars().any(char::is_control) {
+        return Err(invalid(field, "must be a single line of text"));
+    }
+    if text.chars().count() > MAX_DESCRIPTION_LEN {
+        return Err(invalid(
+            field,
+            format!("must be at most {} characters", MAX_DESCRIPTION_LEN),
+        ));
+    }
+    Ok(text.to_string())
+}
+
+/// Checks a payee nickname such as "landlord"
+///
+/// # Returns
+/// The trimmed nickname
+pub fn validate_nickname(nickname: &str) -> BankResult<String> {
+    validate_label("nickname", nickname)
+}
+
+/// Checks the name of a savings goal such as "Vacation"; the same rules
+/// as for payee nicknames apply
+///
+/// # Returns
+/// The trimmed name
+pub fn validate_envelope_name(name: &str) -> BankResult<String> {
+    validate_label("savings goal", name)
+}
+
+/// Checks a short single-line label a customer chooses
+fn validate_label(field: &str, label: &str) -> BankResult<String> {
+    let label = label.trim();
+    if label.is_empty() {
+        return Err(invalid(field, "must not be empty"));
+    }
+    if label.chars().any(char::is_control) {
+        return Err(invalid(field, "must be a single line of text"));
+    }
+    if label.chars().count() > MAX_NICKNAME_LEN {
+        return Err(invalid(
+            field,
+            format!("must be at most {} characters", MAX_NICKNAME_LEN),
+        ));
+    }
+    Ok(label.to_string())
+}
+
+/// Checks the reference of an account at another bank, such as an IBAN
+/// or `021000021-123456789` (routing and account number)
+///
+/// Spaces are dropped, as IBANs are often written in groups of four.
+///
+/// # Returns
+/// The reference in uppercase, without spaces
+pub fn validate_external_ref(reference: &str) -> BankResult<String> {
+    let reference: String = reference.chars().filter(|c| *c != ' ').collect::<String>().to_uppercase();
+    if reference.is_empty() {
+        return Err(invalid("external reference", "must not be empty"));
+    }
+    if !reference.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '/' | ':')) {
+        return Err(invalid("external reference", "may only contain letters, digits, '-', '/' and ':'"));
+    }
+    if reference.len() > MAX_EXTERNAL_REF_LEN {
+        return Err(invalid(
+            "external reference",
+            format!("must be at most {} characters", MAX_EXTERNAL_REF_LEN),
+        ));
+    }
+    Ok(reference)
+}
+
+/// Checks the serial number printed on a cheque, e.g. `000123`
+///
+/// # Returns
+/// The trimmed number, leading zeros kept
+pub fn validate_cheque_number(number: &str) -> BankResult<String> {
+    let number = number.trim();
+    if number.is_empty() {
+        return Err(invalid("cheque number", "must not be empty"));
+    }
+    if !number.chars().all(|c| c.is_ascii_digit()) {
+        return Err(invalid("cheque number", "may only contain digits"));
+    }
+    if number.len() > MAX_CHEQUE_NUMBER_LEN {
+        return Err(invalid(
+            "cheque number",
+            format!("must be at most {} digits", MAX_CHEQUE_NUMBER_LEN),
+        ));
+    }
+    Ok(number.to_string())
+}
+
+/// Checks an account code in the chart of accounts, e.g. `4000`
+///
+/// # Returns
+/// The trimmed code
+pub fn validate_ledger_code(code: &str) -> BankResult<String> {
+    let code = code.trim();
+    if code.is_empty() {
+        return Err(invalid("account code", "must not be empty"));
+    }
+    if !code.chars().all(|c| c.is_ascii_digit()) {
+        return Err(invalid("account code", "may only contain digits"));
+    }
+    if code.len() > MAX_LEDGER_CODE_LEN {
+        return Err(invalid(
+            "account code",
+            format!("must be at most {} digits", MAX_LEDGER_CODE_LEN),
+        ));
+    }
+    Ok(code.to_string())
+}
+
+/// Checks the reference a merchant knows the customer by, e.g. an
+/// electricity account number
+///
+/// # Returns
+/// The trimmed reference in upper case
+pub fn validate_bill_reference(reference: &str) -> BankResult<String> {
+    let reference = reference.trim().to_uppercase();
+    if reference.is_empty() {
+        return Err(invalid("bill reference", "must not be empty"));
+    }
+    if !reference.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '/' | ' ')) {
+        return Err(invalid("bill reference", "may only contain letters, digits, spaces, '-' and '/'"));
+    }
+    if reference.len() > MAX_BILL_REFERENCE_LEN {
+        return Err(invalid(
+            "bill reference",
+            format!("must be at most {} characters", MAX_BILL_REFERENCE_LEN),
+        ));
+    }
+    Ok(reference)
+}
+
+/// Parses a yearly interest rate given in percent, e.g. `4.5` or `4.50%`
+///
+/// # Returns
+/// The rate in basis points (hundredths of a percent), e.g. 450
+pub fn parse_interest_rate(input: &str) -> BankResult<u32> {
+    let input = input.trim().trim_end_matches('%').trim();
+    let (whole, fraction) = input.split_once('.').unwrap_or((input, ""));
+    let digits = |s: &str| !s.is_empty() && s.chars().all(|c| c.is_ascii_digit());
+    if !digits(whole) || (!fraction.is_empty() && !digits(fraction)) || fraction.len() > 2 {
+        return Err(invalid("interest rate", "use a percentage with at most two decimals, e.g. 4.25"));
+    }
+
+    let bps = whole
+        .parse::<u32>()
+        .ok()
+        .and_then(|whole| whole.checked_mul(100))
+        .and_then(|bps| bps.checked_add(format!("{:0<2}", fraction).parse::<u32>().unwrap_or(0)))
+        .unwrap_or(u32::MAX);
+    validate_rate_bps(bps)
+}
+
+/// Checks a yearly interest rate given in basis points
+pub fn validate_rate_bps(bps: u32) -> BankResult<u32> {
+    if !(1..=MAX_RATE_BPS).contains(&bps) {
+        return Err(invalid(
+            "interest rate",
+            format!("must be more than 0% and at most {}%", MAX_RATE_BPS / 100),
+        ));
+    }
+    Ok(bps)
+}
+
+/// Checks the length of a term deposit, in months
+pub fn validate_term_months(months: u32) -> BankResult<u32> {
+    if !(1..=MAX_TERM_MONTHS).contains(&months) {
+        return Err(invalid("term", format!("must be 1 to {} months", MAX_TERM_MONTHS)));
+    }
+    Ok(months)
+}
+
+/// Parses a `YYYY-MM-DD` date of birth
+pub fn parse_date_of_birth(input: &str) -> BankResult<NaiveDate> {
+    NaiveDate::parse_from_str(input.trim(), "%Y-%m-%d")
+        .map_err(|_| invalid("date of birth", "use the format YYYY-MM-DD"))
+}
+
+/// Checks that a customer born on `date_of_birth` is at least
+/// `MIN_CUSTOMER_AGE` years old on `today`
+pub fn validate_date_of_birth(date_of_birth: NaiveDate, today: NaiveDate) -> BankResult<()> {
+    let age = today
+        .years_since(date_of_birth)
+        .ok_or_else(|| invalid("date of birth", "must not be in the future"))?;
+    if age < MIN_CUSTOMER_AGE {
+        return Err(invalid(
+            "date of birth",
+            format!("customers must be at least {} years old", MIN_CUSTOMER_AGE),
+        ));
+    }
+    Ok(())
+}
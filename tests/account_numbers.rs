@@ -0,0 +1,100 @@
+//! Integration tests for short, check-digit-validated account numbers
+
+use rust_banking_system::models::{AccountNumber, CustomerId};
+use rust_banking_system::traits::Summarizable;
+use rust_banking_system::{AccountType, Bank, Money};
+
+/// Opens accounts for three customers, in order, and returns their IDs
+fn bank_with_accounts() -> (Bank, Vec<CustomerId>) {
+    let mut bank = Bank::new("Test Bank".to_string());
+    let ids = ["alice", "bob", "carol"]
+        .iter()
+        .map(|name| {
+            let id = bank
+                .register_customer(name.to_string(), format!("{}@example.com", name))
+                .unwrap();
+            bank.create_account_for_customer(&id, Money::ZERO, AccountType::Checking)
+                .unwrap();
+            id
+        })
+        .collect();
+    (bank, ids)
+}
+
+fn number_of(bank: &Bank, customer_id: &CustomerId) -> Option<AccountNumber> {
+    bank.get_customer(customer_id).unwrap().get_account().unwrap().number
+}
+
+#[test]
+fn numbers_round_trip_and_reject_typos() {
+    for sequence in [1, 42, 123, 999_999, 1_234_567] {
+        let number = AccountNumber::new(sequence);
+        assert_eq!(number.to_string().parse(), Ok(number));
+    }
+
+    assert_eq!(AccountNumber::new(7).to_string(), "ACC-000007-5");
+    assert_eq!("acc-7-5".parse(), Ok(AccountNumber::new(7)));
+
+    // One wrong digit, two swapped digits, a wrong check digit
+    assert!("ACC-000008-5".parse::<AccountNumber>().is_err());
+    assert!("ACC-000132-0".parse::<AccountNumber>().is_err());
+    assert!("ACC-000123-1".parse::<AccountNumber>().is_err());
+    assert!("000123-0".parse::<AccountNumber>().is_err());
+    assert!("ACC-12a-0".parse::<AccountNumber>().is_err());
+}
+
+#[test]
+fn accounts_are_numbered_in_order_and_looked_up() {
+    let (bank, ids) = bank_with_accounts();
+
+    for (i, id) in ids.iter().enumerate() {
+        let number = number_of(&bank, id).unwrap();
+        assert_eq!(number.sequence(), i as u64 + 1);
+        assert_eq!(&bank.find_customer_by_account_number(number).unwrap().id, id);
+        assert_eq!(&bank.resolve_customer(&number.to_string()).unwrap(), id);
+    }
+
+    let summary = bank.get_customer(&ids[1]).unwrap().summary();
+    assert!(summary.contains("ACC-000002-"), "{}", summary);
+}
+
+#[test]
+fn numbers_survive_serialization_and_replay() {
+    let (mut bank, ids) = bank_with_accounts();
+    bank.enable_journal();
+    let dave = bank
+        .register_customer("Dave".to_string(), "dave@example.com".to_string())
+        .unwrap();
+    bank.create_account_for_customer(&dave, Money::ZERO, AccountType::Savings)
+        .unwrap();
+
+    let json = serde_json::to_string(&bank).unwrap();
+    let loaded: Bank = serde_json::from_str(&json).unwrap();
+    let number = number_of(&bank, &ids[0]).unwrap();
+    assert_eq!(&loaded.find_customer_by_account_number(number).unwrap().id, &ids[0]);
+
+    // The journaled events alone rebuild the number index too
+    let replayed = Bank::from_events("Replay".to_string(), bank.take_journal()).unwrap();
+    let dave_number = number_of(&bank, &dave).unwrap();
+    assert_eq!(replayed.find_customer_by_account_number(dave_number).unwrap().id, dave);
+}
+
+#[test]
+fn older_accounts_get_numbers_in_opening_order() {
+    let (bank, ids) = bank_with_accounts();
+
+    // Strip the numbers as if the data file predated them
+    let mut json = serde_json::to_value(&bank).unwrap();
+    json.as_object_mut().unwrap().remove("account_numbers");
+    for customer in json["customers"].as_object_mut().unwrap().values_mut() {
+        customer["account"].as_object_mut().unwrap().remove("number");
+    }
+    let mut legacy: Bank = serde_json::from_value(json).unwrap();
+    assert_eq!(number_of(&legacy, &ids[0]), None);
+
+    assert_eq!(legacy.assign_account_numbers().unwrap(), 3);
+    for (i, id) in ids.iter().enumerate() {
+        assert_eq!(number_of(&legacy, id), Some(AccountNumber::new(i as u64 + 1)));
+    }
+    assert_eq!(legacy.assign_account_numbers().unwrap(), 0);
+}
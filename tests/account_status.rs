@@ -0,0 +1,70 @@
+//! Integration tests for freezing and closing accounts
+
+mod common;
+
+use rust_banking_system::models::{AccountStatus, CustomerId};
+use rust_banking_system::{Bank, BankError, Money};
+
+use common::bank_with_customers;
+
+fn status_of(bank: &Bank, customer_id: &CustomerId) -> AccountStatus {
+    bank.get_customer(customer_id).unwrap().get_account().unwrap().status
+}
+
+#[test]
+fn frozen_accounts_accept_deposits_but_not_withdrawals() {
+    let (mut bank, alice, bob) = bank_with_customers(100, 50);
+    bank.freeze_account(&alice).unwrap();
+
+    assert!(matches!(bank.withdraw(&alice, Money::from_major(10)), Err(BankError::AccountFrozen(_))));
+    assert!(matches!(
+        bank.transfer(&alice, &bob, Money::from_major(10)),
+        Err(BankError::AccountFrozen(_))
+    ));
+    bank.deposit(&alice, Money::from_major(10)).unwrap();
+    bank.transfer(&bob, &alice, Money::from_major(10)).unwrap();
+
+    bank.unfreeze_account(&alice).unwrap();
+    bank.withdraw(&alice, Money::from_major(10)).unwrap();
+}
+
+#[test]
+fn freezing_twice_is_rejected() {
+    let (mut bank, alice, _) = bank_with_customers(100, 50);
+    bank.freeze_account(&alice).unwrap();
+    assert!(matches!(bank.freeze_account(&alice), Err(BankError::InvalidInput(_))));
+    bank.unfreeze_account(&alice).unwrap();
+    assert!(matches!(bank.unfreeze_account(&alice), Err(BankError::InvalidInput(_))));
+}
+
+#[test]
+fn closing_requires_the_balance_to_be_moved_out() {
+    let (mut bank, alice, bob) = bank_with_customers(100, 50);
+    assert!(matches!(bank.close_account(&alice, None), Err(BankError::InvalidInput(_))));
+    assert_eq!(status_of(&bank, &alice), AccountStatus::Active);
+
+    // Closing works even while frozen
+    bank.freeze_account(&alice).unwrap();
+    let moved = bank.close_account(&alice, Some(&bob)).unwrap();
+
+    assert_eq!(moved, Money::from_major(100));
+    assert_eq!(status_of(&bank, &alice), AccountStatus::Closed);
+    assert_eq!(bank.get_customer(&bob).unwrap().get_account().unwrap().balance, Money::from_major(150));
+    assert_eq!(bank.total_bank_balance(), Money::from_major(150));
+}
+
+#[test]
+fn closed_accounts_reject_everything() {
+    let (mut bank, alice, bob) = bank_with_customers(100, 50);
+    bank.withdraw(&alice, Money::from_major(100)).unwrap();
+    assert_eq!(bank.close_account(&alice, None).unwrap(), Money::ZERO);
+
+    assert!(matches!(bank.deposit(&alice, Money::from_major(1)), Err(BankError::AccountClosed(_))));
+    assert!(matches!(bank.withdraw(&alice, Money::from_major(1)), Err(BankError::AccountClosed(_))));
+    assert!(matches!(
+        bank.transfer(&bob, &alice, Money::from_major(1)),
+        Err(BankError::AccountClosed(_))
+    ));
+    assert!(matches!(bank.unfreeze_account(&alice), Err(BankError::AccountClosed(_))));
+    assert!(matches!(bank.close_account(&alice, None), Err(BankError::AccountClosed(_))));
+}
@@ -0,0 +1,259 @@
+//! Integration tests for balance alerts and notifiers
+
+mod common;
+
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use rust_banking_system::bank::{BatchMode, Operation};
+use rust_banking_system::errors::BankResult;
+use rust_banking_system::models::{Alert, AlertKind, AlertRules, CustomerId};
+use rust_banking_system::notifications::{FileNotifier, WebhookNotifier};
+use rust_banking_system::{Bank, BankError, Money, Notifier};
+
+use common::{add_customers, temp_path};
+
+/// Keeps every alert it is sent, for the tests to inspect
+#[derive(Debug, Default)]
+struct Recorder {
+    alerts: Mutex<Vec<Alert>>,
+}
+
+impl Recorder {
+    fn kinds(&self) -> Vec<AlertKind> {
+        self.alerts.lock().unwrap().iter().map(|a| a.kind.clone()).collect()
+    }
+}
+
+impl Notifier for Recorder {
+    fn notify(&self, alert: &Alert) -> BankResult<()> {
+        self.alerts.lock().unwrap().push(alert.clone());
+        Ok(())
+    }
+}
+
+/// Always fails, like a webhook that is down
+#[derive(Debug)]
+struct Broken;
+
+impl Notifier for Broken {
+    fn notify(&self, _alert: &Alert) -> BankResult<()> {
+        Err(BankError::IoError("unreachable".into()))
+    }
+}
+
+/// Creates a bank where Alice has $1,000 and Bob $100, with a recorder
+/// attached; returns (bank, recorder, alice, bob)
+fn bank_with_recorder() -> (Bank, Arc<Recorder>, CustomerId, CustomerId) {
+    let mut bank = Bank::new("Test Bank".to_string());
+    let (alice, bob) = add_customers(&mut bank, 1_000, 100);
+    let recorder = Arc::new(Recorder::default());
+    bank.add_notifier(recorder.clone());
+    (bank, recorder, alice, bob)
+}
+
+fn rules(low_balance: Option<i64>, large_transaction: Option<i64>) -> AlertRules {
+    AlertRules {
+        low_balance: low_balance.map(Money::from_major),
+        large_transaction: large_transaction.map(Money::from_major),
+    }
+}
+
+#[test]
+fn low_balance_alerts_fire_once_per_dip() {
+    let (mut bank, recorder, alice, _) = bank_with_recorder();
+    bank.set_alert_rules(&alice, rules(Some(500), None)).unwrap();
+
+    bank.withdraw(&alice, Money::from_major(400)).unwrap();
+    assert!(recorder.kinds().is_empty());
+
+    bank.withdraw(&alice, Money::from_major(200)).unwrap();
+    bank.withdraw(&alice, Money::from_major(50)).unwrap();
+    assert_eq!(recorder.kinds(), vec![AlertKind::LowBalance { threshold: Money::from_major(500) }]);
+
+    // Back above the threshold and down again is a new dip
+    bank.deposit(&alice, Money::from_major(300)).unwrap();
+    bank.withdraw(&alice, Money::from_major(200)).unwrap();
+    assert_eq!(recorder.kinds().len(), 2);
+
+    let alert = &recorder.alerts.lock().unwrap()[1];
+    assert_eq!(alert.customer_id, alice);
+    assert_eq!(alert.balance, Money::from_major(450));
+}
+
+#[test]
+fn large_transactions_raise_alerts() {
+    let (mut bank, recorder, alice, _) = bank_with_recorder();
+    bank.set_alert_rules(&alice, rules(None, Some(250))).unwrap();
+
+    bank.deposit(&alice, Money::from_major(250)).unwrap();
+    bank.withdraw(&alice, Money::from_major(300)).unwrap();
+
+    assert_eq!(
+        recorder.kinds(),
+        vec![AlertKind::LargeTransaction {
+            amount: Money::from_major(300),
+            threshold: Money::from_major(250),
+        }]
+    );
+}
+
+#[test]
+fn transfers_check_both_accounts() {
+    let (mut bank, recorder, alice, bob) = bank_with_recorder();
+    bank.set_alert_rules(&alice, rules(Some(500), None)).unwrap();
+    bank.set_alert_rules(&bob, rules(None, Some(500))).unwrap();
+
+    bank.transfer(&alice, &bob, Money::from_major(600)).unwrap();
+
+    let alerts = recorder.alerts.lock().unwrap();
+    let customers: Vec<_> = alerts.iter().map(|a| a.customer_id.as_str()).collect();
+    assert_eq!(customers, vec![alice.as_str(), bob.as_str()]);
+    assert!(matches!(alerts[1].kind, AlertKind::LargeTransaction { .. }));
+}
+
+#[test]
+fn accounts_without_rules_raise_nothing() {
+    let (mut bank, recorder, alice, bob) = bank_with_recorder();
+    bank.withdraw(&alice, Money::from_major(999)).unwrap();
+    bank.transfer(&bob, &alice, Money::from_major(100)).unwrap();
+    assert!(recorder.kinds().is_empty());
+}
+
+#[test]
+fn thresholds_must_be_positive() {
+    let (mut bank, _, alice, _) = bank_with_recorder();
+    assert!(matches!(
+        bank.set_alert_rules(&alice, AlertRules { low_balance: Some(Money::ZERO), large_transaction: None }),
+        Err(BankError::InvalidAmount(_))
+    ));
+    assert_eq!(bank.get_customer(&alice).unwrap().get_account().unwrap().alerts, AlertRules::default());
+}
+
+#[test]
+fn failing_notifiers_do_not_fail_the_operation() {
+    let (mut bank, recorder, alice, _) = bank_with_recorder();
+    bank.add_notifier(Arc::new(Broken));
+    bank.set_alert_rules(&alice, rules(None, Some(10))).unwrap();
+
+    assert_eq!(bank.withdraw(&alice, Money::from_major(20)).unwrap(), Money::from_major(980));
+    assert_eq!(recorder.kinds().len(), 1);
+}
+
+#[test]
+fn rolled_back_batches_send_no_alerts() {
+    let (mut bank, recorder, alice, _) = bank_with_recorder();
+    bank.set_alert_rules(&alice, rules(None, Some(10))).unwrap();
+    let withdraw = |amount| Operation::Withdraw { customer: alice.to_string(), amount, description: None };
+
+    let results = bank.apply_batch(
+        vec![withdraw(Money::from_major(20)), withdraw(Money::from_major(5_000))],
+        BatchMode::AllOrNothing,
+    );
+    assert!(results.iter().all(Result::is_err));
+    assert!(recorder.kinds().is_empty());
+
+    let results = bank.apply_batch(
+        vec![withdraw(Money::from_major(20)), withdraw(Money::from_major(30))],
+        BatchMode::AllOrNothing,
+    );
+    assert!(results.iter().all(Result::is_ok));
+    assert_eq!(recorder.kinds().len(), 2);
+}
+
+#[test]
+fn replaying_the_journal_sends_no_alerts() {
+    let (mut bank, _, alice, _) = bank_with_recorder();
+    let snapshot = serde_json::to_string(&bank).unwrap();
+    bank.enable_journal();
+    bank.set_alert_rules(&alice, rules(None, Some(10))).unwrap();
+    bank.withdraw(&alice, Money::from_major(20)).unwrap();
+
+    let mut restored: Bank = serde_json::from_str(&snapshot).unwrap();
+    let recorder = Arc::new(Recorder::default());
+    restored.add_notifier(recorder.clone());
+    for entry in bank.take_journal() {
+        restored.apply_journal_entry(entry).unwrap();
+    }
+    assert_eq!(restored.get_customer(&alice).unwrap().get_account().unwrap().alerts, rules(None, Some(10)));
+    assert!(recorder.kinds().is_empty());
+}
+
+#[test]
+fn file_notifier_appends_json_lines() {
+    let (mut bank, recorder, alice, _) = bank_with_recorder();
+    let path = temp_path("alerts.jsonl");
+    let _ = std::fs::remove_file(&path);
+    bank.add_notifier(Arc::new(FileNotifier::new(&path)));
+    bank.set_alert_rules(&alice, rules(Some(900), Some(50))).unwrap();
+
+    bank.withdraw(&alice, Money::from_major(200)).unwrap();
+
+    let contents = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    let written: Vec<Alert> = contents.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+    assert_eq!(written, *recorder.alerts.lock().unwrap());
+    assert_eq!(written.len(), 2);
+    assert!(contents.contains(r#""alert":"low_balance""#));
+}
+
+/// Serves one request with `status_line`; returns the URL and a handle
+/// yielding the request body
+fn one_shot_server(status_line: &'static str) -> (String, thread::JoinHandle<String>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/hooks/alerts", listener.local_addr().unwrap());
+    let handle = thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+        let mut length = 0;
+        loop {
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+            if line == "\r\n" {
+                break;
+            }
+            if let Some(value) = line.to_lowercase().strip_prefix("content-length:") {
+                length = value.trim().parse().unwrap();
+            }
+        }
+        let mut body = vec![0; length];
+        std::io::Read::read_exact(&mut reader, &mut body).unwrap();
+        write!(&stream, "{}\r\n\r\n", status_line).unwrap();
+        String::from_utf8(body).unwrap()
+    });
+    (url, handle)
+}
+
+/// Triggers one large-transaction alert on Alice's account and returns it
+fn large_withdrawal_alert() -> Alert {
+    let (mut bank, recorder, alice, _) = bank_with_recorder();
+    bank.set_alert_rules(&alice, rules(None, Some(10))).unwrap();
+    bank.withdraw(&alice, Money::from_major(20)).unwrap();
+    let alert = recorder.alerts.lock().unwrap()[0].clone();
+    alert
+}
+
+#[test]
+fn webhook_notifier_posts_the_alert() {
+    let (url, server) = one_shot_server("HTTP/1.1 204 No Content");
+    let alert = large_withdrawal_alert();
+
+    WebhookNotifier::new(&url).unwrap().notify(&alert).unwrap();
+
+    let received: Alert = serde_json::from_str(&server.join().unwrap()).unwrap();
+    assert_eq!(received, alert);
+}
+
+#[test]
+fn webhook_errors_are_reported() {
+    let (url, server) = one_shot_server("HTTP/1.1 500 Internal Server Error");
+    let alert = large_withdrawal_alert();
+
+    assert!(matches!(WebhookNotifier::new(&url).unwrap().notify(&alert), Err(BankError::IoError(_))));
+    server.join().unwrap();
+
+    assert!(matches!(WebhookNotifier::new("https://example.com"), Err(BankError::InvalidInput(_))));
+    assert!(matches!(WebhookNotifier::new("http://"), Err(BankError::InvalidInput(_))));
+}
@@ -0,0 +1,170 @@
+//! Integration tests for the top-N analytics queries
+
+// The tests that run the binary need the `cli` feature; without it the
+// imports and helpers only they use go unused
+#![cfg_attr(not(feature = "cli"), allow(unused))]
+
+mod common;
+
+use std::sync::Arc;
+
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use rust_banking_system::clock::MockClock;
+use rust_banking_system::models::{CustomerId, TransactionId, TransactionType};
+use rust_banking_system::persistence::journal_path;
+use rust_banking_system::{AccountType, Bank, Money};
+
+#[cfg(feature = "cli")]
+use common::run;
+use common::temp_path;
+
+fn start() -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2024, 3, 1, 9, 0, 0).unwrap()
+}
+
+/// Alice opens with $500, Bob with $100 and Carol with $50 on March 1st.
+/// Sixty days later Bob deposits $10 twice and sends Alice $300 the next
+/// day; returns (bank, clock, alice, bob, carol)
+fn busy_bank() -> (Bank, Arc<MockClock>, CustomerId, CustomerId, CustomerId) {
+    let clock = Arc::new(MockClock::new(start()));
+    let mut bank = Bank::new("Test Bank".to_string());
+    bank.set_clock(clock.clone());
+    let alice = bank.register_customer("Alice".to_string(), "alice@example.com".to_string()).unwrap();
+    let bob = bank.register_customer("Bob".to_string(), "bob@example.com".to_string()).unwrap();
+    let carol = bank.register_customer("Carol".to_string(), "carol@example.com".to_string()).unwrap();
+    bank.create_account_for_customer(&alice, Money::from_major(500), AccountType::Checking).unwrap();
+    bank.create_account_for_customer(&bob, Money::from_major(100), AccountType::Checking).unwrap();
+    bank.create_account_for_customer(&carol, Money::from_major(50), AccountType::Checking).unwrap();
+
+    clock.advance(Duration::days(60));
+    bank.deposit(&bob, Money::from_major(10)).unwrap();
+    bank.deposit(&bob, Money::from_major(10)).unwrap();
+    clock.advance(Duration::days(1));
+    bank.deposit(&bob, Money::from_major(200)).unwrap();
+    bank.transfer(&bob, &alice, Money::from_major(300)).unwrap();
+    (bank, clock, alice, bob, carol)
+}
+
+#[test]
+fn largest_transactions_come_largest_first() {
+    let (bank, _, alice, bob, _) = busy_bank();
+    let largest = bank.largest_transactions(3);
+
+    let amounts: Vec<Money> = largest.iter().map(|ranked| ranked.transaction.amount).collect();
+    assert_eq!(amounts, [Money::from_major(500), Money::from_major(300), Money::from_major(200)]);
+    assert_eq!(largest[0].customer_id, alice);
+    assert_eq!(largest[0].name, "Alice");
+    assert!(largest[0].account_number.is_some());
+    // The transfer is listed once, on the sender's side
+    assert_eq!(largest[1].customer_id, bob);
+    assert!(matches!(largest[1].transaction.transaction_type, TransactionType::Transfer { .. }));
+}
+
+#[test]
+fn equal_amounts_rank_the_older_first_and_n_caps_the_list() {
+    let (bank, _, _, _, _) = busy_bank();
+    let all = bank.largest_transactions(100);
+    // Eight transactions, less the receiving side of the transfer
+    assert_eq!(all.len(), 7);
+    let tens: Vec<_> = all.iter().filter(|ranked| ranked.transaction.amount == Money::from_major(10)).collect();
+    assert_eq!(tens.len(), 2);
+    assert!(tens[0].transaction.timestamp <= tens[1].transaction.timestamp);
+    let position = |id: &TransactionId| all.iter().position(|ranked| ranked.transaction.id == *id).unwrap();
+    assert!(position(&tens[0].transaction.id) < position(&tens[1].transaction.id));
+
+    assert!(bank.largest_transactions(0).is_empty());
+    assert!(Bank::new("Empty".to_string()).largest_transactions(5).is_empty());
+}
+
+#[test]
+fn most_active_accounts_count_only_the_period() {
+    let (bank, _, alice, bob, _) = busy_bank();
+
+    let recent = bank.most_active_accounts(10, Duration::days(30));
+    let counts: Vec<(&str, usize)> = recent.iter().map(|a| (a.customer_id.as_str(), a.transactions)).collect();
+    assert_eq!(counts, [(bob.as_str(), 4), (alice.as_str(), 1)]);
+
+    // Over all time everyone shows up; ties are ordered by name
+    let ever = bank.most_active_accounts(10, Duration::days(365));
+    let names: Vec<&str> = ever.iter().map(|a| a.name.as_str()).collect();
+    assert_eq!(names, ["Bob", "Alice", "Carol"]);
+    assert_eq!(bank.most_active_accounts(1, Duration::days(365)).len(), 1);
+}
+
+#[test]
+fn dormant_accounts_are_open_and_quiet_for_long_enough() {
+    let (bank, _, _, _, carol) = busy_bank();
+
+    let dormant = bank.dormant_accounts(30);
+    assert_eq!(dormant.len(), 1);
+    assert_eq!(dormant[0].customer_id, carol);
+    assert_eq!(dormant[0].last_activity, start());
+    assert_eq!(dormant[0].inactive_days, 61);
+
+    assert!(bank.dormant_accounts(62).is_empty());
+    assert_eq!(bank.dormant_accounts(0).len(), 3);
+}
+
+#[test]
+fn dormant_accounts_are_listed_longest_inactive_first() {
+    let (mut bank, clock, alice, bob, carol) = busy_bank();
+    clock.advance(Duration::days(100));
+    bank.deposit(&alice, Money::from_major(1)).unwrap();
+    clock.advance(Duration::days(10));
+
+    let names: Vec<String> = bank.dormant_accounts(5).into_iter().map(|a| a.name).collect();
+    assert_eq!(names, ["Carol", "Bob", "Alice"]);
+
+    // Closed accounts are not dormant, they are closed
+    bank.close_account(&carol, Some(&bob)).unwrap();
+    assert!(bank.dormant_accounts(5).iter().all(|a| a.customer_id != carol));
+}
+
+#[cfg(feature = "cli")]
+#[test]
+fn the_statistics_menu_offers_each_report() {
+    let data_file = temp_path("menu.json");
+    let _ = std::fs::remove_file(&data_file);
+    let _ = std::fs::remove_file(journal_path(&data_file.to_string_lossy()));
+    for (name, email, deposit) in [("Ann", "ann@example.com", "100"), ("Ben", "ben@example.com", "250")] {
+        run(&data_file, &["register", "--name", name, "--email", email, "--pin", "1234"], "\n");
+        run(&data_file, &["create-account", "--customer", email, "--deposit", deposit], "\n");
+    }
+
+    // The first staff login creates the admin
+    let text = run(&data_file, &[], "\n20\n2\nadmin\n9999\n9999\n10\n1\n2\n1\n3\n\n\n4\n\n9\n\n0\n");
+    assert!(text.contains("BANK REPORT: "), "{}", text);
+    assert!(text.contains("Largest transactions:\n  1. Ben"), "{}", text);
+    assert!(!text.contains("2. Ann"), "{}", text);
+    assert!(text.contains("Most active accounts (last 30 days):"), "{}", text);
+    assert!(text.contains("Accounts inactive for 365+ days:\n  None."), "{}", text);
+    assert!(text.contains("Invalid choice"), "{}", text);
+    let _ = std::fs::remove_file(&data_file);
+}
+
+#[cfg(feature = "cli")]
+#[test]
+fn command_mode_lists_the_top_n_as_json() {
+    let data_file = temp_path("cli.json");
+    let _ = std::fs::remove_file(&data_file);
+    let _ = std::fs::remove_file(journal_path(&data_file.to_string_lossy()));
+    for (name, email, deposit) in [("Ann", "ann@example.com", "100"), ("Ben", "ben@example.com", "250")] {
+        run(&data_file, &["register", "--name", name, "--email", email, "--pin", "1234"], "\n");
+        run(&data_file, &["create-account", "--customer", email, "--deposit", deposit], "\n");
+    }
+
+    let json: serde_json::Value =
+        serde_json::from_str(&run(&data_file, &["--json", "largest-transactions", "--count", "1"], "")).unwrap();
+    assert_eq!(json["result"].as_array().unwrap().len(), 1);
+    assert_eq!(json["result"][0]["name"], "Ben");
+    assert_eq!(json["result"][0]["transaction"]["amount"], "250.00");
+
+    let json: serde_json::Value = serde_json::from_str(&run(&data_file, &["--json", "most-active"], "")).unwrap();
+    assert_eq!(json["result"].as_array().unwrap().len(), 2);
+    assert_eq!(json["result"][0]["transactions"], 1);
+
+    let json: serde_json::Value = serde_json::from_str(&run(&data_file, &["--json", "dormant", "--days", "0"], "")).unwrap();
+    assert_eq!(json["result"].as_array().unwrap().len(), 2);
+    assert_eq!(json["result"][0]["inactive_days"], 0);
+    let _ = std::fs::remove_file(&data_file);
+}
@@ -0,0 +1,214 @@
+//! Integration tests for archiving old transactions
+
+// The tests that run the binary need the `cli` feature; without it the
+// imports and helpers only they use go unused
+#![cfg_attr(not(feature = "cli"), allow(unused))]
+
+mod common;
+
+use std::io::Write;
+use std::path::Path;
+use std::sync::Arc;
+
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use rust_banking_system::bank::HistoryArchive;
+use rust_banking_system::clock::MockClock;
+use rust_banking_system::models::{CustomerId, TransactionId};
+use rust_banking_system::persistence::{archive_dir, journal_path, FileArchive, JsonFileStorage, Storage};
+use rust_banking_system::{AccountType, Bank, BankError, Money};
+
+use common::temp_file;
+
+fn start() -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2024, 3, 1, 9, 0, 0).unwrap()
+}
+
+fn remove(path: &str) {
+    let _ = std::fs::remove_file(path);
+    let _ = std::fs::remove_file(journal_path(path));
+    let _ = std::fs::remove_dir_all(archive_dir(path));
+}
+
+/// A bank on a mock clock, archiving to `archive`, where Alice and Bob
+/// open accounts an hour apart and then one operation happens each day;
+/// returns (bank, clock, alice, bob)
+fn busy_bank(archive: &str) -> (Bank, Arc<MockClock>, CustomerId, CustomerId) {
+    let clock = Arc::new(MockClock::new(start()));
+    let mut bank = Bank::new("Test Bank".to_string());
+    bank.set_clock(clock.clone());
+    bank.set_archive(Arc::new(FileArchive::new(archive)));
+    let alice = bank.register_customer("Alice".to_string(), "alice@example.com".to_string()).unwrap();
+    let bob = bank.register_customer("Bob".to_string(), "bob@example.com".to_string()).unwrap();
+    bank.create_account_for_customer(&alice, Money::from_major(500), AccountType::Checking).unwrap();
+    clock.advance(Duration::hours(1));
+    bank.create_account_for_customer(&bob, Money::from_major(100), AccountType::Checking).unwrap();
+
+    clock.advance(Duration::days(1));
+    bank.deposit(&bob, Money::from_major(10)).unwrap();
+    clock.advance(Duration::days(1));
+    bank.withdraw(&alice, Money::from_major(20)).unwrap();
+    clock.advance(Duration::days(1));
+    bank.transfer(&bob, &alice, Money::from_major(30)).unwrap();
+    clock.advance(Duration::days(1));
+    (bank, clock, alice, bob)
+}
+
+fn live(bank: &Bank, customer_id: &CustomerId) -> usize {
+    bank.get_customer(customer_id).unwrap().get_account().unwrap().transactions.len()
+}
+
+#[test]
+fn archiving_moves_old_transactions_out_and_keeps_the_books_whole() {
+    let dir = temp_file("moves");
+    let (mut bank, _, alice, bob) = busy_bank(&dir);
+    let books_before = serde_json::to_value(bank.trial_balance().rows).unwrap();
+
+    let run = bank.archive_transactions(start() + Duration::days(2) + Duration::hours(12)).unwrap();
+
+    assert_eq!((run.accounts, run.transactions), (2, 4));
+    assert_eq!((live(&bank, &alice), live(&bank, &bob)), (1, 1));
+    let archived = bank.get_customer(&alice).unwrap().get_account().unwrap().archived.clone().unwrap();
+    assert_eq!(archived.opening_balance, Money::from_major(480));
+    assert_eq!(archived.transactions, 2);
+    assert_eq!(bank.get_customer(&alice).unwrap().get_account().unwrap().balance, Money::from_major(510));
+
+    assert!(bank.verify_integrity().is_clean(), "{}", bank.verify_integrity());
+    let trial_balance = bank.trial_balance();
+    assert!(trial_balance.is_balanced(), "{}", trial_balance);
+    assert_eq!(serde_json::to_value(trial_balance.rows).unwrap(), books_before);
+    assert!(bank.book_entries().iter().any(|entry| entry.memo == "BROUGHT FORWARD"));
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn statements_page_archived_history_back_in() {
+    let dir = temp_file("statements");
+    let (mut bank, _, alice, _) = busy_bank(&dir);
+    let (from, to) = (start(), start() + Duration::days(10));
+    let statement = serde_json::to_value(bank.statement(&alice, from, to).unwrap()).unwrap();
+    let summary = bank.monthly_summary(&alice, 2024, 3).unwrap();
+
+    bank.archive_transactions(start() + Duration::days(3)).unwrap();
+
+    assert_eq!(serde_json::to_value(bank.statement(&alice, from, to).unwrap()).unwrap(), statement);
+    assert_eq!(bank.monthly_summary(&alice, 2024, 3).unwrap(), summary);
+    assert_eq!(bank.archived_transactions(&alice).unwrap().len(), 2);
+
+    // A period after the cutoff needs only the live history
+    let recent = bank.statement(&alice, start() + Duration::days(3), to).unwrap();
+    assert_eq!(recent.opening_balance, Money::from_major(480));
+    assert_eq!(recent.closing_balance, Money::from_major(510));
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn a_transaction_reversed_after_the_cutoff_stays_live() {
+    let dir = temp_file("reversed");
+    let (mut bank, _, alice, bob) = busy_bank(&dir);
+    let withdrawal = bank.get_customer(&alice).unwrap().get_account().unwrap().transactions[1].id.clone();
+    bank.reverse_transaction(&withdrawal, "entered twice").unwrap();
+
+    let run = bank.archive_transactions(start() + Duration::days(3) + Duration::hours(12)).unwrap();
+
+    // Alice keeps the withdrawal and everything after it; Bob archives all
+    // three, including the transfer whose other half Alice keeps
+    assert_eq!(run.transactions, 4);
+    assert_eq!(live(&bank, &alice), 3);
+    assert_eq!(live(&bank, &bob), 0);
+    assert!(bank.find_transaction(&withdrawal).is_some());
+    assert!(bank.verify_integrity().is_clean(), "{}", bank.verify_integrity());
+    assert!(bank.trial_balance().is_balanced());
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn archiving_needs_an_archive_and_a_past_cutoff() {
+    let dir = temp_file("refused");
+    let (mut bank, _, alice, _) = busy_bank(&dir);
+    let future = bank.now() + Duration::days(1);
+    assert!(matches!(bank.archive_transactions(future), Err(BankError::InvalidInput(_))));
+
+    let mut detached = Bank::new("Other".to_string());
+    assert!(matches!(detached.archive_transactions(start()), Err(BankError::InvalidInput(_))));
+
+    // A bank with archived history but no archive cannot page it in
+    bank.archive_transactions(start() + Duration::days(3)).unwrap();
+    let mut reloaded: Bank = serde_json::from_value(serde_json::to_value(&bank).unwrap()).unwrap();
+    assert!(!reloaded.has_archive());
+    assert!(bank.has_archive());
+    assert!(matches!(reloaded.statement(&alice, start(), future), Err(BankError::InvalidInput(_))));
+    reloaded.set_archive(Arc::new(FileArchive::new(&dir)));
+    assert_eq!(reloaded.statement(&alice, start(), future).unwrap().transactions.len(), 3);
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn archived_history_survives_the_journal_and_a_reload() {
+    let path = temp_file("journaled.json");
+    remove(&path);
+    let (mut bank, _, alice, _) = busy_bank(&archive_dir(&path));
+    let storage = JsonFileStorage::new(&path);
+    storage.save_bank(&bank).unwrap();
+
+    bank.enable_journal();
+    bank.archive_transactions(start() + Duration::days(3)).unwrap();
+    let entries = bank.take_journal();
+    storage.append_journal(&bank, &entries).unwrap();
+
+    let loaded = storage.load_bank().unwrap();
+    assert!(loaded.has_archive());
+    assert_eq!(live(&loaded, &alice), 1);
+    assert!(loaded.verify_integrity().is_clean());
+    let statement = loaded.statement(&alice, start(), start() + Duration::days(10)).unwrap();
+    assert_eq!(statement.transactions.len(), 3);
+    assert_eq!(statement.opening_balance, Money::ZERO);
+    let account_id = &loaded.get_customer(&alice).unwrap().get_account().unwrap().id;
+    assert!(Path::new(&archive_dir(&path)).join(format!("{}.jsonl", account_id)).exists());
+    remove(&path);
+}
+
+#[test]
+fn the_file_archive_stores_each_transaction_once() {
+    let dir = temp_file("file-archive");
+    let (bank, _, alice, _) = busy_bank(&dir);
+    let account = bank.get_customer(&alice).unwrap().get_account().unwrap();
+    let archive = FileArchive::new(&dir);
+    assert!(archive.load(&account.id).unwrap().is_empty());
+
+    archive.store(&account.id, &account.transactions[..2]).unwrap();
+    // An interrupted append leaves a torn line behind
+    let file = archive.dir().join(format!("{}.jsonl", account.id));
+    std::fs::OpenOptions::new().append(true).open(&file).unwrap().write_all(b"{\"id\":\"torn").unwrap();
+    archive.store(&account.id, &account.transactions).unwrap();
+
+    let stored: Vec<TransactionId> = archive.load(&account.id).unwrap().into_iter().map(|tx| tx.id).collect();
+    let expected: Vec<TransactionId> = account.transactions.iter().map(|tx| tx.id.clone()).collect();
+    assert_eq!(stored, expected);
+    assert!(matches!(archive.load(&"../escape".into()), Err(BankError::InvalidInput(_))));
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[cfg(feature = "cli")]
+#[test]
+fn command_mode_archives_and_still_shows_old_statements() {
+    let data_file = temp_file("cli.json");
+    remove(&data_file);
+    let (bank, _, _, _) = busy_bank(&archive_dir(&data_file));
+    JsonFileStorage::new(&data_file).save_bank(&bank).unwrap();
+    let run = |args: &[&str]| serde_json::from_str::<serde_json::Value>(&common::run(&data_file, args, "\n")).unwrap();
+
+    let json = run(&["--json", "archive", "--before", "2024-03-04"]);
+    assert_eq!(json["result"]["transactions"], 4);
+    let json = run(&["--json", "archive", "--before", "2999-01-01"]);
+    assert_eq!(json["ok"], false);
+    assert_eq!(json["kind"], "invalid_input");
+
+    let alice = ["--customer", "alice@example.com"];
+    let json = run(&[&["--json", "statement"], &alice[..], &["--from", "2024-03-01", "--to", "2024-03-31"]].concat());
+    assert_eq!(json["result"]["transactions"].as_array().unwrap().len(), 3);
+    assert_eq!(json["result"]["opening_balance"], "0.00");
+    assert_eq!(json["result"]["closing_balance"], "510.00");
+    let json = run(&[&["--json", "account"], &alice[..]].concat());
+    assert_eq!(json["result"]["account"]["transactions"].as_array().unwrap().len(), 1);
+    remove(&data_file);
+}
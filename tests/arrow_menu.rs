@@ -0,0 +1,127 @@
+//! Integration tests for the arrow-key menu (`arrow-menu` feature)
+#![cfg(feature = "arrow-menu")]
+
+use std::io;
+
+use clap::Parser;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use rust_banking_system::cli::args::Args;
+use rust_banking_system::cli::picker::{LineEditor, LineStep, Picker, Step};
+use rust_banking_system::cli::{is_cancelled, Cancelled};
+use rust_banking_system::Config;
+
+fn key(code: KeyCode) -> KeyEvent {
+    KeyEvent::new(code, KeyModifiers::NONE)
+}
+
+fn typed(picker: &mut Picker, text: &str) {
+    for c in text.chars() {
+        assert_eq!(picker.handle_key(key(KeyCode::Char(c))), Step::Continue);
+    }
+}
+
+fn menu() -> Picker {
+    Picker::new(vec![
+        (3, "Deposit Money".to_string()),
+        (4, "Withdraw Money".to_string()),
+        (5, "Transfer Money".to_string()),
+        (10, "View Bank Statistics".to_string()),
+        (0, "Exit".to_string()),
+    ])
+}
+
+#[test]
+fn arrows_move_the_selection_and_wrap_around() {
+    let mut picker = menu();
+    assert_eq!(picker.handle_key(key(KeyCode::Down)), Step::Continue);
+    assert_eq!(picker.handle_key(key(KeyCode::Down)), Step::Continue);
+    assert_eq!(picker.handle_key(key(KeyCode::Enter)), Step::Chosen(5));
+
+    picker.handle_key(key(KeyCode::Home));
+    picker.handle_key(key(KeyCode::Up));
+    assert_eq!(picker.handle_key(key(KeyCode::Enter)), Step::Chosen(0));
+    picker.handle_key(key(KeyCode::Down));
+    assert_eq!(picker.selected(), 0);
+}
+
+#[test]
+fn typing_filters_by_label_or_number() {
+    let mut picker = menu();
+    typed(&mut picker, "MONEY");
+    let labels: Vec<u8> = picker.visible().iter().map(|(number, _)| *number).collect();
+    assert_eq!(labels, [3, 4, 5]);
+
+    picker.handle_key(key(KeyCode::End));
+    assert_eq!(picker.handle_key(key(KeyCode::Enter)), Step::Chosen(5));
+
+    let mut by_number = menu();
+    typed(&mut by_number, "1");
+    assert_eq!(by_number.handle_key(key(KeyCode::Enter)), Step::Chosen(10));
+}
+
+#[test]
+fn filters_can_be_edited_and_cleared() {
+    let mut picker = menu();
+    typed(&mut picker, "wix");
+    assert!(picker.visible().is_empty());
+    // Nothing to choose, so Enter does nothing
+    assert_eq!(picker.handle_key(key(KeyCode::Enter)), Step::Continue);
+
+    picker.handle_key(key(KeyCode::Backspace));
+    assert_eq!(picker.filter(), "wi");
+    assert_eq!(picker.visible().len(), 1);
+
+    picker.handle_key(key(KeyCode::Esc));
+    assert_eq!(picker.filter(), "");
+    assert_eq!(picker.visible().len(), 5);
+    typed(&mut picker, "e");
+    picker.handle_key(KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL));
+    assert_eq!(picker.filter(), "");
+}
+
+#[test]
+fn changing_the_filter_resets_the_selection() {
+    let mut picker = menu();
+    picker.handle_key(key(KeyCode::Down));
+    picker.handle_key(key(KeyCode::Down));
+    assert_eq!(picker.selected(), 2);
+    typed(&mut picker, "w");
+    assert_eq!(picker.selected(), 0);
+}
+
+#[test]
+fn lines_are_typed_and_finished_with_enter() {
+    let mut editor = LineEditor::new();
+    for c in " 12.5x".chars() {
+        assert_eq!(editor.handle_key(key(KeyCode::Char(c))), LineStep::Continue);
+    }
+    editor.handle_key(key(KeyCode::Backspace));
+    assert_eq!(editor.text(), " 12.5");
+    assert_eq!(editor.handle_key(key(KeyCode::Enter)), LineStep::Done("12.5".to_string()));
+}
+
+#[test]
+fn escape_cancels_a_prompt() {
+    let mut editor = LineEditor::new();
+    editor.handle_key(key(KeyCode::Char('4')));
+    assert_eq!(editor.handle_key(key(KeyCode::Esc)), LineStep::Cancel);
+    assert_eq!(
+        LineEditor::new().handle_key(KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL)),
+        LineStep::Cancel
+    );
+}
+
+#[test]
+fn cancelled_prompts_are_recognised() {
+    assert!(is_cancelled(&io::Error::other(Cancelled)));
+    assert!(!is_cancelled(&io::Error::other("disk full")));
+    assert!(!is_cancelled(&io::Error::from(io::ErrorKind::UnexpectedEof)));
+}
+
+#[test]
+fn the_arrow_menu_is_chosen_by_flag_or_config() {
+    assert!(!Config::default().arrow_menu);
+    let args = Args::try_parse_from(["banking-cli", "--arrow-menu"]).unwrap();
+    assert!(args.startup.apply(Config::default(), None).unwrap().arrow_menu);
+    assert!(Config::from_toml("arrow_menu = true").unwrap().arrow_menu);
+}
@@ -0,0 +1,104 @@
+//! Integration tests for the async bank facade (run with `--features async`)
+
+#![cfg(feature = "async")]
+
+mod common;
+
+use std::sync::{Arc, Mutex};
+
+use rust_banking_system::bank::JournalEntry;
+use rust_banking_system::models::CustomerId;
+use rust_banking_system::{AsyncBank, Bank, BankError, BankResult, Money, Storage};
+
+use common::bank_with_customers;
+
+/// Storage that remembers what it was asked to write
+#[derive(Default)]
+struct RecordingStorage {
+    journal: Mutex<Vec<JournalEntry>>,
+    saves: Mutex<usize>,
+}
+
+impl Storage for RecordingStorage {
+    fn save_bank(&self, _bank: &Bank) -> BankResult<()> {
+        *self.saves.lock().unwrap() += 1;
+        Ok(())
+    }
+
+    fn load_bank(&self) -> BankResult<Bank> {
+        Ok(Bank::new("Test Bank".to_string()))
+    }
+
+    fn append_journal(&self, _bank: &Bank, entries: &[JournalEntry]) -> BankResult<()> {
+        self.journal.lock().unwrap().extend_from_slice(entries);
+        Ok(())
+    }
+}
+
+async fn balance(bank: &AsyncBank, id: &CustomerId) -> Money {
+    bank.read().await.get_customer(id).unwrap().get_account().unwrap().balance
+}
+
+#[tokio::test]
+async fn operations_change_balances() {
+    let (bank, alice, bob) = bank_with_customers(100, 100);
+    let bank = AsyncBank::new(bank);
+
+    assert_eq!(bank.deposit(&alice, Money::from_major(50)).await.unwrap(), Money::from_major(150));
+    assert_eq!(bank.withdraw(&alice, Money::from_major(30)).await.unwrap(), Money::from_major(120));
+    bank.transfer(&alice, &bob, Money::from_major(20)).await.unwrap();
+
+    assert_eq!(balance(&bank, &alice).await, Money::from_major(100));
+    assert_eq!(balance(&bank, &bob).await, Money::from_major(120));
+}
+
+#[tokio::test]
+async fn changes_are_journaled_to_storage() {
+    let (bank, alice, bob) = bank_with_customers(100, 100);
+    let storage = Arc::new(RecordingStorage::default());
+    let bank = AsyncBank::with_storage(bank, storage.clone());
+
+    bank.deposit(&alice, Money::from_major(10)).await.unwrap();
+    bank.transfer(&alice, &bob, Money::from_major(5)).await.unwrap();
+    assert_eq!(storage.journal.lock().unwrap().len(), 2);
+
+    // A failed operation changes nothing, so nothing is written
+    let result = bank.withdraw(&bob, Money::from_major(1_000)).await;
+    assert!(matches!(result, Err(BankError::InsufficientFunds { .. })));
+    assert_eq!(storage.journal.lock().unwrap().len(), 2);
+
+    bank.save().await.unwrap();
+    assert_eq!(*storage.saves.lock().unwrap(), 1);
+}
+
+#[tokio::test]
+async fn save_without_storage_fails() {
+    let bank = AsyncBank::new(Bank::new("Test Bank".to_string()));
+    assert!(matches!(bank.save().await, Err(BankError::IoError(_))));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn concurrent_transfers_conserve_money() {
+    let (bank, alice, bob) = bank_with_customers(100, 100);
+    let storage = Arc::new(RecordingStorage::default());
+    let bank = AsyncBank::with_storage(bank, storage.clone());
+
+    let tasks: Vec<_> = (0..100)
+        .map(|i| {
+            let bank = bank.clone();
+            let (from, to) = if i % 2 == 0 { (alice.clone(), bob.clone()) } else { (bob.clone(), alice.clone()) };
+            tokio::spawn(async move { bank.transfer(&from, &to, Money::from_major(3)).await.is_ok() })
+        })
+        .collect();
+
+    let mut succeeded = 0;
+    for task in tasks {
+        if task.await.unwrap() {
+            succeeded += 1;
+        }
+    }
+
+    assert_eq!(balance(&bank, &alice).await + balance(&bank, &bob).await, Money::from_major(200));
+    assert_eq!(storage.journal.lock().unwrap().len(), succeeded);
+    assert_eq!(bank.report().await.total_transactions, 2 + succeeded as u64 * 2);
+}
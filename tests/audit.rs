@@ -0,0 +1,166 @@
+//! Integration tests for the balance integrity checker
+
+use rust_banking_system::bank::{IntegrityIssue, SeedOptions};
+use rust_banking_system::models::CustomerId;
+use rust_banking_system::{AccountType, Bank, Money};
+use serde_json::{json, Value};
+
+/// Creates a bank where Alice paid Bob $40 and Bob's later deposit was
+/// undone, and returns it as JSON so tests can corrupt it the way a
+/// hand-edited data file would be
+fn bank_json() -> (Value, CustomerId, CustomerId) {
+    let mut bank = Bank::new("Test Bank".to_string());
+    let alice = bank
+        .register_customer("Alice".to_string(), "alice@example.com".to_string())
+        .unwrap();
+    let bob = bank
+        .register_customer("Bob".to_string(), "bob@example.com".to_string())
+        .unwrap();
+    bank.create_account_for_customer(&alice, Money::from_major(100), AccountType::Checking)
+        .unwrap();
+    bank.create_account_for_customer(&bob, Money::ZERO, AccountType::Checking)
+        .unwrap();
+    bank.transfer(&alice, &bob, Money::from_major(40)).unwrap();
+    bank.deposit(&bob, Money::from_major(5)).unwrap();
+    bank.undo_last().unwrap();
+    (serde_json::to_value(&bank).unwrap(), alice, bob)
+}
+
+fn account<'a>(bank: &'a mut Value, customer_id: &CustomerId) -> &'a mut Value {
+    &mut bank["customers"][customer_id.as_str()]["account"]
+}
+
+/// An ID as stored in the JSON
+fn id<T: From<String>>(value: &Value) -> T {
+    value.as_str().unwrap().to_string().into()
+}
+
+fn issues(bank: Value) -> Vec<IntegrityIssue> {
+    serde_json::from_value::<Bank>(bank).unwrap().verify_integrity().issues
+}
+
+#[test]
+fn consistent_banks_pass() {
+    let (bank, _, _) = bank_json();
+    let report = serde_json::from_value::<Bank>(bank).unwrap().verify_integrity();
+    assert!(report.is_clean(), "{}", report);
+    assert_eq!(report.accounts_checked, 2);
+    assert_eq!(report.transactions_checked, 5);
+
+    let mut seeded = Bank::new("Demo Bank".to_string());
+    seeded.seed(SeedOptions::new(10).rng_seed(7)).unwrap();
+    assert!(seeded.verify_integrity().is_clean());
+}
+
+#[test]
+fn balance_that_disagrees_with_history_is_reported() {
+    let (mut bank, alice, _) = bank_json();
+    let account = account(&mut bank, &alice);
+    let account_id = id(&account["id"]);
+    account["balance"] = json!("75.00");
+
+    assert_eq!(
+        issues(bank),
+        vec![IntegrityIssue::BalanceMismatch {
+            customer_id: alice,
+            account_id,
+            recorded: Money::from_major(75),
+            derived: Money::from_major(60),
+        }]
+    );
+}
+
+#[test]
+fn edited_amounts_break_the_running_balance() {
+    let (mut bank, alice, _) = bank_json();
+    let deposit = &mut account(&mut bank, &alice)["transactions"][0];
+    let transaction_id = id(&deposit["id"]);
+    deposit["amount"] = json!("90.00");
+
+    let issues = issues(bank);
+    assert!(issues.contains(&IntegrityIssue::RunningBalanceMismatch {
+        customer_id: alice.clone(),
+        transaction_id,
+        recorded: Money::from_major(100),
+        derived: Money::from_major(90),
+    }));
+    // The transfer's recorded balance and the account balance are off too
+    assert!(issues.iter().any(|i| matches!(i, IntegrityIssue::BalanceMismatch { .. })));
+}
+
+#[test]
+fn negative_balances_are_reported() {
+    let (mut bank, _, bob) = bank_json();
+    let account = account(&mut bank, &bob);
+    let account_id = id(&account["id"]);
+    account["balance"] = json!("-10.00");
+
+    assert!(issues(bank).contains(&IntegrityIssue::NegativeBalance {
+        customer_id: bob,
+        account_id,
+        balance: Money::from_major(-10),
+    }));
+}
+
+#[test]
+fn transfers_to_unknown_accounts_are_orphaned() {
+    let (mut bank, alice, _) = bank_json();
+    let transfer = &mut account(&mut bank, &alice)["transactions"][1];
+    let transaction_id = id(&transfer["id"]);
+    transfer["transaction_type"]["Transfer"]["to_account_id"] = json!("no-such-account");
+
+    let issues = issues(bank);
+    assert!(issues.contains(&IntegrityIssue::OrphanedTransfer {
+        customer_id: alice,
+        transaction_id,
+        counterparty: "no-such-account".into(),
+    }));
+    // Bob's incoming half has lost its partner
+    assert!(issues.iter().any(|i| matches!(i, IntegrityIssue::UnmatchedTransfer { .. })));
+}
+
+#[test]
+fn transfer_halves_must_agree() {
+    let (mut bank, alice, bob) = bank_json();
+    let bob_account_id = id(&account(&mut bank, &bob)["id"]);
+    let transfer = &mut account(&mut bank, &alice)["transactions"][1];
+    let transaction_id = id(&transfer["id"]);
+    transfer["timestamp"] = json!("2020-01-01T00:00:00Z");
+
+    let issues = issues(bank);
+    assert_eq!(issues.len(), 2, "{:?}", issues);
+    assert!(issues.contains(&IntegrityIssue::UnmatchedTransfer {
+        customer_id: alice,
+        transaction_id,
+        counterparty: bob_account_id,
+    }));
+}
+
+#[test]
+fn reversals_of_unknown_transactions_are_orphaned() {
+    let (mut bank, _, bob) = bank_json();
+    let reversal = &mut account(&mut bank, &bob)["transactions"][2];
+    let transaction_id = id(&reversal["id"]);
+    reversal["transaction_type"]["Reversal"]["original_id"] = json!("missing");
+
+    assert_eq!(
+        issues(bank),
+        vec![IntegrityIssue::OrphanedReversal {
+            customer_id: bob,
+            transaction_id,
+            original_id: "missing".into(),
+        }]
+    );
+}
+
+#[test]
+fn report_serializes_with_tagged_issues() {
+    let (mut bank, alice, _) = bank_json();
+    account(&mut bank, &alice)["balance"] = json!("1.00");
+    let report = serde_json::from_value::<Bank>(bank).unwrap().verify_integrity();
+
+    let json = serde_json::to_value(&report).unwrap();
+    assert_eq!(json["issues"][0]["issue"], "balance_mismatch");
+    assert_eq!(json["issues"][0]["derived"], "60.00");
+    assert!(report.to_string().contains("1 problem(s) found"));
+}
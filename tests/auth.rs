@@ -0,0 +1,88 @@
+//! Integration tests for customer PINs and lockout
+
+use rust_banking_system::models::CustomerId;
+use rust_banking_system::models::credentials::MAX_PIN_ATTEMPTS;
+use rust_banking_system::{Bank, BankError};
+
+/// Creates a bank with one customer whose PIN is 1234
+fn bank_with_customer() -> (Bank, CustomerId) {
+    let mut bank = Bank::new("Test Bank".to_string());
+    let id = bank
+        .register_customer("Alice".to_string(), "alice@example.com".to_string())
+        .unwrap();
+    bank.set_pin(&id, "1234").unwrap();
+    (bank, id)
+}
+
+#[test]
+fn correct_pin_authenticates() {
+    let (mut bank, id) = bank_with_customer();
+    assert!(bank.authenticate(&id, "1234").is_ok());
+    assert!(bank.get_customer(&id).unwrap().has_pin());
+}
+
+#[test]
+fn malformed_pins_are_rejected() {
+    let mut bank = Bank::new("Test Bank".to_string());
+    let id = bank
+        .register_customer("Bob".to_string(), "bob@example.com".to_string())
+        .unwrap();
+
+    for pin in ["12", "12ab", "1234567890123"] {
+        assert!(matches!(bank.set_pin(&id, pin), Err(BankError::InvalidInput(_))));
+    }
+    assert!(matches!(bank.authenticate(&id, "1234"), Err(BankError::PinNotSet(_))));
+}
+
+#[test]
+fn repeated_failures_lock_the_customer_out() {
+    let (mut bank, id) = bank_with_customer();
+
+    for attempt in 1..MAX_PIN_ATTEMPTS {
+        match bank.authenticate(&id, "0000") {
+            Err(BankError::InvalidPin { attempts_left }) => {
+                assert_eq!(attempts_left, MAX_PIN_ATTEMPTS - attempt)
+            }
+            other => panic!("expected InvalidPin, got {:?}", other),
+        }
+    }
+    assert!(matches!(bank.authenticate(&id, "0000"), Err(BankError::PinLocked { .. })));
+
+    // Even the right PIN is refused while locked
+    assert!(matches!(bank.authenticate(&id, "1234"), Err(BankError::PinLocked { .. })));
+}
+
+#[test]
+fn change_pin_requires_the_current_pin() {
+    let (mut bank, id) = bank_with_customer();
+
+    assert!(matches!(
+        bank.change_pin(&id, "9999", "5678"),
+        Err(BankError::InvalidPin { .. })
+    ));
+    bank.change_pin(&id, "1234", "5678").unwrap();
+
+    assert!(bank.authenticate(&id, "5678").is_ok());
+    assert!(bank.authenticate(&id, "1234").is_err());
+}
+
+#[test]
+fn failed_attempts_survive_journal_replay() {
+    let (mut bank, id) = bank_with_customer();
+    bank.enable_journal();
+    let snapshot = serde_json::to_string(&bank).unwrap();
+
+    let _ = bank.authenticate(&id, "0000");
+    let entries = bank.take_journal();
+    assert_eq!(entries.len(), 1);
+
+    // Replaying onto the snapshot from before the failure restores the count
+    let mut restored: Bank = serde_json::from_str(&snapshot).unwrap();
+    assert!(restored.apply_journal_entry(entries[0].clone()).unwrap());
+    match restored.authenticate(&id, "0000") {
+        Err(BankError::InvalidPin { attempts_left }) => {
+            assert_eq!(attempts_left, MAX_PIN_ATTEMPTS - 2)
+        }
+        other => panic!("expected InvalidPin, got {:?}", other),
+    }
+}
@@ -0,0 +1,159 @@
+//! Integration tests for authorized withdrawals and available balances
+
+mod common;
+
+use std::sync::{Arc, Mutex};
+
+use rust_banking_system::models::{CustomerId, TransactionDetails, TransactionStatus, TransactionType};
+use rust_banking_system::{Bank, BankError, DomainEvent, Money};
+
+use common::bank_with_customers;
+
+/// (ledger, available) balances of `customer_id`
+fn balances(bank: &Bank, customer_id: &CustomerId) -> (Money, Money) {
+    let account = bank.get_customer(customer_id).unwrap().get_account().unwrap();
+    (account.ledger_balance(), account.available_balance())
+}
+
+#[test]
+fn authorizing_holds_money_without_posting_it() {
+    let (mut bank, alice, _) = bank_with_customers(1_000, 100);
+    let authorization = bank.authorize_withdrawal(&alice, Money::from_major(300)).unwrap();
+
+    assert_eq!(authorization.status, TransactionStatus::Pending);
+    assert!(matches!(authorization.transaction_type, TransactionType::Withdrawal));
+    assert_eq!(balances(&bank, &alice), (Money::from_major(1_000), Money::from_major(700)));
+
+    let account = bank.get_customer(&alice).unwrap().get_account().unwrap();
+    assert_eq!(account.transactions.len(), 1);
+    assert_eq!(account.held_amount(), Money::from_major(300));
+    assert_eq!(account.pending_transactions().count(), 1);
+}
+
+#[test]
+fn held_money_cannot_be_spent_twice() {
+    let (mut bank, alice, bob) = bank_with_customers(1_000, 100);
+    bank.authorize_withdrawal(&alice, Money::from_major(800)).unwrap();
+
+    let overdraw = |result| matches!(result, Err(BankError::InsufficientFunds { available, .. }) if available == Money::from_major(200));
+    assert!(overdraw(bank.withdraw(&alice, Money::from_major(250)).map(|_| ())));
+    assert!(overdraw(bank.transfer(&alice, &bob, Money::from_major(250))));
+    assert!(overdraw(bank.authorize_withdrawal(&alice, Money::from_major(250)).map(|_| ())));
+
+    bank.withdraw(&alice, Money::from_major(200)).unwrap();
+    assert_eq!(balances(&bank, &alice), (Money::from_major(800), Money::ZERO));
+}
+
+#[test]
+fn capturing_posts_the_withdrawal() {
+    let (mut bank, alice, _) = bank_with_customers(1_000, 100);
+    let details = TransactionDetails::new().description("Coffee shop").unwrap();
+    let authorization = bank.authorize_withdrawal_with(&alice, Money::from_major(300), details).unwrap();
+    bank.deposit(&alice, Money::from_major(50)).unwrap();
+
+    let captured = bank.capture(&authorization.id).unwrap();
+    assert_eq!(captured.id, authorization.id);
+    assert_eq!(captured.status, TransactionStatus::Completed);
+    assert_eq!(captured.balance_after, Money::from_major(750));
+    assert_eq!(captured.description.as_deref(), Some("Coffee shop"));
+
+    assert_eq!(balances(&bank, &alice), (Money::from_major(750), Money::from_major(750)));
+    let account = bank.get_customer(&alice).unwrap().get_account().unwrap();
+    assert!(account.authorizations.is_empty());
+    assert_eq!(account.transactions.last().unwrap().id, authorization.id);
+    assert!(bank.verify_integrity().is_clean());
+
+    assert!(matches!(bank.capture(&authorization.id), Err(BankError::InvalidInput(_))));
+    assert!(matches!(bank.void(&authorization.id), Err(BankError::InvalidInput(_))));
+}
+
+#[test]
+fn voiding_releases_the_hold() {
+    let (mut bank, alice, _) = bank_with_customers(1_000, 100);
+    let authorization = bank.authorize_withdrawal(&alice, Money::from_major(300)).unwrap();
+
+    let voided = bank.void(&authorization.id).unwrap();
+    assert_eq!(voided.status, TransactionStatus::Reversed);
+    assert_eq!(balances(&bank, &alice), (Money::from_major(1_000), Money::from_major(1_000)));
+    assert_eq!(bank.get_customer(&alice).unwrap().get_account().unwrap().transactions.len(), 1);
+    assert!(matches!(bank.capture(&authorization.id), Err(BankError::InvalidInput(_))));
+}
+
+#[test]
+fn authorizations_follow_the_withdrawal_rules() {
+    let (mut bank, alice, _) = bank_with_customers(1_000, 100);
+    assert!(matches!(bank.authorize_withdrawal(&alice, Money::ZERO), Err(BankError::InvalidAmount(_))));
+    assert!(matches!(
+        bank.authorize_withdrawal(&alice, Money::from_major(1_001)),
+        Err(BankError::InsufficientFunds { .. })
+    ));
+
+    let authorization = bank.authorize_withdrawal(&alice, Money::from_major(100)).unwrap();
+    bank.freeze_account(&alice).unwrap();
+    assert!(matches!(bank.authorize_withdrawal(&alice, Money::from_major(1)), Err(BankError::AccountFrozen(_))));
+
+    // Already approved, so it can still be captured
+    bank.capture(&authorization.id).unwrap();
+    assert_eq!(balances(&bank, &alice), (Money::from_major(900), Money::from_major(900)));
+}
+
+#[test]
+fn accounts_with_authorizations_cannot_be_closed() {
+    let (mut bank, alice, bob) = bank_with_customers(1_000, 100);
+    let authorization = bank.authorize_withdrawal(&alice, Money::from_major(300)).unwrap();
+
+    assert!(matches!(bank.close_account(&alice, Some(&bob)), Err(BankError::InvalidInput(_))));
+    bank.void(&authorization.id).unwrap();
+    assert_eq!(bank.close_account(&alice, Some(&bob)).unwrap(), Money::from_major(1_000));
+}
+
+#[test]
+fn undone_transactions_are_marked_reversed() {
+    let (mut bank, alice, _) = bank_with_customers(1_000, 100);
+    bank.withdraw(&alice, Money::from_major(50)).unwrap();
+    bank.undo_last().unwrap();
+
+    let account = bank.get_customer(&alice).unwrap().get_account().unwrap();
+    let statuses: Vec<_> = account.transactions.iter().map(|tx| tx.status).collect();
+    assert_eq!(statuses, [TransactionStatus::Completed, TransactionStatus::Reversed, TransactionStatus::Completed]);
+}
+
+#[test]
+fn authorizations_survive_journal_replay() {
+    let (mut bank, alice, _) = bank_with_customers(1_000, 100);
+    let snapshot = serde_json::to_string(&bank).unwrap();
+    bank.enable_journal();
+
+    let captured = bank.authorize_withdrawal(&alice, Money::from_major(100)).unwrap();
+    let voided = bank.authorize_withdrawal(&alice, Money::from_major(50)).unwrap();
+    let waiting = bank.authorize_withdrawal(&alice, Money::from_major(25)).unwrap();
+    bank.capture(&captured.id).unwrap();
+    bank.void(&voided.id).unwrap();
+
+    let mut restored: Bank = serde_json::from_str(&snapshot).unwrap();
+    for entry in bank.take_journal() {
+        restored.apply_journal_entry(entry).unwrap();
+    }
+    assert_eq!(balances(&restored, &alice), (Money::from_major(900), Money::from_major(875)));
+    let account = restored.get_customer(&alice).unwrap().get_account().unwrap();
+    assert_eq!(account.authorizations.len(), 1);
+    assert_eq!(account.authorizations[0].id, waiting.id);
+    assert_eq!(restored.total_transactions, bank.total_transactions);
+}
+
+#[test]
+fn only_captures_are_published() {
+    let (mut bank, alice, _) = bank_with_customers(1_000, 100);
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let sink = events.clone();
+    bank.subscribe(move |event| sink.lock().unwrap().push(event.clone()));
+
+    let captured = bank.authorize_withdrawal(&alice, Money::from_major(100)).unwrap();
+    let voided = bank.authorize_withdrawal(&alice, Money::from_major(50)).unwrap();
+    bank.void(&voided.id).unwrap();
+    bank.capture(&captured.id).unwrap();
+
+    let events = events.lock().unwrap();
+    assert_eq!(events.len(), 1);
+    assert!(matches!(&events[0], DomainEvent::TransactionPosted { transaction, .. } if transaction.id == captured.id));
+}
@@ -0,0 +1,106 @@
+//! Integration tests for batch processing
+
+mod common;
+
+use rust_banking_system::bank::{BatchMode, Operation};
+use rust_banking_system::models::CustomerId;
+use rust_banking_system::{persistence, Bank, BankError, Money};
+
+use common::{balance, bank_with_customers, temp_file};
+
+/// Payroll-style batch: pay bob twice from alice, then overdraw bob
+fn payroll(alice: &CustomerId, bob: &CustomerId) -> Vec<Operation> {
+    vec![
+        Operation::Transfer {
+            from: alice.to_string(),
+            to: "bob@example.com".to_string(),
+            amount: Money::from_major(40),
+            description: Some("Salary".to_string()),
+        },
+        Operation::Deposit { customer: bob.to_string(), amount: Money::from_major(5), description: None },
+        Operation::Withdraw { customer: bob.to_string(), amount: Money::from_major(1_000), description: None },
+    ]
+}
+
+#[test]
+fn best_effort_applies_every_valid_operation() {
+    let (mut bank, alice, bob) = bank_with_customers(100, 100);
+
+    let results = bank.apply_batch(payroll(&alice, &bob), BatchMode::BestEffort);
+
+    assert_eq!(results[0].as_ref().unwrap(), &Money::from_major(60));
+    assert_eq!(results[1].as_ref().unwrap(), &Money::from_major(145));
+    assert!(matches!(results[2], Err(BankError::InsufficientFunds { .. })));
+    assert_eq!(balance(&bank, &alice), Money::from_major(60));
+    assert_eq!(balance(&bank, &bob), Money::from_major(145));
+
+    let salary = bank.get_customer(&bob).unwrap().get_account().unwrap().transactions[1].clone();
+    assert_eq!(salary.description.as_deref(), Some("Salary"));
+}
+
+#[test]
+fn all_or_nothing_rolls_back_on_failure() {
+    let (mut bank, alice, bob) = bank_with_customers(100, 100);
+    bank.enable_journal();
+
+    let results = bank.apply_batch(payroll(&alice, &bob), BatchMode::AllOrNothing);
+
+    assert!(results.iter().all(Result::is_err));
+    assert!(matches!(&results[0], Err(BankError::InvalidInput(msg)) if msg.contains("operation 3")));
+    assert_eq!(balance(&bank, &alice), Money::from_major(100));
+    assert_eq!(balance(&bank, &bob), Money::from_major(100));
+    assert!(bank.take_journal().is_empty());
+}
+
+#[test]
+fn all_or_nothing_commits_and_journals_a_valid_batch() {
+    let (mut bank, alice, bob) = bank_with_customers(100, 100);
+    let snapshot = serde_json::to_string(&bank).unwrap();
+    bank.enable_journal();
+
+    let mut operations = payroll(&alice, &bob);
+    operations.pop();
+    let results = bank.apply_batch(operations, BatchMode::AllOrNothing);
+
+    assert!(results.iter().all(Result::is_ok));
+    assert_eq!(balance(&bank, &bob), Money::from_major(145));
+
+    // Replaying the journal onto the earlier snapshot reaches the same state
+    let mut replayed: Bank = serde_json::from_str(&snapshot).unwrap();
+    for entry in bank.take_journal() {
+        replayed.apply_journal_entry(entry).unwrap();
+    }
+    assert_eq!(balance(&replayed, &alice), Money::from_major(60));
+    assert_eq!(balance(&replayed, &bob), Money::from_major(145));
+}
+
+#[test]
+fn batch_files_load_from_csv_and_json() {
+    let (csv_path, json_path) = (temp_file("batch.csv"), temp_file("batch.json"));
+
+    std::fs::write(
+        &csv_path,
+        "type,customer,to,amount,description\n\
+         transfer,alice@example.com,bob@example.com,40.00,Salary\n\
+         deposit,bob@example.com,,5.00,\n",
+    )
+    .unwrap();
+    let from_csv = persistence::load_batch(&csv_path).unwrap();
+
+    std::fs::write(&json_path, serde_json::to_string(&from_csv).unwrap()).unwrap();
+    let from_json = persistence::load_batch(&json_path).unwrap();
+
+    std::fs::write(&csv_path, "type,customer,to,amount\ntransfer,alice@example.com,,1.00\n").unwrap();
+    let missing_to = persistence::load_batch(&csv_path);
+
+    std::fs::remove_file(&csv_path).unwrap();
+    std::fs::remove_file(&json_path).unwrap();
+
+    assert_eq!(from_csv.len(), 2);
+    assert_eq!(
+        from_csv[1],
+        Operation::Deposit { customer: "bob@example.com".to_string(), amount: Money::from_major(5), description: None }
+    );
+    assert_eq!(from_csv, from_json);
+    assert!(matches!(missing_to, Err(BankError::SerializationError(msg)) if msg.to_string().starts_with("line 2")));
+}
@@ -0,0 +1,133 @@
+//! Integration tests for saved payees (beneficiaries)
+
+mod common;
+
+use rust_banking_system::models::{CustomerId, TransactionDetails};
+use rust_banking_system::validation::MAX_NICKNAME_LEN;
+use rust_banking_system::{AccountType, Bank, BankError, Money};
+
+use common::{balance, bank_with_customers};
+
+/// Creates a bank with funded Alice, and Bob and Carol with empty
+/// accounts; returns (bank, alice, bob, carol)
+fn bank_with_three_customers() -> (Bank, CustomerId, CustomerId, CustomerId) {
+    let (mut bank, alice, bob) = bank_with_customers(1_000, 0);
+    let carol = bank
+        .register_customer("Carol".to_string(), "carol@example.com".to_string())
+        .unwrap();
+    bank.create_account_for_customer(&carol, Money::ZERO, AccountType::Checking)
+        .unwrap();
+    (bank, alice, bob, carol)
+}
+
+#[test]
+fn payees_are_saved_with_the_account_id() {
+    let (mut bank, alice, bob, _) = bank_with_three_customers();
+    let payee = bank.add_beneficiary(&alice, "  Landlord ", "bob@example.com").unwrap();
+
+    assert_eq!(payee.nickname, "Landlord");
+    assert_eq!(payee.account_id, bank.get_customer(&bob).unwrap().get_account_id().unwrap());
+    assert_eq!(bank.list_beneficiaries(&alice).unwrap(), vec![&payee]);
+    assert!(bank.list_beneficiaries(&bob).unwrap().is_empty());
+}
+
+#[test]
+fn payees_can_be_given_by_account_number() {
+    let (mut bank, alice, _, carol) = bank_with_three_customers();
+    let number = bank.get_customer(&carol).unwrap().get_account().unwrap().number.unwrap();
+    let payee = bank.add_beneficiary(&alice, "carol", &number.to_string()).unwrap();
+    assert_eq!(bank.resolve_beneficiary(&alice, "carol").unwrap(), carol);
+    assert_eq!(payee.account_id, bank.get_customer(&carol).unwrap().get_account_id().unwrap());
+}
+
+#[test]
+fn transfers_go_to_the_payee_by_nickname() {
+    let (mut bank, alice, bob, _) = bank_with_three_customers();
+    bank.add_beneficiary(&alice, "Landlord", bob.as_str()).unwrap();
+
+    let details = TransactionDetails::new().description("March rent").unwrap();
+    bank.transfer_to_beneficiary(&alice, "LANDLORD", Money::from_major(600), details)
+        .unwrap();
+
+    assert_eq!(balance(&bank, &alice), Money::from_major(400));
+    assert_eq!(balance(&bank, &bob), Money::from_major(600));
+}
+
+#[test]
+fn nicknames_are_unique_per_customer_ignoring_case() {
+    let (mut bank, alice, bob, carol) = bank_with_three_customers();
+    bank.add_beneficiary(&alice, "Family", bob.as_str()).unwrap();
+
+    assert!(matches!(
+        bank.add_beneficiary(&alice, "family", carol.as_str()),
+        Err(BankError::ValidationError { field, .. }) if field == "nickname"
+    ));
+    // Another customer may use the same nickname
+    bank.add_beneficiary(&bob, "Family", carol.as_str()).unwrap();
+}
+
+#[test]
+fn invalid_payees_are_rejected() {
+    let (mut bank, alice, _, _) = bank_with_three_customers();
+
+    assert!(matches!(bank.add_beneficiary(&alice, "me", alice.as_str()), Err(BankError::InvalidTransfer(_))));
+    assert!(matches!(
+        bank.add_beneficiary(&alice, "ghost", "nobody@example.com"),
+        Err(BankError::CustomerNotFound(_))
+    ));
+    assert!(matches!(bank.add_beneficiary(&alice, "  ", "bob@example.com"), Err(BankError::ValidationError { .. })));
+    let long = "x".repeat(MAX_NICKNAME_LEN + 1);
+    assert!(matches!(bank.add_beneficiary(&alice, &long, "bob@example.com"), Err(BankError::ValidationError { .. })));
+
+    let dave = bank.register_customer("Dave".to_string(), "dave@example.com".to_string()).unwrap();
+    assert!(matches!(bank.add_beneficiary(&alice, "dave", dave.as_str()), Err(BankError::AccountNotFound(_))));
+}
+
+#[test]
+fn removed_payees_can_no_longer_be_paid() {
+    let (mut bank, alice, bob, _) = bank_with_three_customers();
+    bank.add_beneficiary(&alice, "Bob", bob.as_str()).unwrap();
+
+    let removed = bank.remove_beneficiary(&alice, "bob").unwrap();
+    assert_eq!(removed.nickname, "Bob");
+    assert!(matches!(bank.remove_beneficiary(&alice, "bob"), Err(BankError::BeneficiaryNotFound(_))));
+    assert!(matches!(
+        bank.transfer_to_beneficiary(&alice, "bob", Money::from_major(1), TransactionDetails::new()),
+        Err(BankError::BeneficiaryNotFound(_))
+    ));
+}
+
+#[test]
+fn payees_whose_account_is_gone_are_reported() {
+    let (mut bank, alice, bob, carol) = bank_with_three_customers();
+    bank.add_beneficiary(&alice, "bob", bob.as_str()).unwrap();
+    bank.add_beneficiary(&alice, "carol", carol.as_str()).unwrap();
+
+    bank.close_account(&bob, None).unwrap();
+    bank.delete_customer(&carol).unwrap();
+
+    for nickname in ["bob", "carol"] {
+        assert!(matches!(
+            bank.transfer_to_beneficiary(&alice, nickname, Money::from_major(1), TransactionDetails::new()),
+            Err(BankError::InvalidTransfer(_))
+        ));
+    }
+    assert_eq!(balance(&bank, &alice), Money::from_major(1_000));
+}
+
+#[test]
+fn payees_survive_journal_replay() {
+    let (mut bank, alice, bob, carol) = bank_with_three_customers();
+    let snapshot = serde_json::to_string(&bank).unwrap();
+    bank.enable_journal();
+    bank.add_beneficiary(&alice, "bob", bob.as_str()).unwrap();
+    bank.add_beneficiary(&alice, "carol", carol.as_str()).unwrap();
+    bank.remove_beneficiary(&alice, "bob").unwrap();
+
+    let mut restored: Bank = serde_json::from_str(&snapshot).unwrap();
+    for entry in bank.take_journal() {
+        restored.apply_journal_entry(entry).unwrap();
+    }
+    assert_eq!(restored.list_beneficiaries(&alice).unwrap(), bank.list_beneficiaries(&alice).unwrap());
+    assert_eq!(restored.resolve_beneficiary(&alice, "carol").unwrap(), carol);
+}
@@ -0,0 +1,183 @@
+//! Integration tests for merchants, bill payments and autopay
+
+mod common;
+
+use std::sync::Arc;
+
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use rust_banking_system::clock::MockClock;
+use rust_banking_system::models::{CustomerId, MerchantCategory, TransactionKind, TransactionType};
+use rust_banking_system::{AccountType, Bank, BankError, Frequency, Money};
+
+use common::balance;
+
+fn start() -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2024, 3, 1, 9, 0, 0).unwrap()
+}
+
+/// Creates a bank on a mock clock with one merchant, where Alice has
+/// $1,000; returns (bank, clock, alice, merchant ID)
+fn bank_with_merchant() -> (Bank, Arc<MockClock>, CustomerId, String) {
+    let clock = Arc::new(MockClock::new(start()));
+    let mut bank = Bank::new("Test Bank".to_string());
+    bank.set_clock(clock.clone());
+    let alice = bank.register_customer("Alice".to_string(), "alice@example.com".to_string()).unwrap();
+    bank.create_account_for_customer(&alice, Money::from_major(1_000), AccountType::Checking)
+        .unwrap();
+    let power = bank.add_merchant("City Power", MerchantCategory::Utility).unwrap();
+    (bank, clock, alice, power.id)
+}
+
+#[test]
+fn merchants_are_listed_by_name_and_found_by_name_or_id() {
+    let (mut bank, _, _, power) = bank_with_merchant();
+    let phone = bank.add_merchant("  Acme Mobile ", MerchantCategory::Telecom).unwrap();
+
+    let names: Vec<_> = bank.list_merchants().iter().map(|m| m.name.clone()).collect();
+    assert_eq!(names, ["Acme Mobile", "City Power"]);
+    assert_eq!(bank.find_merchant("city power").unwrap().id, power);
+    assert_eq!(bank.find_merchant(&phone.id).unwrap().category, MerchantCategory::Telecom);
+    assert!(matches!(bank.find_merchant("Gas Co"), Err(BankError::MerchantNotFound(_))));
+    assert!(matches!(
+        bank.add_merchant("CITY POWER", MerchantCategory::Other),
+        Err(BankError::InvalidInput(_))
+    ));
+}
+
+#[test]
+fn paying_a_bill_posts_a_bill_payment() {
+    let (mut bank, _, alice, power) = bank_with_merchant();
+
+    let payment = bank.pay_bill(&alice, "City Power", Money::from_major(80), " acc-1234 ").unwrap();
+
+    match &payment.transaction_type {
+        TransactionType::BillPayment { merchant_id, reference } => {
+            assert_eq!(merchant_id, &power);
+            assert_eq!(reference, "ACC-1234");
+        }
+        other => panic!("expected a bill payment, got {:?}", other),
+    }
+    assert_eq!(payment.transaction_type.kind(), TransactionKind::BillPayment);
+    assert_eq!(payment.description.as_deref(), Some("City Power"));
+    assert_eq!(balance(&bank, &alice), Money::from_major(920));
+    assert_eq!(bank.total_transactions, 2);
+}
+
+#[test]
+fn bill_payments_follow_the_account_rules() {
+    let (mut bank, _, alice, power) = bank_with_merchant();
+
+    assert!(matches!(
+        bank.pay_bill(&alice, &power, Money::from_major(5_000), "ACC-1"),
+        Err(BankError::InsufficientFunds { .. })
+    ));
+    assert!(matches!(bank.pay_bill(&alice, &power, Money::ZERO, "ACC-1"), Err(BankError::InvalidAmount(_))));
+    assert!(matches!(
+        bank.pay_bill(&alice, &power, Money::from_major(5), "no#refs!"),
+        Err(BankError::ValidationError { .. })
+    ));
+    assert!(matches!(
+        bank.pay_bill(&alice, "Gas Co", Money::from_major(5), "ACC-1"),
+        Err(BankError::MerchantNotFound(_))
+    ));
+
+    bank.freeze_account(&alice).unwrap();
+    assert!(matches!(
+        bank.pay_bill(&alice, &power, Money::from_major(5), "ACC-1"),
+        Err(BankError::AccountFrozen(_))
+    ));
+    assert_eq!(balance(&bank, &alice), Money::from_major(1_000));
+}
+
+#[test]
+fn autopay_pays_each_due_date_and_catches_up() {
+    let (mut bank, clock, alice, power) = bank_with_merchant();
+    let autopay = bank
+        .create_autopay(&alice, &power, Money::from_major(50), "ACC-1", Frequency::Weekly, start())
+        .unwrap();
+
+    clock.advance(Duration::days(15));
+    let runs = bank.run_due_autopays(bank.now());
+
+    let dates: Vec<_> = runs.iter().map(|run| run.run_at).collect();
+    assert_eq!(dates, [start(), start() + Duration::weeks(1), start() + Duration::weeks(2)]);
+    assert!(runs.iter().all(|run| run.result.is_ok()));
+    assert_eq!(balance(&bank, &alice), Money::from_major(850));
+
+    let rule = bank.autopays_for(&alice)[0].clone();
+    assert_eq!(rule.id, autopay.id);
+    assert_eq!(rule.run_count, 3);
+    assert_eq!(rule.next_run, start() + Duration::weeks(3));
+    assert!(bank.run_due_autopays(bank.now()).is_empty());
+}
+
+#[test]
+fn failed_autopay_runs_are_recorded_and_skipped() {
+    let (mut bank, clock, alice, power) = bank_with_merchant();
+    bank.create_autopay(&alice, &power, Money::from_major(600), "ACC-1", Frequency::Monthly, start())
+        .unwrap();
+
+    clock.advance(Duration::days(1));
+    assert!(bank.run_due_autopays(bank.now())[0].result.is_ok());
+    clock.advance(Duration::days(31));
+    let runs = bank.run_due_autopays(bank.now());
+
+    assert!(matches!(runs[0].result, Err(BankError::InsufficientFunds { .. })));
+    let rule = &bank.list_autopays()[0];
+    assert_eq!(rule.run_count, 1);
+    assert!(rule.last_error.as_deref().unwrap().contains("Insufficient"));
+    assert_eq!(rule.next_run, Utc.with_ymd_and_hms(2024, 5, 1, 9, 0, 0).unwrap());
+    assert_eq!(balance(&bank, &alice), Money::from_major(400));
+}
+
+#[test]
+fn merchants_with_autopay_rules_cannot_be_removed() {
+    let (mut bank, _, alice, power) = bank_with_merchant();
+    let autopay = bank
+        .create_autopay(&alice, &power, Money::from_major(50), "ACC-1", Frequency::Monthly, start())
+        .unwrap();
+
+    assert!(matches!(bank.remove_merchant("City Power"), Err(BankError::InvalidInput(_))));
+
+    bank.cancel_autopay(&autopay.id).unwrap();
+    assert!(matches!(bank.cancel_autopay(&autopay.id), Err(BankError::ScheduleNotFound(_))));
+    assert_eq!(bank.remove_merchant("City Power").unwrap().id, power);
+    assert!(bank.list_merchants().is_empty());
+}
+
+#[test]
+fn bill_payments_count_as_outgoing_in_summaries() {
+    let (mut bank, _, alice, power) = bank_with_merchant();
+    bank.pay_bill(&alice, &power, Money::from_major(80), "ACC-1").unwrap();
+    bank.pay_bill(&alice, &power, Money::from_major(20), "ACC-1").unwrap();
+
+    let summary = bank.get_customer(&alice).unwrap().get_account().unwrap().monthly_summary(2024, 3).unwrap();
+    assert_eq!(summary.bill_payments, Money::from_major(100));
+}
+
+#[test]
+fn bills_survive_saving_and_journal_replay() {
+    let (mut bank, clock, alice, power) = bank_with_merchant();
+    let snapshot = serde_json::to_string(&bank).unwrap();
+    bank.enable_journal();
+
+    bank.add_merchant("Acme Mobile", MerchantCategory::Telecom).unwrap();
+    bank.pay_bill(&alice, &power, Money::from_major(80), "ACC-1").unwrap();
+    bank.create_autopay(&alice, "Acme Mobile", Money::from_major(30), "077 123", Frequency::Weekly, start())
+        .unwrap();
+    clock.advance(Duration::days(8));
+    bank.run_due_autopays(bank.now());
+
+    let mut restored: Bank = serde_json::from_str(&snapshot).unwrap();
+    for entry in bank.take_journal() {
+        restored.apply_journal_entry(entry).unwrap();
+    }
+    assert_eq!(restored.list_merchants(), bank.list_merchants());
+    assert_eq!(restored.list_autopays(), bank.list_autopays());
+    assert_eq!(balance(&restored, &alice), Money::from_major(860));
+    assert_eq!(restored.total_transactions, bank.total_transactions);
+
+    let reloaded: Bank = serde_json::from_str(&serde_json::to_string(&bank).unwrap()).unwrap();
+    assert_eq!(reloaded.list_autopays(), bank.list_autopays());
+    assert_eq!(reloaded.list_merchants(), bank.list_merchants());
+}
@@ -0,0 +1,223 @@
+//! Integration tests for the double-entry books and the trial balance
+
+// The tests that run the binary need the `cli` feature; without it the
+// imports and helpers only they use go unused
+#![cfg_attr(not(feature = "cli"), allow(unused))]
+
+mod common;
+
+use std::sync::Arc;
+
+use chrono::{Months, TimeZone, Utc};
+use rust_banking_system::bank::{SeedOptions, TrialBalance};
+use rust_banking_system::clock::MockClock;
+use rust_banking_system::models::{BookAccount, CustomerId, MerchantCategory};
+use rust_banking_system::persistence::journal_path;
+use rust_banking_system::{AccountType, Bank, Money};
+
+use common::temp_path;
+
+/// Alice with $1,000 and Bob with $500, opened in cash; returns
+/// (bank, clock, alice, bob)
+fn bank_with_two_customers() -> (Bank, Arc<MockClock>, CustomerId, CustomerId) {
+    let clock = Arc::new(MockClock::new(Utc.with_ymd_and_hms(2024, 1, 15, 9, 0, 0).unwrap()));
+    let mut bank = Bank::new("Test Bank".to_string());
+    bank.set_clock(clock.clone());
+    let alice = bank.register_customer("Alice".to_string(), "alice@example.com".to_string()).unwrap();
+    let bob = bank.register_customer("Bob".to_string(), "bob@example.com".to_string()).unwrap();
+    bank.create_account_for_customer(&alice, Money::from_major(1000), AccountType::Checking).unwrap();
+    bank.create_account_for_customer(&bob, Money::from_major(500), AccountType::Checking).unwrap();
+    (bank, clock, alice, bob)
+}
+
+/// (debit, credit) of `account` in the trial balance, zero if it has no
+/// postings
+fn row(trial_balance: &TrialBalance, account: BookAccount) -> (Money, Money) {
+    trial_balance
+        .rows
+        .iter()
+        .find(|row| row.account == account)
+        .map_or((Money::ZERO, Money::ZERO), |row| (row.debit, row.credit))
+}
+
+#[test]
+fn every_transaction_is_a_balanced_entry() {
+    let (mut bank, _, alice, bob) = bank_with_two_customers();
+    bank.deposit(&alice, Money::from_major(200)).unwrap();
+    bank.withdraw(&bob, Money::from_major(50)).unwrap();
+    bank.transfer(&alice, &bob, Money::from_major(125)).unwrap();
+    bank.external_transfer(&bob, "DE89370400440532013000", Money::from_major(75)).unwrap();
+    let power = bank.add_merchant("City Power", MerchantCategory::Utility).unwrap();
+    bank.pay_bill(&alice, &power.id, Money::from_major(60), "ACC-1").unwrap();
+
+    let entries = bank.book_entries();
+    assert_eq!(entries.len(), 8);
+    assert!(entries.iter().all(|entry| entry.is_balanced()));
+    let transfer = entries.iter().filter(|entry| entry.memo.starts_with("TRANSFER")).count();
+    assert_eq!(transfer, 2, "each leg of a transfer is an entry of its own");
+
+    let trial_balance = bank.trial_balance();
+    assert!(trial_balance.is_balanced(), "{}", trial_balance);
+    assert_eq!(trial_balance.total_debits, trial_balance.total_credits);
+}
+
+#[test]
+fn accounts_on_the_other_side_follow_the_transaction_type() {
+    let (mut bank, _, alice, bob) = bank_with_two_customers();
+    bank.withdraw(&alice, Money::from_major(100)).unwrap();
+    bank.transfer(&alice, &bob, Money::from_major(300)).unwrap();
+    bank.external_transfer(&bob, "DE89370400440532013000", Money::from_major(40)).unwrap();
+
+    let trial_balance = bank.trial_balance();
+    assert_eq!(row(&trial_balance, BookAccount::Cash), (Money::from_major(1400), Money::ZERO));
+    assert_eq!(row(&trial_balance, BookAccount::Clearing), (Money::ZERO, Money::from_major(40)));
+    assert_eq!(row(&trial_balance, BookAccount::CustomerDeposits), (Money::ZERO, Money::from_major(1360)));
+    // Both legs of the transfer cancel out
+    assert_eq!(row(&trial_balance, BookAccount::InternalTransfers), (Money::ZERO, Money::ZERO));
+    assert_eq!(trial_balance.total_debits, Money::from_major(1400));
+}
+
+#[test]
+fn a_reversal_mirrors_the_entry_it_undoes() {
+    let (mut bank, _, alice, _) = bank_with_two_customers();
+    bank.withdraw(&alice, Money::from_major(100)).unwrap();
+    bank.undo_last().unwrap();
+
+    let entries = bank.book_entries();
+    let withdrawal = entries.iter().find(|entry| entry.memo == "WITHDRAWAL").unwrap();
+    let reversal = entries.iter().find(|entry| entry.memo.starts_with("REVERSAL")).unwrap();
+    assert_eq!(reversal.lines.len(), withdrawal.lines.len());
+    for (original, mirrored) in withdrawal.lines.iter().zip(&reversal.lines) {
+        assert_eq!(original.account, mirrored.account);
+        assert_eq!((original.debit, original.credit), (mirrored.credit, mirrored.debit));
+    }
+    assert_eq!(row(&bank.trial_balance(), BookAccount::Cash), (Money::from_major(1500), Money::ZERO));
+}
+
+#[test]
+fn term_deposit_interest_is_an_expense() {
+    let (mut bank, clock, alice, _) = bank_with_two_customers();
+    bank.open_term_deposit(&alice, Money::from_major(800), 500, 12).unwrap();
+    assert_eq!(row(&bank.trial_balance(), BookAccount::TermDeposits), (Money::ZERO, Money::from_major(800)));
+
+    let maturity = bank.now().checked_add_months(Months::new(12)).unwrap();
+    clock.set(maturity);
+    bank.process_maturities(maturity).unwrap();
+
+    let trial_balance = bank.trial_balance();
+    assert!(trial_balance.is_balanced(), "{}", trial_balance);
+    assert_eq!(row(&trial_balance, BookAccount::TermDeposits), (Money::ZERO, Money::ZERO));
+    assert_eq!(row(&trial_balance, BookAccount::InterestExpense), (Money::from_major(40), Money::ZERO));
+}
+
+#[test]
+fn vault_top_ups_are_the_bank_s_own_entries() {
+    let (mut bank, _, _, _) = bank_with_two_customers();
+    bank.vault_top_up(Money::from_major(5000)).unwrap();
+    bank.vault_withdraw(Money::from_major(1000)).unwrap();
+
+    let trial_balance = bank.trial_balance();
+    assert!(trial_balance.is_balanced(), "{}", trial_balance);
+    assert_eq!(row(&trial_balance, BookAccount::Cash), (Money::from_major(5500), Money::ZERO));
+    assert_eq!(row(&trial_balance, BookAccount::Capital), (Money::ZERO, Money::from_major(4000)));
+    let last = bank.book_entries().pop().unwrap();
+    assert_eq!(last.memo, "VAULT WITHDRAWAL");
+    assert_eq!(last.transaction_id, None);
+}
+
+#[test]
+fn a_seeded_bank_balances_and_a_tampered_one_does_not() {
+    let mut bank = Bank::new("Seeded Bank".to_string());
+    bank.seed(SeedOptions::new(8).rng_seed(7)).unwrap();
+    let trial_balance = bank.trial_balance();
+    assert!(trial_balance.is_balanced(), "{}", trial_balance);
+    assert!(trial_balance.to_string().contains("The books balance"));
+
+    let (bank, _, alice, _) = bank_with_two_customers();
+    let mut saved = serde_json::to_value(&bank).unwrap();
+    saved["customers"][alice.as_str()]["account"]["balance"] = serde_json::json!("1234.00");
+    let tampered: Bank = serde_json::from_value(saved).unwrap();
+    let trial_balance = tampered.trial_balance();
+    assert!(!trial_balance.is_balanced());
+    assert_eq!(trial_balance.unreconciled.len(), 1);
+    assert_eq!(trial_balance.unreconciled[0].customer_id, alice);
+    assert_eq!(trial_balance.unreconciled[0].books, Money::from_major(1000));
+    assert!(trial_balance.to_string().contains("do not balance"));
+}
+
+#[test]
+fn a_transaction_changed_without_its_entry_does_not_reconcile() {
+    let (mut bank, _, alice, _) = bank_with_two_customers();
+    bank.withdraw(&alice, Money::from_major(100)).unwrap();
+
+    // Shrink the withdrawal and raise the balance to match: the account
+    // adds up on its own, but the books still hold the posted entry
+    let mut saved = serde_json::to_value(&bank).unwrap();
+    let account = &mut saved["customers"][alice.as_str()]["account"];
+    account["transactions"][1]["amount"] = serde_json::json!("10.00");
+    account["transactions"][1]["balance_after"] = serde_json::json!("990.00");
+    account["balance"] = serde_json::json!("990.00");
+    let tampered: Bank = serde_json::from_value(saved).unwrap();
+
+    let trial_balance = tampered.trial_balance();
+    assert_eq!(trial_balance.unreconciled.len(), 1);
+    assert_eq!(trial_balance.unreconciled[0].books, Money::from_major(900));
+    assert_eq!(trial_balance.unreconciled[0].balance, Money::from_major(990));
+}
+
+#[test]
+fn replaying_the_journal_posts_the_same_books() {
+    let (mut bank, _, alice, bob) = bank_with_two_customers();
+    let snapshot = serde_json::to_string(&bank).unwrap();
+    bank.enable_journal();
+    bank.transfer(&alice, &bob, Money::from_major(250)).unwrap();
+    bank.vault_top_up(Money::from_major(1000)).unwrap();
+
+    let mut restored: Bank = serde_json::from_str(&snapshot).unwrap();
+    for entry in bank.take_journal() {
+        restored.apply_journal_entry(entry).unwrap();
+    }
+    assert_eq!(restored.book_entries(), bank.book_entries());
+    assert!(restored.trial_balance().is_balanced());
+}
+
+#[test]
+fn an_older_data_file_gets_its_books_from_the_accounts() {
+    let (mut bank, _, alice, _) = bank_with_two_customers();
+    bank.vault_top_up(Money::from_major(1000)).unwrap();
+    let entries = bank.book_entries();
+
+    // Files from before entries were posted kept only the bank's own
+    let mut saved = serde_json::to_value(&bank).unwrap();
+    let own: Vec<_> = entries.iter().filter(|entry| entry.transaction_id.is_none()).collect();
+    saved["bank_entries"] = serde_json::to_value(own).unwrap();
+    saved.as_object_mut().unwrap().remove("books");
+    let mut older: Bank = serde_json::from_value(saved).unwrap();
+    assert_eq!(older.book_entries().len(), entries.len());
+    assert_eq!(older.trial_balance().rows, bank.trial_balance().rows);
+
+    older.deposit(&alice, Money::from_major(5)).unwrap();
+    assert_eq!(older.book_entries().len(), entries.len() + 1);
+    assert!(older.trial_balance().is_balanced());
+}
+
+#[cfg(feature = "cli")]
+#[test]
+fn command_mode_shows_the_trial_balance() {
+    let data_file = temp_path("cli.json");
+    let _ = std::fs::remove_file(&data_file);
+    let _ = std::fs::remove_file(journal_path(&data_file.to_string_lossy()));
+    let run = |args: &[&str]| common::run(&data_file, args, "\n");
+    run(&["register", "--name", "Ann", "--email", "ann@example.com", "--pin", "1234"]);
+    run(&["create-account", "--customer", "ann@example.com", "--deposit", "100"]);
+    run(&["vault-top-up", "--amount", "900"]);
+
+    let json: serde_json::Value = serde_json::from_str(&run(&["--json", "trial-balance"])).unwrap();
+    assert_eq!(json["result"]["total_debits"], "1000.00");
+    assert_eq!(json["result"]["total_credits"], "1000.00");
+    let cash = &json["result"]["rows"][0];
+    assert_eq!(cash["account"], "cash");
+    assert_eq!((&cash["debit"], &cash["credit"]), (&"1000.00".into(), &"0.00".into()));
+    assert_eq!(json["result"]["unreconciled"], serde_json::json!([]));
+    let _ = std::fs::remove_file(&data_file);
+}
@@ -0,0 +1,185 @@
+//! Integration tests for monthly category budgets
+
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use rust_banking_system::clock::MockClock;
+use rust_banking_system::errors::BankResult;
+use rust_banking_system::models::{Alert, AlertKind, CustomerId, TransactionDetails, TransactionTags};
+use rust_banking_system::{AccountType, Bank, BankError, Money, Notifier};
+
+fn start() -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2024, 3, 20, 10, 0, 0).unwrap()
+}
+
+/// Keeps every alert it is sent
+#[derive(Debug, Default)]
+struct Recorder {
+    alerts: Mutex<Vec<Alert>>,
+}
+
+impl Notifier for Recorder {
+    fn notify(&self, alert: &Alert) -> BankResult<()> {
+        self.alerts.lock().unwrap().push(alert.clone());
+        Ok(())
+    }
+}
+
+/// Creates a bank on a mock clock where Alice has $1,000 and Bob nothing;
+/// returns (bank, clock, alice, bob)
+fn bank_at_start() -> (Bank, Arc<MockClock>, CustomerId, CustomerId) {
+    let clock = Arc::new(MockClock::new(start()));
+    let mut bank = Bank::new("Test Bank".to_string());
+    bank.set_clock(clock.clone());
+    let alice = bank.register_customer("Alice".to_string(), "alice@example.com".to_string()).unwrap();
+    let bob = bank.register_customer("Bob".to_string(), "bob@example.com".to_string()).unwrap();
+    bank.create_account_for_customer(&alice, Money::from_major(1_000), AccountType::Checking)
+        .unwrap();
+    bank.create_account_for_customer(&bob, Money::ZERO, AccountType::Checking).unwrap();
+    (bank, clock, alice, bob)
+}
+
+fn spend(bank: &mut Bank, customer_id: &CustomerId, amount: i64, category: &str) {
+    let details = TransactionDetails::new().tags(TransactionTags::parse(category, "").unwrap());
+    bank.withdraw_with(customer_id, Money::from_major(amount), details).unwrap();
+}
+
+#[test]
+fn report_tracks_spending_per_category() {
+    let (mut bank, _, alice, bob) = bank_at_start();
+    bank.set_budget(&alice, " Groceries ", Money::from_major(300)).unwrap();
+    bank.set_budget(&alice, "fun", Money::from_major(100)).unwrap();
+
+    spend(&mut bank, &alice, 120, "groceries");
+    spend(&mut bank, &alice, 30, "GROCERIES");
+    spend(&mut bank, &alice, 50, "rent");
+    let details = TransactionDetails::new().tags(TransactionTags::parse("fun", "").unwrap());
+    bank.transfer_with(&alice, &bob, Money::from_major(25), details).unwrap();
+
+    let report = bank.budget_report(&alice).unwrap();
+    assert_eq!(report.len(), 2);
+    assert_eq!(report[0].category, "groceries");
+    assert_eq!(report[0].spent, Money::from_major(150));
+    assert_eq!(report[0].remaining, Money::from_major(150));
+    assert_eq!(report[0].percent_used(), 50);
+    assert_eq!(report[1].spent, Money::from_major(25));
+    assert_eq!(report[0].to_string(), "groceries: $150.00 of $300.00 (50%)");
+}
+
+#[test]
+fn budgets_reset_each_month() {
+    let (mut bank, clock, alice, _) = bank_at_start();
+    bank.set_budget(&alice, "groceries", Money::from_major(300)).unwrap();
+    spend(&mut bank, &alice, 280, "groceries");
+
+    clock.advance(Duration::days(15));
+    spend(&mut bank, &alice, 40, "groceries");
+
+    assert_eq!(bank.budget_report(&alice).unwrap()[0].spent, Money::from_major(40));
+    let account = bank.get_customer(&alice).unwrap().get_account().unwrap();
+    let march = account.budget_report(2024, 3).unwrap();
+    assert_eq!(march[0].spent, Money::from_major(280));
+    assert!(matches!(account.budget_report(2024, 13), Err(BankError::InvalidInput(_))));
+}
+
+#[test]
+fn warns_before_spending_past_a_budget() {
+    let (mut bank, _, alice, _) = bank_at_start();
+    bank.set_budget(&alice, "groceries", Money::from_major(300)).unwrap();
+    spend(&mut bank, &alice, 250, "groceries");
+
+    let within = bank.budget_warning(&alice, Some("groceries"), Money::from_major(50)).unwrap();
+    assert!(within.is_none());
+    let warning = bank.budget_warning(&alice, Some("groceries"), Money::from_major(80)).unwrap().unwrap();
+    assert_eq!(warning.spent, Money::from_major(330));
+    assert_eq!(warning.remaining, -Money::from_major(30));
+    assert_eq!(warning.to_string(), "groceries: $330.00 of $300.00 (110%), $30.00 over");
+
+    assert!(bank.budget_warning(&alice, Some("rent"), Money::from_major(900)).unwrap().is_none());
+    assert!(bank.budget_warning(&alice, None, Money::from_major(900)).unwrap().is_none());
+}
+
+#[test]
+fn budgets_warn_but_never_block() {
+    let (mut bank, _, alice, _) = bank_at_start();
+    bank.set_budget(&alice, "fun", Money::from_major(10)).unwrap();
+    spend(&mut bank, &alice, 200, "fun");
+
+    let status = &bank.budget_report(&alice).unwrap()[0];
+    assert!(status.is_exceeded());
+    assert_eq!(status.percent_used(), 2_000);
+    assert_eq!(bank.get_customer(&alice).unwrap().get_account().unwrap().balance, Money::from_major(800));
+}
+
+#[test]
+fn crossing_a_budget_raises_one_alert() {
+    let (mut bank, _, alice, _) = bank_at_start();
+    let recorder = Arc::new(Recorder::default());
+    bank.add_notifier(recorder.clone());
+    bank.set_budget(&alice, "groceries", Money::from_major(300)).unwrap();
+
+    spend(&mut bank, &alice, 200, "groceries");
+    spend(&mut bank, &alice, 150, "groceries");
+    spend(&mut bank, &alice, 10, "groceries");
+
+    let kinds: Vec<AlertKind> = recorder.alerts.lock().unwrap().iter().map(|a| a.kind.clone()).collect();
+    assert_eq!(
+        kinds,
+        [AlertKind::BudgetExceeded {
+            category: "groceries".to_string(),
+            spent: Money::from_major(350),
+            limit: Money::from_major(300),
+        }]
+    );
+}
+
+#[test]
+fn reversed_spending_does_not_count() {
+    let (mut bank, _, alice, _) = bank_at_start();
+    bank.set_budget(&alice, "groceries", Money::from_major(300)).unwrap();
+    spend(&mut bank, &alice, 100, "groceries");
+    spend(&mut bank, &alice, 40, "groceries");
+
+    bank.undo_last().unwrap();
+
+    assert_eq!(bank.budget_report(&alice).unwrap()[0].spent, Money::from_major(100));
+}
+
+#[test]
+fn setting_again_replaces_and_removing_forgets() {
+    let (mut bank, _, alice, _) = bank_at_start();
+    bank.set_budget(&alice, "groceries", Money::from_major(300)).unwrap();
+    bank.set_budget(&alice, "Groceries", Money::from_major(400)).unwrap();
+
+    let budgets = bank.list_budgets(&alice).unwrap();
+    assert_eq!(budgets.len(), 1);
+    assert_eq!(budgets[0].limit, Money::from_major(400));
+
+    assert_eq!(bank.remove_budget(&alice, "GROCERIES").unwrap().limit, Money::from_major(400));
+    assert!(bank.list_budgets(&alice).unwrap().is_empty());
+    assert!(matches!(bank.remove_budget(&alice, "groceries"), Err(BankError::BudgetNotFound(c)) if c == "groceries"));
+    assert!(matches!(bank.set_budget(&alice, "  ", Money::from_major(5)), Err(BankError::InvalidInput(_))));
+    assert!(matches!(bank.set_budget(&alice, "fun", Money::ZERO), Err(BankError::InvalidAmount(_))));
+}
+
+#[test]
+fn budgets_survive_saving_and_journal_replay() {
+    let (mut bank, _, alice, _) = bank_at_start();
+    let snapshot = serde_json::to_string(&bank).unwrap();
+    bank.enable_journal();
+
+    bank.set_budget(&alice, "groceries", Money::from_major(300)).unwrap();
+    bank.set_budget(&alice, "fun", Money::from_major(50)).unwrap();
+    bank.set_budget(&alice, "groceries", Money::from_major(250)).unwrap();
+    bank.remove_budget(&alice, "fun").unwrap();
+
+    let mut restored: Bank = serde_json::from_str(&snapshot).unwrap();
+    for entry in bank.take_journal() {
+        restored.apply_journal_entry(entry).unwrap();
+    }
+    assert_eq!(restored.list_budgets(&alice).unwrap(), bank.list_budgets(&alice).unwrap());
+
+    let reloaded: Bank = serde_json::from_str(&serde_json::to_string(&bank).unwrap()).unwrap();
+    assert_eq!(reloaded.list_budgets(&alice).unwrap(), bank.list_budgets(&alice).unwrap());
+    assert_eq!(reloaded.list_budgets(&alice).unwrap()[0].limit, Money::from_major(250));
+}
@@ -0,0 +1,109 @@
+//! Integration tests for the bank, customer and account builders
+
+use std::sync::Arc;
+
+use chrono::{NaiveDate, TimeZone, Utc};
+use rust_banking_system::clock::MockClock;
+use rust_banking_system::models::{AccountLimits, AlertRules, FraudRules};
+use rust_banking_system::{Account, AccountType, Bank, BankError, Customer, MemoryStorage, Money, Storage};
+
+fn limits(daily_withdrawal: i64) -> AccountLimits {
+    AccountLimits { daily_withdrawal: Some(Money::from_major(daily_withdrawal)), ..AccountLimits::default() }
+}
+
+#[test]
+fn a_customer_builder_validates_and_normalizes_every_field() {
+    let customer = Customer::builder("  Ada Lovelace ", " Ada@Example.com ")
+        .address("12 St James's Square, London")
+        .date_of_birth(NaiveDate::from_ymd_opt(1990, 12, 10).unwrap())
+        .build()
+        .unwrap();
+    assert_eq!(customer.name, "Ada Lovelace");
+    assert_eq!(customer.email, "ada@example.com");
+    assert!(customer.phone.is_none());
+    assert_eq!(customer.address.as_deref(), Some("12 St James's Square, London"));
+
+    assert!(matches!(Customer::builder("", "ada@example.com").build(), Err(BankError::ValidationError { .. })));
+    assert!(matches!(Customer::builder("Ada", "not an email").build(), Err(BankError::ValidationError { .. })));
+    let unborn = NaiveDate::from_ymd_opt(2999, 1, 1).unwrap();
+    assert!(Customer::builder("Ada", "ada@example.com").date_of_birth(unborn).build().is_err());
+}
+
+#[test]
+fn an_account_builder_defaults_to_an_empty_checking_account() {
+    let account = Account::builder().build().unwrap();
+    assert_eq!(account.account_type, AccountType::Checking);
+    assert_eq!(account.balance, Money::ZERO);
+    assert_eq!(account.limits, AccountLimits::default());
+
+    assert!(matches!(
+        Account::builder().initial_deposit(Money::from_major(-5)).build(),
+        Err(BankError::InvalidAmount(_))
+    ));
+    assert!(Account::builder().limits(limits(0)).build().is_err());
+}
+
+#[test]
+fn a_bank_registers_built_customers_and_opens_built_accounts_on_its_clock() {
+    let now = Utc.with_ymd_and_hms(2024, 3, 1, 9, 0, 0).unwrap();
+    let mut bank = Bank::builder("Test Bank").clock(Arc::new(MockClock::new(now))).journal(true).build().unwrap();
+
+    let ada = bank.register(Customer::builder("Ada", "ada@example.com").phone("020 7946 0000")).unwrap();
+    let alerts = AlertRules { low_balance: Some(Money::from_major(50)), ..AlertRules::default() };
+    let account = Account::builder()
+        .account_type(AccountType::Savings)
+        .initial_deposit(Money::from_major(500))
+        .limits(limits(100))
+        .alerts(alerts.clone());
+    bank.open_account(&ada, account).unwrap();
+
+    let customer = bank.get_customer(&ada).unwrap();
+    assert_eq!(customer.registered_at, now);
+    assert!(customer.phone.is_some());
+    let account = customer.get_account().unwrap();
+    assert_eq!(account.account_type, AccountType::Savings);
+    assert_eq!(account.alerts, alerts);
+    assert!(account.number.is_some());
+    assert!(bank.withdraw(&ada, Money::from_major(150)).is_err());
+
+    // The limits and alerts travel in the opening event
+    let replayed = Bank::from_events("Test Bank".to_string(), bank.take_journal()).unwrap();
+    assert_eq!(replayed.get_customer(&ada).unwrap().get_account().unwrap().limits, limits(100));
+}
+
+#[test]
+fn register_and_open_account_refuse_what_the_plain_methods_refuse() {
+    let mut bank = Bank::new("Test Bank".to_string());
+    let ada = bank.register(Customer::builder("Ada", "ada@example.com")).unwrap();
+    assert!(matches!(
+        bank.register(Customer::builder("Other Ada", "ADA@example.com")),
+        Err(BankError::CustomerAlreadyExists(_))
+    ));
+
+    bank.open_account(&ada, Account::builder()).unwrap();
+    assert!(matches!(bank.open_account(&ada, Account::builder()), Err(BankError::CustomerAlreadyExists(_))));
+    assert!(matches!(bank.open_account(&"missing".into(), Account::builder()), Err(BankError::CustomerNotFound(_))));
+    assert_eq!(bank.list_customers().len(), 1);
+}
+
+#[test]
+fn a_bank_builder_loads_from_storage_and_applies_settings_on_top() {
+    let storage = MemoryStorage::new();
+    let mut saved = Bank::new("Saved Bank".to_string());
+    let ada = saved.register_customer("Ada".to_string(), "ada@example.com".to_string()).unwrap();
+    storage.save_bank(&saved).unwrap();
+
+    let rules = FraudRules { new_payee_transfer: Some(Money::from_major(1_000)), ..FraudRules::default() };
+    let bank = Bank::builder("Ignored").storage(&storage).fraud_rules(rules).build().unwrap();
+    assert_eq!(bank.name, "Saved Bank");
+    assert!(bank.get_customer(&ada).is_ok());
+    assert_eq!(bank.fraud_rules(), rules);
+}
+
+#[test]
+fn a_bank_builder_rejects_invalid_settings() {
+    // A history cap needs somewhere to archive to
+    assert!(matches!(Bank::builder("Test Bank").history_cap(10).build(), Err(BankError::InvalidInput(_))));
+    let rules = FraudRules { new_payee_transfer: Some(Money::ZERO), ..FraudRules::default() };
+    assert!(Bank::builder("Test Bank").fraud_rules(rules).build().is_err());
+}
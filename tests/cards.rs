@@ -0,0 +1,177 @@
+//! Integration tests for debit cards and card purchases
+
+use std::sync::Arc;
+
+use chrono::{DateTime, Datelike, Duration, TimeZone, Utc};
+use rust_banking_system::clock::MockClock;
+use rust_banking_system::models::{CardLimits, CardStatus, CustomerId, Limit, TransactionDetails};
+use rust_banking_system::{AccountType, Bank, BankError, Money};
+
+fn start() -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2024, 3, 28, 10, 0, 0).unwrap()
+}
+
+/// Creates a bank on a mock clock where Alice has $1,000; returns
+/// (bank, clock, alice)
+fn bank_with_customer() -> (Bank, Arc<MockClock>, CustomerId) {
+    let clock = Arc::new(MockClock::new(start()));
+    let mut bank = Bank::new("Test Bank".to_string());
+    bank.set_clock(clock.clone());
+    let alice = bank.register_customer("Alice".to_string(), "alice@example.com".to_string()).unwrap();
+    bank.create_account_for_customer(&alice, Money::from_major(1_000), AccountType::Checking)
+        .unwrap();
+    (bank, clock, alice)
+}
+
+fn limits(per_purchase: Option<i64>, daily: Option<i64>) -> CardLimits {
+    CardLimits {
+        per_purchase: per_purchase.map(Money::from_major),
+        daily: daily.map(Money::from_major),
+    }
+}
+
+fn shop(name: &str) -> TransactionDetails {
+    TransactionDetails::new().description(name).unwrap()
+}
+
+#[test]
+fn issued_cards_are_masked_and_linked_to_the_account() {
+    let (mut bank, _, alice) = bank_with_customer();
+    let card = bank.issue_card(&alice, CardLimits::default()).unwrap();
+
+    let account_id = bank.get_customer(&alice).unwrap().get_account().unwrap().id.clone();
+    assert_eq!(card.account_id, account_id);
+    assert_eq!(card.status, CardStatus::Active);
+    assert!(card.masked_number.starts_with("4000 **** **** "));
+    assert!(card.last_four().chars().all(|c| c.is_ascii_digit()));
+    assert_eq!((card.expires_on.year(), card.expires_on.month(), card.expires_on.day()), (2027, 3, 31));
+    assert_eq!(bank.get_customer(&alice).unwrap().cards, [card]);
+}
+
+#[test]
+fn purchases_post_withdrawals_that_record_the_card() {
+    let (mut bank, _, alice) = bank_with_customer();
+    let card = bank.issue_card(&alice, CardLimits::default()).unwrap();
+
+    let purchase = bank.card_purchase(&card.id, Money::from_major(42), shop("Corner Shop")).unwrap();
+
+    assert_eq!(purchase.card_id.as_deref(), Some(card.id.as_str()));
+    assert_eq!(purchase.description.as_deref(), Some("Corner Shop"));
+    assert_eq!(purchase.balance_after, Money::from_major(958));
+    let account = bank.get_customer(&alice).unwrap().get_account().unwrap();
+    assert_eq!(account.transactions.last().unwrap().id, purchase.id);
+}
+
+#[test]
+fn card_limits_apply_per_purchase_and_per_day() {
+    let (mut bank, clock, alice) = bank_with_customer();
+    let card = bank.issue_card(&alice, limits(Some(100), Some(150))).unwrap();
+
+    assert!(matches!(
+        bank.card_purchase(&card.id, Money::from_major(101), shop("TV")),
+        Err(BankError::LimitExceeded(Limit::CardPurchase(_)))
+    ));
+    bank.card_purchase(&card.id, Money::from_major(100), shop("Boots")).unwrap();
+    assert!(matches!(
+        bank.card_purchase(&card.id, Money::from_major(60), shop("Coat")),
+        Err(BankError::LimitExceeded(Limit::CardDaily(_)))
+    ));
+    // Plain withdrawals do not count towards the card's limit
+    bank.withdraw(&alice, Money::from_major(200)).unwrap();
+    bank.card_purchase(&card.id, Money::from_major(50), shop("Hat")).unwrap();
+
+    clock.advance(Duration::days(1));
+    bank.card_purchase(&card.id, Money::from_major(100), shop("Scarf")).unwrap();
+}
+
+#[test]
+fn account_rules_still_apply_to_purchases() {
+    let (mut bank, _, alice) = bank_with_customer();
+    let card = bank.issue_card(&alice, CardLimits::default()).unwrap();
+
+    assert!(matches!(
+        bank.card_purchase(&card.id, Money::from_major(1_001), shop("Car")),
+        Err(BankError::InsufficientFunds { .. })
+    ));
+    bank.freeze_account(&alice).unwrap();
+    assert!(matches!(
+        bank.card_purchase(&card.id, Money::from_major(1), shop("Gum")),
+        Err(BankError::AccountFrozen(_))
+    ));
+}
+
+#[test]
+fn blocked_cards_are_declined_until_replaced() {
+    let (mut bank, _, alice) = bank_with_customer();
+    let card = bank.issue_card(&alice, limits(Some(500), None)).unwrap();
+
+    assert_eq!(bank.block_card(&card.id).unwrap().status, CardStatus::Blocked);
+    assert!(matches!(bank.block_card(&card.id), Err(BankError::CardDeclined(_))));
+    assert!(matches!(
+        bank.card_purchase(&card.id, Money::from_major(5), shop("Cafe")),
+        Err(BankError::CardDeclined(_))
+    ));
+
+    let replacement = bank.replace_card(&card.id).unwrap();
+    assert_ne!(replacement.id, card.id);
+    assert_eq!(replacement.limits, card.limits);
+    let (_, old) = bank.find_card(&card.id).unwrap();
+    assert_eq!((old.status, old.replaced_by.as_deref()), (CardStatus::Replaced, Some(replacement.id.as_str())));
+    assert!(matches!(bank.replace_card(&card.id), Err(BankError::CardDeclined(_))));
+
+    bank.card_purchase(&replacement.id, Money::from_major(5), shop("Cafe")).unwrap();
+}
+
+#[test]
+fn expired_cards_are_declined() {
+    let (mut bank, clock, alice) = bank_with_customer();
+    let card = bank.issue_card(&alice, CardLimits::default()).unwrap();
+
+    clock.set(Utc.with_ymd_and_hms(2027, 3, 31, 23, 59, 0).unwrap());
+    bank.card_purchase(&card.id, Money::from_major(1), shop("Last day")).unwrap();
+    clock.advance(Duration::minutes(1));
+    assert!(matches!(
+        bank.card_purchase(&card.id, Money::from_major(1), shop("Too late")),
+        Err(BankError::CardDeclined(_))
+    ));
+}
+
+#[test]
+fn card_requests_are_validated() {
+    let (mut bank, _, alice) = bank_with_customer();
+    assert!(matches!(bank.issue_card(&alice, limits(Some(0), None)), Err(BankError::InvalidAmount(_))));
+    assert!(matches!(bank.block_card("no-such-card"), Err(BankError::CardNotFound(_))));
+
+    let card = bank.issue_card(&alice, CardLimits::default()).unwrap();
+    assert!(matches!(bank.set_card_limits(&card.id, limits(None, Some(-5))), Err(BankError::InvalidAmount(_))));
+    bank.set_card_limits(&card.id, limits(Some(20), None)).unwrap();
+    assert_eq!(bank.find_card(&card.id).unwrap().1.limits, limits(Some(20), None));
+
+    let bob = bank.register_customer("Bob".to_string(), "bob@example.com".to_string()).unwrap();
+    assert!(matches!(bank.issue_card(&bob, CardLimits::default()), Err(BankError::AccountNotFound(_))));
+}
+
+#[test]
+fn cards_survive_saving_and_journal_replay() {
+    let (mut bank, _, alice) = bank_with_customer();
+    let snapshot = serde_json::to_string(&bank).unwrap();
+    bank.enable_journal();
+
+    let card = bank.issue_card(&alice, limits(None, Some(300))).unwrap();
+    bank.card_purchase(&card.id, Money::from_major(30), shop("Books")).unwrap();
+    bank.set_card_limits(&card.id, limits(Some(50), Some(300))).unwrap();
+    bank.block_card(&card.id).unwrap();
+    bank.replace_card(&card.id).unwrap();
+
+    let mut restored: Bank = serde_json::from_str(&snapshot).unwrap();
+    for entry in bank.take_journal() {
+        restored.apply_journal_entry(entry).unwrap();
+    }
+    let cards = &bank.get_customer(&alice).unwrap().cards;
+    assert_eq!(&restored.get_customer(&alice).unwrap().cards, cards);
+
+    let reloaded: Bank = serde_json::from_str(&serde_json::to_string(&bank).unwrap()).unwrap();
+    assert_eq!(&reloaded.get_customer(&alice).unwrap().cards, cards);
+    let purchase = reloaded.get_customer(&alice).unwrap().get_account().unwrap().transactions.last().cloned();
+    assert_eq!(purchase.unwrap().card_id, Some(card.id));
+}
@@ -0,0 +1,145 @@
+//! Integration tests for the daily / weekly cash-flow report
+
+// The tests that run the binary need the `cli` feature; without it the
+// imports and helpers only they use go unused
+#![cfg_attr(not(feature = "cli"), allow(unused))]
+
+mod common;
+
+use std::sync::Arc;
+
+use chrono::{DateTime, Duration, NaiveDate, TimeZone, Utc};
+use rust_banking_system::bank::{CashFlowPeriod, CHART_WIDTH};
+use rust_banking_system::clock::MockClock;
+use rust_banking_system::persistence::journal_path;
+use rust_banking_system::{AccountType, Bank, Money};
+
+use common::temp_path;
+
+/// A Friday
+fn start() -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2024, 3, 1, 9, 0, 0).unwrap()
+}
+
+fn day(d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2024, 3, d).unwrap()
+}
+
+/// Alice opens with $500 and Bob with $100 on March 1st; Bob deposits $10
+/// on the 2nd, Alice withdraws $20 on the 4th and sends Bob $30 on the 5th
+fn busy_bank() -> (Bank, Arc<MockClock>) {
+    let clock = Arc::new(MockClock::new(start()));
+    let mut bank = Bank::new("Test Bank".to_string());
+    bank.set_clock(clock.clone());
+    let alice = bank.register_customer("Alice".to_string(), "alice@example.com".to_string()).unwrap();
+    let bob = bank.register_customer("Bob".to_string(), "bob@example.com".to_string()).unwrap();
+    bank.create_account_for_customer(&alice, Money::from_major(500), AccountType::Checking).unwrap();
+    bank.create_account_for_customer(&bob, Money::from_major(100), AccountType::Checking).unwrap();
+
+    clock.advance(Duration::days(1));
+    bank.deposit(&bob, Money::from_major(10)).unwrap();
+    clock.advance(Duration::days(2));
+    bank.withdraw(&alice, Money::from_major(20)).unwrap();
+    clock.advance(Duration::days(1));
+    bank.transfer(&alice, &bob, Money::from_major(30)).unwrap();
+    (bank, clock)
+}
+
+#[test]
+fn each_day_reports_money_in_out_and_net() {
+    let (bank, _) = busy_bank();
+    let report = bank.cash_flow(CashFlowPeriod::Daily, None, None);
+
+    let first = &report.buckets[0];
+    assert_eq!(first.start, day(1));
+    assert_eq!((first.transactions, first.inflow, first.outflow), (2, Money::from_major(600), Money::ZERO));
+    let withdrawal = report.buckets.iter().find(|b| b.start == day(4)).unwrap();
+    assert_eq!(withdrawal.outflow, Money::from_major(20));
+    assert_eq!(withdrawal.net, Money::from_major(-20));
+
+    assert_eq!(report.inflow, Money::from_major(610));
+    assert_eq!(report.outflow, Money::from_major(20));
+    assert_eq!(report.net, Money::from_major(590));
+}
+
+#[test]
+fn transfers_between_customers_stay_inside_the_bank() {
+    let (bank, _) = busy_bank();
+    let report = bank.cash_flow(CashFlowPeriod::Daily, None, None);
+
+    let transfer = report.buckets.last().unwrap();
+    assert_eq!(transfer.start, day(5));
+    assert_eq!(transfer.transactions, 2);
+    assert_eq!((transfer.inflow, transfer.outflow), (Money::ZERO, Money::ZERO));
+}
+
+#[test]
+fn quiet_days_in_between_are_listed_with_zeros() {
+    let (bank, _) = busy_bank();
+    let report = bank.cash_flow(CashFlowPeriod::Daily, None, None);
+
+    let starts: Vec<NaiveDate> = report.buckets.iter().map(|b| b.start).collect();
+    assert_eq!(starts, [day(1), day(2), day(3), day(4), day(5)]);
+    assert_eq!(report.buckets[2].transactions, 0);
+    assert_eq!(report.buckets[2].net, Money::ZERO);
+}
+
+#[test]
+fn weeks_start_on_monday() {
+    let (bank, _) = busy_bank();
+    let report = bank.cash_flow(CashFlowPeriod::Weekly, None, None);
+
+    let starts: Vec<NaiveDate> = report.buckets.iter().map(|b| b.start).collect();
+    assert_eq!(starts, [NaiveDate::from_ymd_opt(2024, 2, 26).unwrap(), day(4)]);
+    assert_eq!(report.buckets[0].inflow, Money::from_major(610));
+    assert_eq!(report.buckets[1].outflow, Money::from_major(20));
+}
+
+#[test]
+fn a_range_limits_the_report() {
+    let (bank, _) = busy_bank();
+    let from = start() + Duration::days(1);
+    let to = start() + Duration::days(3);
+    let report = bank.cash_flow(CashFlowPeriod::Daily, Some(from), Some(to));
+
+    assert_eq!(report.buckets.len(), 1);
+    assert_eq!(report.inflow, Money::from_major(10));
+
+    let empty = Bank::new("Empty".to_string()).cash_flow(CashFlowPeriod::Weekly, None, None);
+    assert!(empty.buckets.is_empty());
+    assert!(empty.to_string().contains("No transactions in this period."));
+}
+
+#[test]
+fn the_chart_scales_bars_to_the_busiest_period() {
+    let (bank, _) = busy_bank();
+    let text = bank.cash_flow(CashFlowPeriod::Daily, None, None).to_string();
+
+    assert!(text.contains("DAILY CASH FLOW"), "{}", text);
+    let full = format!("2024-03-01  in  {} $600.00", "#".repeat(CHART_WIDTH));
+    assert!(text.contains(&full), "{}", text);
+    // $20 of $600 is under one character wide, but still shows
+    assert!(text.contains(&format!("out {:<width$} $20.00", "#", width = CHART_WIDTH)), "{}", text);
+    assert!(text.contains("(net -$20.00)"), "{}", text);
+    assert!(text.contains("Net Flow:  $590.00"), "{}", text);
+}
+
+#[cfg(feature = "cli")]
+#[test]
+fn command_mode_prints_the_report_as_json() {
+    let data_file = temp_path("cli.json");
+    let _ = std::fs::remove_file(&data_file);
+    let _ = std::fs::remove_file(journal_path(&data_file.to_string_lossy()));
+    let run = |args: &[&str]| common::run(&data_file, args, "\n");
+    run(&["register", "--name", "Ann", "--email", "ann@example.com", "--pin", "1234"]);
+    run(&["create-account", "--customer", "ann@example.com", "--deposit", "100"]);
+
+    let json: serde_json::Value = serde_json::from_str(&run(&["--json", "cash-flow", "--period", "weekly"])).unwrap();
+    assert_eq!(json["result"]["period"], "weekly");
+    assert_eq!(json["result"]["inflow"], "100.00");
+    assert_eq!(json["result"]["buckets"].as_array().unwrap().len(), 1);
+
+    let json: serde_json::Value = serde_json::from_str(&run(&["--json", "cash-flow", "--to", "2000-01-01"])).unwrap();
+    assert_eq!(json["result"]["buckets"], serde_json::json!([]));
+    let _ = std::fs::remove_file(&data_file);
+}
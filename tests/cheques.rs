@@ -0,0 +1,189 @@
+//! Integration tests for cheque deposits, clearing and bounces
+
+mod common;
+
+use std::sync::Arc;
+
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use rust_banking_system::clock::MockClock;
+use rust_banking_system::models::{Account, ChequePolicy, ChequeStatus, CustomerId, TransactionStatus, TransactionType};
+use rust_banking_system::{Bank, BankError, Money};
+
+use common::add_customers;
+
+fn start() -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2024, 3, 28, 10, 0, 0).unwrap()
+}
+
+/// Creates a bank on a mock clock where Alice has $1,000 and Bob $100;
+/// returns (bank, clock, alice, bob)
+fn bank_on_clock() -> (Bank, Arc<MockClock>, CustomerId, CustomerId) {
+    let clock = Arc::new(MockClock::new(start()));
+    let mut bank = Bank::new("Test Bank".to_string());
+    bank.set_clock(clock.clone());
+    let (alice, bob) = add_customers(&mut bank, 1_000, 100);
+    (bank, clock, alice, bob)
+}
+
+fn account<'a>(bank: &'a Bank, customer_id: &CustomerId) -> &'a Account {
+    bank.get_customer(customer_id).unwrap().get_account().unwrap()
+}
+
+/// (ledger, available) balances of `customer_id` by the bank's clock
+fn balances(bank: &Bank, customer_id: &CustomerId) -> (Money, Money) {
+    let account = account(bank, customer_id);
+    (account.ledger_balance(), account.available_balance_at(bank.now()))
+}
+
+#[test]
+fn cheques_credit_pending_funds_until_they_clear() {
+    let (mut bank, clock, alice, _) = bank_on_clock();
+    let cheque = bank.deposit_cheque(&alice, Money::from_major(200), "000123", None).unwrap();
+
+    assert_eq!(cheque.status, ChequeStatus::Pending);
+    assert_eq!(cheque.clears_at, start() + Duration::days(3));
+    assert_eq!(balances(&bank, &alice), (Money::from_major(1_200), Money::from_major(1_000)));
+    let deposit = account(&bank, &alice).transactions.last().unwrap().clone();
+    assert_eq!((deposit.id.as_str(), deposit.status), (cheque.transaction_id.as_str(), TransactionStatus::Pending));
+    assert!(matches!(bank.withdraw(&alice, Money::from_major(1_100)), Err(BankError::InsufficientFunds { .. })));
+
+    clock.advance(Duration::days(3) - Duration::seconds(1));
+    assert!(bank.clear_due_cheques(bank.now()).unwrap().is_empty());
+
+    clock.advance(Duration::seconds(1));
+    let cleared = bank.clear_due_cheques(bank.now()).unwrap();
+    assert_eq!(cleared.len(), 1);
+    assert_eq!(cleared[0].status, ChequeStatus::Cleared);
+    assert_eq!(balances(&bank, &alice), (Money::from_major(1_200), Money::from_major(1_200)));
+    assert_eq!(account(&bank, &alice).transactions.last().unwrap().status, TransactionStatus::Completed);
+    assert!(bank.outstanding_cheques().is_empty());
+}
+
+#[test]
+fn cheques_from_customers_are_paid_from_their_accounts() {
+    let (mut bank, clock, alice, bob) = bank_on_clock();
+    bank.deposit_cheque(&bob, Money::from_major(300), "42", Some(&alice)).unwrap();
+
+    clock.advance(Duration::days(3));
+    bank.clear_due_cheques(bank.now()).unwrap();
+
+    assert_eq!(balances(&bank, &alice), (Money::from_major(700), Money::from_major(700)));
+    assert_eq!(balances(&bank, &bob), (Money::from_major(400), Money::from_major(400)));
+    let debit = account(&bank, &alice).transactions.last().unwrap();
+    assert_eq!(debit.description.as_deref(), Some("Cheque #42"));
+    assert!(bank.verify_integrity().is_clean());
+}
+
+#[test]
+fn unpaid_cheques_bounce_with_a_fee() {
+    let (mut bank, clock, alice, bob) = bank_on_clock();
+    let cheque = bank.deposit_cheque(&alice, Money::from_major(500), "7", Some(&bob)).unwrap();
+
+    clock.advance(Duration::days(3));
+    let bounced = bank.clear_due_cheques(bank.now()).unwrap();
+
+    assert_eq!(bounced[0].status, ChequeStatus::Bounced);
+    assert!(bounced[0].bounce_reason.as_deref().unwrap().starts_with("Refer to drawer"));
+    assert_eq!(balances(&bank, &alice), (Money::from_major(975), Money::from_major(975)));
+    assert_eq!(balances(&bank, &bob), (Money::from_major(100), Money::from_major(100)));
+
+    let transactions = &account(&bank, &alice).transactions;
+    let deposit = transactions.iter().find(|tx| tx.id == cheque.transaction_id).unwrap();
+    assert_eq!(deposit.status, TransactionStatus::Failed);
+    let fee = transactions.last().unwrap();
+    assert!(matches!(fee.transaction_type, TransactionType::Fee));
+    assert_eq!(fee.amount, Money::from_major(25));
+    assert!(bank.verify_integrity().is_clean());
+}
+
+#[test]
+fn returned_cheques_bounce_at_once() {
+    let (mut bank, _, alice, _) = bank_on_clock();
+    let cheque = bank.deposit_cheque(&alice, Money::from_major(50), "99", None).unwrap();
+
+    let returned = bank.return_cheque(&cheque.id, Some("Account closed")).unwrap();
+    assert_eq!(returned.bounce_reason.as_deref(), Some("Account closed"));
+    assert_eq!(account(&bank, &alice).cheques[0], returned);
+    assert_eq!(balances(&bank, &alice), (Money::from_major(975), Money::from_major(975)));
+
+    assert!(matches!(bank.return_cheque(&cheque.id, None), Err(BankError::InvalidInput(_))));
+    // A bounced cheque may be paid in again
+    bank.deposit_cheque(&alice, Money::from_major(50), "99", None).unwrap();
+}
+
+#[test]
+fn bounce_fees_never_overdraw_the_account() {
+    let (mut bank, _, _, bob) = bank_on_clock();
+    bank.withdraw(&bob, Money::from_major(90)).unwrap();
+    let cheque = bank.deposit_cheque(&bob, Money::from_major(40), "5", None).unwrap();
+
+    bank.return_cheque(&cheque.id, None).unwrap();
+
+    assert_eq!(balances(&bank, &bob), (Money::ZERO, Money::ZERO));
+    assert_eq!(account(&bank, &bob).transactions.last().unwrap().amount, Money::from_major(10));
+}
+
+#[test]
+fn invalid_cheques_are_refused() {
+    let (mut bank, _, alice, bob) = bank_on_clock();
+    bank.deposit_cheque(&alice, Money::from_major(10), "1001", Some(&bob)).unwrap();
+
+    assert!(matches!(
+        bank.deposit_cheque(&alice, Money::from_major(10), "1001", Some(&bob)),
+        Err(BankError::InvalidInput(_))
+    ));
+    assert!(matches!(
+        bank.deposit_cheque(&alice, Money::from_major(10), "1002", Some(&alice)),
+        Err(BankError::InvalidTransfer(_))
+    ));
+    assert!(bank.deposit_cheque(&alice, Money::from_major(10), "12a", None).is_err());
+    assert!(matches!(
+        bank.deposit_cheque(&alice, Money::ZERO, "1003", None),
+        Err(BankError::InvalidAmount(_))
+    ));
+    assert_eq!(account(&bank, &alice).cheques.len(), 1);
+}
+
+#[test]
+fn the_policy_sets_the_clearing_period_and_fee() {
+    let (mut bank, clock, alice, _) = bank_on_clock();
+    let policy = ChequePolicy { clearing_days: 0, bounce_fee: Money::ZERO };
+    bank.set_cheque_policy(policy).unwrap();
+    assert_eq!(bank.cheque_policy(), policy);
+
+    let cheque = bank.deposit_cheque(&alice, Money::from_major(10), "8", None).unwrap();
+    assert_eq!(cheque.clears_at, bank.now());
+    bank.return_cheque(&cheque.id, None).unwrap();
+    assert_eq!(balances(&bank, &alice), (Money::from_major(1_000), Money::from_major(1_000)));
+
+    clock.advance(Duration::days(1));
+    assert!(bank.set_cheque_policy(ChequePolicy { clearing_days: 31, ..policy }).is_err());
+    assert!(bank.set_cheque_policy(ChequePolicy { bounce_fee: Money::from_major(-1), ..policy }).is_err());
+    assert_eq!(bank.cheque_policy(), policy);
+}
+
+#[test]
+fn cheques_survive_journal_replay() {
+    let (mut bank, clock, alice, bob) = bank_on_clock();
+    let snapshot = serde_json::to_string(&bank).unwrap();
+    bank.enable_journal();
+
+    bank.set_cheque_policy(ChequePolicy { clearing_days: 1, bounce_fee: Money::from_major(5) }).unwrap();
+    bank.deposit_cheque(&alice, Money::from_major(60), "1", Some(&bob)).unwrap();
+    bank.deposit_cheque(&alice, Money::from_major(200), "2", Some(&bob)).unwrap();
+    bank.deposit_cheque(&bob, Money::from_major(30), "3", None).unwrap();
+    clock.advance(Duration::days(1));
+    bank.clear_due_cheques(bank.now()).unwrap();
+
+    let mut restored: Bank = serde_json::from_str(&snapshot).unwrap();
+    for entry in bank.take_journal() {
+        restored.apply_journal_entry(entry).unwrap();
+    }
+    for customer in [&alice, &bob] {
+        assert_eq!(balances(&restored, customer), balances(&bank, customer));
+        assert_eq!(account(&restored, customer).cheques, account(&bank, customer).cheques);
+    }
+    assert_eq!(restored.cheque_policy(), bank.cheque_policy());
+    assert_eq!(restored.total_transactions, bank.total_transactions);
+    assert!(restored.verify_integrity().is_clean());
+}
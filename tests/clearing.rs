@@ -0,0 +1,162 @@
+//! Integration tests for external transfers and the clearing queue
+
+mod common;
+
+use std::sync::{Arc, Mutex};
+
+use rust_banking_system::models::{
+    AccountLimits, CustomerId, TransactionDetails, TransactionId, TransactionStatus, TransactionType,
+};
+use rust_banking_system::{Bank, BankError, DomainEvent, Money};
+
+use common::{balance, bank_with_customers};
+
+const IBAN: &str = "GB29 NWBK 6016 1331 9268 19";
+
+fn status_of(bank: &Bank, customer_id: &CustomerId, transaction_id: &TransactionId) -> TransactionStatus {
+    let account = bank.get_customer(customer_id).unwrap().get_account().unwrap();
+    account.transactions.iter().find(|tx| tx.id == *transaction_id).unwrap().status
+}
+
+#[test]
+fn external_transfers_debit_at_once_and_wait_in_the_queue() {
+    let (mut bank, alice, _) = bank_with_customers(1_000, 100);
+    let sent = bank.external_transfer(&alice, IBAN, Money::from_major(300)).unwrap();
+
+    assert_eq!(sent.status, TransactionStatus::Pending);
+    assert!(matches!(
+        &sent.transaction_type,
+        TransactionType::ExternalTransfer { external_ref } if external_ref == "GB29NWBK60161331926819"
+    ));
+    assert_eq!(balance(&bank, &alice), Money::from_major(700));
+
+    let pending = bank.pending_external_transfers();
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending[0].0.id, alice);
+    assert_eq!(pending[0].1.id, sent.id);
+
+    // The money is gone, so it cannot be sent twice
+    assert!(matches!(
+        bank.external_transfer(&alice, IBAN, Money::from_major(800)),
+        Err(BankError::InsufficientFunds { .. })
+    ));
+}
+
+#[test]
+fn settling_completes_the_transfer() {
+    let (mut bank, alice, _) = bank_with_customers(1_000, 100);
+    let sent = bank.external_transfer(&alice, IBAN, Money::from_major(300)).unwrap();
+
+    let settled = bank.settle_external_transfer(&sent.id).unwrap();
+    assert_eq!(settled.status, TransactionStatus::Completed);
+    assert_eq!(status_of(&bank, &alice, &sent.id), TransactionStatus::Completed);
+    assert_eq!(balance(&bank, &alice), Money::from_major(700));
+    assert!(bank.pending_external_transfers().is_empty());
+
+    assert!(matches!(bank.settle_external_transfer(&sent.id), Err(BankError::InvalidTransfer(_))));
+    assert!(matches!(bank.reject_external_transfer(&sent.id, None), Err(BankError::InvalidTransfer(_))));
+}
+
+#[test]
+fn rejecting_refunds_the_sender() {
+    let (mut bank, alice, _) = bank_with_customers(1_000, 100);
+    let sent = bank.external_transfer(&alice, IBAN, Money::from_major(300)).unwrap();
+    bank.freeze_account(&alice).unwrap();
+
+    let refund = bank.reject_external_transfer(&sent.id, Some("Account closed at receiving bank")).unwrap();
+
+    assert_eq!(refund.amount, Money::from_major(300));
+    assert_eq!(refund.description.as_deref(), Some("Account closed at receiving bank"));
+    assert!(matches!(
+        &refund.transaction_type,
+        TransactionType::Reversal { original_id, credit: true } if *original_id == sent.id
+    ));
+    assert_eq!(status_of(&bank, &alice, &sent.id), TransactionStatus::Failed);
+    assert_eq!(balance(&bank, &alice), Money::from_major(1_000));
+    assert!(bank.verify_integrity().is_clean());
+    assert_eq!(bank.get_customer(&alice).unwrap().get_account().unwrap().transactions.len(), 3);
+}
+
+#[test]
+fn unknown_and_ordinary_transactions_cannot_be_cleared() {
+    let (mut bank, alice, bob) = bank_with_customers(1_000, 100);
+    bank.transfer(&alice, &bob, Money::from_major(10)).unwrap();
+    let ordinary = bank.get_customer(&alice).unwrap().get_account().unwrap().transactions[1].id.clone();
+
+    assert!(matches!(bank.settle_external_transfer(&"no-such-id".into()), Err(BankError::InvalidInput(_))));
+    assert!(matches!(bank.settle_external_transfer(&ordinary), Err(BankError::InvalidTransfer(_))));
+}
+
+#[test]
+fn transfer_rules_apply_to_external_transfers() {
+    let (mut bank, alice, _) = bank_with_customers(1_000, 100);
+
+    for reference in ["", "not an iban!", &"X".repeat(35)] {
+        assert!(matches!(
+            bank.external_transfer(&alice, reference, Money::from_major(1)),
+            Err(BankError::ValidationError { .. })
+        ));
+    }
+    assert!(matches!(bank.external_transfer(&alice, IBAN, Money::ZERO), Err(BankError::InvalidAmount(_))));
+
+    bank.freeze_account(&alice).unwrap();
+    assert!(matches!(bank.external_transfer(&alice, IBAN, Money::from_major(1)), Err(BankError::AccountFrozen(_))));
+    bank.unfreeze_account(&alice).unwrap();
+
+    let limits = AccountLimits { daily_transfers: Some(1), ..AccountLimits::default() };
+    bank.set_limits(&alice, limits).unwrap();
+    bank.external_transfer(&alice, IBAN, Money::from_major(1)).unwrap();
+    assert!(matches!(
+        bank.external_transfer(&alice, IBAN, Money::from_major(1)),
+        Err(BankError::LimitExceeded(_))
+    ));
+}
+
+#[test]
+fn accounts_with_pending_transfers_cannot_be_closed() {
+    let (mut bank, alice, bob) = bank_with_customers(1_000, 100);
+    let sent = bank.external_transfer(&alice, IBAN, Money::from_major(300)).unwrap();
+
+    assert!(matches!(bank.close_account(&alice, Some(&bob)), Err(BankError::InvalidInput(_))));
+    bank.settle_external_transfer(&sent.id).unwrap();
+    assert_eq!(bank.close_account(&alice, Some(&bob)).unwrap(), Money::from_major(700));
+}
+
+#[test]
+fn clearing_survives_journal_replay() {
+    let (mut bank, alice, _) = bank_with_customers(1_000, 100);
+    let snapshot = serde_json::to_string(&bank).unwrap();
+    bank.enable_journal();
+
+    let details = TransactionDetails::new().description("Invoice 42").unwrap();
+    let settled = bank.external_transfer_with(&alice, IBAN, Money::from_major(100), details).unwrap();
+    let rejected = bank.external_transfer(&alice, "021000021-123456789", Money::from_major(50)).unwrap();
+    let waiting = bank.external_transfer(&alice, IBAN, Money::from_major(25)).unwrap();
+    bank.settle_external_transfer(&settled.id).unwrap();
+    bank.reject_external_transfer(&rejected.id, None).unwrap();
+
+    let mut restored: Bank = serde_json::from_str(&snapshot).unwrap();
+    for entry in bank.take_journal() {
+        restored.apply_journal_entry(entry).unwrap();
+    }
+    assert_eq!(status_of(&restored, &alice, &settled.id), TransactionStatus::Completed);
+    assert_eq!(status_of(&restored, &alice, &rejected.id), TransactionStatus::Failed);
+    assert_eq!(status_of(&restored, &alice, &waiting.id), TransactionStatus::Pending);
+    assert_eq!(balance(&restored, &alice), Money::from_major(875));
+    assert_eq!(restored.total_transactions, bank.total_transactions);
+}
+
+#[test]
+fn subscribers_hear_the_debit_and_the_refund() {
+    let (mut bank, alice, _) = bank_with_customers(1_000, 100);
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let sink = events.clone();
+    bank.subscribe(move |event| sink.lock().unwrap().push(event.clone()));
+
+    let sent = bank.external_transfer(&alice, IBAN, Money::from_major(300)).unwrap();
+    bank.reject_external_transfer(&sent.id, None).unwrap();
+
+    let events = events.lock().unwrap();
+    assert_eq!(events.len(), 2);
+    assert!(events.iter().all(|e| matches!(e, DomainEvent::TransactionPosted { .. })));
+}
@@ -0,0 +1,140 @@
+//! Integration tests for the injectable clock and in-memory storage
+
+use std::sync::Arc;
+
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use rust_banking_system::clock::MockClock;
+use rust_banking_system::models::credentials::{MAX_PIN_ATTEMPTS, PIN_LOCKOUT_MINUTES};
+use rust_banking_system::models::{AccountLimits, CustomerId};
+use rust_banking_system::persistence::Storage;
+use rust_banking_system::{AccountType, Bank, BankError, MemoryStorage, Money};
+
+fn start() -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2024, 3, 28, 10, 0, 0).unwrap()
+}
+
+/// Creates a bank on a mock clock with Alice ($1000 in an account of
+/// `account_type`) and Bob (empty checking account)
+fn bank_at(account_type: AccountType) -> (Bank, Arc<MockClock>, CustomerId, CustomerId) {
+    let clock = Arc::new(MockClock::new(start()));
+    let mut bank = Bank::new("Test Bank".to_string());
+    bank.set_clock(clock.clone());
+
+    let alice = bank
+        .register_customer("Alice".to_string(), "alice@example.com".to_string())
+        .unwrap();
+    let bob = bank
+        .register_customer("Bob".to_string(), "bob@example.com".to_string())
+        .unwrap();
+    bank.create_account_for_customer(&alice, Money::from_major(1000), account_type)
+        .unwrap();
+    bank.create_account_for_customer(&bob, Money::ZERO, AccountType::Checking)
+        .unwrap();
+    (bank, clock, alice, bob)
+}
+
+#[test]
+fn records_are_stamped_with_the_bank_clock() {
+    let (mut bank, clock, alice, _) = bank_at(AccountType::Checking);
+    let customer = bank.get_customer(&alice).unwrap();
+    assert_eq!(customer.registered_at, start());
+    assert_eq!(customer.get_account().unwrap().created_at, start());
+
+    clock.advance(Duration::hours(3));
+    bank.deposit(&alice, Money::from_major(5)).unwrap();
+    let account = bank.get_customer(&alice).unwrap().get_account().unwrap();
+    assert_eq!(account.transactions.last().unwrap().timestamp, start() + Duration::hours(3));
+    assert_eq!(bank.report().generated_at, start() + Duration::hours(3));
+}
+
+#[test]
+fn daily_limits_reset_when_the_day_changes() {
+    let (mut bank, clock, alice, bob) = bank_at(AccountType::Checking);
+    let limits = AccountLimits {
+        daily_withdrawal: Some(Money::from_major(100)),
+        daily_transfers: Some(1),
+        ..Default::default()
+    };
+    bank.set_limits(&alice, limits).unwrap();
+
+    bank.transfer(&alice, &bob, Money::from_major(10)).unwrap();
+    bank.withdraw(&alice, Money::from_major(90)).unwrap();
+    assert!(matches!(
+        bank.transfer(&alice, &bob, Money::from_major(1)),
+        Err(BankError::LimitExceeded(_))
+    ));
+    assert!(matches!(bank.withdraw(&alice, Money::from_major(1)), Err(BankError::LimitExceeded(_))));
+
+    // Still the same UTC day
+    clock.advance(Duration::hours(13));
+    assert!(bank.withdraw(&alice, Money::from_major(1)).is_err());
+
+    clock.advance(Duration::hours(1));
+    bank.transfer(&alice, &bob, Money::from_major(10)).unwrap();
+    bank.withdraw(&alice, Money::from_major(90)).unwrap();
+}
+
+#[test]
+fn savings_withdrawal_count_resets_in_the_next_month() {
+    let (mut bank, clock, alice, _) = bank_at(AccountType::Savings);
+    let limit = AccountType::SAVINGS_MONTHLY_WITHDRAWAL_LIMIT;
+
+    for _ in 0..limit {
+        bank.withdraw(&alice, Money::from_major(1)).unwrap();
+    }
+    assert!(matches!(
+        bank.withdraw(&alice, Money::from_major(1)),
+        Err(BankError::WithdrawalLimitReached { limit: l }) if l == limit
+    ));
+
+    clock.set(Utc.with_ymd_and_hms(2024, 4, 1, 0, 0, 0).unwrap());
+    bank.withdraw(&alice, Money::from_major(1)).unwrap();
+}
+
+#[test]
+fn pin_lockout_expires_after_the_lockout_period() {
+    let (mut bank, clock, alice, _) = bank_at(AccountType::Checking);
+    bank.set_pin(&alice, "1234").unwrap();
+
+    for _ in 1..MAX_PIN_ATTEMPTS {
+        assert!(matches!(bank.authenticate(&alice, "0000"), Err(BankError::InvalidPin { .. })));
+    }
+    match bank.authenticate(&alice, "0000") {
+        Err(BankError::PinLocked { until }) => {
+            assert_eq!(until, start() + Duration::minutes(PIN_LOCKOUT_MINUTES))
+        }
+        other => panic!("expected PinLocked, got {:?}", other),
+    }
+
+    clock.advance(Duration::minutes(PIN_LOCKOUT_MINUTES - 1));
+    assert!(matches!(bank.authenticate(&alice, "1234"), Err(BankError::PinLocked { .. })));
+
+    clock.advance(Duration::minutes(1));
+    bank.authenticate(&alice, "1234").unwrap();
+}
+
+#[test]
+fn memory_storage_round_trips_snapshot_and_journal() {
+    let (mut bank, _, alice, bob) = bank_at(AccountType::Checking);
+    let storage = MemoryStorage::new();
+    assert!(storage.load_bank().is_err());
+
+    storage.save_bank(&bank).unwrap();
+    bank.enable_journal();
+    bank.deposit(&alice, Money::from_major(50)).unwrap();
+    bank.transfer(&alice, &bob, Money::from_major(25)).unwrap();
+    let entries = bank.take_journal();
+    storage.append_journal(&bank, &entries).unwrap();
+    assert_eq!(storage.journal_len(), entries.len());
+
+    let loaded = storage.load_bank().unwrap();
+    for id in [&alice, &bob] {
+        let original = bank.get_customer(id).unwrap().get_account().unwrap();
+        let restored = loaded.get_customer(id).unwrap().get_account().unwrap();
+        assert_eq!(restored.balance, original.balance);
+        assert_eq!(restored.transactions.len(), original.transactions.len());
+    }
+
+    storage.save_bank(&loaded).unwrap();
+    assert_eq!(storage.journal_len(), 0);
+}
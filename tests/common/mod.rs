@@ -0,0 +1,97 @@
+//! Test helpers - temp files, a bank with customers and runs of the binary,
+//! the same in every test
+//!
+//! Shared by the integration tests with `mod common;`. Each test file is its
+//! own crate and uses only some of them, and the binary is only built with
+//! the `cli` feature.
+
+#![allow(dead_code)]
+#![cfg_attr(not(feature = "cli"), allow(unused))]
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output, Stdio};
+
+use rust_banking_system::models::CustomerId;
+use rust_banking_system::{AccountType, Bank, Money};
+
+/// A path in the temp directory unique to this test process
+pub fn temp_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("bank-tests-{}-{}", std::process::id(), name))
+}
+
+/// `temp_path` as a string, for the storage APIs that take one
+pub fn temp_file(name: &str) -> String {
+    temp_path(name).to_string_lossy().into_owned()
+}
+
+/// Registers Alice and Bob with checking accounts opened with `alice` and
+/// `bob` whole units; returns their IDs
+pub fn add_customers(bank: &mut Bank, alice: i64, bob: i64) -> (CustomerId, CustomerId) {
+    let mut open = |name: &str, deposit: i64| {
+        let id = bank
+            .register_customer(name.to_string(), format!("{}@example.com", name.to_lowercase()))
+            .unwrap();
+        bank.create_account_for_customer(&id, Money::from_major(deposit), AccountType::Checking)
+            .unwrap();
+        id
+    };
+    (open("Alice", alice), open("Bob", bob))
+}
+
+/// A new bank with `add_customers`; returns (bank, alice, bob)
+pub fn bank_with_customers(alice: i64, bob: i64) -> (Bank, CustomerId, CustomerId) {
+    let mut bank = Bank::new("Test Bank".to_string());
+    let (alice, bob) = add_customers(&mut bank, alice, bob);
+    (bank, alice, bob)
+}
+
+/// The balance of `customer_id`'s account
+pub fn balance(bank: &Bank, customer_id: &CustomerId) -> Money {
+    bank.get_customer(customer_id).unwrap().get_account().unwrap().balance
+}
+
+/// The binary on `data_file` with `args`, printing plain text and reading
+/// no real config file
+#[cfg(feature = "cli")]
+pub fn command(data_file: impl AsRef<Path>, args: &[&str]) -> Command {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_rust-banking-system"));
+    command
+        .arg("--data-file")
+        .arg(data_file.as_ref())
+        .args(["--no-color", "--ascii"])
+        .args(args)
+        // Keep any real config file out of the tests
+        .env("XDG_CONFIG_HOME", std::env::temp_dir())
+        .env("HOME", std::env::temp_dir())
+        .env_remove("BANK_DATA_FILE");
+    command
+}
+
+/// Runs `command`, feeding it `input`, and returns what it printed
+#[cfg(feature = "cli")]
+pub fn feed(mut command: Command, input: &str) -> Output {
+    let mut child = command.stdin(Stdio::piped()).stdout(Stdio::piped()).stderr(Stdio::piped()).spawn().unwrap();
+    child.stdin.take().unwrap().write_all(input.as_bytes()).unwrap();
+    child.wait_with_output().unwrap()
+}
+
+/// Runs the binary on `data_file` with `args`, feeding it `input`
+#[cfg(feature = "cli")]
+pub fn output(data_file: impl AsRef<Path>, args: &[&str], input: &str) -> Output {
+    feed(command(data_file, args), input)
+}
+
+/// `output`, keeping only stdout
+#[cfg(feature = "cli")]
+pub fn run(data_file: impl AsRef<Path>, args: &[&str], input: &str) -> String {
+    String::from_utf8_lossy(&output(data_file, args, input).stdout).into_owned()
+}
+
+/// `run` with `dir` as the working directory
+#[cfg(feature = "cli")]
+pub fn run_in(dir: &Path, data_file: impl AsRef<Path>, args: &[&str], input: &str) -> String {
+    let mut command = command(data_file, args);
+    command.current_dir(dir);
+    String::from_utf8_lossy(&feed(command, input).stdout).into_owned()
+}
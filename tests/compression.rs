@@ -0,0 +1,169 @@
+//! Integration tests for compressed data files and streamed saving
+
+// The tests that run the binary need the `cli` feature; without it the
+// imports and helpers only they use go unused
+#![cfg_attr(not(feature = "cli"), allow(unused))]
+
+mod common;
+
+use std::io::Write;
+
+use rust_banking_system::models::CustomerId;
+use rust_banking_system::persistence::{self, journal_path, Compression, DataFormat, JsonFileStorage, Storage};
+use rust_banking_system::{AccountType, Bank, BankError, Money};
+
+use common::temp_file;
+
+/// Creates a bank with two customers and some history
+fn bank_with_history() -> (Bank, CustomerId, CustomerId) {
+    let mut bank = Bank::new("Test Bank".to_string());
+    let alice = bank.register_customer("Alice".to_string(), "alice@example.com".to_string()).unwrap();
+    let bob = bank.register_customer("Bob".to_string(), "bob@example.com".to_string()).unwrap();
+    bank.create_account_for_customer(&alice, Money::from_major(100), AccountType::Checking).unwrap();
+    bank.create_account_for_customer(&bob, Money::from_major(50), AccountType::Savings).unwrap();
+    for _ in 0..20 {
+        bank.transfer(&alice, &bob, Money::from_major(1)).unwrap();
+    }
+    (bank, alice, bob)
+}
+
+/// The bank as a JSON value, to compare two banks field by field
+fn as_value(bank: &Bank) -> serde_json::Value {
+    serde_json::to_value(bank).unwrap()
+}
+
+fn remove(path: &str) {
+    let _ = std::fs::remove_file(path);
+    let _ = std::fs::remove_file(journal_path(path));
+    for index in 1..=3 {
+        let _ = std::fs::remove_file(persistence::backup_path(path, index));
+    }
+}
+
+#[test]
+fn every_compression_round_trips_every_format() {
+    let (bank, _, _) = bank_with_history();
+    for format in DataFormat::ALL {
+        let data = format.encode(&bank).unwrap();
+        for compression in Compression::ALL {
+            let compressed = compression.compress(&data).unwrap();
+            assert_eq!(Compression::detect(&compressed), compression);
+            if compression != Compression::None {
+                assert!(compressed.len() < data.len(), "{} {}", format, compression);
+            }
+            assert_eq!(compression.decompress(&compressed).unwrap(), data);
+        }
+    }
+}
+
+#[test]
+fn the_file_name_picks_the_format_and_the_compression() {
+    let (bank, alice, _) = bank_with_history();
+    let path = temp_file("bank.yaml.gz");
+    persistence::save_bank(&bank, &path).unwrap();
+
+    let data = std::fs::read(&path).unwrap();
+    assert_eq!(Compression::detect(&data), Compression::Gzip);
+    let decompressed = Compression::Gzip.decompress(&data).unwrap();
+    assert_eq!(DataFormat::detect(&decompressed), DataFormat::Yaml);
+
+    let loaded = persistence::load_bank(&path).unwrap();
+    assert_eq!(loaded.get_customer(&alice).unwrap().get_account().unwrap().balance, Money::from_major(80));
+    assert_eq!(Compression::from_path("bank.msgpack.zst"), Compression::Zstd);
+    assert_eq!(DataFormat::from_path("bank.msgpack.zst"), DataFormat::MessagePack);
+    assert_eq!(Compression::strip_extension("bank.json.gz"), "bank.json");
+    assert_eq!(Compression::strip_extension("bank.json"), "bank.json");
+    remove(&path);
+}
+
+#[test]
+fn loading_detects_the_compression_whatever_the_name() {
+    let (bank, _, _) = bank_with_history();
+    let path = temp_file("plain-looking.json");
+    let storage = JsonFileStorage::new(&path).with_compression(Compression::Zstd);
+    assert_eq!(storage.compression(), Compression::Zstd);
+    storage.save_bank(&bank).unwrap();
+
+    assert_eq!(Compression::detect(&std::fs::read(&path).unwrap()), Compression::Zstd);
+    assert_eq!(as_value(&persistence::read_bank(&path).unwrap()), as_value(&bank));
+    assert_eq!(JsonFileStorage::new(&path).compression(), Compression::None);
+    assert_eq!(as_value(&JsonFileStorage::new(&path).load_bank().unwrap()), as_value(&bank));
+    remove(&path);
+}
+
+#[test]
+fn a_replayed_journal_is_compacted_with_the_same_compression() {
+    let (mut bank, alice, _) = bank_with_history();
+    let path = temp_file("journaled.json.zst");
+    let storage = JsonFileStorage::new(&path);
+    storage.save_bank(&bank).unwrap();
+
+    bank.enable_journal();
+    bank.deposit(&alice, Money::from_major(5)).unwrap();
+    let entries = bank.take_journal();
+    storage.append_journal(&bank, &entries).unwrap();
+
+    let loaded = storage.load_bank().unwrap();
+    assert_eq!(loaded.get_customer(&alice).unwrap().get_account().unwrap().balance, Money::from_major(85));
+    assert!(!std::path::Path::new(&journal_path(&path)).exists());
+    assert_eq!(Compression::detect(&std::fs::read(&path).unwrap()), Compression::Zstd);
+    remove(&path);
+}
+
+#[test]
+fn corrupt_compressed_data_is_reported_as_corrupted_storage() {
+    let files = [("bad.gz", &[0x1f, 0x8b, 0x00, 0x01, 0x02][..]), ("bad.zst", &[0x28, 0xb5, 0x2f, 0xfd, 0xff][..])];
+    for (name, data) in files {
+        let path = temp_file(name);
+        std::fs::write(&path, data).unwrap();
+        let result = persistence::read_bank(&path);
+        let corrupted = matches!(result, Err(BankError::StorageCorrupted { ref location, .. }) if *location == path);
+        assert!(corrupted, "{}", name);
+        remove(&path);
+    }
+    assert_eq!("gz".parse::<Compression>().unwrap(), Compression::Gzip);
+    assert_eq!("ZSTD".parse::<Compression>().unwrap(), Compression::Zstd);
+    assert!(matches!("lz4".parse::<Compression>(), Err(BankError::InvalidInput(_))));
+}
+
+#[test]
+fn a_failed_streamed_write_leaves_the_old_file_in_place() {
+    let path = temp_file("atomic.json");
+    persistence::write_atomic(&path, b"old contents").unwrap();
+
+    let result = persistence::write_atomic_with(&path, |writer| {
+        writer.write_all(b"half of the new")?;
+        Err(BankError::InvalidInput("serializer gave up".to_string()))
+    });
+
+    assert!(matches!(result, Err(BankError::InvalidInput(_))));
+    assert_eq!(std::fs::read(&path).unwrap(), b"old contents");
+    assert!(!std::path::Path::new(&format!("{}.tmp", path)).exists());
+    remove(&path);
+}
+
+#[cfg(feature = "cli")]
+#[test]
+fn command_mode_runs_on_a_compressed_data_file() {
+    let data_file = temp_file("cli.json");
+    let zst_file = temp_file("cli.json.zst");
+    remove(&data_file);
+    remove(&zst_file);
+    let run = |data_file: &str, args: &[&str]| common::run(data_file, args, "\n");
+    run(&data_file, &["register", "--name", "Ann", "--email", "ann@example.com", "--pin", "1234"]);
+    run(&data_file, &["create-account", "--customer", "ann@example.com", "--deposit", "100"]);
+
+    let json: serde_json::Value =
+        serde_json::from_str(&run(&data_file, &["--json", "export-bank", "--output", &zst_file])).unwrap();
+    assert_eq!(json["result"]["format"], "json");
+    assert_eq!(Compression::detect(&std::fs::read(&zst_file).unwrap()), Compression::Zstd);
+
+    // The export is a data file in its own right, saved back compressed
+    run(&zst_file, &["deposit", "--customer", "ann@example.com", "--amount", "25", "--pin", "1234"]);
+    assert_eq!(Compression::detect(&std::fs::read(&zst_file).unwrap()), Compression::Zstd);
+    let json: serde_json::Value =
+        serde_json::from_str(&run(&zst_file, &["--json", "account", "--customer", "ann@example.com"])).unwrap();
+    assert_eq!(json["result"]["account"]["balance"], "125.00");
+    remove(&data_file);
+    remove(&zst_file);
+}
@@ -0,0 +1,211 @@
+//! Stress tests for sharing one bank between threads
+
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration as StdDuration;
+
+use chrono::{Duration, Utc};
+use rust_banking_system::models::CustomerId;
+use rust_banking_system::{AccountType, Bank, Money, SharedBank};
+
+const ACCOUNTS: usize = 8;
+const THREADS: usize = 8;
+const TRANSFERS_PER_THREAD: usize = 200;
+
+/// Creates a shared bank with `ACCOUNTS` accounts holding 100 each
+fn shared_bank() -> (SharedBank, Vec<CustomerId>) {
+    let mut bank = Bank::new("Test Bank".to_string());
+    let ids = (0..ACCOUNTS)
+        .map(|i| {
+            let id = bank
+                .register_customer(format!("Customer {}", i), format!("c{}@example.com", i))
+                .unwrap();
+            bank.create_account_for_customer(&id, Money::from_major(100), AccountType::Checking)
+                .unwrap();
+            id
+        })
+        .collect();
+    (SharedBank::new(bank), ids)
+}
+
+fn total_balance(bank: &SharedBank, ids: &[CustomerId]) -> Money {
+    let bank = bank.read();
+    ids.iter()
+        .map(|id| bank.get_customer(id).unwrap().get_account().unwrap().balance)
+        .sum()
+}
+
+#[test]
+fn shared_bank_is_send_and_sync() {
+    fn assert_send_sync<T: Send + Sync + Clone>() {}
+    assert_send_sync::<SharedBank>();
+}
+
+#[test]
+fn concurrent_transfers_conserve_money() {
+    let (bank, ids) = shared_bank();
+    let before = total_balance(&bank, &ids);
+    let transactions_before = bank.report().total_transactions;
+
+    let writers: Vec<_> = (0..THREADS)
+        .map(|t| {
+            let (bank, ids) = (bank.clone(), ids.clone());
+            thread::spawn(move || {
+                let mut succeeded = 0u64;
+                for i in 0..TRANSFERS_PER_THREAD {
+                    let from = &ids[(t + i) % ACCOUNTS];
+                    let to = &ids[(t + i * 3 + 1) % ACCOUNTS];
+                    if from == to {
+                        continue;
+                    }
+                    // Amounts vary so that some transfers overdraw and fail
+                    let amount = Money::from_major(((t * 7 + i) % 60 + 1) as i64);
+                    if bank.transfer(from, to, amount).is_ok() {
+                        succeeded += 1;
+                    }
+                }
+                succeeded
+            })
+        })
+        .collect();
+
+    // Readers run alongside the writers and must always see whole transfers
+    let readers: Vec<_> = (0..2)
+        .map(|_| {
+            let (bank, ids) = (bank.clone(), ids.clone());
+            thread::spawn(move || {
+                for i in 0..100 {
+                    assert_eq!(total_balance(&bank, &ids), before);
+                    let statement = bank
+                        .statement(&ids[i % ACCOUNTS], Utc::now() - Duration::days(1), Utc::now() + Duration::days(1))
+                        .unwrap();
+                    assert!(!statement.closing_balance.is_negative());
+                    bank.report();
+                }
+            })
+        })
+        .collect();
+
+    let succeeded: u64 = writers.into_iter().map(|h| h.join().unwrap()).sum();
+    for reader in readers {
+        reader.join().unwrap();
+    }
+
+    assert!(succeeded > 0);
+    assert_eq!(total_balance(&bank, &ids), before);
+    let bank = bank.read();
+    for id in &ids {
+        assert!(!bank.get_customer(id).unwrap().get_account().unwrap().balance.is_negative());
+    }
+    // Every transfer records a debit and a credit
+    assert_eq!(bank.report().total_transactions, transactions_before + succeeded * 2);
+}
+
+#[test]
+fn concurrent_deposits_and_withdrawals_all_land() {
+    let (bank, ids) = shared_bank();
+    let id = ids[0].clone();
+
+    let handles: Vec<_> = (0..THREADS)
+        .map(|_| {
+            let (bank, id) = (bank.clone(), id.clone());
+            thread::spawn(move || {
+                for _ in 0..50 {
+                    bank.deposit(&id, Money::from_major(2)).unwrap();
+                    bank.withdraw(&id, Money::from_major(1)).unwrap();
+                }
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+
+    let balance = bank.read().get_customer(&id).unwrap().get_account().unwrap().balance;
+    assert_eq!(balance, Money::from_major(100 + (THREADS * 50) as i64));
+}
+
+#[test]
+fn operations_on_other_accounts_go_ahead_while_one_is_locked() {
+    let (bank, ids) = shared_bank();
+    let guard = bank.lock_accounts(&[&ids[0]]);
+
+    let (done, finished) = mpsc::channel();
+    let (other_bank, from, to) = (bank.clone(), ids[1].clone(), ids[2].clone());
+    let other = done.clone();
+    thread::spawn(move || {
+        other_bank.transfer(&from, &to, Money::from_major(5)).unwrap();
+        other.send("other").unwrap();
+    });
+    assert_eq!(finished.recv_timeout(StdDuration::from_secs(10)).unwrap(), "other");
+
+    let (locked_bank, id) = (bank.clone(), ids[0].clone());
+    thread::spawn(move || {
+        locked_bank.deposit(&id, Money::from_major(5)).unwrap();
+        done.send("locked").unwrap();
+    });
+    thread::sleep(StdDuration::from_millis(100));
+    assert!(finished.try_recv().is_err(), "the deposit ran while its account was locked");
+
+    drop(guard);
+    assert_eq!(finished.recv_timeout(StdDuration::from_secs(10)).unwrap(), "locked");
+    assert_eq!(total_balance(&bank, &ids), Money::from_major(100 * ACCOUNTS as i64 + 5));
+}
+
+#[test]
+fn whole_bank_writers_and_account_operations_do_not_interleave() {
+    let (bank, ids) = shared_bank();
+    let before = total_balance(&bank, &ids);
+
+    let handles: Vec<_> = (0..THREADS)
+        .map(|t| {
+            let (bank, ids) = (bank.clone(), ids.clone());
+            thread::spawn(move || {
+                for i in 0..100 {
+                    let from = &ids[(t + i) % ACCOUNTS];
+                    let to = &ids[(t + i + 1) % ACCOUNTS];
+                    let amount = Money::from_major(((t + i) % 40 + 1) as i64);
+                    // Half the threads go through the account locks, half
+                    // lock the whole bank
+                    let _ = if t % 2 == 0 {
+                        bank.transfer(from, to, amount)
+                    } else {
+                        bank.write().transfer(from, to, amount)
+                    };
+                }
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+
+    assert_eq!(total_balance(&bank, &ids), before);
+    let bank = bank.read();
+    for id in &ids {
+        let account = bank.get_customer(id).unwrap().get_account().unwrap();
+        assert!(!account.balance.is_negative());
+        let replayed: Money = account.transactions.iter().map(|tx| tx.signed_amount()).sum();
+        assert_eq!(replayed, account.balance);
+    }
+}
+
+#[test]
+fn withdrawals_from_different_accounts_never_overdraw_the_vault() {
+    let (bank, ids) = shared_bank();
+    // Leave 50 in the vault for eight withdrawals of 10
+    let cash = bank.read().vault().unwrap();
+    bank.write().vault_withdraw(cash - Money::from_major(50)).unwrap();
+
+    let handles: Vec<_> = ids
+        .iter()
+        .map(|id| {
+            let (bank, id) = (bank.clone(), id.clone());
+            thread::spawn(move || bank.withdraw(&id, Money::from_major(10)).is_ok())
+        })
+        .collect();
+    let succeeded = handles.into_iter().map(|h| h.join().unwrap()).filter(|ok| *ok).count();
+
+    assert_eq!(succeeded, 5);
+    assert_eq!(bank.read().vault(), Some(Money::ZERO));
+}
@@ -0,0 +1,124 @@
+//! Integration tests for config files
+
+mod common;
+
+use std::fs;
+use std::path::PathBuf;
+
+use rust_banking_system::config::{MAX_BACKUP_COUNT, MAX_BANK_NAME_LEN};
+use rust_banking_system::{BankError, Config};
+
+use common::temp_path;
+
+fn invalid_field(result: Result<Config, BankError>) -> String {
+    match result {
+        Err(BankError::ValidationError { field, .. }) => field,
+        other => panic!("expected a validation error, got {:?}", other),
+    }
+}
+
+#[test]
+fn defaults_match_the_built_in_settings() {
+    let config = Config::default();
+    assert_eq!(config.bank_name, "Rust National Bank");
+    assert_eq!(config.data_file, "bank_data.json");
+    assert!(!config.autosave);
+    assert_eq!(config.currency_symbol, "$");
+    assert_eq!(config.locale, "en-US");
+    assert!(config.validate().is_ok());
+}
+
+#[test]
+fn partial_toml_files_keep_the_other_defaults() {
+    let config = Config::from_toml("bank_name = \"Campus Credit Union\"\nautosave = true\n").unwrap();
+    assert_eq!(config.bank_name, "Campus Credit Union");
+    assert!(config.autosave);
+    assert_eq!(config.data_file, Config::default().data_file);
+
+    assert_eq!(Config::from_toml("").unwrap(), Config::default());
+}
+
+#[test]
+fn json_files_are_accepted_too() {
+    let config = Config::from_json(r#"{"currency_symbol": "€", "locale": "de-DE", "backup_count": 0}"#).unwrap();
+    assert_eq!(config.currency_symbol, "€");
+    assert_eq!(config.locale, "de-DE");
+    assert_eq!(config.backup_count, 0);
+}
+
+#[test]
+fn unknown_and_mistyped_settings_are_rejected() {
+    assert!(matches!(Config::from_toml("bank_nmae = \"Typo\""), Err(BankError::InvalidInput(_))));
+    assert!(matches!(Config::from_toml("autosave = \"yes\""), Err(BankError::InvalidInput(_))));
+    assert!(matches!(Config::from_json(r#"{"colour": "red"}"#), Err(BankError::InvalidInput(_))));
+}
+
+#[test]
+fn each_setting_is_validated() {
+    let long_name = format!("bank_name = \"{}\"", "x".repeat(MAX_BANK_NAME_LEN + 1));
+    let cases = [
+        ("bank_name = \"   \"", "bank_name"),
+        (long_name.as_str(), "bank_name"),
+        ("data_file = \"\"", "data_file"),
+        ("currency_symbol = \"\"", "currency_symbol"),
+        ("currency_symbol = \"US$1\"", "currency_symbol"),
+        ("locale = \"english\"", "locale"),
+        ("locale = \"en-us\"", "locale"),
+        ("thousands_separator = \"--\"", "thousands_separator"),
+        ("thousands_separator = \"1\"", "thousands_separator"),
+        ("thousands_separator = \".\"", "thousands_separator"),
+        ("decimal_places = 3", "decimal_places"),
+        ("date_format = \"today\"", "date_format"),
+        ("date_format = \"%Q\"", "date_format"),
+    ];
+    for (text, field) in cases {
+        assert_eq!(invalid_field(Config::from_toml(text)), field, "{}", text);
+    }
+    let too_many = format!("backup_count = {}", MAX_BACKUP_COUNT + 1);
+    assert_eq!(invalid_field(Config::from_toml(&too_many)), "backup_count");
+
+    for locale in ["en", "fil", "pt_BR", "es-419"] {
+        assert!(Config::from_toml(&format!("locale = \"{}\"", locale)).is_ok(), "{}", locale);
+    }
+}
+
+#[test]
+fn files_are_parsed_by_extension() {
+    let toml_path = temp_path("bank.toml");
+    let json_path = temp_path("bank.json");
+    fs::write(&toml_path, "data_file = \"from-toml.json\"\n").unwrap();
+    fs::write(&json_path, r#"{"data_file": "from-json.json"}"#).unwrap();
+
+    let from_toml = Config::discover(Some(&toml_path));
+    let from_json = Config::load(&json_path);
+    fs::remove_file(&toml_path).unwrap();
+    fs::remove_file(&json_path).unwrap();
+
+    assert_eq!(from_toml.unwrap().data_file, "from-toml.json");
+    assert_eq!(from_json.unwrap().data_file, "from-json.json");
+}
+
+#[test]
+fn errors_name_the_file() {
+    let path = temp_path("broken.toml");
+    fs::write(&path, "autosave = maybe\n").unwrap();
+    let result = Config::load(&path);
+    fs::remove_file(&path).unwrap();
+
+    match result {
+        Err(BankError::InvalidInput(reason)) => assert!(reason.contains("broken.toml"), "{}", reason),
+        other => panic!("expected a parse error, got {:?}", other),
+    }
+}
+
+#[test]
+fn an_explicit_missing_file_is_an_error() {
+    let missing = temp_path("missing.toml");
+    assert!(matches!(Config::discover(Some(&missing)), Err(BankError::IoError(_))));
+}
+
+#[test]
+fn the_current_directory_is_searched_first() {
+    let locations = Config::default_locations();
+    assert_eq!(locations[0], PathBuf::from("bank.toml"));
+}
@@ -0,0 +1,141 @@
+//! Integration tests for editing, deactivating and deleting customers
+
+mod common;
+
+use chrono::Utc;
+use rust_banking_system::models::{AccountStatus, CustomerStatus, CustomerUpdate};
+use rust_banking_system::{Bank, BankError, BankEvent, Frequency, Money};
+
+use common::bank_with_customers;
+
+#[test]
+fn deactivated_customers_cannot_log_in_or_move_money() {
+    let (mut bank, alice, bob) = bank_with_customers(100, 50);
+    bank.set_pin(&alice, "1234").unwrap();
+    bank.deactivate_customer(&alice).unwrap();
+
+    assert!(matches!(bank.authenticate(&alice, "1234"), Err(BankError::CustomerDeactivated(_))));
+    assert!(matches!(bank.deposit(&alice, Money::from_major(1)), Err(BankError::CustomerDeactivated(_))));
+    assert!(matches!(
+        bank.transfer(&bob, &alice, Money::from_major(1)),
+        Err(BankError::CustomerDeactivated(_))
+    ));
+
+    // Still visible to staff
+    assert_eq!(bank.list_customers().len(), 2);
+    assert_eq!(bank.get_customer(&alice).unwrap().status, CustomerStatus::Deactivated);
+
+    assert!(matches!(bank.deactivate_customer(&alice), Err(BankError::InvalidInput(_))));
+    bank.reactivate_customer(&alice).unwrap();
+    bank.authenticate(&alice, "1234").unwrap();
+    bank.withdraw(&alice, Money::from_major(1)).unwrap();
+}
+
+#[test]
+fn customers_holding_money_cannot_be_deleted() {
+    let (mut bank, alice, _) = bank_with_customers(100, 50);
+    assert!(matches!(bank.delete_customer(&alice), Err(BankError::InvalidInput(_))));
+    assert_eq!(bank.get_customer(&alice).unwrap().status, CustomerStatus::Active);
+}
+
+#[test]
+fn deleted_customers_are_hidden_but_still_resolve_by_account() {
+    let (mut bank, alice, bob) = bank_with_customers(100, 50);
+    let alice_account = bank.get_customer(&alice).unwrap().get_account_id().unwrap();
+    bank.transfer(&alice, &bob, Money::from_major(100)).unwrap();
+
+    bank.delete_customer(&alice).unwrap();
+
+    assert!(matches!(bank.get_customer(&alice), Err(BankError::CustomerNotFound(_))));
+    assert_eq!(bank.list_customers().len(), 1);
+    assert!(bank.find_customers_by_name("alice").is_empty());
+    assert!(matches!(bank.delete_customer(&alice), Err(BankError::CustomerNotFound(_))));
+
+    // Bob's transfer-in still names Alice as the sender
+    let owner = bank.find_customer_by_account_id(&alice_account).unwrap();
+    assert_eq!(owner.name, "Alice");
+    assert_eq!(owner.status, CustomerStatus::Deleted);
+    assert_eq!(owner.get_account().unwrap().status, AccountStatus::Closed);
+
+    // The email address is free again
+    bank.register_customer("Alice".to_string(), "alice@example.com".to_string())
+        .unwrap();
+}
+
+#[test]
+fn deleting_cancels_standing_orders_even_on_replay() {
+    let (mut bank, alice, bob) = bank_with_customers(100, 50);
+    bank.create_schedule(&bob, &alice, Money::from_major(5), Frequency::Weekly, Utc::now())
+        .unwrap();
+    bank.withdraw(&alice, Money::from_major(100)).unwrap();
+
+    bank.enable_journal();
+    let snapshot = serde_json::to_string(&bank).unwrap();
+    bank.delete_customer(&alice).unwrap();
+    assert!(bank.list_schedules().is_empty());
+
+    let mut restored: Bank = serde_json::from_str(&snapshot).unwrap();
+    for entry in bank.take_journal() {
+        restored.apply_journal_entry(entry).unwrap();
+    }
+    assert!(restored.list_schedules().is_empty());
+    assert!(restored.get_customer(&alice).is_err());
+}
+
+#[test]
+fn profile_edits_change_only_the_given_fields() {
+    let (mut bank, alice, _) = bank_with_customers(100, 50);
+    let changes = CustomerUpdate {
+        phone: Some(Some(" 555-0100 ".to_string())),
+        address: Some(Some("1 Main St".to_string())),
+        ..Default::default()
+    };
+    bank.update_customer(&alice, changes).unwrap();
+
+    let customer = bank.get_customer(&alice).unwrap();
+    assert_eq!(customer.name, "Alice");
+    assert_eq!(customer.phone.as_deref(), Some("5550100"));
+    assert_eq!(customer.address.as_deref(), Some("1 Main St"));
+
+    // Some(None) removes a field
+    let changes = CustomerUpdate { phone: Some(None), ..Default::default() };
+    bank.update_customer(&alice, changes).unwrap();
+    assert_eq!(bank.get_customer(&alice).unwrap().phone, None);
+    assert_eq!(bank.get_customer(&alice).unwrap().address.as_deref(), Some("1 Main St"));
+}
+
+#[test]
+fn edited_emails_must_stay_unique() {
+    let (mut bank, alice, _) = bank_with_customers(100, 50);
+    let taken = CustomerUpdate { email: Some("BOB@example.com".to_string()), ..Default::default() };
+    assert!(matches!(bank.update_customer(&alice, taken), Err(BankError::CustomerAlreadyExists(_))));
+
+    // Emails are normalized, so a case-only change is no change at all
+    let own = CustomerUpdate { email: Some("Alice@Example.com".to_string()), ..Default::default() };
+    assert!(matches!(bank.update_customer(&alice, own), Err(BankError::InvalidInput(_))));
+
+    let blank = CustomerUpdate { name: Some("  ".to_string()), ..Default::default() };
+    assert!(matches!(bank.update_customer(&alice, blank), Err(BankError::ValidationError { .. })));
+    assert!(matches!(
+        bank.update_customer(&alice, CustomerUpdate::default()),
+        Err(BankError::InvalidInput(_))
+    ));
+}
+
+#[test]
+fn profile_edits_are_recorded_as_events() {
+    let (mut bank, alice, _) = bank_with_customers(100, 50);
+    bank.enable_journal();
+    let changes = CustomerUpdate { name: Some("Alice Smith".to_string()), ..Default::default() };
+    bank.update_customer(&alice, changes).unwrap();
+
+    let entries = bank.take_journal();
+    assert_eq!(entries.len(), 1);
+    match &entries[0] {
+        BankEvent::CustomerUpdated { customer_id, name, .. } => {
+            assert_eq!(customer_id, &alice);
+            assert_eq!(name, "Alice Smith");
+        }
+        other => panic!("expected CustomerUpdated, got {:?}", other),
+    }
+}
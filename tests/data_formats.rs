@@ -0,0 +1,144 @@
+//! Integration tests for saving and loading the bank in other data formats
+
+// The tests that run the binary need the `cli` feature; without it the
+// imports and helpers only they use go unused
+#![cfg_attr(not(feature = "cli"), allow(unused))]
+
+mod common;
+
+use rust_banking_system::models::CustomerId;
+use rust_banking_system::persistence::{self, journal_path, DataFormat, JsonFileStorage, Storage};
+use rust_banking_system::{AccountType, Bank, BankError, Money};
+
+use common::temp_file;
+
+/// Creates a bank with two customers and some history
+fn bank_with_history() -> (Bank, CustomerId, CustomerId) {
+    let mut bank = Bank::new("Test Bank".to_string());
+    let alice = bank.register_customer("Alice".to_string(), "alice@example.com".to_string()).unwrap();
+    let bob = bank.register_customer("Bob".to_string(), "bob@example.com".to_string()).unwrap();
+    bank.create_account_for_customer(&alice, Money::from_major(100), AccountType::Checking).unwrap();
+    bank.create_account_for_customer(&bob, Money::from_major(50), AccountType::Savings).unwrap();
+    bank.transfer(&alice, &bob, Money::from_major(30)).unwrap();
+    (bank, alice, bob)
+}
+
+/// The bank as a JSON value, to compare two banks field by field
+fn as_value(bank: &Bank) -> serde_json::Value {
+    serde_json::to_value(bank).unwrap()
+}
+
+fn remove(path: &str) {
+    let _ = std::fs::remove_file(path);
+    let _ = std::fs::remove_file(journal_path(path));
+    for index in 1..=3 {
+        let _ = std::fs::remove_file(persistence::backup_path(path, index));
+    }
+}
+
+#[test]
+fn every_format_round_trips_the_whole_bank() {
+    let (bank, _, _) = bank_with_history();
+    for format in DataFormat::ALL {
+        let data = format.encode(&bank).unwrap();
+        assert_eq!(DataFormat::detect(&data), format);
+        let restored = format.decode(&data, "memory").unwrap();
+        assert_eq!(as_value(&restored), as_value(&bank), "{}", format);
+    }
+    let json = DataFormat::Json.encode(&bank).unwrap().len();
+    assert!(DataFormat::MessagePack.encode(&bank).unwrap().len() < json);
+}
+
+#[test]
+fn the_extension_picks_the_format_a_file_is_saved_in() {
+    let (bank, alice, _) = bank_with_history();
+    let files = [("bank.yml", DataFormat::Yaml), ("bank.toml", DataFormat::Toml), ("bank.mpk", DataFormat::MessagePack)];
+    for (name, format) in files {
+        let path = temp_file(name);
+        persistence::save_bank(&bank, &path).unwrap();
+        assert_eq!(DataFormat::detect(&std::fs::read(&path).unwrap()), format);
+
+        let loaded = persistence::load_bank(&path).unwrap();
+        assert_eq!(loaded.get_customer(&alice).unwrap().get_account().unwrap().balance, Money::from_major(70));
+        remove(&path);
+    }
+    assert_eq!(DataFormat::from_path("bank_data.json"), DataFormat::Json);
+    assert_eq!(DataFormat::from_path("bank_data"), DataFormat::Json);
+}
+
+#[test]
+fn loading_detects_the_format_whatever_the_extension() {
+    let (bank, _, _) = bank_with_history();
+    let path = temp_file("renamed.json");
+
+    assert_eq!(persistence::export_bank(&bank, &path, Some(DataFormat::Yaml)).unwrap(), DataFormat::Yaml);
+    assert!(!std::fs::read_to_string(&path).unwrap().starts_with('{'));
+    assert_eq!(as_value(&persistence::read_bank(&path).unwrap()), as_value(&bank));
+    remove(&path);
+}
+
+#[test]
+fn a_storage_with_a_format_replays_its_json_journal() {
+    let (mut bank, alice, _) = bank_with_history();
+    let path = temp_file("journaled.dat");
+    let storage = JsonFileStorage::new(&path).with_format(DataFormat::MessagePack);
+    assert_eq!(storage.format(), DataFormat::MessagePack);
+    storage.save_bank(&bank).unwrap();
+
+    bank.enable_journal();
+    bank.deposit(&alice, Money::from_major(5)).unwrap();
+    let entries = bank.take_journal();
+    storage.append_journal(&bank, &entries).unwrap();
+
+    let loaded = storage.load_bank().unwrap();
+    assert_eq!(loaded.get_customer(&alice).unwrap().get_account().unwrap().balance, Money::from_major(75));
+    // Loading compacted the journal into a snapshot in the same format
+    assert_eq!(DataFormat::detect(&std::fs::read(&path).unwrap()), DataFormat::MessagePack);
+    remove(&path);
+}
+
+#[test]
+fn text_formats_are_told_apart_by_their_first_line() {
+    assert_eq!(DataFormat::detect(b"  \n{\"name\": \"x\"}"), DataFormat::Json);
+    assert_eq!(DataFormat::detect(b"# saved by hand\nname = \"x\"\n"), DataFormat::Toml);
+    assert_eq!(DataFormat::detect(b"[customers.abc]\nname = \"x\"\n"), DataFormat::Toml);
+    assert_eq!(DataFormat::detect(b"---\nname: x\n"), DataFormat::Yaml);
+    assert_eq!(DataFormat::detect(b"name: \"a = b\"\n"), DataFormat::Yaml);
+}
+
+#[test]
+fn bad_data_and_unknown_format_names_are_errors() {
+    assert!(matches!(
+        DataFormat::Yaml.decode(b"name: [unclosed", "bank.yaml"),
+        Err(BankError::StorageCorrupted { ref location, .. }) if location == "bank.yaml"
+    ));
+    assert_eq!("YML".parse::<DataFormat>().unwrap(), DataFormat::Yaml);
+    assert_eq!("msgpack".parse::<DataFormat>().unwrap(), DataFormat::MessagePack);
+    assert!(matches!("xml".parse::<DataFormat>(), Err(BankError::InvalidInput(_))));
+}
+
+#[cfg(feature = "cli")]
+#[test]
+fn command_mode_exports_and_runs_on_a_yaml_file() {
+    let data_file = temp_file("cli.json");
+    let yaml_file = temp_file("cli.yaml");
+    remove(&data_file);
+    remove(&yaml_file);
+    let run = |data_file: &str, args: &[&str]| common::run(data_file, args, "\n");
+    run(&data_file, &["register", "--name", "Ann", "--email", "ann@example.com", "--pin", "1234"]);
+    run(&data_file, &["create-account", "--customer", "ann@example.com", "--deposit", "100"]);
+
+    let json: serde_json::Value =
+        serde_json::from_str(&run(&data_file, &["--json", "export-bank", "--output", &yaml_file])).unwrap();
+    assert_eq!(json["result"]["format"], "yaml");
+    assert!(std::fs::read_to_string(&yaml_file).unwrap().contains("email: ann@example.com"));
+
+    // The export is a data file in its own right, saved back as YAML
+    run(&yaml_file, &["deposit", "--customer", "ann@example.com", "--amount", "25", "--pin", "1234"]);
+    assert_eq!(DataFormat::detect(&std::fs::read(&yaml_file).unwrap()), DataFormat::Yaml);
+    let json: serde_json::Value =
+        serde_json::from_str(&run(&yaml_file, &["--json", "account", "--customer", "ann@example.com"])).unwrap();
+    assert_eq!(json["result"]["account"]["balance"], "125.00");
+    remove(&data_file);
+    remove(&yaml_file);
+}
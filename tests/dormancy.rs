@@ -0,0 +1,178 @@
+//! Integration tests for the dormant-account policy
+
+// The tests that run the binary need the `cli` feature; without it the
+// imports and helpers only they use go unused
+#![cfg_attr(not(feature = "cli"), allow(unused))]
+
+mod common;
+
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use rust_banking_system::clock::MockClock;
+use rust_banking_system::errors::BankResult;
+use rust_banking_system::models::{AccountStatus, Alert, AlertKind, CustomerId, DormancyPolicy};
+use rust_banking_system::persistence::journal_path;
+use rust_banking_system::{AccountType, Bank, Money, Notifier};
+
+use common::temp_path;
+
+/// Keeps every alert it is sent, for the tests to inspect
+#[derive(Debug, Default)]
+struct Recorder {
+    alerts: Mutex<Vec<Alert>>,
+}
+
+impl Notifier for Recorder {
+    fn notify(&self, alert: &Alert) -> BankResult<()> {
+        self.alerts.lock().unwrap().push(alert.clone());
+        Ok(())
+    }
+}
+
+fn start() -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2024, 3, 1, 9, 0, 0).unwrap()
+}
+
+/// Alice and Bob open accounts on March 1st and Alice deposits again 100
+/// days later; the clock is then moved to 400 days after the start.
+/// Returns (bank, clock, recorder, alice, bob)
+fn quiet_bank() -> (Bank, Arc<MockClock>, Arc<Recorder>, CustomerId, CustomerId) {
+    let clock = Arc::new(MockClock::new(start()));
+    let mut bank = Bank::new("Test Bank".to_string());
+    bank.set_clock(clock.clone());
+    let alice = bank.register_customer("Alice".to_string(), "alice@example.com".to_string()).unwrap();
+    let bob = bank.register_customer("Bob".to_string(), "bob@example.com".to_string()).unwrap();
+    bank.create_account_for_customer(&alice, Money::from_major(500), AccountType::Checking).unwrap();
+    bank.create_account_for_customer(&bob, Money::from_major(100), AccountType::Checking).unwrap();
+    clock.advance(Duration::days(100));
+    bank.deposit(&alice, Money::from_major(10)).unwrap();
+    clock.advance(Duration::days(300));
+
+    let recorder = Arc::new(Recorder::default());
+    bank.add_notifier(recorder.clone());
+    (bank, clock, recorder, alice, bob)
+}
+
+fn dormant_since(bank: &Bank, customer_id: &CustomerId) -> Option<DateTime<Utc>> {
+    bank.get_customer(customer_id).unwrap().get_account().unwrap().dormant_since
+}
+
+#[test]
+fn quiet_accounts_are_flagged_and_the_notifiers_told() {
+    let (mut bank, _, recorder, alice, bob) = quiet_bank();
+    assert_eq!(bank.dormancy_policy(), DormancyPolicy::default());
+    let now = bank.now();
+
+    let run = bank.run_policies(now).unwrap();
+    assert_eq!(run.dormant.len(), 1);
+    assert_eq!(run.dormant[0].customer_id, bob);
+    assert!(run.frozen.is_empty());
+    assert_eq!(dormant_since(&bank, &bob), Some(now));
+    assert_eq!(dormant_since(&bank, &alice), None);
+    assert_eq!(bank.get_customer(&bob).unwrap().get_account().unwrap().status, AccountStatus::Active);
+
+    let alerts = recorder.alerts.lock().unwrap();
+    assert_eq!(alerts.len(), 1);
+    assert_eq!(alerts[0].customer_id, bob);
+    assert_eq!(alerts[0].kind, AlertKind::Dormant { inactive_days: 400, frozen: false });
+    assert!(alerts[0].to_string().contains("no activity for 400 days"));
+}
+
+#[test]
+fn an_account_is_flagged_only_once() {
+    let (mut bank, clock, recorder, _, _) = quiet_bank();
+    bank.run_policies(bank.now()).unwrap();
+    clock.advance(Duration::days(30));
+
+    let run = bank.run_policies(bank.now()).unwrap();
+    assert!(run.dormant.is_empty());
+    assert_eq!(recorder.alerts.lock().unwrap().len(), 1);
+}
+
+#[test]
+fn the_policy_can_freeze_dormant_accounts() {
+    let (mut bank, _, recorder, alice, bob) = quiet_bank();
+    bank.set_dormancy_policy(DormancyPolicy { inactive_days: 200, freeze: true }).unwrap();
+    // Already frozen by staff: flagged, but not frozen by the policy
+    bank.freeze_account(&alice).unwrap();
+
+    let run = bank.run_policies(bank.now()).unwrap();
+    assert_eq!(run.dormant.len(), 2);
+    assert_eq!(run.frozen, [bob.as_str()]);
+    assert_eq!(bank.get_customer(&bob).unwrap().get_account().unwrap().status, AccountStatus::Frozen);
+    let frozen: Vec<AlertKind> = recorder.alerts.lock().unwrap().iter().map(|a| a.kind.clone()).collect();
+    assert!(frozen.contains(&AlertKind::Dormant { inactive_days: 400, frozen: true }));
+    assert!(frozen.contains(&AlertKind::Dormant { inactive_days: 300, frozen: false }));
+}
+
+#[test]
+fn activity_clears_the_flag_until_the_account_goes_quiet_again() {
+    let (mut bank, clock, _, _, bob) = quiet_bank();
+    bank.run_policies(bank.now()).unwrap();
+    assert_eq!(bank.report().dormant_accounts, 1);
+
+    bank.deposit(&bob, Money::from_major(5)).unwrap();
+    assert_eq!(dormant_since(&bank, &bob), None);
+    assert_eq!(bank.report().dormant_accounts, 0);
+    assert!(bank.run_policies(bank.now()).unwrap().dormant.is_empty());
+
+    clock.advance(Duration::days(365));
+    let run = bank.run_policies(bank.now()).unwrap();
+    assert!(run.dormant.iter().any(|dormant| dormant.customer_id == bob));
+    assert!(bank.report().to_string().contains("Dormant Accounts: 2"));
+}
+
+#[test]
+fn a_policy_of_zero_days_is_off_and_too_long_is_refused() {
+    let (mut bank, _, recorder, _, _) = quiet_bank();
+    bank.set_dormancy_policy(DormancyPolicy { inactive_days: 0, freeze: true }).unwrap();
+    assert!(bank.run_policies(bank.now()).unwrap().dormant.is_empty());
+    assert!(recorder.alerts.lock().unwrap().is_empty());
+    assert!(bank.dormancy_policy().to_string().contains("not flagged"));
+
+    assert!(bank.set_dormancy_policy(DormancyPolicy { inactive_days: 100_000, freeze: false }).is_err());
+    assert_eq!(bank.dormancy_policy().inactive_days, 0);
+}
+
+#[test]
+fn replaying_the_journal_restores_flags_without_alerting() {
+    let (mut bank, _, _, _, bob) = quiet_bank();
+    let snapshot = serde_json::to_string(&bank).unwrap();
+    bank.enable_journal();
+    bank.set_dormancy_policy(DormancyPolicy { inactive_days: 30, freeze: true }).unwrap();
+    bank.run_policies(bank.now()).unwrap();
+
+    let mut restored: Bank = serde_json::from_str(&snapshot).unwrap();
+    let recorder = Arc::new(Recorder::default());
+    restored.add_notifier(recorder.clone());
+    for entry in bank.take_journal() {
+        restored.apply_journal_entry(entry).unwrap();
+    }
+    assert_eq!(restored.dormancy_policy(), DormancyPolicy { inactive_days: 30, freeze: true });
+    assert_eq!(dormant_since(&restored, &bob), dormant_since(&bank, &bob));
+    assert_eq!(restored.get_customer(&bob).unwrap().get_account().unwrap().status, AccountStatus::Frozen);
+    assert!(recorder.alerts.lock().unwrap().is_empty());
+}
+
+#[cfg(feature = "cli")]
+#[test]
+fn command_mode_sets_the_policy_and_runs_it() {
+    let data_file = temp_path("cli.json");
+    let _ = std::fs::remove_file(&data_file);
+    let _ = std::fs::remove_file(journal_path(&data_file.to_string_lossy()));
+    let run = |args: &[&str]| common::run(&data_file, args, "\n");
+    run(&["register", "--name", "Ann", "--email", "ann@example.com", "--pin", "1234"]);
+    run(&["create-account", "--customer", "ann@example.com", "--deposit", "100"]);
+
+    let json: serde_json::Value =
+        serde_json::from_str(&run(&["--json", "set-dormancy-policy", "--freeze", "true"])).unwrap();
+    assert_eq!(json["result"], serde_json::json!({ "inactive_days": 365, "freeze": true }));
+
+    // Ann's account was opened just now, so nothing is dormant yet
+    let json: serde_json::Value = serde_json::from_str(&run(&["--json", "run-policies"])).unwrap();
+    assert_eq!(json["result"], serde_json::json!({ "dormant": [], "frozen": [] }));
+    let json: serde_json::Value = serde_json::from_str(&run(&["--json", "stats"])).unwrap();
+    assert_eq!(json["result"]["dormant_accounts"], 0);
+    let _ = std::fs::remove_file(&data_file);
+}
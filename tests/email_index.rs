@@ -0,0 +1,107 @@
+//! Integration tests for the email index behind registration and lookups
+
+use rust_banking_system::models::{CustomerId, CustomerUpdate};
+use rust_banking_system::{Bank, BankError, BankEvent, Customer};
+
+fn register(bank: &mut Bank, name: &str, email: &str) -> CustomerId {
+    bank.register_customer(name.to_string(), email.to_string()).unwrap()
+}
+
+#[test]
+fn registration_rejects_an_address_in_use_whatever_its_case() {
+    let mut bank = Bank::new("Test Bank".to_string());
+    let alice = register(&mut bank, "Alice", "alice@example.com");
+    // Built by the first lookup; later registrations go straight in
+    assert_eq!(bank.find_customer_by_email("alice@example.com").unwrap().id, alice);
+    let bob = register(&mut bank, "Bob", "bob@example.com");
+
+    let result = bank.register_customer("Other Bob".to_string(), " BOB@Example.com ".to_string());
+    assert!(matches!(result, Err(BankError::CustomerAlreadyExists(email)) if email == "bob@example.com"));
+    assert_eq!(bank.find_customer_by_email("Bob@Example.com").unwrap().id, bob);
+    assert_eq!(bank.list_customers().len(), 2);
+}
+
+#[test]
+fn changing_an_email_moves_it_in_the_index() {
+    let mut bank = Bank::new("Test Bank".to_string());
+    let alice = register(&mut bank, "Alice", "alice@example.com");
+    let bob = register(&mut bank, "Bob", "bob@example.com");
+    assert!(bank.find_customer_by_email("alice@example.com").is_some());
+
+    let update = CustomerUpdate { email: Some("alice@work.example".to_string()), ..CustomerUpdate::default() };
+    bank.update_customer(&alice, update).unwrap();
+
+    assert!(bank.find_customer_by_email("alice@example.com").is_none());
+    assert_eq!(bank.find_customer_by_email("alice@work.example").unwrap().id, alice);
+    let taken = CustomerUpdate { email: Some("Alice@Work.example".to_string()), ..CustomerUpdate::default() };
+    assert!(matches!(bank.update_customer(&bob, taken), Err(BankError::CustomerAlreadyExists(_))));
+    // The old address is free again
+    let carol = register(&mut bank, "Carol", "alice@example.com");
+    assert_eq!(bank.find_customer_by_email("alice@example.com").unwrap().id, carol);
+}
+
+#[test]
+fn deleting_a_customer_frees_their_address() {
+    let mut bank = Bank::new("Test Bank".to_string());
+    let alice = register(&mut bank, "Alice", "alice@example.com");
+    assert!(bank.find_customer_by_email("alice@example.com").is_some());
+
+    bank.delete_customer(&alice).unwrap();
+    assert!(bank.find_customer_by_email("alice@example.com").is_none());
+    assert!(bank.find_customer("alice@example.com").is_none());
+
+    let again = register(&mut bank, "Alice Again", "alice@example.com");
+    assert_ne!(again, alice);
+    assert_eq!(bank.find_customer_by_email("alice@example.com").unwrap().id, again);
+}
+
+#[test]
+fn deactivated_customers_keep_their_address() {
+    let mut bank = Bank::new("Test Bank".to_string());
+    let alice = register(&mut bank, "Alice", "alice@example.com");
+    bank.deactivate_customer(&alice).unwrap();
+
+    assert!(matches!(
+        bank.register_customer("Other".to_string(), "alice@example.com".to_string()),
+        Err(BankError::CustomerAlreadyExists(_))
+    ));
+    assert_eq!(bank.find_customer_by_email("alice@example.com").unwrap().id, alice);
+}
+
+#[test]
+fn a_reloaded_or_replayed_bank_builds_the_index_again() {
+    let mut bank = Bank::new("Test Bank".to_string());
+    bank.enable_journal();
+    let alice = register(&mut bank, "Alice", "alice@example.com");
+    let bob = register(&mut bank, "Bob", "bob@example.com");
+    bank.delete_customer(&bob).unwrap();
+
+    let reloaded: Bank = serde_json::from_value(serde_json::to_value(&bank).unwrap()).unwrap();
+    assert_eq!(reloaded.find_customer_by_email("alice@example.com").unwrap().id, alice);
+    assert!(reloaded.find_customer_by_email("bob@example.com").is_none());
+
+    let replayed = Bank::from_events("Test Bank".to_string(), bank.take_journal()).unwrap();
+    assert_eq!(replayed.find_customer_by_email("alice@example.com").unwrap().id, alice);
+    assert!(replayed.find_customer_by_email("bob@example.com").is_none());
+}
+
+#[test]
+fn an_address_shared_by_older_customers_goes_to_the_next_one_on_delete() {
+    // Customers registered before emails were normalized can differ only
+    // in case; events apply them as they were
+    let mut bank = Bank::new("Test Bank".to_string());
+    let first = Customer::new("First".to_string(), "Pat@Example.com".to_string());
+    let mut second = Customer::new("Second".to_string(), "pat@example.com".to_string());
+    second.registered_at = first.registered_at + chrono::Duration::seconds(1);
+    let (first_id, second_id) = (first.id.clone(), second.id.clone());
+    bank.apply_event(BankEvent::CustomerRegistered { customer: first }).unwrap();
+    bank.apply_event(BankEvent::CustomerRegistered { customer: second }).unwrap();
+
+    assert_eq!(bank.find_customer_by_email("pat@example.com").unwrap().id, first_id);
+    bank.delete_customer(&first_id).unwrap();
+    assert_eq!(bank.find_customer_by_email("pat@example.com").unwrap().id, second_id);
+    assert!(matches!(
+        bank.register_customer("Third".to_string(), "PAT@example.com".to_string()),
+        Err(BankError::CustomerAlreadyExists(_))
+    ));
+}
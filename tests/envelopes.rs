@@ -0,0 +1,170 @@
+//! Integration tests for savings goals (envelopes)
+
+use rust_banking_system::models::{CustomerId, Envelope, TransactionDetails};
+use rust_banking_system::{AccountType, Bank, BankError, Money};
+
+/// Registers Alice with $1,000; returns (bank, alice)
+fn bank_with_customer() -> (Bank, CustomerId) {
+    let mut bank = Bank::new("Test Bank".to_string());
+    let alice = bank.register_customer("Alice".to_string(), "alice@example.com".to_string()).unwrap();
+    bank.create_account_for_customer(&alice, Money::from_major(1_000), AccountType::Checking)
+        .unwrap();
+    (bank, alice)
+}
+
+fn envelopes(bank: &Bank, customer_id: &CustomerId) -> Vec<Envelope> {
+    bank.list_envelopes(customer_id).unwrap().to_vec()
+}
+
+#[test]
+fn goals_start_empty_and_track_progress() {
+    let (mut bank, alice) = bank_with_customer();
+    let goal = bank.create_envelope(&alice, " Vacation ", Money::from_major(800), false).unwrap();
+    assert_eq!(goal.name, "Vacation");
+    assert_eq!(goal.allocated, Money::ZERO);
+    assert_eq!(goal.progress_percent(), 0);
+
+    let goal = bank.allocate_to_envelope(&alice, "vacation", Money::from_major(200)).unwrap();
+    assert_eq!(goal.progress_percent(), 25);
+    assert!(!goal.is_reached());
+    assert_eq!(goal.to_string(), "Vacation: $200.00 of $800.00 (25%)");
+
+    let goal = bank.allocate_to_envelope(&alice, "Vacation", Money::from_major(700)).unwrap();
+    assert_eq!(goal.progress_percent(), 100);
+    assert!(goal.is_reached());
+    assert_eq!(envelopes(&bank, &alice), [goal]);
+}
+
+#[test]
+fn only_free_money_can_be_set_aside() {
+    let (mut bank, alice) = bank_with_customer();
+    bank.create_envelope(&alice, "Vacation", Money::from_major(800), false).unwrap();
+    bank.create_envelope(&alice, "Car", Money::from_major(5_000), false).unwrap();
+    bank.allocate_to_envelope(&alice, "Vacation", Money::from_major(700)).unwrap();
+
+    let account = bank.get_customer(&alice).unwrap().get_account().unwrap();
+    assert_eq!(account.unallocated_balance_at(bank.now()), Money::from_major(300));
+    assert!(matches!(
+        bank.allocate_to_envelope(&alice, "Car", Money::from_major(301)),
+        Err(BankError::InsufficientFunds { available, .. }) if available == Money::from_major(300)
+    ));
+
+    bank.release_from_envelope(&alice, "Vacation", Money::from_major(100)).unwrap();
+    bank.allocate_to_envelope(&alice, "Car", Money::from_major(400)).unwrap();
+    assert!(matches!(
+        bank.release_from_envelope(&alice, "Vacation", Money::from_major(601)),
+        Err(BankError::InsufficientFunds { .. })
+    ));
+}
+
+#[test]
+fn deposits_can_go_straight_into_a_goal() {
+    let (mut bank, alice) = bank_with_customer();
+    bank.create_envelope(&alice, "Emergency Fund", Money::from_major(3_000), true).unwrap();
+
+    let deposit = bank
+        .deposit_to_envelope(&alice, "emergency fund", Money::from_major(250), TransactionDetails::new())
+        .unwrap();
+
+    assert_eq!(deposit.balance_after, Money::from_major(1_250));
+    let account = bank.get_customer(&alice).unwrap().get_account().unwrap();
+    assert_eq!(account.envelope("Emergency Fund").unwrap().allocated, Money::from_major(250));
+    assert_eq!(account.locked_amount(), Money::from_major(250));
+}
+
+#[test]
+fn withdrawals_cannot_raid_a_locked_goal() {
+    let (mut bank, alice) = bank_with_customer();
+    bank.create_envelope(&alice, "Emergency Fund", Money::from_major(3_000), true).unwrap();
+    bank.allocate_to_envelope(&alice, "Emergency Fund", Money::from_major(600)).unwrap();
+
+    assert!(matches!(
+        bank.withdraw(&alice, Money::from_major(401)),
+        Err(BankError::EnvelopeLocked(name)) if name == "Emergency Fund"
+    ));
+    bank.withdraw(&alice, Money::from_major(400)).unwrap();
+
+    bank.set_envelope_locked(&alice, "Emergency Fund", false).unwrap();
+    bank.withdraw(&alice, Money::from_major(100)).unwrap();
+    assert_eq!(envelopes(&bank, &alice)[0].allocated, Money::from_major(500));
+}
+
+#[test]
+fn spending_comes_out_of_unlocked_goals_newest_first() {
+    let (mut bank, alice) = bank_with_customer();
+    bank.create_envelope(&alice, "Vacation", Money::from_major(800), false).unwrap();
+    bank.create_envelope(&alice, "Gifts", Money::from_major(300), false).unwrap();
+    bank.allocate_to_envelope(&alice, "Vacation", Money::from_major(500)).unwrap();
+    bank.allocate_to_envelope(&alice, "Gifts", Money::from_major(300)).unwrap();
+
+    bank.withdraw(&alice, Money::from_major(450)).unwrap();
+
+    let goals = envelopes(&bank, &alice);
+    assert_eq!(goals[0].allocated, Money::from_major(500));
+    assert_eq!(goals[1].allocated, Money::from_major(50));
+    let account = bank.get_customer(&alice).unwrap().get_account().unwrap();
+    assert_eq!(account.allocated_amount(), account.balance);
+}
+
+#[test]
+fn locked_goals_cannot_be_emptied_or_removed() {
+    let (mut bank, alice) = bank_with_customer();
+    bank.create_envelope(&alice, "House", Money::from_major(20_000), true).unwrap();
+    bank.allocate_to_envelope(&alice, "House", Money::from_major(100)).unwrap();
+
+    assert!(matches!(
+        bank.release_from_envelope(&alice, "House", Money::from_major(10)),
+        Err(BankError::EnvelopeLocked(_))
+    ));
+    assert!(matches!(bank.remove_envelope(&alice, "House"), Err(BankError::EnvelopeLocked(_))));
+
+    bank.set_envelope_locked(&alice, "House", false).unwrap();
+    assert_eq!(bank.remove_envelope(&alice, "house").unwrap().allocated, Money::from_major(100));
+    assert!(envelopes(&bank, &alice).is_empty());
+    assert!(matches!(bank.remove_envelope(&alice, "House"), Err(BankError::EnvelopeNotFound(_))));
+}
+
+#[test]
+fn goal_requests_are_validated() {
+    let (mut bank, alice) = bank_with_customer();
+    bank.create_envelope(&alice, "Vacation", Money::from_major(800), false).unwrap();
+
+    assert!(matches!(
+        bank.create_envelope(&alice, "VACATION", Money::from_major(1), false),
+        Err(BankError::ValidationError { .. })
+    ));
+    assert!(matches!(bank.create_envelope(&alice, "  ", Money::from_major(1), false), Err(BankError::ValidationError { .. })));
+    assert!(matches!(bank.create_envelope(&alice, "Car", Money::ZERO, false), Err(BankError::InvalidAmount(_))));
+    assert!(matches!(
+        bank.allocate_to_envelope(&alice, "Vacation", Money::ZERO),
+        Err(BankError::InvalidAmount(_))
+    ));
+    assert!(matches!(
+        bank.allocate_to_envelope(&alice, "Car", Money::from_major(1)),
+        Err(BankError::EnvelopeNotFound(_))
+    ));
+}
+
+#[test]
+fn goals_survive_saving_and_journal_replay() {
+    let (mut bank, alice) = bank_with_customer();
+    let snapshot = serde_json::to_string(&bank).unwrap();
+    bank.enable_journal();
+
+    bank.create_envelope(&alice, "Vacation", Money::from_major(800), false).unwrap();
+    bank.create_envelope(&alice, "Emergency Fund", Money::from_major(3_000), true).unwrap();
+    bank.deposit_to_envelope(&alice, "Emergency Fund", Money::from_major(300), TransactionDetails::new())
+        .unwrap();
+    bank.allocate_to_envelope(&alice, "Vacation", Money::from_major(600)).unwrap();
+    bank.withdraw(&alice, Money::from_major(500)).unwrap();
+
+    let mut restored: Bank = serde_json::from_str(&snapshot).unwrap();
+    for entry in bank.take_journal() {
+        restored.apply_journal_entry(entry).unwrap();
+    }
+    assert_eq!(envelopes(&restored, &alice), envelopes(&bank, &alice));
+    assert_eq!(envelopes(&bank, &alice)[0].allocated, Money::from_major(500));
+
+    let reloaded: Bank = serde_json::from_str(&serde_json::to_string(&bank).unwrap()).unwrap();
+    assert_eq!(envelopes(&reloaded, &alice), envelopes(&bank, &alice));
+}
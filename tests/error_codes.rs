@@ -0,0 +1,148 @@
+//! Integration tests for error codes and error source chaining
+
+// The tests that run the binary need the `cli` feature; without it the
+// imports and helpers only they use go unused
+#![cfg_attr(not(feature = "cli"), allow(unused))]
+
+mod common;
+
+use std::collections::HashSet;
+use std::error::Error;
+use std::io;
+
+use chrono::Utc;
+use rust_banking_system::{BankError, Cause, JsonFileStorage, Money, Storage};
+
+use common::temp_path;
+
+/// One error of every kind
+fn every_error() -> Vec<BankError> {
+    let text = || "x".to_string();
+    vec![
+        BankError::CustomerNotFound(text().into()),
+        BankError::AccountNotFound(text()),
+        BankError::InsufficientFunds { available: Money::ZERO, requested: Money::from_major(1) },
+        BankError::InsufficientVaultCash { available: Money::ZERO, requested: Money::from_major(1) },
+        BankError::WithdrawalLimitReached { limit: 3 },
+        BankError::AccountFrozen(text().into()),
+        BankError::AccountClosed(text().into()),
+        BankError::AccountLocked { until: Utc::now() },
+        BankError::InvalidInput(text()),
+        BankError::ValidationError { field: text(), reason: text() },
+        BankError::InvalidAmount(Money::ZERO),
+        BankError::InvalidTransfer(text()),
+        BankError::ScheduleNotFound(text()),
+        BankError::BeneficiaryNotFound(text()),
+        BankError::WebhookNotFound(text()),
+        BankError::CardNotFound(text()),
+        BankError::CardDeclined(text()),
+        BankError::MerchantNotFound(text()),
+        BankError::EnvelopeNotFound(text()),
+        BankError::EnvelopeLocked(text()),
+        BankError::BudgetNotFound(text()),
+        BankError::TermDepositNotFound(text()),
+        BankError::TransactionNotFound(text().into()),
+        BankError::CustomerAlreadyExists(text()),
+        BankError::CustomerDeactivated(text().into()),
+        BankError::InvalidPin { attempts_left: 2 },
+        BankError::PinLocked { until: Utc::now() },
+        BankError::PinNotSet(text().into()),
+        BankError::AuthenticationFailed { user: text() },
+        BankError::IoError(text().into()),
+        BankError::SerializationError(text().into()),
+        BankError::StorageCorrupted { location: text(), cause: text().into() },
+        BankError::EncryptionError(text()),
+        BankError::ReadOnly(text()),
+    ]
+}
+
+/// The first error of type `T` in `error`'s source chain
+fn underlying<T: Error + 'static>(error: &BankError) -> Option<&T> {
+    std::iter::successors(error.source(), |e| (*e).source()).find_map(|e| e.downcast_ref::<T>())
+}
+
+#[test]
+fn every_error_has_its_own_code_and_kind() {
+    let errors = every_error();
+    let codes: HashSet<u16> = errors.iter().map(BankError::code).collect();
+    let kinds: HashSet<&str> = errors.iter().map(BankError::kind).collect();
+    assert_eq!(codes.len(), errors.len());
+    assert_eq!(kinds.len(), errors.len());
+    assert!(codes.iter().all(|code| (1001..6000).contains(code)));
+}
+
+#[test]
+fn codes_do_not_change() {
+    let insufficient = BankError::InsufficientFunds { available: Money::ZERO, requested: Money::from_major(5) };
+    assert_eq!((insufficient.code(), insufficient.kind()), (2001, "insufficient_funds"));
+    let missing = BankError::CustomerNotFound("bob".into());
+    assert_eq!((missing.code(), missing.kind()), (1001, "customer_not_found"));
+    assert_eq!(BankError::InvalidPin { attempts_left: 1 }.code(), 4001);
+    assert_eq!(BankError::IoError("disk".into()).code(), 5001);
+}
+
+#[test]
+fn the_thousands_group_related_errors() {
+    for error in every_error() {
+        let not_found = error.kind().ends_with("not_found");
+        assert_eq!(error.code() / 1000 == 1, not_found, "{} {}", error.code(), error.kind());
+        assert!((1..=5).contains(&(error.code() / 1000)));
+    }
+}
+
+#[test]
+fn io_errors_keep_the_underlying_error() {
+    let error = BankError::from(io::Error::new(io::ErrorKind::NotFound, "bank.json is gone"));
+    assert!(matches!(error, BankError::IoError(_)));
+    assert_eq!(error.to_string(), "IO Error: bank.json is gone");
+
+    assert_eq!(underlying::<io::Error>(&error).unwrap().kind(), io::ErrorKind::NotFound);
+    // Cloning shares the source rather than dropping it
+    assert!(underlying::<io::Error>(&error.clone()).is_some());
+}
+
+#[test]
+fn serde_errors_keep_the_underlying_error() {
+    let error = BankError::from(serde_json::from_str::<u32>("twelve").unwrap_err());
+    assert_eq!(error.code(), 5002);
+    assert!(underlying::<serde_json::Error>(&error).unwrap().is_syntax());
+}
+
+#[test]
+fn context_goes_before_the_underlying_error() {
+    let cause = Cause::new(io::Error::other("disk full")).context("saving bank.json");
+    assert_eq!(cause.to_string(), "saving bank.json: disk full");
+    assert_eq!(BankError::IoError(cause).to_string(), "IO Error: saving bank.json: disk full");
+
+    let message = BankError::IoError("nothing saved yet".into());
+    assert_eq!(message.to_string(), "IO Error: nothing saved yet");
+    assert!(underlying::<io::Error>(&message).is_none());
+    assert!(BankError::CustomerNotFound("bob".into()).source().is_none());
+}
+
+#[test]
+fn a_corrupt_data_file_reports_the_parse_error_behind_it() {
+    let path = temp_path("corrupt.json");
+    std::fs::write(&path, "{ not json").unwrap();
+    let error = JsonFileStorage::new(path.to_string_lossy()).load_bank().unwrap_err();
+
+    assert_eq!(error.kind(), "storage_corrupted");
+    assert!(error.to_string().contains("is corrupted"), "{}", error);
+    assert!(underlying::<serde_json::Error>(&error).is_some());
+    let _ = std::fs::remove_file(&path);
+}
+
+#[cfg(feature = "cli")]
+#[test]
+fn command_mode_prints_the_code_with_the_error() {
+    let data_file = temp_path("cli.json");
+    let _ = std::fs::remove_file(&data_file);
+    let output = common::output(&data_file, &["account", "--customer", "nobody@example.com"], "");
+
+    assert_eq!(output.status.code(), Some(1));
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["ok"], false);
+    assert_eq!(json["code"], 1001);
+    assert_eq!(json["kind"], "customer_not_found");
+    let _ = std::fs::remove_file(&data_file);
+}
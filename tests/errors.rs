@@ -0,0 +1,98 @@
+//! Integration tests for the error type's messages and conversions
+
+mod common;
+
+use std::error::Error;
+use std::io;
+
+use chrono::{TimeZone, Utc};
+use rust_banking_system::models::{Limit, Role};
+use rust_banking_system::persistence::encrypt;
+use rust_banking_system::{Bank, BankError, BankResult, EncryptedFileStorage, JsonFileStorage, Money, Storage};
+
+use common::temp_file;
+
+#[test]
+fn messages_read_as_before() {
+    let (available, requested) = (Money::from_major(10), Money::from_major(25));
+    let insufficient = BankError::InsufficientFunds { available, requested };
+    assert_eq!(insufficient.to_string(), "Insufficient funds: available $10.00, requested $25.00");
+    assert_eq!(BankError::CustomerNotFound("bob".into()).to_string(), "Customer 'bob' not found");
+    assert_eq!(BankError::InvalidPin { attempts_left: 2 }.to_string(), "Incorrect PIN (2 attempt(s) left)");
+    assert_eq!(BankError::InvalidAmount(Money::ZERO).to_string(), "Invalid amount: $0.00");
+    assert_eq!(
+        BankError::ValidationError { field: "email".to_string(), reason: "missing @".to_string() }.to_string(),
+        "Invalid email: missing @"
+    );
+    let until = Utc.with_ymd_and_hms(2024, 3, 1, 14, 30, 0).unwrap();
+    assert_eq!(BankError::PinLocked { until }.to_string(), "Too many incorrect PINs; locked until 14:30 UTC");
+}
+
+#[test]
+fn structured_variants_keep_their_fields() {
+    let frozen = BankError::AccountFrozen("ACC-000001-8".into());
+    assert_eq!(frozen.to_string(), "Account 'ACC-000001-8' is frozen");
+    let limit = BankError::LimitExceeded(Limit::DailyWithdrawal(Money::from_major(500)));
+    assert_eq!(limit.to_string(), "Limit exceeded: daily withdrawal limit of $500.00");
+    assert!(matches!(limit, BankError::LimitExceeded(Limit::DailyWithdrawal(max)) if max == Money::from_major(500)));
+}
+
+#[test]
+fn unknown_staff_fail_authentication() {
+    let mut bank = Bank::new("Test Bank".to_string());
+    bank.add_staff("root", Role::Admin, "1234").unwrap();
+
+    let error = bank.authenticate_staff("  Nobody ", "1234").unwrap_err();
+    assert!(matches!(&error, BankError::AuthenticationFailed { user } if user == "Nobody"));
+    assert_eq!(error.to_string(), "Login failed for 'Nobody'");
+    assert_eq!(error.code(), 4005);
+    // A known user with the wrong PIN still learns how many tries are left
+    assert!(matches!(bank.authenticate_staff("root", "0000"), Err(BankError::InvalidPin { .. })));
+}
+
+#[test]
+fn unreadable_snapshots_are_reported_as_corrupted() {
+    let path = temp_file("corrupt.json");
+    std::fs::write(&path, "[1, 2").unwrap();
+    let error = JsonFileStorage::new(path.as_str()).load_bank().unwrap_err();
+
+    assert!(matches!(&error, BankError::StorageCorrupted { location, .. } if *location == path));
+    assert_eq!(error.kind(), "storage_corrupted");
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn decrypted_garbage_is_corrupted_too() {
+    let path = temp_file("corrupt.enc");
+    std::fs::write(&path, encrypt(b"not a bank", "secret").unwrap()).unwrap();
+    let error = EncryptedFileStorage::new(path.as_str(), "secret").load_bank().unwrap_err();
+
+    assert!(matches!(error, BankError::StorageCorrupted { .. }), "{}", error);
+    // A wrong passphrase is a different problem
+    let wrong = EncryptedFileStorage::new(path.as_str(), "guess").load_bank().unwrap_err();
+    assert!(matches!(wrong, BankError::EncryptionError(_)));
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn io_and_serde_errors_convert_with_the_question_mark() {
+    fn read(path: &str) -> BankResult<serde_json::Value> {
+        let text = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&text)?)
+    }
+
+    assert!(matches!(read("/nonexistent/file.json"), Err(BankError::IoError(_))));
+    let path = temp_file("bad.json");
+    std::fs::write(&path, "{").unwrap();
+    assert!(matches!(read(&path), Err(BankError::SerializationError(_))));
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn missing_files_say_why() {
+    let error = JsonFileStorage::new("/nonexistent/dir/bank.json").save_bank(&Bank::new("Test".to_string()));
+    let error = error.unwrap_err();
+    assert_eq!(error.kind(), "io_error");
+    let io = std::iter::successors(error.source(), |e| (*e).source()).find_map(|e| e.downcast_ref::<io::Error>());
+    assert_eq!(io.unwrap().kind(), io::ErrorKind::NotFound);
+}
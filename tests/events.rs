@@ -0,0 +1,239 @@
+//! Integration tests for domain events, subscriptions and webhooks
+
+mod common;
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use rust_banking_system::bank::{BatchMode, Operation};
+use rust_banking_system::events::{deliver, DELIVERY_ATTEMPTS, RETRY_DELAY};
+use rust_banking_system::{AccountType, Bank, BankError, BankObserver, DomainEvent, EventKind, Money};
+
+use common::bank_with_customers;
+
+/// Subscribes a recorder to `bank` and returns the list it fills
+fn record(bank: &mut Bank) -> Arc<Mutex<Vec<DomainEvent>>> {
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let sink = events.clone();
+    bank.subscribe(move |event| sink.lock().unwrap().push(event.clone()));
+    events
+}
+
+fn kinds(events: &Mutex<Vec<DomainEvent>>) -> Vec<EventKind> {
+    events.lock().unwrap().iter().map(DomainEvent::kind).collect()
+}
+
+#[test]
+fn subscribers_hear_registrations_postings_and_transfers() {
+    let mut bank = Bank::new("Test Bank".to_string());
+    let events = record(&mut bank);
+
+    let alice = bank.register_customer("Alice".to_string(), "alice@example.com".to_string()).unwrap();
+    let bob = bank.register_customer("Bob".to_string(), "bob@example.com".to_string()).unwrap();
+    for id in [&alice, &bob] {
+        bank.create_account_for_customer(id, Money::ZERO, AccountType::Checking).unwrap();
+    }
+    bank.deposit(&alice, Money::from_major(100)).unwrap();
+    bank.transfer(&alice, &bob, Money::from_major(40)).unwrap();
+
+    assert_eq!(
+        kinds(&events),
+        vec![
+            EventKind::CustomerRegistered,
+            EventKind::CustomerRegistered,
+            EventKind::TransactionPosted,
+            EventKind::TransactionPosted,
+            EventKind::TransactionPosted,
+            EventKind::TransferCompleted,
+        ]
+    );
+    let last = events.lock().unwrap().last().cloned();
+    match last.unwrap() {
+        DomainEvent::TransferCompleted { from_customer_id, to_customer_id, amount, .. } => {
+            assert_eq!(from_customer_id, alice);
+            assert_eq!(to_customer_id, bob);
+            assert_eq!(amount, Money::from_major(40));
+        }
+        other => panic!("expected a transfer, got {:?}", other),
+    }
+}
+
+#[test]
+fn failed_operations_publish_nothing() {
+    let (mut bank, alice, _) = bank_with_customers(1_000, 100);
+    let events = record(&mut bank);
+
+    assert!(bank.withdraw(&alice, Money::from_major(5_000)).is_err());
+    assert!(kinds(&events).is_empty());
+}
+
+#[test]
+fn unsubscribed_callbacks_are_not_called() {
+    let (mut bank, alice, _) = bank_with_customers(1_000, 100);
+    let events = Arc::new(Mutex::new(0));
+    let count = events.clone();
+    let id = bank.subscribe(move |_| *count.lock().unwrap() += 1);
+
+    bank.deposit(&alice, Money::from_major(1)).unwrap();
+    assert!(bank.unsubscribe(id));
+    assert!(!bank.unsubscribe(id));
+    bank.deposit(&alice, Money::from_major(1)).unwrap();
+
+    assert_eq!(*events.lock().unwrap(), 1);
+}
+
+#[test]
+fn rolled_back_batches_publish_nothing() {
+    let (mut bank, alice, bob) = bank_with_customers(1_000, 100);
+    let events = record(&mut bank);
+    let transfer =
+        |amount| Operation::Transfer { from: alice.to_string(), to: bob.to_string(), amount, description: None };
+
+    bank.apply_batch(
+        vec![transfer(Money::from_major(10)), transfer(Money::from_major(5_000))],
+        BatchMode::AllOrNothing,
+    );
+    assert!(kinds(&events).is_empty());
+
+    bank.apply_batch(vec![transfer(Money::from_major(10))], BatchMode::AllOrNothing);
+    assert_eq!(kinds(&events).len(), 3);
+}
+
+#[test]
+fn webhooks_are_validated_and_journaled() {
+    let (mut bank, _, _) = bank_with_customers(1_000, 100);
+    let snapshot = serde_json::to_string(&bank).unwrap();
+    bank.enable_journal();
+
+    let kept = bank
+        .add_webhook(
+            "http://127.0.0.1:9/hooks",
+            vec![EventKind::TransferCompleted, EventKind::CustomerRegistered, EventKind::TransferCompleted],
+        )
+        .unwrap();
+    assert_eq!(kept.events, vec![EventKind::CustomerRegistered, EventKind::TransferCompleted]);
+    let removed = bank.add_webhook("http://127.0.0.1:9/other", Vec::new()).unwrap();
+
+    assert!(matches!(bank.add_webhook("https://example.com", Vec::new()), Err(BankError::InvalidInput(_))));
+    assert!(matches!(bank.add_webhook("http://127.0.0.1:9/hooks", Vec::new()), Err(BankError::InvalidInput(_))));
+
+    assert_eq!(bank.remove_webhook(&removed.id).unwrap(), removed);
+    assert!(matches!(bank.remove_webhook(&removed.id), Err(BankError::WebhookNotFound(_))));
+
+    let mut restored: Bank = serde_json::from_str(&snapshot).unwrap();
+    for entry in bank.take_journal() {
+        restored.apply_journal_entry(entry).unwrap();
+    }
+    assert_eq!(restored.list_webhooks(), [kept]);
+}
+
+/// Answers each of `statuses` in turn, one connection per status; the
+/// handle yields the request bodies received
+fn scripted_server(statuses: Vec<&'static str>) -> (String, thread::JoinHandle<Vec<String>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/events", listener.local_addr().unwrap());
+    let handle = thread::spawn(move || {
+        statuses
+            .into_iter()
+            .map(|status| {
+                let (stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if line == "\r\n" {
+                        break;
+                    }
+                    if let Some(value) = line.to_lowercase().strip_prefix("content-length:") {
+                        length = value.trim().parse().unwrap();
+                    }
+                }
+                let mut body = vec![0; length];
+                reader.read_exact(&mut body).unwrap();
+                write!(&stream, "{}\r\n\r\n", status).unwrap();
+                String::from_utf8(body).unwrap()
+            })
+            .collect()
+    });
+    (url, handle)
+}
+
+#[test]
+fn webhooks_receive_only_the_events_they_asked_for() {
+    let (url, server) = scripted_server(vec!["HTTP/1.1 200 OK"]);
+    let (mut bank, alice, bob) = bank_with_customers(1_000, 100);
+    bank.add_webhook(&url, vec![EventKind::TransferCompleted]).unwrap();
+
+    bank.deposit(&alice, Money::from_major(5)).unwrap();
+    bank.transfer(&alice, &bob, Money::from_major(25)).unwrap();
+
+    let bodies = server.join().unwrap();
+    let received: serde_json::Value = serde_json::from_str(&bodies[0]).unwrap();
+    assert_eq!(received["event"], "transfer_completed");
+    assert_eq!(received["amount"], "25.00");
+}
+
+#[test]
+fn deliveries_are_retried() {
+    let (url, server) = scripted_server(vec!["HTTP/1.1 503 Service Unavailable", "HTTP/1.1 204 No Content"]);
+    let (mut bank, alice, _) = bank_with_customers(1_000, 100);
+    let events = record(&mut bank);
+    bank.deposit(&alice, Money::from_major(1)).unwrap();
+    let event = events.lock().unwrap()[0].clone();
+
+    assert_eq!(deliver(&url, &event).unwrap(), 2);
+    let bodies = server.join().unwrap();
+    assert_eq!(bodies[0], bodies[1]);
+}
+
+#[test]
+fn deliveries_give_up_after_the_last_attempt() {
+    let failures = vec!["HTTP/1.1 500 Internal Server Error"; DELIVERY_ATTEMPTS as usize];
+    let (url, server) = scripted_server(failures);
+    let (mut bank, alice, _) = bank_with_customers(1_000, 100);
+    let events = record(&mut bank);
+    bank.deposit(&alice, Money::from_major(1)).unwrap();
+    let event = events.lock().unwrap()[0].clone();
+
+    assert!(matches!(deliver(&url, &event), Err(BankError::IoError(_))));
+    assert_eq!(server.join().unwrap().len(), DELIVERY_ATTEMPTS as usize);
+}
+
+#[test]
+fn a_failing_webhook_holds_nothing_up_and_is_reported_to_observers() {
+    /// Passes on the URL of each webhook that gave up
+    #[derive(Debug)]
+    struct Failures(Mutex<mpsc::Sender<String>>);
+
+    impl BankObserver for Failures {
+        fn on_webhook_failed(&self, url: &str, _event: &DomainEvent, _error: &BankError) {
+            let _ = self.0.lock().unwrap().send(url.to_string());
+        }
+    }
+
+    let failures = vec!["HTTP/1.1 500 Internal Server Error"; DELIVERY_ATTEMPTS as usize];
+    let (url, server) = scripted_server(failures);
+    let (mut bank, alice, _) = bank_with_customers(1_000, 100);
+    let (sender, received) = mpsc::channel();
+    bank.add_observer(Arc::new(Failures(Mutex::new(sender))));
+    bank.add_webhook(&url, Vec::new()).unwrap();
+
+    // Delivering in line would wait out at least one retry delay
+    let started = Instant::now();
+    bank.deposit(&alice, Money::from_major(1)).unwrap();
+    assert!(started.elapsed() < RETRY_DELAY);
+
+    assert_eq!(received.recv_timeout(Duration::from_secs(20)).unwrap(), url);
+    assert_eq!(server.join().unwrap().len(), DELIVERY_ATTEMPTS as usize);
+}
+
+#[test]
+fn event_kinds_parse_with_dashes_or_underscores() {
+    assert_eq!("transfer-completed".parse(), Ok(EventKind::TransferCompleted));
+    assert_eq!(" Transaction_Posted ".parse(), Ok(EventKind::TransactionPosted));
+    assert!("account_opened".parse::<EventKind>().is_err());
+}
@@ -0,0 +1,138 @@
+//! Integration tests for `BankFacade`, the library API without the CLI
+
+use std::sync::Arc;
+
+use chrono::{Duration, Utc};
+use rust_banking_system::models::{AccountLimits, Frequency};
+use rust_banking_system::{AccountType, Bank, BankError, BankFacade, MemoryStorage, Money, Storage};
+
+/// A facade over a new bank where Alice has $100 and Bob $20; returns the
+/// facade and the storage it saves to
+fn facade() -> (BankFacade, Arc<MemoryStorage>) {
+    let storage = Arc::new(MemoryStorage::new());
+    let bank = BankFacade::new(Bank::new("Test Bank".to_string()), storage.clone());
+    bank.register("Alice", "alice@example.com").unwrap();
+    bank.register("Bob", "bob@example.com").unwrap();
+    bank.open_account("alice@example.com", Money::from_major(100), AccountType::Checking).unwrap();
+    bank.open_account("bob@example.com", Money::from_major(20), AccountType::Savings).unwrap();
+    (bank, storage)
+}
+
+fn balance(bank: &BankFacade, customer: &str) -> Money {
+    bank.customer(customer).unwrap().get_account().unwrap().balance
+}
+
+#[test]
+fn customers_are_referred_to_like_in_the_cli() {
+    let (bank, _) = facade();
+    let alice = bank.resolve("alice@example.com").unwrap();
+    let number = bank.customer(alice.as_str()).unwrap().get_account().unwrap().number.unwrap();
+
+    assert_eq!(bank.deposit(&number.to_string(), Money::from_major(5)).unwrap(), Money::from_major(105));
+    assert_eq!(bank.withdraw(alice.short(), Money::from_major(10)).unwrap(), Money::from_major(95));
+    bank.transfer("ALICE@example.com", "bob@example.com", Money::from_major(45)).unwrap();
+    assert_eq!(balance(&bank, "alice@example.com"), Money::from_major(50));
+    assert_eq!(balance(&bank, "bob@example.com"), Money::from_major(65));
+}
+
+#[test]
+fn every_change_reaches_the_storage() {
+    let (bank, storage) = facade();
+    bank.deposit("alice@example.com", Money::from_major(25)).unwrap();
+    bank.freeze_account("bob@example.com").unwrap();
+    assert!(storage.journal_len() > 0);
+
+    let reopened = BankFacade::open(storage.clone()).unwrap();
+    assert_eq!(balance(&reopened, "alice@example.com"), Money::from_major(125));
+    let error = reopened.withdraw("bob@example.com", Money::from_major(1)).unwrap_err();
+    assert!(matches!(error, BankError::AccountFrozen(_)), "{:?}", error);
+
+    bank.save().unwrap();
+    assert_eq!(storage.journal_len(), 0);
+}
+
+#[test]
+fn schedules_save_the_whole_bank() {
+    let (bank, storage) = facade();
+    let first_run = Utc::now() + Duration::days(1);
+    let id = bank
+        .schedule_transfer("alice@example.com", "bob@example.com", Money::from_major(10), Frequency::Weekly, first_run)
+        .unwrap();
+    assert_eq!(bank.schedules().len(), 1);
+    assert_eq!(storage.load_bank().unwrap().list_schedules()[0].id, id);
+
+    bank.cancel_schedule(&id).unwrap();
+    assert!(storage.load_bank().unwrap().list_schedules().is_empty());
+    assert!(matches!(bank.cancel_schedule(&id), Err(BankError::ScheduleNotFound(_))));
+}
+
+#[test]
+fn failed_operations_change_nothing() {
+    let (bank, storage) = facade();
+    let saved = storage.journal_len();
+
+    let error = bank.deposit("nobody@example.com", Money::from_major(1)).unwrap_err();
+    assert!(matches!(error, BankError::CustomerNotFound(_)), "{:?}", error);
+    let error = bank.withdraw("bob@example.com", Money::from_major(500)).unwrap_err();
+    assert!(matches!(error, BankError::InsufficientFunds { .. }), "{:?}", error);
+    let error = bank.register("Alice Again", "alice@example.com").unwrap_err();
+    assert!(matches!(error, BankError::CustomerAlreadyExists(_)), "{:?}", error);
+
+    assert_eq!(storage.journal_len(), saved);
+    assert_eq!(balance(&bank, "bob@example.com"), Money::from_major(20));
+}
+
+#[test]
+fn history_receipts_and_reversals() {
+    let (bank, _) = facade();
+    bank.deposit("alice@example.com", Money::from_major(30)).unwrap();
+    bank.withdraw("alice@example.com", Money::from_major(5)).unwrap();
+
+    let history = bank.history("alice@example.com", 2).unwrap();
+    assert_eq!(history.len(), 2);
+    assert_eq!(history[0].amount, Money::from_major(5));
+    let deposit = history[1].id.clone();
+    assert_eq!(bank.receipt(&deposit).unwrap().transaction_id, deposit);
+
+    bank.reverse(&deposit, "Posted twice").unwrap();
+    assert_eq!(balance(&bank, "alice@example.com"), Money::from_major(95));
+    // Reversals are not undone; the withdrawal before them is
+    bank.undo().unwrap();
+    assert_eq!(balance(&bank, "alice@example.com"), Money::from_major(100));
+
+    let statement = bank.statement("alice@example.com", Utc::now() - Duration::days(1), Utc::now()).unwrap();
+    assert_eq!(statement.closing_balance, Money::from_major(100));
+}
+
+#[test]
+fn listings_and_account_lifecycle() {
+    let (bank, _) = facade();
+    bank.register("Carol", "carol@example.com").unwrap();
+    let names: Vec<String> = bank.customers().into_iter().map(|customer| customer.name).collect();
+    assert_eq!(names, ["Alice", "Bob", "Carol"]);
+    assert_eq!(bank.search("o").len(), 2);
+
+    bank.set_limits("bob@example.com", AccountLimits::default()).unwrap();
+    assert_eq!(bank.close_account("bob@example.com", Some("alice@example.com")).unwrap(), Money::from_major(20));
+    assert_eq!(balance(&bank, "alice@example.com"), Money::from_major(120));
+    bank.delete_customer("bob@example.com").unwrap();
+    assert!(bank.resolve("bob@example.com").is_err());
+
+    assert_eq!(bank.report().customers, 2);
+    assert!(bank.audit().is_clean());
+}
+
+#[test]
+fn other_operations_run_through_update_and_query() {
+    let (bank, storage) = facade();
+    let alice = bank.resolve("alice@example.com").unwrap();
+    bank.update(|bank| bank.set_pin(&alice, "4821")).unwrap();
+    let error = bank.update(|bank| bank.authenticate(&alice, "0000")).unwrap_err();
+    assert!(matches!(error, BankError::InvalidPin { .. }), "{:?}", error);
+    assert_eq!(bank.query(|bank| bank.total_bank_balance()), Money::from_major(120));
+
+    // The PIN went to the storage with the rest
+    let reopened = BankFacade::open(storage).unwrap();
+    assert!(reopened.update(|bank| bank.authenticate(&alice, "4821")).is_ok());
+    assert_eq!(reopened.shared().read().list_customers().len(), 2);
+}
@@ -0,0 +1,124 @@
+//! Integration tests for currency and date formatting
+
+use chrono::{DateTime, TimeZone, Utc};
+use rust_banking_system::format::{self, Format};
+use rust_banking_system::models::{BudgetStatus, Limit};
+use rust_banking_system::{BankError, Config, Money};
+
+fn at() -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2024, 1, 15, 9, 5, 0).unwrap()
+}
+
+#[test]
+fn defaults_group_thousands_and_keep_iso_dates() {
+    let format = Format::default();
+    assert_eq!(format.money(Money::from_cents(123_456)), "$1,234.56");
+    assert_eq!(format.money(Money::from_cents(100_000_000)), "$1,000,000.00");
+    assert_eq!(format.money(Money::from_cents(99_999)), "$999.99");
+    assert_eq!(format.money(Money::from_cents(-500)), "-$5.00");
+    assert_eq!(format.money(Money::ZERO), "$0.00");
+    assert_eq!(format.date(&at()), "2024-01-15");
+    assert_eq!(format.datetime(&at()), "2024-01-15 09:05");
+}
+
+#[test]
+fn separators_and_symbol_position_are_configurable() {
+    let format = Format {
+        currency_symbol: "CHF".to_string(),
+        symbol_after: true,
+        thousands_separator: Some('\''),
+        ..Format::default()
+    };
+    assert_eq!(format.money(Money::from_cents(-1_234_567)), "-12'345.67 CHF");
+
+    let plain = Format { thousands_separator: None, ..Format::default() };
+    assert_eq!(plain.money(Money::from_cents(123_456)), "$1234.56");
+}
+
+#[test]
+fn fewer_decimal_places_round_half_away_from_zero() {
+    let whole = Format { decimal_places: 0, ..Format::default() };
+    assert_eq!(whole.money(Money::from_cents(123_450)), "$1,235");
+    assert_eq!(whole.money(Money::from_cents(123_449)), "$1,234");
+    assert_eq!(whole.money(Money::from_cents(-150)), "-$2");
+    // Rounding to zero drops the sign
+    assert_eq!(whole.money(Money::from_cents(-49)), "$0");
+
+    let one = Format { decimal_places: 1, ..Format::default() };
+    assert_eq!(one.money(Money::from_cents(1_235)), "$12.4");
+}
+
+#[test]
+fn locales_bring_their_own_conventions() {
+    let german = Format::for_locale("de-DE", "€");
+    assert_eq!(german.money(Money::from_cents(123_456)), "1.234,56 €");
+    assert_eq!(german.date(&at()), "15.01.2024");
+
+    let french = Format::for_locale("fr-FR", "€");
+    assert_eq!(french.money(Money::from_cents(123_456)), "1\u{a0}234,56 €");
+
+    let american = Format::for_locale("en-US", "$");
+    assert_eq!(american.money(Money::from_cents(123_456)), "$1,234.56");
+    assert_eq!(american.datetime(&at()), "01/15/2024 09:05 AM");
+    assert_eq!(Format::for_locale("en_GB", "£").date(&at()), "15/01/2024");
+
+    // Without a preset the defaults apply, with the given symbol
+    let unknown = Format::for_locale("xx", "¤");
+    assert_eq!(unknown, Format { currency_symbol: "¤".to_string(), ..Format::default() });
+}
+
+#[test]
+fn config_settings_override_the_locale() {
+    let config = Config::from_toml(
+        "locale = \"de-DE\"\ncurrency_symbol = \"€\"\nthousands_separator = \"\"\n\
+         decimal_places = 0\ndate_format = \"%d %b %Y\"\n",
+    )
+    .unwrap();
+    let format = config.format();
+    assert_eq!(format.money(Money::from_cents(123_456)), "1235 €");
+    assert_eq!(format.date(&at()), "15 Jan 2024");
+    assert_eq!(format.datetime(&at()), "15 Jan 2024 09:05");
+
+    assert_eq!(Config::default().format(), Format::for_locale("en-US", "$"));
+}
+
+#[test]
+fn widths_still_pad_formatted_amounts() {
+    let format = Format::default();
+    assert_eq!(format!("{:>10}|", format.money(Money::from_major(5))), "     $5.00|");
+    assert_eq!(format!("{:<8}|", Money::from_major(5)), "5.00    |");
+}
+
+#[test]
+fn plain_money_display_is_unchanged_for_files() {
+    // Data files and exports rely on the plain form, whatever the locale
+    let amount = Money::from_cents(-123_456);
+    assert_eq!(amount.to_string(), "-1234.56");
+    assert_eq!(serde_json::to_string(&amount).unwrap(), "\"-1234.56\"");
+    assert_eq!("-1234.56".parse::<Money>().unwrap(), amount);
+}
+
+// The only test here that installs a format, since it is shared by the
+// whole test binary
+#[test]
+fn the_installed_format_is_used_everywhere_amounts_are_shown() {
+    assert_eq!(Money::from_major(1_500).display().to_string(), "$1,500.00");
+
+    format::install(Format::for_locale("de-DE", "€"));
+    let amount = Money::from_cents(150_050);
+    assert_eq!(format!("{:>12}|", amount.display()), "  1.500,50 €|");
+    assert_eq!(Limit::SingleWithdrawal(amount).to_string(), "single withdrawal limit of 1.500,50 €");
+    let error = BankError::InsufficientFunds { available: Money::from_major(10), requested: amount };
+    assert_eq!(error.to_string(), "Insufficient funds: available 10,00 €, requested 1.500,50 €");
+    let status = BudgetStatus {
+        category: "groceries".to_string(),
+        limit: Money::from_major(300),
+        spent: Money::from_major(330),
+        remaining: -Money::from_major(30),
+    };
+    assert_eq!(status.to_string(), "groceries: 330,00 € of 300,00 € (110%), 30,00 € over");
+    assert_eq!(format::date(&at()), "15.01.2024");
+    assert_eq!(format::current().currency_symbol, "€");
+
+    format::install(Format::default());
+}
@@ -0,0 +1,250 @@
+//! Integration tests for the fraud rules and the review queue
+
+// The tests that run the binary need the `cli` feature; without it the
+// imports and helpers only they use go unused
+#![cfg_attr(not(feature = "cli"), allow(unused))]
+
+mod common;
+
+use std::sync::Arc;
+
+use chrono::{Duration, TimeZone, Utc};
+use rust_banking_system::clock::MockClock;
+use rust_banking_system::errors::BankError;
+use rust_banking_system::models::{CustomerId, FraudReason, FraudRules, ReviewStatus, TransactionStatus, VelocityRule};
+use rust_banking_system::persistence::{journal_path, FileArchive};
+use rust_banking_system::{AccountType, Bank, Money};
+
+use common::{balance, temp_path};
+
+/// Alice and Bob with $1,000 each; returns (bank, clock, alice, bob)
+fn bank_with_rules(rules: FraudRules) -> (Bank, Arc<MockClock>, CustomerId, CustomerId) {
+    let clock = Arc::new(MockClock::new(Utc.with_ymd_and_hms(2024, 3, 1, 9, 0, 0).unwrap()));
+    let mut bank = Bank::new("Test Bank".to_string());
+    bank.set_clock(clock.clone());
+    let alice = bank.register_customer("Alice".to_string(), "alice@example.com".to_string()).unwrap();
+    let bob = bank.register_customer("Bob".to_string(), "bob@example.com".to_string()).unwrap();
+    bank.create_account_for_customer(&alice, Money::from_major(1000), AccountType::Checking).unwrap();
+    bank.create_account_for_customer(&bob, Money::from_major(1000), AccountType::Checking).unwrap();
+    bank.set_fraud_rules(rules).unwrap();
+    (bank, clock, alice, bob)
+}
+
+fn new_payee_rule(threshold: i64) -> FraudRules {
+    FraudRules { new_payee_transfer: Some(Money::from_major(threshold)), ..FraudRules::default() }
+}
+
+#[test]
+fn nothing_is_flagged_while_the_rules_are_off() {
+    let (mut bank, _, alice, bob) = bank_with_rules(FraudRules::default());
+    for _ in 0..5 {
+        bank.withdraw(&alice, Money::from_major(10)).unwrap();
+    }
+    bank.transfer(&alice, &bob, Money::from_major(500)).unwrap();
+    assert!(bank.review_queue().is_empty());
+    assert!(bank.flagged_transactions().is_empty());
+}
+
+#[test]
+fn withdrawals_over_the_count_within_the_window_are_flagged() {
+    let rules = FraudRules { rapid_withdrawals: Some(VelocityRule { count: 2, minutes: 10 }), ..FraudRules::default() };
+    let (mut bank, clock, alice, _) = bank_with_rules(rules);
+    bank.withdraw(&alice, Money::from_major(10)).unwrap();
+    clock.advance(Duration::minutes(3));
+    bank.withdraw(&alice, Money::from_major(10)).unwrap();
+    assert!(bank.review_queue().is_empty());
+
+    clock.advance(Duration::minutes(3));
+    bank.withdraw(&alice, Money::from_major(10)).unwrap();
+    let queue = bank.review_queue();
+    assert_eq!(queue.len(), 1);
+    assert_eq!(queue[0].customer_id, alice);
+    assert_eq!(queue[0].reason, FraudReason::RapidWithdrawals { count: 3, minutes: 10 });
+    assert_eq!(queue[0].status, ReviewStatus::Pending);
+    let newest = bank.get_customer(&alice).unwrap().get_account().unwrap().transactions.last().unwrap().id.clone();
+    assert_eq!(queue[0].transaction_id, newest);
+
+    // The first two have dropped out of the window by now
+    clock.advance(Duration::minutes(8));
+    bank.withdraw(&alice, Money::from_major(10)).unwrap();
+    assert_eq!(bank.review_queue().len(), 1);
+}
+
+#[test]
+fn only_the_first_large_transfer_to_a_payee_is_flagged() {
+    let (mut bank, _, alice, bob) = bank_with_rules(new_payee_rule(200));
+    bank.transfer(&alice, &bob, Money::from_major(200)).unwrap();
+    assert!(bank.review_queue().is_empty(), "the threshold itself is not above the threshold");
+
+    // Bob has been paid before, Alice has not
+    bank.transfer(&alice, &bob, Money::from_major(300)).unwrap();
+    assert!(bank.review_queue().is_empty());
+    bank.transfer(&bob, &alice, Money::from_major(300)).unwrap();
+    let queue = bank.review_queue();
+    assert_eq!(queue.len(), 1);
+    assert_eq!(queue[0].customer_id, bob);
+    let alice_number = bank.get_customer(&alice).unwrap().get_account().unwrap().number.unwrap();
+    assert_eq!(
+        queue[0].reason,
+        FraudReason::NewPayeeTransfer {
+            payee: alice_number.to_string(),
+            amount: Money::from_major(300),
+            threshold: Money::from_major(200),
+        }
+    );
+}
+
+#[test]
+fn a_payee_paid_before_the_history_was_capped_is_not_new() {
+    let (mut bank, clock, alice, bob) = bank_with_rules(new_payee_rule(200));
+    let dir = temp_path("capped-payee");
+    bank.set_archive(Arc::new(FileArchive::new(&dir)));
+    bank.transfer(&alice, &bob, Money::from_major(300)).unwrap();
+    let first = bank.review_queue()[0].transaction_id.clone();
+    bank.approve_flagged(&first, "admin").unwrap();
+
+    // This month's transfers stay live for the limits
+    clock.advance(Duration::days(31));
+    bank.set_history_cap(Some(2)).unwrap();
+    for _ in 0..3 {
+        bank.deposit(&alice, Money::from_major(1)).unwrap();
+    }
+    let live = &bank.get_customer(&alice).unwrap().get_account().unwrap().transactions;
+    assert!(live.iter().all(|tx| tx.id != first), "the first transfer should be archived");
+
+    bank.transfer(&alice, &bob, Money::from_major(300)).unwrap();
+    assert!(bank.review_queue().is_empty());
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn the_withdrawal_window_reaches_into_archived_history() {
+    let rules = FraudRules { rapid_withdrawals: Some(VelocityRule { count: 2, minutes: 10 }), ..FraudRules::default() };
+    let (mut bank, clock, alice, _) = bank_with_rules(rules);
+    let dir = temp_path("capped-window");
+    bank.set_archive(Arc::new(FileArchive::new(&dir)));
+    bank.set_history_cap(Some(1)).unwrap();
+
+    // The first two fall in March, so the deposit after midnight archives them
+    clock.set(Utc.with_ymd_and_hms(2024, 3, 31, 23, 55, 0).unwrap());
+    bank.withdraw(&alice, Money::from_major(10)).unwrap();
+    clock.advance(Duration::minutes(3));
+    bank.withdraw(&alice, Money::from_major(10)).unwrap();
+    clock.advance(Duration::minutes(3));
+    bank.deposit(&alice, Money::from_major(1)).unwrap();
+    assert_eq!(bank.get_customer(&alice).unwrap().get_account().unwrap().transactions.len(), 1);
+    bank.withdraw(&alice, Money::from_major(10)).unwrap();
+
+    let queue = bank.review_queue();
+    assert_eq!(queue.len(), 1);
+    assert_eq!(queue[0].reason, FraudReason::RapidWithdrawals { count: 3, minutes: 10 });
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn approving_takes_a_transaction_off_the_queue_once() {
+    let (mut bank, _, alice, bob) = bank_with_rules(new_payee_rule(100));
+    bank.transfer(&alice, &bob, Money::from_major(400)).unwrap();
+    let id = bank.review_queue()[0].transaction_id.clone();
+
+    let flag = bank.approve_flagged(&id, "admin").unwrap();
+    assert_eq!(flag.status, ReviewStatus::Approved);
+    assert_eq!(flag.reviewed_by.as_deref(), Some("admin"));
+    assert_eq!(flag.reviewed_at, Some(bank.now()));
+    assert!(bank.review_queue().is_empty());
+    assert_eq!(bank.flagged_transactions().len(), 1);
+    assert_eq!(balance(&bank, &bob), Money::from_major(1400));
+
+    assert!(matches!(bank.approve_flagged(&id, "admin"), Err(BankError::InvalidInput(_))));
+    assert!(matches!(bank.reverse_flagged(&id, "admin"), Err(BankError::InvalidInput(_))));
+    assert!(matches!(bank.approve_flagged(&"no-such-id".into(), "admin"), Err(BankError::TransactionNotFound(_))));
+}
+
+#[test]
+fn reversing_a_flagged_transfer_undoes_both_sides() {
+    let (mut bank, _, alice, bob) = bank_with_rules(new_payee_rule(100));
+    bank.transfer(&alice, &bob, Money::from_major(400)).unwrap();
+    let id = bank.review_queue()[0].transaction_id.clone();
+
+    let reversals = bank.reverse_flagged(&id, "teller1").unwrap();
+    assert_eq!(reversals.len(), 2);
+    assert_eq!(balance(&bank, &alice), Money::from_major(1000));
+    assert_eq!(balance(&bank, &bob), Money::from_major(1000));
+    let original = bank.get_customer(&alice).unwrap().get_account().unwrap().transactions.iter().find(|tx| tx.id == id);
+    assert_eq!(original.unwrap().status, TransactionStatus::Reversed);
+
+    assert!(bank.review_queue().is_empty());
+    let flag = &bank.flagged_transactions()[0];
+    assert_eq!(flag.status, ReviewStatus::Reversed);
+    assert_eq!(flag.reviewed_by.as_deref(), Some("teller1"));
+    // Reversals are never flagged themselves
+    assert_eq!(bank.flagged_transactions().len(), 1);
+}
+
+#[test]
+fn invalid_rules_are_refused() {
+    let (mut bank, _, _, _) = bank_with_rules(FraudRules::default());
+    let zero_count = FraudRules { rapid_withdrawals: Some(VelocityRule { count: 0, minutes: 10 }), ..FraudRules::default() };
+    let long_window =
+        FraudRules { rapid_withdrawals: Some(VelocityRule { count: 3, minutes: 2000 }), ..FraudRules::default() };
+    assert!(matches!(bank.set_fraud_rules(zero_count), Err(BankError::InvalidInput(_))));
+    assert!(matches!(bank.set_fraud_rules(long_window), Err(BankError::InvalidInput(_))));
+    assert!(matches!(bank.set_fraud_rules(new_payee_rule(0)), Err(BankError::InvalidAmount(_))));
+    assert_eq!(bank.fraud_rules(), FraudRules::default());
+}
+
+#[test]
+fn replaying_the_journal_restores_the_queue_and_reviews() {
+    let (mut bank, _, alice, bob) = bank_with_rules(FraudRules::default());
+    let snapshot = serde_json::to_string(&bank).unwrap();
+    bank.enable_journal();
+    bank.set_fraud_rules(new_payee_rule(100)).unwrap();
+    bank.transfer(&alice, &bob, Money::from_major(400)).unwrap();
+    bank.transfer(&bob, &alice, Money::from_major(150)).unwrap();
+    let id = bank.review_queue()[0].transaction_id.clone();
+    bank.approve_flagged(&id, "admin").unwrap();
+
+    let mut restored: Bank = serde_json::from_str(&snapshot).unwrap();
+    for entry in bank.take_journal() {
+        restored.apply_journal_entry(entry).unwrap();
+    }
+    assert_eq!(restored.fraud_rules(), new_payee_rule(100));
+    assert_eq!(restored.flagged_transactions(), bank.flagged_transactions());
+    assert_eq!(restored.review_queue().len(), 1);
+}
+
+#[cfg(feature = "cli")]
+#[test]
+fn command_mode_sets_rules_and_reviews_flags() {
+    let data_file = temp_path("cli.json");
+    let _ = std::fs::remove_file(&data_file);
+    let _ = std::fs::remove_file(journal_path(&data_file.to_string_lossy()));
+    let run = |args: &[&str]| common::run(&data_file, args, "\n");
+    run(&["register", "--name", "Ann", "--email", "ann@example.com", "--pin", "1234"]);
+    run(&["register", "--name", "Ben", "--email", "ben@example.com", "--pin", "1234"]);
+    run(&["create-account", "--customer", "ann@example.com", "--deposit", "1000"]);
+    run(&["create-account", "--customer", "ben@example.com", "--deposit", "10"]);
+
+    let json: serde_json::Value =
+        serde_json::from_str(&run(&["--json", "set-fraud-rules", "--max-withdrawals", "5", "--new-payee-amount", "250"]))
+            .unwrap();
+    assert_eq!(json["result"]["rapid_withdrawals"], serde_json::json!({ "count": 5, "minutes": 60 }));
+    assert_eq!(json["result"]["new_payee_transfer"], "250.00");
+
+    run(&["transfer", "--from", "ann@example.com", "--to", "ben@example.com", "--amount", "300", "--pin", "1234"]);
+    let json: serde_json::Value = serde_json::from_str(&run(&["--json", "review-queue"])).unwrap();
+    let queue = json["result"].as_array().unwrap();
+    assert_eq!(queue.len(), 1);
+    assert_eq!(queue[0]["reason"]["rule"], "new_payee_transfer");
+    let id = queue[0]["transaction_id"].as_str().unwrap().to_string();
+
+    let json: serde_json::Value =
+        serde_json::from_str(&run(&["--json", "reverse-flag", "--transaction", &id, "--reviewer", "admin"])).unwrap();
+    assert_eq!(json["result"].as_array().unwrap().len(), 2);
+    let json: serde_json::Value = serde_json::from_str(&run(&["--json", "review-queue"])).unwrap();
+    assert_eq!(json["result"], serde_json::json!([]));
+    let json: serde_json::Value = serde_json::from_str(&run(&["--json", "review-queue", "--all"])).unwrap();
+    assert_eq!(json["result"][0]["status"], "reversed");
+    assert_eq!(json["result"][0]["reviewed_by"], "admin");
+    let _ = std::fs::remove_file(&data_file);
+}
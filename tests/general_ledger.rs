@@ -0,0 +1,212 @@
+//! Integration tests for the chart of accounts, the mapping rules and the
+//! income statement
+
+// The tests that run the binary need the `cli` feature; without it the
+// imports and helpers only they use go unused
+#![cfg_attr(not(feature = "cli"), allow(unused))]
+
+mod common;
+
+use std::sync::Arc;
+
+use chrono::{Duration, Months, TimeZone, Utc};
+use rust_banking_system::clock::MockClock;
+use rust_banking_system::errors::BankError;
+use rust_banking_system::models::{
+    AccountClass, BookAccount, ChartOfAccounts, CustomerId, MerchantCategory, TransactionKind,
+};
+use rust_banking_system::persistence::{self, journal_path};
+use rust_banking_system::{AccountType, Bank, Money};
+
+use common::temp_path;
+
+/// Alice with $1,000 and Bob with $500 on 15 January 2024; returns
+/// (bank, clock, alice, bob)
+fn bank_with_two_customers() -> (Bank, Arc<MockClock>, CustomerId, CustomerId) {
+    let clock = Arc::new(MockClock::new(Utc.with_ymd_and_hms(2024, 1, 15, 9, 0, 0).unwrap()));
+    let mut bank = Bank::new("Test Bank".to_string());
+    bank.set_clock(clock.clone());
+    let alice = bank.register_customer("Alice".to_string(), "alice@example.com".to_string()).unwrap();
+    let bob = bank.register_customer("Bob".to_string(), "bob@example.com".to_string()).unwrap();
+    bank.create_account_for_customer(&alice, Money::from_major(1000), AccountType::Checking).unwrap();
+    bank.create_account_for_customer(&bob, Money::from_major(500), AccountType::Checking).unwrap();
+    (bank, clock, alice, bob)
+}
+
+/// A bounced $100 cheque: $25 of fee income
+fn bounce_a_cheque(bank: &mut Bank, customer_id: &CustomerId) {
+    let cheque = bank.deposit_cheque(customer_id, Money::from_major(100), "000123", None).unwrap();
+    bank.return_cheque(&cheque.id, None).unwrap();
+}
+
+#[test]
+fn the_trial_balance_lists_accounts_by_code_with_their_class() {
+    let (mut bank, _, alice, _) = bank_with_two_customers();
+    bounce_a_cheque(&mut bank, &alice);
+
+    assert!(bank.chart_of_accounts().validate().is_ok());
+    let trial_balance = bank.trial_balance();
+    let codes: Vec<&str> = trial_balance.rows.iter().map(|row| row.code.as_str()).collect();
+    assert_eq!(codes, ["1000", "1100", "2000", "4000"]);
+    let fees = trial_balance.rows.iter().find(|row| row.account == BookAccount::FeeIncome).unwrap();
+    assert_eq!((fees.name.as_str(), fees.class), ("Fee income", AccountClass::Income));
+    assert_eq!(fees.credit, Money::from_major(25));
+    assert!(trial_balance.to_string().contains("4000   Fee income"), "{}", trial_balance);
+}
+
+#[test]
+fn a_changed_mapping_rule_applies_to_later_postings() {
+    let (mut bank, _, alice, _) = bank_with_two_customers();
+    let power = bank.add_merchant("City Power", MerchantCategory::Utility).unwrap();
+    bank.pay_bill(&alice, &power.id, Money::from_major(80), "ACC-1").unwrap();
+    let credit = |bank: &Bank, account: BookAccount| {
+        let row = bank.trial_balance().rows.into_iter().find(|row| row.account == account);
+        row.map_or(Money::ZERO, |row| row.credit)
+    };
+    assert_eq!(credit(&bank, BookAccount::MerchantPayables), Money::from_major(80));
+
+    let mut chart = bank.chart_of_accounts().clone();
+    chart.map(TransactionKind::BillPayment, BookAccount::Clearing);
+    bank.set_chart_of_accounts(chart).unwrap();
+    bank.pay_bill(&alice, &power.id, Money::from_major(30), "ACC-1").unwrap();
+
+    assert_eq!(bank.chart_of_accounts().mapped(TransactionKind::BillPayment), BookAccount::Clearing);
+    assert_eq!(credit(&bank, BookAccount::MerchantPayables), Money::from_major(80));
+    assert_eq!(credit(&bank, BookAccount::Clearing), Money::from_major(30));
+    assert!(bank.trial_balance().is_balanced());
+}
+
+#[test]
+fn invalid_charts_are_refused() {
+    let (mut bank, _, _, _) = bank_with_two_customers();
+    let with = |change: &dyn Fn(&mut ChartOfAccounts)| {
+        let mut chart = ChartOfAccounts::default();
+        change(&mut chart);
+        chart
+    };
+
+    let duplicate = with(&|chart| chart.accounts[1].code = "1000".to_string());
+    let missing = with(&|chart| {
+        chart.accounts.pop();
+    });
+    let letters = with(&|chart| chart.accounts[0].code = "CASH".to_string());
+    let reversal = with(&|chart| chart.map(TransactionKind::Reversal, BookAccount::Suspense));
+    assert!(matches!(bank.set_chart_of_accounts(duplicate), Err(BankError::InvalidInput(_))));
+    assert!(matches!(bank.set_chart_of_accounts(missing), Err(BankError::InvalidInput(_))));
+    assert!(matches!(
+        bank.set_chart_of_accounts(letters),
+        Err(BankError::ValidationError { ref field, .. }) if field == "account code"
+    ));
+    assert!(matches!(bank.set_chart_of_accounts(reversal), Err(BankError::InvalidInput(_))));
+    assert_eq!(bank.chart_of_accounts(), &ChartOfAccounts::default());
+}
+
+#[test]
+fn the_income_statement_covers_only_its_period() {
+    let (mut bank, clock, alice, _) = bank_with_two_customers();
+    bank.open_term_deposit(&alice, Money::from_major(600), 500, 12).unwrap();
+    bounce_a_cheque(&mut bank, &alice);
+    let maturity = bank.now().checked_add_months(Months::new(12)).unwrap();
+    clock.set(maturity);
+    bank.process_maturities(maturity).unwrap();
+
+    let all = bank.income_statement(None, None);
+    assert_eq!(all.total_income, Money::from_major(25));
+    assert_eq!(all.total_expenses, Money::from_major(30));
+    assert_eq!(all.net_income, Money::from_major(-5));
+    assert_eq!(all.expenses[0].name, "Interest expense");
+
+    let this_year = bank.income_statement(Some(maturity - Duration::days(1)), None);
+    assert_eq!((this_year.total_income, this_year.net_income), (Money::ZERO, Money::from_major(-30)));
+    let last_year = bank.income_statement(None, Some(maturity - Duration::days(1)));
+    assert_eq!(last_year.net_income, Money::from_major(25));
+    assert!(all.to_string().contains("Net income"));
+}
+
+#[test]
+fn renamed_and_reclassified_accounts_show_in_the_reports() {
+    let (mut bank, _, alice, _) = bank_with_two_customers();
+    bounce_a_cheque(&mut bank, &alice);
+    let mut chart = bank.chart_of_accounts().clone();
+    let fees = chart.accounts.iter_mut().find(|line| line.account == BookAccount::FeeIncome).unwrap();
+    fees.code = "4100".to_string();
+    fees.name = "Cheque charges".to_string();
+    bank.set_chart_of_accounts(chart).unwrap();
+
+    assert_eq!(bank.chart_of_accounts().find("cheque CHARGES"), Some(BookAccount::FeeIncome));
+    assert_eq!(bank.chart_of_accounts().find("4100"), Some(BookAccount::FeeIncome));
+    let statement = bank.income_statement(None, None);
+    assert_eq!(statement.income[0].name, "Cheque charges");
+    assert_eq!(statement.income[0].code, "4100");
+
+    // As equity it no longer counts as income
+    let mut chart = bank.chart_of_accounts().clone();
+    chart.accounts.iter_mut().find(|line| line.account == BookAccount::FeeIncome).unwrap().class = AccountClass::Equity;
+    bank.set_chart_of_accounts(chart).unwrap();
+    assert_eq!(bank.income_statement(None, None).total_income, Money::ZERO);
+}
+
+#[test]
+fn both_reports_export_to_csv_with_a_totals_row() {
+    let (mut bank, _, alice, _) = bank_with_two_customers();
+    bounce_a_cheque(&mut bank, &alice);
+    let trial_balance_file = temp_path("trial-balance.csv");
+    let statement_file = temp_path("income-statement.csv");
+
+    let written = persistence::export_trial_balance_csv(&bank.trial_balance(), &trial_balance_file.to_string_lossy());
+    assert_eq!(written.unwrap(), 4);
+    let text = std::fs::read_to_string(&trial_balance_file).unwrap();
+    let lines: Vec<&str> = text.lines().collect();
+    assert_eq!(lines[0], "code,account,class,debit,credit");
+    assert_eq!(lines[1], "1000,Cash,Asset,1500.00,0.00");
+    assert_eq!(*lines.last().unwrap(), ",Total,,1500.00,1500.00");
+
+    let statement = bank.income_statement(None, None);
+    persistence::export_income_statement_csv(&statement, &statement_file.to_string_lossy()).unwrap();
+    let text = std::fs::read_to_string(&statement_file).unwrap();
+    assert_eq!(
+        text.lines().collect::<Vec<_>>(),
+        [
+            "section,code,account,amount",
+            "Income,4000,Fee income,25.00",
+            "Expenses,5000,Interest expense,0.00",
+            "Net income,,,25.00",
+        ]
+    );
+    let _ = std::fs::remove_file(&trial_balance_file);
+    let _ = std::fs::remove_file(&statement_file);
+}
+
+#[cfg(feature = "cli")]
+#[test]
+fn command_mode_edits_the_chart_and_reports() {
+    let data_file = temp_path("cli.json");
+    let _ = std::fs::remove_file(&data_file);
+    let _ = std::fs::remove_file(journal_path(&data_file.to_string_lossy()));
+    let run = |args: &[&str]| common::run(&data_file, args, "\n");
+    run(&["register", "--name", "Ann", "--email", "ann@example.com", "--pin", "1234"]);
+    run(&["create-account", "--customer", "ann@example.com", "--deposit", "100"]);
+
+    let json: serde_json::Value = serde_json::from_str(&run(&[
+        "--json", "set-chart-account", "--account", "Capital", "--code", "3100", "--name", "Owner's capital",
+    ]))
+    .unwrap();
+    let capital = json["result"]["accounts"].as_array().unwrap().iter().find(|line| line["account"] == "capital");
+    assert_eq!(capital.unwrap()["code"], "3100");
+
+    let json: serde_json::Value =
+        serde_json::from_str(&run(&["--json", "map-transaction", "--kind", "withdrawal", "--account", "2900"]))
+            .unwrap();
+    let rule = json["result"]["rules"].as_array().unwrap().iter().find(|rule| rule["kind"] == "Withdrawal");
+    assert_eq!(rule.unwrap()["account"], "suspense");
+
+    let json: serde_json::Value =
+        serde_json::from_str(&run(&["--json", "map-transaction", "--kind", "reversal", "--account", "2900"])).unwrap();
+    assert_eq!(json["ok"], false);
+
+    let json: serde_json::Value =
+        serde_json::from_str(&run(&["--json", "income-statement", "--from", "2020-01-01", "--to", "2020-12-31"]))
+            .unwrap();
+    assert_eq!(json["result"]["net_income"], "0.00");
+    let _ = std::fs::remove_file(&data_file);
+}
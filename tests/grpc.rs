@@ -0,0 +1,115 @@
+//! Integration tests for the gRPC service (run with `--features grpc`)
+
+#![cfg(feature = "grpc")]
+
+use std::sync::Arc;
+
+use tonic::{Code, Request};
+
+use rust_banking_system::grpc::proto::bank_service_server::BankService;
+use rust_banking_system::grpc::{proto, GrpcBank};
+use rust_banking_system::{Bank, BankResult, SharedBank, Storage};
+
+/// Storage that keeps nothing, so tests never touch the file system
+struct NullStorage;
+
+impl Storage for NullStorage {
+    fn save_bank(&self, _bank: &Bank) -> BankResult<()> {
+        Ok(())
+    }
+
+    fn load_bank(&self) -> BankResult<Bank> {
+        Ok(Bank::new("Test Bank".to_string()))
+    }
+}
+
+fn service() -> GrpcBank {
+    let bank = SharedBank::new(Bank::new("Test Bank".to_string()));
+    GrpcBank::new(bank, Arc::new(NullStorage))
+}
+
+/// Registers a customer with a checking account and returns the customer ID
+async fn customer_with_account(service: &GrpcBank, name: &str, deposit: &str) -> String {
+    let customer_id = service
+        .register_customer(Request::new(proto::RegisterCustomerRequest {
+            name: name.to_string(),
+            email: format!("{}@example.com", name),
+        }))
+        .await
+        .unwrap()
+        .into_inner()
+        .customer_id;
+
+    service
+        .create_account(Request::new(proto::CreateAccountRequest {
+            customer_id: customer_id.clone(),
+            initial_deposit: deposit.to_string(),
+            account_type: proto::AccountKind::Checking as i32,
+            term_months: 0,
+        }))
+        .await
+        .unwrap();
+    customer_id
+}
+
+#[tokio::test]
+async fn transfer_shows_up_in_history() {
+    let service = service();
+    let alice = customer_with_account(&service, "alice", "100").await;
+    let bob = customer_with_account(&service, "bob", "0").await;
+
+    service
+        .transfer(Request::new(proto::TransferRequest {
+            from_customer_id: alice.clone(),
+            to_customer_id: bob.clone(),
+            amount: "40".to_string(),
+        }))
+        .await
+        .unwrap();
+
+    let history = service
+        .get_history(Request::new(proto::GetHistoryRequest { customer_id: bob }))
+        .await
+        .unwrap()
+        .into_inner()
+        .transactions;
+
+    let last = history.last().unwrap();
+    assert_eq!(last.transaction_type, "TRANSFER_IN");
+    assert_eq!(last.amount, "40.00");
+    assert_eq!(last.balance_after, "40.00");
+    assert!(!last.counterparty_account_id.is_empty());
+}
+
+#[tokio::test]
+async fn errors_map_to_status_codes() {
+    let service = service();
+    let alice = customer_with_account(&service, "alice", "10").await;
+
+    let status = service
+        .withdraw(Request::new(proto::AmountRequest {
+            customer_id: alice.clone(),
+            amount: "50".to_string(),
+        }))
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), Code::FailedPrecondition);
+
+    let status = service
+        .deposit(Request::new(proto::AmountRequest {
+            customer_id: "nobody".to_string(),
+            amount: "5".to_string(),
+        }))
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), Code::NotFound);
+
+    let status = service
+        .deposit(Request::new(proto::AmountRequest {
+            customer_id: alice,
+            amount: "five".to_string(),
+        }))
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), Code::InvalidArgument);
+}
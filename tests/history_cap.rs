@@ -0,0 +1,297 @@
+//! Integration tests for capping the transactions kept in memory
+
+// The tests that run the binary need the `cli` feature; without it the
+// imports and helpers only they use go unused
+#![cfg_attr(not(feature = "cli"), allow(unused))]
+
+mod common;
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::io::Write;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use chrono::{Duration, TimeZone, Utc};
+use rust_banking_system::bank::{HistoryArchive, HISTORY_PAGE_SIZE};
+#[cfg(feature = "cli")]
+use rust_banking_system::cli::startup::StartupArgs;
+use rust_banking_system::clock::MockClock;
+use rust_banking_system::config::Config;
+use rust_banking_system::models::{AccountId, AccountLimits, CustomerId, Limit, TransactionId};
+use rust_banking_system::persistence::{archive_dir, journal_path, FileArchive, JsonFileStorage, Storage};
+use rust_banking_system::{AccountType, Bank, BankError, BankResult, Money, Transaction};
+
+use common::temp_file;
+
+fn remove(path: &str) {
+    let _ = std::fs::remove_file(path);
+    let _ = std::fs::remove_file(journal_path(path));
+    let _ = std::fs::remove_dir_all(archive_dir(path));
+}
+
+/// An archive in memory that counts the pages loaded from it
+#[derive(Debug, Default)]
+struct CountingArchive {
+    accounts: Mutex<HashMap<AccountId, Vec<Transaction>>>,
+    pages: AtomicUsize,
+}
+
+impl HistoryArchive for CountingArchive {
+    fn store(&self, account_id: &AccountId, transactions: &[Transaction]) -> BankResult<()> {
+        let mut accounts = self.accounts.lock().unwrap();
+        let stored = accounts.entry(account_id.clone()).or_default();
+        for transaction in transactions {
+            if !stored.iter().any(|tx| tx.id == transaction.id) {
+                stored.push(transaction.clone());
+            }
+        }
+        Ok(())
+    }
+
+    fn load(&self, account_id: &AccountId) -> BankResult<Vec<Transaction>> {
+        Ok(self.accounts.lock().unwrap().get(account_id).cloned().unwrap_or_default())
+    }
+
+    fn load_page(&self, account_id: &AccountId, start: usize, len: usize) -> BankResult<Vec<Transaction>> {
+        self.pages.fetch_add(1, Ordering::SeqCst);
+        Ok(self.load(account_id)?.into_iter().skip(start).take(len).collect())
+    }
+}
+
+/// A bank on a mock clock with `archive` attached where Alice opens an
+/// account and then makes `deposits` deposits of 1, two hours apart;
+/// returns (bank, alice)
+fn bank_with_deposits(archive: Arc<dyn HistoryArchive>, deposits: usize) -> (Bank, CustomerId) {
+    let clock = Arc::new(MockClock::new(Utc.with_ymd_and_hms(2024, 3, 1, 9, 0, 0).unwrap()));
+    let mut bank = Bank::new("Test Bank".to_string());
+    bank.set_clock(clock.clone());
+    bank.set_archive(archive);
+    let alice = bank.register_customer("Alice".to_string(), "alice@example.com".to_string()).unwrap();
+    bank.create_account_for_customer(&alice, Money::from_major(100), AccountType::Checking).unwrap();
+    for _ in 0..deposits {
+        clock.advance(Duration::hours(2));
+        bank.deposit(&alice, Money::from_major(1)).unwrap();
+    }
+    (bank, alice)
+}
+
+fn live(bank: &Bank, customer_id: &CustomerId) -> Vec<Transaction> {
+    bank.get_customer(customer_id).unwrap().get_account().unwrap().transactions.to_vec()
+}
+
+/// The whole history as transaction IDs, newest first
+fn history_ids(bank: &Bank, customer_id: &CustomerId) -> Vec<TransactionId> {
+    bank.get_transaction_history(customer_id).unwrap().map(|tx| tx.unwrap().id.clone()).collect()
+}
+
+#[test]
+fn the_cap_trims_each_account_as_transactions_post() {
+    let (mut bank, alice) = bank_with_deposits(Arc::new(CountingArchive::default()), 5);
+    let books_before = serde_json::to_value(bank.trial_balance().rows).unwrap();
+
+    bank.set_history_cap(Some(3)).unwrap();
+    assert_eq!(bank.history_cap(), Some(3));
+    assert_eq!(live(&bank, &alice).len(), 3);
+    assert_eq!(serde_json::to_value(bank.trial_balance().rows).unwrap(), books_before);
+    bank.deposit(&alice, Money::from_major(1)).unwrap();
+    bank.withdraw(&alice, Money::from_major(2)).unwrap();
+
+    assert_eq!(live(&bank, &alice).len(), 3);
+    let account = bank.get_customer(&alice).unwrap().get_account().unwrap();
+    let archived = account.archived.clone().unwrap();
+    assert_eq!(archived.transactions, 5);
+    assert_eq!(archived.before, account.transactions[0].timestamp);
+    assert_eq!(account.balance, Money::from_major(104));
+    assert!(bank.verify_integrity().is_clean(), "{}", bank.verify_integrity());
+    assert!(bank.trial_balance().is_balanced());
+
+    // Lifting the cap keeps what was archived where it is
+    bank.set_history_cap(None).unwrap();
+    bank.deposit(&alice, Money::from_major(1)).unwrap();
+    assert_eq!(live(&bank, &alice).len(), 4);
+}
+
+#[test]
+fn a_capped_account_still_reaches_its_daily_limits() {
+    let (mut bank, alice) = bank_with_deposits(Arc::new(CountingArchive::default()), 0);
+    let bob = bank.register_customer("Bob".to_string(), "bob@example.com".to_string()).unwrap();
+    bank.create_account_for_customer(&bob, Money::from_major(10), AccountType::Checking).unwrap();
+    let limits = AccountLimits {
+        daily_withdrawal: Some(Money::from_major(100)),
+        daily_transfers: Some(1),
+        ..AccountLimits::default()
+    };
+    bank.set_limits(&alice, limits).unwrap();
+    bank.set_history_cap(Some(1)).unwrap();
+
+    // A deposit after each debit would push it out of a one-transaction history
+    bank.withdraw(&alice, Money::from_major(60)).unwrap();
+    bank.deposit(&alice, Money::from_major(1)).unwrap();
+    let result = bank.withdraw(&alice, Money::from_major(60));
+    assert!(matches!(result, Err(BankError::LimitExceeded(Limit::DailyWithdrawal(_)))), "{:?}", result);
+
+    bank.transfer(&alice, &bob, Money::from_major(5)).unwrap();
+    bank.deposit(&alice, Money::from_major(1)).unwrap();
+    let result = bank.transfer(&alice, &bob, Money::from_major(5));
+    assert!(matches!(result, Err(BankError::LimitExceeded(Limit::DailyTransfers(1)))), "{:?}", result);
+}
+
+#[test]
+fn a_capped_savings_account_still_counts_the_months_withdrawals() {
+    let clock = Arc::new(MockClock::new(Utc.with_ymd_and_hms(2024, 3, 1, 9, 0, 0).unwrap()));
+    let mut bank = Bank::new("Test Bank".to_string());
+    bank.set_clock(clock.clone());
+    bank.set_archive(Arc::new(CountingArchive::default()));
+    let alice = bank.register_customer("Alice".to_string(), "alice@example.com".to_string()).unwrap();
+    bank.create_account_for_customer(&alice, Money::from_major(100), AccountType::Savings).unwrap();
+    bank.set_history_cap(Some(1)).unwrap();
+
+    for _ in 0..AccountType::SAVINGS_MONTHLY_WITHDRAWAL_LIMIT {
+        bank.withdraw(&alice, Money::from_major(1)).unwrap();
+        clock.advance(Duration::days(1));
+        bank.deposit(&alice, Money::from_major(1)).unwrap();
+    }
+    let result = bank.withdraw(&alice, Money::from_major(1));
+    assert!(matches!(result, Err(BankError::WithdrawalLimitReached { .. })), "{:?}", result);
+
+    // Once the month turns, last month's withdrawals go to the archive
+    clock.set(Utc.with_ymd_and_hms(2024, 4, 1, 9, 0, 0).unwrap());
+    bank.withdraw(&alice, Money::from_major(1)).unwrap();
+    assert_eq!(live(&bank, &alice).len(), 1);
+}
+
+#[test]
+fn the_history_walks_back_from_the_newest_through_the_archive() {
+    let (mut bank, alice) = bank_with_deposits(Arc::new(CountingArchive::default()), 6);
+    let mut everything: Vec<TransactionId> = live(&bank, &alice).into_iter().map(|tx| tx.id).collect();
+    everything.reverse();
+
+    bank.set_history_cap(Some(2)).unwrap();
+
+    assert_eq!(history_ids(&bank, &alice), everything);
+    let kinds: Vec<bool> = bank
+        .get_transaction_history(&alice)
+        .unwrap()
+        .map(|tx| matches!(tx.unwrap(), Cow::Borrowed(_)))
+        .collect();
+    assert_eq!(kinds, [true, true, false, false, false, false, false]);
+    let account = bank.get_customer(&alice).unwrap().get_account().unwrap();
+    assert_eq!(account.get_transaction_history().len(), 2);
+    assert!(matches!(bank.get_transaction_history(&"nobody".into()), Err(BankError::CustomerNotFound(_))));
+}
+
+#[test]
+fn older_pages_are_only_loaded_when_the_walk_reaches_them() {
+    let archive = Arc::new(CountingArchive::default());
+    let (mut bank, alice) = bank_with_deposits(archive.clone(), HISTORY_PAGE_SIZE + 20);
+    bank.set_history_cap(Some(10)).unwrap();
+
+    let recent: Vec<_> = bank.get_transaction_history(&alice).unwrap().take(10).collect();
+    assert_eq!(recent.len(), 10);
+    assert_eq!(archive.pages.load(Ordering::SeqCst), 0);
+
+    let mut history = bank.get_transaction_history(&alice).unwrap();
+    assert_eq!(history.by_ref().take(11).count(), 11);
+    assert_eq!(history.pages_fetched(), 1);
+    assert_eq!(history.by_ref().count(), HISTORY_PAGE_SIZE + 21 - 11);
+    assert_eq!(history.pages_fetched(), 2);
+
+    // A history nobody can page in ends in an error
+    let detached: Bank = serde_json::from_value(serde_json::to_value(&bank).unwrap()).unwrap();
+    let last = detached.get_transaction_history(&alice).unwrap().last().unwrap();
+    assert!(matches!(last, Err(BankError::InvalidInput(_))));
+}
+
+#[cfg(feature = "cli")]
+#[test]
+fn a_cap_must_be_positive_and_needs_an_archive() {
+    let mut bank = Bank::new("Test Bank".to_string());
+    assert!(matches!(bank.set_history_cap(Some(5)), Err(BankError::InvalidInput(_))));
+    assert!(bank.set_history_cap(None).is_ok());
+    bank.set_archive(Arc::new(CountingArchive::default()));
+    assert!(matches!(bank.set_history_cap(Some(0)), Err(BankError::InvalidInput(_))));
+    assert_eq!(bank.history_cap(), None);
+    assert!(bank.set_history_cap(Some(5)).is_ok());
+
+    let config = Config { history_cap: Some(0), ..Config::default() };
+    match config.validate() {
+        Err(BankError::ValidationError { field, .. }) => assert_eq!(field, "history_cap"),
+        other => panic!("expected a validation error, got {:?}", other),
+    }
+    assert_eq!(Config::from_toml("history_cap = 1000").unwrap().history_cap, Some(1000));
+    let flags = StartupArgs { history_cap: Some(50), ..StartupArgs::default() };
+    assert_eq!(flags.apply(Config::default(), None).unwrap().history_cap, Some(50));
+}
+
+#[test]
+fn capped_histories_replay_from_the_journal() {
+    let path = temp_file("journaled.json");
+    remove(&path);
+    let (mut bank, alice) = bank_with_deposits(Arc::new(FileArchive::for_data_file(&path)), 4);
+    let storage = JsonFileStorage::new(&path);
+    storage.save_bank(&bank).unwrap();
+    let everything = history_ids(&bank, &alice);
+
+    bank.enable_journal();
+    bank.set_history_cap(Some(2)).unwrap();
+    let entries = bank.take_journal();
+    storage.append_journal(&bank, &entries).unwrap();
+
+    let loaded = storage.load_bank().unwrap();
+    assert_eq!(loaded.history_cap(), None);
+    assert_eq!(live(&loaded, &alice).len(), 2);
+    assert_eq!(history_ids(&loaded, &alice), everything);
+    assert!(loaded.verify_integrity().is_clean(), "{}", loaded.verify_integrity());
+    remove(&path);
+}
+
+#[test]
+fn the_file_archive_mends_a_torn_tail_and_reads_single_pages() {
+    let dir = temp_file("file-archive");
+    let (bank, alice) = bank_with_deposits(Arc::new(CountingArchive::default()), 4);
+    let transactions = live(&bank, &alice);
+    let account_id = bank.get_customer(&alice).unwrap().get_account().unwrap().id.clone();
+    let archive = FileArchive::new(&dir);
+    assert!(archive.load_page(&account_id, 0, 10).unwrap().is_empty());
+
+    archive.store(&account_id, &transactions[..3]).unwrap();
+    let file = archive.dir().join(format!("{}.jsonl", account_id));
+    std::fs::OpenOptions::new().append(true).open(&file).unwrap().write_all(b"{\"id\":\"torn").unwrap();
+    // Overlaps what is stored: only the last two are new
+    archive.store(&account_id, &transactions[1..]).unwrap();
+
+    let contents = std::fs::read_to_string(&file).unwrap();
+    assert_eq!(contents.lines().count(), 5);
+    assert!(contents.ends_with('\n') && !contents.contains("torn"));
+    let page: Vec<TransactionId> = archive.load_page(&account_id, 1, 2).unwrap().into_iter().map(|tx| tx.id).collect();
+    assert_eq!(page, [transactions[1].id.clone(), transactions[2].id.clone()]);
+    assert_eq!(archive.load_page(&account_id, 4, 10).unwrap().len(), 1);
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[cfg(feature = "cli")]
+#[test]
+fn command_mode_caps_histories_with_the_flag() {
+    let data_file = temp_file("cli.json");
+    remove(&data_file);
+    let (bank, _) = bank_with_deposits(Arc::new(FileArchive::for_data_file(&data_file)), 5);
+    JsonFileStorage::new(&data_file).save_bank(&bank).unwrap();
+    let run = |args: &[&str]| serde_json::from_str::<serde_json::Value>(&common::run(&data_file, args, "\n")).unwrap();
+    let alice = ["--customer", "alice@example.com"];
+
+    // Read-only runs leave the file as it is
+    let json = run(&[&["--read-only", "--history-cap", "2", "--json", "account"], &alice[..]].concat());
+    assert_eq!(json["result"]["account"]["transactions"].as_array().unwrap().len(), 6);
+
+    let json = run(&[&["--history-cap", "2", "--json", "account"], &alice[..]].concat());
+    assert_eq!(json["result"]["account"]["transactions"].as_array().unwrap().len(), 2);
+    let json = run(&[&["--json", "history"], &alice[..]].concat());
+    let history = json["result"].as_array().unwrap();
+    assert_eq!(history.len(), 6);
+    assert_eq!(history[0]["balance_after"], "100.00");
+    let json = run(&[&["--json", "history"], &alice[..], &["--last", "3"]].concat());
+    assert_eq!(json["result"].as_array().unwrap().len(), 3);
+    assert_eq!(json["result"][2]["balance_after"], "105.00");
+    remove(&data_file);
+}
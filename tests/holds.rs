@@ -0,0 +1,156 @@
+//! Integration tests for holds and the available balance
+
+mod common;
+
+use std::sync::Arc;
+
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use rust_banking_system::clock::MockClock;
+use rust_banking_system::models::{Account, CustomerId, TransactionType};
+use rust_banking_system::{AccountType, Bank, BankError, Money};
+
+use common::add_customers;
+
+fn start() -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2024, 3, 28, 10, 0, 0).unwrap()
+}
+
+/// Creates a bank on a mock clock where Alice has $1,000 and Bob $100;
+/// returns (bank, clock, alice, bob)
+fn bank_on_clock() -> (Bank, Arc<MockClock>, CustomerId, CustomerId) {
+    let clock = Arc::new(MockClock::new(start()));
+    let mut bank = Bank::new("Test Bank".to_string());
+    bank.set_clock(clock.clone());
+    let (alice, bob) = add_customers(&mut bank, 1_000, 100);
+    (bank, clock, alice, bob)
+}
+
+fn account<'a>(bank: &'a Bank, customer_id: &CustomerId) -> &'a Account {
+    bank.get_customer(customer_id).unwrap().get_account().unwrap()
+}
+
+/// (ledger, available) balances of `customer_id` by the bank's clock
+fn balances(bank: &Bank, customer_id: &CustomerId) -> (Money, Money) {
+    let account = account(bank, customer_id);
+    (account.ledger_balance(), account.available_balance_at(bank.now()))
+}
+
+#[test]
+fn holds_reduce_the_available_balance_only() {
+    let (mut bank, _, alice, bob) = bank_on_clock();
+    let hold = bank.place_hold(&alice, Money::from_major(600), "Hotel deposit", None).unwrap();
+
+    assert_eq!(hold.reason, "Hotel deposit");
+    assert_eq!(balances(&bank, &alice), (Money::from_major(1_000), Money::from_major(400)));
+    assert_eq!(account(&bank, &alice).transactions.len(), 1);
+
+    assert!(matches!(
+        bank.withdraw(&alice, Money::from_major(500)),
+        Err(BankError::InsufficientFunds { available, .. }) if available == Money::from_major(400)
+    ));
+    assert!(matches!(bank.transfer(&alice, &bob, Money::from_major(401)), Err(BankError::InsufficientFunds { .. })));
+    bank.transfer(&alice, &bob, Money::from_major(400)).unwrap();
+}
+
+#[test]
+fn holds_are_checked() {
+    let (mut bank, _, alice, _) = bank_on_clock();
+    assert!(matches!(bank.place_hold(&alice, Money::ZERO, "x", None), Err(BankError::InvalidAmount(_))));
+    assert!(matches!(bank.place_hold(&alice, Money::from_major(1), "  ", None), Err(BankError::ValidationError { .. })));
+    assert!(matches!(
+        bank.place_hold(&alice, Money::from_major(1), "Deposit", Some(bank.now() - Duration::hours(1))),
+        Err(BankError::InvalidInput(_))
+    ));
+
+    bank.authorize_withdrawal(&alice, Money::from_major(700)).unwrap();
+    assert!(matches!(
+        bank.place_hold(&alice, Money::from_major(301), "Deposit", None),
+        Err(BankError::InsufficientFunds { .. })
+    ));
+}
+
+#[test]
+fn released_holds_free_the_money() {
+    let (mut bank, _, alice, _) = bank_on_clock();
+    let hold = bank.place_hold(&alice, Money::from_major(600), "Legal order", None).unwrap();
+
+    assert_eq!(bank.release_hold(&hold.id).unwrap(), hold);
+    assert_eq!(balances(&bank, &alice), (Money::from_major(1_000), Money::from_major(1_000)));
+    assert!(matches!(bank.release_hold(&hold.id), Err(BankError::InvalidInput(_))));
+}
+
+#[test]
+fn captured_holds_become_withdrawals() {
+    let (mut bank, _, alice, _) = bank_on_clock();
+    let hold = bank.place_hold(&alice, Money::from_major(250), "Car rental", None).unwrap();
+    bank.freeze_account(&alice).unwrap();
+
+    let withdrawal = bank.capture_hold(&hold.id).unwrap();
+    assert!(matches!(withdrawal.transaction_type, TransactionType::Withdrawal));
+    assert_eq!(withdrawal.amount, Money::from_major(250));
+    assert_eq!(withdrawal.description.as_deref(), Some("Car rental"));
+    assert_eq!(balances(&bank, &alice), (Money::from_major(750), Money::from_major(750)));
+    assert!(account(&bank, &alice).holds.is_empty());
+    assert!(bank.verify_integrity().is_clean());
+}
+
+#[test]
+fn expired_holds_stop_counting_and_are_swept() {
+    let (mut bank, clock, alice, bob) = bank_on_clock();
+    let expiring = bank
+        .place_hold(&alice, Money::from_major(300), "Pre-authorization", Some(start() + Duration::days(3)))
+        .unwrap();
+    bank.place_hold(&bob, Money::from_major(50), "Dispute", None).unwrap();
+
+    clock.advance(Duration::days(3));
+    assert_eq!(balances(&bank, &alice), (Money::from_major(1_000), Money::from_major(1_000)));
+    assert!(matches!(bank.capture_hold(&expiring.id), Err(BankError::InvalidInput(_))));
+    bank.withdraw(&alice, Money::from_major(1_000)).unwrap();
+
+    assert_eq!(bank.release_expired_holds(bank.now()).unwrap(), [expiring]);
+    assert!(account(&bank, &alice).holds.is_empty());
+    assert_eq!(account(&bank, &bob).holds.len(), 1);
+    assert!(bank.release_expired_holds(bank.now()).unwrap().is_empty());
+}
+
+#[test]
+fn accounts_with_holds_cannot_be_closed() {
+    let (mut bank, _, alice, bob) = bank_on_clock();
+    let hold = bank.place_hold(&alice, Money::from_major(10), "Chargeback", None).unwrap();
+
+    assert!(matches!(bank.close_account(&alice, Some(&bob)), Err(BankError::InvalidInput(_))));
+    bank.release_hold(&hold.id).unwrap();
+    bank.close_account(&alice, Some(&bob)).unwrap();
+}
+
+#[test]
+fn holds_survive_journal_replay() {
+    let (mut bank, _, alice, _) = bank_on_clock();
+    let snapshot = serde_json::to_string(&bank).unwrap();
+    bank.enable_journal();
+
+    let kept = bank.place_hold(&alice, Money::from_major(100), "Kept", None).unwrap();
+    let released = bank.place_hold(&alice, Money::from_major(50), "Released", None).unwrap();
+    let captured = bank.place_hold(&alice, Money::from_major(25), "Captured", None).unwrap();
+    bank.release_hold(&released.id).unwrap();
+    bank.capture_hold(&captured.id).unwrap();
+
+    let mut restored: Bank = serde_json::from_str(&snapshot).unwrap();
+    for entry in bank.take_journal() {
+        restored.apply_journal_entry(entry).unwrap();
+    }
+    assert_eq!(account(&restored, &alice).holds, [kept]);
+    assert_eq!(balances(&restored, &alice), (Money::from_major(975), Money::from_major(875)));
+    assert_eq!(restored.total_transactions, bank.total_transactions);
+}
+
+#[test]
+fn accounts_can_hold_money_directly() {
+    let mut account = Account::new(Money::from_major(100), AccountType::Checking).unwrap();
+    let hold = account.place_hold(Money::from_major(40), "Deposit").unwrap();
+
+    assert_eq!(account.available_balance(), Money::from_major(60));
+    assert!(account.withdraw(Money::from_major(61)).is_err());
+    assert_eq!(account.release_hold(&hold.id).unwrap(), hold);
+    assert_eq!(account.available_balance(), Money::from_major(100));
+}
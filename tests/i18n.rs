@@ -0,0 +1,47 @@
+//! Integration tests for the CLI message catalog
+
+#![cfg(feature = "cli")]
+
+use rust_banking_system::cli::i18n::{self, Language, Msg};
+
+#[test]
+fn languages_parse_from_codes_and_locales() {
+    assert_eq!("es".parse::<Language>().unwrap(), Language::Spanish);
+    assert_eq!(" EN ".parse::<Language>().unwrap(), Language::English);
+    assert!("fr".parse::<Language>().is_err());
+
+    assert_eq!(Language::from_locale("es-MX"), Language::Spanish);
+    assert_eq!(Language::from_locale("es_ES"), Language::Spanish);
+    assert_eq!(Language::from_locale("en-US"), Language::English);
+    // Locales without a translation fall back to English
+    assert_eq!(Language::from_locale("de-DE"), Language::English);
+
+    for language in Language::ALL {
+        assert_eq!(language.code().parse::<Language>().unwrap(), language);
+    }
+    assert_eq!(Language::Spanish.to_string(), "Español");
+}
+
+// The only test here that changes the language, since it is shared by the
+// whole test binary
+#[test]
+fn messages_follow_the_selected_language() {
+    assert_eq!(i18n::language(), Language::English);
+    assert_eq!(Msg::DepositMoney.text(), "Deposit Money");
+    assert_eq!(Msg::LoggedInAs.fill(&[&"Alice", &"Customer"]), "🔐 Logged in as Alice (Customer)");
+    // Missing arguments leave the gap empty; extra ones are ignored
+    assert_eq!(Msg::ChooseOneOf.fill(&[]), "Choose 1- (blank to cancel): ");
+    assert_eq!(Msg::InvalidAmount.fill(&[&1]), "Invalid amount");
+
+    i18n::set_language(Language::Spanish);
+    assert_eq!(i18n::language(), Language::Spanish);
+    assert_eq!(Msg::DepositMoney.text(), "Depositar dinero");
+    assert_eq!(Msg::InvalidAmount.to_string(), "Importe no válido");
+    assert_eq!(
+        Msg::MatchesCustomers.fill(&[&"al", &3]),
+        "\n'al' coincide con 3 clientes:"
+    );
+
+    i18n::set_language(Language::English);
+    assert_eq!(Msg::InvalidAmount.to_string(), "Invalid amount");
+}
@@ -0,0 +1,96 @@
+//! Integration tests for the customer, account and transaction ID types
+
+use std::collections::HashMap;
+
+use rust_banking_system::models::{AccountId, CustomerId, TransactionId};
+use rust_banking_system::{AccountType, Bank, BankError, Money};
+
+/// Alice with $100 in checking; returns (bank, alice, her account)
+fn bank_with_alice() -> (Bank, CustomerId, AccountId) {
+    let mut bank = Bank::new("Test Bank".to_string());
+    let alice = bank.register_customer("Alice".to_string(), "alice@example.com".to_string()).unwrap();
+    let account = bank.create_account_for_customer(&alice, Money::from_major(100), AccountType::Checking).unwrap();
+    (bank, alice, account)
+}
+
+#[test]
+fn ids_parse_from_typed_input() {
+    let id: TransactionId = "  67e55044-10b1-426f-9247-bb680e5fe0c8\n".parse().unwrap();
+    assert_eq!(id, "67e55044-10b1-426f-9247-bb680e5fe0c8");
+    assert_eq!(id.short(), "67e55044");
+
+    let error = "   ".parse::<AccountId>().unwrap_err();
+    assert_eq!(error, "an account ID cannot be empty");
+    // Shorter than eight characters, the short form is the whole ID
+    assert_eq!(CustomerId::from("abc").short(), "abc");
+}
+
+#[test]
+fn ids_convert_to_and_from_strings() {
+    let id = CustomerId::from("c-1".to_string());
+    assert_eq!(id.as_str(), "c-1");
+    assert_eq!(id.to_string(), "c-1");
+    assert_eq!(format!("<{}>", id), "<c-1>");
+    assert_eq!(String::from(id.clone()), "c-1");
+    assert_eq!(id, CustomerId::from("c-1"));
+    assert_ne!(CustomerId::generate(), CustomerId::generate());
+}
+
+#[test]
+fn ids_serialize_as_bare_strings() {
+    let id = AccountId::from("a-1");
+    assert_eq!(serde_json::to_value(&id).unwrap(), serde_json::json!("a-1"));
+    assert_eq!(serde_json::from_str::<AccountId>("\"a-1\"").unwrap(), id);
+
+    // Maps keyed by IDs keep string keys
+    let balances = HashMap::from([(id, 5)]);
+    assert_eq!(serde_json::to_string(&balances).unwrap(), r#"{"a-1":5}"#);
+}
+
+#[test]
+fn maps_keyed_by_ids_are_searched_with_str() {
+    let mut owners = HashMap::new();
+    owners.insert(AccountId::from("a-1"), CustomerId::from("c-1"));
+    assert_eq!(owners.get("a-1"), Some(&CustomerId::from("c-1")));
+    assert!(!owners.contains_key("c-1"));
+}
+
+#[test]
+fn bank_operations_hand_back_typed_ids() {
+    let (mut bank, alice, account) = bank_with_alice();
+    let customer = bank.get_customer(&alice).unwrap();
+    assert_eq!(customer.id, alice);
+    assert_eq!(customer.get_account_id(), Some(account.clone()));
+    assert_eq!(bank.find_customer_by_account_id(&account).unwrap().id, alice);
+
+    bank.deposit(&alice, Money::from_major(5)).unwrap();
+    let deposit = bank.get_customer(&alice).unwrap().get_account().unwrap().transactions.last().unwrap().clone();
+    let (owner, found) = bank.find_transaction(&deposit.id).unwrap();
+    assert_eq!((owner.customer_id, owner.account_id), (&alice, &account));
+    assert_eq!(found.amount, Money::from_major(5));
+}
+
+#[test]
+fn errors_carry_the_id_that_was_not_found() {
+    let (mut bank, _, _) = bank_with_alice();
+    let nobody = CustomerId::from("nobody");
+    let error = bank.deposit(&nobody, Money::from_major(1)).unwrap_err();
+    assert!(matches!(error, BankError::CustomerNotFound(id) if id == nobody));
+
+    let missing = TransactionId::from("missing");
+    let error = bank.reverse_transaction(&missing, "Duplicate").unwrap_err();
+    assert!(matches!(&error, BankError::TransactionNotFound(id) if *id == missing));
+    assert!(error.to_string().contains("missing"), "{}", error);
+}
+
+#[test]
+fn saved_banks_keep_their_string_ids() {
+    let (bank, alice, account) = bank_with_alice();
+    let json = serde_json::to_value(&bank).unwrap();
+    let customer = &json["customers"][alice.as_str()];
+    assert_eq!(customer["id"], alice.as_str());
+    assert_eq!(customer["account"]["id"], account.as_str());
+
+    let reloaded: Bank = serde_json::from_value(json).unwrap();
+    assert_eq!(reloaded.get_customer(&alice).unwrap().get_account_id(), Some(account));
+}
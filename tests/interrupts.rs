@@ -0,0 +1,137 @@
+//! Integration tests for saving the bank when the CLI is interrupted
+//!
+//! The sessions run the real binary and send it a signal with `kill`, so
+//! they only run on Unix.
+
+#![cfg(feature = "cli")]
+
+#![cfg(unix)]
+
+mod common;
+
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output, Stdio};
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+use rust_banking_system::cli::shutdown::INTERRUPTED_EXIT_CODE;
+use rust_banking_system::persistence::journal_path;
+use rust_banking_system::{JsonFileStorage, Money, Storage};
+
+use common::{command, temp_path};
+
+const LOGIN: &str = "\n20\n1\nann@example.com\n1234\n";
+
+/// A data file where Ann (PIN 1234) has $100
+fn bank_with_ann(name: &str) -> PathBuf {
+    let data_file = temp_path(name);
+    let _ = std::fs::remove_file(&data_file);
+    let _ = std::fs::remove_file(journal_path(&data_file.to_string_lossy()));
+    for args in [
+        ["register", "--name", "Ann", "--email", "ann@example.com", "--pin", "1234"].as_slice(),
+        ["create-account", "--customer", "ann@example.com", "--deposit", "100"].as_slice(),
+    ] {
+        let status = command(&data_file, args).stdin(Stdio::null()).output().unwrap().status;
+        assert!(status.success());
+    }
+    data_file
+}
+
+/// Starts a session on `data_file`, types `input` without ending it, and
+/// sends `signal` once the output contains `marker`
+fn interrupt(data_file: &Path, args: &[&str], input: &str, marker: &str, signal: &str) -> Output {
+    let mut child = command(data_file, args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    // Keep stdin open so the session is still waiting when the signal comes
+    let mut stdin = child.stdin.take().unwrap();
+    stdin.write_all(input.as_bytes()).unwrap();
+
+    let (lines, received) = mpsc::channel();
+    let stdout = BufReader::new(child.stdout.take().unwrap());
+    let reader = thread::spawn(move || {
+        for line in stdout.lines().map_while(Result::ok) {
+            let _ = lines.send(line);
+        }
+    });
+    let mut seen = String::new();
+    while !seen.contains(marker) {
+        let line = received.recv_timeout(Duration::from_secs(20)).expect("marker never printed");
+        seen.push_str(&line);
+        seen.push('\n');
+    }
+
+    let status = Command::new("kill").args(["-s", signal, &child.id().to_string()]).status().unwrap();
+    assert!(status.success());
+    let mut output = child.wait_with_output().unwrap();
+    drop(stdin);
+    reader.join().unwrap();
+    seen.extend(received.try_iter().map(|line| line + "\n"));
+    output.stdout = seen.into_bytes();
+    output
+}
+
+fn balance(data_file: &Path) -> Money {
+    let bank = JsonFileStorage::new(data_file.to_string_lossy()).load_bank().unwrap();
+    bank.find_customer_by_email("ann@example.com").unwrap().get_account().unwrap().balance
+}
+
+#[test]
+fn ctrl_c_saves_the_bank_and_says_goodbye() {
+    let data_file = bank_with_ann("sigint.json");
+    let input = format!("{}3\n5\n\n", LOGIN);
+    let output = interrupt(&data_file, &[], &input, "New Balance: $105.00", "INT");
+
+    assert_eq!(output.status.code(), Some(INTERRUPTED_EXIT_CODE));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Interrupted; saving before exiting"), "{}", stderr);
+    assert!(stderr.contains("Data saved. Goodbye!"), "{}", stderr);
+    // The deposit is in the snapshot itself: the journal was folded in
+    assert!(!Path::new(&journal_path(&data_file.to_string_lossy())).exists());
+    assert_eq!(balance(&data_file), Money::from_major(105));
+    let _ = std::fs::remove_file(&data_file);
+}
+
+#[test]
+fn sigterm_is_handled_like_ctrl_c() {
+    let data_file = bank_with_ann("sigterm.json");
+    let input = format!("{}4\n30\n\n\n\n", LOGIN);
+    let output = interrupt(&data_file, &[], &input, "New Balance: $70.00", "TERM");
+
+    assert_eq!(output.status.code(), Some(INTERRUPTED_EXIT_CODE));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Data saved"));
+    assert_eq!(balance(&data_file), Money::from_major(70));
+    let _ = std::fs::remove_file(&data_file);
+}
+
+#[test]
+fn an_interrupted_read_only_session_saves_nothing() {
+    let data_file = bank_with_ann("read-only.json");
+    let before = std::fs::read(&data_file).unwrap();
+    let input = format!("{}3\n5\n\n", LOGIN);
+    let output = interrupt(&data_file, &["--read-only"], &input, "New Balance: $105.00", "INT");
+
+    assert_eq!(output.status.code(), Some(INTERRUPTED_EXIT_CODE));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Read-only: nothing was saved"));
+    assert_eq!(std::fs::read(&data_file).unwrap(), before);
+    let _ = std::fs::remove_file(&data_file);
+}
+
+#[test]
+fn json_output_stays_json_when_interrupted() {
+    let data_file = bank_with_ann("json.json");
+    let input = format!("{}3\n5\n\n", LOGIN);
+    let output = interrupt(&data_file, &["--json"], &input, "105.00", "INT");
+
+    assert_eq!(output.status.code(), Some(INTERRUPTED_EXIT_CODE));
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        serde_json::from_str::<serde_json::Value>(line).unwrap();
+    }
+    assert_eq!(balance(&data_file), Money::from_major(105));
+    let _ = std::fs::remove_file(&data_file);
+}
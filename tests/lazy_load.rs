@@ -0,0 +1,163 @@
+//! Integration tests for loading transaction histories on demand
+
+// The tests that run the binary need the `cli` feature; without it the
+// imports and helpers only they use go unused
+#![cfg_attr(not(feature = "cli"), allow(unused))]
+
+mod common;
+
+#[cfg(feature = "cli")]
+use rust_banking_system::cli::startup::StartupArgs;
+use rust_banking_system::config::Config;
+use rust_banking_system::models::CustomerId;
+use rust_banking_system::persistence::{self, journal_path, JsonFileStorage, Storage};
+use rust_banking_system::{AccountType, Bank, BankError, Money};
+
+use common::temp_file;
+
+/// Creates a bank with two customers and some history
+fn bank_with_history() -> (Bank, CustomerId, CustomerId) {
+    let mut bank = Bank::new("Test Bank".to_string());
+    let alice = bank.register_customer("Alice".to_string(), "alice@example.com".to_string()).unwrap();
+    let bob = bank.register_customer("Bob".to_string(), "bob@example.com".to_string()).unwrap();
+    bank.create_account_for_customer(&alice, Money::from_major(100), AccountType::Checking).unwrap();
+    bank.create_account_for_customer(&bob, Money::from_major(50), AccountType::Savings).unwrap();
+    bank.transfer(&alice, &bob, Money::from_major(30)).unwrap();
+    (bank, alice, bob)
+}
+
+/// Whether the customer's transaction history has been parsed
+fn is_loaded(bank: &Bank, customer_id: &CustomerId) -> bool {
+    bank.get_customer(customer_id).unwrap().get_account().unwrap().transactions.is_loaded()
+}
+
+/// The bank as a JSON value, to compare two banks field by field
+fn as_value(bank: &Bank) -> serde_json::Value {
+    serde_json::to_value(bank).unwrap()
+}
+
+fn remove(path: &str) {
+    let _ = std::fs::remove_file(path);
+    let _ = std::fs::remove_file(journal_path(path));
+    for index in 1..=3 {
+        let _ = std::fs::remove_file(persistence::backup_path(path, index));
+    }
+}
+
+#[test]
+fn histories_are_parsed_the_first_time_they_are_used() {
+    let (bank, alice, bob) = bank_with_history();
+    let path = temp_file("first-use.json");
+    persistence::save_bank(&bank, &path).unwrap();
+
+    let lazy = persistence::load_bank_lazy(&path).unwrap();
+    assert!(!is_loaded(&lazy, &alice) && !is_loaded(&lazy, &bob));
+    // Balances live outside the history
+    assert_eq!(lazy.get_customer(&bob).unwrap().get_account().unwrap().balance, Money::from_major(80));
+    assert!(!is_loaded(&lazy, &bob));
+
+    assert_eq!(lazy.get_customer(&alice).unwrap().get_account().unwrap().transactions.len(), 2);
+    assert!(is_loaded(&lazy, &alice));
+    assert!(!is_loaded(&lazy, &bob));
+    remove(&path);
+}
+
+#[test]
+fn a_lazily_loaded_bank_is_the_same_bank() {
+    let (bank, _, _) = bank_with_history();
+    let path = temp_file("same.json.gz");
+    persistence::save_bank(&bank, &path).unwrap();
+
+    let lazy = persistence::load_bank_lazy(&path).unwrap();
+    assert_eq!(as_value(&lazy), as_value(&bank));
+    assert_eq!(as_value(&lazy), as_value(&persistence::load_bank(&path).unwrap()));
+    remove(&path);
+}
+
+#[test]
+fn saving_writes_untouched_histories_back_without_parsing_them() {
+    let (bank, alice, bob) = bank_with_history();
+    let path = temp_file("save.json");
+    persistence::save_bank(&bank, &path).unwrap();
+
+    let mut lazy = persistence::load_bank_lazy(&path).unwrap();
+    lazy.deposit(&alice, Money::from_major(5)).unwrap();
+    persistence::save_bank(&lazy, &path).unwrap();
+    assert!(is_loaded(&lazy, &alice));
+    assert!(!is_loaded(&lazy, &bob));
+
+    let reloaded = persistence::load_bank(&path).unwrap();
+    assert_eq!(reloaded.get_customer(&alice).unwrap().get_account().unwrap().transactions.len(), 3);
+    assert_eq!(
+        as_value(&reloaded)["customers"][bob.as_str()]["account"]["transactions"],
+        as_value(&bank)["customers"][bob.as_str()]["account"]["transactions"]
+    );
+    remove(&path);
+}
+
+#[test]
+fn replaying_a_journal_parses_only_the_accounts_it_touches() {
+    let (mut bank, alice, bob) = bank_with_history();
+    let path = temp_file("journal.json");
+    let storage = JsonFileStorage::new(&path).with_lazy_load(true);
+    storage.save_bank(&bank).unwrap();
+
+    bank.enable_journal();
+    bank.deposit(&alice, Money::from_major(5)).unwrap();
+    let entries = bank.take_journal();
+    storage.append_journal(&bank, &entries).unwrap();
+
+    let loaded = storage.load_bank().unwrap();
+    assert!(is_loaded(&loaded, &alice));
+    assert!(!is_loaded(&loaded, &bob));
+    assert_eq!(loaded.get_customer(&alice).unwrap().get_account().unwrap().balance, Money::from_major(75));
+    assert!(!std::path::Path::new(&journal_path(&path)).exists(), "the journal is compacted as usual");
+    remove(&path);
+}
+
+#[test]
+fn other_formats_are_loaded_in_full() {
+    let (bank, alice, _) = bank_with_history();
+    let path = temp_file("full.yaml");
+    persistence::save_bank(&bank, &path).unwrap();
+
+    let loaded = persistence::load_bank_lazy(&path).unwrap();
+    assert!(is_loaded(&loaded, &alice));
+    assert_eq!(as_value(&loaded), as_value(&bank));
+    remove(&path);
+}
+
+#[test]
+fn a_history_that_is_not_a_list_of_transactions_fails_the_load() {
+    let (bank, alice, _) = bank_with_history();
+    let path = temp_file("bad-history.json");
+    let mut value = as_value(&bank);
+    value["customers"][alice.as_str()]["account"]["transactions"] = serde_json::json!([1, 2]);
+    std::fs::write(&path, serde_json::to_vec(&value).unwrap()).unwrap();
+
+    assert!(matches!(persistence::load_bank(&path), Err(BankError::StorageCorrupted { .. })));
+    // Valid JSON, so only checking it as a list of transactions catches it
+    assert!(matches!(persistence::load_bank_lazy(&path), Err(BankError::StorageCorrupted { .. })));
+    remove(&path);
+}
+
+#[cfg(feature = "cli")]
+#[test]
+fn command_mode_loads_lazily_with_the_flag_or_the_setting() {
+    let flags = StartupArgs { lazy_load: true, ..StartupArgs::default() };
+    assert!(flags.apply(Config::default(), None).unwrap().lazy_load);
+    assert!(Config::from_toml("lazy_load = true").unwrap().lazy_load);
+
+    let data_file = temp_file("cli.json");
+    remove(&data_file);
+    let run = |args: &[&str]| common::run(&data_file, &[&["--lazy-load"], args].concat(), "\n");
+    run(&["register", "--name", "Ann", "--email", "ann@example.com", "--pin", "1234"]);
+    run(&["create-account", "--customer", "ann@example.com", "--deposit", "100"]);
+    run(&["deposit", "--customer", "ann@example.com", "--amount", "25", "--pin", "1234"]);
+
+    let json: serde_json::Value =
+        serde_json::from_str(&run(&["--json", "account", "--customer", "ann@example.com"])).unwrap();
+    assert_eq!(json["result"]["account"]["balance"], "125.00");
+    assert_eq!(json["result"]["account"]["transactions"].as_array().map(Vec::len), Some(2));
+    remove(&data_file);
+}
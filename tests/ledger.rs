@@ -0,0 +1,180 @@
+//! Integration tests for the bank-wide transaction ledger
+
+// The tests that run the binary need the `cli` feature; without it the
+// imports and helpers only they use go unused
+#![cfg_attr(not(feature = "cli"), allow(unused))]
+
+mod common;
+
+use std::sync::Arc;
+
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use rust_banking_system::clock::MockClock;
+use rust_banking_system::models::{CustomerId, Transaction, TransactionType};
+use rust_banking_system::persistence::journal_path;
+use rust_banking_system::{AccountType, Bank, Money};
+
+#[cfg(feature = "cli")]
+use common::{output, run};
+use common::temp_path;
+
+fn start() -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2024, 3, 1, 9, 0, 0).unwrap()
+}
+
+/// A bank on a mock clock where Alice and Bob open accounts an hour
+/// apart, then one operation happens each day; returns (bank, clock,
+/// alice, bob)
+fn busy_bank() -> (Bank, Arc<MockClock>, CustomerId, CustomerId) {
+    let clock = Arc::new(MockClock::new(start()));
+    let mut bank = Bank::new("Test Bank".to_string());
+    bank.set_clock(clock.clone());
+    let alice = bank.register_customer("Alice".to_string(), "alice@example.com".to_string()).unwrap();
+    let bob = bank.register_customer("Bob".to_string(), "bob@example.com".to_string()).unwrap();
+    bank.create_account_for_customer(&alice, Money::from_major(500), AccountType::Checking).unwrap();
+    clock.advance(Duration::hours(1));
+    bank.create_account_for_customer(&bob, Money::from_major(100), AccountType::Checking).unwrap();
+
+    clock.advance(Duration::days(1));
+    bank.deposit(&bob, Money::from_major(10)).unwrap();
+    clock.advance(Duration::days(1));
+    bank.withdraw(&alice, Money::from_major(20)).unwrap();
+    clock.advance(Duration::days(1));
+    bank.transfer(&bob, &alice, Money::from_major(30)).unwrap();
+    (bank, clock, alice, bob)
+}
+
+#[test]
+fn every_account_is_merged_in_time_order() {
+    let (bank, _, alice, bob) = busy_bank();
+    let entries: Vec<_> = bank.all_transactions().collect();
+
+    let owners: Vec<&CustomerId> = entries.iter().map(|entry| entry.customer_id).collect();
+    assert_eq!(owners[..4], [&alice, &bob, &bob, &alice]);
+    // Both sides of the transfer share a timestamp
+    assert!(owners[4..].contains(&&alice) && owners[4..].contains(&&bob));
+    assert!(entries.windows(2).all(|pair| pair[0].transaction.timestamp <= pair[1].transaction.timestamp));
+    let account = bank.get_customer(&alice).unwrap().get_account().unwrap();
+    assert_eq!(entries[0].account_id, &account.id);
+    assert_eq!(entries[0].transaction.amount, Money::from_major(500));
+}
+
+#[test]
+fn the_ledger_holds_as_many_entries_as_the_accounts() {
+    let (bank, _, _, _) = busy_bank();
+    let total: usize = bank
+        .list_customers()
+        .iter()
+        .map(|customer| customer.get_account().unwrap().transactions.len())
+        .sum();
+    assert_eq!(bank.all_transactions().count(), total);
+}
+
+#[test]
+fn an_empty_bank_has_an_empty_ledger() {
+    let mut bank = Bank::new("Test Bank".to_string());
+    assert_eq!(bank.all_transactions().count(), 0);
+
+    // Customers without an account add nothing either
+    bank.register_customer("Alice".to_string(), "alice@example.com".to_string()).unwrap();
+    assert_eq!(bank.all_transactions().count(), 0);
+}
+
+#[test]
+fn between_keeps_only_the_period() {
+    let (bank, _, alice, _) = busy_bank();
+    let from = start() + Duration::days(2);
+    let to = start() + Duration::days(3);
+
+    let entries: Vec<_> = bank.all_transactions().between(Some(from), Some(to)).collect();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].customer_id, &alice);
+    assert!(matches!(entries[0].transaction.transaction_type, TransactionType::Withdrawal));
+
+    assert_eq!(bank.all_transactions().between(Some(from), None).count(), 3);
+    assert_eq!(bank.all_transactions().between(None, Some(from)).count(), 3);
+}
+
+#[test]
+fn imported_transactions_take_their_place_by_date() {
+    let (mut bank, _, alice, _) = busy_bank();
+    // Dated before everything else, but posted last
+    let old_date = start() - Duration::days(30);
+    let old = Transaction::new_at(TransactionType::Deposit, Money::from_major(5), Money::ZERO, old_date);
+    bank.import_transactions(&alice, vec![old.clone()]).unwrap();
+
+    let first = bank.all_transactions().next().unwrap();
+    assert_eq!(first.transaction.id, old.id);
+    let last = bank.all_transactions().last().unwrap();
+    assert_eq!(last.transaction.timestamp, start() + Duration::hours(1) + Duration::days(3));
+}
+
+#[test]
+fn deleted_customers_stay_in_the_ledger() {
+    let (mut bank, _, alice, bob) = busy_bank();
+    let before = bank.all_transactions().count();
+    let balance = bank.get_customer(&bob).unwrap().get_account().unwrap().balance;
+    bank.transfer(&bob, &alice, balance).unwrap();
+    bank.delete_customer(&bob).unwrap();
+
+    assert_eq!(bank.all_transactions().count(), before + 2);
+    assert!(bank.all_transactions().any(|entry| *entry.customer_id == bob));
+}
+
+#[cfg(feature = "cli")]
+#[test]
+fn staff_page_through_the_ledger_and_customers_cannot() {
+    let data_file = temp_path("menu.json");
+    let _ = std::fs::remove_file(&data_file);
+    let _ = std::fs::remove_file(journal_path(&data_file.to_string_lossy()));
+    for (name, email) in [("Ann", "ann@example.com"), ("Ben", "ben@example.com")] {
+        run(&data_file, &["register", "--name", name, "--email", email, "--pin", "1234"], "\n");
+        run(&data_file, &["create-account", "--customer", email, "--deposit", "100"], "\n");
+    }
+    let transfer = ["transfer", "--from", "ann@example.com", "--to", "ben@example.com", "--amount", "25"];
+    run(&data_file, &[&transfer[..], &["--pin", "1234"]].concat(), "\n");
+
+    // The first staff login creates the admin
+    let text = run(&data_file, &[], "\n20\n2\nadmin\n9999\n9999\n50\n\n\n0\n");
+    assert!(text.contains("Ledger (page 1 of 1)"), "{}", text);
+    assert!(text.contains("Ann ("), "{}", text);
+    assert!(text.contains("Ben ("), "{}", text);
+    assert!(text.contains("Total (4)"), "{}", text);
+
+    let text = run(&data_file, &[], "\n20\n1\nann@example.com\n1234\n50\n0\n");
+    assert!(!text.contains("Ledger (page"), "{}", text);
+
+    // Command mode, limited to a period that has nothing
+    let output = run(&data_file, &["--json", "ledger", "--to", "2000-01-01"], "");
+    let json: serde_json::Value = serde_json::from_str(&output).unwrap();
+    assert_eq!(json["result"], serde_json::json!([]));
+    let output = run(&data_file, &["--json", "ledger", "--limit", "2"], "");
+    let json: serde_json::Value = serde_json::from_str(&output).unwrap();
+    assert_eq!(json["result"]["total"], 4);
+    assert_eq!(json["result"]["items"][0]["transaction"]["amount"], "100.00");
+    let _ = std::fs::remove_file(&data_file);
+}
+
+#[cfg(feature = "cli")]
+#[test]
+fn the_last_date_there_is_is_refused_as_an_end_date() {
+    let data_file = temp_path("last-date.json");
+    let _ = std::fs::remove_file(&data_file);
+    let _ = std::fs::remove_file(journal_path(&data_file.to_string_lossy()));
+    run(&data_file, &["register", "--name", "Ann", "--email", "ann@example.com", "--pin", "1234"], "\n");
+
+    // No midnight follows it, so a span cannot end after it
+    let last = "+262142-12-31";
+    for args in [
+        &["ledger", "--to", last][..],
+        &["search-transactions", "--customer", "ann@example.com", "--to", last],
+        &["statement", "--customer", "ann@example.com", "--from", "2024-01-01", "--to", last],
+        &["income-statement", "--to", last],
+    ] {
+        let result = output(&data_file, &[&["--json"], args].concat(), "");
+        let text = String::from_utf8_lossy(&result.stdout);
+        assert!(!result.status.success(), "{:?}", args);
+        assert!(text.contains("invalid_input") && text.contains("no day follows"), "{:?}: {}", args, text);
+    }
+    let _ = std::fs::remove_file(&data_file);
+}
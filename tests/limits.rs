@@ -0,0 +1,82 @@
+//! Integration tests for per-account withdrawal and transfer limits
+
+mod common;
+
+use rust_banking_system::models::{AccountLimits, Limit};
+use rust_banking_system::{Bank, BankError, Money};
+
+use common::bank_with_customers;
+
+#[test]
+fn accounts_start_without_limits() {
+    let (mut bank, alice, _) = bank_with_customers(1_000, 0);
+    assert_eq!(bank.get_customer(&alice).unwrap().get_account().unwrap().limits, AccountLimits::default());
+    bank.withdraw(&alice, Money::from_major(900)).unwrap();
+}
+
+#[test]
+fn single_withdrawal_limit_applies_to_withdrawals_and_transfers() {
+    let (mut bank, alice, bob) = bank_with_customers(1_000, 0);
+    let limits = AccountLimits { max_withdrawal: Some(Money::from_major(100)), ..Default::default() };
+    bank.set_limits(&alice, limits).unwrap();
+
+    match bank.withdraw(&alice, Money::from_major(101)) {
+        Err(BankError::LimitExceeded(limit)) => {
+            assert_eq!(limit, Limit::SingleWithdrawal(Money::from_major(100)))
+        }
+        other => panic!("expected LimitExceeded, got {:?}", other),
+    }
+    assert!(matches!(
+        bank.transfer(&alice, &bob, Money::from_major(150)),
+        Err(BankError::LimitExceeded(Limit::SingleWithdrawal(_)))
+    ));
+    bank.withdraw(&alice, Money::from_major(100)).unwrap();
+}
+
+#[test]
+fn daily_withdrawal_total_counts_transfers_out() {
+    let (mut bank, alice, bob) = bank_with_customers(1_000, 0);
+    let limits = AccountLimits { daily_withdrawal: Some(Money::from_major(300)), ..Default::default() };
+    bank.set_limits(&alice, limits).unwrap();
+
+    bank.withdraw(&alice, Money::from_major(200)).unwrap();
+    bank.transfer(&alice, &bob, Money::from_major(100)).unwrap();
+    assert!(matches!(
+        bank.withdraw(&alice, Money::from_major(1)),
+        Err(BankError::LimitExceeded(Limit::DailyWithdrawal(_)))
+    ));
+}
+
+#[test]
+fn daily_transfer_count_is_enforced() {
+    let (mut bank, alice, bob) = bank_with_customers(1_000, 0);
+    let limits = AccountLimits { daily_transfers: Some(2), ..Default::default() };
+    bank.set_limits(&alice, limits).unwrap();
+
+    bank.transfer(&alice, &bob, Money::from_major(1)).unwrap();
+    bank.transfer(&alice, &bob, Money::from_major(1)).unwrap();
+    assert!(matches!(
+        bank.transfer(&alice, &bob, Money::from_major(1)),
+        Err(BankError::LimitExceeded(Limit::DailyTransfers(2)))
+    ));
+    // Plain withdrawals are not transfers
+    bank.withdraw(&alice, Money::from_major(1)).unwrap();
+}
+
+#[test]
+fn limits_must_be_positive_and_survive_replay() {
+    let (mut bank, alice, _) = bank_with_customers(1_000, 0);
+    let zero = AccountLimits { max_withdrawal: Some(Money::ZERO), ..Default::default() };
+    assert!(matches!(bank.set_limits(&alice, zero), Err(BankError::InvalidAmount(_))));
+
+    bank.enable_journal();
+    let snapshot = serde_json::to_string(&bank).unwrap();
+    let limits = AccountLimits { daily_transfers: Some(5), ..Default::default() };
+    bank.set_limits(&alice, limits.clone()).unwrap();
+
+    let mut restored: Bank = serde_json::from_str(&snapshot).unwrap();
+    for event in bank.take_journal() {
+        restored.apply_event(event).unwrap();
+    }
+    assert_eq!(restored.get_customer(&alice).unwrap().get_account().unwrap().limits, limits);
+}
@@ -0,0 +1,93 @@
+//! Integration tests for finding customers by email, account ID or ID prefix
+
+use rust_banking_system::bank::PrefixMatch;
+use rust_banking_system::models::{AccountId, CustomerId};
+use rust_banking_system::{AccountType, Bank, BankError, Money};
+
+/// Creates a bank with Alice (who has an account) and Bob; returns
+/// (bank, alice, alice's account ID, bob)
+fn bank_with_one_account() -> (Bank, CustomerId, AccountId, CustomerId) {
+    let mut bank = Bank::new("Test Bank".to_string());
+    let alice = bank
+        .register_customer("Alice".to_string(), "alice@example.com".to_string())
+        .unwrap();
+    let bob = bank
+        .register_customer("Bob".to_string(), "bob@example.com".to_string())
+        .unwrap();
+    let account = bank
+        .create_account_for_customer(&alice, Money::ZERO, AccountType::Checking)
+        .unwrap();
+    (bank, alice, account, bob)
+}
+
+#[test]
+fn finds_customers_by_normalized_email() {
+    let (bank, alice, _, _) = bank_with_one_account();
+    assert_eq!(bank.find_customer_by_email(" Alice@Example.COM ").unwrap().id, alice);
+    assert!(bank.find_customer_by_email("carol@example.com").is_none());
+}
+
+#[test]
+fn resolves_any_kind_of_reference() {
+    let (bank, alice, account, bob) = bank_with_one_account();
+
+    assert_eq!(bank.resolve_customer(alice.as_str()).unwrap(), alice);
+    assert_eq!(bank.resolve_customer("bob@example.com").unwrap(), bob);
+    assert_eq!(bank.resolve_customer(account.as_str()).unwrap(), alice);
+    assert_eq!(bank.resolve_customer(bob.short()).unwrap(), bob);
+}
+
+#[test]
+fn unknown_and_empty_references_are_not_found() {
+    let (bank, ..) = bank_with_one_account();
+    assert!(matches!(bank.resolve_customer("nobody"), Err(BankError::CustomerNotFound(_))));
+    assert!(matches!(bank.resolve_customer("  "), Err(BankError::CustomerNotFound(_))));
+}
+
+#[test]
+fn ambiguous_prefixes_are_rejected() {
+    let mut bank = Bank::new("Test Bank".to_string());
+    for i in 0..40 {
+        bank.register_customer(format!("Customer {}", i), format!("c{}@example.com", i))
+            .unwrap();
+    }
+
+    // With 40 customers some two IDs share their first hex digit
+    let ids: Vec<String> = bank.list_customers().iter().map(|c| c.id.to_string()).collect();
+    let shared = ids
+        .iter()
+        .map(|id| &id[..1])
+        .find(|prefix| ids.iter().filter(|id| id.starts_with(prefix)).count() > 1)
+        .unwrap();
+    assert!(matches!(bank.resolve_customer(shared), Err(BankError::InvalidInput(_))));
+
+    let PrefixMatch::Ambiguous(matches) = bank.resolve_customer_prefix(shared) else {
+        panic!("expected several matches for '{}'", shared);
+    };
+    let expected = ids.iter().filter(|id| id.starts_with(shared)).count();
+    assert_eq!(matches.len(), expected);
+    assert!(matches.windows(2).all(|w| w[0].registered_at <= w[1].registered_at));
+}
+
+#[test]
+fn prefixes_match_customer_or_account_ids() {
+    let (bank, alice, account, bob) = bank_with_one_account();
+
+    assert!(matches!(bank.resolve_customer_prefix(bob.short()), PrefixMatch::Unique(c) if c.id == bob));
+    assert!(matches!(
+        bank.resolve_customer_prefix(&account.short().to_uppercase()),
+        PrefixMatch::Unique(c) if c.id == alice
+    ));
+    assert!(matches!(bank.resolve_customer_prefix("zzz"), PrefixMatch::NotFound));
+    assert!(matches!(bank.resolve_customer_prefix(""), PrefixMatch::NotFound));
+}
+
+#[test]
+fn deleted_customers_do_not_resolve() {
+    let (mut bank, alice, account, _) = bank_with_one_account();
+    bank.delete_customer(&alice).unwrap();
+
+    assert!(bank.find_customer_by_email("alice@example.com").is_none());
+    assert!(matches!(bank.resolve_customer(account.as_str()), Err(BankError::CustomerNotFound(_))));
+    assert!(matches!(bank.resolve_customer(alice.short()), Err(BankError::CustomerNotFound(_))));
+}
@@ -0,0 +1,90 @@
+//! Integration tests for transaction descriptions (memos)
+
+mod common;
+
+use rust_banking_system::models::{CustomerId, Transaction, TransactionDetails};
+use rust_banking_system::persistence;
+use rust_banking_system::validation::MAX_DESCRIPTION_LEN;
+use rust_banking_system::{Account, AccountType, Bank, BankError, Money};
+
+use common::{bank_with_customers, temp_path};
+
+fn memo(text: &str) -> TransactionDetails {
+    TransactionDetails::new().description(text).unwrap()
+}
+
+fn last_transaction<'a>(bank: &'a Bank, customer_id: &CustomerId) -> &'a Transaction {
+    bank.get_customer(customer_id).unwrap().get_account().unwrap().transactions.last().unwrap()
+}
+
+#[test]
+fn descriptions_are_trimmed_and_checked() {
+    assert_eq!(memo("  salary ").description.as_deref(), Some("salary"));
+
+    let too_long = "x".repeat(MAX_DESCRIPTION_LEN + 1);
+    for bad in ["", "   ", "two\nlines", too_long.as_str()] {
+        assert!(
+            matches!(TransactionDetails::new().description(bad), Err(BankError::ValidationError { .. })),
+            "{:?} was accepted",
+            bad
+        );
+    }
+}
+
+#[test]
+fn deposits_and_withdrawals_keep_their_description() {
+    let (mut bank, alice, _) = bank_with_customers(1_000, 0);
+
+    bank.deposit_with(&alice, Money::from_major(2_500), memo("salary")).unwrap();
+    let deposit = last_transaction(&bank, &alice);
+    assert_eq!(deposit.description.as_deref(), Some("salary"));
+    assert!(deposit.to_string().ends_with("\"salary\""), "{}", deposit);
+
+    bank.withdraw_with(&alice, Money::from_major(20), memo("cinema")).unwrap();
+    assert_eq!(last_transaction(&bank, &alice).description.as_deref(), Some("cinema"));
+
+    // The plain operations leave it empty
+    bank.deposit(&alice, Money::from_major(1)).unwrap();
+    assert_eq!(last_transaction(&bank, &alice).description, None);
+}
+
+#[test]
+fn transfers_show_the_description_on_both_sides() {
+    let (mut bank, alice, bob) = bank_with_customers(1_000, 0);
+    bank.transfer_with(&alice, &bob, Money::from_major(300), memo("March rent"))
+        .unwrap();
+
+    assert_eq!(last_transaction(&bank, &alice).description.as_deref(), Some("March rent"));
+    assert_eq!(last_transaction(&bank, &bob).description.as_deref(), Some("March rent"));
+}
+
+#[test]
+fn accounts_take_descriptions_directly() {
+    let mut account = Account::new(Money::ZERO, AccountType::Savings).unwrap();
+    account.deposit_with(Money::from_major(50), memo("birthday")).unwrap();
+    account.withdraw_with(Money::from_major(5), memo("snacks")).unwrap();
+
+    let descriptions: Vec<_> = account.transactions.iter().map(|tx| tx.description.as_deref()).collect();
+    assert_eq!(descriptions, vec![Some("birthday"), Some("snacks")]);
+}
+
+#[test]
+fn descriptions_survive_csv_and_older_files_still_load() {
+    let (mut bank, alice, _) = bank_with_customers(1_000, 0);
+    bank.deposit_with(&alice, Money::from_major(10), memo("refund, shoes")).unwrap();
+
+    let path = temp_path("memos.csv");
+    let path = path.to_str().unwrap();
+    persistence::export_transactions_csv(bank.get_customer(&alice).unwrap().get_account().unwrap(), path)
+        .unwrap();
+    let imported = persistence::import_transactions_csv(path).unwrap();
+    std::fs::remove_file(path).unwrap();
+    assert_eq!(imported[1].description.as_deref(), Some("refund, shoes"));
+    assert_eq!(imported[0].description, None);
+
+    // Transactions saved before descriptions existed have no such field
+    let mut json = serde_json::to_value(last_transaction(&bank, &alice)).unwrap();
+    json.as_object_mut().unwrap().remove("description");
+    let old: Transaction = serde_json::from_value(json).unwrap();
+    assert_eq!(old.description, None);
+}
@@ -0,0 +1,92 @@
+//! Integration tests for the metrics registry and exporters
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use rust_banking_system::metrics::{JsonExporter, Metrics, MetricsExporter, PrometheusExporter};
+use rust_banking_system::models::CustomerId;
+use rust_banking_system::{AccountType, Bank, BankError, Money};
+
+/// A bank with Alice ($100) and Bob (empty) and metrics observing it;
+/// returns (bank, metrics, alice, bob)
+fn observed_bank() -> (Bank, Arc<Metrics>, CustomerId, CustomerId) {
+    let mut bank = Bank::new("Test Bank".to_string());
+    let metrics = Arc::new(Metrics::new());
+    bank.add_observer(metrics.clone());
+    let alice = bank.register_customer("Alice".to_string(), "alice@example.com".to_string()).unwrap();
+    let bob = bank.register_customer("Bob".to_string(), "bob@example.com".to_string()).unwrap();
+    bank.create_account_for_customer(&alice, Money::from_major(100), AccountType::Checking).unwrap();
+    bank.create_account_for_customer(&bob, Money::ZERO, AccountType::Checking).unwrap();
+    (bank, metrics, alice, bob)
+}
+
+#[test]
+fn transactions_are_counted_by_type() {
+    let (mut bank, metrics, alice, bob) = observed_bank();
+    bank.deposit(&alice, Money::from_major(10)).unwrap();
+    bank.deposit(&bob, Money::from_major(10)).unwrap();
+    bank.withdraw(&alice, Money::from_major(5)).unwrap();
+    bank.transfer(&alice, &bob, Money::from_major(5)).unwrap();
+    assert!(bank.withdraw(&bob, Money::from_major(1_000)).is_err());
+
+    let snapshot = metrics.snapshot(&bank);
+    assert_eq!(snapshot.transactions["deposit"], 2);
+    assert_eq!(snapshot.transactions["withdrawal"], 1);
+    assert_eq!(snapshot.transactions["transfer"], 1);
+    assert_eq!(snapshot.total_balance, Money::from_major(115));
+    assert_eq!(snapshot.customers, 2);
+}
+
+#[test]
+fn timed_operations_record_latency_and_errors() {
+    let (mut bank, metrics, alice, _) = observed_bank();
+
+    metrics.time("withdraw", || bank.withdraw(&alice, Money::from_major(1))).unwrap();
+    let failed = metrics.time("withdraw", || bank.withdraw(&alice, Money::from_major(1_000)));
+    assert!(matches!(failed, Err(BankError::InsufficientFunds { .. })));
+    metrics.observe("withdraw", Duration::from_secs(10));
+
+    let snapshot = metrics.snapshot(&bank);
+    assert_eq!(snapshot.errors["insufficient_funds"], 1);
+    let histogram = &snapshot.latencies["withdraw"];
+    assert_eq!(histogram.count, 3);
+    // Buckets are cumulative; the 10s observation is only in +Inf
+    assert_eq!(histogram.buckets.last().unwrap().1, 2);
+    assert!(histogram.sum >= 10.0);
+}
+
+#[test]
+fn prometheus_exporter_writes_the_text_format() {
+    let (mut bank, metrics, alice, _) = observed_bank();
+    bank.deposit(&alice, Money::from_major(10)).unwrap();
+    metrics.record_error(&BankError::CustomerNotFound("nobody".into()));
+    metrics.observe("deposit", Duration::from_millis(3));
+
+    let text = PrometheusExporter.export(&metrics.snapshot(&bank));
+
+    for line in [
+        "# TYPE bank_transactions_total counter",
+        "bank_transactions_total{type=\"deposit\"} 1",
+        "bank_errors_total{error=\"customer_not_found\"} 1",
+        "# TYPE bank_total_balance gauge",
+        "bank_total_balance 110.00",
+        "# TYPE bank_operation_duration_seconds histogram",
+        "bank_operation_duration_seconds_bucket{operation=\"deposit\",le=\"0.001\"} 0",
+        "bank_operation_duration_seconds_bucket{operation=\"deposit\",le=\"0.005\"} 1",
+        "bank_operation_duration_seconds_bucket{operation=\"deposit\",le=\"+Inf\"} 1",
+        "bank_operation_duration_seconds_count{operation=\"deposit\"} 1",
+    ] {
+        assert!(text.lines().any(|l| l == line), "missing {:?} in\n{}", line, text);
+    }
+}
+
+#[test]
+fn json_exporter_writes_the_snapshot() {
+    let (bank, metrics, _, _) = observed_bank();
+    let exporter = JsonExporter;
+    assert_eq!(exporter.content_type(), "application/json");
+
+    let json: serde_json::Value = serde_json::from_str(&exporter.export(&metrics.snapshot(&bank))).unwrap();
+    assert_eq!(json["total_balance"], "100.00");
+    assert_eq!(json["customers"], 2);
+}
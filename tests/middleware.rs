@@ -0,0 +1,237 @@
+//! Integration tests for the operation pipeline
+
+mod common;
+
+use std::sync::{Arc, Mutex};
+
+use rust_banking_system::bank::{BatchMode, Operation};
+use rust_banking_system::middleware::{Fees, OperationKind, OperationMiddleware, OperationRequest, Pipeline};
+use rust_banking_system::models::{Alert, AlertRules, FraudReason, FraudRules};
+use rust_banking_system::{Bank, BankError, BankResult, Money, Notifier, Transaction, TransactionType};
+
+use common::bank_with_customers;
+
+/// Records each stage's hooks, in order
+#[derive(Debug)]
+struct Recorder {
+    name: &'static str,
+    calls: Arc<Mutex<Vec<String>>>,
+}
+
+impl OperationMiddleware for Recorder {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn before(&self, _bank: &Bank, request: &mut OperationRequest) -> BankResult<()> {
+        self.calls.lock().unwrap().push(format!("{} before {:?}", self.name, request.kind));
+        Ok(())
+    }
+
+    fn after(&self, _bank: &Bank, _request: &OperationRequest, posted: &[Transaction]) {
+        self.calls.lock().unwrap().push(format!("{} after {}", self.name, posted.len()));
+    }
+}
+
+/// Refuses every withdrawal
+#[derive(Debug)]
+struct NoWithdrawals;
+
+impl OperationMiddleware for NoWithdrawals {
+    fn name(&self) -> &str {
+        "no-withdrawals"
+    }
+
+    fn before(&self, _bank: &Bank, request: &mut OperationRequest) -> BankResult<()> {
+        if request.kind == OperationKind::Withdrawal {
+            return Err(BankError::InvalidInput("withdrawals are closed".to_string()));
+        }
+        Ok(())
+    }
+}
+
+/// Counts the alerts it is sent
+#[derive(Debug, Default)]
+struct AlertCount(Mutex<usize>);
+
+impl Notifier for AlertCount {
+    fn notify(&self, _alert: &Alert) -> BankResult<()> {
+        *self.0.lock().unwrap() += 1;
+        Ok(())
+    }
+}
+
+#[test]
+fn test_standard_pipeline_runs_from_validation_to_notification() {
+    let (bank, _, _) = bank_with_customers(100, 50);
+    assert_eq!(bank.pipeline().names(), vec!["validation", "limits", "fees", "fraud-check", "notify"]);
+}
+
+#[test]
+fn test_stages_run_in_order_around_the_operation() {
+    let (mut bank, alice, bob) = bank_with_customers(100, 50);
+    let calls = Arc::new(Mutex::new(Vec::new()));
+    bank.pipeline_mut().push(Arc::new(Recorder { name: "first", calls: calls.clone() }));
+    bank.pipeline_mut().push(Arc::new(Recorder { name: "second", calls: calls.clone() }));
+
+    bank.transfer(&alice, &bob, Money::from_major(10)).unwrap();
+
+    assert_eq!(
+        *calls.lock().unwrap(),
+        vec!["first before Transfer", "second before Transfer", "first after 2", "second after 2"]
+    );
+}
+
+#[test]
+fn test_a_refusal_stops_the_operation_and_later_stages() {
+    let (mut bank, alice, _) = bank_with_customers(100, 50);
+    let calls = Arc::new(Mutex::new(Vec::new()));
+    bank.pipeline_mut().insert_before("validation", Arc::new(NoWithdrawals));
+    bank.pipeline_mut().push(Arc::new(Recorder { name: "last", calls: calls.clone() }));
+
+    let result = bank.withdraw(&alice, Money::from_major(10));
+
+    assert!(matches!(result, Err(BankError::InvalidInput(_))));
+    assert_eq!(bank.get_customer(&alice).unwrap().get_account().unwrap().balance, Money::from_major(100));
+    assert!(calls.lock().unwrap().is_empty());
+    assert!(bank.deposit(&alice, Money::from_major(5)).is_ok());
+}
+
+#[test]
+fn test_fees_are_posted_after_the_operation_and_undone_with_it() {
+    let (mut bank, alice, bob) = bank_with_customers(100, 50);
+    bank.pipeline_mut().push(Arc::new(Fees { withdrawal: Money::from_major(2), transfer: Money::from_major(3) }));
+
+    assert_eq!(bank.withdraw(&alice, Money::from_major(10)).unwrap(), Money::from_major(88));
+    bank.transfer(&alice, &bob, Money::from_major(20)).unwrap();
+
+    let account = bank.get_customer(&alice).unwrap().get_account().unwrap();
+    assert_eq!(account.balance, Money::from_major(65));
+    let last = account.transactions.last().unwrap();
+    assert!(matches!(last.transaction_type, TransactionType::Fee));
+    assert_eq!(last.amount, Money::from_major(3));
+
+    let reversals = bank.undo_last().unwrap();
+    assert_eq!(reversals.len(), 3);
+    assert_eq!(bank.get_customer(&alice).unwrap().get_account().unwrap().balance, Money::from_major(88));
+    assert_eq!(bank.get_customer(&bob).unwrap().get_account().unwrap().balance, Money::from_major(50));
+}
+
+#[test]
+fn test_a_fee_the_balance_cannot_cover_refuses_the_operation() {
+    let (mut bank, alice, _) = bank_with_customers(100, 50);
+    bank.pipeline_mut().push(Arc::new(Fees { withdrawal: Money::from_major(1), ..Fees::default() }));
+
+    let result = bank.withdraw(&alice, Money::from_major(100));
+
+    assert!(matches!(result, Err(BankError::InsufficientFunds { .. })));
+    assert_eq!(bank.get_customer(&alice).unwrap().get_account().unwrap().transactions.len(), 1);
+}
+
+#[test]
+fn test_an_empty_pipeline_keeps_the_bookkeeping_checks() {
+    let (mut bank, alice, bob) = bank_with_customers(100, 50);
+    bank.set_pipeline(Pipeline::empty());
+
+    assert!(matches!(bank.withdraw(&alice, Money::from_major(500)), Err(BankError::InsufficientFunds { .. })));
+    assert!(matches!(bank.transfer(&alice, &alice, Money::from_major(5)), Err(BankError::InvalidTransfer(_))));
+    assert!(matches!(bank.deposit(&bob, Money::ZERO), Err(BankError::InvalidAmount(_))));
+}
+
+#[test]
+fn test_removing_a_stage() {
+    let (mut bank, _, _) = bank_with_customers(100, 50);
+    assert!(bank.pipeline_mut().remove("limits").is_some());
+    assert!(bank.pipeline_mut().remove("limits").is_none());
+    assert_eq!(bank.pipeline().names(), vec!["validation", "fees", "fraud-check", "notify"]);
+}
+
+#[test]
+fn test_replacing_the_standard_fees() {
+    let (mut bank, alice, _) = bank_with_customers(100, 50);
+    let fees = Arc::new(Fees { withdrawal: Money::from_major(2), ..Fees::default() });
+    assert!(bank.pipeline_mut().replace("fees", fees).is_some());
+
+    assert_eq!(bank.pipeline().names(), vec!["validation", "limits", "fees", "fraud-check", "notify"]);
+    assert_eq!(bank.withdraw(&alice, Money::from_major(10)).unwrap(), Money::from_major(88));
+}
+
+#[test]
+fn test_only_the_fraud_check_stage_flags_operations() {
+    let (mut bank, alice, bob) = bank_with_customers(100, 50);
+    let rules = FraudRules { new_payee_transfer: Some(Money::from_major(5)), ..FraudRules::default() };
+    bank.set_fraud_rules(rules).unwrap();
+
+    let fraud_check = bank.pipeline_mut().remove("fraud-check").unwrap();
+    bank.transfer(&alice, &bob, Money::from_major(10)).unwrap();
+    assert!(bank.review_queue().is_empty());
+
+    bank.pipeline_mut().insert_before("notify", fraud_check);
+    bank.transfer(&bob, &alice, Money::from_major(10)).unwrap();
+    let debit = bank.get_customer(&bob).unwrap().get_account().unwrap().transactions.last().unwrap().clone();
+    let queue = bank.review_queue();
+    assert_eq!(queue.len(), 1);
+    assert_eq!(queue[0].customer_id, bob);
+    assert_eq!(queue[0].transaction_id, debit.id);
+}
+
+#[test]
+fn test_a_stage_can_mark_an_operation_for_review() {
+    /// Flags every deposit as if it broke the new-payee rule
+    #[derive(Debug)]
+    struct SuspiciousDeposits;
+
+    impl OperationMiddleware for SuspiciousDeposits {
+        fn name(&self) -> &str {
+            "suspicious-deposits"
+        }
+
+        fn before(&self, _bank: &Bank, request: &mut OperationRequest) -> BankResult<()> {
+            if request.kind == OperationKind::Deposit {
+                request.flag = Some(FraudReason::NewPayeeTransfer {
+                    payee: "cash".to_string(),
+                    amount: request.amount,
+                    threshold: Money::ZERO,
+                });
+            }
+            Ok(())
+        }
+    }
+
+    let (mut bank, alice, _) = bank_with_customers(100, 50);
+    bank.pipeline_mut().insert_before("fraud-check", Arc::new(SuspiciousDeposits));
+    bank.deposit(&alice, Money::from_major(10)).unwrap();
+
+    assert_eq!(bank.review_queue().len(), 1);
+}
+
+#[test]
+fn test_only_the_notify_stage_sends_operation_alerts() {
+    let (mut bank, alice, _) = bank_with_customers(100, 50);
+    let count = Arc::new(AlertCount::default());
+    bank.add_notifier(count.clone());
+    bank.set_alert_rules(&alice, AlertRules { large_transaction: Some(Money::from_major(5)), ..AlertRules::default() })
+        .unwrap();
+
+    bank.withdraw(&alice, Money::from_major(10)).unwrap();
+    assert_eq!(*count.0.lock().unwrap(), 1);
+
+    bank.pipeline_mut().remove("notify");
+    bank.withdraw(&alice, Money::from_major(10)).unwrap();
+    assert_eq!(*count.0.lock().unwrap(), 1);
+}
+
+#[test]
+fn test_after_hooks_wait_for_an_all_or_nothing_batch_to_commit() {
+    let (mut bank, alice, _) = bank_with_customers(100, 50);
+    let calls = Arc::new(Mutex::new(Vec::new()));
+    bank.pipeline_mut().push(Arc::new(Recorder { name: "last", calls: calls.clone() }));
+    let withdraw = |amount| Operation::Withdraw { customer: alice.to_string(), amount, description: None };
+
+    bank.apply_batch(vec![withdraw(Money::from_major(10)), withdraw(Money::from_major(500))], BatchMode::AllOrNothing);
+    assert_eq!(*calls.lock().unwrap(), vec!["last before Withdrawal", "last before Withdrawal"]);
+
+    calls.lock().unwrap().clear();
+    bank.apply_batch(vec![withdraw(Money::from_major(10))], BatchMode::AllOrNothing);
+    assert_eq!(*calls.lock().unwrap(), vec!["last before Withdrawal", "last after 1"]);
+}
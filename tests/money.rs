@@ -0,0 +1,95 @@
+//! Integration tests for overflow-safe money
+
+mod common;
+
+use rust_banking_system::models::CustomerId;
+use rust_banking_system::{AccountType, Bank, BankError, JsonFileStorage, Money, Storage};
+
+use common::temp_file;
+
+/// A bank whose only customer holds `balance`; returns (bank, customer)
+fn bank_holding(balance: Money) -> (Bank, CustomerId) {
+    let mut bank = Bank::new("Test Bank".to_string());
+    let alice = bank.register_customer("Alice".to_string(), "alice@example.com".to_string()).unwrap();
+    bank.create_account_for_customer(&alice, balance, AccountType::Checking).unwrap();
+    (bank, alice)
+}
+
+#[test]
+fn checked_arithmetic_reports_overflow() {
+    let top = Money::from_cents(i64::MAX);
+    assert_eq!(top.checked_add(Money::from_cents(1)), None);
+    assert_eq!(Money::from_cents(i64::MIN).checked_sub(Money::from_cents(1)), None);
+    assert_eq!(Money::from_major(2).checked_add(Money::from_major(3)), Some(Money::from_major(5)));
+    assert_eq!(Money::from_major(2).checked_sub(Money::from_major(3)), Some(Money::from_major(-1)));
+}
+
+#[test]
+fn amounts_beyond_the_maximum_do_not_parse() {
+    assert_eq!("999999999999.99".parse::<Money>().unwrap(), Money::MAX);
+    assert_eq!("-999999999999.99".parse::<Money>().unwrap(), -Money::MAX);
+    assert!("1000000000000".parse::<Money>().is_err());
+    assert!("92233720368547758".parse::<Money>().is_err());
+    assert!("-92233720368547758".parse::<Money>().is_err());
+}
+
+#[test]
+fn a_deposit_that_would_overflow_the_balance_is_refused() {
+    let (mut bank, alice) = bank_holding(Money::from_cents(i64::MAX - 50));
+
+    let result = bank.deposit(&alice, Money::from_major(1));
+
+    assert!(matches!(result, Err(BankError::InvalidAmount(amount)) if amount == Money::from_major(1)));
+    let account = bank.get_customer(&alice).unwrap().get_account().unwrap();
+    assert_eq!(account.balance, Money::from_cents(i64::MAX - 50));
+    assert_eq!(account.transactions.len(), 1);
+}
+
+#[test]
+fn cash_that_would_overflow_the_vault_is_refused() {
+    let (mut bank, _) = bank_holding(Money::from_cents(i64::MAX - 50));
+
+    assert!(matches!(bank.vault_top_up(Money::from_major(1)), Err(BankError::InvalidAmount(_))));
+    let bob = bank.register_customer("Bob".to_string(), "bob@example.com".to_string()).unwrap();
+    let result = bank.create_account_for_customer(&bob, Money::from_major(1), AccountType::Checking);
+    assert!(matches!(result, Err(BankError::InvalidAmount(_))));
+    assert_eq!(bank.vault(), Some(Money::from_cents(i64::MAX - 50)));
+}
+
+#[test]
+fn a_transfer_that_would_overflow_the_recipient_changes_nothing() {
+    let (mut bank, alice) = bank_holding(Money::from_cents(i64::MAX - 50));
+    // Make room in the vault for Bob's opening deposit
+    bank.vault_withdraw(Money::from_cents(i64::MAX - 100)).unwrap();
+    let bob = bank.register_customer("Bob".to_string(), "bob@example.com".to_string()).unwrap();
+    bank.create_account_for_customer(&bob, Money::from_major(10), AccountType::Checking).unwrap();
+
+    let result = bank.transfer(&bob, &alice, Money::from_major(5));
+
+    assert!(matches!(result, Err(BankError::InvalidAmount(_))));
+    assert_eq!(bank.get_customer(&bob).unwrap().get_account().unwrap().balance, Money::from_major(10));
+}
+
+#[test]
+fn balances_past_the_maximum_save_and_load() {
+    let (mut bank, alice) = bank_holding(Money::MAX);
+    bank.deposit(&alice, Money::MAX).unwrap();
+    let twice = Money::MAX.checked_add(Money::MAX).unwrap();
+    let path = temp_file("past-max.json");
+    JsonFileStorage::new(&path).save_bank(&bank).unwrap();
+
+    let loaded = JsonFileStorage::new(&path).load_bank().unwrap();
+    assert_eq!(loaded.get_customer(&alice).unwrap().get_account().unwrap().balance, twice);
+    assert_eq!(loaded.vault(), Some(twice));
+    assert!(loaded.trial_balance().is_balanced());
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn an_operation_past_the_maximum_is_refused_however_it_arrives() {
+    let (mut bank, alice) = bank_holding(Money::from_major(10));
+    let past_max: Money = serde_json::from_str("\"1000000000000.00\"").unwrap();
+    assert_eq!(past_max, Money::MAX.checked_add(Money::from_cents(1)).unwrap());
+
+    assert!(matches!(bank.deposit(&alice, past_max), Err(BankError::InvalidAmount(amount)) if amount == past_max));
+}
@@ -0,0 +1,102 @@
+//! Integration tests for per-month account summaries
+
+mod common;
+
+use chrono::{TimeZone, Utc};
+use rust_banking_system::models::{AccountId, Transaction};
+use rust_banking_system::persistence;
+use rust_banking_system::{Account, AccountType, BankError, Money, TransactionType};
+
+use common::temp_path;
+
+/// Posts a transaction dated `year-month-day` and updates the balance
+fn post(account: &mut Account, kind: TransactionType, major: i64, (year, month, day): (i32, u32, u32)) {
+    let mut tx = Transaction::new(kind, Money::from_major(major), Money::ZERO);
+    account.balance += tx.signed_amount();
+    tx.balance_after = account.balance;
+    tx.timestamp = Utc.with_ymd_and_hms(year, month, day, 12, 0, 0).unwrap();
+    account.transactions.push(tx);
+}
+
+/// An account with activity in January and March 2024, none in February
+fn account_with_history() -> Account {
+    let mut account = Account::new(Money::ZERO, AccountType::Checking).unwrap();
+    let other = AccountId::from("other-account");
+
+    post(&mut account, TransactionType::Deposit, 1_000, (2024, 1, 5));
+    post(&mut account, TransactionType::Withdrawal, 200, (2024, 1, 31));
+    post(&mut account, TransactionType::TransferIn { from_account_id: other.clone() }, 300, (2024, 3, 1));
+    post(&mut account, TransactionType::Transfer { to_account_id: other }, 50, (2024, 3, 15));
+    let original_id = account.transactions.last().unwrap().id.clone();
+    post(&mut account, TransactionType::Reversal { original_id, credit: true }, 50, (2024, 3, 16));
+    post(&mut account, TransactionType::Deposit, 25, (2024, 4, 1));
+    account
+}
+
+#[test]
+fn summarizes_a_month() {
+    let account = account_with_history();
+    let summary = account.monthly_summary(2024, 1).unwrap();
+
+    assert_eq!(summary.transactions, 2);
+    assert_eq!(summary.opening_balance, Money::ZERO);
+    assert_eq!(summary.deposits, Money::from_major(1_000));
+    assert_eq!(summary.withdrawals, Money::from_major(200));
+    assert_eq!(summary.fees, Money::ZERO);
+    assert_eq!(summary.net_change, Money::from_major(800));
+    assert_eq!(summary.ending_balance, Money::from_major(800));
+}
+
+#[test]
+fn figures_reconcile_with_transfers_and_reversals() {
+    let account = account_with_history();
+    let s = account.monthly_summary(2024, 3).unwrap();
+
+    assert_eq!(s.opening_balance, Money::from_major(800));
+    assert_eq!(s.transfers_in, Money::from_major(300));
+    assert_eq!(s.transfers_out, Money::from_major(50));
+    assert_eq!(s.reversals, Money::from_major(50));
+    assert_eq!(s.ending_balance, Money::from_major(1_100));
+    assert_eq!(
+        s.opening_balance + s.deposits + s.transfers_in + s.reversals - s.withdrawals - s.transfers_out - s.fees,
+        s.ending_balance
+    );
+}
+
+#[test]
+fn quiet_months_carry_the_balance() {
+    let account = account_with_history();
+
+    let february = account.monthly_summary(2024, 2).unwrap();
+    assert_eq!(february.transactions, 0);
+    assert_eq!(february.opening_balance, Money::from_major(800));
+    assert_eq!(february.ending_balance, Money::from_major(800));
+
+    // December rolls over into the next year
+    let december = account.monthly_summary(2023, 12).unwrap();
+    assert_eq!(december.ending_balance, Money::ZERO);
+}
+
+#[test]
+fn invalid_months_are_rejected() {
+    let account = account_with_history();
+    assert!(matches!(account.monthly_summary(2024, 0), Err(BankError::InvalidInput(_))));
+    assert!(matches!(account.monthly_summary(2024, 13), Err(BankError::InvalidInput(_))));
+}
+
+#[test]
+fn exports_summaries_as_csv() {
+    let account = account_with_history();
+    let summaries: Vec<_> = (1..=3).map(|m| account.monthly_summary(2024, m).unwrap()).collect();
+
+    let path = temp_path("monthly.csv");
+    let path = path.to_str().unwrap();
+    assert_eq!(persistence::export_monthly_summaries_csv(&summaries, path).unwrap(), 3);
+
+    let csv = std::fs::read_to_string(path).unwrap();
+    std::fs::remove_file(path).unwrap();
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines.len(), 4);
+    assert!(lines[0].starts_with("account_id,year,month,opening_balance,deposits"), "{}", lines[0]);
+    assert!(lines[2].contains(",2024,2,"), "{}", lines[2]);
+}
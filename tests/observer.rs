@@ -0,0 +1,135 @@
+//! Integration tests for bank observers
+
+mod common;
+
+use std::io::{self, Write};
+use std::sync::{Arc, Mutex};
+
+use rust_banking_system::bank::{BatchMode, Operation};
+use rust_banking_system::models::CustomerId;
+use rust_banking_system::{Bank, BankObserver, Customer, LoggingObserver, Money, Transaction};
+
+use common::add_customers;
+
+/// Records which hooks were called, in order
+#[derive(Debug, Default)]
+struct Calls(Mutex<Vec<String>>);
+
+impl Calls {
+    fn take(&self) -> Vec<String> {
+        std::mem::take(&mut *self.0.lock().unwrap())
+    }
+}
+
+impl BankObserver for Calls {
+    fn on_deposit(&self, _customer_id: &CustomerId, transaction: &Transaction) {
+        self.0.lock().unwrap().push(format!("deposit {}", transaction.amount));
+    }
+
+    fn on_withdraw(&self, _customer_id: &CustomerId, transaction: &Transaction) {
+        self.0.lock().unwrap().push(format!("withdraw {}", transaction.amount));
+    }
+
+    fn on_transfer(&self, _from: &CustomerId, _to: &CustomerId, debit: &Transaction, _credit: &Transaction) {
+        self.0.lock().unwrap().push(format!("transfer {}", debit.amount));
+    }
+
+    fn on_customer_registered(&self, customer: &Customer) {
+        self.0.lock().unwrap().push(format!("register {}", customer.name));
+    }
+}
+
+/// Only cares about registrations; the other hooks keep their defaults
+#[derive(Debug, Default)]
+struct Registrations(Mutex<u32>);
+
+impl BankObserver for Registrations {
+    fn on_customer_registered(&self, _customer: &Customer) {
+        *self.0.lock().unwrap() += 1;
+    }
+}
+
+/// A writer the test can read back after handing it to the observer
+#[derive(Clone, Default)]
+struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+impl Write for SharedBuffer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn observers_hear_each_kind_of_change() {
+    let mut bank = Bank::new("Test Bank".to_string());
+    let calls = Arc::new(Calls::default());
+    let registrations = Arc::new(Registrations::default());
+    bank.add_observer(calls.clone());
+    bank.add_observer(registrations.clone());
+
+    let (alice, bob) = add_customers(&mut bank, 1_000, 0);
+    bank.deposit(&alice, Money::from_major(50)).unwrap();
+    bank.withdraw(&alice, Money::from_major(20)).unwrap();
+    bank.transfer(&alice, &bob, Money::from_major(30)).unwrap();
+
+    assert_eq!(
+        calls.take(),
+        ["register Alice", "register Bob", "deposit 50.00", "withdraw 20.00", "transfer 30.00"]
+    );
+    assert_eq!(*registrations.0.lock().unwrap(), 2);
+}
+
+#[test]
+fn failed_and_rolled_back_changes_are_not_observed() {
+    let mut bank = Bank::new("Test Bank".to_string());
+    let (alice, bob) = add_customers(&mut bank, 1_000, 0);
+    let calls = Arc::new(Calls::default());
+    bank.add_observer(calls.clone());
+
+    assert!(bank.withdraw(&bob, Money::from_major(1)).is_err());
+    let deposit = Operation::Deposit { customer: alice.to_string(), amount: Money::from_major(5), description: None };
+    let overdraw = Operation::Withdraw { customer: bob.to_string(), amount: Money::from_major(5), description: None };
+    bank.apply_batch(vec![deposit.clone(), overdraw], BatchMode::AllOrNothing);
+    assert!(calls.take().is_empty());
+
+    bank.apply_batch(vec![deposit], BatchMode::AllOrNothing);
+    assert_eq!(calls.take(), ["deposit 5.00"]);
+}
+
+#[test]
+fn replaying_the_journal_is_not_observed() {
+    let mut bank = Bank::new("Test Bank".to_string());
+    bank.enable_journal();
+    let (alice, _) = add_customers(&mut bank, 1_000, 0);
+    bank.deposit(&alice, Money::from_major(5)).unwrap();
+
+    let mut restored = Bank::new("Test Bank".to_string());
+    let calls = Arc::new(Calls::default());
+    restored.add_observer(calls.clone());
+    for entry in bank.take_journal() {
+        restored.apply_journal_entry(entry).unwrap();
+    }
+    assert!(calls.take().is_empty());
+}
+
+#[test]
+fn logging_observer_writes_one_line_per_change() {
+    let mut bank = Bank::new("Test Bank".to_string());
+    let buffer = SharedBuffer::default();
+    bank.add_observer(Arc::new(LoggingObserver::with_writer(buffer.clone())));
+
+    let (alice, bob) = add_customers(&mut bank, 1_000, 0);
+    bank.deposit(&alice, Money::from_major(50)).unwrap();
+    bank.transfer(&alice, &bob, Money::from_major(25)).unwrap();
+
+    let log = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+    let lines: Vec<_> = log.lines().collect();
+    assert_eq!(lines.len(), 4);
+    assert!(lines[0].contains(&format!("register customer={} email=alice@example.com", alice)));
+    assert!(lines[2].contains(&format!("deposit customer={} amount=50.00 balance=1050.00", alice)));
+    assert!(lines[3].contains(&format!("transfer from={} to={} amount=25.00", alice, bob)));
+}
@@ -0,0 +1,136 @@
+//! Integration tests for importing customers from a CSV file
+
+// The tests that run the binary need the `cli` feature; without it the
+// imports and helpers only they use go unused
+#![cfg_attr(not(feature = "cli"), allow(unused))]
+
+mod common;
+
+use rust_banking_system::persistence::journal_path;
+use rust_banking_system::{AccountType, Bank, BankError, Money};
+
+use common::temp_path;
+
+fn balance_of(bank: &Bank, email: &str) -> Money {
+    let id = bank.find_customer_by_email(email).unwrap().id.clone();
+    bank.get_customer(&id).unwrap().get_account().unwrap().balance
+}
+
+#[test]
+fn every_valid_row_gets_a_customer_and_a_checking_account() {
+    let mut bank = Bank::new("Test Bank".to_string());
+    let csv = "name,email,initial_deposit\nAlice,alice@example.com,100\nBob,bob@example.com,25.50\n";
+
+    let import = bank.import_customers(csv.as_bytes()).unwrap();
+
+    assert_eq!(import.rows.len(), 2);
+    assert_eq!(import.imported(), 2);
+    assert_eq!(import.failed(), 0);
+    assert_eq!(import.total_deposited(), "125.50".parse().unwrap());
+    let alice = bank.get_customer(import.rows[0].result.as_ref().unwrap()).unwrap();
+    assert_eq!(alice.name, "Alice");
+    assert_eq!(alice.get_account().unwrap().account_type, AccountType::Checking);
+    assert_eq!(balance_of(&bank, "bob@example.com"), "25.50".parse().unwrap());
+}
+
+#[test]
+fn bad_rows_are_reported_with_their_line_and_skipped() {
+    let mut bank = Bank::new("Test Bank".to_string());
+    let csv = "name,email,initial_deposit\n\
+               Alice,alice@example.com,100\n\
+               Bob,not-an-email,10\n\
+               ,carol@example.com,10\n\
+               Dave,dave@example.com,ten\n\
+               Erin,erin@example.com,-5\n\
+               Fay,fay@example.com,20\n";
+
+    let import = bank.import_customers(csv.as_bytes()).unwrap();
+
+    assert_eq!(import.imported(), 2);
+    assert_eq!(import.failed(), 4);
+    let failed: Vec<usize> = import.rows.iter().filter(|row| row.result.is_err()).map(|row| row.line).collect();
+    assert_eq!(failed, vec![3, 4, 5, 6]);
+    assert!(matches!(import.rows[1].result, Err(BankError::ValidationError { .. })));
+    assert!(matches!(import.rows[3].result, Err(BankError::InvalidInput(_))));
+    assert!(matches!(import.rows[4].result, Err(BankError::InvalidAmount(_))));
+    // A skipped row never leaves a customer behind
+    assert_eq!(bank.list_customers().len(), 2);
+    assert_eq!(import.total_deposited(), Money::from_major(120));
+}
+
+#[test]
+fn emails_already_in_use_fail_their_row() {
+    let mut bank = Bank::new("Test Bank".to_string());
+    bank.register_customer("Alice".to_string(), "alice@example.com".to_string()).unwrap();
+    let csv = "name,email,initial_deposit\n\
+               Alice Again,ALICE@example.com,5\n\
+               Bob,bob@example.com,5\n\
+               Bob Twin,bob@example.com,5\n";
+
+    let import = bank.import_customers(csv.as_bytes()).unwrap();
+
+    assert!(matches!(import.rows[0].result, Err(BankError::CustomerAlreadyExists(_))));
+    assert!(import.rows[1].result.is_ok());
+    assert!(matches!(import.rows[2].result, Err(BankError::CustomerAlreadyExists(_))));
+    assert_eq!(bank.list_customers().len(), 2);
+}
+
+#[test]
+fn the_deposit_column_is_optional_and_fields_are_trimmed() {
+    let mut bank = Bank::new("Test Bank".to_string());
+    let csv = "email, name\n gina@example.com , Gina \n";
+
+    let import = bank.import_customers(csv.as_bytes()).unwrap();
+
+    assert_eq!(import.imported(), 1);
+    assert_eq!(import.rows[0].name, "Gina");
+    assert_eq!(balance_of(&bank, "gina@example.com"), Money::ZERO);
+}
+
+#[test]
+fn a_file_without_the_required_columns_is_rejected() {
+    let mut bank = Bank::new("Test Bank".to_string());
+    let result = bank.import_customers("name,initial_deposit\nAlice,10\n".as_bytes());
+    assert!(matches!(result, Err(BankError::SerializationError(_))));
+    assert!(bank.list_customers().is_empty());
+}
+
+#[test]
+fn imported_deposits_reach_the_vault_and_the_journal() {
+    let mut bank = Bank::new("Test Bank".to_string());
+    let snapshot = serde_json::to_string(&bank).unwrap();
+    bank.enable_journal();
+    bank.import_customers("name,email,initial_deposit\nHal,hal@example.com,300\n".as_bytes()).unwrap();
+    assert_eq!(bank.vault(), Some(Money::from_major(300)));
+
+    let mut restored: Bank = serde_json::from_str(&snapshot).unwrap();
+    for entry in bank.take_journal() {
+        restored.apply_journal_entry(entry).unwrap();
+    }
+    assert_eq!(balance_of(&restored, "hal@example.com"), Money::from_major(300));
+}
+
+#[cfg(feature = "cli")]
+#[test]
+fn command_mode_imports_customers_from_a_file() {
+    let data_file = temp_path("cli.json");
+    let csv_file = temp_path("customers.csv");
+    let _ = std::fs::remove_file(&data_file);
+    let _ = std::fs::remove_file(journal_path(&data_file.to_string_lossy()));
+    std::fs::write(&csv_file, "name,email,initial_deposit\nAnn,ann@example.com,40\nBen,,10\n").unwrap();
+    let run = |args: &[&str]| common::run(&data_file, args, "\n");
+
+    let json: serde_json::Value =
+        serde_json::from_str(&run(&["--json", "import-customers", "--file", &csv_file.to_string_lossy()])).unwrap();
+    assert_eq!(json["result"]["imported"], 1);
+    assert_eq!(json["result"]["failed"], 1);
+    assert_eq!(json["result"]["total_deposited"], "40.00");
+    assert_eq!(json["result"]["rows"][1]["line"], 3);
+    assert_eq!(json["result"]["rows"][1]["ok"], false);
+
+    let json: serde_json::Value =
+        serde_json::from_str(&run(&["--json", "account", "--customer", "ann@example.com"])).unwrap();
+    assert_eq!(json["result"]["account"]["balance"], "40.00");
+    let _ = std::fs::remove_file(&csv_file);
+    let _ = std::fs::remove_file(&data_file);
+}
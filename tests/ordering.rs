@@ -0,0 +1,77 @@
+//! Integration tests for model equality, ordering and display
+
+use std::collections::HashSet;
+
+use chrono::{Duration, TimeZone, Utc};
+use rust_banking_system::models::{AccountNumber, TransactionOrder};
+use rust_banking_system::{AccountType, Bank, Money, Transaction, TransactionType};
+
+fn at(minutes: i64) -> Transaction {
+    let start = Utc.with_ymd_and_hms(2024, 1, 1, 9, 0, 0).unwrap();
+    let amount = Money::from_major(minutes);
+    Transaction::new_at(TransactionType::Deposit, amount, Money::ZERO, start + Duration::minutes(minutes))
+}
+
+#[test]
+fn models_are_equal_by_id() {
+    let mut bank = Bank::new("Test Bank".to_string());
+    let alice = bank.register_customer("Alice".to_string(), "alice@example.com".to_string()).unwrap();
+    bank.create_account_for_customer(&alice, Money::from_major(100), AccountType::Checking).unwrap();
+    let before = bank.get_customer(&alice).unwrap().clone();
+    bank.deposit(&alice, Money::from_major(5)).unwrap();
+    let after = bank.get_customer(&alice).unwrap();
+
+    assert_eq!(before, *after);
+    assert_eq!(before.account, after.account);
+
+    let first = at(1);
+    let mut tagged = first.clone();
+    tagged.tags.push("rent".to_string());
+    assert_eq!(first, tagged);
+    assert_ne!(first, at(1));
+
+    let unique: HashSet<Transaction> = [first.clone(), tagged, at(2)].into_iter().collect();
+    assert_eq!(unique.len(), 2);
+}
+
+#[test]
+fn transactions_sort_by_time_then_id() {
+    let (late, early) = (at(5), at(1));
+    let mut same_time = at(1);
+    same_time.timestamp = early.timestamp;
+
+    let mut sorted = [late.clone(), same_time, early.clone()];
+    sorted.sort();
+
+    assert_eq!(sorted[2], late);
+    assert!(sorted[0].id < sorted[1].id);
+    assert!(early < late);
+}
+
+#[test]
+fn transaction_orders() {
+    let transactions = [at(3), at(1), at(2)];
+    let amounts = |order: TransactionOrder| {
+        let mut refs: Vec<&Transaction> = transactions.iter().collect();
+        order.sort(&mut refs);
+        refs.iter().map(|tx| tx.amount.to_string()).collect::<Vec<_>>()
+    };
+
+    assert_eq!(amounts(TransactionOrder::OldestFirst), ["1.00", "2.00", "3.00"]);
+    assert_eq!(amounts(TransactionOrder::NewestFirst), ["3.00", "2.00", "1.00"]);
+    assert_eq!(amounts(TransactionOrder::LargestFirst), ["3.00", "2.00", "1.00"]);
+    assert_eq!(amounts(TransactionOrder::SmallestFirst), ["1.00", "2.00", "3.00"]);
+}
+
+#[test]
+fn customers_and_accounts_display() {
+    let mut bank = Bank::new("Test Bank".to_string());
+    let alice = bank.register_customer("Alice".to_string(), "alice@example.com".to_string()).unwrap();
+    bank.create_account_for_customer(&alice, Money::from_major(90), AccountType::Savings).unwrap();
+    let customer = bank.get_customer(&alice).unwrap();
+    let account = customer.get_account().unwrap();
+
+    assert_eq!(customer.to_string(), "Alice <alice@example.com>");
+    let number: AccountNumber = account.number.unwrap();
+    assert_eq!(account.to_string(), format!("{} (Savings) $90.00", number));
+}
@@ -0,0 +1,70 @@
+//! Integration tests for paginated customer and transaction listings
+
+use rust_banking_system::models::{CustomerId, Page};
+use rust_banking_system::{AccountType, Bank, Money};
+
+#[test]
+fn pages_report_their_position() {
+    let page = Page::from_items(1..=25, 20, 10);
+    assert_eq!(page.items, vec![21, 22, 23, 24, 25]);
+    assert_eq!(page.total, 25);
+    assert_eq!((page.number(), page.count()), (3, 3));
+    assert!(page.has_prev());
+    assert!(!page.has_next());
+
+    let past_the_end = Page::from_items(1..=25, 40, 10);
+    assert!(past_the_end.items.is_empty());
+    assert_eq!(past_the_end.total, 25);
+
+    let empty = Page::from_items(Vec::<u32>::new(), 0, 10);
+    assert_eq!((empty.number(), empty.count()), (1, 1));
+    assert!(!empty.has_prev() && !empty.has_next());
+}
+
+#[test]
+fn customer_pages_cover_everyone_once_in_registration_order() {
+    let mut bank = Bank::new("Test Bank".to_string());
+    let mut ids: Vec<CustomerId> = (0..7)
+        .map(|i| {
+            bank.register_customer(format!("Customer {}", i), format!("c{}@example.com", i))
+                .unwrap()
+        })
+        .collect();
+
+    let mut seen = Vec::new();
+    let mut offset = 0;
+    loop {
+        let page = bank.list_customers_page(offset, 3);
+        assert_eq!(page.total, 7);
+        seen.extend(page.items.iter().map(|c| (c.registered_at, c.id.clone())));
+        if !page.has_next() {
+            break;
+        }
+        offset += 3;
+    }
+    assert!(seen.windows(2).all(|w| w[0] <= w[1]));
+
+    let mut seen: Vec<CustomerId> = seen.into_iter().map(|(_, id)| id).collect();
+    seen.sort();
+    ids.sort();
+    assert_eq!(seen, ids);
+}
+
+#[test]
+fn transaction_pages_are_oldest_first() {
+    let mut bank = Bank::new("Test Bank".to_string());
+    let id = bank
+        .register_customer("Alice".to_string(), "alice@example.com".to_string())
+        .unwrap();
+    bank.create_account_for_customer(&id, Money::from_major(100), AccountType::Checking)
+        .unwrap();
+    for amount in 1..=4 {
+        bank.deposit(&id, Money::from_major(amount)).unwrap();
+    }
+
+    let account = bank.get_customer(&id).unwrap().get_account().unwrap();
+    let page = account.transactions_page(1, 2);
+    assert_eq!(page.total, 5);
+    let amounts: Vec<Money> = page.items.iter().map(|tx| tx.amount).collect();
+    assert_eq!(amounts, vec![Money::from_major(1), Money::from_major(2)]);
+}
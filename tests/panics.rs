@@ -0,0 +1,80 @@
+//! Integration tests for recovering from panics and poisoned locks
+
+use std::panic;
+use std::thread;
+
+#[cfg(feature = "cli")]
+use rust_banking_system::cli::isolate;
+use rust_banking_system::models::CustomerId;
+use rust_banking_system::{AccountType, Bank, Money, SharedBank};
+
+/// A shared bank holding one customer with a $100 account
+fn funded_bank() -> (SharedBank, CustomerId) {
+    let bank = SharedBank::new(Bank::new("Test Bank".to_string()));
+    let id = {
+        let mut bank = bank.write();
+        let id = bank.register_customer("Ann".to_string(), "ann@example.com".to_string()).unwrap();
+        bank.create_account_for_customer(&id, Money::from_major(100), AccountType::Checking).unwrap();
+        id
+    };
+    (bank, id)
+}
+
+fn balance(bank: &SharedBank, id: &CustomerId) -> Money {
+    bank.read().get_customer(id).unwrap().get_account().unwrap().balance
+}
+
+#[test]
+fn a_thread_that_panics_holding_the_bank_does_not_lock_everyone_out() {
+    let (bank, id) = funded_bank();
+    let writer = bank.clone();
+    let crashed = thread::spawn(move || {
+        let _bank = writer.write();
+        panic!("crashed mid-operation");
+    })
+    .join();
+    assert!(crashed.is_err());
+
+    // Both kinds of lock are handed out again, more than once
+    assert_eq!(balance(&bank, &id), Money::from_major(100));
+    bank.deposit(&id, Money::from_major(5)).unwrap();
+    bank.deposit(&id, Money::from_major(5)).unwrap();
+    assert_eq!(balance(&bank, &id), Money::from_major(110));
+}
+
+#[cfg(feature = "cli")]
+#[test]
+fn changes_made_before_a_panic_are_kept() {
+    let (bank, id) = funded_bank();
+    let result = isolate(|| {
+        let mut locked = bank.write();
+        locked.deposit(&id, Money::from_major(20)).unwrap();
+        panic!("after the deposit");
+    });
+
+    assert!(result.is_err());
+    assert_eq!(balance(&bank, &id), Money::from_major(120));
+}
+
+#[cfg(feature = "cli")]
+#[test]
+fn isolate_passes_the_result_through() {
+    assert_eq!(isolate(|| 2 + 2), Ok(4));
+    let failed: Result<Result<(), &str>, String> = isolate(|| Err("refused"));
+    assert_eq!(failed, Ok(Err("refused")));
+}
+
+#[cfg(feature = "cli")]
+#[test]
+fn isolate_reports_the_panic_message() {
+    assert_eq!(isolate(|| panic!("boom")), Err::<(), _>("boom".to_string()));
+    let amount = 12;
+    assert_eq!(isolate(|| panic!("bad amount {}", amount)), Err::<(), _>("bad amount 12".to_string()));
+}
+
+#[cfg(feature = "cli")]
+#[test]
+fn isolate_copes_with_panics_that_are_not_text() {
+    let result: Result<(), String> = isolate(|| panic::panic_any(42));
+    assert_eq!(result.unwrap_err(), "unknown panic");
+}
@@ -0,0 +1,127 @@
+//! Integration tests for cancelling prompts with `:q` and asking again on
+//! invalid input
+//!
+//! The interactive sessions run the real binary with its input piped in.
+
+#![cfg(feature = "cli")]
+
+mod common;
+
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Output;
+
+use rust_banking_system::cli::{is_cancelled, Cancelled, CANCEL_INPUT};
+
+use common::temp_path;
+
+/// A data file where Ann (PIN 1234) has $100 and Bob has nothing; the
+/// interactive input logs in as Ann after the (blank) passphrase
+fn bank_with_ann(name: &str) -> (PathBuf, &'static str) {
+    let data_file = temp_path(name);
+    let _ = std::fs::remove_file(&data_file);
+    common::output(&data_file, &["register", "--name", "Ann", "--email", "ann@example.com", "--pin", "1234"], "\n");
+    common::output(&data_file, &["register", "--name", "Bob", "--email", "bob@example.com", "--pin", "4321"], "\n");
+    common::output(&data_file, &["create-account", "--customer", "ann@example.com", "--deposit", "100"], "\n");
+    common::output(&data_file, &["create-account", "--customer", "bob@example.com"], "\n");
+    (data_file, "\n20\n1\nann@example.com\n1234\n")
+}
+
+/// Ann's balance as saved in the data file
+fn saved_balance(data_file: &Path) -> String {
+    let output = common::output(data_file, &["--json", "account", "--customer", "ann@example.com"], "\n");
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    json["result"]["account"]["balance"].to_string()
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+#[test]
+fn only_the_cancelled_error_counts_as_cancelling() {
+    assert!(is_cancelled(&io::Error::other(Cancelled)));
+    assert!(!is_cancelled(&io::Error::other("disk full")));
+    assert!(!is_cancelled(&io::Error::new(io::ErrorKind::UnexpectedEof, "no more input")));
+    assert!(!is_cancelled(&io::Error::from(io::ErrorKind::Interrupted)));
+}
+
+#[test]
+fn the_cancel_word_is_short_and_explained() {
+    assert_eq!(CANCEL_INPUT, ":q");
+    assert_eq!(Cancelled.to_string(), "cancelled");
+    let error = io::Error::other(Cancelled);
+    assert_eq!(error.to_string(), "cancelled");
+}
+
+#[test]
+fn invalid_amounts_are_asked_for_again() {
+    let (data_file, login) = bank_with_ann("reask.json");
+    let output = common::output(&data_file, &[], &format!("{}3\nabc\n12x\n25\n\n\n0\n", login));
+    let text = stdout(&output);
+
+    assert!(output.status.success());
+    assert_eq!(text.matches("Invalid amount").count(), 2, "{}", text);
+    assert_eq!(text.matches("Enter amount to deposit").count(), 3);
+    assert!(text.contains("New Balance: $125.00"), "{}", text);
+    let _ = std::fs::remove_file(&data_file);
+}
+
+#[test]
+fn cancelling_drops_the_operation_and_keeps_the_session() {
+    let (data_file, login) = bank_with_ann("cancel.json");
+    // Withdraw, cancel at the amount, then deposit $5 in the same session
+    let output = common::output(&data_file, &[], &format!("{}4\n:q\n3\n5\n\n\n0\n", login));
+    let text = stdout(&output);
+
+    assert!(output.status.success());
+    assert!(text.contains("Cancelled"), "{}", text);
+    assert!(text.contains("New Balance: $105.00"), "{}", text);
+    let _ = std::fs::remove_file(&data_file);
+}
+
+#[test]
+fn cancelling_halfway_through_a_transfer_moves_nothing() {
+    let (data_file, login) = bank_with_ann("transfer.json");
+    // Recipient and amount given, then cancelled at the description
+    let output = common::output(&data_file, &[], &format!("{}5\nbob@example.com\n40\n:q\n0\n", login));
+
+    assert!(stdout(&output).contains("Cancelled"));
+    assert_eq!(saved_balance(&data_file), "\"100.00\"");
+    let _ = std::fs::remove_file(&data_file);
+}
+
+#[test]
+fn rejected_values_are_asked_for_again() {
+    let (data_file, login) = bank_with_ann("payee.json");
+    // A blank nickname is refused, so the nickname is asked for again
+    let output = common::output(&data_file, &[], &format!("{}34\n1\n\nlandlord\nbob@example.com\n\n0\n", login));
+    let text = stdout(&output);
+
+    assert_eq!(text.matches("Nickname (e.g. landlord)").count(), 2, "{}", text);
+    assert!(text.contains("landlord"), "{}", text);
+    assert!(!text.contains("Cancelled"));
+    let _ = std::fs::remove_file(&data_file);
+}
+
+#[test]
+fn cancelling_at_the_menu_is_harmless() {
+    let (data_file, login) = bank_with_ann("menu.json");
+    let output = common::output(&data_file, &[], &format!("{}:q\n0\n", login));
+    let text = stdout(&output);
+
+    assert!(output.status.success());
+    assert!(text.contains("Cancelled") && text.contains("Goodbye"), "{}", text);
+    let _ = std::fs::remove_file(&data_file);
+}
+
+#[test]
+fn running_out_of_input_ends_the_session_and_keeps_finished_work() {
+    let (data_file, login) = bank_with_ann("eof.json");
+    // A deposit, then input ends in the middle of a withdrawal
+    let output = common::output(&data_file, &[], &format!("{}3\n10\n\n\n4\n", login));
+
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert_eq!(saved_balance(&data_file), "\"110.00\"");
+    let _ = std::fs::remove_file(&data_file);
+}
@@ -0,0 +1,167 @@
+//! Property-based tests: random operation sequences keep the books balanced
+//!
+//! Each case drives a bank on a mock clock through a random mix of
+//! deposits, withdrawals, transfers, undos and clock jumps, then checks
+//! invariants that must hold whatever succeeded or failed along the way.
+
+use std::sync::Arc;
+
+use chrono::{Duration, TimeZone, Utc};
+use proptest::prelude::*;
+use rust_banking_system::clock::MockClock;
+use rust_banking_system::models::CustomerId;
+use rust_banking_system::persistence::Storage;
+use rust_banking_system::{AccountType, Bank, MemoryStorage, Money};
+
+const CUSTOMERS: usize = 3;
+
+#[derive(Debug, Clone)]
+enum Op {
+    Deposit(usize, i64),
+    Withdraw(usize, i64),
+    Transfer(usize, usize, i64),
+    Undo,
+    Advance(i64),
+}
+
+fn op() -> impl Strategy<Value = Op> {
+    let who = 0..CUSTOMERS;
+    // Zero and oversized amounts are included so failures get exercised too
+    let cents = 0i64..150_000;
+    prop_oneof![
+        3 => (who.clone(), cents.clone()).prop_map(|(c, a)| Op::Deposit(c, a)),
+        3 => (who.clone(), cents.clone()).prop_map(|(c, a)| Op::Withdraw(c, a)),
+        3 => (who.clone(), who, cents).prop_map(|(f, t, a)| Op::Transfer(f, t, a)),
+        1 => Just(Op::Undo),
+        1 => (1i64..60 * 24 * 10).prop_map(Op::Advance),
+    ]
+}
+
+/// A bank on a mock clock with a checking, a savings and an empty
+/// checking account
+fn bank() -> (Bank, Arc<MockClock>, Vec<CustomerId>) {
+    let clock = Arc::new(MockClock::new(Utc.with_ymd_and_hms(2024, 1, 30, 12, 0, 0).unwrap()));
+    let mut bank = Bank::new("Property Bank".to_string());
+    bank.set_clock(clock.clone());
+
+    let accounts = [
+        (Money::from_major(500), AccountType::Checking),
+        (Money::from_major(1000), AccountType::Savings),
+        (Money::ZERO, AccountType::Checking),
+    ];
+    let mut ids = Vec::new();
+    for (i, (initial, account_type)) in accounts.into_iter().enumerate() {
+        let id = bank
+            .register_customer(format!("Customer {}", i), format!("customer{}@example.com", i))
+            .unwrap();
+        bank.create_account_for_customer(&id, initial, account_type).unwrap();
+        ids.push(id);
+    }
+    (bank, clock, ids)
+}
+
+fn total(bank: &Bank, ids: &[CustomerId]) -> Money {
+    ids.iter()
+        .map(|id| bank.get_customer(id).unwrap().get_account().unwrap().balance)
+        .sum()
+}
+
+/// Runs `ops`, returning the money expected to be held afterwards
+fn run(bank: &mut Bank, clock: &MockClock, ids: &[CustomerId], ops: &[Op]) -> Money {
+    let mut expected = total(bank, ids);
+    for op in ops {
+        match *op {
+            Op::Deposit(c, cents) => {
+                if bank.deposit(&ids[c], Money::from_cents(cents)).is_ok() {
+                    expected += Money::from_cents(cents);
+                }
+            }
+            Op::Withdraw(c, cents) => {
+                if bank.withdraw(&ids[c], Money::from_cents(cents)).is_ok() {
+                    expected += -Money::from_cents(cents);
+                }
+            }
+            Op::Transfer(from, to, cents) => {
+                let _ = bank.transfer(&ids[from], &ids[to], Money::from_cents(cents));
+            }
+            Op::Undo => {
+                if let Ok(reversals) = bank.undo_last() {
+                    expected += reversals.iter().map(|tx| tx.signed_amount()).sum();
+                }
+            }
+            Op::Advance(minutes) => clock.advance(Duration::minutes(minutes)),
+        }
+    }
+    expected
+}
+
+proptest! {
+    #[test]
+    fn history_adds_up_to_the_balance(ops in prop::collection::vec(op(), 0..60)) {
+        let (mut bank, clock, ids) = bank();
+        run(&mut bank, &clock, &ids, &ops);
+
+        for id in &ids {
+            let account = bank.get_customer(id).unwrap().get_account().unwrap();
+            let mut running = Money::ZERO;
+            for tx in &account.transactions {
+                running += tx.signed_amount();
+                prop_assert_eq!(tx.balance_after, running);
+                prop_assert!(!running.is_negative());
+            }
+            prop_assert_eq!(running, account.balance);
+        }
+        prop_assert!(bank.verify_integrity().is_clean());
+    }
+
+    #[test]
+    fn money_is_only_created_by_deposits_and_removed_by_withdrawals(
+        ops in prop::collection::vec(op(), 0..60)
+    ) {
+        let (mut bank, clock, ids) = bank();
+        let expected = run(&mut bank, &clock, &ids, &ops);
+        prop_assert_eq!(total(&bank, &ids), expected);
+    }
+
+    #[test]
+    fn timestamps_follow_the_clock(ops in prop::collection::vec(op(), 0..60)) {
+        let (mut bank, clock, ids) = bank();
+        run(&mut bank, &clock, &ids, &ops);
+
+        for id in &ids {
+            let account = bank.get_customer(id).unwrap().get_account().unwrap();
+            for pair in account.transactions.windows(2) {
+                prop_assert!(pair[0].timestamp <= pair[1].timestamp);
+            }
+            if let Some(last) = account.transactions.last() {
+                prop_assert!(last.timestamp <= bank.now());
+            }
+        }
+    }
+
+    #[test]
+    fn journal_replay_reproduces_the_bank(ops in prop::collection::vec(op(), 0..60)) {
+        let (mut bank, clock, ids) = bank();
+        let storage = MemoryStorage::new();
+        storage.save_bank(&bank).unwrap();
+        bank.enable_journal();
+
+        // Journal in a few chunks, as a server would after each request
+        for chunk in ops.chunks(7) {
+            run(&mut bank, &clock, &ids, chunk);
+            let entries = bank.take_journal();
+            storage.append_journal(&bank, &entries).unwrap();
+        }
+
+        let loaded = storage.load_bank().unwrap();
+        for id in &ids {
+            let original = bank.get_customer(id).unwrap().get_account().unwrap();
+            let restored = loaded.get_customer(id).unwrap().get_account().unwrap();
+            prop_assert_eq!(restored.balance, original.balance);
+            prop_assert_eq!(
+                serde_json::to_string(&restored.transactions).unwrap(),
+                serde_json::to_string(&original.transactions).unwrap()
+            );
+        }
+    }
+}
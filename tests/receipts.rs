@@ -0,0 +1,183 @@
+//! Integration tests for transaction receipts
+
+// The tests that run the binary need the `cli` feature; without it the
+// imports and helpers only they use go unused
+#![cfg_attr(not(feature = "cli"), allow(unused))]
+
+mod common;
+
+use rust_banking_system::models::{CustomerId, MerchantCategory, TransactionId, TransactionType};
+use rust_banking_system::persistence::journal_path;
+use rust_banking_system::{Bank, BankError, Money};
+
+#[cfg(feature = "cli")]
+use common::{run, run_in};
+use common::{bank_with_customers, temp_path};
+
+const IBAN: &str = "GB29 NWBK 6016 1331 9268 19";
+
+/// ID of the newest transaction on a customer's account
+fn last_id(bank: &Bank, customer_id: &CustomerId) -> TransactionId {
+    let account = bank.get_customer(customer_id).unwrap().get_account().unwrap();
+    account.get_transaction_history().last().unwrap().id.clone()
+}
+
+/// Account number shown on receipts for a customer
+fn number(bank: &Bank, customer_id: &CustomerId) -> String {
+    bank.get_customer(customer_id).unwrap().get_account().unwrap().number.unwrap().to_string()
+}
+
+#[test]
+fn a_deposit_receipt_names_only_the_receiving_account() {
+    let (mut bank, alice, _) = bank_with_customers(1_000, 50);
+    bank.deposit(&alice, Money::from_major(25)).unwrap();
+
+    let receipt = bank.get_receipt(&last_id(&bank, &alice)).unwrap();
+    assert_eq!(receipt.bank_name, "Test Bank");
+    assert!(matches!(receipt.transaction_type, TransactionType::Deposit));
+    assert!(receipt.from.is_none());
+    let to = receipt.to.unwrap();
+    assert_eq!(to.name.as_deref(), Some("Alice"));
+    assert_eq!(to.account, number(&bank, &alice));
+    assert_eq!(receipt.amount, Money::from_major(25));
+    assert_eq!(receipt.fees, Money::ZERO);
+    assert_eq!(receipt.balance_after, Money::from_major(1_025));
+}
+
+#[test]
+fn a_withdrawal_receipt_names_only_the_paying_account() {
+    let (mut bank, alice, _) = bank_with_customers(1_000, 50);
+    bank.withdraw(&alice, Money::from_major(40)).unwrap();
+
+    let receipt = bank.get_receipt(&last_id(&bank, &alice)).unwrap();
+    assert_eq!(receipt.from.unwrap().name.as_deref(), Some("Alice"));
+    assert!(receipt.to.is_none());
+    assert_eq!(receipt.balance_after, Money::from_major(960));
+}
+
+#[test]
+fn both_sides_of_a_transfer_have_a_receipt() {
+    let (mut bank, alice, bob) = bank_with_customers(1_000, 50);
+    bank.transfer(&alice, &bob, Money::from_major(100)).unwrap();
+
+    let sent = bank.get_receipt(&last_id(&bank, &alice)).unwrap();
+    let received = bank.get_receipt(&last_id(&bank, &bob)).unwrap();
+    for receipt in [&sent, &received] {
+        assert_eq!(receipt.from.as_ref().unwrap().account, number(&bank, &alice));
+        assert_eq!(receipt.to.as_ref().unwrap().account, number(&bank, &bob));
+    }
+    // Each receipt shows the balance of the account it was issued for
+    assert_eq!(sent.balance_after, Money::from_major(900));
+    assert_eq!(received.balance_after, Money::from_major(150));
+}
+
+#[test]
+fn bills_and_external_transfers_name_the_payee() {
+    let (mut bank, alice, _) = bank_with_customers(1_000, 50);
+    let power = bank.add_merchant("City Power", MerchantCategory::Utility).unwrap();
+    let bill = bank.pay_bill(&alice, &power.id, Money::from_major(60), "ACC-778").unwrap();
+    let sent = bank.external_transfer(&alice, IBAN, Money::from_major(10)).unwrap();
+
+    let to = bank.get_receipt(&bill.id).unwrap().to.unwrap();
+    assert_eq!(to.to_string(), "City Power (ACC-778)");
+    let receipt = bank.get_receipt(&sent.id).unwrap();
+    assert!(receipt.to.unwrap().name.is_none());
+    assert_eq!(receipt.status.to_string(), "PENDING");
+}
+
+#[test]
+fn receipts_of_deleted_customers_can_still_be_reprinted() {
+    let (mut bank, alice, bob) = bank_with_customers(1_000, 50);
+    bank.transfer(&bob, &alice, Money::from_major(50)).unwrap();
+    let id = last_id(&bank, &bob);
+    bank.delete_customer(&bob).unwrap();
+
+    assert_eq!(bank.get_receipt(&id).unwrap().from.unwrap().name.as_deref(), Some("Bob"));
+}
+
+#[test]
+fn unknown_transactions_have_no_receipt() {
+    let (bank, _, _) = bank_with_customers(1_000, 50);
+    let error = bank.get_receipt(&"no-such-id".into()).unwrap_err();
+
+    assert!(matches!(&error, BankError::TransactionNotFound(id) if id == "no-such-id"));
+    assert_eq!(error.code(), 1011);
+    assert_eq!(error.kind(), "transaction_not_found");
+}
+
+#[test]
+fn the_printed_receipt_lists_every_detail() {
+    let (mut bank, alice, bob) = bank_with_customers(1_000, 50);
+    bank.transfer(&alice, &bob, Money::from_major(100)).unwrap();
+    let id = last_id(&bank, &alice);
+    let text = bank.get_receipt(&id).unwrap().to_string();
+
+    assert!(text.contains("RECEIPT - Test Bank"), "{}", text);
+    assert!(text.contains(&format!("Transaction: {}", id)), "{}", text);
+    assert!(text.contains(&format!("From:        Alice ({})", number(&bank, &alice))), "{}", text);
+    assert!(text.contains(&format!("To:          Bob ({})", number(&bank, &bob))), "{}", text);
+    assert!(text.contains("Amount:      $100.00"), "{}", text);
+    assert!(text.contains("Fees:        $0.00"), "{}", text);
+    assert!(text.contains("Balance:     $900.00"), "{}", text);
+}
+
+#[cfg(feature = "cli")]
+#[test]
+fn a_deposit_in_the_menu_can_save_its_receipt_and_reprint_it_later() {
+    let data_file = temp_path("menu.json");
+    let receipt_file = temp_path("receipt.txt");
+    let _ = std::fs::remove_file(&data_file);
+    let _ = std::fs::remove_file(journal_path(&data_file.to_string_lossy()));
+    run(&data_file, &["register", "--name", "Ann", "--email", "ann@example.com", "--pin", "1234"], "\n");
+    run(&data_file, &["create-account", "--customer", "ann@example.com", "--deposit", "100"], "\n");
+
+    let login = "\n20\n1\nann@example.com\n1234\n";
+    let input = format!("{}3\n5\n\ny\n{}\n11\n0\n", login, receipt_file.display());
+    let text = run(&data_file, &[], &input);
+    assert!(text.contains("Receipt saved to"), "{}", text);
+    let saved = std::fs::read_to_string(&receipt_file).unwrap();
+    assert!(saved.contains("Amount:      $5.00"), "{}", saved);
+    assert!(saved.contains("Balance:     $105.00"), "{}", saved);
+
+    // Command mode reprints it by ID
+    let id = saved.lines().find_map(|line| line.strip_prefix("Transaction: ")).unwrap().trim();
+    let output = run(&data_file, &["--json", "receipt", "--id", id], "");
+    let json: serde_json::Value = serde_json::from_str(&output).unwrap();
+    assert_eq!(json["result"]["transaction_id"], id);
+    assert_eq!(json["result"]["balance_after"], "105.00");
+    let _ = std::fs::remove_file(&data_file);
+    let _ = std::fs::remove_file(&receipt_file);
+}
+
+#[cfg(feature = "cli")]
+#[test]
+fn declining_the_receipt_writes_no_file_and_existing_files_are_kept() {
+    let data_file = temp_path("decline.json");
+    let dir = temp_path("decline-dir");
+    let _ = std::fs::remove_file(&data_file);
+    let _ = std::fs::remove_file(journal_path(&data_file.to_string_lossy()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    let existing = dir.join("kept.txt");
+    std::fs::write(&existing, "keep me\n").unwrap();
+    run(&data_file, &["register", "--name", "Ann", "--email", "ann@example.com", "--pin", "1234"], "\n");
+    run(&data_file, &["create-account", "--customer", "ann@example.com", "--deposit", "100"], "\n");
+
+    // "n", "no" and the cancel word all skip the receipt; then a "yes"
+    // that names a file already there
+    let login = "\n20\n1\nann@example.com\n1234\n";
+    let input = format!(
+        "{}3\n1\n\nn\n3\n1\n\nno\n3\n1\n\n:q\n3\n1\n\ny\n{}\n0\n",
+        login,
+        existing.display()
+    );
+    let text = run_in(&dir, &data_file, &[], &input);
+
+    assert!(text.contains("already exists; the receipt was not saved"), "{}", text);
+    assert!(!text.contains("Cancelled"), "{}", text);
+    assert_eq!(std::fs::read_to_string(&existing).unwrap(), "keep me\n");
+    let files: Vec<_> = std::fs::read_dir(&dir).unwrap().map(|entry| entry.unwrap().file_name()).collect();
+    assert_eq!(files, vec!["kept.txt"]);
+    let _ = std::fs::remove_file(&data_file);
+    let _ = std::fs::remove_dir_all(&dir);
+}
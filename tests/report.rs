@@ -0,0 +1,105 @@
+//! Integration tests for the bank statistics report
+
+use rust_banking_system::models::CustomerId;
+use rust_banking_system::{AccountType, Bank, Money};
+
+/// Creates a bank with four accounts holding 100, 50, 5000 and 0;
+/// returns (bank, [alice, bob, carol, dave])
+fn bank_with_accounts() -> (Bank, Vec<CustomerId>) {
+    let mut bank = Bank::new("Test Bank".to_string());
+    let ids = [("alice", 100), ("bob", 50), ("carol", 5_000), ("dave", 0)]
+        .iter()
+        .map(|(name, balance)| {
+            let id = bank
+                .register_customer(name.to_string(), format!("{}@example.com", name))
+                .unwrap();
+            bank.create_account_for_customer(&id, Money::from_major(*balance), AccountType::Checking)
+                .unwrap();
+            id
+        })
+        .collect();
+    (bank, ids)
+}
+
+#[test]
+fn empty_bank_reports_zeros() {
+    let report = Bank::new("Empty".to_string()).report();
+
+    assert_eq!(report.customers, 0);
+    assert_eq!(report.average_balance, Money::ZERO);
+    assert_eq!(report.median_balance, Money::ZERO);
+    assert!(report.balance_distribution.iter().all(|b| b.accounts == 0));
+    assert!(report.daily_volume.is_empty());
+    assert!(report.busiest_accounts.is_empty());
+    assert!(report.to_string().contains("No transactions yet."));
+}
+
+#[test]
+fn balance_figures_cover_open_accounts() {
+    let (mut bank, ids) = bank_with_accounts();
+    bank.deposit(&ids[0], Money::from_major(20)).unwrap();
+
+    let report = bank.report();
+    assert_eq!(report.open_accounts, 4);
+    assert_eq!(report.total_balance, Money::from_major(5_170));
+    assert_eq!(report.average_balance, Money::from_cents(129_250));
+    // Balances 0, 50, 120, 5000: the median averages the middle two
+    assert_eq!(report.median_balance, Money::from_major(85));
+
+    let counts: Vec<usize> = report.balance_distribution.iter().map(|b| b.accounts).collect();
+    assert_eq!(counts, vec![2, 1, 1, 0, 0]);
+    assert_eq!(report.balance_distribution[4].max, None);
+
+    // A closed account drops out of the balance figures
+    bank.close_account(&ids[3], None).unwrap();
+    let report = bank.report();
+    assert_eq!(report.open_accounts, 3);
+    assert_eq!(report.median_balance, Money::from_major(120));
+}
+
+#[test]
+fn flows_leave_out_transfers_and_undone_operations() {
+    let (mut bank, ids) = bank_with_accounts();
+    bank.deposit(&ids[0], Money::from_major(20)).unwrap();
+    bank.withdraw(&ids[0], Money::from_major(10)).unwrap();
+    bank.transfer(&ids[0], &ids[1], Money::from_major(30)).unwrap();
+
+    let report = bank.report();
+    assert_eq!(report.inflow, Money::from_major(5_170));
+    assert_eq!(report.outflow, Money::from_major(10));
+    assert_eq!(report.net_flow, report.total_balance);
+
+    // Undo the transfer, then the withdrawal
+    bank.undo_last().unwrap();
+    bank.undo_last().unwrap();
+    let report = bank.report();
+    assert_eq!(report.inflow, Money::from_major(5_170));
+    assert_eq!(report.outflow, Money::ZERO);
+    assert_eq!(report.net_flow, report.total_balance);
+}
+
+#[test]
+fn volumes_and_busiest_accounts() {
+    let (mut bank, ids) = bank_with_accounts();
+    bank.deposit(&ids[1], Money::from_major(5)).unwrap();
+    bank.transfer(&ids[0], &ids[1], Money::from_major(30)).unwrap();
+
+    let report = bank.report();
+    // Three initial deposits, one deposit and both sides of the transfer
+    assert_eq!(report.daily_volume.len(), 1);
+    assert_eq!(report.daily_volume[0].transactions, 6);
+    assert_eq!(report.daily_volume[0].amount, Money::from_major(5_215));
+    assert_eq!(report.weekly_volume[0].transactions, 6);
+
+    let busiest: Vec<(&str, usize)> = report
+        .busiest_accounts
+        .iter()
+        .map(|a| (a.name.as_str(), a.transactions))
+        .collect();
+    assert_eq!(busiest, vec![("bob", 3), ("alice", 2), ("carol", 1)]);
+    assert!(report.busiest_accounts[0].account_number.is_some());
+
+    let json = serde_json::to_value(&report).unwrap();
+    assert_eq!(json["busiest_accounts"][0]["customer_id"], ids[1].as_str());
+    assert_eq!(json["balance_distribution"].as_array().unwrap().len(), 5);
+}
@@ -0,0 +1,141 @@
+//! Integration tests for typed prompts that explain what is wrong and ask
+//! again a limited number of times
+//!
+//! The interactive sessions run the real binary with its input piped in.
+
+#![cfg(feature = "cli")]
+
+mod common;
+
+use std::path::{Path, PathBuf};
+
+use rust_banking_system::cli::{parse_amount, MAX_ATTEMPTS};
+use rust_banking_system::Money;
+
+use common::temp_path;
+
+/// A data file where Ann (PIN 1234) has $100; returns it with the input
+/// that logs in as Ann after the (blank) passphrase
+fn bank_with_ann(name: &str) -> (PathBuf, &'static str) {
+    let data_file = temp_path(name);
+    let _ = std::fs::remove_file(&data_file);
+    common::output(&data_file, &["register", "--name", "Ann", "--email", "ann@example.com", "--pin", "1234"], "\n");
+    common::output(&data_file, &["create-account", "--customer", "ann@example.com", "--deposit", "100"], "\n");
+    (data_file, "\n20\n1\nann@example.com\n1234\n")
+}
+
+fn session(data_file: &Path, input: &str) -> String {
+    let output = common::output(data_file, &[], input);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+#[test]
+fn amounts_are_parsed_with_a_reason_for_refusing_them() {
+    assert_eq!(parse_amount("12.50").unwrap(), Money::from_cents(1_250));
+    assert_eq!(parse_amount("0").unwrap(), Money::ZERO);
+
+    let too_precise = parse_amount("12.345").unwrap_err();
+    assert!(too_precise.contains("'12.345'") && too_precise.contains("two decimals"), "{}", too_precise);
+    assert!(parse_amount("ten").unwrap_err().contains("'ten'"));
+    assert!(parse_amount("-5").unwrap_err().contains("negative"));
+}
+
+#[test]
+fn giving_up_leaves_the_whole_operation_undone() {
+    let (data_file, login) = bank_with_ann("payee.json");
+    // Blank nicknames are refused until the payee screen is abandoned;
+    // blank at the menu afterwards shows the menu was reached again
+    let text = session(&data_file, &format!("{}34\n1\n\n\n\n\n0\n", login));
+
+    assert_eq!(text.matches("Nickname (e.g. landlord)").count(), MAX_ATTEMPTS);
+    assert!(text.contains("No valid answer after 3 tries"), "{}", text);
+    assert!(text.contains("Invalid choice. Please try again."), "{}", text);
+    assert!(!text.contains("Saved payees:"));
+    let _ = std::fs::remove_file(&data_file);
+}
+
+#[test]
+fn each_refused_amount_says_what_was_wrong() {
+    let (data_file, login) = bank_with_ann("reasons.json");
+    let text = session(&data_file, &format!("{}3\n1.234\n-5\n5\n\n\n0\n", login));
+
+    assert!(text.contains("Invalid amount '1.234'"), "{}", text);
+    assert!(text.contains("The amount cannot be negative"), "{}", text);
+    assert!(text.contains("New Balance: $105.00"), "{}", text);
+    let _ = std::fs::remove_file(&data_file);
+}
+
+#[test]
+fn too_many_invalid_answers_abandon_the_operation() {
+    let (data_file, login) = bank_with_ann("give-up.json");
+    // Three bad amounts end the deposit; the next deposit still works
+    let text = session(&data_file, &format!("{}3\na\nb\nc\n3\n1\n\n\n0\n", login));
+
+    assert_eq!(text.matches("Invalid amount").count(), MAX_ATTEMPTS, "{}", text);
+    assert!(text.contains("No valid answer after 3 tries"), "{}", text);
+    assert!(text.contains("Cancelled"));
+    assert!(text.contains("New Balance: $101.00"), "{}", text);
+    let _ = std::fs::remove_file(&data_file);
+}
+
+#[test]
+fn a_good_answer_on_the_last_try_is_still_taken() {
+    let (data_file, login) = bank_with_ann("last-try.json");
+    let text = session(&data_file, &format!("{}3\nx\ny\n7\n\n\n0\n", login));
+
+    assert!(!text.contains("No valid answer"), "{}", text);
+    assert!(text.contains("New Balance: $107.00"), "{}", text);
+    let _ = std::fs::remove_file(&data_file);
+}
+
+#[test]
+fn list_numbers_are_asked_for_again_when_out_of_range() {
+    let (data_file, login) = bank_with_ann("choice.json");
+    let open = ["open-term-deposit", "--customer", "ann@example.com", "--pin", "1234"];
+    common::output(&data_file, &[&open[..], &["--amount", "50", "--rate", "4", "--months", "12"]].concat(), "\n");
+    // Pick deposit 9 of 1, then 1, then decline the early withdrawal
+    let text = session(&data_file, &format!("{}48\n2\n9\n1\nn\n\n0\n", login));
+
+    assert!(text.contains("Enter a number from 1 to 1"), "{}", text);
+    assert!(text.contains("Withdrawing now pays"), "{}", text);
+    let _ = std::fs::remove_file(&data_file);
+}
+
+#[test]
+fn ids_must_be_complete() {
+    let data_file = temp_path("ids.json");
+    let _ = std::fs::remove_file(&data_file);
+    // The first staff login creates an admin, who cancels a schedule
+    let login = "\n20\n2\nadmin\n1234\n1234\n";
+    let text = session(&data_file, &format!("{}14\n42\n67e55044-10b1-426f-9247-bb680e5fe0c8\n0\n", login));
+
+    assert!(text.contains("'42' is not a full ID"), "{}", text);
+    // A well-formed ID is accepted and looked up
+    assert!(text.contains("67e55044-10b1-426f-9247-bb680e5fe0c8"), "{}", text);
+    assert_eq!(text.matches("Enter schedule ID").count(), 2);
+    let _ = std::fs::remove_file(&data_file);
+}
+
+#[test]
+fn spanish_sessions_explain_refusals_in_spanish() {
+    let (data_file, login) = bank_with_ann("spanish.json");
+    let output = common::output(&data_file, &["--locale", "es-ES"], &format!("{}3\n1.234\n\n\n\n0\n", login));
+    let text = String::from_utf8_lossy(&output.stdout);
+
+    assert!(text.contains("Importe no válido '1.234'"), "{}", text);
+    assert!(text.contains("Ninguna respuesta válida tras 3 intentos"), "{}", text);
+    let _ = std::fs::remove_file(&data_file);
+}
+
+#[test]
+fn spanish_sessions_show_every_screen_in_spanish() {
+    let (data_file, login) = bank_with_ann("spanish-payees.json");
+    let output = common::output(&data_file, &["--locale", "es-ES"], &format!("{}34\n1\n\n\n\n\n0\n", login));
+    let text = String::from_utf8_lossy(&output.stdout);
+
+    assert!(text.contains("Todavía no hay beneficiarios guardados"), "{}", text);
+    assert_eq!(text.matches("Alias (p. ej. casero)").count(), MAX_ATTEMPTS, "{}", text);
+    assert!(!text.contains("Nickname"), "{}", text);
+    let _ = std::fs::remove_file(&data_file);
+}
@@ -0,0 +1,150 @@
+//! Integration tests for reversing a transaction by ID
+
+// The tests that run the binary need the `cli` feature; without it the
+// imports and helpers only they use go unused
+#![cfg_attr(not(feature = "cli"), allow(unused))]
+
+mod common;
+
+use chrono::{Duration, Utc};
+use rust_banking_system::models::{CustomerId, TransactionId, TransactionStatus};
+use rust_banking_system::persistence::journal_path;
+use rust_banking_system::{Bank, BankError, Money, Transaction};
+
+use common::{balance, bank_with_customers, temp_path};
+
+fn latest(bank: &Bank, customer_id: &CustomerId) -> Transaction {
+    bank.get_customer(customer_id).unwrap().get_account().unwrap().transactions.last().unwrap().clone()
+}
+
+fn find(bank: &Bank, transaction_id: &TransactionId) -> Transaction {
+    bank.find_transaction(transaction_id).unwrap().1.clone()
+}
+
+#[test]
+fn a_reversal_links_both_ways_and_keeps_the_reason() {
+    let (mut bank, alice, _) = bank_with_customers(100, 50);
+    bank.deposit(&alice, Money::from_major(25)).unwrap();
+    let deposit = latest(&bank, &alice);
+
+    let reversals = bank.reverse_transaction(&deposit.id, "  Deposited to the wrong account ").unwrap();
+
+    assert_eq!(reversals.len(), 1);
+    assert_eq!(reversals[0].reverses(), Some(&deposit.id));
+    assert_eq!(reversals[0].reversal_reason.as_deref(), Some("Deposited to the wrong account"));
+    let original = find(&bank, &deposit.id);
+    assert_eq!(original.status, TransactionStatus::Reversed);
+    assert_eq!(original.reversed_by.as_ref(), Some(&reversals[0].id));
+    assert_eq!(balance(&bank, &alice), Money::from_major(100));
+}
+
+#[test]
+fn either_leg_of_a_transfer_reverses_both_sides() {
+    let (mut bank, alice, bob) = bank_with_customers(100, 50);
+    bank.transfer(&alice, &bob, Money::from_major(30)).unwrap();
+    let incoming = latest(&bank, &bob);
+
+    let reversals = bank.reverse_transaction(&incoming.id, "Sent in error").unwrap();
+
+    assert_eq!(reversals.len(), 2);
+    assert_eq!(balance(&bank, &alice), Money::from_major(100));
+    assert_eq!(balance(&bank, &bob), Money::from_major(50));
+    let outgoing = &bank.get_customer(&alice).unwrap().get_account().unwrap().transactions[1];
+    assert_eq!(outgoing.status, TransactionStatus::Reversed);
+    assert!(outgoing.reversed_by.is_some());
+}
+
+#[test]
+fn a_transaction_is_reversed_only_once() {
+    let (mut bank, alice, _) = bank_with_customers(100, 50);
+    bank.deposit(&alice, Money::from_major(25)).unwrap();
+    let deposit = latest(&bank, &alice);
+    let reversal = bank.reverse_transaction(&deposit.id, "Duplicate").unwrap().remove(0);
+
+    assert!(matches!(bank.reverse_transaction(&deposit.id, "Again"), Err(BankError::InvalidInput(_))));
+    // Undo must not reverse it a second time either
+    assert!(matches!(bank.undo_last(), Err(BankError::InvalidInput(_))));
+    assert!(matches!(bank.reverse_transaction(&reversal.id, "Undo the reversal"), Err(BankError::InvalidInput(_))));
+    assert_eq!(balance(&bank, &alice), Money::from_major(100));
+}
+
+#[test]
+fn a_reason_and_a_known_transaction_are_required() {
+    let (mut bank, alice, _) = bank_with_customers(100, 50);
+    bank.deposit(&alice, Money::from_major(25)).unwrap();
+    let deposit = latest(&bank, &alice);
+
+    assert!(matches!(
+        bank.reverse_transaction(&deposit.id, "   "),
+        Err(BankError::ValidationError { ref field, .. }) if field == "reason"
+    ));
+    assert!(matches!(
+        bank.reverse_transaction(&"no-such-id".into(), "Duplicate"),
+        Err(BankError::TransactionNotFound(_))
+    ));
+    assert_eq!(find(&bank, &deposit.id).status, TransactionStatus::Completed);
+}
+
+#[test]
+fn statements_show_the_audit_trail() {
+    let (mut bank, alice, _) = bank_with_customers(100, 50);
+    bank.deposit(&alice, Money::from_major(25)).unwrap();
+    let deposit = latest(&bank, &alice);
+    let reversal = bank.reverse_transaction(&deposit.id, "Cheque was forged").unwrap().remove(0);
+
+    let account = bank.get_customer(&alice).unwrap().get_account().unwrap();
+    let text = account.statement(Utc::now() - Duration::days(1), Utc::now() + Duration::days(1)).to_string();
+    assert!(text.contains(&format!("Reversed by {}", reversal.id)), "{}", text);
+    assert!(text.contains(&format!("REVERSAL of {}", deposit.id.short())), "{}", text);
+    assert!(text.contains("Reason: Cheque was forged"), "{}", text);
+}
+
+#[test]
+fn replaying_the_journal_keeps_the_links() {
+    let (mut bank, alice, _) = bank_with_customers(100, 50);
+    bank.deposit(&alice, Money::from_major(25)).unwrap();
+    let deposit = latest(&bank, &alice);
+    bank.enable_journal();
+    let snapshot = serde_json::to_string(&bank).unwrap();
+    bank.reverse_transaction(&deposit.id, "Duplicate").unwrap();
+
+    let mut restored: Bank = serde_json::from_str(&snapshot).unwrap();
+    for entry in bank.take_journal() {
+        restored.apply_journal_entry(entry).unwrap();
+    }
+    let original = find(&restored, &deposit.id);
+    assert_eq!(original.reversed_by, find(&bank, &deposit.id).reversed_by);
+    let reversal = find(&restored, original.reversed_by.as_ref().unwrap());
+    assert_eq!(reversal.reversal_reason.as_deref(), Some("Duplicate"));
+}
+
+#[cfg(feature = "cli")]
+#[test]
+fn command_mode_reverses_by_id() {
+    let data_file = temp_path("cli.json");
+    let _ = std::fs::remove_file(&data_file);
+    let _ = std::fs::remove_file(journal_path(&data_file.to_string_lossy()));
+    let run = |args: &[&str]| common::run(&data_file, args, "\n");
+    run(&["register", "--name", "Ann", "--email", "ann@example.com", "--pin", "1234"]);
+    run(&["create-account", "--customer", "ann@example.com", "--deposit", "100"]);
+    let json: serde_json::Value =
+        serde_json::from_str(&run(&["--json", "history", "--customer", "ann@example.com"])).unwrap();
+    let id = json["result"][0]["id"].as_str().unwrap().to_string();
+
+    let json: serde_json::Value =
+        serde_json::from_str(&run(&["--json", "reverse-transaction", "--transaction", &id, "--reason", "Test"]))
+            .unwrap();
+    assert_eq!(json["result"][0]["reversal_reason"], "Test");
+    let reversal_id = json["result"][0]["id"].as_str().unwrap().to_string();
+
+    let json: serde_json::Value = serde_json::from_str(&run(&["--json", "lookup-transaction", "--id", &id])).unwrap();
+    assert_eq!(json["result"]["transaction"]["status"], "reversed");
+    assert_eq!(json["result"]["transaction"]["reversed_by"], reversal_id.as_str());
+
+    let json: serde_json::Value =
+        serde_json::from_str(&run(&["--json", "reverse-transaction", "--transaction", &id, "--reason", "Again"]))
+            .unwrap();
+    assert_eq!(json["ok"], false);
+    assert_eq!(json["kind"], "invalid_input");
+    let _ = std::fs::remove_file(&data_file);
+}
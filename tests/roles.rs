@@ -0,0 +1,84 @@
+//! Integration tests for staff logins and role checks
+
+#![cfg(feature = "cli")]
+
+use rust_banking_system::cli::Session;
+use rust_banking_system::models::Role;
+use rust_banking_system::{Bank, BankError};
+
+#[test]
+fn staff_authenticate_with_their_role() {
+    let mut bank = Bank::new("Test Bank".to_string());
+    assert!(!bank.has_staff());
+
+    bank.add_staff("root", Role::Admin, "1234").unwrap();
+    bank.add_staff("Tina", Role::Teller, "5678").unwrap();
+
+    assert_eq!(bank.authenticate_staff("root", "1234").unwrap(), Role::Admin);
+    // Usernames are case-insensitive
+    assert_eq!(bank.authenticate_staff("tina", "5678").unwrap(), Role::Teller);
+    assert!(matches!(
+        bank.authenticate_staff("tina", "0000"),
+        Err(BankError::InvalidPin { .. })
+    ));
+}
+
+#[test]
+fn invalid_staff_accounts_are_rejected() {
+    let mut bank = Bank::new("Test Bank".to_string());
+    bank.add_staff("root", Role::Admin, "1234").unwrap();
+
+    for (username, role) in [("ROOT", Role::Teller), ("", Role::Teller), ("carl", Role::Customer)] {
+        assert!(matches!(
+            bank.add_staff(username, role, "1234"),
+            Err(BankError::InvalidInput(_))
+        ));
+    }
+    assert!(bank.authenticate_staff("nobody", "1234").is_err());
+}
+
+#[test]
+fn staff_roles_are_persisted() {
+    let mut bank = Bank::new("Test Bank".to_string());
+    bank.add_staff("root", Role::Admin, "1234").unwrap();
+    bank.add_staff("tina", Role::Teller, "5678").unwrap();
+
+    let json = serde_json::to_string(&bank).unwrap();
+    let mut restored: Bank = serde_json::from_str(&json).unwrap();
+
+    let roles: Vec<_> = restored.list_staff().iter().map(|m| (m.username.clone(), m.role)).collect();
+    assert_eq!(roles, [("root".to_string(), Role::Admin), ("tina".to_string(), Role::Teller)]);
+    assert_eq!(restored.authenticate_staff("tina", "5678").unwrap(), Role::Teller);
+}
+
+#[test]
+fn staff_changes_survive_journal_replay() {
+    let mut bank = Bank::new("Test Bank".to_string());
+    bank.enable_journal();
+    let snapshot = serde_json::to_string(&bank).unwrap();
+
+    bank.add_staff("root", Role::Admin, "1234").unwrap();
+    bank.change_staff_pin("root", "1234", "4321").unwrap();
+
+    let mut restored: Bank = serde_json::from_str(&snapshot).unwrap();
+    for entry in bank.take_journal() {
+        restored.apply_journal_entry(entry).unwrap();
+    }
+    assert_eq!(restored.authenticate_staff("root", "4321").unwrap(), Role::Admin);
+}
+
+#[test]
+fn sessions_allow_only_their_roles() {
+    let admin = Session::staff("root".to_string(), Role::Admin);
+    let teller = Session::staff("tina".to_string(), Role::Teller);
+    let customer = Session::customer("c-1".into());
+
+    assert!(admin.allows(&[Role::Admin]));
+    assert!(!teller.allows(&[Role::Admin]));
+    assert!(teller.allows(&[Role::Admin, Role::Teller]));
+    assert!(!customer.allows(&[Role::Admin, Role::Teller]));
+
+    // Customers are pinned to their own account; staff choose one
+    assert_eq!(customer.customer_id(), Some("c-1".into()));
+    assert_eq!(admin.customer_id(), None);
+}
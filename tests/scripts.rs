@@ -0,0 +1,126 @@
+//! Integration tests for running command scripts
+
+#![cfg(feature = "cli")]
+
+mod common;
+
+use std::path::{Path, PathBuf};
+use std::process::Output;
+
+use rust_banking_system::cli::script::{run_lines, ScriptSummary};
+use rust_banking_system::cli::JsonRenderer;
+use rust_banking_system::{Bank, JsonFileStorage, MemoryStorage, Money, Storage};
+
+use common::temp_path;
+
+const SEED: &str = "# Ann opens an account\n\
+                    register Ann ann@example.com --pin 1234\n\
+                    create-account ann@example.com --deposit 100\n\
+                    \n\
+                    deposit ann@example.com 25 --pin 1234\n";
+
+/// Runs `script` with the binary against a fresh data file; `args` go
+/// after the script name
+fn run(name: &str, script: &str, args: &[&str]) -> (Output, PathBuf) {
+    let data_file = temp_path(&format!("{}.json", name));
+    let script_file = temp_path(&format!("{}.txt", name));
+    let _ = std::fs::remove_file(&data_file);
+    std::fs::write(&script_file, script).unwrap();
+
+    let output = common::output(&data_file, &[&["run", &script_file.to_string_lossy()], args].concat(), "\n");
+    let _ = std::fs::remove_file(&script_file);
+    (output, data_file)
+}
+
+fn results(output: &Output) -> Vec<serde_json::Value> {
+    String::from_utf8_lossy(&output.stdout).lines().map(|line| serde_json::from_str(line).unwrap()).collect()
+}
+
+fn balance(data_file: &Path) -> Money {
+    let bank = JsonFileStorage::new(data_file.to_string_lossy()).load_bank().unwrap();
+    let customer = bank.find_customer_by_email("ann@example.com").unwrap();
+    customer.get_account().unwrap().balance
+}
+
+#[test]
+fn a_script_runs_every_command_and_saves_them() {
+    let (output, data_file) = run("seed", SEED, &[]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert!(String::from_utf8_lossy(&output.stdout).contains("3 succeeded, 0 failed"));
+    assert_eq!(balance(&data_file), Money::from_major(125));
+    let _ = std::fs::remove_file(&data_file);
+}
+
+#[test]
+fn the_first_failure_stops_the_script() {
+    let script = format!("{}withdraw ann@example.com 500 --pin 1234\ndeposit ann@example.com 5 --pin 1234\n", SEED);
+    let (output, data_file) = run("stop", &script, &["--json"]);
+
+    assert_eq!(output.status.code(), Some(1));
+    let results = results(&output);
+    assert_eq!(results.len(), 5);
+    assert_eq!(results[3]["kind"], "insufficient_funds");
+    assert_eq!(results[4]["result"]["summary"], serde_json::json!({ "succeeded": 3, "failed": [6], "skipped": 1 }));
+    // What ran before the failure is kept
+    assert_eq!(balance(&data_file), Money::from_major(125));
+    let _ = std::fs::remove_file(&data_file);
+}
+
+#[test]
+fn keep_going_runs_the_rest_after_a_failure() {
+    let script = format!("{}withdraw ann@example.com 500 --pin 1234\ndeposit ann@example.com 5 --pin 1234\n", SEED);
+    let (output, data_file) = run("keep-going", &script, &["--json", "--keep-going"]);
+
+    assert_eq!(output.status.code(), Some(1));
+    let summary = &results(&output)[5]["result"]["summary"];
+    assert_eq!(summary["succeeded"], 4);
+    assert_eq!(summary["skipped"], 0);
+    assert_eq!(balance(&data_file), Money::from_major(130));
+    let _ = std::fs::remove_file(&data_file);
+}
+
+#[test]
+fn mistyped_lines_fail_with_their_line_number() {
+    let mut bank = Bank::new("Test Bank".to_string());
+    let storage = MemoryStorage::new();
+    let summary = run_lines("stats\n\nbogus\ndeposit alice\n", &mut bank, &storage, true, &JsonRenderer::new());
+
+    assert_eq!(summary.succeeded, 1);
+    assert_eq!(summary.failed, [3, 4]);
+    assert!(!summary.is_success());
+}
+
+#[test]
+fn exit_ends_the_script_early() {
+    let mut bank = Bank::new("Test Bank".to_string());
+    let storage = MemoryStorage::new();
+    let script = "register Ann ann@example.com\nexit\nregister Bob bob@example.com\n";
+    let summary = run_lines(script, &mut bank, &storage, false, &JsonRenderer::new());
+
+    assert_eq!(summary, ScriptSummary { succeeded: 1, failed: Vec::new(), skipped: 0 });
+    assert!(bank.find_customer_by_email("bob@example.com").is_none());
+}
+
+#[test]
+fn the_summary_names_the_failed_lines() {
+    let summary = ScriptSummary { succeeded: 4, failed: vec![2, 9], skipped: 3 };
+    assert_eq!(summary.to_string(), "4 succeeded, 2 failed (line 2, 9), 3 skipped");
+    assert_eq!(ScriptSummary::default().to_string(), "0 succeeded, 0 failed");
+    assert!(ScriptSummary::default().is_success());
+}
+
+#[test]
+fn echoed_lines_do_not_show_pins() {
+    let (output, data_file) = run("echo", SEED, &[]);
+    let echoed = String::from_utf8_lossy(&output.stderr).into_owned() + &String::from_utf8_lossy(&output.stdout);
+    assert!(echoed.contains("5: deposit ann@example.com 25 --pin ****"), "{}", echoed);
+    assert!(!echoed.contains("1234"));
+    let _ = std::fs::remove_file(&data_file);
+}
+
+#[test]
+fn a_missing_script_is_reported() {
+    let output = common::output(temp_path("missing.json"), &["--json", "run", "/nonexistent/script.txt"], "");
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Could not read /nonexistent/script.txt"));
+}
@@ -0,0 +1,100 @@
+//! Integration tests for searching transactions with `TransactionFilter`
+
+use chrono::{Duration, Utc};
+use rust_banking_system::models::{AccountId, CustomerId, TransactionFilter, TransactionKind};
+use rust_banking_system::{AccountType, Bank, Money};
+
+/// Creates a bank where Alice deposited, withdrew and sent money to Bob;
+/// returns (bank, alice, bob's account ID)
+fn bank_with_activity() -> (Bank, CustomerId, AccountId) {
+    let mut bank = Bank::new("Test Bank".to_string());
+    let alice = bank
+        .register_customer("Alice".to_string(), "alice@example.com".to_string())
+        .unwrap();
+    let bob = bank
+        .register_customer("Bob".to_string(), "bob@example.com".to_string())
+        .unwrap();
+    bank.create_account_for_customer(&alice, Money::from_major(1000), AccountType::Checking)
+        .unwrap();
+    let bob_account = bank
+        .create_account_for_customer(&bob, Money::ZERO, AccountType::Checking)
+        .unwrap();
+
+    bank.deposit(&alice, Money::from_major(50)).unwrap();
+    bank.withdraw(&alice, Money::from_major(600)).unwrap();
+    bank.withdraw(&alice, Money::from_major(20)).unwrap();
+    bank.transfer(&alice, &bob, Money::from_major(100)).unwrap();
+    (bank, alice, bob_account)
+}
+
+fn amounts(bank: &Bank, customer_id: &CustomerId, filter: &TransactionFilter) -> Vec<Money> {
+    let account = bank.get_customer(customer_id).unwrap().get_account().unwrap();
+    account.filter_transactions(filter).iter().map(|tx| tx.amount).collect()
+}
+
+#[test]
+fn empty_filter_matches_everything() {
+    let (bank, alice, _) = bank_with_activity();
+    assert_eq!(amounts(&bank, &alice, &TransactionFilter::new()).len(), 5);
+}
+
+#[test]
+fn criteria_combine() {
+    let (bank, alice, _) = bank_with_activity();
+
+    let large_withdrawals = TransactionFilter::new()
+        .kind(TransactionKind::Withdrawal)
+        .min_amount(Money::from_major(500));
+    assert_eq!(amounts(&bank, &alice, &large_withdrawals), vec![Money::from_major(600)]);
+
+    let mid_range = TransactionFilter::new()
+        .min_amount(Money::from_major(50))
+        .max_amount(Money::from_major(100));
+    assert_eq!(
+        amounts(&bank, &alice, &mid_range),
+        vec![Money::from_major(50), Money::from_major(100)]
+    );
+
+    let credits = TransactionFilter::new()
+        .kind(TransactionKind::Deposit)
+        .kind(TransactionKind::TransferIn);
+    assert_eq!(
+        amounts(&bank, &alice, &credits),
+        vec![Money::from_major(1000), Money::from_major(50)]
+    );
+}
+
+#[test]
+fn date_range_is_half_open() {
+    let (bank, alice, _) = bank_with_activity();
+    let now = Utc::now();
+
+    let around_now = TransactionFilter::new()
+        .from(now - Duration::hours(1))
+        .to(now + Duration::hours(1));
+    assert_eq!(amounts(&bank, &alice, &around_now).len(), 5);
+
+    let future = TransactionFilter::new().from(now + Duration::hours(1));
+    assert!(amounts(&bank, &alice, &future).is_empty());
+
+    let until_now = TransactionFilter::new().to(now - Duration::hours(1));
+    assert!(amounts(&bank, &alice, &until_now).is_empty());
+}
+
+#[test]
+fn counterparty_matches_by_prefix() {
+    let (bank, alice, bob_account) = bank_with_activity();
+
+    let to_bob = TransactionFilter::new().counterparty(bob_account.short());
+    assert_eq!(amounts(&bank, &alice, &to_bob), vec![Money::from_major(100)]);
+
+    let to_nobody = TransactionFilter::new().counterparty("no-such-account");
+    assert!(amounts(&bank, &alice, &to_nobody).is_empty());
+}
+
+#[test]
+fn kinds_parse_from_cli_names() {
+    assert_eq!("transfer-out".parse(), Ok(TransactionKind::TransferOut));
+    assert_eq!("Transfer_In".parse(), Ok(TransactionKind::TransferIn));
+    assert!("refund".parse::<TransactionKind>().is_err());
+}
@@ -0,0 +1,96 @@
+//! Integration tests for demo data seeding
+
+use chrono::NaiveDate;
+use rust_banking_system::bank::SeedOptions;
+use rust_banking_system::models::CustomerId;
+use rust_banking_system::{Bank, BankError, Money};
+
+fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(y, m, d).unwrap()
+}
+
+fn seeded(customers: usize, rng_seed: u64) -> (Bank, Vec<CustomerId>) {
+    let mut bank = Bank::new("Demo Bank".to_string());
+    let options = SeedOptions::new(customers)
+        .from(date(2024, 1, 1))
+        .to(date(2024, 3, 31))
+        .rng_seed(rng_seed);
+    let ids = bank.seed(options).unwrap();
+    (bank, ids)
+}
+
+#[test]
+fn seeded_histories_are_consistent_and_in_range() {
+    let (bank, ids) = seeded(12, 1);
+    assert_eq!(ids.len(), 12);
+
+    let start = date(2024, 1, 1).and_hms_opt(0, 0, 0).unwrap().and_utc();
+    let end = date(2024, 4, 1).and_hms_opt(0, 0, 0).unwrap().and_utc();
+    for id in &ids {
+        let account = bank.get_customer(id).unwrap().get_account().unwrap();
+        assert_eq!(account.created_at.date_naive(), date(2024, 1, 1));
+        assert!(account.transactions.len() > 3);
+
+        let mut balance = Money::ZERO;
+        for pair in account.transactions.windows(2) {
+            assert!(pair[0].timestamp < pair[1].timestamp);
+        }
+        for tx in &account.transactions {
+            assert!(tx.timestamp >= start && tx.timestamp < end);
+            balance += tx.signed_amount();
+            assert_eq!(tx.balance_after, balance);
+            assert!(!balance.is_negative());
+        }
+        assert_eq!(account.balance, balance);
+    }
+}
+
+#[test]
+fn transfers_between_seeded_customers_balance_out() {
+    let (bank, ids) = seeded(12, 2);
+
+    let accounts: Vec<_> = ids.iter().map(|id| bank.get_customer(id).unwrap().get_account().unwrap()).collect();
+    let sent: Money = accounts
+        .iter()
+        .flat_map(|a| &a.transactions)
+        .filter(|tx| tx.transaction_type.counterparty().is_some() && tx.signed_amount().is_negative())
+        .map(|tx| tx.amount)
+        .sum();
+    let received: Money = accounts
+        .iter()
+        .flat_map(|a| &a.transactions)
+        .filter(|tx| tx.transaction_type.counterparty().is_some() && tx.signed_amount().is_positive())
+        .map(|tx| tx.amount)
+        .sum();
+    assert_eq!(sent, received);
+    assert!(sent.is_positive());
+    assert!(accounts.iter().any(|a| !a.category_breakdown().is_empty()));
+}
+
+#[test]
+fn the_same_rng_seed_gives_the_same_data() {
+    let summary = |bank: &Bank, ids: &[CustomerId]| -> Vec<(String, Money, usize)> {
+        ids.iter()
+            .map(|id| {
+                let customer = bank.get_customer(id).unwrap();
+                let account = customer.get_account().unwrap();
+                (customer.name.clone(), account.balance, account.transactions.len())
+            })
+            .collect()
+    };
+
+    let (first, first_ids) = seeded(6, 99);
+    let (second, second_ids) = seeded(6, 99);
+    assert_eq!(summary(&first, &first_ids), summary(&second, &second_ids));
+}
+
+#[test]
+fn seeding_twice_keeps_emails_unique_and_bad_ranges_fail() {
+    let (mut bank, _) = seeded(8, 3);
+    let more = bank.seed(SeedOptions::new(8).rng_seed(3)).unwrap();
+    assert_eq!(more.len(), 8);
+    assert_eq!(bank.list_customers().len(), 16);
+
+    let backwards = SeedOptions::new(1).from(date(2024, 5, 1)).to(date(2024, 4, 1));
+    assert!(matches!(bank.seed(backwards), Err(BankError::InvalidInput(_))));
+}
@@ -0,0 +1,131 @@
+//! Integration tests for the HTTP API (run with `--features server`)
+
+#![cfg(feature = "server")]
+
+use std::sync::Arc;
+
+use axum::body::{self, Body};
+use axum::http::{Request, StatusCode};
+use axum::Router;
+use serde_json::{json, Value};
+use tower::ServiceExt;
+
+use rust_banking_system::server;
+use rust_banking_system::{Bank, BankResult, SharedBank, Storage};
+
+/// Storage that keeps nothing, so tests never touch the file system
+struct NullStorage;
+
+impl Storage for NullStorage {
+    fn save_bank(&self, _bank: &Bank) -> BankResult<()> {
+        Ok(())
+    }
+
+    fn load_bank(&self) -> BankResult<Bank> {
+        Ok(Bank::new("Test Bank".to_string()))
+    }
+}
+
+fn app() -> Router {
+    let bank = SharedBank::new(Bank::new("Test Bank".to_string()));
+    server::router(bank, Arc::new(NullStorage))
+}
+
+/// Sends a request and returns the status and parsed JSON body
+async fn send(app: &Router, method: &str, uri: &str, body: Option<Value>) -> (StatusCode, Value) {
+    let request = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header("content-type", "application/json")
+        .body(body.map_or_else(Body::empty, |b| Body::from(b.to_string())))
+        .unwrap();
+
+    let response = app.clone().oneshot(request).await.unwrap();
+    let status = response.status();
+    let bytes = body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    (status, serde_json::from_slice(&bytes).unwrap())
+}
+
+/// Registers a customer with an account and returns the customer ID
+async fn customer_with_account(app: &Router, name: &str, deposit: &str) -> String {
+    let (_, body) = send(
+        app,
+        "POST",
+        "/customers",
+        Some(json!({ "name": name, "email": format!("{}@example.com", name) })),
+    )
+    .await;
+    let id = body["result"]["customer_id"].as_str().unwrap().to_string();
+
+    let uri = format!("/customers/{}/account", id);
+    let (status, _) = send(app, "POST", &uri, Some(json!({ "deposit": deposit }))).await;
+    assert_eq!(status, StatusCode::OK);
+    id
+}
+
+#[tokio::test]
+async fn deposit_and_transfer_over_http() {
+    let app = app();
+    let alice = customer_with_account(&app, "alice", "100").await;
+    let bob = customer_with_account(&app, "bob", "0").await;
+
+    let uri = format!("/customers/{}/deposit", alice);
+    let (status, body) = send(&app, "POST", &uri, Some(json!({ "amount": "50.25" }))).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["result"]["balance"], "150.25");
+
+    let transfer = json!({ "from": alice, "to": bob, "amount": "25" });
+    let (status, body) = send(&app, "POST", "/transfers", Some(transfer)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["ok"], true);
+
+    let (_, body) = send(&app, "GET", &format!("/customers/{}", bob), None).await;
+    assert_eq!(body["result"]["account"]["balance"], "25.00");
+}
+
+#[tokio::test]
+async fn errors_map_to_status_codes() {
+    let app = app();
+    let alice = customer_with_account(&app, "alice", "10").await;
+
+    let (status, body) = send(&app, "GET", "/customers/nobody", None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(body["ok"], false);
+
+    let uri = format!("/customers/{}/withdraw", alice);
+    let (status, _) = send(&app, "POST", &uri, Some(json!({ "amount": "500" }))).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+
+    let uri = format!("/customers/{}/statement?from=yesterday&to=today", alice);
+    let (status, _) = send(&app, "GET", &uri, None).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn metrics_count_transactions_errors_and_requests() {
+    let app = app();
+    let alice = customer_with_account(&app, "alice", "100").await;
+    let bob = customer_with_account(&app, "bob", "0").await;
+
+    let uri = format!("/customers/{}/deposit", alice);
+    send(&app, "POST", &uri, Some(json!({ "amount": "5" }))).await;
+    let transfer = json!({ "from": alice, "to": bob, "amount": "500" });
+    send(&app, "POST", "/transfers", Some(transfer)).await;
+
+    let request = Request::builder().uri("/metrics").body(Body::empty()).unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(response.headers()["content-type"].to_str().unwrap().starts_with("text/plain"));
+    let bytes = body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let text = String::from_utf8(bytes.to_vec()).unwrap();
+
+    assert!(text.contains("bank_transactions_total{type=\"deposit\"} 1"));
+    assert!(!text.contains("type=\"transfer\""));
+    assert!(text.contains("bank_errors_total{error=\"insufficient_funds\"} 1"));
+    assert!(text.contains("bank_total_balance 105.00"));
+    assert!(text.contains("bank_customers 2"));
+    assert!(text.contains(
+        "bank_operation_duration_seconds_count{operation=\"POST /customers/{id}/deposit\"} 1"
+    ));
+    assert!(!text.contains("operation=\"GET /metrics\""));
+}
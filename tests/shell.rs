@@ -0,0 +1,157 @@
+//! Integration tests for the command shell
+
+#![cfg(feature = "cli")]
+
+mod common;
+
+use clap::error::ErrorKind;
+use clap::CommandFactory;
+use rust_banking_system::cli::args::{Args, Command};
+use rust_banking_system::cli::shell::{expand_shorthand, history_entry, parse_line, ShellHelper, SHORTHANDS};
+use rust_banking_system::{AccountType, Bank, Money};
+
+fn words(line: &str) -> Vec<String> {
+    line.split_whitespace().map(String::from).collect()
+}
+
+fn sample_bank() -> Bank {
+    let mut bank = Bank::new("Test Bank".to_string());
+    let alice = bank.register_customer("Alice Liddell".to_string(), "alice@example.com".to_string()).unwrap();
+    bank.register_customer("Bob".to_string(), "bob@example.com".to_string()).unwrap();
+    bank.create_account_for_customer(&alice, Money::from_major(10), AccountType::Checking).unwrap();
+    bank
+}
+
+#[test]
+fn lines_parse_like_subcommands() {
+    let command = parse_line("deposit --customer alice --amount 12.50 --pin 1234").unwrap().unwrap();
+    assert!(matches!(command, Command::Deposit { customer, amount, pin, description: None }
+        if customer == "alice" && amount == "12.50" && pin == "1234"));
+
+    // Quotes keep a value with spaces together
+    let command = parse_line("register --name 'Alice Liddell' --email alice@example.com").unwrap().unwrap();
+    assert!(matches!(command, Command::Register { name, .. } if name == "Alice Liddell"));
+
+    assert!(parse_line("").unwrap().is_none());
+    assert!(parse_line("   # a comment").unwrap().is_none());
+}
+
+#[test]
+fn common_commands_take_their_first_values_bare() {
+    assert_eq!(
+        expand_shorthand(words("transfer alice bob 5 --pin 1234")),
+        words("transfer --from alice --to bob --amount 5 --pin 1234")
+    );
+    // Bare values after the first option are left for clap to reject
+    assert_eq!(expand_shorthand(words("history --last 3 alice")), words("history --last 3 alice"));
+    assert_eq!(expand_shorthand(words("stats")), words("stats"));
+    assert_eq!(expand_shorthand(words("audit x")), words("audit x"));
+
+    let command = parse_line("history alice@example.com --last 10").unwrap().unwrap();
+    assert!(matches!(command, Command::History { customer, last: Some(10), .. } if customer == "alice@example.com"));
+}
+
+#[test]
+fn every_shorthand_names_real_options() {
+    let args = Args::command();
+    for (name, options) in SHORTHANDS {
+        let command = args.find_subcommand(name).unwrap_or_else(|| panic!("no command {}", name));
+        for option in *options {
+            assert!(command.get_arguments().any(|arg| arg.get_long() == Some(option)), "{} --{}", name, option);
+        }
+    }
+}
+
+#[test]
+fn bad_lines_are_explained_and_help_is_not_an_error() {
+    assert_eq!(parse_line("bogus").unwrap_err().kind(), ErrorKind::InvalidSubcommand);
+    assert_eq!(parse_line("deposit alice").unwrap_err().kind(), ErrorKind::MissingRequiredArgument);
+    assert!(parse_line("deposit 'alice").unwrap_err().to_string().contains("unclosed quote"));
+    assert!(parse_line("history alice --last 2 --limit 5").is_err());
+
+    let help = parse_line("help").unwrap_err();
+    assert_eq!(help.kind(), ErrorKind::DisplayHelp);
+    assert!(help.to_string().contains("deposit"));
+    assert!(parse_line("help transfer").unwrap_err().to_string().contains("--from"));
+}
+
+#[test]
+fn tab_completes_commands_then_options() {
+    let helper = ShellHelper::default();
+    let (start, candidates) = helper.candidates("dep", 3);
+    assert_eq!(start, 0);
+    assert_eq!(candidates, ["deposit", "deposit-cheque", "deposit-to-envelope"]);
+    assert_eq!(helper.candidates("ex", 2).1, ["external-transfer", "export", "export-bank", "exit"]);
+    assert_eq!(helper.candidates("hel", 3).1, ["help"]);
+
+    let line = "history alice --la";
+    assert_eq!(helper.candidates(line, line.len()), (14, vec!["--last".to_string()]));
+    // Completion works at the cursor, not the end of the line
+    assert_eq!(helper.candidates("wi alice", 2).1, ["withdraw", "withdraw-term-deposit"]);
+}
+
+#[test]
+fn tab_completes_customer_names_and_emails() {
+    let mut helper = ShellHelper::new(&sample_bank());
+    let line = "deposit al";
+    let expected = vec!["'Alice Liddell'".to_string(), "alice@example.com".to_string()];
+    assert_eq!(helper.candidates(line, line.len()), (8, expected));
+
+    // A quoted name being typed is one word, spaces and all
+    let line = "deposit 'Alice L";
+    assert_eq!(helper.candidates(line, line.len()), (8, vec!["'Alice Liddell'".to_string()]));
+
+    let mut bank = sample_bank();
+    bank.register_customer("Carol".to_string(), "carol@example.com".to_string()).unwrap();
+    assert!(helper.candidates("account c", 9).1.is_empty());
+    helper.refresh(&bank);
+    assert_eq!(helper.candidates("account c", 9).1, ["Carol", "carol@example.com"]);
+}
+
+#[test]
+fn pins_are_masked_in_the_history() {
+    assert_eq!(history_entry("deposit alice 5 --pin 1234"), "deposit alice 5 --pin ****");
+    assert_eq!(
+        history_entry("change-pin --customer bob --current-pin=1111 --new-pin 2222"),
+        "change-pin --customer bob --current-pin=**** --new-pin ****"
+    );
+    assert_eq!(history_entry("  history   alice --last 2 "), "history alice --last 2");
+    assert_eq!(history_entry("search pinto"), "search pinto");
+}
+
+#[test]
+fn a_session_runs_commands_and_remembers_them() {
+    let dir = common::temp_path("shell");
+    std::fs::create_dir_all(&dir).unwrap();
+    let _ = std::fs::remove_file(dir.join("bank.json"));
+    let _ = std::fs::remove_file(dir.join(".bank_history"));
+
+    let input = "register Ann ann@example.com --pin 1234\n\
+                 create-account ann@example.com --deposit 50\n\
+                 bogus\n\
+                 deposit ann@example.com 25 --pin 1234\n\
+                 history ann@example.com --last 1\n\
+                 exit\n\
+                 stats\n";
+    let mut command = common::command("bank.json", &["--json", "shell"]);
+    // The history file goes in the session's own home
+    command.current_dir(&dir).env("XDG_CONFIG_HOME", &dir).env("HOME", &dir);
+    let output = common::feed(command, input);
+    assert!(output.status.success());
+
+    let results: Vec<serde_json::Value> =
+        String::from_utf8_lossy(&output.stdout).lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+    // The mistyped line is reported and the session goes on; nothing runs after `exit`
+    assert_eq!(results.len(), 5);
+    assert_eq!(results[2]["ok"], false);
+    assert_eq!(results[3]["result"]["balance"], "75.00");
+    let history = results[4]["result"].as_array().unwrap();
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0]["amount"], "25.00");
+
+    let saved = std::fs::read_to_string(dir.join(".bank_history")).unwrap();
+    assert!(saved.contains("deposit ann@example.com 25 --pin ****") && saved.contains("bogus"));
+    assert!(!saved.contains("1234"));
+    assert!(!saved.contains("stats"));
+    let _ = std::fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,148 @@
+//! Integration tests for comparing two saved bank states
+
+// The tests that run the binary need the `cli` feature; without it the
+// imports and helpers only they use go unused
+#![cfg_attr(not(feature = "cli"), allow(unused))]
+
+mod common;
+
+use rust_banking_system::bank::{BatchMode, Operation};
+use rust_banking_system::persistence::{self, diff_banks, journal_path};
+use rust_banking_system::{AccountType, Bank, BankError, Money};
+
+use common::{bank_with_customers, temp_path};
+
+/// A copy of the bank as it would be reloaded from its data file
+fn snapshot(bank: &Bank) -> Bank {
+    serde_json::from_str(&serde_json::to_string(bank).unwrap()).unwrap()
+}
+
+#[test]
+fn identical_states_have_no_differences() {
+    let (bank, _, _) = bank_with_customers(100, 100);
+    let diff = diff_banks(&bank, &snapshot(&bank));
+    assert!(diff.is_empty());
+    assert_eq!(diff.to_string(), "No differences");
+}
+
+#[test]
+fn new_transactions_and_balance_changes_are_reported() {
+    let (mut bank, alice, bob) = bank_with_customers(100, 100);
+    let before = snapshot(&bank);
+    bank.transfer(&alice, &bob, Money::from_major(30)).unwrap();
+    bank.deposit(&alice, Money::from_major(5)).unwrap();
+
+    let diff = diff_banks(&before, &bank);
+    assert!(diff.added_customers.is_empty() && diff.removed_customers.is_empty());
+    assert_eq!(diff.new_transactions.len(), 3);
+    assert_eq!(diff.new_transactions.iter().filter(|new| new.customer_id == alice).count(), 2);
+    let changes: Vec<_> = diff.balance_changes.iter().map(|c| (c.name.as_str(), c.before, c.after)).collect();
+    assert_eq!(
+        changes,
+        vec![
+            ("Alice", Money::from_major(100), Money::from_major(75)),
+            ("Bob", Money::from_major(100), Money::from_major(130)),
+        ]
+    );
+    assert_eq!(diff.balance_changes[0].change(), Money::from_major(-25));
+}
+
+#[test]
+fn added_and_removed_customers_are_reported() {
+    let (mut bank, _, bob) = bank_with_customers(100, 100);
+    bank.withdraw(&bob, Money::from_major(100)).unwrap();
+    let before = snapshot(&bank);
+    bank.delete_customer(&bob).unwrap();
+    let carol = bank.register_customer("Carol".to_string(), "carol@example.com".to_string()).unwrap();
+    bank.create_account_for_customer(&carol, Money::from_major(60), AccountType::Savings).unwrap();
+
+    let diff = diff_banks(&before, &bank);
+    assert_eq!(diff.added_customers.len(), 1);
+    assert_eq!(diff.added_customers[0].customer_id, carol);
+    assert_eq!(diff.added_customers[0].balance, Some(Money::from_major(60)));
+    assert_eq!(diff.removed_customers.len(), 1);
+    assert_eq!(diff.removed_customers[0].email, "bob@example.com");
+    assert!(diff.balance_changes.is_empty(), "only customers in both states have balance changes");
+    assert_eq!(diff.new_transactions.len(), 1, "Carol's opening deposit");
+}
+
+#[test]
+fn opening_an_account_is_a_change_from_zero() {
+    let mut bank = Bank::new("Test Bank".to_string());
+    let dave = bank.register_customer("Dave".to_string(), "dave@example.com".to_string()).unwrap();
+    let before = snapshot(&bank);
+    bank.create_account_for_customer(&dave, Money::from_major(20), AccountType::Checking).unwrap();
+
+    let diff = diff_banks(&before, &bank);
+    assert_eq!(diff.balance_changes.len(), 1);
+    assert_eq!(diff.balance_changes[0].before, Money::ZERO);
+    assert_eq!(diff.balance_changes[0].change(), Money::from_major(20));
+    let text = diff.to_string();
+    assert!(text.contains("Balance changes (1):"), "{}", text);
+    assert!(text.contains("New transactions (1):"), "{}", text);
+}
+
+#[test]
+fn files_are_compared_with_their_journals_and_left_untouched() {
+    let (mut bank, alice, bob) = bank_with_customers(100, 100);
+    let file_a = temp_path("a.json");
+    let file_b = temp_path("b.json");
+    persistence::save_bank(&bank, &file_a.to_string_lossy()).unwrap();
+    persistence::save_bank(&bank, &file_b.to_string_lossy()).unwrap();
+
+    // A batch run that crashed before saving leaves its changes in the journal
+    bank.enable_journal();
+    let payroll = vec![Operation::Transfer {
+        from: alice.to_string(),
+        to: bob.to_string(),
+        amount: Money::from_major(40),
+        description: Some("Salary".to_string()),
+    }];
+    bank.apply_batch(payroll, BatchMode::AllOrNothing);
+    let journal = journal_path(&file_b.to_string_lossy());
+    persistence::append_journal(&file_b.to_string_lossy(), &bank.take_journal()).unwrap();
+
+    let diff = persistence::diff(&file_a.to_string_lossy(), &file_b.to_string_lossy()).unwrap();
+    assert_eq!(diff.new_transactions.len(), 2);
+    assert_eq!(diff.balance_changes.len(), 2);
+    assert!(std::path::Path::new(&journal).exists(), "diff must not compact the journal");
+
+    let _ = std::fs::remove_file(&journal);
+    let _ = std::fs::remove_file(&file_a);
+    let _ = std::fs::remove_file(&file_b);
+}
+
+#[test]
+fn a_missing_file_is_an_error() {
+    let file = temp_path("missing.json");
+    let result = persistence::diff(&file.to_string_lossy(), &file.to_string_lossy());
+    assert!(matches!(result, Err(BankError::IoError(_))));
+}
+
+#[cfg(feature = "cli")]
+#[test]
+fn command_mode_diffs_a_backup_against_the_data_file() {
+    let data_file = temp_path("cli.json");
+    let _ = std::fs::remove_file(&data_file);
+    let _ = std::fs::remove_file(journal_path(&data_file.to_string_lossy()));
+    let run = |args: &[&str]| common::run(&data_file, args, "\n");
+    run(&["register", "--name", "Ann", "--email", "ann@example.com", "--pin", "1234"]);
+    run(&["create-account", "--customer", "ann@example.com", "--deposit", "100"]);
+    run(&["deposit", "--customer", "ann@example.com", "--amount", "25", "--pin", "1234"]);
+
+    // The deposit's save rotated the previous state into backup 1
+    let backup = persistence::backup_path(&data_file.to_string_lossy(), 1);
+    let after = data_file.to_string_lossy().into_owned();
+    let json: serde_json::Value =
+        serde_json::from_str(&run(&["--json", "diff", "--before", &backup, "--after", &after])).unwrap();
+    assert_eq!(json["ok"], true);
+    assert_eq!(json["result"]["balance_changes"][0]["before"], "100.00");
+    assert_eq!(json["result"]["balance_changes"][0]["after"], "125.00");
+    assert_eq!(json["result"]["new_transactions"].as_array().unwrap().len(), 1);
+    assert_eq!(json["result"]["added_customers"], serde_json::json!([]));
+
+    for index in 1..=3 {
+        let _ = std::fs::remove_file(persistence::backup_path(&data_file.to_string_lossy(), index));
+    }
+    let _ = std::fs::remove_file(&data_file);
+}
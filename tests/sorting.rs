@@ -0,0 +1,71 @@
+//! Integration tests for sorted customer listings
+
+use std::sync::Arc;
+
+use chrono::{Duration, TimeZone, Utc};
+use rust_banking_system::bank::{SortKey, SortOrder};
+use rust_banking_system::clock::MockClock;
+use rust_banking_system::{AccountType, Bank, Money};
+
+/// Carol, alice and Bob registered a day apart, in that order; alice has
+/// $50, Bob $500 and Carol no account
+fn sample_bank() -> Bank {
+    let clock = Arc::new(MockClock::new(Utc.with_ymd_and_hms(2024, 1, 1, 9, 0, 0).unwrap()));
+    let mut bank = Bank::new("Test Bank".to_string());
+    bank.set_clock(clock.clone());
+    for (name, email, deposit) in [
+        ("Carol", "carol@example.com", None),
+        ("alice", "alice@example.com", Some(50)),
+        ("Bob", "bob@example.com", Some(500)),
+    ] {
+        let id = bank.register_customer(name.to_string(), email.to_string()).unwrap();
+        if let Some(deposit) = deposit {
+            bank.create_account_for_customer(&id, Money::from_major(deposit), AccountType::Checking).unwrap();
+        }
+        clock.advance(Duration::days(1));
+    }
+    bank
+}
+
+fn names(bank: &Bank, key: SortKey, order: SortOrder) -> Vec<String> {
+    bank.list_customers_sorted(key, order).iter().map(|c| c.name.clone()).collect()
+}
+
+#[test]
+fn customers_sort_by_name_ignoring_case() {
+    let bank = sample_bank();
+    assert_eq!(names(&bank, SortKey::Name, SortOrder::Ascending), ["alice", "Bob", "Carol"]);
+    assert_eq!(names(&bank, SortKey::Name, SortOrder::Descending), ["Carol", "Bob", "alice"]);
+}
+
+#[test]
+fn customers_sort_by_registration() {
+    let bank = sample_bank();
+    assert_eq!(names(&bank, SortKey::Registered, SortOrder::Ascending), ["Carol", "alice", "Bob"]);
+    assert_eq!(names(&bank, SortKey::Registered, SortOrder::Descending), ["Bob", "alice", "Carol"]);
+}
+
+#[test]
+fn customers_without_an_account_sort_below_any_balance() {
+    let bank = sample_bank();
+    assert_eq!(names(&bank, SortKey::Balance, SortOrder::Ascending), ["Carol", "alice", "Bob"]);
+    assert_eq!(names(&bank, SortKey::Balance, SortOrder::Descending), ["Bob", "alice", "Carol"]);
+}
+
+#[test]
+fn ties_fall_back_to_registration_order() {
+    let mut bank = sample_bank();
+    let carol = bank.find_customer("carol@example.com").unwrap().id.clone();
+    bank.create_account_for_customer(&carol, Money::from_major(50), AccountType::Savings).unwrap();
+
+    // Carol and alice both have $50; Carol registered first
+    assert_eq!(names(&bank, SortKey::Balance, SortOrder::Ascending), ["Carol", "alice", "Bob"]);
+    assert_eq!(names(&bank, SortKey::Balance, SortOrder::Descending), ["Bob", "Carol", "alice"]);
+}
+
+#[test]
+fn search_results_come_back_in_registration_order() {
+    let bank = sample_bank();
+    let found: Vec<&str> = bank.find_customers_by_name("o").iter().map(|c| c.name.as_str()).collect();
+    assert_eq!(found, ["Carol", "Bob"]);
+}
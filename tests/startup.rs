@@ -0,0 +1,162 @@
+//! Integration tests for startup overrides and read-only storage
+
+#![cfg(feature = "cli")]
+
+mod common;
+
+use std::fs;
+
+use clap::Parser;
+use rust_banking_system::bank::Bank;
+use rust_banking_system::cli::args::{Args, Command};
+use rust_banking_system::cli::{self, StartupArgs};
+use rust_banking_system::models::CustomerId;
+use rust_banking_system::persistence::{self, JsonFileStorage, ReadOnlyStorage, Storage};
+use rust_banking_system::{AccountType, BankError, Config, Money};
+
+use common::temp_file;
+
+fn config_from_file() -> Config {
+    Config {
+        data_file: "from-config.json".to_string(),
+        bank_name: "Config Bank".to_string(),
+        ..Config::default()
+    }
+}
+
+#[test]
+fn flags_are_accepted_before_or_after_the_subcommand() {
+    let args = Args::try_parse_from([
+        "banking-cli",
+        "--data-file",
+        "campus.json",
+        "stats",
+        "--bank-name",
+        "Campus Credit Union",
+        "--read-only",
+        "--locale",
+        "es-ES",
+    ])
+    .unwrap();
+
+    assert!(matches!(args.command, Some(Command::Stats)));
+    assert_eq!(args.startup.data_file.as_deref(), Some("campus.json"));
+    assert_eq!(args.startup.bank_name.as_deref(), Some("Campus Credit Union"));
+    assert!(args.startup.read_only);
+    assert_eq!(args.startup.locale.as_deref(), Some("es-ES"));
+}
+
+#[test]
+fn flags_beat_the_environment_which_beats_the_config_file() {
+    let none = StartupArgs::default();
+    assert_eq!(none.apply(config_from_file(), None).unwrap(), config_from_file());
+
+    let from_env = none.apply(config_from_file(), Some("from-env.json".to_string())).unwrap();
+    assert_eq!(from_env.data_file, "from-env.json");
+
+    let flags = StartupArgs {
+        data_file: Some("from-flag.json".to_string()),
+        bank_name: Some("  Flag Bank ".to_string()),
+        read_only: true,
+        locale: Some(" es-MX ".to_string()),
+        ..StartupArgs::default()
+    };
+    let from_flags = flags.apply(config_from_file(), Some("from-env.json".to_string())).unwrap();
+    assert_eq!(from_flags.data_file, "from-flag.json");
+    assert_eq!(from_flags.bank_name, "Flag Bank");
+    assert!(from_flags.read_only);
+    assert_eq!(from_flags.locale, "es-MX");
+}
+
+#[test]
+fn an_empty_environment_variable_is_ignored() {
+    let config = StartupArgs::default().apply(config_from_file(), Some("  ".to_string())).unwrap();
+    assert_eq!(config.data_file, "from-config.json");
+}
+
+#[test]
+fn overridden_settings_are_validated() {
+    let blank_name = StartupArgs { bank_name: Some("   ".to_string()), ..StartupArgs::default() };
+    assert!(matches!(
+        blank_name.apply(Config::default(), None),
+        Err(BankError::ValidationError { field, .. }) if field == "bank_name"
+    ));
+
+    let blank_file = StartupArgs { data_file: Some(String::new()), ..StartupArgs::default() };
+    assert!(matches!(
+        blank_file.apply(Config::default(), None),
+        Err(BankError::ValidationError { field, .. }) if field == "data_file"
+    ));
+}
+
+/// Saves a bank with one funded customer (PIN 1234) to `path` and journals a deposit
+/// on top, as an interrupted session would leave it; returns the customer
+fn interrupted_session(path: &str) -> CustomerId {
+    let mut bank = Bank::new("Test Bank".to_string());
+    let alice = bank.register_customer("Alice".to_string(), "alice@example.com".to_string()).unwrap();
+    bank.create_account_for_customer(&alice, Money::from_major(100), AccountType::Checking).unwrap();
+    bank.set_pin(&alice, "1234").unwrap();
+    persistence::save_bank(&bank, path).unwrap();
+
+    bank.enable_journal();
+    bank.deposit(&alice, Money::from_major(50)).unwrap();
+    persistence::append_journal(path, &bank.take_journal()).unwrap();
+    alice
+}
+
+fn remove_data_file(path: &str) {
+    let _ = fs::remove_file(path);
+    let _ = fs::remove_file(persistence::journal_path(path));
+}
+
+#[test]
+fn read_only_storage_loads_without_writing() {
+    let path = temp_file("read-only.json");
+    let alice = interrupted_session(&path);
+    let before = fs::read(&path).unwrap();
+
+    let storage = ReadOnlyStorage::new(Box::new(JsonFileStorage::new(path.as_str())));
+    let bank = storage.load_bank().unwrap();
+    let balance = bank.get_customer(&alice).unwrap().get_account().unwrap().balance;
+    let saved = storage.save_bank(&bank);
+    let restored = storage.restore_backup(1);
+    let untouched = fs::read(&path).unwrap() == before;
+    let journal_kept = std::path::Path::new(&persistence::journal_path(&path)).exists();
+    remove_data_file(&path);
+
+    assert_eq!(balance, Money::from_major(150));
+    assert!(matches!(saved, Err(BankError::ReadOnly(_))));
+    assert!(matches!(restored, Err(BankError::ReadOnly(_))));
+    assert!(untouched);
+    assert!(journal_kept);
+    assert!(storage.location().ends_with("(read-only)"));
+}
+
+#[test]
+fn read_only_commands_may_read_but_not_write() {
+    let path = temp_file("commands.json");
+    let alice = interrupted_session(&path);
+    let before = fs::read(&path).unwrap();
+    let config = Config { data_file: path.clone(), read_only: true, ..Config::default() };
+
+    let deposit = |customer: &CustomerId| Command::Deposit {
+        customer: customer.clone(),
+        amount: "10".to_string(),
+        pin: "1234".to_string(),
+        description: None,
+    };
+
+    let stats = cli::run_command(Command::Stats, &config);
+    let refused = cli::run_command(deposit(&alice), &config);
+    let untouched = fs::read(&path).unwrap() == before;
+    let writable = Config { read_only: false, ..config };
+    let accepted = cli::run_command(deposit(&alice), &writable);
+    let changed = fs::read(&path).unwrap() != before;
+    remove_data_file(&path);
+
+    assert_eq!(stats, 0);
+    assert_eq!(refused, 1);
+    assert!(untouched);
+    assert_eq!(accepted, 0);
+    assert!(changed);
+}
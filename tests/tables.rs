@@ -0,0 +1,180 @@
+//! Integration tests for table-formatted listings
+
+#![cfg(feature = "cli")]
+
+use rust_banking_system::cli::table::{
+    self, audit_table, customer_table, parse_columns, transaction_table, Align, ColumnSet, CustomerColumn, Table,
+    TransactionColumn,
+};
+use rust_banking_system::models::CustomerId;
+use rust_banking_system::{AccountType, Bank, BankError, Config, Money};
+
+/// A bank where Alice has $1,500 and sent Bob $250.50, and Carol has no
+/// account; returns (bank, alice, bob)
+fn sample_bank() -> (Bank, CustomerId, CustomerId) {
+    let mut bank = Bank::new("Test Bank".to_string());
+    let alice = bank.register_customer("Alice Liddell".to_string(), "alice@example.com".to_string()).unwrap();
+    let bob = bank.register_customer("Bob".to_string(), "bob@example.com".to_string()).unwrap();
+    bank.register_customer("Carol".to_string(), "carol@example.com".to_string()).unwrap();
+    bank.create_account_for_customer(&alice, Money::from_major(1_500), AccountType::Checking).unwrap();
+    bank.create_account_for_customer(&bob, Money::ZERO, AccountType::Savings).unwrap();
+    bank.transfer(&alice, &bob, Money::from_cents(25_050)).unwrap();
+    (bank, alice, bob)
+}
+
+fn names(columns: &[&str]) -> Vec<String> {
+    columns.iter().map(|name| name.to_string()).collect()
+}
+
+#[test]
+fn columns_are_aligned_under_a_header_with_a_totals_row() {
+    let mut table = Table::new().column("Item", Align::Left, None).column("Cost", Align::Right, None);
+    table.row(vec!["Tea".to_string(), "$4.50".to_string()]);
+    table.row(vec!["Sandwich".to_string(), "$12.00".to_string()]);
+    table.totals(vec!["Total".to_string(), "$16.50".to_string()]);
+
+    assert_eq!(
+        table.render(),
+        "Item        Cost\n\
+         ────────────────\n\
+         Tea        $4.50\n\
+         Sandwich  $12.00\n\
+         ────────────────\n\
+         Total     $16.50\n"
+    );
+    assert!(!table.is_empty());
+    assert!(Table::new().is_empty());
+}
+
+#[test]
+fn long_cells_are_cut_short_and_missing_ones_left_blank() {
+    let mut table = Table::new().column("Name", Align::Left, Some(6)).column("Note", Align::Left, None);
+    table.row(vec!["Bartholomew".to_string(), "a very long note stays whole".to_string()]);
+    table.row(vec!["Ann".to_string()]);
+
+    let rendered = table.render();
+    let lines: Vec<&str> = rendered.lines().collect();
+    assert_eq!(lines[2], "Barth…  a very long note stays whole");
+    // Trailing padding is trimmed
+    assert_eq!(lines[3], "Ann");
+}
+
+#[test]
+fn column_lists_are_parsed_and_checked() {
+    let columns: Vec<CustomerColumn> = parse_columns(&names(&["Name", " balance "])).unwrap();
+    assert_eq!(columns, [CustomerColumn::Name, CustomerColumn::Balance]);
+
+    let unknown = parse_columns::<CustomerColumn>(&names(&["name", "shoe_size"])).unwrap_err();
+    assert!(unknown.contains("shoe_size") && unknown.contains("balance"), "{}", unknown);
+    assert!(parse_columns::<TransactionColumn>(&names(&["date", "date"])).unwrap_err().contains("twice"));
+    assert!(parse_columns::<TransactionColumn>(&[]).is_err());
+
+    for &column in TransactionColumn::ALL {
+        assert_eq!(column.to_string().parse::<TransactionColumn>().unwrap(), column);
+    }
+    for &column in CustomerColumn::ALL {
+        assert_eq!(column.name().parse::<CustomerColumn>().unwrap(), column);
+    }
+}
+
+#[test]
+fn customer_tables_total_the_balances_shown() {
+    let (bank, _, _) = sample_bank();
+    let customers = bank.find_customers_by_name("");
+    let rendered = customer_table(&customers).render();
+    let lines: Vec<&str> = rendered.lines().collect();
+
+    assert!(lines[0].starts_with("ID") && lines[0].ends_with("Balance"), "{}", lines[0]);
+    assert_eq!(lines.len(), 2 + customers.len() + 2);
+    assert!(rendered.contains("Alice Liddell") && rendered.contains("Checking"));
+    // Carol has no account, so her account cells are blank
+    let carol = lines.iter().find(|line| line.contains("Carol")).unwrap();
+    assert!(carol.ends_with("Active"), "{}", carol);
+    assert!(lines.last().unwrap().starts_with("Total (3)"));
+    assert!(lines.last().unwrap().ends_with("$1,500.00"));
+}
+
+#[test]
+fn transaction_tables_sign_amounts_and_show_the_net() {
+    let (bank, alice, _) = sample_bank();
+    let account = bank.get_customer(&alice).unwrap().get_account().unwrap();
+    let transactions: Vec<_> = account.transactions.iter().collect();
+
+    let rendered = transaction_table(&transactions, |_| None).render();
+    assert!(rendered.contains("-$250.50"), "{}", rendered);
+    assert!(rendered.contains("$1,249.50"));
+    let totals = rendered.lines().last().unwrap();
+    assert!(totals.starts_with("Total (2)") && totals.contains("$1,249.50"), "{}", totals);
+
+    let counterparty = TransactionColumn::Counterparty;
+    let transfer = transactions[1];
+    let bob_account = transfer.transaction_type.counterparty().unwrap();
+    assert_eq!(counterparty.cell(transfer), bob_account[..8]);
+    assert_eq!(counterparty.cell(transactions[0]), "");
+
+    let named = transaction_table(&transactions, |tx| tx.transaction_type.counterparty().map(|_| "Bob".to_string()));
+    assert!(named.render().contains("Bob"));
+}
+
+#[test]
+fn cells_show_each_field() {
+    let (bank, _, bob) = sample_bank();
+    let bob = bank.get_customer(&bob).unwrap();
+    assert_eq!(CustomerColumn::Id.cell(bob), bob.id.short());
+    assert_eq!(CustomerColumn::Email.cell(bob), "bob@example.com");
+    assert_eq!(CustomerColumn::Type.cell(bob), "Savings");
+    // Opening with nothing records no deposit
+    assert_eq!(CustomerColumn::Transactions.cell(bob), "1");
+
+    let incoming = &bob.get_account().unwrap().transactions[0];
+    assert_eq!(TransactionColumn::Amount.cell(incoming), "$250.50");
+    assert!(TransactionColumn::Type.cell(incoming).starts_with("TRANSFER from"));
+}
+
+#[test]
+fn the_config_picks_the_columns() {
+    let config = Config::default();
+    assert_eq!(config.table_columns(), (CustomerColumn::DEFAULT.to_vec(), TransactionColumn::DEFAULT.to_vec()));
+
+    let config =
+        Config::from_toml("customer_columns = [\"name\", \"email\"]\ntransaction_columns = [\"date\", \"amount\"]").unwrap();
+    assert_eq!(
+        config.table_columns(),
+        (vec![CustomerColumn::Name, CustomerColumn::Email], vec![TransactionColumn::Date, TransactionColumn::Amount])
+    );
+
+    let bad = Config { transaction_columns: names(&["when"]), ..Config::default() };
+    assert!(matches!(bad.validate(), Err(BankError::ValidationError { field, .. }) if field == "transaction_columns"));
+    let empty = Config { customer_columns: Vec::new(), ..Config::default() };
+    assert!(matches!(empty.validate(), Err(BankError::ValidationError { field, .. }) if field == "customer_columns"));
+}
+
+// The only test that changes the installed columns, so tests running in
+// parallel never see each other's
+#[test]
+fn installed_columns_shape_every_table() {
+    let (bank, _, _) = sample_bank();
+    let customers = bank.find_customers_by_name("Bob");
+
+    table::set_columns(vec![CustomerColumn::Name, CustomerColumn::Balance], vec![TransactionColumn::Id]);
+    let rendered = customer_table(&customers).render();
+    table::set_columns(CustomerColumn::DEFAULT.to_vec(), TransactionColumn::DEFAULT.to_vec());
+
+    assert_eq!(rendered.lines().next().unwrap(), "Name       Balance");
+    assert_eq!(rendered.lines().nth(2).unwrap(), "Bob        $250.50");
+    assert_eq!(table::customer_columns(), CustomerColumn::DEFAULT);
+}
+
+#[test]
+fn audit_tables_list_one_fact_per_row() {
+    let (bank, alice, _) = sample_bank();
+    let customer = bank.get_customer(&alice).unwrap();
+
+    let rendered = audit_table(customer).render();
+    let lines: Vec<&str> = rendered.lines().collect();
+
+    assert!(lines[0].starts_with("Fact"));
+    assert_eq!(lines[2], format!("id             {}", alice));
+    assert!(lines.contains(&"email          alice@example.com"));
+    assert_eq!(lines.len(), 2 + 6);
+}
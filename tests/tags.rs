@@ -0,0 +1,116 @@
+//! Integration tests for transaction categories, tags and spending breakdowns
+
+mod common;
+
+use rust_banking_system::models::{TransactionDetails, TransactionFilter, TransactionTags};
+use rust_banking_system::persistence;
+use rust_banking_system::{Bank, BankError, Money};
+
+use common::{bank_with_customers, temp_path};
+
+fn tags(category: &str, tags: &str) -> TransactionTags {
+    TransactionTags::parse(category, tags).unwrap()
+}
+
+fn tagged(category: &str, labels: &str) -> TransactionDetails {
+    TransactionDetails::new().tags(tags(category, labels))
+}
+
+#[test]
+fn labels_are_normalized_and_validated() {
+    let parsed = tags("  Rent ", "Monthly, ,home,monthly");
+    assert_eq!(parsed.category.as_deref(), Some("rent"));
+    assert_eq!(parsed.tags, ["monthly", "home"]);
+    assert!(tags("", " , ").is_empty());
+
+    let too_long = "x".repeat(33);
+    assert!(matches!(TransactionTags::parse(&too_long, ""), Err(BankError::InvalidInput(_))));
+    assert!(matches!(TransactionTags::new(None, ["a,b"]), Err(BankError::InvalidInput(_))));
+}
+
+#[test]
+fn withdrawals_and_transfers_carry_tags() {
+    let (mut bank, alice, bob) = bank_with_customers(1_000, 0);
+    bank.withdraw_with(&alice, Money::from_major(40), tagged("Groceries", "food"))
+        .unwrap();
+    bank.transfer_with(&alice, &bob, Money::from_major(500), tagged("rent", "monthly"))
+        .unwrap();
+
+    let history = bank.get_customer(&alice).unwrap().get_account().unwrap().get_transaction_history();
+    assert_eq!(history[1].category.as_deref(), Some("groceries"));
+    assert!(history[1].to_string().ends_with("(groceries) #food"), "{}", history[1]);
+    assert_eq!(history[2].tags, ["monthly"]);
+
+    // The recipient's side is not filed under the sender's category
+    let received = &bank.get_customer(&bob).unwrap().get_account().unwrap().transactions[0];
+    assert!(received.category.is_none() && received.tags.is_empty());
+}
+
+#[test]
+fn tagging_after_the_fact_is_journaled() {
+    let (mut bank, alice, _) = bank_with_customers(1_000, 0);
+    bank.withdraw(&alice, Money::from_major(25)).unwrap();
+    let tx_id = bank.get_customer(&alice).unwrap().get_account().unwrap().transactions[1].id.clone();
+    let snapshot = serde_json::to_string(&bank).unwrap();
+
+    bank.enable_journal();
+    bank.tag_transaction(&alice, &tx_id, tags("coffee", "treat")).unwrap();
+    assert!(bank.tag_transaction(&alice, &"missing".into(), tags("x", "")).is_err());
+
+    let account = bank.get_customer(&alice).unwrap().get_account().unwrap();
+    let found = account.filter_transactions(&TransactionFilter::new().tag("TREAT"));
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].id, tx_id);
+
+    // The snapshot plus the journal gives the tagged state
+    let mut restored: Bank = serde_json::from_str(&snapshot).unwrap();
+    for event in bank.take_journal() {
+        assert!(restored.apply_event(event.clone()).unwrap());
+        assert!(!restored.apply_event(event).unwrap());
+    }
+    let tx = &restored.get_customer(&alice).unwrap().get_account().unwrap().transactions[1];
+    assert_eq!(tx.category.as_deref(), Some("coffee"));
+}
+
+#[test]
+fn breakdown_groups_spending_and_skips_undone_items() {
+    let (mut bank, alice, bob) = bank_with_customers(1_000, 0);
+    bank.withdraw_with(&alice, Money::from_major(40), tagged("groceries", "")).unwrap();
+    bank.withdraw_with(&alice, Money::from_major(60), tagged("Groceries", "")).unwrap();
+    bank.withdraw(&alice, Money::from_major(10)).unwrap();
+    bank.transfer_with(&alice, &bob, Money::from_major(500), tagged("rent", "")).unwrap();
+    bank.withdraw_with(&alice, Money::from_major(99), tagged("mistake", "")).unwrap();
+    bank.undo_last().unwrap();
+
+    let breakdown = bank.get_customer(&alice).unwrap().get_account().unwrap().category_breakdown();
+    let summary: Vec<(Option<&str>, usize, Money)> = breakdown
+        .iter()
+        .map(|c| (c.category.as_deref(), c.transactions, c.amount))
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            (Some("rent"), 1, Money::from_major(500)),
+            (Some("groceries"), 2, Money::from_major(100)),
+            (None, 1, Money::from_major(10)),
+        ]
+    );
+}
+
+#[test]
+fn tags_survive_a_csv_round_trip() {
+    let (mut bank, alice, _) = bank_with_customers(1_000, 0);
+    bank.withdraw_with(&alice, Money::from_major(40), tagged("groceries", "food, weekly"))
+        .unwrap();
+
+    let path = temp_path("tags.csv");
+    let path = path.to_str().unwrap();
+    let account = bank.get_customer(&alice).unwrap().get_account().unwrap();
+    persistence::export_transactions_csv(account, path).unwrap();
+    let imported = persistence::import_transactions_csv(path).unwrap();
+    std::fs::remove_file(path).unwrap();
+
+    assert_eq!(imported[1].category.as_deref(), Some("groceries"));
+    assert_eq!(imported[1].tags, ["food", "weekly"]);
+    assert!(imported[0].category.is_none() && imported[0].tags.is_empty());
+}
@@ -0,0 +1,167 @@
+//! Integration tests for term deposits
+
+mod common;
+
+use std::sync::Arc;
+
+use chrono::{DateTime, Duration, Months, TimeZone, Utc};
+use rust_banking_system::clock::MockClock;
+use rust_banking_system::models::{CustomerId, TermDepositStatus, TransactionKind, TransactionType};
+use rust_banking_system::validation::parse_interest_rate;
+use rust_banking_system::{AccountType, Bank, BankError, Money};
+
+use common::balance;
+
+fn start() -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2024, 1, 15, 9, 0, 0).unwrap()
+}
+
+/// Creates a bank on a mock clock where Alice has $10,000; returns
+/// (bank, clock, alice)
+fn bank_at_start() -> (Bank, Arc<MockClock>, CustomerId) {
+    let clock = Arc::new(MockClock::new(start()));
+    let mut bank = Bank::new("Test Bank".to_string());
+    bank.set_clock(clock.clone());
+    let alice = bank.register_customer("Alice".to_string(), "alice@example.com".to_string()).unwrap();
+    bank.create_account_for_customer(&alice, Money::from_major(10_000), AccountType::Checking)
+        .unwrap();
+    (bank, clock, alice)
+}
+
+#[test]
+fn opening_moves_the_principal_out_of_the_account() {
+    let (mut bank, _, alice) = bank_at_start();
+    let deposit = bank.open_term_deposit(&alice, Money::from_major(4_000), 450, 12).unwrap();
+
+    assert_eq!(deposit.status, TermDepositStatus::Active);
+    assert_eq!(deposit.maturity_date, start().checked_add_months(Months::new(12)).unwrap());
+    assert_eq!(balance(&bank, &alice), Money::from_major(6_000));
+
+    let account = bank.get_customer(&alice).unwrap().get_account().unwrap();
+    let debit = account.transactions.last().unwrap();
+    assert_eq!(debit.transaction_type.kind(), TransactionKind::TermDeposit);
+    assert!(!debit.transaction_type.is_credit());
+    assert_eq!(bank.term_deposits_for(&alice).unwrap(), [deposit]);
+}
+
+#[test]
+fn interest_is_simple_and_rounded_down() {
+    let (mut bank, _, alice) = bank_at_start();
+    let yearly = bank.open_term_deposit(&alice, Money::from_major(4_000), 450, 12).unwrap();
+    assert_eq!(yearly.interest_at_maturity(), Money::from_major(180));
+
+    let odd = bank.open_term_deposit(&alice, Money::from_cents(100_001), 333, 7).unwrap();
+    // 1000.01 * 3.33% * 7/12 = 19.425...
+    assert_eq!(odd.interest_at_maturity(), Money::from_cents(1_942));
+    assert_eq!(odd.to_string(), format!("$1,000.01 at 3.33% for 7 months, matures {} - Active", odd.maturity_date.format("%Y-%m-%d")));
+}
+
+#[test]
+fn matured_deposits_pay_principal_and_interest_back() {
+    let (mut bank, clock, alice) = bank_at_start();
+    let deposit = bank.open_term_deposit(&alice, Money::from_major(4_000), 450, 12).unwrap();
+
+    clock.set(deposit.maturity_date - Duration::seconds(1));
+    assert!(bank.process_maturities(bank.now()).unwrap().is_empty());
+
+    clock.set(deposit.maturity_date + Duration::days(3));
+    let matured = bank.process_maturities(bank.now()).unwrap();
+    assert_eq!(matured.len(), 1);
+    assert_eq!(matured[0].status, TermDepositStatus::Matured);
+    assert_eq!(matured[0].payout, Some(Money::from_major(4_180)));
+    assert_eq!(balance(&bank, &alice), Money::from_major(10_180));
+    assert_eq!(bank.term_deposits_for(&alice).unwrap(), matured.as_slice());
+
+    assert!(bank.process_maturities(bank.now()).unwrap().is_empty());
+}
+
+#[test]
+fn early_withdrawal_pays_interest_so_far_less_the_penalty() {
+    let (mut bank, clock, alice) = bank_at_start();
+    let deposit = bank.open_term_deposit(&alice, Money::from_major(10_000), 600, 12).unwrap();
+
+    let halfway = start() + (deposit.maturity_date - start()) / 2;
+    clock.set(halfway);
+    assert_eq!(deposit.accrued_interest(halfway), Money::from_major(300));
+    assert_eq!(deposit.early_withdrawal_penalty(), Money::from_major(100));
+
+    let payout = bank.withdraw_term_deposit(&alice, &deposit.id).unwrap();
+    assert_eq!(payout.amount, Money::from_major(10_200));
+    assert!(matches!(payout.transaction_type, TransactionType::TermDeposit { credit: true, .. }));
+
+    let closed = &bank.term_deposits_for(&alice).unwrap()[0];
+    assert_eq!(closed.status, TermDepositStatus::Withdrawn);
+    assert_eq!(closed.closed_at, Some(halfway));
+    assert!(matches!(bank.withdraw_term_deposit(&alice, &deposit.id), Err(BankError::InvalidInput(_))));
+}
+
+#[test]
+fn withdrawing_on_the_first_day_can_cost_principal() {
+    let (mut bank, _, alice) = bank_at_start();
+    let deposit = bank.open_term_deposit(&alice, Money::from_major(1_000), 500, 24).unwrap();
+
+    let payout = bank.withdraw_term_deposit(&alice, &deposit.id).unwrap();
+    assert_eq!(payout.amount, Money::from_major(990));
+    assert_eq!(balance(&bank, &alice), Money::from_major(9_990));
+}
+
+#[test]
+fn requests_are_validated() {
+    let (mut bank, _, alice) = bank_at_start();
+    assert!(matches!(
+        bank.open_term_deposit(&alice, Money::from_major(20_000), 450, 12),
+        Err(BankError::InsufficientFunds { .. })
+    ));
+    assert!(matches!(
+        bank.open_term_deposit(&alice, Money::from_major(100), 0, 12),
+        Err(BankError::ValidationError { .. })
+    ));
+    assert!(matches!(
+        bank.open_term_deposit(&alice, Money::from_major(100), 450, 0),
+        Err(BankError::ValidationError { .. })
+    ));
+    assert!(matches!(bank.withdraw_term_deposit(&alice, "nope"), Err(BankError::TermDepositNotFound(_))));
+
+    assert_eq!(parse_interest_rate("4.5").unwrap(), 450);
+    assert_eq!(parse_interest_rate(" 4.25% ").unwrap(), 425);
+    assert_eq!(parse_interest_rate("7").unwrap(), 700);
+    for bad in ["", "abc", "4.555", "-1", "0", "99"] {
+        assert!(parse_interest_rate(bad).is_err(), "{}", bad);
+    }
+}
+
+#[test]
+fn accounts_with_active_deposits_cannot_close() {
+    let (mut bank, _, alice) = bank_at_start();
+    let bob = bank.register_customer("Bob".to_string(), "bob@example.com".to_string()).unwrap();
+    bank.create_account_for_customer(&bob, Money::ZERO, AccountType::Checking).unwrap();
+    let deposit = bank.open_term_deposit(&alice, Money::from_major(1_000), 450, 6).unwrap();
+
+    assert!(matches!(bank.close_account(&alice, Some(&bob)), Err(BankError::InvalidInput(_))));
+
+    bank.withdraw_term_deposit(&alice, &deposit.id).unwrap();
+    bank.close_account(&alice, Some(&bob)).unwrap();
+}
+
+#[test]
+fn deposits_survive_saving_and_journal_replay() {
+    let (mut bank, clock, alice) = bank_at_start();
+    let snapshot = serde_json::to_string(&bank).unwrap();
+    bank.enable_journal();
+
+    let first = bank.open_term_deposit(&alice, Money::from_major(2_000), 450, 3).unwrap();
+    let second = bank.open_term_deposit(&alice, Money::from_major(1_000), 500, 12).unwrap();
+    clock.set(first.maturity_date);
+    bank.process_maturities(bank.now()).unwrap();
+    bank.withdraw_term_deposit(&alice, &second.id).unwrap();
+
+    let mut restored: Bank = serde_json::from_str(&snapshot).unwrap();
+    for entry in bank.take_journal() {
+        restored.apply_journal_entry(entry).unwrap();
+    }
+    assert_eq!(restored.term_deposits_for(&alice).unwrap(), bank.term_deposits_for(&alice).unwrap());
+    assert_eq!(balance(&restored, &alice), balance(&bank, &alice));
+
+    let reloaded: Bank = serde_json::from_str(&serde_json::to_string(&bank).unwrap()).unwrap();
+    assert_eq!(reloaded.term_deposits_for(&alice).unwrap(), bank.term_deposits_for(&alice).unwrap());
+}
@@ -0,0 +1,112 @@
+//! Integration tests for output themes, plain mode and ASCII fallback
+
+#![cfg(feature = "cli")]
+
+use clap::Parser;
+use rust_banking_system::cli::args::Args;
+use rust_banking_system::cli::theme::{self, TextStyle, Theme, Tone};
+use rust_banking_system::cli::StartupArgs;
+use rust_banking_system::{BankError, Config};
+
+fn colored(theme: Theme) -> TextStyle {
+    TextStyle { theme, color: true, unicode: true }
+}
+
+#[test]
+fn themes_are_parsed_by_name() {
+    assert_eq!("default".parse::<Theme>().unwrap(), Theme::Default);
+    assert_eq!(" High-Contrast ".parse::<Theme>().unwrap(), Theme::HighContrast);
+    for theme in Theme::ALL {
+        assert_eq!(theme.to_string().parse::<Theme>().unwrap(), theme);
+    }
+
+    let err = "solarized".parse::<Theme>().unwrap_err();
+    assert!(err.contains("solarized") && err.contains("high-contrast"), "{}", err);
+}
+
+#[test]
+fn the_default_style_prints_text_unchanged() {
+    let style = TextStyle::default();
+    let text = "\n✅ Deposited $50.00\n   New balance: $150.00";
+    for tone in [Tone::Success, Tone::Error, Tone::Heading, Tone::Dim] {
+        assert_eq!(style.paint(tone, text), text);
+    }
+    assert_eq!(style.symbols(text), text);
+}
+
+#[test]
+fn colors_wrap_each_line_and_follow_the_theme() {
+    assert_eq!(colored(Theme::Default).paint(Tone::Success, "Done"), "\x1b[32mDone\x1b[0m");
+    assert_eq!(colored(Theme::Default).paint(Tone::Error, "Failed"), "\x1b[31mFailed\x1b[0m");
+    assert_eq!(colored(Theme::HighContrast).paint(Tone::Error, "Failed"), "\x1b[1;91mFailed\x1b[0m");
+
+    // Blank lines carry no codes, so nothing is left colored between lines
+    assert_eq!(
+        colored(Theme::Default).paint(Tone::Dim, "one\n\ntwo"),
+        "\x1b[2mone\x1b[0m\n\n\x1b[2mtwo\x1b[0m"
+    );
+}
+
+#[test]
+fn every_tone_looks_different_within_a_theme() {
+    for theme in Theme::ALL {
+        let tones = [Tone::Success, Tone::Error, Tone::Heading, Tone::Dim];
+        for (i, a) in tones.iter().enumerate() {
+            for b in &tones[i + 1..] {
+                assert_ne!(theme.sgr(*a), theme.sgr(*b), "{} {:?} {:?}", theme, a, b);
+            }
+        }
+    }
+}
+
+#[test]
+fn ascii_mode_swaps_status_symbols_and_borders() {
+    assert_eq!(theme::to_ascii("✅ Saved"), "[ok] Saved");
+    assert_eq!(theme::to_ascii("❌ Error: no"), "[x] Error: no");
+    assert_eq!(theme::to_ascii("⚠️  Over budget"), "[!]  Over budget");
+    assert_eq!(theme::to_ascii("╔═══╗\n║ a ║\n╚═══╝"), "+===+\n| a |\n+===+");
+    assert_eq!(theme::to_ascii("  • Bob → Alice…"), "  * Bob -> Alice...");
+    assert_eq!(theme::to_ascii("Groceries [████░░]"), "Groceries [####..]");
+}
+
+#[test]
+fn ascii_mode_drops_other_emoji_but_keeps_letters() {
+    assert_eq!(theme::to_ascii("  1. 💰 Deposit Money"), "  1. Deposit Money");
+    assert_eq!(theme::to_ascii("📭 No holds"), "No holds");
+    assert_eq!(theme::to_ascii("🏷️ Tag"), "Tag");
+    // Padded emoji keep their width so boxes stay aligned
+    assert_eq!(theme::to_ascii("║ 🏦  Bank 🏦  ║"), "|     Bank     |");
+    assert_eq!(theme::to_ascii("Depositar dinero en Año Nuevo: €5"), "Depositar dinero en Año Nuevo: €5");
+
+    let style = TextStyle { unicode: false, ..colored(Theme::Default) };
+    assert_eq!(style.paint(Tone::Success, "✅ Done"), "\x1b[32m[ok] Done\x1b[0m");
+}
+
+#[test]
+fn the_config_picks_the_theme_and_can_turn_styling_off() {
+    let config = Config::default();
+    assert_eq!(config.theme, "default");
+    assert!(config.color && config.unicode);
+
+    let plain = Config { theme: "high-contrast".to_string(), color: false, unicode: false, ..Config::default() };
+    plain.validate().unwrap();
+    assert_eq!(TextStyle::for_terminal(&plain), TextStyle { theme: Theme::HighContrast, color: false, unicode: false });
+
+    let unknown = Config { theme: "neon".to_string(), ..Config::default() };
+    assert!(matches!(unknown.validate(), Err(BankError::ValidationError { field, .. }) if field == "theme"));
+}
+
+#[test]
+fn flags_choose_the_theme_and_turn_styling_off() {
+    let args = Args::try_parse_from(["banking-cli", "--theme", "high-contrast", "--no-color", "--ascii"]).unwrap();
+    let config = args.startup.apply(Config::default(), None).unwrap();
+    assert_eq!(config.theme, "high-contrast");
+    assert!(!config.color && !config.unicode);
+
+    // Without the flags the config file's choice stands
+    let from_file = Config { color: false, ..Config::default() };
+    assert!(!StartupArgs::default().apply(from_file, None).unwrap().color);
+
+    let bad = StartupArgs { theme: Some("neon".to_string()), ..StartupArgs::default() };
+    assert!(matches!(bad.apply(Config::default(), None), Err(BankError::ValidationError { field, .. }) if field == "theme"));
+}
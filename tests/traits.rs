@@ -0,0 +1,85 @@
+//! Integration tests for summaries and audit facts
+
+use rust_banking_system::traits::{AuditFact, Auditable, Summarizable};
+use rust_banking_system::models::TransactionDetails;
+use rust_banking_system::{AccountType, Bank, Money};
+
+fn bank_with_alice() -> Bank {
+    let mut bank = Bank::new("Test Bank".to_string());
+    let alice = bank.register_customer("Alice".to_string(), "alice@example.com".to_string()).unwrap();
+    bank.create_account_for_customer(&alice, Money::from_major(100), AccountType::Savings).unwrap();
+    bank.withdraw_with(&alice, Money::from_major(10), TransactionDetails::new().description("rent").unwrap()).unwrap();
+    bank
+}
+
+#[test]
+fn summaries_are_one_line() {
+    let bank = bank_with_alice();
+    let customer = bank.list_customers()[0];
+    let account = customer.get_account().unwrap();
+    let transaction = account.transactions.last().unwrap();
+
+    for summary in [bank.summary(), customer.summary(), account.summary(), transaction.summary()] {
+        assert_eq!(summary.lines().count(), 1, "{}", summary);
+    }
+    assert!(account.summary().contains("Savings"));
+    assert!(customer.summary().contains(&account.summary()));
+    assert_eq!(transaction.summary(), transaction.to_string());
+}
+
+#[test]
+fn detailed_summaries_add_a_line_per_detail() {
+    let bank = bank_with_alice();
+    let customer = bank.list_customers()[0];
+    let account = customer.get_account().unwrap();
+    let transaction = account.transactions.last().unwrap();
+
+    let detailed = transaction.detailed_summary();
+    assert!(detailed.starts_with(&format!("Transaction: {}", transaction.id)));
+    assert!(detailed.contains("\n  Description: rent"));
+    assert!(!detailed.contains("Category"));
+
+    let detailed = customer.detailed_summary();
+    assert!(detailed.contains("\n  Email: alice@example.com"));
+    // The account's details are nested under the customer's
+    assert!(detailed.contains("\n    Available: $90.00"));
+
+    assert!(bank.detailed_summary().contains("\n  Accounts: 1"));
+}
+
+#[test]
+fn detailed_summary_defaults_to_the_summary() {
+    struct Plain;
+
+    impl Summarizable for Plain {
+        fn summary(&self) -> String {
+            "plain".to_string()
+        }
+    }
+
+    assert_eq!(Plain.detailed_summary(), "plain");
+}
+
+#[test]
+fn audit_facts_list_identifying_facts_first() {
+    let bank = bank_with_alice();
+    let customer = bank.list_customers()[0];
+    let account = customer.get_account().unwrap();
+    let transaction = account.transactions.last().unwrap();
+
+    let records: [&dyn Auditable; 4] = [&bank, customer, account, transaction];
+    let kinds: Vec<&str> = records.iter().map(|record| record.audit_kind()).collect();
+    assert_eq!(kinds, ["bank", "customer", "account", "transaction"]);
+
+    let facts = customer.audit_facts();
+    assert_eq!(facts[0], AuditFact::new("id", &customer.id));
+    assert!(facts.contains(&AuditFact::new("account_id", &account.id)));
+    assert!(!facts.iter().any(|fact| fact.key == "phone"));
+
+    let facts = transaction.audit_facts();
+    assert!(facts.contains(&AuditFact::new("amount", "10.00")));
+    assert!(facts.contains(&AuditFact::new("description", "rent")));
+    assert!(facts.contains(&AuditFact::new("timestamp", transaction.timestamp.to_rfc3339())));
+
+    assert!(bank.audit_facts().contains(&AuditFact::new("total_balance", "90.00")));
+}
@@ -0,0 +1,131 @@
+//! Integration tests for finding any transaction by its ID
+
+// The tests that run the binary need the `cli` feature; without it the
+// imports and helpers only they use go unused
+#![cfg_attr(not(feature = "cli"), allow(unused))]
+
+mod common;
+
+use std::path::Path;
+
+use rust_banking_system::models::{CustomerId, TransactionId};
+use rust_banking_system::persistence::journal_path;
+use rust_banking_system::{AccountType, Bank, MemoryStorage, Money, Storage};
+
+#[cfg(feature = "cli")]
+use common::run;
+use common::{bank_with_customers, temp_path};
+
+/// ID of the newest transaction on a customer's account
+fn last_id(bank: &Bank, customer_id: &CustomerId) -> TransactionId {
+    let account = bank.get_customer(customer_id).unwrap().get_account().unwrap();
+    account.get_transaction_history().last().unwrap().id.clone()
+}
+
+#[test]
+fn a_transaction_is_found_with_its_owner() {
+    let (mut bank, alice, _) = bank_with_customers(1_000, 50);
+    bank.deposit(&alice, Money::from_major(25)).unwrap();
+    let id = last_id(&bank, &alice);
+
+    let (owner, transaction) = bank.find_transaction(&id).unwrap();
+    assert_eq!(transaction.id, id);
+    assert_eq!(transaction.amount, Money::from_major(25));
+    assert_eq!(owner.customer_id, &alice);
+    assert_eq!(owner.name, "Alice");
+    assert_eq!(owner.email, "alice@example.com");
+    let account = bank.get_customer(&alice).unwrap().get_account().unwrap();
+    assert_eq!(owner.account_id, &account.id);
+    assert_eq!(owner.account_number, account.number);
+}
+
+#[test]
+fn each_side_of_a_transfer_belongs_to_its_own_customer() {
+    let (mut bank, alice, bob) = bank_with_customers(1_000, 50);
+    bank.transfer(&alice, &bob, Money::from_major(100)).unwrap();
+
+    assert_eq!(bank.find_transaction(&last_id(&bank, &alice)).unwrap().0.customer_id, &alice);
+    assert_eq!(bank.find_transaction(&last_id(&bank, &bob)).unwrap().0.customer_id, &bob);
+}
+
+#[test]
+fn unknown_ids_are_not_found() {
+    let (bank, _, _) = bank_with_customers(1_000, 50);
+    assert!(bank.find_transaction(&"no-such-id".into()).is_none());
+    assert!(bank.find_transaction(&"".into()).is_none());
+}
+
+#[test]
+fn transactions_posted_after_the_first_lookup_are_found() {
+    let (mut bank, alice, bob) = bank_with_customers(1_000, 50);
+    let opening = last_id(&bank, &alice);
+    assert!(bank.find_transaction(&opening).is_some());
+
+    // The index is built now; later postings must be added to it
+    bank.withdraw(&alice, Money::from_major(10)).unwrap();
+    bank.transfer(&bob, &alice, Money::from_major(5)).unwrap();
+    let dave = bank.register_customer("Dave".to_string(), "dave@example.com".to_string()).unwrap();
+    bank.create_account_for_customer(&dave, Money::from_major(1), AccountType::Savings).unwrap();
+
+    assert_eq!(bank.find_transaction(&last_id(&bank, &alice)).unwrap().0.customer_id, &alice);
+    assert_eq!(bank.find_transaction(&last_id(&bank, &bob)).unwrap().0.customer_id, &bob);
+    assert_eq!(bank.find_transaction(&last_id(&bank, &dave)).unwrap().0.name, "Dave");
+}
+
+#[test]
+fn a_loaded_bank_finds_transactions_saved_before() {
+    let (mut bank, alice, _) = bank_with_customers(1_000, 50);
+    bank.deposit(&alice, Money::from_major(25)).unwrap();
+    let id = last_id(&bank, &alice);
+    let storage = MemoryStorage::new();
+    storage.save_bank(&bank).unwrap();
+
+    let loaded = storage.load_bank().unwrap();
+    assert_eq!(loaded.find_transaction(&id).unwrap().0.customer_id, &alice);
+}
+
+#[test]
+fn deleted_customers_keep_their_transactions() {
+    let (mut bank, alice, bob) = bank_with_customers(1_000, 50);
+    bank.transfer(&bob, &alice, Money::from_major(50)).unwrap();
+    let id = last_id(&bank, &bob);
+    bank.delete_customer(&bob).unwrap();
+
+    assert_eq!(bank.find_transaction(&id).unwrap().0.name, "Bob");
+}
+
+/// ID of the opening deposit of a customer, through command mode
+#[cfg(feature = "cli")]
+fn opening_id(data_file: &Path, email: &str) -> String {
+    let output = run(data_file, &["--json", "history", "--customer", email], "");
+    let json: serde_json::Value = serde_json::from_str(&output).unwrap();
+    json["result"][0]["id"].as_str().unwrap().to_string()
+}
+
+#[cfg(feature = "cli")]
+#[test]
+fn customers_look_up_their_own_transactions_only() {
+    let data_file = temp_path("menu.json");
+    let _ = std::fs::remove_file(&data_file);
+    let _ = std::fs::remove_file(journal_path(&data_file.to_string_lossy()));
+    for (name, email) in [("Ann", "ann@example.com"), ("Ben", "ben@example.com")] {
+        run(&data_file, &["register", "--name", name, "--email", email, "--pin", "1234"], "\n");
+        run(&data_file, &["create-account", "--customer", email, "--deposit", "100"], "\n");
+    }
+    let ann = opening_id(&data_file, "ann@example.com");
+    let ben = opening_id(&data_file, "ben@example.com");
+
+    let login = "\n20\n1\nann@example.com\n1234\n";
+    let text = run(&data_file, &[], &format!("{}49\n{}\n49\n{}\n0\n", login, ann, ben));
+    assert!(text.contains(&format!("ID: {}", ann)), "{}", text);
+    assert!(text.contains("Customer: Ann <ann@example.com>"), "{}", text);
+    assert!(text.contains(&format!("Transaction '{}' not found", ben)), "{}", text);
+    assert!(!text.contains("Ben <ben@example.com>"), "{}", text);
+
+    // Command mode is not limited to one customer
+    let output = run(&data_file, &["--json", "lookup-transaction", "--id", &ben], "");
+    let json: serde_json::Value = serde_json::from_str(&output).unwrap();
+    assert_eq!(json["result"]["owner"]["email"], "ben@example.com");
+    assert_eq!(json["result"]["transaction"]["id"], ben.as_str());
+    let _ = std::fs::remove_file(&data_file);
+}
@@ -0,0 +1,127 @@
+//! Integration tests for all-or-nothing transfers
+
+mod common;
+
+use rust_banking_system::models::CustomerId;
+use rust_banking_system::{AccountType, Bank, BankError, Money};
+
+use common::balance;
+
+/// Creates a bank with a funded sender and returns (bank, sender_id)
+fn bank_with_sender(balance: i64) -> (Bank, CustomerId) {
+    let mut bank = Bank::new("Test Bank".to_string());
+    let sender = bank
+        .register_customer("Alice".to_string(), "alice@example.com".to_string())
+        .unwrap();
+    bank.create_account_for_customer(&sender, Money::from_major(balance), AccountType::Checking)
+        .unwrap();
+    (bank, sender)
+}
+
+fn history_len(bank: &Bank, customer_id: &CustomerId) -> usize {
+    bank.get_customer(customer_id).unwrap().get_account().unwrap().transactions.len()
+}
+
+#[test]
+fn transfer_moves_money_between_accounts() {
+    let (mut bank, sender) = bank_with_sender(100);
+    let recipient = bank
+        .register_customer("Bob".to_string(), "bob@example.com".to_string())
+        .unwrap();
+    bank.create_account_for_customer(&recipient, Money::ZERO, AccountType::Checking)
+        .unwrap();
+
+    bank.transfer(&sender, &recipient, Money::from_major(40)).unwrap();
+
+    assert_eq!(balance(&bank, &sender), Money::from_major(60));
+    assert_eq!(balance(&bank, &recipient), Money::from_major(40));
+}
+
+#[test]
+fn transfer_to_customer_without_account_leaves_sender_untouched() {
+    let (mut bank, sender) = bank_with_sender(100);
+    let recipient = bank
+        .register_customer("Bob".to_string(), "bob@example.com".to_string())
+        .unwrap();
+    let transactions_before = bank.total_transactions;
+
+    let result = bank.transfer(&sender, &recipient, Money::from_major(40));
+
+    assert!(matches!(result, Err(BankError::AccountNotFound(_))));
+    assert_eq!(balance(&bank, &sender), Money::from_major(100));
+    assert_eq!(history_len(&bank, &sender), 1);
+    assert_eq!(bank.total_transactions, transactions_before);
+}
+
+#[test]
+fn transfer_to_unknown_customer_leaves_sender_untouched() {
+    let (mut bank, sender) = bank_with_sender(100);
+
+    let result = bank.transfer(&sender, &"no-such-customer".into(), Money::from_major(40));
+
+    assert!(matches!(result, Err(BankError::CustomerNotFound(_))));
+    assert_eq!(balance(&bank, &sender), Money::from_major(100));
+    assert_eq!(history_len(&bank, &sender), 1);
+}
+
+#[test]
+fn transfer_with_insufficient_funds_changes_nothing() {
+    let (mut bank, sender) = bank_with_sender(10);
+    let recipient = bank
+        .register_customer("Bob".to_string(), "bob@example.com".to_string())
+        .unwrap();
+    bank.create_account_for_customer(&recipient, Money::ZERO, AccountType::Checking)
+        .unwrap();
+
+    let result = bank.transfer(&sender, &recipient, Money::from_major(40));
+
+    assert!(matches!(result, Err(BankError::InsufficientFunds { .. })));
+    assert_eq!(balance(&bank, &sender), Money::from_major(10));
+    assert_eq!(balance(&bank, &recipient), Money::ZERO);
+    assert_eq!(history_len(&bank, &recipient), 0);
+}
+
+#[test]
+fn transfer_to_self_is_rejected() {
+    let (mut bank, sender) = bank_with_sender(100);
+
+    let result = bank.transfer(&sender, &sender, Money::from_major(40));
+
+    assert!(matches!(result, Err(BankError::InvalidTransfer(_))));
+    assert_eq!(balance(&bank, &sender), Money::from_major(100));
+}
+
+#[test]
+fn transfer_of_non_positive_amount_is_rejected() {
+    let (mut bank, sender) = bank_with_sender(100);
+    let recipient = bank
+        .register_customer("Bob".to_string(), "bob@example.com".to_string())
+        .unwrap();
+    bank.create_account_for_customer(&recipient, Money::ZERO, AccountType::Checking)
+        .unwrap();
+
+    let result = bank.transfer(&sender, &recipient, Money::ZERO);
+
+    assert!(matches!(result, Err(BankError::InvalidAmount(_))));
+    assert_eq!(history_len(&bank, &sender), 1);
+}
+
+#[test]
+fn transfer_records_counterparty_on_both_sides() {
+    let (mut bank, sender) = bank_with_sender(100);
+    let recipient = bank
+        .register_customer("Bob".to_string(), "bob@example.com".to_string())
+        .unwrap();
+    bank.create_account_for_customer(&recipient, Money::ZERO, AccountType::Checking)
+        .unwrap();
+
+    bank.transfer(&sender, &recipient, Money::from_major(40)).unwrap();
+
+    let sender_account = bank.get_customer(&sender).unwrap().get_account().unwrap();
+    let recipient_account = bank.get_customer(&recipient).unwrap().get_account().unwrap();
+    let sent = sender_account.transactions.last().unwrap();
+    let received = recipient_account.transactions.last().unwrap();
+
+    assert_eq!(sent.transaction_type.counterparty(), Some(recipient_account.id.as_str()));
+    assert_eq!(received.transaction_type.counterparty(), Some(sender_account.id.as_str()));
+}
@@ -0,0 +1,61 @@
+//! Integration tests for the terminal UI (run with `--features tui`)
+
+#![cfg(feature = "tui")]
+
+
+use ratatui::backend::TestBackend;
+use ratatui::crossterm::event::KeyCode;
+use ratatui::Terminal;
+
+use rust_banking_system::tui::{Focus, TuiApp};
+use rust_banking_system::{AccountType, Bank, Money, SharedBank};
+
+/// A bank with two customers; Alice has an account with one deposit
+fn sample_bank() -> SharedBank {
+    let mut bank = Bank::new("Test Bank".to_string());
+    let alice = bank
+        .register_customer("Alice".to_string(), "alice@example.com".to_string())
+        .unwrap();
+    bank.create_account_for_customer(&alice, Money::from_major(100), AccountType::Savings)
+        .unwrap();
+    bank.deposit(&alice, Money::from_major(25)).unwrap();
+    bank.register_customer("Bob".to_string(), "bob@example.com".to_string())
+        .unwrap();
+    SharedBank::new(bank)
+}
+
+/// Renders one frame and returns the screen as text
+fn render(app: &mut TuiApp) -> String {
+    let mut terminal = Terminal::new(TestBackend::new(100, 30)).unwrap();
+    terminal.draw(|frame| app.draw(frame)).unwrap();
+
+    let buffer = terminal.backend().buffer();
+    buffer.content().iter().map(|cell| cell.symbol()).collect()
+}
+
+#[test]
+fn shows_selected_customer_and_history() {
+    let mut app = TuiApp::new(sample_bank());
+    let screen = render(&mut app);
+
+    assert!(screen.contains("Alice"));
+    assert!(screen.contains("Bob"));
+    assert!(screen.contains("Balance: $125.00"));
+    assert!(screen.contains("DEPOSIT"));
+}
+
+#[test]
+fn keys_move_selection_and_focus() {
+    let mut app = TuiApp::new(sample_bank());
+
+    app.handle_key(KeyCode::Down);
+    app.handle_key(KeyCode::Down);
+    assert_eq!(app.selected(), Some(1), "selection stops at the last customer");
+    assert!(render(&mut app).contains("No account yet."));
+
+    app.handle_key(KeyCode::Tab);
+    assert_eq!(app.focus(), Focus::History);
+
+    app.handle_key(KeyCode::Char('q'));
+    assert!(app.should_quit());
+}
@@ -0,0 +1,144 @@
+//! Integration tests for undoing operations with reversals
+
+mod common;
+
+use std::sync::Arc;
+
+use chrono::{Duration, Utc};
+use rust_banking_system::bank::UNDO_LIMIT;
+use rust_banking_system::clock::MockClock;
+use rust_banking_system::persistence::FileArchive;
+use rust_banking_system::{Bank, BankError, Money, Transaction, TransactionType};
+
+use common::{balance, bank_with_customers, temp_path};
+
+/// Transactions across all accounts, which `total_transactions` must match
+fn posted_transactions(bank: &Bank) -> u64 {
+    bank.list_customers()
+        .iter()
+        .filter_map(|c| c.account.as_ref())
+        .map(|a| a.transactions.len() as u64)
+        .sum()
+}
+
+#[test]
+fn undo_reverses_a_deposit() {
+    let (mut bank, alice, _) = bank_with_customers(100, 50);
+    bank.deposit(&alice, Money::from_major(25)).unwrap();
+
+    let reversals = bank.undo_last().unwrap();
+
+    assert_eq!(reversals.len(), 1);
+    assert!(matches!(
+        reversals[0].transaction_type,
+        TransactionType::Reversal { credit: false, .. }
+    ));
+    assert_eq!(balance(&bank, &alice), Money::from_major(100));
+    assert_eq!(bank.total_transactions, posted_transactions(&bank));
+}
+
+#[test]
+fn undo_reverses_both_sides_of_a_transfer() {
+    let (mut bank, alice, bob) = bank_with_customers(100, 50);
+    bank.transfer(&alice, &bob, Money::from_major(30)).unwrap();
+
+    bank.undo_last().unwrap();
+
+    assert_eq!(balance(&bank, &alice), Money::from_major(100));
+    assert_eq!(balance(&bank, &bob), Money::from_major(50));
+    assert_eq!(bank.total_transactions, posted_transactions(&bank));
+}
+
+#[test]
+fn undo_works_back_through_the_stack() {
+    let (mut bank, alice, _) = bank_with_customers(100, 50);
+    bank.deposit(&alice, Money::from_major(10)).unwrap();
+    bank.withdraw(&alice, Money::from_major(40)).unwrap();
+
+    bank.undo_last().unwrap();
+    assert_eq!(balance(&bank, &alice), Money::from_major(110));
+    bank.undo_last().unwrap();
+    assert_eq!(balance(&bank, &alice), Money::from_major(100));
+
+    assert!(matches!(bank.undo_last(), Err(BankError::InvalidInput(_))));
+}
+
+#[test]
+fn spent_money_cannot_be_taken_back() {
+    let (mut bank, alice, _) = bank_with_customers(100, 50);
+    bank.deposit(&alice, Money::from_major(25)).unwrap();
+
+    // Imports are not undoable, so they can spend the deposit underneath it
+    let spend = Transaction::new(TransactionType::Withdrawal, Money::from_major(125), Money::ZERO);
+    bank.import_transactions(&alice, vec![spend]).unwrap();
+
+    assert!(matches!(bank.undo_last(), Err(BankError::InsufficientFunds { .. })));
+    // The failed undo changes nothing and can be retried later
+    assert_eq!(bank.undo_depth(), 1);
+    assert_eq!(balance(&bank, &alice), Money::ZERO);
+}
+
+#[test]
+fn undo_history_is_bounded() {
+    let (mut bank, alice, _) = bank_with_customers(100, 50);
+    for _ in 0..UNDO_LIMIT + 5 {
+        bank.deposit(&alice, Money::from_major(1)).unwrap();
+    }
+    assert_eq!(bank.undo_depth(), UNDO_LIMIT);
+}
+
+#[test]
+fn an_operation_reversed_by_id_is_skipped() {
+    let (mut bank, alice, _) = bank_with_customers(100, 50);
+    bank.deposit(&alice, Money::from_major(10)).unwrap();
+    bank.withdraw(&alice, Money::from_major(40)).unwrap();
+    let withdrawal = bank.get_customer(&alice).unwrap().get_account().unwrap().transactions.last().unwrap().id.clone();
+    bank.reverse_transaction(&withdrawal, "Teller error").unwrap();
+    assert_eq!(bank.undo_depth(), 1);
+
+    // Undo moves on to the deposit instead of failing on the withdrawal
+    bank.undo_last().unwrap();
+    assert_eq!(balance(&bank, &alice), Money::from_major(100));
+    assert!(matches!(bank.undo_last(), Err(BankError::InvalidInput(_))));
+}
+
+#[test]
+fn an_archived_operation_is_skipped() {
+    let archive = temp_path("undo-archive");
+    let _ = std::fs::remove_dir_all(&archive);
+    let (mut bank, alice, _) = bank_with_customers(100, 50);
+    // The accounts were opened on the real clock
+    let start = Utc::now();
+    let clock = Arc::new(MockClock::new(start));
+    bank.set_clock(clock.clone());
+    bank.set_archive(Arc::new(FileArchive::new(&archive)));
+    bank.deposit(&alice, Money::from_major(10)).unwrap();
+    clock.advance(Duration::days(2));
+    bank.deposit(&alice, Money::from_major(5)).unwrap();
+
+    bank.archive_transactions(start + Duration::days(1)).unwrap();
+
+    assert_eq!(bank.undo_depth(), 1);
+    bank.undo_last().unwrap();
+    assert_eq!(balance(&bank, &alice), Money::from_major(110));
+    assert!(matches!(bank.undo_last(), Err(BankError::InvalidInput(_))));
+    let _ = std::fs::remove_dir_all(&archive);
+}
+
+#[test]
+fn reversals_survive_journal_replay() {
+    let (mut bank, alice, bob) = bank_with_customers(100, 50);
+    bank.transfer(&alice, &bob, Money::from_major(30)).unwrap();
+    bank.enable_journal();
+    let snapshot = serde_json::to_string(&bank).unwrap();
+
+    bank.undo_last().unwrap();
+
+    let mut restored: Bank = serde_json::from_str(&snapshot).unwrap();
+    for entry in bank.take_journal() {
+        assert!(restored.apply_journal_entry(entry).unwrap());
+    }
+    assert_eq!(balance(&restored, &alice), Money::from_major(100));
+    assert_eq!(balance(&restored, &bob), Money::from_major(50));
+    assert_eq!(restored.total_transactions, bank.total_transactions);
+}
@@ -0,0 +1,174 @@
+//! Integration tests for tracking changes not yet saved in full
+
+#![cfg(feature = "cli")]
+
+mod common;
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use chrono::Utc;
+use rust_banking_system::bank::JournalEntry;
+use rust_banking_system::cli::script::run_lines;
+use rust_banking_system::cli::JsonRenderer;
+use rust_banking_system::models::Frequency;
+use rust_banking_system::persistence::journal_path;
+use rust_banking_system::{AccountType, Bank, BankResult, JsonFileStorage, MemoryStorage, Money, Storage};
+
+use common::temp_path;
+
+/// Counts full saves, keeping the data in memory
+#[derive(Default)]
+struct CountingStorage {
+    inner: MemoryStorage,
+    saves: AtomicUsize,
+}
+
+impl Storage for CountingStorage {
+    fn save_bank(&self, bank: &Bank) -> BankResult<()> {
+        self.saves.fetch_add(1, Ordering::Relaxed);
+        self.inner.save_bank(bank)
+    }
+
+    fn load_bank(&self) -> BankResult<Bank> {
+        self.inner.load_bank()
+    }
+
+    fn append_journal(&self, bank: &Bank, entries: &[JournalEntry]) -> BankResult<()> {
+        self.inner.append_journal(bank, entries)
+    }
+}
+
+/// `common::run` for runs that must succeed
+fn run(data_file: &Path, args: &[&str], input: &str) -> String {
+    let output = common::output(data_file, args, input);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+/// A data file where Ann (PIN 1234) has $100; returns it with the input
+/// that logs in as Ann after the (blank) passphrase
+fn bank_with_ann(name: &str) -> (PathBuf, &'static str) {
+    let data_file = temp_path(name);
+    let _ = std::fs::remove_file(&data_file);
+    let _ = std::fs::remove_file(journal_path(&data_file.to_string_lossy()));
+    run(&data_file, &["register", "--name", "Ann", "--email", "ann@example.com", "--pin", "1234"], "\n");
+    run(&data_file, &["create-account", "--customer", "ann@example.com", "--deposit", "100"], "\n");
+    (data_file, "\n20\n1\nann@example.com\n1234\n")
+}
+
+fn balance(data_file: &Path) -> Money {
+    let bank = JsonFileStorage::new(data_file.to_string_lossy()).load_bank().unwrap();
+    bank.find_customer_by_email("ann@example.com").unwrap().get_account().unwrap().balance
+}
+
+fn has_journal(data_file: &Path) -> bool {
+    Path::new(&journal_path(&data_file.to_string_lossy())).exists()
+}
+
+#[test]
+fn changes_count_until_the_bank_is_saved() {
+    let mut bank = Bank::new("Test Bank".to_string());
+    assert!(!bank.has_unsaved_changes());
+
+    let id = bank.register_customer("Ann".to_string(), "ann@example.com".to_string()).unwrap();
+    bank.create_account_for_customer(&id, Money::from_major(10), AccountType::Checking).unwrap();
+    bank.deposit(&id, Money::from_major(5)).unwrap();
+    assert_eq!(bank.unsaved_changes(), 3);
+
+    // Refused operations and reads change nothing
+    assert!(bank.withdraw(&id, Money::from_major(500)).is_err());
+    let _ = bank.report();
+    assert_eq!(bank.unsaved_changes(), 3);
+
+    bank.mark_saved();
+    assert!(!bank.has_unsaved_changes());
+}
+
+#[test]
+fn a_loaded_bank_has_nothing_to_save() {
+    let storage = MemoryStorage::new();
+    let mut bank = Bank::new("Test Bank".to_string());
+    bank.register_customer("Ann".to_string(), "ann@example.com".to_string()).unwrap();
+    storage.save_bank(&bank).unwrap();
+
+    assert_eq!(storage.load_bank().unwrap().unsaved_changes(), 0);
+}
+
+#[test]
+fn schedules_count_although_they_are_not_journaled() {
+    let mut bank = Bank::new("Test Bank".to_string());
+    let ann = bank.register_customer("Ann".to_string(), "ann@example.com".to_string()).unwrap();
+    let bob = bank.register_customer("Bob".to_string(), "bob@example.com".to_string()).unwrap();
+    bank.mark_saved();
+
+    let schedule = bank.create_schedule(&ann, &bob, Money::from_major(5), Frequency::Weekly, Utc::now()).unwrap();
+    assert_eq!(bank.unsaved_changes(), 1);
+    bank.mark_saved();
+    bank.cancel_schedule(&schedule).unwrap();
+    assert!(bank.has_unsaved_changes());
+}
+
+#[test]
+fn commands_that_only_read_do_not_rewrite_the_file() {
+    let mut bank = Bank::new("Test Bank".to_string());
+    let storage = CountingStorage::default();
+    run_lines("register Ann ann@example.com\n", &mut bank, &storage, false, &JsonRenderer::new());
+    assert_eq!(storage.saves.load(Ordering::Relaxed), 1);
+    assert!(!bank.has_unsaved_changes());
+
+    let summary = run_lines("stats\nlist\naccount ann@example.com\n", &mut bank, &storage, true, &JsonRenderer::new());
+    assert_eq!(summary.succeeded + summary.failed.len(), 3);
+    assert_eq!(storage.saves.load(Ordering::Relaxed), 1);
+}
+
+#[test]
+fn the_menu_shows_unsaved_changes_and_exit_offers_to_save() {
+    let (data_file, login) = bank_with_ann("save.json");
+    let text = run(&data_file, &[], &format!("{}3\n5\n\n\n0\n\n", login));
+
+    assert!(text.contains("* 1 unsaved change(s)"), "{}", text);
+    assert!(text.contains("1 unsaved change(s). Save before exiting? (Y/n)"), "{}", text);
+    assert!(text.contains("Data saved. Goodbye!"), "{}", text);
+    // Saved in full: the journal was folded into the snapshot
+    assert!(!has_journal(&data_file));
+    assert_eq!(balance(&data_file), Money::from_major(105));
+    let _ = std::fs::remove_file(&data_file);
+}
+
+#[test]
+fn declining_to_save_leaves_the_changes_in_the_journal() {
+    let (data_file, login) = bank_with_ann("decline.json");
+    let text = run(&data_file, &[], &format!("{}3\n5\n\n\n0\nn\n", login));
+
+    assert!(text.contains("the journal keeps the changes"), "{}", text);
+    assert!(!text.contains("Data saved"));
+    assert!(has_journal(&data_file));
+    assert_eq!(balance(&data_file), Money::from_major(105));
+    let _ = std::fs::remove_file(&data_file);
+    let _ = std::fs::remove_file(journal_path(&data_file.to_string_lossy()));
+}
+
+#[test]
+fn saving_with_no_changes_is_skipped() {
+    let (data_file, login) = bank_with_ann("nothing.json");
+    let before = std::fs::metadata(&data_file).unwrap().modified().unwrap();
+    let text = run(&data_file, &[], &format!("{}11\n\n0\n", login));
+
+    assert!(text.contains("Nothing to save: no changes since the last save"), "{}", text);
+    assert!(!text.contains("unsaved change"), "{}", text);
+    assert!(text.contains("Data saved. Goodbye!"), "{}", text);
+    assert_eq!(std::fs::metadata(&data_file).unwrap().modified().unwrap(), before);
+    let _ = std::fs::remove_file(&data_file);
+}
+
+#[test]
+fn read_only_sessions_warn_that_changes_are_lost() {
+    let (data_file, login) = bank_with_ann("read-only.json");
+    let text = run(&data_file, &["--read-only"], &format!("{}3\n5\n\n\n0\n", login));
+
+    assert!(text.contains("1 change(s) made in this session are lost"), "{}", text);
+    assert!(text.contains("Read-only: nothing was saved"), "{}", text);
+    assert_eq!(balance(&data_file), Money::from_major(100));
+    let _ = std::fs::remove_file(&data_file);
+}
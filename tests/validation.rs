@@ -0,0 +1,96 @@
+//! Integration tests for customer detail validation
+
+use chrono::NaiveDate;
+use rust_banking_system::models::CustomerUpdate;
+use rust_banking_system::validation::{
+    normalize_phone, parse_date_of_birth, validate_date_of_birth, validate_email,
+};
+use rust_banking_system::{Bank, BankError};
+
+fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(y, m, d).unwrap()
+}
+
+fn field_of(error: BankError) -> String {
+    match error {
+        BankError::ValidationError { field, .. } => field,
+        other => panic!("expected ValidationError, got {:?}", other),
+    }
+}
+
+#[test]
+fn emails_must_be_well_formed() {
+    assert_eq!(validate_email("  Bob@Example.COM ").unwrap(), "bob@example.com");
+    assert!(validate_email("first.last+tag@mail.example.co.uk").is_ok());
+
+    for bad in ["", "bob", "bob@", "@example.com", "bob@localhost", "bob@@example.com",
+                "bob smith@example.com", "Bob <bob@example.com>", "bob@[127.0.0.1]"] {
+        assert_eq!(field_of(validate_email(bad).unwrap_err()), "email", "{:?}", bad);
+    }
+}
+
+#[test]
+fn phone_numbers_are_normalized() {
+    assert_eq!(normalize_phone("+1 (555) 010-0100").unwrap(), "+15550100100");
+    assert_eq!(normalize_phone("555.0100.99").unwrap(), "555010099");
+
+    for bad in ["12345", "555-CALL-NOW", "+1 555 0100 0100 0100 0", "1+5550100"] {
+        assert_eq!(field_of(normalize_phone(bad).unwrap_err()), "phone", "{:?}", bad);
+    }
+}
+
+#[test]
+fn customers_must_be_adults() {
+    let today = date(2024, 6, 15);
+    assert!(validate_date_of_birth(date(2006, 6, 15), today).is_ok());
+    assert!(validate_date_of_birth(date(2006, 6, 16), today).is_err());
+    assert!(validate_date_of_birth(date(2030, 1, 1), today).is_err());
+
+    assert_eq!(parse_date_of_birth("1990-02-28").unwrap(), date(1990, 2, 28));
+    assert_eq!(field_of(parse_date_of_birth("28/02/1990").unwrap_err()), "date of birth");
+}
+
+#[test]
+fn profile_updates_are_validated_and_normalized() {
+    let mut bank = Bank::new("Test Bank".to_string());
+    let id = bank
+        .register_customer("Alice".to_string(), "alice@example.com".to_string())
+        .unwrap();
+
+    let bad = CustomerUpdate { email: Some("not-an-email".to_string()), ..Default::default() };
+    assert_eq!(field_of(bank.update_customer(&id, bad).unwrap_err()), "email");
+
+    let today = chrono::Utc::now().date_naive();
+    let too_young = CustomerUpdate { date_of_birth: Some(Some(today)), ..Default::default() };
+    assert_eq!(field_of(bank.update_customer(&id, too_young).unwrap_err()), "date of birth");
+
+    let good = CustomerUpdate {
+        phone: Some(Some("(555) 010-0100".to_string())),
+        date_of_birth: Some(Some(date(1990, 1, 1))),
+        ..Default::default()
+    };
+    bank.update_customer(&id, good).unwrap();
+
+    let customer = bank.get_customer(&id).unwrap();
+    assert_eq!(customer.phone.as_deref(), Some("5550100100"));
+    assert_eq!(customer.date_of_birth, Some(date(1990, 1, 1)));
+}
+
+#[test]
+fn registration_normalizes_and_checks_emails() {
+    let mut bank = Bank::new("Test Bank".to_string());
+    let id = bank
+        .register_customer("Bob".to_string(), " Bob@Example.com ".to_string())
+        .unwrap();
+    assert_eq!(bank.get_customer(&id).unwrap().email, "bob@example.com");
+
+    assert!(matches!(
+        bank.register_customer("Robert".to_string(), "BOB@EXAMPLE.COM".to_string()),
+        Err(BankError::CustomerAlreadyExists(_))
+    ));
+    assert_eq!(
+        field_of(bank.register_customer("Eve".to_string(), "eve.example.com".to_string()).unwrap_err()),
+        "email"
+    );
+    assert_eq!(bank.list_customers().len(), 1);
+}
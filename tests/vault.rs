@@ -0,0 +1,137 @@
+//! Integration tests for the bank's cash vault
+
+// The tests that run the binary need the `cli` feature; without it the
+// imports and helpers only they use go unused
+#![cfg_attr(not(feature = "cli"), allow(unused))]
+
+mod common;
+
+use rust_banking_system::persistence::journal_path;
+use rust_banking_system::{Bank, BankError, Money};
+
+use common::{balance, bank_with_customers, temp_path};
+
+#[test]
+fn cash_deposits_and_withdrawals_move_the_vault() {
+    let (mut bank, alice, bob) = bank_with_customers(100, 50);
+    assert_eq!(bank.vault(), Some(Money::from_major(150)));
+
+    bank.deposit(&alice, Money::from_major(40)).unwrap();
+    bank.withdraw(&bob, Money::from_major(30)).unwrap();
+    assert_eq!(bank.vault(), Some(Money::from_major(160)));
+
+    // Money moving between customers never leaves the bank
+    bank.transfer(&alice, &bob, Money::from_major(25)).unwrap();
+    assert_eq!(bank.vault(), Some(Money::from_major(160)));
+}
+
+#[test]
+fn a_withdrawal_the_vault_cannot_cover_is_refused() {
+    let (mut bank, alice, _) = bank_with_customers(100, 50);
+    bank.vault_withdraw(Money::from_major(130)).unwrap();
+
+    let result = bank.withdraw(&alice, Money::from_major(50));
+    assert!(matches!(
+        result,
+        Err(BankError::InsufficientVaultCash { available, requested })
+            if available == Money::from_major(20) && requested == Money::from_major(50)
+    ));
+    assert_eq!(balance(&bank, &alice), Money::from_major(100));
+    assert_eq!(bank.vault(), Some(Money::from_major(20)));
+
+    bank.withdraw(&alice, Money::from_major(20)).unwrap();
+    assert_eq!(bank.vault(), Some(Money::ZERO));
+}
+
+#[test]
+fn undoing_and_reversing_put_the_cash_back() {
+    let (mut bank, alice, bob) = bank_with_customers(100, 50);
+    bank.withdraw(&alice, Money::from_major(60)).unwrap();
+    assert_eq!(bank.vault(), Some(Money::from_major(90)));
+    bank.undo_last().unwrap();
+    assert_eq!(bank.vault(), Some(Money::from_major(150)));
+
+    bank.deposit(&bob, Money::from_major(10)).unwrap();
+    let deposit = bank.get_customer(&bob).unwrap().get_account().unwrap().transactions.last().unwrap().id.clone();
+    bank.reverse_transaction(&deposit, "Counterfeit note").unwrap();
+    assert_eq!(bank.vault(), Some(Money::from_major(150)));
+}
+
+#[test]
+fn top_ups_and_vault_withdrawals_are_checked() {
+    let (mut bank, _, _) = bank_with_customers(100, 50);
+    assert_eq!(bank.vault_top_up(Money::from_major(1000)).unwrap(), Money::from_major(1150));
+    assert_eq!(bank.vault_withdraw(Money::from_major(150)).unwrap(), Money::from_major(1000));
+
+    assert!(matches!(bank.vault_top_up(Money::ZERO), Err(BankError::InvalidAmount(_))));
+    assert!(matches!(bank.vault_withdraw(Money::from_major(-5)), Err(BankError::InvalidAmount(_))));
+    assert!(matches!(
+        bank.vault_withdraw(Money::from_major(1001)),
+        Err(BankError::InsufficientVaultCash { .. })
+    ));
+    assert_eq!(bank.vault(), Some(Money::from_major(1000)));
+}
+
+#[test]
+fn a_bank_saved_before_the_vault_starts_untracked() {
+    let (mut bank, alice, _) = bank_with_customers(100, 50);
+    let mut saved: serde_json::Value = serde_json::to_value(&bank).unwrap();
+    saved.as_object_mut().unwrap().remove("vault");
+    let mut old: Bank = serde_json::from_value(saved).unwrap();
+
+    assert_eq!(old.vault(), None);
+    assert!(old.report().to_string().contains("Vault Cash:      not tracked"));
+    old.withdraw(&alice, Money::from_major(90)).unwrap();
+    assert_eq!(old.vault(), None);
+
+    assert_eq!(old.vault_top_up(Money::from_major(500)).unwrap(), Money::from_major(500));
+    old.deposit(&alice, Money::from_major(20)).unwrap();
+    assert_eq!(old.vault(), Some(Money::from_major(520)));
+
+    bank.withdraw(&alice, Money::from_major(90)).unwrap();
+    assert_eq!(bank.report().vault_cash, Some(Money::from_major(60)));
+}
+
+#[test]
+fn replaying_the_journal_restores_the_vault() {
+    let (mut bank, alice, _) = bank_with_customers(100, 50);
+    let snapshot = serde_json::to_string(&bank).unwrap();
+    bank.enable_journal();
+    bank.vault_top_up(Money::from_major(300)).unwrap();
+    bank.withdraw(&alice, Money::from_major(75)).unwrap();
+    bank.vault_withdraw(Money::from_major(25)).unwrap();
+
+    let mut restored: Bank = serde_json::from_str(&snapshot).unwrap();
+    for entry in bank.take_journal() {
+        restored.apply_journal_entry(entry).unwrap();
+    }
+    assert_eq!(restored.vault(), Some(Money::from_major(350)));
+    assert_eq!(restored.vault(), bank.vault());
+}
+
+#[cfg(feature = "cli")]
+#[test]
+fn command_mode_manages_the_vault() {
+    let data_file = temp_path("cli.json");
+    let _ = std::fs::remove_file(&data_file);
+    let _ = std::fs::remove_file(journal_path(&data_file.to_string_lossy()));
+    let run = |args: &[&str]| common::run(&data_file, args, "\n");
+    run(&["register", "--name", "Ann", "--email", "ann@example.com", "--pin", "1234"]);
+    run(&["create-account", "--customer", "ann@example.com", "--deposit", "100"]);
+
+    let json: serde_json::Value = serde_json::from_str(&run(&["--json", "vault"])).unwrap();
+    assert_eq!(json["result"]["vault"], "100.00");
+    let json: serde_json::Value = serde_json::from_str(&run(&["--json", "vault-top-up", "--amount", "50"])).unwrap();
+    assert_eq!(json["result"]["vault"], "150.00");
+    let json: serde_json::Value = serde_json::from_str(&run(&["--json", "vault-withdraw", "--amount", "140"])).unwrap();
+    assert_eq!(json["result"]["vault"], "10.00");
+
+    let json: serde_json::Value = serde_json::from_str(&run(&[
+        "--json", "withdraw", "--customer", "ann@example.com", "--amount", "20", "--pin", "1234",
+    ]))
+    .unwrap();
+    assert_eq!(json["ok"], false);
+    assert_eq!(json["kind"], "insufficient_vault_cash");
+    assert_eq!(json["code"], 2010);
+    let _ = std::fs::remove_file(&data_file);
+}